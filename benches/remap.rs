@@ -10,7 +10,7 @@ use vector::transforms::{
     FunctionTransform,
 };
 use vector::{
-    config::TransformConfig,
+    config::{GlobalOptions, TransformConfig},
     event::{Event, Value},
     test_util::runtime,
 };
@@ -72,7 +72,8 @@ fn benchmark_remap(c: &mut Criterion) {
             .bar = "baz"
             .copy = .copy_from"#
                     .to_string(),
-                drop_on_err: true,
+                drop_on_error: true,
+                ..Default::default()
             })
             .unwrap(),
         );
@@ -132,7 +133,8 @@ fn benchmark_remap(c: &mut Criterion) {
         let mut tform: Box<dyn FunctionTransform> = Box::new(
             Remap::new(RemapConfig {
                 source: ".bar = parse_json!(.foo)".to_owned(),
-                drop_on_err: false,
+                drop_on_error: false,
+                ..Default::default()
             })
             .unwrap(),
         );
@@ -201,7 +203,8 @@ fn benchmark_remap(c: &mut Criterion) {
                 .timestamp = parse_timestamp!(.timestamp, format: "%d/%m/%Y:%H:%M:%S %z")
                 "#
                 .to_owned(),
-                drop_on_err: true,
+                drop_on_error: true,
+                ..Default::default()
             })
             .unwrap(),
         );
@@ -240,7 +243,7 @@ fn benchmark_remap(c: &mut Criterion) {
                    "#,
                 )
                 .unwrap()
-                .build()
+                .build("default", &GlobalOptions::default())
                 .await
                 .unwrap()
             })
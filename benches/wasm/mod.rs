@@ -84,7 +84,8 @@ end
 .test_key2 = "test_value2"
 "#
                     .to_string(),
-                    drop_on_err: false,
+                    drop_on_error: false,
+                    ..Default::default()
                 })
                 .unwrap(),
             ),
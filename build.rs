@@ -1,5 +1,9 @@
+use std::{env, fs, path::PathBuf, process::Command};
+
 fn main() {
+    println!("cargo:rerun-if-changed=proto/dd-sketches.proto");
     println!("cargo:rerun-if-changed=proto/event.proto");
+    println!("cargo:rerun-if-changed=proto/opentelemetry.proto");
     println!("cargo:rerun-if-changed=proto/prometheus-remote.proto");
     println!("cargo:rerun-if-changed=proto/prometheus-types.proto");
     let mut prost_build = prost_build::Config::new();
@@ -10,9 +14,71 @@ fn main() {
     prost_build.type_attribute(".prometheus.Label", "#[derive(Eq, Hash, Ord, PartialOrd)]");
     prost_build
         .compile_protos(
-            &["proto/event.proto", "proto/prometheus-remote.proto"],
+            &[
+                "proto/dd-sketches.proto",
+                "proto/event.proto",
+                "proto/opentelemetry.proto",
+                "proto/prometheus-remote.proto",
+            ],
             &["proto/"],
         )
         .unwrap();
     built::write_built_file().expect("Failed to acquire build-time information");
+
+    if env::var_os("CARGO_FEATURE_SOURCES_EBPF").is_some() {
+        build_ebpf_programs();
+    }
+}
+
+/// Compiles the programs of the `ebpf` source with clang, against the kernel
+/// types dumped by bpftool, from `VMLINUX_H` or the BTF of the build host.
+fn build_ebpf_programs() {
+    const SOURCE: &str = "src/sources/ebpf/bpf/telemetry.bpf.c";
+    println!("cargo:rerun-if-changed={}", SOURCE);
+    println!("cargo:rerun-if-changed=src/sources/ebpf/bpf/telemetry.h");
+    println!("cargo:rerun-if-env-changed=VMLINUX_H");
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let vmlinux = out_dir.join("vmlinux.h");
+    match env::var_os("VMLINUX_H") {
+        Some(path) => {
+            fs::copy(path, &vmlinux).expect("Failed to copy VMLINUX_H");
+        }
+        None => {
+            let output = Command::new("bpftool")
+                .args(&[
+                    "btf",
+                    "dump",
+                    "file",
+                    "/sys/kernel/btf/vmlinux",
+                    "format",
+                    "c",
+                ])
+                .output()
+                .expect("Failed to run bpftool, needed to build the `ebpf` source");
+            assert!(
+                output.status.success(),
+                "Failed to dump the kernel types: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            fs::write(&vmlinux, output.stdout).unwrap();
+        }
+    }
+
+    let arch = match env::var("CARGO_CFG_TARGET_ARCH").unwrap().as_str() {
+        "x86_64" => "x86",
+        "aarch64" => "arm64",
+        arch => arch,
+    }
+    .to_owned();
+    let status = Command::new("clang")
+        .args(&["-g", "-O2", "-target", "bpf"])
+        .arg(format!("-D__TARGET_ARCH_{}", arch))
+        .arg("-I")
+        .arg(&out_dir)
+        .args(&["-c", SOURCE, "-o"])
+        .arg(out_dir.join("telemetry.bpf.o"))
+        .status()
+        .expect("Failed to run clang, needed to build the `ebpf` source");
+    assert!(status.success(), "Failed to compile {}", SOURCE);
 }
@@ -50,6 +50,52 @@ api: {
 				of the address set using the `bind` parameter.
 				"""
 		}
+		history_retention_secs: {
+			common:   false
+			required: false
+			type: uint: {
+				default: 300
+				unit:    "seconds"
+			}
+			description: """
+				How long per-component throughput is kept in memory, sampled every
+				second. The history can be queried with `componentThroughputHistory`
+				right after connecting, rather than waiting for subscriptions to
+				produce data. Set to `0` to disable.
+				"""
+		}
+		auth: {
+			common:      false
+			required:    false
+			description: """
+				Requires clients to authenticate. A warning is logged when the API
+				binds to a non-loopback address without authentication.
+				"""
+			type: object: options: {
+				token: {
+					required:    true
+					description: """
+						Static token clients must send as `Authorization: Bearer <token>`
+						with every request to `/graphql` and `/playground`. The `/health`
+						endpoint remains unauthenticated.
+						"""
+					type: string: {
+						examples: ["${VECTOR_API_TOKEN}"]
+						syntax: "literal"
+					}
+				}
+			}
+		}
+		tls: {
+			common:      false
+			required:    false
+			description: """
+				Serves the API over HTTPS/WSS. Accepts the same options as the `tls`
+				option of server sources, including `verify_certificate` to require
+				client certificates.
+				"""
+			type: object: options: {}
+		}
 	}
 
 	endpoints: {
@@ -175,6 +175,145 @@ cli: {
 	}
 
 	commands: {
+		"buffer inspect": {
+			description: """
+				Print the events and bytes pending in the disk buffers of the sinks,
+				including the buffers left by a previous buffer type, then exit
+				"""
+
+			flags: _default_flags
+
+			options: {
+				"sink": {
+					_short:      "s"
+					description: "Name of the sink whose buffer is inspected, all the sinks with a disk buffer by default"
+					type:        "string"
+				}
+			}
+
+			args: {
+				paths: _paths_arg & {
+					description: """
+						Any number of Vector config files. If none are specified the default
+						config path `/etc/vector/vector.toml` will be targeted
+						"""
+				}
+			}
+		}
+
+		"buffer migrate": {
+			description: """
+				Move the events left in the disk buffers of a previous buffer type, such
+				as `disk` after switching a sink to `disk_v2`, into the configured buffers,
+				then exit. Vector also migrates the buffers when it starts. Fails for the
+				buffers used by a running Vector
+				"""
+
+			flags: _default_flags
+
+			options: {
+				"sink": {
+					_short:      "s"
+					description: "Name of the sink whose buffer is migrated, all the sinks with a disk buffer by default"
+					type:        "string"
+				}
+			}
+
+			args: {
+				paths: _paths_arg & {
+					description: """
+						Any number of Vector config files. If none are specified the default
+						config path `/etc/vector/vector.toml` will be targeted
+						"""
+				}
+			}
+		}
+
+		"checkpoints export": {
+			description: """
+				Export the checkpoints of a `file` source, for the `checkpoint.import_path`
+				option of a `file` source on another host, then exit
+				"""
+
+			flags: _default_flags
+
+			options: {
+				"source": {
+					_short:      "s"
+					description: "Name of the `file` source, can be omitted when the config has a single one"
+					type:        "string"
+				}
+				"output": {
+					_short:      "o"
+					description: "Write the checkpoints to a file instead of stdout"
+					type:        "string"
+					example:     "/var/lib/vector/checkpoints.json"
+				}
+			}
+
+			args: {
+				paths: _paths_arg & {
+					description: """
+						Any number of Vector config files. If none are specified the default
+						config path `/etc/vector/vector.toml` will be targeted
+						"""
+				}
+			}
+		}
+
+		"config schema": {
+			description: """
+				Print a JSON Schema describing all configuration options, then exit.
+				Component options are described from each component's example
+				configuration, without constraining their types, so the schema is
+				meant for editor completion rather than validation. Use `vector
+				validate` to validate configs
+				"""
+
+			flags: _default_flags
+
+			options: {
+				"output": {
+					description: "Write the schema to a file instead of stdout"
+					type:        "string"
+					example:     "/etc/vector/schema.json"
+				}
+			}
+		}
+
+		"convert-config": {
+			description: """
+				Convert deprecated transforms in the target config into equivalent
+				`remap` transforms, then print the converted config
+				"""
+
+			flags: _default_flags
+
+			options: {
+				"format": {
+					description: "Format of the input config, detected from the file name by default"
+					type:        "string"
+					enum: {
+						toml: "Read the input config as TOML"
+						json: "Read the input config as JSON"
+						yaml: "Read the input config as YAML"
+					}
+				}
+				"output": {
+					description: "Write the converted TOML config to a file instead of stdout"
+					type:        "string"
+					example:     "/etc/vector/vector.converted.toml"
+				}
+			}
+
+			args: {
+				path: {
+					description: "The Vector config file to convert"
+					type:        "string"
+				}
+			}
+		}
+
 		"generate": {
 			description: "Generate a Vector configuration containing a list of components"
 
@@ -223,6 +362,81 @@ cli: {
 			}
 		}
 
+		"lsp": {
+			description: """
+				Run a Vector Remap Language language server, speaking the Language Server
+				Protocol over STDIN and STDOUT. It reports the diagnostics of the compiler,
+				documents the functions on hover, and completes the functions, as well as
+				the paths of the event given with `--schema`
+				"""
+
+			flags: _default_flags
+
+			options: {
+				"schema": {
+					_short: "s"
+					description: """
+						File containing a JSON event, as handled by the programs. Its paths
+						are completed after `.`, and their types shown on hover
+						"""
+					type: "string"
+				}
+			}
+		}
+
+		"tap": {
+			description: """
+				Print a sample of the events flowing out of a source or transform, for a
+				local or remote Vector instance
+				"""
+
+			flags: _default_flags
+
+			options: {
+				"interval": {
+					_short:      "i"
+					description: "Interval to sample events at, in milliseconds"
+					type:        "integer"
+					default:     500
+				}
+				"limit": {
+					_short:      "l"
+					description: "Maximum number of events printed per interval, the others being skipped"
+					type:        "integer"
+					default:     100
+				}
+				"url": {
+					_short:      "u"
+					description: "The URL for the GraphQL endpoint of the running Vector instance"
+					type:        "string"
+				}
+				"token": {
+					description: "Token to authenticate with, if the API server requires one"
+					type:        "string"
+					env_var:     "VECTOR_API_TOKEN"
+				}
+				"ca-file": {
+					description: "PEM encoded CA certificate to verify the API server with"
+					type:        "string"
+				}
+				"identity-file": {
+					description: "PKCS#12 archive with a client certificate and key, for mutual TLS"
+					type:        "string"
+				}
+				"identity-pass": {
+					description: "Password of the client identity archive"
+					type:        "string"
+				}
+			}
+
+			args: {
+				component: {
+					description: "Name of the source or transform to sample the output events of"
+					type:        "string"
+				}
+			}
+		}
+
 		"test": {
 			description: """
 				Run Vector config unit tests, then exit. This command is experimental and
@@ -255,6 +469,23 @@ cli: {
 					type:    "string"
 					env_var: "VECTOR_CONFIG_YAML"
 				}
+				"filter": {
+					description: """
+						Only run the tests whose name contains this string. Tests that
+						are filtered out aren't built either.
+						"""
+					type: "string"
+				}
+				"coverage": {
+					description: """
+						Write an lcov report of the lines of the `remap` transform
+						programs exercised by the tests to this file. Each transform is
+						reported as a `transforms.<name>.source` source file, with line
+						numbers relative to its `source` option. Transforms no test
+						reaches aren't reported.
+						"""
+					type: "string"
+				}
 			}
 
 			args: {
@@ -295,6 +526,30 @@ cli: {
 					description: "The URL for the GraphQL endpoint of the running Vector instance"
 					type:        "string"
 				}
+				"targets": {
+					description: """
+						Comma separated URLs of the GraphQL endpoints of multiple running Vector
+						instances, to display their components together, tagged by instance
+						"""
+					type: "string"
+				}
+				"token": {
+					description: "Token to authenticate with, if the API server requires one"
+					type:        "string"
+					env_var:     "VECTOR_API_TOKEN"
+				}
+				"ca-file": {
+					description: "PEM encoded CA certificate to verify the API server with"
+					type:        "string"
+				}
+				"identity-file": {
+					description: "PKCS#12 archive with a client certificate and key, for mutual TLS"
+					type:        "string"
+				}
+				"identity-pass": {
+					description: "Password of the client identity archive"
+					type:        "string"
+				}
 			}
 		}
 
@@ -313,6 +568,13 @@ cli: {
 					_short:      "d"
 					description: "Fail validation on warnings"
 				}
+				"fix": {
+					description: """
+						Rewrite deprecated options in the config files in place before
+						validating, where the migration is mechanical. Comments and
+						formatting of rewritten files are not preserved
+						"""
+				}
 			}
 
 			options: {
@@ -350,7 +612,11 @@ cli: {
 		}
 
 		"vrl": {
-			description: "Vector Remap Language CLI"
+			description: """
+				Vector Remap Language CLI. Given a program, it's run against each of the
+				objects of the input, printing a line of JSON for each of them, and exits
+				with an error if any of them fails with a runtime error
+				"""
 
 			flags: _default_flags & {
 				"print-object": {
@@ -362,13 +628,23 @@ cli: {
 						The same result can be achieved by using `.` as the final expression.
 						"""
 				}
+				"fuzz": {
+					description: """
+						Run the program against mutations of the object(s) instead, and
+						report the inputs it fails on with a runtime error or a panic. One
+						input is reported per distinct error, as JSON that can be passed
+						back with `--input`. Exits with an error if any input fails.
+						"""
+				}
 			}
 
 			options: {
 				"input": {
 					_short: "i"
 					description: """
-						File containing the object(s) to manipulate. Leave empty to use stdin.
+						File containing the object(s) to manipulate, as JSON, including
+						arrays of objects, or NDJSON. Leave empty to use stdin, whose objects
+						are handled as they're read.
 						"""
 					type: "string"
 				}
@@ -380,6 +656,23 @@ cli: {
 						"""
 					type: "string"
 				}
+
+				"iterations": {
+					description: """
+						The number of inputs to generate with `--fuzz`.
+						"""
+					type:    "integer"
+					default: 10000
+				}
+
+				"seed": {
+					description: """
+						The seed of the mutations of `--fuzz`. The same seed and objects
+						always generate the same inputs.
+						"""
+					type:    "integer"
+					default: 0
+				}
 			}
 
 			args: {
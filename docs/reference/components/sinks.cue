@@ -8,6 +8,20 @@ components: sinks: [Name=string]: {
 	kind: "sink"
 
 	configuration: {
+		acknowledgements: {
+			common: false
+			description: """
+				Holds back the status of the events sent to the sources with
+				`acknowledgements` enabled until this sink acknowledges them,
+				instead of as soon as they're buffered. Overrides the global
+				[`acknowledgements`](\(urls.vector_configuration)#acknowledgements)
+				option, so that a sink of low-value data can be disabled not to hold
+				back the sources of the other sinks.
+				"""
+			required: false
+			type: bool: default: null
+		}
+
 		if sinks[Name].features.send != _|_ && sinks[Name].features.send.batch != _|_ {
 			if sinks[Name].features.send.batch.enabled {
 				batch: {
@@ -20,7 +34,7 @@ components: sinks: [Name=string]: {
 							if sinks[Name].features.send.batch.max_bytes != null {
 								max_bytes: {
 									common:      true
-									description: "The maximum size of a batch, in bytes, before it is flushed."
+									description: "The maximum size of a batch, in bytes, before it is flushed. Also accepts a size such as `\"5MiB\"`."
 									required:    false
 									type: uint: {
 										default: sinks[Name].features.send.batch.max_bytes
@@ -42,7 +56,7 @@ components: sinks: [Name=string]: {
 							if sinks[Name].features.send.batch.timeout_secs != null {
 								timeout_secs: {
 									common:      true
-									description: "The maximum age of a batch before it is flushed."
+									description: "The maximum age of a batch before it is flushed. Also accepts a duration such as `\"1m30s\"`."
 									required:    false
 									type: uint: {
 										default: sinks[Name].features.send.batch.timeout_secs
@@ -75,23 +89,35 @@ components: sinks: [Name=string]: {
 							}
 						}
 						max_size: {
-							description:   "The maximum size of the buffer on the disk."
+							description:   "The maximum size of the buffer on the disk. Also accepts a size such as `\"5MiB\"`."
 							required:      true
-							relevant_when: "type = \"disk\""
+							relevant_when: "type = \"disk\" or type = \"disk_v2\""
 							type: uint: {
 								examples: [104900000]
 								unit: "bytes"
 							}
 						}
+						min_free_space: {
+							common:        false
+							description:   "The space to leave available on the file system of the `data_dir`. Vector doesn't start when less space is available, and the buffer is considered full, applying the `when_full` behavior, while the available space stays below it. The available space is reported in the `data_dir_available_bytes` internal metric. Also accepts a size such as `\"1GiB\"`."
+							required:      false
+							relevant_when: "type = \"disk_v2\""
+							type: uint: {
+								default: null
+								examples: [1073741824]
+								unit: "bytes"
+							}
+						}
 						type: {
 							common:      true
-							description: "The buffer's type and storage mechanism."
+							description: "The buffer's type and storage mechanism. When a sink switches between the `disk` and `disk_v2` types, the events left in its previous buffer are migrated to the new one as Vector starts."
 							required:    false
 							type: string: {
 								default: "memory"
 								enum: {
-									memory: "Stores the sink's buffer in memory. This is more performant, but less durable. Data will be lost if Vector is restarted forcefully."
-									disk:   "Stores the sink's buffer on disk. This is less performant, but durable. Data will not be lost between restarts."
+									memory:  "Stores the sink's buffer in memory. This is more performant, but less durable. Data will be lost if Vector is restarted forcefully."
+									disk:    "Stores the sink's buffer on disk. This is less performant, but durable. Data will not be lost between restarts."
+									disk_v2: "Stores the sink's buffer in append-only segment files on disk. Segments are synced to disk at least once per second, records partially written when Vector stopped are dropped on restart, and memory use doesn't grow with the size of the buffer."
 								}
 								syntax: "literal"
 							}
@@ -103,8 +129,9 @@ components: sinks: [Name=string]: {
 							type: string: {
 								default: "block"
 								enum: {
-									block:       "Applies back pressure when the buffer is full. This prevents data loss, but will cause data to pile up on the edge."
-									drop_newest: "Drops new data as it's received. This data is lost. This should be used when performance is the highest priority."
+									block:            "Applies back pressure when the buffer is full. This prevents data loss, but will cause data to pile up on the edge."
+									drop_newest:      "Drops new data as it's received. This data is lost. This should be used when performance is the highest priority."
+									drop_by_priority: "Drops data by the priority kept in the metadata of events, which can be set by the `set_metadata_field` function of a `remap` transform or the `priority` option of sources. `low` priority events are dropped once the buffer is half full, the ones without priority or with a `normal` one when it's full, and `high` priority events apply back pressure instead of being dropped. Dropped events are counted in the `buffer_events_dropped_total` internal metric."
 								}
 								syntax: "literal"
 							}
@@ -193,6 +220,24 @@ components: sinks: [Name=string]: {
 									syntax: "literal"
 								}
 							}
+
+							remap: {
+								common: false
+								description: """
+									A [Vector Remap Language](\(urls.vrl_reference)) program run on
+									each event right before it is encoded, ahead of the other
+									encoding options. Use it to shape the final payload of this
+									sink only, e.g. dropping internal metadata or adding a
+									checksum, without a dedicated upstream transform. Events the
+									program fails on are still sent.
+									"""
+								required: false
+								type: string: {
+									default: null
+									examples: [".checksum = md5(.message)\ndel(.internal)"]
+									syntax: "remap_program"
+								}
+							}
 						}
 					}
 				}
@@ -214,6 +259,44 @@ components: sinks: [Name=string]: {
 								required:    false
 								type: bool: default: true
 							}
+							mode: {
+								common: false
+								description: """
+									Whether a failing healthcheck prevents Vector from starting.
+									Overrides `healthchecks.require_healthy` and the
+									`--require-healthy` flag for this sink.
+									"""
+								required: false
+								type: string: {
+									default: null
+									enum: {
+										required: "A failing healthcheck prevents Vector from starting."
+										advisory: "A failing healthcheck is only logged."
+									}
+								}
+							}
+							timeout_secs: {
+								common:      false
+								description: "How long the healthcheck may take before it's considered failed."
+								required:    false
+								type: uint: {
+									default: 10
+									unit:    "seconds"
+								}
+							}
+							interval_secs: {
+								common: false
+								description: """
+									Re-runs the healthcheck at this interval while the sink is
+									running. The outcome is reported through the
+									`healthcheck_healthy` internal metric.
+									"""
+								required: false
+								type: uint: {
+									default: null
+									unit:    "seconds"
+								}
+							}
 						}
 					}
 				}
@@ -273,7 +356,7 @@ components: sinks: [Name=string]: {
 							}
 							rate_limit_duration_secs: {
 								common:      true
-								description: "The time window, in seconds, used for the `rate_limit_num` option."
+								description: "The time window, in seconds, used for the `rate_limit_num` option. Also accepts a duration such as `\"1m30s\"`."
 								required:    false
 								type: uint: {
 									default: sinks[Name].features.send.request.rate_limit_duration_secs
@@ -300,7 +383,7 @@ components: sinks: [Name=string]: {
 							}
 							retry_initial_backoff_secs: {
 								common:      false
-								description: "The amount of time to wait before attempting the first retry for a failed request. Once, the first retry has failed the fibonacci sequence will be used to select future backoffs."
+								description: "The amount of time to wait before attempting the first retry for a failed request. Once, the first retry has failed the fibonacci sequence will be used to select future backoffs. Also accepts a duration such as `\"1m30s\"`."
 								required:    false
 								type: uint: {
 									default: sinks[Name].features.send.request.retry_initial_backoff_secs
@@ -309,7 +392,7 @@ components: sinks: [Name=string]: {
 							}
 							retry_max_duration_secs: {
 								common:      false
-								description: "The maximum amount of time, in seconds, to wait between retries."
+								description: "The maximum amount of time, in seconds, to wait between retries. Also accepts a duration such as `\"1m30s\"`."
 								required:    false
 								type: uint: {
 									default: sinks[Name].features.send.request.retry_max_duration_secs
@@ -318,7 +401,7 @@ components: sinks: [Name=string]: {
 							}
 							timeout_secs: {
 								common:      true
-								description: "The maximum time a request can take before being aborted. It is highly recommended that you do not lower this value below the service's internal timeout, as this could create orphaned requests, pile on retries, and result in duplicate data downstream."
+								description: "The maximum time a request can take before being aborted. It is highly recommended that you do not lower this value below the service's internal timeout, as this could create orphaned requests, pile on retries, and result in duplicate data downstream. Also accepts a duration such as `\"1m30s\"`."
 								required:    false
 								type: uint: {
 									default: sinks[Name].features.send.request.timeout_secs
@@ -393,6 +476,141 @@ components: sinks: [Name=string]: {
 				}}
 			}
 		}
+
+		if sinks[Name].features.send != _|_ {
+			if sinks[Name].features.send.request.enabled {
+				debug_capture: {
+					common: false
+					description: """
+						Captures a sample of the HTTP requests of this sink, and their responses
+						or errors, to diagnose the requests a service rejects. Captures are
+						written to the internal logs, or appended to a file as JSON lines.
+						Bodies compressed with gzip are decompressed. The credentials and query
+						values of the URLs are redacted, as they often hold API keys. This is a
+						debugging aid, bodies hold the events sent.
+						"""
+					required: false
+					type: object: {
+						examples: []
+						options: {
+							max_body_bytes: {
+								common:      false
+								description: "The most bytes of each request and response body captured."
+								required:    false
+								type: uint: {
+									default: 4096
+									unit:    "bytes"
+								}
+							}
+							path: {
+								common:      false
+								description: "The file captures are appended to. By default, they're written to the internal logs."
+								required:    false
+								type: string: {
+									default: null
+									examples: ["/var/lib/vector/captures.jsonl"]
+									syntax: "file_system_path"
+								}
+							}
+							redact_headers: {
+								common:      false
+								description: "The headers whose values are replaced by `[REDACTED]`, case insensitively. `*` redacts all of them."
+								required:    false
+								type: array: {
+									default: ["authorization", "proxy-authorization", "cookie", "set-cookie", "x-api-key", "dd-api-key", "x-insert-key", "x-license-key", "x-sumo-token", "x-auth-token"]
+									items: type: string: {
+										examples: ["x-api-key", "*"]
+										syntax: "literal"
+									}
+								}
+							}
+							sample_rate: {
+								common:      true
+								description: "The fraction of the requests captured, between 0 and 1."
+								required:    false
+								type: float: default: 0.01
+							}
+						}
+					}
+				}
+			}
+		}
+
+		quota: {
+			common: false
+			description: """
+				Accounts the bytes this sink sends and optionally limits them per hour or
+				day, for cost governance on sinks billed by volume. Event sizes are
+				estimated from their JSON encoding.
+				"""
+			required: false
+			type: object: {
+				examples: []
+				options: {
+					max_bytes: {
+						common:      true
+						description: "The bytes this sink may send per period. Without it, bytes are only accounted."
+						required:    false
+						type: uint: {
+							default: null
+							examples: [10000000000]
+							unit: "bytes"
+						}
+					}
+					period: {
+						common:      true
+						description: "The period `max_bytes` applies to. Periods start at the full hour or at midnight, in UTC."
+						required:    false
+						type: string: {
+							default: "daily"
+							enum: {
+								hourly: "The quota resets every hour."
+								daily:  "The quota resets every day."
+							}
+							syntax: "literal"
+						}
+					}
+					when_exceeded: {
+						common:      true
+						description: "What to do with events once the quota is exceeded."
+						required:    false
+						type: string: {
+							default: "drop"
+							enum: {
+								drop:   "Drop the events."
+								sample: "Send one in every `sample_rate` events and drop the others."
+								divert: "Send the events to the `divert` sink instead."
+							}
+							syntax: "literal"
+						}
+					}
+					sample_rate: {
+						common:        false
+						description:   "Send one in this many events while the quota is exceeded."
+						relevant_when: "when_exceeded = \"sample\""
+						required:      false
+						type: uint: {
+							default: 10
+							unit:    null
+						}
+					}
+					divert: {
+						common: false
+						description: """
+							A sink, configured like any other, that events are sent to while the
+							quota is exceeded, such as a cheaper archive. It shares the buffer of
+							this sink.
+							"""
+						relevant_when: "when_exceeded = \"divert\""
+						required:      false
+						type: object: {
+							examples: [{type: "aws_s3", bucket: "vector-overflow", region: "us-east-1", encoding: "ndjson"}]
+							options: {}
+						}
+					}
+				}
+			}
+		}
 	}
 
 	how_it_works: {
@@ -437,6 +655,33 @@ components: sinks: [Name=string]: {
 			}
 		}
 
+		acknowledgements: {
+			title: "End-to-end acknowledgements"
+			body: """
+				Sources with `acknowledgements` enabled, such as `kafka` and `file`, only
+				commit their offsets or checkpoints once the events read were delivered.
+				An event is delivered once every sink with `acknowledgements` enabled it
+				was sent to acknowledged it, after flushing it, and every other sink
+				buffered it. A disk buffer acknowledges the events once it wrote them, as they're
+				stored durably. Events dropped by transforms count as delivered.
+
+				When a sink fails to deliver events, such as after its requests errored
+				or were rejected, their sources don't commit past them anymore, so that
+				they're read again once Vector restarts.
+				"""
+		}
+
+		quotas: {
+			title: "Byte quotas"
+			body: """
+				The [`quota.*`](#quota) options account the bytes this sink sends and can cap
+				them per hour or day. Quota usage is exposed through the `quota_used_bytes`,
+				`quota_limit_bytes` and `quota_exceeded` gauges, and events over the quota are
+				counted by `quota_exceeded_events_total`, tagged with the action taken. Usage is
+				kept in memory, so a restart starts the current period over.
+				"""
+		}
+
 		if sinks[Name].features.healthcheck.enabled {
 			healthchecks: {
 				title: "Health checks"
@@ -467,6 +712,15 @@ components: sinks: [Name=string]: {
 								`false`.
 								"""
 					},
+					{
+						title: "Advisory health checks"
+						body: """
+								Setting `healthcheck.mode` to `advisory` only logs a
+								failing health check of this sink, even when
+								`--require-healthy` is passed, while `required` makes
+								Vector exit when it fails.
+								"""
+					},
 				]
 			}
 		}
@@ -518,6 +772,21 @@ components: sinks: [Name=string]: {
 								[sinks.my-sink]
 								  request.concurrency = "adaptive"
 								```
+
+								The limit decided on is exposed by the `adaptive_concurrency_current_limit`
+								internal metric, and its changes by `adaptive_concurrency_decisions_total`.
+								During an incident, the limit can be pinned through the
+								[API][docs.reference.api] with the `pinAdaptiveConcurrency` mutation,
+								suspending its adjustments until the `unpinAdaptiveConcurrency` one:
+
+								```graphql
+								mutation {
+								  pinAdaptiveConcurrency(sinkName: "my-sink", limit: 5) {
+								    currentLimit
+								    inFlight
+								  }
+								}
+								```
 								"""
 						},
 						{
@@ -93,6 +93,7 @@ components: sinks: clickhouse: {
 		auth: configuration._http_auth & {_args: {
 			password_example: "${CLICKHOUSE_PASSWORD}"
 			username_example: "${CLICKHOUSE_USERNAME}"
+			oauth2:           true
 		}}
 		database: {
 			common:      true
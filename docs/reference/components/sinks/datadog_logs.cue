@@ -67,4 +67,15 @@ components: sinks: datadog_logs: {
 		logs:    true
 		metrics: null
 	}
+
+	how_it_works: {
+		api_keys: {
+			title: "API keys"
+			body: """
+				The logs received by a `datadog_agent` source with `store_api_key`
+				enabled are sent with the API key of the agent that sent them, the
+				other logs with the `api_key` of the sink.
+				"""
+		}
+	}
 }
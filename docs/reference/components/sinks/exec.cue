@@ -0,0 +1,138 @@
+package metadata
+
+components: sinks: exec: {
+	title: "Exec"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: false
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10000000
+				max_events:   null
+				timeout_secs: 30
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					default: null
+					enum: ["ndjson", "text"]
+				}
+			}
+			request: {
+				enabled:                    true
+				concurrency:                1
+				rate_limit_duration_secs:   1
+				rate_limit_num:             5
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    3600
+				timeout_secs:               300
+				headers:                    false
+			}
+			tls: enabled: false
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: [
+			"""
+				The command runs with the permissions of the Vector process. Make sure
+				it can't be altered by users who shouldn't run commands as Vector.
+				""",
+		]
+		notices: []
+	}
+
+	configuration: {
+		command: {
+			description: "The program to run for each batch, followed by its arguments. It's run directly, not through a shell."
+			required:    true
+			warnings: []
+			type: array: items: type: string: {
+				examples: [["bq", "load", "--source_format=NEWLINE_DELIMITED_JSON", "dataset.table"]]
+				syntax: "literal"
+			}
+		}
+		environment: {
+			common:      false
+			description: "Variables added to the environment of the command, on top of the environment of Vector."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"AZCOPY_LOG_LOCATION": "/var/log/azcopy"}]
+				options: {}
+			}
+		}
+		working_directory: {
+			common:      false
+			description: "The directory the command runs in. Defaults to the working directory of Vector."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		batches: {
+			title: "One Command per Batch"
+			body: """
+				Vector runs the command once per batch, writing the encoded events
+				of the batch to its standard input, one per line, then closing it.
+				The standard output of the command is discarded.
+				"""
+		}
+		retries: {
+			title: "Failed Commands"
+			body: """
+				A batch is delivered once the command exits with a zero status. Any
+				other exit status, or a command running for longer than
+				`request.timeout_secs`, is a failure: the command is killed if
+				needed and run again for the same batch, as configured by the
+				`request` options. The end of the standard error of the failed
+				command is logged along with its exit status.
+
+				Since a batch may be passed to the command more than once, commands
+				loading data should be idempotent where possible.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		command_executed_total:                 components.sources.internal_metrics.output.metrics.command_executed_total
+		command_execution_duration_nanoseconds: components.sources.internal_metrics.output.metrics.command_execution_duration_nanoseconds
+		processed_bytes_total:                  components.sources.internal_metrics.output.metrics.processed_bytes_total
+	}
+}
@@ -93,6 +93,8 @@ components: sinks: http: {
 		auth: configuration._http_auth & {_args: {
 			password_example: "${HTTP_PASSWORD}"
 			username_example: "${HTTP_USERNAME}"
+			oauth2:           true
+			aws:              true
 		}}
 		uri: {
 			description: """
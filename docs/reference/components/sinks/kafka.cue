@@ -34,7 +34,7 @@ components: sinks: kafka: {
 				codec: {
 					enabled: true
 					default: null
-					enum: ["json", "text"]
+					enum: ["avro", "json", "text"]
 				}
 			}
 			request: enabled: false
@@ -53,6 +53,17 @@ components: sinks: kafka: {
 
 	configuration: {
 		bootstrap_servers: components._kafka.configuration.bootstrap_servers
+		encoding: type: object: options: schema: {
+			common:      false
+			description: "The Avro schema of the records, required by the `avro` codec. The fields of the events are resolved against it."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: [#"{"type": "record", "name": "Log", "fields": [{"name": "message", "type": "string"}]}"#]
+				syntax: "literal"
+			}
+		}
 		key_field: {
 			description: "The log field name or tags key to use for the topic key. If unspecified, the key will be randomly generated. If the field does not exist on the log or in tags, a blank value will be used."
 			required:    true
@@ -125,6 +136,59 @@ components: sinks: kafka: {
 				}
 			}
 		}
+		schema_registry: {
+			common:      false
+			description: "Registers the Avro schema in a [Confluent Schema Registry](\(urls.confluent_schema_registry)), prefixing each record with the magic byte and the ID of its schema, following the [Confluent wire format](\(urls.confluent_wire_format)). Requires the `avro` codec."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					auth: configuration._http_auth & {_args: {
+						password_example: "${SCHEMA_REGISTRY_PASSWORD}"
+						username_example: "${SCHEMA_REGISTRY_USERNAME}"
+						oauth2:           true
+					}}
+					auto_register: {
+						common:      false
+						description: "Whether to register the schema. When disabled, the schema must already be registered under the subject, and only its ID is looked up."
+						required:    false
+						warnings: []
+						type: bool: default: true
+					}
+					subject_name_strategy: {
+						common:      false
+						description: "How the subject the schema is registered under is named."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "topic_name"
+							enum: {
+								topic_name:        "The subject is `<topic>-value`."
+								record_name:       "The subject is the fully qualified name of the record."
+								topic_record_name: "The subject is `<topic>-<record name>`, the record name being fully qualified."
+							}
+							syntax: "literal"
+						}
+					}
+					tls: configuration._tls_connect & {_args: {
+						can_enable:             false
+						can_verify_certificate: true
+						can_verify_hostname:    true
+						enabled_default:        false
+					}}
+					url: {
+						description: "The URL of the schema registry."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["http://localhost:8081"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
 		socket_timeout_ms: components._kafka.configuration.socket_timeout_ms
 		topic: {
 			description: "The Kafka topic name to write events to."
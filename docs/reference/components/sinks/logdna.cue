@@ -79,6 +79,17 @@ components: sinks: logdna: {
 				syntax: "literal"
 			}
 		}
+		app: {
+			common:      false
+			description: "The app of each line, overriding the `app` field of the event and `default_app`. Values longer than 512 characters are truncated."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ service }}", "{{ kubernetes.container_name }}"]
+				syntax: "template"
+			}
+		}
 		default_app: {
 			common:      false
 			description: "The default app that will be set for events that do not contain a `file` or `app` field."
@@ -111,6 +122,28 @@ components: sinks: logdna: {
 				syntax: "literal"
 			}
 		}
+		env: {
+			common:      false
+			description: "The environment of each line, overriding the `env` field of the event and `default_env`. Values longer than 80 characters are truncated."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ stage }}"]
+				syntax: "template"
+			}
+		}
+		file: {
+			common:      false
+			description: "The file of each line, overriding the `file` field of the event. Values longer than 512 characters are truncated."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ path }}"]
+				syntax: "template"
+			}
+		}
 		hostname: {
 			description: "The hostname that will be attached to each batch of events."
 			required:    true
@@ -131,6 +164,17 @@ components: sinks: logdna: {
 				syntax: "literal"
 			}
 		}
+		level: {
+			common:      false
+			description: "The severity level of each line. Values longer than 80 characters are truncated."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ severity }}", "{{ level }}"]
+				syntax: "template"
+			}
+		}
 		mac: {
 			common:      false
 			description: "The mac address that will be attached to each batch of events."
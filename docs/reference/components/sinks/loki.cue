@@ -80,6 +80,20 @@ components: sinks: loki: {
 	}
 
 	configuration: {
+		api: {
+			common:      false
+			description: "The Loki API the events are sent to."
+			required:    false
+			warnings: []
+			type: string: {
+				syntax:  "literal"
+				default: "push"
+				enum: {
+					"push": "Send the events to the push API, `/loki/api/v1/push`."
+					"otlp": "Send the events to the OTLP endpoint of Loki 3.x, `/otlp/v1/logs`. The labels are sent as resource attributes, and the structured metadata as log attributes."
+				}
+			}
+		}
 		endpoint: {
 			description: "The base URL of the Loki instance."
 			required:    true
@@ -91,6 +105,7 @@ components: sinks: loki: {
 		auth: configuration._http_auth & {_args: {
 			password_example: "${LOKI_PASSWORD}"
 			username_example: "${LOKI_USERNAME}"
+			oauth2:           true
 		}}
 		labels: {
 			description: "A set of labels that will be attached to each batch of events. These values are also templateable to allow events to provide dynamic label values.Note: If the set of label values has high cardinality this can cause drastic performance issues with Loki. To ensure this does not happen one should try to reduce the amount of unique label values."
@@ -146,6 +161,13 @@ components: sinks: loki: {
 			warnings: []
 			type: bool: default: false
 		}
+		remove_structured_metadata_fields: {
+			common:      false
+			description: "If this is set to `true` then when structured metadata is collected from events those fields will also get removed from the event."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
 		remove_timestamp: {
 			common:      false
 			description: "If this is set to `true` then the timestamp will be removed from the event. This is useful because Loki uses the timestamp to index the event."
@@ -153,6 +175,32 @@ components: sinks: loki: {
 			warnings: []
 			type: bool: default: true
 		}
+		structured_metadata: {
+			common:      false
+			description: "A set of key/value pairs attached to each event as [structured metadata](\(urls.loki_structured_metadata)), which requires Loki 3.0 or later. Unlike labels, structured metadata does not define the streams, so high cardinality values such as trace IDs can be kept queryable without affecting the performance of Loki. The values are templateable."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						"trace_id": "{{ trace_id }}"
+						"pod":      "{{ kubernetes.pod_name }}"
+					},
+				]
+				options: {
+					"*": {
+						common:      false
+						description: "Any structured metadata"
+						required:    false
+						type: string: {
+							default: null
+							examples: ["{{ trace_id }}"]
+							syntax: "template"
+						}
+					}
+				}
+			}
+		}
 		tenant_id: {
 			common:      false
 			description: "The tenant id that will be sent with every request, by default this is not required since a proxy should set this header. When running Loki locally a tenant id is not required either.\n\nYou can read more about tenant id's [here][urls.loki_multi_tenancy]"
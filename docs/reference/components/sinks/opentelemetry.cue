@@ -0,0 +1,206 @@
+package metadata
+
+components: sinks: opentelemetry: {
+	title: "OpenTelemetry"
+
+	description: """
+		Exports logs and metrics to OpenTelemetry collectors and backends over
+		the OTLP/gRPC or OTLP/HTTP protocol.
+		"""
+
+	classes: {
+		commonly_used: false
+		service_providers: []
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    1048576
+				max_events:   1000
+				timeout_secs: 1
+			}
+			compression: {
+				enabled: true
+				default: "none"
+				algorithms: ["none", "gzip"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+			}
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			request: {
+				enabled:                    true
+				concurrency:                5
+				rate_limit_duration_secs:   1
+				rate_limit_num:             5
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+				headers:                    true
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.opentelemetry
+
+				interface: {
+					socket: {
+						api: {
+							title: "OTLP"
+							url:   urls.otlp
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		auth: configuration._http_auth & {_args: {
+			password_example: "${OTLP_PASSWORD}"
+			username_example: "${OTLP_USERNAME}"
+			oauth2:           true
+		}}
+		endpoint: {
+			description: """
+				The base URI of the OTLP receiver. The path of each signal is
+				appended to it: the gRPC method for the `grpc` protocol, or
+				`/v1/logs` and `/v1/metrics` for the `http` protocol.
+				"""
+			required: true
+			warnings: []
+			type: string: {
+				examples: ["http://localhost:4317", "https://otlp.example.com:4318"]
+				syntax: "literal"
+			}
+		}
+		expire_metrics_secs: {
+			common:      false
+			description: "Overrides the global `expire_metrics_secs` option. Each expired series is ended with a data point flagged as having no recorded value."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [300]
+				unit: "seconds"
+			}
+		}
+		protocol: {
+			common:      true
+			description: "The OTLP transport to export with."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "grpc"
+				enum: {
+					grpc: "Protobuf messages in unary gRPC calls, over HTTP/2."
+					http: "Protobuf messages in HTTP POST requests."
+				}
+				syntax: "literal"
+			}
+		}
+		resource_attributes: {
+			common:      true
+			description: "The attributes of the resource the records come from. They override the attributes of the `resource` field of logs, and of the `resource.*` tags of metrics."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"service.name": "{{ service }}", "deployment.environment": "production"}]
+				options: {
+					"*": {
+						common:      false
+						description: "A resource attribute."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["{{ service }}", "production"]
+							syntax: "template"
+						}
+					}
+				}
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: false
+			gauge:        true
+			histogram:    true
+			summary:      true
+			set:          false
+		}
+	}
+
+	how_it_works: {
+		records: {
+			title: "Records"
+			body: """
+				Logs are exported as log records: the `message` field is their
+				body, the `attributes` field and any other field their
+				attributes. The `severity_text`, `severity_number`, `trace_id`,
+				`span_id`, and `observed_timestamp` fields, as set by the
+				`opentelemetry` source, are exported as the fields of the same
+				name.
+
+				Counters are exported as monotonic sums, and gauges as gauges.
+				Incremental metrics have a delta aggregation temporality,
+				absolute ones a cumulative one. Sets and distributions have no
+				OTLP equivalent and are dropped.
+
+				The records of a batch are grouped by resource, and exported
+				with the `vector` instrumentation scope.
+				"""
+		}
+		retries: {
+			title: "Retries"
+			body: """
+				Requests are retried on the gRPC status codes that the OTLP
+				specification marks as retryable, such as `UNAVAILABLE`, and on
+				HTTP `429` and `5xx` responses.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processed_bytes_total:  components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total: components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
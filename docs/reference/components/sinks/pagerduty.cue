@@ -0,0 +1,217 @@
+package metadata
+
+components: sinks: pagerduty: {
+	title: "PagerDuty"
+
+	description: """
+		Raises alerts from events, by sending them to the PagerDuty
+		[Events API v2](\(urls.pagerduty_events_api)), or to any alert webhook
+		accepting its payloads.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: ["PagerDuty"]
+		stateful: false
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: false
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			request: {
+				enabled:                    true
+				concurrency:                5
+				rate_limit_duration_secs:   1
+				rate_limit_num:             5
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+				headers:                    false
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.pagerduty
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		action: {
+			common:      true
+			description: "The action of the alerts: `trigger`, `acknowledge`, or `resolve`. Alerts with any other action are triggered. Acknowledgements and resolutions require a `dedup_key`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ action }}", "resolve"]
+				syntax: "template"
+			}
+		}
+		class: {
+			common:      false
+			description: "The class of the alerts."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ error.kind }}"]
+				syntax: "template"
+			}
+		}
+		component: {
+			common:      false
+			description: "The component of the alerts."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ service }}"]
+				syntax: "template"
+			}
+		}
+		dedup_key: {
+			common:      true
+			description: "The key grouping the alerts of the same incident. Values longer than 255 characters are truncated. PagerDuty opens an incident for each alert when unset."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ service }}-{{ check }}"]
+				syntax: "template"
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The endpoint to send alerts to."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "https://events.pagerduty.com/v2/enqueue"
+				examples: ["https://events.eu.pagerduty.com/v2/enqueue"]
+				syntax: "literal"
+			}
+		}
+		group: {
+			common:      false
+			description: "The group of the alerts."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ cluster }}"]
+				syntax: "template"
+			}
+		}
+		routing_key: {
+			description: "The [integration key](\(urls.pagerduty_routing_key)) of the PagerDuty service."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["${PAGERDUTY_ROUTING_KEY}"]
+				syntax: "literal"
+			}
+		}
+		severity: {
+			common:      true
+			description: "The severity of the alerts, from the usual names of log levels. Alerts without a known severity are errors."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ level }}", "critical"]
+				syntax: "template"
+			}
+		}
+		source: {
+			common:      false
+			description: "The source of the alerts. The `host` field of the events is used when unset."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ kubernetes.pod_name }}"]
+				syntax: "template"
+			}
+		}
+		summary: {
+			description: "The summary of the alerts. Values longer than 1024 characters are truncated."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["{{ message }}", "{{ service }} is down"]
+				syntax: "template"
+			}
+		}
+		throttle_secs: {
+			common:      true
+			description: "Triggers each `dedup_key` at most once in this many seconds, dropping the other triggers. Resolving an alert lets the next trigger through right away."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [300]
+				unit: "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		alerts: {
+			title: "Alerts"
+			body: """
+				Each log event is sent as an alert, in its own request. The
+				fields of the event, once the `encoding` options are applied,
+				are sent as the custom details of triggered alerts.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+		missing_keys_total:     components.sources.internal_metrics.output.metrics.missing_keys_total
+	}
+}
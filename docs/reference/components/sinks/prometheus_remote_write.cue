@@ -34,7 +34,7 @@ components: sinks: prometheus_remote_write: {
 				retry_initial_backoff_secs: 1
 				retry_max_duration_secs:    10
 				timeout_secs:               60
-				headers:                    false
+				headers:                    true
 			}
 			tls: {
 				enabled:                true
@@ -98,7 +98,25 @@ components: sinks: prometheus_remote_write: {
 		auth: configuration._http_auth & {_args: {
 			password_example: "${HTTP_PASSWORD}"
 			username_example: "${HTTP_USERNAME}"
+			oauth2:           true
+			aws:              true
 		}}
+		compatibility: {
+			common:      false
+			description: "The backend the metrics are written to. The options are validated against its quirks, and the requests adapted to them."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "prometheus"
+				enum: {
+					prometheus:      "Prometheus, Cortex, or any other backend implementing the remote_write protocol."
+					chronosphere:    "Chronosphere, which requires `auth` or an authentication header in `request.headers`. Metrics without timestamps are sent with the current time, as they would be dropped."
+					m3:              "M3. Metrics without timestamps are sent with the current time, as they would be dropped."
+					victoriametrics: "VictoriaMetrics, which reads the tenant from the endpoint path instead of `tenant_id`, and supports `import_format`."
+				}
+				syntax: "literal"
+			}
+		}
 		default_namespace: {
 			common:      true
 			description: """
@@ -124,6 +142,42 @@ components: sinks: prometheus_remote_write: {
 				items: type: float: examples: [0.005, 0.01]
 			}
 		}
+		import_format: {
+			common:      false
+			description: "The format the metrics are written in. Only supported with `compatibility` set to `victoriametrics`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "remote_write"
+				enum: {
+					remote_write: "The snappy compressed protobuf of the remote_write protocol."
+					prometheus:   "The [Prometheus text format](\(urls.prometheus_text_based_exposition_format)), for the `/api/v1/import/prometheus` endpoint of VictoriaMetrics."
+				}
+				syntax: "literal"
+			}
+		}
+		expire_metrics_secs: {
+			common:      false
+			description: "Overrides the global `expire_metrics_secs` option. Each expired series is ended with a staleness marker, a sample of the NaN Prometheus reads as the end of a series. Only sent with `import_format` set to `remote_write`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [300]
+				unit: "seconds"
+			}
+		}
+		max_samples_per_request: {
+			common:      false
+			description: "The most samples sent in one request, the batches above it being split into several requests, sent in order. Only supported with `import_format` set to `remote_write`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [10000]
+				unit: null
+			}
+		}
 		quantiles: {
 			common:      false
 			description: "Quantiles to use for aggregating [distribution][docs.data-model.metric#distribution] metrics into a summary."
@@ -136,7 +190,7 @@ components: sinks: prometheus_remote_write: {
 		}
 		tenant_id: {
 			common:      false
-			description: "If set, a header named `X-Scope-OrgID` will be added to outgoing requests with the text of this setting. This may be used by Cortex or other remote services to identify the tenant making the request."
+			description: "If set, a header named by `tenant_header` will be added to outgoing requests with the text of this setting. This may be used by Cortex or other remote services to identify the tenant making the request."
 			required:    false
 			warnings: []
 			type: string: {
@@ -145,6 +199,17 @@ components: sinks: prometheus_remote_write: {
 				syntax: "template"
 			}
 		}
+		tenant_header: {
+			common:      false
+			description: "The header the `tenant_id` is sent in."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "X-Scope-OrgID"
+				examples: ["X-Scope-OrgID", "M3-Tenant"]
+				syntax: "literal"
+			}
+		}
 	}
 
 	input: {
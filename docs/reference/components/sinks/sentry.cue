@@ -0,0 +1,250 @@
+package metadata
+
+components: sinks: sentry: {
+	title: "Sentry"
+
+	description: """
+		Raises Sentry issues from error logs, by sending them to a Sentry
+		project as Sentry events.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: ["Sentry"]
+		stateful: false
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: false
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			request: {
+				enabled:                    true
+				concurrency:                5
+				rate_limit_duration_secs:   1
+				rate_limit_num:             5
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+				headers:                    false
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.sentry
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		dsn: {
+			description: "The [DSN](\(urls.sentry_dsn)) of the Sentry project to send events to."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["${SENTRY_DSN}", "https://public@o0.ingest.sentry.io/0"]
+				syntax: "literal"
+			}
+		}
+		environment: {
+			common:      true
+			description: "The environment of the Sentry events."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["production", "{{ env }}"]
+				syntax: "template"
+			}
+		}
+		fields: {
+			common:      true
+			description: "The fields of the log events mapped to the attributes of the Sentry events. Mapped fields, except the fingerprint ones, are not repeated in the `extra` of the Sentry events."
+			required:    false
+			warnings: []
+			type: object: options: {
+				exception_type: {
+					common:      true
+					description: "The field holding the type of the exception. When present, the Sentry event holds an exception rather than only a message."
+					required:    false
+					warnings: []
+					type: string: {
+						default: null
+						examples: ["error.kind"]
+						syntax: "literal"
+					}
+				}
+				exception_value: {
+					common:      false
+					description: "The field holding the value of the exception. The message of the log event is used when unset or missing."
+					required:    false
+					warnings: []
+					type: string: {
+						default: null
+						examples: ["error.message"]
+						syntax: "literal"
+					}
+				}
+				fingerprint: {
+					common:      true
+					description: "The fields whose values make up the fingerprint of the Sentry events, which decides the issue they are grouped in. Sentry groups events with its own rules when unset."
+					required:    false
+					warnings: []
+					type: array: {
+						default: []
+						items: type: string: {
+							examples: ["service", "error.kind"]
+							syntax: "literal"
+						}
+					}
+				}
+				level: {
+					common:      false
+					description: "The field holding the level of the log events. Events without a known level are taken as errors."
+					required:    false
+					warnings: []
+					type: string: {
+						default: "level"
+						examples: ["severity"]
+						syntax: "literal"
+					}
+				}
+				tags: {
+					common:      false
+					description: "The fields sent as the tags of the Sentry events, which can be searched in Sentry."
+					required:    false
+					warnings: []
+					type: array: {
+						default: []
+						items: type: string: {
+							examples: ["service"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		min_level: {
+			common:      true
+			description: "The lowest level of the log events sent to Sentry. Less severe events are dropped."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "error"
+				enum: {
+					debug:   "Debug events, from the `trace` and `debug` levels."
+					info:    "Informational events, from the `info` and `notice` levels."
+					warning: "Warnings, from the `warn` and `warning` levels."
+					error:   "Errors, from the `err` and `error` levels."
+					fatal:   "Fatal errors, from the `crit`, `alert`, `emerg`, `fatal`, and `panic` levels."
+				}
+				syntax: "literal"
+			}
+		}
+		rate_limit: {
+			common:      false
+			description: "Limits the number of events sent for each key, over fixed windows of time. Events above the limit are dropped."
+			required:    false
+			warnings: []
+			type: object: options: {
+				key: {
+					description: "The key events are counted by."
+					required:    true
+					warnings: []
+					type: string: {
+						examples: ["{{ service }}", "{{ error.kind }}"]
+						syntax: "template"
+					}
+				}
+				max_events: {
+					description: "The maximum number of events of a key in each window."
+					required:    true
+					warnings: []
+					type: uint: {
+						examples: [10]
+						unit: "events"
+					}
+				}
+				window_secs: {
+					common:      false
+					description: "The length of the windows."
+					required:    false
+					warnings: []
+					type: uint: {
+						default: 60
+						unit:    "seconds"
+					}
+				}
+			}
+		}
+		release: {
+			common:      false
+			description: "The release of the Sentry events."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["checkout@1.2.3", "{{ version }}"]
+				syntax: "template"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		events: {
+			title: "Sentry events"
+			body: """
+				Each log event is sent as a Sentry event, in its own
+				envelope. The message of the log event is the message of the
+				Sentry event, its `host` field the server name, and its
+				remaining fields the `extra` data.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+}
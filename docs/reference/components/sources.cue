@@ -4,6 +4,51 @@ components: sources: [Name=string]: {
 	kind: "source"
 
 	configuration: {
+		filter: {
+			common: false
+			description: """
+				A [Vector Remap Language](\(urls.vrl_reference)) condition events
+				must satisfy to be sent on by this source. Other events are dropped
+				before reaching any transform or sink, saving the cost of
+				forwarding high-volume noise like health check requests.
+				"""
+			required: false
+			type: string: {
+				default: null
+				examples: [".status != 200"]
+				syntax: "remap_program"
+			}
+		}
+
+		on_backpressure: {
+			common:      false
+			description: "What this source does with its events while the components downstream of it can't keep up. By default, UDP based sources, including the `statsd`, `socket` and `syslog` sources in `udp` mode, shed, all other sources block."
+			required:    false
+			type: string: {
+				default: null
+				enum: {
+					block: "Waits for room downstream, slowing the source down. Nothing is lost, but senders may be pushed back on or fall behind."
+					shed:  "Drops the events there is no room for and counts them in the `events_shed_total` internal metric. Sources with `acknowledgements` enabled see their delivery as errored."
+				}
+				syntax: "literal"
+			}
+		}
+
+		priority: {
+			common:      false
+			description: "The priority given to the events of this source which don't have one already, kept in their metadata rather than in a field or tag. Sinks whose buffer drops events by priority drop the lower priority ones first."
+			required:    false
+			type: string: {
+				default: null
+				enum: {
+					low:    "Dropped once the buffer is half full."
+					normal: "Dropped when the buffer is full."
+					high:   "Never dropped, waiting for room in the buffer."
+				}
+				syntax: "literal"
+			}
+		}
+
 		if sources[Name].features.collect != _|_ {
 			if sources[Name].features.collect.checkpoint.enabled {
 				data_dir: {
@@ -109,7 +154,7 @@ components: sources: [Name=string]: {
 			if sources[Name].features.receive.receive_buffer_size != _|_ {
 				send_buffer_bytes: {
 					common:      false
-					description: "Configures the receive buffer size using the `SO_RCVBUF` option on the socket."
+					description: "Configures the receive buffer size using the `SO_RCVBUF` option on the socket. Also accepts a size such as `\"5MiB\"`."
 					required:    false
 					type: uint: {
 						examples: [65536]
@@ -199,12 +244,24 @@ components: sources: [Name=string]: {
 						off if restarted, preventing data from being read twice. The
 						checkpoint positions are stored in the data directory which is
 						specified via the global `data_dir` option, but can be overridden
-						via the `data_dir` option in the file source directly.
+						via the `data_dir` option in the file source directly. When the
+						global `state` option sets a remote backend, they are stored there
+						instead.
 						"""
 				}
 			}
 		}
 
+		backpressure: {
+			title: "Backpressure"
+			body: """
+				When the components downstream of the `\( Name )` source are saturated,
+				the source either waits for them (`block`) or drops the events there is
+				no room for (`shed`), as set by the `on_backpressure` option. Shed events
+				are counted in the `events_shed_total` internal metric.
+				"""
+		}
+
 		context: {
 			title: "Context"
 			body:  """
@@ -27,7 +27,7 @@ components: sources: apache_metrics: {
 						}
 						direction: "outgoing"
 						protocols: ["http"]
-						ssl: "disabled"
+						ssl: "optional"
 					}
 				}
 			}
@@ -60,7 +60,7 @@ components: sources: apache_metrics: {
 
 	configuration: {
 		endpoints: {
-			description: "mod_status endpoints to scrape metrics from."
+			description: "mod_status endpoints to scrape metrics from. An endpoint can also be a table with its `url`, and `tags` to add to its metrics."
 			required:    true
 			type: array: {
 				items: type: string: {
@@ -70,7 +70,7 @@ components: sources: apache_metrics: {
 			}
 		}
 		scrape_interval_secs: {
-			description: "The interval between scrapes."
+			description: "The interval between scrapes. Also accepts a duration such as `\"1m30s\"`."
 			common:      true
 			required:    false
 			type: uint: {
@@ -88,6 +88,18 @@ components: sources: apache_metrics: {
 				syntax:  "literal"
 			}
 		}
+		tls: configuration._tls_connect & {_args: {
+			can_enable:             true
+			can_verify_certificate: true
+			can_verify_hostname:    true
+			enabled_default:        false
+		}}
+		auth: configuration._http_auth & {_args: {
+			password_example: "${HTTP_PASSWORD}"
+			username_example: "${HTTP_USERNAME}"
+			oauth2:           true
+			aws:              true
+		}}
 	}
 
 	output: metrics: {
@@ -163,6 +175,12 @@ components: sources: apache_metrics: {
 				}
 			}
 		}
+		scrape_errors_total: {
+			description:       "The number of failed scrapes of the endpoint, including error responses and responses that couldn't be parsed."
+			type:              "counter"
+			default_namespace: "apache"
+			tags:              _apache_metrics_tags
+		}
 		sent_bytes_total: {
 			description:       "The amount of bytes sent by the Apache server."
 			relevant_when:     "`ExtendedStatus On`"
@@ -196,7 +214,16 @@ components: sources: apache_metrics: {
 		}
 	}
 
-	how_it_works: {}
+	how_it_works: {
+		endpoint_tags: {
+			title: "Endpoint Tags"
+			body: """
+				Metrics are tagged with the `endpoint` they were scraped from, without its
+				credentials, and its `host`. Other tags can be set on each endpoint, for example
+				`endpoints = [{ url = "http://localhost:8080/server-status/?auto", tags = { datacenter = "eu-west" } }]`.
+				"""
+		}
+	}
 
 	telemetry: metrics: {
 		http_error_response_total:    components.sources.internal_metrics.output.metrics.http_error_response_total
@@ -0,0 +1,206 @@
+package metadata
+
+components: sources: auditd: {
+	title: "Linux Audit"
+
+	description: """
+		Collects the records of the Linux Audit subsystem, from the audit netlink
+		socket or as an audisp plugin, and reassembles the records of each event
+		into a single log.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: service:       services.auditd
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            false
+			"x86_64-pc-windows-msv":          false
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+
+		requirements: [
+			"""
+				In `netlink` mode, Linux 3.16 or later, and Vector running as root or with
+				the `CAP_AUDIT_READ` capability.
+				""",
+		]
+		warnings: []
+		notices: [
+			"""
+				The audit rules are managed by `auditctl`, or the rules of `auditd`. This
+				source only reads the records they produce.
+				""",
+		]
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: "The key name added to each event representing the current host. This can also be globally set via the [global `host_key` option][docs.reference.configuration.global-options#host_key]."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "host"
+				syntax:  "literal"
+			}
+		}
+		max_in_flight: {
+			common:      false
+			description: "The most events being reassembled at once. When exceeded, the oldest event is emitted with the records received so far."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 256
+				unit:    null
+			}
+		}
+		mode: {
+			common:      true
+			description: "Where the records are read from."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "netlink"
+				enum: {
+					netlink: "Reads the records from the audit netlink socket, alongside `auditd`."
+					audisp:  "Reads the records from STDIN, with Vector started as an [audisp plugin](\(urls.audisp_plugins))."
+				}
+				syntax: "literal"
+			}
+		}
+		reassembly_timeout_secs: {
+			common:      false
+			description: "How long the records of an event are waited for, before it's emitted without its end of event record. Also accepts a duration such as `\"1m30s\"`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 2
+				unit:    "seconds"
+			}
+		}
+	}
+
+	how_it_works: {
+		reassembly: {
+			title: "Event Reassembly"
+			body: """
+				The kernel emits an event as several records, such as `SYSCALL`, `CWD`,
+				`PATH` and `PROCTITLE`, which share the serial number of the event, and ends
+				it with an `EOE` record. The records are grouped by serial number until that
+				`EOE` record, or `reassembly_timeout_secs`. The records of user space, such as
+				`USER_LOGIN`, make up an event on their own.
+				"""
+		}
+
+		fields: {
+			title: "Record Fields"
+			body: """
+				Each record is parsed into its `key=value` fields, under the lowercase name of
+				its [type](\(urls.auditd_record_types)), and the records of the same type,
+				such as `PATH`, make up an array. The hex encoded values of fields such as
+				`proctitle`, `name` or the arguments of `EXECVE` are decoded, and the
+				`msg='...'` of user space records is parsed into fields of its own. Values
+				are kept as strings.
+				"""
+		}
+
+		rule_keys: {
+			title: "Rule Keys"
+			body: """
+				The keys of the rules which matched the event, set with `auditctl -k`, are
+				listed in the `keys` field, so events can be routed by rule.
+				"""
+		}
+
+		netlink: {
+			title: "Netlink Mode"
+			body: """
+				In `netlink` mode, the source joins the multicast group of the audit netlink
+				socket, so `auditd` keeps running and writing its own logs. Records dropped
+				because Vector can't keep up are counted by the `events_discarded_total`
+				internal metric.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		connection_read_errors_total: components.sources.internal_metrics.output.metrics.connection_read_errors_total
+		events_discarded_total:       components.sources.internal_metrics.output.metrics.events_discarded_total
+		parse_errors_total:           components.sources.internal_metrics.output.metrics.parse_errors_total
+		processed_events_total:       components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+
+	output: logs: event: {
+		description: "An audit event, made of one or more records."
+		fields: {
+			host: fields._local_host
+			keys: {
+				description: "The keys of the rules which matched the event."
+				required:    false
+				type: array: items: type: string: {
+					examples: ["passwd_changes"]
+					syntax: "literal"
+				}
+			}
+			node: {
+				description: "The node name of the records, in `audisp` mode with `name_format` set."
+				required:    false
+				type: string: {
+					examples: ["web1"]
+					syntax: "literal"
+				}
+			}
+			sequence: {
+				description: "The serial number of the event."
+				required:    true
+				type: uint: {
+					examples: [24287]
+					unit: null
+				}
+			}
+			timestamp: {
+				description: "The time of the event."
+				required:    true
+				type: timestamp: {}
+			}
+			type: {
+				description: "The type of the first record of the event."
+				required:    true
+				type: string: {
+					examples: ["SYSCALL", "USER_LOGIN"]
+					syntax: "literal"
+				}
+			}
+			"*": {
+				description: "The fields of the records of the event, keyed by the lowercase name of their type, such as `syscall.comm` or `path[0].name`."
+				required:    true
+				type: "*": {}
+			}
+		}
+	}
+}
@@ -85,7 +85,7 @@ components: sources: aws_ecs_metrics: {
 			}
 		}
 		scrape_interval_secs: {
-			description: "The interval between scrapes, in seconds."
+			description: "The interval between scrapes, in seconds. Also accepts a duration such as `\"1m30s\"`."
 			common:      true
 			required:    false
 			type: uint: {
@@ -0,0 +1,173 @@
+package metadata
+
+components: sources: datadog_agent: {
+	_port: 8080
+
+	title: "Datadog Agent"
+
+	description: """
+		Receives logs and metrics from Datadog Agents configured to forward their
+		data to Vector instead of Datadog.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.datadog_agent
+
+				interface: socket: {
+					direction: "incoming"
+					port:      _port
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		access_log: {
+			common:      false
+			description: "Log every request received, with its response status, size, duration and client address, and count them by status class."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		address: {
+			common:      true
+			description: "The address to accept connections on. The agents must be configured to send their logs (`logs_config.logs_dd_url`) and metrics (`dd_url`) to this address."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["0.0.0.0:\(_port)"]
+				syntax: "literal"
+			}
+		}
+		deny_origin: configuration._deny_origin
+		permit_origin: configuration._permit_origin
+		store_api_key: {
+			common:      false
+			description: "When enabled, the API key sent by the agents is kept in the metadata of the log events, for the `datadog_logs` sinks to send them with it. The key is never added to the fields of the events, and isn't kept by the disk buffers."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+	}
+
+	output: {
+		logs: line: {
+			description: "A log collected by a Datadog Agent."
+			fields: {
+				ddsource: {
+					description: "The integration the log comes from."
+					required:    false
+					type: string: {
+						examples: ["nginx"]
+						syntax: "literal"
+					}
+				}
+				ddtags: {
+					description: "The comma separated tags of the log."
+					required:    false
+					type: string: {
+						examples: ["env:prod,team:checkout"]
+						syntax: "literal"
+					}
+				}
+				host: fields._local_host
+				message: {
+					description: "The message of the log."
+					required:    true
+					type: string: {
+						examples: ["GET /index.html 200"]
+						syntax: "literal"
+					}
+				}
+				service: {
+					description: "The service the log belongs to."
+					required:    false
+					type: string: {
+						examples: ["checkout"]
+						syntax: "literal"
+					}
+				}
+				status: {
+					description: "The severity of the log."
+					required:    false
+					type: string: {
+						examples: ["info"]
+						syntax: "literal"
+					}
+				}
+				timestamp: fields._current_timestamp
+			}
+		}
+		metrics: {
+			counter:      output._passthrough_counter
+			distribution: output._passthrough_distribution
+			gauge:        output._passthrough_gauge
+		}
+	}
+
+	how_it_works: {
+		metric_types: {
+			title: "Metric types"
+			body: """
+				Gauges are received as absolute gauges, and counts as incremental
+				counters. Rates are converted back to incremental counters, by
+				multiplying them by their interval. The tags of the series are
+				split on their first `:`, and the host of the series is kept in a
+				`host` tag.
+
+				The sketches of the distributions are received as incremental
+				distributions, each bin of a sketch becoming a sample of the lower
+				bound of the bin, so the values are only accurate to about 1%.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		connections_rejected_total:               components.sources.internal_metrics.output.metrics.connections_rejected_total
+		http_server_request_duration_nanoseconds: components.sources.internal_metrics.output.metrics.http_server_request_duration_nanoseconds
+		http_server_requests_total:               components.sources.internal_metrics.output.metrics.http_server_requests_total
+		request_read_errors_total:                components.sources.internal_metrics.output.metrics.request_read_errors_total
+		requests_received_total:                  components.sources.internal_metrics.output.metrics.requests_received_total
+	}
+}
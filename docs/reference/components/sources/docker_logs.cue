@@ -252,7 +252,7 @@ components: sources: docker_logs: {
 		}
 		retry_backoff_secs: {
 			common: false
-			description: """
+			description: "" Also accepts a duration such as `\"1m30s\"`."
 				The amount of time to wait before retrying after an error.
 				"""
 			required: false
@@ -0,0 +1,282 @@
+package metadata
+
+components: sources: ebpf: {
+	title: "eBPF"
+
+	description: """
+		Loads eBPF programs into the Linux kernel, and emits the process executions,
+		TCP connections and DNS queries of the host as logs.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: service:       services.host
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  false
+			"armv7-unknown-linux-musleabihf": false
+			"x86_64-apple-darwin":            false
+			"x86_64-pc-windows-msv":          false
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+
+		requirements: [
+			"""
+				Linux 5.8 or later, built with BTF (`CONFIG_DEBUG_INFO_BTF`), so that
+				`/sys/kernel/btf/vmlinux` exists.
+				""",
+			"""
+				Vector should run as root, or with the `CAP_BPF` and `CAP_PERFMON`
+				capabilities.
+				""",
+		]
+		warnings: []
+		notices: [
+			"""
+				This source isn't part of the default builds. It's built with the
+				`sources-ebpf` feature, which needs `clang`, `bpftool` and the libbpf headers.
+				""",
+		]
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		events: {
+			common:      true
+			description: "The kinds of events to emit."
+			required:    false
+			warnings: []
+			type: array: {
+				default: ["exec", "tcp", "dns"]
+				items: type: string: {
+					enum: {
+						exec: "Programs executed by processes."
+						tcp:  "TCP connections established and closed."
+						dns:  "DNS queries sent over UDP."
+					}
+					syntax: "literal"
+				}
+			}
+		}
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: "The key name added to each event representing the current host. This can also be globally set via the [global `host_key` option][docs.reference.configuration.global-options#host_key]."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "host"
+				syntax:  "literal"
+			}
+		}
+	}
+
+	how_it_works: {
+		programs: {
+			title: "Programs"
+			body: """
+				The programs are compiled once, with BPF CO-RE, and adapt to the kernel they're
+				loaded into. Executions are traced by the `sched_process_exec` tracepoint, TCP
+				connections by the `inet_sock_set_state` tracepoint, and DNS queries by kprobes
+				on `udp_sendmsg` and `udpv6_sendmsg`. Only the programs of the configured
+				`events` are attached.
+				"""
+		}
+
+		attribution: {
+			title: "Process Attribution"
+			body: """
+				The `pid`, `uid` and `comm` of an event are those of the task running when it
+				happened. Connections accepted, and closed by the peer, are usually handled by
+				the kernel outside of the process owning them, so their process fields shouldn't
+				be relied upon.
+				"""
+		}
+
+		lost_events: {
+			title: "Lost Events"
+			body: """
+				Events are sent through a perf buffer. When Vector can't keep up, events are
+				dropped, and counted by the `events_discarded_total` internal metric.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+		parse_errors_total:     components.sources.internal_metrics.output.metrics.parse_errors_total
+		processed_bytes_total:  components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total: components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+
+	output: logs: {
+		_process_fields: {
+			host: fields._local_host
+			pid: {
+				description: "The process ID."
+				required:    true
+				type: uint: {
+					examples: [4242]
+					unit: null
+				}
+			}
+			uid: {
+				description: "The user ID of the process."
+				required:    true
+				type: uint: {
+					examples: [1000]
+					unit: null
+				}
+			}
+			comm: {
+				description: "The command name of the task, truncated to 15 bytes."
+				required:    true
+				type: string: {
+					examples: ["curl"]
+					syntax: "literal"
+				}
+			}
+			timestamp: fields._current_timestamp
+		}
+
+		_event_field: {
+			description: "The kind of event."
+			required:    true
+			type: string: syntax: "literal"
+		}
+
+		exec: {
+			description: "A program executed by a process."
+			fields:      _process_fields & {
+				event: _event_field & {type: string: examples: ["exec"]}
+				ppid: {
+					description: "The process ID of the parent."
+					required:    true
+					type: uint: {
+						examples: [1]
+						unit: null
+					}
+				}
+				filename: {
+					description: "The path of the executed program."
+					required:    true
+					type: string: {
+						examples: ["/usr/bin/curl"]
+						syntax: "literal"
+					}
+				}
+			}
+		}
+
+		tcp: {
+			description: "A TCP connection established or closed."
+			fields:      _process_fields & {
+				event: _event_field & {type: string: examples: ["tcp"]}
+				action: {
+					description: "What happened to the connection."
+					required:    true
+					type: string: {
+						enum: {
+							connect:     "A connection established by the host."
+							accept:      "A connection accepted by the host."
+							established: "A connection established from another state, such as a simultaneous open."
+							close:       "A connection closed, or a connection attempt that failed."
+						}
+						syntax: "literal"
+					}
+				}
+				source_address: {
+					description: "The local IP address."
+					required:    true
+					type: string: {
+						examples: ["10.0.0.2"]
+						syntax: "literal"
+					}
+				}
+				source_port: {
+					description: "The local port."
+					required:    true
+					type: uint: {
+						examples: [51234]
+						unit: null
+					}
+				}
+				destination_address: {
+					description: "The remote IP address."
+					required:    true
+					type: string: {
+						examples: ["93.184.216.34", "2001:db8::1"]
+						syntax: "literal"
+					}
+				}
+				destination_port: {
+					description: "The remote port."
+					required:    true
+					type: uint: {
+						examples: [443]
+						unit: null
+					}
+				}
+			}
+		}
+
+		dns: {
+			description: "A DNS query sent over UDP."
+			fields:      _process_fields & {
+				event: _event_field & {type: string: examples: ["dns"]}
+				destination_address: {
+					description: "The IP address of the DNS server."
+					required:    true
+					type: string: {
+						examples: ["127.0.0.53", "2001:4860:4860::8888"]
+						syntax: "literal"
+					}
+				}
+				destination_port: {
+					description: "The port of the DNS server."
+					required:    true
+					type: uint: {
+						examples: [53]
+						unit: null
+					}
+				}
+				query: {
+					description: "The name looked up by the first question of the query."
+					required:    true
+					type: string: {
+						examples: ["example.com"]
+						syntax: "literal"
+					}
+				}
+				query_type: {
+					description: "The type of record looked up, `TYPE` followed by its number for uncommon types."
+					required:    true
+					type: string: {
+						examples: ["A", "AAAA", "TYPE64"]
+						syntax: "literal"
+					}
+				}
+			}
+		}
+	}
+}
@@ -50,6 +50,42 @@ components: sources: file: {
 	}
 
 	configuration: {
+		acknowledgements: {
+			common:      false
+			description: "Only checkpoints the lines once their events were delivered by the sinks with `acknowledgements` enabled, for at-least-once delivery. The file of a line whose delivery errored is read again from that line, while the lines that failed in a way retrying wouldn't fix are counted in the `events_delivery_failed_total` internal metric and checkpointed."
+			required:    false
+			type: bool: default: false
+		}
+		checkpoint: {
+			common:      false
+			description: "Configuration for the checkpoints of the source."
+			required:    false
+			type: object: options: {
+				import_path: {
+					common:      false
+					description: "A file written by the `vector checkpoints export` command, usually on another host, whose checkpoints are imported on startup. See [checkpoint migration](#checkpoint-migration)."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["/var/lib/vector/checkpoints.json"]
+						syntax: "literal"
+					}
+				}
+			}
+		}
+		decoding: {
+			common:      false
+			description: "How each line is decoded into an event."
+			required:    false
+			type: string: {
+				default: "bytes"
+				enum: {
+					bytes: "The line is the `message` of the event."
+					json:  "The line is a JSON object holding the fields of the event. Lines that aren't JSON objects are handled according to `on_decode_error`."
+				}
+				syntax: "literal"
+			}
+		}
 		exclude: {
 			common:      false
 			description: "Array of file patterns to exclude. [Globbing](#globbing) is supported.*Takes precedence over the [`include` option](#include).*"
@@ -93,7 +129,7 @@ components: sources: file: {
 				}
 				ignored_header_bytes: {
 					common:        false
-					description:   "The number of bytes to skip ahead (or ignore) when generating a unique fingerprint. This is helpful if all files share a common header."
+					description:   "The number of bytes to skip ahead (or ignore) when generating a unique fingerprint. This is helpful if all files share a common header. Also accepts a size such as `\"5MiB\"`."
 					relevant_when: "strategy = \"checksum\""
 					required:      false
 					type: uint: {
@@ -130,7 +166,7 @@ components: sources: file: {
 		}
 		ignore_older: {
 			common:      true
-			description: "Ignore files with a data modification date that does not exceed this age."
+			description: "Ignore files with a data modification date that does not exceed this age. Also accepts a duration such as `\"1m30s\"`."
 			required:    false
 			type: uint: {
 				default: null
@@ -158,7 +194,7 @@ components: sources: file: {
 		}
 		max_line_bytes: {
 			common:      false
-			description: "The maximum number of a bytes a line can contain before being discarded. This protects against malformed lines or tailing incorrect files."
+			description: "The maximum number of a bytes a line can contain before being discarded. This protects against malformed lines or tailing incorrect files. Also accepts a size such as `\"5MiB\"`."
 			required:    false
 			type: uint: {
 				default: 102_400
@@ -168,7 +204,7 @@ components: sources: file: {
 		max_read_bytes: {
 			category:    "Reading"
 			common:      false
-			description: "An approximate limit on the amount of data read from a single file at a given time."
+			description: "An approximate limit on the amount of data read from a single file at a given time. Also accepts a size such as `\"5MiB\"`."
 			required:    false
 			type: uint: {
 				default: null
@@ -176,6 +212,19 @@ components: sources: file: {
 				unit: "bytes"
 			}
 		}
+		on_decode_error: {
+			common:      false
+			description: "What the source does with the lines that fail to decode."
+			required:    false
+			type: string: {
+				default: "drop"
+				enum: {
+					drop:    "The line is discarded."
+					forward: "An event describing the error is sent to the `decode_errors` output of the source, see [decode errors](#decode-errors)."
+				}
+				syntax: "literal"
+			}
+		}
 		oldest_first: {
 			category:    "Reading"
 			common:      false
@@ -185,7 +234,7 @@ components: sources: file: {
 		}
 		remove_after: {
 			common:      false
-			description: "Timeout from reaching `eof` after which file will be removed from filesystem, unless new data is written in the meantime. If not specified, files will not be removed."
+			description: "Timeout from reaching `eof` after which file will be removed from filesystem, unless new data is written in the meantime. If not specified, files will not be removed. Also accepts a duration such as `\"1m30s\"`."
 			required:    false
 			warnings: ["Vector's process must have permission to delete files."]
 			type: uint: {
@@ -276,6 +325,22 @@ components: sources: file: {
 				"""
 		}
 
+		checkpoint_migration: {
+			title: "Checkpoint migration"
+			body:  """
+				To move a `file` source to another host without reading its files
+				again, export its checkpoints with `vector checkpoints export`, and
+				point the `checkpoint.import_path` option of the source on the new
+				host to the exported file. The imported checkpoints are loaded on
+				startup, unless the source already has a more recent checkpoint for
+				the same file, so the option can be left in place.
+
+				Only the `checksum` fingerprint strategy identifies files across
+				hosts, the checkpoints of the `device_and_inode` strategy are not
+				imported.
+				"""
+		}
+
 		compressed_files: {
 			title: "Compressed Files"
 			body: """
@@ -296,6 +361,28 @@ components: sources: file: {
 				"""
 		}
 
+		decode_errors: {
+			title: "Decode Errors"
+			body: """
+				With `decoding = "json"` and `on_decode_error = "forward"`, each line that
+				fails to decode, such as a JSON line cut short by a crashing application, is
+				sent to the `decode_errors` output of the source instead of its main output.
+				The event's `decode_error` field holds the error `message`, the first 1 KiB of
+				the line as `raw`, its length as `raw_bytes` and whether it was `truncated`,
+				and the `file` field the path of the file. Other components take the output
+				as input as `<source>.decode_errors`:
+
+				```toml
+				[sinks.faulty_lines]
+				  type = "file"
+				  inputs = ["in.decode_errors"]
+				```
+
+				The lines that fail to decode are acknowledged like the other lines, whether
+				they're forwarded or dropped.
+				"""
+		}
+
 		file_deletion: {
 			title: "File Deletion"
 			body: """
@@ -545,6 +632,8 @@ components: sources: file: {
 		checkpoint_write_errors_total: components.sources.internal_metrics.output.metrics.checkpoint_write_errors_total
 		checkpoints_total:             components.sources.internal_metrics.output.metrics.checkpoints_total
 		checksum_errors_total:         components.sources.internal_metrics.output.metrics.checksum_errors_total
+		decode_errors_total:           components.sources.internal_metrics.output.metrics.decode_errors_total
+		events_delivery_failed_total:  components.sources.internal_metrics.output.metrics.events_delivery_failed_total
 		file_delete_errors_total:      components.sources.internal_metrics.output.metrics.file_delete_errors_total
 		file_watch_errors_total:       components.sources.internal_metrics.output.metrics.file_watch_errors_total
 		files_added_total:             components.sources.internal_metrics.output.metrics.files_added_total
@@ -0,0 +1,262 @@
+package metadata
+
+components: sources: haproxy_metrics: {
+	title: "HAProxy Metrics"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["daemon", "sidecar"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: services.haproxy
+
+				interface: {
+					socket: {
+						api: {
+							title: "HAProxy statistics CSV format"
+							url:   urls.haproxy_stats_csv
+						}
+						direction: "outgoing"
+						protocols: ["http", "unix"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: [
+			"The stats page, or a stats socket, should be enabled.",
+		]
+
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		endpoints: {
+			description: "The CSV export of HAProxy stats pages, ending with `;csv`, or `unix://` paths of stats sockets. An endpoint can also be a table with its `url`, and `tags` to add to its metrics."
+			required:    true
+			type: array: {
+				items: type: string: {
+					examples: ["http://localhost:8404/stats;csv", "unix:///var/run/haproxy.sock"]
+					syntax: "literal"
+				}
+			}
+		}
+		scrape_interval_secs: {
+			description: "The interval between scrapes. Also accepts a duration such as `\"1m30s\"`."
+			common:      true
+			required:    false
+			type: uint: {
+				default: 15
+				unit:    "seconds"
+			}
+		}
+		namespace: {
+			description: "The namespace of metrics. Disabled if empty."
+			common:      false
+			required:    false
+			type: string: {
+				default: "haproxy"
+				syntax:  "literal"
+			}
+		}
+		tls: configuration._tls_connect & {_args: {
+			can_enable:             true
+			can_verify_certificate: true
+			can_verify_hostname:    true
+			enabled_default:        false
+		}}
+		auth: configuration._http_auth & {_args: {
+			password_example: "${HTTP_PASSWORD}"
+			username_example: "${HTTP_USERNAME}"
+			oauth2:           true
+			aws:              true
+		}}
+	}
+
+	how_it_works: {
+		stats: {
+			title: "Statistics"
+			body: """
+				The statistics are read in the [CSV format][urls.haproxy_stats_csv], either from
+				the stats page, or with the `show stat` command of a stats socket, which should be
+				readable by Vector. Each frontend, backend, server and listener gets its own metrics,
+				tagged with its `proxy`, `service` and `type`. Fields that don't apply to it, and are
+				left empty by HAProxy, aren't emitted.
+				"""
+		}
+
+		endpoint_tags: {
+			title: "Endpoint Tags"
+			body: """
+				Metrics are tagged with the `endpoint` they come from, without its credentials, and
+				the `host` of HTTP endpoints. Other tags can be set for each endpoint, for example
+				`endpoints = [{ url = "unix:///var/run/haproxy.sock", tags = { role = "ingress" } }]`.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		collect_completed_total:      components.sources.internal_metrics.output.metrics.collect_completed_total
+		collect_duration_nanoseconds: components.sources.internal_metrics.output.metrics.collect_duration_nanoseconds
+		http_request_errors_total:    components.sources.internal_metrics.output.metrics.http_request_errors_total
+		parse_errors_total:           components.sources.internal_metrics.output.metrics.parse_errors_total
+	}
+
+	output: metrics: {
+		_haproxy_endpoint_tags: {
+			endpoint: {
+				description: "The HAProxy endpoint."
+				required:    true
+				examples: ["http://localhost:8404/stats;csv", "unix:///var/run/haproxy.sock"]
+			}
+			host: {
+				description: "The hostname of the HAProxy server, for HTTP endpoints."
+				required:    false
+				examples: [_values.local_host]
+			}
+		}
+
+		_haproxy_service_tags: _haproxy_endpoint_tags & {
+			proxy: {
+				description: "The name of the proxy."
+				required:    true
+				examples: ["http-in", "app"]
+			}
+			service: {
+				description: "The name of the service, `FRONTEND` and `BACKEND` for frontends and backends."
+				required:    true
+				examples: ["FRONTEND", "BACKEND", "web1"]
+			}
+			type: {
+				description: "The type of the service."
+				required:    true
+				examples: ["frontend", "backend", "server", "listener"]
+			}
+		}
+
+		_haproxy_counter: {
+			type:              "counter"
+			default_namespace: "haproxy"
+			tags:              _haproxy_service_tags
+		}
+
+		_haproxy_gauge: {
+			type:              "gauge"
+			default_namespace: "haproxy"
+			tags:              _haproxy_service_tags
+		}
+
+		up: {
+			description:       "If the statistics could be read or not."
+			type:              "gauge"
+			default_namespace: "haproxy"
+			tags:              _haproxy_endpoint_tags
+		}
+		scrape_errors_total: {
+			description:       "The number of failed scrapes of the endpoint."
+			type:              "counter"
+			default_namespace: "haproxy"
+			tags:              _haproxy_endpoint_tags
+		}
+		active_servers: _haproxy_gauge & {
+			description: "The number of active servers of a backend, or if a server is active."
+		}
+		backup_servers: _haproxy_gauge & {
+			description: "The number of backup servers of a backend, or if a server is a backup."
+		}
+		bytes_in_total: _haproxy_counter & {
+			description: "The total number of bytes received."
+		}
+		bytes_out_total: _haproxy_counter & {
+			description: "The total number of bytes sent."
+		}
+		check_failures_total: _haproxy_counter & {
+			description: "The total number of failed health checks of a server."
+		}
+		connection_errors_total: _haproxy_counter & {
+			description: "The total number of errors connecting to a server."
+		}
+		current_queue: _haproxy_gauge & {
+			description: "The current number of requests waiting for a server."
+		}
+		current_session_rate: _haproxy_gauge & {
+			description: "The number of sessions per second over the last second."
+		}
+		current_sessions: _haproxy_gauge & {
+			description: "The current number of sessions."
+		}
+		downtime_seconds_total: _haproxy_counter & {
+			description: "The total time a backend or a server has been down."
+		}
+		http_requests_total: _haproxy_counter & {
+			description: "The total number of HTTP requests received by a frontend."
+		}
+		http_responses_total: _haproxy_counter & {
+			description: "The total number of HTTP responses, by class of status code."
+			tags:        _haproxy_service_tags & {
+				code: {
+					description: "The class of the status code."
+					required:    true
+					examples: ["1xx", "2xx", "3xx", "4xx", "5xx", "other"]
+				}
+			}
+		}
+		limit_sessions: _haproxy_gauge & {
+			description: "The configured limit of sessions."
+		}
+		redispatch_warnings_total: _haproxy_counter & {
+			description: "The total number of requests redispatched to another server."
+		}
+		request_errors_total: _haproxy_counter & {
+			description: "The total number of request errors of a frontend."
+		}
+		requests_denied_total: _haproxy_counter & {
+			description: "The total number of requests denied by security concerns."
+		}
+		response_errors_total: _haproxy_counter & {
+			description: "The total number of response errors of a backend or a server."
+		}
+		responses_denied_total: _haproxy_counter & {
+			description: "The total number of responses denied by security concerns."
+		}
+		retry_warnings_total: _haproxy_counter & {
+			description: "The total number of connections to a server retried."
+		}
+		service_up: _haproxy_gauge & {
+			description: "If the service is up, or open for frontends, or not."
+		}
+		sessions_total: _haproxy_counter & {
+			description: "The total number of sessions."
+		}
+		weight: _haproxy_gauge & {
+			description: "The weight of a server, or the total weight of the servers of a backend."
+		}
+	}
+}
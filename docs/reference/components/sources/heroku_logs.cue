@@ -68,8 +68,11 @@ components: sources: heroku_logs: {
 	}
 
 	configuration: {
+		access_log:       sources.http.configuration.access_log
 		address:          sources.http.configuration.address
 		auth:             sources.http.configuration.auth
+		deny_origin: configuration._deny_origin
+		permit_origin: configuration._permit_origin
 		query_parameters: sources.http.configuration.query_parameters
 	}
 
@@ -106,7 +109,11 @@ components: sources: heroku_logs: {
 	}
 
 	telemetry: metrics: {
-		request_read_errors_total: components.sources.internal_metrics.output.metrics.request_read_errors_total
-		requests_received_total:   components.sources.internal_metrics.output.metrics.requests_received_total
+		connections_rejected_total:               components.sources.internal_metrics.output.metrics.connections_rejected_total
+		http_authentication_failures_total:       components.sources.internal_metrics.output.metrics.http_authentication_failures_total
+		http_server_request_duration_nanoseconds: components.sources.internal_metrics.output.metrics.http_server_request_duration_nanoseconds
+		http_server_requests_total:               components.sources.internal_metrics.output.metrics.http_server_requests_total
+		request_read_errors_total:                components.sources.internal_metrics.output.metrics.request_read_errors_total
+		requests_received_total:                  components.sources.internal_metrics.output.metrics.requests_received_total
 	}
 }
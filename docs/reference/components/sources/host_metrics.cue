@@ -97,7 +97,7 @@ components: sources: host_metrics: {
 			}
 		}
 		scrape_interval_secs: {
-			description: "The interval between metric gathering, in seconds."
+			description: "The interval between metric gathering, in seconds. Also accepts a duration such as `\"1m30s\"`."
 			common:      true
 			required:    false
 			type: uint: {
@@ -60,6 +60,13 @@ components: sources: http: {
 	}
 
 	configuration: {
+		access_log: {
+			common:      false
+			description: "Log every request received, with its response status, size, duration and client address, and count them by status class."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
 		address: {
 			description: "The address to accept connections on. The address _must_ include a port."
 			required:    true
@@ -68,6 +75,7 @@ components: sources: http: {
 				syntax: "literal"
 			}
 		}
+		deny_origin: configuration._deny_origin
 		encoding: {
 			common:      true
 			description: "The expected encoding of received data. Note that for `json` and `ndjson` encodings, the fields of the JSON objects are output as separate fields."
@@ -94,7 +102,8 @@ components: sources: http: {
 				}
 			}
 		}
-		auth: configuration._http_basic_auth
+		auth: configuration._http_source_auth
+		permit_origin: configuration._permit_origin
 		query_parameters: {
 			common:      false
 			description: "A list of URL query parameters to include in the log event. These will override any values included in the body with conflicting names."
@@ -200,8 +209,12 @@ components: sources: http: {
 	]
 
 	telemetry: metrics: {
-		http_bad_requests_total: components.sources.internal_metrics.output.metrics.http_bad_requests_total
-		parse_errors_total:      components.sources.internal_metrics.output.metrics.parse_errors_total
+		connections_rejected_total:               components.sources.internal_metrics.output.metrics.connections_rejected_total
+		http_authentication_failures_total:       components.sources.internal_metrics.output.metrics.http_authentication_failures_total
+		http_bad_requests_total:                  components.sources.internal_metrics.output.metrics.http_bad_requests_total
+		http_server_request_duration_nanoseconds: components.sources.internal_metrics.output.metrics.http_server_request_duration_nanoseconds
+		http_server_requests_total:               components.sources.internal_metrics.output.metrics.http_server_requests_total
+		parse_errors_total:                       components.sources.internal_metrics.output.metrics.parse_errors_total
 	}
 
 	how_it_works: {
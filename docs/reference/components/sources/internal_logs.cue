@@ -74,6 +74,14 @@ components: sources: internal_logs: {
 				type: object: {
 					examples: []
 					options: {
+						component_name: {
+							description: "The name of the component the log or trace was emitted on behalf of. Only present for those emitted by a component."
+							required:    false
+							type: string: {
+								examples: ["my_source"]
+								syntax: "literal"
+							}
+						}
 						kind: {
 							description: "What kind of call site caused this log or trace."
 							required:    true
@@ -148,6 +148,27 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		adaptive_concurrency_current_limit: {
+			description:       "The concurrency limit that the adaptive concurrency feature has decided on for the last window."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		adaptive_concurrency_decisions_total: {
+			description:       "The total number of times the adaptive concurrency feature increased or decreased its concurrency limit."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				decision: {
+					description: "Whether the limit was increased or decreased."
+					required:    true
+					enum: {
+						increase: "The limit was increased, as the responses were quick."
+						decrease: "The limit was decreased, due to back pressure or slower responses."
+					}
+				}
+			}
+		}
 		adaptive_concurrency_in_flight: {
 			description:       "The number of outbound requests from the HTTP sink currently awaiting a response."
 			type:              "histogram"
@@ -166,6 +187,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		anomalies_detected_total: {
+			description:       "The total number of anomalous values flagged by the `anomaly_detection` transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		checkpoint_write_errors_total: {
 			description:       "The total number of errors writing checkpoints."
 			type:              "counter"
@@ -198,6 +225,30 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		command_executed_total: {
+			description:       "The total number of times a command has been executed."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				exit_status: {
+					description: "The exit status of the command, or `unknown` when it was killed by a signal."
+					required:    true
+					examples: ["0", "1", "unknown"]
+				}
+			}
+		}
+		command_execution_duration_nanoseconds: {
+			description:       "The command execution duration in nanoseconds."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				exit_status: {
+					description: "The exit status of the command, or `unknown` when it was killed by a signal."
+					required:    true
+					examples: ["0", "1", "unknown"]
+				}
+			}
+		}
 		communication_errors_total: {
 			description:       "The total number of errors stemming from communication with the Docker daemon."
 			type:              "counter"
@@ -218,6 +269,21 @@ components: sources: internal_metrics: {
 				}
 			}
 		}
+		connections_rejected_total: {
+			description:       "The total number of connections, or datagrams in UDP mode, rejected because their origin isn't permitted by `permit_origin` and `deny_origin`."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				mode: {
+					description: "The connection mode used by the component."
+					required:    true
+					enum: {
+						tcp: "Transmission Control Protocol"
+						udp: "User Datagram Protocol"
+					}
+				}
+			}
+		}
 		consumer_offset_updates_failed_total: {
 			description:       "The total number of failures to update a Kafka consumer offset."
 			type:              "counter"
@@ -266,6 +332,18 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		dedupe_persistence_errors_total: {
+			description:       "The total number of errors persisting the `dedupe` transform's cache to disk."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		decode_errors_total: {
+			description:       "The total number of frames or lines that couldn't be decoded into an event."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		encode_errors_total: {
 			description:       "The total number of errors encountered when encoding an event."
 			type:              "counter"
@@ -278,6 +356,75 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		buffer_events_dropped_total: {
+			description:       "The total number of events dropped by the buffer of a sink set to drop them by priority."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				priority: {
+					description: "The priority of the dropped events."
+					required:    true
+					enum: {
+						low:    "Dropped once the buffer is half full."
+						normal: "Dropped when the buffer is full."
+					}
+				}
+			}
+		}
+		buffer_byte_size: {
+			description:       "The size in bytes of the events stored in the disk buffer of a sink, and not acknowledged yet."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		buffer_corrupted_records_total: {
+			description:       "The total number of corrupted disk buffer segments found, whose remaining records were dropped."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		buffer_events: {
+			description:       "The number of events stored in the disk buffer of a sink, and not acknowledged yet."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		buffer_low_space_total: {
+			description:       "The total number of times the disk buffer of a sink found less space available than its `min_free_space`, and was considered full."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		counter_resets_total: {
+			description:       "The total number of absolute counters that decreased, as their source restarted."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		data_dir_available_bytes: {
+			description:       "The space available on the file system holding the disk buffer of a sink, in bytes."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		expired_metric_series_total: {
+			description:       "The total number of metric series a sink forgot, as they weren't updated for `expire_metrics_secs`."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		events_shed_total: {
+			description:       "The total number of events dropped by a source set to shed them while downstream is saturated."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		events_delivery_failed_total: {
+			description:       "The total number of events a source acknowledged although its sinks failed to deliver them, as retrying them would fail again."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		events_failed_total: {
 			description:       "The total number of failures to read a Kafka message."
 			type:              "counter"
@@ -356,6 +503,23 @@ components: sources: internal_metrics: {
 				path: _path
 			}
 		}
+		http_authentication_failures_total: {
+			description:       "The total number of HTTP requests rejected by the authentication of this component."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				reason: {
+					description: "Why the request was rejected."
+					required:    true
+					enum: {
+						invalid_credentials: "The `Authorization` header didn't match any of the accepted credentials."
+						invalid_signature:   "The signature of the body didn't match."
+						missing_credentials: "The request had no `Authorization` header."
+						missing_signature:   "The request had no signature header."
+					}
+				}
+			}
+		}
 		http_bad_requests_total: {
 			description:       "The total number of HTTP `400 Bad Request` errors encountered."
 			type:              "counter"
@@ -380,6 +544,30 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		http_server_request_duration_nanoseconds: {
+			description:       "The time taken to serve the HTTP requests received by this component, when its `access_log` option is enabled."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				status_class: {
+					description: "The class of the response status, such as `2xx` or `4xx`."
+					required:    true
+					examples: ["2xx"]
+				}
+			}
+		}
+		http_server_requests_total: {
+			description:       "The total number of HTTP requests served by this component, when its `access_log` option is enabled."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				status_class: {
+					description: "The class of the response status, such as `2xx` or `4xx`."
+					required:    true
+					examples: ["2xx"]
+				}
+			}
+		}
 		invalid_record_total: {
 			description:       "The total number of invalid records that have been discarded."
 			type:              "counter"
@@ -392,6 +580,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		log_patterns_created_total: {
+			description:       "The total number of new log patterns discovered by the `log_patterns` transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		logging_driver_errors_total: {
 			description: """
 				The total number of logging driver errors encountered caused by not using either
@@ -577,6 +771,18 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		udp_receive_drops_total: {
+			description:       "The total number of datagrams the kernel dropped on the UDP sockets bound to a port because their receive buffer was full, as reported by `/proc/net/udp`. Only available on Linux."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				port: {
+					description: "The port the sockets are bound to."
+					required:    true
+					examples: ["514"]
+				}
+			}
+		}
 		uptime_seconds: {
 			description:       "The total number of seconds the Vector instance has been up."
 			type:              "gauge"
@@ -606,6 +812,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		windows_flushed_total: {
+			description:       "The total number of aggregation windows flushed by the `sql` transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 
 		// Windows metrics
 		windows_service_does_not_exist_total: {
@@ -34,6 +34,12 @@ components: sources: kafka: {
 	}
 
 	configuration: {
+		acknowledgements: {
+			common:      false
+			description: "Only stores the offsets of the messages once their events were delivered by the sinks with `acknowledgements` enabled, for at-least-once delivery. Messages whose delivery errored hold the offsets of their partition back until they're consumed again, while the ones that failed in a way retrying wouldn't fix are counted in the `events_delivery_failed_total` internal metric and don't."
+			required:    false
+			type: bool: default: false
+		}
 		auto_offset_reset: {
 			common:      false
 			description: "If offsets for consumer group do not exist, set them using this strategy. [librdkafka documentation][urls.librdkafka_config] for `auto.offset.reset` option for explanation."
@@ -239,6 +245,7 @@ components: sources: kafka: {
 
 	telemetry: metrics: {
 		consumer_offset_updates_failed_total: components.sources.internal_metrics.output.metrics.consumer_offset_updates_failed_total
+		events_delivery_failed_total:         components.sources.internal_metrics.output.metrics.events_delivery_failed_total
 		events_failed_total:                  components.sources.internal_metrics.output.metrics.events_failed_total
 		processed_bytes_total:                components.sources.internal_metrics.output.metrics.processed_bytes_total
 		processed_events_total:               components.sources.internal_metrics.output.metrics.processed_events_total
@@ -0,0 +1,270 @@
+package metadata
+
+components: sources: macos_unified_log: {
+	title: "macOS Unified Log"
+
+	description: """
+		Streams the messages of the macOS unified logging system, which are kept
+		in a binary store the `file` source can't read.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: service:       services.macos_unified_log
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      false
+			"aarch64-unknown-linux-musl":     false
+			"armv7-unknown-linux-gnueabihf":  false
+			"armv7-unknown-linux-musleabihf": false
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          false
+			"x86_64-unknown-linux-gnu":       false
+			"x86_64-unknown-linux-musl":      false
+		}
+
+		requirements: [
+			"""
+				Vector should run as root, or as a member of the `admin` group, to stream
+				the messages of all of the processes.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		event_types: {
+			common:      false
+			description: "The types of events to stream. All of them are streamed when empty."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					enum: {
+						activity: "The creation and the transitions of activities."
+						log:      "The messages logged."
+						trace:    "The trace messages."
+					}
+					syntax: "literal"
+				}
+			}
+		}
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: "The key name added to each event representing the current host. This can also be globally set via the [global `host_key` option][docs.reference.configuration.global-options#host_key]."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "host"
+				syntax:  "literal"
+			}
+		}
+		level: {
+			common:      true
+			description: "The most verbose level of the messages streamed."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "default"
+				enum: {
+					default: "The `default`, `error` and `fault` messages."
+					info:    "The `info` messages too."
+					debug:   "All of the messages."
+				}
+				syntax: "literal"
+			}
+		}
+		log_path: {
+			common:      false
+			description: "The path of the `log` command."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "/usr/bin/log"
+				syntax:  "literal"
+			}
+		}
+		predicate: {
+			common:      true
+			description: "The [predicate](\(urls.apple_log_predicates)) filtering the messages, as accepted by `log stream --predicate`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: [#"subsystem == "com.apple.sharing""#, #"process == "sshd" AND messageType == error"#]
+				syntax: "literal"
+			}
+		}
+	}
+
+	how_it_works: {
+		log_stream: {
+			title: "Log Stream"
+			body: """
+				The messages are read from `log stream --style ndjson`, which is restarted
+				when it stops. Filtering them with a `predicate` is more efficient than with a
+				transform, as it's done by the logging system. Only the messages logged while
+				Vector runs are collected.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		parse_errors_total:     components.sources.internal_metrics.output.metrics.parse_errors_total
+		processed_bytes_total:  components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total: components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+
+	output: logs: entry: {
+		description: "A message of the unified logging system."
+		fields: {
+			activity_id: {
+				description: "The identifier of the activity the message belongs to."
+				required:    false
+				type: uint: {
+					examples: [1234]
+					unit: null
+				}
+			}
+			category: {
+				description: "The category of the message, within its subsystem."
+				required:    false
+				type: string: {
+					examples: ["network"]
+					syntax: "literal"
+				}
+			}
+			event_type: {
+				description: "The type of the event."
+				required:    false
+				type: string: {
+					examples: ["logEvent", "activityCreateEvent"]
+					syntax: "literal"
+				}
+			}
+			format_string: {
+				description: "The format string the message was logged with."
+				required:    false
+				type: string: {
+					examples: ["Connection to %{public}@ established"]
+					syntax: "literal"
+				}
+			}
+			host: fields._local_host
+			level: {
+				description: "The level of the message."
+				required:    false
+				type: string: {
+					examples: ["default", "info", "debug", "error", "fault"]
+					syntax: "literal"
+				}
+			}
+			message: {
+				description: "The message, with its arguments formatted."
+				required:    true
+				type: string: {
+					examples: ["Connection to 10.0.0.2 established"]
+					syntax: "literal"
+				}
+			}
+			parent_activity_id: {
+				description: "The identifier of the parent of the activity of the message."
+				required:    false
+				type: uint: {
+					examples: [1233]
+					unit: null
+				}
+			}
+			pid: {
+				description: "The process ID of the process which logged the message."
+				required:    false
+				type: uint: {
+					examples: [420]
+					unit: null
+				}
+			}
+			process: {
+				description: "The name of the process which logged the message."
+				required:    false
+				type: string: {
+					examples: ["sshd"]
+					syntax: "literal"
+				}
+			}
+			process_path: {
+				description: "The path of the executable of the process."
+				required:    false
+				type: string: {
+					examples: ["/usr/sbin/sshd"]
+					syntax: "literal"
+				}
+			}
+			sender: {
+				description: "The name of the library or executable which logged the message."
+				required:    false
+				type: string: {
+					examples: ["libnetwork.dylib"]
+					syntax: "literal"
+				}
+			}
+			sender_path: {
+				description: "The path of the library or executable which logged the message."
+				required:    false
+				type: string: {
+					examples: ["/usr/lib/libnetwork.dylib"]
+					syntax: "literal"
+				}
+			}
+			subsystem: {
+				description: "The subsystem of the message, usually the reverse DNS name of its application."
+				required:    false
+				type: string: {
+					examples: ["com.apple.sharing"]
+					syntax: "literal"
+				}
+			}
+			tid: {
+				description: "The thread ID of the thread which logged the message."
+				required:    false
+				type: uint: {
+					examples: [781214]
+					unit: null
+				}
+			}
+			timestamp: {
+				description: "The time the message was logged."
+				required:    true
+				type: timestamp: {}
+			}
+			uid: {
+				description: "The user ID of the process."
+				required:    false
+				type: uint: {
+					examples: [501]
+					unit: null
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,226 @@
+package metadata
+
+components: sources: mongodb_change_stream: {
+	title:       "MongoDB Change Stream"
+	description: "Captures the changes made to a [MongoDB][urls.mongodb] deployment, database or collection through a [change stream][urls.mongodb_change_streams]."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: {
+					name:     "MongoDB"
+					thing:    "a \(name) replica set or sharded cluster"
+					url:      urls.mongodb
+					versions: "4.2+"
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "MongoDB change streams"
+							url:   urls.mongodb_change_streams
+						}
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: [
+			"""
+				Change streams are only available on replica sets and sharded clusters.
+				""",
+			"""
+				User from endpoint should have the `changeStream` and `find` privileges on the
+				watched collections, and `readAnyDatabase` to watch the whole deployment.
+				""",
+		]
+
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		endpoint: {
+			description: "MongoDB [Connection String URI Format][urls.mongodb_connection_string_uri_format]"
+			required:    true
+			type: string: {
+				examples: ["mongodb://localhost:27017/?replicaSet=rs0"]
+				syntax: "literal"
+			}
+		}
+		database: {
+			description: "The database to watch. All the databases of the deployment are watched if unset."
+			common:      true
+			required:    false
+			type: string: {
+				default: null
+				examples: ["shop"]
+				syntax: "literal"
+			}
+		}
+		collection: {
+			description: "The collection of `database` to watch. All its collections are watched if unset."
+			common:      true
+			required:    false
+			type: string: {
+				default: null
+				examples: ["orders"]
+				syntax: "literal"
+			}
+		}
+		full_document: {
+			description: "Which changes carry the full document."
+			common:      false
+			required:    false
+			type: string: {
+				default: "default"
+				enum: {
+					default:       "Only inserts and replaces."
+					update_lookup: "Updates too, with the current version of the document, which may include later changes."
+				}
+				syntax: "literal"
+			}
+		}
+	}
+
+	how_it_works: {
+		resume_tokens: {
+			title: "Resume Tokens"
+			body: """
+				The resume token of the last change forwarded is checkpointed, and the change stream
+				is opened after it on restart, so no change is lost as long as it's still in the
+				oplog. Without changes, the token is checkpointed every minute, for it not to fall
+				out of the oplog.
+				"""
+		}
+
+		invalidate: {
+			title: "Invalidate Events"
+			body: """
+				Dropping or renaming the watched collection, or dropping the watched database,
+				closes the change stream after an `invalidate` change. The change is forwarded and
+				the change stream is opened again after it.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		checkpoint_write_errors_total: components.sources.internal_metrics.output.metrics.checkpoint_write_errors_total
+		processed_events_total:        components.sources.internal_metrics.output.metrics.processed_events_total
+		request_errors_total:          components.sources.internal_metrics.output.metrics.request_errors_total
+	}
+
+	output: logs: {
+		change: {
+			description: "A change to the watched collections."
+			fields: {
+				op: {
+					description: "The kind of change."
+					required:    true
+					type: string: {
+						examples: ["insert", "update", "replace", "delete", "drop", "rename", "dropDatabase", "invalidate"]
+						syntax: "literal"
+					}
+				}
+				database: {
+					description: "The database of the change."
+					required:    false
+					common:      true
+					type: string: {
+						examples: ["shop"]
+						syntax: "literal"
+					}
+				}
+				collection: {
+					description: "The collection of the change."
+					required:    false
+					common:      true
+					type: string: {
+						examples: ["orders"]
+						syntax: "literal"
+					}
+				}
+				document_key: {
+					description: "The `_id` of the document, and the shard key in sharded collections."
+					required:    false
+					common:      true
+					type: object: {
+						examples: [{"_id": "5f9b2a7c8e1d4a3b2c1d0e0f"}]
+						options: {}
+					}
+				}
+				full_document: {
+					description: "The document, see `full_document`."
+					required:    false
+					common:      true
+					type: object: {
+						examples: [{"_id": "5f9b2a7c8e1d4a3b2c1d0e0f", "status": "shipped"}]
+						options: {}
+					}
+				}
+				updated_fields: {
+					description: "The fields set by an update, by their dotted path."
+					required:    false
+					common:      true
+					type: object: {
+						examples: [{"status": "shipped", "items.0.count": 2}]
+						options: {}
+					}
+				}
+				removed_fields: {
+					description: "The fields removed by an update."
+					required:    false
+					common:      true
+					type: array: items: type: string: {
+						examples: ["note"]
+						syntax: "literal"
+					}
+				}
+				to: {
+					description: "The new database and collection names of a `rename`."
+					required:    false
+					common:      false
+					type: object: {
+						examples: [{"db": "shop", "coll": "orders_archive"}]
+						options: {}
+					}
+				}
+				timestamp: {
+					description: "The time of the change's oplog entry."
+					required:    true
+					type: timestamp: {}
+				}
+			}
+		}
+	}
+}
@@ -50,6 +50,12 @@ components: sources: mongodb_metrics: {
 				User from endpoint should have enough privileges for running
 				[serverStatus][urls.mongodb_command_server_status] command.
 				""",
+			"""
+				On replica set members, the user also needs to be able to run the
+				[replSetGetStatus][urls.mongodb_command_repl_set_get_status] command,
+				and the [collStats][urls.mongodb_command_coll_stats] command on the
+				databases of `collections`.
+				""",
 		]
 
 		warnings: []
@@ -72,7 +78,7 @@ components: sources: mongodb_metrics: {
 			}
 		}
 		scrape_interval_secs: {
-			description: "The interval between scrapes."
+			description: "The interval between scrapes. Also accepts a duration such as `\"1m30s\"`."
 			common:      true
 			required:    false
 			type: uint: {
@@ -80,6 +86,18 @@ components: sources: mongodb_metrics: {
 				unit:    "seconds"
 			}
 		}
+		collections: {
+			description: "The collections to collect metrics for, as `<database>.<collection>`."
+			common:      false
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["shop.orders"]
+					syntax: "literal"
+				}
+			}
+		}
 		namespace: {
 			description: "The namespace of metrics. Disabled if empty."
 			common:      false
@@ -103,6 +121,17 @@ components: sources: mongodb_metrics: {
 				`replica set` member.
 				"""
 		}
+		replication_lag: {
+			title: "Replication lag"
+			body: """
+				On replica set members, the
+				[replSetGetStatus][urls.mongodb_command_repl_set_get_status]
+				command gives the health of every member and the time of the
+				last operation each applied. The replication lag of a member
+				is how far that time is behind the primary's, it isn't
+				reported while the replica set has no primary.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
@@ -127,6 +156,19 @@ components: sources: mongodb_metrics: {
 			}
 		}
 
+		_mongodb_collection_tags: _mongodb_metrics_tags & {
+			database: {
+				description: "The database of the collection."
+				required:    true
+				examples: ["shop"]
+			}
+			collection: {
+				description: "The name of the collection."
+				required:    true
+				examples: ["orders"]
+			}
+		}
+
 		assets_total: {
 			description:       "Number of assertions raised since the MongoDB process started."
 			type:              "counter"
@@ -145,6 +187,36 @@ components: sources: mongodb_metrics: {
 			default_namespace: "mongodb"
 			tags:              _mongodb_metrics_tags
 		}
+		collection_documents: {
+			description:       "The number of documents in the collection."
+			type:              "gauge"
+			default_namespace: "mongodb"
+			tags:              _mongodb_collection_tags
+		}
+		collection_index_size_bytes: {
+			description:       "The total size of the indexes of the collection."
+			type:              "gauge"
+			default_namespace: "mongodb"
+			tags:              _mongodb_collection_tags
+		}
+		collection_indexes: {
+			description:       "The number of indexes of the collection."
+			type:              "gauge"
+			default_namespace: "mongodb"
+			tags:              _mongodb_collection_tags
+		}
+		collection_size_bytes: {
+			description:       "The uncompressed size of the documents of the collection."
+			type:              "gauge"
+			default_namespace: "mongodb"
+			tags:              _mongodb_collection_tags
+		}
+		collection_storage_size_bytes: {
+			description:       "The storage allocated to the collection."
+			type:              "gauge"
+			default_namespace: "mongodb"
+			tags:              _mongodb_collection_tags
+		}
 		connections: {
 			description:       "Number of connections in some state."
 			type:              "gauge"
@@ -462,6 +534,35 @@ components: sources: mongodb_metrics: {
 				}
 			}
 		}
+		mongod_replset_member_health: {
+			description:       "The health of the replica set member, `1` if it's up and `0` if it's down."
+			type:              "gauge"
+			default_namespace: "mongodb"
+			tags:              _mongodb_metrics_tags & {
+				member: {
+					description: "The host and port of the member."
+					required:    true
+					examples: ["mongo-1:27017"]
+				}
+				state: {
+					description: "The state of the member."
+					required:    true
+					examples: ["PRIMARY", "SECONDARY", "ARBITER"]
+				}
+			}
+		}
+		mongod_replset_member_replication_lag_seconds: {
+			description:       "How far the last operation applied by the replica set member is behind the primary's."
+			type:              "gauge"
+			default_namespace: "mongodb"
+			tags:              _mongodb_metrics_tags & {
+				member: {
+					description: "The host and port of the member."
+					required:    true
+					examples: ["mongo-1:27017"]
+				}
+			}
+		}
 		mongod_storage_engine: {
 			description:       "The name of the current storage engine."
 			type:              "gauge"
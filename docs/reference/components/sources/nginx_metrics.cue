@@ -59,7 +59,7 @@ components: sources: nginx_metrics: {
 
 	configuration: {
 		endpoints: {
-			description: "HTTP/HTTPS endpoint to Nginx server with enabled `ngx_http_stub_status_module` module."
+			description: "HTTP/HTTPS endpoint to Nginx server with enabled `ngx_http_stub_status_module` module. An endpoint can also be a table with its `url`, and `tags` to add to its metrics."
 			required:    true
 			type: array: {
 				items: type: string: {
@@ -69,7 +69,7 @@ components: sources: nginx_metrics: {
 			}
 		}
 		scrape_interval_secs: {
-			description: "The interval between scrapes."
+			description: "The interval between scrapes. Also accepts a duration such as `\"1m30s\"`."
 			common:      true
 			required:    false
 			type: uint: {
@@ -95,6 +95,8 @@ components: sources: nginx_metrics: {
 		auth: configuration._http_auth & {_args: {
 			password_example: "${HTTP_PASSWORD}"
 			username_example: "${HTTP_USERNAME}"
+			oauth2:           true
+			aws:              true
 		}}
 	}
 
@@ -107,6 +109,15 @@ components: sources: nginx_metrics: {
 				information is a simple web page with text data.
 				"""
 		}
+
+		endpoint_tags: {
+			title: "Endpoint Tags"
+			body: """
+				Besides the `endpoint`, without its credentials, and the `host` of the server,
+				the metrics of an endpoint get the tags it sets, for example
+				`endpoints = [{ url = "http://localhost:8000/basic_status", tags = { pool = "edge" } }]`.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
@@ -137,6 +148,12 @@ components: sources: nginx_metrics: {
 			default_namespace: "nginx"
 			tags:              _nginx_metrics_tags
 		}
+		scrape_errors_total: {
+			description:       "The number of failed scrapes of the endpoint."
+			type:              "counter"
+			default_namespace: "nginx"
+			tags:              _nginx_metrics_tags
+		}
 		connections_active: {
 			description:       "The current number of active client connections including `Waiting` connections."
 			type:              "gauge"
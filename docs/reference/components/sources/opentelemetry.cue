@@ -0,0 +1,210 @@
+package metadata
+
+components: sources: opentelemetry: {
+	_port: 4317
+
+	title: "OpenTelemetry"
+
+	description: """
+		Receives logs, metrics, and traces from OpenTelemetry SDKs and
+		collectors over the OTLP/gRPC protocol.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator", "sidecar"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.opentelemetry
+
+				interface: socket: {
+					api: {
+						title: "OTLP/gRPC"
+						url:   urls.otlp
+					}
+					direction: "incoming"
+					port:      _port
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			description: "The address to accept gRPC connections on. The address _must_ include a port."
+			required:    true
+			type: string: {
+				examples: ["0.0.0.0:\(_port)"]
+				syntax: "literal"
+			}
+		}
+		max_message_bytes: {
+			common:      false
+			description: "The maximum size of a request message, after decompression. Larger requests are rejected with the `RESOURCE_EXHAUSTED` status. Also accepts a size such as `\"5MiB\"`."
+			required:    false
+			type: uint: {
+				default: 4194304
+				unit:    "bytes"
+			}
+		}
+	}
+
+	output: {
+		logs: {
+			log: {
+				description: "An OTLP log record."
+				fields: {
+					attributes: {
+						description: "The attributes of the log record."
+						required:    false
+						common:      true
+						type: object: {}
+					}
+					message: {
+						description: "The body of the log record."
+						required:    true
+						type: "*": {}
+					}
+					observed_timestamp: {
+						description: "The time the log record was observed by the OpenTelemetry SDK or collector."
+						required:    false
+						common:      false
+						type: timestamp: {}
+					}
+					resource: {
+						description: "The attributes of the resource the log record comes from."
+						required:    false
+						common:      true
+						type: object: {}
+					}
+					scope: {
+						description: "The name, version, and attributes of the instrumentation scope of the log record."
+						required:    false
+						common:      false
+						type: object: {}
+					}
+					severity_number: {
+						description: "The severity of the log record, from 1 (`TRACE`) to 24 (`FATAL4`)."
+						required:    false
+						common:      true
+						type: uint: {
+							examples: [9, 17]
+							unit: null
+						}
+					}
+					severity_text: {
+						description: "The severity of the log record, as named by its source."
+						required:    false
+						common:      true
+						type: string: {
+							examples: ["INFO", "ERROR"]
+							syntax: "literal"
+						}
+					}
+					span_id: {
+						description: "The hex encoded ID of the span the log record was emitted in."
+						required:    false
+						common:      false
+						type: string: {
+							examples: ["00f067aa0ba902b7"]
+							syntax: "literal"
+						}
+					}
+					timestamp: {
+						description: "The time of the log record, or the time it was observed if unset."
+						required:    true
+						type: timestamp: {}
+					}
+					trace_id: {
+						description: "The hex encoded ID of the trace the log record was emitted in."
+						required:    false
+						common:      false
+						type: string: {
+							examples: ["4bf92f3577b34da6a3ce929d0e0e4736"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		metrics: {
+			counter:   output._passthrough_counter
+			gauge:     output._passthrough_gauge
+			histogram: output._passthrough_histogram
+			summary:   output._passthrough_summary
+		}
+	}
+
+	how_it_works: {
+		signals: {
+			title: "Signals"
+			body: """
+				The source serves the `Export` calls of the OTLP
+				`LogsService`, `MetricsService`, and `TraceService`:
+
+				* Log records are emitted as log events.
+				* Gauges, and non-monotonic sums, are emitted as gauges.
+				  Monotonic sums are emitted as counters. Sums and histograms
+				  with a delta aggregation temporality are incremental,
+				  cumulative ones are absolute.
+				* Histograms and summaries are emitted as aggregated
+				  histograms and summaries. Exponential histograms aren't
+				  supported and are dropped.
+				* Spans are emitted as trace events.
+
+				The resource and instrumentation scope attributes of metrics are
+				added to their tags, with the `resource.` and `scope.` prefixes.
+				"""
+		}
+		compression: {
+			title: "Compression"
+			body: """
+				Request messages may be compressed with `gzip`, as set by the
+				`grpc-encoding` header. Other encodings are rejected with the
+				`UNIMPLEMENTED` status.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processed_bytes_total:  components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total: components.sources.internal_metrics.output.metrics.processed_events_total
+		request_errors_total:   components.sources.internal_metrics.output.metrics.request_errors_total
+	}
+}
@@ -0,0 +1,217 @@
+package metadata
+
+components: sources: osquery: {
+	title: "osquery"
+
+	description: """
+		Tails the results logs of osquery, and emits a log for each row of the
+		results of its scheduled queries.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: services.osquery
+
+				interface: file_system: {
+					directory: "/var/log/osquery"
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: [
+			"""
+				osqueryd should log with its default `filesystem` logger plugin.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: "The key name added to each event representing the current host. This can also be globally set via the [global `host_key` option][docs.reference.configuration.global-options#host_key]."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "host"
+				syntax:  "literal"
+			}
+		}
+		include: {
+			common:      true
+			description: "The results logs to tail. Globbing is supported."
+			required:    false
+			warnings: []
+			type: array: {
+				default: ["/var/log/osquery/osqueryd.results.log", "/var/log/osquery/osqueryd.snapshots.log"]
+				items: type: string: {
+					examples: ["/var/osquery/logs/osqueryd.results.log"]
+					syntax: "literal"
+				}
+			}
+		}
+		max_line_bytes: {
+			common:      false
+			description: "The maximum size of a line of results. Longer lines are discarded. Also accepts a size such as `\"5MiB\"`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1048576
+				unit:    "bytes"
+			}
+		}
+		read_from_beginning: {
+			common:      false
+			description: "Read the logs found when Vector first starts from their beginning, instead of their end. Once checkpointed, logs are always read from where Vector left off."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+	}
+
+	how_it_works: {
+		formats: {
+			title: "Result Formats"
+			body: """
+				Each line of the [results logs](\(urls.osquery_logging)) holds the results of
+				a run of a query, in one of three formats, which are all supported:
+
+				* The event format, the default, with one row per line, under `columns`.
+				* The batch format, with `logger_event_type` disabled, with the rows added and
+				  removed since the last run under `diffResults`.
+				* The snapshot format, of the queries scheduled with `snapshot` enabled, with
+				  all of the rows of the run under `snapshot`.
+
+				A log is emitted for each row, with the name of the query, and whether the row
+				was `added`, `removed` or part of a `snapshot`.
+				"""
+		}
+
+		numerics: {
+			title: "Column Types"
+			body: """
+				The values of the columns keep the types osquery logged them with. Unless
+				osqueryd runs with `logger_numerics` enabled, they're all strings.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		checkpoint_write_errors_total: components.sources.internal_metrics.output.metrics.checkpoint_write_errors_total
+		checkpoints_total:             components.sources.internal_metrics.output.metrics.checkpoints_total
+		checksum_errors_total:         components.sources.internal_metrics.output.metrics.checksum_errors_total
+		file_delete_errors_total:      components.sources.internal_metrics.output.metrics.file_delete_errors_total
+		file_watch_errors_total:       components.sources.internal_metrics.output.metrics.file_watch_errors_total
+		files_added_total:             components.sources.internal_metrics.output.metrics.files_added_total
+		files_deleted_total:           components.sources.internal_metrics.output.metrics.files_deleted_total
+		files_resumed_total:           components.sources.internal_metrics.output.metrics.files_resumed_total
+		files_unwatched_total:         components.sources.internal_metrics.output.metrics.files_unwatched_total
+		fingerprint_read_errors_total: components.sources.internal_metrics.output.metrics.fingerprint_read_errors_total
+		glob_errors_total:             components.sources.internal_metrics.output.metrics.glob_errors_total
+		parse_errors_total:            components.sources.internal_metrics.output.metrics.parse_errors_total
+		processed_bytes_total:         components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:        components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+
+	output: logs: row: {
+		description: "A row of the results of a scheduled query."
+		fields: {
+			action: {
+				description: "Whether the row was added or removed since the last run, or is part of a snapshot."
+				required:    true
+				type: string: {
+					enum: {
+						added:    "The row was added since the last run."
+						removed:  "The row was removed since the last run."
+						snapshot: "The row is part of the results of a snapshot query."
+					}
+					syntax: "literal"
+				}
+			}
+			columns: {
+				description: "The columns of the row."
+				required:    true
+				type: object: {
+					examples: [{"pid": "42", "name": "nginx"}]
+					options: {}
+				}
+			}
+			counter: {
+				description: "The number of runs of the query since its epoch."
+				required:    false
+				type: uint: {
+					examples: [3]
+					unit: null
+				}
+			}
+			decorations: {
+				description: "The decorations of the results, added by the decorator queries."
+				required:    false
+				type: object: {
+					examples: [{"uuid": "4740D59F-699E-5B29-960B-979AAF9BBEEB"}]
+					options: {}
+				}
+			}
+			epoch: {
+				description: "The epoch of the differential results of the query."
+				required:    false
+				type: uint: {
+					examples: [0]
+					unit: null
+				}
+			}
+			host:            fields._local_host
+			host_identifier: {
+				description: "The identifier osquery gives the host, its hostname by default."
+				required:    false
+				type: string: {
+					examples: ["web1"]
+					syntax: "literal"
+				}
+			}
+			name: {
+				description: "The name of the scheduled query, prefixed by the name of its pack, if any."
+				required:    true
+				type: string: {
+					examples: ["pack_incident-response_processes"]
+					syntax: "literal"
+				}
+			}
+			timestamp: {
+				description: "The time of the run of the query."
+				required:    true
+				type: timestamp: {}
+			}
+		}
+	}
+}
@@ -69,7 +69,7 @@ components: sources: postgresql_metrics: {
 			}
 		}
 		scrape_interval_secs: {
-			description: "The interval between scrapes."
+			description: "The interval between scrapes. Also accepts a duration such as `\"1m30s\"`."
 			common:      true
 			required:    false
 			type: uint: {
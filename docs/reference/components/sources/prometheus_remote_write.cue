@@ -67,7 +67,9 @@ components: sources: prometheus_remote_write: {
 				syntax: "literal"
 			}
 		}
-		auth: configuration._http_basic_auth
+		auth: configuration._http_source_auth
+		deny_origin: configuration._deny_origin
+		permit_origin: configuration._permit_origin
 	}
 
 	output: metrics: {
@@ -94,13 +96,15 @@ components: sources: prometheus_remote_write: {
 	}
 
 	telemetry: metrics: {
-		http_error_response_total:    components.sources.internal_metrics.output.metrics.http_error_response_total
-		http_request_errors_total:    components.sources.internal_metrics.output.metrics.http_request_errors_total
-		parse_errors_total:           components.sources.internal_metrics.output.metrics.parse_errors_total
-		processed_bytes_total:        components.sources.internal_metrics.output.metrics.processed_bytes_total
-		processed_events_total:       components.sources.internal_metrics.output.metrics.processed_events_total
-		requests_completed_total:     components.sources.internal_metrics.output.metrics.requests_completed_total
-		requests_received_total:      components.sources.internal_metrics.output.metrics.requests_received_total
-		request_duration_nanoseconds: components.sources.internal_metrics.output.metrics.request_duration_nanoseconds
+		connections_rejected_total:         components.sources.internal_metrics.output.metrics.connections_rejected_total
+		http_authentication_failures_total: components.sources.internal_metrics.output.metrics.http_authentication_failures_total
+		http_error_response_total:          components.sources.internal_metrics.output.metrics.http_error_response_total
+		http_request_errors_total:          components.sources.internal_metrics.output.metrics.http_request_errors_total
+		parse_errors_total:                 components.sources.internal_metrics.output.metrics.parse_errors_total
+		processed_bytes_total:              components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:             components.sources.internal_metrics.output.metrics.processed_events_total
+		requests_completed_total:           components.sources.internal_metrics.output.metrics.requests_completed_total
+		requests_received_total:            components.sources.internal_metrics.output.metrics.requests_received_total
+		request_duration_nanoseconds:       components.sources.internal_metrics.output.metrics.request_duration_nanoseconds
 	}
 }
@@ -74,7 +74,7 @@ components: sources: prometheus_scrape: {
 		}
 		scrape_interval_secs: {
 			common:      true
-			description: "The interval between scrapes, in seconds."
+			description: "The interval between scrapes, in seconds. Also accepts a duration such as `\"1m30s\"`."
 			required:    false
 			warnings: []
 			type: uint: {
@@ -85,6 +85,8 @@ components: sources: prometheus_scrape: {
 		auth: configuration._http_auth & {_args: {
 			password_example: "${PROMETHEUS_PASSWORD}"
 			username_example: "${PROMETHEUS_USERNAME}"
+			oauth2:           true
+			aws:              true
 		}}
 	}
 
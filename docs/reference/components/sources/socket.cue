@@ -71,6 +71,7 @@ components: sources: socket: {
 				syntax: "literal"
 			}
 		}
+		deny_origin: configuration._deny_origin & {relevant_when: "mode = `tcp` or `udp`"}
 		host_key: {
 			category:    "Context"
 			common:      false
@@ -84,7 +85,7 @@ components: sources: socket: {
 		}
 		max_length: {
 			common:      true
-			description: "The maximum bytes size of incoming messages before they are discarded."
+			description: "The maximum bytes size of incoming messages before they are discarded. Also accepts a size such as `\"5MiB\"`."
 			required:    false
 			warnings: []
 			type: uint: {
@@ -116,9 +117,10 @@ components: sources: socket: {
 				syntax: "literal"
 			}
 		}
+		permit_origin: configuration._permit_origin & {relevant_when: "mode = `tcp` or `udp`"}
 		shutdown_timeout_secs: {
 			common:        false
-			description:   "The timeout before a connection is forcefully closed during shutdown."
+			description:   "The timeout before a connection is forcefully closed during shutdown. Also accepts a duration such as `\"1m30s\"`."
 			relevant_when: "mode = `tcp``"
 			required:      false
 			warnings: []
@@ -127,6 +129,17 @@ components: sources: socket: {
 				unit:    "seconds"
 			}
 		}
+		workers: {
+			common:        false
+			description:   "The number of sockets bound to the address, each received from by its own task. With more than one, the sockets share the address through `SO_REUSEPORT` and the kernel spreads the datagrams across them, which is only supported on Unix."
+			relevant_when: "mode = `udp`"
+			required:      false
+			warnings: []
+			type: uint: {
+				default: 1
+				unit:    null
+			}
+		}
 	}
 
 	output: logs: line: {
@@ -165,5 +178,7 @@ components: sources: socket: {
 		connection_failed_total:      components.sources.internal_metrics.output.metrics.connection_failed_total
 		connection_send_errors_total: components.sources.internal_metrics.output.metrics.connection_send_errors_total
 		connection_shutdown_total:    components.sources.internal_metrics.output.metrics.connection_shutdown_total
+		connections_rejected_total:   components.sources.internal_metrics.output.metrics.connections_rejected_total
+		udp_receive_drops_total:      components.sources.internal_metrics.output.metrics.udp_receive_drops_total
 	}
 }
@@ -62,6 +62,13 @@ components: sources: splunk_hec: {
 	}
 
 	configuration: {
+		access_log: {
+			common:      false
+			description: "Log every request received, with its response status, size, duration and client address, and count them by status class."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
 		address: {
 			common:      true
 			description: "The address to accept connections on."
@@ -72,6 +79,8 @@ components: sources: splunk_hec: {
 				syntax:  "literal"
 			}
 		}
+		deny_origin: configuration._deny_origin
+		permit_origin: configuration._permit_origin
 		token: {
 			common:      true
 			description: "If supplied, incoming requests must supply this token in the `Authorization` header, just as a client would if it was communicating with the Splunk HEC endpoint directly. If _not_ supplied, the `Authorization` header will be ignored and requests will not be authenticated."
@@ -99,7 +108,10 @@ components: sources: splunk_hec: {
 	}
 
 	telemetry: metrics: {
-		http_request_errors_total: components.sources.internal_metrics.output.metrics.http_request_errors_total
-		requests_received_total:   components.sources.internal_metrics.output.metrics.requests_received_total
+		connections_rejected_total:               components.sources.internal_metrics.output.metrics.connections_rejected_total
+		http_request_errors_total:                components.sources.internal_metrics.output.metrics.http_request_errors_total
+		http_server_request_duration_nanoseconds: components.sources.internal_metrics.output.metrics.http_server_request_duration_nanoseconds
+		http_server_requests_total:               components.sources.internal_metrics.output.metrics.http_server_requests_total
+		requests_received_total:                  components.sources.internal_metrics.output.metrics.requests_received_total
 	}
 }
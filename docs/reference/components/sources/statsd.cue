@@ -70,6 +70,7 @@ components: sources: statsd: {
 				syntax: "literal"
 			}
 		}
+		deny_origin: configuration._deny_origin & {relevant_when: "mode = `tcp` or `udp`"}
 		mode: {
 			description: "The type of socket to use."
 			required:    true
@@ -93,9 +94,10 @@ components: sources: statsd: {
 				syntax: "literal"
 			}
 		}
+		permit_origin: configuration._permit_origin & {relevant_when: "mode = `tcp` or `udp`"}
 		shutdown_timeout_secs: {
 			common:        false
-			description:   "The timeout before a connection is forcefully closed during shutdown."
+			description:   "The timeout before a connection is forcefully closed during shutdown. Also accepts a duration such as `\"1m30s\"`."
 			relevant_when: "mode = `tcp`"
 			required:      false
 			warnings: []
@@ -104,7 +106,17 @@ components: sources: statsd: {
 				unit:    "seconds"
 			}
 		}
-
+		workers: {
+			common:        false
+			description:   "The number of sockets bound to the address, each received from by its own task. With more than one, the sockets share the address through `SO_REUSEPORT` and the kernel spreads the datagrams across them, which is only supported on Unix."
+			relevant_when: "mode = `udp`"
+			required:      false
+			warnings: []
+			type: uint: {
+				default: 1
+				unit:    null
+			}
+		}
 	}
 
 	output: metrics: {
@@ -131,9 +143,11 @@ components: sources: statsd: {
 
 	telemetry: metrics: {
 		connection_errors_total:    components.sources.internal_metrics.output.metrics.connection_errors_total
+		connections_rejected_total: components.sources.internal_metrics.output.metrics.connections_rejected_total
 		invalid_record_total:       components.sources.internal_metrics.output.metrics.invalid_record_total
 		invalid_record_bytes_total: components.sources.internal_metrics.output.metrics.invalid_record_bytes_total
 		processed_bytes_total:      components.sources.internal_metrics.output.metrics.processed_bytes_total
 		processed_events_total:     components.sources.internal_metrics.output.metrics.processed_events_total
+		udp_receive_drops_total:    components.sources.internal_metrics.output.metrics.udp_receive_drops_total
 	}
 }
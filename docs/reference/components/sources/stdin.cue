@@ -13,7 +13,7 @@ components: sources: stdin: {
 	}
 
 	features: {
-		multiline: enabled: false
+		multiline: enabled: true
 		receive: {
 			from: {
 				service: services.stdin
@@ -45,6 +45,34 @@ components: sources: stdin: {
 	}
 
 	configuration: {
+		decoding: {
+			common:      false
+			description: "How each frame is decoded into an event."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "bytes"
+				enum: {
+					bytes: "The frame is the `message` of the event."
+					json:  "The frame is a JSON object holding the fields of the event. Frames that aren't JSON objects are handled according to `on_decode_error`."
+				}
+				syntax: "literal"
+			}
+		}
+		framing: {
+			common:      false
+			description: "How the bytes read from STDIN are split into frames."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "newline_delimited"
+				enum: {
+					newline_delimited: "Frames end with a newline, optionally preceded by a carriage return."
+					length_delimited:  "Frames start with their length in bytes, as a 4 bytes big-endian integer."
+				}
+				syntax: "literal"
+			}
+		}
 		host_key: {
 			category:    "Context"
 			common:      false
@@ -58,7 +86,7 @@ components: sources: stdin: {
 		}
 		max_length: {
 			common:      false
-			description: "The maximum bytes size of a message before rest of it will be discarded."
+			description: "The maximum bytes size of a frame. Longer frames are discarded. Also accepts a size such as `\"5MiB\"`."
 			required:    false
 			warnings: []
 			type: uint: {
@@ -66,6 +94,46 @@ components: sources: stdin: {
 				unit:    "bytes"
 			}
 		}
+		on_decode_error: {
+			common:      false
+			description: "What the source does with the frames that fail to decode."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "drop"
+				enum: {
+					drop:    "The frame is discarded."
+					forward: "An event describing the error is sent to the `decode_errors` output of the source, see [decode errors](#decode-errors)."
+				}
+				syntax: "literal"
+			}
+		}
+		on_eof: {
+			common:      false
+			description: "What the source does once it reads the end of STDIN."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "shutdown"
+				enum: {
+					shutdown:     "The source finishes, which shuts Vector down when it was the last source running."
+					keep_running: "The source keeps running until Vector shuts down, e.g. to keep serving the API or other sources."
+				}
+				syntax: "literal"
+			}
+		}
+		stream_key: {
+			category:    "Context"
+			common:      false
+			description: "The key name added to each event with the value `stdin`, if set."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["stream"]
+				syntax: "literal"
+			}
+		}
 	}
 
 	output: logs: line: {
@@ -101,12 +169,47 @@ components: sources: stdin: {
 		line_delimiters: {
 			title: "Line Delimiters"
 			body: """
-				Each line is read until a new line delimiter, the `0xA` byte, is found.
+				By default each line is read until a new line delimiter, the `0xA` byte, is found.
+				The `framing` option switches to frames prefixed with their length, and the
+				`multiline` options aggregate consecutive frames, such as the lines of a stack
+				trace, into a single event.
+				"""
+		}
+		ad_hoc_pipelines: {
+			title: "Ad-hoc Pipelines"
+			body: """
+				The source makes it possible to process the output of another command, for
+				example the JSON logs of a pod:
+
+				```bash
+				kubectl logs -f my-pod | vector --config vector.toml
+				```
+
+				with `decoding = "json"` to turn each line into the fields of an event. Vector
+				shuts down once the command exits, unless `on_eof` is set to `keep_running`.
+				"""
+		}
+		decode_errors: {
+			title: "Decode Errors"
+			body: """
+				With `on_decode_error = "forward"`, each frame that fails to decode, such as a
+				JSON line cut short by a crashing application, is sent to the `decode_errors`
+				output of the source instead of its main output. The event's `decode_error`
+				field holds the error `message`, the first 1 KiB of the frame as `raw`, its
+				length as `raw_bytes` and whether it was `truncated`. Other components take the
+				output as input as `<source>.decode_errors`:
+
+				```toml
+				[sinks.faulty_lines]
+				  type = "file"
+				  inputs = ["in.decode_errors"]
+				```
 				"""
 		}
 	}
 
 	telemetry: metrics: {
+		decode_errors_total:      components.sources.internal_metrics.output.metrics.decode_errors_total
 		processed_bytes_total:    components.sources.internal_metrics.output.metrics.processed_bytes_total
 		processed_events_total:   components.sources.internal_metrics.output.metrics.processed_events_total
 		stdin_reads_failed_total: components.sources.internal_metrics.output.metrics.stdin_reads_failed_total
@@ -203,8 +203,10 @@ components: sources: syslog: {
 
 	telemetry: metrics: {
 		connection_read_errors_total: components.sources.internal_metrics.output.metrics.connection_read_errors_total
+		connections_rejected_total:   components.sources.internal_metrics.output.metrics.connections_rejected_total
 		processed_bytes_total:        components.sources.internal_metrics.output.metrics.processed_bytes_total
 		processed_events_total:       components.sources.internal_metrics.output.metrics.processed_events_total
+		udp_receive_drops_total:      components.sources.internal_metrics.output.metrics.udp_receive_drops_total
 		utf8_convert_errors_total:    components.sources.internal_metrics.output.metrics.utf8_convert_errors_total
 	}
 }
@@ -72,9 +72,11 @@ components: sources: vector: {
 				syntax: "literal"
 			}
 		}
+		deny_origin: configuration._deny_origin
+		permit_origin: configuration._permit_origin
 		shutdown_timeout_secs: {
 			common:      false
-			description: "The timeout before a connection is forcefully closed during shutdown."
+			description: "The timeout before a connection is forcefully closed during shutdown. Also accepts a duration such as `\"1m30s\"`."
 			required:    false
 			warnings: []
 			type: uint: {
@@ -131,6 +133,7 @@ components: sources: vector: {
 	}
 
 	telemetry: metrics: {
+		connections_rejected_total:   components.sources.internal_metrics.output.metrics.connections_rejected_total
 		protobuf_decode_errors_total: components.sources.internal_metrics.output.metrics.protobuf_decode_errors_total
 	}
 }
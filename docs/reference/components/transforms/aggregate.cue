@@ -0,0 +1,117 @@
+package metadata
+
+components: transforms: aggregate: {
+	title: "Aggregate"
+
+	description: """
+		Merges the metric events of each series over a fixed window, and emits a
+		single metric per series at the end of each window. This reduces the
+		volume of metrics sent to the sinks, such as the per-event counters
+		emitted by the `log_to_metric` transform.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      true
+	}
+
+	features: {
+		reduce: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		interval_ms: {
+			common:      true
+			description: "The window over which the metrics of each series are merged, after which they are emitted."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10000
+				unit:    "milliseconds"
+			}
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	examples: [
+		{
+			title: "Aggregate counters"
+			configuration: {
+				interval_ms: 5000
+			}
+			input: [
+				{metric: {
+					kind: "incremental"
+					name: "requests_total"
+					tags: status: "200"
+					counter: value: 1.0
+				}},
+				{metric: {
+					kind: "incremental"
+					name: "requests_total"
+					tags: status: "200"
+					counter: value: 1.0
+				}},
+			]
+			output: [
+				{metric: {
+					kind: "incremental"
+					name: "requests_total"
+					tags: status: "200"
+					counter: value: 2.0
+				}},
+			]
+		},
+	]
+
+	how_it_works: {
+		series: {
+			title: "Series"
+			body: """
+				A series is identified by the name, namespace and tags of its metrics.
+				The incremental metrics of a series are added together: counters and
+				gauges are summed, the values of sets are merged, and the samples of
+				distributions, histograms and summaries are combined. An absolute metric
+				replaces the metric of its series, the later increments being added to it.
+				The emitted metric has the timestamp of the last metric merged into it.
+				"""
+		}
+		conflicting_values: {
+			title: "Conflicting Values"
+			body: """
+				A metric whose value can't be merged with the one of its series, such as
+				a gauge following a counter, or a histogram with other buckets, emits the
+				metric of the series right away and starts over from it.
+				"""
+		}
+	}
+}
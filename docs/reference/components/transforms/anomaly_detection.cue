@@ -0,0 +1,174 @@
+package metadata
+
+components: transforms: anomaly_detection: {
+	title: "Anomaly Detection"
+
+	description: """
+		Flags log events whose numeric field deviates from an exponentially weighted moving
+		average of recent values, tracked separately for each series.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		alpha: {
+			common:      false
+			description: "The weight given to the most recent value when updating the moving average, between `0` (exclusive) and `1`. Higher values adapt faster to change but tolerate less noise."
+			required:    false
+			warnings: []
+			type: float: default: 0.1
+		}
+		drop_normal: {
+			common:      true
+			description: "When `true`, only anomalous events are forwarded, turning this transform into an alerting stream."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		field: {
+			description: "The numeric field to track. Strings containing numbers are parsed, other values are passed through untouched."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["duration_ms", "response.bytes"]
+				syntax: "literal"
+			}
+		}
+		group_by: {
+			common:      true
+			description: "The fields whose values identify independent series. Each distinct combination is tracked with its own moving average."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["service", "host"]
+					syntax: "literal"
+				}
+			}
+		}
+		max_series: {
+			common:      false
+			description: "The maximum number of series kept in memory, greater than 0. The least recently seen series is forgotten when this is exceeded."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10000
+				unit:    null
+			}
+		}
+		min_samples: {
+			common:      false
+			description: "The number of values a series must have seen before any of its values are flagged."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10
+				unit:    null
+			}
+		}
+		target_field: {
+			common:      false
+			description: "The field the anomaly details are written to on flagged events."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "anomaly"
+				syntax:  "literal"
+			}
+		}
+		threshold: {
+			common:      true
+			description: "The number of standard deviations a value must be away from the moving average to be flagged."
+			required:    false
+			warnings: []
+			type: float: default: 3.0
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	output: logs: anomaly: {
+		description: "An event whose value was flagged as anomalous."
+		fields: anomaly: {
+			description: "The anomaly details, written to `target_field`."
+			required:    false
+			type: object: {
+				examples: [{"score": 14.2, "mean": 120.5, "stddev": 8.1}]
+				options: {
+					score: {
+						description: "The signed number of standard deviations the value is away from the mean."
+						required:    true
+						type: float: examples: [14.2]
+					}
+					mean: {
+						description: "The moving average of the series before this value."
+						required:    true
+						type: float: examples: [120.5]
+					}
+					stddev: {
+						description: "The moving standard deviation of the series before this value."
+						required:    true
+						type: float: examples: [8.1]
+					}
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		scoring: {
+			title: "Scoring"
+			body: """
+				Each series keeps an exponentially weighted moving mean and variance of `field`.
+				Every incoming value is scored against the series as it was before the value
+				arrived, as the number of standard deviations it lies from the mean, and is then
+				folded into the averages. Values whose absolute score exceeds `threshold` are
+				flagged. Anomalous values are folded in as well, so a lasting change in level
+				stops being flagged once the averages catch up.
+				"""
+		}
+
+		memory_usage: {
+			title: "Memory Usage"
+			body: """
+				Only three numbers are kept per series, alongside the values of the `group_by`
+				fields identifying it. The number of series is bounded by `max_series`; a series
+				evicted from the cache starts warming up again from scratch when it is next seen.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		anomalies_detected_total: components.sources.internal_metrics.output.metrics.anomalies_detected_total
+		processing_errors_total:  components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
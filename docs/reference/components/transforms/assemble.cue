@@ -0,0 +1,121 @@
+package metadata
+
+components: transforms: assemble: {
+	title: "Assemble"
+
+	description: """
+		Collects the events sharing the values of the `group_by` fields into a single event holding
+		them as an array, for the sinks whose APIs take batches of events as a single JSON document.
+		This is the inverse of the [`explode` transform][docs.transforms.explode].
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		field: {
+			common:      true
+			description: "The field of the assembled event holding the array of events."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "events"
+				syntax:  "literal"
+			}
+		}
+		group_by: {
+			common:      true
+			description: "The fields the events are grouped by. Their values are copied from the first event of the group to the assembled event. All the events are assembled together if empty."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["tenant", "host"]
+					syntax: "literal"
+				}
+			}
+		}
+		max_bytes: {
+			common:      false
+			description: "The maximum size of the events of an assembled event, encoded as JSON. An event that would exceed it assembles the group first, and starts the next one. Also accepts a size such as `\"5MiB\"`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				unit:    "bytes"
+			}
+		}
+		max_events: {
+			common:      true
+			description: "The maximum number of events of an assembled event. The group is assembled as soon as it is reached."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 100
+				unit:    "events"
+			}
+		}
+		timeout_ms: {
+			common:      true
+			description: "The time after which a group is assembled, counted from its first event."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    "milliseconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Assemble events by tenant"
+			configuration: {
+				group_by: ["tenant"]
+			}
+			input: [
+				{log: {tenant: "acme", message: "one"}},
+				{log: {tenant: "acme", message: "two"}},
+			]
+			output: [
+				{log: {
+					tenant: "acme"
+					events: [
+						{tenant: "acme", message: "one"},
+						{tenant: "acme", message: "two"},
+					]
+				}},
+			]
+		},
+	]
+}
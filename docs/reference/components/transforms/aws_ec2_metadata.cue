@@ -84,7 +84,7 @@ components: transforms: aws_ec2_metadata: {
 		}
 		refresh_interval_secs: {
 			common:      true
-			description: "The interval in seconds at which the EC2 Metadata api will be called."
+			description: "The interval in seconds at which the EC2 Metadata api will be called. Also accepts a duration such as `\"1m30s\"`."
 			required:    false
 			warnings: []
 			type: uint: {
@@ -0,0 +1,124 @@
+package metadata
+
+components: transforms: byte_attribution: {
+	title: "Byte Attribution"
+
+	description: """
+		Counts the bytes of the log events passing through, grouped by the values of
+		chosen fields, and periodically emits the totals as metric events. Place it in
+		front of an expensive sink to attribute its volume to the services or teams
+		producing it.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		convert: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		group_by: {
+			description: "The fields whose values the bytes are attributed to. Each field becomes a tag of the emitted metrics; events missing a field are counted without that tag."
+			required:    true
+			warnings: []
+			type: array: items: type: string: {
+				examples: ["service", "team"]
+				syntax: "field_path"
+			}
+		}
+		interval_secs: {
+			common:      true
+			description: "How often the accumulated totals are emitted. Also accepts a duration such as `\"1m30s\"`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		namespace: {
+			common:      false
+			description: "The namespace of the emitted metrics."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "vector"
+				examples: ["billing"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	output: metrics: {
+		attributed_bytes_total: {
+			description: "The bytes of the events of a group since the previous emission, estimated from their JSON encoding."
+			tags: {
+				"*": {
+					description: "The value of each `group_by` field."
+					examples: ["checkout"]
+					required: false
+				}
+			}
+			type:              "counter"
+			default_namespace: "vector"
+		}
+		attributed_events_total: {
+			description: "The number of events of a group since the previous emission."
+			tags: {
+				"*": {
+					description: "The value of each `group_by` field."
+					examples: ["checkout"]
+					required: false
+				}
+			}
+			type:              "counter"
+			default_namespace: "vector"
+		}
+	}
+
+	how_it_works: {
+		passthrough: {
+			title: "Passthrough"
+			body: """
+				Log events are forwarded unchanged, the metric events are emitted alongside
+				them. Route the metrics on to a metrics sink with a `filter` transform, or
+				send both to sinks that accept either.
+				"""
+		}
+
+		memory_usage: {
+			title: "Memory Usage"
+			body: """
+				Two counters are kept per group seen since the previous emission, and all of
+				them are dropped once emitted. The metrics are incremental, so totals over
+				longer periods are summed by the metrics backend.
+				"""
+		}
+	}
+}
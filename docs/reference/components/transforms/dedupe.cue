@@ -90,6 +90,37 @@ components: transforms: dedupe: {
 				}
 			}
 		}
+		persistence: {
+			common:      false
+			description: "Options controlling how the cache is persisted to disk so it survives restarts. The cache is kept in memory only when this is not set."
+			required:    false
+			warnings: []
+			type: object: {
+				options: {
+					data_dir: {
+						common:      false
+						description: "The directory used to persist the cache. By default, the global `data_dir` option is used. Please make sure the Vector project has write permissions to this dir."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["/var/local/lib/vector/"]
+							syntax: "literal"
+						}
+					}
+					sync_interval_ms: {
+						common:      false
+						description: "How often the cache's changes are synced to disk. Events processed since the last sync can be forgotten if the host crashes. Set to `0` to sync after every event, at the cost of throughput."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 1000
+							unit:    "milliseconds"
+						}
+					}
+				}
+			}
+		}
 	}
 
 	input: {
@@ -148,6 +179,20 @@ components: transforms: dedupe: {
 				"""
 		}
 
+		persistence: {
+			title: "Persistence"
+			body: """
+				When `persistence` is set, the cache is stored in a subdirectory of the
+				`data_dir` named after the transform, as a snapshot of the cache followed by
+				a log of every event processed since. Both are replayed on startup, so events
+				delivered again after a restart, as commonly happens with at-least-once
+				sources, are still recognized as duplicates. The snapshot is rewritten once
+				the log holds `cache.num_events` entries, which bounds the state on disk to
+				about twice the size of the cache. The global `state` option doesn't apply to
+				the cache, which is always kept in the `data_dir`.
+				"""
+		}
+
 		missing_fields: {
 			title: "Missing Fields"
 			body: """
@@ -160,6 +205,7 @@ components: transforms: dedupe: {
 	}
 
 	telemetry: metrics: {
-		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+		dedupe_persistence_errors_total: components.sources.internal_metrics.output.metrics.dedupe_persistence_errors_total
+		events_discarded_total:          components.sources.internal_metrics.output.metrics.events_discarded_total
 	}
 }
@@ -0,0 +1,90 @@
+package metadata
+
+components: transforms: explode: {
+	title: "Explode"
+
+	description: """
+		Emits one event per element of an array field, each a copy of the original event holding a
+		single element, in the order of the array. Useful for batched payloads, such as webhooks
+		delivering arrays of events.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		field: {
+			description: "The array field to emit an event for each element of. Events without it, or whose field isn't an array, are passed through as they are, while an empty array drops the event."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["events", "payload.records"]
+				syntax: "literal"
+			}
+		}
+		target: {
+			common:      false
+			description: "The field each element is written to. If unset, the fields of the elements that are maps are merged into the event, overwriting its own, and the other elements are written to `field`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["event"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Webhook batch"
+			configuration: {
+				field: "events"
+			}
+			input: log: {
+				source: "sendgrid"
+				events: [
+					{email: "a@example.com", event: "delivered"},
+					{email: "b@example.com", event: "bounce"},
+				]
+			}
+			output: [
+				{log: {source: "sendgrid", email: "a@example.com", event: "delivered"}},
+				{log: {source: "sendgrid", email: "b@example.com", event: "bounce"}},
+			]
+		},
+	]
+
+	telemetry: metrics: {
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
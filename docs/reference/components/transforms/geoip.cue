@@ -38,7 +38,12 @@ components: transforms: geoip: {
 		}
 		requirements: []
 		warnings: []
-		notices: []
+		notices: [
+			"""
+				The `get_geoip` and `get_asn` functions of the [`remap` transform](\(urls.vector_remap_transform))
+				look up the same databases, with full control over where the results are placed.
+				""",
+		]
 	}
 
 	configuration: {
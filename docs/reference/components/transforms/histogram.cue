@@ -0,0 +1,178 @@
+package metadata
+
+components: transforms: histogram: {
+	title: "Histogram"
+
+	description: """
+		Re-buckets histograms to a set of bucket boundaries, merges the
+		histograms of series told apart only by some tags, and estimates
+		quantiles from histograms and distributions as gauges, for backends
+		only accepting gauges.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		convert: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		buckets: {
+			common:      true
+			description: "The increasing upper limits of the buckets the histograms are re-bucketed to. Histograms keep their buckets when empty."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: float: examples: [0.1, 1.0, 10.0]
+			}
+		}
+		drop_tags: {
+			common:      true
+			description: "The tags dropped from the histograms, merging the series told apart only by them."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["instance", "pod"]
+					syntax: "literal"
+				}
+			}
+		}
+		expire_metrics_secs: {
+			common:      false
+			description: "Forget the merged series that weren't updated for this long. Overrides the global `expire_metrics_secs` option. Also accepts a duration such as `\"1m30s\"`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [300]
+				unit: "seconds"
+			}
+		}
+		quantiles: {
+			common:      false
+			description: "The quantiles, between `0.0` and `1.0`, estimated from the histograms and sent as gauges instead of them."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: float: examples: [0.5, 0.9, 0.99]
+			}
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	examples: [
+		{
+			title: "Re-bucket a histogram"
+			configuration: {
+				buckets: [1.0, 3.0]
+			}
+			input: metric: {
+				kind: "absolute"
+				name: "request_duration_seconds"
+				histogram: {
+					buckets: [
+						{upper_limit: 2.0, count: 10},
+						{upper_limit: 4.0, count: 10},
+					]
+					count: 20
+					sum:   50.0
+				}
+			}
+			output: metric: {
+				kind: "absolute"
+				name: "request_duration_seconds"
+				histogram: {
+					buckets: [
+						{upper_limit: 1.0, count: 5},
+						{upper_limit: 3.0, count: 10},
+					]
+					count: 20
+					sum:   50.0
+				}
+			}
+		},
+	]
+
+	how_it_works: {
+		rebucketing: {
+			title: "Re-bucketing"
+			body: """
+				The observations of each bucket are assumed to be evenly spread
+				between its lower limit, the upper limit of the previous bucket,
+				and its upper limit. The first bucket starts at zero. The
+				observations above the last of the `buckets` are only part of
+				the total count of the histogram, as are the ones above the last
+				bucket of the original histogram.
+
+				Distributions meant as histograms are counted into the
+				`buckets`, and become aggregated histograms.
+				"""
+		}
+		merging: {
+			title: "Merging"
+			body: """
+				The `drop_tags` are removed from the histograms. The last value
+				of each absolute histogram is kept, and the sum of the values of
+				the series merged is sent. Histograms with other buckets than the
+				first one of their merged series are re-bucketed to its buckets.
+				Incremental histograms only lose the tags, as their values add up
+				downstream.
+
+				The series not updated for `expire_metrics_secs` are forgotten,
+				and are no longer part of the sum.
+				"""
+		}
+		quantiles: {
+			title: "Quantile estimation"
+			body: """
+				With `quantiles`, each histogram is replaced by an absolute gauge
+				per quantile, tagged with the quantile as `quantile`. Quantiles of
+				aggregated histograms are interpolated within the bucket holding
+				them, like the `histogram_quantile` function of Prometheus, and
+				quantiles above the last bucket are estimated as its upper limit.
+				Quantiles of distributions are the sample at their rank.
+				Histograms without observations send no gauges.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		expired_metric_series_total: components.sources.internal_metrics.output.metrics.expired_metric_series_total
+	}
+}
@@ -0,0 +1,184 @@
+package metadata
+
+components: transforms: log_patterns: {
+	title: "Log Patterns"
+
+	description: """
+		Incrementally clusters log messages into patterns with the Drain algorithm and tags each
+		event with the id and template of its pattern, optionally emitting a periodic count of
+		the events seen per pattern.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		parse: {
+			format: {
+				name:     "Drain"
+				url:      urls.drain
+				versions: null
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		depth: {
+			common:      false
+			description: "The number of leading tokens used to route a message to its candidate patterns. Messages whose leading tokens differ never share a pattern, tokens containing digits excepted."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 2
+				unit:    null
+			}
+		}
+		field: {
+			common:      true
+			description: "The field holding the message to cluster. Defaults to the [global `log_schema.message_key` option][docs.reference.configuration.global-options#log_schema.message_key]."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["message", "parent.child"]
+				syntax: "literal"
+			}
+		}
+		id_field: {
+			common:      false
+			description: "The field the numeric pattern id is written to."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "pattern_id"
+				syntax:  "literal"
+			}
+		}
+		max_children: {
+			common:      false
+			description: "The maximum number of distinct tokens routed separately at each level of the tree. Once reached, further tokens share a single wildcard branch."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 100
+				unit:    null
+			}
+		}
+		max_patterns: {
+			common:      false
+			description: "The maximum number of patterns kept in memory. The least recently matched pattern is forgotten when this is exceeded."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10000
+				unit:    null
+			}
+		}
+		pattern_field: {
+			common:      false
+			description: "The field the pattern template is written to."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "pattern"
+				syntax:  "literal"
+			}
+		}
+		similarity_threshold: {
+			common:      true
+			description: "The share of tokens, between `0` and `1`, a message must have in common with a pattern to be assigned to it. Lower values produce fewer, more general patterns."
+			required:    false
+			warnings: []
+			type: float: default: 0.4
+		}
+		summary_interval_secs: {
+			common:      false
+			description: "When set, a `log_pattern_events_total` counter tagged with `pattern_id` and `pattern` is emitted for every pattern seen during each interval. Also accepts a duration such as `\"1m30s\"`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				unit:    "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	output: logs: event: {
+		description: "The incoming event, tagged with its pattern."
+		fields: {
+			pattern_id: {
+				description: "The id of the pattern, written to `id_field`. Ids are assigned in discovery order and are not stable across restarts."
+				required:    true
+				type: uint: {
+					examples: [1, 42]
+					unit: null
+				}
+			}
+			pattern: {
+				description: "The pattern's template, written to `pattern_field`, where variable tokens are replaced by `<*>`."
+				required:    true
+				type: string: {
+					examples: ["connected to <*> port 22"]
+					syntax: "literal"
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		clustering: {
+			title: "Clustering"
+			body: """
+				Messages are split into whitespace-separated tokens and routed through a fixed
+				depth tree, first by their number of tokens and then by their first `depth`
+				tokens, to a small set of candidate patterns. The message joins the most similar
+				candidate if at least `similarity_threshold` of its tokens match, replacing the
+				tokens that differ in the pattern's template with `<*>`. Otherwise it starts a
+				new pattern of its own.
+
+				Since templates only ever become more general, the template attached to the
+				first events of a pattern can be more specific than the one attached to later
+				events, while the pattern id stays the same.
+				"""
+		}
+
+		novelty_detection: {
+			title: "Novelty Detection"
+			body: """
+				The `log_patterns_created_total` internal metric counts newly discovered
+				patterns, and the per-pattern counters emitted when `summary_interval_secs` is
+				set make rare or previously unseen patterns easy to alert on downstream.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		log_patterns_created_total: components.sources.internal_metrics.output.metrics.log_patterns_created_total
+		processing_errors_total:    components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
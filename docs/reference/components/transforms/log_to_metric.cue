@@ -65,7 +65,7 @@ components: transforms: log_to_metric: {
 						}
 					}
 					name: {
-						description: "The name of the metric. Defaults to `<field>_total` for `counter` and `<field>` for `gauge`."
+						description: "The name of the metric. Defaults to `<field>_total` for `counter` and `<field>` for `gauge`. Can also be a [VRL expression](#vrl-expressions)."
 						required:    false
 						common:      true
 						warnings: []
@@ -76,7 +76,7 @@ components: transforms: log_to_metric: {
 						}
 					}
 					namespace: {
-						description: "The namespace of the metric."
+						description: "The namespace of the metric. Can also be a [VRL expression](#vrl-expressions)."
 						required:    false
 						common:      true
 						warnings: []
@@ -103,7 +103,8 @@ components: transforms: log_to_metric: {
 								"*": {
 									description: """
 	                      Key/value pairs representing [metric tags][docs.data-model.metric#tags].
-	                      Environment variables and field interpolation is allowed.
+	                      Environment variables and field interpolation is allowed, as well as
+	                      [VRL expressions](#vrl-expressions).
 	                      """
 									required: true
 									warnings: []
@@ -406,6 +407,22 @@ components: transforms: log_to_metric: {
 				individual metrics for reduction in the metrics storage itself.
 				"""
 		}
+		vrl_expressions: {
+			title: "VRL Expressions"
+			body: """
+				The `name`, `namespace` and tag values can be a [VRL][docs.vrl] expression
+				instead of a template, evaluated against each log event, which derives them without
+				a preceding `remap` transform:
+
+				```toml
+				tags.status_class.vrl = 'floor(to_int!(.status) / 100)'
+				```
+
+				Strings are used as they are, and the other values converted to strings. A tag
+				whose expression fails or resolves to `null` is left out, while the metric itself
+				isn't emitted when its name or namespace expression fails.
+				"""
+		}
 		null_fields: {
 			title: "Null Fields"
 			body: """
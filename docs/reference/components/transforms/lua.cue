@@ -192,7 +192,7 @@ components: transforms: lua: {
 							}
 						}
 						interval_seconds: {
-							description: "Defines the interval at which the timer handler would be executed."
+							description: "Defines the interval at which the timer handler would be executed. Also accepts a duration such as `\"1m30s\"`."
 							required:    true
 							warnings: []
 							type: uint: {
@@ -0,0 +1,129 @@
+package metadata
+
+components: transforms: metric_kind: {
+	title: "Metric Kind"
+
+	description: """
+		Converts counters between the absolute (cumulative) kind, such as the
+		counters of Prometheus, and the incremental (delta) kind, such as the
+		counters of StatsD and Datadog.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		convert: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		expire_metrics_secs: {
+			common:      false
+			description: "Forget the series that weren't updated for this long. Overrides the global `expire_metrics_secs` option. Also accepts a duration such as `\"1m30s\"`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [300]
+				unit: "seconds"
+			}
+		}
+		to: {
+			description: "The kind the counters are converted to."
+			required:    true
+			warnings: []
+			type: string: {
+				enum: {
+					absolute:    "Sums the incremental counters of each series into a running total."
+					incremental: "Sends the change of the absolute counters of each series since their previous value."
+				}
+				syntax: "literal"
+			}
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	examples: [
+		{
+			title: "Absolute to incremental, after a value of 10"
+			configuration: {
+				to: "incremental"
+			}
+			input: metric: {
+				kind: "absolute"
+				name: "requests_total"
+				counter: {
+					value: 15.0
+				}
+			}
+			output: metric: {
+				kind: "incremental"
+				name: "requests_total"
+				counter: {
+					value: 5.0
+				}
+			}
+		},
+	]
+
+	how_it_works: {
+		state: {
+			title: "State"
+			body: """
+				The transform keeps the last absolute value of each series,
+				identified by its name, namespace, and tags. Converted to
+				incremental, the first value of a series is only the reference
+				of the next ones, and isn't sent. A value lower than the
+				previous one means the source of the counter restarted, so it
+				is sent as the change since zero.
+
+				Metrics other than counters, and counters already of the
+				target kind, pass through unchanged.
+				"""
+		}
+		expiration: {
+			title: "Expiration"
+			body: """
+				The series not updated for `expire_metrics_secs` are forgotten,
+				bounding the memory the transform uses. Once a series expired,
+				its running total starts from zero again.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		counter_resets_total:        components.sources.internal_metrics.output.metrics.counter_resets_total
+		expired_metric_series_total: components.sources.internal_metrics.output.metrics.expired_metric_series_total
+	}
+}
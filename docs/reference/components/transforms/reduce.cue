@@ -123,6 +123,7 @@ components: transforms: reduce: {
 								concat:         "Concatenate each string value (delimited with a space)."
 								concat_newline: "Concatenate each string value (delimited with a newline)."
 								discard:        "Discard all but the first value found."
+								retain:         "Discard all but the last value found."
 								sum:            "Sum all numeric values."
 								max:            "The maximum of all numeric values."
 								min:            "The minimum of all numeric values."
@@ -208,7 +209,7 @@ components: transforms: reduce: {
 			]
 			configuration: {
 				group_by: ["host", "pid", "tid"]
-				marge_strategies: message: "concat_newline"
+				merge_strategies: message: "concat_newline"
 				starts_when: #"match(.message, /^[^\s]/)"#
 			}
 			output: [
@@ -64,6 +64,97 @@ components: transforms: "remap": {
 				syntax: "remap_program"
 			}
 		}
+		drop_on_abort: {
+			common:      false
+			description: "Drop the events whose program ran an `abort` expression, instead of sending them unchanged."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		drop_on_error: {
+			common:      false
+			description: "Drop the events whose program failed, instead of sending them as the program left them. Formerly `drop_on_err`."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		reroute_dropped: {
+			common:      false
+			description: "Send the dropped events, as they were before the program ran, to the `<transform_name>.dropped` output instead of discarding them."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		encryption_keys: {
+			common:      false
+			description: """
+				The data keys available to the `encrypt_field` and `decrypt_field` functions, by key id. The keys
+				are loaded once when the transform is built.
+				"""
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						primary: {
+							provider: "file"
+							path:     "/etc/vector/keys/primary.key"
+						}
+					},
+				]
+				options: {
+					"*": {
+						description: "The provider of the data key with this id."
+						required:    true
+						warnings: []
+						type: object: options: {
+							provider: {
+								description: "Where the data key is loaded from."
+								required:    true
+								warnings: []
+								type: string: {
+									enum: {
+										file:    "A file holding the base64 encoded 256-bit key, e.g. generated with `openssl rand -base64 32`."
+										aws_kms: "A data key encrypted with an AWS KMS key, decrypted with the KMS `Decrypt` API. Requires Vector to be built with the `encryption-aws_kms` feature."
+									}
+									syntax: "literal"
+								}
+							}
+							path: {
+								description:   "The path of the key file."
+								relevant_when: "provider = \"file\""
+								required:      true
+								warnings: []
+								type: string: {
+									examples: ["/etc/vector/keys/primary.key"]
+									syntax: "literal"
+								}
+							}
+							encrypted_data_key: {
+								description:   "The base64 encoded `CiphertextBlob` of a data key, as returned by the KMS `GenerateDataKey` API with a `KeySpec` of `AES_256`."
+								relevant_when: "provider = \"aws_kms\""
+								required:      true
+								warnings: []
+								type: string: {
+									examples: ["AQIDAHhBf..."]
+									syntax: "literal"
+								}
+							}
+							region: {
+								description:   "The [AWS region](\(urls.aws_regions)) of the KMS key."
+								relevant_when: "provider = \"aws_kms\""
+								required:      true
+								warnings: []
+								type: string: {
+									examples: ["us-east-1"]
+									syntax: "literal"
+								}
+							}
+						}
+					}
+				}
+			}
+		}
 	}
 
 	input: {
@@ -105,9 +196,42 @@ components: transforms: "remap": {
 				[Vector Remap Language reference](\(urls.vector_remap_language_reference)).
 				"""#
 		}
+
+		dropped_events: {
+			title: "Dropped Events"
+			body: """
+				The program of an event can fail, or stop with an `abort` expression. The events of failed
+				programs are dropped with `drop_on_error`, and the events of aborted programs with
+				`drop_on_abort`. The other events are sent as the program left them.
+
+				With `reroute_dropped`, the dropped events are sent to the `<transform_name>.dropped` output, which
+				other components can take as input, such as a sink keeping the events for inspection. They are the
+				events as they were before the program ran, with the reason they were dropped under
+				`metadata.dropped`: `reason` is `error` or `abort`, `message` is the error, and `component` is the
+				name of the transform. Metrics get these as the tags `metadata.dropped.reason`,
+				`metadata.dropped.message`, and `metadata.dropped.component`. The program only runs once for each
+				event, the transform keeps a copy of the event to send to the `dropped` output if it's dropped.
+				The `dropped` output buffers up to 100 events, past which the transform waits for its consumers.
+				"""
+		}
+
+		field_encryption: {
+			title: "Field Encryption"
+			body: """
+				The `encrypt_field` function replaces a value with an envelope holding the algorithm, the key id,
+				the IV and the AES-256-GCM ciphertext of the value, and `decrypt_field` turns the envelope back
+				into the value. Only the data key is needed to decrypt an envelope, it's looked up in
+				`encryption_keys` by the key id the envelope carries, so keys can be rotated by adding a new id
+				while keeping the old one for decryption.
+
+				With the `aws_kms` provider only the encrypted copy of the data key is stored in the configuration,
+				the master key it's encrypted with never leaves KMS.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
+		events_discarded_total:  components.sources.internal_metrics.output.metrics.events_discarded_total
 		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
 	}
 }
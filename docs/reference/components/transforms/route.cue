@@ -40,6 +40,8 @@ components: transforms: route: {
 			description: """
 				A table of route identifiers to logical conditions representing the filter of the route. Each route
 				can then be referenced as an input by other components with the name `<transform_name>.<route_id>`.
+				The events matching none of the routes are sent to `<transform_name>._unmatched`, so the route
+				identifier `_unmatched` is reserved.
 				"""
 			required: true
 			warnings: []
@@ -89,6 +91,19 @@ components: transforms: route: {
 		},
 	]
 
+	how_it_works: {
+		unmatched: {
+			title: "Unmatched events"
+			body: """
+				Each route sends the events matching its condition, so an event
+				matching several conditions is sent to each of their routes. The
+				events matching none of them are sent to the `_unmatched` route,
+				which, like the other routes, is referenced with the name
+				`<transform_name>._unmatched`.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
 		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
 	}
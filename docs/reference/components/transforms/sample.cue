@@ -42,7 +42,8 @@ components: transforms: sample: {
 
 				Consistently samples the same events. Actual rate of sampling may differ from the configured one if
 				values in the field are not uniformly distributed. If left unspecified, or if the event doesn't have
-				`key_field`, events will be count rated.
+				`key_field`, events will be count rated. Trace events are sampled by their `trace_id` unless a
+				`key_field` is given, so that all spans of a trace are either kept or dropped together.
 				"""
 			required: false
 			warnings: []
@@ -84,6 +85,7 @@ components: transforms: sample: {
 	input: {
 		logs:    true
 		metrics: null
+		traces:  true
 	}
 
 	telemetry: metrics: {
@@ -0,0 +1,113 @@
+package metadata
+
+components: transforms: shard: {
+	title: "Shard"
+
+	description: """
+		Assigns events a stable shard number computed from a key, so that they can be routed to a number of
+		parallel downstream sinks deterministically.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		algorithm: {
+			common:      false
+			description: "The consistent hashing algorithm mapping the keys to the shards."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "jump"
+				enum: {
+					jump:       "Jump consistent hashing. Fast and evenly spread, but only moves the fewest keys when shards are added or removed last."
+					rendezvous: "Rendezvous (highest random weight) hashing. Hashes the key once per shard."
+				}
+				syntax: "literal"
+			}
+		}
+		field: {
+			common:      false
+			description: "The log field (or metric tag) the shard number is written to."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "shard"
+				examples: ["partition"]
+				syntax: "literal"
+			}
+		}
+		key: {
+			description: """
+				The key the shard is computed from. Events with the same key always get the same shard, on all the
+				architectures. Events whose key can't be rendered are passed through without a shard.
+				"""
+			required: true
+			warnings: []
+			type: string: {
+				examples: ["{{ host }}", "{{ tags.region }}-{{ name }}"]
+				syntax: "template"
+			}
+		}
+		shards: {
+			description: "The number of shards, numbered from `0` to `shards - 1`."
+			required:    true
+			warnings: []
+			type: uint: {
+				examples: [4]
+				unit: null
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+		traces: true
+	}
+
+	how_it_works: {
+		routing: {
+			title: "Routing Shards"
+			body: """
+				The shard number can be matched by the [`route` transform][docs.transforms.route] or by the
+				[`filter` transform][docs.transforms.filter] to send each shard to its own sink.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
@@ -0,0 +1,176 @@
+package metadata
+
+components: transforms: sql: {
+	title: "SQL"
+
+	description: """
+		Filters, projects and aggregates log events with a SQL-like query. Queries with aggregate
+		functions or a `GROUP BY` clause roll events up over tumbling or hopping windows and emit one
+		summary event per group when each window closes.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		reduce: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		flush_period_ms: {
+			common:      false
+			description: "Controls the frequency that Vector checks for (and flushes) closed windows."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    "milliseconds"
+			}
+		}
+		query: {
+			description: "The query to run against each event. See [How it works](#query-syntax) for the supported syntax."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: [
+					"SELECT service, count(*) AS errors WHERE level = 'error' GROUP BY service",
+					"SELECT host, message WHERE status >= 500",
+				]
+				syntax: "literal"
+			}
+		}
+		window: {
+			common:      true
+			description: "The windows used by aggregate queries. Windows are aligned on the Unix epoch and use the time events are processed at."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					type: {
+						description: "The type of window."
+						required:    true
+						warnings: []
+						type: string: {
+							enum: {
+								tumbling: "Consecutive, non-overlapping windows of `size_secs`."
+								hopping:  "Windows of `size_secs` starting every `hop_secs`. An event belongs to every window covering it."
+							}
+							syntax: "literal"
+						}
+					}
+					size_secs: {
+						description: "The length of each window."
+						required:    true
+						warnings: []
+						type: uint: {
+							examples: [60]
+							unit: "seconds"
+						}
+					}
+					hop_secs: {
+						common:        false
+						description:   "The interval between the start of two consecutive windows. A window may overlap at most 100 others, so `size_secs` can be at most 100 times `hop_secs`."
+						relevant_when: #"type = "hopping""#
+						required:      false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [10]
+							unit: "seconds"
+						}
+					}
+				}
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Per-minute error counts by service"
+			configuration: {
+				query: "SELECT service, count(*) AS errors, avg(duration_ms) WHERE level = 'error' GROUP BY service"
+			}
+			input: [
+				{log: {service: "api", level: "error", duration_ms: 20}},
+				{log: {service: "api", level: "info", duration_ms:  5}},
+				{log: {service: "api", level: "error", duration_ms: 40}},
+			]
+			output: log: {
+				service:         "api"
+				errors:          2
+				avg_duration_ms: 30.0
+				window_start:    "2021-01-01T00:00:00Z"
+				window_end:      "2021-01-01T00:01:00Z"
+			}
+		},
+	]
+
+	how_it_works: {
+		query_syntax: {
+			title: "Query syntax"
+			body: """
+				```sql
+				SELECT <projection> [, <projection>]* [FROM <name>]
+				  [WHERE <condition>]
+				  [GROUP BY <field> [, <field>]*]
+				```
+
+				* Projections are `*`, a field path (`host.name`) or one of the aggregate functions
+				  `count(*)`, `count(field)`, `sum(field)`, `avg(field)`, `min(field)` and `max(field)`,
+				  optionally followed by `AS alias`. Aggregates without an alias are named after the
+				  function and field, for example `avg_duration_ms`.
+				* Conditions compare a field with a string (`'error'`), number or boolean literal using
+				  `=`, `!=`, `<>`, `<`, `<=`, `>` or `>=`, test for `IS [NOT] NULL`, and can be combined
+				  with `AND`, `OR`, `NOT` and parentheses. Comparisons against missing fields are false.
+				* `FROM` is optional and only there for readability, the input is given by `inputs`.
+				* Field names clashing with keywords can be quoted with double quotes.
+
+				Queries without aggregates are applied to each event as it arrives. Otherwise, every
+				non-aggregated projection must appear in the `GROUP BY` clause, and a summary event is
+				emitted for each group when its window closes, with `window_start` and `window_end`
+				fields. Open windows are flushed when Vector shuts down.
+				"""
+		}
+
+		processing_time: {
+			title: "Processing time windows"
+			body: """
+				Events are aggregated into the windows covering the time they are processed at, not
+				their own timestamp. An event arriving late is counted in the window open when it
+				arrives, and a backlog replayed after a restart is counted in the current windows.
+				The `timestamp` of each summary event is the start of its window.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+		windows_flushed_total:  components.sources.internal_metrics.output.metrics.windows_flushed_total
+	}
+}
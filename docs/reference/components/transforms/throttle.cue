@@ -0,0 +1,116 @@
+package metadata
+
+components: transforms: throttle: {
+	title: "Throttle"
+
+	description: """
+		Rate limits the events of each key to a number of events per time window, discarding the
+		events past the limit.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		emit_events_discarded_per_key: {
+			common:      false
+			description: "Whether the `events_discarded_total` internal metric is tagged with the `key` of the discarded events. Beware of the cardinality of the keys."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		key_field: {
+			common: true
+			description: """
+				The key the events are throttled by, each key being rate limited on its own. All the events
+				share a single limit if unset. Can also be a [VRL expression](#vrl-expressions). Events whose
+				key can't be rendered are passed through.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ tenant }}", "{{ host }}-{{ service }}"]
+				syntax: "template"
+			}
+		}
+		threshold: {
+			description: "The number of events allowed per key in each window."
+			required:    true
+			warnings: []
+			type: uint: {
+				examples: [100, 1000]
+				unit: null
+			}
+		}
+		window_secs: {
+			description: "The time window the `threshold` applies to. Also accepts a duration such as `\"1m30s\"`."
+			required:    true
+			warnings: []
+			type: uint: {
+				examples: [1, 60]
+				unit: "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		token_buckets: {
+			title: "Token Buckets"
+			body: """
+				Each key has a bucket of `threshold` tokens, refilled continuously at `threshold` tokens
+				per `window_secs`. An event takes a token from the bucket of its key to pass through, and
+				is discarded when the bucket is empty. Bursts of up to `threshold` events are let through
+				at once, while the rate is held to `threshold` events per window in the long run.
+				"""
+		}
+		vrl_expressions: {
+			title: "VRL Expressions"
+			body: """
+				The `key_field` can be a [VRL][docs.vrl] expression instead of a template, evaluated
+				against each log event:
+
+				```toml
+				key_field.vrl = 'downcase!(.tenant)'
+				```
+
+				Strings are used as they are, and the other values converted to strings. Events whose
+				expression fails or resolves to `null` are passed through.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total:  components.sources.internal_metrics.output.metrics.events_discarded_total
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
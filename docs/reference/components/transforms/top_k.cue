@@ -0,0 +1,176 @@
+package metadata
+
+components: transforms: top_k: {
+	title: "Top K"
+
+	description: """
+		Tracks the most frequent values of a field over a sliding window, and
+		periodically emits them as metric events. Events whose value is among
+		them can be marked, to spot noisy tenants or hosts as the events pass
+		through.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		convert: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		capacity: {
+			common:      false
+			description: "The number of values counted at once. More values than `k` make the counts of the top values more accurate, at the cost of memory. Defaults to ten times `k`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [1000]
+				unit: null
+			}
+		}
+		field: {
+			description: "The field of log events, or the tag of metric events, whose values are counted. Events without it pass through uncounted."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["tenant", "host"]
+				syntax: "field_path"
+			}
+		}
+		heavy_hitter_field: {
+			common:      false
+			description: "The field set to `true` on log events, or the tag set to `\"true\"` on metric events, whose value was among the top values at the previous emission."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["heavy_hitter"]
+				syntax: "literal"
+			}
+		}
+		interval_secs: {
+			common:      true
+			description: "How often the top values are emitted. Also accepts a duration such as `\"1m30s\"`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		k: {
+			common:      true
+			description: "The number of top values emitted."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10
+				unit:    null
+			}
+		}
+		namespace: {
+			common:      false
+			description: "The namespace of the emitted metrics."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "vector"
+				examples: ["tenants"]
+				syntax: "literal"
+			}
+		}
+		window_secs: {
+			common:      true
+			description: "The period the values are counted over, rounded up to a multiple of `interval_secs`. Also accepts a duration such as `\"1m30s\"`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 300
+				unit:    "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	output: metrics: {
+		top_k_count: {
+			description: "The number of events with one of the top values over the window."
+			tags: {
+				field: {
+					description: "The field whose values are counted."
+					examples: ["tenant"]
+					required: true
+				}
+				rank: {
+					description: "The rank of the value, from `1` for the most frequent."
+					examples: ["1"]
+					required: true
+				}
+				value: {
+					description: "The value."
+					examples: ["acme"]
+					required: true
+				}
+			}
+			type:              "gauge"
+			default_namespace: "vector"
+		}
+	}
+
+	how_it_works: {
+		space_saving: {
+			title: "Space-Saving Algorithm"
+			body: """
+				The values are counted with the space-saving algorithm, keeping at most
+				`capacity` counters per interval. A value without a counter takes over the
+				counter of the least frequent value, and its count starts from the count of
+				that value. The counts of the top values can therefore be overestimated, by
+				at most the number of events of the interval divided by `capacity`, but a
+				value more frequent than that is never missed.
+				"""
+		}
+
+		sliding_window: {
+			title: "Sliding Window"
+			body: """
+				The counters of each interval are kept for `window_secs`. Every
+				`interval_secs`, the counts of the intervals of the window are summed, the
+				`k` top values are emitted as `top_k_count` gauges, and the counters of the
+				oldest interval are dropped. The events are forwarded, the metric events
+				are emitted alongside them.
+				"""
+		}
+	}
+}
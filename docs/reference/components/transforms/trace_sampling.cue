@@ -0,0 +1,127 @@
+package metadata
+
+components: transforms: trace_sampling: {
+	title: "Trace Sampling"
+
+	description: """
+		Samples traces after all of their spans have been received (tail-based sampling). Spans are
+		buffered by trace id until the root span arrives or `decision_wait_secs` elapses, at which
+		point the configured policies decide whether the whole trace is kept or dropped.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		decision_wait_secs: {
+			common:      true
+			description: "The maximum amount of time to wait for the root span of a trace before deciding on the spans received so far. Also accepts a duration such as `\"1m30s\"`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 30
+				unit:    "seconds"
+			}
+		}
+		flush_period_ms: {
+			common:      false
+			description: "Controls the frequency that Vector checks for traces that have waited for `decision_wait_secs`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    "milliseconds"
+			}
+		}
+		keep_errors: {
+			common:      true
+			description: "Whether traces containing at least one span with an `error` status are always kept."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		latency_threshold_ms: {
+			common:      true
+			description: "Traces lasting at least this long are always kept. The duration of the root span is used when available."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [500]
+				unit: "milliseconds"
+			}
+		}
+		max_traces: {
+			common:      false
+			description: "The maximum number of traces to buffer. When reached, the oldest trace is decided on early."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 50000
+				unit:    null
+			}
+		}
+		rate: {
+			description: """
+				The rate at which traces not kept by another policy will be forwarded, expressed as 1/N. Traces are
+				selected by hashing their `trace_id`, so the same trace is consistently kept or dropped.
+				"""
+			required: true
+			warnings: []
+			type: uint: {
+				examples: [10]
+				unit: null
+			}
+		}
+	}
+
+	input: {
+		logs:    false
+		metrics: null
+		traces:  true
+	}
+
+	how_it_works: {
+		policies: {
+			title: "Sampling policies"
+			body: """
+				Policies are applied in order, and the first matching one decides:
+
+				1. `error`: the trace contains a span whose `status.code` is `error` (when `keep_errors` is set).
+				2. `latency`: the trace lasted at least `latency_threshold_ms`.
+				3. `probabilistic`: the trace is kept with a probability of 1/`rate`.
+
+				Kept spans are annotated with a `sample_rate` field, `1` for traces kept by the `error` and
+				`latency` policies. Spans arriving after their trace was decided follow the same decision.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+}
@@ -70,7 +70,7 @@ components: transforms: wasm: {
 		}
 		heap_max_size: {
 			common:      false
-			description: "The maximum size of the heap of this module, in bytes. (This includes the module itself, default is 10 MB.)"
+			description: "The maximum size of the heap of this module, in bytes. (This includes the module itself, default is 10 MB.) Also accepts a size such as `\"5MiB\"`."
 			required:    false
 			warnings: []
 			type: uint: {
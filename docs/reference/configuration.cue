@@ -7,13 +7,164 @@ configuration: {
 
 configuration: {
 	configuration: {
+		acknowledgements: {
+			common: false
+			description: """
+				Hold back the status of the events received by the sources
+				with `acknowledgements` enabled until every sink delivered
+				them. When disabled, sinks acknowledge the events as soon
+				as they're buffered. Sinks can override it with their own
+				`acknowledgements` option.
+				"""
+			required: false
+			warnings: []
+			type: bool: default: null
+		}
+
+		cluster: {
+			common: false
+			description: """
+				Configures work sharing between multiple Vector instances. When enabled, instances
+				sharing the same store divide pull-based work (such as `prometheus_scrape`
+				endpoints) between them, and rebalance it automatically when an instance joins or
+				leaves.
+				"""
+			required: false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					enabled: {
+						common:      true
+						description: "Enables cluster mode."
+						required:    false
+						warnings: []
+						type: bool: default: false
+					}
+					member_id: {
+						common:      false
+						description: "The unique identifier of this instance within the cluster."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "<hostname>"
+							examples: ["aggregator-1"]
+							syntax: "literal"
+						}
+					}
+					heartbeat_interval_secs: {
+						common:      false
+						description: "How often this instance records its heartbeat and refreshes the member list."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 5
+							unit:    "seconds"
+						}
+					}
+					member_timeout_secs: {
+						common:      false
+						description: "How long after its last heartbeat an instance is considered gone."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 15
+							unit:    "seconds"
+						}
+					}
+					store: {
+						common:      false
+						description: "The shared store used to track cluster members."
+						required:    false
+						warnings: []
+						type: object: {
+							examples: []
+							options: {
+								type: {
+									common:      true
+									description: "The store type."
+									required:    false
+									warnings: []
+									type: string: {
+										default: "directory"
+										enum: directory: "A directory shared by all instances, such as a network file system mount."
+									}
+								}
+								path: {
+									common:      true
+									description: "The path of the directory shared by all instances. Required when cluster mode is enabled, since the `data_dir` of each instance is its own."
+									required:    false
+									warnings: []
+									type: string: {
+										default: null
+										examples: ["/mnt/shared/vector-cluster"]
+										syntax: "literal"
+									}
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+
+		crash_report: {
+			common: false
+			description: """
+				Configures crash reports. When enabled, a panic or a fatal signal writes a JSON report with
+				the Vector version, a fingerprint of the config, the components, the usage of the sink
+				buffers and, for panics, a backtrace to the `crash_reports` subdirectory of the `data_dir`.
+				"""
+			required: false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					enabled: {
+						common:      true
+						description: "Enables crash reports."
+						required:    false
+						warnings: []
+						type: bool: default: false
+					}
+					endpoint: {
+						common: true
+						description: """
+							A URL the reports are POSTed to. Reports are uploaded by the next Vector process
+							started with the same `data_dir`, and renamed to `*.sent` once uploaded.
+							"""
+						required: false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["https://crash-reports.example.com/vector"]
+							syntax: "literal"
+						}
+					}
+					refresh_interval_secs: {
+						common:      false
+						description: "How often the buffer usage recorded in the reports of fatal signals is refreshed."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 10
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+		}
+
 		data_dir: {
 			common: false
 			description: """
 				The directory used for persisting Vector state, such
 				as on-disk buffers, file checkpoints, and more.
 				Please make sure the Vector project has write
-				permissions to this directory.
+				permissions to this directory. Vector checks that
+				it can write files there before starting the
+				components using it. Disk buffers can also require
+				some free space with their `min_free_space` option.
 				"""
 			required: false
 			type: string: {
@@ -23,6 +174,90 @@ configuration: {
 			}
 		}
 
+		enrichment_tables: {
+			common: false
+			description: """
+				The enrichment tables available to the `get_enrichment_table_record` and
+				`find_enrichment_table_records` functions of the `remap` transform, by name. The
+				tables are loaded in memory when the configuration is loaded, with every column
+				indexed, and reloaded along with it. Changing this section on reload rebuilds all
+				transforms.
+				"""
+			required: false
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						status_codes: {
+							type: "file"
+							path: "/etc/vector/status_codes.csv"
+						}
+					},
+				]
+				options: {
+					"*": {
+						description: "The enrichment table with this name."
+						required:    true
+						warnings: []
+						type: object: options: {
+							type: {
+								description: "Where the table is loaded from."
+								required:    true
+								warnings: []
+								type: string: {
+									enum: {
+										file: "A CSV file, with the names of the columns on its first line. All fields are loaded as strings."
+									}
+									syntax: "literal"
+								}
+							}
+							path: {
+								description:   "The path of the CSV file."
+								relevant_when: "type = \"file\""
+								required:      true
+								warnings: []
+								type: string: {
+									examples: ["/etc/vector/status_codes.csv"]
+									syntax: "literal"
+								}
+							}
+							delimiter: {
+								description:   "The ASCII character separating the fields of the CSV file."
+								relevant_when: "type = \"file\""
+								required:      false
+								warnings: []
+								type: string: {
+									default: ","
+									examples: [";", "\t"]
+									syntax: "literal"
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+
+		expire_metrics_secs: {
+			common: false
+			description: """
+				Forget the metric series that weren't updated for this
+				long in the sinks keeping their state, such as the
+				incremental counters they make absolute, to bound their
+				memory. The `prometheus_remote_write` and `opentelemetry`
+				sinks also send a marker ending each expired series.
+				Sinks can override it with their own
+				`expire_metrics_secs` option.
+				"""
+			required: false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [300]
+				unit: "seconds"
+			}
+		}
+
 		healthchecks: {
 			common: false
 			description: """
@@ -61,9 +296,106 @@ configuration: {
 				}
 			}
 		}
+
+		state: {
+			common: false
+			description: """
+				Configures where components keep their state, such as the `journald` cursor. By
+				default it is stored in the `data_dir`. Storing it remotely lets instances without
+				a persistent disk, such as containers, resume where they left off after a restart.
+				This includes the checkpoints of the sources reading files. Disk buffers and
+				`dedupe` caches are logs appended to on every event rather than values replaced
+				as a whole, so they are always kept in the `data_dir`.
+				"""
+			required: false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					backend: {
+						common:      true
+						description: "The state backend."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "local"
+							enum: {
+								local: "Files in each component's subdirectory of the `data_dir`."
+								s3:    "Objects in an AWS S3 bucket. Requires Vector to be built with the `state-s3` feature."
+							}
+						}
+					}
+					bucket: {
+						common:        true
+						description:   "The S3 bucket to store state in."
+						relevant_when: "backend = \"s3\""
+						required:      true
+						warnings: []
+						type: string: {
+							examples: ["my-vector-state"]
+							syntax: "literal"
+						}
+					}
+					key_prefix: {
+						common:        false
+						description:   "The prefix of the S3 objects. Each component's state is stored under `<key_prefix><component>/`."
+						relevant_when: "backend = \"s3\""
+						required:      false
+						warnings: []
+						type: string: {
+							default: "vector/state/"
+							examples: ["state/aggregator-1/"]
+							syntax: "literal"
+						}
+					}
+					region: {
+						common:        true
+						description:   "The AWS region of the bucket."
+						relevant_when: "backend = \"s3\""
+						required:      false
+						warnings: []
+						type: string: {
+							examples: ["us-east-1"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
 	}
 
 	how_it_works: {
+		component_templates: {
+			title: "Component templates"
+			body: """
+				Components that only differ by a few values can be generated from a single
+				template. Each template in the `component_templates` table defines the section
+				(`kind`) and `name` of the components it generates, a `for_each` list, and the
+				`component` itself. One component is generated per `for_each` item, replacing
+				`{{ key }}` placeholders in the name and in all string options with the values
+				of the item:
+
+				```toml title="vector.toml"
+				[component_templates.tenant_kafka]
+				  kind = "sources"
+				  name = "kafka_{{ tenant }}"
+				  for_each = [
+				    { tenant = "acme", topic = "acme-logs" },
+				    { tenant = "globex", topic = "globex-logs" },
+				  ]
+
+				  [component_templates.tenant_kafka.component]
+				    type = "kafka"
+				    bootstrap_servers = "kafka:9092"
+				    group_id = "vector-{{ tenant }}"
+				    topics = ["{{ topic }}"]
+				```
+
+				Items that aren't tables, like in `for_each = ["acme", "globex"]`, are available
+				as `{{ item }}`. Templates are expanded when the configuration is loaded, and it
+				is an error for a template to generate a component name that is already in use.
+				"""
+		}
 		environment_variables: {
 			title: "Environment variables"
 			body: """
@@ -387,4 +387,116 @@ data_model: schema: {
 			}
 		}
 	}
+
+	trace: {
+		common: false
+		description: """
+			A Vector trace event represents a single span of a distributed trace. Like a log event it
+			is a structured map of fields, with a set of well-known fields describing the span.
+			"""
+		required: false
+		warnings: []
+		type: object: {
+			examples: [
+				{
+					"trace_id":       "0af7651916cd43dd8448eb211c80319c"
+					"span_id":        "b7ad6b7169203331"
+					"parent_span_id": "00f067aa0ba902b7"
+					"name":           "GET /checkout"
+					"start_time":     "2021-01-01T00:00:00.000Z"
+					"end_time":       "2021-01-01T00:00:00.250Z"
+					"attributes": {"http.status_code": 200}
+					"resource": {"service.name": "checkout"}
+				},
+			]
+			options: {
+				trace_id: {
+					description: "The identifier of the trace this span belongs to."
+					required:    true
+					warnings: []
+					type: string: syntax: "literal"
+				}
+				span_id: {
+					description: "The identifier of this span."
+					required:    true
+					warnings: []
+					type: string: syntax: "literal"
+				}
+				parent_span_id: {
+					common:      true
+					description: "The identifier of the parent span. Root spans have no parent."
+					required:    false
+					warnings: []
+					type: string: {
+						default: null
+						syntax:  "literal"
+					}
+				}
+				name: {
+					common:      true
+					description: "The operation name of the span."
+					required:    false
+					warnings: []
+					type: string: {
+						default: null
+						syntax:  "literal"
+					}
+				}
+				start_time: {
+					common:      true
+					description: "When the span started."
+					required:    false
+					warnings: []
+					type: timestamp: {}
+				}
+				end_time: {
+					common:      true
+					description: "When the span ended."
+					required:    false
+					warnings: []
+					type: timestamp: {}
+				}
+				status: {
+					common:      false
+					description: "The span status, with a `code` (`ok`, `error`) and an optional `message`."
+					required:    false
+					warnings: []
+					type: object: {
+						examples: []
+						options: {}
+					}
+				}
+				attributes: {
+					common:      true
+					description: "Key/value attributes of the span."
+					required:    false
+					warnings: []
+					type: object: {
+						examples: []
+						options: {}
+					}
+				}
+				links: {
+					common:      false
+					description: "Links to other spans, each with a `trace_id`, `span_id` and `attributes`."
+					required:    false
+					warnings: []
+					type: array: items: type: object: {
+						examples: []
+						options: {}
+					}
+				}
+				resource: {
+					common:      false
+					description: "Attributes of the entity producing the span, such as the service name."
+					required:    false
+					warnings: []
+					type: object: {
+						examples: []
+						options: {}
+					}
+				}
+			}
+		}
+	}
 }
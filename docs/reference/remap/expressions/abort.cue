@@ -0,0 +1,34 @@
+package metadata
+
+remap: expressions: abort: {
+	title: "Abort"
+	description: """
+		An _abort_ expression stops the program, leaving the event as the previous expressions changed it. How the
+		event is handled then depends on the component running the program, the `remap` transform drops it by
+		default.
+		"""
+	return: """
+		Doesn't return, the program stops.
+		"""
+
+	grammar: {
+		source: """
+			"abort"
+			"""
+		definitions: {}
+	}
+
+	examples: [
+		{
+			title: "Abort on a condition"
+			input: log: message: "debug"
+			source: #"""
+				if .message == "debug" {
+					abort
+				}
+				.message = "kept"
+				"""#
+			raises: runtime: "aborted"
+		},
+	]
+}
@@ -6,6 +6,9 @@ remap: expressions: arithmetic: {
 		An _arithmetic_ expression performs an operation on two expressions (operands) as defined by the operator.
 
 		Although arithmetic is commonly applied to numbers, you can use it with other types as well, such as strings.
+
+		Integer arithmetic is checked: a result that doesn't fit a 64-bit signed integer fails with an `integer overflow`
+		runtime error. Taking the remainder of a division by zero fails with a runtime error as well.
 		"""
 	return: """
 		Returns the result of the expression as defined by the operator.
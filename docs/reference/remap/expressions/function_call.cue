@@ -3,7 +3,8 @@ package metadata
 remap: expressions: function_call: {
 	title:       "Function call"
 	description: """
-		A _function call_ expression invokes built-in [VRL functions](\(urls.vrl_functions)).
+		A _function call_ expression invokes built-in [VRL functions](\(urls.vrl_functions)), or the
+		[functions defined](#function_definition) at the top of the program.
 		"""
 	return:      """
 		Returns the value of the function invocation if the invocation succeeds. If the invocation fails, the error must
@@ -20,7 +21,7 @@ remap: expressions: function_call: {
 		definitions: {
 			function: {
 				description: """
-					`function` represents the name of the built-in function.
+					`function` represents the name of the built-in or defined function.
 					"""
 			}
 			abort: {
@@ -0,0 +1,69 @@
+package metadata
+
+remap: expressions: function_definition: {
+	title: "Function definition"
+	description: """
+		A _function definition_ declares a function that can be called, by name, by the expressions that follow it,
+		in the same way as the built-in [VRL functions](\(urls.vrl_functions)).
+
+		Functions are defined at the top of the program, before any other expression. They can call the functions
+		defined before them, but not themselves.
+		"""
+	return: """
+		Defining a function doesn't return a value. Calling it returns the result of the last expression of its body.
+		"""
+
+	grammar: {
+		source: """
+			"fn" ~ name ~ "(" ~ parameters? ~ ")" ~ block
+			"""
+		definitions: {
+			name: {
+				description: """
+					`name` is the name the function is called with. It can't be the name of a built-in function, or
+					of another defined function.
+					"""
+			}
+			parameters: {
+				description: """
+					The `parameters` are the comma-delimited names of the arguments of the function. All of them are
+					required, and can be provided positionally or by name.
+					"""
+			}
+			block: {
+				description: """
+					The `block` is the body of the function. Its only variables are the parameters, so it can't read or
+					change the variables of the expression calling it, while it can read and change the event.
+
+					The body is checked at compile time like the rest of the program, with the parameters accepting
+					any value: if it can fail, the errors of the calls to the function must be
+					[handled](\(urls.vrl_errors_reference)), as for the fallible built-in functions.
+					"""
+			}
+		}
+	}
+
+	examples: [
+		{
+			title: "Reused function"
+			input: log: {
+				host:     "WWW.Example.com."
+				upstream: "API.example.COM"
+			}
+			source: #"""
+				fn normalize_host(host) {
+					downcase(strip_whitespace(host))
+				}
+
+				# `host` can hold any value, so the function can fail.
+				.host = normalize_host!(.host)
+				.upstream = normalize_host!(.upstream)
+				"""#
+			return: "api.example.com"
+			output: log: {
+				host:     "www.example.com."
+				upstream: "api.example.com"
+			}
+		},
+	]
+}
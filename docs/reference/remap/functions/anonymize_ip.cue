@@ -0,0 +1,66 @@
+package metadata
+
+remap: functions: anonymize_ip: {
+	category: "IP"
+	description: """
+		Anonymizes the IP address `value` by zeroing all bits past its network prefix, keeping the address
+		useful for coarse geolocation and aggregation while no longer identifying a single client.
+		"""
+	notices: [
+		"""
+			Works with both IPv4 and IPv6 addresses, the prefix length applied depends on the version of `value`.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address to anonymize - either a v4 or a v6 address."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "ipv4_prefix"
+			description: "The number of leading bits of an IPv4 address to keep, between 0 and 32."
+			required:    false
+			default:     24
+			type: ["integer"]
+		},
+		{
+			name:        "ipv6_prefix"
+			description: "The number of leading bits of an IPv6 address to keep, between 0 and 128."
+			required:    false
+			default:     48
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` is not a valid IP address",
+		"`ipv4_prefix` or `ipv6_prefix` is out of range",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Anonymize an IPv4 address"
+			source: #"""
+				anonymize_ip("203.0.113.7")
+				"""#
+			return: "203.0.113.0"
+		},
+		{
+			title: "Anonymize an IPv4 address with a shorter prefix"
+			source: #"""
+				anonymize_ip("203.0.113.7", ipv4_prefix: 16)
+				"""#
+			return: "203.0.0.0"
+		},
+		{
+			title: "Anonymize an IPv6 address"
+			source: #"""
+				anonymize_ip("2001:db8:85a3:8d3:1319:8a2e:370:7348")
+				"""#
+			return: "2001:db8:85a3::"
+		},
+	]
+}
@@ -0,0 +1,63 @@
+package metadata
+
+remap: functions: assert_eq: {
+	category: "Debug"
+	description: """
+		Asserts that `left` and `right` are equal.
+
+		If they differ the program is aborted with the `message`, followed by every path at which
+		the values differ, down to the innermost differing values of nested objects and arrays.
+		"""
+	notices: [
+		"""
+			This function is designed to be used in a standalone fashion, aborting the script if it fails. It should
+			not be used in logical expressions.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "left"
+			description: "The value to check, usually the one produced by the program."
+			required:    true
+			type: ["any"]
+		},
+		{
+			name:        "right"
+			description: "The value `left` must be equal to."
+			required:    true
+			type: ["any"]
+		},
+		{
+			name:        "message"
+			description: "Should the values differ, message will be reported as the failure message."
+			required:    false
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`left` and `right` aren't equal",
+	]
+	return: types: ["null"]
+
+	examples: [
+		{
+			title: "Assertion (equal)"
+			source: #"""
+				assert_eq({"status": 200}, {"status": 200})
+				"""#
+			return: null
+		},
+		{
+			title: "Assertion (not equal)"
+			source: #"""
+				assert_eq({"status": 200, "tags": ["a"]}, {"status": 500, "tags": ["a", "b"]}, message: "Unexpected response")
+				"""#
+			raises: runtime: #"""
+				Unexpected response
+				  .status: 200 != 500
+				  .tags[1]: <missing> != "b"
+				"""#
+		},
+	]
+}
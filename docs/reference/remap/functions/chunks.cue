@@ -0,0 +1,38 @@
+package metadata
+
+remap: functions: chunks: {
+	category:    "Enumerate"
+	description: """
+		Splits the `value` into arrays of `chunk_size` items, the last one holding the remaining
+		items.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The array to split."
+			required:    true
+			type: ["array"]
+		},
+		{
+			name:        "chunk_size"
+			description: "The number of items of each chunk."
+			required:    true
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`chunk_size` is lower than 1",
+	]
+	return: types: ["array"]
+
+	examples: [
+		{
+			title: "Split an array into chunks"
+			source: #"""
+				chunks([1, 2, 3, 4, 5], 2)
+				"""#
+			return: [[1, 2], [3, 4], [5]]
+		},
+	]
+}
@@ -0,0 +1,58 @@
+package metadata
+
+remap: functions: decrypt: {
+	category:    "Codec"
+	description: """
+		Decrypts `ciphertext` with `algorithm` under the given `key` and `iv`, as returned by
+		`encrypt`.
+		"""
+	notices: [
+		"""
+			The supported algorithms are `AES-256-GCM`, taking a 12 bytes IV, and `AES-256-CBC-PKCS7`,
+			taking a 16 bytes IV. Both take a 32 bytes key.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "ciphertext"
+			description: "The raw ciphertext to decrypt."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "algorithm"
+			description: "The name of the algorithm the ciphertext was encrypted with."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: "The key the ciphertext was encrypted with."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "iv"
+			description: "The initialization vector the ciphertext was encrypted with."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`algorithm` is not a supported algorithm",
+		"`key` or `iv` doesn't have the length expected by `algorithm`",
+		"`ciphertext` can't be decrypted with `key` and `iv`, or has been tampered with",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decrypt a value"
+			source: #"""
+				decrypt!(decode_base64!("LMvmsI7cpf6UmThgt6Uy8g=="), "AES-256-CBC-PKCS7", key: "01234567890123456789012345678901", iv: "0123456789012345")
+				"""#
+			return: "secret"
+		},
+	]
+}
@@ -0,0 +1,38 @@
+package metadata
+
+remap: functions: decrypt_field: {
+	category: "Codec"
+	description: """
+		Decrypts an envelope produced by `encrypt_field`, using the data key named by its `key_id`.
+		"""
+	notices: [
+		"""
+			The data keys are configured with the `encryption_keys` option of the `remap` transform.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The envelope to decrypt."
+			required:    true
+			type: ["map"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` is missing an envelope field or isn't encrypted with `AES-256-GCM`",
+		"the `key_id` of `value` is not a configured data key",
+		"`value` was encrypted with another key or has been tampered with",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decrypt a field"
+			source: #"""
+				.card_number = decrypt_field!(.card_number)
+				"""#
+			return: "4111111111111111"
+		},
+	]
+}
@@ -0,0 +1,60 @@
+package metadata
+
+remap: functions: encrypt: {
+	category:    "Codec"
+	description: """
+		Encrypts `plaintext` with `algorithm` under the given `key` and `iv`, and returns the raw
+		ciphertext.
+		"""
+	notices: [
+		"""
+			The supported algorithms are `AES-256-GCM`, taking a 12 bytes IV, and `AES-256-CBC-PKCS7`,
+			taking a 16 bytes IV. Both take a 32 bytes key.
+			""",
+		"""
+			An IV must never be reused with the same key, `random_bytes` generates a fresh one.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "plaintext"
+			description: "The string to encrypt."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "algorithm"
+			description: "The name of the algorithm to encrypt with."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: "The key to encrypt with."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "iv"
+			description: "The initialization vector."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`algorithm` is not a supported algorithm",
+		"`key` or `iv` doesn't have the length expected by `algorithm`",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Encrypt a value"
+			source: #"""
+				encode_base64(encrypt!("secret", "AES-256-GCM", key: "01234567890123456789012345678901", iv: "012345678901"))
+				"""#
+			return: "A+qrGMSBtCEyQ1HpAHQgssx/j8u9kw=="
+		},
+	]
+}
@@ -0,0 +1,51 @@
+package metadata
+
+remap: functions: encrypt_field: {
+	category: "Codec"
+	description: """
+		Encrypts `value` with AES-256-GCM under the data key `key_id` and returns an envelope holding everything
+		but the key needed to decrypt it again with `decrypt_field`.
+		"""
+	notices: [
+		"""
+			The data keys are configured with the `encryption_keys` option of the `remap` transform.
+			""",
+		"""
+			A random IV is used for every call, encrypting the same value twice gives different envelopes.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to encrypt."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key_id"
+			description: "The id of the data key to encrypt with."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`key_id` is not a configured data key",
+	]
+	return: types: ["map"]
+
+	examples: [
+		{
+			title: "Encrypt a field"
+			source: #"""
+				.card_number = encrypt_field!(.card_number, "primary")
+				"""#
+			return: {
+				alg:        "AES-256-GCM"
+				key_id:     "primary"
+				iv:         "6e0bQ1uBKwdBmSfx"
+				ciphertext: "kPUvkm8YqX0fu0wpCjP3JQDo0tttDbxHtQZ0jgFu"
+			}
+		},
+	]
+}
@@ -0,0 +1,51 @@
+package metadata
+
+remap: functions: find_enrichment_table_records: {
+	category: "Enrich"
+	description: """
+		Looks up all the records of the enrichment table `table` whose fields equal the values of `condition`, by
+		column, and returns them in the order of the table.
+		"""
+	notices: [
+		"""
+			The tables are configured in the `enrichment_tables` section of the configuration. An unknown `table`
+			fails when the program is compiled.
+			""",
+		"""
+			All columns are indexed, the lookup doesn't depend on the size of the table.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "table"
+			description: "The name of the enrichment table, as a string literal."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "condition"
+			description: "The values the fields of the records must equal, by column. Integers are compared with their decimal representation."
+			required:    true
+			type: ["map"]
+		},
+	]
+	internal_failure_reasons: [
+		"`condition` is empty or references an unknown column",
+		"a value of `condition` is neither a string nor an integer",
+	]
+	return: types: ["array"]
+
+	examples: [
+		{
+			title: "Find the server error codes"
+			source: #"""
+				find_enrichment_table_records!("status_codes", { "class": "5xx" })
+				"""#
+			return: [
+				{code: "500", class: "5xx", message: "Internal Server Error"},
+				{code: "503", class: "5xx", message: "Service Unavailable"},
+			]
+		},
+	]
+}
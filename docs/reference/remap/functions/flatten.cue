@@ -3,7 +3,7 @@ package metadata
 remap: functions: flatten: {
 	category: "Enumerate"
 	description: #"""
-		Flattens the `value` into a single-level representation.
+		Flattens the `value` into a single-level representation, or down to `depth` levels.
 		"""#
 
 	arguments: [
@@ -13,8 +13,16 @@ remap: functions: flatten: {
 			required:    true
 			type: ["array", "map"]
 		},
+		{
+			name:        "depth"
+			description: "The number of nested levels to flatten, all of them if omitted."
+			required:    false
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`depth` is negative",
 	]
-	internal_failure_reasons: []
 	return: {
 		types: ["array", "map"]
 		rules: [
@@ -30,6 +38,13 @@ remap: functions: flatten: {
 				"""#
 			return: [1, 2, 3, 4, 5, 6, 7, 8, 9]
 		},
+		{
+			title: "Flatten array one level deep"
+			source: #"""
+				flatten([1, [2, [3, 4]], 5], depth: 1)
+				"""#
+			return: [1, 2, [3, 4], 5]
+		},
 		{
 			title: "Flatten map"
 			source: #"""
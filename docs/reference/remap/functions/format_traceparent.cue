@@ -0,0 +1,46 @@
+package metadata
+
+remap: functions: format_traceparent: {
+	category:    "String"
+	description: """
+		Formats a [W3C trace context](\(urls.w3c_trace_context)) `traceparent` header from a trace id and span id,
+		so it can be propagated to downstream systems.
+		"""
+
+	arguments: [
+		{
+			name:        "trace_id"
+			description: "The trace id, as 32 hex characters. 16 character ids are left-padded with zeros."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "span_id"
+			description: "The span id, as 16 hex characters."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "sampled"
+			description: "Whether the trace is sampled."
+			required:    false
+			default:     true
+			type: ["boolean"]
+		},
+	]
+	internal_failure_reasons: [
+		"`trace_id` or `span_id` are not hex strings of the right length",
+		"`trace_id` or `span_id` are all zeros",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Format traceparent"
+			source: #"""
+				format_traceparent("0af7651916cd43dd8448eb211c80319c", "b7ad6b7169203331")
+				"""#
+			return: "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"
+		},
+	]
+}
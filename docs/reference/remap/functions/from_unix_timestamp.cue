@@ -0,0 +1,55 @@
+package metadata
+
+remap: functions: from_unix_timestamp: {
+	category:    "Coerce"
+	description: """
+		Coerces the `value`, a [Unix timestamp](\(urls.unix_timestamp)), into a timestamp.
+
+		By default, the `value` is taken as the number of seconds since the Unix epoch, but milliseconds,
+		microseconds, or nanoseconds can be given via the `unit` argument. This is the inverse of
+		`to_unix_timestamp`.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The Unix timestamp to convert."
+			required:    true
+			type: ["integer"]
+		},
+		{
+			name:        "unit"
+			description: "The time unit"
+			type: ["string"]
+			required: false
+			enum: {
+				seconds:      "Express Unix time in seconds"
+				milliseconds: "Express Unix time in milliseconds"
+				microseconds: "Express Unix time in microseconds"
+				nanoseconds:  "Express Unix time in nanoseconds"
+			}
+			default: "seconds"
+		},
+	]
+	internal_failure_reasons: [
+		"`value` is not within the range of supported timestamps",
+	]
+	return: types: ["timestamp"]
+
+	examples: [
+		{
+			title: "Convert from a Unix timestamp (seconds)"
+			source: #"""
+				from_unix_timestamp!(1609459200)
+				"""#
+			return: "2021-01-01T00:00:00Z"
+		},
+		{
+			title: "Convert from a Unix timestamp (microseconds)"
+			source: #"""
+				from_unix_timestamp!(1609459200123456, unit: "microseconds")
+				"""#
+			return: "2021-01-01T00:00:00.123456Z"
+		},
+	]
+}
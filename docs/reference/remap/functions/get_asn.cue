@@ -0,0 +1,51 @@
+package metadata
+
+remap: functions: get_asn: {
+	category: "Enrich"
+	description: """
+		Looks up the autonomous system of the IP address `ip` in the MaxMind GeoLite2-ASN or GeoIP2-ISP database
+		`database`. The `isp` and `organization` fields are only set by ISP databases, the fields missing from the
+		record of the address are `null`.
+		"""
+	notices: [
+		"""
+			The database is loaded once and shared by all the programs using it. It's checked for changes every 30
+			seconds, and loaded again when the file was replaced, without reloading Vector.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "ip"
+			description: "The IPv4 or IPv6 address to look up."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "database"
+			description: "The path of the ASN or ISP database file, as a string literal. It's opened when the program is compiled."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`ip` isn't a valid IP address",
+		"the database holds no record for `ip`",
+	]
+	return: types: ["map"]
+
+	examples: [
+		{
+			title: "Find the autonomous system of an IP address"
+			source: #"""
+				get_asn!("2600:7000::1", database: "/path/to/GeoLite2-ASN.mmdb")
+				"""#
+			return: {
+				autonomous_system_number:       6939
+				autonomous_system_organization: "Hurricane Electric, Inc."
+				isp:                            null
+				organization:                   null
+			}
+		},
+	]
+}
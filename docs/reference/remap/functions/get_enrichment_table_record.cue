@@ -0,0 +1,53 @@
+package metadata
+
+remap: functions: get_enrichment_table_record: {
+	category: "Enrich"
+	description: """
+		Looks up the one record of the enrichment table `table` whose fields equal the values of `condition`, by
+		column, and returns it as a map of strings.
+		"""
+	notices: [
+		"""
+			The tables are configured in the `enrichment_tables` section of the configuration. An unknown `table`
+			fails when the program is compiled.
+			""",
+		"""
+			All columns are indexed, the lookup doesn't depend on the size of the table.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "table"
+			description: "The name of the enrichment table, as a string literal."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "condition"
+			description: "The values the fields of the record must equal, by column. Integers are compared with their decimal representation."
+			required:    true
+			type: ["map"]
+		},
+	]
+	internal_failure_reasons: [
+		"no record matches `condition`",
+		"several records match `condition`",
+		"`condition` is empty or references an unknown column",
+		"a value of `condition` is neither a string nor an integer",
+	]
+	return: types: ["map"]
+
+	examples: [
+		{
+			title: "Look up a status code"
+			source: #"""
+				get_enrichment_table_record!("status_codes", { "code": "404" })
+				"""#
+			return: {
+				code:    "404"
+				message: "Not Found"
+			}
+		},
+	]
+}
@@ -0,0 +1,53 @@
+package metadata
+
+remap: functions: get_geoip: {
+	category: "Enrich"
+	description: """
+		Looks up the location of the IP address `ip` in the MaxMind GeoIP2-City or GeoLite2-City database
+		`database`. The fields missing from the record of the address are `null`.
+		"""
+	notices: [
+		"""
+			The database is loaded once and shared by all the programs using it. It's checked for changes every 30
+			seconds, and loaded again when the file was replaced, without reloading Vector.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "ip"
+			description: "The IPv4 or IPv6 address to look up."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "database"
+			description: "The path of the city database file, as a string literal. It's opened when the program is compiled."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`ip` isn't a valid IP address",
+		"the database holds no record for `ip`",
+	]
+	return: types: ["map"]
+
+	examples: [
+		{
+			title: "Locate an IP address"
+			source: #"""
+				get_geoip!("2.125.160.216", database: "/path/to/GeoLite2-City.mmdb")
+				"""#
+			return: {
+				city_name:      "Boxford"
+				continent_code: "EU"
+				country_code:   "GB"
+				timezone:       "Europe/London"
+				latitude:       51.75
+				longitude:      -1.25
+				postal_code:    "OX1"
+			}
+		},
+	]
+}
@@ -0,0 +1,38 @@
+package metadata
+
+remap: functions: get_metadata_field: {
+	category: "Event"
+	description: """
+		Gets the metadata field `key` of the current event, set by its source or by `set_metadata_field`. The only
+		available field is `priority`.
+		"""
+
+	arguments: [
+		{
+			name:        "key"
+			description: "The name of the metadata field."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`key` isn't a known metadata field",
+	]
+	return: {
+		types: ["string", "null"]
+		rules: [
+			"If the event has no value for the field, `null` is returned.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Get the priority of the event"
+			input: log: message: "disk full"
+			source: #"""
+				get_metadata_field("priority")
+				"""#
+			return: null
+		},
+	]
+}
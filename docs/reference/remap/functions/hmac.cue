@@ -0,0 +1,67 @@
+package metadata
+
+remap: functions: hmac: {
+	category:    "Hash"
+	description: """
+		Calculates a [HMAC](\(urls.hmac)) signature of the `value` under the `key`, and returns
+		its raw bytes.
+		"""
+	notices: [
+		"""
+			The signature is binary, use `encode_base16` or `encode_base64` to get a printable
+			string.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to sign."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: "The secret key to sign with."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "algorithm"
+			description: "The hash algorithm the signature is based on."
+			enum: {
+				"SHA1":     "SHA-1 algorithm"
+				"SHA-224":  "SHA-224 algorithm"
+				"SHA-256":  "SHA-256 algorithm"
+				"SHA-384":  "SHA-384 algorithm"
+				"SHA-512":  "SHA-512 algorithm"
+				"SHA3-224": "SHA3-224 algorithm"
+				"SHA3-256": "SHA3-256 algorithm"
+				"SHA3-384": "SHA3-384 algorithm"
+				"SHA3-512": "SHA3-512 algorithm"
+			}
+			required: false
+			default:  "SHA-256"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Sign a payload"
+			source: #"""
+				encode_base16(hmac("Hello there", "super-secret-key"))
+				"""#
+			return: "78b184f1832f8aff3934f5e0212454671b2d04d494e3b25075c5e45167029662"
+		},
+		{
+			title: "Sign a payload with SHA-512"
+			source: #"""
+				encode_base64(hmac("Hello there", "super-secret-key", algorithm: "SHA-512"))
+				"""#
+			return: "IMkqB2si80Mr/pGN/kMU0CQ8hQhkOrHX13mlZYSBzi/UCCEEQBDpeME2UX9Y/8jmwfJYMHOIWDA88KcQc8YOlg=="
+		},
+	]
+}
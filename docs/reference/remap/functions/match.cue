@@ -15,12 +15,17 @@ remap: functions: match: {
 		},
 		{
 			name:        "pattern"
-			description: "The regular expression pattern to match against."
+			description: """
+				The regular expression pattern to match against. A string is compiled as a pattern, which
+				fails when it's built at runtime and isn't a valid one.
+				"""
 			required:    true
-			type: ["regex"]
+			type: ["regex", "string"]
 		},
 	]
-	internal_failure_reasons: []
+	internal_failure_reasons: [
+		"`pattern` is built at runtime and isn't a valid regular expression",
+	]
 	return: types: ["boolean"]
 
 	examples: [
@@ -0,0 +1,56 @@
+package metadata
+
+remap: functions: match_array: {
+	category:    "Enumerate"
+	description: """
+		Determines if any item of the `value` array matches the `pattern`, or all of them when
+		`all` is set.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The array of strings to match."
+			required:    true
+			type: ["array"]
+		},
+		{
+			name:        "pattern"
+			description: """
+				The regular expression pattern to match against. A string is compiled as a pattern, which
+				fails when it's built at runtime and isn't a valid one.
+				"""
+			required:    true
+			type: ["regex", "string"]
+		},
+		{
+			name:        "all"
+			description: "Whether all the items must match, rather than any of them."
+			required:    false
+			default:     false
+			type: ["boolean"]
+		},
+	]
+	internal_failure_reasons: [
+		"an item of `value` isn't a string",
+		"`pattern` is built at runtime and isn't a valid regular expression",
+	]
+	return: types: ["boolean"]
+
+	examples: [
+		{
+			title: "Match any item"
+			source: #"""
+				match_array!(["foo", "bar 2", "baz"], r'\d')
+				"""#
+			return: true
+		},
+		{
+			title: "Match all items"
+			source: #"""
+				match_array!(["foo 1", "bar"], r'\d', all: true)
+				"""#
+			return: false
+		},
+	]
+}
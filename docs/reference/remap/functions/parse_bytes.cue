@@ -0,0 +1,50 @@
+package metadata
+
+remap: functions: parse_bytes: {
+	category: "Parse"
+	description: """
+		Parses the `value` in a human size format, such as `5MiB`, into a number of bytes.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string of the size. Units are case insensitive, a number without one is in bytes."
+			required:    true
+			type: ["string"]
+			enum: {
+				B:   "Bytes"
+				kB:  "Kilobytes (1,000 bytes)"
+				MB:  "Megabytes (1,000 kilobytes)"
+				GB:  "Gigabytes (1,000 megabytes)"
+				TB:  "Terabytes (1,000 gigabytes)"
+				KiB: "Kibibytes (1,024 bytes)"
+				MiB: "Mebibytes (1,024 kibibytes)"
+				GiB: "Gibibytes (1,024 mebibytes)"
+				TiB: "Tebibytes (1,024 gibibytes)"
+			}
+		},
+	]
+	internal_failure_reasons: [
+		"`value` is not a properly formatted size",
+		"`value` is too large to fit an integer",
+	]
+	return: types: ["integer"]
+
+	examples: [
+		{
+			title: "Parse size (binary)"
+			source: #"""
+				parse_bytes("5MiB")
+				"""#
+			return: 5242880
+		},
+		{
+			title: "Parse size (decimal)"
+			source: #"""
+				parse_bytes("1.5 GB")
+				"""#
+			return: 1500000000
+		},
+	]
+}
@@ -0,0 +1,46 @@
+package metadata
+
+remap: functions: parse_cef: {
+	category:    "Parse"
+	description: """
+		Parses the `value` in the [Common Event Format (CEF)](\(urls.cef)), as
+		emitted by ArcSight and many security appliances. The fields of the header
+		and of the extension are returned in a single map, with the escaped `|`, `=`,
+		`\\` and line breaks unescaped. A prefix preceding `CEF:`, such as a syslog
+		header, is ignored.
+		"""
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` does not contain `CEF:`",
+		"`value` is missing some of the fields of the CEF header",
+		"the extension of `value` is not made of `key=value` pairs",
+	]
+	return: types: ["map"]
+	examples: [
+		{
+			title: "Parse CEF"
+			source: #"""
+				parse_cef("CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 act=blocked a \\= sign")
+				"""#
+			return: {
+				cef_version:           "0"
+				device_vendor:         "Security"
+				device_product:        "threatmanager"
+				device_version:        "1.0"
+				device_event_class_id: "100"
+				name:                  "worm successfully stopped"
+				severity:              "10"
+				src:                   "10.0.0.1"
+				dst:                   "2.1.2.2"
+				act:                   "blocked a = sign"
+			}
+		},
+	]
+}
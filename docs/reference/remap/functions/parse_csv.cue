@@ -0,0 +1,68 @@
+package metadata
+
+remap: functions: parse_csv: {
+	category:    "Parse"
+	description: """
+		Parses the `value` as a single CSV record, returning its fields as an array, or as a map
+		keyed by the `headers` when provided.
+
+		A field enclosed in `quote` characters can hold the `delimiter`, a newline or the quote
+		itself, written twice.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "delimiter"
+			description: "The single character separating the fields."
+			required:    false
+			default:     ","
+			type: ["string"]
+		},
+		{
+			name:        "quote"
+			description: "The single character enclosing the fields holding special characters."
+			required:    false
+			default:     "\""
+			type: ["string"]
+		},
+		{
+			name:        "headers"
+			description: "The names of the fields, in order. The record must have as many fields as there are headers."
+			required:    false
+			type: ["array"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a single valid CSV record",
+		"`delimiter` or `quote` isn't a single character",
+		"the number of fields doesn't match the number of `headers`",
+	]
+	return: types: ["array", "map"]
+
+	examples: [
+		{
+			title: "Parse a CSV record"
+			source: #"""
+				parse_csv("foo,\"bar, \"\"baz\"\"\",qux")
+				"""#
+			return: ["foo", "bar, \"baz\"", "qux"]
+		},
+		{
+			title: "Parse a CSV record with headers"
+			source: #"""
+				parse_csv("200;GET;/index.html", delimiter: ";", headers: ["status", "method", "path"])
+				"""#
+			return: {
+				status: "200"
+				method: "GET"
+				path:   "/index.html"
+			}
+		},
+	]
+}
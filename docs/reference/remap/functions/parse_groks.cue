@@ -0,0 +1,74 @@
+package metadata
+
+remap: functions: parse_groks: {
+	category:    "Parse"
+	description: """
+		Parses the `value` using the [`grok` format](\(urls.grok)), trying each of the `patterns` in order and
+		returning the fields of the first one that matches.
+
+		All patterns [listed here](\(urls.grok_patterns)) are supported, as well as the `aliases`.
+		"""
+	notices: [
+		"""
+			It is recommended to use maintained Grok patterns when possible, since they will be improved over time
+			by the community.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "patterns"
+			description: "The [Grok patterns](https://github.com/daschl/grok/tree/master/patterns), tried in order."
+			required:    true
+			type: ["array"]
+		},
+		{
+			name:        "aliases"
+			description: "Reusable sub-patterns, which the `patterns` and the other aliases can reference by their name as `%{NAME}`."
+			required:    false
+			type: ["map"]
+		},
+		{
+			name:        "remove_empty"
+			description: "If set to true, any patterns that resolve to an empty value will be removed from the result."
+			required:    false
+			default:     false
+			type: ["boolean"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` fails to parse via any of the provided `patterns`",
+	]
+	return: types: ["map"]
+
+	examples: [
+		{
+			title: "Parse via Grok with several patterns"
+			source: #"""
+				parse_groks(
+					"2020-10-02T23:22:12.223222Z info [api] Hello world",
+					patterns: [
+						"%{PREFIX} %{COMPONENT} %{GREEDYDATA:message}",
+						"%{PREFIX} %{GREEDYDATA:message}",
+					],
+					aliases: {
+						"PREFIX": "^%{TIMESTAMP_ISO8601:timestamp} %{LOGLEVEL:level}",
+						"COMPONENT": "\\[%{WORD:component}\\]"
+					}
+				)
+				"""#
+			return: {
+				timestamp: "2020-10-02T23:22:12.223222Z"
+				level:     "info"
+				component: "api"
+				message:   "Hello world"
+			}
+		},
+	]
+}
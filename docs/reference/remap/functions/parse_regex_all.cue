@@ -18,13 +18,17 @@ remap: functions: parse_regex_all: {
 		},
 		{
 			name:        "pattern"
-			description: "The regular expression pattern to search against."
+			description: """
+				The regular expression pattern to search against. A string is compiled as a pattern, which
+				fails when it's built at runtime and isn't a valid one.
+				"""
 			required:    true
-			type: ["regex"]
+			type: ["regex", "string"]
 		},
 	]
 	internal_failure_reasons: [
 		"`value` fails to parse via the provided `pattern`",
+		"`pattern` is built at runtime and isn't a valid regular expression",
 	]
 	return: {
 		types: ["array"]
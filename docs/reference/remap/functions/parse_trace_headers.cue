@@ -0,0 +1,55 @@
+package metadata
+
+remap: functions: parse_trace_headers: {
+	category:    "Parse"
+	description: """
+		Extracts the trace context from a map of request headers, so that logs can be correlated with
+		traces. The following propagation formats are supported, in order of precedence:
+
+		* [W3C trace context](\(urls.w3c_trace_context)) (`traceparent`)
+		* [B3](\(urls.b3_propagation)) single header (`b3`)
+		* B3 multiple headers (`X-B3-TraceId`, `X-B3-SpanId`, `X-B3-Sampled`, `X-B3-Flags`)
+		* Datadog (`x-datadog-trace-id`, `x-datadog-parent-id`, `x-datadog-sampling-priority`)
+
+		Header names are matched case-insensitively. Ids are normalized to their W3C representation:
+		lowercase hex, with 32 characters for trace ids and 16 for span ids.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The map of headers."
+			required:    true
+			type: ["map"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` does not contain a valid trace context in any of the supported formats",
+	]
+	return: {
+		types: ["map"]
+		rules: [
+			#"`format` is one of `w3c`, `b3_single`, `b3` or `datadog`."#,
+			#"`sampled` is `null` if the headers do not carry a sampling decision."#,
+		]
+	}
+
+	examples: [
+		{
+			title: "Extract Datadog headers"
+			source: #"""
+				parse_trace_headers({
+					"x-datadog-trace-id": "7245652779352043654",
+					"x-datadog-parent-id": "4003058461449618871",
+					"x-datadog-sampling-priority": "1"
+				})
+				"""#
+			return: {
+				format:   "datadog"
+				trace_id: "0000000000000000648dbad04f19b086"
+				span_id:  "378db87629d385b7"
+				sampled:  true
+			}
+		},
+	]
+}
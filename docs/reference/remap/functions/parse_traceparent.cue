@@ -0,0 +1,38 @@
+package metadata
+
+remap: functions: parse_traceparent: {
+	category:    "Parse"
+	description: """
+		Parses the `value` as a [W3C trace context](\(urls.w3c_trace_context)) `traceparent` header.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The `traceparent` header value."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` is not a properly formatted `traceparent` header",
+		"the trace id or span id is all zeros",
+	]
+	return: types: ["map"]
+
+	examples: [
+		{
+			title: "Parse traceparent"
+			source: #"""
+				parse_traceparent("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01")
+				"""#
+			return: {
+				version:  "00"
+				trace_id: "0af7651916cd43dd8448eb211c80319c"
+				span_id:  "b7ad6b7169203331"
+				flags:    1
+				sampled:  true
+			}
+		},
+	]
+}
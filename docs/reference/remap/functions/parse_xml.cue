@@ -0,0 +1,75 @@
+package metadata
+
+remap: functions: parse_xml: {
+	category: "Parse"
+	description: """
+		Parses the `value` as XML, into a map with the root element as its only field.
+
+		Elements with neither attributes nor child elements become strings, or `null` when empty. Other
+		elements become maps holding their attributes, their child elements, and their text. Child elements
+		sharing a name are gathered in an array.
+		"""
+	notices: [
+		"""
+			Only strings are returned for the attributes and text of the elements. If you need to convert
+			them into other types, consider the `to_int`, `to_float`, or `to_timestamp` functions.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string representation of the XML document to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "attr_prefix"
+			description: "The prefix of the keys of the attributes, telling them apart from child elements."
+			required:    false
+			default:     "@"
+			type: ["string"]
+		},
+		{
+			name:        "text_key"
+			description: "The key of the text of elements that also have attributes or child elements."
+			required:    false
+			default:     "text"
+			type: ["string"]
+		},
+		{
+			name:        "always_array"
+			description: "The names of the elements always gathered in an array, even when appearing only once."
+			required:    false
+			default:     []
+			type: ["array"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` is not a valid XML document",
+	]
+	return: types: ["map"]
+
+	examples: [
+		{
+			title: "Parse XML"
+			source: #"""
+				parse_xml!("<order id=\"42\"><item>book</item><item>pen</item></order>")
+				"""#
+			return: order: {
+				"@id": "42"
+				item: ["book", "pen"]
+			}
+		},
+		{
+			title: "Parse XML with options"
+			source: #"""
+				parse_xml!("<order id=\"42\"><item>book</item></order>", attr_prefix: "", always_array: ["item"])
+				"""#
+			return: order: {
+				id: "42"
+				item: ["book"]
+			}
+		},
+	]
+}
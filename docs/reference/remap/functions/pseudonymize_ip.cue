@@ -0,0 +1,50 @@
+package metadata
+
+remap: functions: pseudonymize_ip: {
+	category: "IP"
+	description: """
+		Replaces the IP address `value` with a pseudonym, the hex encoded HMAC-SHA256 of the address keyed
+		with `key`. The same address and key always give the same pseudonym, so events of a client can still be
+		correlated without revealing its address.
+		"""
+	notices: [
+		"""
+			The address is hashed in its canonical form, different notations of the same IPv6 address get the
+			same pseudonym.
+			""",
+		"""
+			Anyone knowing the key can recover addresses by hashing candidates, keep it secret and out of the
+			configuration file, e.g. with `get_env_var`.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address to pseudonymize - either a v4 or a v6 address."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: "The secret key of the HMAC."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` is not a valid IP address",
+		"`key` is empty",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Pseudonymize an IP address"
+			source: #"""
+				pseudonymize_ip("203.0.113.7", "secret")
+				"""#
+			return: "d1eda5f85436ad2d5e25828b7bc77ca290d6c797a9ab2479327aec37fa3e09d3"
+		},
+	]
+}
@@ -0,0 +1,31 @@
+package metadata
+
+remap: functions: random_bytes: {
+	category:    "Random"
+	description: """
+		Generates `length` cryptographically secure random bytes.
+		"""
+
+	arguments: [
+		{
+			name:        "length"
+			description: "The number of bytes to generate, up to 65536."
+			required:    true
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`length` is negative or greater than 65536",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Generate an IV"
+			source: #"""
+				encode_base64(random_bytes!(16))
+				"""#
+			return: "cQvQ3mTvMAyxm8FHEy7ztw=="
+		},
+	]
+}
@@ -0,0 +1,43 @@
+package metadata
+
+remap: functions: set_metadata_field: {
+	category: "Event"
+	description: """
+		Sets the metadata field `key` of the current event to `value`. Metadata isn't part of the event, and so
+		isn't encoded by sinks. The only available field is `priority`, one of `low`, `normal` or `high`, which
+		the sinks whose buffer drops events by priority read.
+		"""
+
+	arguments: [
+		{
+			name:        "key"
+			description: "The name of the metadata field."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "value"
+			description: "The value to set the field to."
+			required:    true
+			type: ["any"]
+		},
+	]
+	internal_failure_reasons: [
+		"`key` isn't a known metadata field",
+		"`value` isn't a valid value of the field",
+	]
+	return: types: ["null"]
+
+	examples: [
+		{
+			title: "Lower the priority of debug logs"
+			input: log: level: "debug"
+			source: #"""
+				if .level == "debug" {
+					set_metadata_field("priority", "low")
+				}
+				"""#
+			output: log: level: "debug"
+		},
+	]
+}
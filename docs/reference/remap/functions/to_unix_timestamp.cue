@@ -5,7 +5,7 @@ remap: functions: to_unix_timestamp: {
 	description: """
 		Coerces the `value` into a [Unix timestamp](\(urls.unix_timestamp)).
 
-		By default, the number of seconds since the Unix epoch is returned, but milliseconds or nanoseconds can be
+		By default, the number of seconds since the Unix epoch is returned, but milliseconds, microseconds, or nanoseconds can be
 		returned via the `unit` argument.
 		"""
 
@@ -24,6 +24,7 @@ remap: functions: to_unix_timestamp: {
 			enum: {
 				seconds:      "Express Unix time in seconds"
 				milliseconds: "Express Unix time in milliseconds"
+				microseconds: "Express Unix time in microseconds"
 				nanoseconds:  "Express Unix time in nanoseconds"
 			}
 			default: "seconds"
@@ -47,6 +48,13 @@ remap: functions: to_unix_timestamp: {
 				"""#
 			return: 1609459200000
 		},
+		{
+			title: "Convert to a Unix timestamp (microseconds)"
+			source: #"""
+				to_unix_timestamp(to_timestamp("2021-01-01T00:00:00+00:00"), unit: "microseconds")
+				"""#
+			return: 1609459200000000
+		},
 		{
 			title: "Convert to a Unix timestamp (nanoseconds)"
 			source: #"""
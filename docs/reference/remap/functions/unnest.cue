@@ -0,0 +1,40 @@
+package metadata
+
+remap: functions: unnest: {
+	category:    "Enumerate"
+	description: """
+		Returns one copy of the event for each item of the array at `path`, the array being
+		replaced by the item in each copy.
+		"""
+
+	arguments: [
+		{
+			name:        "path"
+			description: "The path of the array to unnest."
+			required:    true
+			type: ["path"]
+		},
+	]
+	internal_failure_reasons: [
+		"`path` doesn't exist",
+		"`path` isn't an array",
+	]
+	return: types: ["array"]
+
+	examples: [
+		{
+			title: "Unnest an array"
+			input: log: {
+				host: "localhost"
+				events: [{"id": 1}, {"id": 2}]
+			}
+			source: #"""
+				unnest(.events)
+				"""#
+			return: [
+				{host: "localhost", events: {"id": 1}},
+				{host: "localhost", events: {"id": 2}},
+			]
+		},
+	]
+}
@@ -0,0 +1,36 @@
+package metadata
+
+remap: functions: zip: {
+	category:    "Enumerate"
+	description: """
+		Pairs the items of `array1` with the ones of `array2` at the same index. The items past
+		the end of the shorter array are left out.
+		"""
+
+	arguments: [
+		{
+			name:        "array1"
+			description: "The array of the first items of the pairs."
+			required:    true
+			type: ["array"]
+		},
+		{
+			name:        "array2"
+			description: "The array of the second items of the pairs."
+			required:    true
+			type: ["array"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["array"]
+
+	examples: [
+		{
+			title: "Zip two arrays"
+			source: #"""
+				zip([1, 2, 3], ["a", "b"])
+				"""#
+			return: [[1, "a"], [2, "b"]]
+		},
+	]
+}
@@ -10,8 +10,10 @@ remap: literals: integer: {
 		ordering: {
 			title: "Limits"
 			description: """
-				Integers in VRL can range from `-9223372036854775807` to `9223372036854775807`. Integers outside that range are
-				wrapped.
+				Integers in VRL can range from `-9223372036854775808` to `9223372036854775807`. Integer literals outside that
+				range are rejected, and arithmetic whose result falls outside it fails with an `integer overflow` runtime
+				error instead of wrapping. Integers parsed from JSON that fall outside it, such as large unsigned counters,
+				are rounded to floats.
 				"""
 		}
 
@@ -12,6 +12,7 @@ remap: syntax: keywords: {
 		* `continue`
 		* `else`
 		* `false`
+		* `fn`
 		* `for`
 		* `if`
 		* `impl`
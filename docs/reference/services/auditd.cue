@@ -0,0 +1,10 @@
+package metadata
+
+services: auditd: {
+	name:     "Linux Audit"
+	thing:    "the \(name) subsystem"
+	url:      urls.auditd
+	versions: null
+
+	description: "The [Linux Audit](\(urls.auditd)) subsystem records the system calls, logins and other security relevant actions of a host, according to its audit rules."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: datadog_agent: {
+	name:     "Datadog Agent"
+	thing:    "a \(name)"
+	url:      urls.datadog_agent
+	versions: ">= 6.0"
+
+	description: services._datadog.description
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: haproxy: {
+	name:     "HAProxy"
+	thing:    "an \(name) server"
+	url:      urls.haproxy
+	versions: null
+
+	description: "[HAProxy][urls.haproxy] is a reliable, high performance TCP/HTTP load balancer."
+}
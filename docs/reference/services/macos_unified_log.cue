@@ -0,0 +1,10 @@
+package metadata
+
+services: macos_unified_log: {
+	name:     "macOS Unified Logging"
+	thing:    "the \(name) system"
+	url:      urls.apple_unified_logging
+	versions: ">= 10.12"
+
+	description: "The [unified logging system](\(urls.apple_unified_logging)) of macOS collects the logs of the system and of the applications, in a binary store of its own."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: opentelemetry: {
+	name:     "OpenTelemetry"
+	thing:    "an \(name) SDK or collector"
+	url:      urls.opentelemetry
+	versions: null
+
+	description: "[OpenTelemetry][urls.opentelemetry] is a collection of APIs, SDKs, and tools to instrument, generate, collect, and export telemetry data (metrics, logs, and traces), which are sent between its components with the OpenTelemetry protocol (OTLP)."
+}
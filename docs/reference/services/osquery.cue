@@ -0,0 +1,10 @@
+package metadata
+
+services: osquery: {
+	name:     "osquery"
+	thing:    "an \(name) daemon"
+	url:      urls.osquery
+	versions: null
+
+	description: "[osquery](\(urls.osquery)) exposes the operating system as a relational database, and runs scheduled SQL queries to monitor hosts."
+}
@@ -11,8 +11,13 @@ urls: {
 	apache_extended_status:                                   "\(apache)/docs/current/mod/core.html#extendedstatus"
 	apache_install:                                           "\(apache)/docs/current/install.html"
 	apache_mod_status:                                        "http://httpd.apache.org/docs/current/mod/mod_status.html"
+	apple_log_predicates:                                     "https://developer.apple.com/library/archive/documentation/Cocoa/Conceptual/Predicates/Articles/pSyntax.html"
+	apple_unified_logging:                                    "https://developer.apple.com/documentation/os/logging"
 	apt:                                                      "\(wikipedia)/wiki/APT_(software)"
 	arm:                                                      "\(wikipedia)/wiki/ARM_architecture"
+	audisp_plugins:                                           "https://man7.org/linux/man-pages/man8/audispd.8.html"
+	auditd:                                                   "https://man7.org/linux/man-pages/man8/auditd.8.html"
+	auditd_record_types:                                      "https://access.redhat.com/documentation/en-us/red_hat_enterprise_linux/8/html/security_hardening/auditing-the-system_security-hardening#understanding-audit-log-files_auditing-the-system"
 	aws_access_keys:                                          "\(aws_docs)/IAM/latest/UserGuide/id_credentials_access-keys.html"
 	aws_arm_g2_announcement:                                  "https://aws.amazon.com/about-aws/whats-new/2019/12/announcing-new-amazon-ec2-m6g-c6g-and-r6g-instances-powered-by-next-generation-arm-based-aws-graviton2-processors/"
 	aws_athena:                                               "https://aws.amazon.com/athena/"
@@ -73,12 +78,15 @@ urls: {
 	aws_s3_sse:                                               "\(aws_docs)/AmazonS3/latest/dev/UsingServerSideEncryption.html"
 	aws_s3_storage_classes:                                   "https://aws.amazon.com/s3/storage-classes/"
 	aws_s3_tags:                                              "\(aws_docs)/AmazonS3/latest/user-guide/add-object-tags.html"
+	aws_sigv4:                                                "\(aws_docs)/general/latest/gr/signature-version-4.html"
 	aws_sqs:                                                  "https://aws.amazon.com/sqs/"
 	aws_sqs_api:                                              "\(aws_docs)/AWSSimpleQueueService/latest/APIReference/Welcome.html"
 	aws_sqs_create:                                           "\(aws_docs)/AWSSimpleQueueService/latest/SQSDeveloperGuide/sqs-configure-create-queue.html"
 	aws_vpc_flow_logs:                                        "\(aws_docs)/vpc/latest/userguide/flow-logs.html"
 	azure_monitor:                                            "https://azure.microsoft.com/en-us/services/monitor/"
 	azure_monitor_logs_endpoints:                             "https://docs.microsoft.com/en-us/rest/api/monitor/"
+	b3_propagation:                                           "\(github)/openzipkin/b3-propagation"
+	base16:                                                   "\(wikipedia)/wiki/Hexadecimal"
 	base64:                                                   "\(wikipedia)/wiki/Base64"
 	base64_padding:                                           "\(wikipedia)/wiki/Base64#Output_padding"
 	base64_standard:                                          "https://tools.ietf.org/html/rfc4648#section-4"
@@ -87,6 +95,7 @@ urls: {
 	big_query_streaming:                                      "https://cloud.google.com/bigquery/streaming-data-into-bigquery"
 	b_tree_map:                                               "https://doc.rust-lang.org/std/collections/struct.BTreeMap.html"
 	cargo_audit:                                              "\(github)/RustSec/cargo-audit"
+	cef:                                                      "https://www.microfocus.com/documentation/arcsight/arcsight-smartconnectors/pdfdoc/common-event-format-v25/common-event-format-v25.pdf"
 	centos:                                                   "https://www.centos.org/"
 	chrono_time_formats:                                      "https://docs.rs/chrono/latest/chrono/format/strftime/index.html#specifiers"
 	cgroups_limit_resources:                                  "https://the.binbashtheory.com/control-resources-cgroups/"
@@ -95,6 +104,8 @@ urls: {
 	cloudsmith:                                               "https://cloudsmith.io/~timber/repos/vector/packages/"
 	cloudsmith_apt:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-deb"
 	cloudsmith_yum:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-rpm"
+	confluent_schema_registry:                                "https://docs.confluent.io/platform/current/schema-registry/index.html"
+	confluent_wire_format:                                    "https://docs.confluent.io/platform/current/schema-registry/serdes-develop/index.html#wire-format"
 	console:                                                  "\(wikipedia)/wiki/System_console"
 	conventional_commits:                                     "https://www.conventionalcommits.org"
 	contributing:                                             "\(vector_repo)/blob/master/CONTRIBUTING.md#setup"
@@ -103,6 +114,7 @@ urls: {
 	cue:                                                      "https://cuelang.org/"
 	dag:                                                      "\(wikipedia)/wiki/Directed_acyclic_graph"
 	datadog:                                                  "https://www.datadoghq.com"
+	datadog_agent:                                            "\(datadog_docs)/agent/"
 	datadog_distribution:                                     "\(datadog_docs)/developers/metrics/types/?tab=distribution#definition"
 	datadog_docs:                                             "https://docs.datadoghq.com"
 	datadog_logs:                                             "\(datadog_docs)/logs/"
@@ -136,6 +148,7 @@ urls: {
 	docker_setup:                                             "\(docker_docs)/get-docker/"
 	dockerfile:                                               "\(vector_repo)/blob/master/Dockerfile"
 	dogstatsd:                                                "\(datadog_docs)/developers/dogstatsd/?tab=hostagent"
+	drain:                                                    "https://jiemingzhu.github.io/pub/pjhe_icws2017.pdf"
 	dpkg:                                                     "https://wiki.debian.org/dpkg"
 	dry_code:                                                 "\(wikipedia)/wiki/Don%27t_repeat_yourself"
 	cidr:                                                     "\(wikipedia)/wiki/Classless_Inter-Domain_Routing"
@@ -189,10 +202,12 @@ urls: {
 	grok_patterns:                                            "\(github)/daschl/grok/tree/master/patterns"
 	gzip:                                                     "https://www.gzip.org/"
 	haproxy:                                                  "https://www.haproxy.org/"
+	haproxy_stats_csv:                                        "https://www.haproxy.org/download/2.2/doc/management.txt"
 	helm:                                                     "https://helm.sh/"
 	heroku:                                                   "https://www.heroku.com"
 	heroku_http_log_drain:                                    "https://devcenter.heroku.com/articles/log-drains#https-drains"
 	heroku_start:                                             "https://devcenter.heroku.com/start"
+	hmac:                                                     "\(wikipedia)/wiki/HMAC"
 	homebrew:                                                 "https://brew.sh/"
 	homebrew_services:                                        "\(github)/Homebrew/homebrew-services"
 	honeycomb:                                                "https://honeycomb.io"
@@ -250,6 +265,7 @@ urls: {
 	logfmt:                                                   "https://brandur.org/logfmt"
 	loki:                                                     "https://grafana.com/oss/loki/"
 	loki_multi_tenancy:                                       "\(github)/grafana/loki/blob/master/docs/operations/multi-tenancy.md"
+	loki_structured_metadata:                                 "https://grafana.com/docs/loki/latest/get-started/labels/structured-metadata/"
 	log_event_source:                                         "\(vector_repo)/blob/master/src/event/"
 	logplex:                                                  "https://devcenter.heroku.com/articles/logplex"
 	logplex_protocol:                                         "\(github)/heroku/logplex/blob/master/doc/README.http_drains.md"
@@ -286,6 +302,9 @@ urls: {
 	memory_safety_bugs:                                       "https://thenewstack.io/microsoft-rust-is-the-industrys-best-chance-at-safe-systems-programming/"
 	metric_event_source:                                      "\(vector_repo)/blob/master/src/event/metric.rs"
 	mongodb:                                                  "https://www.mongodb.com"
+	mongodb_change_streams:                                   "https://docs.mongodb.com/manual/changeStreams/"
+	mongodb_command_coll_stats:                               "https://docs.mongodb.com/manual/reference/command/collStats/"
+	mongodb_command_repl_set_get_status:                      "https://docs.mongodb.com/manual/reference/command/replSetGetStatus/"
 	mongodb_command_server_status:                            "https://docs.mongodb.com/manual/reference/command/serverStatus/"
 	mongodb_connection_string_uri_format:                     "https://docs.mongodb.com/manual/reference/connection-string/"
 	musl_builder_docker_image:                                "\(vector_repo)/blob/master/scripts/ci-docker-images/builder-x86_64-unknown-linux-musl/Dockerfile"
@@ -304,14 +323,23 @@ urls: {
 	nix:                                                      "https://nixos.org/nix/"
 	nixos:                                                    "https://nixos.org/"
 	nixpkgs_9682:                                             "\(github)/NixOS/nixpkgs/issues/9682"
+	oauth2_client_credentials:                                "https://datatracker.ietf.org/doc/html/rfc6749#section-4.4"
 	openssl:                                                  "https://www.openssl.org/"
+	opentelemetry:                                            "https://opentelemetry.io/"
+	otlp:                                                     "https://opentelemetry.io/docs/specs/otlp/"
 	order_of_ops:                                             "\(wikipedia)/wiki/Order_of_operations"
+	osquery:                                                  "https://osquery.io/"
+	osquery_logging:                                          "https://osquery.readthedocs.io/en/stable/deployment/logging/"
+	pagerduty:                                                "https://www.pagerduty.com"
+	pagerduty_events_api:                                     "https://developer.pagerduty.com/docs/events-api-v2/overview/"
+	pagerduty_routing_key:                                    "https://support.pagerduty.com/docs/services-and-integrations#create-a-generic-events-api-integration"
 	papertrail:                                               "https://www.papertrail.com/"
 	papertrail_syslog:                                        "https://help.papertrailapp.com/kb/how-it-works/http-api/#submitting-log-messages"
 	perl_windows:                                             "https://www.perl.org/get.html#win32"
 	postgresql:                                               "https://www.postgresql.org/"
 	postgresql_csvlog:                                        "https://www.postgresql.org/docs/current/runtime-config-logging.html#RUNTIME-CONFIG-LOGGING-CSVLOG"
 	postgresql_matching:                                      "https://www.postgresql.org/docs/current/functions-matching.html#FUNCTIONS-POSIX-REGEXP"
+	postgresql_publication:                                   "https://www.postgresql.org/docs/current/logical-replication-publication.html"
 	prometheus:                                               "https://prometheus.io/"
 	prometheus_client:                                        "https://prometheus.io/docs/instrumenting/clientlibs/"
 	prometheus_counter:                                       "https://prometheus.io/docs/concepts/metric_types/#counter"
@@ -359,6 +387,8 @@ urls: {
 	sematext_monitoring:                                      "https://sematext.com/docs/monitoring/"
 	sematext_registration:                                    "https://apps.sematext.com/ui/registration"
 	semver:                                                   "https://semver.org/"
+	sentry:                                                   "https://sentry.io"
+	sentry_dsn:                                               "https://docs.sentry.io/product/sentry-basics/dsn-explainer/"
 	sha1:                                                     "https://en.wikipedia.org/wiki/SHA-1"
 	sha2:                                                     "https://en.wikipedia.org/wiki/SHA-2"
 	sha3:                                                     "https://en.wikipedia.org/wiki/SHA-3"
@@ -501,6 +531,7 @@ urls: {
 	vrl_safety:                                               "\(vrl_reference)#safety"
 	vrl_type_safety:                                          "\(vrl_reference)#type-safety"
 	vote_feature:                                             "\(vector_repo)/issues?q=is%3Aissue+is%3Aopen+sort%3Areactions-%2B1-desc+label%3A%22Type%3A+New+Feature%22"
+	w3c_trace_context:                                        "https://www.w3.org/TR/trace-context/"
 	wasm:                                                     "https://webassembly.org/"
 	wasm_languages:                                           "\(github)/appcypher/awesome-wasm-langs"
 	wikipedia:                                                "https://en.wikipedia.org"
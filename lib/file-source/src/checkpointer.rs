@@ -4,7 +4,8 @@ use dashmap::DashMap;
 use glob::glob;
 use serde::{Deserialize, Serialize};
 use std::{
-    fs, io,
+    fs,
+    io::{self, Write},
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -32,12 +33,24 @@ struct Checkpoint {
     modified: DateTime<Utc>,
 }
 
+/// Storage the checkpoints are persisted to in place of the files of the data directory, such as
+/// a remote store for hosts that don't keep their disk across restarts. It is only ever called
+/// from blocking threads.
+pub trait CheckpointStore: Send + Sync {
+    /// Read the checkpoints last put, if there are any.
+    fn get(&self) -> Result<Option<Vec<u8>>, io::Error>;
+
+    /// Replace the stored checkpoints as a whole.
+    fn put(&self, checkpoints: Vec<u8>) -> Result<(), io::Error>;
+}
+
 pub struct Checkpointer {
     directory: PathBuf,
     tmp_file_path: PathBuf,
     stable_file_path: PathBuf,
     glob_string: String,
     checkpoints: Arc<CheckpointsView>,
+    store: Option<Box<dyn CheckpointStore>>,
 }
 
 /// A thread-safe handle for reading and writing checkpoints in-memory across multiple threads.
@@ -128,6 +141,35 @@ impl CheckpointsView {
         }
     }
 
+    /// Loads the checkpoints of another host, keeping the local ones that are more recent.
+    /// Device and inode fingerprints are skipped, as they don't identify the same files on
+    /// another host. Returns the number of checkpoints imported.
+    fn import_state(&self, state: State, ignore_before: Option<DateTime<Utc>>) -> usize {
+        let mut imported = 0;
+        match state {
+            State::V1 { checkpoints } => {
+                for checkpoint in checkpoints {
+                    if matches!(checkpoint.fingerprint, FileFingerprint::DevInode(..))
+                        || ignore_before
+                            .map_or(false, |ignore_before| checkpoint.modified < ignore_before)
+                    {
+                        continue;
+                    }
+
+                    let local_modified = self
+                        .modified_times
+                        .get(&checkpoint.fingerprint)
+                        .map(|r| *r.value());
+                    if local_modified.map_or(true, |local| local < checkpoint.modified) {
+                        self.load(checkpoint);
+                        imported += 1;
+                    }
+                }
+            }
+        }
+        imported
+    }
+
     fn get_state(&self) -> State {
         State::V1 {
             checkpoints: self
@@ -175,6 +217,17 @@ impl Checkpointer {
             tmp_file_path,
             stable_file_path,
             checkpoints: Arc::new(CheckpointsView::default()),
+            store: None,
+        }
+    }
+
+    /// Persist the checkpoints to `store` rather than to the data directory. The checkpoints
+    /// found in the data directory are still read when the store has none yet, carrying them
+    /// over to the store.
+    pub fn with_store(data_dir: &Path, store: Box<dyn CheckpointStore>) -> Checkpointer {
+        Checkpointer {
+            store: Some(store),
+            ..Checkpointer::new(data_dir)
         }
     }
 
@@ -256,6 +309,11 @@ impl Checkpointer {
         // writing checkpoints that don't matter anymore.
         self.checkpoints.remove_expired();
 
+        if let Some(store) = &self.store {
+            store.put(serde_json::to_vec(&self.checkpoints.get_state())?)?;
+            return Ok(self.checkpoints.checkpoints.len());
+        }
+
         // Write the new checkpoints to a tmp file and flush it fully to disk. If vector
         // dies anywhere during this section, the existing stable file will still be in its current
         // valid state and we'll be able to recover.
@@ -309,6 +367,32 @@ impl Checkpointer {
             }
         }
 
+        // The store, when there is one, is where the checkpoints are expected to be. Only fall back
+        // to the files of the data directory when it has none yet.
+        if let Some(store) = &self.store {
+            let state = store.get().and_then(|data| {
+                data.map(|data| {
+                    serde_json::from_slice(&data)
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+                })
+                .transpose()
+            });
+            match state {
+                Ok(Some(state)) => {
+                    info!(message = "Loaded checkpoint data.");
+                    self.checkpoints.set_state(state, ignore_before);
+                    return;
+                }
+                Ok(None) => {
+                    // This is expected on the first start with a store.
+                }
+                Err(error) => {
+                    warn!(message = "Unable to load checkpoint data.", %error);
+                    return;
+                }
+            }
+        }
+
         // Next, attempt to read checkpoints from the stable file location. This is the
         // expected location, so warn more aggressively if something goes wrong.
         match self.read_checkpoints_file(&self.stable_file_path) {
@@ -335,6 +419,37 @@ impl Checkpointer {
         }
     }
 
+    /// Import the checkpoints exported by `export_checkpoints` on another host, typically once
+    /// the persisted ones are read, so that files moved along with a workload are read from where
+    /// they were left off.
+    pub fn import_checkpoints(&mut self, path: &Path, ignore_before: Option<DateTime<Utc>>) {
+        match self.read_checkpoints_file(path) {
+            Ok(state) => {
+                let imported = self.checkpoints.import_state(state, ignore_before);
+                info!(message = "Imported checkpoint data.", path = ?path, imported);
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                warn!(message = "Checkpoint data to import not found.", path = ?path);
+            }
+            Err(error) => {
+                error!(message = "Unable to import checkpoint data.", path = ?path, %error);
+            }
+        }
+    }
+
+    /// Write the persisted checkpoints in a portable format, independent of the data directory,
+    /// for `import_checkpoints` to read on another host. Returns the number of checkpoints
+    /// exported.
+    pub fn export_checkpoints(&mut self, writer: impl io::Write) -> Result<usize, io::Error> {
+        self.read_checkpoints(None);
+
+        let mut writer = io::BufWriter::new(writer);
+        serde_json::to_writer_pretty(&mut writer, &self.checkpoints.get_state())?;
+        writer.flush()?;
+
+        Ok(self.checkpoints.checkpoints.len())
+    }
+
     fn read_checkpoints_file(&self, path: &Path) -> Result<State, io::Error> {
         let reader = io::BufReader::new(fs::File::open(path)?);
         serde_json::from_reader(reader).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
@@ -365,9 +480,14 @@ impl Checkpointer {
 #[cfg(test)]
 mod test {
     use super::{
-        Checkpoint, Checkpointer, FileFingerprint, FilePosition, STABLE_FILE_NAME, TMP_FILE_NAME,
+        Checkpoint, CheckpointStore, Checkpointer, FileFingerprint, FilePosition, STABLE_FILE_NAME,
+        TMP_FILE_NAME,
     };
     use chrono::{Duration, Utc};
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
     use tempfile::tempdir;
 
     #[test]
@@ -441,6 +561,44 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_checkpointer_export_import() {
+        let portable = FileFingerprint::FirstLineChecksum(78910);
+        let local = FileFingerprint::DevInode(1, 2);
+        let position: FilePosition = 1234;
+        let old_host = tempdir().unwrap();
+        let new_host = tempdir().unwrap();
+        let export_path = new_host.path().join("export.json");
+
+        {
+            let mut chkptr = Checkpointer::new(&old_host.path());
+            chkptr.update_checkpoint(portable, position);
+            chkptr.update_checkpoint(local, position);
+            chkptr.write_checkpoints().unwrap();
+        }
+        {
+            let mut chkptr = Checkpointer::new(&old_host.path());
+            let file = std::fs::File::create(&export_path).unwrap();
+            assert_eq!(chkptr.export_checkpoints(file).unwrap(), 2);
+        }
+        {
+            let mut chkptr = Checkpointer::new(&new_host.path());
+            chkptr.read_checkpoints(None);
+            chkptr.import_checkpoints(&export_path, None);
+            assert_eq!(chkptr.get_checkpoint(portable), Some(position));
+            assert_eq!(chkptr.get_checkpoint(local), None);
+        }
+        {
+            // The checkpoints moved on since the import are kept on the next startup.
+            let mut chkptr = Checkpointer::new(&new_host.path());
+            chkptr.update_checkpoint(portable, position + 10);
+            chkptr.write_checkpoints().unwrap();
+            chkptr.read_checkpoints(None);
+            chkptr.import_checkpoints(&export_path, None);
+            assert_eq!(chkptr.get_checkpoint(portable), Some(position + 10));
+        }
+    }
+
     #[test]
     fn test_checkpointer_restart() {
         let fingerprints = vec![
@@ -467,6 +625,54 @@ mod test {
         }
     }
 
+    #[derive(Clone, Default)]
+    struct MemoryStore(Arc<Mutex<Option<Vec<u8>>>>);
+
+    impl CheckpointStore for MemoryStore {
+        fn get(&self) -> Result<Option<Vec<u8>>, io::Error> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+
+        fn put(&self, checkpoints: Vec<u8>) -> Result<(), io::Error> {
+            *self.0.lock().unwrap() = Some(checkpoints);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_checkpointer_store() {
+        let fingerprint = FileFingerprint::FirstLineChecksum(78910);
+        let position: FilePosition = 1234;
+        let data_dir = tempdir().unwrap();
+        let store = MemoryStore::default();
+
+        // The checkpoints of the data directory are carried over to an empty store.
+        {
+            let mut chkptr = Checkpointer::new(&data_dir.path());
+            chkptr.update_checkpoint(fingerprint, position);
+            chkptr.write_checkpoints().unwrap();
+        }
+        {
+            let mut chkptr = Checkpointer::with_store(&data_dir.path(), Box::new(store.clone()));
+            chkptr.read_checkpoints(None);
+            assert_eq!(chkptr.get_checkpoint(fingerprint), Some(position));
+            chkptr.update_checkpoint(fingerprint, position + 10);
+            chkptr.write_checkpoints().unwrap();
+        }
+
+        // From then on the store is read, and the data directory left alone.
+        {
+            let mut chkptr = Checkpointer::new(&data_dir.path());
+            chkptr.read_checkpoints(None);
+            assert_eq!(chkptr.get_checkpoint(fingerprint), Some(position));
+        }
+        {
+            let mut chkptr = Checkpointer::with_store(&data_dir.path(), Box::new(store));
+            chkptr.read_checkpoints(None);
+            assert_eq!(chkptr.get_checkpoint(fingerprint), Some(position + 10));
+        }
+    }
+
     #[test]
     fn test_checkpointer_fingerprint_upgrades() {
         let new_fingerprint = FileFingerprint::DevInode(1, 2);
@@ -1,12 +1,13 @@
 use crate::{
-    checkpointer::{Checkpointer, CheckpointsView},
+    checkpointer::{CheckpointStore, Checkpointer, CheckpointsView},
     file_watcher::FileWatcher,
     fingerprinter::{FileFingerprint, Fingerprinter},
-    FileSourceInternalEvents, ReadFrom,
+    FilePosition, FileSourceInternalEvents, ReadFrom,
 };
 use bytes::Bytes;
 use chrono::{DateTime, Utc};
 use futures::{
+    channel::mpsc,
     executor::block_on,
     future::{select, Either, FutureExt},
     stream, Future, Sink, SinkExt,
@@ -44,12 +45,40 @@ where
     pub max_line_bytes: usize,
     pub line_delimiter: Bytes,
     pub data_dir: PathBuf,
+    pub checkpoint_import_path: Option<PathBuf>,
+    /// When set, the checkpoints are persisted here instead of in `data_dir`.
+    pub checkpoint_store: Option<Box<dyn CheckpointStore>>,
     pub glob_minimum_cooldown: Duration,
     pub fingerprinter: Fingerprinter,
     pub oldest_first: bool,
     pub remove_after: Option<Duration>,
     pub emitter: E,
     pub handle: tokio::runtime::Handle,
+    /// When set, the checkpoints of the files only advance to the positions
+    /// received here, once the lines before them were acknowledged, instead of
+    /// as soon as the lines are read.
+    pub acknowledgements: Option<mpsc::UnboundedReceiver<Acknowledgement>>,
+}
+
+/// The outcome of the lines read from a file, in the order they were read.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Acknowledgement {
+    /// The lines of the file up to the position are done with, and its
+    /// checkpoint advances to it.
+    Delivered(FileFingerprint, FilePosition),
+    /// The line following the checkpoint of the file errored, and the file is
+    /// read again from its checkpoint.
+    Errored(FileFingerprint),
+}
+
+/// A line read from a file.
+#[derive(Clone, Debug)]
+pub struct Line {
+    pub text: Bytes,
+    pub filename: String,
+    pub file_id: FileFingerprint,
+    /// The position in the file after the line.
+    pub offset: FilePosition,
 }
 
 /// `FileServer` as Source
@@ -71,13 +100,13 @@ where
     E: FileSourceInternalEvents,
 {
     pub fn run<C, S>(
-        self,
+        mut self,
         mut chans: C,
         shutdown: S,
-    ) -> Result<Shutdown, <C as Sink<Vec<(Bytes, String)>>>::Error>
+    ) -> Result<Shutdown, <C as Sink<Vec<Line>>>::Error>
     where
-        C: Sink<Vec<(Bytes, String)>> + Unpin,
-        <C as Sink<Vec<(Bytes, String)>>>::Error: std::error::Error,
+        C: Sink<Vec<Line>> + Unpin,
+        <C as Sink<Vec<Line>>>::Error: std::error::Error,
         S: Future + Unpin + Send + 'static,
         <S as Future>::Output: Clone + Send + Sync,
     {
@@ -88,8 +117,14 @@ where
         let mut backoff_cap: usize = 1;
         let mut lines = Vec::new();
 
-        let mut checkpointer = Checkpointer::new(&self.data_dir);
+        let mut checkpointer = match self.checkpoint_store.take() {
+            Some(store) => Checkpointer::with_store(&self.data_dir, store),
+            None => Checkpointer::new(&self.data_dir),
+        };
         checkpointer.read_checkpoints(self.ignore_before);
+        if let Some(import_path) = &self.checkpoint_import_path {
+            checkpointer.import_checkpoints(import_path, self.ignore_before);
+        }
 
         let mut known_small_files = HashSet::new();
 
@@ -115,6 +150,7 @@ where
         checkpointer.maybe_upgrade(existing_files.iter().map(|(_, id)| id).cloned());
 
         let checkpoints = checkpointer.view();
+        let mut acknowledgements = self.acknowledgements.take();
 
         let needs_checksum_upgrade = checkpoints.contains_bytes_checksums();
 
@@ -257,6 +293,19 @@ where
                 stats.record("discovery", start.elapsed());
             }
 
+            if let Some(acknowledgements) = &mut acknowledgements {
+                while let Ok(Some(acknowledgement)) = acknowledgements.try_next() {
+                    match acknowledgement {
+                        Acknowledgement::Delivered(file_id, position) => {
+                            checkpoints.update(file_id, position)
+                        }
+                        Acknowledgement::Errored(file_id) => {
+                            self.read_again(file_id, &mut fp_map, &checkpoints)
+                        }
+                    }
+                }
+            }
+
             // Collect lines by polling files.
             let mut global_bytes_read: usize = 0;
             let mut maxed_out_reading_single_file = false;
@@ -266,6 +315,7 @@ where
                 }
 
                 let start = time::Instant::now();
+                let start_position = watcher.get_file_position();
                 let mut bytes_read: usize = 0;
                 while let Ok(Some(line)) = watcher.read_line() {
                     if line.is_empty() {
@@ -282,10 +332,12 @@ where
 
                     bytes_read += sz;
 
-                    lines.push((
-                        line,
-                        watcher.path.to_str().expect("not a valid path").to_owned(),
-                    ));
+                    lines.push(Line {
+                        text: line,
+                        filename: watcher.path.to_str().expect("not a valid path").to_owned(),
+                        file_id,
+                        offset: watcher.get_file_position(),
+                    });
 
                     if bytes_read > self.max_read_bytes {
                         maxed_out_reading_single_file = true;
@@ -296,7 +348,13 @@ where
 
                 if bytes_read > 0 {
                     global_bytes_read = global_bytes_read.saturating_add(bytes_read);
-                    checkpoints.update(file_id, watcher.get_file_position());
+                    if acknowledgements.is_none() {
+                        checkpoints.update(file_id, watcher.get_file_position());
+                    } else if checkpoints.get(file_id).is_none() {
+                        // Where the file is read again from if its first line
+                        // errors.
+                        checkpoints.update(file_id, start_position);
+                    }
                 } else {
                     // Should the file be removed
                     if let Some(grace_period) = self.remove_after {
@@ -432,6 +490,35 @@ where
             Err(error) => self.emitter.emit_file_watch_failed(&path, error),
         };
     }
+
+    /// Reads the file `file_id` again from its checkpoint, which the lines
+    /// acknowledged before an errored one advanced to.
+    fn read_again(
+        &self,
+        file_id: FileFingerprint,
+        fp_map: &mut IndexMap<FileFingerprint, FileWatcher>,
+        checkpoints: &CheckpointsView,
+    ) {
+        let (path, file_position) = match (fp_map.get(&file_id), checkpoints.get(file_id)) {
+            (Some(watcher), Some(file_position)) => (watcher.path.clone(), file_position),
+            _ => return,
+        };
+
+        match FileWatcher::new(
+            path.clone(),
+            ReadFrom::Checkpoint(file_position),
+            self.ignore_before,
+            self.max_line_bytes,
+            self.line_delimiter.clone(),
+        ) {
+            Ok(mut watcher) => {
+                self.emitter.emit_file_resumed(&path, file_position);
+                watcher.set_file_findable(true);
+                fp_map.insert(file_id, watcher);
+            }
+            Err(error) => self.emitter.emit_file_watch_failed(&path, error),
+        };
+    }
 }
 
 /// A sentinel type to signal that file server was gracefully shut down.
@@ -1,9 +1,9 @@
-use super::{repl, Error};
+use super::{fuzz, repl, Error};
 use remap::{state, Formatter, Object, Program, Runtime, Value};
 use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{self, Read};
-use std::iter::IntoIterator;
+use std::iter::{self, IntoIterator};
 use std::path::PathBuf;
 use structopt::StructOpt;
 
@@ -15,7 +15,8 @@ pub struct Opts {
     #[structopt(name = "PROGRAM")]
     program: Option<String>,
 
-    /// The file containing the event object(s) to handle. The supported formats are JSON and jsonl.
+    /// The file containing the event object(s) to handle, read from STDIN otherwise. The supported
+    /// formats are JSON, with arrays of objects, and jsonl.
     #[structopt(short, long = "input", parse(from_os_str))]
     input_file: Option<PathBuf>,
 
@@ -27,6 +28,24 @@ pub struct Opts {
     /// this flag is equivalent to using `.` as the final expression.
     #[structopt(short = "o", long)]
     print_object: bool,
+
+    /// Print the result of the final expression, which is the default.
+    #[structopt(long, conflicts_with("print-object"))]
+    print_result: bool,
+
+    /// Run the program against mutations of the event object(s) instead, and report the inputs
+    /// it fails on with a runtime error or a panic.
+    #[structopt(long)]
+    fuzz: bool,
+
+    /// The number of inputs to generate when fuzzing.
+    #[structopt(long, default_value = "10000")]
+    iterations: u64,
+
+    /// The seed of the mutations when fuzzing. Fuzzing with the same seed and inputs generates the
+    /// same mutations.
+    #[structopt(long, default_value = "0")]
+    seed: u64,
 }
 
 pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
@@ -50,26 +69,55 @@ fn run(opts: &Opts) -> Result<(), Error> {
         };
 
         repl(repl_objects)
-    } else {
+    } else if opts.fuzz {
         let objects = read_into_objects(opts.input_file.as_ref())?;
-        let program = read_program(opts.program.as_deref(), opts.program_file.as_ref())?;
-
-        for mut object in objects {
-            let result = execute(&mut object, program.clone()).map(|v| {
-                if opts.print_object {
-                    object.to_string()
-                } else {
-                    v.to_string()
+        let source = read_program(opts.program.as_deref(), opts.program_file.as_ref())?;
+        let program = compile(&source)?;
+
+        let failures = fuzz::fuzz(&program, &objects, opts.iterations, opts.seed);
+        for failure in &failures {
+            println!(
+                "{}\n  occurrences: {}\n  input: {}\n",
+                failure.error,
+                failure.count,
+                serde_json::to_string(&failure.input)?
+            );
+        }
+        println!(
+            "{} inputs, {} distinct failures",
+            opts.iterations,
+            failures.len()
+        );
+
+        match failures.len() {
+            0 => Ok(()),
+            count => Err(Error::Fuzz(count)),
+        }
+    } else {
+        let source = read_program(opts.program.as_deref(), opts.program_file.as_ref())?;
+        let program = compile(&source)?;
+
+        // The objects are handled as they're read, so that a stream can be piped in.
+        let mut total = 0;
+        let mut failed = 0;
+        for object in read_objects(opts.input_file.as_ref())? {
+            let mut object = object?;
+            total += 1;
+
+            match execute(&mut object, &program) {
+                Ok(_) if opts.print_object => println!("{}", serde_json::to_string(&object)?),
+                Ok(result) => println!("{}", serde_json::to_string(&result)?),
+                Err(err) => {
+                    failed += 1;
+                    eprintln!("{}", err);
                 }
-            });
-
-            match result {
-                Ok(ok) => println!("{}", ok),
-                Err(err) => eprintln!("{}", err),
             }
         }
 
-        Ok(())
+        match failed {
+            0 => Ok(()),
+            failed => Err(Error::Failed(failed, total)),
+        }
     }
 }
 
@@ -83,18 +131,24 @@ fn repl(_: Vec<Value>) -> Result<(), Error> {
     Err(Error::ReplFeature)
 }
 
-fn execute(object: &mut impl Object, source: String) -> Result<Value, Error> {
+fn execute(object: &mut impl Object, program: &Program) -> Result<Value, Error> {
     let state = state::Program::default();
     let mut runtime = Runtime::new(state);
-    let (program, _) = Program::new(source.clone(), &remap_functions::all(), None, true).map_err(
-        |diagnostics| Error::Parse(Formatter::new(&source, diagnostics).colored().to_string()),
-    )?;
 
     runtime
-        .run(object, &program)
+        .run(object, program)
         .map_err(|err| Error::Runtime(err.to_string()))
 }
 
+fn compile(source: &str) -> Result<Program, Error> {
+    let (program, _) = Program::new(source.to_owned(), &remap_functions::all(), None, true)
+        .map_err(|diagnostics| {
+            Error::Parse(Formatter::new(source, diagnostics).colored().to_string())
+        })?;
+
+    Ok(program)
+}
+
 fn read_program(source: Option<&str>, file: Option<&PathBuf>) -> Result<String, Error> {
     match source {
         Some(source) => Ok(source.to_owned()),
@@ -106,18 +160,38 @@ fn read_program(source: Option<&str>, file: Option<&PathBuf>) -> Result<String,
 }
 
 fn read_into_objects(input: Option<&PathBuf>) -> Result<Vec<Value>, Error> {
-    let input = match input {
-        Some(path) => read(File::open(path)?),
-        None => read(io::stdin()),
-    }?;
-
-    match input.as_str() {
-        "" => Ok(vec![Value::Map(BTreeMap::default())]),
-        _ => input
-            .lines()
-            .map(|line| Ok(serde_to_remap(serde_json::from_str(&line)?)))
-            .collect::<Result<Vec<Value>, Error>>(),
+    read_objects(input)?.collect()
+}
+
+/// Reads the objects of a JSON or NDJSON input as they're needed. The elements of top-level
+/// arrays are read as objects of their own, and an empty input as a single empty object.
+fn read_objects(
+    input: Option<&PathBuf>,
+) -> Result<Box<dyn Iterator<Item = Result<Value, Error>>>, Error> {
+    let reader: Box<dyn Read> = match input {
+        Some(path) => Box::new(io::BufReader::new(File::open(path)?)),
+        None => Box::new(io::stdin()),
+    };
+
+    let mut values = serde_json::Deserializer::from_reader(reader)
+        .into_iter::<serde_json::Value>()
+        .peekable();
+    if values.peek().is_none() {
+        return Ok(Box::new(iter::once(Ok(Value::Map(BTreeMap::default())))));
     }
+
+    Ok(Box::new(values.flat_map(|value| {
+        let values = match value {
+            Ok(serde_json::Value::Array(values)) => values,
+            Ok(value) => vec![value],
+            Err(err) => return vec![Err(err.into())],
+        };
+
+        values
+            .into_iter()
+            .map(|value| Ok(serde_to_remap(value)))
+            .collect::<Vec<_>>()
+    })))
 }
 
 fn serde_to_remap(value: serde_json::Value) -> Value {
@@ -131,8 +205,9 @@ fn serde_to_remap(value: serde_json::Value) -> Value {
             .collect::<BTreeMap<_, _>>()
             .into(),
         Value::Bool(v) => v.into(),
-        Value::Number(v) if v.is_f64() => v.as_f64().unwrap().into(),
-        Value::Number(v) => v.as_i64().unwrap_or(i64::MAX).into(),
+        Value::Number(v) if v.is_i64() => v.as_i64().unwrap().into(),
+        // Floats, and integers above `i64::MAX` which are rounded to floats.
+        Value::Number(v) => v.as_f64().unwrap().into(),
         Value::String(v) => v.into(),
         Value::Array(v) => v.into_iter().map(serde_to_remap).collect::<Vec<_>>().into(),
     }
@@ -0,0 +1,176 @@
+use remap::{state, Program, Runtime, Value};
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+
+/// An input the program failed on, along with how many of the generated
+/// inputs failed with the same error.
+#[derive(Debug)]
+pub struct Failure {
+    pub error: String,
+    pub input: Value,
+    pub count: u64,
+}
+
+/// Runs `program` against `iterations` mutations of the `samples`, returning
+/// the distinct runtime errors and panics it ran into. The same `seed` always
+/// generates the same inputs.
+pub fn fuzz(program: &Program, samples: &[Value], iterations: u64, seed: u64) -> Vec<Failure> {
+    let mut rng = Rng::new(seed);
+    let mut failures = BTreeMap::<String, Failure>::new();
+
+    // Panics are reported as failures, the default hook would print them too.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    for iteration in 0..iterations {
+        let index = iteration as usize % samples.len();
+        let mut input = samples[index].clone();
+
+        // The samples themselves are run first.
+        if iteration >= samples.len() as u64 {
+            for _ in 0..=rng.below(3) {
+                mutate(&mut rng, &mut input);
+            }
+        }
+
+        if let Err(error) = execute(program, input.clone()) {
+            failures
+                .entry(error.clone())
+                .or_insert(Failure {
+                    error,
+                    input,
+                    count: 0,
+                })
+                .count += 1;
+        }
+    }
+
+    panic::set_hook(hook);
+
+    failures.into_iter().map(|(_, failure)| failure).collect()
+}
+
+fn execute(program: &Program, mut object: Value) -> Result<Value, String> {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        Runtime::new(state::Program::default()).run(&mut object, program)
+    }));
+
+    match result {
+        Ok(result) => result.map_err(|err| err.to_string()),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_owned());
+            Err(format!("panic: {}", message))
+        }
+    }
+}
+
+/// Mutates a value nested somewhere in `value`, or `value` itself.
+fn mutate(rng: &mut Rng, value: &mut Value) {
+    match value {
+        Value::Map(map) if !map.is_empty() && rng.below(4) != 0 => {
+            let key = map.keys().nth(rng.below(map.len())).cloned().unwrap();
+            match rng.below(8) {
+                0 => {
+                    map.remove(&key);
+                }
+                1 => {
+                    map.insert(format!("{}_fuzz", key), interesting(rng));
+                }
+                _ => mutate(rng, map.get_mut(&key).unwrap()),
+            }
+        }
+        Value::Array(array) if !array.is_empty() && rng.below(4) != 0 => {
+            let index = rng.below(array.len());
+            match rng.below(8) {
+                0 => {
+                    array.remove(index);
+                }
+                1 => array.push(interesting(rng)),
+                _ => mutate(rng, &mut array[index]),
+            }
+        }
+        Value::Bytes(bytes) if rng.below(2) == 0 => {
+            let mut string = String::from_utf8_lossy(bytes).into_owned();
+            mutate_string(rng, &mut string);
+            *value = string.into();
+        }
+        _ => *value = interesting(rng),
+    }
+}
+
+fn mutate_string(rng: &mut Rng, string: &mut String) {
+    let boundaries = string
+        .char_indices()
+        .map(|(index, _)| index)
+        .chain(std::iter::once(string.len()))
+        .collect::<Vec<_>>();
+    let at = boundaries[rng.below(boundaries.len())];
+
+    match rng.below(3) {
+        0 => string.truncate(at),
+        1 => string.insert_str(at, STRINGS[rng.below(STRINGS.len())]),
+        _ => *string = string.repeat(2 + rng.below(8)),
+    }
+}
+
+const STRINGS: &[&str] = &[
+    "",
+    " ",
+    "0",
+    "-1",
+    "1e309",
+    "true",
+    "null",
+    "{}",
+    "[]",
+    "\"",
+    "\\",
+    "\n",
+    "\0",
+    "%s",
+    "é",
+    "🦀",
+    "2021-01-01T00:00:00Z",
+    "not a timestamp",
+    "127.0.0.1",
+    "::1",
+];
+
+/// A value of a random type, picked among edge cases programs often miss.
+fn interesting(rng: &mut Rng) -> Value {
+    match rng.below(7) {
+        0 => Value::Null,
+        1 => (rng.below(2) == 0).into(),
+        2 => [0, -1, 1, i64::MIN, i64::MAX][rng.below(5)].into(),
+        3 => [0.0, -0.0, 0.5, f64::MIN, f64::MAX, f64::INFINITY, f64::NAN][rng.below(7)].into(),
+        4 => STRINGS[rng.below(STRINGS.len())].into(),
+        5 => Value::Array(vec![]),
+        _ => Value::Map(BTreeMap::new()),
+    }
+}
+
+/// A SplitMix64 generator, which is plenty for picking mutations.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
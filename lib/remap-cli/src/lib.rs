@@ -1,4 +1,6 @@
 pub mod cmd;
+mod fuzz;
+pub mod lsp;
 #[cfg(feature = "repl")]
 mod repl;
 
@@ -9,15 +11,21 @@ pub enum Error {
     #[error("io error")]
     Io(#[from] std::io::Error),
 
-    #[error("parse error")]
+    #[error("{0}")]
     Parse(String),
 
-    #[error("runtime error")]
+    #[error("runtime error: {0}")]
     Runtime(String),
 
     #[error("json error")]
     Json(#[from] serde_json::Error),
 
+    #[error("fuzzing found {0} distinct failures")]
+    Fuzz(usize),
+
+    #[error("{0} of {1} objects failed with a runtime error")]
+    Failed(usize, usize),
+
     #[cfg(not(feature = "repl"))]
     #[error("repl feature disabled, program input required")]
     ReplFeature,
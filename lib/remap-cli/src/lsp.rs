@@ -0,0 +1,558 @@
+//! A language server for VRL, speaking the Language Server Protocol over STDIN
+//! and STDOUT, so editors can report the diagnostics of the compiler, document
+//! the functions and complete them, as well as the paths of the events.
+
+use super::Error;
+use remap::{diagnostic::Severity, Diagnostic, Function, Program};
+use serde_json::{json, Value as JsonValue};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{self, BufRead, Read, Write};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+const FUNCTIONS_ROOT_URL: &str = "https://vector.dev/docs/reference/vrl/functions";
+
+/// The kinds of the completion items of the protocol.
+const COMPLETION_KIND_FUNCTION: u8 = 3;
+const COMPLETION_KIND_FIELD: u8 = 5;
+
+/// The error code of the requests whose method isn't supported.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "lsp", about = "Vector Remap Language language server")]
+pub struct Opts {
+    /// The file containing a JSON event object, as handled by the programs. Its paths are
+    /// completed after `.`, and their types shown on hover.
+    #[structopt(short, long, parse(from_os_str))]
+    schema: Option<PathBuf>,
+}
+
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    match run(opts) {
+        // The client is expected to request a shutdown before exiting the server.
+        Ok(true) => exitcode::OK,
+        Ok(false) => exitcode::SOFTWARE,
+        Err(err) => {
+            eprintln!("{}", err);
+            exitcode::SOFTWARE
+        }
+    }
+}
+
+fn run(opts: &Opts) -> Result<bool, Error> {
+    let paths = match &opts.schema {
+        Some(path) => event_paths(&serde_json::from_reader(File::open(path)?)?),
+        None => BTreeMap::new(),
+    };
+    let mut server = Server::new(remap_functions::all(), paths);
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let stdout = io::stdout();
+    let mut output = stdout.lock();
+
+    while let Some(message) = read_message(&mut input)? {
+        for message in server.handle(message) {
+            write_message(&mut output, &message)?;
+        }
+
+        if server.exited {
+            break;
+        }
+    }
+
+    Ok(server.shut_down)
+}
+
+/// Reads a message, preceded by its `Content-Length` header. Returns `None`
+/// once the input is closed.
+fn read_message(input: &mut impl BufRead) -> Result<Option<JsonValue>, Error> {
+    let mut length = None;
+
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        let mut parts = header.splitn(2, ':');
+        if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = Some(
+                    value
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|err| Error::Parse(err.to_string()))?,
+                );
+            }
+        }
+    }
+
+    let length = length.ok_or_else(|| Error::Parse("missing Content-Length header".to_owned()))?;
+    let mut content = vec![0; length];
+    input.read_exact(&mut content)?;
+
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+fn write_message(output: &mut impl Write, message: &JsonValue) -> Result<(), Error> {
+    let content = serde_json::to_string(message)?;
+    write!(
+        output,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    output.flush()?;
+
+    Ok(())
+}
+
+struct Server {
+    functions: Vec<Box<dyn Function>>,
+    /// The paths of the sample event, along with the type of their values.
+    paths: BTreeMap<String, &'static str>,
+    /// The text of the open documents, by URI.
+    documents: HashMap<String, String>,
+    shut_down: bool,
+    exited: bool,
+}
+
+impl Server {
+    fn new(functions: Vec<Box<dyn Function>>, paths: BTreeMap<String, &'static str>) -> Self {
+        Self {
+            functions,
+            paths,
+            documents: HashMap::new(),
+            shut_down: false,
+            exited: false,
+        }
+    }
+
+    /// Handles a request or a notification, returning the messages to send
+    /// back.
+    fn handle(&mut self, message: JsonValue) -> Vec<JsonValue> {
+        let id = message.get("id").cloned();
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or_default()
+            .to_owned();
+
+        match method {
+            "initialize" => vec![response(
+                id,
+                json!({
+                    "capabilities": {
+                        // The full text of the documents is sent on change.
+                        "textDocumentSync": 1,
+                        "hoverProvider": true,
+                        "completionProvider": { "triggerCharacters": ["."] },
+                    },
+                    "serverInfo": { "name": "vector lsp" },
+                }),
+            )],
+            "shutdown" => {
+                self.shut_down = true;
+                vec![response(id, JsonValue::Null)]
+            }
+            "exit" => {
+                self.exited = true;
+                vec![]
+            }
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents.insert(uri.clone(), text.to_owned());
+                vec![self.publish_diagnostics(&uri)]
+            }
+            "textDocument/didChange" => {
+                let changes = params["contentChanges"].as_array();
+                if let Some(text) = changes
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                {
+                    self.documents.insert(uri.clone(), text.to_owned());
+                }
+                vec![self.publish_diagnostics(&uri)]
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                vec![notification(
+                    "textDocument/publishDiagnostics",
+                    json!({ "uri": uri, "diagnostics": [] }),
+                )]
+            }
+            "textDocument/hover" => {
+                let hover = self.documents.get(&uri).and_then(|source| {
+                    let offset = offset(source, &params["position"]);
+                    self.hover(source, offset)
+                });
+                vec![response(id, hover.unwrap_or(JsonValue::Null))]
+            }
+            "textDocument/completion" => {
+                let items = self.documents.get(&uri).map(|source| {
+                    let offset = offset(source, &params["position"]);
+                    self.complete(source, offset)
+                });
+                vec![response(id, items.unwrap_or_else(|| json!([])))]
+            }
+            _ => match id {
+                Some(id) => vec![json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {
+                        "code": METHOD_NOT_FOUND,
+                        "message": format!("unsupported method: {}", method),
+                    },
+                })],
+                // Unsupported notifications are ignored.
+                None => vec![],
+            },
+        }
+    }
+
+    fn publish_diagnostics(&self, uri: &str) -> JsonValue {
+        let source = self.documents.get(uri).map(String::as_str).unwrap_or("");
+        let diagnostics = self
+            .diagnostics(source)
+            .iter()
+            .map(|diagnostic| to_lsp_diagnostic(source, diagnostic))
+            .collect::<Vec<_>>();
+
+        notification(
+            "textDocument/publishDiagnostics",
+            json!({ "uri": uri, "diagnostics": diagnostics }),
+        )
+    }
+
+    fn diagnostics(&self, source: &str) -> Vec<Diagnostic> {
+        match Program::new(source.to_owned(), &self.functions, None, true) {
+            Ok((_, diagnostics)) => diagnostics.into_iter().collect(),
+            Err(diagnostics) => diagnostics.into_iter().collect(),
+        }
+    }
+
+    /// Documents the function called, or the type of the path, at `offset`.
+    fn hover(&self, source: &str, offset: usize) -> Option<JsonValue> {
+        let start = token_start(source, offset);
+        let end = token_end(source, offset);
+        let token = &source[start..end];
+
+        let contents = if token.starts_with('.') {
+            let kind = self.paths.get(token)?;
+            format!("```vrl\n{}: {}\n```", token, kind)
+        } else {
+            let function = self.function(token)?;
+            format!(
+                "```vrl\n{}\n```\n\n[Documentation]({}/#{})",
+                signature(function),
+                FUNCTIONS_ROOT_URL,
+                function.identifier()
+            )
+        };
+
+        Some(json!({
+            "contents": { "kind": "markdown", "value": contents },
+            "range": range(source, start, end),
+        }))
+    }
+
+    /// Completes the path, or the function, being written at `offset`.
+    fn complete(&self, source: &str, offset: usize) -> JsonValue {
+        let start = token_start(source, offset);
+        let prefix = &source[start..offset];
+        let range = range(source, start, offset);
+
+        let items = if prefix.starts_with('.') {
+            self.paths
+                .iter()
+                .filter(|(path, _)| path.starts_with(prefix))
+                .map(|(path, kind)| {
+                    json!({
+                        "label": path,
+                        "kind": COMPLETION_KIND_FIELD,
+                        "detail": kind,
+                        "textEdit": { "range": range, "newText": path },
+                    })
+                })
+                .collect::<Vec<_>>()
+        } else if prefix.contains('.') {
+            vec![]
+        } else {
+            self.functions
+                .iter()
+                .filter(|function| function.identifier().starts_with(prefix))
+                .map(|function| {
+                    json!({
+                        "label": function.identifier(),
+                        "kind": COMPLETION_KIND_FUNCTION,
+                        "detail": signature(function.as_ref()),
+                        "textEdit": { "range": range, "newText": function.identifier() },
+                    })
+                })
+                .collect()
+        };
+
+        JsonValue::Array(items)
+    }
+
+    fn function(&self, identifier: &str) -> Option<&dyn Function> {
+        self.functions
+            .iter()
+            .find(|function| function.identifier() == identifier)
+            .map(|function| function.as_ref())
+    }
+}
+
+fn response(id: Option<JsonValue>, result: JsonValue) -> JsonValue {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn notification(method: &str, params: JsonValue) -> JsonValue {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+fn to_lsp_diagnostic(source: &str, diagnostic: &Diagnostic) -> JsonValue {
+    let labels = diagnostic.labels();
+    let label = labels
+        .iter()
+        .find(|label| label.primary)
+        .or_else(|| labels.first());
+    let span = label.map(|label| label.span).unwrap_or_default();
+
+    let mut message = diagnostic.message().to_owned();
+    if let Some(label) = label.filter(|label| label.message != message) {
+        message.push_str(": ");
+        message.push_str(&label.message);
+    }
+    for note in diagnostic.notes() {
+        message.push('\n');
+        message.push_str(&note.to_string());
+    }
+
+    let severity = match diagnostic.severity() {
+        Severity::Bug | Severity::Error => 1,
+        Severity::Warning => 2,
+        Severity::Note => 3,
+    };
+
+    json!({
+        "range": range(source, span.start, span.end),
+        "severity": severity,
+        "source": "vrl",
+        "message": message,
+    })
+}
+
+/// The signature of a function, with its optional parameters marked by `?`.
+fn signature(function: &dyn Function) -> String {
+    let parameters = function
+        .parameters()
+        .iter()
+        .map(|parameter| {
+            if parameter.required {
+                parameter.keyword.to_owned()
+            } else {
+                format!("{}?", parameter.keyword)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    format!("{}({})", function.identifier(), parameters.join(", "))
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '.'
+}
+
+/// The start of the identifier or path around `offset`.
+fn token_start(source: &str, offset: usize) -> usize {
+    source[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_token_char(*c))
+        .last()
+        .map(|(index, _)| index)
+        .unwrap_or(offset)
+}
+
+/// The end of the identifier or path around `offset`.
+fn token_end(source: &str, offset: usize) -> usize {
+    source[offset..]
+        .char_indices()
+        .find(|(_, c)| !is_token_char(*c))
+        .map(|(index, _)| offset + index)
+        .unwrap_or_else(|| source.len())
+}
+
+fn range(source: &str, start: usize, end: usize) -> JsonValue {
+    json!({ "start": position(source, start), "end": position(source, end) })
+}
+
+/// Converts a byte offset into a position of the protocol, whose character is
+/// counted in UTF-16 code units.
+fn position(source: &str, offset: usize) -> JsonValue {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    let before = &source[..offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+    let character = before[line_start..].encode_utf16().count();
+
+    json!({ "line": line, "character": character })
+}
+
+/// Converts a position of the protocol into a byte offset, clamped to the end
+/// of its line.
+fn offset(source: &str, position: &JsonValue) -> usize {
+    let line = position["line"].as_u64().unwrap_or(0) as usize;
+    let character = position["character"].as_u64().unwrap_or(0) as usize;
+
+    let line_start = match line {
+        0 => 0,
+        line => match source.match_indices('\n').nth(line - 1) {
+            Some((index, _)) => index + 1,
+            None => return source.len(),
+        },
+    };
+
+    let mut units = 0;
+    for (index, c) in source[line_start..].char_indices() {
+        if units >= character || c == '\n' {
+            return line_start + index;
+        }
+        units += c.len_utf16();
+    }
+
+    source.len()
+}
+
+/// The paths of a sample event, along with the type of their values.
+fn event_paths(event: &JsonValue) -> BTreeMap<String, &'static str> {
+    fn walk(prefix: &str, value: &JsonValue, paths: &mut BTreeMap<String, &'static str>) {
+        if let JsonValue::Object(fields) = value {
+            for (key, value) in fields {
+                let path = if key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    format!("{}.{}", prefix, key)
+                } else {
+                    format!("{}.{:?}", prefix, key)
+                };
+                paths.insert(path.clone(), kind(value));
+                walk(&path, value, paths);
+            }
+        }
+    }
+
+    let mut paths = BTreeMap::new();
+    walk("", event, &mut paths);
+    paths
+}
+
+fn kind(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(number) if number.is_f64() => "float",
+        JsonValue::Number(_) => "integer",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "map",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server() -> Server {
+        let event = json!({ "message": "foo", "http": { "status": 200 }, "user-agent": "curl" });
+        Server::new(remap_functions::all(), event_paths(&event))
+    }
+
+    #[test]
+    fn converts_positions() {
+        let source = ".foo = \"é\"\n.bar = 1";
+
+        assert_eq!(position(source, 11), json!({ "line": 0, "character": 10 }));
+        assert_eq!(position(source, 13), json!({ "line": 1, "character": 1 }));
+        assert_eq!(offset(source, &json!({ "line": 0, "character": 10 })), 11);
+        assert_eq!(offset(source, &json!({ "line": 0, "character": 42 })), 11);
+        assert_eq!(offset(source, &json!({ "line": 1, "character": 1 })), 13);
+    }
+
+    #[test]
+    fn frames_messages() {
+        let mut output = Vec::new();
+        write_message(&mut output, &json!({ "jsonrpc": "2.0" })).unwrap();
+        assert_eq!(output, b"Content-Length: 17\r\n\r\n{\"jsonrpc\":\"2.0\"}");
+
+        let mut input = io::Cursor::new(output);
+        assert_eq!(
+            read_message(&mut input).unwrap(),
+            Some(json!({ "jsonrpc": "2.0" }))
+        );
+        assert_eq!(read_message(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn publishes_diagnostics() {
+        let mut server = server();
+        let messages = server.handle(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": "file:///a.vrl", "text": ".foo = 1\nnope(.foo)" } },
+        }));
+
+        let diagnostics = messages[0]["params"]["diagnostics"].as_array().unwrap();
+        assert!(!diagnostics.is_empty());
+        assert_eq!(diagnostics[0]["severity"], json!(1));
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], json!(1));
+    }
+
+    #[test]
+    fn completes_functions_and_paths() {
+        let server = server();
+
+        let items = server.complete("upc", 3);
+        assert_eq!(items[0]["label"], json!("upcase"));
+        assert_eq!(items[0]["detail"], json!("upcase(value)"));
+
+        let items = server.complete(".h", 2);
+        let labels = items
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["label"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, vec![".http", ".http.status"]);
+    }
+
+    #[test]
+    fn hovers_functions_and_paths() {
+        let server = server();
+
+        let hover = server.hover("upcase(.message)", 2).unwrap();
+        assert!(hover["contents"]["value"]
+            .as_str()
+            .unwrap()
+            .contains("upcase(value)"));
+
+        let hover = server.hover("upcase(.http.status)", 15).unwrap();
+        assert_eq!(
+            hover["contents"]["value"],
+            json!("```vrl\n.http.status: integer\n```")
+        );
+        assert!(server.hover("upcase(.missing)", 10).is_none());
+    }
+}
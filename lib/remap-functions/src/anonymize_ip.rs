@@ -0,0 +1,230 @@
+use remap::prelude::*;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+#[derive(Clone, Copy, Debug)]
+pub struct AnonymizeIp;
+
+impl Function for AnonymizeIp {
+    fn identifier(&self) -> &'static str {
+        "anonymize_ip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "ipv4_prefix",
+                accepts: |v| matches!(v, Value::Integer(_)),
+                required: false,
+            },
+            Parameter {
+                keyword: "ipv6_prefix",
+                accepts: |v| matches!(v, Value::Integer(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+        let ipv4_prefix = arguments.optional("ipv4_prefix").map(Expr::boxed);
+        let ipv6_prefix = arguments.optional("ipv6_prefix").map(Expr::boxed);
+
+        Ok(Box::new(AnonymizeIpFn {
+            value,
+            ipv4_prefix,
+            ipv6_prefix,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AnonymizeIpFn {
+    value: Box<dyn Expression>,
+    ipv4_prefix: Option<Box<dyn Expression>>,
+    ipv6_prefix: Option<Box<dyn Expression>>,
+}
+
+impl AnonymizeIpFn {
+    #[cfg(test)]
+    fn new(
+        value: Box<dyn Expression>,
+        ipv4_prefix: Option<Box<dyn Expression>>,
+        ipv6_prefix: Option<Box<dyn Expression>>,
+    ) -> Self {
+        Self {
+            value,
+            ipv4_prefix,
+            ipv6_prefix,
+        }
+    }
+}
+
+/// By default the last octet of IPv4 addresses, and all but the first 48
+/// bits of IPv6 addresses are zeroed.
+const DEFAULT_IPV4_PREFIX: i64 = 24;
+const DEFAULT_IPV6_PREFIX: i64 = 48;
+
+impl Expression for AnonymizeIpFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let value: IpAddr = self
+            .value
+            .execute(state, object)?
+            .try_bytes_utf8_lossy()?
+            .parse()
+            .map_err(|err| format!("unable to parse IP address: {}", err))?;
+
+        let anonymized: IpAddr = match value {
+            IpAddr::V4(addr) => {
+                let prefix = prefix(&self.ipv4_prefix, DEFAULT_IPV4_PREFIX, 32, state, object)?;
+                let mask = (!0u64 << (32 - prefix)) as u32;
+                Ipv4Addr::from(u32::from(addr) & mask).into()
+            }
+            IpAddr::V6(addr) => {
+                let prefix = prefix(&self.ipv6_prefix, DEFAULT_IPV6_PREFIX, 128, state, object)?;
+                let mask = if prefix == 0 {
+                    0
+                } else {
+                    !0u128 << (128 - prefix)
+                };
+                Ipv6Addr::from(u128::from(addr) & mask).into()
+            }
+        };
+
+        Ok(anonymized.to_string().into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        use value::Kind;
+
+        let ipv4_def = self
+            .ipv4_prefix
+            .as_ref()
+            .map(|prefix| prefix.type_def(state).fallible_unless(Kind::Integer));
+        let ipv6_def = self
+            .ipv6_prefix
+            .as_ref()
+            .map(|prefix| prefix.type_def(state).fallible_unless(Kind::Integer));
+
+        self.value
+            .type_def(state)
+            .merge_optional(ipv4_def)
+            .merge_optional(ipv6_def)
+            .into_fallible(true)
+            .with_constraint(Kind::Bytes)
+    }
+}
+
+/// Evaluates the number of leading bits to keep, between 0 and `max`.
+fn prefix(
+    expr: &Option<Box<dyn Expression>>,
+    default: i64,
+    max: i64,
+    state: &mut state::Program,
+    object: &mut dyn Object,
+) -> Result<u32> {
+    let prefix = match expr {
+        Some(expr) => expr.execute(state, object)?.try_integer()?,
+        None => default,
+    };
+
+    if prefix < 0 || prefix > max {
+        return Err(format!("prefix must be between 0 and {}, got {}", max, prefix).into());
+    }
+
+    Ok(prefix as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    remap::test_type_def![value_string {
+        expr: |_| AnonymizeIpFn {
+            value: Literal::from("192.168.0.1").boxed(),
+            ipv4_prefix: None,
+            ipv6_prefix: None,
+        },
+        def: TypeDef {
+            kind: value::Kind::Bytes,
+            fallible: true,
+            ..Default::default()
+        },
+    }];
+
+    #[test]
+    fn anonymize_ip() {
+        let cases = vec![
+            (
+                btreemap! { "foo" => "192.168.10.23" },
+                Ok(Value::from("192.168.10.0")),
+                AnonymizeIpFn::new(Box::new(Path::from("foo")), None, None),
+            ),
+            (
+                btreemap! { "foo" => "2404:6800:4003:c02::64" },
+                Ok(Value::from("2404:6800:4003::")),
+                AnonymizeIpFn::new(Box::new(Path::from("foo")), None, None),
+            ),
+            (
+                btreemap! { "foo" => "192.168.10.23" },
+                Ok(Value::from("192.168.0.0")),
+                AnonymizeIpFn::new(
+                    Box::new(Path::from("foo")),
+                    Some(Box::new(Literal::from(16))),
+                    None,
+                ),
+            ),
+            (
+                btreemap! { "foo" => "192.168.10.23" },
+                Ok(Value::from("0.0.0.0")),
+                AnonymizeIpFn::new(
+                    Box::new(Path::from("foo")),
+                    Some(Box::new(Literal::from(0))),
+                    None,
+                ),
+            ),
+            (
+                btreemap! { "foo" => "2404:6800:4003:c02::64" },
+                Ok(Value::from("2404:6800:4003:c02::64")),
+                AnonymizeIpFn::new(
+                    Box::new(Path::from("foo")),
+                    None,
+                    Some(Box::new(Literal::from(128))),
+                ),
+            ),
+            (
+                btreemap! { "foo" => "192.168.10.23" },
+                Err("function call error: prefix must be between 0 and 32, got 33".into()),
+                AnonymizeIpFn::new(
+                    Box::new(Path::from("foo")),
+                    Some(Box::new(Literal::from(33))),
+                    None,
+                ),
+            ),
+            (
+                btreemap! { "foo" => "not an ip" },
+                Err(
+                    "function call error: unable to parse IP address: invalid IP address syntax"
+                        .into(),
+                ),
+                AnonymizeIpFn::new(Box::new(Path::from("foo")), None, None),
+            ),
+        ];
+
+        let mut state = state::Program::default();
+
+        for (object, exp, func) in cases {
+            let mut object = Value::Map(object);
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
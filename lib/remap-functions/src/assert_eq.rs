@@ -0,0 +1,197 @@
+use remap::prelude::*;
+use std::collections::BTreeSet;
+
+#[derive(Clone, Copy, Debug)]
+pub struct AssertEq;
+
+impl Function for AssertEq {
+    fn identifier(&self) -> &'static str {
+        "assert_eq"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "left",
+                accepts: |_| true,
+                required: true,
+            },
+            Parameter {
+                keyword: "right",
+                accepts: |_| true,
+                required: true,
+            },
+            Parameter {
+                keyword: "message",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let left = arguments.required("left")?.boxed();
+        let right = arguments.required("right")?.boxed();
+        let message = arguments.optional("message").map(Expr::boxed);
+
+        Ok(Box::new(AssertEqFn {
+            left,
+            right,
+            message,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AssertEqFn {
+    left: Box<dyn Expression>,
+    right: Box<dyn Expression>,
+    message: Option<Box<dyn Expression>>,
+}
+
+impl AssertEqFn {
+    #[cfg(test)]
+    fn new(
+        left: Box<dyn Expression>,
+        right: Box<dyn Expression>,
+        message: Option<Box<dyn Expression>>,
+    ) -> Self {
+        Self {
+            left,
+            right,
+            message,
+        }
+    }
+}
+
+impl Expression for AssertEqFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let left = self.left.execute(state, object)?;
+        let right = self.right.execute(state, object)?;
+        if left == right {
+            return Ok(Value::Null);
+        }
+
+        let message = match self.message.as_ref() {
+            Some(message) => message
+                .execute(state, object)?
+                .try_bytes_utf8_lossy()?
+                .into_owned(),
+            None => "left != right".to_string(),
+        };
+
+        let mut lines = Vec::new();
+        diff(".", Some(&left), Some(&right), &mut lines);
+        Err(Error::Assert(format!("{}\n{}", message, lines.join("\n"))))
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef {
+            fallible: true,
+            kind: value::Kind::Null,
+            ..Default::default()
+        }
+    }
+}
+
+/// Collects a line for every path at which `left` and `right` differ, down to
+/// the innermost differing values of nested maps and arrays.
+fn diff(path: &str, left: Option<&Value>, right: Option<&Value>, lines: &mut Vec<String>) {
+    // The root path is ".", its children are ".foo" rather than "..foo".
+    let prefix = path.trim_end_matches('.');
+
+    match (left, right) {
+        (Some(Value::Map(left)), Some(Value::Map(right))) => {
+            let keys = left.keys().chain(right.keys()).collect::<BTreeSet<_>>();
+            for key in keys {
+                let path = format!("{}.{}", prefix, key);
+                diff(&path, left.get(key), right.get(key), lines);
+            }
+        }
+        (Some(Value::Array(left)), Some(Value::Array(right))) => {
+            for index in 0..left.len().max(right.len()) {
+                let path = format!("{}[{}]", prefix, index);
+                diff(&path, left.get(index), right.get(index), lines);
+            }
+        }
+        (left, right) if left == right => {}
+        (left, right) => {
+            let show =
+                |value: Option<&Value>| value.map_or("<missing>".to_owned(), Value::to_string);
+            lines.push(format!("  {}: {} != {}", path, show(left), show(right)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    remap::test_type_def![static_def {
+        expr: |_| AssertEqFn::new(Box::new(Literal::from(1)), Box::new(Literal::from(1)), None),
+        def: TypeDef {
+            fallible: true,
+            kind: value::Kind::Null,
+            ..Default::default()
+        },
+    }];
+
+    #[test]
+    fn assert_eq() {
+        let cases = vec![
+            (
+                btreemap! {
+                    "got" => btreemap! {
+                        "status" => 200,
+                        "tags" => vec!["a", "b"],
+                    },
+                    "want" => btreemap! {
+                        "status" => 200,
+                        "tags" => vec!["a", "c", "d"],
+                        "user" => "ana",
+                    },
+                },
+                Err(r#"assertion failed: left != right
+  .tags[1]: "b" != "c"
+  .tags[2]: <missing> != "d"
+  .user: <missing> != "ana""#
+                    .to_string()),
+                AssertEqFn::new(
+                    Box::new(Path::from("got")),
+                    Box::new(Path::from("want")),
+                    None,
+                ),
+            ),
+            (
+                btreemap! { "got" => 1, "want" => "1" },
+                Err("assertion failed: Status must match\n  .: 1 != \"1\"".to_string()),
+                AssertEqFn::new(
+                    Box::new(Path::from("got")),
+                    Box::new(Path::from("want")),
+                    Some(Box::new(Literal::from("Status must match"))),
+                ),
+            ),
+            (
+                btreemap! { "got" => vec![1, 2], "want" => vec![1, 2] },
+                Ok(Value::Null),
+                AssertEqFn::new(
+                    Box::new(Path::from("got")),
+                    Box::new(Path::from("want")),
+                    None,
+                ),
+            ),
+        ];
+
+        let mut state = state::Program::default();
+
+        for (object, exp, func) in cases {
+            let mut object = Value::Map(object);
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Chunks;
+
+impl Function for Chunks {
+    fn identifier(&self) -> &'static str {
+        "chunks"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Array(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "chunk_size",
+                accepts: |v| matches!(v, Value::Integer(_)),
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+        let chunk_size = arguments.required("chunk_size")?.boxed();
+
+        Ok(Box::new(ChunksFn { value, chunk_size }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ChunksFn {
+    value: Box<dyn Expression>,
+    chunk_size: Box<dyn Expression>,
+}
+
+impl Expression for ChunksFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let value = self.value.execute(state, object)?.try_array()?;
+        let chunk_size = match self.chunk_size.execute(state, object)?.try_integer()? {
+            chunk_size if chunk_size <= 0 => return Err("chunk_size must be greater than 0".into()),
+            chunk_size => chunk_size as usize,
+        };
+
+        Ok(value
+            .chunks(chunk_size)
+            .map(|chunk| Value::from(chunk.to_vec()))
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        // A chunk size lower than 1 fails.
+        TypeDef {
+            fallible: true,
+            kind: value::Kind::Array,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        chunks => Chunks;
+
+        even {
+            args: func_args![value: array![1, 2, 3, 4], chunk_size: 2],
+            want: Ok(value!([[1, 2], [3, 4]])),
+        }
+
+        remainder {
+            args: func_args![value: array![1, 2, 3], chunk_size: 2],
+            want: Ok(value!([[1, 2], [3]])),
+        }
+
+        larger_than_array {
+            args: func_args![value: array![1, 2], chunk_size: 5],
+            want: Ok(value!([[1, 2]])),
+        }
+
+        zero_chunk_size {
+            args: func_args![value: array![1, 2], chunk_size: 0],
+            want: Err("function call error: chunk_size must be greater than 0"),
+        }
+    ];
+
+    remap::test_type_def![array {
+        expr: |_| ChunksFn {
+            value: Array::from(vec![1]).boxed(),
+            chunk_size: Literal::from(1).boxed(),
+        },
+        def: TypeDef {
+            fallible: true,
+            kind: value::Kind::Array,
+            ..Default::default()
+        },
+    }];
+}
@@ -0,0 +1,85 @@
+//! The AES ciphers shared by `encrypt` and `decrypt`, looked up by name.
+//!
+//! Unlike `encrypt_field`, the key and IV are given by the caller, and the
+//! ciphertext is returned as raw bytes, to be encoded as needed.
+
+use aes::Aes256;
+use aes_gcm::aead::{generic_array::GenericArray, Aead, NewAead};
+use aes_gcm::Aes256Gcm;
+use block_modes::{block_padding::Pkcs7, BlockMode, Cbc};
+use remap::prelude::*;
+
+type Aes256Cbc = Cbc<Aes256, Pkcs7>;
+
+const KEY_LEN: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Algorithm {
+    /// Authenticated, with a 12 bytes IV which must never be reused with the
+    /// same key.
+    Aes256Gcm,
+    /// Padded with PKCS#7, with a 16 bytes IV.
+    Aes256CbcPkcs7,
+}
+
+impl Algorithm {
+    pub(crate) fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "AES-256-GCM" => Ok(Algorithm::Aes256Gcm),
+            "AES-256-CBC-PKCS7" => Ok(Algorithm::Aes256CbcPkcs7),
+            _ => Err(format!("unsupported algorithm \"{}\"", name).into()),
+        }
+    }
+
+    fn iv_len(self) -> usize {
+        match self {
+            Algorithm::Aes256Gcm => 12,
+            Algorithm::Aes256CbcPkcs7 => 16,
+        }
+    }
+
+    fn check(self, key: &[u8], iv: &[u8]) -> Result<()> {
+        if key.len() != KEY_LEN {
+            return Err(format!("key must be {} bytes long, got {}", KEY_LEN, key.len()).into());
+        }
+        if iv.len() != self.iv_len() {
+            return Err(
+                format!("iv must be {} bytes long, got {}", self.iv_len(), iv.len()).into(),
+            );
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "encrypt")]
+    pub(crate) fn encrypt(self, plaintext: &[u8], key: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+        self.check(key, iv)?;
+
+        match self {
+            Algorithm::Aes256Gcm => Aes256Gcm::new(GenericArray::from_slice(key))
+                .encrypt(GenericArray::from_slice(iv), plaintext)
+                .map_err(|_| "unable to encrypt value".into()),
+            Algorithm::Aes256CbcPkcs7 => Ok(Aes256Cbc::new_var(key, iv)
+                .map_err(|_| "unable to encrypt value")?
+                .encrypt_vec(plaintext)),
+        }
+    }
+
+    #[cfg(feature = "decrypt")]
+    pub(crate) fn decrypt(self, ciphertext: &[u8], key: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+        self.check(key, iv)?;
+
+        let plaintext = match self {
+            Algorithm::Aes256Gcm => Aes256Gcm::new(GenericArray::from_slice(key))
+                .decrypt(GenericArray::from_slice(iv), ciphertext)
+                .ok(),
+            Algorithm::Aes256CbcPkcs7 => Aes256Cbc::new_var(key, iv)
+                .ok()
+                .and_then(|cipher| cipher.decrypt_vec(ciphertext).ok()),
+        };
+
+        plaintext.ok_or_else(|| {
+            "unable to decrypt value, wrong key or iv, or tampered ciphertext".into()
+        })
+    }
+}
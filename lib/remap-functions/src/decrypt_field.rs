@@ -0,0 +1,127 @@
+use crate::envelope::Keyring;
+use remap::prelude::*;
+
+/// Decrypts an envelope produced by `encrypt_field`, using the data key of
+/// its [`Keyring`] the envelope names.
+#[derive(Clone, Debug, Default)]
+pub struct DecryptField {
+    keyring: Keyring,
+}
+
+impl DecryptField {
+    pub fn new(keyring: Keyring) -> Self {
+        Self { keyring }
+    }
+}
+
+impl Function for DecryptField {
+    fn identifier(&self) -> &'static str {
+        "decrypt_field"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Map(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+
+        Ok(Box::new(DecryptFieldFn {
+            value,
+            keyring: self.keyring.clone(),
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DecryptFieldFn {
+    value: Box<dyn Expression>,
+    keyring: Keyring,
+}
+
+impl Expression for DecryptFieldFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let envelope = self.value.execute(state, object)?.try_map()?;
+
+        self.keyring.decrypt(&envelope).map(Into::into)
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .into_fallible(true) // malformed envelope, unknown or wrong data key
+            .with_constraint(value::Kind::Bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+    use std::collections::BTreeMap;
+
+    fn keyring(key: u8) -> Keyring {
+        let mut keys = BTreeMap::new();
+        keys.insert("primary".to_owned(), vec![key; 32]);
+        Keyring::new(keys).unwrap()
+    }
+
+    remap::test_type_def![value_map {
+        expr: |_| DecryptFieldFn {
+            value: Box::new(Path::from("foo")),
+            keyring: Keyring::default(),
+        },
+        def: TypeDef {
+            kind: value::Kind::Bytes,
+            fallible: true,
+            ..Default::default()
+        },
+    }];
+
+    #[test]
+    fn decrypt_field() {
+        let envelope = keyring(7).encrypt("primary", b"secret").unwrap();
+        let mut tampered = envelope.clone().try_map().unwrap();
+        tampered.insert("iv".to_owned(), base64::encode(&[0; 12]).into());
+        let mut unknown_alg = envelope.clone().try_map().unwrap();
+        unknown_alg.insert("alg".to_owned(), "ROT13".into());
+
+        let cases = vec![
+            (envelope.clone(), keyring(7), Ok(Value::from("secret"))),
+            (
+                envelope,
+                keyring(8),
+                Err("function call error: unable to decrypt envelope, wrong data key or tampered ciphertext".to_owned()),
+            ),
+            (
+                tampered.into(),
+                keyring(7),
+                Err("function call error: unable to decrypt envelope, wrong data key or tampered ciphertext".to_owned()),
+            ),
+            (
+                unknown_alg.into(),
+                keyring(7),
+                Err("function call error: unsupported envelope algorithm \"ROT13\"".to_owned()),
+            ),
+        ];
+
+        let mut state = state::Program::default();
+
+        for (envelope, keyring, exp) in cases {
+            let mut object = Value::Map(btreemap! { "foo" => envelope });
+            let func = DecryptFieldFn {
+                value: Box::new(Path::from("foo")),
+                keyring,
+            };
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeBase16;
+
+impl Function for EncodeBase16 {
+    fn identifier(&self) -> &'static str {
+        "encode_base16"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Bytes(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+
+        Ok(Box::new(EncodeBase16Fn { value }))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeBase16Fn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for EncodeBase16Fn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let value = self.value.execute(state, object)?.try_bytes()?;
+
+        Ok(hex::encode(value).into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .fallible_unless(value::Kind::Bytes)
+            .with_constraint(value::Kind::Bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use value::Kind;
+
+    test_function![
+        encode_base16 => EncodeBase16;
+
+        string {
+            args: func_args![value: "please encode me"],
+            want: Ok("706c6561736520656e636f6465206d65"),
+        }
+
+        raw_bytes {
+            args: func_args![value: &b"\x00\xff\x10"[..]],
+            want: Ok("00ff10"),
+        }
+
+        empty {
+            args: func_args![value: ""],
+            want: Ok(""),
+        }
+    ];
+
+    remap::test_type_def![
+        value_string {
+            expr: |_| EncodeBase16Fn {
+                value: Literal::from("foo").boxed(),
+            },
+            def: TypeDef { kind: Kind::Bytes, ..Default::default() },
+        }
+
+        value_non_string {
+            expr: |_| EncodeBase16Fn {
+                value: Literal::from(1).boxed(),
+            },
+            def: TypeDef { fallible: true, kind: Kind::Bytes, ..Default::default() },
+        }
+    ];
+}
@@ -0,0 +1,140 @@
+use crate::cipher::Algorithm;
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Encrypt;
+
+impl Function for Encrypt {
+    fn identifier(&self) -> &'static str {
+        "encrypt"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "plaintext",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "algorithm",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "iv",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let plaintext = arguments.required("plaintext")?.boxed();
+        let algorithm = arguments.required("algorithm")?.boxed();
+        let key = arguments.required("key")?.boxed();
+        let iv = arguments.required("iv")?.boxed();
+
+        Ok(Box::new(EncryptFn {
+            plaintext,
+            algorithm,
+            key,
+            iv,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncryptFn {
+    plaintext: Box<dyn Expression>,
+    algorithm: Box<dyn Expression>,
+    key: Box<dyn Expression>,
+    iv: Box<dyn Expression>,
+}
+
+impl Expression for EncryptFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let plaintext = self.plaintext.execute(state, object)?.try_bytes()?;
+        let algorithm = self.algorithm.execute(state, object)?;
+        let key = self.key.execute(state, object)?.try_bytes()?;
+        let iv = self.iv.execute(state, object)?.try_bytes()?;
+
+        Algorithm::from_name(&algorithm.try_bytes_utf8_lossy()?)?
+            .encrypt(&plaintext, &key, &iv)
+            .map(Into::into)
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.plaintext
+            .type_def(state)
+            .merge(self.algorithm.type_def(state))
+            .merge(self.key.type_def(state))
+            .merge(self.iv.type_def(state))
+            .into_fallible(true) // unsupported algorithm, or wrong key or iv length
+            .with_constraint(value::Kind::Bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        encrypt => Encrypt;
+
+        aes_256_gcm {
+            args: func_args![
+                plaintext: "secret",
+                algorithm: "AES-256-GCM",
+                key: "01234567890123456789012345678901",
+                iv: "012345678901"
+            ],
+            want: Ok(&b"\x03\xea\xab\x18\xc4\x81\xb4\x21\x32\x43\x51\xe9\x00\x74\x20\xb2\xcc\x7f\x8f\xcb\xbd\x93"[..]),
+        }
+
+        aes_256_cbc_pkcs7 {
+            args: func_args![
+                plaintext: "secret",
+                algorithm: "AES-256-CBC-PKCS7",
+                key: "01234567890123456789012345678901",
+                iv: "0123456789012345"
+            ],
+            want: Ok(&b"\x2c\xcb\xe6\xb0\x8e\xdc\xa5\xfe\x94\x99\x38\x60\xb7\xa5\x32\xf2"[..]),
+        }
+
+        unsupported_algorithm {
+            args: func_args![
+                plaintext: "secret",
+                algorithm: "ROT13",
+                key: "01234567890123456789012345678901",
+                iv: "012345678901"
+            ],
+            want: Err("function call error: unsupported algorithm \"ROT13\""),
+        }
+
+        invalid_iv_length {
+            args: func_args![
+                plaintext: "secret",
+                algorithm: "AES-256-GCM",
+                key: "01234567890123456789012345678901",
+                iv: "0123456789012345"
+            ],
+            want: Err("function call error: iv must be 12 bytes long, got 16"),
+        }
+
+        invalid_key_length {
+            args: func_args![
+                plaintext: "secret",
+                algorithm: "AES-256-CBC-PKCS7",
+                key: "0123456789012345",
+                iv: "0123456789012345"
+            ],
+            want: Err("function call error: key must be 32 bytes long, got 16"),
+        }
+    ];
+}
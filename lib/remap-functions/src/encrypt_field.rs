@@ -0,0 +1,157 @@
+use crate::envelope::{self, Keyring};
+use remap::prelude::*;
+
+/// Encrypts a value into an envelope, using a data key of its [`Keyring`].
+#[derive(Clone, Debug, Default)]
+pub struct EncryptField {
+    keyring: Keyring,
+}
+
+impl EncryptField {
+    pub fn new(keyring: Keyring) -> Self {
+        Self { keyring }
+    }
+}
+
+impl Function for EncryptField {
+    fn identifier(&self) -> &'static str {
+        "encrypt_field"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "key_id",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+        let key_id = arguments.required("key_id")?.boxed();
+
+        Ok(Box::new(EncryptFieldFn {
+            value,
+            key_id,
+            keyring: self.keyring.clone(),
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncryptFieldFn {
+    value: Box<dyn Expression>,
+    key_id: Box<dyn Expression>,
+    keyring: Keyring,
+}
+
+impl Expression for EncryptFieldFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let value = self.value.execute(state, object)?.try_bytes()?;
+        let key_id = self.key_id.execute(state, object)?;
+
+        self.keyring
+            .encrypt(&key_id.try_bytes_utf8_lossy()?, &value)
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .merge(self.key_id.type_def(state))
+            .into_fallible(true) // unknown data key
+            .with_inner_type(envelope::inner_type_def())
+            .with_constraint(value::Kind::Map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+    use std::collections::BTreeMap;
+
+    fn keyring() -> Keyring {
+        let mut keys = BTreeMap::new();
+        keys.insert("primary".to_owned(), vec![7; 32]);
+        Keyring::new(keys).unwrap()
+    }
+
+    remap::test_type_def![value_string {
+        expr: |_| EncryptFieldFn {
+            value: Literal::from("secret").boxed(),
+            key_id: Literal::from("primary").boxed(),
+            keyring: Keyring::default(),
+        },
+        def: TypeDef {
+            kind: value::Kind::Map,
+            fallible: true,
+            inner_type_def: envelope::inner_type_def(),
+        },
+    }];
+
+    #[test]
+    fn encrypt_field() {
+        let envelope = EncryptFieldFn {
+            value: Box::new(Path::from("foo")),
+            key_id: Literal::from("primary").boxed(),
+            keyring: keyring(),
+        }
+        .execute(
+            &mut state::Program::default(),
+            &mut Value::Map(btreemap! { "foo" => "secret" }),
+        )
+        .unwrap()
+        .try_map()
+        .unwrap();
+
+        assert_eq!(envelope["alg"], Value::from(envelope::ALGORITHM));
+        assert_eq!(envelope["key_id"], Value::from("primary"));
+        assert_eq!(
+            base64::decode(envelope["iv"].try_bytes_utf8_lossy().unwrap().as_ref())
+                .unwrap()
+                .len(),
+            12
+        );
+        assert_ne!(
+            envelope["ciphertext"],
+            Value::from(base64::encode("secret"))
+        );
+    }
+
+    #[test]
+    fn unknown_key_id() {
+        let envelope = EncryptFieldFn {
+            value: Literal::from("secret").boxed(),
+            key_id: Literal::from("missing").boxed(),
+            keyring: keyring(),
+        }
+        .execute(
+            &mut state::Program::default(),
+            &mut Value::Map(BTreeMap::new()),
+        )
+        .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+        assert_eq!(
+            envelope,
+            Err("function call error: unknown data key \"missing\"".to_owned())
+        );
+    }
+
+    #[test]
+    fn invalid_key_length() {
+        let mut keys = BTreeMap::new();
+        keys.insert("short".to_owned(), vec![7; 16]);
+
+        assert_eq!(
+            Keyring::new(keys).unwrap_err(),
+            "data key \"short\" must be 32 bytes long, got 16"
+        );
+    }
+}
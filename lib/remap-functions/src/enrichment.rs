@@ -0,0 +1,216 @@
+//! Enrichment tables shared by `get_enrichment_table_record` and
+//! `find_enrichment_table_records`.
+//!
+//! A table is a list of records with string fields, loaded once at startup.
+//! Every column is indexed, so that looking records up by the value of any of
+//! their columns doesn't scan the table.
+
+use remap::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+
+/// The enrichment tables available to the enrichment functions, by name.
+#[derive(Debug, Clone, Default)]
+pub struct EnrichmentTables {
+    tables: BTreeMap<String, Arc<Table>>,
+}
+
+impl EnrichmentTables {
+    pub fn new(tables: BTreeMap<String, Table>) -> Self {
+        Self {
+            tables: tables
+                .into_iter()
+                .map(|(name, table)| (name, Arc::new(table)))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Result<Arc<Table>> {
+        self.tables
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("unknown enrichment table \"{}\"", name).into())
+    }
+}
+
+/// The records of a table, with an index of the rows by value for each column.
+pub struct Table {
+    records: Vec<BTreeMap<String, Value>>,
+    indexes: HashMap<String, HashMap<String, Vec<usize>>>,
+}
+
+impl Table {
+    /// Builds the table of `rows`, each of them holding one field per column
+    /// of `columns`.
+    pub fn new(columns: Vec<String>, rows: Vec<Vec<String>>) -> std::result::Result<Self, String> {
+        let mut indexes: HashMap<String, HashMap<String, Vec<usize>>> = columns
+            .iter()
+            .map(|column| (column.clone(), HashMap::new()))
+            .collect();
+        if indexes.len() != columns.len() {
+            return Err("duplicate column names".to_owned());
+        }
+
+        let mut records = Vec::with_capacity(rows.len());
+        for (row, fields) in rows.into_iter().enumerate() {
+            if fields.len() != columns.len() {
+                return Err(format!(
+                    "row {} has {} fields, expected {}",
+                    row + 1,
+                    fields.len(),
+                    columns.len()
+                ));
+            }
+
+            let mut record = BTreeMap::new();
+            for (column, field) in columns.iter().zip(fields) {
+                indexes
+                    .get_mut(column)
+                    .expect("every column is indexed")
+                    .entry(field.clone())
+                    .or_default()
+                    .push(row);
+                record.insert(column.clone(), Value::from(field));
+            }
+            records.push(record);
+        }
+
+        Ok(Self { records, indexes })
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// The records whose fields equal the values of `condition`, by column,
+    /// in the order of the table.
+    pub(crate) fn find(
+        &self,
+        condition: &BTreeMap<String, Value>,
+    ) -> Result<Vec<&BTreeMap<String, Value>>> {
+        if condition.is_empty() {
+            return Err("condition must not be empty".into());
+        }
+
+        let mut condition = condition
+            .iter()
+            .map(|(column, value)| -> Result<_> {
+                let index = self
+                    .indexes
+                    .get(column)
+                    .ok_or_else(|| format!("unknown column \"{}\"", column))?;
+                let value = match value {
+                    Value::Bytes(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+                    Value::Integer(integer) => integer.to_string(),
+                    _ => {
+                        return Err(format!(
+                            "value of column \"{}\" must be a string or an integer",
+                            column
+                        )
+                        .into())
+                    }
+                };
+                let rows = index.get(&value).map(Vec::as_slice).unwrap_or(&[]);
+                Ok((column.as_str(), value, rows))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Starting from the column matching the fewest rows keeps the lookup
+        // cheap, whatever the size of the table.
+        condition.sort_by_key(|(_, _, rows)| rows.len());
+        let candidates = condition[0].2;
+
+        Ok(candidates
+            .iter()
+            .map(|&row| &self.records[row])
+            .filter(|record| {
+                condition[1..]
+                    .iter()
+                    .all(|(column, value, _)| record[*column] == Value::from(value.as_str()))
+            })
+            .collect())
+    }
+}
+
+impl fmt::Debug for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Table")
+            .field("records", &self.records.len())
+            .field("columns", &self.indexes.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn test_tables() -> EnrichmentTables {
+    let columns = vec!["code".to_owned(), "region".to_owned(), "name".to_owned()];
+    let rows = vec![
+        vec!["1".to_owned(), "eu".to_owned(), "Paris".to_owned()],
+        vec!["2".to_owned(), "us".to_owned(), "Boston".to_owned()],
+        vec!["3".to_owned(), "eu".to_owned(), "Berlin".to_owned()],
+    ];
+
+    let mut tables = BTreeMap::new();
+    tables.insert("cities".to_owned(), Table::new(columns, rows).unwrap());
+    EnrichmentTables::new(tables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    #[test]
+    fn finds_records() {
+        let table = test_tables().get("cities").unwrap();
+
+        let found = table
+            .find(&btreemap! { "region" => "eu" })
+            .unwrap()
+            .into_iter()
+            .map(|record| record["name"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec![Value::from("Paris"), Value::from("Berlin")]);
+
+        let found = table
+            .find(&btreemap! { "region" => "eu", "code" => 3 })
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0]["name"], Value::from("Berlin"));
+
+        assert!(table
+            .find(&btreemap! { "region" => "ap" })
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_conditions() {
+        let table = test_tables().get("cities").unwrap();
+
+        assert_eq!(
+            table.find(&btreemap! { "country" => "fr" }).unwrap_err(),
+            Error::from("unknown column \"country\"")
+        );
+        assert_eq!(
+            table.find(&BTreeMap::new()).unwrap_err(),
+            Error::from("condition must not be empty")
+        );
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        let error = Table::new(
+            vec!["code".to_owned(), "name".to_owned()],
+            vec![vec!["1".to_owned()]],
+        )
+        .unwrap_err();
+
+        assert_eq!(error, "row 1 has 1 fields, expected 2");
+    }
+}
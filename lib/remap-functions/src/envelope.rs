@@ -0,0 +1,112 @@
+//! Envelope encryption shared by `encrypt_field` and `decrypt_field`.
+//!
+//! Values are encrypted with AES-256-GCM under a data key looked up by id in a
+//! [`Keyring`]. The result is an envelope map carrying everything needed to
+//! decrypt it again, except for the data key itself:
+//!
+//! ```text
+//! { "alg": "AES-256-GCM", "key_id": "...", "iv": "<base64>", "ciphertext": "<base64>" }
+//! ```
+
+use aes_gcm::aead::{generic_array::GenericArray, Aead, NewAead};
+use aes_gcm::Aes256Gcm;
+use remap::prelude::*;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+pub(crate) const ALGORITHM: &str = "AES-256-GCM";
+
+const KEY_LEN: usize = 32;
+const IV_LEN: usize = 12;
+
+/// The data keys available to the encryption functions, by id.
+#[derive(Debug, Clone, Default)]
+pub struct Keyring {
+    keys: Arc<BTreeMap<String, Vec<u8>>>,
+}
+
+impl Keyring {
+    pub fn new(keys: BTreeMap<String, Vec<u8>>) -> std::result::Result<Self, String> {
+        for (key_id, key) in &keys {
+            if key.len() != KEY_LEN {
+                return Err(format!(
+                    "data key \"{}\" must be {} bytes long, got {}",
+                    key_id,
+                    KEY_LEN,
+                    key.len()
+                ));
+            }
+        }
+
+        Ok(Self {
+            keys: Arc::new(keys),
+        })
+    }
+
+    fn cipher(&self, key_id: &str) -> Result<Aes256Gcm> {
+        let key = self
+            .keys
+            .get(key_id)
+            .ok_or_else(|| format!("unknown data key \"{}\"", key_id))?;
+
+        Ok(Aes256Gcm::new(GenericArray::from_slice(key)))
+    }
+
+    #[cfg(feature = "encrypt_field")]
+    pub(crate) fn encrypt(&self, key_id: &str, plaintext: &[u8]) -> Result<Value> {
+        let iv: [u8; IV_LEN] = rand::random();
+        let ciphertext = self
+            .cipher(key_id)?
+            .encrypt(GenericArray::from_slice(&iv), plaintext)
+            .map_err(|_| "unable to encrypt value")?;
+
+        let mut envelope = BTreeMap::new();
+        envelope.insert("alg".to_owned(), ALGORITHM.into());
+        envelope.insert("key_id".to_owned(), key_id.into());
+        envelope.insert("iv".to_owned(), base64::encode(&iv).into());
+        envelope.insert("ciphertext".to_owned(), base64::encode(&ciphertext).into());
+
+        Ok(envelope.into())
+    }
+
+    #[cfg(feature = "decrypt_field")]
+    pub(crate) fn decrypt(&self, envelope: &BTreeMap<String, Value>) -> Result<Vec<u8>> {
+        let field = |name: &str| -> Result<String> {
+            let value = envelope
+                .get(name)
+                .ok_or_else(|| format!("envelope is missing the \"{}\" field", name))?;
+            Ok(value.try_bytes_utf8_lossy()?.into_owned())
+        };
+
+        let alg = field("alg")?;
+        if alg != ALGORITHM {
+            return Err(format!("unsupported envelope algorithm \"{}\"", alg).into());
+        }
+        let iv = base64::decode(field("iv")?).map_err(|e| format!("invalid envelope iv: {}", e))?;
+        if iv.len() != IV_LEN {
+            return Err(format!(
+                "envelope iv must be {} bytes long, got {}",
+                IV_LEN,
+                iv.len()
+            )
+            .into());
+        }
+        let ciphertext = base64::decode(field("ciphertext")?)
+            .map_err(|e| format!("invalid envelope ciphertext: {}", e))?;
+
+        self.cipher(&field("key_id")?)?
+            .decrypt(GenericArray::from_slice(&iv), ciphertext.as_ref())
+            .map_err(|_| "unable to decrypt envelope, wrong data key or tampered ciphertext".into())
+    }
+}
+
+/// The type defs of the fields of an envelope.
+#[cfg(feature = "encrypt_field")]
+pub(crate) fn inner_type_def() -> Option<InnerTypeDef> {
+    Some(inner_type_def! ({
+        "alg": value::Kind::Bytes,
+        "key_id": value::Kind::Bytes,
+        "iv": value::Kind::Bytes,
+        "ciphertext": value::Kind::Bytes,
+    }))
+}
@@ -0,0 +1,112 @@
+use crate::enrichment::{EnrichmentTables, Table};
+use remap::prelude::*;
+use std::sync::Arc;
+use value::Kind;
+
+/// Looks up all the records of an enrichment table matching a condition.
+#[derive(Clone, Debug, Default)]
+pub struct FindEnrichmentTableRecords {
+    tables: EnrichmentTables,
+}
+
+impl FindEnrichmentTableRecords {
+    pub fn new(tables: EnrichmentTables) -> Self {
+        Self { tables }
+    }
+}
+
+impl Function for FindEnrichmentTableRecords {
+    fn identifier(&self) -> &'static str {
+        "find_enrichment_table_records"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "table",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "condition",
+                accepts: |v| matches!(v, Value::Map(_)),
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let table = arguments
+            .required_literal("table")?
+            .as_value()
+            .clone()
+            .try_bytes_utf8_lossy()?
+            .into_owned();
+        let table = self.tables.get(&table)?;
+        let condition = arguments.required("condition")?.boxed();
+
+        Ok(Box::new(FindEnrichmentTableRecordsFn { table, condition }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FindEnrichmentTableRecordsFn {
+    table: Arc<Table>,
+    condition: Box<dyn Expression>,
+}
+
+impl Expression for FindEnrichmentTableRecordsFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let condition = self.condition.execute(state, object)?.try_map()?;
+
+        Ok(self
+            .table
+            .find(&condition)?
+            .into_iter()
+            .map(|record| Value::from(record.clone()))
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.condition
+            .type_def(state)
+            .into_fallible(true) // unknown column
+            .with_inner_type(Some(inner_type_def!([Kind::Map])))
+            .with_constraint(Kind::Array)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enrichment::test_tables;
+    use shared::btreemap;
+
+    fn find(condition: Value) -> Result<Value> {
+        FindEnrichmentTableRecordsFn {
+            table: test_tables().get("cities").unwrap(),
+            condition: Literal::from(condition).boxed(),
+        }
+        .execute(&mut state::Program::default(), &mut Value::Null)
+    }
+
+    #[test]
+    fn find_enrichment_table_records() {
+        assert_eq!(
+            find(Value::from(btreemap! { "region" => "eu" })),
+            Ok(Value::from(vec![
+                Value::from(btreemap! { "code" => "1", "region" => "eu", "name" => "Paris" }),
+                Value::from(btreemap! { "code" => "3", "region" => "eu", "name" => "Berlin" }),
+            ]))
+        );
+        assert_eq!(
+            find(Value::from(btreemap! { "region" => "ap" })),
+            Ok(Value::from(Vec::<Value>::new()))
+        );
+        assert_eq!(
+            find(Value::from(btreemap! { "region" => true })),
+            Err("value of column \"region\" must be a string or an integer".into())
+        );
+    }
+}
@@ -10,32 +10,49 @@ impl Function for Flatten {
     }
 
     fn parameters(&self) -> &'static [Parameter] {
-        &[Parameter {
-            keyword: "value",
-            accepts: |v| matches!(v, Value::Array(_) | Value::Map(_)),
-            required: true,
-        }]
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Array(_) | Value::Map(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "depth",
+                accepts: |v| matches!(v, Value::Integer(_)),
+                required: false,
+            },
+        ]
     }
 
     fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
         let value = arguments.required("value")?.boxed();
-        Ok(Box::new(FlattenFn { value }))
+        let depth = arguments.optional("depth").map(Expr::boxed);
+        Ok(Box::new(FlattenFn { value, depth }))
     }
 }
 
 #[derive(Debug, Clone)]
 struct FlattenFn {
     value: Box<dyn Expression>,
+    depth: Option<Box<dyn Expression>>,
 }
 
 impl Expression for FlattenFn {
     fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let depth = match &self.depth {
+            Some(depth) => match depth.execute(state, object)?.try_integer()? {
+                depth if depth < 0 => return Err("depth must not be negative".into()),
+                depth => Some(depth as usize),
+            },
+            None => None,
+        };
+
         match self.value.execute(state, object)? {
             Value::Array(arr) => Ok(Value::Array(
-                ArrayFlatten::new(arr.iter()).cloned().collect(),
+                ArrayFlatten::new(arr.iter(), depth).cloned().collect(),
             )),
             Value::Map(map) => Ok(Value::Map(
-                MapFlatten::new(map.iter())
+                MapFlatten::new(map.iter(), depth)
                     .map(|(k, v)| (k, v.clone()))
                     .collect(),
             )),
@@ -44,33 +61,44 @@ impl Expression for FlattenFn {
     }
 
     fn type_def(&self, state: &state::Compiler) -> TypeDef {
-        self.value
+        let type_def = self
+            .value
             .type_def(state)
-            .fallible_unless(value::Kind::Map | value::Kind::Array)
+            .fallible_unless(value::Kind::Map | value::Kind::Array);
+        // A negative depth fails.
+        type_def.into_fallible(self.depth.is_some())
     }
 }
 
 /// An iterator to walk over maps allowing us to flatten nested maps to a single level.
+/// The maps nested deeper than `depth` levels, if any, are kept as they are.
 struct MapFlatten<'a> {
     values: btree_map::Iter<'a, String, Value>,
     inner: Option<Box<MapFlatten<'a>>>,
     parent: Option<String>,
+    depth: Option<usize>,
 }
 
 impl<'a> MapFlatten<'a> {
-    fn new(values: btree_map::Iter<'a, String, Value>) -> Self {
+    fn new(values: btree_map::Iter<'a, String, Value>, depth: Option<usize>) -> Self {
         Self {
             values,
             inner: None,
             parent: None,
+            depth,
         }
     }
 
-    fn new_from_parent(parent: String, values: btree_map::Iter<'a, String, Value>) -> Self {
+    fn new_from_parent(
+        parent: String,
+        values: btree_map::Iter<'a, String, Value>,
+        depth: Option<usize>,
+    ) -> Self {
         Self {
             values,
             inner: None,
             parent: Some(parent),
+            depth,
         }
     }
 
@@ -97,10 +125,11 @@ impl<'a> std::iter::Iterator for MapFlatten<'a> {
 
         let next = self.values.next();
         match next {
-            Some((key, Value::Map(value))) => {
+            Some((key, Value::Map(value))) if self.depth != Some(0) => {
                 self.inner = Some(Box::new(MapFlatten::new_from_parent(
                     self.new_key(key),
                     value.iter(),
+                    self.depth.map(|depth| depth - 1),
                 )));
                 self.next()
             }
@@ -111,17 +140,19 @@ impl<'a> std::iter::Iterator for MapFlatten<'a> {
 }
 
 /// Create an iterator that can walk a tree of Array values.
-/// This can be used to flatten the array.
+/// This can be used to flatten the array, down to `depth` levels if set.
 struct ArrayFlatten<'a> {
     values: std::slice::Iter<'a, Value>,
     inner: Option<Box<ArrayFlatten<'a>>>,
+    depth: Option<usize>,
 }
 
 impl<'a> ArrayFlatten<'a> {
-    fn new(values: std::slice::Iter<'a, Value>) -> Self {
+    fn new(values: std::slice::Iter<'a, Value>, depth: Option<usize>) -> Self {
         ArrayFlatten {
             values,
             inner: None,
+            depth,
         }
     }
 }
@@ -145,9 +176,12 @@ impl<'a> std::iter::Iterator for ArrayFlatten<'a> {
         // Then iterate over our values.
         let next = self.values.next();
         match next {
-            Some(Value::Array(next)) => {
+            Some(Value::Array(next)) if self.depth != Some(0) => {
                 // Create a new iterator for this child list.
-                self.inner = Some(Box::new(ArrayFlatten::new(next.iter())));
+                self.inner = Some(Box::new(ArrayFlatten::new(
+                    next.iter(),
+                    self.depth.map(|depth| depth - 1),
+                )));
                 self.next()
             }
             _ => next,
@@ -245,6 +279,26 @@ mod test {
             ])),
         }
 
+        array_with_depth {
+            args: func_args![value: value!([42, [43, [44, [45]]]]), depth: 2],
+            want: Ok(value!([42, 43, 44, [45]])),
+        }
+
+        map_with_depth {
+            args: func_args![value: value!({parent: {child: {grandchild: 1}}, key: "val"}), depth: 1],
+            want: Ok(value!({"parent.child": {grandchild: 1}, key: "val"})),
+        }
+
+        zero_depth {
+            args: func_args![value: value!([42, [43]]), depth: 0],
+            want: Ok(value!([42, [43]])),
+        }
+
+        negative_depth {
+            args: func_args![value: value!([42, [43]]), depth: -1],
+            want: Err("function call error: depth must not be negative"),
+        }
+
         triple_nested_map {
             args: func_args![value: value!({
                 parent1: {
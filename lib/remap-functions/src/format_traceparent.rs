@@ -0,0 +1,124 @@
+use crate::util;
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct FormatTraceparent;
+
+impl Function for FormatTraceparent {
+    fn identifier(&self) -> &'static str {
+        "format_traceparent"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "trace_id",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "span_id",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "sampled",
+                accepts: |v| matches!(v, Value::Boolean(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let trace_id = arguments.required("trace_id")?.boxed();
+        let span_id = arguments.required("span_id")?.boxed();
+        let sampled = arguments.optional("sampled").map(Expr::boxed);
+
+        Ok(Box::new(FormatTraceparentFn {
+            trace_id,
+            span_id,
+            sampled,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FormatTraceparentFn {
+    trace_id: Box<dyn Expression>,
+    span_id: Box<dyn Expression>,
+    sampled: Option<Box<dyn Expression>>,
+}
+
+impl Expression for FormatTraceparentFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let trace_id = self.trace_id.execute(state, object)?;
+        let trace_id = util::normalize_trace_id(&trace_id.try_bytes_utf8_lossy()?)
+            .ok_or("trace_id must be 16 or 32 hex characters and not all zeros")?;
+
+        let span_id = self.span_id.execute(state, object)?;
+        let span_id = util::normalize_span_id(&span_id.try_bytes_utf8_lossy()?)
+            .ok_or("span_id must be 16 hex characters and not all zeros")?;
+
+        let sampled = match &self.sampled {
+            Some(sampled) => sampled.execute(state, object)?.try_boolean()?,
+            None => true,
+        };
+
+        Ok(format!("00-{}-{}-{:02x}", trace_id, span_id, sampled as u8).into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.trace_id
+            .type_def(state)
+            .merge(self.span_id.type_def(state))
+            .merge_optional(self.sampled.as_ref().map(|sampled| {
+                sampled
+                    .type_def(state)
+                    .fallible_unless(value::Kind::Boolean)
+            }))
+            .into_fallible(true) // invalid ids
+            .with_constraint(value::Kind::Bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    remap::test_type_def![value_string {
+        expr: |_| FormatTraceparentFn {
+            trace_id: Literal::from("foo").boxed(),
+            span_id: Literal::from("bar").boxed(),
+            sampled: None,
+        },
+        def: TypeDef {
+            fallible: true,
+            kind: value::Kind::Bytes,
+            ..Default::default()
+        },
+    }];
+
+    test_function![
+        format_traceparent => FormatTraceparent;
+
+        default_sampled {
+            args: func_args![trace_id: "0af7651916cd43dd8448eb211c80319c", span_id: "b7ad6b7169203331"],
+            want: Ok("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"),
+        }
+
+        not_sampled {
+            args: func_args![trace_id: "0af7651916cd43dd8448eb211c80319c", span_id: "b7ad6b7169203331", sampled: false],
+            want: Ok("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-00"),
+        }
+
+        short_trace_id {
+            args: func_args![trace_id: "8448EB211C80319C", span_id: "B7AD6B7169203331"],
+            want: Ok("00-00000000000000008448eb211c80319c-b7ad6b7169203331-01"),
+        }
+
+        invalid_span_id {
+            args: func_args![trace_id: "0af7651916cd43dd8448eb211c80319c", span_id: "0000000000000000"],
+            want: Err("function call error: span_id must be 16 hex characters and not all zeros"),
+        }
+    ];
+}
@@ -0,0 +1,212 @@
+use chrono::{TimeZone, Utc};
+use remap::prelude::*;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug)]
+pub struct FromUnixTimestamp;
+
+impl Function for FromUnixTimestamp {
+    fn identifier(&self) -> &'static str {
+        "from_unix_timestamp"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Integer(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "unit",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+
+        let unit = arguments
+            .optional_enum("unit", &Unit::all_str())?
+            .map(|s| Unit::from_str(&s).expect("validated enum"))
+            .unwrap_or_default();
+
+        Ok(Box::new(FromUnixTimestampFn { value, unit }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    Nanoseconds,
+}
+
+impl Unit {
+    fn all_str() -> Vec<&'static str> {
+        use Unit::*;
+
+        vec![Seconds, Milliseconds, Microseconds, Nanoseconds]
+            .into_iter()
+            .map(|u| u.as_str())
+            .collect::<Vec<_>>()
+    }
+
+    const fn as_str(self) -> &'static str {
+        use Unit::*;
+
+        match self {
+            Seconds => "seconds",
+            Milliseconds => "milliseconds",
+            Microseconds => "microseconds",
+            Nanoseconds => "nanoseconds",
+        }
+    }
+}
+
+impl Default for Unit {
+    fn default() -> Self {
+        Unit::Seconds
+    }
+}
+
+impl FromStr for Unit {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        use Unit::*;
+
+        match s {
+            "seconds" => Ok(Seconds),
+            "milliseconds" => Ok(Milliseconds),
+            "microseconds" => Ok(Microseconds),
+            "nanoseconds" => Ok(Nanoseconds),
+            _ => Err("unit not recognized"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FromUnixTimestampFn {
+    value: Box<dyn Expression>,
+    unit: Unit,
+}
+
+impl FromUnixTimestampFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>, unit: Unit) -> Self {
+        Self { value, unit }
+    }
+}
+
+impl Expression for FromUnixTimestampFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let time = self.value.execute(state, object)?.try_integer()?;
+
+        let (secs, nanos) = match self.unit {
+            Unit::Seconds => (time, 0),
+            Unit::Milliseconds => (time.div_euclid(1_000), time.rem_euclid(1_000) * 1_000_000),
+            Unit::Microseconds => (
+                time.div_euclid(1_000_000),
+                time.rem_euclid(1_000_000) * 1_000,
+            ),
+            Unit::Nanoseconds => (
+                time.div_euclid(1_000_000_000),
+                time.rem_euclid(1_000_000_000),
+            ),
+        };
+
+        Utc.timestamp_opt(secs, nanos as u32)
+            .single()
+            .map(Into::into)
+            .ok_or_else(|| "timestamp out of range".into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .into_fallible(true)
+            .with_constraint(value::Kind::Timestamp)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use shared::btreemap;
+    use value::Kind;
+
+    test_type_def![integer_fallible {
+        expr: |_| FromUnixTimestampFn {
+            value: lit!(1609459200).boxed(),
+            unit: Unit::Seconds,
+        },
+        def: TypeDef {
+            fallible: true,
+            kind: Kind::Timestamp,
+            ..Default::default()
+        },
+    }];
+
+    #[test]
+    fn from_unix_timestamp() {
+        let cases = vec![
+            (
+                btreemap! {},
+                Ok(chrono::Utc.ymd(2021, 1, 1).and_hms_milli(0, 0, 0, 0).into()),
+                FromUnixTimestampFn::new(lit!(1609459200).boxed(), Unit::Seconds),
+            ),
+            (
+                btreemap! {},
+                Ok(chrono::Utc
+                    .ymd(2021, 1, 1)
+                    .and_hms_milli(0, 0, 0, 123)
+                    .into()),
+                FromUnixTimestampFn::new(lit!(1609459200123i64).boxed(), Unit::Milliseconds),
+            ),
+            (
+                btreemap! {},
+                Ok(chrono::Utc
+                    .ymd(2021, 1, 1)
+                    .and_hms_micro(0, 0, 0, 123456)
+                    .into()),
+                FromUnixTimestampFn::new(lit!(1609459200123456i64).boxed(), Unit::Microseconds),
+            ),
+            (
+                btreemap! {},
+                Ok(chrono::Utc
+                    .ymd(2021, 1, 1)
+                    .and_hms_nano(0, 0, 0, 123456789)
+                    .into()),
+                FromUnixTimestampFn::new(lit!(1609459200123456789i64).boxed(), Unit::Nanoseconds),
+            ),
+            (
+                btreemap! {},
+                Ok(chrono::Utc
+                    .ymd(1969, 12, 31)
+                    .and_hms_micro(23, 59, 59, 999999)
+                    .into()),
+                FromUnixTimestampFn::new(Literal::from(-1i64).boxed(), Unit::Microseconds),
+            ),
+            (
+                btreemap! {},
+                Err("function call error: timestamp out of range".to_owned()),
+                FromUnixTimestampFn::new(Literal::from(i64::MAX).boxed(), Unit::Seconds),
+            ),
+        ];
+
+        let mut state = state::Program::default();
+
+        for (object, exp, func) in cases {
+            let mut object: Value = object.into();
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
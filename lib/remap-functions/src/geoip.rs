@@ -0,0 +1,182 @@
+//! MaxMind databases shared by `get_geoip` and `get_asn`.
+//!
+//! A database file is loaded once, however many programs look addresses up in
+//! it. The file is checked for changes at most every `RELOAD_INTERVAL`, and
+//! loaded again when it was replaced, so that updating the databases doesn't
+//! require reloading Vector.
+
+use lazy_static::lazy_static;
+use maxminddb::Reader;
+use remap::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant, SystemTime};
+
+const RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+// The types of the databases holding ASN records. The other databases are
+// expected to hold City records.
+const ASN_DATABASE_TYPES: &[&str] = &["GeoLite2-ASN", "GeoIP2-ISP"];
+
+lazy_static! {
+    static ref DATABASES: Mutex<HashMap<PathBuf, Weak<Database>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Kind {
+    City,
+    Asn,
+}
+
+pub(crate) struct Database {
+    path: PathBuf,
+    state: Mutex<State>,
+}
+
+struct State {
+    reader: Arc<Reader<Vec<u8>>>,
+    modified: Option<SystemTime>,
+    checked: Instant,
+}
+
+impl Database {
+    /// Opens the database at `path`, sharing it with the programs which
+    /// already opened it. Fails unless it holds records of `kind`.
+    pub(crate) fn open(path: &str, kind: Kind) -> Result<Arc<Self>> {
+        let path = PathBuf::from(path);
+        let mut databases = DATABASES.lock().expect("poisoned lock");
+
+        let database = match databases.get(&path).and_then(Weak::upgrade) {
+            Some(database) => database,
+            None => {
+                let (reader, modified) = load(&path)?;
+                let database = Arc::new(Self {
+                    path: path.clone(),
+                    state: Mutex::new(State {
+                        reader: Arc::new(reader),
+                        modified,
+                        checked: Instant::now(),
+                    }),
+                });
+                databases.retain(|_, database| database.strong_count() > 0);
+                databases.insert(path, Arc::downgrade(&database));
+                database
+            }
+        };
+
+        let database_type = database.reader().metadata.database_type.clone();
+        let actual = if ASN_DATABASE_TYPES.contains(&database_type.as_str()) {
+            Kind::Asn
+        } else {
+            Kind::City
+        };
+        if actual != kind {
+            return Err(format!(
+                "{} is a {} database, which doesn't hold {} records",
+                database.path.display(),
+                database_type,
+                match kind {
+                    Kind::City => "city",
+                    Kind::Asn => "ASN",
+                }
+            )
+            .into());
+        }
+
+        Ok(database)
+    }
+
+    /// The current reader of the database, loaded again first if the file
+    /// changed since the last check.
+    pub(crate) fn reader(&self) -> Arc<Reader<Vec<u8>>> {
+        let mut state = self.state.lock().expect("poisoned lock");
+
+        if state.checked.elapsed() >= RELOAD_INTERVAL {
+            state.checked = Instant::now();
+            let modified = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+            if modified != state.modified {
+                match load(&self.path) {
+                    Ok((reader, modified)) => {
+                        tracing::info!(message = "Reloaded GeoIP database.", path = ?self.path);
+                        state.reader = Arc::new(reader);
+                        state.modified = modified;
+                    }
+                    // The previous version keeps being used until the file
+                    // can be loaded.
+                    Err(error) => tracing::warn!(
+                        message = "Failed to reload GeoIP database.",
+                        path = ?self.path,
+                        %error,
+                        internal_log_rate_secs = 30,
+                    ),
+                }
+            }
+        }
+
+        Arc::clone(&state.reader)
+    }
+}
+
+impl fmt::Debug for Database {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Database")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+fn load(path: &Path) -> Result<(Reader<Vec<u8>>, Option<SystemTime>)> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+    let reader = Reader::open_readfile(path)
+        .map_err(|error| format!("unable to open {}: {}", path.display(), error))?;
+
+    Ok((reader, modified))
+}
+
+/// Parses the IP address `value` looked up by the GeoIP functions.
+pub(crate) fn parse_address(value: Value) -> Result<IpAddr> {
+    let bytes = value.try_bytes()?;
+    String::from_utf8_lossy(&bytes)
+        .parse()
+        .map_err(|error| format!("unable to parse IP address: {}", error).into())
+}
+
+pub(crate) fn optional<T: Into<Value>>(value: Option<T>) -> Value {
+    value.map(Into::into).unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+pub(crate) fn test_database(name: &str) -> String {
+    format!("{}/../../tests/data/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shares_databases() {
+        let path = test_database("GeoIP2-City-Test.mmdb");
+        let first = Database::open(&path, Kind::City).unwrap();
+        let second = Database::open(&path, Kind::City).unwrap();
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(Arc::ptr_eq(&first.reader(), &second.reader()));
+    }
+
+    #[test]
+    fn rejects_other_kinds() {
+        let path = test_database("GeoLite2-ASN-Test.mmdb");
+
+        assert!(Database::open(&path, Kind::Asn).is_ok());
+        assert!(Database::open(&path, Kind::City)
+            .unwrap_err()
+            .to_string()
+            .ends_with("is a GeoLite2-ASN database, which doesn't hold city records"));
+        assert!(Database::open("/nonexistent.mmdb", Kind::City).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::geoip::{optional, parse_address, Database, Kind};
+use remap::prelude::*;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug)]
+pub struct GetAsn;
+
+impl Function for GetAsn {
+    fn identifier(&self) -> &'static str {
+        "get_asn"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "ip",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "database",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let ip = arguments.required("ip")?.boxed();
+        let database = arguments
+            .required_literal("database")?
+            .as_value()
+            .clone()
+            .try_bytes_utf8_lossy()?
+            .into_owned();
+        let database = Database::open(&database, Kind::Asn)?;
+
+        Ok(Box::new(GetAsnFn { ip, database }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GetAsnFn {
+    ip: Box<dyn Expression>,
+    database: Arc<Database>,
+}
+
+impl Expression for GetAsnFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let ip = parse_address(self.ip.execute(state, object)?)?;
+
+        // ASN databases hold a subset of the fields of ISP databases.
+        let reader = self.database.reader();
+        let isp = reader
+            .lookup::<maxminddb::geoip2::Isp>(ip)
+            .map_err(|error| format!("no ASN record found: {}", error))?;
+
+        let mut map = BTreeMap::new();
+        map.insert(
+            "autonomous_system_number".to_owned(),
+            optional(isp.autonomous_system_number.map(i64::from)),
+        );
+        map.insert(
+            "autonomous_system_organization".to_owned(),
+            optional(isp.autonomous_system_organization),
+        );
+        map.insert("isp".to_owned(), optional(isp.isp));
+        map.insert("organization".to_owned(), optional(isp.organization));
+
+        Ok(map.into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.ip
+            .type_def(state)
+            .into_fallible(true) // invalid address, or no record for it
+            .with_constraint(value::Kind::Map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geoip::test_database;
+    use shared::btreemap;
+
+    fn get_asn(database: &str, ip: &str) -> Result<Value> {
+        GetAsnFn {
+            ip: Literal::from(ip).boxed(),
+            database: Database::open(&test_database(database), Kind::Asn).unwrap(),
+        }
+        .execute(&mut state::Program::default(), &mut Value::Null)
+    }
+
+    #[test]
+    fn get_asn_isp() {
+        assert_eq!(
+            get_asn("GeoIP2-ISP-Test.mmdb", "208.192.1.2"),
+            Ok(Value::from(btreemap! {
+                "autonomous_system_number" => 701,
+                "autonomous_system_organization" => "MCI Communications Services, Inc. d/b/a Verizon Business",
+                "isp" => "Verizon Business",
+                "organization" => "Verizon Business",
+            }))
+        );
+    }
+
+    #[test]
+    fn get_asn_asn() {
+        assert_eq!(
+            get_asn("GeoLite2-ASN-Test.mmdb", "2600:7000::1"),
+            Ok(Value::from(btreemap! {
+                "autonomous_system_number" => 6939,
+                "autonomous_system_organization" => "Hurricane Electric, Inc.",
+                "isp" => Value::Null,
+                "organization" => Value::Null,
+            }))
+        );
+    }
+
+    #[test]
+    fn fails_without_record() {
+        assert!(get_asn("GeoLite2-ASN-Test.mmdb", "10.1.12.1").is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use crate::enrichment::{EnrichmentTables, Table};
+use remap::prelude::*;
+use std::sync::Arc;
+
+/// Looks up the one record of an enrichment table matching a condition.
+#[derive(Clone, Debug, Default)]
+pub struct GetEnrichmentTableRecord {
+    tables: EnrichmentTables,
+}
+
+impl GetEnrichmentTableRecord {
+    pub fn new(tables: EnrichmentTables) -> Self {
+        Self { tables }
+    }
+}
+
+impl Function for GetEnrichmentTableRecord {
+    fn identifier(&self) -> &'static str {
+        "get_enrichment_table_record"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "table",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "condition",
+                accepts: |v| matches!(v, Value::Map(_)),
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let table = arguments
+            .required_literal("table")?
+            .as_value()
+            .clone()
+            .try_bytes_utf8_lossy()?
+            .into_owned();
+        let table = self.tables.get(&table)?;
+        let condition = arguments.required("condition")?.boxed();
+
+        Ok(Box::new(GetEnrichmentTableRecordFn { table, condition }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GetEnrichmentTableRecordFn {
+    table: Arc<Table>,
+    condition: Box<dyn Expression>,
+}
+
+impl Expression for GetEnrichmentTableRecordFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let condition = self.condition.execute(state, object)?.try_map()?;
+
+        match self.table.find(&condition)?.as_slice() {
+            [record] => Ok(Value::from((*record).clone())),
+            [] => Err("no record found".into()),
+            records => Err(format!("{} records found, expected one", records.len()).into()),
+        }
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.condition
+            .type_def(state)
+            .into_fallible(true) // no record, or several of them
+            .with_constraint(value::Kind::Map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enrichment::test_tables;
+    use shared::btreemap;
+
+    fn get(condition: Value) -> Result<Value> {
+        GetEnrichmentTableRecordFn {
+            table: test_tables().get("cities").unwrap(),
+            condition: Literal::from(condition).boxed(),
+        }
+        .execute(&mut state::Program::default(), &mut Value::Null)
+    }
+
+    #[test]
+    fn get_enrichment_table_record() {
+        assert_eq!(
+            get(Value::from(btreemap! { "code" => "2" })),
+            Ok(Value::from(btreemap! {
+                "code" => "2",
+                "region" => "us",
+                "name" => "Boston",
+            }))
+        );
+        assert_eq!(
+            get(Value::from(btreemap! { "code" => "4" })),
+            Err("no record found".into())
+        );
+        assert_eq!(
+            get(Value::from(btreemap! { "region" => "eu" })),
+            Err("2 records found, expected one".into())
+        );
+    }
+
+    #[test]
+    fn unknown_table() {
+        assert_eq!(
+            test_tables().get("countries").unwrap_err(),
+            Error::from("unknown enrichment table \"countries\"")
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use crate::geoip::{optional, parse_address, Database, Kind};
+use remap::prelude::*;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug)]
+pub struct GetGeoip;
+
+impl Function for GetGeoip {
+    fn identifier(&self) -> &'static str {
+        "get_geoip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "ip",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "database",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let ip = arguments.required("ip")?.boxed();
+        let database = arguments
+            .required_literal("database")?
+            .as_value()
+            .clone()
+            .try_bytes_utf8_lossy()?
+            .into_owned();
+        let database = Database::open(&database, Kind::City)?;
+
+        Ok(Box::new(GetGeoipFn { ip, database }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GetGeoipFn {
+    ip: Box<dyn Expression>,
+    database: Arc<Database>,
+}
+
+impl Expression for GetGeoipFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let ip = parse_address(self.ip.execute(state, object)?)?;
+
+        let reader = self.database.reader();
+        let city = reader
+            .lookup::<maxminddb::geoip2::City>(ip)
+            .map_err(|error| format!("no GeoIP record found: {}", error))?;
+        let location = city.location.as_ref();
+
+        let mut map = BTreeMap::new();
+        map.insert(
+            "city_name".to_owned(),
+            optional(
+                city.city
+                    .and_then(|c| c.names)
+                    .and_then(|n| n.get("en").copied()),
+            ),
+        );
+        map.insert(
+            "continent_code".to_owned(),
+            optional(city.continent.and_then(|c| c.code)),
+        );
+        map.insert(
+            "country_code".to_owned(),
+            optional(city.country.and_then(|c| c.iso_code)),
+        );
+        map.insert(
+            "timezone".to_owned(),
+            optional(location.and_then(|l| l.time_zone)),
+        );
+        map.insert(
+            "latitude".to_owned(),
+            optional(location.and_then(|l| l.latitude)),
+        );
+        map.insert(
+            "longitude".to_owned(),
+            optional(location.and_then(|l| l.longitude)),
+        );
+        map.insert(
+            "postal_code".to_owned(),
+            optional(city.postal.and_then(|p| p.code)),
+        );
+
+        Ok(map.into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.ip
+            .type_def(state)
+            .into_fallible(true) // invalid address, or no record for it
+            .with_constraint(value::Kind::Map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geoip::test_database;
+    use shared::btreemap;
+
+    fn get_geoip(ip: &str) -> Result<Value> {
+        GetGeoipFn {
+            ip: Literal::from(ip).boxed(),
+            database: Database::open(&test_database("GeoIP2-City-Test.mmdb"), Kind::City).unwrap(),
+        }
+        .execute(&mut state::Program::default(), &mut Value::Null)
+    }
+
+    #[test]
+    fn get_geoip() {
+        assert_eq!(
+            get_geoip("2.125.160.216"),
+            Ok(Value::from(btreemap! {
+                "city_name" => "Boxford",
+                "continent_code" => "EU",
+                "country_code" => "GB",
+                "timezone" => "Europe/London",
+                "latitude" => 51.75,
+                "longitude" => -1.25,
+                "postal_code" => "OX1",
+            }))
+        );
+    }
+
+    #[test]
+    fn partial_results() {
+        assert_eq!(
+            get_geoip("67.43.156.9"),
+            Ok(Value::from(btreemap! {
+                "city_name" => Value::Null,
+                "continent_code" => "AS",
+                "country_code" => "BT",
+                "timezone" => "Asia/Thimphu",
+                "latitude" => 27.5,
+                "longitude" => 90.5,
+                "postal_code" => Value::Null,
+            }))
+        );
+    }
+
+    #[test]
+    fn fails_without_record() {
+        assert!(get_geoip("10.1.12.1").is_err());
+        assert_eq!(
+            get_geoip("not an address").unwrap_err().to_string(),
+            "function call error: unable to parse IP address: invalid IP address syntax"
+        );
+    }
+}
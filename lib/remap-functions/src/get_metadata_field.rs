@@ -0,0 +1,73 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct GetMetadataField;
+
+impl Function for GetMetadataField {
+    fn identifier(&self) -> &'static str {
+        "get_metadata_field"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "key",
+            accepts: |v| matches!(v, Value::Bytes(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let key = arguments.required("key")?.boxed();
+
+        Ok(Box::new(GetMetadataFieldFn { key }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GetMetadataFieldFn {
+    key: Box<dyn Expression>,
+}
+
+impl Expression for GetMetadataFieldFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let bytes = self.key.execute(state, object)?.try_bytes()?;
+        let key = String::from_utf8_lossy(&bytes);
+
+        Ok(object.get_metadata(&key)?.unwrap_or(Value::Null))
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.key
+            .type_def(state)
+            .into_fallible(true)
+            .with_constraint(value::Kind::Bytes | value::Kind::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    remap::test_type_def![value_string {
+        expr: |_| GetMetadataFieldFn {
+            key: Literal::from("priority").boxed()
+        },
+        def: TypeDef {
+            fallible: true,
+            kind: value::Kind::Bytes | value::Kind::Null,
+            ..Default::default()
+        },
+    }];
+
+    #[test]
+    fn get_metadata_field() {
+        let mut state = state::Program::default();
+        let func = GetMetadataFieldFn {
+            key: Literal::from("priority").boxed(),
+        };
+
+        // Values have no metadata.
+        let mut object = Value::from("value");
+        assert!(func.execute(&mut state, &mut object).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use crypto_hmac::digest::{generic_array::ArrayLength, BlockInput, FixedOutput, Reset, Update};
+use crypto_hmac::{Mac, NewMac};
+use remap::prelude::*;
+
+const ALGORITHMS: &[&str] = &[
+    "SHA1", "SHA-224", "SHA-256", "SHA-384", "SHA-512", "SHA3-224", "SHA3-256", "SHA3-384",
+    "SHA3-512",
+];
+
+#[derive(Clone, Copy, Debug)]
+pub struct Hmac;
+
+impl Function for Hmac {
+    fn identifier(&self) -> &'static str {
+        "hmac"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "algorithm",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+        let key = arguments.required("key")?.boxed();
+        let algorithm = arguments.optional_enum("algorithm", &ALGORITHMS)?;
+
+        Ok(Box::new(HmacFn {
+            value,
+            key,
+            algorithm,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HmacFn {
+    value: Box<dyn Expression>,
+    key: Box<dyn Expression>,
+    algorithm: Option<String>,
+}
+
+impl Expression for HmacFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let value = self.value.execute(state, object)?.try_bytes()?;
+        let key = self.key.execute(state, object)?.try_bytes()?;
+
+        let signature = match self.algorithm.as_deref() {
+            Some("SHA1") => sign::<sha_1::Sha1>(&value, &key),
+            Some("SHA-224") => sign::<sha_2::Sha224>(&value, &key),
+            Some("SHA-256") | None => sign::<sha_2::Sha256>(&value, &key),
+            Some("SHA-384") => sign::<sha_2::Sha384>(&value, &key),
+            Some("SHA-512") => sign::<sha_2::Sha512>(&value, &key),
+            Some("SHA3-224") => sign::<sha_3::Sha3_224>(&value, &key),
+            Some("SHA3-256") => sign::<sha_3::Sha3_256>(&value, &key),
+            Some("SHA3-384") => sign::<sha_3::Sha3_384>(&value, &key),
+            Some("SHA3-512") => sign::<sha_3::Sha3_512>(&value, &key),
+            _ => unreachable!("enum invariant"),
+        };
+
+        Ok(signature.into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .fallible_unless(value::Kind::Bytes)
+            .merge(self.key.type_def(state).fallible_unless(value::Kind::Bytes))
+            .with_constraint(value::Kind::Bytes)
+    }
+}
+
+/// Signs `value` with `key`, returning the raw bytes of the signature.
+fn sign<D>(value: &[u8], key: &[u8]) -> Vec<u8>
+where
+    D: Update + BlockInput + FixedOutput + Reset + Default + Clone,
+    D::BlockSize: ArrayLength<u8>,
+{
+    let mut mac = crypto_hmac::Hmac::<D>::new_varkey(key).expect("HMAC accepts keys of any size");
+    mac.update(value);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use value::Kind;
+
+    test_function![
+        hmac => Hmac;
+
+        default_sha_256 {
+            args: func_args![value: "Hello there", key: "super-secret-key"],
+            want: Ok(&b"\x78\xb1\x84\xf1\x83\x2f\x8a\xff\x39\x34\xf5\xe0\x21\x24\x54\x67\x1b\x2d\x04\xd4\x94\xe3\xb2\x50\x75\xc5\xe4\x51\x67\x02\x96\x62"[..]),
+        }
+
+        sha1 {
+            args: func_args![value: "Hello there", key: "super-secret-key", algorithm: "SHA1"],
+            want: Ok(&b"\x32\x2c\x81\x20\x73\xbc\x49\xeb\x7d\xfb\xa7\x2b\x00\xb8\xb0\x91\x2d\x32\x14\xf1"[..]),
+        }
+
+        sha_512 {
+            args: func_args![value: "Hello there", key: "super-secret-key", algorithm: "SHA-512"],
+            want: Ok(&b"\x20\xc9\x2a\x07\x6b\x22\xf3\x43\x2b\xfe\x91\x8d\xfe\x43\x14\xd0\x24\x3c\x85\x08\x64\x3a\xb1\xd7\xd7\x79\xa5\x65\x84\x81\xce\x2f\xd4\x08\x21\x04\x40\x10\xe9\x78\xc1\x36\x51\x7f\x58\xff\xc8\xe6\xc1\xf2\x58\x30\x73\x88\x58\x30\x3c\xf0\xa7\x10\x73\xc6\x0e\x96"[..]),
+        }
+
+        sha3_256 {
+            args: func_args![value: "Hello there", key: "super-secret-key", algorithm: "SHA3-256"],
+            want: Ok(&b"\x9d\xdd\xa2\xd4\x20\xb5\xa4\xe8\xca\x08\xca\x83\xab\xd6\x4c\xa5\x52\x2e\x20\xed\x23\xf6\xd6\x42\x4d\xd6\x92\x56\xf5\xef\x9f\x73"[..]),
+        }
+    ];
+
+    remap::test_type_def![
+        value_string {
+            expr: |_| HmacFn {
+                value: Literal::from("foo").boxed(),
+                key: Literal::from("bar").boxed(),
+                algorithm: None,
+            },
+            def: TypeDef { kind: Kind::Bytes, ..Default::default() },
+        }
+
+        key_non_string {
+            expr: |_| HmacFn {
+                value: Literal::from("foo").boxed(),
+                key: Literal::from(1).boxed(),
+                algorithm: None,
+            },
+            def: TypeDef { fallible: true, kind: Kind::Bytes, ..Default::default() },
+        }
+    ];
+}
@@ -1,29 +1,66 @@
 mod util;
 
+#[cfg(any(feature = "decrypt", feature = "encrypt"))]
+mod cipher;
+#[cfg(any(
+    feature = "find_enrichment_table_records",
+    feature = "get_enrichment_table_record"
+))]
+mod enrichment;
+#[cfg(any(feature = "decrypt_field", feature = "encrypt_field"))]
+mod envelope;
+#[cfg(any(feature = "get_asn", feature = "get_geoip"))]
+mod geoip;
+#[cfg(any(
+    feature = "match",
+    feature = "match_array",
+    feature = "parse_regex",
+    feature = "parse_regex_all"
+))]
+mod pattern;
+
+#[cfg(feature = "anonymize_ip")]
+mod anonymize_ip;
 #[cfg(feature = "append")]
 mod append;
 #[cfg(feature = "assert")]
 mod assert;
+#[cfg(feature = "assert_eq")]
+mod assert_eq;
 #[cfg(feature = "ceil")]
 mod ceil;
+#[cfg(feature = "chunks")]
+mod chunks;
 #[cfg(feature = "compact")]
 mod compact;
 #[cfg(feature = "contains")]
 mod contains;
 #[cfg(feature = "decode_base64")]
 mod decode_base64;
+#[cfg(feature = "decrypt")]
+mod decrypt;
+#[cfg(feature = "decrypt_field")]
+mod decrypt_field;
 #[cfg(feature = "del")]
 mod del;
 #[cfg(feature = "downcase")]
 mod downcase;
+#[cfg(feature = "encode_base16")]
+mod encode_base16;
 #[cfg(feature = "encode_base64")]
 mod encode_base64;
 #[cfg(feature = "encode_json")]
 mod encode_json;
+#[cfg(feature = "encrypt")]
+mod encrypt;
+#[cfg(feature = "encrypt_field")]
+mod encrypt_field;
 #[cfg(feature = "ends_with")]
 mod ends_with;
 #[cfg(feature = "exists")]
 mod exists;
+#[cfg(feature = "find_enrichment_table_records")]
+mod find_enrichment_table_records;
 #[cfg(feature = "flatten")]
 mod flatten;
 #[cfg(feature = "floor")]
@@ -32,10 +69,24 @@ mod floor;
 mod format_number;
 #[cfg(feature = "format_timestamp")]
 mod format_timestamp;
+#[cfg(feature = "format_traceparent")]
+mod format_traceparent;
+#[cfg(feature = "from_unix_timestamp")]
+mod from_unix_timestamp;
+#[cfg(feature = "get_asn")]
+mod get_asn;
+#[cfg(feature = "get_enrichment_table_record")]
+mod get_enrichment_table_record;
 #[cfg(feature = "get_env_var")]
 mod get_env_var;
+#[cfg(feature = "get_geoip")]
+mod get_geoip;
 #[cfg(feature = "get_hostname")]
 mod get_hostname;
+#[cfg(feature = "get_metadata_field")]
+mod get_metadata_field;
+#[cfg(feature = "hmac")]
+mod hmac;
 #[cfg(feature = "includes")]
 mod includes;
 #[cfg(feature = "ip_cidr_contains")]
@@ -56,6 +107,8 @@ mod length;
 mod log;
 #[cfg(feature = "match")]
 mod r#match;
+#[cfg(feature = "match_array")]
+mod match_array;
 #[cfg(feature = "md5")]
 mod md5;
 #[cfg(feature = "merge")]
@@ -68,14 +121,22 @@ mod parse_aws_alb_log;
 mod parse_aws_cloudwatch_log_subscription_message;
 #[cfg(feature = "parse_aws_vpc_flow_log")]
 mod parse_aws_vpc_flow_log;
+#[cfg(feature = "parse_bytes")]
+mod parse_bytes;
+#[cfg(feature = "parse_cef")]
+mod parse_cef;
 #[cfg(feature = "parse_common_log")]
 mod parse_common_log;
+#[cfg(feature = "parse_csv")]
+mod parse_csv;
 #[cfg(feature = "parse_duration")]
 mod parse_duration;
 #[cfg(feature = "parse_glog")]
 mod parse_glog;
 #[cfg(feature = "parse_grok")]
 mod parse_grok;
+#[cfg(feature = "parse_groks")]
+mod parse_groks;
 #[cfg(feature = "parse_json")]
 mod parse_json;
 #[cfg(feature = "parse_key_value")]
@@ -90,16 +151,28 @@ mod parse_syslog;
 mod parse_timestamp;
 #[cfg(feature = "parse_tokens")]
 mod parse_tokens;
+#[cfg(feature = "parse_trace_headers")]
+mod parse_trace_headers;
+#[cfg(feature = "parse_traceparent")]
+mod parse_traceparent;
 #[cfg(feature = "parse_url")]
 mod parse_url;
+#[cfg(feature = "parse_xml")]
+mod parse_xml;
+#[cfg(feature = "pseudonymize_ip")]
+mod pseudonymize_ip;
 #[cfg(feature = "push")]
 mod push;
+#[cfg(feature = "random_bytes")]
+mod random_bytes;
 #[cfg(feature = "redact")]
 mod redact;
 #[cfg(feature = "replace")]
 mod replace;
 #[cfg(feature = "round")]
 mod round;
+#[cfg(feature = "set_metadata_field")]
+mod set_metadata_field;
 #[cfg(feature = "sha1")]
 mod sha1;
 #[cfg(feature = "sha2")]
@@ -136,41 +209,70 @@ mod to_timestamp;
 mod to_unix_timestamp;
 #[cfg(feature = "truncate")]
 mod truncate;
+#[cfg(feature = "unnest")]
+mod unnest;
 #[cfg(feature = "upcase")]
 mod upcase;
 #[cfg(feature = "uuid_v4")]
 mod uuid_v4;
+#[cfg(feature = "zip")]
+mod zip;
 
 // -----------------------------------------------------------------------------
 
+#[cfg(any(
+    feature = "find_enrichment_table_records",
+    feature = "get_enrichment_table_record"
+))]
+pub use crate::enrichment::{EnrichmentTables, Table};
+#[cfg(any(feature = "decrypt_field", feature = "encrypt_field"))]
+pub use crate::envelope::Keyring;
 #[cfg(feature = "md5")]
 pub use crate::md5::Md5;
 #[cfg(feature = "sha1")]
 pub use crate::sha1::Sha1;
+#[cfg(feature = "anonymize_ip")]
+pub use anonymize_ip::AnonymizeIp;
 #[cfg(feature = "append")]
 pub use append::Append;
 #[cfg(feature = "assert")]
 pub use assert::Assert;
+#[cfg(feature = "assert_eq")]
+pub use assert_eq::AssertEq;
 #[cfg(feature = "ceil")]
 pub use ceil::Ceil;
+#[cfg(feature = "chunks")]
+pub use chunks::Chunks;
 #[cfg(feature = "compact")]
 pub use compact::Compact;
 #[cfg(feature = "contains")]
 pub use contains::Contains;
 #[cfg(feature = "decode_base64")]
 pub use decode_base64::DecodeBase64;
+#[cfg(feature = "decrypt")]
+pub use decrypt::Decrypt;
+#[cfg(feature = "decrypt_field")]
+pub use decrypt_field::DecryptField;
 #[cfg(feature = "del")]
 pub use del::Del;
 #[cfg(feature = "downcase")]
 pub use downcase::Downcase;
+#[cfg(feature = "encode_base16")]
+pub use encode_base16::EncodeBase16;
 #[cfg(feature = "encode_base64")]
 pub use encode_base64::EncodeBase64;
 #[cfg(feature = "encode_json")]
 pub use encode_json::EncodeJson;
+#[cfg(feature = "encrypt")]
+pub use encrypt::Encrypt;
+#[cfg(feature = "encrypt_field")]
+pub use encrypt_field::EncryptField;
 #[cfg(feature = "ends_with")]
 pub use ends_with::EndsWith;
 #[cfg(feature = "exists")]
 pub use exists::Exists;
+#[cfg(feature = "find_enrichment_table_records")]
+pub use find_enrichment_table_records::FindEnrichmentTableRecords;
 #[cfg(feature = "flatten")]
 pub use flatten::Flatten;
 #[cfg(feature = "floor")]
@@ -179,10 +281,24 @@ pub use floor::Floor;
 pub use format_number::FormatNumber;
 #[cfg(feature = "format_timestamp")]
 pub use format_timestamp::FormatTimestamp;
+#[cfg(feature = "format_traceparent")]
+pub use format_traceparent::FormatTraceparent;
+#[cfg(feature = "from_unix_timestamp")]
+pub use from_unix_timestamp::FromUnixTimestamp;
+#[cfg(feature = "get_asn")]
+pub use get_asn::GetAsn;
+#[cfg(feature = "get_enrichment_table_record")]
+pub use get_enrichment_table_record::GetEnrichmentTableRecord;
 #[cfg(feature = "get_env_var")]
 pub use get_env_var::GetEnvVar;
+#[cfg(feature = "get_geoip")]
+pub use get_geoip::GetGeoip;
 #[cfg(feature = "get_hostname")]
 pub use get_hostname::GetHostname;
+#[cfg(feature = "get_metadata_field")]
+pub use get_metadata_field::GetMetadataField;
+#[cfg(feature = "hmac")]
+pub use hmac::Hmac;
 #[cfg(feature = "includes")]
 pub use includes::Includes;
 #[cfg(feature = "ip_cidr_contains")]
@@ -201,6 +317,8 @@ pub use join::Join;
 pub use length::Length;
 #[cfg(feature = "log")]
 pub use log::Log;
+#[cfg(feature = "match_array")]
+pub use match_array::MatchArray;
 #[cfg(feature = "merge")]
 pub use merge::Merge;
 #[cfg(feature = "now")]
@@ -211,14 +329,22 @@ pub use parse_aws_alb_log::ParseAwsAlbLog;
 pub use parse_aws_cloudwatch_log_subscription_message::ParseAwsCloudWatchLogSubscriptionMessage;
 #[cfg(feature = "parse_aws_vpc_flow_log")]
 pub use parse_aws_vpc_flow_log::ParseAwsVpcFlowLog;
+#[cfg(feature = "parse_bytes")]
+pub use parse_bytes::ParseBytes;
+#[cfg(feature = "parse_cef")]
+pub use parse_cef::ParseCef;
 #[cfg(feature = "parse_common_log")]
 pub use parse_common_log::ParseCommonLog;
+#[cfg(feature = "parse_csv")]
+pub use parse_csv::ParseCsv;
 #[cfg(feature = "parse_duration")]
 pub use parse_duration::ParseDuration;
 #[cfg(feature = "parse_glog")]
 pub use parse_glog::ParseGlog;
 #[cfg(feature = "parse_grok")]
 pub use parse_grok::ParseGrok;
+#[cfg(feature = "parse_groks")]
+pub use parse_groks::ParseGroks;
 #[cfg(feature = "parse_json")]
 pub use parse_json::ParseJson;
 #[cfg(feature = "parse_key_value")]
@@ -233,18 +359,30 @@ pub use parse_syslog::ParseSyslog;
 pub use parse_timestamp::ParseTimestamp;
 #[cfg(feature = "parse_tokens")]
 pub use parse_tokens::ParseTokens;
+#[cfg(feature = "parse_trace_headers")]
+pub use parse_trace_headers::ParseTraceHeaders;
+#[cfg(feature = "parse_traceparent")]
+pub use parse_traceparent::ParseTraceparent;
 #[cfg(feature = "parse_url")]
 pub use parse_url::ParseUrl;
+#[cfg(feature = "parse_xml")]
+pub use parse_xml::ParseXml;
+#[cfg(feature = "pseudonymize_ip")]
+pub use pseudonymize_ip::PseudonymizeIp;
 #[cfg(feature = "push")]
 pub use push::Push;
 #[cfg(feature = "match")]
 pub use r#match::Match;
+#[cfg(feature = "random_bytes")]
+pub use random_bytes::RandomBytes;
 #[cfg(feature = "redact")]
 pub use redact::Redact;
 #[cfg(feature = "replace")]
 pub use replace::Replace;
 #[cfg(feature = "round")]
 pub use round::Round;
+#[cfg(feature = "set_metadata_field")]
+pub use set_metadata_field::SetMetadataField;
 #[cfg(feature = "sha2")]
 pub use sha2::Sha2;
 #[cfg(feature = "sha3")]
@@ -279,33 +417,53 @@ pub use to_timestamp::ToTimestamp;
 pub use to_unix_timestamp::ToUnixTimestamp;
 #[cfg(feature = "truncate")]
 pub use truncate::Truncate;
+#[cfg(feature = "unnest")]
+pub use unnest::Unnest;
 #[cfg(feature = "upcase")]
 pub use upcase::Upcase;
 #[cfg(feature = "uuid_v4")]
 pub use uuid_v4::UuidV4;
+#[cfg(feature = "zip")]
+pub use zip::Zip;
 
 pub fn all() -> Vec<Box<dyn remap::Function>> {
     vec![
+        #[cfg(feature = "anonymize_ip")]
+        Box::new(AnonymizeIp),
         #[cfg(feature = "append")]
         Box::new(Append),
         #[cfg(feature = "assert")]
         Box::new(Assert),
+        #[cfg(feature = "assert_eq")]
+        Box::new(AssertEq),
         #[cfg(feature = "ceil")]
         Box::new(Ceil),
+        #[cfg(feature = "chunks")]
+        Box::new(Chunks),
         #[cfg(feature = "compact")]
         Box::new(Compact),
         #[cfg(feature = "contains")]
         Box::new(Contains),
         #[cfg(feature = "decode_base64")]
         Box::new(DecodeBase64),
+        #[cfg(feature = "decrypt")]
+        Box::new(Decrypt),
+        #[cfg(feature = "decrypt_field")]
+        Box::new(DecryptField::default()),
         #[cfg(feature = "del")]
         Box::new(Del),
         #[cfg(feature = "downcase")]
         Box::new(Downcase),
+        #[cfg(feature = "encode_base16")]
+        Box::new(EncodeBase16),
         #[cfg(feature = "encode_base64")]
         Box::new(EncodeBase64),
         #[cfg(feature = "encode_json")]
         Box::new(EncodeJson),
+        #[cfg(feature = "encrypt")]
+        Box::new(Encrypt),
+        #[cfg(feature = "encrypt_field")]
+        Box::new(EncryptField::default()),
         #[cfg(feature = "ends_with")]
         Box::new(EndsWith),
         #[cfg(feature = "exists")]
@@ -314,6 +472,8 @@ pub fn all() -> Vec<Box<dyn remap::Function>> {
         Box::new(ParseRegex),
         #[cfg(feature = "parse_regex_all")]
         Box::new(ParseRegexAll),
+        #[cfg(feature = "find_enrichment_table_records")]
+        Box::new(FindEnrichmentTableRecords::default()),
         #[cfg(feature = "flatten")]
         Box::new(Flatten),
         #[cfg(feature = "floor")]
@@ -322,10 +482,24 @@ pub fn all() -> Vec<Box<dyn remap::Function>> {
         Box::new(FormatNumber),
         #[cfg(feature = "format_timestamp")]
         Box::new(FormatTimestamp),
+        #[cfg(feature = "format_traceparent")]
+        Box::new(FormatTraceparent),
+        #[cfg(feature = "from_unix_timestamp")]
+        Box::new(FromUnixTimestamp),
+        #[cfg(feature = "get_asn")]
+        Box::new(GetAsn),
+        #[cfg(feature = "get_enrichment_table_record")]
+        Box::new(GetEnrichmentTableRecord::default()),
         #[cfg(feature = "get_env_var")]
         Box::new(GetEnvVar),
+        #[cfg(feature = "get_geoip")]
+        Box::new(GetGeoip),
         #[cfg(feature = "get_hostname")]
         Box::new(GetHostname),
+        #[cfg(feature = "get_metadata_field")]
+        Box::new(GetMetadataField),
+        #[cfg(feature = "hmac")]
+        Box::new(Hmac),
         #[cfg(feature = "includes")]
         Box::new(Includes),
         #[cfg(feature = "ip_cidr_contains")]
@@ -356,16 +530,24 @@ pub fn all() -> Vec<Box<dyn remap::Function>> {
         Box::new(ParseAwsCloudWatchLogSubscriptionMessage),
         #[cfg(feature = "parse_aws_vpc_flow_log")]
         Box::new(ParseAwsVpcFlowLog),
+        #[cfg(feature = "parse_bytes")]
+        Box::new(ParseBytes),
+        #[cfg(feature = "parse_cef")]
+        Box::new(ParseCef),
         #[cfg(feature = "parse_duration")]
         Box::new(ParseDuration),
         #[cfg(feature = "parse_glog")]
         Box::new(ParseGlog),
         #[cfg(feature = "parse_grok")]
         Box::new(ParseGrok),
+        #[cfg(feature = "parse_groks")]
+        Box::new(ParseGroks),
         #[cfg(feature = "parse_json")]
         Box::new(ParseJson),
         #[cfg(feature = "parse_common_log")]
         Box::new(ParseCommonLog),
+        #[cfg(feature = "parse_csv")]
+        Box::new(ParseCsv),
         #[cfg(feature = "parse_key_value")]
         Box::new(ParseKeyValue),
         #[cfg(feature = "parse_syslog")]
@@ -374,18 +556,32 @@ pub fn all() -> Vec<Box<dyn remap::Function>> {
         Box::new(ParseTimestamp),
         #[cfg(feature = "parse_tokens")]
         Box::new(ParseTokens),
+        #[cfg(feature = "parse_trace_headers")]
+        Box::new(ParseTraceHeaders),
+        #[cfg(feature = "parse_traceparent")]
+        Box::new(ParseTraceparent),
         #[cfg(feature = "parse_url")]
         Box::new(ParseUrl),
+        #[cfg(feature = "parse_xml")]
+        Box::new(ParseXml),
+        #[cfg(feature = "pseudonymize_ip")]
+        Box::new(PseudonymizeIp),
         #[cfg(feature = "push")]
         Box::new(Push),
         #[cfg(feature = "match")]
         Box::new(Match),
+        #[cfg(feature = "match_array")]
+        Box::new(MatchArray),
+        #[cfg(feature = "random_bytes")]
+        Box::new(RandomBytes),
         #[cfg(feature = "redact")]
         Box::new(Redact),
         #[cfg(feature = "replace")]
         Box::new(Replace),
         #[cfg(feature = "round")]
         Box::new(Round),
+        #[cfg(feature = "set_metadata_field")]
+        Box::new(SetMetadataField),
         #[cfg(feature = "sha1")]
         Box::new(Sha1),
         #[cfg(feature = "sha2")]
@@ -422,9 +618,55 @@ pub fn all() -> Vec<Box<dyn remap::Function>> {
         Box::new(ToUnixTimestamp),
         #[cfg(feature = "truncate")]
         Box::new(Truncate),
+        #[cfg(feature = "unnest")]
+        Box::new(Unnest),
         #[cfg(feature = "upcase")]
         Box::new(Upcase),
         #[cfg(feature = "uuid_v4")]
         Box::new(UuidV4),
+        #[cfg(feature = "zip")]
+        Box::new(Zip),
     ]
 }
+
+/// All functions, with `decrypt_field` and `encrypt_field` using the data keys
+/// of `keyring`.
+#[cfg(any(feature = "decrypt_field", feature = "encrypt_field"))]
+pub fn all_with_keyring(keyring: Keyring) -> Vec<Box<dyn remap::Function>> {
+    all()
+        .into_iter()
+        .map(|function| match function.identifier() {
+            #[cfg(feature = "decrypt_field")]
+            "decrypt_field" => Box::new(DecryptField::new(keyring.clone())) as _,
+            #[cfg(feature = "encrypt_field")]
+            "encrypt_field" => Box::new(EncryptField::new(keyring.clone())) as _,
+            _ => function,
+        })
+        .collect()
+}
+
+/// `functions`, with `find_enrichment_table_records` and
+/// `get_enrichment_table_record` looking records up in `tables`.
+#[cfg(any(
+    feature = "find_enrichment_table_records",
+    feature = "get_enrichment_table_record"
+))]
+pub fn with_enrichment_tables(
+    functions: Vec<Box<dyn remap::Function>>,
+    tables: EnrichmentTables,
+) -> Vec<Box<dyn remap::Function>> {
+    functions
+        .into_iter()
+        .map(|function| match function.identifier() {
+            #[cfg(feature = "find_enrichment_table_records")]
+            "find_enrichment_table_records" => {
+                Box::new(FindEnrichmentTableRecords::new(tables.clone())) as _
+            }
+            #[cfg(feature = "get_enrichment_table_record")]
+            "get_enrichment_table_record" => {
+                Box::new(GetEnrichmentTableRecord::new(tables.clone())) as _
+            }
+            _ => function,
+        })
+        .collect()
+}
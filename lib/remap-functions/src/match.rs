@@ -1,4 +1,4 @@
-use regex::Regex;
+use crate::pattern::Pattern;
 use remap::prelude::*;
 
 #[derive(Clone, Copy, Debug)]
@@ -18,7 +18,7 @@ impl Function for Match {
             },
             Parameter {
                 keyword: "pattern",
-                accepts: |_| true,
+                accepts: |v| matches!(v, Value::Bytes(_) | Value::Regex(_)),
                 required: true,
             },
         ]
@@ -26,7 +26,7 @@ impl Function for Match {
 
     fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
         let value = arguments.required("value")?.boxed();
-        let pattern = arguments.required_regex("pattern")?;
+        let pattern = Pattern::required(&mut arguments, "pattern")?;
 
         Ok(Box::new(MatchFn { value, pattern }))
     }
@@ -35,13 +35,16 @@ impl Function for Match {
 #[derive(Debug, Clone)]
 pub(crate) struct MatchFn {
     value: Box<dyn Expression>,
-    pattern: Regex,
+    pattern: Pattern,
 }
 
 impl MatchFn {
     #[cfg(test)]
-    fn new(value: Box<dyn Expression>, pattern: Regex) -> Self {
-        Self { value, pattern }
+    fn new(value: Box<dyn Expression>, pattern: regex::Regex) -> Self {
+        Self {
+            value,
+            pattern: pattern.into(),
+        }
     }
 }
 
@@ -49,14 +52,16 @@ impl Expression for MatchFn {
     fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
         let value = self.value.execute(state, object)?;
         let string = value.try_bytes_utf8_lossy()?;
+        let pattern = self.pattern.resolve(state, object)?;
 
-        Ok(self.pattern.is_match(&string).into())
+        Ok(pattern.is_match(&string).into())
     }
 
     fn type_def(&self, state: &state::Compiler) -> TypeDef {
         self.value
             .type_def(state)
             .fallible_unless(value::Kind::Bytes)
+            .merge_optional(self.pattern.type_def(state))
             .with_constraint(value::Kind::Boolean)
     }
 }
@@ -65,6 +70,7 @@ impl Expression for MatchFn {
 #[allow(clippy::trivial_regex)]
 mod tests {
     use super::*;
+    use regex::Regex;
     use shared::btreemap;
     use value::Kind;
 
@@ -72,7 +78,7 @@ mod tests {
         value_string {
             expr: |_| MatchFn {
                 value: Literal::from("foo").boxed(),
-                pattern: Regex::new("").unwrap(),
+                pattern: Regex::new("").unwrap().into(),
             },
             def: TypeDef { kind: Kind::Boolean, ..Default::default() },
         }
@@ -80,7 +86,7 @@ mod tests {
         value_non_string {
             expr: |_| MatchFn {
                 value: Literal::from(1).boxed(),
-                pattern: Regex::new("").unwrap(),
+                pattern: Regex::new("").unwrap().into(),
             },
             def: TypeDef { fallible: true, kind: Kind::Boolean, ..Default::default() },
         }
@@ -88,7 +94,7 @@ mod tests {
         value_optional {
             expr: |_| MatchFn {
                 value: Box::new(Noop),
-                pattern: Regex::new("").unwrap(),
+                pattern: Regex::new("").unwrap().into(),
             },
             def: TypeDef { fallible: true, kind: Kind::Boolean, ..Default::default() },
         }
@@ -0,0 +1,151 @@
+use crate::pattern::Pattern;
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct MatchArray;
+
+impl Function for MatchArray {
+    fn identifier(&self) -> &'static str {
+        "match_array"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Array(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "pattern",
+                accepts: |v| matches!(v, Value::Bytes(_) | Value::Regex(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "all",
+                accepts: |v| matches!(v, Value::Boolean(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+        let pattern = Pattern::required(&mut arguments, "pattern")?;
+        let all = arguments
+            .optional("all")
+            .unwrap_or_else(|| Literal::from(false).into())
+            .boxed();
+
+        Ok(Box::new(MatchArrayFn {
+            value,
+            pattern,
+            all,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MatchArrayFn {
+    value: Box<dyn Expression>,
+    pattern: Pattern,
+    all: Box<dyn Expression>,
+}
+
+impl Expression for MatchArrayFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let items = self.value.execute(state, object)?.try_array()?;
+        let pattern = self.pattern.resolve(state, object)?;
+        let all = self.all.execute(state, object)?.try_boolean()?;
+
+        let matches = items
+            .into_iter()
+            .map(|item| Ok(pattern.is_match(&item.try_bytes_utf8_lossy()?)))
+            .collect::<Result<Vec<bool>>>()?;
+
+        let matched = if all {
+            matches.into_iter().all(|matched| matched)
+        } else {
+            matches.into_iter().any(|matched| matched)
+        };
+
+        Ok(matched.into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .merge(self.all.type_def(state))
+            .merge_optional(self.pattern.type_def(state))
+            .into_fallible(true) // items aren't known to be strings
+            .with_constraint(value::Kind::Boolean)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::trivial_regex)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    test_function![
+        match_array => MatchArray;
+
+        any_matches {
+            args: func_args![
+                value: array!["foo", "bar 2", "baz"],
+                pattern: Regex::new(r"\d").unwrap()
+            ],
+            want: Ok(true),
+        }
+
+        none_match {
+            args: func_args![
+                value: array!["foo", "bar", "baz"],
+                pattern: Regex::new(r"\d").unwrap()
+            ],
+            want: Ok(false),
+        }
+
+        all_match {
+            args: func_args![
+                value: array!["foo 1", "bar 2"],
+                pattern: r"\d",
+                all: true
+            ],
+            want: Ok(true),
+        }
+
+        not_all_match {
+            args: func_args![
+                value: array!["foo 1", "bar"],
+                pattern: r"\d",
+                all: true
+            ],
+            want: Ok(false),
+        }
+
+        empty_array {
+            args: func_args![value: array![], pattern: r"\d"],
+            want: Ok(false),
+        }
+
+        non_string_item {
+            args: func_args![value: array!["foo", 1], pattern: r"\d"],
+            want: Err(r#"value error: expected "string", got "integer""#),
+        }
+    ];
+
+    remap::test_type_def![static_pattern {
+        expr: |_| MatchArrayFn {
+            value: array!["foo"].boxed(),
+            pattern: Regex::new("foo").unwrap().into(),
+            all: Literal::from(false).boxed(),
+        },
+        def: TypeDef {
+            fallible: true,
+            kind: value::Kind::Boolean,
+            ..Default::default()
+        },
+    }];
+}
@@ -0,0 +1,100 @@
+use remap::prelude::*;
+use std::convert::TryFrom;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseBytes;
+
+impl Function for ParseBytes {
+    fn identifier(&self) -> &'static str {
+        "parse_bytes"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Bytes(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+
+        Ok(Box::new(ParseBytesFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseBytesFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for ParseBytesFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let bytes = self.value.execute(state, object)?.try_bytes()?;
+        let value = String::from_utf8_lossy(&bytes);
+
+        let size = shared::units::parse_bytes(&value)
+            .map_err(|error| format!("unable to parse size '{}': {}", value, error))?;
+        let size = i64::try_from(size).map_err(|_| format!("size is too large: '{}'", value))?;
+
+        Ok(size.into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .into_fallible(true) // parsing errors
+            .with_constraint(value::Kind::Integer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    remap::test_type_def![
+        value_string {
+            expr: |_| ParseBytesFn { value: Literal::from("foo").boxed() },
+            def: TypeDef { fallible: true, kind: value::Kind::Integer, ..Default::default() },
+        }
+
+        optional_expression {
+            expr: |_| ParseBytesFn { value: Box::new(Noop) },
+            def: TypeDef { fallible: true, kind: value::Kind::Integer, ..Default::default() },
+        }
+    ];
+
+    #[test]
+    fn parse_bytes() {
+        let cases = vec![
+            (btreemap! {}, Ok(1024.into()), "1024"),
+            (btreemap! {}, Ok(5_242_880.into()), "5MiB"),
+            (btreemap! {}, Ok(1_500_000.into()), "1.5 MB"),
+            (
+                btreemap! {},
+                Err(r#"function call error: unable to parse size '5 apples': Unknown unit "apples", expected one of B, kB, MB, GB, TB, KiB, MiB, GiB or TiB"#.into()),
+                "5 apples",
+            ),
+            (
+                btreemap! {},
+                Err("function call error: size is too large: '10000000TiB'".into()),
+                "10000000TiB",
+            ),
+        ];
+
+        let mut state = state::Program::default();
+
+        for (object, exp, value) in cases {
+            let mut object: Value = object.into();
+            let got = ParseBytesFn {
+                value: Literal::from(value).boxed(),
+            }
+            .execute(&mut state, &mut object)
+            .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use remap::prelude::*;
+use std::collections::BTreeMap;
+
+/// The fields of the header, in order, up to the extension.
+const HEADER_FIELDS: [&str; 7] = [
+    "cef_version",
+    "device_vendor",
+    "device_product",
+    "device_version",
+    "device_event_class_id",
+    "name",
+    "severity",
+];
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseCef;
+
+impl Function for ParseCef {
+    fn identifier(&self) -> &'static str {
+        "parse_cef"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Bytes(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+
+        Ok(Box::new(ParseCefFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseCefFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for ParseCefFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let bytes = self.value.execute(state, object)?.try_bytes()?;
+        let message = String::from_utf8_lossy(&bytes);
+
+        let mut log: BTreeMap<String, Value> = BTreeMap::new();
+        for (key, value) in parse(&message)? {
+            log.insert(key, Value::Bytes(value.into()));
+        }
+
+        Ok(log.into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .into_fallible(true)
+            .with_constraint(value::Kind::Map)
+            .with_inner_type(inner_type_def())
+    }
+}
+
+fn inner_type_def() -> Option<InnerTypeDef> {
+    use value::Kind;
+
+    Some(inner_type_def!({
+        "cef_version": Kind::Bytes,
+        "device_vendor": Kind::Bytes,
+        "device_product": Kind::Bytes,
+        "device_version": Kind::Bytes,
+        "device_event_class_id": Kind::Bytes,
+        "name": Kind::Bytes,
+        "severity": Kind::Bytes,
+    }))
+}
+
+/// Parses a CEF message, optionally preceded by a syslog prefix, into the
+/// fields of its header followed by the ones of its extension.
+fn parse(message: &str) -> std::result::Result<Vec<(String, String)>, String> {
+    let start = message.find("CEF:").ok_or("missing CEF prefix")?;
+    let mut rest = &message[start + "CEF:".len()..];
+
+    let mut fields = Vec::new();
+    for key in HEADER_FIELDS.iter() {
+        let end = find_unescaped(rest, '|')
+            .ok_or_else(|| format!(r#"missing CEF header field "{}""#, key))?;
+        fields.push((key.to_string(), unescape(rest[..end].trim(), &['|', '\\'])));
+        rest = &rest[end + 1..];
+    }
+
+    let extension = parse_extension(rest)?;
+
+    // The header fields take precedence over the extension fields of the same name.
+    let mut parsed: Vec<(String, String)> = extension
+        .into_iter()
+        .filter(|(key, _)| !HEADER_FIELDS.contains(&key.as_str()))
+        .collect();
+    parsed.extend(fields);
+
+    Ok(parsed)
+}
+
+/// Parses the space separated `key=value` pairs of the extension. Values can
+/// hold spaces, so a value runs until the key of the next pair.
+fn parse_extension(extension: &str) -> std::result::Result<Vec<(String, String)>, String> {
+    let mut pairs = Vec::new();
+    let mut key: Option<&str> = None;
+    let mut value_start = 0;
+    let mut offset = 0;
+
+    while let Some(position) = find_unescaped(&extension[offset..], '=') {
+        let equals = offset + position;
+        let before = &extension[value_start..equals];
+
+        match key {
+            None => {
+                let name = before.trim();
+                if name.is_empty() || name.contains(char::is_whitespace) {
+                    return Err(format!(r#"invalid CEF extension key "{}""#, name));
+                }
+                key = Some(name);
+                value_start = equals + 1;
+            }
+            Some(previous) => {
+                // An unescaped `=` not preceded by a key is part of the value.
+                let before = before.trim_end();
+                if let Some(space) = before.rfind(' ') {
+                    let name = &before[space + 1..];
+                    if !name.is_empty() {
+                        pairs.push((previous.to_owned(), unescape_value(&before[..space])));
+                        key = Some(name);
+                        value_start = equals + 1;
+                    }
+                }
+            }
+        }
+
+        offset = equals + 1;
+    }
+
+    match key {
+        Some(key) => pairs.push((key.to_owned(), unescape_value(&extension[value_start..]))),
+        None if !extension.trim().is_empty() => {
+            return Err("failed parsing CEF extension".to_owned())
+        }
+        None => (),
+    }
+
+    Ok(pairs)
+}
+
+/// The position of the first occurrence of `target` not escaped by a backslash.
+fn find_unescaped(input: &str, target: char) -> Option<usize> {
+    let mut escaped = false;
+    for (position, c) in input.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == target => return Some(position),
+            _ => (),
+        }
+    }
+    None
+}
+
+fn unescape_value(value: &str) -> String {
+    unescape(value.trim(), &['=', '\\', '|', 'n', 'r'])
+}
+
+/// Unescapes the `escapable` characters, `n` and `r` standing for line breaks,
+/// and leaves the other backslashes as is.
+fn unescape(input: &str, escapable: &[char]) -> String {
+    let mut unescaped = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.peek().filter(|next| escapable.contains(*next)) {
+                unescaped.push(match next {
+                    'n' => '\n',
+                    'r' => '\r',
+                    next => *next,
+                });
+                chars.next();
+                continue;
+            }
+        }
+        unescaped.push(c);
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    test_function![
+        parse_cef => ParseCef;
+
+        header_and_extension {
+            args: func_args![value: "CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232"],
+            want: Ok(btreemap! {
+                "cef_version" => "0",
+                "device_vendor" => "Security",
+                "device_product" => "threatmanager",
+                "device_version" => "1.0",
+                "device_event_class_id" => "100",
+                "name" => "worm successfully stopped",
+                "severity" => "10",
+                "src" => "10.0.0.1",
+                "dst" => "2.1.2.2",
+                "spt" => "1232",
+            }),
+        }
+
+        syslog_prefix {
+            args: func_args![value: "Sep 29 08:26:10 host CEF:1|Security|threatmanager|1.0|100|detected a \\| in message|10|"],
+            want: Ok(btreemap! {
+                "cef_version" => "1",
+                "device_vendor" => "Security",
+                "device_product" => "threatmanager",
+                "device_version" => "1.0",
+                "device_event_class_id" => "100",
+                "name" => "detected a | in message",
+                "severity" => "10",
+            }),
+        }
+
+        escaped_extension {
+            args: func_args![value: r#"CEF:0|Vendor|Product|1.0|42|Name|Low|act=blocked a \= sign msg=C:\\Windows path\nsecond line request=https://example.com/?a=b"#],
+            want: Ok(btreemap! {
+                "cef_version" => "0",
+                "device_vendor" => "Vendor",
+                "device_product" => "Product",
+                "device_version" => "1.0",
+                "device_event_class_id" => "42",
+                "name" => "Name",
+                "severity" => "Low",
+                "act" => "blocked a = sign",
+                "msg" => "C:\\Windows path\nsecond line",
+                "request" => "https://example.com/?a=b",
+            }),
+        }
+
+        missing_header_field {
+            args: func_args![value: "CEF:0|Security|threatmanager|1.0|100"],
+            want: Err(r#"function call error: missing CEF header field "name""#),
+        }
+
+        not_cef {
+            args: func_args![value: "<34>Oct 11 22:14:15 mymachine su: 'su root' failed"],
+            want: Err("function call error: missing CEF prefix"),
+        }
+    ];
+}
@@ -0,0 +1,226 @@
+use remap::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseCsv;
+
+impl Function for ParseCsv {
+    fn identifier(&self) -> &'static str {
+        "parse_csv"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "delimiter",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: false,
+            },
+            Parameter {
+                keyword: "quote",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: false,
+            },
+            Parameter {
+                keyword: "headers",
+                accepts: |v| matches!(v, Value::Array(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+        let delimiter = arguments
+            .optional("delimiter")
+            .unwrap_or_else(|| Literal::from(",").into())
+            .boxed();
+        let quote = arguments
+            .optional("quote")
+            .unwrap_or_else(|| Literal::from("\"").into())
+            .boxed();
+        let headers = arguments.optional("headers").map(Expr::boxed);
+
+        Ok(Box::new(ParseCsvFn {
+            value,
+            delimiter,
+            quote,
+            headers,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseCsvFn {
+    value: Box<dyn Expression>,
+    delimiter: Box<dyn Expression>,
+    quote: Box<dyn Expression>,
+    headers: Option<Box<dyn Expression>>,
+}
+
+impl Expression for ParseCsvFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let value = self.value.execute(state, object)?.try_bytes()?;
+        let delimiter = single_byte("delimiter", &self.delimiter.execute(state, object)?)?;
+        let quote = single_byte("quote", &self.quote.execute(state, object)?)?;
+
+        let fields = parse(&value, delimiter, quote)?;
+
+        match &self.headers {
+            None => Ok(fields.into()),
+            Some(headers) => {
+                let headers = headers
+                    .execute(state, object)?
+                    .try_array()?
+                    .into_iter()
+                    .map(|header| Ok(header.try_bytes_utf8_lossy()?.into_owned()))
+                    .collect::<Result<Vec<_>>>()?;
+
+                if headers.len() != fields.len() {
+                    return Err(format!(
+                        "expected {} fields to match the headers, got {}",
+                        headers.len(),
+                        fields.len()
+                    )
+                    .into());
+                }
+
+                Ok(headers
+                    .into_iter()
+                    .zip(fields.into_iter().map(Value::from))
+                    .collect::<BTreeMap<_, _>>()
+                    .into())
+            }
+        }
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        let kind = match self.headers {
+            Some(_) => value::Kind::Map,
+            None => value::Kind::Array,
+        };
+
+        self.value
+            .type_def(state)
+            .merge(self.delimiter.type_def(state))
+            .merge(self.quote.type_def(state))
+            .merge_optional(self.headers.as_ref().map(|headers| headers.type_def(state)))
+            .into_fallible(true)
+            .with_constraint(kind)
+    }
+}
+
+fn single_byte(name: &str, value: &Value) -> Result<u8> {
+    match value.try_bytes_utf8_lossy()?.as_bytes() {
+        [byte] => Ok(*byte),
+        _ => Err(format!("{} must be a single character", name).into()),
+    }
+}
+
+/// Parses `value` as a single CSV record. Fields can be quoted to hold the
+/// delimiter, a newline or the quote itself, doubled.
+fn parse(value: &[u8], delimiter: u8, quote: u8) -> Result<Vec<String>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .delimiter(delimiter)
+        .quote(quote)
+        .from_reader(value);
+
+    let mut records = reader.byte_records();
+    let record = match records.next() {
+        Some(record) => record.map_err(|error| format!("invalid CSV: {}", error))?,
+        None => return Ok(Vec::new()),
+    };
+    if records.next().is_some() {
+        return Err("expected a single CSV record".into());
+    }
+
+    Ok(record
+        .iter()
+        .map(|field| String::from_utf8_lossy(field).into_owned())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    test_function![
+        parse_csv => ParseCsv;
+
+        fields {
+            args: func_args![value: "foo,bar,baz"],
+            want: Ok(vec!["foo", "bar", "baz"]),
+        }
+
+        quoted_fields {
+            args: func_args![value: r#"foo,"bar, ""quoted"" baz",,"#],
+            want: Ok(vec!["foo", r#"bar, "quoted" baz"#, "", ""]),
+        }
+
+        delimiter_and_quote {
+            args: func_args![value: "foo;'bar;baz'", delimiter: ";", quote: "'"],
+            want: Ok(vec!["foo", "bar;baz"]),
+        }
+
+        headers {
+            args: func_args![value: "200,GET,/", headers: array!["status", "method", "path"]],
+            want: Ok(btreemap! {
+                "status" => "200",
+                "method" => "GET",
+                "path" => "/",
+            }),
+        }
+
+        headers_mismatch {
+            args: func_args![value: "200,GET", headers: array!["status", "method", "path"]],
+            want: Err("function call error: expected 3 fields to match the headers, got 2"),
+        }
+
+        several_records {
+            args: func_args![value: "foo,bar\nbaz"],
+            want: Err("function call error: expected a single CSV record"),
+        }
+
+        invalid_delimiter {
+            args: func_args![value: "foo,bar", delimiter: ", "],
+            want: Err("function call error: delimiter must be a single character"),
+        }
+    ];
+
+    remap::test_type_def![
+        array {
+            expr: |_| ParseCsvFn {
+                value: Literal::from("foo").boxed(),
+                delimiter: Literal::from(",").boxed(),
+                quote: Literal::from("\"").boxed(),
+                headers: None,
+            },
+            def: TypeDef {
+                fallible: true,
+                kind: value::Kind::Array,
+                ..Default::default()
+            },
+        }
+
+        map {
+            expr: |_| ParseCsvFn {
+                value: Literal::from("foo").boxed(),
+                delimiter: Literal::from(",").boxed(),
+                quote: Literal::from("\"").boxed(),
+                headers: Some(Array::from(vec!["bar"]).boxed()),
+            },
+            def: TypeDef {
+                fallible: true,
+                kind: value::Kind::Map,
+                ..Default::default()
+            },
+        }
+    ];
+}
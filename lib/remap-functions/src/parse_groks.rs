@@ -0,0 +1,229 @@
+use remap::prelude::*;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseGroks;
+
+impl Function for ParseGroks {
+    fn identifier(&self) -> &'static str {
+        "parse_groks"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "patterns",
+                accepts: |v| matches!(v, Value::Array(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "aliases",
+                accepts: |v| matches!(v, Value::Map(_)),
+                required: false,
+            },
+            Parameter {
+                keyword: "remove_empty",
+                accepts: |v| matches!(v, Value::Boolean(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+
+        let patterns = arguments
+            .required_array("patterns")?
+            .into_iter()
+            .map(literal_string)
+            .collect::<Result<Vec<_>>>()?;
+
+        let aliases = match arguments.optional("aliases") {
+            Some(expr) => Map::try_from(expr)?
+                .into_iter()
+                .map(|(name, expr)| Ok((name, literal_string(expr)?)))
+                .collect::<Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+
+        let patterns = compile_patterns(&patterns, aliases)?;
+
+        let remove_empty = arguments.optional("remove_empty").map(Expr::boxed);
+
+        Ok(Box::new(ParseGroksFn {
+            value,
+            patterns: Arc::new(patterns),
+            remove_empty,
+        }))
+    }
+}
+
+fn literal_string(expr: Expr) -> Result<String> {
+    Ok(Literal::try_from(expr)?
+        .into_value()
+        .try_bytes_utf8_lossy()?
+        .into_owned())
+}
+
+/// Compiles `patterns` in order, with the `aliases` they can reference as
+/// `%{NAME}`, like the built-in patterns.
+fn compile_patterns(
+    patterns: &[String],
+    aliases: Vec<(String, String)>,
+) -> Result<Vec<grok::Pattern>> {
+    if patterns.is_empty() {
+        return Err("at least one pattern is required".into());
+    }
+
+    let mut grok = grok::Grok::with_patterns();
+    for (name, definition) in aliases {
+        grok.insert_definition(name, definition);
+    }
+
+    patterns
+        .iter()
+        .map(|pattern| {
+            grok.compile(pattern, true)
+                .map_err(|e| Error::from(e.to_string()))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+struct ParseGroksFn {
+    value: Box<dyn Expression>,
+    // Wrapping the patterns in an Arc, as cloning them could otherwise be expensive.
+    patterns: Arc<Vec<grok::Pattern>>,
+    remove_empty: Option<Box<dyn Expression>>,
+}
+
+impl Expression for ParseGroksFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let bytes = self.value.execute(state, object)?.try_bytes()?;
+        let value = String::from_utf8_lossy(&bytes);
+        let remove_empty = match &self.remove_empty {
+            Some(expr) => expr.execute(state, object)?.try_boolean()?,
+            None => false,
+        };
+
+        let matches = self
+            .patterns
+            .iter()
+            .find_map(|pattern| pattern.match_against(&value))
+            .ok_or("unable to parse input with grok patterns")?;
+
+        let mut result = BTreeMap::new();
+        for (name, value) in matches.iter() {
+            if !remove_empty || !value.is_empty() {
+                result.insert(name.to_string(), Value::from(value));
+            }
+        }
+
+        Ok(Value::from(result))
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .into_fallible(true)
+            .with_constraint(value::Kind::Map)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use shared::btreemap;
+
+    remap::test_type_def![string {
+        expr: |_| ParseGroksFn {
+            value: Literal::from("foo").boxed(),
+            patterns: Arc::new(
+                compile_patterns(&["%{LOGLEVEL:level}".to_owned()], Vec::new()).unwrap()
+            ),
+            remove_empty: None,
+        },
+        def: TypeDef {
+            kind: value::Kind::Map,
+            fallible: true,
+            ..Default::default()
+        },
+    }];
+
+    test_function![
+        parse_groks => ParseGroks;
+
+        first_matching_pattern {
+            args: func_args![
+                value: "2020-10-02T23:22:12.223222Z info Hello world",
+                patterns: array![
+                    "%{LOGLEVEL:level}: %{GREEDYDATA:message}",
+                    "%{TIMESTAMP_ISO8601:timestamp} %{LOGLEVEL:level} %{GREEDYDATA:message}",
+                    "%{TIMESTAMP_ISO8601:timestamp} %{GREEDYDATA:message}",
+                ],
+            ],
+            want: Ok(btreemap! {
+                "timestamp" => "2020-10-02T23:22:12.223222Z",
+                "level" => "info",
+                "message" => "Hello world",
+            }),
+        }
+
+        aliases {
+            args: func_args![
+                value: "2020-10-02T23:22:12.223222Z info [api] Hello world",
+                patterns: array!["%{PREFIX} %{COMPONENT} %{GREEDYDATA:message}"],
+                aliases: map![
+                    "PREFIX": "%{TIMESTAMP_ISO8601:timestamp} %{SEVERITY}",
+                    "SEVERITY": "%{LOGLEVEL:level}",
+                    "COMPONENT": "\\[%{WORD:component}\\]",
+                ],
+            ],
+            want: Ok(btreemap! {
+                "timestamp" => "2020-10-02T23:22:12.223222Z",
+                "level" => "info",
+                "component" => "api",
+                "message" => "Hello world",
+            }),
+        }
+
+        remove_empty {
+            args: func_args![
+                value: "2020-10-02T23:22:12.223222Z",
+                patterns: array!["(%{TIMESTAMP_ISO8601:timestamp}|%{LOGLEVEL:level})"],
+                remove_empty: true,
+            ],
+            want: Ok(btreemap! { "timestamp" => "2020-10-02T23:22:12.223222Z" }),
+        }
+
+        no_matching_pattern {
+            args: func_args![
+                value: "an ungrokkable message",
+                patterns: array![
+                    "%{TIMESTAMP_ISO8601:timestamp} %{GREEDYDATA:message}",
+                    "%{LOGLEVEL:level}: %{GREEDYDATA:message}",
+                ],
+            ],
+            want: Err("function call error: unable to parse input with grok patterns"),
+        }
+    ];
+
+    #[test]
+    fn check_invalid_patterns() {
+        assert_eq!(
+            compile_patterns(&["%{NOG}".to_owned()], Vec::new()).unwrap_err(),
+            Error::Call("The given pattern definition name \"NOG\" could not be found in the definition map".to_string())
+        );
+        assert_eq!(
+            compile_patterns(&[], Vec::new()).unwrap_err(),
+            Error::Call("at least one pattern is required".to_string())
+        );
+    }
+}
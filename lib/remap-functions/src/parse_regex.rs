@@ -1,7 +1,6 @@
-use regex::Regex;
 use remap::prelude::*;
 
-use crate::util;
+use crate::{pattern::Pattern, util};
 
 #[derive(Clone, Copy, Debug)]
 pub struct ParseRegex;
@@ -20,7 +19,7 @@ impl Function for ParseRegex {
             },
             Parameter {
                 keyword: "pattern",
-                accepts: |_| true,
+                accepts: |v| matches!(v, Value::Bytes(_) | Value::Regex(_)),
                 required: true,
             },
         ]
@@ -28,7 +27,7 @@ impl Function for ParseRegex {
 
     fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
         let value = arguments.required("value")?.boxed();
-        let pattern = arguments.required_regex("pattern")?;
+        let pattern = Pattern::required(&mut arguments, "pattern")?;
 
         Ok(Box::new(ParseRegexFn { value, pattern }))
     }
@@ -37,7 +36,7 @@ impl Function for ParseRegex {
 #[derive(Debug, Clone)]
 pub(crate) struct ParseRegexFn {
     value: Box<dyn Expression>,
-    pattern: Regex,
+    pattern: Pattern,
 }
 
 impl Expression for ParseRegexFn {
@@ -45,10 +44,11 @@ impl Expression for ParseRegexFn {
         let bytes = self.value.execute(state, object)?.try_bytes()?;
         let value = String::from_utf8_lossy(&bytes);
 
-        let parsed = self
-            .pattern
+        let pattern = self.pattern.resolve(state, object)?;
+
+        let parsed = pattern
             .captures(&value)
-            .map(|capture| util::capture_regex_to_map(&self.pattern, capture))
+            .map(|capture| util::capture_regex_to_map(&pattern, capture))
             .ok_or("unable to parse regular expression")?;
 
         Ok(parsed.into())
@@ -57,8 +57,9 @@ impl Expression for ParseRegexFn {
     fn type_def(&self, state: &state::Compiler) -> TypeDef {
         self.value
             .type_def(state)
+            .merge_optional(self.pattern.type_def(state))
             .into_fallible(true)
-            .with_inner_type(Some(util::regex_type_def(&self.pattern)))
+            .with_inner_type(self.pattern.as_static().map(util::regex_type_def))
             .with_constraint(value::Kind::Map)
     }
 }
@@ -67,13 +68,15 @@ impl Expression for ParseRegexFn {
 #[allow(clippy::trivial_regex)]
 mod tests {
     use super::*;
+    use regex::Regex;
+    use shared::btreemap;
     use value::Kind;
 
     remap::test_type_def![
         value_string {
             expr: |_| ParseRegexFn {
                 value: Literal::from("foo").boxed(),
-                pattern: Regex::new("^(?P<group>.*)$").unwrap(),
+                pattern: Regex::new("^(?P<group>.*)$").unwrap().into(),
             },
             def: TypeDef { kind: Kind::Map,
                            fallible: true,
@@ -86,7 +89,7 @@ mod tests {
         value_non_string {
             expr: |_| ParseRegexFn {
                 value: Literal::from(1).boxed(),
-                pattern: Regex::new("^(?P<group>.*)$").unwrap(),
+                pattern: Regex::new("^(?P<group>.*)$").unwrap().into(),
             },
             def: TypeDef { fallible: true,
                            kind: Kind::Map,
@@ -100,7 +103,7 @@ mod tests {
         value_optional {
             expr: |_| ParseRegexFn {
                 value: Box::new(Noop),
-                pattern: Regex::new("^(?P<group>.*)$").unwrap(),
+                pattern: Regex::new("^(?P<group>.*)$").unwrap().into(),
             },
             def: TypeDef { fallible: true,
                            kind: Kind::Map,
@@ -110,6 +113,14 @@ mod tests {
                            }))
             },
         }
+
+        dynamic_pattern {
+            expr: |_| ParseRegexFn {
+                value: Literal::from("foo").boxed(),
+                pattern: Pattern::Dynamic(Box::new(Path::from("pattern"))),
+            },
+            def: TypeDef { fallible: true, kind: Kind::Map, ..Default::default() },
+        }
     ];
 
     test_function![
@@ -160,5 +171,50 @@ mod tests {
             ],
             want: Err("function call error: unable to parse regular expression".to_string()),
         }
+
+        string_pattern {
+            args: func_args! [
+                value: "first group and second group",
+                pattern: "^(?P<number>\\w+)"
+            ],
+            want: Ok(value!({"number": "first",
+                             "0": "first",
+                             "1": "first"
+            }))
+        }
     ];
+
+    #[test]
+    fn dynamic_pattern() {
+        let func = ParseRegexFn {
+            value: Box::new(Path::from("message")),
+            pattern: Pattern::Dynamic(Box::new(Path::from("pattern"))),
+        };
+        let mut state = state::Program::default();
+
+        let mut object: Value = btreemap! {
+            "message" => "level=info",
+            "pattern" => "^level=(?P<level>\\w+)",
+        }
+        .into();
+        assert_eq!(
+            func.execute(&mut state, &mut object),
+            Ok(value!({"level": "info", "0": "level=info", "1": "info"}))
+        );
+
+        let mut object: Value = btreemap! {
+            "message" => "level=info",
+            "pattern" => "^level=(?P<level",
+        }
+        .into();
+        let error = func
+            .execute(&mut state, &mut object)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.starts_with("function call error: invalid regex pattern: "),
+            "{}",
+            error
+        );
+    }
 }
@@ -1,7 +1,6 @@
-use regex::Regex;
 use remap::prelude::*;
 
-use crate::util;
+use crate::{pattern::Pattern, util};
 
 #[derive(Clone, Copy, Debug)]
 pub struct ParseRegexAll;
@@ -20,7 +19,7 @@ impl Function for ParseRegexAll {
             },
             Parameter {
                 keyword: "pattern",
-                accepts: |_| true,
+                accepts: |v| matches!(v, Value::Bytes(_) | Value::Regex(_)),
                 required: true,
             },
         ]
@@ -28,7 +27,7 @@ impl Function for ParseRegexAll {
 
     fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
         let value = arguments.required("value")?.boxed();
-        let pattern = arguments.required_regex("pattern")?;
+        let pattern = Pattern::required(&mut arguments, "pattern")?;
 
         Ok(Box::new(ParseRegexAllFn { value, pattern }))
     }
@@ -37,7 +36,7 @@ impl Function for ParseRegexAll {
 #[derive(Debug, Clone)]
 pub(crate) struct ParseRegexAllFn {
     value: Box<dyn Expression>,
-    pattern: Regex,
+    pattern: Pattern,
 }
 
 impl Expression for ParseRegexAllFn {
@@ -45,10 +44,11 @@ impl Expression for ParseRegexAllFn {
         let bytes = self.value.execute(state, object)?.try_bytes()?;
         let value = String::from_utf8_lossy(&bytes);
 
-        Ok(self
-            .pattern
+        let pattern = self.pattern.resolve(state, object)?;
+
+        Ok(pattern
             .captures_iter(&value)
-            .map(|capture| util::capture_regex_to_map(&self.pattern, capture).into())
+            .map(|capture| util::capture_regex_to_map(&pattern, capture).into())
             .collect::<Vec<Value>>()
             .into())
     }
@@ -57,8 +57,9 @@ impl Expression for ParseRegexAllFn {
         self.value
             .type_def(state)
             .fallible_unless(value::Kind::Bytes)
+            .merge_optional(self.pattern.type_def(state))
             .with_inner_type(Some(inner_type_def!([TypeDef::from(value::Kind::Map)
-                .with_inner_type(Some(util::regex_type_def(&self.pattern)))])))
+                .with_inner_type(self.pattern.as_static().map(util::regex_type_def))])))
             .with_constraint(value::Kind::Array)
     }
 }
@@ -67,13 +68,14 @@ impl Expression for ParseRegexAllFn {
 #[allow(clippy::trivial_regex)]
 mod tests {
     use super::*;
+    use regex::Regex;
     use value::Kind;
 
     remap::test_type_def![
         value_string {
             expr: |_| ParseRegexAllFn {
                 value: Literal::from("foo").boxed(),
-                pattern: Regex::new("^(?P<group>.*)$").unwrap(),
+                pattern: Regex::new("^(?P<group>.*)$").unwrap().into(),
             },
             def: TypeDef { kind: Kind::Array,
                            inner_type_def: Some(inner_type_def!([ TypeDef::from(Kind::Map)
@@ -87,7 +89,7 @@ mod tests {
         value_non_string {
             expr: |_| ParseRegexAllFn {
                 value: Literal::from(1).boxed(),
-                pattern: Regex::new("^(?P<group>.*)$").unwrap(),
+                pattern: Regex::new("^(?P<group>.*)$").unwrap().into(),
             },
             def: TypeDef { fallible: true,
                            kind: Kind::Array,
@@ -102,7 +104,7 @@ mod tests {
         value_optional {
             expr: |_| ParseRegexAllFn {
                 value: Box::new(Noop),
-                pattern: Regex::new("^(?P<group>.*)$").unwrap(),
+                pattern: Regex::new("^(?P<group>.*)$").unwrap().into(),
             },
             def: TypeDef { fallible: true,
                            kind: Kind::Array,
@@ -0,0 +1,241 @@
+use crate::util;
+use remap::prelude::*;
+use std::collections::BTreeMap;
+use value::Kind;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseTraceHeaders;
+
+impl Function for ParseTraceHeaders {
+    fn identifier(&self) -> &'static str {
+        "parse_trace_headers"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Map(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+
+        Ok(Box::new(ParseTraceHeadersFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseTraceHeadersFn {
+    value: Box<dyn Expression>,
+}
+
+/// A trace context extracted from one of the supported propagation formats,
+/// with ids normalized to their W3C representation.
+struct TraceContext {
+    format: &'static str,
+    trace_id: String,
+    span_id: String,
+    sampled: Option<bool>,
+}
+
+impl Expression for ParseTraceHeadersFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let headers = self.value.execute(state, object)?.try_map()?;
+
+        // Header names are case-insensitive.
+        let headers = headers
+            .iter()
+            .filter_map(|(k, v)| {
+                v.try_bytes_utf8_lossy()
+                    .ok()
+                    .map(|v| (k.to_ascii_lowercase(), v.trim().to_owned()))
+            })
+            .collect::<BTreeMap<_, _>>();
+        let header = |name: &str| headers.get(name).map(String::as_str);
+
+        let context = w3c(header("traceparent"))
+            .or_else(|| b3_single(header("b3")))
+            .or_else(|| {
+                b3_multi(
+                    header("x-b3-traceid"),
+                    header("x-b3-spanid"),
+                    header("x-b3-sampled"),
+                    header("x-b3-flags"),
+                )
+            })
+            .or_else(|| {
+                datadog(
+                    header("x-datadog-trace-id"),
+                    header("x-datadog-parent-id"),
+                    header("x-datadog-sampling-priority"),
+                )
+            })
+            .ok_or("no valid trace context found")?;
+
+        let mut map = BTreeMap::<String, Value>::new();
+        map.insert("format".to_owned(), context.format.into());
+        map.insert("trace_id".to_owned(), context.trace_id.into());
+        map.insert("span_id".to_owned(), context.span_id.into());
+        map.insert("sampled".to_owned(), context.sampled.into());
+
+        Ok(map.into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .into_fallible(true) // no trace context found
+            .with_inner_type(inner_type_def())
+            .with_constraint(Kind::Map)
+    }
+}
+
+fn w3c(traceparent: Option<&str>) -> Option<TraceContext> {
+    let parent = util::parse_traceparent(traceparent?).ok()?;
+    Some(TraceContext {
+        format: "w3c",
+        sampled: Some(parent.sampled()),
+        trace_id: parent.trace_id,
+        span_id: parent.span_id,
+    })
+}
+
+/// `b3: {trace_id}-{span_id}-{sampling_state}-{parent_span_id}`, where the
+/// last two fields are optional. A lone sampling state carries no ids.
+fn b3_single(b3: Option<&str>) -> Option<TraceContext> {
+    let mut parts = b3?.split('-');
+    let trace_id = util::normalize_trace_id(parts.next()?)?;
+    let span_id = util::normalize_span_id(parts.next()?)?;
+    let sampled = parts.next().and_then(b3_sampled);
+    Some(TraceContext {
+        format: "b3_single",
+        trace_id,
+        span_id,
+        sampled,
+    })
+}
+
+fn b3_multi(
+    trace_id: Option<&str>,
+    span_id: Option<&str>,
+    sampled: Option<&str>,
+    flags: Option<&str>,
+) -> Option<TraceContext> {
+    let debug = flags == Some("1");
+    Some(TraceContext {
+        format: "b3",
+        trace_id: util::normalize_trace_id(trace_id?)?,
+        span_id: util::normalize_span_id(span_id?)?,
+        sampled: if debug {
+            Some(true)
+        } else {
+            sampled.and_then(b3_sampled)
+        },
+    })
+}
+
+fn b3_sampled(state: &str) -> Option<bool> {
+    match state {
+        "1" | "d" | "true" => Some(true),
+        "0" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Datadog ids are unsigned 64-bit decimal integers.
+fn datadog(
+    trace_id: Option<&str>,
+    parent_id: Option<&str>,
+    priority: Option<&str>,
+) -> Option<TraceContext> {
+    let trace_id = trace_id?.parse::<u64>().ok().filter(|id| *id != 0)?;
+    let span_id = parent_id?.parse::<u64>().ok().filter(|id| *id != 0)?;
+    let sampled = priority
+        .and_then(|priority| priority.parse::<i64>().ok())
+        .map(|priority| priority > 0);
+    Some(TraceContext {
+        format: "datadog",
+        trace_id: format!("{:032x}", trace_id),
+        span_id: format!("{:016x}", span_id),
+        sampled,
+    })
+}
+
+/// The type defs of the fields contained by the returned map.
+fn inner_type_def() -> Option<InnerTypeDef> {
+    Some(inner_type_def! ({
+        "format": Kind::Bytes,
+        "trace_id": Kind::Bytes,
+        "span_id": Kind::Bytes,
+        "sampled": Kind::Boolean | Kind::Null,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    test_function![
+        parse_trace_headers => ParseTraceHeaders;
+
+        w3c {
+            args: func_args![value: btreemap! {
+                "Traceparent" => "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+                "x-b3-traceid" => "80f198ee56343ba864fe8b2a57d3eff7",
+            }],
+            want: Ok(btreemap! {
+                "format" => "w3c",
+                "trace_id" => "0af7651916cd43dd8448eb211c80319c",
+                "span_id" => "b7ad6b7169203331",
+                "sampled" => true,
+            }),
+        }
+
+        b3_single {
+            args: func_args![value: btreemap! {
+                "b3" => "80f198ee56343ba864fe8b2a57d3eff7-e457b5a2e4d86bd1-1-05e3ac9a4f6e3b90",
+            }],
+            want: Ok(btreemap! {
+                "format" => "b3_single",
+                "trace_id" => "80f198ee56343ba864fe8b2a57d3eff7",
+                "span_id" => "e457b5a2e4d86bd1",
+                "sampled" => true,
+            }),
+        }
+
+        b3_multi {
+            args: func_args![value: btreemap! {
+                "X-B3-TraceId" => "64fe8b2a57d3eff7",
+                "X-B3-SpanId" => "e457b5a2e4d86bd1",
+            }],
+            want: Ok(btreemap! {
+                "format" => "b3",
+                "trace_id" => "000000000000000064fe8b2a57d3eff7",
+                "span_id" => "e457b5a2e4d86bd1",
+                "sampled" => Value::Null,
+            }),
+        }
+
+        datadog {
+            args: func_args![value: btreemap! {
+                "x-datadog-trace-id" => "7245652779352043654",
+                "x-datadog-parent-id" => "4003058461449618871",
+                "x-datadog-sampling-priority" => "-1",
+            }],
+            want: Ok(btreemap! {
+                "format" => "datadog",
+                "trace_id" => "0000000000000000648dbad04f19b086",
+                "span_id" => "378db87629d385b7",
+                "sampled" => false,
+            }),
+        }
+
+        missing {
+            args: func_args![value: btreemap! { "b3" => "1" }],
+            want: Err("function call error: no valid trace context found"),
+        }
+    ];
+}
@@ -0,0 +1,142 @@
+use crate::util;
+use remap::prelude::*;
+use std::collections::BTreeMap;
+use value::Kind;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseTraceparent;
+
+impl Function for ParseTraceparent {
+    fn identifier(&self) -> &'static str {
+        "parse_traceparent"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Bytes(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+
+        Ok(Box::new(ParseTraceparentFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseTraceparentFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for ParseTraceparentFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let value = self.value.execute(state, object)?;
+        let string = value.try_bytes_utf8_lossy()?;
+
+        let parent = util::parse_traceparent(&string)
+            .map_err(|e| format!("unable to parse traceparent: {}", e))?;
+
+        let mut map = BTreeMap::<String, Value>::new();
+        map.insert(
+            "version".to_owned(),
+            format!("{:02x}", parent.version).into(),
+        );
+        map.insert("sampled".to_owned(), parent.sampled().into());
+        map.insert("flags".to_owned(), (parent.flags as i64).into());
+        map.insert("trace_id".to_owned(), parent.trace_id.into());
+        map.insert("span_id".to_owned(), parent.span_id.into());
+
+        Ok(map.into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .into_fallible(true) // header parsing error
+            .with_inner_type(inner_type_def())
+            .with_constraint(Kind::Map)
+    }
+}
+
+/// The type defs of the fields contained by the returned map.
+fn inner_type_def() -> Option<InnerTypeDef> {
+    Some(inner_type_def! ({
+        "version": Kind::Bytes,
+        "trace_id": Kind::Bytes,
+        "span_id": Kind::Bytes,
+        "flags": Kind::Integer,
+        "sampled": Kind::Boolean,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    remap::test_type_def![value_string {
+        expr: |_| ParseTraceparentFn {
+            value: Literal::from("foo").boxed()
+        },
+        def: TypeDef {
+            fallible: true,
+            kind: Kind::Map,
+            inner_type_def: inner_type_def()
+        },
+    }];
+
+    test_function![
+        parse_traceparent => ParseTraceparent;
+
+        sampled {
+            args: func_args![value: "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"],
+            want: Ok(btreemap! {
+                "version" => "00",
+                "trace_id" => "0af7651916cd43dd8448eb211c80319c",
+                "span_id" => "b7ad6b7169203331",
+                "flags" => 1,
+                "sampled" => true,
+            }),
+        }
+
+        not_sampled {
+            args: func_args![value: "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-00"],
+            want: Ok(btreemap! {
+                "version" => "00",
+                "trace_id" => "0af7651916cd43dd8448eb211c80319c",
+                "span_id" => "b7ad6b7169203331",
+                "flags" => 0,
+                "sampled" => false,
+            }),
+        }
+
+        future_version {
+            args: func_args![value: "cc-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01-what-the-future-holds"],
+            want: Ok(btreemap! {
+                "version" => "cc",
+                "trace_id" => "0af7651916cd43dd8448eb211c80319c",
+                "span_id" => "b7ad6b7169203331",
+                "flags" => 1,
+                "sampled" => true,
+            }),
+        }
+
+        zero_trace_id {
+            args: func_args![value: "00-00000000000000000000000000000000-b7ad6b7169203331-01"],
+            want: Err("function call error: unable to parse traceparent: invalid trace id"),
+        }
+
+        uppercase {
+            args: func_args![value: "00-0AF7651916CD43DD8448EB211C80319C-b7ad6b7169203331-01"],
+            want: Err("function call error: unable to parse traceparent: invalid trace id"),
+        }
+
+        invalid {
+            args: func_args![value: "foo"],
+            want: Err("function call error: unable to parse traceparent: expected 4 dash-separated fields"),
+        }
+    ];
+}
@@ -0,0 +1,324 @@
+use remap::prelude::*;
+use std::collections::{btree_map::Entry, BTreeMap};
+use xml::{
+    name::OwnedName,
+    reader::{EventReader, ParserConfig, XmlEvent},
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseXml;
+
+impl Function for ParseXml {
+    fn identifier(&self) -> &'static str {
+        "parse_xml"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "attr_prefix",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: false,
+            },
+            Parameter {
+                keyword: "text_key",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: false,
+            },
+            Parameter {
+                keyword: "always_array",
+                accepts: |v| matches!(v, Value::Array(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+        let attr_prefix = arguments
+            .optional("attr_prefix")
+            .unwrap_or_else(|| Literal::from("@").into())
+            .boxed();
+        let text_key = arguments
+            .optional("text_key")
+            .unwrap_or_else(|| Literal::from("text").into())
+            .boxed();
+        let always_array = arguments.optional("always_array").map(Expr::boxed);
+
+        Ok(Box::new(ParseXmlFn {
+            value,
+            attr_prefix,
+            text_key,
+            always_array,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseXmlFn {
+    value: Box<dyn Expression>,
+    attr_prefix: Box<dyn Expression>,
+    text_key: Box<dyn Expression>,
+    always_array: Option<Box<dyn Expression>>,
+}
+
+impl Expression for ParseXmlFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let bytes = self.value.execute(state, object)?.try_bytes()?;
+
+        let attr_prefix = self.attr_prefix.execute(state, object)?.try_bytes()?;
+        let text_key = self.text_key.execute(state, object)?.try_bytes()?;
+        let always_array = match &self.always_array {
+            Some(expr) => expr
+                .execute(state, object)?
+                .try_array()?
+                .into_iter()
+                .map(|name| Ok(String::from_utf8_lossy(&name.try_bytes()?).into_owned()))
+                .collect::<Result<Vec<_>>>()?,
+            None => vec![],
+        };
+
+        let options = Options {
+            attr_prefix: &String::from_utf8_lossy(&attr_prefix),
+            text_key: &String::from_utf8_lossy(&text_key),
+            always_array: &always_array,
+        };
+
+        parse(&bytes, &options)
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        let always_array_def = self.always_array.as_ref().map(|always_array| {
+            always_array
+                .type_def(state)
+                .fallible_unless_array_has_inner_type(value::Kind::Bytes)
+        });
+
+        self.value
+            .type_def(state)
+            .merge(self.attr_prefix.type_def(state))
+            .merge(self.text_key.type_def(state))
+            .merge_optional(always_array_def)
+            .into_fallible(true) // XML parsing errors
+            .with_constraint(value::Kind::Map)
+    }
+}
+
+struct Options<'a> {
+    attr_prefix: &'a str,
+    text_key: &'a str,
+    always_array: &'a [String],
+}
+
+/// An element being read, until its end tag.
+struct Element {
+    name: String,
+    fields: BTreeMap<String, Value>,
+    text: String,
+}
+
+impl Element {
+    fn insert(&mut self, name: String, value: Value, options: &Options) {
+        let always_array = options.always_array.contains(&name);
+
+        match self.fields.entry(name) {
+            Entry::Vacant(entry) if always_array => {
+                entry.insert(Value::Array(vec![value]));
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+            }
+            // Elements are never read into arrays, so an array holds the
+            // elements of a name already seen.
+            Entry::Occupied(mut entry) => match entry.get_mut() {
+                Value::Array(values) => values.push(value),
+                first => {
+                    let previous = std::mem::replace(first, Value::Null);
+                    *first = Value::Array(vec![previous, value]);
+                }
+            },
+        }
+    }
+
+    fn into_value(mut self, options: &Options) -> Value {
+        if self.fields.is_empty() {
+            if self.text.is_empty() {
+                Value::Null
+            } else {
+                self.text.into()
+            }
+        } else {
+            if !self.text.is_empty() {
+                self.fields
+                    .insert(options.text_key.to_owned(), self.text.into());
+            }
+            self.fields.into()
+        }
+    }
+}
+
+fn qualified_name(name: OwnedName) -> String {
+    match name.prefix {
+        Some(prefix) => format!("{}:{}", prefix, name.local_name),
+        None => name.local_name,
+    }
+}
+
+fn parse(input: &[u8], options: &Options) -> Result<Value> {
+    let config = ParserConfig::new()
+        .trim_whitespace(true)
+        .cdata_to_characters(true)
+        .ignore_comments(true);
+    let reader = EventReader::new_with_config(input, config);
+
+    let mut elements: Vec<Element> = vec![];
+    let mut root = BTreeMap::new();
+    for event in reader {
+        match event.map_err(|e| format!("unable to parse xml: {}", e))? {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => {
+                let fields = attributes
+                    .into_iter()
+                    .map(|attribute| {
+                        let name = qualified_name(attribute.name);
+                        (
+                            format!("{}{}", options.attr_prefix, name),
+                            attribute.value.into(),
+                        )
+                    })
+                    .collect();
+
+                elements.push(Element {
+                    name: qualified_name(name),
+                    fields,
+                    text: String::new(),
+                });
+            }
+            XmlEvent::Characters(text) => {
+                if let Some(element) = elements.last_mut() {
+                    element.text.push_str(&text);
+                }
+            }
+            XmlEvent::EndElement { .. } => {
+                let element = elements.pop().expect("tags are balanced by the parser");
+                let name = element.name.clone();
+                let value = element.into_value(options);
+
+                match elements.last_mut() {
+                    Some(parent) => parent.insert(name, value, options),
+                    None => {
+                        root.insert(name, value);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(root.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+    use value::Kind;
+
+    test_function![
+        parse_xml => ParseXml;
+
+        text {
+            args: func_args![value: "<message>hello</message>"],
+            want: Ok(btreemap! { "message" => "hello" }),
+        }
+
+        empty {
+            args: func_args![value: "<message/>"],
+            want: Ok(btreemap! { "message" => Value::Null }),
+        }
+
+        attributes {
+            args: func_args![value: r#"<request method="GET" status="200">/index.html</request>"#],
+            want: Ok(btreemap! {
+                "request" => btreemap! {
+                    "@method" => "GET",
+                    "@status" => "200",
+                    "text" => "/index.html",
+                },
+            }),
+        }
+
+        nested {
+            args: func_args![value: r#"<?xml version="1.0" encoding="UTF-8"?>
+                <soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope">
+                  <!-- a comment -->
+                  <soap:Body>
+                    <order id="42">
+                      <item>book</item>
+                      <item>pen</item>
+                      <note><![CDATA[fragile & urgent]]></note>
+                    </order>
+                  </soap:Body>
+                </soap:Envelope>
+            "#],
+            want: Ok(btreemap! {
+                "soap:Envelope" => btreemap! {
+                    "soap:Body" => btreemap! {
+                        "order" => btreemap! {
+                            "@id" => "42",
+                            "item" => vec!["book", "pen"],
+                            "note" => "fragile & urgent",
+                        },
+                    },
+                },
+            }),
+        }
+
+        options {
+            args: func_args![
+                value: r#"<order id="42"><item>book</item></order>"#,
+                attr_prefix: "_",
+                text_key: "value",
+                always_array: array!["item"],
+            ],
+            want: Ok(btreemap! {
+                "order" => btreemap! {
+                    "_id" => "42",
+                    "item" => vec!["book"],
+                },
+            }),
+        }
+    ];
+
+    test_type_def![value_string {
+        expr: |_| ParseXmlFn {
+            value: lit!("<message>hello</message>").boxed(),
+            attr_prefix: lit!("@").boxed(),
+            text_key: lit!("text").boxed(),
+            always_array: None,
+        },
+        def: TypeDef {
+            fallible: true,
+            kind: Kind::Map,
+            ..Default::default()
+        },
+    }];
+
+    #[test]
+    fn invalid() {
+        let options = Options {
+            attr_prefix: "@",
+            text_key: "text",
+            always_array: &[],
+        };
+
+        let error = parse(b"<message>hello</msg>", &options).unwrap_err();
+        assert!(error.to_string().contains("Unexpected closing tag"));
+    }
+}
@@ -0,0 +1,125 @@
+//! The regular expressions taken by `match`, `match_array`, `parse_regex` and
+//! `parse_regex_all`.
+//!
+//! A pattern is usually a regex or string literal, compiled with the program.
+//! It can also be built at runtime, and is then compiled when the function is
+//! called. Those patterns are kept in a cache shared by all programs, so that a
+//! pattern used for every event is only compiled once.
+
+use lazy_static::lazy_static;
+use lru::LruCache;
+use regex::Regex;
+use remap::prelude::*;
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+
+/// The number of patterns built at runtime kept compiled.
+const CACHE_CAPACITY: usize = 128;
+
+lazy_static! {
+    static ref CACHE: Mutex<LruCache<String, Arc<Regex>>> =
+        Mutex::new(LruCache::new(CACHE_CAPACITY));
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Pattern {
+    Static(Arc<Regex>),
+    Dynamic(Box<dyn Expression>),
+}
+
+impl Pattern {
+    pub(crate) fn required(arguments: &mut ArgumentList, keyword: &str) -> Result<Self> {
+        let expr = arguments.required(keyword)?;
+
+        match Literal::try_from(expr.clone()).map(Literal::into_value) {
+            Ok(Value::Regex(regex)) => Ok(regex.into()),
+            Ok(Value::Bytes(bytes)) => compile(&String::from_utf8_lossy(&bytes)).map(Into::into),
+            Ok(value) => Err(expected(&value)),
+            Err(_) => Ok(Pattern::Dynamic(expr.boxed())),
+        }
+    }
+
+    /// The regex to match against, compiled if needed.
+    pub(crate) fn resolve(
+        &self,
+        state: &mut state::Program,
+        object: &mut dyn Object,
+    ) -> Result<Arc<Regex>> {
+        match self {
+            Pattern::Static(regex) => Ok(regex.clone()),
+            Pattern::Dynamic(expr) => match expr.execute(state, object)? {
+                Value::Regex(regex) => Ok(Arc::new(regex)),
+                Value::Bytes(bytes) => cached(&String::from_utf8_lossy(&bytes)),
+                value => Err(expected(&value)),
+            },
+        }
+    }
+
+    /// The type of the pattern argument, none for a literal as it's already
+    /// known to be valid.
+    pub(crate) fn type_def(&self, state: &state::Compiler) -> Option<TypeDef> {
+        match self {
+            Pattern::Static(_) => None,
+            Pattern::Dynamic(expr) => Some(expr.type_def(state).into_fallible(true)),
+        }
+    }
+
+    /// The regex of a literal pattern, its captures being unknown otherwise.
+    pub(crate) fn as_static(&self) -> Option<&Regex> {
+        match self {
+            Pattern::Static(regex) => Some(regex.as_ref()),
+            Pattern::Dynamic(_) => None,
+        }
+    }
+}
+
+impl From<Regex> for Pattern {
+    fn from(regex: Regex) -> Self {
+        Pattern::Static(Arc::new(regex))
+    }
+}
+
+fn compile(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern).map_err(|error| format!("invalid regex pattern: {}", error).into())
+}
+
+fn cached(pattern: &str) -> Result<Arc<Regex>> {
+    let mut cache = CACHE.lock().expect("poisoned lock");
+    if let Some(regex) = cache.get(&pattern.to_owned()) {
+        return Ok(regex.clone());
+    }
+
+    let regex = Arc::new(compile(pattern)?);
+    cache.put(pattern.to_owned(), regex.clone());
+    Ok(regex)
+}
+
+fn expected(value: &Value) -> Error {
+    Error::Value(value::Error::Expected(
+        value::Kind::Bytes | value::Kind::Regex,
+        value.kind(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caches_dynamic_patterns() {
+        let first = cached(r"^\d+ cached$").unwrap();
+        let second = cached(r"^\d+ cached$").unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(first.is_match("42 cached"));
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        let error = cached("(unclosed").unwrap_err().to_string();
+        assert!(
+            error.starts_with("function call error: invalid regex pattern: "),
+            "{}",
+            error
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use crypto_hmac::{Hmac, Mac, NewMac};
+use remap::prelude::*;
+use sha_2::Sha256;
+use std::net::IpAddr;
+
+#[derive(Clone, Copy, Debug)]
+pub struct PseudonymizeIp;
+
+impl Function for PseudonymizeIp {
+    fn identifier(&self) -> &'static str {
+        "pseudonymize_ip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+        let key = arguments.required("key")?.boxed();
+
+        Ok(Box::new(PseudonymizeIpFn { value, key }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PseudonymizeIpFn {
+    value: Box<dyn Expression>,
+    key: Box<dyn Expression>,
+}
+
+impl PseudonymizeIpFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>, key: Box<dyn Expression>) -> Self {
+        Self { value, key }
+    }
+}
+
+impl Expression for PseudonymizeIpFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let value: IpAddr = self
+            .value
+            .execute(state, object)?
+            .try_bytes_utf8_lossy()?
+            .parse()
+            .map_err(|err| format!("unable to parse IP address: {}", err))?;
+
+        let key = self.key.execute(state, object)?.try_bytes()?;
+        if key.is_empty() {
+            return Err("key must not be empty".into());
+        }
+
+        // The address is hashed in its canonical form, so different notations
+        // of the same address get the same pseudonym.
+        let mut mac = Hmac::<Sha256>::new_varkey(&key).expect("HMAC accepts keys of any size");
+        mac.update(value.to_string().as_bytes());
+
+        Ok(hex::encode(mac.finalize().into_bytes()).into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .merge(self.key.type_def(state))
+            .into_fallible(true)
+            .with_constraint(value::Kind::Bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    remap::test_type_def![value_string {
+        expr: |_| PseudonymizeIpFn {
+            value: Literal::from("192.168.0.1").boxed(),
+            key: Literal::from("secret").boxed(),
+        },
+        def: TypeDef {
+            kind: value::Kind::Bytes,
+            fallible: true,
+            ..Default::default()
+        },
+    }];
+
+    #[test]
+    fn pseudonymize_ip() {
+        let pseudonym = |key: &str| {
+            let mut mac = Hmac::<Sha256>::new_varkey(key.as_bytes()).unwrap();
+            mac.update(b"2001:db8::1");
+            Value::from(hex::encode(mac.finalize().into_bytes()))
+        };
+
+        let cases = vec![
+            (
+                btreemap! { "foo" => "2001:db8::1" },
+                Ok(pseudonym("secret")),
+                PseudonymizeIpFn::new(
+                    Box::new(Path::from("foo")),
+                    Box::new(Literal::from("secret")),
+                ),
+            ),
+            (
+                btreemap! { "foo" => "2001:0db8:0000::0001" },
+                Ok(pseudonym("secret")),
+                PseudonymizeIpFn::new(
+                    Box::new(Path::from("foo")),
+                    Box::new(Literal::from("secret")),
+                ),
+            ),
+            (
+                btreemap! { "foo" => "2001:db8::1" },
+                Ok(pseudonym("other")),
+                PseudonymizeIpFn::new(
+                    Box::new(Path::from("foo")),
+                    Box::new(Literal::from("other")),
+                ),
+            ),
+            (
+                btreemap! { "foo" => "2001:db8::1" },
+                Err("function call error: key must not be empty".into()),
+                PseudonymizeIpFn::new(Box::new(Path::from("foo")), Box::new(Literal::from(""))),
+            ),
+        ];
+
+        let mut state = state::Program::default();
+
+        for (object, exp, func) in cases {
+            let mut object = Value::Map(object);
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
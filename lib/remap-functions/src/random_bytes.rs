@@ -0,0 +1,94 @@
+use rand::RngCore;
+use remap::prelude::*;
+
+const MAX_LENGTH: i64 = 64 * 1024;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RandomBytes;
+
+impl Function for RandomBytes {
+    fn identifier(&self) -> &'static str {
+        "random_bytes"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "length",
+            accepts: |v| matches!(v, Value::Integer(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let length = arguments.required("length")?.boxed();
+
+        Ok(Box::new(RandomBytesFn { length }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RandomBytesFn {
+    length: Box<dyn Expression>,
+}
+
+impl Expression for RandomBytesFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let length = match self.length.execute(state, object)?.try_integer()? {
+            length if length < 0 || length > MAX_LENGTH => {
+                return Err(format!("length must be between 0 and {}", MAX_LENGTH).into())
+            }
+            length => length as usize,
+        };
+
+        let mut bytes = vec![0; length];
+        rand::thread_rng().fill_bytes(&mut bytes);
+
+        Ok(bytes.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef {
+            fallible: true,
+            kind: value::Kind::Bytes,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    remap::test_type_def![static_def {
+        expr: |_| RandomBytesFn {
+            length: Literal::from(16).boxed(),
+        },
+        def: TypeDef {
+            fallible: true,
+            kind: value::Kind::Bytes,
+            ..Default::default()
+        },
+    }];
+
+    fn random(length: i64) -> Result<Value> {
+        RandomBytesFn {
+            length: Literal::from(length).boxed(),
+        }
+        .execute(
+            &mut state::Program::default(),
+            &mut Value::Map(Default::default()),
+        )
+    }
+
+    #[test]
+    fn random_bytes() {
+        let first = random(16).unwrap().try_bytes().unwrap();
+        let second = random(16).unwrap().try_bytes().unwrap();
+        assert_eq!(first.len(), 16);
+        assert_ne!(first, second);
+
+        assert_eq!(random(0).unwrap(), Value::from(Vec::<u8>::new()));
+        assert!(random(-1).is_err());
+        assert!(random(MAX_LENGTH + 1).is_err());
+    }
+}
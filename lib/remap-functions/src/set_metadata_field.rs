@@ -0,0 +1,87 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct SetMetadataField;
+
+impl Function for SetMetadataField {
+    fn identifier(&self) -> &'static str {
+        "set_metadata_field"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "key",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "value",
+                accepts: |_| true,
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let key = arguments.required("key")?.boxed();
+        let value = arguments.required("value")?.boxed();
+
+        Ok(Box::new(SetMetadataFieldFn { key, value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SetMetadataFieldFn {
+    key: Box<dyn Expression>,
+    value: Box<dyn Expression>,
+}
+
+impl Expression for SetMetadataFieldFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let bytes = self.key.execute(state, object)?.try_bytes()?;
+        let key = String::from_utf8_lossy(&bytes);
+        let value = self.value.execute(state, object)?;
+
+        object.set_metadata(&key, value)?;
+        Ok(Value::Null)
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.key
+            .type_def(state)
+            .merge(self.value.type_def(state))
+            .into_fallible(true)
+            .with_constraint(value::Kind::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    remap::test_type_def![value_string {
+        expr: |_| SetMetadataFieldFn {
+            key: Literal::from("priority").boxed(),
+            value: Literal::from("low").boxed(),
+        },
+        def: TypeDef {
+            fallible: true,
+            kind: value::Kind::Null,
+            ..Default::default()
+        },
+    }];
+
+    #[test]
+    fn set_metadata_field() {
+        let mut state = state::Program::default();
+        let func = SetMetadataFieldFn {
+            key: Literal::from("priority").boxed(),
+            value: Literal::from("low").boxed(),
+        };
+
+        // Values have no metadata.
+        let mut object = Value::from("value");
+        assert!(func.execute(&mut state, &mut object).is_err());
+    }
+}
@@ -40,6 +40,7 @@ impl Function for ToUnixTimestamp {
 enum Unit {
     Seconds,
     Milliseconds,
+    Microseconds,
     Nanoseconds,
 }
 
@@ -47,7 +48,7 @@ impl Unit {
     fn all_str() -> Vec<&'static str> {
         use Unit::*;
 
-        vec![Seconds, Milliseconds, Nanoseconds]
+        vec![Seconds, Milliseconds, Microseconds, Nanoseconds]
             .into_iter()
             .map(|u| u.as_str())
             .collect::<Vec<_>>()
@@ -59,6 +60,7 @@ impl Unit {
         match self {
             Seconds => "seconds",
             Milliseconds => "milliseconds",
+            Microseconds => "microseconds",
             Nanoseconds => "nanoseconds",
         }
     }
@@ -79,6 +81,7 @@ impl FromStr for Unit {
         match s {
             "seconds" => Ok(Seconds),
             "milliseconds" => Ok(Milliseconds),
+            "microseconds" => Ok(Microseconds),
             "nanoseconds" => Ok(Nanoseconds),
             _ => Err("unit not recognized"),
         }
@@ -105,6 +108,9 @@ impl Expression for ToUnixTimestampFn {
         let time = match self.unit {
             Unit::Seconds => ts.timestamp(),
             Unit::Milliseconds => ts.timestamp_millis(),
+            Unit::Microseconds => {
+                ts.timestamp() * 1_000_000 + i64::from(ts.timestamp_subsec_micros())
+            }
             Unit::Nanoseconds => ts.timestamp_nanos(),
         };
 
@@ -170,6 +176,23 @@ mod test {
                     Unit::Milliseconds,
                 ),
             ),
+            (
+                btreemap! {},
+                Ok(1609459200000000i64.into()),
+                ToUnixTimestampFn::new(
+                    Literal::from(chrono::Utc.ymd(2021, 1, 1).and_hms_milli(0, 0, 0, 0)).boxed(),
+                    Unit::Microseconds,
+                ),
+            ),
+            (
+                btreemap! {},
+                Ok(1609459200123456i64.into()),
+                ToUnixTimestampFn::new(
+                    Literal::from(chrono::Utc.ymd(2021, 1, 1).and_hms_micro(0, 0, 0, 123456))
+                        .boxed(),
+                    Unit::Microseconds,
+                ),
+            ),
             (
                 btreemap! {},
                 Ok(1609459200000000000i64.into()),
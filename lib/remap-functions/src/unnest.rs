@@ -0,0 +1,123 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Unnest;
+
+impl Function for Unnest {
+    fn identifier(&self) -> &'static str {
+        "unnest"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "path",
+            accepts: |_| true,
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let path = arguments.required_path("path")?;
+
+        Ok(Box::new(UnnestFn { path }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct UnnestFn {
+    path: Path,
+}
+
+impl UnnestFn {
+    #[cfg(test)]
+    fn new(path: &str) -> Self {
+        use std::str::FromStr;
+
+        Self {
+            path: remap::Path::from_str(path).unwrap().into(),
+        }
+    }
+}
+
+impl Expression for UnnestFn {
+    fn execute(&self, _: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let root = object
+            .get(&remap::Path::root())?
+            .unwrap_or_else(|| Value::Map(Default::default()));
+        let values = match object.get(self.path.as_ref())? {
+            Some(value) => value.try_array()?,
+            None => return Err(format!("{} does not exist", self.path).into()),
+        };
+
+        // One copy of the object for each item of the array, the array being
+        // replaced by the item.
+        values
+            .into_iter()
+            .map(|value| {
+                let mut copy = root.clone();
+                copy.insert(self.path.as_ref(), value)?;
+                Ok(copy)
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Into::into)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef {
+            fallible: true,
+            kind: value::Kind::Array,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    #[test]
+    fn unnest() {
+        let cases = vec![
+            (
+                btreemap! { "host" => "localhost", "events" => vec![value!({id: 1}), value!({id: 2})] },
+                Ok(value!([
+                    {host: "localhost", events: {id: 1}},
+                    {host: "localhost", events: {id: 2}},
+                ])),
+                UnnestFn::new(".events"),
+            ),
+            (
+                btreemap! { "nested" => btreemap! { "values" => vec![1, 2] } },
+                Ok(value!([{nested: {values: 1}}, {nested: {values: 2}}])),
+                UnnestFn::new(".nested.values"),
+            ),
+            (
+                btreemap! { "events" => Vec::<Value>::new() },
+                Ok(value!([])),
+                UnnestFn::new(".events"),
+            ),
+            (
+                btreemap! {},
+                Err("function call error: .events does not exist".to_owned()),
+                UnnestFn::new(".events"),
+            ),
+            (
+                btreemap! { "events" => "foo" },
+                Err(r#"value error: expected "array", got "string""#.to_owned()),
+                UnnestFn::new(".events"),
+            ),
+        ];
+
+        let mut state = state::Program::default();
+
+        for (object, exp, func) in cases {
+            let mut object = Value::Map(object);
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
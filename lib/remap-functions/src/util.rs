@@ -130,3 +130,111 @@ impl FromStr for Base64Charset {
         }
     }
 }
+
+/// Normalizes a hex trace id to the 32 lowercase characters used by W3C trace
+/// context. 64-bit ids, as used by B3 and Datadog, are left-padded with zeros.
+#[cfg(any(
+    feature = "format_traceparent",
+    feature = "parse_traceparent",
+    feature = "parse_trace_headers"
+))]
+pub(crate) fn normalize_trace_id(id: &str) -> Option<String> {
+    let id = id.to_ascii_lowercase();
+    let id = match id.len() {
+        16 => format!("{:0>32}", id),
+        32 => id,
+        _ => return None,
+    };
+    if is_valid_hex_id(&id) {
+        Some(id)
+    } else {
+        None
+    }
+}
+
+/// Normalizes a hex span id to 16 lowercase characters.
+#[cfg(any(
+    feature = "format_traceparent",
+    feature = "parse_traceparent",
+    feature = "parse_trace_headers"
+))]
+pub(crate) fn normalize_span_id(id: &str) -> Option<String> {
+    let id = id.to_ascii_lowercase();
+    if id.len() == 16 && is_valid_hex_id(&id) {
+        Some(id)
+    } else {
+        None
+    }
+}
+
+/// Ids must be hex and not all zeros, which is used to represent an invalid id.
+#[cfg(any(
+    feature = "format_traceparent",
+    feature = "parse_traceparent",
+    feature = "parse_trace_headers"
+))]
+fn is_valid_hex_id(id: &str) -> bool {
+    id.bytes().all(|b| b.is_ascii_hexdigit()) && id.bytes().any(|b| b != b'0')
+}
+
+/// A trace context parsed from a `traceparent` header.
+#[cfg(any(feature = "parse_traceparent", feature = "parse_trace_headers"))]
+#[derive(Debug, PartialEq)]
+pub(crate) struct TraceParent {
+    pub version: u8,
+    pub trace_id: String,
+    pub span_id: String,
+    pub flags: u8,
+}
+
+#[cfg(any(feature = "parse_traceparent", feature = "parse_trace_headers"))]
+impl TraceParent {
+    pub(crate) fn sampled(&self) -> bool {
+        self.flags & 0x01 == 0x01
+    }
+}
+
+/// Parses a `traceparent` header as described in
+/// <https://www.w3.org/TR/trace-context/#traceparent-header>.
+#[cfg(any(feature = "parse_traceparent", feature = "parse_trace_headers"))]
+pub(crate) fn parse_traceparent(header: &str) -> std::result::Result<TraceParent, &'static str> {
+    let parts = header.trim().split('-').collect::<Vec<_>>();
+    if parts.len() < 4 {
+        return Err("expected 4 dash-separated fields");
+    }
+
+    let byte = |part: &str| -> Option<u8> {
+        if part.len() == 2 && part.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+            u8::from_str_radix(part, 16).ok()
+        } else {
+            None
+        }
+    };
+
+    let version = byte(parts[0]).ok_or("invalid version")?;
+    if version == 0xff {
+        return Err("invalid version");
+    }
+    // Future versions may append fields, version 00 has exactly four.
+    if version == 0 && parts.len() != 4 {
+        return Err("expected 4 dash-separated fields");
+    }
+
+    let is_lowercase = |part: &str| !part.bytes().any(|b| b.is_ascii_uppercase());
+    let trace_id = Some(parts[1])
+        .filter(|id| id.len() == 32 && is_lowercase(id))
+        .and_then(normalize_trace_id)
+        .ok_or("invalid trace id")?;
+    let span_id = Some(parts[2])
+        .filter(|id| is_lowercase(id))
+        .and_then(normalize_span_id)
+        .ok_or("invalid span id")?;
+    let flags = byte(parts[3]).ok_or("invalid flags")?;
+
+    Ok(TraceParent {
+        version,
+        trace_id,
+        span_id,
+        flags,
+    })
+}
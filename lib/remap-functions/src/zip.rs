@@ -0,0 +1,116 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Zip;
+
+impl Function for Zip {
+    fn identifier(&self) -> &'static str {
+        "zip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "array1",
+                accepts: |v| matches!(v, Value::Array(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "array2",
+                accepts: |v| matches!(v, Value::Array(_)),
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let array1 = arguments.required("array1")?.boxed();
+        let array2 = arguments.required("array2")?.boxed();
+
+        Ok(Box::new(ZipFn { array1, array2 }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ZipFn {
+    array1: Box<dyn Expression>,
+    array2: Box<dyn Expression>,
+}
+
+impl Expression for ZipFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let array1 = self.array1.execute(state, object)?.try_array()?;
+        let array2 = self.array2.execute(state, object)?.try_array()?;
+
+        Ok(array1
+            .into_iter()
+            .zip(array2)
+            .map(|(a, b)| Value::from(vec![a, b]))
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        let fallible = self
+            .array1
+            .type_def(state)
+            .merge(self.array2.type_def(state))
+            .fallible_unless(value::Kind::Array)
+            .is_fallible();
+
+        TypeDef {
+            fallible,
+            kind: value::Kind::Array,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        zip => Zip;
+
+        same_length {
+            args: func_args![array1: array![1, 2], array2: array!["a", "b"]],
+            want: Ok(value!([[1, "a"], [2, "b"]])),
+        }
+
+        truncated_to_shorter {
+            args: func_args![array1: array![1, 2, 3], array2: array!["a"]],
+            want: Ok(value!([[1, "a"]])),
+        }
+
+        empty {
+            args: func_args![array1: array![], array2: array!["a"]],
+            want: Ok(value!([])),
+        }
+    ];
+
+    remap::test_type_def![
+        arrays {
+            expr: |_| ZipFn {
+                array1: Array::from(vec![1]).boxed(),
+                array2: Array::from(vec!["a"]).boxed(),
+            },
+            def: TypeDef {
+                kind: value::Kind::Array,
+                ..Default::default()
+            },
+        }
+
+        non_array {
+            expr: |_| ZipFn {
+                array1: Literal::from("foo").boxed(),
+                array2: Array::from(vec!["a"]).boxed(),
+            },
+            def: TypeDef {
+                fallible: true,
+                kind: value::Kind::Array,
+                ..Default::default()
+            },
+        }
+    ];
+}
@@ -0,0 +1,64 @@
+use crate::Span;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+/// Counts how often each statement of a program is executed.
+///
+/// Clones share their counts, so a single `Coverage` can be attached to every
+/// copy of a program to measure all of them together.
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    /// Executions, by the start of the span of the executed statement.
+    hits: Arc<Mutex<BTreeMap<usize, u64>>>,
+}
+
+impl Coverage {
+    pub(crate) fn hit(&self, span: Span) {
+        *self
+            .hits
+            .lock()
+            .expect("Couldn't acquire lock on coverage")
+            .entry(span.start)
+            .or_default() += 1;
+    }
+
+    /// Returns the lines of `source` holding the start of one of `statements`,
+    /// each with the number of times the statements starting on it were
+    /// executed. Lines are numbered from one.
+    pub(crate) fn lines(&self, source: &str, statements: &[Span]) -> BTreeMap<usize, u64> {
+        let hits = self.hits.lock().expect("Couldn't acquire lock on coverage");
+
+        let mut lines = BTreeMap::new();
+        for span in statements {
+            let line = 1 + source[..span.start].matches('\n').count();
+            *lines.entry(line).or_default() += hits.get(&span.start).copied().unwrap_or_default();
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Coverage, Program, Runtime, Value};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn counts_statements_by_line() {
+        let source = ".a = 1\nif .a == 2 {\n  .b = 2\n} else {\n  .c = 3\n}\n";
+        let (mut program, _) = Program::new(source.to_owned(), &[], None, false).unwrap();
+        program.collect_coverage(Coverage::default());
+
+        for _ in 0..2 {
+            let mut object = Value::Map(BTreeMap::new());
+            Runtime::default().run(&mut object, &program).unwrap();
+        }
+
+        let lines = program.line_coverage().unwrap();
+        assert_eq!(
+            lines.into_iter().collect::<Vec<_>>(),
+            vec![(1, 2), (2, 2), (3, 0), (5, 2)]
+        );
+    }
+}
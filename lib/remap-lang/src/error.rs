@@ -18,6 +18,9 @@ pub enum Error {
 
     #[error("assertion failed: {0}")]
     Assert(String),
+
+    #[error("aborted")]
+    Abort,
 }
 
 impl From<String> for Error {
@@ -59,6 +62,7 @@ impl fmt::Display for Rule {
         }
 
         rules_str![
+            abort,
             addition,
             argument,
             arguments,
@@ -71,6 +75,7 @@ impl fmt::Display for Rule {
             call,
             char,
             comparison,
+            definitions,
             EOE: "",
             EOI: "",
             empty_line,
@@ -79,11 +84,13 @@ impl fmt::Display for Rule {
             expressions,
             field,
             float,
+            function_definition,
             group,
             ident: "",
             if_condition,
             if_statement: "if-statement",
             integer,
+            keyword_fn: "fn",
             kv_pair,
             map,
             multiplication,
@@ -95,6 +102,7 @@ impl fmt::Display for Rule {
             operator_equality: "",
             operator_multiplication: "operator",
             operator_not: "function call, value, variable, path, group, !",
+            parameters,
             path,
             path_coalesce: "coalesced path",
             path_field,
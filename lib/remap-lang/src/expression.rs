@@ -2,6 +2,7 @@ use crate::{state, Object, Result, TypeDef, Value};
 use std::convert::TryFrom;
 use std::fmt;
 
+mod abort;
 mod argument;
 mod arithmetic;
 mod array;
@@ -14,8 +15,10 @@ mod map;
 mod noop;
 mod not;
 pub(crate) mod path;
+pub(crate) mod user_function;
 mod variable;
 
+pub use abort::Abort;
 pub use argument::Argument;
 pub use arithmetic::Arithmetic;
 pub use array::Array;
@@ -28,6 +31,7 @@ pub use map::Map;
 pub use noop::Noop;
 pub use not::Not;
 pub use path::Path;
+pub use user_function::UserFunction;
 pub use variable::Variable;
 
 #[derive(thiserror::Error, Clone, Debug, PartialEq)]
@@ -145,6 +149,7 @@ macro_rules! expression_dispatch {
 }
 
 expression_dispatch![
+    Abort,
     Argument,
     Arithmetic,
     Array,
@@ -157,6 +162,7 @@ expression_dispatch![
     Noop,
     Not,
     Path,
+    UserFunction,
     Variable,
 ];
 
@@ -0,0 +1,33 @@
+use crate::{state, value, Error, Expression, Object, Result, TypeDef, Value};
+
+/// Stops the program, which the runtime reports apart from its errors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Abort;
+
+impl Expression for Abort {
+    fn execute(&self, _: &mut state::Program, _: &mut dyn Object) -> Result<Value> {
+        Err(Error::Abort)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        // Aborting is deliberate, so it isn't an error to handle.
+        TypeDef {
+            kind: value::Kind::Null,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_type_def;
+
+    test_type_def![abort {
+        expr: |_| Abort,
+        def: TypeDef {
+            kind: value::Kind::Null,
+            ..Default::default()
+        },
+    }];
+}
@@ -19,10 +19,10 @@ impl Expression for Arithmetic {
         use Operator::*;
 
         if matches!(self.op, ErrorOr) {
-            return self
-                .lhs
-                .execute(state, object)
-                .or_else(|_| self.rhs.execute(state, object));
+            return self.lhs.execute(state, object).or_else(|err| match err {
+                crate::Error::Abort => Err(err),
+                _ => self.rhs.execute(state, object),
+            });
         }
 
         let lhs = self.lhs.execute(state, object)?;
@@ -152,6 +152,8 @@ impl Expression for Assignment {
             Target::Infallible { ok, err } => {
                 let (ok_value, err_value) = match value {
                     Ok(value) => (value, Value::Null),
+                    // Aborting stops the program, whatever handles its errors.
+                    Err(crate::Error::Abort) => return Err(crate::Error::Abort),
                     Err(err) => (Value::Null, Value::from(err)),
                 };
 
@@ -1,13 +1,25 @@
-use crate::{state, value, Expr, Expression, Object, Result, TypeDef, Value};
+use crate::{state, value, Expr, Expression, Object, Result, Span, TypeDef, Value};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Block {
     expressions: Vec<Expr>,
+
+    /// The spans of `expressions` in the program source, used to collect
+    /// coverage. Blocks that aren't parsed from source have none.
+    spans: Vec<Span>,
 }
 
 impl Block {
     pub fn new(expressions: Vec<Expr>) -> Self {
-        Self { expressions }
+        Self {
+            expressions,
+            spans: vec![],
+        }
+    }
+
+    pub(crate) fn with_spans(mut self, spans: Vec<Span>) -> Self {
+        self.spans = spans;
+        self
     }
 }
 
@@ -15,7 +27,13 @@ impl Expression for Block {
     fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
         self.expressions
             .iter()
-            .map(|expr| expr.execute(state, object))
+            .enumerate()
+            .map(|(i, expr)| {
+                if let Some(span) = self.spans.get(i) {
+                    state.cover(*span);
+                }
+                expr.execute(state, object)
+            })
             .collect::<Result<Vec<_>>>()
             .map(|mut v| v.pop().unwrap_or(Value::Null))
     }
@@ -0,0 +1,269 @@
+use crate::{state, Expr, Expression, Object, Result, TypeDef, Value};
+use std::sync::Arc;
+
+#[derive(thiserror::Error, Clone, Debug, PartialEq)]
+pub enum Error {
+    #[error("invalid argument count (expected {expected}, got {got})")]
+    ArityMismatch { expected: usize, got: usize },
+
+    #[error(r#"unknown argument keyword "{0}""#)]
+    UnknownKeyword(String),
+
+    #[error(r#"argument "{0}" provided more than once"#)]
+    DuplicateArg(String),
+
+    #[error(r#"missing required argument "{0}""#)]
+    MissingArg(String),
+
+    #[error(r#"cannot mark infallible function as "abort on error", remove the "!" signature"#)]
+    AbortInfallible,
+}
+
+/// A function defined at the top of the program source, using the `fn`
+/// keyword.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Definition {
+    ident: String,
+    parameters: Vec<String>,
+    body: Expr,
+
+    /// The type definition of the body, resolved when the function is defined,
+    /// with each parameter accepting any value.
+    type_def: TypeDef,
+}
+
+impl Definition {
+    pub fn new(ident: String, parameters: Vec<String>, body: Expr, type_def: TypeDef) -> Self {
+        Self {
+            ident,
+            parameters,
+            body,
+            type_def,
+        }
+    }
+
+    pub fn ident(&self) -> &str {
+        &self.ident
+    }
+
+    pub fn parameters(&self) -> &[String] {
+        &self.parameters
+    }
+}
+
+/// A call to a function defined in the program source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserFunction {
+    definition: Arc<Definition>,
+
+    /// The arguments, in the order of the parameters of the definition.
+    arguments: Vec<Expr>,
+
+    // If set to true, and the function fails at runtime, the program aborts.
+    abort_on_error: bool,
+}
+
+impl UserFunction {
+    pub fn new(
+        definition: Arc<Definition>,
+        abort_on_error: bool,
+        arguments: Vec<(Option<String>, Expr)>,
+        state: &state::Compiler,
+    ) -> std::result::Result<Self, Error> {
+        let parameters = definition.parameters();
+
+        // Unlike the standard library functions, all parameters are required.
+        if arguments.len() > parameters.len() {
+            return Err(Error::ArityMismatch {
+                expected: parameters.len(),
+                got: arguments.len(),
+            });
+        }
+
+        let mut slots: Vec<Option<Expr>> = vec![None; parameters.len()];
+        let mut index = 0;
+
+        for (keyword, argument) in arguments {
+            let position = match keyword {
+                None => {
+                    index += 1;
+                    index - 1
+                }
+                Some(keyword) => {
+                    let position = parameters
+                        .iter()
+                        .position(|parameter| parameter == &keyword)
+                        .ok_or(Error::UnknownKeyword(keyword))?;
+
+                    if position == index {
+                        index += 1;
+                    }
+
+                    position
+                }
+            };
+
+            if slots[position].is_some() {
+                return Err(Error::DuplicateArg(parameters[position].clone()));
+            }
+            slots[position] = Some(argument);
+        }
+
+        let arguments = slots
+            .into_iter()
+            .zip(parameters)
+            .map(|(argument, parameter)| {
+                argument.ok_or_else(|| Error::MissingArg(parameter.clone()))
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let function = Self {
+            definition,
+            arguments,
+            abort_on_error: false,
+        };
+
+        // Asking for an infallible function to abort on error makes no sense,
+        // same as for the standard library functions.
+        if abort_on_error && !function.type_def(state).is_fallible() {
+            return Err(Error::AbortInfallible);
+        }
+
+        Ok(Self {
+            abort_on_error,
+            ..function
+        })
+    }
+
+    pub fn ident(&self) -> &str {
+        self.definition.ident()
+    }
+
+    /// If `true`, the function asks the program to abort when it raises an error.
+    pub fn abort_on_error(&self) -> bool {
+        self.abort_on_error
+    }
+}
+
+impl Expression for UserFunction {
+    /// Runs the body of the function with its parameters as the only
+    /// variables, restoring the variables of the caller afterwards.
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let arguments = self
+            .arguments
+            .iter()
+            .map(|argument| argument.execute(state, object))
+            .collect::<Result<Vec<_>>>()?;
+
+        let variables = self
+            .definition
+            .parameters
+            .iter()
+            .cloned()
+            .zip(arguments)
+            .collect();
+
+        let caller = std::mem::replace(state.variables_mut(), variables);
+        let result = self.definition.body.execute(state, object);
+        *state.variables_mut() = caller;
+
+        result
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        let mut type_def = self.definition.type_def.clone();
+
+        type_def.fallible = !self.abort_on_error
+            && (type_def.fallible
+                || self
+                    .arguments
+                    .iter()
+                    .any(|argument| argument.type_def(state).is_fallible()));
+
+        type_def
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        expression::{Arithmetic, Literal, Variable},
+        value::Kind,
+        Operator,
+    };
+
+    fn definition() -> Arc<Definition> {
+        let body = Arithmetic::new(
+            Box::new(Variable::new("a".to_owned(), None).into()),
+            Box::new(Variable::new("b".to_owned(), None).into()),
+            Operator::Add,
+        );
+
+        Arc::new(Definition::new(
+            "add".to_owned(),
+            vec!["a".to_owned(), "b".to_owned()],
+            body.into(),
+            TypeDef {
+                fallible: true,
+                kind: Kind::Bytes | Kind::Integer | Kind::Float,
+                ..Default::default()
+            },
+        ))
+    }
+
+    #[test]
+    fn maps_arguments() {
+        let state = state::Compiler::default();
+        let arguments = vec![
+            (Some("b".to_owned()), Literal::from(2).into()),
+            (None, Literal::from(1).into()),
+        ];
+
+        let function = UserFunction::new(definition(), true, arguments, &state).unwrap();
+        let mut program = state::Program::default();
+        program
+            .variables_mut()
+            .insert("a".to_owned(), "caller".into());
+
+        assert_eq!(
+            function.execute(&mut program, &mut Value::Null),
+            Ok(3.into())
+        );
+        assert_eq!(program.variable("a"), Some(&"caller".into()));
+        assert_eq!(program.variable("b"), None);
+        assert!(!function.type_def(&state).is_fallible());
+    }
+
+    #[test]
+    fn checks_arguments() {
+        let state = state::Compiler::default();
+        let call = |arguments: Vec<(Option<&str>, i64)>| {
+            let arguments = arguments
+                .into_iter()
+                .map(|(keyword, value)| (keyword.map(Into::into), Literal::from(value).into()))
+                .collect();
+            UserFunction::new(definition(), false, arguments, &state).map(|_| ())
+        };
+
+        assert_eq!(
+            call(vec![(None, 1), (None, 2), (None, 3)]),
+            Err(Error::ArityMismatch {
+                expected: 2,
+                got: 3
+            })
+        );
+        assert_eq!(
+            call(vec![(None, 1), (Some("c"), 2)]),
+            Err(Error::UnknownKeyword("c".to_owned()))
+        );
+        assert_eq!(
+            call(vec![(None, 1), (Some("a"), 2)]),
+            Err(Error::DuplicateArg("a".to_owned()))
+        );
+        assert_eq!(
+            call(vec![(None, 1)]),
+            Err(Error::MissingArg("b".to_owned()))
+        );
+    }
+}
@@ -1,3 +1,4 @@
+mod coverage;
 mod error;
 mod operator;
 mod parser;
@@ -15,6 +16,7 @@ pub mod prelude;
 pub mod state;
 pub mod value;
 
+pub use coverage::Coverage;
 pub use diagnostic::{Diagnostic, DiagnosticList, Formatter, Span};
 pub use error::Error;
 pub use expression::{Expr, Expression};
@@ -23,7 +25,7 @@ pub use object::Object;
 pub use operator::Operator;
 pub use path::{Field, Path, Segment};
 pub use program::{Program, TypeConstraint};
-pub use runtime::{Runtime, RuntimeResult};
+pub use runtime::{Abort, Runtime, RuntimeResult};
 pub use type_def::{InnerTypeDef, TypeDef};
 pub use value::Value;
 
@@ -290,6 +292,9 @@ mod tests {
             // ("false * 5 ?? true * 5", Ok(()), Err("remap error: value error: unable to multiply value type boolean by integer")),
             ("5 + (true * 5 ?? 0)", Ok(()), Ok(value!(5))),
             ("fallible_func!()", Ok(()), Err("function call error: failed!")),
+            ("abort", Ok(()), Err("aborted")),
+            (r#".foo = "bar"; if true { abort }; .foo = "baz""#, Ok(()), Err("aborted")),
+            ("(abort) ?? true", Ok(()), Err("aborted")),
             // TODO: move to `remap-tests`
             // ("fallible_func()", Ok(()), Err("remap error: function call error: failed!")),
             // (
@@ -346,6 +351,36 @@ mod tests {
             ("two_param_func(true, true)", Ok(()), Ok(value!(null))),
             ("two_param_func(.foo, param2: true)", Ok(()), Ok(value!(null))),
             ("two_param_func(param2: .foo, param1: true)", Ok(()), Ok(value!(null))),
+            (
+                r#"
+                    fn greet(name) { "hello " + name }
+                    greet!("world")
+                "#,
+                Ok(()),
+                Ok(value!("hello world")),
+            ),
+            (
+                r#"
+                    fn wrap(value) {
+                        inner = value
+                        [inner, "wrapped"]
+                    }
+
+                    inner = "outer"
+                    [wrap(value: .foo.bar), inner]
+                "#,
+                Ok(()),
+                Ok(value!([["baz", "wrapped"], "outer"])),
+            ),
+            (
+                r#"
+                    fn first(a, b) { a }
+                    fn second(a, b) { first(b, a) }
+                    second(1, 2)
+                "#,
+                Ok(()),
+                Ok(value!(2)),
+            ),
         ];
 
         for (script, compile_expected, runtime_expected) in cases {
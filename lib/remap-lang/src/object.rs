@@ -51,4 +51,19 @@ pub trait Object: std::fmt::Debug {
     /// If `compact` is true, after deletion, if an empty object or array is
     /// left behind, it should be removed as well.
     fn remove(&mut self, path: &Path, compact: bool) -> Result<Option<Value>, String>;
+
+    /// Get the value of a metadata field of the object, which isn't part of
+    /// the object itself.
+    ///
+    /// Objects without metadata return an error.
+    fn get_metadata(&self, key: &str) -> Result<Option<Value>, String> {
+        Err(format!("unknown metadata field: {}", key))
+    }
+
+    /// Set the value of a metadata field of the object.
+    ///
+    /// Objects without metadata return an error.
+    fn set_metadata(&mut self, key: &str, _value: Value) -> Result<(), String> {
+        Err(format!("unknown metadata field: {}", key))
+    }
 }
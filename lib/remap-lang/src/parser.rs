@@ -3,17 +3,19 @@
 use crate::{
     diagnostic::{self, Diagnostic, DiagnosticList, Label, Note, Span},
     expression::{
-        self, function, if_statement::IfCondition, Arithmetic, Array, Assignment, Block, Function,
-        IfStatement, Literal, Map, Noop, Not, Path, Target, Variable,
+        self, function, if_statement::IfCondition, user_function, Abort, Arithmetic, Array,
+        Assignment, Block, Function, IfStatement, Literal, Map, Noop, Not, Path, Target,
+        UserFunction, Variable,
     },
-    path, state, Expr, Expression, Function as Fn, Operator, Value,
+    path, state, Expr, Expression, Function as Fn, Operator, TypeDef, Value,
 };
 use pest::error::InputLocation;
 use pest::iterators::{Pair, Pairs};
 use regex::{Regex, RegexBuilder};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
+use std::sync::Arc;
 
 pub(crate) type R = Rule;
 type IResult<T> = Result<ParsedNode<T>, ParserBug>;
@@ -40,6 +42,14 @@ pub(super) struct Parser<'a> {
     /// All parsing functions take `self` such that this state cannot leak into
     /// subsequent parsing calls.
     diagnostics: DiagnosticList,
+
+    /// The spans of all statements of the program, both at the top level and
+    /// within blocks, which coverage is collected for.
+    statements: Vec<Span>,
+
+    /// The functions defined in the program source, callable by the
+    /// expressions following their definition.
+    user_functions: HashMap<String, Arc<user_function::Definition>>,
 }
 
 impl<'a> From<&Pair<'a, R>> for Span {
@@ -277,14 +287,17 @@ impl<'a> Parser<'a> {
             allow_regex_return,
             compiler_state,
             diagnostics: DiagnosticList::default(),
+            statements: vec![],
+            user_functions: HashMap::default(),
         }
     }
 
-    /// Parse a source string into a valid [`Program`].
+    /// Parse a source string into a valid [`Program`], returning its
+    /// expressions along with the spans of all of its statements.
     pub(crate) fn program_from_str(
         mut self,
         source: &'a str,
-    ) -> diagnostic::Result<Vec<ParsedExpression>> {
+    ) -> diagnostic::Result<(Vec<ParsedExpression>, Vec<Span>)> {
         let expressions = self
             .pairs_from_str(R::program, source)
             .and_then(|pairs| self.pairs_to_expressions(pairs.into_inner()))
@@ -295,7 +308,16 @@ impl<'a> Parser<'a> {
         match expressions {
             Err(_) => Err(self.diagnostics),
             Ok(_) if self.diagnostics.is_err() => Err(self.diagnostics),
-            Ok(expressions) => Ok((expressions, self.diagnostics)),
+            Ok(expressions) => {
+                let mut statements = expressions
+                    .iter()
+                    .map(ParsedExpression::span)
+                    .collect::<Vec<_>>();
+                statements.append(&mut self.statements);
+                statements.sort_by_key(|span| span.start);
+
+                Ok(((expressions, statements), self.diagnostics))
+            }
         }
     }
 
@@ -357,6 +379,7 @@ impl<'a> Parser<'a> {
                 R::assignment | R::boolean_expr | R::block | R::if_statement => {
                     nodes.push(self.expression_from_pair(pair)?)
                 }
+                R::function_definition => self.function_definition_from_pair(pair)?,
                 R::EOI => (),
                 _ => return Err(e(R::expression, &pair)),
             }
@@ -397,6 +420,7 @@ impl<'a> Parser<'a> {
     /// Given a `Pair`, build a boxed [`Expression`] trait object from it.
     fn expression_from_pair(&mut self, pair: Pair<R>) -> IResult<Expr> {
         match pair.as_rule() {
+            R::abort => Ok((Span::from(&pair), Abort).into()),
             R::assignment => self.assignment_from_pair(pair),
             R::boolean_expr => self.boolean_expr_from_pair(pair),
             R::block => self.block_from_pair(pair),
@@ -512,12 +536,97 @@ impl<'a> Parser<'a> {
     fn block_from_pair(&mut self, pair: Pair<R>) -> IResult<Expr> {
         let span = Span::from(&pair);
         let mut expressions = vec![];
+        let mut spans = vec![];
 
         for pair in pair.into_inner() {
-            expressions.push(self.expression_from_pair(pair)?.into_inner());
+            let (span, expression) = self.expression_from_pair(pair)?.take();
+            expressions.push(expression);
+            spans.push(span);
+        }
+        self.statements.extend(spans.iter().copied());
+
+        Ok((span, Block::new(expressions).with_spans(spans)).into())
+    }
+
+    /// Parse a function definition, making the function callable by the
+    /// expressions that follow it.
+    ///
+    /// The body is type checked in a scope of its own, where the parameters
+    /// can hold any value, and the paths of the event are of any type.
+    fn function_definition_from_pair(&mut self, pair: Pair<R>) -> Result<(), ParserBug> {
+        let span = Span::from(&pair);
+        let mut pairs = pair
+            .into_inner()
+            .filter(|pair| pair.as_rule() != R::keyword_fn);
+
+        let ident_pair = pairs.next().ok_or(e(R::function_definition, span))?;
+        let ident_span = Span::from(&ident_pair);
+        let ident = ident_pair.as_str().to_owned();
+
+        let mut parameters: Vec<String> = vec![];
+        let mut body = pairs.next().ok_or(e(R::function_definition, span))?;
+
+        if body.as_rule() == R::parameters {
+            for parameter in body.into_inner() {
+                let name = parameter.as_str().to_owned();
+
+                if parameters.contains(&name) {
+                    self.diagnostics.push(
+                        Diagnostic::error("duplicate function parameter").with_primary(
+                            format!(r#"parameter "{}" is already defined"#, name),
+                            Span::from(&parameter),
+                        ),
+                    );
+                } else {
+                    parameters.push(name);
+                }
+            }
+
+            body = pairs.next().ok_or(e(R::function_definition, span))?;
+        }
+
+        let variable_types = std::mem::take(self.compiler_state.variable_types_mut());
+        let path_query_types = std::mem::take(self.compiler_state.path_query_types_mut());
+
+        for parameter in &parameters {
+            self.compiler_state
+                .variable_types_mut()
+                .insert(parameter.clone(), TypeDef::default());
+        }
+
+        let body = self
+            .block_from_pair(body)
+            .map(ParsedNode::into_inner)
+            .map(|body| {
+                let type_def = body.type_def(&self.compiler_state);
+                (body, type_def)
+            });
+
+        *self.compiler_state.variable_types_mut() = variable_types;
+        *self.compiler_state.path_query_types_mut() = path_query_types;
+
+        let (body, type_def) = body?;
+
+        if self.user_functions.contains_key(&ident)
+            || self
+                .function_definitions
+                .iter()
+                .any(|function| function.identifier() == ident)
+        {
+            self.diagnostics.push(
+                Diagnostic::error("duplicate function definition").with_primary(
+                    format!(r#"function "{}" is already defined"#, ident),
+                    ident_span,
+                ),
+            );
+
+            return Ok(());
         }
 
-        Ok((span, Block::new(expressions)).into())
+        let definition = user_function::Definition::new(ident.clone(), parameters, body, type_def);
+        self.user_functions.insert(ident, Arc::new(definition));
+
+        Ok(())
     }
 
     /// Parse if-statement expressions.
@@ -757,6 +866,35 @@ impl<'a> Parser<'a> {
             .map(|s| s.take())
             .unwrap_or_else(|| (Span::default(), vec![]));
 
+        if let Some(definition) = self.user_functions.get(ident).cloned() {
+            let function =
+                UserFunction::new(definition, abort_on_error, arguments, &self.compiler_state);
+
+            let expression: Expr = match function {
+                Ok(function) => function.into(),
+                Err(err) => {
+                    self.compiler_state.revert_changes();
+
+                    self.diagnostics.push(match err {
+                        user_function::Error::AbortInfallible => {
+                            let bang_span = *ident_span.end() + 1..*ident_span.end() + 1;
+
+                            Diagnostic::error("cannot abort function that never fails")
+                                .with_primary("this function cannot fail", ident_span)
+                                .with_context("remove this abort-instruction", bang_span)
+                                .with_note(Note::SeeErrDocs)
+                        }
+                        err => Diagnostic::error("function argument error")
+                            .with_primary(err.to_string(), arguments_span),
+                    });
+
+                    Noop.into()
+                }
+            };
+
+            return Ok((span, expression).into());
+        }
+
         let function = Function::new(
             ident,
             abort_on_error,
@@ -1174,7 +1312,7 @@ mod tests {
                 .map_err(|err| diagnostic::Formatter::new(source, err).to_string());
 
             match pairs {
-                Ok((got, _)) => {
+                Ok(((got, _), _)) => {
                     if compile_check.is_empty() {
                         let got = got.into_iter().map(|e| e.expr).collect();
 
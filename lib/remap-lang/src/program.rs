@@ -1,8 +1,9 @@
 use crate::{
     diagnostic::{self, Note},
     parser::{ParsedExpression, Parser},
-    state, value, Diagnostic, Expression, Function, TypeDef,
+    state, value, Coverage, Diagnostic, Expression, Function, Span, TypeDef,
 };
+use std::collections::BTreeMap;
 
 /// The constraint applied to the result of a program.
 pub struct TypeConstraint {
@@ -34,6 +35,11 @@ pub struct TypeConstraint {
 pub struct Program {
     pub(crate) source: String,
     pub(crate) expressions: Vec<ParsedExpression>,
+
+    /// The spans of all statements of the program, ordered by position.
+    statements: Vec<Span>,
+
+    pub(crate) coverage: Option<Coverage>,
 }
 
 impl Program {
@@ -46,7 +52,7 @@ impl Program {
     ) -> diagnostic::Result<Self> {
         let parser = Parser::new(function_definitions, state, allow_regex_return);
 
-        let (expressions, mut diagnostics) = parser.program_from_str(&source)?;
+        let ((expressions, statements), mut diagnostics) = parser.program_from_str(&source)?;
 
         // optional type constraint checking
         if let Some(constraint) = constraint {
@@ -92,6 +98,8 @@ impl Program {
         let program = Self {
             source,
             expressions,
+            statements,
+            coverage: None,
         };
 
         diagnostics
@@ -118,6 +126,21 @@ impl Program {
     pub fn expressions(&self) -> &[ParsedExpression] {
         &self.expressions
     }
+
+    /// Counts the executions of the statements of the program in `coverage`,
+    /// which may be shared with other copies of the program.
+    pub fn collect_coverage(&mut self, coverage: Coverage) {
+        self.coverage = Some(coverage);
+    }
+
+    /// Returns the lines of the program holding a statement, each with the
+    /// number of times its statements were executed, or `None` if coverage
+    /// isn't collected.
+    pub fn line_coverage(&self) -> Option<BTreeMap<usize, u64>> {
+        self.coverage
+            .as_ref()
+            .map(|coverage| coverage.lines(&self.source, &self.statements))
+    }
 }
 
 #[cfg(test)]
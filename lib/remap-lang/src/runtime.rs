@@ -1,4 +1,4 @@
-use crate::{state, Expression, Object, Program, Value};
+use crate::{state, Error as ProgramError, Expression, Object, Program, Value};
 use std::{error::Error, fmt};
 
 pub type RuntimeResult = Result<Value, Abort>;
@@ -10,11 +10,19 @@ pub struct Runtime {
 
 /// The error raised if the runtime is aborted.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Abort(String);
+pub enum Abort {
+    /// An expression of the program failed.
+    Error(String),
+    /// The program ran an `abort` expression.
+    Explicit,
+}
 
 impl fmt::Display for Abort {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(&self.0)
+        match self {
+            Abort::Error(message) => f.write_str(message),
+            Abort::Explicit => f.write_str("aborted"),
+        }
     }
 }
 
@@ -32,12 +40,18 @@ impl Runtime {
     /// Given the provided [`Object`], run the provided [`Program`] to
     /// completion.
     pub fn run<'a>(&mut self, object: &mut impl Object, program: &'a Program) -> RuntimeResult {
+        self.state.coverage = program.coverage.clone();
+
         let mut values = program
             .expressions
             .iter()
             .map(|expr| {
+                self.state.cover(expr.span());
                 expr.execute(&mut self.state, object)
-                    .map_err(|err| Abort(err.to_string()))
+                    .map_err(|err| match err {
+                        ProgramError::Abort => Abort::Explicit,
+                        err => Abort::Error(err.to_string()),
+                    })
             })
             .collect::<Result<Vec<_>, _>>()?;
 
@@ -1,9 +1,12 @@
-use crate::{path::Path, TypeDef, Value};
+use crate::{path::Path, Coverage, Span, TypeDef, Value};
 use std::collections::HashMap;
 
 #[derive(Debug, Default)]
 pub struct Program {
     variables: HashMap<String, Value>,
+
+    /// The coverage of the program being run, if collected.
+    pub(crate) coverage: Option<Coverage>,
 }
 
 impl Program {
@@ -14,6 +17,13 @@ impl Program {
     pub fn variables_mut(&mut self) -> &mut HashMap<String, Value> {
         &mut self.variables
     }
+
+    /// Counts an execution of the statement at `span`, if coverage is collected.
+    pub(crate) fn cover(&self, span: Span) {
+        if let Some(coverage) = &self.coverage {
+            coverage.hit(span);
+        }
+    }
 }
 
 /// State held by the compiler as it parses the program source.
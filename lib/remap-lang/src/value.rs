@@ -77,7 +77,19 @@ impl<'de> Deserialize<'de> for Value {
 
             #[inline]
             fn visit_u64<E>(self, value: u64) -> Result<Value, E> {
-                Ok((value as i64).into())
+                // Integers above `i64::MAX` are rounded to floats, like the
+                // values of events parsed from JSON.
+                Ok(i64::try_from(value).map_or(Value::Float(value as f64), Value::Integer))
+            }
+
+            #[inline]
+            fn visit_i128<E>(self, value: i128) -> Result<Value, E> {
+                Ok(i64::try_from(value).map_or(Value::Float(value as f64), Value::Integer))
+            }
+
+            #[inline]
+            fn visit_u128<E>(self, value: u128) -> Result<Value, E> {
+                Ok(i64::try_from(value).map_or(Value::Float(value as f64), Value::Integer))
             }
 
             #[inline]
@@ -197,6 +209,9 @@ pub enum Error {
     #[error("unable to divide by zero")]
     DivideByZero,
 
+    #[error("integer overflow")]
+    Overflow,
+
     #[error("unable to add value type {1} to {0}")]
     Add(Kind, Kind),
 
@@ -491,10 +506,15 @@ impl Value {
         let err = || Error::Mul(self.kind(), rhs.kind());
 
         let value = match &self {
-            Value::Bytes(lhv) => lhv
-                .repeat(i64::try_from(&rhs).map_err(|_| err())? as usize)
+            Value::Bytes(lhv) => {
+                let count = i64::try_from(&rhs).map_err(|_| err())?;
+                lhv.repeat(usize::try_from(count).map_err(|_| err())?)
+                    .into()
+            }
+            Value::Integer(lhv) => lhv
+                .checked_mul(i64::try_from(&rhs).map_err(|_| err())?)
+                .ok_or(Error::Overflow)?
                 .into(),
-            Value::Integer(lhv) => (lhv * i64::try_from(&rhs).map_err(|_| err())?).into(),
             Value::Float(lhv) => (lhv * f64::try_from(&rhs).map_err(|_| err())?).into(),
             _ => return Err(err()),
         };
@@ -531,8 +551,11 @@ impl Value {
         }
 
         let value = match &self {
-            Value::Integer(lhv) => (lhv / rhs).into(),
-            Value::Float(lhv) => (*lhv as i64 / rhs).into(),
+            Value::Integer(lhv) => lhv.checked_div(rhs).ok_or(Error::Overflow)?.into(),
+            Value::Float(lhv) => (*lhv as i64)
+                .checked_div(rhs)
+                .ok_or(Error::Overflow)?
+                .into(),
             _ => return Err(err()),
         };
 
@@ -550,7 +573,10 @@ impl Value {
                 String::try_from(&rhs).map_err(|_| err())?
             )
             .into(),
-            Value::Integer(lhv) => (lhv + i64::try_from(&rhs).map_err(|_| err())?).into(),
+            Value::Integer(lhv) => lhv
+                .checked_add(i64::try_from(&rhs).map_err(|_| err())?)
+                .ok_or(Error::Overflow)?
+                .into(),
             Value::Float(lhv) => (lhv + f64::try_from(&rhs).map_err(|_| err())?).into(),
             _ => return Err(err()),
         };
@@ -563,7 +589,10 @@ impl Value {
         let err = || Error::Sub(self.kind(), rhs.kind());
 
         let value = match self {
-            Value::Integer(lhv) => (lhv - i64::try_from(&rhs).map_err(|_| err())?).into(),
+            Value::Integer(lhv) => lhv
+                .checked_sub(i64::try_from(&rhs).map_err(|_| err())?)
+                .ok_or(Error::Overflow)?
+                .into(),
             Value::Float(lhv) => (lhv - f64::try_from(&rhs).map_err(|_| err())?).into(),
             _ => return Err(err()),
         };
@@ -610,7 +639,10 @@ impl Value {
         let err = || Error::Rem(self.kind(), rhs.kind());
 
         let value = match self {
-            Value::Integer(lhv) => (lhv % i64::try_from(&rhs).map_err(|_| err())?).into(),
+            Value::Integer(lhv) => match i64::try_from(&rhs).map_err(|_| err())? {
+                0 => return Err(Error::DivideByZero),
+                rhv => lhv.checked_rem(rhv).ok_or(Error::Overflow)?.into(),
+            },
             Value::Float(lhv) => (lhv % f64::try_from(&rhs).map_err(|_| err())?).into(),
             _ => return Err(err()),
         };
@@ -1183,4 +1215,29 @@ mod tests {
         let null = format!("{}", Value::Null);
         assert_eq!("null", null);
     }
+
+    #[test]
+    fn test_integer_overflow() {
+        let max = Value::from(i64::MAX);
+        let min = Value::from(i64::MIN);
+
+        assert_eq!(max.clone().try_add(1.into()), Err(Error::Overflow));
+        assert_eq!(min.clone().try_sub(1.into()), Err(Error::Overflow));
+        assert_eq!(max.clone().try_mul(2.into()), Err(Error::Overflow));
+        assert_eq!(min.clone().try_int_div((-1).into()), Err(Error::Overflow));
+        assert_eq!(min.try_rem((-1).into()), Err(Error::Overflow));
+        assert_eq!(max.try_rem(0.into()), Err(Error::DivideByZero));
+        assert!(Value::from("foo").try_mul((-1).into()).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_wide_integers() {
+        let value: Value =
+            serde_json::from_str("[9223372036854775807, 18446744073709551615]").unwrap();
+
+        assert_eq!(
+            value,
+            Value::from(vec![Value::from(i64::MAX), Value::from(u64::MAX as f64)])
+        );
+    }
 }
@@ -9,3 +9,6 @@ pub mod conversion;
 
 #[cfg(feature = "tokenize")]
 pub mod tokenize;
+
+#[cfg(feature = "units")]
+pub mod units;
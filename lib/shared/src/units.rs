@@ -0,0 +1,149 @@
+use snafu::Snafu;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum UnitError {
+    #[snafu(display("Invalid number {:?}", number))]
+    InvalidNumber { number: String },
+    #[snafu(display("Unknown unit {:?}, expected one of {}", unit, expected))]
+    UnknownUnit {
+        unit: String,
+        expected: &'static str,
+    },
+    #[snafu(display("Value is too large"))]
+    TooLarge,
+}
+
+const BYTE_UNITS: &[(&str, u64)] = &[
+    ("b", 1),
+    ("kb", 1_000),
+    ("mb", 1_000_000),
+    ("gb", 1_000_000_000),
+    ("tb", 1_000_000_000_000),
+    ("kib", 1 << 10),
+    ("mib", 1 << 20),
+    ("gib", 1 << 30),
+    ("tib", 1 << 40),
+];
+
+/// Nanoseconds per unit.
+const DURATION_UNITS: &[(&str, u64)] = &[
+    ("ns", 1),
+    ("us", 1_000),
+    ("ms", 1_000_000),
+    ("s", 1_000_000_000),
+    ("m", 60 * 1_000_000_000),
+    ("h", 60 * 60 * 1_000_000_000),
+    ("d", 24 * 60 * 60 * 1_000_000_000),
+];
+
+/// Parses a size such as `"5MiB"`, `"1.5 GB"` or `"512"` into a number of
+/// bytes. Units are case insensitive, a number without one is in bytes.
+pub fn parse_bytes(s: &str) -> Result<u64, UnitError> {
+    let (number, unit) = split_number(s.trim());
+    let multiplier = match unit.trim() {
+        "" => 1,
+        unit => lookup(BYTE_UNITS, unit).ok_or_else(|| UnitError::UnknownUnit {
+            unit: unit.into(),
+            expected: "B, kB, MB, GB, TB, KiB, MiB, GiB or TiB",
+        })?,
+    };
+
+    scale(number, multiplier)
+}
+
+/// Parses a duration such as `"30s"`, `"1h30m"` or `"250ms"`. A number without
+/// a unit is in seconds.
+pub fn parse_duration(s: &str) -> Result<Duration, UnitError> {
+    let mut rest = s.trim();
+    if !rest.is_empty() && split_number(rest).1.is_empty() {
+        return scale(rest, 1_000_000_000).map(Duration::from_nanos);
+    }
+
+    let mut nanos = 0u64;
+    loop {
+        let (number, after) = split_number(rest);
+        let after = after.trim_start();
+        let unit_len = after
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or_else(|| after.len());
+        let (unit, after) = after.split_at(unit_len);
+
+        let multiplier = lookup(DURATION_UNITS, unit).ok_or_else(|| UnitError::UnknownUnit {
+            unit: unit.into(),
+            expected: "ns, us, ms, s, m, h or d",
+        })?;
+        nanos = nanos
+            .checked_add(scale(number, multiplier)?)
+            .ok_or(UnitError::TooLarge)?;
+
+        rest = after.trim_start();
+        if rest.is_empty() {
+            return Ok(Duration::from_nanos(nanos));
+        }
+    }
+}
+
+/// Splits the leading number off `s`, underscores may separate its digits.
+fn split_number(s: &str) -> (&str, &str) {
+    let end = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '_'))
+        .unwrap_or_else(|| s.len());
+    s.split_at(end)
+}
+
+fn lookup(units: &[(&str, u64)], unit: &str) -> Option<u64> {
+    units
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+        .map(|(_, multiplier)| *multiplier)
+}
+
+fn scale(number: &str, multiplier: u64) -> Result<u64, UnitError> {
+    let invalid = || UnitError::InvalidNumber {
+        number: number.into(),
+    };
+    let number = number.replace('_', "");
+
+    if let Ok(integer) = number.parse::<u64>() {
+        return integer.checked_mul(multiplier).ok_or(UnitError::TooLarge);
+    }
+
+    let float = number.parse::<f64>().map_err(|_| invalid())?;
+    let scaled = (float * multiplier as f64).round();
+    if !scaled.is_finite() || scaled < 0.0 {
+        return Err(invalid());
+    }
+    if scaled >= u64::MAX as f64 {
+        return Err(UnitError::TooLarge);
+    }
+    Ok(scaled as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes() {
+        assert_eq!(parse_bytes("512"), Ok(512));
+        assert_eq!(parse_bytes("5MiB"), Ok(5 * 1024 * 1024));
+        assert_eq!(parse_bytes("1.5 kb"), Ok(1500));
+        assert_eq!(parse_bytes("10_000B"), Ok(10_000));
+        assert!(parse_bytes("5 MiBs").is_err());
+        assert!(parse_bytes("MiB").is_err());
+        assert_eq!(parse_bytes("20000000TiB"), Err(UnitError::TooLarge));
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("30"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("1m 0.5s"), Ok(Duration::from_millis(60_500)));
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("2 d"), Ok(Duration::from_secs(172_800)));
+        assert!(parse_duration("5 minutes").is_err());
+        assert!(parse_duration("").is_err());
+    }
+}
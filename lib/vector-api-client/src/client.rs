@@ -1,21 +1,93 @@
 use anyhow::Context;
 use graphql_client::GraphQLQuery;
+use std::{fs, path::PathBuf};
 use url::Url;
 
 /// Wrapped `Result` type, that returns deserialized GraphQL response data
 pub type QueryResult<T> =
     anyhow::Result<graphql_client::Response<<T as GraphQLQuery>::ResponseData>>;
 
+/// Options for connecting to a remote Vector API server, which may require TLS and
+/// authentication
+#[derive(Debug, Clone, Default)]
+pub struct ClientOptions {
+    /// Static token sent as `Authorization: Bearer <token>` with every request
+    pub token: Option<String>,
+    /// PEM encoded CA certificate to trust when verifying the server, in addition to
+    /// the system roots
+    pub ca_file: Option<PathBuf>,
+    /// PKCS#12 archive holding the client certificate and key, for mutual TLS
+    pub identity_file: Option<PathBuf>,
+    /// Password of the PKCS#12 archive
+    pub identity_pass: Option<String>,
+}
+
+impl ClientOptions {
+    /// Returns the value of the `Authorization` header, if a token is set
+    pub fn authorization(&self) -> Option<String> {
+        self.token.as_ref().map(|token| format!("Bearer {}", token))
+    }
+
+    /// Reads the CA certificate, if set
+    pub fn ca_certificate(&self) -> anyhow::Result<Option<Vec<u8>>> {
+        self.ca_file
+            .as_ref()
+            .map(|path| fs::read(path).with_context(|| format!("Couldn't read {:?}", path)))
+            .transpose()
+    }
+
+    /// Reads the client identity archive, if set
+    pub fn identity(&self) -> anyhow::Result<Option<Vec<u8>>> {
+        self.identity_file
+            .as_ref()
+            .map(|path| fs::read(path).with_context(|| format!("Couldn't read {:?}", path)))
+            .transpose()
+    }
+}
+
 /// GraphQL query client over HTTP
 #[derive(Debug)]
 pub struct Client {
     url: Url,
+    client: reqwest::Client,
+    authorization: Option<String>,
 }
 
 impl Client {
     /// Returns a new GraphQL query client, bound to the provided URL
     pub fn new(url: Url) -> Self {
-        Self { url }
+        Self {
+            url,
+            client: reqwest::Client::new(),
+            authorization: None,
+        }
+    }
+
+    /// Returns a new GraphQL query client, bound to the provided URL and connecting with
+    /// the provided TLS and authentication options
+    pub fn with_options(url: Url, options: &ClientOptions) -> anyhow::Result<Self> {
+        let mut builder = reqwest::Client::builder();
+
+        if let Some(pem) = options.ca_certificate()? {
+            builder = builder.add_root_certificate(
+                reqwest::Certificate::from_pem(&pem).context("Invalid CA certificate")?,
+            );
+        }
+        if let Some(der) = options.identity()? {
+            builder = builder.identity(
+                reqwest::Identity::from_pkcs12_der(
+                    &der,
+                    options.identity_pass.as_deref().unwrap_or(""),
+                )
+                .context("Invalid client identity")?,
+            );
+        }
+
+        Ok(Self {
+            url,
+            client: builder.build().context("Couldn't build HTTP client")?,
+            authorization: options.authorization(),
+        })
     }
 
     /// Issue a GraphQL query using Reqwest, serializing the response to the associated
@@ -24,11 +96,12 @@ impl Client {
         &self,
         request_body: &graphql_client::QueryBody<T::Variables>,
     ) -> QueryResult<T> {
-        let client = reqwest::Client::new();
+        let mut request = self.client.post(self.url.clone()).json(request_body);
+        if let Some(authorization) = &self.authorization {
+            request = request.header("Authorization", authorization);
+        }
 
-        client
-            .post(self.url.clone())
-            .json(request_body)
+        request
             .send()
             .await
             .with_context(|| {
@@ -0,0 +1,45 @@
+//! Subscriptions to the events flowing through the topology
+
+use graphql_client::GraphQLQuery;
+
+/// OutputEventsSubscription samples the events flowing out of a source or transform,
+/// returning batches of events encoded as JSON
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/output_events.graphql",
+    response_derives = "Debug"
+)]
+pub struct OutputEventsSubscription;
+
+/// Extension methods for event subscriptions
+pub trait EventsSubscriptionExt {
+    /// Executes an output events subscription, sampling at most `limit` events of
+    /// `component_name` every `interval` milliseconds
+    fn output_events_subscription(
+        &self,
+        component_name: String,
+        interval: i64,
+        limit: i64,
+    ) -> crate::BoxedSubscription<OutputEventsSubscription>;
+}
+
+impl EventsSubscriptionExt for crate::SubscriptionClient {
+    /// Executes an output events subscription, sampling at most `limit` events of
+    /// `component_name` every `interval` milliseconds
+    fn output_events_subscription(
+        &self,
+        component_name: String,
+        interval: i64,
+        limit: i64,
+    ) -> crate::BoxedSubscription<OutputEventsSubscription> {
+        let request_body =
+            OutputEventsSubscription::build_query(output_events_subscription::Variables {
+                component_name,
+                interval,
+                limit,
+            });
+
+        self.start::<OutputEventsSubscription>(&request_body)
+    }
+}
@@ -83,6 +83,46 @@ pub struct ComponentProcessedBytesThroughputsSubscription;
 )]
 pub struct ComponentProcessedBytesTotalsSubscription;
 
+/// ComponentReceivedBytesTotalsSubscription contains metrics on the number of bytes
+/// that have been received by the sources of a Vector instance
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_received_bytes_totals.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentReceivedBytesTotalsSubscription;
+
+/// ComponentSentBytesTotalsSubscription contains metrics on the number of bytes
+/// that have been sent by the sinks of a Vector instance
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_sent_bytes_totals.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentSentBytesTotalsSubscription;
+
+/// ComponentErrorsTotalsSubscription contains metrics on the number of errors
+/// encountered by a Vector instance, against specific components
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_errors_totals.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentErrorsTotalsSubscription;
+
+/// ComponentBuffersSubscription contains the state of the buffers of the sinks, sampled
+/// every `interval`, including how full they are
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_buffers.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentBuffersSubscription;
+
 /// Extension methods for metrics subscriptions
 pub trait MetricsSubscriptionExt {
     /// Executes an uptime metrics subscription
@@ -129,6 +169,30 @@ pub trait MetricsSubscriptionExt {
         &self,
         interval: i64,
     ) -> crate::BoxedSubscription<ComponentProcessedBytesThroughputsSubscription>;
+
+    /// Executes a component bytes received totals subscription
+    fn component_received_bytes_totals_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentReceivedBytesTotalsSubscription>;
+
+    /// Executes a component bytes sent totals subscription
+    fn component_sent_bytes_totals_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentSentBytesTotalsSubscription>;
+
+    /// Executes a component errors totals subscription
+    fn component_errors_totals_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentErrorsTotalsSubscription>;
+
+    /// Executes a component buffers subscription
+    fn component_buffers_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentBuffersSubscription>;
 }
 
 impl MetricsSubscriptionExt for crate::SubscriptionClient {
@@ -222,4 +286,53 @@ impl MetricsSubscriptionExt for crate::SubscriptionClient {
 
         self.start::<ComponentProcessedBytesThroughputsSubscription>(&request_body)
     }
+
+    /// Executes an all source bytes received totals subscription
+    fn component_received_bytes_totals_subscription(
+        &self,
+        interval: i64,
+    ) -> BoxedSubscription<ComponentReceivedBytesTotalsSubscription> {
+        let request_body = ComponentReceivedBytesTotalsSubscription::build_query(
+            component_received_bytes_totals_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentReceivedBytesTotalsSubscription>(&request_body)
+    }
+
+    /// Executes an all sink bytes sent totals subscription
+    fn component_sent_bytes_totals_subscription(
+        &self,
+        interval: i64,
+    ) -> BoxedSubscription<ComponentSentBytesTotalsSubscription> {
+        let request_body = ComponentSentBytesTotalsSubscription::build_query(
+            component_sent_bytes_totals_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentSentBytesTotalsSubscription>(&request_body)
+    }
+
+    /// Executes an all component errors totals subscription
+    fn component_errors_totals_subscription(
+        &self,
+        interval: i64,
+    ) -> BoxedSubscription<ComponentErrorsTotalsSubscription> {
+        let request_body = ComponentErrorsTotalsSubscription::build_query(
+            component_errors_totals_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentErrorsTotalsSubscription>(&request_body)
+    }
+
+    /// Executes an all sink buffers subscription
+    fn component_buffers_subscription(
+        &self,
+        interval: i64,
+    ) -> BoxedSubscription<ComponentBuffersSubscription> {
+        let request_body =
+            ComponentBuffersSubscription::build_query(component_buffers_subscription::Variables {
+                interval,
+            });
+
+        self.start::<ComponentBuffersSubscription>(&request_body)
+    }
 }
@@ -1,11 +1,13 @@
 //! Queries, subscriptions, and extension methods for executing them
 
 mod components;
+mod events;
 mod health;
 mod meta;
 mod metrics;
 
 pub use self::meta::*;
 pub use components::*;
+pub use events::*;
 pub use health::*;
 pub use metrics::*;
@@ -1,3 +1,4 @@
+use crate::ClientOptions;
 use futures::SinkExt;
 use graphql_client::GraphQLQuery;
 use serde::{Deserialize, Serialize};
@@ -6,11 +7,19 @@ use std::{
     pin::Pin,
     sync::{Arc, Mutex, Weak},
 };
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpStream,
+};
 use tokio::{
     stream::{Stream, StreamExt},
     sync::{broadcast, mpsc, oneshot},
 };
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::{
+    client_async, connect_async,
+    tungstenite::{self, client::IntoClientRequest, http::HeaderValue, Message},
+    WebSocketStream,
+};
 use url::Url;
 use uuid::Uuid;
 use weak_table::WeakValueHashMap;
@@ -221,6 +230,67 @@ pub async fn connect_subscription_client(
     url: Url,
 ) -> Result<SubscriptionClient, tokio_tungstenite::tungstenite::Error> {
     let (ws, _) = connect_async(url).await?;
+    Ok(forward(ws))
+}
+
+/// Connect to a new WebSocket GraphQL server endpoint like `connect_subscription_client`,
+/// using the provided TLS and authentication options.
+pub async fn connect_subscription_client_with_options(
+    url: Url,
+    options: &ClientOptions,
+) -> Result<SubscriptionClient, tungstenite::Error> {
+    let mut request = url.clone().into_client_request()?;
+    if let Some(authorization) = options.authorization() {
+        request.headers_mut().insert(
+            "Authorization",
+            HeaderValue::from_str(&authorization)
+                .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidInput, error))?,
+        );
+    }
+
+    let host = url
+        .host_str()
+        .ok_or_else(|| tungstenite::Error::Url("URL has no host".into()))?;
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| tungstenite::Error::Url("URL has no port".into()))?;
+    let stream = TcpStream::connect((host, port)).await?;
+
+    if url.scheme() == "wss" {
+        let connector = tls_connector(options)?;
+        let stream = tokio_native_tls::TlsConnector::from(connector)
+            .connect(host, stream)
+            .await?;
+        let (ws, _) = client_async(request, stream).await?;
+        Ok(forward(ws))
+    } else {
+        let (ws, _) = client_async(request, stream).await?;
+        Ok(forward(ws))
+    }
+}
+
+fn tls_connector(options: &ClientOptions) -> Result<native_tls::TlsConnector, tungstenite::Error> {
+    let read_error =
+        |error: anyhow::Error| std::io::Error::new(std::io::ErrorKind::Other, error.to_string());
+
+    let mut builder = native_tls::TlsConnector::builder();
+    if let Some(pem) = options.ca_certificate().map_err(read_error)? {
+        builder.add_root_certificate(native_tls::Certificate::from_pem(&pem)?);
+    }
+    if let Some(der) = options.identity().map_err(read_error)? {
+        builder.identity(native_tls::Identity::from_pkcs12(
+            &der,
+            options.identity_pass.as_deref().unwrap_or(""),
+        )?);
+    }
+    Ok(builder.build()?)
+}
+
+/// Set up channel forwarding between the WebSocket and the returned `SubscriptionClient`
+fn forward<S>(ws: WebSocketStream<S>) -> SubscriptionClient
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
     let (mut ws_tx, mut ws_rx) = futures::StreamExt::split(ws);
 
     let (send_tx, mut send_rx) = mpsc::unbounded_channel::<Payload>();
@@ -248,5 +318,5 @@ pub async fn connect_subscription_client(
         }
     });
 
-    Ok(SubscriptionClient::new(send_tx, recv_rx))
+    SubscriptionClient::new(send_tx, recv_rx)
 }
@@ -0,0 +1,72 @@
+use crate::sinks::util::adaptive_concurrency::{self, ConcurrencyStatus};
+use async_graphql::{validators::IntRange, FieldError, FieldResult, Object, SimpleObject};
+
+#[derive(SimpleObject)]
+pub struct AdaptiveConcurrency {
+    /// Sink name
+    sink_name: String,
+
+    /// Current concurrency limit, the highest of the sink's services
+    current_limit: i64,
+
+    /// Requests in flight
+    in_flight: i64,
+
+    /// Whether the limit is pinned, suspending its adjustments
+    pinned: bool,
+}
+
+impl AdaptiveConcurrency {
+    fn new(sink_name: String, status: Option<ConcurrencyStatus>) -> FieldResult<Self> {
+        let status = status.ok_or_else(|| {
+            FieldError::from(format!(
+                "Sink \"{}\" has no adaptive concurrency limit running.",
+                sink_name
+            ))
+        })?;
+
+        Ok(Self {
+            sink_name,
+            current_limit: status.current_limit as i64,
+            in_flight: status.in_flight as i64,
+            pinned: status.pinned,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct AdaptiveConcurrencyMutation;
+
+#[Object]
+impl AdaptiveConcurrencyMutation {
+    /// Pins the adaptive concurrency limit of a sink until it's unpinned, also across reloads
+    async fn pin_adaptive_concurrency(
+        &self,
+        sink_name: String,
+        #[graphql(validator(IntRange(min = "1", max = "200")))] limit: i32,
+    ) -> FieldResult<AdaptiveConcurrency> {
+        let status = adaptive_concurrency::pin(&sink_name, Some(limit as usize));
+        AdaptiveConcurrency::new(sink_name, status)
+    }
+
+    /// Resumes the adjustments of the adaptive concurrency limit of a sink
+    async fn unpin_adaptive_concurrency(
+        &self,
+        sink_name: String,
+    ) -> FieldResult<AdaptiveConcurrency> {
+        let status = adaptive_concurrency::pin(&sink_name, None);
+        AdaptiveConcurrency::new(sink_name, status)
+    }
+}
+
+#[derive(Default)]
+pub struct AdaptiveConcurrencyQuery;
+
+#[Object]
+impl AdaptiveConcurrencyQuery {
+    /// Adaptive concurrency of a sink
+    async fn adaptive_concurrency(&self, sink_name: String) -> FieldResult<AdaptiveConcurrency> {
+        let status = adaptive_concurrency::status(&sink_name);
+        AdaptiveConcurrency::new(sink_name, status)
+    }
+}
@@ -5,10 +5,12 @@ use crate::{
         metrics::{self, IntoSinkMetrics},
         sort,
     },
+    buffers::{self, BufferConfig, BufferUsage},
+    event::MetricValue,
     filter_check,
 };
 use async_graphql::{Enum, InputObject, Object};
-use std::cmp;
+use std::{cmp, sync::Arc};
 
 #[derive(Debug, Clone)]
 pub struct Data {
@@ -29,6 +31,132 @@ impl Sink {
     }
 }
 
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum BufferType {
+    Memory,
+    Disk,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum BufferWhenFull {
+    Block,
+    DropNewest,
+    DropByPriority,
+}
+
+impl From<buffers::WhenFull> for BufferWhenFull {
+    fn from(when_full: buffers::WhenFull) -> Self {
+        match when_full {
+            buffers::WhenFull::Block => BufferWhenFull::Block,
+            buffers::WhenFull::DropNewest => BufferWhenFull::DropNewest,
+            buffers::WhenFull::DropByPriority => BufferWhenFull::DropByPriority,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Buffer(Arc<BufferUsage>);
+
+#[Object]
+impl Buffer {
+    /// Buffer type
+    async fn buffer_type(&self) -> BufferType {
+        match self.0.config() {
+            BufferConfig::Memory { .. } => BufferType::Memory,
+            #[cfg(feature = "leveldb")]
+            BufferConfig::Disk { .. } => BufferType::Disk,
+            BufferConfig::DiskV2 { .. } => BufferType::Disk,
+        }
+    }
+
+    /// Maximum number of buffered events, for memory buffers
+    async fn max_events(&self) -> Option<i64> {
+        match self.0.config() {
+            BufferConfig::Memory { max_events, .. } => Some(*max_events as i64),
+            #[cfg(feature = "leveldb")]
+            BufferConfig::Disk { .. } => None,
+            BufferConfig::DiskV2 { .. } => None,
+        }
+    }
+
+    /// Maximum size of the buffered events in bytes, for disk buffers
+    async fn max_size_bytes(&self) -> Option<i64> {
+        match self.0.config() {
+            BufferConfig::Memory { .. } => None,
+            #[cfg(feature = "leveldb")]
+            BufferConfig::Disk { max_size, .. } => Some(*max_size as i64),
+            BufferConfig::DiskV2 { max_size, .. } => Some(*max_size as i64),
+        }
+    }
+
+    /// Behavior when the buffer is full
+    async fn when_full(&self) -> BufferWhenFull {
+        match self.0.config() {
+            BufferConfig::Memory { when_full, .. } => (*when_full).into(),
+            #[cfg(feature = "leveldb")]
+            BufferConfig::Disk { when_full, .. } => (*when_full).into(),
+            BufferConfig::DiskV2 { when_full, .. } => (*when_full).into(),
+        }
+    }
+
+    /// Events written to the buffer since it was opened, which the sink hasn't read yet
+    async fn events(&self) -> i64 {
+        self.0.events() as i64
+    }
+
+    /// Size of the buffered events in bytes, for disk buffers
+    async fn bytes(&self) -> Option<i64> {
+        self.0.bytes().map(|bytes| bytes as i64)
+    }
+
+    /// Events the sink has read from the buffer, but not acknowledged yet. A growing number
+    /// indicates the sink isn't able to deliver events
+    async fn unacked_events(&self) -> i64 {
+        self.0.unacked_events() as i64
+    }
+
+    /// How full the buffer is, between 0 and 1. A buffer staying full indicates the sink
+    /// applies back-pressure. Unknown for disk buffers that don't track their size
+    async fn utilization(&self) -> Option<f64> {
+        self.0.fill()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ComponentBuffer {
+    name: String,
+    buffer: Buffer,
+}
+
+#[Object]
+impl ComponentBuffer {
+    /// Sink name
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Sink buffer state
+    async fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+}
+
+/// Returns the buffers of the sinks that have been built, ordered by sink name
+pub fn component_buffers() -> Vec<ComponentBuffer> {
+    let mut buffers = state::get_sinks()
+        .into_iter()
+        .filter_map(|sink| {
+            let usage = buffers::usage(sink.get_name())?;
+            Some(ComponentBuffer {
+                name: sink.0.name,
+                buffer: Buffer(usage),
+            })
+        })
+        .collect::<Vec<_>>();
+    buffers.sort_by(|a, b| a.name.cmp(&b.name));
+    buffers
+}
+
 #[derive(Default, InputObject)]
 pub struct SinksFilter {
     name: Option<Vec<filter::StringFilter>>,
@@ -111,6 +239,22 @@ impl Sink {
     pub async fn metrics(&self) -> metrics::SinkMetrics {
         metrics::by_component_name(self.get_name()).to_sink_metrics(self.get_component_type())
     }
+
+    /// Outcome of the latest healthcheck, if one has run
+    pub async fn healthy(&self) -> Option<bool> {
+        metrics::by_component_name(self.get_name())
+            .into_iter()
+            .find(|m| m.name() == "healthcheck_healthy")
+            .and_then(|m| match m.data.value {
+                MetricValue::Gauge { value } => Some(value > 0.0),
+                _ => None,
+            })
+    }
+
+    /// Sink buffer state, once the sink has been built
+    pub async fn buffer(&self) -> Option<Buffer> {
+        buffers::usage(self.get_name()).map(Buffer)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,107 @@
+use crate::{event::Event, topology::tap::Tap};
+use async_graphql::{
+    validators::IntRange, Enum, FieldError, FieldResult, SimpleObject, Subscription,
+};
+use async_stream::stream;
+use tokio::{stream::Stream, sync::mpsc::error::TryRecvError, time::Duration};
+
+#[derive(Enum, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EventType {
+    Log,
+    Metric,
+    Trace,
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+/// An event flowing out of a source or transform
+pub struct OutputEvent {
+    /// Name of the component the event flowed out of
+    component_name: String,
+
+    /// Type of the event
+    event_type: EventType,
+
+    /// The event, encoded as JSON
+    json: String,
+}
+
+impl OutputEvent {
+    fn new(component_name: &str, event: &Event) -> Self {
+        let (event_type, json) = match event {
+            Event::Log(log) => (EventType::Log, serde_json::to_string(log)),
+            Event::Metric(metric) => (EventType::Metric, serde_json::to_string(metric)),
+            Event::Trace(trace) => (EventType::Trace, serde_json::to_string(trace)),
+        };
+
+        Self {
+            component_name: component_name.to_owned(),
+            event_type,
+            json: json.expect("Events should be serializable to JSON."),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct EventsSubscription;
+
+#[Subscription]
+impl EventsSubscription {
+    /// A sample of the events flowing out of a source or transform, sent every `interval`
+    /// milliseconds with at most `limit` events. The events flowing while the sample is full
+    /// are skipped. The subscription ends when the component is removed or reloaded.
+    async fn output_events(
+        &self,
+        component_name: String,
+        #[graphql(default = 500, validator(IntRange(min = "10", max = "60_000")))] interval: i32,
+        #[graphql(default = 100, validator(IntRange(min = "1", max = "10_000")))] limit: i32,
+    ) -> FieldResult<impl Stream<Item = Vec<OutputEvent>>> {
+        let (tap, mut rx) = Tap::new(&component_name, limit as usize).ok_or_else(|| {
+            FieldError::from(format!(
+                "No source or transform named {:?} is running",
+                component_name
+            ))
+        })?;
+        let mut interval = tokio::time::interval(Duration::from_millis(interval as u64));
+
+        Ok(stream! {
+            // Dropped with the subscription, removing the tap.
+            let _tap = tap;
+            loop {
+                interval.tick().await;
+
+                let mut events = Vec::new();
+                let closed = loop {
+                    match rx.try_recv() {
+                        Ok(event) => events.push(OutputEvent::new(&component_name, &event)),
+                        Err(TryRecvError::Empty) => break false,
+                        Err(TryRecvError::Closed) => break true,
+                    }
+                };
+
+                if !events.is_empty() {
+                    yield events;
+                }
+                if closed {
+                    break;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::log_schema;
+
+    #[test]
+    fn encodes_events_as_json() {
+        let mut event = Event::from("hello");
+        event.as_mut_log().remove(log_schema().timestamp_key());
+
+        let output = OutputEvent::new("in", &event);
+        assert_eq!(output.component_name, "in");
+        assert_eq!(output.event_type, EventType::Log);
+        assert_eq!(output.json, r#"{"message":"hello"}"#);
+    }
+}
@@ -73,7 +73,7 @@ pub trait CustomFilter<T> {
 }
 
 /// Returns true if a provided `Item` passes all 'AND' or 'OR' filter rules, recursively.
-fn filter_item<Item, Filter>(item: &Item, f: &Filter) -> bool
+pub fn filter_item<Item, Filter>(item: &Item, f: &Filter) -> bool
 where
     Filter: CustomFilter<Item>,
 {
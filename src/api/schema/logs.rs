@@ -0,0 +1,168 @@
+use crate::{
+    api::schema::filter::{self, CustomFilter, StringFilter},
+    event::Event,
+    filter_check, trace,
+};
+use async_graphql::{Enum, InputObject, SimpleObject, Subscription};
+use chrono::{DateTime, Utc};
+use tokio::stream::{Stream, StreamExt};
+
+#[derive(Enum, Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    fn from_level(level: &str) -> Option<Self> {
+        match level {
+            "TRACE" => Some(LogLevel::Trace),
+            "DEBUG" => Some(LogLevel::Debug),
+            "INFO" => Some(LogLevel::Info),
+            "WARN" => Some(LogLevel::Warn),
+            "ERROR" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+/// A log event emitted by Vector itself
+pub struct LogEvent {
+    /// Time the event was emitted
+    timestamp: DateTime<Utc>,
+
+    /// Level of the event
+    level: LogLevel,
+
+    /// Name of the component the event was emitted on behalf of, if any
+    component_name: Option<String>,
+
+    /// Internal module the event was emitted from
+    target: String,
+
+    /// Message of the event
+    message: String,
+}
+
+impl LogEvent {
+    /// Picks the fields of an internal log event, as built by `trace`.
+    fn from_event(event: Event) -> Option<Self> {
+        let log = event.into_log();
+        let level = LogLevel::from_level(&log.get("metadata.level")?.to_string_lossy())?;
+        let timestamp = *log.get("timestamp")?.as_timestamp()?;
+        let string = |key: &str| log.get(key).map(|value| value.to_string_lossy());
+
+        Some(Self {
+            timestamp,
+            level,
+            component_name: string("metadata.component_name"),
+            target: string("metadata.target").unwrap_or_default(),
+            message: string("message").unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Default, InputObject)]
+pub struct LogEventsFilter {
+    min_level: Option<LogLevel>,
+    component_name: Option<Vec<StringFilter>>,
+    message: Option<Vec<StringFilter>>,
+    or: Option<Vec<Self>>,
+}
+
+impl CustomFilter<LogEvent> for LogEventsFilter {
+    fn matches(&self, log: &LogEvent) -> bool {
+        filter_check!(
+            self.min_level.map(|level| log.level >= level),
+            self.component_name.as_ref().map(|f| f
+                .iter()
+                .all(|f| f.filter_value(log.component_name.as_deref().unwrap_or_default()))),
+            self.message
+                .as_ref()
+                .map(|f| f.iter().all(|f| f.filter_value(&log.message)))
+        );
+        true
+    }
+
+    fn or(&self) -> Option<&Vec<Self>> {
+        self.or.as_ref()
+    }
+}
+
+#[derive(Default)]
+pub struct LogsSubscription;
+
+#[Subscription]
+impl LogsSubscription {
+    /// Log events emitted by Vector itself from now on, optionally filtered
+    async fn log_events(&self, filter: Option<LogEventsFilter>) -> impl Stream<Item = LogEvent> {
+        // Events missed by a lagging subscriber are skipped.
+        trace::subscribe_live().filter_map(move |event| {
+            let log = LogEvent::from_event(event.ok()?)?;
+            match &filter {
+                Some(f) if !filter::filter_item(&log, f) => None,
+                _ => Some(log),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn internal_log(level: &str, component_name: Option<&str>, message: &str) -> LogEvent {
+        let mut event = Event::from(message);
+        let log = event.as_mut_log();
+        log.insert("timestamp", Utc::now());
+        log.insert("metadata.level", level);
+        log.insert("metadata.target", "vector::topology");
+        if let Some(component_name) = component_name {
+            log.insert("metadata.component_name", component_name);
+        }
+        LogEvent::from_event(event).unwrap()
+    }
+
+    #[test]
+    fn picks_internal_log_fields() {
+        let log = internal_log("WARN", Some("in"), "Something happened.");
+
+        assert_eq!(log.level, LogLevel::Warn);
+        assert_eq!(log.component_name.as_deref(), Some("in"));
+        assert_eq!(log.target, "vector::topology");
+        assert_eq!(log.message, "Something happened.");
+    }
+
+    #[test]
+    fn filters_by_level_and_component() {
+        let filter = LogEventsFilter {
+            min_level: Some(LogLevel::Warn),
+            component_name: Some(vec![StringFilter {
+                equals: Some("in".to_string()),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        assert!(filter::filter_item(
+            &internal_log("ERROR", Some("in"), "Failed."),
+            &filter
+        ));
+        assert!(!filter::filter_item(
+            &internal_log("INFO", Some("in"), "Started."),
+            &filter
+        ));
+        assert!(!filter::filter_item(
+            &internal_log("ERROR", Some("out"), "Failed."),
+            &filter
+        ));
+        assert!(!filter::filter_item(
+            &internal_log("ERROR", None, "Failed."),
+            &filter
+        ));
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{
+    event::{Event, MetricValue},
+    metrics::{capture_metrics, get_controller},
+};
+use async_graphql::Object;
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::RwLock,
+};
+use tokio::{
+    sync::oneshot,
+    time::{self, Duration},
+};
+
+/// How often component throughput is sampled into the history.
+const SAMPLE_INTERVAL_SECS: u64 = 1;
+
+lazy_static! {
+    static ref HISTORY: RwLock<History> = RwLock::new(History::new(0));
+}
+
+/// Throughput of a single component between two consecutive samples.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentThroughputSample {
+    name: String,
+    processed_events_throughput: i64,
+    processed_bytes_throughput: i64,
+}
+
+#[Object]
+impl ComponentThroughputSample {
+    /// Component name
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Events processed since the previous sample
+    async fn processed_events_throughput(&self) -> i64 {
+        self.processed_events_throughput
+    }
+
+    /// Bytes processed since the previous sample
+    async fn processed_bytes_throughput(&self) -> i64 {
+        self.processed_bytes_throughput
+    }
+}
+
+/// Throughput of all components at a point in time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThroughputSample {
+    timestamp: DateTime<Utc>,
+    components: Vec<ComponentThroughputSample>,
+}
+
+#[Object]
+impl ThroughputSample {
+    /// Time the sample was taken
+    async fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    /// Throughput of each component
+    async fn components(&self) -> &Vec<ComponentThroughputSample> {
+        &self.components
+    }
+}
+
+/// Processed counter totals of a component, as last observed.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Totals {
+    events: f64,
+    bytes: f64,
+}
+
+/// Ring buffer of throughput samples covering the configured retention.
+#[derive(Debug)]
+struct History {
+    capacity: usize,
+    samples: VecDeque<ThroughputSample>,
+    last_totals: BTreeMap<String, Totals>,
+}
+
+impl History {
+    fn new(retention_secs: u64) -> Self {
+        Self {
+            capacity: (retention_secs / SAMPLE_INTERVAL_SECS) as usize,
+            samples: VecDeque::new(),
+            last_totals: BTreeMap::new(),
+        }
+    }
+
+    fn set_retention(&mut self, retention_secs: u64) {
+        self.capacity = (retention_secs / SAMPLE_INTERVAL_SECS) as usize;
+        while self.samples.len() > self.capacity {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Records the current counter totals. Throughput is derived from the
+    /// difference to the previous totals, so the first observation of a
+    /// component only primes it.
+    fn record(&mut self, timestamp: DateTime<Utc>, totals: BTreeMap<String, Totals>) {
+        let components = totals
+            .iter()
+            .filter_map(|(name, totals)| {
+                let last = self.last_totals.get(name)?;
+                Some(ComponentThroughputSample {
+                    name: name.clone(),
+                    processed_events_throughput: (totals.events - last.events).max(0.0) as i64,
+                    processed_bytes_throughput: (totals.bytes - last.bytes).max(0.0) as i64,
+                })
+            })
+            .collect::<Vec<_>>();
+        let primed = !self.last_totals.is_empty();
+        self.last_totals = totals;
+
+        if self.capacity == 0 || !primed {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(ThroughputSample {
+            timestamp,
+            components,
+        });
+    }
+
+    /// Returns the samples taken after `since`, oldest first.
+    fn since(&self, since: DateTime<Utc>) -> Vec<ThroughputSample> {
+        self.samples
+            .iter()
+            .filter(|sample| sample.timestamp > since)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Sums the processed counters of every component from the current metrics.
+fn capture_totals() -> BTreeMap<String, Totals> {
+    let controller = get_controller().expect("Metrics system not initialized. Please report.");
+
+    capture_metrics(&controller)
+        .filter_map(|event| match event {
+            Event::Metric(m) => Some(m),
+            _ => None,
+        })
+        .filter_map(|m| {
+            let name = m.tag_value("component_name")?;
+            match m.data.value {
+                MetricValue::Counter { value } => Some((name, m.name().to_owned(), value)),
+                _ => None,
+            }
+        })
+        .fold(BTreeMap::new(), |mut totals, (name, metric, value)| {
+            let entry: &mut Totals = totals.entry(name).or_default();
+            match metric.as_str() {
+                "processed_events_total" => entry.events += value,
+                "processed_bytes_total" => entry.bytes += value,
+                _ => (),
+            }
+            totals
+        })
+}
+
+/// Changes how many seconds of samples are kept, dropping older ones.
+pub fn set_retention(retention_secs: u64) {
+    HISTORY
+        .write()
+        .expect("metrics history lock poisoned")
+        .set_retention(retention_secs);
+}
+
+/// Returns the samples of the last `seconds`, oldest first.
+pub fn throughput_history(seconds: i64) -> Vec<ThroughputSample> {
+    HISTORY
+        .read()
+        .expect("metrics history lock poisoned")
+        .since(Utc::now() - chrono::Duration::seconds(seconds))
+}
+
+/// Spawns the task sampling component throughput into the history until
+/// `shutdown` resolves or is dropped.
+pub fn spawn_sampler(retention_secs: u64, mut shutdown: oneshot::Receiver<()>) {
+    set_retention(retention_secs);
+
+    tokio::spawn(async move {
+        let mut interval = time::interval(Duration::from_secs(SAMPLE_INTERVAL_SECS));
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = interval.tick() => {
+                    let totals = capture_totals();
+                    HISTORY
+                        .write()
+                        .expect("metrics history lock poisoned")
+                        .record(Utc::now(), totals);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn totals(events: f64, bytes: f64) -> BTreeMap<String, Totals> {
+        vec![("in".to_owned(), Totals { events, bytes })]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn records_throughput_between_samples() {
+        let mut history = History::new(10);
+        let start = Utc::now();
+
+        history.record(start, totals(10.0, 100.0));
+        history.record(start + chrono::Duration::seconds(1), totals(15.0, 180.0));
+
+        let samples = history.since(start - chrono::Duration::seconds(1));
+        assert_eq!(samples.len(), 1);
+        assert_eq!(
+            samples[0].components,
+            vec![ComponentThroughputSample {
+                name: "in".into(),
+                processed_events_throughput: 5,
+                processed_bytes_throughput: 80,
+            }]
+        );
+    }
+
+    #[test]
+    fn evicts_samples_beyond_retention() {
+        let mut history = History::new(3);
+        let start = Utc::now();
+
+        for i in 0..10 {
+            history.record(
+                start + chrono::Duration::seconds(i),
+                totals(i as f64, i as f64),
+            );
+        }
+        assert_eq!(history.samples.len(), 3);
+        assert_eq!(
+            history.samples[0].timestamp,
+            start + chrono::Duration::seconds(7)
+        );
+
+        history.set_retention(1);
+        assert_eq!(history.samples.len(), 1);
+        assert_eq!(
+            history.samples[0].timestamp,
+            start + chrono::Duration::seconds(9)
+        );
+    }
+}
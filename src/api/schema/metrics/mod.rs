@@ -1,5 +1,6 @@
 mod errors;
 pub mod filter;
+pub mod history;
 mod host;
 mod processed_bytes;
 mod processed_events;
@@ -8,12 +9,24 @@ pub mod source;
 mod transform;
 mod uptime;
 
+use crate::{
+    api::schema::components::{
+        sink::{component_buffers, ComponentBuffer},
+        state::component_by_name,
+        Component,
+    },
+    event::Metric,
+};
 use async_graphql::{validators::IntRange, Interface, Object, Subscription};
 use chrono::{DateTime, Utc};
-use tokio::stream::{Stream, StreamExt};
+use tokio::{
+    stream::{Stream, StreamExt},
+    time::Duration,
+};
 
 pub use errors::{ComponentErrorsTotal, ErrorsTotal};
 pub use filter::*;
+pub use history::{ComponentThroughputSample, ThroughputSample};
 pub use host::HostMetrics;
 pub use processed_bytes::{
     ComponentProcessedBytesThroughput, ComponentProcessedBytesTotal, ProcessedBytesTotal,
@@ -43,6 +56,15 @@ impl MetricsQuery {
     async fn host_metrics(&self) -> HostMetrics {
         HostMetrics::new()
     }
+
+    /// Per-component throughput sampled every second over the last `seconds`, oldest first.
+    /// Only as much history as the `api.history_retention_secs` option keeps is available.
+    async fn component_throughput_history(
+        &self,
+        #[graphql(default = 300, validator(IntRange(min = "1", max = "86_400")))] seconds: i32,
+    ) -> Vec<ThroughputSample> {
+        history::throughput_history(seconds as i64)
+    }
 }
 
 #[derive(Default)]
@@ -161,6 +183,47 @@ impl MetricsSubscription {
         })
     }
 
+    /// Component bytes received metrics of the sources over `interval`.
+    async fn component_received_bytes_totals(
+        &self,
+        #[graphql(default = 1000, validator(IntRange(min = "10", max = "60_000")))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentProcessedBytesTotal>> {
+        component_counter_metrics(interval, &|m| {
+            m.name() == "processed_bytes_total"
+                && matches!(component_of(m), Some(Component::Source(_)))
+        })
+        .map(|m| {
+            m.into_iter()
+                .map(ComponentProcessedBytesTotal::new)
+                .collect()
+        })
+    }
+
+    /// Component bytes sent metrics of the sinks over `interval`.
+    async fn component_sent_bytes_totals(
+        &self,
+        #[graphql(default = 1000, validator(IntRange(min = "10", max = "60_000")))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentProcessedBytesTotal>> {
+        component_counter_metrics(interval, &|m| {
+            m.name() == "processed_bytes_total"
+                && matches!(component_of(m), Some(Component::Sink(_)))
+        })
+        .map(|m| {
+            m.into_iter()
+                .map(ComponentProcessedBytesTotal::new)
+                .collect()
+        })
+    }
+
+    /// Buffer state of the sinks, sampled every `interval`, to spot the sinks applying
+    /// back-pressure.
+    async fn component_buffers(
+        &self,
+        #[graphql(default = 1000, validator(IntRange(min = "10", max = "60_000")))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentBuffer>> {
+        tokio::time::interval(Duration::from_millis(interval as u64)).map(|_| component_buffers())
+    }
+
     /// Total error metrics.
     async fn errors_total(
         &self,
@@ -193,3 +256,8 @@ impl MetricsSubscription {
         })
     }
 }
+
+/// Returns the component a metric was emitted on behalf of.
+fn component_of(metric: &Metric) -> Option<Component> {
+    component_by_name(&metric.tag_value("component_name")?)
+}
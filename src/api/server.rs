@@ -1,5 +1,5 @@
 use super::{handler, schema};
-use crate::config;
+use crate::{config, tls::MaybeTlsSettings};
 use async_graphql::{
     http::{playground_source, GraphQLPlaygroundConfig},
     Request, Schema,
@@ -8,22 +8,50 @@ use async_graphql_warp::{graphql_subscription, Response as GQLResponse};
 use std::{convert::Infallible, net::SocketAddr};
 use tokio::sync::oneshot;
 use warp::filters::BoxedFilter;
-use warp::{http::Response, Filter, Reply};
+use warp::{
+    http::{Response, StatusCode},
+    reject::Reject,
+    Filter, Rejection, Reply,
+};
+
+#[derive(Debug)]
+struct Unauthorized;
+
+impl Reject for Unauthorized {}
 
 pub struct Server {
     _shutdown: oneshot::Sender<()>,
+    _history_shutdown: oneshot::Sender<()>,
     addr: SocketAddr,
 }
 
 impl Server {
     /// Start the API server. This creates the routes and spawns a Warp server. The server is
     /// gracefully shut down when Self falls out of scope by way of the oneshot sender closing
-    pub fn start(config: &config::Config) -> Self {
-        let routes = make_routes(config.api.playground);
+    pub fn start(config: &config::Config) -> crate::Result<Self> {
+        let routes = make_routes(
+            config.api.playground,
+            config.api.auth.as_ref().map(|auth| auth.token.clone()),
+        );
+
+        let address = config.api.address.expect("No socket address");
+        if !address.ip().is_loopback() && config.api.auth.is_none() {
+            warn!(
+                message = "API is reachable from other hosts without authentication, consider setting `api.auth.token`.",
+                %address
+            );
+        }
+
+        // Bind synchronously so the bound address is known before returning
+        let tls = MaybeTlsSettings::from_config(&config.api.tls, true)?;
+        let listener = std::net::TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let listener = tls.bind_std(listener)?;
 
         let (_shutdown, rx) = oneshot::channel();
-        let (addr, server) = warp::serve(routes).bind_with_graceful_shutdown(
-            config.api.address.expect("No socket address"),
+        let server = warp::serve(routes).serve_incoming_with_graceful_shutdown(
+            listener.accept_stream(),
             async {
                 rx.await.ok();
             },
@@ -35,7 +63,15 @@ impl Server {
         // Spawn the server in the background
         tokio::spawn(server);
 
-        Self { addr, _shutdown }
+        // Sample component throughput for historical queries while the server is running
+        let (_history_shutdown, history_rx) = oneshot::channel();
+        schema::metrics::history::spawn_sampler(config.api.history_retention_secs, history_rx);
+
+        Ok(Self {
+            addr,
+            _shutdown,
+            _history_shutdown,
+        })
     }
 
     /// Returns a copy of the SocketAddr that the server was started on
@@ -47,11 +83,54 @@ impl Server {
     /// directly involve `self`, it provides a neater API to expose an internal implementation
     /// detail than exposing the function of the sub-mod directly
     pub fn update_config(&self, config: &config::Config) {
-        schema::components::update_config(config)
+        schema::components::update_config(config);
+        schema::metrics::history::set_retention(config.api.history_retention_secs);
+    }
+}
+
+/// Requires the `Authorization: Bearer <token>` header when a token is configured.
+fn authorized(token: Option<String>) -> BoxedFilter<()> {
+    match token {
+        None => warp::any().boxed(),
+        Some(token) => {
+            let expected = format!("Bearer {}", token);
+            warp::header::optional::<String>("authorization")
+                .and_then(move |header: Option<String>| {
+                    let authorized = header.map_or(false, |header| {
+                        // Compare in constant time to not leak the token through timing
+                        header.len() == expected.len()
+                            && header
+                                .bytes()
+                                .zip(expected.bytes())
+                                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                                == 0
+                    });
+                    async move {
+                        if authorized {
+                            Ok(())
+                        } else {
+                            Err(warp::reject::custom(Unauthorized))
+                        }
+                    }
+                })
+                .untuple_one()
+                .boxed()
+        }
+    }
+}
+
+async fn handle_rejection(rejection: Rejection) -> Result<impl Reply, Rejection> {
+    if rejection.find::<Unauthorized>().is_some() {
+        Ok(warp::reply::with_status(
+            "Unauthorized",
+            StatusCode::UNAUTHORIZED,
+        ))
+    } else {
+        Err(rejection)
     }
 }
 
-fn make_routes(playground: bool) -> BoxedFilter<(impl Reply,)> {
+fn make_routes(playground: bool, token: Option<String>) -> BoxedFilter<(impl Reply,)> {
     // Build the GraphQL schema
     let schema = schema::build_schema().finish();
 
@@ -63,18 +142,20 @@ fn make_routes(playground: bool) -> BoxedFilter<(impl Reply,)> {
     // 404
     let not_found = warp::any().and_then(|| async { Err(warp::reject::not_found()) });
 
-    // GraphQL query and subscription handler
-    let graphql_handler = warp::path("graphql").and(graphql_subscription(schema.clone()).or(
-        async_graphql_warp::graphql(schema).and_then(
+    // GraphQL query and subscription handler, behind authentication if configured. The
+    // health endpoint stays open to allow for load balancer checks.
+    let graphql_handler = warp::path("graphql").and(authorized(token.clone())).and(
+        graphql_subscription(schema.clone()).or(async_graphql_warp::graphql(schema).and_then(
             |(schema, request): (Schema<_, _, _>, Request)| async move {
                 Ok::<_, Infallible>(GQLResponse::from(schema.execute(request).await))
             },
-        ),
-    ));
+        )),
+    );
 
     // GraphQL playground
     let graphql_playground = if playground {
         warp::path("playground")
+            .and(authorized(token))
             .map(move || {
                 Response::builder()
                     .header("content-type", "text/html")
@@ -91,6 +172,7 @@ fn make_routes(playground: bool) -> BoxedFilter<(impl Reply,)> {
         .or(graphql_handler)
         .or(graphql_playground)
         .or(not_found)
+        .recover(handle_rejection)
         .with(
             warp::cors()
                 .allow_any_origin()
@@ -1,8 +1,12 @@
-use crate::cli::{handle_config_errors, Color, LogFormat, Opts, RootOpts, SubCommand};
+use crate::cli::{
+    handle_config_errors, BufferCommand, Color, ConfigCommand, LogFormat, Opts, RootOpts,
+    SubCommand,
+};
 use crate::signal::SignalTo;
 use crate::topology::RunningTopology;
 use crate::{
-    config, generate, heartbeat, list, metrics, signal, topology, trace, unit_test, validate,
+    buffers, config, convert_config, crash_report, generate, heartbeat, list, metrics, signal,
+    topology, trace, unit_test, validate,
 };
 use std::cmp::max;
 use std::collections::HashMap;
@@ -13,10 +17,12 @@ use tokio::sync::mpsc;
 
 #[cfg(feature = "sources-host_metrics")]
 use crate::sources::host_metrics;
-#[cfg(feature = "api-client")]
-use crate::top;
 #[cfg(feature = "api")]
 use crate::{api, internal_events::ApiStarted};
+#[cfg(feature = "sources-file")]
+use crate::{checkpoints, cli::CheckpointsCommand};
+#[cfg(feature = "api-client")]
+use crate::{tap, top};
 
 #[cfg(windows)]
 use crate::service;
@@ -114,12 +120,24 @@ impl Application {
                         SubCommand::List(l) => list::cmd(&l),
                         SubCommand::Test(t) => unit_test::cmd(&t).await,
                         SubCommand::Generate(g) => generate::cmd(&g),
+                        SubCommand::ConvertConfig(c) => convert_config::cmd(&c),
+                        SubCommand::Config(ConfigCommand::Schema(s)) => config::schema::cmd(&s),
+                        SubCommand::Buffer(BufferCommand::Inspect(i)) => buffers::cmd::inspect(&i),
+                        SubCommand::Buffer(BufferCommand::Migrate(m)) => buffers::cmd::migrate(&m),
+                        #[cfg(feature = "sources-file")]
+                        SubCommand::Checkpoints(CheckpointsCommand::Export(e)) => {
+                            checkpoints::export(&e).await
+                        }
                         #[cfg(feature = "api-client")]
                         SubCommand::Top(t) => top::cmd(&t).await,
+                        #[cfg(feature = "api-client")]
+                        SubCommand::Tap(t) => tap::cmd(&t).await,
                         #[cfg(windows)]
                         SubCommand::Service(s) => service::cmd(&s),
                         #[cfg(feature = "vrl-cli")]
                         SubCommand::VRL(s) => remap_cli::cmd::cmd(&s),
+                        #[cfg(feature = "vrl-cli")]
+                        SubCommand::Lsp(l) => remap_cli::lsp::cmd(&l),
                     };
 
                     return Err(code);
@@ -158,13 +176,18 @@ impl Application {
                 }
                 config.healthchecks.set_require_healthy(require_healthy);
 
+                crash_report::init(&config).map_err(|error| {
+                    error!(message = "Unable to set up crash reports.", %error);
+                    exitcode::CONFIG
+                })?;
+
                 let diff = config::ConfigDiff::initial(&config);
                 let pieces = topology::build_or_log_errors(&config, &diff, HashMap::new())
                     .await
                     .ok_or(exitcode::CONFIG)?;
 
                 #[cfg(feature = "api")]
-                let api = config.api;
+                let api = config.api.clone();
 
                 let result = topology::start_validated(config, diff, pieces).await;
                 let (topology, graceful_crash) = result.ok_or(exitcode::CONFIG)?;
@@ -206,6 +229,7 @@ impl Application {
         rt.block_on(async move {
             emit!(VectorStarted);
             tokio::spawn(heartbeat::heartbeat());
+            tokio::spawn(crash_report::run(topology.config().global.crash_report.clone()));
 
             #[cfg(feature = "api")]
             // assigned to prevent the API terminating when falling out of scope
@@ -215,7 +239,13 @@ impl Application {
                     playground: api_config.playground
                 });
 
-                Some(api::Server::start(topology.config()))
+                match api::Server::start(topology.config()) {
+                    Ok(api_server) => Some(api_server),
+                    Err(error) => {
+                        error!(message = "Failed to start the API server.", %error);
+                        None
+                    }
+                }
             } else {
                 info!(message="API is disabled, enable by setting `api.enabled` to `true` and use commands like `vector top`.");
                 None
@@ -246,6 +276,10 @@ impl Application {
                                         api_server.update_config(topology.config())
                                     }
 
+                                    if let Err(error) = crash_report::init(topology.config()) {
+                                        error!(message = "Unable to set up crash reports.", %error);
+                                    }
+
                                     emit!(VectorReloaded { config_paths: &config_paths })
                                 },
                                 Ok(false) => emit!(VectorReloadFailed),
@@ -0,0 +1,191 @@
+//! The `vector buffer` subcommands, inspecting and migrating the disk buffers
+//! of the sinks of a config.
+
+use super::{disk, BufferConfig};
+use crate::config::{self, Config};
+use colored::*;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct InspectOpts {
+    /// Name of the sink whose buffer is inspected, all the sinks with a disk
+    /// buffer by default.
+    #[structopt(short, long)]
+    sink: Option<String>,
+
+    #[structopt(flatten)]
+    config: ConfigOpts,
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct MigrateOpts {
+    /// Name of the sink whose buffer is migrated, all the sinks with a disk
+    /// buffer by default.
+    #[structopt(short, long)]
+    sink: Option<String>,
+
+    #[structopt(flatten)]
+    config: ConfigOpts,
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+struct ConfigOpts {
+    /// Vector config files in TOML format.
+    #[structopt(name = "config-toml", long)]
+    paths_toml: Vec<PathBuf>,
+
+    /// Vector config files in JSON format.
+    #[structopt(name = "config-json", long)]
+    paths_json: Vec<PathBuf>,
+
+    /// Vector config files in YAML format.
+    #[structopt(name = "config-yaml", long)]
+    paths_yaml: Vec<PathBuf>,
+
+    /// Any number of Vector config files.
+    /// Format is detected from the file name.
+    /// If none are specified the default config path `/etc/vector/vector.toml`
+    /// will be targeted.
+    paths: Vec<PathBuf>,
+}
+
+impl ConfigOpts {
+    fn load(&self) -> Result<Config, exitcode::ExitCode> {
+        let paths = config::process_paths(&config::merge_path_lists(vec![
+            (&self.paths, None),
+            (&self.paths_toml, Some(config::Format::TOML)),
+            (&self.paths_json, Some(config::Format::JSON)),
+            (&self.paths_yaml, Some(config::Format::YAML)),
+        ]))
+        .ok_or_else(|| {
+            eprintln!("{}", "No config file paths".red());
+            exitcode::CONFIG
+        })?;
+
+        config::load_from_paths(&paths, false).map_err(|errors| {
+            errors.iter().for_each(|e| eprintln!("{}", e.red()));
+            exitcode::CONFIG
+        })
+    }
+}
+
+/// The sinks with a disk buffer, and the format they are configured with.
+fn disk_buffers(
+    config: &Config,
+    name: Option<&str>,
+) -> Result<(PathBuf, Vec<(String, disk::Format)>), String> {
+    let sinks = config
+        .sinks
+        .iter()
+        .filter(|(sink_name, _)| name.map_or(true, |name| sink_name.as_str() == name))
+        .filter_map(|(sink_name, sink)| {
+            let format = match sink.buffer {
+                BufferConfig::Memory { .. } => return None,
+                #[cfg(feature = "leveldb")]
+                BufferConfig::Disk { .. } => disk::Format::LevelDb,
+                BufferConfig::DiskV2 { .. } => disk::Format::Segments,
+            };
+            Some((sink_name.clone(), format))
+        })
+        .collect::<Vec<_>>();
+
+    if sinks.is_empty() {
+        return Err(match name {
+            Some(name) => format!("no sink with a disk buffer named {:?}", name),
+            None => "no sink with a disk buffer in the config".to_owned(),
+        });
+    }
+
+    let data_dir = config
+        .global
+        .resolve_and_validate_data_dir(None)
+        .map_err(|error| error.to_string())?;
+    Ok((data_dir, sinks))
+}
+
+/// Prints the events and bytes pending in the disk buffers of the sinks,
+/// including the buffers left in another format than the configured one.
+pub fn inspect(opts: &InspectOpts) -> exitcode::ExitCode {
+    let config = match opts.config.load() {
+        Ok(config) => config,
+        Err(code) => return code,
+    };
+    let (data_dir, sinks) = match disk_buffers(&config, opts.sink.as_deref()) {
+        Ok(buffers) => buffers,
+        Err(error) => {
+            eprintln!("{}", error.red());
+            return exitcode::CONFIG;
+        }
+    };
+
+    let mut code = exitcode::OK;
+    for (sink_name, configured) in sinks {
+        for format in disk::Format::all() {
+            match disk::pending(&data_dir, &sink_name, format) {
+                Ok(Some(pending)) => {
+                    let note = if format == configured {
+                        ""
+                    } else {
+                        " (not migrated yet)"
+                    };
+                    println!(
+                        "{}: {} events, {} bytes pending in the {} buffer{}",
+                        sink_name,
+                        pending.events,
+                        pending.bytes,
+                        format.buffer_type(),
+                        note
+                    );
+                }
+                Ok(None) if format == configured => {
+                    println!("{}: no {} buffer yet", sink_name, format.buffer_type());
+                }
+                Ok(None) => {}
+                Err(error) => {
+                    eprintln!("{}", format!("{}: {}", sink_name, error).red());
+                    code = exitcode::IOERR;
+                }
+            }
+        }
+    }
+    code
+}
+
+/// Moves the events left in the disk buffers of the sinks by their previous
+/// buffer type into the buffers they are configured with, as Vector does when
+/// it starts.
+pub fn migrate(opts: &MigrateOpts) -> exitcode::ExitCode {
+    let config = match opts.config.load() {
+        Ok(config) => config,
+        Err(code) => return code,
+    };
+    let (data_dir, sinks) = match disk_buffers(&config, opts.sink.as_deref()) {
+        Ok(buffers) => buffers,
+        Err(error) => {
+            eprintln!("{}", error.red());
+            return exitcode::CONFIG;
+        }
+    };
+
+    let mut code = exitcode::OK;
+    for (sink_name, format) in sinks {
+        match disk::migrate(&data_dir, &sink_name, format) {
+            Ok(0) => println!("{}: nothing to migrate", sink_name),
+            Ok(events) => println!(
+                "{}: migrated {} events to the {} buffer",
+                sink_name,
+                events,
+                format.buffer_type()
+            ),
+            Err(error) => {
+                eprintln!("{}", format!("{}: {}", sink_name, error).red());
+                code = exitcode::IOERR;
+            }
+        }
+    }
+    code
+}
@@ -16,8 +16,9 @@ use snafu::ResultExt;
 use std::{
     collections::VecDeque,
     convert::TryInto,
+    io,
     mem::size_of,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc, Mutex,
@@ -130,6 +131,10 @@ impl Sink for Writer {
 }
 
 impl Writer {
+    pub fn current_size(&self) -> Arc<AtomicUsize> {
+        Arc::clone(&self.current_size)
+    }
+
     fn write_batch(&mut self) {
         self.db
             .write(WriteOptions::new(), &self.writebatch)
@@ -341,3 +346,72 @@ impl super::DiskBuffer for Buffer {
         Ok((writer, reader, acker))
     }
 }
+
+fn open_database(path: &Path, create_if_missing: bool) -> io::Result<Database<Key>> {
+    let mut options = Options::new();
+    options.create_if_missing = create_if_missing;
+    Database::open(path, options).map_err(|error| io::Error::new(io::ErrorKind::Other, error))
+}
+
+/// Calls `f` with the payloads of the events of the buffer at `path` not
+/// acknowledged yet, oldest first. Returns the number of events.
+pub fn read_payloads(path: &Path, mut f: impl FnMut(&[u8]) -> io::Result<()>) -> io::Result<usize> {
+    let db = open_database(path, false)?;
+    let mut count = 0;
+    for value in db.value_iter(ReadOptions::new()) {
+        f(&value)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Appends encoded events to the buffer at `path`, outside of a topology.
+pub struct Appender {
+    db: Database<Key>,
+    offset: usize,
+    writebatch: Writebatch<Key>,
+    batch_size: usize,
+}
+
+impl Appender {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let db = open_database(path, true)?;
+        let offset = {
+            let iter = db.keys_iter(ReadOptions::new());
+            iter.seek_to_last();
+            if iter.valid() {
+                iter.key().0 + 1
+            } else {
+                0
+            }
+        };
+
+        Ok(Self {
+            db,
+            offset,
+            writebatch: Writebatch::new(),
+            batch_size: 0,
+        })
+    }
+
+    pub fn append(&mut self, payload: &[u8]) -> io::Result<()> {
+        self.writebatch.put(Key(self.offset), payload);
+        self.offset += 1;
+        self.batch_size += 1;
+        if self.batch_size >= 100 {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        if self.batch_size > 0 {
+            self.db
+                .write(WriteOptions::new(), &self.writebatch)
+                .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+            self.writebatch = Writebatch::new();
+            self.batch_size = 0;
+        }
+        Ok(())
+    }
+}
@@ -1,12 +1,18 @@
-#![cfg(feature = "leveldb")]
-
+use crate::config::data_dir;
+#[cfg(feature = "leveldb")]
 use crate::event::Event;
+#[cfg(feature = "leveldb")]
 use futures01::{Async, AsyncSink, Poll, Sink, Stream};
-use snafu::Snafu;
+use snafu::{ResultExt, Snafu};
+use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
+#[cfg(feature = "leveldb")]
+use std::sync::{atomic::AtomicUsize, Arc};
 
+#[cfg(feature = "leveldb")]
 pub mod leveldb_buffer;
+pub mod segment_buffer;
 
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -14,18 +20,272 @@ pub enum Error {
     DataDirNotFound { data_dir: PathBuf },
     #[snafu(display("The configured data_dir {:?} is not writable by the vector process, please ensure vector can write to that directory", data_dir))]
     DataDirNotWritable { data_dir: PathBuf },
+    #[snafu(display("The configured data_dir {:?} has {} bytes available, less than the {} bytes of `min_free_space`, please free some space or lower it", data_dir, available, min_free_space))]
+    DataDirLowSpace {
+        data_dir: PathBuf,
+        available: u64,
+        min_free_space: usize,
+    },
     #[snafu(display("Unable to look up data_dir {:?}", data_dir))]
     DataDirMetadataError {
         data_dir: PathBuf,
         source: std::io::Error,
     },
+    #[cfg(feature = "leveldb")]
     #[snafu(display("Unable to open data_dir {:?}", data_dir))]
     DataDirOpenError {
         data_dir: PathBuf,
         source: leveldb::database::error::Error,
     },
+    #[snafu(display("Unable to open the buffer in {:?}: {}", path, source))]
+    SegmentsOpenError {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Unable to read the buffer in {:?}: {}", path, source))]
+    ReadError {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display(
+        "The buffer in {:?} is used by another process, stop Vector before migrating it",
+        path
+    ))]
+    BufferLocked { path: PathBuf },
+    #[snafu(display("Unable to migrate the buffer in {:?} to {:?}: {}", from, to, source))]
+    MigrateError {
+        from: PathBuf,
+        to: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// The formats of the disk buffers, whose content can be migrated from one to
+/// the other as they store the same encoded events.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// The `disk` buffer, stored in leveldb.
+    #[cfg(feature = "leveldb")]
+    LevelDb,
+    /// The `disk_v2` buffer, stored in segment files.
+    Segments,
+}
+
+impl Format {
+    pub fn all() -> Vec<Format> {
+        let mut formats = Vec::new();
+        #[cfg(feature = "leveldb")]
+        formats.push(Format::LevelDb);
+        formats.push(Format::Segments);
+        formats
+    }
+
+    /// The `type` of the buffers stored in this format.
+    pub fn buffer_type(self) -> &'static str {
+        match self {
+            #[cfg(feature = "leveldb")]
+            Format::LevelDb => "disk",
+            Format::Segments => "disk_v2",
+        }
+    }
+
+    /// The directory of the buffer of `sink_name` in this format.
+    pub fn path(self, data_dir: &Path, sink_name: &str) -> PathBuf {
+        match self {
+            #[cfg(feature = "leveldb")]
+            Format::LevelDb => data_dir.join(format!("{}_buffer", sink_name)),
+            Format::Segments => data_dir.join(format!("{}_buffer_v2", sink_name)),
+        }
+    }
+
+    /// Locks the buffer at `path` until the returned file is closed, if it's
+    /// not locked by the database holding it.
+    fn lock(self, path: &Path) -> io::Result<Option<fs::File>> {
+        match self {
+            #[cfg(feature = "leveldb")]
+            Format::LevelDb => Ok(None),
+            Format::Segments => segment_buffer::lock(path).map(Some),
+        }
+    }
+
+    /// Calls `f` with the encoded events of the buffer at `path` not
+    /// acknowledged yet, oldest first. Returns the number of events.
+    fn read_payloads(
+        self,
+        path: &Path,
+        f: impl FnMut(&[u8]) -> io::Result<()>,
+    ) -> io::Result<usize> {
+        match self {
+            #[cfg(feature = "leveldb")]
+            Format::LevelDb => leveldb_buffer::read_payloads(path, f),
+            Format::Segments => segment_buffer::read_payloads(path, f),
+        }
+    }
 }
 
+/// The events stored in a disk buffer, and not acknowledged yet.
+#[derive(Debug, PartialEq)]
+pub struct Pending {
+    pub events: usize,
+    /// The size of the encoded events.
+    pub bytes: usize,
+}
+
+/// Counts the events of the buffer of `sink_name` in `format`, if it exists.
+/// Segment buffers can be inspected while Vector runs, leveldb ones can't.
+pub fn pending(data_dir: &Path, sink_name: &str, format: Format) -> Result<Option<Pending>, Error> {
+    let path = format.path(data_dir, sink_name);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let mut bytes = 0;
+    let events = format
+        .read_payloads(&path, |payload| {
+            bytes += payload.len();
+            Ok(())
+        })
+        .context(ReadError { path })?;
+    Ok(Some(Pending { events, bytes }))
+}
+
+/// Moves the events of the buffers of `sink_name` stored in other formats
+/// into its buffer in `format`, after the events it already holds. Returns
+/// the number of events moved.
+///
+/// The events of the buffer and the migrated ones are written to a new buffer
+/// next to it, which takes its place once complete. The migrated buffer is
+/// deleted after that, so an interrupted migration leaves the buffers as they
+/// were, unless interrupted between these two steps, where it duplicates
+/// events rather than losing them. Both buffers are locked while migrated, so
+/// it fails if either is used by a running Vector.
+pub fn migrate(data_dir: &Path, sink_name: &str, format: Format) -> Result<usize, Error> {
+    let to = format.path(data_dir, sink_name);
+    let mut migrated = 0;
+    for from_format in Format::all().into_iter().filter(|f| *f != format) {
+        let from = from_format.path(data_dir, sink_name);
+        if !from.exists() {
+            continue;
+        }
+
+        let migrate_error = |source: io::Error, path: &Path| match source.kind() {
+            io::ErrorKind::WouldBlock => Error::BufferLocked { path: path.into() },
+            _ => Error::MigrateError {
+                from: from.clone(),
+                to: to.clone(),
+                source,
+            },
+        };
+        let _lock = from_format
+            .lock(&from)
+            .map_err(|source| migrate_error(source, &from))?;
+        migrated += Staging::new(&to)
+            .copy(from_format, &from, format)
+            .and_then(|count| fs::remove_dir_all(&from).map(|_| count))
+            .map_err(|source| migrate_error(source, &to))?;
+    }
+    Ok(migrated)
+}
+
+/// The directories next to a buffer migrated to, holding the new buffer until
+/// it's complete, and then the buffer it replaces until it's deleted.
+struct Staging<'a> {
+    path: &'a Path,
+    staged: PathBuf,
+    replaced: PathBuf,
+}
+
+impl<'a> Staging<'a> {
+    fn new(path: &'a Path) -> Self {
+        let with_suffix = |suffix: &str| {
+            let mut path = path.as_os_str().to_owned();
+            path.push(suffix);
+            PathBuf::from(path)
+        };
+        Self {
+            path,
+            staged: with_suffix(".staged"),
+            replaced: with_suffix(".replaced"),
+        }
+    }
+
+    /// Writes the events of the buffer at `path` in `format`, then the ones
+    /// of `from`, to a new buffer which then takes its place. Returns the
+    /// number of events of `from`.
+    fn copy(&self, from_format: Format, from: &Path, format: Format) -> io::Result<usize> {
+        self.recover()?;
+
+        let _lock = if self.path.exists() {
+            let lock = format.lock(self.path)?;
+            copy_payloads(format, self.path, format, &self.staged)?;
+            lock
+        } else {
+            None
+        };
+        let count = copy_payloads(from_format, from, format, &self.staged)?;
+
+        if self.path.exists() {
+            fs::rename(self.path, &self.replaced)?;
+            fs::rename(&self.staged, self.path)?;
+            self.sync()?;
+            fs::remove_dir_all(&self.replaced)?;
+        } else {
+            fs::rename(&self.staged, self.path)?;
+            self.sync()?;
+        }
+        Ok(count)
+    }
+
+    /// Deletes what's left of an interrupted migration, restoring the buffer
+    /// if it was interrupted while replaced.
+    fn recover(&self) -> io::Result<()> {
+        if self.staged.exists() {
+            fs::remove_dir_all(&self.staged)?;
+        }
+        if self.replaced.exists() {
+            if self.path.exists() {
+                fs::remove_dir_all(&self.replaced)?;
+            } else {
+                fs::rename(&self.replaced, self.path)?;
+                self.sync()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Syncs the directory of the buffer, so that the renamed directories are
+    /// found after a crash.
+    fn sync(&self) -> io::Result<()> {
+        match self.path.parent() {
+            Some(dir) => segment_buffer::sync_dir(dir),
+            None => Ok(()),
+        }
+    }
+}
+
+fn copy_payloads(
+    from_format: Format,
+    from: &Path,
+    to_format: Format,
+    to: &Path,
+) -> io::Result<usize> {
+    match to_format {
+        #[cfg(feature = "leveldb")]
+        Format::LevelDb => {
+            let mut appender = leveldb_buffer::Appender::open(to)?;
+            let count = from_format.read_payloads(from, |payload| appender.append(payload))?;
+            appender.flush()?;
+            Ok(count)
+        }
+        Format::Segments => {
+            // The limits only apply to the writers of a topology.
+            let (writer, _reader, _acker) = segment_buffer::open(to, usize::MAX, 0)?;
+            from_format.read_payloads(from, |payload| writer.write(payload))
+        }
+    }
+}
+
+#[cfg(feature = "leveldb")]
 pub trait DiskBuffer {
     type Writer: Sink<SinkItem = Event, SinkError = ()>;
     type Reader: Stream<Item = Event, Error = ()> + Send;
@@ -36,11 +296,21 @@ pub trait DiskBuffer {
     ) -> Result<(Self::Writer, Self::Reader, super::Acker), Error>;
 }
 
+#[cfg(feature = "leveldb")]
 #[derive(Clone)]
 pub struct Writer {
     inner: leveldb_buffer::Writer,
 }
 
+#[cfg(feature = "leveldb")]
+impl Writer {
+    /// Size in bytes of the events stored in the buffer.
+    pub fn current_size(&self) -> Arc<AtomicUsize> {
+        self.inner.current_size()
+    }
+}
+
+#[cfg(feature = "leveldb")]
 impl Sink for Writer {
     type SinkItem = Event;
     type SinkError = ();
@@ -61,6 +331,7 @@ impl Sink for Writer {
     }
 }
 
+#[cfg(feature = "leveldb")]
 pub fn open(
     data_dir: &Path,
     name: &str,
@@ -74,8 +345,47 @@ pub fn open(
     Error,
 > {
     let path = data_dir.join(name);
+    check_data_dir(data_dir)?;
+
+    let (writer, reader, acker) = leveldb_buffer::Buffer::build(path, max_size)?;
+    Ok((Writer { inner: writer }, Box::new(reader), acker))
+}
+
+/// Opens the segment buffer `name` in `data_dir`, recovering the events it
+/// held when it was last closed. The buffer is considered full whenever the
+/// file system has less than `min_free_space` bytes available.
+pub fn open_segments(
+    data_dir: &Path,
+    name: &str,
+    max_size: usize,
+    min_free_space: Option<usize>,
+) -> Result<(segment_buffer::Writer, segment_buffer::Reader, super::Acker), Error> {
+    let path = data_dir.join(name);
+    check_data_dir(data_dir)?;
+    if let Some(min_free_space) = min_free_space {
+        check_free_space(data_dir, min_free_space)?;
+    }
+
+    segment_buffer::open(&path, max_size, min_free_space.unwrap_or(0))
+        .context(SegmentsOpenError { path })
+}
+
+fn check_free_space(data_dir: &Path, min_free_space: usize) -> Result<(), Error> {
+    match data_dir::available_space(data_dir) {
+        Ok(available) if available < min_free_space as u64 => Err(Error::DataDirLowSpace {
+            data_dir: data_dir.into(),
+            available,
+            min_free_space,
+        }),
+        Ok(_) => Ok(()),
+        Err(error) => {
+            warn!(message = "Unable to get the space available in data_dir.", ?data_dir, %error);
+            Ok(())
+        }
+    }
+}
 
-    // Check data dir
+fn check_data_dir(data_dir: &Path) -> Result<(), Error> {
     std::fs::metadata(&data_dir)
         .map_err(|e| match e.kind() {
             io::ErrorKind::PermissionDenied => Error::DataDirNotWritable {
@@ -89,16 +399,122 @@ pub fn open(
                 source: e,
             },
         })
-        .and_then(|m| {
-            if m.permissions().readonly() {
-                Err(Error::DataDirNotWritable {
-                    data_dir: data_dir.into(),
-                })
-            } else {
+        .and_then(|_| {
+            data_dir::check_writable(data_dir).map_err(|_| Error::DataDirNotWritable {
+                data_dir: data_dir.into(),
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payloads(format: Format, data_dir: &Path) -> Vec<Vec<u8>> {
+        let mut payloads = Vec::new();
+        format
+            .read_payloads(&format.path(data_dir, "sink"), |payload| {
+                payloads.push(payload.to_vec());
                 Ok(())
+            })
+            .unwrap();
+        payloads
+    }
+
+    #[test]
+    fn migrates_between_formats() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let data_dir = data_dir.path();
+        {
+            let path = Format::Segments.path(data_dir, "sink");
+            let (writer, _reader, _acker) = segment_buffer::open(&path, usize::MAX, 0).unwrap();
+            writer.write(b"first").unwrap();
+            writer.write(b"second").unwrap();
+        }
+        let expected = vec![b"first".to_vec(), b"second".to_vec()];
+        assert_eq!(
+            pending(data_dir, "sink", Format::Segments).unwrap(),
+            Some(Pending {
+                events: 2,
+                bytes: 11
+            })
+        );
+
+        #[cfg(feature = "leveldb")]
+        {
+            assert_eq!(migrate(data_dir, "sink", Format::LevelDb).unwrap(), 2);
+            assert!(!Format::Segments.path(data_dir, "sink").exists());
+            assert_eq!(payloads(Format::LevelDb, data_dir), expected);
+
+            assert_eq!(migrate(data_dir, "sink", Format::Segments).unwrap(), 2);
+            assert!(!Format::LevelDb.path(data_dir, "sink").exists());
+        }
+
+        // Nothing left to migrate.
+        assert_eq!(migrate(data_dir, "sink", Format::Segments).unwrap(), 0);
+        assert_eq!(payloads(Format::Segments, data_dir), expected);
+        assert_eq!(pending(data_dir, "other", Format::Segments).unwrap(), None);
+    }
+
+    #[cfg(feature = "leveldb")]
+    fn write(format: Format, path: &Path, payload: &[u8]) {
+        match format {
+            Format::LevelDb => {
+                let mut appender = leveldb_buffer::Appender::open(path).unwrap();
+                appender.append(payload).unwrap();
+                appender.flush().unwrap();
+            }
+            Format::Segments => {
+                let (writer, _reader, _acker) = segment_buffer::open(path, usize::MAX, 0).unwrap();
+                writer.write(payload).unwrap();
             }
-        })?;
+        }
+    }
 
-    let (writer, reader, acker) = leveldb_buffer::Buffer::build(path, max_size)?;
-    Ok((Writer { inner: writer }, Box::new(reader), acker))
+    #[cfg(feature = "leveldb")]
+    #[test]
+    fn recovers_interrupted_migrations() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let data_dir = data_dir.path();
+        let path = Format::Segments.path(data_dir, "sink");
+        let leveldb_path = Format::LevelDb.path(data_dir, "sink");
+        let staging = Staging::new(&path);
+
+        // Interrupted while writing the new buffer.
+        write(Format::Segments, &path, b"first");
+        write(Format::LevelDb, &leveldb_path, b"second");
+        write(Format::Segments, &staging.staged, b"stale");
+        assert_eq!(migrate(data_dir, "sink", Format::Segments).unwrap(), 1);
+        assert!(!staging.staged.exists());
+        assert_eq!(
+            payloads(Format::Segments, data_dir),
+            vec![b"first".to_vec(), b"second".to_vec()]
+        );
+
+        // Interrupted while replacing the buffer.
+        write(Format::LevelDb, &leveldb_path, b"third");
+        fs::rename(&path, &staging.replaced).unwrap();
+        assert_eq!(migrate(data_dir, "sink", Format::Segments).unwrap(), 1);
+        assert!(!staging.replaced.exists());
+        assert_eq!(
+            payloads(Format::Segments, data_dir),
+            vec![b"first".to_vec(), b"second".to_vec(), b"third".to_vec()]
+        );
+    }
+
+    #[cfg(all(unix, feature = "leveldb"))]
+    #[test]
+    fn refuses_to_migrate_open_buffers() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let data_dir = data_dir.path();
+        let path = Format::Segments.path(data_dir, "sink");
+        let (writer, _reader, _acker) = segment_buffer::open(&path, usize::MAX, 0).unwrap();
+        writer.write(b"first").unwrap();
+
+        assert!(matches!(
+            migrate(data_dir, "sink", Format::LevelDb),
+            Err(Error::BufferLocked { .. })
+        ));
+        assert!(path.exists());
+    }
 }
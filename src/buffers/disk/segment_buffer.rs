@@ -0,0 +1,1051 @@
+//! An append-only disk buffer, storing events in a sequence of segment files.
+//!
+//! Each record is framed with the length and CRC32 checksum of its event, so
+//! records torn by an unclean shutdown are found and truncated when the buffer
+//! is opened again. Segments are synced to disk when they are closed, and at
+//! least once per `SYNC_INTERVAL` while written, so a crash of the host loses
+//! at most the records written since. Syncing waits for the disk, so it's done
+//! off the reactor. The position of the oldest unacknowledged record is
+//! checkpointed at most once per `CHECKPOINT_INTERVAL` as events are
+//! acknowledged, so a crash reads the records acknowledged since again, and
+//! segments are deleted once the checkpoint is past all of their records, so
+//! the buffer never needs to be compacted.
+//!
+//! The buffer is locked while open, so that another process, such as
+//! `vector buffer migrate`, can't write to it at the same time.
+//!
+//! When a minimum of free space is configured, the buffer is also full while
+//! the file system holding it has less space available, so that the
+//! `when_full` policy applies before writes start failing.
+
+use crate::{
+    buffers::Acker,
+    config::data_dir,
+    event::{proto, Event},
+    internal_events::{DiskBufferLowSpace, DiskBufferRecordCorrupted, DiskBufferUsage},
+};
+use bytes::Bytes;
+use futures::{task::AtomicWaker, Future, Sink, Stream};
+use prost::Message;
+use std::{
+    collections::VecDeque,
+    convert::TryInto,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, Read, Seek, SeekFrom, Write},
+    mem,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, Weak,
+    },
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+};
+use tokio::time::Delay;
+
+/// The length of the header of records, holding the length and the checksum
+/// of their event.
+const HEADER_LEN: u64 = 8;
+
+/// Segments are closed once they are this large, or an eighth of the size of
+/// the buffer for smaller buffers.
+const MAX_SEGMENT_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Events are at most this large once encoded, so that a corrupted length
+/// can't make the reader allocate more.
+const MAX_RECORD_LEN: usize = 64 * 1024 * 1024;
+
+/// How often the segment being written is synced to disk.
+const SYNC_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the position of the oldest unacknowledged record is
+/// checkpointed while events are acknowledged.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(1);
+
+const CHECKPOINT_FILE: &str = "checkpoint";
+
+const LOCK_FILE: &str = "lock";
+
+/// How often the space available on the file system is checked.
+const SPACE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// State shared by the writers and the reader of the buffer.
+struct Shared {
+    dir: PathBuf,
+    max_size: usize,
+    /// The space to leave available on the file system, 0 to not check it.
+    min_free_space: u64,
+    space: Mutex<Space>,
+    /// Whether a task will wake the writers blocked by the lack of space up.
+    space_recheck_scheduled: AtomicBool,
+    segment_size: u64,
+    /// Size of the records not acknowledged yet, headers included.
+    current_size: Arc<AtomicUsize>,
+    /// Number of the records not acknowledged yet.
+    current_events: AtomicUsize,
+    head: Mutex<Head>,
+    read_waker: Arc<AtomicWaker>,
+    blocked_writers: Mutex<Vec<Waker>>,
+    ack_counter: Arc<AtomicUsize>,
+    /// Held until the writers and the reader are gone.
+    _lock: File,
+}
+
+/// The space available on the file system when it was last checked.
+struct Space {
+    available: Option<u64>,
+    checked: Instant,
+}
+
+/// The segment being written.
+struct Head {
+    id: u64,
+    file: File,
+    len: u64,
+    /// When the segment was last synced to disk.
+    synced: Instant,
+}
+
+impl Shared {
+    fn segment_path(&self, id: u64) -> PathBuf {
+        segment_path(&self.dir, id)
+    }
+
+    fn is_full(&self) -> bool {
+        self.current_size.load(Ordering::Relaxed) >= self.max_size || self.is_low_on_space()
+    }
+
+    fn is_low_on_space(&self) -> bool {
+        if self.min_free_space == 0 {
+            return false;
+        }
+
+        let mut space = self.space.lock().expect("Space lock is poisoned");
+        if space.checked.elapsed() >= SPACE_CHECK_INTERVAL {
+            space.available = self.available_space();
+            space.checked = Instant::now();
+            if let Some(available) = space.available.filter(|a| *a < self.min_free_space) {
+                emit!(DiskBufferLowSpace {
+                    data_dir: &self.dir,
+                    available,
+                    min_free_space: self.min_free_space,
+                });
+            }
+        }
+        space
+            .available
+            .map_or(false, |available| available < self.min_free_space)
+    }
+
+    fn available_space(&self) -> Option<u64> {
+        data_dir::available_space(&self.dir)
+            .map_err(|error| debug!(message = "Unable to get the available space.", %error))
+            .ok()
+    }
+
+    /// Wakes the writers up once the space is worth checking again, as it may
+    /// be freed by other processes rather than by acknowledging events.
+    fn schedule_space_recheck(self: &Arc<Self>) {
+        if self.space_recheck_scheduled.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        // A weak reference, so the reader still ends once the writers are gone.
+        let shared = Arc::downgrade(self);
+        tokio::spawn(async move {
+            tokio::time::delay_for(SPACE_CHECK_INTERVAL).await;
+            if let Some(shared) = Weak::upgrade(&shared) {
+                shared
+                    .space_recheck_scheduled
+                    .store(false, Ordering::Release);
+                shared.wake_writers();
+            }
+        });
+    }
+
+    fn wake_writers(&self) {
+        for waker in self
+            .blocked_writers
+            .lock()
+            .expect("Blocked writers lock is poisoned")
+            .drain(..)
+        {
+            waker.wake();
+        }
+    }
+
+    fn emit_usage(&self) {
+        emit!(DiskBufferUsage {
+            events: self.current_events.load(Ordering::Relaxed),
+            byte_size: self.current_size.load(Ordering::Relaxed),
+            available_space: self.space.lock().expect("Space lock is poisoned").available,
+        });
+    }
+}
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        let head = self.head.get_mut().expect("Segment lock is poisoned");
+        if let Err(error) = head.file.sync_data() {
+            error!(message = "Failed to sync disk buffer segment.", %error);
+        }
+    }
+}
+
+fn segment_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(format!("{:020}.seg", id))
+}
+
+pub struct Writer {
+    shared: Arc<Shared>,
+}
+
+impl Clone for Writer {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl Writer {
+    /// Size in bytes of the events stored in the buffer.
+    pub fn current_size(&self) -> Arc<AtomicUsize> {
+        Arc::clone(&self.shared.current_size)
+    }
+
+    /// Appends the encoded event `payload`.
+    pub(super) fn write(&self, payload: &[u8]) -> io::Result<()> {
+        let shared = &self.shared;
+        let mut record = Vec::with_capacity(HEADER_LEN as usize + payload.len());
+        record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        record.extend_from_slice(&crc32fast::hash(payload).to_le_bytes());
+        record.extend_from_slice(payload);
+
+        let mut head = shared.head.lock().expect("Segment lock is poisoned");
+        // Records are written at once, so the reader never sees a partial one
+        // while the process runs.
+        head.file.write_all(&record)?;
+        head.len += record.len() as u64;
+        shared
+            .current_size
+            .fetch_add(record.len(), Ordering::Relaxed);
+        shared.current_events.fetch_add(1, Ordering::Relaxed);
+
+        // The segment is synced without holding its lock, so that the other
+        // writers keep appending meanwhile.
+        let (file, created) = if head.len >= shared.segment_size {
+            let id = head.id + 1;
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(shared.segment_path(id))?;
+            let closed = mem::replace(
+                &mut *head,
+                Head {
+                    id,
+                    file,
+                    len: 0,
+                    synced: Instant::now(),
+                },
+            );
+            (closed.file, true)
+        } else if head.synced.elapsed() >= SYNC_INTERVAL {
+            head.synced = Instant::now();
+            (head.file.try_clone()?, false)
+        } else {
+            return Ok(());
+        };
+        drop(head);
+
+        tokio::task::block_in_place(|| {
+            file.sync_data()?;
+            if created {
+                sync_dir(&shared.dir)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl Sink<Event> for Writer {
+    type Error = ();
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let shared = &self.shared;
+        if !shared.is_full() {
+            return Poll::Ready(Ok(()));
+        }
+
+        {
+            // Writers are polled again and again while blocked, but only
+            // need to be woken up once.
+            let mut blocked_writers = shared
+                .blocked_writers
+                .lock()
+                .expect("Blocked writers lock is poisoned");
+            if !blocked_writers
+                .iter()
+                .any(|waker| waker.will_wake(cx.waker()))
+            {
+                blocked_writers.push(cx.waker().clone());
+            }
+        }
+        // The reader may have made room before the waker was registered.
+        if shared.is_full() {
+            if shared.is_low_on_space() {
+                shared.schedule_space_recheck();
+            }
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, event: Event) -> Result<(), Self::Error> {
+        let mut payload = Vec::new();
+        proto::EventWrapper::from(event)
+            .encode(&mut payload)
+            .expect("Encoding into a Vec can't fail");
+        if payload.len() > MAX_RECORD_LEN {
+            error!(
+                message = "Event is too large for the disk buffer; dropping it.",
+                size = payload.len(),
+                max_size = MAX_RECORD_LEN,
+            );
+            return Ok(());
+        }
+
+        self.write(&payload)
+            .map_err(|error| error!(message = "Failed to write to disk buffer.", %error))?;
+        self.shared.read_waker.wake();
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        // Wakes the reader up so it can end once there are no writers left.
+        self.shared.read_waker.wake();
+    }
+}
+
+pub struct Reader {
+    shared: Arc<Shared>,
+    /// The segment being read, and the position of its next record.
+    segment: u64,
+    offset: u64,
+    file: Option<BufReader<File>>,
+    /// The oldest segment not deleted yet.
+    first_segment: u64,
+    /// Segment, position, and size of the records read but not acknowledged
+    /// yet.
+    unacked: VecDeque<(u64, u64, usize)>,
+    /// Whether records were acknowledged since the last checkpoint.
+    checkpoint_pending: bool,
+    /// When the last checkpoint was written.
+    checkpointed: Instant,
+    /// Wakes the reader up once the pending checkpoint is due.
+    checkpoint_delay: Option<Delay>,
+}
+
+impl Stream for Reader {
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        this.delete_acked();
+        if this.checkpoint_pending {
+            this.poll_checkpoint(cx);
+        }
+
+        // If there's no record to read, we return Pending and rely on the
+        // writers and the acker to wake this task up.
+        this.shared.read_waker.register(cx.waker());
+
+        loop {
+            let (head_id, head_len) = {
+                let head = this.shared.head.lock().expect("Segment lock is poisoned");
+                (head.id, head.len)
+            };
+
+            if this.segment == head_id && this.offset >= head_len {
+                return if Arc::strong_count(&this.shared) == 1 {
+                    // There are no writers left.
+                    if this.checkpoint_pending {
+                        this.checkpoint();
+                    }
+                    Poll::Ready(None)
+                } else {
+                    Poll::Pending
+                };
+            }
+
+            let offset = this.offset;
+            match tokio::task::block_in_place(|| this.read_record()) {
+                Ok(Some(payload)) => {
+                    let size = (HEADER_LEN as usize) + payload.len();
+                    match proto::EventWrapper::decode(Bytes::from(payload)) {
+                        Ok(event) => {
+                            this.unacked.push_back((this.segment, offset, size));
+                            return Poll::Ready(Some(event.into()));
+                        }
+                        Err(error) => {
+                            error!(message = "Error deserializing proto.", %error);
+                            this.discard(1, size);
+                        }
+                    }
+                }
+                // Older segments are read until their end.
+                Ok(None) => this.next_segment(),
+                Err(error) => {
+                    emit!(DiskBufferRecordCorrupted {
+                        segment: this.segment,
+                        error
+                    });
+                    if this.segment == head_id {
+                        this.offset = head_len;
+                        this.file = None;
+                    } else {
+                        this.next_segment();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Reader {
+    /// Reads the payload of the next record, or `None` at the end of the
+    /// segment.
+    fn read_record(&mut self) -> io::Result<Option<Vec<u8>>> {
+        if self.file.is_none() {
+            let mut file = File::open(self.shared.segment_path(self.segment))?;
+            file.seek(SeekFrom::Start(self.offset))?;
+            self.file = Some(BufReader::new(file));
+        }
+        let file = self.file.as_mut().expect("Segment was just opened");
+
+        let payload = read_record(file)?;
+        if let Some(payload) = &payload {
+            self.offset += HEADER_LEN + payload.len() as u64;
+        }
+        Ok(payload)
+    }
+
+    fn next_segment(&mut self) {
+        self.segment += 1;
+        self.offset = 0;
+        self.file = None;
+    }
+
+    /// Forgets records that won't be acknowledged, as they were never sent.
+    fn discard(&mut self, events: usize, size: usize) {
+        saturating_sub(&self.shared.current_events, events);
+        saturating_sub(&self.shared.current_size, size);
+    }
+
+    fn delete_acked(&mut self) {
+        let num_to_delete = self.shared.ack_counter.swap(0, Ordering::Relaxed);
+        if num_to_delete == 0 {
+            return;
+        }
+
+        assert!(
+            num_to_delete <= self.unacked.len(),
+            "Tried to ack beyond read offset"
+        );
+        let size = self
+            .unacked
+            .drain(..num_to_delete)
+            .map(|(_, _, size)| size)
+            .sum();
+        self.discard(num_to_delete, size);
+        self.checkpoint_pending = true;
+
+        self.shared.wake_writers();
+        self.shared.emit_usage();
+    }
+
+    /// Checkpoints once `CHECKPOINT_INTERVAL` has passed since the last
+    /// checkpoint, as it syncs both the checkpoint and the directory, and
+    /// otherwise wakes the reader up when it's due.
+    fn poll_checkpoint(&mut self, cx: &mut Context<'_>) {
+        let due = self.checkpointed + CHECKPOINT_INTERVAL;
+        if Instant::now() < due {
+            let delay = self
+                .checkpoint_delay
+                .get_or_insert_with(|| tokio::time::delay_until(due.into()));
+            if Pin::new(delay).poll(cx).is_pending() {
+                return;
+            }
+        }
+        self.checkpoint();
+    }
+
+    /// Checkpoints the oldest record not acknowledged yet, and deletes the
+    /// segments before it.
+    fn checkpoint(&mut self) {
+        self.checkpoint_pending = false;
+        self.checkpointed = Instant::now();
+        self.checkpoint_delay = None;
+
+        let (segment, offset) = self
+            .unacked
+            .front()
+            .map(|(segment, offset, _)| (*segment, *offset))
+            .unwrap_or((self.segment, self.offset));
+        let shared = &self.shared;
+        let first_segment = &mut self.first_segment;
+        tokio::task::block_in_place(|| {
+            if let Err(error) = write_checkpoint(&shared.dir, segment, offset) {
+                error!(message = "Failed to checkpoint disk buffer.", %error);
+                return;
+            }
+            while *first_segment < segment {
+                match fs::remove_file(shared.segment_path(*first_segment)) {
+                    Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                        error!(message = "Failed to delete disk buffer segment.", %error);
+                    }
+                    _ => {}
+                }
+                *first_segment += 1;
+            }
+        });
+    }
+}
+
+impl Drop for Reader {
+    fn drop(&mut self) {
+        self.delete_acked();
+        if self.checkpoint_pending {
+            self.checkpoint();
+        }
+    }
+}
+
+fn saturating_sub(counter: &AtomicUsize, num: usize) {
+    let _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+        Some(n.saturating_sub(num))
+    });
+}
+
+/// Reads the payload of the record at the current position of `file`, or
+/// `None` at its end.
+fn read_record(file: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut header = [0; HEADER_LEN as usize];
+    match file.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+    let (len, checksum) = parse_header(header);
+    if len as usize > MAX_RECORD_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "record too large",
+        ));
+    }
+
+    let mut payload = vec![0; len as usize];
+    file.read_exact(&mut payload)?;
+    if crc32fast::hash(&payload) != checksum {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "checksum mismatch",
+        ));
+    }
+    Ok(Some(payload))
+}
+
+fn parse_header(header: [u8; HEADER_LEN as usize]) -> (u32, u32) {
+    let len = u32::from_le_bytes(header[..4].try_into().expect("4 bytes"));
+    let checksum = u32::from_le_bytes(header[4..].try_into().expect("4 bytes"));
+    (len, checksum)
+}
+
+fn read_checkpoint(dir: &Path) -> io::Result<Option<(u64, u64)>> {
+    match fs::read(dir.join(CHECKPOINT_FILE)) {
+        Ok(bytes) if bytes.len() == 16 => {
+            let segment = u64::from_le_bytes(bytes[..8].try_into().expect("8 bytes"));
+            let offset = u64::from_le_bytes(bytes[8..].try_into().expect("8 bytes"));
+            Ok(Some((segment, offset)))
+        }
+        Ok(_) => {
+            warn!(message = "Disk buffer checkpoint is invalid; reading all segments.");
+            Ok(None)
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// Replaces the checkpoint at once, so that a crash leaves either the old or
+/// the new one.
+fn write_checkpoint(dir: &Path, segment: u64, offset: u64) -> io::Result<()> {
+    let path = dir.join(CHECKPOINT_FILE);
+    let tmp_path = path.with_extension("tmp");
+
+    let mut checkpoint = [0; 16];
+    checkpoint[..8].copy_from_slice(&segment.to_le_bytes());
+    checkpoint[8..].copy_from_slice(&offset.to_le_bytes());
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(&checkpoint)?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, &path)?;
+    sync_dir(dir)
+}
+
+/// Syncs the entries of `dir`, so that the files created or renamed in it
+/// are found after a crash.
+#[cfg(unix)]
+pub(super) fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+pub(super) fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Locks the buffer in `dir` until the returned file is closed. Fails with
+/// `WouldBlock` if it's already locked, even by the same process.
+#[cfg(unix)]
+pub fn lock(dir: &Path) -> io::Result<File> {
+    use nix::{
+        errno::Errno,
+        fcntl::{flock, FlockArg},
+    };
+    use std::os::unix::io::AsRawFd;
+
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .open(dir.join(LOCK_FILE))?;
+    flock(file.as_raw_fd(), FlockArg::LockExclusiveNonblock).map_err(|error| match error {
+        nix::Error::Sys(Errno::EWOULDBLOCK) => io::Error::new(
+            io::ErrorKind::WouldBlock,
+            "the buffer is used by another process",
+        ),
+        error => io::Error::new(io::ErrorKind::Other, error),
+    })?;
+    Ok(file)
+}
+
+/// Buffers are only locked on Unix.
+#[cfg(not(unix))]
+pub fn lock(dir: &Path) -> io::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .write(true)
+        .open(dir.join(LOCK_FILE))
+}
+
+/// The records of a segment left after its recovery.
+#[derive(Debug, Default, PartialEq)]
+struct Recovered {
+    len: u64,
+    events: usize,
+    size: usize,
+}
+
+/// Truncates a segment after its last valid record, counting the records
+/// from `offset` on.
+fn recover_segment(path: &Path, offset: u64) -> io::Result<Recovered> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let file_len = file.metadata()?.len();
+    let mut reader = BufReader::new(&mut file);
+
+    let mut recovered = Recovered::default();
+    let mut header = [0; HEADER_LEN as usize];
+    let mut payload = Vec::new();
+    while recovered.len < file_len {
+        if reader.read_exact(&mut header).is_err() {
+            break;
+        }
+        let (len, checksum) = parse_header(header);
+        if len as usize > MAX_RECORD_LEN || recovered.len + HEADER_LEN + u64::from(len) > file_len {
+            break;
+        }
+        payload.resize(len as usize, 0);
+        if reader.read_exact(&mut payload).is_err() || crc32fast::hash(&payload) != checksum {
+            break;
+        }
+
+        if recovered.len >= offset {
+            recovered.events += 1;
+            recovered.size += (HEADER_LEN + u64::from(len)) as usize;
+        }
+        recovered.len += HEADER_LEN + u64::from(len);
+    }
+
+    if recovered.len < file_len {
+        emit!(DiskBufferRecordCorrupted {
+            segment: segment_id(path).unwrap_or_default(),
+            error: io::Error::new(io::ErrorKind::InvalidData, "partially written record"),
+        });
+        file.set_len(recovered.len)?;
+        file.sync_data()?;
+    }
+    Ok(recovered)
+}
+
+fn segment_id(path: &Path) -> Option<u64> {
+    let name = path.file_name()?.to_str()?;
+    name.strip_suffix(".seg")?.parse().ok()
+}
+
+fn segment_ids(dir: &Path) -> io::Result<Vec<u64>> {
+    let mut segments = fs::read_dir(dir)?
+        .filter_map(|entry| segment_id(&entry.ok()?.path()))
+        .collect::<Vec<_>>();
+    segments.sort_unstable();
+    Ok(segments)
+}
+
+/// Calls `f` with the payloads of the records of the buffer in `dir` not
+/// acknowledged yet, oldest first, without modifying the buffer. Returns the
+/// number of records.
+pub fn read_payloads(dir: &Path, mut f: impl FnMut(&[u8]) -> io::Result<()>) -> io::Result<usize> {
+    let segments = segment_ids(dir)?;
+    let (first, offset) =
+        read_checkpoint(dir)?.unwrap_or_else(|| (segments.first().copied().unwrap_or(0), 0));
+
+    let mut count = 0;
+    for id in segments.into_iter().filter(|id| *id >= first) {
+        let mut file = BufReader::new(File::open(segment_path(dir, id))?);
+        if id == first {
+            file.seek(SeekFrom::Start(offset))?;
+        }
+        loop {
+            match read_record(&mut file) {
+                Ok(Some(payload)) => {
+                    f(&payload)?;
+                    count += 1;
+                }
+                Ok(None) => break,
+                Err(error) => {
+                    emit!(DiskBufferRecordCorrupted { segment: id, error });
+                    break;
+                }
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// Opens the buffer in `dir`, creating it if needed. Fails if it's already
+/// open.
+pub fn open(
+    dir: &Path,
+    max_size: usize,
+    min_free_space: usize,
+) -> io::Result<(Writer, Reader, Acker)> {
+    fs::create_dir_all(dir)?;
+    let lock = lock(dir)?;
+
+    let mut segments = segment_ids(dir)?;
+
+    // Segments older than the checkpoint were acknowledged, but not deleted
+    // before the buffer was closed.
+    let (mut segment, mut offset) =
+        read_checkpoint(dir)?.unwrap_or_else(|| (segments.first().copied().unwrap_or(0), 0));
+    for id in segments.iter().filter(|id| **id < segment) {
+        fs::remove_file(segment_path(dir, *id))?;
+    }
+    segments.retain(|id| *id >= segment);
+    match segments.first() {
+        Some(first) if *first > segment => {
+            segment = *first;
+            offset = 0;
+        }
+        None => offset = 0,
+        _ => {}
+    }
+
+    let mut current_events = 0;
+    let mut current_size = 0;
+    let mut head_len = 0;
+    for id in &segments {
+        let start = if *id == segment { offset } else { 0 };
+        let recovered = recover_segment(&segment_path(dir, *id), start)?;
+        current_events += recovered.events;
+        current_size += recovered.size;
+        head_len = recovered.len;
+        if *id == segment {
+            offset = offset.min(recovered.len);
+        }
+    }
+
+    let head_id = segments.last().copied().unwrap_or(segment);
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(segment_path(dir, head_id))?;
+    sync_dir(dir)?;
+
+    let ack_counter = Arc::new(AtomicUsize::new(0));
+    let read_waker = Arc::new(AtomicWaker::new());
+    let shared = Arc::new(Shared {
+        dir: dir.to_owned(),
+        max_size,
+        min_free_space: min_free_space as u64,
+        space: Mutex::new(Space {
+            available: data_dir::available_space(dir).ok(),
+            checked: Instant::now(),
+        }),
+        space_recheck_scheduled: AtomicBool::new(false),
+        segment_size: (max_size as u64 / 8).max(1).min(MAX_SEGMENT_SIZE),
+        current_size: Arc::new(AtomicUsize::new(current_size)),
+        current_events: AtomicUsize::new(current_events),
+        head: Mutex::new(Head {
+            id: head_id,
+            file,
+            len: head_len,
+            synced: Instant::now(),
+        }),
+        read_waker: Arc::clone(&read_waker),
+        blocked_writers: Mutex::new(Vec::new()),
+        ack_counter: Arc::clone(&ack_counter),
+        _lock: lock,
+    });
+    shared.emit_usage();
+
+    let writer = Writer {
+        shared: Arc::clone(&shared),
+    };
+    let reader = Reader {
+        shared,
+        segment,
+        offset,
+        file: None,
+        first_segment: segment,
+        unacked: VecDeque::new(),
+        checkpoint_pending: false,
+        checkpointed: Instant::now(),
+        checkpoint_delay: None,
+    };
+    let acker = Acker::Segments(ack_counter, read_waker);
+
+    Ok((writer, reader, acker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::trace_init;
+    use futures::{SinkExt, StreamExt};
+
+    fn events(count: usize) -> Vec<Event> {
+        (0..count)
+            .map(|i| Event::from(format!("event {}", i)))
+            .collect()
+    }
+
+    async fn read(reader: &mut Reader, count: usize) -> Vec<Event> {
+        let mut events = Vec::new();
+        for _ in 0..count {
+            events.push(reader.next().await.unwrap());
+        }
+        events
+    }
+
+    #[tokio::test(core_threads = 2)]
+    async fn writes_and_reads_across_segments() {
+        trace_init();
+        let dir = tempfile::tempdir().unwrap();
+
+        // Segments of 128 bytes, holding a couple of events each.
+        let (mut writer, mut reader, acker) = open(dir.path(), 1024, 0).unwrap();
+        let sent = events(10);
+        for event in sent.clone() {
+            writer.send(event).await.unwrap();
+        }
+        assert!(segment_path(dir.path(), 1).exists());
+
+        assert_eq!(read(&mut reader, 10).await, sent);
+        acker.ack(10);
+        drop(writer);
+        assert!(reader.next().await.is_none());
+
+        assert!(!segment_path(dir.path(), 0).exists());
+        assert_eq!(reader.shared.current_size.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test(core_threads = 2)]
+    async fn resumes_after_acked_events() {
+        trace_init();
+        let dir = tempfile::tempdir().unwrap();
+        let sent = events(6);
+
+        {
+            let (mut writer, mut reader, acker) = open(dir.path(), 1024, 0).unwrap();
+            for event in sent.clone() {
+                writer.send(event).await.unwrap();
+            }
+            read(&mut reader, 4).await;
+            acker.ack(2);
+        }
+
+        let (_writer, mut reader, _acker) = open(dir.path(), 1024, 0).unwrap();
+        assert_eq!(reader.shared.current_events.load(Ordering::Relaxed), 4);
+        assert_eq!(read(&mut reader, 4).await, sent[2..].to_vec());
+    }
+
+    #[tokio::test(core_threads = 2)]
+    async fn rate_limits_checkpoints() {
+        trace_init();
+        let dir = tempfile::tempdir().unwrap();
+
+        let (mut writer, mut reader, acker) = open(dir.path(), 1024, 0).unwrap();
+        for event in events(3) {
+            writer.send(event).await.unwrap();
+        }
+        read(&mut reader, 2).await;
+        acker.ack(1);
+        assert_eq!(read(&mut reader, 1).await, events(3)[2..].to_vec());
+        assert_eq!(read_checkpoint(dir.path()).unwrap(), None);
+
+        // The checkpoint is written once it's due.
+        reader.checkpointed -= CHECKPOINT_INTERVAL;
+        assert!(futures::poll!(reader.next()).is_pending());
+        let (segment, offset, _) = reader.unacked[0];
+        assert_eq!(
+            read_checkpoint(dir.path()).unwrap(),
+            Some((segment, offset))
+        );
+        assert!(!reader.checkpoint_pending);
+    }
+
+    #[tokio::test(core_threads = 2)]
+    async fn truncates_partial_records() {
+        trace_init();
+        let dir = tempfile::tempdir().unwrap();
+        let sent = events(2);
+
+        {
+            let (mut writer, _reader, _acker) = open(dir.path(), 1024 * 1024, 0).unwrap();
+            for event in sent.clone() {
+                writer.send(event).await.unwrap();
+            }
+        }
+        // A record torn by a crash, with its header but only part of its event.
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(segment_path(dir.path(), 0))
+            .unwrap();
+        file.write_all(&[100, 0, 0, 0, 1, 2, 3, 4, 5, 6]).unwrap();
+        drop(file);
+
+        let (mut writer, mut reader, _acker) = open(dir.path(), 1024 * 1024, 0).unwrap();
+        assert_eq!(reader.shared.current_events.load(Ordering::Relaxed), 2);
+        writer.send(Event::from("after recovery")).await.unwrap();
+
+        let mut expected = sent;
+        expected.push(Event::from("after recovery"));
+        assert_eq!(read(&mut reader, 3).await, expected);
+    }
+
+    #[tokio::test(core_threads = 2)]
+    async fn blocks_when_full() {
+        trace_init();
+        let dir = tempfile::tempdir().unwrap();
+
+        // A single event fills the buffer.
+        let (mut writer, mut reader, acker) = open(dir.path(), 1, 0).unwrap();
+        writer.send(Event::from("first")).await.unwrap();
+        assert!(futures::poll!(writer.send(Event::from("blocked"))).is_pending());
+
+        read(&mut reader, 1).await;
+        acker.ack(1);
+        assert!(futures::poll!(reader.next()).is_pending());
+        writer.send(Event::from("unblocked")).await.unwrap();
+    }
+
+    #[tokio::test(core_threads = 2)]
+    async fn registers_blocked_writers_once() {
+        trace_init();
+        let dir = tempfile::tempdir().unwrap();
+
+        let (mut writer, _reader, _acker) = open(dir.path(), 1, 0).unwrap();
+        writer.send(Event::from("first")).await.unwrap();
+        for _ in 0..3 {
+            assert!(futures::poll!(writer.send(Event::from("blocked"))).is_pending());
+        }
+        assert_eq!(writer.shared.blocked_writers.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn locks_open_buffers() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let buffer = open(dir.path(), 1024, 0).unwrap();
+        #[cfg(unix)]
+        {
+            let error = open(dir.path(), 1024, 0).err().unwrap();
+            assert_eq!(error.kind(), io::ErrorKind::WouldBlock);
+        }
+        drop(buffer);
+        open(dir.path(), 1024, 0).unwrap();
+    }
+
+    #[test]
+    fn rejects_oversized_records() {
+        let mut record = io::Cursor::new(vec![0xff; HEADER_LEN as usize]);
+        let error = read_record(&mut record).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test(core_threads = 2)]
+    async fn reads_unacked_payloads() {
+        trace_init();
+        let dir = tempfile::tempdir().unwrap();
+
+        {
+            let (mut writer, mut reader, acker) = open(dir.path(), 1024, 0).unwrap();
+            for event in events(6) {
+                writer.send(event).await.unwrap();
+            }
+            read(&mut reader, 3).await;
+            acker.ack(3);
+        }
+
+        let mut payloads = Vec::new();
+        let count = read_payloads(dir.path(), |payload| {
+            payloads.push(payload.to_vec());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(count, 3);
+
+        let events = payloads
+            .into_iter()
+            .map(|payload| Event::from(proto::EventWrapper::decode(Bytes::from(payload)).unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(events, self::events(6)[3..].to_vec());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(core_threads = 2)]
+    async fn blocks_when_low_on_space() {
+        trace_init();
+        let dir = tempfile::tempdir().unwrap();
+
+        // More free space than any file system has.
+        let (mut writer, _reader, _acker) = open(dir.path(), 1024, usize::MAX).unwrap();
+        assert!(futures::poll!(writer.send(Event::from("blocked"))).is_pending());
+        assert_eq!(writer.shared.current_events.load(Ordering::Relaxed), 0);
+
+        let dir = tempfile::tempdir().unwrap();
+        let (mut writer, _reader, _acker) = open(dir.path(), 1024, 1).unwrap();
+        writer.send(Event::from("written")).await.unwrap();
+    }
+}
@@ -1,24 +1,38 @@
-use crate::{config::Resource, Event};
+use crate::{
+    config::Resource,
+    event::{EventFinalizers, EventStatus},
+    Event,
+};
 #[cfg(feature = "leveldb")]
 use futures::compat::{Sink01CompatExt, Stream01CompatExt};
-use futures::{channel::mpsc, Sink, SinkExt, Stream};
+use futures::{channel::mpsc, future, task::AtomicWaker, Sink, SinkExt, Stream};
 use futures01::task::AtomicTask;
 use pin_project::pin_project;
 use serde::{Deserialize, Serialize};
 use std::{
-    path::PathBuf,
+    collections::VecDeque,
+    path::{Path, PathBuf},
     pin::Pin,
     sync::{
         atomic::{AtomicUsize, Ordering},
-        Arc,
+        Arc, Mutex,
     },
     task::{Context, Poll},
 };
 #[cfg(feature = "leveldb")]
 use tokio::stream::StreamExt;
 
-#[cfg(feature = "leveldb")]
+pub mod cmd;
 pub mod disk;
+mod priority;
+mod split;
+mod usage;
+
+use priority::DropByPriority;
+pub use priority::Priority;
+pub use split::SplitAcker;
+use usage::{register, Tracked};
+pub use usage::{usage, BufferUsage};
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(tag = "type")]
@@ -32,10 +46,26 @@ pub enum BufferConfig {
     },
     #[cfg(feature = "leveldb")]
     Disk {
+        #[serde(deserialize_with = "crate::serde::bytes")]
         max_size: usize,
         #[serde(default)]
         when_full: WhenFull,
     },
+    /// The segment-based disk buffer, which doesn't depend on leveldb.
+    DiskV2 {
+        #[serde(deserialize_with = "crate::serde::bytes")]
+        max_size: usize,
+        #[serde(default)]
+        when_full: WhenFull,
+        /// The space to leave available on the file system of the `data_dir`,
+        /// checked before starting. Below it the buffer is full.
+        #[serde(
+            default,
+            deserialize_with = "crate::serde::optional_bytes",
+            skip_serializing_if = "Option::is_none"
+        )]
+        min_free_space: Option<usize>,
+    },
 }
 
 impl Default for BufferConfig {
@@ -52,6 +82,9 @@ impl Default for BufferConfig {
 pub enum WhenFull {
     Block,
     DropNewest,
+    /// Drops the low and normal priority events first, and blocks on the high
+    /// priority ones.
+    DropByPriority,
 }
 
 impl Default for WhenFull {
@@ -62,45 +95,93 @@ impl Default for WhenFull {
 
 #[derive(Clone)]
 pub enum BufferInputCloner {
-    Memory(mpsc::Sender<Event>, WhenFull),
+    Memory(mpsc::Sender<Event>, WhenFull, Option<Arc<BufferUsage>>),
     #[cfg(feature = "leveldb")]
-    Disk(disk::Writer, WhenFull),
+    Disk(disk::Writer, WhenFull, Arc<BufferUsage>),
+    DiskV2(disk::segment_buffer::Writer, WhenFull, Arc<BufferUsage>),
+    /// Finalizes the events as they're buffered, for a sink without
+    /// acknowledgements, so that it doesn't hold back their sources.
+    Finalizing(Box<BufferInputCloner>),
 }
 
 impl BufferInputCloner {
+    /// Finalizes the events as they're buffered when `finalize` is set. A
+    /// reused input is only ever wrapped once.
+    pub fn finalizing(self, finalize: bool) -> Self {
+        let inner = match self {
+            BufferInputCloner::Finalizing(inner) => *inner,
+            inner => inner,
+        };
+        if finalize {
+            BufferInputCloner::Finalizing(Box::new(inner))
+        } else {
+            inner
+        }
+    }
+
     pub fn get(&self) -> Box<dyn Sink<Event, Error = ()> + Send> {
         match self {
-            BufferInputCloner::Memory(tx, when_full) => {
+            BufferInputCloner::Memory(tx, when_full, usage) => {
                 let inner = tx
                     .clone()
                     .sink_map_err(|error| error!(message = "Sender error.", %error));
-                if when_full == &WhenFull::DropNewest {
-                    Box::new(DropWhenFull::new(inner))
-                } else {
-                    Box::new(inner)
+                match usage {
+                    Some(usage) => with_when_full(
+                        Tracked::new(inner, Arc::clone(usage)),
+                        *when_full,
+                        Some(Arc::clone(usage)),
+                    ),
+                    None => with_when_full(inner, *when_full, None),
                 }
             }
 
             #[cfg(feature = "leveldb")]
-            BufferInputCloner::Disk(writer, when_full) => {
+            BufferInputCloner::Disk(writer, when_full, usage) => {
                 let inner = writer.clone().sink_compat();
-                if when_full == &WhenFull::DropNewest {
-                    Box::new(DropWhenFull::new(inner))
-                } else {
-                    Box::new(inner)
-                }
+                with_when_full(
+                    Tracked::new(inner, Arc::clone(usage)),
+                    *when_full,
+                    Some(Arc::clone(usage)),
+                )
+            }
+
+            BufferInputCloner::DiskV2(writer, when_full, usage) => with_when_full(
+                Tracked::new(writer.clone(), Arc::clone(usage)),
+                *when_full,
+                Some(Arc::clone(usage)),
+            ),
+
+            BufferInputCloner::Finalizing(inner) => {
+                Box::new(inner.get().with(|mut event: Event| {
+                    drop(event.take_finalizers());
+                    future::ready(Ok(event))
+                }))
             }
         }
     }
 }
 
+fn with_when_full<S>(
+    inner: S,
+    when_full: WhenFull,
+    usage: Option<Arc<BufferUsage>>,
+) -> Box<dyn Sink<Event, Error = ()> + Send>
+where
+    S: Sink<Event, Error = ()> + Send + Unpin + 'static,
+{
+    match when_full {
+        WhenFull::Block => Box::new(inner),
+        WhenFull::DropNewest => Box::new(DropWhenFull::new(inner)),
+        WhenFull::DropByPriority => Box::new(DropByPriority::new(inner, usage)),
+    }
+}
+
 impl BufferConfig {
     #[inline]
     const fn memory_max_events() -> usize {
         500
     }
 
-    #[cfg_attr(not(feature = "leveldb"), allow(unused))]
     pub fn build(
         &self,
         data_dir: &Option<PathBuf>,
@@ -118,10 +199,13 @@ impl BufferConfig {
                 max_events,
                 when_full,
             } => {
+                let usage = Arc::new(BufferUsage::new(self.clone(), None));
+                register(sink_name, Arc::clone(&usage));
+
                 let (tx, rx) = mpsc::channel(*max_events);
-                let tx = BufferInputCloner::Memory(tx, *when_full);
-                let rx = Box::new(rx);
-                Ok((tx, rx, Acker::Null))
+                let tx = BufferInputCloner::Memory(tx, *when_full, Some(Arc::clone(&usage)));
+                let rx = Box::new(Tracked::new(rx, Arc::clone(&usage)));
+                Ok((tx, rx, Acker::Tracked(Box::new(Acker::Null), usage)))
             }
 
             #[cfg(feature = "leveldb")]
@@ -133,37 +217,90 @@ impl BufferConfig {
                     .as_ref()
                     .ok_or_else(|| "Must set data_dir to use on-disk buffering.".to_string())?;
                 let buffer_dir = format!("{}_buffer", sink_name);
+                migrate(data_dir, sink_name, disk::Format::LevelDb)?;
 
                 let (tx, rx, acker) = disk::open(&data_dir, buffer_dir.as_ref(), *max_size)
                     .map_err(|error| error.to_string())?;
-                let tx = BufferInputCloner::Disk(tx, *when_full);
-                let rx = Box::new(
+                let usage = Arc::new(BufferUsage::new(self.clone(), Some(tx.current_size())));
+                register(sink_name, Arc::clone(&usage));
+
+                let tx = BufferInputCloner::Disk(tx, *when_full, Arc::clone(&usage));
+                let rx = Box::new(Tracked::new(
                     rx.compat()
                         .take_while(|event| event.is_ok())
                         .map(|event| event.unwrap()),
-                );
-                Ok((tx, rx, acker))
+                    Arc::clone(&usage),
+                ));
+                Ok((tx, rx, Acker::Tracked(Box::new(acker), usage)))
+            }
+
+            BufferConfig::DiskV2 {
+                max_size,
+                when_full,
+                min_free_space,
+            } => {
+                let data_dir = data_dir
+                    .as_ref()
+                    .ok_or_else(|| "Must set data_dir to use on-disk buffering.".to_string())?;
+                let buffer_dir = format!("{}_buffer_v2", sink_name);
+                migrate(data_dir, sink_name, disk::Format::Segments)?;
+
+                let (tx, rx, acker) =
+                    disk::open_segments(&data_dir, buffer_dir.as_ref(), *max_size, *min_free_space)
+                        .map_err(|error| error.to_string())?;
+                let usage = Arc::new(BufferUsage::new(self.clone(), Some(tx.current_size())));
+                register(sink_name, Arc::clone(&usage));
+
+                let tx = BufferInputCloner::DiskV2(tx, *when_full, Arc::clone(&usage));
+                let rx = Box::new(Tracked::new(rx, Arc::clone(&usage)));
+                Ok((tx, rx, Acker::Tracked(Box::new(acker), usage)))
             }
         }
     }
 
     /// Resources that the sink is using.
-    #[cfg_attr(not(feature = "leveldb"), allow(unused))]
     pub fn resources(&self, sink_name: &str) -> Vec<Resource> {
         match self {
             BufferConfig::Memory { .. } => Vec::new(),
             #[cfg(feature = "leveldb")]
             BufferConfig::Disk { .. } => vec![Resource::DiskBuffer(sink_name.to_string())],
+            BufferConfig::DiskV2 { .. } => vec![Resource::DiskBuffer(sink_name.to_string())],
         }
     }
 }
 
+/// Moves the events left in the buffer of the sink by its previous disk buffer
+/// type into the buffer it's configured with.
+fn migrate(data_dir: &Path, sink_name: &str, format: disk::Format) -> Result<(), String> {
+    let events = disk::migrate(data_dir, sink_name, format).map_err(|error| error.to_string())?;
+    if events > 0 {
+        info!(
+            message = "Migrated disk buffer.",
+            sink = %sink_name,
+            %events,
+            buffer_type = format.buffer_type()
+        );
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone)]
 pub enum Acker {
     Disk(Arc<AtomicUsize>, Arc<AtomicTask>),
+    Segments(Arc<AtomicUsize>, Arc<AtomicWaker>),
     Null,
+    /// Keeps the unacked events of a buffer's usage up to date.
+    Tracked(Box<Acker>, Arc<BufferUsage>),
+    /// Finalizes the events of a sink with acknowledgements enabled, in the
+    /// order they were read, as they're acked.
+    Finalizing(Box<Acker>, PendingFinalizers),
+    /// Acks the events of one of the consumers sharing a buffer.
+    Split(Arc<SplitAcker>, usize),
 }
 
+/// The finalizers of the events read by a sink, not acked yet.
+pub type PendingFinalizers = Arc<Mutex<VecDeque<EventFinalizers>>>;
+
 impl Acker {
     // This method should be called by a sink to indicate that it has successfully
     // flushed the next `num` events from its input stream. If there are events that
@@ -172,6 +309,13 @@ impl Acker {
     // This is primary used by the on-disk buffer to know which events are okay to
     // delete from disk.
     pub fn ack(&self, num: usize) {
+        self.ack_with_status(num, EventStatus::Delivered)
+    }
+
+    /// Acks the next `num` events like `ack`, finalizing them with `status`
+    /// when the sink has acknowledgements enabled. Failed events are still
+    /// acked, as the sink is done with them.
+    pub fn ack_with_status(&self, num: usize, status: EventStatus) {
         // Only ack items if the amount to ack is larger than zero.
         if num > 0 {
             match self {
@@ -180,10 +324,38 @@ impl Acker {
                     counter.fetch_add(num, Ordering::Relaxed);
                     notifier.notify();
                 }
+                Acker::Segments(counter, waker) => {
+                    counter.fetch_add(num, Ordering::Relaxed);
+                    waker.wake();
+                }
+                Acker::Tracked(inner, usage) => {
+                    inner.ack_with_status(num, status);
+                    usage.acked(num);
+                }
+                Acker::Finalizing(inner, pending) => {
+                    inner.ack_with_status(num, status);
+                    let mut pending = pending.lock().unwrap();
+                    let num = num.min(pending.len());
+                    for finalizers in pending.drain(..num) {
+                        finalizers.update_status(status);
+                    }
+                }
+                Acker::Split(split, consumer) => split.ack(*consumer, num, status),
             }
         }
     }
 
+    /// Wraps the acker so that acking events also finalizes them.
+    /// The finalizers of the events read by the sink have to be pushed to the
+    /// returned queue, in order.
+    pub fn with_finalizers(self) -> (Self, PendingFinalizers) {
+        let pending = PendingFinalizers::default();
+        (
+            Acker::Finalizing(Box::new(self), Arc::clone(&pending)),
+            pending,
+        )
+    }
+
     pub fn new_for_testing() -> (Self, Arc<AtomicUsize>) {
         let ack_counter = Arc::new(AtomicUsize::new(0));
         let notifier = Arc::new(AtomicTask::new());
@@ -247,8 +419,10 @@ impl<T, S: Sink<T> + Unpin> Sink<T> for DropWhenFull<S> {
 
 #[cfg(test)]
 mod test {
-    use super::{Acker, BufferConfig, DropWhenFull, WhenFull};
+    use super::{Acker, BufferConfig, BufferInputCloner, DropWhenFull, WhenFull};
+    use crate::event::{BatchNotifier, BatchStatus, EventStatus};
     use crate::sink::BoundedSink;
+    use crate::Event;
     use futures::{future, Sink, Stream};
     use futures01::task::AtomicTask;
     use std::{
@@ -301,6 +475,93 @@ mod test {
         assert!(mock.is_notified());
     }
 
+    #[tokio::test]
+    async fn tracks_usage() {
+        use futures::{SinkExt, StreamExt};
+
+        let config = BufferConfig::Memory {
+            max_events: 10,
+            when_full: WhenFull::Block,
+        };
+        let (tx, rx, acker) = config.build(&None, "tracks_usage").unwrap();
+        let usage = super::usage("tracks_usage").unwrap();
+
+        let mut tx = tx.get();
+        for _ in 0..3 {
+            tx.send(Event::from("line")).await.unwrap();
+        }
+        assert_eq!(usage.events(), 3);
+        assert_eq!(usage.unacked_events(), 0);
+
+        let mut rx = std::pin::Pin::from(rx);
+        rx.next().await.unwrap();
+        rx.next().await.unwrap();
+        assert_eq!(usage.events(), 1);
+        assert_eq!(usage.unacked_events(), 2);
+
+        acker.ack(2);
+        assert_eq!(usage.unacked_events(), 0);
+        assert_eq!(usage.bytes(), None);
+    }
+
+    #[test]
+    fn ack_finalizes_events() {
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let (acker, pending) = Acker::Null.with_finalizers();
+        for _ in 0..2 {
+            let mut event = Event::from("line").with_batch_notifier(&batch);
+            pending.lock().unwrap().push_back(event.take_finalizers());
+        }
+        drop(batch);
+
+        acker.ack(1);
+        assert!(receiver.try_recv().is_err());
+        acker.ack(1);
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+        assert!(pending.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn finalizing_input_finalizes_buffered_events() {
+        use futures::SinkExt;
+
+        let config = BufferConfig::Memory {
+            max_events: 10,
+            when_full: WhenFull::Block,
+        };
+        let (tx, _rx, _acker) = config.build(&None, "finalizing_input").unwrap();
+        let tx = tx.finalizing(true).finalizing(true);
+        assert!(matches!(&tx, BufferInputCloner::Finalizing(inner)
+            if matches!(**inner, BufferInputCloner::Memory(..))));
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let event = Event::from("line").with_batch_notifier(&batch);
+        drop(batch);
+        tx.get().send(event).await.unwrap();
+        // Delivered while still in the buffer.
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+        assert!(matches!(
+            tx.finalizing(false),
+            BufferInputCloner::Memory(..)
+        ));
+    }
+
+    #[test]
+    fn ack_with_status_finalizes_events() {
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let (acker, pending) = Acker::Null.with_finalizers();
+        for _ in 0..2 {
+            let mut event = Event::from("line").with_batch_notifier(&batch);
+            pending.lock().unwrap().push_back(event.take_finalizers());
+        }
+        drop(batch);
+
+        acker.ack(1);
+        acker.ack_with_status(1, EventStatus::Errored);
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Errored));
+    }
+
     #[test]
     fn config_default_values() {
         fn check(source: &str, config: BufferConfig) {
@@ -351,5 +612,43 @@ mod test {
                 when_full: WhenFull::Block,
             },
         );
+
+        #[cfg(feature = "leveldb")]
+        check(
+            r#"
+          type = "disk"
+          max_size = "5MiB"
+          "#,
+            BufferConfig::Disk {
+                max_size: 5 * 1024 * 1024,
+                when_full: WhenFull::Block,
+            },
+        );
+
+        check(
+            r#"
+          type = "disk_v2"
+          max_size = "5MiB"
+          "#,
+            BufferConfig::DiskV2 {
+                max_size: 5 * 1024 * 1024,
+                when_full: WhenFull::Block,
+                min_free_space: None,
+            },
+        );
+
+        check(
+            r#"
+          type = "disk_v2"
+          max_size = "5MiB"
+          when_full = "drop_newest"
+          min_free_space = "1GiB"
+          "#,
+            BufferConfig::DiskV2 {
+                max_size: 5 * 1024 * 1024,
+                when_full: WhenFull::DropNewest,
+                min_free_space: Some(1024 * 1024 * 1024),
+            },
+        );
     }
 }
@@ -0,0 +1,254 @@
+use super::BufferUsage;
+use crate::{internal_events::BufferEventDropped, Event};
+use futures::Sink;
+use pin_project::pin_project;
+use serde::{Deserialize, Serialize};
+use std::{
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+/// How full a buffer can get before its low priority events are dropped,
+/// keeping the rest of it for the events of higher priorities.
+const LOW_PRIORITY_FILL: f64 = 0.5;
+
+/// The priority class of an event, kept in its metadata.
+#[derive(Deserialize, Serialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    /// Dropped first, once the buffer is half full.
+    Low,
+    /// Dropped when the buffer is full.
+    Normal,
+    /// Never dropped, waiting for room in the buffer when it's full.
+    High,
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Priority::Normal
+    }
+}
+
+impl Priority {
+    /// The priority of `event`, `normal` when it has none.
+    pub fn of(event: &Event) -> Self {
+        event.metadata().priority().unwrap_or_default()
+    }
+
+    /// Gives `event` this priority, unless it already has one.
+    pub fn set_default(self, event: &mut Event) {
+        let metadata = event.metadata_mut();
+        if metadata.priority().is_none() {
+            metadata.set_priority(Some(self));
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Normal => "normal",
+            Priority::High => "high",
+        }
+    }
+}
+
+impl FromStr for Priority {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "low" => Ok(Priority::Low),
+            "normal" => Ok(Priority::Normal),
+            "high" => Ok(Priority::High),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Drops the events of the lower priorities when the buffer is filling up,
+/// while the high priority ones wait for room in it.
+#[pin_project]
+pub struct DropByPriority<S> {
+    #[pin]
+    inner: S,
+    usage: Option<Arc<BufferUsage>>,
+    ready: bool,
+    /// A high priority event waiting for room in the buffer.
+    pending: Option<Event>,
+}
+
+impl<S> DropByPriority<S> {
+    pub fn new(inner: S, usage: Option<Arc<BufferUsage>>) -> Self {
+        Self {
+            inner,
+            usage,
+            ready: false,
+            pending: None,
+        }
+    }
+}
+
+impl<S: Sink<Event>> DropByPriority<S> {
+    fn poll_pending(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        let mut this = self.project();
+        if this.pending.is_some() {
+            futures::ready!(this.inner.as_mut().poll_ready(cx))?;
+            let event = this.pending.take().expect("pending event");
+            this.inner.start_send(event)?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S: Sink<Event>> Sink<Event> for DropByPriority<S> {
+    type Error = S::Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        futures::ready!(self.as_mut().poll_pending(cx))?;
+
+        let this = self.project();
+        match this.inner.poll_ready(cx) {
+            Poll::Ready(Ok(())) => *this.ready = true,
+            Poll::Pending => *this.ready = false,
+            Poll::Ready(Err(error)) => return Poll::Ready(Err(error)),
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Event) -> Result<(), Self::Error> {
+        let this = self.project();
+        let priority = Priority::of(&item);
+
+        let keep = match priority {
+            Priority::High => true,
+            Priority::Normal => *this.ready,
+            Priority::Low => {
+                let fill = this.usage.as_ref().and_then(|usage| usage.fill());
+                *this.ready && fill.map_or(true, |fill| fill < LOW_PRIORITY_FILL)
+            }
+        };
+
+        if !keep {
+            emit!(BufferEventDropped {
+                priority: priority.as_str()
+            });
+            Ok(())
+        } else if *this.ready {
+            *this.ready = false;
+            this.inner.start_send(item)
+        } else {
+            *this.pending = Some(item);
+            Ok(())
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        futures::ready!(self.as_mut().poll_pending(cx))?;
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        futures::ready!(self.as_mut().poll_pending(cx))?;
+        self.project().inner.poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        buffers::{BufferConfig, WhenFull},
+        config::log_schema,
+        sink::BoundedSink,
+    };
+    use futures::{future, Stream};
+    use tokio::sync::mpsc;
+
+    fn event(priority: &str) -> Event {
+        let mut event = Event::from(priority);
+        event.metadata_mut().set_priority(priority.parse().ok());
+        event
+    }
+
+    fn message(event: Event) -> String {
+        event.as_log()[log_schema().message_key()].to_string_lossy()
+    }
+
+    #[test]
+    fn reads_priority() {
+        assert_eq!(Priority::of(&event("low")), Priority::Low);
+        assert_eq!(Priority::of(&event("high")), Priority::High);
+        assert_eq!(Priority::of(&Event::from("line")), Priority::Normal);
+    }
+
+    #[test]
+    fn sets_default_priority() {
+        let mut low = event("low");
+        Priority::High.set_default(&mut low);
+        assert_eq!(Priority::of(&low), Priority::Low);
+
+        let mut line = Event::from("line");
+        Priority::High.set_default(&mut line);
+        assert_eq!(Priority::of(&line), Priority::High);
+        assert!(!line.as_log().contains("priority"));
+    }
+
+    #[tokio::test]
+    async fn drops_lower_priorities_when_full() {
+        future::lazy(|cx| {
+            let (tx, rx) = mpsc::channel(2);
+            let mut tx = Box::pin(DropByPriority::new(BoundedSink::new(tx), None));
+
+            for priority in &["normal", "low", "normal", "low"] {
+                assert_eq!(tx.as_mut().poll_ready(cx), Poll::Ready(Ok(())));
+                assert_eq!(tx.as_mut().start_send(event(priority)), Ok(()));
+            }
+
+            // The high priority event waits for room in the buffer.
+            assert_eq!(tx.as_mut().poll_ready(cx), Poll::Ready(Ok(())));
+            assert_eq!(tx.as_mut().start_send(event("high")), Ok(()));
+            assert_eq!(tx.as_mut().poll_ready(cx), Poll::Pending);
+
+            let mut rx = Box::pin(rx);
+            let first = rx.as_mut().poll_next(cx).map(|event| event.map(message));
+            assert_eq!(first, Poll::Ready(Some("normal".into())));
+
+            assert_eq!(tx.as_mut().poll_ready(cx), Poll::Ready(Ok(())));
+            let mut received = Vec::new();
+            while let Poll::Ready(Some(event)) = rx.as_mut().poll_next(cx) {
+                received.push(message(event));
+            }
+            assert_eq!(received, vec!["low", "high"]);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn drops_low_priority_when_half_full() {
+        use futures::SinkExt;
+
+        let config = BufferConfig::Memory {
+            max_events: 4,
+            when_full: WhenFull::DropByPriority,
+        };
+        let (tx, rx, _acker) = config
+            .build(&None, "drops_low_priority_when_half_full")
+            .unwrap();
+
+        let mut tx = tx.get();
+        for priority in &["low", "low", "low", "low", "normal"] {
+            tx.send(event(priority)).await.unwrap();
+        }
+        drop(tx);
+
+        let received = crate::test_util::collect_ready(Pin::from(rx))
+            .await
+            .into_iter()
+            .map(message)
+            .collect::<Vec<_>>();
+        assert_eq!(received, vec!["low", "low", "normal"]);
+    }
+}
@@ -0,0 +1,135 @@
+use super::Acker;
+use crate::event::EventStatus;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+/// Shares the acker of a buffer between several consumers of its events, like
+/// a sink and the sink its quota diverts events to.
+///
+/// Each consumer acks the events it was handed in order, but not in step with
+/// the others, while the buffer has to be acked in the order the events were
+/// read. So the consumer of every event read is recorded, and the acks of the
+/// consumers are merged back into that order.
+#[derive(Debug)]
+pub struct SplitAcker {
+    acker: Acker,
+    state: Mutex<SplitState>,
+}
+
+#[derive(Debug)]
+struct SplitState {
+    /// The events read and not acked yet, in order.
+    pending: VecDeque<Pending>,
+    /// The statuses each consumer acked its events with, waiting for the
+    /// events read before them to be acked.
+    acked: Vec<VecDeque<EventStatus>>,
+}
+
+#[derive(Debug)]
+enum Pending {
+    /// Handed to a consumer, which acks it.
+    Consumer(usize),
+    /// Done with without being handed to any consumer.
+    Done(EventStatus),
+}
+
+impl SplitAcker {
+    pub fn new(acker: Acker, consumers: usize) -> Arc<Self> {
+        Arc::new(Self {
+            acker,
+            state: Mutex::new(SplitState {
+                pending: VecDeque::new(),
+                acked: vec![VecDeque::new(); consumers],
+            }),
+        })
+    }
+
+    /// The acker `consumer` acks the events it was handed with.
+    pub fn acker(self: &Arc<Self>, consumer: usize) -> Acker {
+        Acker::Split(Arc::clone(self), consumer)
+    }
+
+    /// Records that the next event read is handed to `consumer`. It must be
+    /// called before the consumer gets the event.
+    pub fn handed_to(&self, consumer: usize) {
+        self.state
+            .lock()
+            .unwrap()
+            .pending
+            .push_back(Pending::Consumer(consumer));
+    }
+
+    /// Acks the next event read with `status`, as soon as the events read
+    /// before it are acked, without handing it to any consumer.
+    pub fn done(&self, status: EventStatus) {
+        let mut state = self.state.lock().unwrap();
+        state.pending.push_back(Pending::Done(status));
+        self.merge(&mut state);
+    }
+
+    pub(super) fn ack(&self, consumer: usize, num: usize, status: EventStatus) {
+        let mut state = self.state.lock().unwrap();
+        state.acked[consumer].extend(std::iter::repeat(status).take(num));
+        self.merge(&mut state);
+    }
+
+    /// Acks the buffer for the events at the front of the pending ones that
+    /// are acked, grouping those of the same status.
+    fn merge(&self, state: &mut SplitState) {
+        let mut run: Option<(EventStatus, usize)> = None;
+        loop {
+            let status = match state.pending.front() {
+                Some(Pending::Done(status)) => *status,
+                Some(Pending::Consumer(consumer)) => match state.acked[*consumer].pop_front() {
+                    Some(status) => status,
+                    None => break,
+                },
+                None => break,
+            };
+            state.pending.pop_front();
+
+            run = match run {
+                Some((run_status, num)) if run_status == status => Some((status, num + 1)),
+                Some((run_status, num)) => {
+                    self.acker.ack_with_status(num, run_status);
+                    Some((status, 1))
+                }
+                None => Some((status, 1)),
+            };
+        }
+        if let Some((status, num)) = run {
+            self.acker.ack_with_status(num, status);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn acks_in_read_order() {
+        let (acker, counter) = Acker::new_for_testing();
+        let split = SplitAcker::new(acker, 2);
+        let (first, second) = (split.acker(0), split.acker(1));
+
+        split.handed_to(0);
+        split.handed_to(1);
+        split.done(EventStatus::Dropped);
+        split.handed_to(0);
+
+        // The second consumer can't ack ahead of the first one.
+        second.ack(1);
+        assert_eq!(counter.load(Ordering::Relaxed), 0);
+
+        // Nor can the dropped event be acked ahead of the last event.
+        first.ack(1);
+        assert_eq!(counter.load(Ordering::Relaxed), 3);
+
+        first.ack(1);
+        assert_eq!(counter.load(Ordering::Relaxed), 4);
+    }
+}
@@ -0,0 +1,162 @@
+use super::BufferConfig;
+use crate::Event;
+use futures::{Sink, Stream};
+use lazy_static::lazy_static;
+use pin_project::pin_project;
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+    task::{Context, Poll},
+};
+
+lazy_static! {
+    static ref USAGE: RwLock<HashMap<String, Arc<BufferUsage>>> = RwLock::new(HashMap::new());
+}
+
+/// Live state of the buffer of a sink, shared by its writers, its reader and its acker.
+#[derive(Debug)]
+pub struct BufferUsage {
+    config: BufferConfig,
+    events: AtomicUsize,
+    bytes: Option<Arc<AtomicUsize>>,
+    unacked_events: AtomicUsize,
+}
+
+impl BufferUsage {
+    /// `bytes` is the size counter of buffers that know the size of their content.
+    pub(super) fn new(config: BufferConfig, bytes: Option<Arc<AtomicUsize>>) -> Self {
+        Self {
+            config,
+            events: AtomicUsize::new(0),
+            bytes,
+            unacked_events: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &BufferConfig {
+        &self.config
+    }
+
+    /// Events written to the buffer since it was opened, which the sink hasn't read yet.
+    pub fn events(&self) -> usize {
+        self.events.load(Ordering::Relaxed)
+    }
+
+    /// Size of the buffered events, only known for disk buffers.
+    pub fn bytes(&self) -> Option<usize> {
+        self.bytes
+            .as_ref()
+            .map(|bytes| bytes.load(Ordering::Relaxed))
+    }
+
+    /// How full the buffer is, between 0 and 1, when its content is tracked.
+    pub fn fill(&self) -> Option<f64> {
+        match &self.config {
+            BufferConfig::Memory { max_events, .. } => {
+                Some(self.events() as f64 / (*max_events).max(1) as f64)
+            }
+            #[cfg(feature = "leveldb")]
+            BufferConfig::Disk { max_size, .. } => self
+                .bytes()
+                .map(|bytes| bytes as f64 / (*max_size).max(1) as f64),
+            BufferConfig::DiskV2 { max_size, .. } => self
+                .bytes()
+                .map(|bytes| bytes as f64 / (*max_size).max(1) as f64),
+        }
+    }
+
+    /// Events the sink has read, but not acknowledged yet.
+    pub fn unacked_events(&self) -> usize {
+        self.unacked_events.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn acked(&self, num: usize) {
+        saturating_sub(&self.unacked_events, num);
+    }
+
+    fn written(&self) {
+        self.events.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn read(&self) {
+        saturating_sub(&self.events, 1);
+        self.unacked_events.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn saturating_sub(counter: &AtomicUsize, num: usize) {
+    let _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+        Some(n.saturating_sub(num))
+    });
+}
+
+/// Records the buffer of a sink, replacing the one of a previous build.
+pub(super) fn register(sink_name: &str, usage: Arc<BufferUsage>) {
+    USAGE
+        .write()
+        .expect("buffer usage lock poisoned")
+        .insert(sink_name.to_owned(), usage);
+}
+
+/// Returns the state of the buffer of a sink, if it has been built.
+pub fn usage(sink_name: &str) -> Option<Arc<BufferUsage>> {
+    USAGE
+        .read()
+        .expect("buffer usage lock poisoned")
+        .get(sink_name)
+        .cloned()
+}
+
+/// Wraps the writing and reading ends of a buffer to keep its usage up to date.
+#[pin_project]
+pub struct Tracked<S> {
+    #[pin]
+    inner: S,
+    usage: Arc<BufferUsage>,
+}
+
+impl<S> Tracked<S> {
+    pub(super) fn new(inner: S, usage: Arc<BufferUsage>) -> Self {
+        Self { inner, usage }
+    }
+}
+
+impl<S: Sink<Event>> Sink<Event> for Tracked<S> {
+    type Error = S::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Event) -> Result<(), Self::Error> {
+        let this = self.project();
+        this.inner.start_send(item)?;
+        this.usage.written();
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_close(cx)
+    }
+}
+
+impl<S: Stream<Item = Event>> Stream for Tracked<S> {
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let poll = this.inner.poll_next(cx);
+        if let Poll::Ready(Some(_)) = &poll {
+            this.usage.read();
+        }
+        poll
+    }
+}
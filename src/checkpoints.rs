@@ -0,0 +1,158 @@
+use crate::{
+    config::{self, Config},
+    state::FileCheckpoints,
+};
+use colored::*;
+use file_source::Checkpointer;
+use std::{fs::File, io, path::PathBuf};
+use structopt::StructOpt;
+use tokio::task::spawn_blocking;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct ExportOpts {
+    /// Name of the `file` source whose checkpoints are exported. Can be omitted
+    /// when the config has a single `file` source.
+    #[structopt(short, long)]
+    source: Option<String>,
+
+    /// Write the checkpoints to a file instead of stdout.
+    #[structopt(short, long, parse(from_os_str))]
+    output: Option<PathBuf>,
+
+    /// Vector config files in TOML format.
+    #[structopt(name = "config-toml", long)]
+    paths_toml: Vec<PathBuf>,
+
+    /// Vector config files in JSON format.
+    #[structopt(name = "config-json", long)]
+    paths_json: Vec<PathBuf>,
+
+    /// Vector config files in YAML format.
+    #[structopt(name = "config-yaml", long)]
+    paths_yaml: Vec<PathBuf>,
+
+    /// Any number of Vector config files.
+    /// Format is detected from the file name.
+    /// If none are specified the default config path `/etc/vector/vector.toml`
+    /// will be targeted.
+    paths: Vec<PathBuf>,
+}
+
+impl ExportOpts {
+    fn paths_with_formats(&self) -> Vec<(PathBuf, config::FormatHint)> {
+        config::merge_path_lists(vec![
+            (&self.paths, None),
+            (&self.paths_toml, Some(config::Format::TOML)),
+            (&self.paths_json, Some(config::Format::JSON)),
+            (&self.paths_yaml, Some(config::Format::YAML)),
+        ])
+    }
+}
+
+/// Writes the checkpoints of a `file` source, in the format its
+/// `checkpoint.import_path` option reads.
+pub async fn export(opts: &ExportOpts) -> exitcode::ExitCode {
+    let paths = match config::process_paths(&opts.paths_with_formats()) {
+        Some(paths) => paths,
+        None => {
+            eprintln!("{}", "No config file paths".red());
+            return exitcode::CONFIG;
+        }
+    };
+    let config = match config::load_from_paths(&paths, false) {
+        Ok(config) => config,
+        Err(errors) => {
+            errors.iter().for_each(|e| eprintln!("{}", e.red()));
+            return exitcode::CONFIG;
+        }
+    };
+
+    let (name, local) = match file_source(&config, opts.source.as_deref()) {
+        Ok(source) => source,
+        Err(error) => {
+            eprintln!("{}", error.red());
+            return exitcode::CONFIG;
+        }
+    };
+    let data_dir = match config.global.resolve_and_validate_data_dir(local.as_ref()) {
+        Ok(data_dir) => data_dir.join(&name),
+        Err(error) => {
+            eprintln!("{}", error.to_string().red());
+            return exitcode::CONFIG;
+        }
+    };
+    if config.global.state.is_default() && !data_dir.exists() {
+        eprintln!(
+            "{}",
+            format!("no checkpoints found in {:?}", data_dir).red()
+        );
+        return exitcode::NOINPUT;
+    }
+    let store = match config.global.state_store(local.as_ref(), &name) {
+        Ok(store) => FileCheckpoints::new(store.into()),
+        Err(error) => {
+            eprintln!("{}", error.to_string().red());
+            return exitcode::CONFIG;
+        }
+    };
+
+    // The checkpointer blocks on the state store.
+    let output = opts.output.clone();
+    let exported = spawn_blocking(move || {
+        let mut checkpointer = Checkpointer::with_store(&data_dir, Box::new(store));
+        match &output {
+            Some(path) => File::create(path).and_then(|file| checkpointer.export_checkpoints(file)),
+            None => checkpointer.export_checkpoints(io::stdout()),
+        }
+    })
+    .await
+    .expect("Checkpoint export panicked");
+
+    match exported {
+        Ok(count) => {
+            if let Some(path) = &opts.output {
+                println!("Exported {} checkpoints to {:?}", count, path);
+            }
+            exitcode::OK
+        }
+        Err(error) => {
+            eprintln!(
+                "{}",
+                format!("failed to export checkpoints: {}", error).red()
+            );
+            exitcode::IOERR
+        }
+    }
+}
+
+/// The name of a `file` source, along with its own `data_dir` option.
+fn file_source(config: &Config, name: Option<&str>) -> Result<(String, Option<PathBuf>), String> {
+    let mut sources = config
+        .sources
+        .iter()
+        .filter(|(_, source)| source.inner.source_type() == "file");
+
+    let (name, source) = match name {
+        Some(name) => sources
+            .find(|(source_name, _)| source_name.as_str() == name)
+            .ok_or_else(|| format!("no `file` source named {:?}", name))?,
+        None => match (sources.next(), sources.next()) {
+            (Some(source), None) => source,
+            (None, _) => return Err("no `file` source in the config".to_owned()),
+            (Some(_), Some(_)) => {
+                return Err(
+                    "several `file` sources in the config, pick one with `--source`".to_owned(),
+                )
+            }
+        },
+    };
+
+    let local = serde_json::to_value(&source.inner)
+        .ok()
+        .and_then(|value| value.get("data_dir").cloned())
+        .and_then(|data_dir| serde_json::from_value::<Option<PathBuf>>(data_dir).ok())
+        .flatten();
+
+    Ok((name.clone(), local))
+}
@@ -1,9 +1,12 @@
-use crate::{config, generate, get_version, list, unit_test, validate};
+use crate::{buffers, config, convert_config, generate, get_version, list, unit_test, validate};
 use std::path::PathBuf;
 use structopt::{clap::AppSettings, StructOpt};
 
+#[cfg(feature = "sources-file")]
+use crate::checkpoints;
+
 #[cfg(feature = "api-client")]
-use crate::top;
+use crate::{tap, top};
 
 #[cfg(windows)]
 use crate::service;
@@ -33,6 +36,9 @@ impl Opts {
         let (quiet_level, verbose_level) = match self.sub_command {
             Some(SubCommand::Validate(_))
             | Some(SubCommand::Generate(_))
+            | Some(SubCommand::ConvertConfig(_))
+            | Some(SubCommand::Config(_))
+            | Some(SubCommand::Buffer(_))
             | Some(SubCommand::List(_)) => {
                 if self.root.verbose == 0 {
                     (self.root.quiet + 1, self.root.verbose)
@@ -40,6 +46,25 @@ impl Opts {
                     (self.root.quiet, self.root.verbose - 1)
                 }
             }
+            #[cfg(feature = "sources-file")]
+            Some(SubCommand::Checkpoints(_)) => {
+                if self.root.verbose == 0 {
+                    (self.root.quiet + 1, self.root.verbose)
+                } else {
+                    (self.root.quiet, self.root.verbose - 1)
+                }
+            }
+            #[cfg(feature = "api-client")]
+            Some(SubCommand::Tap(_)) => {
+                if self.root.verbose == 0 {
+                    (self.root.quiet + 1, self.root.verbose)
+                } else {
+                    (self.root.quiet, self.root.verbose - 1)
+                }
+            }
+            // The internal logs are written to STDOUT, along with the messages of the protocol.
+            #[cfg(feature = "vrl-cli")]
+            Some(SubCommand::Lsp(_)) => return "off",
             _ => (self.root.quiet, self.root.verbose),
         };
         match quiet_level {
@@ -139,6 +164,23 @@ pub enum SubCommand {
     /// List available components, then exit.
     List(list::Opts),
 
+    /// Convert deprecated transforms in the target config into equivalent `remap`
+    /// transforms, then print the converted config.
+    ConvertConfig(convert_config::Opts),
+
+    /// Inspect the configuration surface of Vector.
+    #[structopt(name = "config")]
+    Config(ConfigCommand),
+
+    /// Inspect and migrate the disk buffers of the sinks.
+    #[structopt(name = "buffer")]
+    Buffer(BufferCommand),
+
+    /// Manage the checkpoints of the `file` sources.
+    #[cfg(feature = "sources-file")]
+    #[structopt(name = "checkpoints")]
+    Checkpoints(CheckpointsCommand),
+
     /// Run Vector config unit tests, then exit. This command is experimental and therefore subject to change.
     /// For guidance on how to write unit tests check out: https://vector.dev/docs/setup/guides/unit-testing/
     Test(unit_test::Opts),
@@ -147,6 +189,11 @@ pub enum SubCommand {
     #[cfg(feature = "api-client")]
     Top(top::Opts),
 
+    /// Print a sample of the events flowing out of a source or transform, for a local or
+    /// remote Vector instance
+    #[cfg(feature = "api-client")]
+    Tap(tap::Opts),
+
     /// Manage the vector service.
     #[cfg(windows)]
     Service(service::Opts),
@@ -154,6 +201,39 @@ pub enum SubCommand {
     /// Vector Remap Language CLI
     #[cfg(feature = "vrl-cli")]
     VRL(remap_cli::Opts),
+
+    /// Run a Vector Remap Language language server, speaking the Language Server Protocol over
+    /// STDIN and STDOUT.
+    #[cfg(feature = "vrl-cli")]
+    Lsp(remap_cli::lsp::Opts),
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub enum ConfigCommand {
+    /// Print a JSON Schema describing all configuration options, for editor completion, then
+    /// exit.
+    Schema(config::schema::Opts),
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub enum BufferCommand {
+    /// Print the events and bytes pending in the disk buffers of the sinks, then exit.
+    Inspect(buffers::cmd::InspectOpts),
+
+    /// Move the events left in disk buffers of another type than the configured one
+    /// into the configured buffers, then exit. Vector also does it when it starts.
+    Migrate(buffers::cmd::MigrateOpts),
+}
+
+#[cfg(feature = "sources-file")]
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub enum CheckpointsCommand {
+    /// Export the checkpoints of a `file` source, for the `checkpoint.import_path`
+    /// option of a `file` source on another host, then exit.
+    Export(checkpoints::ExportOpts),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -0,0 +1,485 @@
+//! Work sharing between multiple Vector instances.
+//!
+//! When several aggregators are pointed at the same pull-based inputs (for
+//! example the same set of `prometheus_scrape` endpoints) they would normally
+//! all pull the same data. Cluster mode lets each instance register itself in
+//! a shared lock store and only take ownership of the work units (endpoints,
+//! partitions, ...) assigned to it by rendezvous hashing over the set of live
+//! members. When a member joins or leaves, the assignment is recomputed on the
+//! next heartbeat and ownership moves automatically.
+//!
+//! The components of an instance share its membership, so it heartbeats once
+//! whatever the number of components using it.
+
+use crate::{
+    config::GlobalOptions,
+    internal_events::{ClusterHeartbeatFailed, ClusterMembershipChanged},
+    BoolAndSome,
+};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt, Snafu};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock, Weak},
+    time::{Duration, SystemTime},
+};
+use tokio::time::interval;
+
+#[derive(Debug, Snafu)]
+pub enum ClusterError {
+    #[snafu(display("Could not create cluster directory {:?}: {}", path, source))]
+    CreateDirectory {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Could not write heartbeat file {:?}: {}", path, source))]
+    WriteHeartbeat {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Could not read cluster directory {:?}: {}", path, source))]
+    ReadDirectory {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Could not determine cluster member id: {}", source))]
+    MemberId { source: std::io::Error },
+    #[snafu(display("The cluster store has no `path`, which all the members must share"))]
+    MissingStorePath,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields, default)]
+pub struct ClusterOptions {
+    pub enabled: bool,
+    /// Identifier of this instance. Defaults to the hostname.
+    pub member_id: Option<String>,
+    pub store: StoreConfig,
+    pub heartbeat_interval_secs: u64,
+    pub member_timeout_secs: u64,
+}
+
+impl Default for ClusterOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            member_id: None,
+            store: StoreConfig::default(),
+            heartbeat_interval_secs: 5,
+            member_timeout_secs: 15,
+        }
+    }
+}
+
+impl ClusterOptions {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn merge(&mut self, other: Self) -> Result<(), String> {
+        if other.is_default() {
+            return Ok(());
+        }
+        if !self.is_default() && *self != other {
+            return Err("conflicting values for 'cluster' found".to_owned());
+        }
+        *self = other;
+        Ok(())
+    }
+
+    fn member_id(&self) -> Result<String, ClusterError> {
+        match &self.member_id {
+            Some(id) => Ok(id.clone()),
+            None => crate::get_hostname().context(MemberId),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum StoreConfig {
+    /// A directory shared by all the members, e.g. a network file system
+    /// mount. Each member periodically touches a heartbeat file in it.
+    /// Required when cluster mode is enabled, since the `data_dir` of each
+    /// instance is its own.
+    Directory {
+        #[serde(default)]
+        path: Option<PathBuf>,
+    },
+}
+
+impl Default for StoreConfig {
+    fn default() -> Self {
+        StoreConfig::Directory { path: None }
+    }
+}
+
+impl StoreConfig {
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            StoreConfig::Directory { path } => path.as_deref(),
+        }
+    }
+}
+
+/// Storage keeping track of the live members of the cluster.
+pub trait LockStore: Send + Sync {
+    /// Records that `member_id` is alive at `now`.
+    fn heartbeat(&self, member_id: &str, now: SystemTime) -> Result<(), ClusterError>;
+
+    /// Returns the members that sent a heartbeat less than `timeout` ago.
+    fn members(&self, now: SystemTime, timeout: Duration) -> Result<Vec<String>, ClusterError>;
+}
+
+const HEARTBEAT_EXTENSION: &str = "heartbeat";
+
+pub struct DirectoryStore {
+    path: PathBuf,
+}
+
+impl DirectoryStore {
+    pub fn new(path: PathBuf) -> Result<Self, ClusterError> {
+        std::fs::create_dir_all(&path).with_context(|| CreateDirectory { path: path.clone() })?;
+        Ok(Self { path })
+    }
+}
+
+impl LockStore for DirectoryStore {
+    fn heartbeat(&self, member_id: &str, now: SystemTime) -> Result<(), ClusterError> {
+        let path = self
+            .path
+            .join(format!("{}.{}", escape(member_id), HEARTBEAT_EXTENSION));
+        let secs = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        std::fs::write(&path, format!("{}\n{}\n", member_id, secs))
+            .with_context(|| WriteHeartbeat { path })
+    }
+
+    fn members(&self, now: SystemTime, timeout: Duration) -> Result<Vec<String>, ClusterError> {
+        let entries = std::fs::read_dir(&self.path).with_context(|| ReadDirectory {
+            path: self.path.clone(),
+        })?;
+
+        let now = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut members = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry.path().extension().and_then(|ext| ext.to_str()) == Some(HEARTBEAT_EXTENSION)
+            })
+            .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+            .filter_map(|contents| {
+                let mut lines = contents.lines();
+                let member = lines.next()?.to_owned();
+                let seen = lines.next()?.parse::<u64>().ok()?;
+                (now.saturating_sub(seen) <= timeout.as_secs()).and_some(member)
+            })
+            .collect::<Vec<_>>();
+        members.sort();
+        members.dedup();
+        Ok(members)
+    }
+}
+
+/// Escapes the bytes of `member_id` that can't be used in file names as
+/// `%XX`, `%` included, so that different ids get different files.
+fn escape(member_id: &str) -> String {
+    let mut escaped = String::with_capacity(member_id.len());
+    for byte in member_id.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' => {
+                escaped.push(byte as char)
+            }
+            _ => escaped.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    escaped
+}
+
+/// A view of the current cluster membership, shared between the heartbeat
+/// task and the components using it to decide on ownership.
+#[derive(Clone)]
+pub struct Membership {
+    member_id: String,
+    members: Arc<RwLock<Vec<String>>>,
+}
+
+impl Membership {
+    /// A membership with a single member, which owns everything. Used when
+    /// cluster mode is disabled.
+    pub fn standalone() -> Self {
+        let member_id = String::from("standalone");
+        Self {
+            members: Arc::new(RwLock::new(vec![member_id.clone()])),
+            member_id,
+        }
+    }
+
+    pub fn member_id(&self) -> &str {
+        &self.member_id
+    }
+
+    pub fn members(&self) -> Vec<String> {
+        self.members.read().unwrap().clone()
+    }
+
+    /// Whether this member is responsible for the given work unit.
+    pub fn owns(&self, key: &str) -> bool {
+        let members = self.members.read().unwrap();
+        owner(&members, key).map_or(true, |owner| owner == self.member_id)
+    }
+
+    fn update(&self, mut members: Vec<String>) {
+        // Always consider ourselves alive, in case our own heartbeat is not
+        // visible yet.
+        if !members.contains(&self.member_id) {
+            members.push(self.member_id.clone());
+            members.sort();
+        }
+
+        let mut current = self.members.write().unwrap();
+        if *current != members {
+            emit!(ClusterMembershipChanged {
+                member_id: &self.member_id,
+                members: &members,
+            });
+            *current = members;
+        }
+    }
+}
+
+/// Rendezvous (highest random weight) hashing: the member with the highest
+/// hash of `(member, key)` owns the key. Only the keys owned by a departing
+/// member move when membership changes.
+pub fn owner<'a>(members: &'a [String], key: &str) -> Option<&'a str> {
+    members
+        .iter()
+        .max_by_key(|member| fnv1a(&[member.as_bytes(), b"\0", key.as_bytes()]))
+        .map(String::as_str)
+}
+
+/// FNV-1a is used instead of `DefaultHasher` since its output must be stable
+/// across members that may be running different Vector versions.
+fn fnv1a(parts: &[&[u8]]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in parts.iter().flat_map(|part| part.iter()) {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// The membership joined last, with the options and store path it was joined
+/// with.
+struct Joined {
+    options: ClusterOptions,
+    path: PathBuf,
+    member_id: String,
+    members: Weak<RwLock<Vec<String>>>,
+}
+
+static JOINED: Lazy<Mutex<Option<Joined>>> = Lazy::new(|| Mutex::new(None));
+
+/// Builds the membership described by the `cluster` options of `globals`,
+/// heartbeating in the background until the returned membership and all its
+/// clones are dropped. The components joining with the same options share
+/// the same membership.
+pub async fn join(globals: &GlobalOptions) -> crate::Result<Membership> {
+    let options = &globals.cluster;
+    if !options.enabled {
+        return Ok(Membership::standalone());
+    }
+
+    let path = options.store.path().context(MissingStorePath)?.to_owned();
+    if let Some(membership) = joined(options, &path) {
+        return Ok(membership);
+    }
+
+    // Creating the store and the first heartbeat touch the file system, which
+    // may be a slow network mount.
+    let (store, membership) = {
+        let membership = Membership {
+            member_id: options.member_id()?,
+            members: Arc::new(RwLock::new(Vec::new())),
+        };
+        let options = options.clone();
+        let path = path.clone();
+        tokio::task::spawn_blocking(move || {
+            DirectoryStore::new(path).map(|store| {
+                refresh(&store, &membership, &options);
+                (store, membership)
+            })
+        })
+        .await??
+    };
+
+    let mut joined = JOINED.lock().expect("Cluster membership lock is poisoned");
+    // Another component may have joined while this one was heartbeating.
+    if let Some(membership) = upgrade(joined.as_ref(), options, &path) {
+        return Ok(membership);
+    }
+    *joined = Some(Joined {
+        options: options.clone(),
+        path,
+        member_id: membership.member_id.clone(),
+        members: Arc::downgrade(&membership.members),
+    });
+    drop(joined);
+
+    let store: Arc<dyn LockStore> = Arc::new(store);
+    let weak = Arc::downgrade(&membership.members);
+    let member_id = membership.member_id.clone();
+    let options = options.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(options.heartbeat_interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            let members = match weak.upgrade() {
+                Some(members) => members,
+                None => break,
+            };
+            let membership = Membership {
+                member_id: member_id.clone(),
+                members,
+            };
+            let store = Arc::clone(&store);
+            let options = options.clone();
+            let _ =
+                tokio::task::spawn_blocking(move || refresh(&*store, &membership, &options)).await;
+        }
+    });
+
+    Ok(membership)
+}
+
+/// The membership already joined with `options` and `path`, if it's still in
+/// use.
+fn joined(options: &ClusterOptions, path: &Path) -> Option<Membership> {
+    let joined = JOINED.lock().expect("Cluster membership lock is poisoned");
+    upgrade(joined.as_ref(), options, path)
+}
+
+fn upgrade(joined: Option<&Joined>, options: &ClusterOptions, path: &Path) -> Option<Membership> {
+    let joined = joined.filter(|joined| joined.options == *options && joined.path == path)?;
+    joined.members.upgrade().map(|members| Membership {
+        member_id: joined.member_id.clone(),
+        members,
+    })
+}
+
+fn refresh(store: &dyn LockStore, membership: &Membership, options: &ClusterOptions) {
+    let now = SystemTime::now();
+    if let Err(error) = store.heartbeat(&membership.member_id, now) {
+        emit!(ClusterHeartbeatFailed { error });
+    }
+    match store.members(now, Duration::from_secs(options.member_timeout_secs)) {
+        Ok(members) => membership.update(members),
+        Err(error) => emit!(ClusterHeartbeatFailed { error }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn members(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn owner_is_stable_and_unique() {
+        let all = members(&["a", "b", "c"]);
+        for key in &["http://one/metrics", "http://two/metrics", "http://three"] {
+            let owner = owner(&all, key).unwrap();
+            assert_eq!(Some(owner), super::owner(&all, key));
+        }
+        assert_eq!(owner(&[], "key"), None);
+    }
+
+    #[test]
+    fn only_departed_keys_move() {
+        let before = members(&["a", "b", "c"]);
+        let after = members(&["a", "b"]);
+        for i in 0..100 {
+            let key = format!("endpoint-{}", i);
+            let old = owner(&before, &key).unwrap();
+            let new = owner(&after, &key).unwrap();
+            if old != "c" {
+                assert_eq!(old, new);
+            }
+        }
+    }
+
+    #[test]
+    fn directory_store_expires_members() {
+        let store = DirectoryStore::new(temp_dir()).unwrap();
+        let now = SystemTime::now();
+        store
+            .heartbeat("old", now - Duration::from_secs(60))
+            .unwrap();
+        store.heartbeat("agg-1", now).unwrap();
+        store.heartbeat("agg/2", now).unwrap();
+
+        let live = store.members(now, Duration::from_secs(15)).unwrap();
+        assert_eq!(live, members(&["agg-1", "agg/2"]));
+    }
+
+    #[test]
+    fn escapes_member_ids() {
+        assert_eq!(escape("agg-1.local"), "agg-1.local");
+        assert_eq!(escape("agg/2"), "agg%2F2");
+        assert_ne!(escape("agg/2"), escape("agg_2"));
+        assert_ne!(escape("agg%2F2"), escape("agg/2"));
+    }
+
+    #[tokio::test]
+    async fn components_share_the_membership() {
+        let path = temp_dir();
+        let mut globals = GlobalOptions::default();
+        globals.cluster.enabled = true;
+        globals.cluster.member_id = Some("agg-1".into());
+        globals.cluster.store = StoreConfig::Directory {
+            path: Some(path.clone()),
+        };
+
+        let first = join(&globals).await.unwrap();
+        let second = join(&globals).await.unwrap();
+        assert!(Arc::ptr_eq(&first.members, &second.members));
+        assert_eq!(first.members(), members(&["agg-1"]));
+        assert!(path.join("agg-1.heartbeat").exists());
+
+        globals.cluster.member_id = Some("agg-2".into());
+        let third = join(&globals).await.unwrap();
+        assert!(!Arc::ptr_eq(&first.members, &third.members));
+    }
+
+    #[tokio::test]
+    async fn requires_a_store_path() {
+        let mut globals = GlobalOptions::default();
+        globals.cluster.enabled = true;
+        assert!(join(&globals).await.is_err());
+    }
+
+    #[test]
+    fn standalone_owns_everything() {
+        let membership = Membership::standalone();
+        assert!(membership.owns("anything"));
+    }
+
+    #[test]
+    fn membership_includes_self() {
+        let membership = Membership {
+            member_id: "b".into(),
+            members: Arc::new(RwLock::new(Vec::new())),
+        };
+        membership.update(members(&["a"]));
+        assert_eq!(membership.members(), members(&["a", "b"]));
+    }
+}
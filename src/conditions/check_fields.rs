@@ -82,6 +82,7 @@ impl CheckFieldsPredicate for EqualsPredicate {
                     CheckFieldsPredicateArg::String(s) => s.as_bytes() == v.as_bytes(),
                     _ => false,
                 }),
+            Event::Trace(_) => false,
         }
     }
 }
@@ -247,6 +248,7 @@ impl CheckFieldsPredicate for NotEqualsPredicate {
                 .map_or(false, |v| {
                     !self.arg.iter().any(|s| v.as_bytes() == s.as_bytes())
                 }),
+            Event::Trace(_) => false,
         }
     }
 }
@@ -285,6 +287,7 @@ impl CheckFieldsPredicate for RegexPredicate {
                 .tags()
                 .and_then(|tags| tags.get(&self.target))
                 .map_or(false, |field| self.regex.is_match(field)),
+            Event::Trace(_) => false,
         }
     }
 }
@@ -314,6 +317,7 @@ impl CheckFieldsPredicate for ExistsPredicate {
         (match event {
             Event::Log(l) => l.get(&self.target).is_some(),
             Event::Metric(m) => m.tags().map_or(false, |t| t.contains_key(&self.target)),
+            Event::Trace(t) => t.get(&self.target).is_some(),
         }) == self.arg
     }
 }
@@ -75,6 +75,9 @@ impl Remap {
         match event {
             Event::Log(event) => Runtime::default().run(&mut event.clone(), &self.program),
             Event::Metric(event) => Runtime::default().run(&mut event.clone(), &self.program),
+            Event::Trace(event) => {
+                Runtime::default().run(&mut event.as_log().clone(), &self.program)
+            }
         }
     }
 }
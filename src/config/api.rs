@@ -1,7 +1,8 @@
+use crate::tls::TlsConfig;
 use serde::{Deserialize, Serialize};
 use std::net::{Ipv4Addr, SocketAddr};
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Copy, Clone)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 #[serde(default, deny_unknown_fields)]
 pub struct Options {
     #[serde(default = "default_enabled")]
@@ -12,6 +13,22 @@ pub struct Options {
 
     #[serde(default = "default_playground")]
     pub playground: bool,
+
+    #[serde(default = "default_history_retention_secs")]
+    pub history_retention_secs: u64,
+
+    pub tls: Option<TlsConfig>,
+
+    pub auth: Option<Auth>,
+}
+
+/// Authentication required from API clients, in addition to any client certificate
+/// verification configured through `tls`
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Auth {
+    /// Static token clients must send as `Authorization: Bearer <token>`
+    pub token: String,
 }
 
 impl Default for Options {
@@ -20,6 +37,9 @@ impl Default for Options {
             enabled: default_enabled(),
             playground: default_playground(),
             address: default_address(),
+            history_retention_secs: default_history_retention_secs(),
+            tls: None,
+            auth: None,
         }
     }
 }
@@ -39,6 +59,11 @@ fn default_playground() -> bool {
     true
 }
 
+/// Per-component throughput is kept in memory for the last 5 minutes by default
+fn default_history_retention_secs() -> u64 {
+    300
+}
+
 impl Options {
     pub fn merge(&mut self, other: Self) -> Result<(), String> {
         // Merge options
@@ -60,10 +85,27 @@ impl Options {
             }
         };
 
+        if self.tls.is_some() && other.tls.is_some() && self.tls != other.tls {
+            return Err("Conflicting `api` tls options.".to_owned());
+        }
+        if self.auth.is_some() && other.auth.is_some() && self.auth != other.auth {
+            return Err("Conflicting `api` auth options.".to_owned());
+        }
+
         let options = Options {
             address,
             enabled: self.enabled | other.enabled,
             playground: self.playground & other.playground,
+            // Prefer non default retention
+            history_retention_secs: if other.history_retention_secs
+                != default_history_retention_secs()
+            {
+                other.history_retention_secs
+            } else {
+                self.history_retention_secs
+            },
+            tls: other.tls.or_else(|| self.tls.take()),
+            auth: other.auth.or_else(|| self.auth.take()),
         };
 
         *self = options;
@@ -77,6 +119,9 @@ fn bool_merge() {
         enabled: true,
         address: None,
         playground: false,
+        history_retention_secs: default_history_retention_secs(),
+        tls: None,
+        auth: None,
     };
 
     a.merge(Options::default()).unwrap();
@@ -87,6 +132,9 @@ fn bool_merge() {
             enabled: true,
             address: default_address(),
             playground: false,
+            history_retention_secs: default_history_retention_secs(),
+            tls: None,
+            auth: None,
         }
     );
 }
@@ -98,6 +146,9 @@ fn bind_merge() {
         enabled: true,
         address: Some(address),
         playground: true,
+        history_retention_secs: default_history_retention_secs(),
+        tls: None,
+        auth: None,
     };
 
     a.merge(Options::default()).unwrap();
@@ -108,6 +159,9 @@ fn bind_merge() {
             enabled: true,
             address: Some(address),
             playground: true,
+            history_retention_secs: default_history_retention_secs(),
+            tls: None,
+            auth: None,
         }
     );
 }
@@ -2,8 +2,9 @@
 use super::api;
 use super::{
     compiler, default_data_dir, Config, GlobalOptions, HealthcheckOptions, SinkConfig, SinkOuter,
-    SourceConfig, TestDefinition, TransformConfig, TransformOuter,
+    SourceConfig, SourceOuter, TestDefinition, TransformConfig, TransformOuter,
 };
+use crate::enrichment_tables::EnrichmentTableConfig;
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
@@ -18,12 +19,14 @@ pub struct ConfigBuilder {
     #[serde(default)]
     pub healthchecks: HealthcheckOptions,
     #[serde(default)]
-    pub sources: IndexMap<String, Box<dyn SourceConfig>>,
+    pub sources: IndexMap<String, SourceOuter>,
     #[serde(default)]
     pub sinks: IndexMap<String, SinkOuter>,
     #[serde(default)]
     pub transforms: IndexMap<String, TransformOuter>,
     #[serde(default)]
+    pub enrichment_tables: IndexMap<String, EnrichmentTableConfig>,
+    #[serde(default)]
     pub tests: Vec<TestDefinition>,
 }
 
@@ -49,6 +52,7 @@ impl From<Config> for ConfigBuilder {
             sources: c.sources,
             sinks: c.sinks,
             transforms: c.transforms,
+            enrichment_tables: c.enrichment_tables,
             tests: c.tests,
         }
     }
@@ -64,7 +68,8 @@ impl ConfigBuilder {
     }
 
     pub fn add_source<S: SourceConfig + 'static, T: Into<String>>(&mut self, name: T, source: S) {
-        self.sources.insert(name.into(), Box::new(source));
+        self.sources
+            .insert(name.into(), SourceOuter::new(Box::new(source)));
     }
 
     pub fn add_sink<S: SinkConfig + 'static, T: Into<String>>(
@@ -89,6 +94,7 @@ impl ConfigBuilder {
         let transform = TransformOuter {
             inner: Box::new(transform),
             inputs,
+            telemetry_labels: Default::default(),
         };
 
         self.transforms.insert(name.into(), transform);
@@ -118,6 +124,34 @@ impl ConfigBuilder {
             errors.extend(merge_errors);
         }
 
+        if let Err(error) = self.global.cluster.merge(with.global.cluster) {
+            errors.push(error);
+        }
+
+        if let Err(error) = self.global.crash_report.merge(with.global.crash_report) {
+            errors.push(error);
+        }
+
+        if let Err(error) = self.global.state.merge(with.global.state) {
+            errors.push(error);
+        }
+
+        if self.global.expire_metrics_secs.is_none() {
+            self.global.expire_metrics_secs = with.global.expire_metrics_secs;
+        } else if with.global.expire_metrics_secs.is_some()
+            && self.global.expire_metrics_secs != with.global.expire_metrics_secs
+        {
+            errors.push("conflicting values for 'expire_metrics_secs' found".to_owned());
+        }
+
+        if self.global.acknowledgements.is_none() {
+            self.global.acknowledgements = with.global.acknowledgements;
+        } else if with.global.acknowledgements.is_some()
+            && self.global.acknowledgements != with.global.acknowledgements
+        {
+            errors.push("conflicting values for 'acknowledgements' found".to_owned());
+        }
+
         self.healthchecks.merge(with.healthchecks);
 
         with.sources.keys().for_each(|k| {
@@ -135,6 +169,11 @@ impl ConfigBuilder {
                 errors.push(format!("duplicate transform name found: {}", k));
             }
         });
+        with.enrichment_tables.keys().for_each(|k| {
+            if self.enrichment_tables.contains_key(k) {
+                errors.push(format!("duplicate enrichment table name found: {}", k));
+            }
+        });
         with.tests.iter().for_each(|wt| {
             if self.tests.iter().any(|t| t.name == wt.name) {
                 errors.push(format!("duplicate test name found: {}", wt.name));
@@ -147,6 +186,7 @@ impl ConfigBuilder {
         self.sources.extend(with.sources);
         self.sinks.extend(with.sinks);
         self.transforms.extend(with.transforms);
+        self.enrichment_tables.extend(with.enrichment_tables);
         self.tests.extend(with.tests);
 
         Ok(())
@@ -1,10 +1,14 @@
 use super::{builder::ConfigBuilder, handle_warnings, validation, Config, TransformOuter};
+use crate::enrichment_tables;
 use indexmap::IndexMap;
+use regex::Regex;
 
 pub fn compile(mut builder: ConfigBuilder, deny_warnings: bool) -> Result<Config, Vec<String>> {
     let mut errors = Vec::new();
 
-    expand_wildcards(&mut builder);
+    if let Err(wildcard_errors) = expand_wildcards(&mut builder) {
+        errors.extend(wildcard_errors);
+    }
 
     let expansions = expand_macros(&mut builder)?;
 
@@ -24,6 +28,11 @@ pub fn compile(mut builder: ConfigBuilder, deny_warnings: bool) -> Result<Config
         errors.extend(type_errors);
     }
 
+    match enrichment_tables::load(&builder.enrichment_tables) {
+        Ok(tables) => builder.global.enrichment_tables = tables,
+        Err(load_errors) => errors.extend(load_errors),
+    }
+
     if errors.is_empty() {
         Ok(Config {
             global: builder.global,
@@ -33,6 +42,7 @@ pub fn compile(mut builder: ConfigBuilder, deny_warnings: bool) -> Result<Config
             sources: builder.sources,
             sinks: builder.sinks,
             transforms: builder.transforms,
+            enrichment_tables: builder.enrichment_tables,
             tests: builder.tests,
             expansions,
         })
@@ -60,11 +70,22 @@ pub(super) fn expand_macros(
         } {
             let mut children = Vec::new();
             for (name, child) in expanded {
-                let full_name = format!("{}.{}", k, name);
+                // A child without a name takes the place of its parent.
+                let full_name = if name.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{}.{}", k, name)
+                };
+                let inputs = if child.is_secondary_output() {
+                    Vec::new()
+                } else {
+                    t.inputs.clone()
+                };
                 expanded_transforms.insert(
                     full_name.clone(),
                     TransformOuter {
-                        inputs: t.inputs.clone(),
+                        inputs,
+                        telemetry_labels: t.telemetry_labels.clone(),
                         inner: child,
                     },
                 );
@@ -84,36 +105,97 @@ pub(super) fn expand_macros(
     }
 }
 
-/// Expand trailing `*` wildcards in input lists
-fn expand_wildcards(config: &mut ConfigBuilder) {
+/// Expand glob (`apache_*`) and regex (`/^tenant_\d+$/`) patterns in input
+/// lists into the names of the matching sources and transforms.
+fn expand_wildcards(config: &mut ConfigBuilder) -> Result<(), Vec<String>> {
     let candidates = config
         .sources
         .keys()
         .chain(config.transforms.keys())
         .cloned()
         .collect::<Vec<String>>();
+    let mut errors = Vec::new();
 
     for (name, transform) in config.transforms.iter_mut() {
-        expand_wildcards_inner(&mut transform.inputs, name, &candidates);
+        expand_wildcards_inner(&mut transform.inputs, name, &candidates, &mut errors);
     }
 
     for (name, sink) in config.sinks.iter_mut() {
-        expand_wildcards_inner(&mut sink.inputs, name, &candidates);
+        expand_wildcards_inner(&mut sink.inputs, name, &candidates, &mut errors);
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// A pattern matching component names in an input list.
+enum InputPattern {
+    Glob(glob::Pattern),
+    Regex(Regex),
+}
+
+impl InputPattern {
+    /// Parses `input` as a pattern, returning `None` for plain component names.
+    fn parse(input: &str) -> Option<Result<Self, String>> {
+        if input.len() > 1 && input.starts_with('/') && input.ends_with('/') {
+            Some(
+                Regex::new(&input[1..input.len() - 1])
+                    .map(InputPattern::Regex)
+                    .map_err(|error| error.to_string()),
+            )
+        } else if input.contains(|c| matches!(c, '*' | '?' | '[')) {
+            Some(
+                glob::Pattern::new(input)
+                    .map(InputPattern::Glob)
+                    .map_err(|error| error.to_string()),
+            )
+        } else {
+            None
+        }
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            InputPattern::Glob(pattern) => pattern.matches(name),
+            InputPattern::Regex(regex) => regex.is_match(name),
+        }
     }
 }
 
-fn expand_wildcards_inner(inputs: &mut Vec<String>, name: &str, candidates: &[String]) {
+fn expand_wildcards_inner(
+    inputs: &mut Vec<String>,
+    name: &str,
+    candidates: &[String],
+    errors: &mut Vec<String>,
+) {
     let raw_inputs = std::mem::take(inputs);
     for raw_input in raw_inputs {
-        if raw_input.ends_with('*') {
-            let prefix = &raw_input[0..raw_input.len() - 1];
-            for input in candidates {
-                if input.starts_with(prefix) && input != name {
-                    inputs.push(input.clone())
+        match InputPattern::parse(&raw_input) {
+            None => inputs.push(raw_input),
+            Some(Err(error)) => errors.push(format!(
+                "Input pattern \"{}\" for component \"{}\" is invalid: {}",
+                raw_input, name, error
+            )),
+            Some(Ok(pattern)) => {
+                let mut matched = false;
+                for input in candidates {
+                    if input != name && pattern.matches(input) {
+                        matched = true;
+                        if !inputs.contains(input) {
+                            inputs.push(input.clone())
+                        }
+                    }
+                }
+                if !matched {
+                    errors.push(format!(
+                        "Input pattern \"{}\" for component \"{}\" doesn't match any components.",
+                        raw_input, name
+                    ));
                 }
             }
-        } else {
-            inputs.push(raw_input);
         }
     }
 }
@@ -166,7 +248,7 @@ mod test {
     #[async_trait]
     #[typetag::serde(name = "mock")]
     impl TransformConfig for MockTransformConfig {
-        async fn build(&self) -> crate::Result<Transform> {
+        async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
             unimplemented!()
         }
 
@@ -218,4 +300,33 @@ mod test {
             vec!["foo1", "foo2", "bar", "foos"]
         );
     }
+
+    #[test]
+    fn regex_expansion() {
+        let mut builder = ConfigBuilder::default();
+        builder.add_source("tenant_1", MockSourceConfig);
+        builder.add_source("tenant_22", MockSourceConfig);
+        builder.add_source("tenant_admin", MockSourceConfig);
+        builder.add_sink("out", &[r"/^tenant_\d+$/", "tenant_?"], MockSinkConfig);
+
+        let config = builder.build().expect("build should succeed");
+
+        assert_eq!(config.sinks["out"].inputs, vec!["tenant_1", "tenant_22"]);
+    }
+
+    #[test]
+    fn wildcard_expansion_errors() {
+        let mut builder = ConfigBuilder::default();
+        builder.add_source("foo", MockSourceConfig);
+        builder.add_sink("out", &["foo", "bar*", "/(/"], MockSinkConfig);
+
+        let errors = builder.build().expect_err("build should fail");
+
+        assert!(errors.contains(
+            &r#"Input pattern "bar*" for component "out" doesn't match any components."#.to_owned()
+        ));
+        assert!(errors.iter().any(
+            |error| error.starts_with(r#"Input pattern "/(/" for component "out" is invalid"#)
+        ));
+    }
 }
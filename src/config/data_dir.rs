@@ -0,0 +1,66 @@
+//! Checks of the health of the `data_dir`, run before the components keeping
+//! their state or buffers there start, and while disk buffers write to it.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+/// The file written to check that a directory is writable.
+const WRITE_CHECK_FILE: &str = ".vector_write_check";
+
+/// Checks that files can be created and written in `dir`, which the
+/// permissions of the directory alone don't tell, as they depend on the
+/// user Vector runs as and on the file system being mounted read-only.
+pub fn check_writable(dir: &Path) -> io::Result<()> {
+    let path = dir.join(WRITE_CHECK_FILE);
+    let result = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(b"ok"));
+    // The file may have been created before the write failed.
+    let removed = fs::remove_file(&path);
+    result.and(removed)
+}
+
+/// The space available to Vector on the file system holding `dir`, in bytes.
+#[cfg(unix)]
+pub fn available_space(dir: &Path) -> io::Result<u64> {
+    let stat = nix::sys::statvfs::statvfs(dir)
+        .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+    Ok(stat.blocks_available() as u64 * stat.fragment_size() as u64)
+}
+
+#[cfg(not(unix))]
+pub fn available_space(_dir: &Path) -> io::Result<u64> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "the available space is only known on Unix",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_writable_directories() {
+        let dir = tempfile::tempdir().unwrap();
+
+        check_writable(dir.path()).unwrap();
+        assert!(!dir.path().join(WRITE_CHECK_FILE).exists());
+        assert!(check_writable(&dir.path().join("missing")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn gets_available_space() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert!(available_space(dir.path()).unwrap() > 0);
+        assert!(available_space(&dir.path().join("missing")).is_err());
+    }
+}
@@ -0,0 +1,362 @@
+//! Machine-readable deprecations of configuration options.
+//!
+//! Every deprecated option is described by a [`Rule`] with a stable id and the
+//! mechanical [`Migration`] that replaces it. Loading a config reports each
+//! match as a structured warning, and `vector validate --fix` applies the
+//! migrations to the config files in place.
+
+use serde::Serialize;
+use std::fmt;
+use toml::{map::Map, Value};
+
+const AWS_SINKS: &[&str] = &[
+    "aws_cloudwatch_logs",
+    "aws_cloudwatch_metrics",
+    "aws_kinesis_firehose",
+    "aws_kinesis_streams",
+    "aws_s3",
+    "aws_sqs",
+];
+
+const HOST_ENDPOINT_SINKS: &[&str] = &[
+    "clickhouse",
+    "datadog_metrics",
+    "elasticsearch",
+    "humio_logs",
+    "humio_metrics",
+    "logdna",
+    "sematext_logs",
+    "splunk_hec",
+];
+
+/// How the value of a deprecated option is carried over to its replacement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Migration {
+    /// Move the value as is to the given dotted option path.
+    Rename(&'static str),
+    /// Move the value into a single element array at the given dotted option
+    /// path.
+    WrapInArray(&'static str),
+}
+
+impl Migration {
+    fn target(&self) -> &'static str {
+        match self {
+            Migration::Rename(target) | Migration::WrapInArray(target) => target,
+        }
+    }
+
+    fn migrate(&self, value: Value) -> Value {
+        match self {
+            Migration::Rename(_) => value,
+            Migration::WrapInArray(_) => Value::Array(vec![value]),
+        }
+    }
+}
+
+/// A deprecated option of some component types.
+#[derive(Debug, Clone, Copy)]
+pub struct Rule {
+    /// Stable identifier for tooling to key off.
+    pub id: &'static str,
+    /// The top level section the component lives in, e.g. `sinks`.
+    pub section: &'static str,
+    /// Component types the option is deprecated on, every type if empty.
+    pub types: &'static [&'static str],
+    /// The deprecated option.
+    pub option: &'static str,
+    /// How the option is replaced.
+    pub migration: Migration,
+}
+
+pub const RULES: &[Rule] = &[
+    Rule {
+        id: "sink_healthcheck_uri",
+        section: "sinks",
+        types: &[],
+        option: "healthcheck_uri",
+        migration: Migration::Rename("healthcheck.uri"),
+    },
+    Rule {
+        id: "sink_assume_role",
+        section: "sinks",
+        types: AWS_SINKS,
+        option: "assume_role",
+        migration: Migration::Rename("auth.assume_role"),
+    },
+    Rule {
+        id: "sink_headers",
+        section: "sinks",
+        types: &["elasticsearch", "http"],
+        option: "headers",
+        migration: Migration::Rename("request.headers"),
+    },
+    Rule {
+        id: "sink_host",
+        section: "sinks",
+        types: HOST_ENDPOINT_SINKS,
+        option: "host",
+        migration: Migration::Rename("endpoint"),
+    },
+    Rule {
+        id: "sink_pulsar_address",
+        section: "sinks",
+        types: &["pulsar"],
+        option: "address",
+        migration: Migration::Rename("endpoint"),
+    },
+    Rule {
+        id: "transform_aws_ec2_metadata_host",
+        section: "transforms",
+        types: &["aws_ec2_metadata"],
+        option: "host",
+        migration: Migration::Rename("endpoint"),
+    },
+    Rule {
+        id: "transform_merge_fields",
+        section: "transforms",
+        types: &["merge"],
+        option: "merge_fields",
+        migration: Migration::Rename("fields"),
+    },
+    Rule {
+        id: "transform_route_lanes",
+        section: "transforms",
+        types: &["route"],
+        option: "lanes",
+        migration: Migration::Rename("route"),
+    },
+    Rule {
+        id: "transform_regex_parser_regex",
+        section: "transforms",
+        types: &["regex_parser"],
+        option: "regex",
+        migration: Migration::WrapInArray("patterns"),
+    },
+];
+
+/// A deprecated option found in a config.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Deprecation {
+    pub id: &'static str,
+    /// The component using the option, e.g. `sinks.out`.
+    pub component: String,
+    pub option: &'static str,
+    /// The option path that replaces the deprecated one.
+    pub replacement_option: &'static str,
+    /// A config snippet equivalent to the deprecated option.
+    pub replacement: String,
+    /// Whether `vector validate --fix` is able to apply the migration. This is
+    /// not the case when the replacement option is set as well.
+    pub fixable: bool,
+}
+
+impl Deprecation {
+    pub fn emit(&self) {
+        warn!(
+            message = "Deprecated configuration option.",
+            id = self.id,
+            component = %self.component,
+            option = self.option,
+            replacement = %self.replacement,
+            fixable = self.fixable,
+        );
+    }
+}
+
+impl fmt::Display for Deprecation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Option `{}` of `{}` is deprecated, use `{}` instead.",
+            self.option, self.component, self.replacement
+        )
+    }
+}
+
+/// Finds all deprecated options used in a raw config.
+pub fn check(config: &Value) -> Vec<Deprecation> {
+    let mut found = Vec::new();
+    visit(config, |rule, name, component| {
+        if let Some(value) = component.get(rule.option) {
+            let target = rule.migration.target();
+            found.push(Deprecation {
+                id: rule.id,
+                component: format!("{}.{}", rule.section, name),
+                option: rule.option,
+                replacement_option: target,
+                replacement: format!("{} = {}", target, rule.migration.migrate(value.clone())),
+                fixable: get_path(component, target).is_none(),
+            });
+        }
+    });
+    found
+}
+
+/// Rewrites all fixable deprecated options of a raw config, returning the
+/// deprecations that were migrated.
+pub fn fix(config: &mut Value) -> Vec<Deprecation> {
+    let fixable = check(config)
+        .into_iter()
+        .filter(|deprecation| deprecation.fixable)
+        .collect::<Vec<_>>();
+
+    visit_mut(config, |rule, component| {
+        let target = rule.migration.target();
+        if get_path(component, target).is_none() {
+            if let Some(value) = component.remove(rule.option) {
+                insert_path(component, target, rule.migration.migrate(value));
+            }
+        }
+    });
+
+    fixable
+}
+
+fn applies(rule: &Rule, component: &Map<String, Value>) -> bool {
+    rule.types.is_empty()
+        || component
+            .get("type")
+            .and_then(Value::as_str)
+            .map_or(false, |t| rule.types.contains(&t))
+}
+
+fn visit(config: &Value, mut f: impl FnMut(&Rule, &str, &Map<String, Value>)) {
+    for rule in RULES {
+        let section = config.get(rule.section).and_then(Value::as_table);
+        for (name, component) in section.into_iter().flatten() {
+            if let Some(component) = component.as_table() {
+                if applies(rule, component) {
+                    f(rule, name, component);
+                }
+            }
+        }
+    }
+}
+
+fn visit_mut(config: &mut Value, mut f: impl FnMut(&Rule, &mut Map<String, Value>)) {
+    for rule in RULES {
+        let section = config.get_mut(rule.section).and_then(Value::as_table_mut);
+        for (_, component) in section.into_iter().flatten() {
+            if let Some(component) = component.as_table_mut() {
+                if applies(rule, component) {
+                    f(rule, component);
+                }
+            }
+        }
+    }
+}
+
+fn get_path<'a>(table: &'a Map<String, Value>, path: &str) -> Option<&'a Value> {
+    let mut segments = path.split('.');
+    let first = table.get(segments.next()?)?;
+    segments.try_fold(first, |value, segment| value.get(segment))
+}
+
+fn insert_path(table: &mut Map<String, Value>, path: &str, value: Value) {
+    match path.find('.') {
+        None => {
+            table.insert(path.to_owned(), value);
+        }
+        Some(index) => {
+            let entry = table
+                .entry(path[..index].to_owned())
+                .or_insert_with(|| Value::Table(Map::new()));
+            if let Value::Table(inner) = entry {
+                insert_path(inner, &path[index + 1..], value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(config: &str) -> Value {
+        toml::from_str(config).unwrap()
+    }
+
+    #[test]
+    fn reports_deprecated_options() {
+        let config = parse(
+            r#"
+            [sinks.out]
+            type = "http"
+            healthcheck_uri = "http://localhost/health"
+            headers.Authorization = "token"
+
+            [sinks.other]
+            type = "console"
+            assume_role = "not-an-aws-sink"
+            "#,
+        );
+
+        let deprecations = check(&config);
+        assert_eq!(deprecations.len(), 2);
+        assert_eq!(deprecations[0].id, "sink_healthcheck_uri");
+        assert_eq!(deprecations[0].component, "sinks.out");
+        assert_eq!(
+            deprecations[0].replacement,
+            r#"healthcheck.uri = "http://localhost/health""#
+        );
+        assert_eq!(deprecations[1].id, "sink_headers");
+        assert!(deprecations.iter().all(|d| d.fixable));
+    }
+
+    #[test]
+    fn fixes_deprecated_options() {
+        let mut config = parse(
+            r#"
+            [sinks.out]
+            type = "aws_s3"
+            assume_role = "arn:aws:iam::123456789098:role/vector"
+            healthcheck.enabled = false
+
+            [transforms.parse]
+            type = "regex_parser"
+            regex = "^(?P<level>\\w+)"
+            "#,
+        );
+
+        assert_eq!(fix(&mut config).len(), 2);
+        assert_eq!(
+            config,
+            parse(
+                r#"
+                [sinks.out]
+                type = "aws_s3"
+                auth.assume_role = "arn:aws:iam::123456789098:role/vector"
+                healthcheck.enabled = false
+
+                [transforms.parse]
+                type = "regex_parser"
+                patterns = ["^(?P<level>\\w+)"]
+                "#
+            )
+        );
+        assert!(check(&config).is_empty());
+    }
+
+    #[test]
+    fn does_not_overwrite_replacement() {
+        let mut config = parse(
+            r#"
+            [sinks.out]
+            type = "clickhouse"
+            host = "http://old:8123"
+            endpoint = "http://new:8123"
+            "#,
+        );
+
+        let deprecations = check(&config);
+        assert_eq!(deprecations.len(), 1);
+        assert!(!deprecations[0].fixable);
+
+        assert!(fix(&mut config).is_empty());
+        assert_eq!(
+            config["sinks"]["out"]["host"].as_str(),
+            Some("http://old:8123")
+        );
+    }
+}
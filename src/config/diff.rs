@@ -14,9 +14,21 @@ impl ConfigDiff {
     }
 
     pub fn new(old: &Config, new: &Config) -> Self {
+        let mut transforms = Difference::new(&old.transforms, &new.transforms);
+        // The transforms look their enrichment tables up when they are built.
+        if old.enrichment_tables != new.enrichment_tables {
+            let kept = old
+                .transforms
+                .keys()
+                .filter(|name| new.transforms.contains_key(*name))
+                .cloned()
+                .collect::<Vec<_>>();
+            transforms.to_change.extend(kept);
+        }
+
         ConfigDiff {
             sources: Difference::new(&old.sources, &new.sources),
-            transforms: Difference::new(&old.transforms, &new.transforms),
+            transforms,
             sinks: Difference::new(&old.sinks, &new.sinks),
         }
     }
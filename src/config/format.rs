@@ -2,7 +2,7 @@
 
 #![deny(missing_docs, missing_debug_implementations)]
 
-use serde::de;
+use serde::{de, ser};
 use std::path::Path;
 
 /// A type alias to better capture the semantics.
@@ -51,6 +51,19 @@ where
     }
 }
 
+/// Serialize the value into the specified format, defaulting to TOML when the
+/// format is unknown.
+pub fn serialize<T>(value: &T, format: FormatHint) -> Result<String, Vec<String>>
+where
+    T: ser::Serialize,
+{
+    match format.unwrap_or_default() {
+        Format::TOML => toml::to_string(value).map_err(|e| vec![e.to_string()]),
+        Format::YAML => serde_yaml::to_string(value).map_err(|e| vec![e.to_string()]),
+        Format::JSON => serde_json::to_string_pretty(value).map_err(|e| vec![e.to_string()]),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
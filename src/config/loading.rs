@@ -1,4 +1,8 @@
-use super::{builder::ConfigBuilder, format, handle_warnings, vars, Config, Format, FormatHint};
+use super::{
+    builder::ConfigBuilder,
+    deprecation::{self, Deprecation},
+    format, handle_warnings, templates, vars, Config, Format, FormatHint,
+};
 use glob::glob;
 use lazy_static::lazy_static;
 use std::{
@@ -164,5 +168,14 @@ fn load(
     let (with_vars, warnings) = vars::interpolate(&source_string, &vars);
     handle_warnings(warnings, deny_warnings)?;
 
+    if let Ok(mut raw) = format::deserialize::<toml::Value>(&with_vars, format) {
+        if raw.get(templates::SECTION).is_some() {
+            templates::expand(&mut raw)?;
+            deprecation::check(&raw).iter().for_each(Deprecation::emit);
+            return raw.try_into().map_err(|e| vec![e.to_string()]);
+        }
+        deprecation::check(&raw).iter().for_each(Deprecation::emit);
+    }
+
     format::deserialize(&with_vars, format)
 }
@@ -1,31 +1,41 @@
+#[cfg(feature = "state-s3")]
+use crate::state::S3Store;
 use crate::{
     buffers::Acker,
-    conditions,
+    conditions::{self, ConditionConfig},
+    enrichment_tables::EnrichmentTableConfig,
     event::Metric,
     shutdown::ShutdownSignal,
     sinks::{self, util::UriSerde},
-    sources, transforms, Pipeline,
+    sources,
+    state::{LocalStore, StateOptions, StateStore},
+    transforms, BackpressurePolicy, Pipeline,
 };
 use async_trait::async_trait;
 use component::ComponentDescription;
 use indexmap::IndexMap; // IndexMap preserves insertion order, allowing us to output errors in the same order they are present in the file
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::{self, Display, Formatter};
 use std::fs::DirBuilder;
 use std::hash::Hash;
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::time::Duration;
 
 pub mod api;
 mod builder;
 mod compiler;
 pub mod component;
+pub mod data_dir;
+pub mod deprecation;
 mod diff;
 pub mod format;
 mod loading;
 mod log_schema;
+pub mod schema;
+mod templates;
 mod unit_test;
 mod validation;
 mod vars;
@@ -45,9 +55,10 @@ pub struct Config {
     #[cfg(feature = "api")]
     pub api: api::Options,
     pub healthchecks: HealthcheckOptions,
-    pub sources: IndexMap<String, Box<dyn SourceConfig>>,
+    pub sources: IndexMap<String, SourceOuter>,
     pub sinks: IndexMap<String, SinkOuter>,
     pub transforms: IndexMap<String, TransformOuter>,
+    pub enrichment_tables: IndexMap<String, EnrichmentTableConfig>,
     tests: Vec<TestDefinition>,
     expansions: IndexMap<String, Vec<String>>,
 }
@@ -61,6 +72,32 @@ pub struct GlobalOptions {
         default
     )]
     pub log_schema: LogSchema,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub cluster: crate::cluster::ClusterOptions,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub crash_report: crate::crash_report::CrashReportOptions,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub state: StateOptions,
+    /// Forget the metric series not updated for this long, in the sinks
+    /// keeping their state. Sinks can override it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expire_metrics_secs: Option<u64>,
+    /// Holds back the status of the events sent to their sources until the
+    /// sinks acknowledge them. Sinks can override it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acknowledgements: Option<bool>,
+    /// The tables of the `enrichment_tables` section, once loaded.
+    #[serde(skip)]
+    pub enrichment_tables: remap_functions::EnrichmentTables,
 }
 
 pub fn default_data_dir() -> Option<PathBuf> {
@@ -73,8 +110,11 @@ pub enum DataDirError {
     MissingDataDir,
     #[snafu(display("data_dir {:?} does not exist", data_dir))]
     DoesNotExist { data_dir: PathBuf },
-    #[snafu(display("data_dir {:?} is not writable", data_dir))]
-    NotWritable { data_dir: PathBuf },
+    #[snafu(display("data_dir {:?} is not writable: {}", data_dir, source))]
+    NotWritable {
+        data_dir: PathBuf,
+        source: std::io::Error,
+    },
     #[snafu(display(
         "Could not create subdirectory {:?} inside of data dir {:?}: {}",
         subdir,
@@ -90,7 +130,7 @@ pub enum DataDirError {
 
 impl GlobalOptions {
     /// Resolve the `data_dir` option in either the global or local
-    /// config, and validate that it exists and is writable.
+    /// config, and validate that it exists and files can be written to it.
     pub fn resolve_and_validate_data_dir(
         &self,
         local_data_dir: Option<&PathBuf>,
@@ -103,12 +143,9 @@ impl GlobalOptions {
         if !data_dir.exists() {
             return Err(DataDirError::DoesNotExist { data_dir }.into());
         }
-        let readonly = std::fs::metadata(&data_dir)
-            .map(|meta| meta.permissions().readonly())
-            .unwrap_or(true);
-        if readonly {
-            return Err(DataDirError::NotWritable { data_dir }.into());
-        }
+        data_dir::check_writable(&data_dir).with_context(|| NotWritable {
+            data_dir: data_dir.clone(),
+        })?;
         Ok(data_dir)
     }
 
@@ -131,6 +168,23 @@ impl GlobalOptions {
             .with_context(|| CouldNotCreate { subdir, data_dir })?;
         Ok(data_subdir)
     }
+
+    /// Build the store holding the state of `component`. The local backend
+    /// keeps it in the subdirectory `resolve_and_make_data_subdir` returns.
+    pub fn state_store(
+        &self,
+        local: Option<&PathBuf>,
+        component: &str,
+    ) -> crate::Result<Box<dyn StateStore>> {
+        match &self.state {
+            StateOptions::Local => {
+                let dir = self.resolve_and_make_data_subdir(local, component)?;
+                Ok(Box::new(LocalStore::new(dir)))
+            }
+            #[cfg(feature = "state-s3")]
+            StateOptions::S3(config) => Ok(Box::new(S3Store::new(config, component)?)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
@@ -167,6 +221,24 @@ pub enum DataType {
     Any,
     Log,
     Metric,
+    Trace,
+    /// Logs and traces, but not metrics.
+    LogOrTrace,
+}
+
+impl DataType {
+    /// Whether events of type `self` can be sent to a component taking
+    /// `other`.
+    pub fn is_compatible(self, other: DataType) -> bool {
+        match (self, other) {
+            (DataType::Any, _) | (_, DataType::Any) => true,
+            (DataType::LogOrTrace, DataType::Log)
+            | (DataType::LogOrTrace, DataType::Trace)
+            | (DataType::Log, DataType::LogOrTrace)
+            | (DataType::Trace, DataType::LogOrTrace) => true,
+            _ => self == other,
+        }
+    }
 }
 
 pub trait GenerateConfig {
@@ -204,12 +276,92 @@ pub trait SourceConfig: core::fmt::Debug + Send + Sync {
     fn resources(&self) -> Vec<Resource> {
         Vec::new()
     }
+
+    /// What the source does when downstream is saturated, unless configured otherwise.
+    fn default_on_backpressure(&self) -> BackpressurePolicy {
+        BackpressurePolicy::Block
+    }
+
+    /// The outputs of the source besides its main one, which it gets from
+    /// `Pipeline::output`, and other components take as input as
+    /// `<source>.<output>`.
+    fn outputs(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
 }
 
 pub type SourceDescription = ComponentDescription<Box<dyn SourceConfig>>;
 
 inventory::collect!(SourceDescription);
 
+#[derive(Deserialize, Serialize, Debug)]
+pub struct SourceOuter {
+    /// VRL condition events must satisfy to be sent on by the source. Other events are
+    /// dropped before reaching any transform or sink.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+
+    /// Overrides whether the source blocks or sheds events when downstream is saturated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_backpressure: Option<BackpressurePolicy>,
+
+    /// Priority given to the events of the source which don't have one, for
+    /// the buffers dropping events by priority.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<crate::buffers::Priority>,
+
+    /// Labels added to the internal metrics and logs of the source.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub telemetry_labels: BTreeMap<String, String>,
+
+    #[serde(flatten)]
+    pub inner: Box<dyn SourceConfig>,
+}
+
+impl SourceOuter {
+    pub fn new(inner: Box<dyn SourceConfig>) -> Self {
+        SourceOuter {
+            filter: None,
+            on_backpressure: None,
+            priority: None,
+            telemetry_labels: BTreeMap::new(),
+            inner,
+        }
+    }
+
+    pub fn on_backpressure(&self) -> BackpressurePolicy {
+        self.on_backpressure
+            .unwrap_or_else(|| self.inner.default_on_backpressure())
+    }
+
+    pub fn resources(&self) -> Vec<Resource> {
+        self.inner.resources()
+    }
+
+    /// The outputs of the source named `name` besides its main one, with the
+    /// names other components take them as input by.
+    pub fn outputs(&self, name: &str) -> Vec<(&'static str, String)> {
+        self.inner
+            .outputs()
+            .into_iter()
+            .map(|output| (output, format!("{}.{}", name, output)))
+            .collect()
+    }
+
+    /// Builds the filter of the source, if set.
+    pub fn build_filter(&self) -> crate::Result<Option<Box<dyn conditions::Condition>>> {
+        self.filter
+            .as_ref()
+            .map(|source| {
+                conditions::remap::RemapConfig {
+                    source: source.clone(),
+                }
+                .build()
+            })
+            .transpose()
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct SinkOuter {
     pub inputs: Vec<String>,
@@ -225,6 +377,23 @@ pub struct SinkOuter {
     #[serde(default)]
     pub buffer: crate::buffers::BufferConfig,
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quota: Option<crate::sinks::util::quota::QuotaConfig>,
+
+    /// Holds back the status of the events sent to their sources until the
+    /// sink acknowledges them, instead of as soon as they're buffered.
+    /// Overrides the global `acknowledgements` for this sink.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acknowledgements: Option<bool>,
+
+    /// Captures a sample of the HTTP requests of the sink, and their responses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debug_capture: Option<crate::sinks::util::debug_capture::DebugCaptureConfig>,
+
+    /// Labels added to the internal metrics and logs of the sink.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub telemetry_labels: BTreeMap<String, String>,
+
     #[serde(flatten)]
     pub inner: Box<dyn SinkConfig>,
 }
@@ -235,6 +404,10 @@ impl SinkOuter {
             buffer: Default::default(),
             healthcheck: SinkHealthcheckOptions::default(),
             healthcheck_uri: None,
+            quota: None,
+            acknowledgements: None,
+            debug_capture: None,
+            telemetry_labels: BTreeMap::new(),
             inner,
             inputs,
         }
@@ -243,14 +416,15 @@ impl SinkOuter {
     pub fn resources(&self, name: &str) -> Vec<Resource> {
         let mut resources = self.inner.resources();
         resources.append(&mut self.buffer.resources(name));
+        if let Some(divert) = self.quota.as_ref().and_then(|quota| quota.divert.as_ref()) {
+            resources.append(&mut divert.resources());
+        }
         resources
     }
 
     pub fn healthcheck(&self) -> SinkHealthcheckOptions {
         if self.healthcheck_uri.is_some() && self.healthcheck.uri.is_some() {
             warn!("Both `healthcheck.uri` and `healthcheck_uri` options are specified. Using value of `healthcheck.uri`.")
-        } else if self.healthcheck_uri.is_some() {
-            warn!("`healthcheck_uri` option has been deprecated, use `healthcheck.uri` instead. ")
         }
         SinkHealthcheckOptions {
             uri: self
@@ -261,6 +435,14 @@ impl SinkOuter {
             ..self.healthcheck.clone()
         }
     }
+
+    /// Whether the sink holds back the status of its events until it
+    /// acknowledges them.
+    pub fn acknowledgements(&self, global: &GlobalOptions) -> bool {
+        self.acknowledgements
+            .or(global.acknowledgements)
+            .unwrap_or(false)
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -268,6 +450,20 @@ impl SinkOuter {
 pub struct SinkHealthcheckOptions {
     pub enabled: bool,
     pub uri: Option<UriSerde>,
+    /// Overrides `healthchecks.require_healthy` for this sink.
+    pub mode: Option<HealthcheckMode>,
+    pub timeout_secs: u64,
+    /// Re-runs the healthcheck periodically while the sink is running.
+    pub interval_secs: Option<u64>,
+}
+
+impl SinkHealthcheckOptions {
+    /// Whether a failing healthcheck prevents the topology from starting.
+    pub fn is_required(&self, global: &HealthcheckOptions) -> bool {
+        self.mode.map_or(global.require_healthy, |mode| {
+            mode == HealthcheckMode::Required
+        })
+    }
 }
 
 impl Default for SinkHealthcheckOptions {
@@ -275,25 +471,40 @@ impl Default for SinkHealthcheckOptions {
         Self {
             enabled: true,
             uri: None,
+            mode: None,
+            timeout_secs: 10,
+            interval_secs: None,
         }
     }
 }
 
 impl From<bool> for SinkHealthcheckOptions {
     fn from(enabled: bool) -> Self {
-        Self { enabled, uri: None }
+        Self {
+            enabled,
+            ..Self::default()
+        }
     }
 }
 
 impl From<UriSerde> for SinkHealthcheckOptions {
     fn from(uri: UriSerde) -> Self {
         Self {
-            enabled: true,
             uri: Some(uri),
+            ..Self::default()
         }
     }
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthcheckMode {
+    /// A failing healthcheck prevents Vector from starting.
+    Required,
+    /// A failing healthcheck is only logged.
+    Advisory,
+}
+
 #[async_trait]
 #[typetag::serde(tag = "type")]
 pub trait SinkConfig: core::fmt::Debug + Send + Sync {
@@ -316,6 +527,7 @@ pub trait SinkConfig: core::fmt::Debug + Send + Sync {
 pub struct SinkContext {
     pub(super) acker: Acker,
     pub(super) healthcheck: SinkHealthcheckOptions,
+    pub(super) expire_metrics: Option<Duration>,
 }
 
 impl SinkContext {
@@ -324,12 +536,27 @@ impl SinkContext {
         Self {
             acker: Acker::Null,
             healthcheck: SinkHealthcheckOptions::default(),
+            expire_metrics: None,
         }
     }
 
     pub fn acker(&self) -> Acker {
         self.acker.clone()
     }
+
+    /// The context of a sink that acks its events with `acker` instead.
+    pub fn with_acker(&self, acker: Acker) -> Self {
+        Self {
+            acker,
+            ..self.clone()
+        }
+    }
+
+    /// How long metric series are kept without updates, from the global
+    /// `expire_metrics_secs` option.
+    pub fn expire_metrics(&self) -> Option<Duration> {
+        self.expire_metrics
+    }
 }
 
 pub type SinkDescription = ComponentDescription<Box<dyn SinkConfig>>;
@@ -339,6 +566,9 @@ inventory::collect!(SinkDescription);
 #[derive(Deserialize, Serialize, Debug)]
 pub struct TransformOuter {
     pub inputs: Vec<String>,
+    /// Labels added to the internal metrics and logs of the transform.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub telemetry_labels: BTreeMap<String, String>,
     #[serde(flatten)]
     pub inner: Box<dyn TransformConfig>,
 }
@@ -346,7 +576,11 @@ pub struct TransformOuter {
 #[async_trait]
 #[typetag::serde(tag = "type")]
 pub trait TransformConfig: core::fmt::Debug + Send + Sync + dyn_clone::DynClone {
-    async fn build(&self) -> crate::Result<transforms::Transform>;
+    async fn build(
+        &self,
+        name: &str,
+        globals: &GlobalOptions,
+    ) -> crate::Result<transforms::Transform>;
 
     fn input_type(&self) -> DataType;
 
@@ -357,9 +591,18 @@ pub trait TransformConfig: core::fmt::Debug + Send + Sync + dyn_clone::DynClone
     /// Allows a transform configuration to expand itself into multiple "child"
     /// transformations to replace it. This allows a transform to act as a macro
     /// for various patterns.
+    ///
+    /// The children are named `<parent>.<child>`, except for a child named
+    /// with an empty string, which keeps the name of its parent.
     fn expand(&mut self) -> crate::Result<Option<IndexMap<String, Box<dyn TransformConfig>>>> {
         Ok(None)
     }
+
+    /// Whether the transform is an output of the transform it was expanded
+    /// from, which sends it its events directly. It has no inputs.
+    fn is_secondary_output(&self) -> bool {
+        false
+    }
 }
 
 dyn_clone::clone_trait_object!(TransformConfig);
@@ -512,6 +755,42 @@ impl Config {
             .cloned()
             .unwrap_or_else(|| vec![String::from(identifier)])
     }
+
+    /// The telemetry labels of the components which have some, by component name.
+    pub fn telemetry_labels(&self) -> HashMap<String, BTreeMap<String, String>> {
+        let sources = self
+            .sources
+            .iter()
+            .map(|(name, source)| (name, &source.telemetry_labels));
+        let transforms = self
+            .transforms
+            .iter()
+            .map(|(name, transform)| (name, &transform.telemetry_labels));
+        let sinks = self
+            .sinks
+            .iter()
+            .map(|(name, sink)| (name, &sink.telemetry_labels));
+
+        sources
+            .chain(transforms)
+            .chain(sinks)
+            .filter(|(_, labels)| !labels.is_empty())
+            .map(|(name, labels)| (name.clone(), labels.clone()))
+            .collect()
+    }
+
+    /// The debug capture settings of the sinks which enabled them, by sink name.
+    pub fn debug_captures(
+        &self,
+    ) -> HashMap<String, crate::sinks::util::debug_capture::DebugCaptureConfig> {
+        self.sinks
+            .iter()
+            .filter_map(|(name, sink)| {
+                let capture = sink.debug_capture.clone()?;
+                Some((name.clone(), capture))
+            })
+            .collect()
+    }
 }
 
 fn handle_warnings(warnings: Vec<String>, deny_warnings: bool) -> Result<(), Vec<String>> {
@@ -560,6 +839,84 @@ mod test {
         )
     }
 
+    #[test]
+    fn source_filter() {
+        let config = load_from_str(
+            r#"
+            [sources.in]
+            type = "file"
+            include = ["/var/log/messages"]
+            filter = '.status != 200'
+
+            [sinks.out]
+            type = "console"
+            inputs = ["in"]
+            encoding = "json"
+            "#,
+            Some(Format::TOML),
+        )
+        .unwrap();
+
+        let filter = config.sources["in"].build_filter().unwrap().unwrap();
+        let mut event = crate::Event::from("healthcheck");
+        event.as_mut_log().insert("status", 200);
+        assert!(!filter.check(&event));
+        event.as_mut_log().insert("status", 500);
+        assert!(filter.check(&event));
+    }
+
+    #[test]
+    fn telemetry_labels() {
+        let config = load_from_str(
+            r#"
+            [sources.in]
+            type = "file"
+            include = ["/var/log/messages"]
+            telemetry_labels = { team = "payments" }
+
+            [sinks.out]
+            type = "console"
+            inputs = ["in"]
+            encoding = "json"
+            "#,
+            Some(Format::TOML),
+        )
+        .unwrap();
+
+        let labels = config.telemetry_labels();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels["in"]["team"], "payments");
+    }
+
+    #[test]
+    fn sinks_override_acknowledgements() {
+        let config = load_from_str(
+            r#"
+            acknowledgements = true
+
+            [sources.in]
+            type = "file"
+            include = ["/var/log/messages"]
+
+            [sinks.strict]
+            type = "console"
+            inputs = ["in"]
+            encoding = "json"
+
+            [sinks.best_effort]
+            type = "console"
+            inputs = ["in"]
+            encoding = "json"
+            acknowledgements = false
+            "#,
+            Some(Format::TOML),
+        )
+        .unwrap();
+
+        assert!(config.sinks["strict"].acknowledgements(&config.global));
+        assert!(!config.sinks["best_effort"].acknowledgements(&config.global));
+    }
+
     #[test]
     fn default_schema() {
         let config = load_from_str(
@@ -849,4 +1206,31 @@ mod resource_tests {
         )
         .is_err());
     }
+
+    #[cfg(feature = "transforms-remap")]
+    #[test]
+    fn secondary_output_has_no_inputs() {
+        let config = load_from_str(
+            r#"
+            [sources.in]
+            type = "stdin"
+
+            [transforms.remap]
+            type = "remap"
+            inputs = ["in"]
+            source = ".message = parse_json!(.message)"
+            reroute_dropped = true
+
+            [sinks.out]
+            type = "console"
+            inputs = ["remap", "remap.dropped"]
+            encoding = "json"
+            "#,
+            Some(Format::TOML),
+        )
+        .unwrap();
+
+        assert_eq!(config.transforms["remap"].inputs, vec!["in"]);
+        assert!(config.transforms["remap.dropped"].inputs.is_empty());
+    }
 }
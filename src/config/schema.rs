@@ -0,0 +1,315 @@
+//! JSON Schema generation for the configuration surface.
+//!
+//! Global options are described from their default values, while each
+//! registered source, transform and sink is described from the example config
+//! its `GenerateConfig` implementation produces. Component schemas are keyed on
+//! the `type` option so editors can offer completion per component type.
+//!
+//! Values only show which options exist and what they commonly hold, not every
+//! form they accept, so no type is inferred from them. The schema is meant for
+//! completion and documentation in editors, not for validating configs, which
+//! is what `vector validate` is for.
+
+use super::{
+    default_data_dir, HealthcheckOptions, LogSchema, SinkDescription, SinkHealthcheckOptions,
+    SourceDescription, TransformDescription,
+};
+use crate::{
+    buffers::BufferConfig, cluster::ClusterOptions, crash_report::CrashReportOptions,
+    state::StateOptions,
+};
+use colored::*;
+use serde::Serialize;
+use serde_json::{json, Map, Value as JsonValue};
+use std::{fs, path::PathBuf};
+use structopt::StructOpt;
+use toml::Value;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// Write the schema to a file instead of stdout
+    #[structopt(short, long, parse(from_os_str))]
+    output: Option<PathBuf>,
+}
+
+/// Generates a JSON Schema (draft 7) describing a complete Vector config, for
+/// completion rather than validation.
+pub fn generate() -> JsonValue {
+    let mut properties = Map::new();
+    properties.insert(
+        "data_dir".into(),
+        json!({
+            "type": "string",
+            "default": default_data_dir(),
+        }),
+    );
+    properties.insert("log_schema".into(), default_schema(&LogSchema::default()));
+    properties.insert("cluster".into(), default_schema(&ClusterOptions::default()));
+    properties.insert(
+        "crash_report".into(),
+        default_schema(&CrashReportOptions::default()),
+    );
+    properties.insert("state".into(), default_schema(&StateOptions::default()));
+    properties.insert(
+        "expire_metrics_secs".into(),
+        json!({
+            "type": "integer",
+            "minimum": 1,
+        }),
+    );
+    properties.insert("acknowledgements".into(), json!({ "type": "boolean" }));
+    properties.insert(
+        "healthchecks".into(),
+        default_schema(&HealthcheckOptions::default()),
+    );
+    #[cfg(feature = "api")]
+    properties.insert(
+        "api".into(),
+        default_schema(&super::api::Options::default()),
+    );
+
+    properties.insert(
+        "component_templates".into(),
+        json!({
+            "type": "object",
+            "additionalProperties": {
+                "type": "object",
+                "properties": {
+                    "kind": { "enum": ["sources", "transforms", "sinks"] },
+                    "name": { "type": "string" },
+                    "for_each": { "type": "array" },
+                    "component": { "type": "object" },
+                },
+                "required": ["kind", "name", "for_each", "component"],
+                "additionalProperties": false,
+            },
+        }),
+    );
+    properties.insert(
+        "sources".into(),
+        section_schema(
+            SourceDescription::types(),
+            SourceDescription::example,
+            json!({
+                "filter": {
+                    "type": "string",
+                    "description": "A VRL condition events must satisfy to be sent on by the source.",
+                },
+                "priority": {
+                    "enum": ["low", "normal", "high"],
+                    "description": "The priority given to the events of the source which don't have one.",
+                },
+                "telemetry_labels": telemetry_labels_schema(),
+            }),
+        ),
+    );
+    properties.insert(
+        "transforms".into(),
+        section_schema(
+            TransformDescription::types(),
+            TransformDescription::example,
+            json!({
+                "inputs": inputs_schema(),
+                "telemetry_labels": telemetry_labels_schema(),
+            }),
+        ),
+    );
+    properties.insert(
+        "sinks".into(),
+        section_schema(
+            SinkDescription::types(),
+            SinkDescription::example,
+            json!({
+                "inputs": inputs_schema(),
+                "healthcheck": default_schema(&SinkHealthcheckOptions::default()),
+                "buffer": default_schema(&BufferConfig::default()),
+                "quota": { "type": "object" },
+                "acknowledgements": { "type": "boolean" },
+                "debug_capture": { "type": "object" },
+                "telemetry_labels": telemetry_labels_schema(),
+            }),
+        ),
+    );
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "Vector configuration",
+        "description": "Describes the options of a Vector config for editors. Not every valid config value is described, use `vector validate` to validate configs.",
+        "type": "object",
+        "properties": properties,
+    })
+}
+
+fn inputs_schema() -> JsonValue {
+    json!({
+        "type": "array",
+        "items": { "type": "string" },
+        "description": "The names of the upstream components to read events from.",
+    })
+}
+
+fn telemetry_labels_schema() -> JsonValue {
+    json!({
+        "type": "object",
+        "additionalProperties": { "type": "string" },
+        "description": "Labels added to the internal metrics and logs of the component.",
+    })
+}
+
+/// A map of component names to components, each being one of the registered
+/// component types.
+fn section_schema<E>(
+    types: Vec<&'static str>,
+    example: fn(&str) -> Result<Value, E>,
+    common: JsonValue,
+) -> JsonValue {
+    let variants = types
+        .into_iter()
+        .map(|component_type| {
+            let mut properties = match example(component_type) {
+                Ok(Value::Table(table)) => table
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value_schema(value, "examples")))
+                    .collect(),
+                _ => Map::new(),
+            };
+            if let JsonValue::Object(common) = &common {
+                properties.extend(common.clone());
+            }
+            properties.insert("type".into(), json!({ "const": component_type }));
+
+            let mut required = vec!["type"];
+            if common.get("inputs").is_some() {
+                required.push("inputs");
+            }
+
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "type": "object",
+        "additionalProperties": { "oneOf": variants },
+    })
+}
+
+/// Describes a value of which all options are known from its default.
+fn default_schema<T: Serialize>(value: &T) -> JsonValue {
+    match Value::try_from(value) {
+        Ok(value) => value_schema(&value, "default"),
+        Err(_) => json!({}),
+    }
+}
+
+/// Describes the options in `value`, recording leaf values under `annotation`,
+/// either `default` or `examples`. Options often accept other types than the
+/// one of their value, like a table in place of a string, so the schema only
+/// annotates them and never constrains their type.
+fn value_schema(value: &Value, annotation: &str) -> JsonValue {
+    let leaf = match value {
+        Value::String(s) => json!(s),
+        Value::Integer(i) => json!(i),
+        Value::Float(f) => json!(f),
+        Value::Boolean(b) => json!(b),
+        Value::Datetime(dt) => json!(dt.to_string()),
+        Value::Array(values) => {
+            let mut schema = json!({});
+            if let Some(first) = values.first() {
+                schema["items"] = value_schema(first, annotation);
+            }
+            return schema;
+        }
+        Value::Table(table) => {
+            let properties = table
+                .iter()
+                .map(|(key, value)| (key.clone(), value_schema(value, annotation)))
+                .collect::<Map<_, _>>();
+            return json!({ "properties": properties });
+        }
+    };
+
+    let mut schema = Map::new();
+    if annotation == "examples" {
+        schema.insert(annotation.into(), json!([leaf]));
+    } else {
+        schema.insert(annotation.into(), leaf);
+    }
+    JsonValue::Object(schema)
+}
+
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let schema = match serde_json::to_string_pretty(&generate()) {
+        Ok(schema) => schema,
+        Err(error) => {
+            eprintln!("{}", format!("failed to marshal schema: {}", error).red());
+            return exitcode::SOFTWARE;
+        }
+    };
+
+    match &opts.output {
+        Some(path) => {
+            if let Err(error) = fs::write(path, schema) {
+                eprintln!("{}", format!("failed to write to file: {}", error).red());
+                return exitcode::IOERR;
+            }
+        }
+        None => println!("{}", schema),
+    }
+
+    exitcode::OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_every_component() {
+        let schema = generate();
+
+        for (section, types) in &[
+            ("sources", SourceDescription::types()),
+            ("transforms", TransformDescription::types()),
+            ("sinks", SinkDescription::types()),
+        ] {
+            let variants = schema["properties"][section]["additionalProperties"]["oneOf"]
+                .as_array()
+                .unwrap();
+            assert_eq!(variants.len(), types.len(), "{}", section);
+            for (variant, component_type) in variants.iter().zip(types) {
+                assert_eq!(
+                    variant["properties"]["type"]["const"],
+                    json!(component_type)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn annotates_value_schemas() {
+        let value: Value = toml::from_str(
+            r#"
+            name = "foo"
+            count = 3
+            tags = ["a"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value_schema(&value, "default"),
+            json!({
+                "properties": {
+                    "name": { "default": "foo" },
+                    "count": { "default": 3 },
+                    "tags": { "items": { "default": "a" } },
+                },
+            })
+        );
+    }
+}
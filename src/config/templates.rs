@@ -0,0 +1,291 @@
+//! Generation of components from `[component_templates]`.
+//!
+//! A template describes one parametrized component and a `for_each` list of
+//! parameters. Loading a config expands every template into one concrete
+//! component per `for_each` item, substituting `{{ name }}` placeholders in
+//! the component name and in all of its string options.
+//!
+//! ```toml
+//! [component_templates.tenant_kafka]
+//! kind = "sources"
+//! name = "kafka_{{ tenant }}"
+//! for_each = [
+//!   { tenant = "acme", topic = "acme-logs" },
+//!   { tenant = "globex", topic = "globex-logs" },
+//! ]
+//! component.type = "kafka"
+//! component.topics = ["{{ topic }}"]
+//! ```
+//!
+//! Items of `for_each` that aren't tables are bound to `{{ item }}`.
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use toml::{map::Map, Value};
+
+pub const SECTION: &str = "component_templates";
+
+const KINDS: &[&str] = &["sources", "transforms", "sinks"];
+
+lazy_static! {
+    static ref PLACEHOLDER: Regex = Regex::new(r"\{\{\s*([[:word:]]+)\s*\}\}").unwrap();
+}
+
+/// Replaces the `component_templates` section of a raw config with the
+/// components it generates.
+pub fn expand(config: &mut Value) -> Result<(), Vec<String>> {
+    let table = match config.as_table_mut() {
+        Some(table) => table,
+        None => return Ok(()),
+    };
+    let templates = match table.remove(SECTION) {
+        Some(Value::Table(templates)) => templates,
+        Some(_) => return Err(vec![format!("`{}` must be a table.", SECTION)]),
+        None => return Ok(()),
+    };
+
+    let mut errors = Vec::new();
+    for (template_name, template) in templates {
+        match generate(&template_name, template) {
+            Ok((kind, components)) => {
+                let section = table
+                    .entry(kind)
+                    .or_insert_with(|| Value::Table(Map::new()));
+                let section = match section.as_table_mut() {
+                    Some(section) => section,
+                    None => {
+                        errors.push(format!("`{}` must be a table.", kind));
+                        continue;
+                    }
+                };
+                for (name, component) in components {
+                    if section.contains_key(&name) {
+                        errors.push(format!(
+                            "Template \"{}\" generates {} \"{}\", which is already defined.",
+                            template_name, kind, name
+                        ));
+                    } else {
+                        section.insert(name, component);
+                    }
+                }
+            }
+            Err(error) => errors.push(error),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Generates the components of a single template, returning the section they
+/// belong to.
+fn generate(
+    template_name: &str,
+    template: Value,
+) -> Result<(&'static str, Vec<(String, Value)>), String> {
+    let error = |message: &str| format!("Template \"{}\" {}", template_name, message);
+
+    let mut template = match template {
+        Value::Table(template) => template,
+        _ => return Err(error("must be a table.")),
+    };
+    let kind = match template.remove("kind") {
+        Some(Value::String(kind)) => KINDS
+            .iter()
+            .find(|&&known| known == kind)
+            .copied()
+            .ok_or_else(|| {
+                error("has an unknown `kind`, expected one of `sources`, `transforms` or `sinks`.")
+            })?,
+        _ => return Err(error("is missing the `kind` option.")),
+    };
+    let name = match template.remove("name") {
+        Some(Value::String(name)) => name,
+        _ => return Err(error("is missing the `name` option.")),
+    };
+    let for_each = match template.remove("for_each") {
+        Some(Value::Array(for_each)) => for_each,
+        _ => return Err(error("is missing the `for_each` list.")),
+    };
+    let component = match template.remove("component") {
+        Some(component @ Value::Table(_)) => component,
+        _ => return Err(error("is missing the `component` table.")),
+    };
+    if let Some(unknown) = template.keys().next() {
+        return Err(error(&format!("has an unknown option `{}`.", unknown)));
+    }
+
+    let mut components: Vec<(String, Value)> = Vec::new();
+    for item in for_each {
+        let params = match item {
+            Value::Table(params) => params,
+            item => {
+                let mut params = Map::new();
+                params.insert("item".into(), item);
+                params
+            }
+        };
+
+        let component_name = substitute(&name, &params).map_err(|e| error(&e))?;
+        if components.iter().any(|(name, _)| name == &component_name) {
+            return Err(error(&format!(
+                "generates the name \"{}\" more than once.",
+                component_name
+            )));
+        }
+        let component = substitute_value(&component, &params).map_err(|e| error(&e))?;
+        components.push((component_name, component));
+    }
+
+    Ok((kind, components))
+}
+
+fn substitute_value(value: &Value, params: &Map<String, Value>) -> Result<Value, String> {
+    Ok(match value {
+        Value::String(s) => Value::String(substitute(s, params)?),
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| substitute_value(value, params))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Table(table) => Value::Table(
+            table
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), substitute_value(value, params)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        value => value.clone(),
+    })
+}
+
+fn substitute(template: &str, params: &Map<String, Value>) -> Result<String, String> {
+    let mut unknown = None;
+    let result = PLACEHOLDER.replace_all(template, |caps: &Captures<'_>| {
+        let key = &caps[1];
+        match params.get(key) {
+            Some(Value::String(s)) => s.clone(),
+            Some(value) => value.to_string(),
+            None => {
+                unknown.get_or_insert_with(|| key.to_owned());
+                String::new()
+            }
+        }
+    });
+
+    match unknown {
+        Some(key) => Err(format!(
+            "references the undefined parameter \"{}\" in \"{}\".",
+            key, template
+        )),
+        None => Ok(result.into_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(config: &str) -> Value {
+        toml::from_str(config).unwrap()
+    }
+
+    #[test]
+    fn expands_templates() {
+        let mut config = parse(
+            r#"
+            [sources.other]
+            type = "stdin"
+
+            [component_templates.tenant_kafka]
+            kind = "sources"
+            name = "kafka_{{ tenant }}"
+            for_each = [
+              { tenant = "acme", topic = "acme-logs", partitions = 3 },
+              { tenant = "globex", topic = "globex-logs", partitions = 1 },
+            ]
+            component.type = "kafka"
+            component.topics = ["{{topic}}"]
+            component.key_field = "{{ tenant }}_{{ partitions }}"
+
+            [component_templates.outputs]
+            kind = "sinks"
+            name = "out_{{ item }}"
+            for_each = ["acme", "globex"]
+            component.type = "console"
+            component.inputs = ["kafka_{{ item }}"]
+            "#,
+        );
+
+        expand(&mut config).unwrap();
+
+        assert_eq!(
+            config,
+            parse(
+                r#"
+                [sources.other]
+                type = "stdin"
+
+                [sources.kafka_acme]
+                type = "kafka"
+                topics = ["acme-logs"]
+                key_field = "acme_3"
+
+                [sources.kafka_globex]
+                type = "kafka"
+                topics = ["globex-logs"]
+                key_field = "globex_1"
+
+                [sinks.out_acme]
+                type = "console"
+                inputs = ["kafka_acme"]
+
+                [sinks.out_globex]
+                type = "console"
+                inputs = ["kafka_globex"]
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn reports_template_errors() {
+        let mut config = parse(
+            r#"
+            [sources.in_a]
+            type = "stdin"
+
+            [component_templates.collides]
+            kind = "sources"
+            name = "in_{{ item }}"
+            for_each = ["a", "b"]
+            component.type = "stdin"
+
+            [component_templates.undefined]
+            kind = "sinks"
+            name = "out_{{ item }}"
+            for_each = ["a"]
+            component.type = "console"
+            component.inputs = ["{{ missing }}"]
+
+            [component_templates.bad_kind]
+            kind = "tests"
+            name = "x"
+            for_each = []
+            component.type = "console"
+            "#,
+        );
+
+        assert_eq!(
+            expand(&mut config).unwrap_err(),
+            vec![
+                r#"Template "bad_kind" has an unknown `kind`, expected one of `sources`, `transforms` or `sinks`."#,
+                r#"Template "collides" generates sources "in_a", which is already defined."#,
+                r#"Template "undefined" references the undefined parameter "missing" in "{{ missing }}"."#,
+            ]
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use super::{Config, ConfigBuilder, TestDefinition, TestInput, TestInputValue};
-use crate::config::{self, TransformConfig};
+use crate::config::{self, GlobalOptions, TransformConfig};
 use crate::{
     conditions::Condition,
+    enrichment_tables,
     event::{Event, Value},
     transforms::Transform,
 };
@@ -10,8 +11,14 @@ use std::{collections::HashMap, path::PathBuf};
 
 pub async fn build_unit_tests_main(
     paths: &[(PathBuf, config::FormatHint)],
+    filter: Option<&str>,
 ) -> Result<Vec<UnitTest>, Vec<String>> {
-    let config = super::loading::load_builder_from_paths(paths, false)?;
+    let mut config = super::loading::load_builder_from_paths(paths, false)?;
+
+    // Tests filtered out aren't built either, so they can't fail the run.
+    if let Some(filter) = filter {
+        config.tests.retain(|test| test.name.contains(filter));
+    }
 
     // Ignore failures on calls other than the first
     crate::config::LOG_SCHEMA
@@ -26,6 +33,7 @@ async fn build_unit_tests(mut builder: ConfigBuilder) -> Result<Vec<UnitTest>, V
     let mut errors = vec![];
 
     let expansions = super::compiler::expand_macros(&mut builder)?;
+    builder.global.enrichment_tables = enrichment_tables::load(&builder.enrichment_tables)?;
 
     // Don't let this escape since it's not validated
     let config = Config {
@@ -36,6 +44,7 @@ async fn build_unit_tests(mut builder: ConfigBuilder) -> Result<Vec<UnitTest>, V
         sources: builder.sources,
         sinks: builder.sinks,
         transforms: builder.transforms,
+        enrichment_tables: builder.enrichment_tables,
         tests: builder.tests,
         expansions,
     };
@@ -83,6 +92,7 @@ fn event_to_string(event: &Event) -> String {
     match event {
         Event::Log(log) => serde_json::to_string(&log).unwrap_or_else(|_| "{}".into()),
         Event::Metric(metric) => serde_json::to_string(&metric).unwrap_or_else(|_| "{}".into()),
+        Event::Trace(trace) => serde_json::to_string(&trace).unwrap_or_else(|_| "{}".into()),
     }
 }
 
@@ -134,12 +144,16 @@ fn walk(
                 targets = target.next.clone();
                 // TODO: This is a hack.
                 // Our tasktransforms must consume the transform to attach it to an input stream, so we rebuild it between input streams.
-                transforms.insert(key, UnitTestTransform {
-                    transform:  futures::executor::block_on(target.config.clone().build())
-                        .expect("Failed to build a known valid transform config. Things may have changed during runtime."),
-                    config: target.config,
-                    next: target.next
-                });
+                let transform = futures::executor::block_on(target.config.clone().build(&key, &GlobalOptions::default()))
+                    .expect("Failed to build a known valid transform config. Things may have changed during runtime.");
+                transforms.insert(
+                    key,
+                    UnitTestTransform {
+                        transform,
+                        config: target.config,
+                        next: target.next,
+                    },
+                );
             }
         }
     }
@@ -446,7 +460,13 @@ async fn build_unit_test(
     let mut transforms: IndexMap<String, UnitTestTransform> = IndexMap::new();
     for (name, transform_config) in &config.transforms {
         if let Some(outputs) = transform_outputs.remove(name) {
-            match transform_config.inner.build().await {
+            // Transforms are built without the global options so that unit
+            // tests never touch the state persisted in the `data_dir`.
+            match transform_config
+                .inner
+                .build(name, &GlobalOptions::default())
+                .await
+            {
                 Ok(transform) => {
                     transforms.insert(
                         name.clone(),
@@ -1,5 +1,5 @@
 use super::{builder::ConfigBuilder, DataType, Resource};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 pub fn check_shape(config: &ConfigBuilder) -> Result<(), Vec<String>> {
     let mut errors = vec![];
@@ -12,6 +12,12 @@ pub fn check_shape(config: &ConfigBuilder) -> Result<(), Vec<String>> {
         errors.push("No sinks defined in the config.".to_owned());
     }
 
+    if config.global.cluster.enabled && config.global.cluster.store.path().is_none() {
+        errors.push(
+            "Cluster mode requires a `cluster.store.path` shared by all the instances.".to_owned(),
+        );
+    }
+
     // Helper for below
     fn tagged<'a>(
         tag: &'static str,
@@ -46,7 +52,14 @@ pub fn check_shape(config: &ConfigBuilder) -> Result<(), Vec<String>> {
     let transform_inputs = config
         .transforms
         .iter()
+        .filter(|(_, transform)| !transform.inner.is_secondary_output())
         .map(|(name, transform)| ("transform", name.clone(), transform.inputs.clone()));
+    let source_outputs = config
+        .sources
+        .iter()
+        .flat_map(|(name, source)| source.outputs(name))
+        .map(|(_, output)| output)
+        .collect::<HashSet<_>>();
     for (output_type, name, inputs) in sink_inputs.chain(transform_inputs) {
         if inputs.is_empty() {
             errors.push(format!(
@@ -57,7 +70,10 @@ pub fn check_shape(config: &ConfigBuilder) -> Result<(), Vec<String>> {
         }
 
         for input in inputs {
-            if !config.sources.contains_key(&input) && !config.transforms.contains_key(&input) {
+            if !config.sources.contains_key(&input)
+                && !source_outputs.contains(&input)
+                && !config.transforms.contains_key(&input)
+            {
                 errors.push(format!(
                     "Input {:?} for {} {:?} doesn't exist.",
                     input, output_type, name
@@ -228,7 +244,7 @@ impl Graph {
                     | (Node::Source { ty: ty1 }, Node::Transform { in_ty: ty2, .. })
                     | (Node::Transform { out_ty: ty1, .. }, Node::Transform { in_ty: ty2, .. })
                     | (Node::Transform { out_ty: ty1, .. }, Node::Sink { ty: ty2, .. }) => {
-                        if ty1 != ty2 && ty1 != DataType::Any && ty2 != DataType::Any {
+                        if !ty1.is_compatible(ty2) {
                             errors.push(format!(
                                 "Data type mismatch between {} ({:?}) and {} ({:?})",
                                 x, ty1, y, ty2
@@ -256,7 +272,10 @@ impl From<&ConfigBuilder> for Graph {
 
         // TODO: validate that node names are unique across sources/transforms/sinks?
         for (name, config) in config.sources.iter() {
-            graph.add_source(name, config.output_type());
+            graph.add_source(name, config.inner.output_type());
+            for (_, output) in config.outputs(name) {
+                graph.add_source(&output, config.inner.output_type());
+            }
         }
 
         for (name, config) in config.transforms.iter() {
@@ -416,6 +435,34 @@ mod test {
         assert_eq!(Ok(()), graph.typecheck());
     }
 
+    #[test]
+    fn allows_logs_and_traces_into_log_or_trace() {
+        let mut graph = Graph::default();
+        graph.add_source("log_source", DataType::Log);
+        graph.add_source("trace_source", DataType::Trace);
+        graph.add_source("metric_source", DataType::Metric);
+        graph.add_transform(
+            "sample",
+            DataType::LogOrTrace,
+            DataType::LogOrTrace,
+            vec!["log_source", "trace_source"],
+        );
+        graph.add_sink("log_sink", DataType::Log, vec!["sample"]);
+        assert_eq!(Ok(()), graph.typecheck());
+
+        graph.add_sink(
+            "metric_sink",
+            DataType::Metric,
+            vec!["metric_source", "sample"],
+        );
+        assert_eq!(
+            Err(vec![
+                "Data type mismatch between sample (LogOrTrace) and metric_sink (Metric)".into()
+            ]),
+            graph.typecheck()
+        );
+    }
+
     #[test]
     fn allows_any_into_log_or_metric() {
         let mut graph = Graph::default();
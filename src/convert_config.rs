@@ -0,0 +1,633 @@
+use crate::config::{format, Format, FormatHint};
+use colored::*;
+use indexmap::IndexMap;
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::Write,
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use toml::{map::Map, Value};
+
+/// Transforms that have been superseded by `remap` and can be converted to VRL.
+const CONVERTIBLE_TYPES: &[&str] = &[
+    "add_fields",
+    "rename_fields",
+    "coercer",
+    "json_parser",
+    "grok_parser",
+];
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// Vector config file to convert. Format is detected from the file name.
+    #[structopt(parse(from_os_str))]
+    path: PathBuf,
+
+    /// Treat the input config as the given format instead of detecting it
+    /// from the file name.
+    #[structopt(long, possible_values = &["toml", "json", "yaml"])]
+    format: Option<String>,
+
+    /// Write the converted config to a file instead of stdout. The converted
+    /// config is always written in TOML format.
+    #[structopt(short, long, parse(from_os_str))]
+    output: Option<PathBuf>,
+}
+
+/// A `remap` transform being assembled from one or more deprecated transforms.
+#[derive(Debug, PartialEq)]
+struct Remap {
+    inputs: Vec<String>,
+    source: Vec<String>,
+    drop_on_error: bool,
+}
+
+/// Converts the deprecated transforms of `config` into `remap` transforms,
+/// returning the converted config along with warnings for every transform that
+/// had to be left untouched.
+fn convert(mut config: Value) -> Result<(Value, Vec<String>), Vec<String>> {
+    let root = config
+        .as_table_mut()
+        .ok_or_else(|| vec!["config root must be a table".to_owned()])?;
+
+    let mut warnings = Vec::new();
+    let mut transforms = match root.remove("transforms") {
+        Some(Value::Table(transforms)) => transforms,
+        Some(_) => return Err(vec!["`transforms` must be a table".to_owned()]),
+        None => return Ok((config, warnings)),
+    };
+
+    let mut remaps = IndexMap::new();
+    for (name, transform) in &transforms {
+        let transform_type = transform.get("type").and_then(Value::as_str);
+        if !transform_type.map_or(false, |t| CONVERTIBLE_TYPES.contains(&t)) {
+            continue;
+        }
+
+        match to_remap(transform) {
+            Ok(remap) => {
+                remaps.insert(name.clone(), remap);
+            }
+            Err(error) => warnings.push(format!(
+                "transform {:?} was left unconverted: {}",
+                name, error
+            )),
+        }
+    }
+
+    // Fold chains of converted transforms into a single `remap` whenever the
+    // upstream transform has no other consumer.
+    while let Some((name, upstream)) = next_mergeable(&remaps, &transforms, root) {
+        let upstream_remap = remaps.remove(&upstream).expect("upstream is converted");
+        let remap = remaps.get_mut(&name).expect("downstream is converted");
+
+        let mut source = upstream_remap.source;
+        source.append(&mut remap.source);
+        remap.inputs = upstream_remap.inputs;
+        remap.source = source;
+        remap.drop_on_error |= upstream_remap.drop_on_error;
+        transforms.remove(&upstream);
+    }
+
+    for (name, remap) in remaps {
+        let mut table = Map::new();
+        table.insert("type".into(), "remap".into());
+        table.insert(
+            "inputs".into(),
+            Value::Array(remap.inputs.into_iter().map(Value::String).collect()),
+        );
+        table.insert("source".into(), remap.source.join("\n\n").into());
+        if remap.drop_on_error {
+            table.insert("drop_on_error".into(), true.into());
+        }
+        transforms.insert(name, Value::Table(table));
+    }
+
+    root.insert("transforms".into(), Value::Table(transforms));
+    Ok((config, warnings))
+}
+
+/// Finds a converted transform whose single input is another converted
+/// transform consumed by nothing else.
+fn next_mergeable(
+    remaps: &IndexMap<String, Remap>,
+    transforms: &Map<String, Value>,
+    root: &Map<String, Value>,
+) -> Option<(String, String)> {
+    let mut consumers = HashMap::<&str, usize>::new();
+    let unconverted_inputs = transforms
+        .iter()
+        .filter(|(name, _)| !remaps.contains_key(*name))
+        .chain(
+            root.get("sinks")
+                .and_then(Value::as_table)
+                .into_iter()
+                .flatten(),
+        )
+        .flat_map(|(_, component)| component_inputs(component));
+    let converted_inputs = remaps
+        .values()
+        .flat_map(|remap| remap.inputs.iter().map(String::as_str));
+    for input in unconverted_inputs.chain(converted_inputs) {
+        *consumers.entry(input).or_default() += 1;
+    }
+
+    remaps
+        .iter()
+        .find_map(|(name, remap)| match remap.inputs.as_slice() {
+            [upstream]
+                if upstream != name
+                    && remaps.contains_key(upstream)
+                    && consumers.get(upstream.as_str()) == Some(&1) =>
+            {
+                Some((name.clone(), upstream.clone()))
+            }
+            _ => None,
+        })
+}
+
+fn component_inputs(component: &Value) -> impl Iterator<Item = &str> {
+    component
+        .get("inputs")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+}
+
+fn to_remap(transform: &Value) -> Result<Remap, String> {
+    let inputs = component_inputs(transform).map(Into::into).collect();
+    let get_str = |key: &str| transform.get(key).and_then(Value::as_str);
+    let get_bool = |key: &str, default: bool| {
+        transform
+            .get(key)
+            .and_then(Value::as_bool)
+            .unwrap_or(default)
+    };
+
+    let mut drop_on_error = false;
+    let source = match get_str("type") {
+        Some("add_fields") => add_fields(transform.get("fields"), get_bool("overwrite", true))?,
+        Some("rename_fields") => {
+            if get_bool("drop_empty", false) {
+                return Err("`drop_empty` has no VRL equivalent".into());
+            }
+            rename_fields(transform.get("fields"))?
+        }
+        Some("coercer") => coercer(transform.get("types"), get_bool("drop_unspecified", false))?,
+        Some("json_parser") => {
+            let drop_invalid = get_bool("drop_invalid", false);
+            drop_on_error = drop_invalid;
+            json_parser(
+                get_str("field").unwrap_or("message"),
+                drop_invalid,
+                get_bool("drop_field", true),
+                get_str("target_field"),
+                get_bool("overwrite_target", false),
+            )
+        }
+        Some("grok_parser") => grok_parser(
+            get_str("pattern").ok_or("`pattern` is required")?,
+            get_str("field").unwrap_or("message"),
+            get_bool("drop_field", true),
+            transform.get("types"),
+        )?,
+        other => return Err(format!("unsupported transform type {:?}", other)),
+    };
+
+    Ok(Remap {
+        inputs,
+        source: vec![source],
+        drop_on_error,
+    })
+}
+
+fn add_fields(fields: Option<&Value>, overwrite: bool) -> Result<String, String> {
+    let mut flattened = Vec::new();
+    flatten_fields(
+        String::new(),
+        fields.ok_or("`fields` is required")?,
+        &mut flattened,
+    );
+
+    let mut lines = Vec::new();
+    for (field, value) in flattened {
+        let value = vrl_literal(value)?;
+        let path = vrl_path(&field);
+        if overwrite {
+            lines.push(format!("{} = {}", path, value));
+        } else {
+            lines.push(format!(
+                "if !exists({}) {{\n  {} = {}\n}}",
+                path, path, value
+            ));
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+fn rename_fields(fields: Option<&Value>) -> Result<String, String> {
+    let mut flattened = Vec::new();
+    flatten_fields(
+        String::new(),
+        fields.ok_or("`fields` is required")?,
+        &mut flattened,
+    );
+
+    flattened
+        .into_iter()
+        .map(|(from, to)| {
+            let to = to.as_str().ok_or("renamed field names must be strings")?;
+            Ok(format!("{} = del({})", vrl_path(to), vrl_path(&from)))
+        })
+        .collect::<Result<Vec<_>, String>>()
+        .map(|lines| lines.join("\n"))
+}
+
+fn coercer(types: Option<&Value>, drop_unspecified: bool) -> Result<String, String> {
+    let types = conversions(types)?;
+    let mut lines = types
+        .iter()
+        .map(|(field, conversion)| convert_field(field, conversion))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if drop_unspecified {
+        if types.is_empty() || types.len() > 16 {
+            return Err("`drop_unspecified` requires between 1 and 16 fields".into());
+        }
+        let paths = types
+            .iter()
+            .map(|(field, _)| vrl_path(field))
+            .collect::<Vec<_>>();
+        lines.push(format!("only_fields({})", paths.join(", ")));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+fn json_parser(
+    field: &str,
+    drop_invalid: bool,
+    drop_field: bool,
+    target_field: Option<&str>,
+    overwrite_target: bool,
+) -> String {
+    let field = vrl_path(field);
+    let mut lines = Vec::new();
+    let indent = if drop_invalid {
+        lines.push(format!("parsed = parse_json!({})", field));
+        ""
+    } else {
+        lines.push(format!("parsed, err = parse_json({})", field));
+        lines.push("if err == null {".into());
+        "  "
+    };
+
+    match target_field.map(vrl_path) {
+        Some(target) => {
+            let (guard, indent) = if overwrite_target {
+                (None, indent.to_owned())
+            } else {
+                (
+                    Some(format!("{}if !exists({}) {{", indent, target)),
+                    format!("{}  ", indent),
+                )
+            };
+            lines.extend(guard.clone());
+            if drop_field {
+                lines.push(format!("{}del({})", indent, field));
+            }
+            lines.push(format!("{}{} = parsed", indent, target));
+            if guard.is_some() {
+                lines.push(format!("{}}}", &indent[2..]));
+            }
+        }
+        None => {
+            if drop_field {
+                lines.push(format!("{}del({})", indent, field));
+            }
+            if drop_invalid {
+                lines.push(format!("{}. = merge!(., parsed)", indent));
+            } else {
+                lines.push(format!("{}. = merge(., parsed) ?? .", indent));
+            }
+        }
+    }
+
+    if !drop_invalid {
+        lines.push("}".into());
+    }
+    lines.join("\n")
+}
+
+fn grok_parser(
+    pattern: &str,
+    field: &str,
+    drop_field: bool,
+    types: Option<&Value>,
+) -> Result<String, String> {
+    let field = vrl_path(field);
+    let mut lines = vec![
+        format!(
+            "parsed, err = parse_grok({}, {})",
+            field,
+            vrl_literal(&Value::String(pattern.into()))?
+        ),
+        "if err == null {".into(),
+    ];
+    if drop_field {
+        lines.push(format!("  del({})", field));
+    }
+    lines.push("  . = merge(., parsed)".into());
+    for (field, conversion) in conversions(types)? {
+        for line in convert_field(&field, &conversion)?.lines() {
+            lines.push(format!("  {}", line));
+        }
+    }
+    lines.push("}".into());
+    Ok(lines.join("\n"))
+}
+
+fn conversions(types: Option<&Value>) -> Result<Vec<(String, String)>, String> {
+    match types {
+        None => Ok(Vec::new()),
+        Some(Value::Table(types)) => types
+            .iter()
+            .map(|(field, conversion)| {
+                conversion
+                    .as_str()
+                    .map(|conversion| (field.clone(), conversion.to_owned()))
+                    .ok_or_else(|| format!("type of field {:?} must be a string", field))
+            })
+            .collect(),
+        Some(_) => Err("`types` must be a table".into()),
+    }
+}
+
+/// Emits VRL that converts `field` in place, removing it if the conversion
+/// fails, which mirrors how the deprecated transforms treat bad values.
+fn convert_field(field: &str, conversion: &str) -> Result<String, String> {
+    let path = vrl_path(field);
+    let call = match conversion {
+        "bytes" | "string" => format!("to_string({})", path),
+        "int" | "integer" => format!("to_int({})", path),
+        "float" => format!("to_float({})", path),
+        "bool" | "boolean" => format!("to_bool({})", path),
+        "timestamp" => format!("to_timestamp({})", path),
+        conversion if conversion.starts_with("timestamp|") => format!(
+            "parse_timestamp({}, {})",
+            path,
+            vrl_literal(&Value::String(conversion["timestamp|".len()..].into()))?
+        ),
+        conversion => return Err(format!("unknown conversion {:?}", conversion)),
+    };
+
+    Ok(format!(
+        "if exists({path}) {{\n  converted, err = {call}\n  if err == null {{\n    {path} = converted\n  }} else {{\n    del({path})\n  }}\n}}",
+        path = path,
+        call = call,
+    ))
+}
+
+/// Flattens nested tables into dotted field names, the same way
+/// `crate::serde::Fields` does when these transforms are loaded.
+fn flatten_fields<'a>(prefix: String, value: &'a Value, out: &mut Vec<(String, &'a Value)>) {
+    match value {
+        Value::Table(table) => {
+            for (key, value) in table {
+                let name = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_fields(name, value, out);
+            }
+        }
+        value => out.push((prefix, value)),
+    }
+}
+
+fn vrl_path(field: &str) -> String {
+    field
+        .split('.')
+        .map(|segment| {
+            if !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                format!(".{}", segment)
+            } else {
+                format!(
+                    ".{}",
+                    serde_json::to_string(segment).expect("strings serialize")
+                )
+            }
+        })
+        .collect()
+}
+
+fn vrl_literal(value: &Value) -> Result<String, String> {
+    Ok(match value {
+        Value::String(s) if s.contains("{{") => {
+            return Err(format!("templated value {:?} has no VRL equivalent", s))
+        }
+        Value::String(s) => serde_json::to_string(s).expect("strings serialize"),
+        Value::Integer(i) => i.to_string(),
+        Value::Float(f) => format!("{:?}", f),
+        Value::Boolean(b) => b.to_string(),
+        Value::Datetime(dt) => format!("to_timestamp!({:?})", dt.to_string()),
+        Value::Array(values) => format!(
+            "[{}]",
+            values
+                .iter()
+                .map(vrl_literal)
+                .collect::<Result<Vec<_>, _>>()?
+                .join(", ")
+        ),
+        Value::Table(table) => format!(
+            "{{{}}}",
+            table
+                .iter()
+                .map(|(key, value)| Ok(format!(
+                    "{}: {}",
+                    serde_json::to_string(key).expect("strings serialize"),
+                    vrl_literal(value)?
+                )))
+                .collect::<Result<Vec<_>, String>>()?
+                .join(", ")
+        ),
+    })
+}
+
+fn load(opts: &Opts) -> Result<Value, Vec<String>> {
+    let hint: FormatHint = match opts.format.as_deref() {
+        Some("json") => Some(Format::JSON),
+        Some("yaml") => Some(Format::YAML),
+        Some(_) => Some(Format::TOML),
+        None => Format::from_path(&opts.path).ok(),
+    };
+    let content = fs::read_to_string(&opts.path)
+        .map_err(|error| vec![format!("failed to read {:?}: {}", opts.path, error)])?;
+
+    // Environment variables are deliberately left uninterpolated so the
+    // converted config stays portable.
+    format::deserialize(&content, hint)
+}
+
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let (config, warnings) = match load(opts).and_then(convert) {
+        Ok(converted) => converted,
+        Err(errs) => {
+            errs.iter().for_each(|e| eprintln!("{}", e.red()));
+            return exitcode::CONFIG;
+        }
+    };
+    warnings.iter().for_each(|w| eprintln!("{}", w.yellow()));
+
+    let output = match toml::to_string(&config) {
+        Ok(output) => output,
+        Err(error) => {
+            eprintln!("{}", format!("failed to marshal config: {}", error).red());
+            return exitcode::SOFTWARE;
+        }
+    };
+
+    match &opts.output {
+        Some(path) => {
+            if let Err(error) = File::create(path).and_then(|mut f| f.write_all(output.as_bytes()))
+            {
+                eprintln!("{}", format!("failed to write to file: {}", error).red());
+                return exitcode::IOERR;
+            }
+            println!("Converted config written to {:?}", path);
+        }
+        None => print!("{}", output),
+    }
+
+    exitcode::OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert_str(input: &str) -> (Map<String, Value>, Vec<String>) {
+        let (config, warnings) = convert(toml::from_str(input).unwrap()).unwrap();
+        match config {
+            Value::Table(mut table) => match table.remove("transforms") {
+                Some(Value::Table(transforms)) => (transforms, warnings),
+                _ => panic!("transforms missing"),
+            },
+            _ => panic!("config is not a table"),
+        }
+    }
+
+    fn source(transforms: &Map<String, Value>, name: &str) -> String {
+        transforms[name]["source"].as_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn converts_add_and_rename_fields() {
+        let (transforms, warnings) = convert_str(
+            r#"
+            [transforms.add]
+            type = "add_fields"
+            inputs = ["in"]
+            fields.foo = "bar"
+            fields.nested.count = 3
+
+            [transforms.rename]
+            type = "rename_fields"
+            inputs = ["in"]
+            fields.old = "new.field"
+            "#,
+        );
+
+        assert!(warnings.is_empty());
+        assert_eq!(transforms["add"]["type"].as_str(), Some("remap"));
+        assert_eq!(
+            source(&transforms, "add"),
+            ".foo = \"bar\"\n.nested.count = 3"
+        );
+        assert_eq!(source(&transforms, "rename"), ".new.field = del(.old)");
+    }
+
+    #[test]
+    fn merges_linear_chains() {
+        let (transforms, _) = convert_str(
+            r#"
+            [transforms.parse]
+            type = "json_parser"
+            inputs = ["in"]
+            drop_invalid = true
+
+            [transforms.add]
+            type = "add_fields"
+            inputs = ["parse"]
+            fields.foo = "bar"
+
+            [sinks.out]
+            type = "console"
+            inputs = ["add"]
+            "#,
+        );
+
+        assert!(!transforms.contains_key("parse"));
+        assert_eq!(
+            transforms["add"]["inputs"].as_array().unwrap(),
+            &vec![Value::String("in".into())]
+        );
+        assert_eq!(transforms["add"]["drop_on_error"].as_bool(), Some(true));
+        assert_eq!(
+            source(&transforms, "add"),
+            "parsed = parse_json!(.message)\ndel(.message)\n. = merge!(., parsed)\n\n.foo = \"bar\""
+        );
+    }
+
+    #[test]
+    fn keeps_shared_upstreams_separate() {
+        let (transforms, _) = convert_str(
+            r#"
+            [transforms.parse]
+            type = "json_parser"
+            inputs = ["in"]
+
+            [transforms.add]
+            type = "add_fields"
+            inputs = ["parse"]
+            fields.foo = "bar"
+
+            [sinks.out]
+            type = "console"
+            inputs = ["parse", "add"]
+            "#,
+        );
+
+        assert_eq!(transforms["parse"]["type"].as_str(), Some("remap"));
+        assert_eq!(transforms["add"]["type"].as_str(), Some("remap"));
+    }
+
+    #[test]
+    fn leaves_unconvertible_transforms() {
+        let (transforms, warnings) = convert_str(
+            r#"
+            [transforms.add]
+            type = "add_fields"
+            inputs = ["in"]
+            fields.host = "{{ hostname }}"
+            "#,
+        );
+
+        assert_eq!(transforms["add"]["type"].as_str(), Some("add_fields"));
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn quotes_unusual_path_segments() {
+        assert_eq!(vrl_path("foo.bar-baz"), ".foo.\"bar-baz\"");
+    }
+}
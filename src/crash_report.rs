@@ -0,0 +1,582 @@
+//! Crash reports for fleet-wide triage.
+//!
+//! When enabled, a panic or a fatal signal writes a JSON report to the
+//! `crash_reports` subdirectory of the `data_dir`. Every report holds the
+//! build, a fingerprint of the config, the running components and the usage of
+//! the sink buffers. Panic reports also hold the panic message, its location
+//! and a backtrace.
+//!
+//! Fatal signals (`SIGSEGV`, `SIGBUS`, `SIGILL` and `SIGFPE`) leave the process
+//! in a state where allocating or taking locks isn't safe. The report body is
+//! therefore rendered ahead of time, refreshed periodically, and the signal
+//! handler only writes it out with async-signal-safe calls before handing the
+//! signal back to the previous handler.
+//!
+//! A crashing process can't be trusted to make requests either, so reports are
+//! uploaded to the configured `endpoint` by the next Vector process instead.
+
+use crate::{
+    buffers::{self, BufferConfig},
+    config::Config,
+    http::HttpClient,
+    internal_events::{CrashReportUploadFailed, CrashReportUploaded},
+};
+use chrono::{DateTime, Utc};
+use http::{header, Request};
+use hyper::Body;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    hash::Hasher,
+    panic::{self, PanicInfo},
+    path::{Path, PathBuf},
+    sync::{Mutex, Once},
+    time::Duration,
+};
+use tokio::time::interval;
+
+const SUBDIR: &str = "crash_reports";
+const EXTENSION: &str = "json";
+const SENT_EXTENSION: &str = "sent";
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields, default)]
+pub struct CrashReportOptions {
+    pub enabled: bool,
+    /// URL reports are POSTed to by the next Vector process.
+    pub endpoint: Option<String>,
+    /// How often the report written on fatal signals is refreshed.
+    pub refresh_interval_secs: u64,
+}
+
+impl Default for CrashReportOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: None,
+            refresh_interval_secs: 10,
+        }
+    }
+}
+
+impl CrashReportOptions {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn merge(&mut self, other: Self) -> Result<(), String> {
+        if other.is_default() {
+            return Ok(());
+        }
+        if !self.is_default() && *self != other {
+            return Err("conflicting values for 'crash_report' found".to_owned());
+        }
+        *self = other;
+        Ok(())
+    }
+}
+
+/// What a report knows about the process, independent of the crash.
+#[derive(Serialize, Debug, Clone)]
+struct Context {
+    version: String,
+    target: &'static str,
+    pid: u32,
+    started_at: DateTime<Utc>,
+    config_fingerprint: String,
+    components: Vec<Component>,
+    buffers: BTreeMap<String, BufferStats>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+struct Component {
+    name: String,
+    kind: &'static str,
+    #[serde(rename = "type")]
+    component_type: &'static str,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+struct BufferStats {
+    config: BufferConfig,
+    events: usize,
+    bytes: Option<usize>,
+    unacked_events: usize,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "snake_case")]
+enum Reason {
+    Panic {
+        message: String,
+        location: Option<String>,
+        thread: Option<String>,
+        backtrace: String,
+    },
+}
+
+#[derive(Serialize, Debug)]
+struct Report<'a> {
+    reason: Reason,
+    crashed_at: DateTime<Utc>,
+    #[serde(flatten)]
+    context: &'a Context,
+}
+
+struct State {
+    dir: PathBuf,
+    context: Context,
+}
+
+static STATE: Lazy<Mutex<Option<State>>> = Lazy::new(|| Mutex::new(None));
+static INSTALL: Once = Once::new();
+
+impl Context {
+    fn new(config: &Config) -> Self {
+        let components = config
+            .sources
+            .iter()
+            .map(|(name, source)| Component {
+                name: name.clone(),
+                kind: "source",
+                component_type: source.inner.source_type(),
+            })
+            .chain(config.transforms.iter().map(|(name, transform)| Component {
+                name: name.clone(),
+                kind: "transform",
+                component_type: transform.inner.transform_type(),
+            }))
+            .chain(config.sinks.iter().map(|(name, sink)| Component {
+                name: name.clone(),
+                kind: "sink",
+                component_type: sink.inner.sink_type(),
+            }))
+            .collect();
+
+        let mut context = Self {
+            version: crate::get_version(),
+            target: crate::built_info::TARGET,
+            pid: std::process::id(),
+            started_at: Utc::now(),
+            config_fingerprint: fingerprint(config),
+            components,
+            buffers: BTreeMap::new(),
+        };
+        context.refresh_buffers();
+        context
+    }
+
+    fn refresh_buffers(&mut self) {
+        self.buffers = self
+            .components
+            .iter()
+            .filter(|component| component.kind == "sink")
+            .filter_map(|component| {
+                let usage = buffers::usage(&component.name)?;
+                let stats = BufferStats {
+                    config: usage.config().clone(),
+                    events: usage.events(),
+                    bytes: usage.bytes(),
+                    unacked_events: usage.unacked_events(),
+                };
+                Some((component.name.clone(), stats))
+            })
+            .collect();
+    }
+}
+
+/// A fingerprint of the loaded config, to tell which instances run the same
+/// one. It's stable between instances of the same Vector version.
+fn fingerprint(config: &Config) -> String {
+    let serialized = serde_json::to_vec(&(
+        &config.global,
+        &config.sources,
+        &config.transforms,
+        &config.sinks,
+    ))
+    .unwrap_or_default();
+
+    let mut hasher = DefaultHasher::new();
+    hasher.write(&serialized);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Enables or disables crash reports for `config`, to be called whenever a
+/// config is loaded.
+pub fn init(config: &Config) -> crate::Result<()> {
+    let options = &config.global.crash_report;
+    let state = if options.enabled {
+        let dir = config.global.resolve_and_make_data_subdir(None, SUBDIR)?;
+        INSTALL.call_once(install);
+        Some(State {
+            dir,
+            context: Context::new(config),
+        })
+    } else {
+        None
+    };
+
+    #[cfg(unix)]
+    signal::prepare(state.as_ref());
+    *STATE.lock().expect("crash report lock poisoned") = state;
+    Ok(())
+}
+
+fn install() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        write_panic_report(info);
+        previous(info);
+    }));
+
+    #[cfg(unix)]
+    signal::install();
+}
+
+fn write_panic_report(info: &PanicInfo<'_>) {
+    // The panic may have happened while the lock was held.
+    let state = match STATE.try_lock() {
+        Ok(state) => state,
+        Err(_) => return,
+    };
+    let state = match state.as_ref() {
+        Some(state) => state,
+        None => return,
+    };
+
+    let message = match info.payload().downcast_ref::<&str>() {
+        Some(message) => (*message).to_owned(),
+        None => match info.payload().downcast_ref::<String>() {
+            Some(message) => message.clone(),
+            None => "Box<Any>".to_owned(),
+        },
+    };
+    let report = Report {
+        reason: Reason::Panic {
+            message,
+            location: info.location().map(ToString::to_string),
+            thread: std::thread::current().name().map(Into::into),
+            backtrace: format!("{:?}", backtrace::Backtrace::new()),
+        },
+        crashed_at: Utc::now(),
+        context: &state.context,
+    };
+
+    let path = state.dir.join(format!(
+        "crash-{}-panic.{}",
+        report.crashed_at.timestamp_nanos(),
+        EXTENSION
+    ));
+    if let Ok(report) = serde_json::to_vec_pretty(&report) {
+        if std::fs::write(&path, report).is_ok() {
+            eprintln!("Crash report written to {:?}.", path);
+        }
+    }
+}
+
+/// Uploads the reports of previous crashes, then keeps the report written on
+/// fatal signals up to date with the buffer usage.
+pub async fn run(options: CrashReportOptions) {
+    if let (true, Some(endpoint)) = (options.enabled, &options.endpoint) {
+        let dir = STATE
+            .lock()
+            .expect("crash report lock poisoned")
+            .as_ref()
+            .map(|state| state.dir.clone());
+        if let Some(dir) = dir {
+            upload_pending(endpoint, &dir).await;
+        }
+    }
+
+    let mut interval = interval(Duration::from_secs(options.refresh_interval_secs.max(1)));
+    loop {
+        interval.tick().await;
+
+        // Crash reports may have been enabled or disabled by a reload since.
+        let mut state = STATE.lock().expect("crash report lock poisoned");
+        if let Some(state) = state.as_mut() {
+            state.context.refresh_buffers();
+            #[cfg(unix)]
+            signal::prepare(Some(state));
+        }
+    }
+}
+
+/// POSTs every report of `dir` that hasn't been sent yet to `endpoint`,
+/// renaming them once sent.
+async fn upload_pending(endpoint: &str, dir: &Path) {
+    let client = match HttpClient::new(None) {
+        Ok(client) => client,
+        Err(error) => {
+            emit!(CrashReportUploadFailed {
+                path: dir,
+                error: error.to_string(),
+            });
+            return;
+        }
+    };
+
+    for path in pending_reports(dir) {
+        let result = async {
+            let report = tokio::fs::read(&path).await?;
+            let request = Request::post(endpoint)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(report))?;
+            let response = client.send(request).await?;
+            if !response.status().is_success() {
+                return Err(format!("Unexpected status: {}", response.status()).into());
+            }
+            tokio::fs::rename(&path, path.with_extension(SENT_EXTENSION)).await?;
+            Ok::<_, crate::Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => emit!(CrashReportUploaded { path: &path }),
+            Err(error) => emit!(CrashReportUploadFailed {
+                path: &path,
+                error: error.to_string(),
+            }),
+        }
+    }
+}
+
+fn pending_reports(dir: &Path) -> Vec<PathBuf> {
+    let mut reports = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().map_or(false, |ext| ext == EXTENSION))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    reports.sort();
+    reports
+}
+
+#[cfg(unix)]
+mod signal {
+    //! Reports of fatal signals, written from the signal handler.
+
+    use super::{State, EXTENSION};
+    use once_cell::sync::Lazy;
+    use std::{
+        ffi::CString,
+        mem::MaybeUninit,
+        os::unix::ffi::OsStrExt,
+        ptr,
+        sync::{
+            atomic::{AtomicPtr, AtomicUsize, Ordering},
+            Mutex,
+        },
+    };
+
+    const SIGNALS: [libc::c_int; 4] = [libc::SIGSEGV, libc::SIGBUS, libc::SIGILL, libc::SIGFPE];
+
+    /// Everything the handler writes, rendered ahead of time.
+    struct Prepared {
+        /// The report path up to the crash time, `<dir>/crash-`.
+        path_prefix: Vec<u8>,
+        /// The report following the signal number and crash time, starting
+        /// with the comma separating them from the context.
+        body: Vec<u8>,
+    }
+
+    static PREPARED: AtomicPtr<Prepared> = AtomicPtr::new(ptr::null_mut());
+    /// The number of handlers running, each of which may be reading a report.
+    static HANDLING: AtomicUsize = AtomicUsize::new(0);
+    /// The replaced reports, only freed once no handler is running as one may
+    /// have loaded them before they were replaced.
+    static RETIRED: Lazy<Mutex<Vec<Box<Prepared>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+    static mut PREVIOUS: [MaybeUninit<libc::sigaction>; 4] = [MaybeUninit::uninit(); 4];
+
+    /// Replaces the report written on fatal signals.
+    pub(super) fn prepare(state: Option<&State>) {
+        let prepared = state.and_then(|state| {
+            let context = serde_json::to_vec(&state.context).ok()?;
+            let path_prefix = state.dir.join("crash-").as_os_str().as_bytes().to_vec();
+            // The handler can't deal with paths it can't pass to `open`.
+            CString::new(path_prefix.clone()).ok()?;
+
+            // `context` is a JSON object, whose fields are spliced in.
+            let mut body = b",".to_vec();
+            body.extend_from_slice(&context[1..]);
+            body.push(b'\n');
+            Some(Box::into_raw(Box::new(Prepared { path_prefix, body })))
+        });
+
+        let replaced = PREPARED.swap(prepared.unwrap_or(ptr::null_mut()), Ordering::SeqCst);
+        let mut retired = RETIRED.lock().expect("crash report lock poisoned");
+        if !replaced.is_null() {
+            // Safety: `replaced` came from `Box::into_raw` and was just unpublished.
+            retired.push(unsafe { Box::from_raw(replaced) });
+        }
+        // Handlers count themselves before loading the report, so the ones
+        // starting from now on can only load the report just published.
+        if HANDLING.load(Ordering::SeqCst) == 0 {
+            retired.clear();
+        }
+    }
+
+    pub(super) fn install() {
+        for (signal, previous) in SIGNALS.iter().zip(unsafe { PREVIOUS.iter_mut() }) {
+            unsafe {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = handler as usize;
+                action.sa_flags = libc::SA_SIGINFO | libc::SA_ONSTACK;
+                libc::sigemptyset(&mut action.sa_mask);
+                libc::sigaction(*signal, &action, previous.as_mut_ptr());
+            }
+        }
+    }
+
+    extern "C" fn handler(
+        signal: libc::c_int,
+        _info: *mut libc::siginfo_t,
+        _context: *mut libc::c_void,
+    ) {
+        HANDLING.fetch_add(1, Ordering::SeqCst);
+        let prepared = PREPARED.load(Ordering::SeqCst);
+        if !prepared.is_null() {
+            // Safety: reports are only freed while no handler is running.
+            write_report(signal, unsafe { &*prepared });
+        }
+        HANDLING.fetch_sub(1, Ordering::SeqCst);
+
+        // Hand the signal back to the previous handler, which gets it again
+        // as soon as the faulting instruction is retried.
+        if let Some(index) = SIGNALS.iter().position(|s| *s == signal) {
+            unsafe {
+                libc::sigaction(signal, PREVIOUS[index].as_ptr(), ptr::null_mut());
+            }
+        }
+    }
+
+    /// Writes the report with async-signal-safe calls only, into buffers on
+    /// the stack.
+    fn write_report(signal: libc::c_int, prepared: &Prepared) {
+        let now = unsafe { libc::time(ptr::null_mut()) } as u64;
+
+        let mut path = [0u8; 4096];
+        let mut len = 0;
+        for part in &[
+            &prepared.path_prefix[..],
+            format_decimal(now, &mut [0; 20]),
+            b"-signal.",
+            EXTENSION.as_bytes(),
+        ] {
+            if len + part.len() >= path.len() {
+                return;
+            }
+            path[len..len + part.len()].copy_from_slice(part);
+            len += part.len();
+        }
+        // `path` is NUL terminated by its zeroed tail.
+
+        let fd = unsafe {
+            libc::open(
+                path.as_ptr() as *const libc::c_char,
+                libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC,
+                0o600,
+            )
+        };
+        if fd < 0 {
+            return;
+        }
+        for part in &[
+            &b"{\"reason\":{\"signal\":"[..],
+            format_decimal(signal as u64, &mut [0; 20]),
+            b"},\"crashed_at\":",
+            format_decimal(now, &mut [0; 20]),
+            &prepared.body[..],
+        ] {
+            write_all(fd, part);
+        }
+        unsafe {
+            libc::close(fd);
+        }
+    }
+
+    fn write_all(fd: libc::c_int, mut bytes: &[u8]) {
+        while !bytes.is_empty() {
+            let written =
+                unsafe { libc::write(fd, bytes.as_ptr() as *const libc::c_void, bytes.len()) };
+            if written <= 0 {
+                return;
+            }
+            bytes = &bytes[written as usize..];
+        }
+    }
+
+    /// Formats `n` into the end of `buf` without allocating.
+    fn format_decimal(mut n: u64, buf: &mut [u8; 20]) -> &[u8] {
+        let mut start = buf.len();
+        loop {
+            start -= 1;
+            buf[start] = b'0' + (n % 10) as u8;
+            n /= 10;
+            if n == 0 {
+                return &buf[start..];
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn formats_decimals() {
+            assert_eq!(format_decimal(0, &mut [0; 20]), b"0");
+            assert_eq!(format_decimal(1612345678, &mut [0; 20]), b"1612345678");
+            assert_eq!(
+                format_decimal(u64::MAX, &mut [0; 20]),
+                b"18446744073709551615"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_options() {
+        let mut options = CrashReportOptions::default();
+        let enabled = CrashReportOptions {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(options.merge(CrashReportOptions::default()).is_ok());
+        assert!(options.merge(enabled.clone()).is_ok());
+        assert_eq!(options, enabled);
+        assert!(options
+            .merge(CrashReportOptions {
+                endpoint: Some("http://localhost".into()),
+                ..Default::default()
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn lists_pending_reports() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in &[
+            "crash-2-panic.json",
+            "crash-1-signal.json",
+            "crash-0-panic.sent",
+        ] {
+            std::fs::write(dir.path().join(name), "{}").unwrap();
+        }
+
+        assert_eq!(
+            pending_reports(dir.path()),
+            vec![
+                dir.path().join("crash-1-signal.json"),
+                dir.path().join("crash-2-panic.json"),
+            ]
+        );
+    }
+}
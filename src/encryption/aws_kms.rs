@@ -0,0 +1,50 @@
+use crate::rusoto::{self, AWSAuthentication, RegionOrEndpoint};
+use rusoto_core::RusotoError;
+use rusoto_kms::{DecryptError, DecryptRequest, Kms, KmsClient};
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt, Snafu};
+use std::convert::TryInto;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct AwsKmsKeyConfig {
+    /// The base64 encoded `CiphertextBlob` of a data key, as returned by
+    /// `GenerateDataKey`.
+    pub encrypted_data_key: String,
+    #[serde(flatten)]
+    pub region: RegionOrEndpoint,
+    #[serde(default)]
+    pub auth: AWSAuthentication,
+}
+
+#[derive(Debug, Snafu)]
+enum AwsKmsKeyError {
+    #[snafu(display("`encrypted_data_key` is not base64 encoded: {}", source))]
+    Decode { source: base64::DecodeError },
+    #[snafu(display("Could not decrypt data key: {}", source))]
+    Decrypt { source: RusotoError<DecryptError> },
+    #[snafu(display("KMS returned no plaintext for the data key"))]
+    MissingPlaintext,
+}
+
+impl AwsKmsKeyConfig {
+    /// Decrypts the data key with KMS, so only the encrypted copy of it has to
+    /// be stored.
+    pub(super) async fn data_key(&self) -> crate::Result<Vec<u8>> {
+        let ciphertext_blob = base64::decode(&self.encrypted_data_key).context(Decode)?;
+
+        let region = (&self.region).try_into()?;
+        let client = rusoto::client()?;
+        let creds = self.auth.build(&region, None)?;
+        let client = KmsClient::new_with(client, creds, region);
+
+        let response = client
+            .decrypt(DecryptRequest {
+                ciphertext_blob: ciphertext_blob.into(),
+                ..Default::default()
+            })
+            .await
+            .context(Decrypt)?;
+
+        Ok(response.plaintext.context(MissingPlaintext)?.to_vec())
+    }
+}
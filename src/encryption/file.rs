@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::path::PathBuf;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FileKeyConfig {
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Snafu)]
+enum FileKeyError {
+    #[snafu(display("Could not read key file {:?}: {}", path, source))]
+    ReadFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Key file {:?} is not base64 encoded: {}", path, source))]
+    Decode {
+        path: PathBuf,
+        source: base64::DecodeError,
+    },
+}
+
+impl FileKeyConfig {
+    pub(super) async fn data_key(&self) -> crate::Result<Vec<u8>> {
+        let contents = tokio::fs::read_to_string(&self.path)
+            .await
+            .context(ReadFile { path: &self.path })?;
+
+        Ok(base64::decode(contents.trim()).context(Decode { path: &self.path })?)
+    }
+}
@@ -0,0 +1,94 @@
+//! Data keys for the `encrypt_field` and `decrypt_field` VRL functions.
+//!
+//! The keys are configured by id on the components running VRL and resolved
+//! once when the component is built. A key is either read from a local file
+//! or, for envelope encryption with a KMS, decrypted from a data key that was
+//! encrypted under a master key which never leaves the KMS.
+
+#[cfg(feature = "encryption-aws_kms")]
+mod aws_kms;
+mod file;
+
+#[cfg(feature = "encryption-aws_kms")]
+pub use self::aws_kms::AwsKmsKeyConfig;
+pub use self::file::FileKeyConfig;
+
+use remap_functions::Keyring;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum KeyProviderConfig {
+    /// A key stored base64 encoded in a local file.
+    File(FileKeyConfig),
+    /// A data key encrypted with an AWS KMS key.
+    #[cfg(feature = "encryption-aws_kms")]
+    AwsKms(AwsKmsKeyConfig),
+}
+
+impl KeyProviderConfig {
+    async fn data_key(&self) -> crate::Result<Vec<u8>> {
+        match self {
+            KeyProviderConfig::File(config) => config.data_key().await,
+            #[cfg(feature = "encryption-aws_kms")]
+            KeyProviderConfig::AwsKms(config) => config.data_key().await,
+        }
+    }
+}
+
+/// Resolves the data keys of `keys`, by id.
+pub async fn build_keyring(keys: &BTreeMap<String, KeyProviderConfig>) -> crate::Result<Keyring> {
+    let mut data_keys = BTreeMap::new();
+    for (key_id, provider) in keys {
+        let data_key = provider
+            .data_key()
+            .await
+            .map_err(|error| format!("Could not load data key {:?}: {}", key_id, error))?;
+        data_keys.insert(key_id.clone(), data_key);
+    }
+
+    Keyring::new(data_keys).map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[tokio::test]
+    async fn builds_keyring_from_files() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "{}", base64::encode(&[7; 32])).unwrap();
+
+        let keys: BTreeMap<String, KeyProviderConfig> = toml::from_str(&format!(
+            r#"
+            primary.provider = "file"
+            primary.path = "{}"
+            "#,
+            file.path().display()
+        ))
+        .unwrap();
+
+        assert!(build_keyring(&keys).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn rejects_short_keys() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "{}", base64::encode(&[7; 16])).unwrap();
+
+        let mut keys = BTreeMap::new();
+        keys.insert(
+            "primary".to_owned(),
+            KeyProviderConfig::File(FileKeyConfig {
+                path: file.path().into(),
+            }),
+        );
+
+        assert_eq!(
+            build_keyring(&keys).await.unwrap_err().to_string(),
+            "data key \"primary\" must be 32 bytes long, got 16"
+        );
+    }
+}
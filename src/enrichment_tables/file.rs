@@ -0,0 +1,60 @@
+use remap_functions::Table;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::path::PathBuf;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct FileTableConfig {
+    pub path: PathBuf,
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+#[derive(Debug, Snafu)]
+enum FileTableError {
+    #[snafu(display("The delimiter {:?} is not an ASCII character", delimiter))]
+    Delimiter { delimiter: char },
+    #[snafu(display("Could not read CSV file {:?}: {}", path, source))]
+    ReadFile { path: PathBuf, source: csv::Error },
+    #[snafu(display("Invalid CSV file {:?}: {}", path, message))]
+    Invalid { path: PathBuf, message: String },
+}
+
+impl FileTableConfig {
+    pub(super) fn load(&self) -> crate::Result<Table> {
+        if !self.delimiter.is_ascii() {
+            return Err(Box::new(FileTableError::Delimiter {
+                delimiter: self.delimiter,
+            }));
+        }
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(self.delimiter as u8)
+            .from_path(&self.path)
+            .context(ReadFile { path: &self.path })?;
+
+        let columns = reader
+            .headers()
+            .context(ReadFile { path: &self.path })?
+            .iter()
+            .map(str::to_owned)
+            .collect();
+        let rows = reader
+            .records()
+            .map(|record| record.map(|record| record.iter().map(str::to_owned).collect()))
+            .collect::<Result<Vec<_>, _>>()
+            .context(ReadFile { path: &self.path })?;
+
+        Ok(
+            Table::new(columns, rows).map_err(|message| FileTableError::Invalid {
+                path: self.path.clone(),
+                message,
+            })?,
+        )
+    }
+}
@@ -0,0 +1,99 @@
+//! Enrichment tables for the `get_enrichment_table_record` and
+//! `find_enrichment_table_records` VRL functions.
+//!
+//! The tables are configured by name in the `enrichment_tables` section, and
+//! loaded in memory once when the config is built. Every column of a table is
+//! indexed, so that looking records up doesn't scan it.
+
+mod file;
+
+pub use self::file::FileTableConfig;
+
+use indexmap::IndexMap;
+use remap_functions::{EnrichmentTables, Table};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EnrichmentTableConfig {
+    /// A CSV file, with the names of the columns on its first line.
+    File(FileTableConfig),
+}
+
+impl EnrichmentTableConfig {
+    fn load(&self) -> crate::Result<Table> {
+        match self {
+            EnrichmentTableConfig::File(config) => config.load(),
+        }
+    }
+}
+
+/// Loads the tables of `tables`, by name.
+pub fn load(
+    tables: &IndexMap<String, EnrichmentTableConfig>,
+) -> Result<EnrichmentTables, Vec<String>> {
+    let mut loaded = BTreeMap::new();
+    let mut errors = Vec::new();
+    for (name, config) in tables {
+        match config.load() {
+            Ok(table) => {
+                debug!(message = "Loaded enrichment table.", %name, records = table.len());
+                loaded.insert(name.clone(), table);
+            }
+            Err(error) => errors.push(format!(
+                "Could not load enrichment table {:?}: {}",
+                name, error
+            )),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(EnrichmentTables::new(loaded))
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn tables(
+        contents: &str,
+    ) -> (
+        tempfile::NamedTempFile,
+        IndexMap<String, EnrichmentTableConfig>,
+    ) {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "{}", contents).unwrap();
+
+        let tables = toml::from_str(&format!(
+            r#"
+            codes.type = "file"
+            codes.path = "{}"
+            "#,
+            file.path().display()
+        ))
+        .unwrap();
+
+        (file, tables)
+    }
+
+    #[test]
+    fn loads_csv_files() {
+        let (_file, tables) = tables("code,message\n404,Not Found\n500,Internal Server Error\n");
+
+        assert!(load(&tables).is_ok());
+    }
+
+    #[test]
+    fn rejects_ragged_csv_files() {
+        let (_file, tables) = tables("code,message\n404\n");
+
+        let errors = load(&tables).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("Could not load enrichment table \"codes\""));
+    }
+}
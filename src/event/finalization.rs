@@ -0,0 +1,268 @@
+//! Delivery tracking of events, from the sources that want to know when their
+//! events were delivered, to the sinks that deliver them.
+//!
+//! A source creates a `BatchNotifier` for the events whose delivery it waits
+//! for, and attaches an `EventFinalizer` of it to each event. The finalizers
+//! are shared by the copies of an event sent to several sinks, which update
+//! their status. Once every finalizer of a batch is dropped, the status of the
+//! batch is sent to the source.
+
+use futures::{future::ready, FutureExt, Stream, StreamExt};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, oneshot};
+
+/// The status of an event, as reported by the sinks it was sent to.
+///
+/// The variants are ordered from the best to the worst outcome, and an event
+/// sent to several sinks gets the worst of their statuses.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum EventStatus {
+    /// The event was dropped without being delivered, e.g. by a transform, or
+    /// by a sink not acknowledging its events.
+    Dropped,
+    /// The event was delivered by its sinks.
+    Delivered,
+    /// The event failed to be delivered, but could be retried.
+    Errored,
+    /// The event failed to be delivered, and retrying it would fail again.
+    Failed,
+}
+
+impl EventStatus {
+    pub fn update(self, status: Self) -> Self {
+        self.max(status)
+    }
+}
+
+/// The status of a batch of events, once all of them were finalized.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BatchStatus {
+    /// All the events of the batch were delivered or dropped.
+    Delivered,
+    /// At least one event of the batch errored, and none failed.
+    Errored,
+    /// At least one event of the batch failed.
+    Failed,
+}
+
+impl BatchStatus {
+    fn update(self, status: EventStatus) -> Self {
+        match (self, status) {
+            (_, EventStatus::Dropped) | (_, EventStatus::Delivered) => self,
+            (BatchStatus::Failed, _) | (_, EventStatus::Failed) => BatchStatus::Failed,
+            (_, EventStatus::Errored) => BatchStatus::Errored,
+        }
+    }
+}
+
+pub type BatchStatusReceiver = oneshot::Receiver<BatchStatus>;
+
+/// Sends the status of a batch of events to its source when dropped, which
+/// happens once all the finalizers of the batch are dropped.
+#[derive(Debug)]
+pub struct BatchNotifier {
+    status: Mutex<BatchStatus>,
+    notifier: Option<oneshot::Sender<BatchStatus>>,
+}
+
+impl BatchNotifier {
+    pub fn new_with_receiver() -> (Arc<Self>, BatchStatusReceiver) {
+        let (sender, receiver) = oneshot::channel();
+        let notifier = Self {
+            status: Mutex::new(BatchStatus::Delivered),
+            notifier: Some(sender),
+        };
+        (Arc::new(notifier), receiver)
+    }
+
+    fn update_status(&self, status: EventStatus) {
+        let mut batch_status = self.status.lock().unwrap();
+        *batch_status = batch_status.update(status);
+    }
+}
+
+impl Drop for BatchNotifier {
+    fn drop(&mut self) {
+        if let Some(notifier) = self.notifier.take() {
+            let status = *self.status.lock().unwrap();
+            // The source may not be waiting for the batch anymore.
+            let _ = notifier.send(status);
+        }
+    }
+}
+
+/// Reports the status of an event to its batch when dropped.
+#[derive(Debug)]
+pub struct EventFinalizer {
+    status: Mutex<EventStatus>,
+    batch: Arc<BatchNotifier>,
+}
+
+impl EventFinalizer {
+    pub fn new(batch: Arc<BatchNotifier>) -> Self {
+        Self {
+            status: Mutex::new(EventStatus::Dropped),
+            batch,
+        }
+    }
+
+    pub fn update_status(&self, status: EventStatus) {
+        let mut event_status = self.status.lock().unwrap();
+        *event_status = event_status.update(status);
+    }
+}
+
+impl Drop for EventFinalizer {
+    fn drop(&mut self) {
+        let status = *self.status.lock().unwrap();
+        self.batch.update_status(status);
+    }
+}
+
+/// The finalizers of an event, more than one once events are merged.
+///
+/// Finalizers don't take part in the equality of events.
+#[derive(Clone, Debug, Default)]
+pub struct EventFinalizers(Vec<Arc<EventFinalizer>>);
+
+impl PartialEq for EventFinalizers {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl EventFinalizers {
+    pub fn new(finalizer: EventFinalizer) -> Self {
+        Self(vec![Arc::new(finalizer)])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn add(&mut self, finalizer: EventFinalizer) {
+        self.0.push(Arc::new(finalizer));
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    pub fn update_status(&self, status: EventStatus) {
+        for finalizer in &self.0 {
+            finalizer.update_status(status);
+        }
+    }
+}
+
+/// Hands the status of the batches of a source to `handler`, in the order the
+/// batches were added, so that a source can only commit what precedes the
+/// batches delivered.
+pub struct OrderedFinalizer<T> {
+    sender: mpsc::UnboundedSender<(BatchStatusReceiver, T)>,
+}
+
+impl<T: Send + 'static> OrderedFinalizer<T> {
+    /// Spawns the task waiting for the batches, which ends once the finalizer
+    /// is dropped and the batches added before were finalized.
+    pub fn new(handler: impl FnMut(BatchStatus, T) + Send + 'static) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(finalize_in_order(receiver, handler));
+        Self { sender }
+    }
+
+    pub fn add(&self, entry: T, receiver: BatchStatusReceiver) {
+        // The task only ends once this sender is dropped.
+        let _ = self.sender.send((receiver, entry));
+    }
+}
+
+async fn finalize_in_order<T>(
+    entries: impl Stream<Item = (BatchStatusReceiver, T)>,
+    mut handler: impl FnMut(BatchStatus, T),
+) {
+    entries
+        .map(|(receiver, entry)| {
+            // The notifier always sends a status, unless it was leaked.
+            receiver.map(|status| (status.unwrap_or(BatchStatus::Failed), entry))
+        })
+        // Waits for any number of batches, as the events are already bounded
+        // by the buffers of the topology.
+        .buffered(usize::MAX)
+        .for_each(|(status, entry)| {
+            handler(status, entry);
+            ready(())
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::oneshot::error::TryRecvError;
+
+    #[test]
+    fn batch_delivered_once_finalized() {
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let first = EventFinalizers::new(EventFinalizer::new(Arc::clone(&batch)));
+        let second = EventFinalizers::new(EventFinalizer::new(batch));
+
+        first.update_status(EventStatus::Delivered);
+        drop(first);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+
+        // Copies of an event share its finalizer.
+        let copy = second.clone();
+        drop(second);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+        drop(copy);
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+    }
+
+    #[test]
+    fn batch_gets_worst_status() {
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let finalizers = vec![
+            EventFinalizers::new(EventFinalizer::new(Arc::clone(&batch))),
+            EventFinalizers::new(EventFinalizer::new(Arc::clone(&batch))),
+            EventFinalizers::new(EventFinalizer::new(batch)),
+        ];
+
+        finalizers[0].update_status(EventStatus::Delivered);
+        finalizers[1].update_status(EventStatus::Failed);
+        finalizers[1].update_status(EventStatus::Delivered);
+        finalizers[2].update_status(EventStatus::Errored);
+        drop(finalizers);
+
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Failed));
+    }
+
+    #[tokio::test]
+    async fn ordered_finalizer_waits_for_previous_batches() {
+        let (sender, mut finalized) = mpsc::unbounded_channel();
+        let finalizer = OrderedFinalizer::new(move |status, entry| {
+            sender.send((entry, status)).unwrap();
+        });
+
+        let (first, first_receiver) = BatchNotifier::new_with_receiver();
+        let (second, second_receiver) = BatchNotifier::new_with_receiver();
+        finalizer.add(1, first_receiver);
+        finalizer.add(2, second_receiver);
+        drop(finalizer);
+
+        let finalizers = EventFinalizers::new(EventFinalizer::new(second));
+        finalizers.update_status(EventStatus::Errored);
+        drop(finalizers);
+        tokio::task::yield_now().await;
+        assert!(finalized.try_recv().is_err());
+
+        drop(first);
+        assert_eq!(finalized.recv().await, Some((1, BatchStatus::Delivered)));
+        assert_eq!(finalized.recv().await, Some((2, BatchStatus::Errored)));
+        assert_eq!(finalized.recv().await, None);
+    }
+}
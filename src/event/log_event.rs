@@ -1,4 +1,8 @@
-use crate::event::{lookup::Segment, util, Lookup, PathComponent, Value};
+use crate::event::{
+    finalization::{BatchNotifier, EventFinalizer, EventFinalizers},
+    lookup::Segment,
+    util, EventMetadata, Lookup, PathComponent, Value,
+};
 use remap::{Object, Path};
 use serde::{Serialize, Serializer};
 use std::{
@@ -6,11 +10,14 @@ use std::{
     convert::{TryFrom, TryInto},
     fmt::{Debug, Display},
     iter::FromIterator,
+    sync::Arc,
 };
 
 #[derive(PartialEq, Debug, Clone, Default)]
 pub struct LogEvent {
     fields: BTreeMap<String, Value>,
+    finalizers: EventFinalizers,
+    metadata: EventMetadata,
 }
 
 impl LogEvent {
@@ -98,6 +105,29 @@ impl LogEvent {
         &self.fields
     }
 
+    pub fn add_finalizer(&mut self, finalizer: EventFinalizer) {
+        self.finalizers.add(finalizer);
+    }
+
+    /// Adds a finalizer of `batch`, whose status is then only sent once this
+    /// event is finalized.
+    pub fn with_batch_notifier(mut self, batch: &Arc<BatchNotifier>) -> Self {
+        self.add_finalizer(EventFinalizer::new(Arc::clone(batch)));
+        self
+    }
+
+    pub fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+
+    pub fn metadata(&self) -> &EventMetadata {
+        &self.metadata
+    }
+
+    pub fn metadata_mut(&mut self) -> &mut EventMetadata {
+        &mut self.metadata
+    }
+
     #[instrument(level = "trace", skip(self, lookup), fields(lookup = %lookup), err)]
     fn entry(&mut self, lookup: Lookup) -> crate::Result<Entry<String, Value>> {
         trace!("Seeking to entry.");
@@ -140,13 +170,17 @@ impl LogEvent {
 
 impl From<BTreeMap<String, Value>> for LogEvent {
     fn from(map: BTreeMap<String, Value>) -> Self {
-        LogEvent { fields: map }
+        LogEvent {
+            fields: map,
+            finalizers: Default::default(),
+            metadata: Default::default(),
+        }
     }
 }
 
 impl Into<BTreeMap<String, Value>> for LogEvent {
     fn into(self) -> BTreeMap<String, Value> {
-        let Self { fields } = self;
+        let Self { fields, .. } = self;
         fields
     }
 }
@@ -155,6 +189,8 @@ impl From<HashMap<String, Value>> for LogEvent {
     fn from(map: HashMap<String, Value>) -> Self {
         LogEvent {
             fields: map.into_iter().collect(),
+            finalizers: Default::default(),
+            metadata: Default::default(),
         }
     }
 }
@@ -311,6 +347,14 @@ impl Object for LogEvent {
 
         Ok(())
     }
+
+    fn get_metadata(&self, key: &str) -> Result<Option<remap::Value>, String> {
+        self.metadata.get_field(key)
+    }
+
+    fn set_metadata(&mut self, key: &str, value: remap::Value) -> Result<(), String> {
+        self.metadata.set_field(key, value)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,58 @@
+use crate::buffers::Priority;
+use std::sync::Arc;
+
+/// The data attached to an event by its source for the sinks, which isn't
+/// part of the event itself and so is never encoded by the sinks.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EventMetadata {
+    /// The API key of the Datadog Agent that sent the event, which the
+    /// Datadog sinks use instead of their own.
+    datadog_api_key: Option<Arc<str>>,
+    /// The priority class of the event, which the buffers dropping events by
+    /// priority read.
+    priority: Option<Priority>,
+}
+
+impl EventMetadata {
+    pub fn datadog_api_key(&self) -> Option<&Arc<str>> {
+        self.datadog_api_key.as_ref()
+    }
+
+    pub fn set_datadog_api_key(&mut self, api_key: Option<Arc<str>>) {
+        self.datadog_api_key = api_key;
+    }
+
+    pub fn priority(&self) -> Option<Priority> {
+        self.priority
+    }
+
+    pub fn set_priority(&mut self, priority: Option<Priority>) {
+        self.priority = priority;
+    }
+
+    /// The metadata field `key` as read by the `get_metadata_field` function
+    /// of VRL. Only `priority` is available to programs.
+    pub fn get_field(&self, key: &str) -> Result<Option<remap::Value>, String> {
+        match key {
+            "priority" => Ok(self.priority.map(|priority| priority.as_str().into())),
+            _ => Err(format!("unknown metadata field: {}", key)),
+        }
+    }
+
+    /// Sets the metadata field `key` for the `set_metadata_field` function of
+    /// VRL.
+    pub fn set_field(&mut self, key: &str, value: remap::Value) -> Result<(), String> {
+        match key {
+            "priority" => {
+                let priority = value
+                    .try_bytes_utf8_lossy()
+                    .map_err(|e| e.to_string())?
+                    .parse()
+                    .map_err(|_| "priority must be one of low, normal or high".to_owned())?;
+                self.priority = Some(priority);
+                Ok(())
+            }
+            _ => Err(format!("unknown metadata field: {}", key)),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use super::EventMetadata;
 use chrono::{DateTime, Utc};
 use derive_is_enum_variant::is_enum_variant;
 use remap::{Object, Segment};
@@ -16,6 +17,8 @@ pub struct Metric {
     pub series: MetricSeries,
     #[serde(flatten)]
     pub data: MetricData,
+    #[serde(skip)]
+    pub metadata: EventMetadata,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
@@ -28,6 +31,15 @@ pub struct MetricSeries {
 
 pub type MetricTags = BTreeMap<String, String>;
 
+/// The bits of the NaN Prometheus reads as the end of a series, which is
+/// distinct from the NaN of regular samples.
+pub const STALE_NAN_BITS: u64 = 0x7ff0_0000_0000_0002;
+
+/// Whether the value is the NaN marking the end of a series.
+pub fn is_stale_nan(value: f64) -> bool {
+    value.to_bits() == STALE_NAN_BITS
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct MetricName {
     pub name: String,
@@ -239,6 +251,7 @@ impl Metric {
                 kind,
                 value,
             },
+            metadata: EventMetadata::default(),
         }
     }
 
@@ -262,6 +275,7 @@ impl Metric {
         Self {
             series: self.series,
             data: self.data.into_absolute(),
+            metadata: self.metadata,
         }
     }
 
@@ -270,6 +284,7 @@ impl Metric {
         Self {
             series: self.series,
             data: self.data.into_incremental(),
+            metadata: self.metadata,
         }
     }
 
@@ -361,8 +376,15 @@ impl Metric {
         Self {
             series: self.series.clone(),
             data: self.data.zero(),
+            metadata: self.metadata.clone(),
         }
     }
+
+    /// Whether the metric marks the end of its series, see
+    /// `MetricValue::stale`.
+    pub fn is_stale(&self) -> bool {
+        self.data.value.is_stale()
+    }
 }
 
 impl MetricData {
@@ -448,6 +470,52 @@ impl MetricValue {
         }
     }
 
+    /// Create the value marking the end of a series of this value's type,
+    /// its samples being the stale NaN. This keeps the bucket and quantile
+    /// limits of histograms and summaries, while zeroing their counts. Sets
+    /// and distributions have no such marker, and return `None`.
+    pub fn stale(&self) -> Option<Self> {
+        let stale = f64::from_bits(STALE_NAN_BITS);
+        match self {
+            Self::Counter { .. } => Some(Self::Counter { value: stale }),
+            Self::Gauge { .. } => Some(Self::Gauge { value: stale }),
+            Self::AggregatedHistogram { buckets, .. } => Some(Self::AggregatedHistogram {
+                buckets: buckets
+                    .iter()
+                    .map(|&Bucket { upper_limit, .. }| Bucket {
+                        upper_limit,
+                        count: 0,
+                    })
+                    .collect(),
+                count: 0,
+                sum: stale,
+            }),
+            Self::AggregatedSummary { quantiles, .. } => Some(Self::AggregatedSummary {
+                quantiles: quantiles
+                    .iter()
+                    .map(|&Quantile { upper_limit, .. }| Quantile {
+                        upper_limit,
+                        value: stale,
+                    })
+                    .collect(),
+                count: 0,
+                sum: stale,
+            }),
+            Self::Set { .. } | Self::Distribution { .. } => None,
+        }
+    }
+
+    /// Whether this value marks the end of its series.
+    pub fn is_stale(&self) -> bool {
+        match self {
+            Self::Counter { value } | Self::Gauge { value } => is_stale_nan(*value),
+            Self::AggregatedHistogram { sum, .. } | Self::AggregatedSummary { sum, .. } => {
+                is_stale_nan(*sum)
+            }
+            Self::Set { .. } | Self::Distribution { .. } => false,
+        }
+    }
+
     /// Add another same value to this.
     pub fn add(&mut self, other: &Self) {
         match (self, other) {
@@ -844,6 +912,14 @@ impl Object for Metric {
             .to_string()),
         }
     }
+
+    fn get_metadata(&self, key: &str) -> Result<Option<remap::Value>, String> {
+        self.metadata.get_field(key)
+    }
+
+    fn set_metadata(&mut self, key: &str, value: remap::Value) -> Result<(), String> {
+        self.metadata.set_field(key, value)
+    }
 }
 
 fn write_list<I, T, W>(
@@ -895,6 +971,31 @@ mod test {
         .collect()
     }
 
+    #[test]
+    fn stale_values() {
+        let histogram = MetricValue::AggregatedHistogram {
+            buckets: buckets![1.0 => 2, 2.0 => 3],
+            count: 5,
+            sum: 7.0,
+        };
+        let stale = histogram.stale().unwrap();
+        assert!(stale.is_stale());
+        assert!(!histogram.is_stale());
+        assert!(!MetricValue::Gauge { value: f64::NAN }.is_stale());
+        match stale {
+            MetricValue::AggregatedHistogram { buckets, count, .. } => {
+                assert_eq!(buckets, buckets![1.0 => 0, 2.0 => 0]);
+                assert_eq!(count, 0);
+            }
+            _ => panic!("Stale value of another type"),
+        }
+
+        let set = MetricValue::Set {
+            values: vec!["a".into()].into_iter().collect(),
+        };
+        assert_eq!(set.stale(), None);
+    }
+
     #[test]
     fn merge_counters() {
         let mut counter = Metric::new(
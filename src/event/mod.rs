@@ -1,22 +1,33 @@
-use self::proto::{event_wrapper::Event as EventProto, metric::Value as MetricProto, Log};
+use self::proto::{
+    event_wrapper::Event as EventProto, metric::Value as MetricProto, Log, Trace,
+};
 use crate::config::log_schema;
 use bytes::Bytes;
 use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 
 pub mod discriminant;
+pub mod finalization;
 pub mod merge;
 pub mod merge_state;
+pub mod metadata;
 pub mod metric;
+pub mod trace;
 pub mod util;
 
 mod log_event;
 mod lookup;
 mod value;
 
+pub use finalization::{BatchNotifier, BatchStatus, EventFinalizers, EventStatus};
 pub use log_event::LogEvent;
 pub use lookup::Lookup;
+pub use metadata::EventMetadata;
 pub use metric::{Metric, MetricKind, MetricValue, StatisticKind};
+pub use trace::TraceEvent;
 use std::convert::{TryFrom, TryInto};
 pub(crate) use util::log::PathComponent;
 pub(crate) use util::log::PathIter;
@@ -32,6 +43,7 @@ pub const PARTIAL: &str = "_partial";
 pub enum Event {
     Log(LogEvent),
     Metric(Metric),
+    Trace(TraceEvent),
 }
 
 impl Event {
@@ -80,6 +92,61 @@ impl Event {
             _ => panic!("Failed type coercion, {:?} is not a metric", self),
         }
     }
+
+    pub fn as_trace(&self) -> &TraceEvent {
+        match self {
+            Event::Trace(trace) => trace,
+            _ => panic!("Failed type coercion, {:?} is not a trace event", self),
+        }
+    }
+
+    pub fn as_mut_trace(&mut self) -> &mut TraceEvent {
+        match self {
+            Event::Trace(trace) => trace,
+            _ => panic!("Failed type coercion, {:?} is not a trace event", self),
+        }
+    }
+
+    pub fn into_trace(self) -> TraceEvent {
+        match self {
+            Event::Trace(trace) => trace,
+            _ => panic!("Failed type coercion, {:?} is not a trace event", self),
+        }
+    }
+
+    pub fn metadata(&self) -> &EventMetadata {
+        match self {
+            Event::Log(log) => log.metadata(),
+            Event::Metric(metric) => &metric.metadata,
+            Event::Trace(trace) => trace.0.metadata(),
+        }
+    }
+
+    pub fn metadata_mut(&mut self) -> &mut EventMetadata {
+        match self {
+            Event::Log(log) => log.metadata_mut(),
+            Event::Metric(metric) => &mut metric.metadata,
+            Event::Trace(trace) => trace.0.metadata_mut(),
+        }
+    }
+
+    /// Adds a finalizer of `batch` to the event. Only logs and traces carry
+    /// finalizers, so the other events don't hold back the status of `batch`.
+    pub fn with_batch_notifier(self, batch: &Arc<BatchNotifier>) -> Self {
+        match self {
+            Event::Log(log) => Event::Log(log.with_batch_notifier(batch)),
+            Event::Trace(trace) => Event::Trace(TraceEvent(trace.0.with_batch_notifier(batch))),
+            metric @ Event::Metric(_) => metric,
+        }
+    }
+
+    pub fn take_finalizers(&mut self) -> EventFinalizers {
+        match self {
+            Event::Log(log) => log.take_finalizers(),
+            Event::Trace(trace) => trace.0.take_finalizers(),
+            Event::Metric(_) => EventFinalizers::default(),
+        }
+    }
 }
 
 fn timestamp_to_string(timestamp: &DateTime<Utc>) -> String {
@@ -166,6 +233,7 @@ impl TryInto<serde_json::Value> for Event {
         match self {
             Event::Log(fields) => serde_json::to_value(fields),
             Event::Metric(metric) => serde_json::to_value(metric),
+            Event::Trace(trace) => serde_json::to_value(trace),
         }
     }
 }
@@ -184,6 +252,15 @@ impl From<proto::EventWrapper> for Event {
 
                 Event::Log(LogEvent::from(fields))
             }
+            EventProto::Trace(proto) => {
+                let fields = proto
+                    .fields
+                    .into_iter()
+                    .filter_map(|(k, v)| decode_value(v).map(|value| (k, value)))
+                    .collect::<BTreeMap<_, _>>();
+
+                Event::Trace(TraceEvent::from(fields))
+            }
             EventProto::Metric(proto) => {
                 let kind = match proto.kind() {
                     proto::metric::Kind::Incremental => MetricKind::Incremental,
@@ -294,7 +371,18 @@ impl From<Event> for proto::EventWrapper {
 
                 proto::EventWrapper { event: Some(event) }
             }
-            Event::Metric(Metric { series, data }) => {
+            Event::Trace(trace) => {
+                let fields = trace
+                    .into_log()
+                    .into_iter()
+                    .map(|(k, v)| (k, encode_value(v)))
+                    .collect::<BTreeMap<_, _>>();
+
+                let event = EventProto::Trace(Trace { fields });
+
+                proto::EventWrapper { event: Some(event) }
+            }
+            Event::Metric(Metric { series, data, .. }) => {
                 let name = series.name.name;
                 let namespace = series.name.namespace.unwrap_or_default();
 
@@ -410,6 +498,12 @@ impl From<Metric> for Event {
     }
 }
 
+impl From<TraceEvent> for Event {
+    fn from(trace: TraceEvent) -> Self {
+        Event::Trace(trace)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -504,4 +598,18 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn trace_proto_round_trip() {
+        let event = Event::from(
+            TraceEvent::new("0af7651916cd43dd8448eb211c80319c", "b7ad6b7169203331")
+                .with_name("GET /checkout")
+                .with_attribute("http.status_code", 200)
+                .with_resource_attribute("service", "checkout"),
+        );
+
+        let proto = proto::EventWrapper::from(event.clone());
+        assert!(matches!(proto.event, Some(EventProto::Trace(_))));
+        assert_eq!(Event::from(proto), event);
+    }
 }
@@ -0,0 +1,261 @@
+use crate::event::{LogEvent, Value};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Serializer};
+use std::{
+    collections::BTreeMap,
+    ops::{Deref, DerefMut},
+};
+
+/// Well-known span fields. Everything else on a trace event is considered
+/// free-form data, just like on a log event.
+pub const TRACE_ID: &str = "trace_id";
+pub const SPAN_ID: &str = "span_id";
+pub const PARENT_SPAN_ID: &str = "parent_span_id";
+pub const NAME: &str = "name";
+pub const KIND: &str = "kind";
+pub const START_TIME: &str = "start_time";
+pub const END_TIME: &str = "end_time";
+pub const STATUS: &str = "status";
+pub const ATTRIBUTES: &str = "attributes";
+pub const LINKS: &str = "links";
+pub const RESOURCE: &str = "resource";
+
+/// A single span of a trace.
+///
+/// Spans are stored as a structured map of fields so that they can be
+/// manipulated with the same tools as logs (templates, VRL, field paths),
+/// while the accessors below give typed access to the well-known fields.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct TraceEvent(pub(crate) LogEvent);
+
+/// A reference from a span to another span, possibly in another trace.
+#[derive(PartialEq, Debug, Clone)]
+pub struct SpanLink {
+    pub trace_id: String,
+    pub span_id: String,
+    pub attributes: BTreeMap<String, Value>,
+}
+
+impl TraceEvent {
+    pub fn new(trace_id: impl Into<String>, span_id: impl Into<String>) -> Self {
+        let mut log = LogEvent::default();
+        log.insert_flat(TRACE_ID, trace_id.into());
+        log.insert_flat(SPAN_ID, span_id.into());
+        Self(log)
+    }
+
+    pub fn with_parent_span_id(mut self, parent_span_id: impl Into<String>) -> Self {
+        self.0.insert_flat(PARENT_SPAN_ID, parent_span_id.into());
+        self
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.0.insert_flat(NAME, name.into());
+        self
+    }
+
+    pub fn with_times(mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        self.0.insert_flat(START_TIME, start);
+        self.0.insert_flat(END_TIME, end);
+        self
+    }
+
+    pub fn with_attribute(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.0.insert(format!("{}.{}", ATTRIBUTES, key), value.into());
+        self
+    }
+
+    pub fn with_resource_attribute(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.0.insert(format!("{}.{}", RESOURCE, key), value.into());
+        self
+    }
+
+    pub fn with_link(mut self, link: SpanLink) -> Self {
+        let mut links = match self.0.remove(LINKS) {
+            Some(Value::Array(links)) => links,
+            _ => Vec::new(),
+        };
+        links.push(link.into());
+        self.0.insert_flat(LINKS, links);
+        self
+    }
+
+    pub fn with_status_error(mut self, message: impl Into<String>) -> Self {
+        self.0.insert(format!("{}.code", STATUS), String::from("error"));
+        self.0.insert(format!("{}.message", STATUS), message.into());
+        self
+    }
+
+    pub fn trace_id(&self) -> Option<String> {
+        self.0.get(TRACE_ID).map(Value::to_string_lossy)
+    }
+
+    pub fn span_id(&self) -> Option<String> {
+        self.0.get(SPAN_ID).map(Value::to_string_lossy)
+    }
+
+    pub fn parent_span_id(&self) -> Option<String> {
+        self.0.get(PARENT_SPAN_ID).map(Value::to_string_lossy)
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.0.get(NAME).map(Value::to_string_lossy)
+    }
+
+    pub fn start_time(&self) -> Option<&DateTime<Utc>> {
+        self.0.get(START_TIME).and_then(Value::as_timestamp)
+    }
+
+    pub fn end_time(&self) -> Option<&DateTime<Utc>> {
+        self.0.get(END_TIME).and_then(Value::as_timestamp)
+    }
+
+    /// The span duration, if both its start and end time are known.
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        match (self.start_time(), self.end_time()) {
+            (Some(start), Some(end)) => Some(*end - *start),
+            _ => None,
+        }
+    }
+
+    /// A root span has no parent.
+    pub fn is_root(&self) -> bool {
+        match self.0.get(PARENT_SPAN_ID) {
+            None | Some(Value::Null) => true,
+            Some(Value::Bytes(id)) => id.is_empty(),
+            Some(_) => false,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.0
+            .get(format!("{}.code", STATUS))
+            .map_or(false, |code| code.to_string_lossy() == "error")
+    }
+
+    pub fn links(&self) -> Vec<SpanLink> {
+        match self.0.get(LINKS) {
+            Some(Value::Array(links)) => links.iter().filter_map(SpanLink::from_value).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn as_log(&self) -> &LogEvent {
+        &self.0
+    }
+
+    pub fn as_mut_log(&mut self) -> &mut LogEvent {
+        &mut self.0
+    }
+
+    pub fn into_log(self) -> LogEvent {
+        self.0
+    }
+}
+
+impl SpanLink {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Map(map) => Some(Self {
+                trace_id: map.get(TRACE_ID)?.to_string_lossy(),
+                span_id: map.get(SPAN_ID)?.to_string_lossy(),
+                attributes: match map.get(ATTRIBUTES) {
+                    Some(Value::Map(attributes)) => attributes.clone(),
+                    _ => BTreeMap::new(),
+                },
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl From<SpanLink> for Value {
+    fn from(link: SpanLink) -> Self {
+        let mut map = BTreeMap::new();
+        map.insert(TRACE_ID.to_owned(), link.trace_id.into());
+        map.insert(SPAN_ID.to_owned(), link.span_id.into());
+        map.insert(ATTRIBUTES.to_owned(), Value::Map(link.attributes));
+        Value::Map(map)
+    }
+}
+
+impl From<LogEvent> for TraceEvent {
+    fn from(log: LogEvent) -> Self {
+        Self(log)
+    }
+}
+
+impl From<BTreeMap<String, Value>> for TraceEvent {
+    fn from(map: BTreeMap<String, Value>) -> Self {
+        Self(map.into())
+    }
+}
+
+impl Deref for TraceEvent {
+    type Target = LogEvent;
+
+    fn deref(&self) -> &LogEvent {
+        &self.0
+    }
+}
+
+impl DerefMut for TraceEvent {
+    fn deref_mut(&mut self) -> &mut LogEvent {
+        &mut self.0
+    }
+}
+
+impl Serialize for TraceEvent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn root_span() {
+        let root = TraceEvent::new("abc", "1");
+        assert!(root.is_root());
+
+        let child = TraceEvent::new("abc", "2").with_parent_span_id("1");
+        assert!(!child.is_root());
+        assert_eq!(child.parent_span_id(), Some("1".into()));
+    }
+
+    #[test]
+    fn duration_and_status() {
+        let start = Utc.ymd(2021, 1, 1).and_hms_milli(0, 0, 0, 0);
+        let end = Utc.ymd(2021, 1, 1).and_hms_milli(0, 0, 1, 500);
+        let span = TraceEvent::new("abc", "1")
+            .with_times(start, end)
+            .with_status_error("boom");
+
+        assert_eq!(span.duration(), Some(chrono::Duration::milliseconds(1500)));
+        assert!(span.is_error());
+    }
+
+    #[test]
+    fn links_round_trip() {
+        let link = SpanLink {
+            trace_id: "def".into(),
+            span_id: "9".into(),
+            attributes: BTreeMap::new(),
+        };
+        let span = TraceEvent::new("abc", "1")
+            .with_link(link.clone())
+            .with_resource_attribute("service.name", "checkout");
+
+        assert_eq!(span.links(), vec![link]);
+        assert_eq!(
+            span.get("resource.service.name"),
+            Some(&Value::from("checkout"))
+        );
+    }
+}
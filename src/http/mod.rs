@@ -5,7 +5,7 @@ use crate::{
 };
 use futures::future::BoxFuture;
 use headers::{Authorization, HeaderMapExt};
-use http::header::HeaderValue;
+use http::header::{HeaderValue, AUTHORIZATION};
 use http::request::Builder;
 use http::HeaderMap;
 use http::Request;
@@ -24,6 +24,14 @@ use tower::Service;
 use tracing::Span;
 use tracing_futures::Instrument;
 
+#[cfg(feature = "rusoto_core")]
+mod aws;
+mod oauth2;
+
+#[cfg(feature = "rusoto_core")]
+pub use aws::{AwsAuthConfig, AwsAuthError};
+pub use oauth2::{OAuth2Config, OAuth2Error};
+
 #[derive(Debug, Snafu)]
 pub enum HttpError {
     #[snafu(display("Failed to build TLS connector"))]
@@ -49,11 +57,25 @@ where
     B::Error: Into<crate::Error>,
 {
     pub fn new(tls_settings: impl Into<MaybeTlsSettings>) -> Result<HttpClient<B>, HttpError> {
+        Self::build(tls_settings.into(), false)
+    }
+
+    /// Builds a client speaking only HTTP/2, as gRPC requires, which is
+    /// negotiated with ALPN on TLS connections.
+    pub fn new_http2_only(
+        tls_settings: impl Into<MaybeTlsSettings>,
+    ) -> Result<HttpClient<B>, HttpError> {
+        Self::build(tls_settings.into(), true)
+    }
+
+    fn build(settings: MaybeTlsSettings, http2_only: bool) -> Result<HttpClient<B>, HttpError> {
         let mut http = HttpConnector::new_with_resolver(Resolver);
         http.enforce_http(false);
 
-        let settings = tls_settings.into();
-        let tls = tls_connector_builder(&settings).context(BuildTlsConnector)?;
+        let mut tls = tls_connector_builder(&settings).context(BuildTlsConnector)?;
+        if http2_only {
+            tls.set_alpn_protos(b"\x02h2").context(MakeHttpsConnector)?;
+        }
         let mut https = HttpsConnector::with_connector(http, tls).context(MakeHttpsConnector)?;
 
         let settings = settings.tls().cloned();
@@ -65,7 +87,7 @@ where
             Ok(())
         });
 
-        let client = Client::builder().build(https);
+        let client = Client::builder().http2_only(http2_only).build(https);
 
         let version = crate::get_version();
         let user_agent = HeaderValue::from_str(&format!("Vector/{}", version))
@@ -186,8 +208,91 @@ impl<B> fmt::Debug for HttpClient<B> {
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 #[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
 pub enum Auth {
-    Basic { user: String, password: String },
-    Bearer { token: String },
+    Basic {
+        user: String,
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+    #[serde(rename = "oauth2")]
+    OAuth2(OAuth2Config),
+    #[cfg(feature = "rusoto_core")]
+    Aws(AwsAuthConfig),
+}
+
+#[derive(Debug)]
+pub enum AuthError {
+    OAuth2(OAuth2Error),
+    #[cfg(feature = "rusoto_core")]
+    Aws(AwsAuthError),
+}
+
+impl AuthError {
+    /// Whether authorizing the request may succeed later.
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            AuthError::OAuth2(error) => error.is_retriable(),
+            #[cfg(feature = "rusoto_core")]
+            AuthError::Aws(error) => error.is_retriable(),
+        }
+    }
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::OAuth2(error) => error.fmt(f),
+            #[cfg(feature = "rusoto_core")]
+            AuthError::Aws(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AuthError::OAuth2(error) => Some(error),
+            #[cfg(feature = "rusoto_core")]
+            AuthError::Aws(error) => Some(error),
+        }
+    }
+}
+
+impl From<OAuth2Error> for AuthError {
+    fn from(error: OAuth2Error) -> Self {
+        AuthError::OAuth2(error)
+    }
+}
+
+#[cfg(feature = "rusoto_core")]
+impl From<AwsAuthError> for AuthError {
+    fn from(error: AwsAuthError) -> Self {
+        AuthError::Aws(error)
+    }
+}
+
+/// The bodies of the requests `Auth::authorize` can sign.
+pub trait SignableBody {
+    /// The bytes of the body, or `None` if it's streamed.
+    fn payload(&self) -> Option<&[u8]>;
+}
+
+impl SignableBody for Vec<u8> {
+    fn payload(&self) -> Option<&[u8]> {
+        Some(self)
+    }
+}
+
+impl SignableBody for Body {
+    fn payload(&self) -> Option<&[u8]> {
+        // Only empty bodies, such as those of the healthchecks, are known
+        // without reading them.
+        match HttpBody::size_hint(self).exact() {
+            Some(0) => Some(&[]),
+            _ => None,
+        }
+    }
 }
 
 pub trait MaybeAuth: Sized {
@@ -205,10 +310,49 @@ impl MaybeAuth for Option<Auth> {
 }
 
 impl Auth {
+    pub fn validate(&self) -> crate::Result<()> {
+        match self {
+            Auth::OAuth2(config) => Ok(config.validate()?),
+            #[cfg(feature = "rusoto_core")]
+            Auth::Aws(config) => Ok(config.validate()?),
+            _ => Ok(()),
+        }
+    }
+
+    /// Applies the credentials to `req`. Unlike `authorize`, it doesn't fetch
+    /// OAuth2 tokens, using the cached one if any, nor signs AWS requests.
     pub fn apply<B>(&self, req: &mut Request<B>) {
         self.apply_headers_map(req.headers_mut())
     }
 
+    /// Applies the credentials to `req`, fetching an OAuth2 token first if
+    /// there is none or it's about to expire, or signing it for AWS. The
+    /// request must not be changed once signed.
+    pub async fn authorize<B: SignableBody>(&self, req: &mut Request<B>) -> Result<(), AuthError> {
+        match self {
+            Auth::OAuth2(config) => {
+                let header = config.header().await?;
+                req.headers_mut().insert(AUTHORIZATION, header);
+            }
+            #[cfg(feature = "rusoto_core")]
+            Auth::Aws(config) => config.sign(req).await?,
+            _ => self.apply(req),
+        }
+        Ok(())
+    }
+
+    /// Forgets the OAuth2 token rejected by a server, so that the next request
+    /// fetches another one. Returns whether the credentials are OAuth2 ones.
+    pub fn reject_token(&self) -> bool {
+        match self {
+            Auth::OAuth2(config) => {
+                config.reject_token();
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub fn apply_builder(&self, mut builder: Builder) -> Builder {
         if let Some(map) = builder.headers_mut() {
             self.apply_headers_map(map)
@@ -226,6 +370,22 @@ impl Auth {
                 Ok(auth) => map.typed_insert(auth),
                 Err(error) => error!(message = "Invalid bearer token.", token = %token, %error),
             },
+            Auth::OAuth2(config) => match config.cached_header() {
+                Some(header) => {
+                    map.insert(AUTHORIZATION, header);
+                }
+                None => error!(
+                    message = "No OAuth2 token was fetched yet.",
+                    token_endpoint = %config.token_endpoint,
+                    internal_log_rate_secs = 30,
+                ),
+            },
+            #[cfg(feature = "rusoto_core")]
+            Auth::Aws(config) => error!(
+                message = "AWS requests can't be signed by this component.",
+                service = %config.service,
+                internal_log_rate_secs = 30,
+            ),
         }
     }
 }
@@ -246,6 +406,54 @@ mod tests {
         assert_eq!(request.headers().get("User-Agent"), Some(&user_agent));
     }
 
+    #[test]
+    fn parses_oauth2_auth() {
+        let auth: Auth = toml::from_str(
+            r#"
+            strategy = "oauth2"
+            token_endpoint = "https://example.com/token"
+            client_id = "vector"
+            client_secret = "s3cr3t"
+            scopes = ["logs.write"]
+            "#,
+        )
+        .unwrap();
+
+        match auth {
+            Auth::OAuth2(config) => {
+                assert_eq!(config.client_id, "vector");
+                assert_eq!(config.scopes, vec!["logs.write".to_owned()]);
+                assert!(config.validate().is_ok());
+            }
+            auth => panic!("unexpected auth {:?}", auth),
+        }
+    }
+
+    #[cfg(feature = "rusoto_core")]
+    #[test]
+    fn parses_aws_auth() {
+        let auth: Auth = toml::from_str(
+            r#"
+            strategy = "aws"
+            service = "aps"
+            region = "us-east-1"
+            assume_role = "arn:aws:iam::123456789012:role/vector"
+            "#,
+        )
+        .unwrap();
+
+        match auth {
+            Auth::Aws(config) => {
+                assert_eq!(config.service, "aps");
+                assert!(matches!(
+                    config.credentials,
+                    crate::rusoto::AWSAuthentication::Role { .. }
+                ));
+            }
+            auth => panic!("unexpected auth {:?}", auth),
+        }
+    }
+
     #[test]
     fn test_default_request_headers_does_not_overwrite() {
         let mut request = Request::post("http://example.com")
@@ -0,0 +1,421 @@
+//! OAuth2 client credentials grant (RFC 6749, section 4.4), the client
+//! authenticating with a secret or with a JWT assertion (RFC 7523).
+//!
+//! Tokens are cached by configuration, so that the components sharing the same
+//! credentials share their tokens too. A token is fetched again shortly before
+//! it expires, or once a server rejected it.
+
+use super::{HttpClient, HttpError};
+use http::{
+    header::{HeaderValue, InvalidHeaderValue, ACCEPT, CONTENT_TYPE},
+    Request, StatusCode, Uri,
+};
+use hyper::Body;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+const CLIENT_ASSERTION_TYPE: &str = "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+
+/// Tokens are fetched again this long before they expire, or halfway through
+/// their lifetime when it's shorter.
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    // The configurations are few and live as long as Vector, the caches are
+    // never removed.
+    static ref CACHES: Mutex<HashMap<OAuth2Config, Arc<TokenCache>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(deny_unknown_fields)]
+pub struct OAuth2Config {
+    pub token_endpoint: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    /// A file holding the JWT authenticating the client. It's read again for
+    /// each token, as such assertions are usually short lived.
+    pub client_assertion_path: Option<PathBuf>,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Snafu)]
+pub enum OAuth2Error {
+    #[snafu(display("Exactly one of `client_secret` and `client_assertion_path` must be set"))]
+    InvalidClientCredentials,
+    #[snafu(display("Invalid token endpoint {:?}: {}", uri, source))]
+    InvalidTokenEndpoint {
+        uri: String,
+        source: http::uri::InvalidUri,
+    },
+    #[snafu(display("Could not read the client assertion from {:?}: {}", path, source))]
+    ReadClientAssertion {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Failed to build HTTP client: {}", source))]
+    BuildHttpClient { source: HttpError },
+    #[snafu(display("Failed to request an OAuth2 token: {}", source))]
+    RequestToken { source: HttpError },
+    #[snafu(display("Failed to read the OAuth2 token response: {}", source))]
+    ReadTokenResponse { source: hyper::Error },
+    #[snafu(display("OAuth2 token request failed with {}: {}", status, body))]
+    TokenRequestFailed { status: StatusCode, body: String },
+    #[snafu(display("Invalid OAuth2 token response: {}", source))]
+    ParseTokenResponse { source: serde_json::Error },
+    #[snafu(display("Invalid OAuth2 access token: {}", source))]
+    InvalidAccessToken { source: InvalidHeaderValue },
+}
+
+impl OAuth2Error {
+    /// Whether fetching a token may succeed later, the other errors coming
+    /// from the configuration or the credentials.
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            OAuth2Error::RequestToken { .. } | OAuth2Error::ReadTokenResponse { .. } => true,
+            OAuth2Error::TokenRequestFailed { status, .. } => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+#[derive(Clone)]
+struct Token {
+    header: HeaderValue,
+    refresh_at: Option<Instant>,
+    expires_at: Option<Instant>,
+}
+
+impl Token {
+    fn is_fresh(&self, now: Instant) -> bool {
+        self.refresh_at.map_or(true, |at| now < at)
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.map_or(false, |at| now >= at)
+    }
+}
+
+struct TokenCache {
+    token: RwLock<Option<Token>>,
+    /// Held while fetching a token, so that one request fetches it for all the
+    /// requests needing one.
+    fetching: tokio::sync::Mutex<()>,
+}
+
+impl TokenCache {
+    fn new() -> Self {
+        Self {
+            token: RwLock::new(None),
+            fetching: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    fn fresh_header(&self) -> Option<HeaderValue> {
+        let token = self.token.read().expect("poisoned lock");
+        token
+            .as_ref()
+            .filter(|token| token.is_fresh(Instant::now()))
+            .map(|token| token.header.clone())
+    }
+}
+
+impl OAuth2Config {
+    pub fn validate(&self) -> Result<(), OAuth2Error> {
+        if self.client_secret.is_some() == self.client_assertion_path.is_some() {
+            return Err(OAuth2Error::InvalidClientCredentials);
+        }
+        self.token_endpoint
+            .parse::<Uri>()
+            .with_context(|| InvalidTokenEndpoint {
+                uri: self.token_endpoint.clone(),
+            })?;
+        Ok(())
+    }
+
+    /// The `Authorization` header value of the cached token, if it didn't
+    /// expire yet.
+    pub fn cached_header(&self) -> Option<HeaderValue> {
+        let cache = self.cache();
+        let token = cache.token.read().expect("poisoned lock");
+        token
+            .as_ref()
+            .filter(|token| !token.is_expired(Instant::now()))
+            .map(|token| token.header.clone())
+    }
+
+    /// The `Authorization` header value of the cached token, fetching a new
+    /// token first if there is none or it's about to expire.
+    pub async fn header(&self) -> Result<HeaderValue, OAuth2Error> {
+        let cache = self.cache();
+        if let Some(header) = cache.fresh_header() {
+            return Ok(header);
+        }
+
+        let _fetching = cache.fetching.lock().await;
+        // Another request may have fetched a token while this one waited.
+        if let Some(header) = cache.fresh_header() {
+            return Ok(header);
+        }
+
+        match self.fetch().await {
+            Ok(token) => {
+                debug!(message = "Fetched OAuth2 token.", token_endpoint = %self.token_endpoint);
+                let header = token.header.clone();
+                *cache.token.write().expect("poisoned lock") = Some(token);
+                Ok(header)
+            }
+            Err(error) => match self.cached_header() {
+                // The current token is still usable until it expires.
+                Some(header) => {
+                    warn!(
+                        message = "Failed to refresh OAuth2 token.",
+                        token_endpoint = %self.token_endpoint,
+                        %error,
+                        internal_log_rate_secs = 30,
+                    );
+                    Ok(header)
+                }
+                None => Err(error),
+            },
+        }
+    }
+
+    /// Forgets the cached token, after a server rejected it.
+    pub fn reject_token(&self) {
+        *self.cache().token.write().expect("poisoned lock") = None;
+    }
+
+    fn cache(&self) -> Arc<TokenCache> {
+        let mut caches = CACHES.lock().expect("poisoned lock");
+        let cache = caches
+            .entry(self.clone())
+            .or_insert_with(|| Arc::new(TokenCache::new()));
+        Arc::clone(cache)
+    }
+
+    async fn fetch(&self) -> Result<Token, OAuth2Error> {
+        self.validate()?;
+
+        let assertion = match &self.client_assertion_path {
+            Some(path) => Some(
+                tokio::fs::read_to_string(path)
+                    .await
+                    .context(ReadClientAssertion { path })?,
+            ),
+            None => None,
+        };
+
+        // The serializer isn't `Send`, it must not live across an await.
+        let form = {
+            let mut form = url::form_urlencoded::Serializer::new(String::new());
+            form.append_pair("grant_type", "client_credentials")
+                .append_pair("client_id", &self.client_id);
+            if let Some(client_secret) = &self.client_secret {
+                form.append_pair("client_secret", client_secret);
+            }
+            if let Some(assertion) = &assertion {
+                form.append_pair("client_assertion_type", CLIENT_ASSERTION_TYPE)
+                    .append_pair("client_assertion", assertion.trim());
+            }
+            if !self.scopes.is_empty() {
+                form.append_pair("scope", &self.scopes.join(" "));
+            }
+            form.finish()
+        };
+
+        let request = Request::post(self.token_endpoint.as_str())
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(ACCEPT, "application/json")
+            .body(Body::from(form))
+            .expect("The token endpoint was validated.");
+
+        let requested = Instant::now();
+        let response = HttpClient::new(None)
+            .context(BuildHttpClient)?
+            .send(request)
+            .await
+            .context(RequestToken)?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .context(ReadTokenResponse)?;
+        if !status.is_success() {
+            return Err(OAuth2Error::TokenRequestFailed {
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            });
+        }
+
+        let response: TokenResponse = serde_json::from_slice(&body).context(ParseTokenResponse)?;
+        let mut header = HeaderValue::from_str(&format!("Bearer {}", response.access_token))
+            .context(InvalidAccessToken)?;
+        header.set_sensitive(true);
+
+        // Tokens without a lifetime are used until a server rejects them.
+        let lifetime = response.expires_in.map(Duration::from_secs);
+        Ok(Token {
+            header,
+            refresh_at: lifetime
+                .map(|lifetime| requested + lifetime - std::cmp::min(REFRESH_MARGIN, lifetime / 2)),
+            expires_at: lifetime.map(|lifetime| requested + lifetime),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::next_addr;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+    use std::{
+        convert::Infallible,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    /// Serves tokens numbered by request, recording the forms they were
+    /// requested with.
+    fn token_server(expires_in: u64) -> (String, Arc<Mutex<Vec<String>>>) {
+        let addr = next_addr();
+        let forms = Arc::new(Mutex::new(Vec::new()));
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let recorded = Arc::clone(&forms);
+        let make_service = make_service_fn(move |_| {
+            let forms = Arc::clone(&recorded);
+            let count = Arc::clone(&count);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                    let forms = Arc::clone(&forms);
+                    let count = Arc::clone(&count);
+                    async move {
+                        let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+                        forms
+                            .lock()
+                            .unwrap()
+                            .push(String::from_utf8_lossy(&body).into_owned());
+
+                        let n = count.fetch_add(1, Ordering::SeqCst);
+                        let token = format!(
+                            r#"{{"access_token": "token-{}", "token_type": "Bearer", "expires_in": {}}}"#,
+                            n, expires_in
+                        );
+                        Ok::<_, Infallible>(Response::new(Body::from(token)))
+                    }
+                }))
+            }
+        });
+        tokio::spawn(Server::bind(&addr).serve(make_service));
+
+        (format!("http://{}/token", addr), forms)
+    }
+
+    fn config(token_endpoint: String) -> OAuth2Config {
+        OAuth2Config {
+            token_endpoint,
+            client_id: "vector".to_owned(),
+            client_secret: Some("s3cr3t".to_owned()),
+            client_assertion_path: None,
+            scopes: vec!["logs.write".to_owned(), "metrics.write".to_owned()],
+        }
+    }
+
+    #[tokio::test]
+    async fn fetches_and_caches_tokens() {
+        let (token_endpoint, forms) = token_server(3600);
+        let config = config(token_endpoint);
+
+        assert_eq!(config.cached_header(), None);
+        assert_eq!(config.header().await.unwrap(), "Bearer token-0");
+        assert_eq!(config.header().await.unwrap(), "Bearer token-0");
+        assert_eq!(config.cached_header().unwrap(), "Bearer token-0");
+
+        assert_eq!(
+            *forms.lock().unwrap(),
+            vec!["grant_type=client_credentials&client_id=vector&client_secret=s3cr3t&scope=logs.write+metrics.write"]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetches_rejected_tokens_again() {
+        let (token_endpoint, _) = token_server(3600);
+        let config = config(token_endpoint);
+
+        assert_eq!(config.header().await.unwrap(), "Bearer token-0");
+        config.reject_token();
+        assert_eq!(config.cached_header(), None);
+        assert_eq!(config.header().await.unwrap(), "Bearer token-1");
+    }
+
+    #[tokio::test]
+    async fn refreshes_expiring_tokens() {
+        // Tokens living 1 second are refreshed after half a second.
+        let (token_endpoint, forms) = token_server(1);
+        let config = config(token_endpoint);
+
+        assert_eq!(config.header().await.unwrap(), "Bearer token-0");
+        tokio::time::delay_for(Duration::from_millis(600)).await;
+        assert_eq!(config.header().await.unwrap(), "Bearer token-1");
+        assert_eq!(forms.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn sends_client_assertions() {
+        let (token_endpoint, forms) = token_server(3600);
+        let mut assertion = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut assertion, b"header.payload.signature\n").unwrap();
+
+        let config = OAuth2Config {
+            client_secret: None,
+            client_assertion_path: Some(assertion.path().to_owned()),
+            scopes: vec![],
+            ..config(token_endpoint)
+        };
+        config.header().await.unwrap();
+
+        assert_eq!(
+            *forms.lock().unwrap(),
+            vec![format!(
+                "grant_type=client_credentials&client_id=vector&client_assertion_type={}&client_assertion=header.payload.signature",
+                "urn%3Aietf%3Aparams%3Aoauth%3Aclient-assertion-type%3Ajwt-bearer"
+            )]
+        );
+    }
+
+    #[test]
+    fn validates_client_credentials() {
+        let mut config = config("https://example.com/token".to_owned());
+        assert!(config.validate().is_ok());
+
+        config.client_assertion_path = Some("/path/to/assertion".into());
+        assert!(matches!(
+            config.validate(),
+            Err(OAuth2Error::InvalidClientCredentials)
+        ));
+
+        config.client_secret = None;
+        config.client_assertion_path = None;
+        assert!(matches!(
+            config.validate(),
+            Err(OAuth2Error::InvalidClientCredentials)
+        ));
+    }
+}
@@ -1,5 +1,5 @@
 use super::InternalEvent;
-use metrics::histogram;
+use metrics::{counter, gauge, histogram};
 use std::time::Duration;
 
 #[derive(Debug)]
@@ -25,6 +25,43 @@ impl InternalEvent for AdaptiveConcurrencyLimit {
 
     fn emit_metrics(&self) {
         histogram!("adaptive_concurrency_limit", self.concurrency as f64);
+        gauge!(
+            "adaptive_concurrency_current_limit",
+            self.concurrency as f64
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct AdaptiveConcurrencyDecision {
+    pub decision: &'static str,
+}
+
+impl InternalEvent for AdaptiveConcurrencyDecision {
+    fn emit_metrics(&self) {
+        counter!("adaptive_concurrency_decisions_total", 1, "decision" => self.decision);
+    }
+}
+
+#[derive(Debug)]
+pub struct AdaptiveConcurrencyPinned<'a> {
+    pub sink_name: &'a str,
+    pub limit: Option<u64>,
+}
+
+impl<'a> InternalEvent for AdaptiveConcurrencyPinned<'a> {
+    fn emit_logs(&self) {
+        match self.limit {
+            Some(limit) => info!(
+                message = "Pinned adaptive concurrency limit.",
+                sink = %self.sink_name,
+                %limit
+            ),
+            None => info!(
+                message = "Unpinned adaptive concurrency limit.",
+                sink = %self.sink_name
+            ),
+        }
     }
 }
 
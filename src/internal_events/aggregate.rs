@@ -0,0 +1,12 @@
+use super::InternalEvent;
+
+#[derive(Debug)]
+pub(crate) struct AggregateFlushed {
+    pub series: usize,
+}
+
+impl InternalEvent for AggregateFlushed {
+    fn emit_logs(&self) {
+        debug!(message = "Flushed aggregated metrics.", series = %self.series);
+    }
+}
@@ -0,0 +1,36 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct AnomalyDetected<'a> {
+    pub field: &'a str,
+}
+
+impl<'a> InternalEvent for AnomalyDetected<'a> {
+    fn emit_logs(&self) {
+        debug!(message = "Anomaly detected.", field = %self.field);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("anomalies_detected_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AnomalyDetectionFieldInvalid<'a> {
+    pub field: &'a str,
+}
+
+impl<'a> InternalEvent for AnomalyDetectionFieldInvalid<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Field is missing or not numeric.",
+            field = %self.field,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "invalid_field");
+    }
+}
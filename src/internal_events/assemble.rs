@@ -0,0 +1,17 @@
+use super::InternalEvent;
+
+#[derive(Debug)]
+pub(crate) struct AssembleFlushed {
+    pub events: usize,
+    pub reason: &'static str,
+}
+
+impl InternalEvent for AssembleFlushed {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Assembled events.",
+            events = %self.events,
+            reason = %self.reason,
+        );
+    }
+}
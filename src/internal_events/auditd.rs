@@ -0,0 +1,68 @@
+use super::InternalEvent;
+use crate::sources::auditd::ParseError;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct AuditdEventsReceived {
+    pub count: usize,
+}
+
+impl InternalEvent for AuditdEventsReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received events.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AuditdEventsLost;
+
+impl InternalEvent for AuditdEventsLost {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Records lost, as the socket buffer overflowed.",
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AuditdRecordParseError {
+    pub error: ParseError,
+}
+
+impl InternalEvent for AuditdRecordParseError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed parsing record.",
+            error = %self.error,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("parse_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AuditdReadError {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for AuditdReadError {
+    fn emit_logs(&self) {
+        error!(message = "Failed reading records.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_read_errors_total", 1);
+    }
+}
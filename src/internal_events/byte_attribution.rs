@@ -0,0 +1,12 @@
+use super::InternalEvent;
+
+#[derive(Debug)]
+pub(crate) struct ByteAttributionFlushed {
+    pub groups: usize,
+}
+
+impl InternalEvent for ByteAttributionFlushed {
+    fn emit_logs(&self) {
+        debug!(message = "Flushed attributed bytes.", groups = %self.groups);
+    }
+}
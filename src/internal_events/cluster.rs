@@ -0,0 +1,43 @@
+use super::InternalEvent;
+use crate::cluster::ClusterError;
+use metrics::{counter, gauge};
+
+#[derive(Debug)]
+pub struct ClusterMembershipChanged<'a> {
+    pub member_id: &'a str,
+    pub members: &'a [String],
+}
+
+impl<'a> InternalEvent for ClusterMembershipChanged<'a> {
+    fn emit_logs(&self) {
+        info!(
+            message = "Cluster membership changed.",
+            member_id = %self.member_id,
+            members = ?self.members,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("cluster_rebalances_total", 1);
+        gauge!("cluster_members", self.members.len() as f64);
+    }
+}
+
+#[derive(Debug)]
+pub struct ClusterHeartbeatFailed {
+    pub error: ClusterError,
+}
+
+impl InternalEvent for ClusterHeartbeatFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Cluster heartbeat failed.",
+            error = %self.error,
+            internal_log_rate_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("cluster_heartbeat_errors_total", 1);
+    }
+}
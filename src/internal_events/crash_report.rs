@@ -0,0 +1,38 @@
+use super::InternalEvent;
+use metrics::counter;
+use std::path::Path;
+
+#[derive(Debug)]
+pub struct CrashReportUploaded<'a> {
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for CrashReportUploaded<'a> {
+    fn emit_logs(&self) {
+        info!(message = "Uploaded crash report.", path = ?self.path);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("crash_reports_uploaded_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct CrashReportUploadFailed<'a> {
+    pub path: &'a Path,
+    pub error: String,
+}
+
+impl<'a> InternalEvent for CrashReportUploadFailed<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to upload crash report.",
+            path = ?self.path,
+            error = %self.error,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("crash_report_upload_errors_total", 1);
+    }
+}
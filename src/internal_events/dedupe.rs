@@ -19,3 +19,22 @@ impl InternalEvent for DedupeEventDiscarded {
         counter!("events_discarded_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub(crate) struct DedupePersistenceFailed {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for DedupePersistenceFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed writing dedupe state to disk.",
+            error = ?self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("dedupe_persistence_errors_total", 1);
+    }
+}
@@ -0,0 +1,59 @@
+use super::InternalEvent;
+use crate::sources::ebpf::DecodeError;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct EbpfEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for EbpfEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.", byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct EbpfEventsDiscarded {
+    pub count: u64,
+    pub reason: &'static str,
+}
+
+impl InternalEvent for EbpfEventsDiscarded {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Events discarded.",
+            count = %self.count,
+            reason = %self.reason,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", self.count);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct EbpfEventDecodeError {
+    pub error: DecodeError,
+}
+
+impl InternalEvent for EbpfEventDecodeError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed decoding event.",
+            error = %self.error,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("parse_errors_total", 1);
+    }
+}
@@ -0,0 +1,42 @@
+use super::InternalEvent;
+use metrics::{counter, histogram};
+use std::time::Duration;
+
+#[derive(Debug)]
+pub struct ExecCommandExecuted<'a> {
+    pub command: &'a str,
+    /// `None` when the command was killed by a signal.
+    pub exit_status: Option<i32>,
+    pub elapsed: Duration,
+    pub byte_size: usize,
+}
+
+impl ExecCommandExecuted<'_> {
+    fn exit_status_string(&self) -> String {
+        self.exit_status
+            .map(|code| code.to_string())
+            .unwrap_or_else(|| "unknown".to_owned())
+    }
+}
+
+impl InternalEvent for ExecCommandExecuted<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Executed command.",
+            command = %self.command,
+            exit_status = %self.exit_status_string(),
+            elapsed_millis = %self.elapsed.as_millis(),
+        );
+    }
+
+    fn emit_metrics(&self) {
+        let exit_status = self.exit_status_string();
+        counter!("command_executed_total", 1, "exit_status" => exit_status.clone());
+        histogram!(
+            "command_execution_duration_nanoseconds",
+            self.elapsed,
+            "exit_status" => exit_status
+        );
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
@@ -0,0 +1,40 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct ExplodeFieldMissing<'a> {
+    pub field: &'a str,
+}
+
+impl<'a> InternalEvent for ExplodeFieldMissing<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Field does not exist; passing the event through.",
+            field = %self.field,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "field_missing");
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ExplodeFieldNotArray<'a> {
+    pub field: &'a str,
+}
+
+impl<'a> InternalEvent for ExplodeFieldNotArray<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Field is not an array; passing the event through.",
+            field = %self.field,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "value_invalid");
+    }
+}
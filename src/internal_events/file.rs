@@ -1,7 +1,11 @@
 use super::InternalEvent;
 use metrics::gauge;
 
-#[cfg(any(feature = "sources-file", feature = "sources-kubernetes-logs"))]
+#[cfg(any(
+    feature = "sources-file",
+    feature = "sources-kubernetes-logs",
+    feature = "sources-osquery",
+))]
 pub(crate) use self::source::*;
 
 #[derive(Debug)]
@@ -15,7 +19,11 @@ impl InternalEvent for FileOpen {
     }
 }
 
-#[cfg(any(feature = "sources-file", feature = "sources-kubernetes-logs"))]
+#[cfg(any(
+    feature = "sources-file",
+    feature = "sources-kubernetes-logs",
+    feature = "sources-osquery",
+))]
 mod source {
     use super::{FileOpen, InternalEvent};
     use file_source::FileSourceInternalEvents;
@@ -49,6 +57,45 @@ mod source {
         }
     }
 
+    #[derive(Debug)]
+    pub struct FileDecodeFailed<'a, E> {
+        pub file: &'a str,
+        pub error: E,
+        /// Whether an event describing the error was sent to the
+        /// `decode_errors` output.
+        pub forwarded: bool,
+    }
+
+    impl<E> InternalEvent for FileDecodeFailed<'_, E>
+    where
+        E: std::error::Error,
+    {
+        fn emit_logs(&self) {
+            if self.forwarded {
+                warn!(
+                    message = "Unable to decode line, forwarding the error.",
+                    file = %self.file,
+                    error = %self.error,
+                    internal_log_rate_secs = 10
+                );
+            } else {
+                warn!(
+                    message = "Unable to decode line, discarding it.",
+                    file = %self.file,
+                    error = %self.error,
+                    internal_log_rate_secs = 10
+                );
+            }
+        }
+
+        fn emit_metrics(&self) {
+            counter!(
+                "decode_errors_total", 1,
+                "file" => self.file.to_owned(),
+            );
+        }
+    }
+
     #[derive(Debug)]
     pub struct FileChecksumFailed<'a> {
         pub path: &'a Path,
@@ -0,0 +1,51 @@
+use super::InternalEvent;
+use crate::sources::haproxy_metrics::parser::ParseError;
+use metrics::{counter, histogram};
+use std::time::Instant;
+
+#[derive(Debug)]
+pub struct HaproxyMetricsCollectCompleted {
+    pub start: Instant,
+    pub end: Instant,
+}
+
+impl InternalEvent for HaproxyMetricsCollectCompleted {
+    fn emit_logs(&self) {
+        debug!(message = "Collection completed.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("collect_completed_total", 1);
+        histogram!("collect_duration_nanoseconds", self.end - self.start);
+    }
+}
+
+pub struct HaproxyMetricsRequestError<'a> {
+    pub error: crate::Error,
+    pub endpoint: &'a str,
+}
+
+impl<'a> InternalEvent for HaproxyMetricsRequestError<'a> {
+    fn emit_logs(&self) {
+        error!(message = "HAProxy request error.", endpoint = %self.endpoint, error = ?self.error)
+    }
+
+    fn emit_metrics(&self) {
+        counter!("http_request_errors_total", 1);
+    }
+}
+
+pub struct HaproxyMetricsParseError<'a> {
+    pub error: ParseError,
+    pub endpoint: &'a str,
+}
+
+impl<'a> InternalEvent for HaproxyMetricsParseError<'a> {
+    fn emit_logs(&self) {
+        error!(message = "HAProxy statistics parse error.", endpoint = %self.endpoint, error = ?self.error)
+    }
+
+    fn emit_metrics(&self) {
+        counter!("parse_errors_total", 1);
+    }
+}
@@ -44,6 +44,25 @@ impl<'a> InternalEvent for HTTPBadRequest<'a> {
     }
 }
 
+#[derive(Debug)]
+pub struct HTTPAuthenticationFailed {
+    pub reason: &'static str,
+}
+
+impl InternalEvent for HTTPAuthenticationFailed {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Request failed authentication.",
+            reason = %self.reason,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("http_authentication_failures_total", 1, "reason" => self.reason);
+    }
+}
+
 #[derive(Debug)]
 pub struct HTTPEventMissingMessage;
 
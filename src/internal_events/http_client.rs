@@ -107,3 +107,14 @@ impl<'a, B: HttpBody> std::fmt::Display for FormatBody<'a, B> {
         }
     }
 }
+
+#[derive(Debug)]
+pub struct HttpDebugCaptured<'a> {
+    pub capture: &'a serde_json::Value,
+}
+
+impl<'a> InternalEvent for HttpDebugCaptured<'a> {
+    fn emit_logs(&self) {
+        info!(message = "Captured HTTP request.", capture = %self.capture);
+    }
+}
@@ -0,0 +1,39 @@
+use super::InternalEvent;
+use metrics::{counter, histogram};
+use std::{net::SocketAddr, time::Duration};
+
+/// A request served by an HTTP source with `access_log` enabled.
+#[derive(Debug)]
+pub struct HTTPRequestCompleted<'a> {
+    pub method: &'a str,
+    pub path: &'a str,
+    pub status: u16,
+    pub byte_size: Option<u64>,
+    pub duration: Duration,
+    pub client: Option<SocketAddr>,
+}
+
+impl<'a> HTTPRequestCompleted<'a> {
+    fn status_class(&self) -> String {
+        format!("{}xx", self.status / 100)
+    }
+}
+
+impl<'a> InternalEvent for HTTPRequestCompleted<'a> {
+    fn emit_logs(&self) {
+        info!(
+            message = "Request completed.",
+            method = %self.method,
+            path = %self.path,
+            status = %self.status,
+            byte_size = ?self.byte_size,
+            duration_ms = %self.duration.as_millis(),
+            client = ?self.client,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("http_server_requests_total", 1, "status_class" => self.status_class());
+        histogram!("http_server_request_duration_nanoseconds", self.duration, "status_class" => self.status_class());
+    }
+}
@@ -0,0 +1,34 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct LogPatternCreated;
+
+impl InternalEvent for LogPatternCreated {
+    fn emit_logs(&self) {
+        trace!(message = "New log pattern created.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("log_patterns_created_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct LogPatternFieldMissing<'a> {
+    pub field: &'a str,
+}
+
+impl<'a> InternalEvent for LogPatternFieldMissing<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Field does not exist.",
+            field = %self.field,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "field_missing");
+    }
+}
@@ -80,3 +80,23 @@ impl InternalEvent for LogToMetricTemplateParseError {
         );
     }
 }
+
+pub(crate) struct LogToMetricVrlError {
+    pub error: String,
+}
+
+impl InternalEvent for LogToMetricVrlError {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to evaluate VRL expression.",
+            error = %self.error,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+                 "error_type" => "vrl_error",
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct MacosUnifiedLogEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for MacosUnifiedLogEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.", byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct MacosUnifiedLogParseError {
+    pub error: serde_json::Error,
+}
+
+impl InternalEvent for MacosUnifiedLogParseError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed parsing log entry.",
+            error = %self.error,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("parse_errors_total", 1);
+    }
+}
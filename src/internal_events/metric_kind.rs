@@ -0,0 +1,21 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct MetricKindCounterReset<'a> {
+    pub name: &'a str,
+}
+
+impl<'a> InternalEvent for MetricKindCounterReset<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Counter decreased, its source restarted.",
+            name = self.name,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("counter_resets_total", 1);
+    }
+}
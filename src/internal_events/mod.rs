@@ -3,11 +3,19 @@ use std::borrow::Cow;
 mod adaptive_concurrency;
 mod add_fields;
 mod add_tags;
+#[cfg(feature = "transforms-aggregate")]
+mod aggregate;
+#[cfg(feature = "transforms-anomaly_detection")]
+mod anomaly_detection;
 mod ansi_stripper;
 #[cfg(feature = "sources-apache_metrics")]
 mod apache_metrics;
 #[cfg(feature = "api")]
 mod api;
+#[cfg(feature = "transforms-assemble")]
+mod assemble;
+#[cfg(all(target_os = "linux", feature = "sources-auditd"))]
+mod auditd;
 #[cfg(feature = "transforms-aws_cloudwatch_logs_subscription_parser")]
 mod aws_cloudwatch_logs_subscription_parser;
 #[cfg(feature = "transforms-aws_ec2_metadata")]
@@ -23,18 +31,28 @@ pub(crate) mod aws_s3;
 #[cfg(feature = "sinks-aws_sqs")]
 mod aws_sqs;
 mod blackhole;
+#[cfg(feature = "transforms-byte_attribution")]
+mod byte_attribution;
+mod cluster;
 #[cfg(feature = "transforms-coercer")]
 mod coercer;
 #[cfg(feature = "transforms-concat")]
 mod concat;
 #[cfg(feature = "sinks-console")]
 mod console;
+mod crash_report;
 #[cfg(feature = "transforms-dedupe")]
 mod dedupe;
 #[cfg(feature = "sources-docker_logs")]
 mod docker_logs;
+#[cfg(all(target_os = "linux", feature = "sources-ebpf"))]
+mod ebpf;
 mod elasticsearch;
 mod encoding_transcode;
+#[cfg(feature = "sinks-exec")]
+mod exec;
+#[cfg(feature = "transforms-explode")]
+mod explode;
 #[cfg(feature = "transforms-filter")]
 mod filter;
 #[cfg(feature = "sources-generator")]
@@ -43,11 +61,15 @@ mod generator;
 mod geoip;
 #[cfg(feature = "transforms-grok_parser")]
 mod grok_parser;
+#[cfg(feature = "sources-haproxy_metrics")]
+mod haproxy_metrics;
 mod heartbeat;
 #[cfg(feature = "sources-host_metrics")]
 mod host_metrics;
 mod http;
 pub mod http_client;
+#[cfg(any(feature = "sources-utils-http", feature = "sources-splunk_hec"))]
+mod http_server;
 #[cfg(all(unix, feature = "sources-journald"))]
 mod journald;
 #[cfg(feature = "transforms-json_parser")]
@@ -58,6 +80,8 @@ mod kafka;
 mod key_value_parser;
 #[cfg(feature = "sources-kubernetes-logs")]
 mod kubernetes_logs;
+#[cfg(feature = "transforms-log_patterns")]
+mod log_patterns;
 #[cfg(feature = "transforms-log_to_metric")]
 mod log_to_metric;
 #[cfg(feature = "transforms-logfmt_parser")]
@@ -65,8 +89,14 @@ mod logfmt_parser;
 mod logplex;
 #[cfg(feature = "transforms-lua")]
 mod lua;
+#[cfg(all(target_os = "macos", feature = "sources-macos_unified_log"))]
+mod macos_unified_log;
+#[cfg(feature = "transforms-metric_kind")]
+mod metric_kind;
 #[cfg(feature = "transforms-metric_to_log")]
 mod metric_to_log;
+#[cfg(feature = "sources-mongodb_change_stream")]
+mod mongodb_change_stream;
 #[cfg(feature = "sources-mongodb_metrics")]
 mod mongodb_metrics;
 #[cfg(feature = "sinks-nats")]
@@ -74,6 +104,14 @@ mod nats;
 #[cfg(feature = "sources-nginx_metrics")]
 mod nginx_metrics;
 mod open;
+#[cfg(feature = "sources-opentelemetry")]
+mod opentelemetry;
+#[cfg(feature = "sources-osquery")]
+mod osquery;
+#[cfg(feature = "sinks-pagerduty")]
+mod pagerduty;
+#[cfg(feature = "sources-postgresql_cdc")]
+mod postgresql_cdc;
 #[cfg(feature = "sources-postgresql_metrics")]
 mod postgresql_metrics;
 mod process;
@@ -92,12 +130,20 @@ mod rename_fields;
 #[cfg(feature = "transforms-route")]
 mod route;
 mod sample;
+#[cfg(feature = "transforms-shard")]
+mod shard;
 #[cfg(feature = "sinks-sematext")]
 mod sematext_metrics;
+#[cfg(feature = "sinks-sentry")]
+mod sentry;
 mod socket;
 mod split;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 mod splunk_hec;
+#[cfg(feature = "sources-sql_query")]
+mod sql_query;
+#[cfg(feature = "transforms-sql")]
+mod sql;
 #[cfg(feature = "sinks-statsd")]
 mod statsd_sink;
 #[cfg(feature = "sources-statsd")]
@@ -107,8 +153,12 @@ mod syslog;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 mod tag_cardinality_limit;
 mod tcp;
+#[cfg(feature = "transforms-throttle")]
+mod throttle;
 #[cfg(feature = "transforms-tokenizer")]
 mod tokenizer;
+#[cfg(feature = "transforms-trace_sampling")]
+mod trace_sampling;
 mod topology;
 mod udp;
 mod unix;
@@ -121,11 +171,19 @@ pub mod kubernetes;
 pub use self::adaptive_concurrency::*;
 pub use self::add_fields::*;
 pub use self::add_tags::*;
+#[cfg(feature = "transforms-aggregate")]
+pub(crate) use self::aggregate::*;
+#[cfg(feature = "transforms-anomaly_detection")]
+pub(crate) use self::anomaly_detection::*;
 pub use self::ansi_stripper::*;
 #[cfg(feature = "sources-apache_metrics")]
 pub use self::apache_metrics::*;
 #[cfg(feature = "api")]
 pub use self::api::*;
+#[cfg(feature = "transforms-assemble")]
+pub(crate) use self::assemble::*;
+#[cfg(all(target_os = "linux", feature = "sources-auditd"))]
+pub(crate) use self::auditd::*;
 #[cfg(feature = "transforms-aws_cloudwatch_logs_subscription_parser")]
 pub(crate) use self::aws_cloudwatch_logs_subscription_parser::*;
 #[cfg(feature = "transforms-aws_ec2_metadata")]
@@ -139,21 +197,32 @@ pub use self::aws_kinesis_streams::*;
 #[cfg(feature = "sinks-aws_sqs")]
 pub use self::aws_sqs::*;
 pub use self::blackhole::*;
+#[cfg(feature = "transforms-byte_attribution")]
+pub(crate) use self::byte_attribution::*;
+pub use self::cluster::*;
 #[cfg(feature = "transforms-coercer")]
 pub(crate) use self::coercer::*;
 #[cfg(feature = "transforms-concat")]
 pub use self::concat::*;
 #[cfg(feature = "sinks-console")]
 pub use self::console::*;
+pub use self::crash_report::*;
 #[cfg(feature = "transforms-dedupe")]
 pub(crate) use self::dedupe::*;
 #[cfg(feature = "sources-docker_logs")]
 pub use self::docker_logs::*;
+#[cfg(all(target_os = "linux", feature = "sources-ebpf"))]
+pub(crate) use self::ebpf::*;
 pub use self::elasticsearch::*;
 pub use self::encoding_transcode::*;
+#[cfg(feature = "sinks-exec")]
+pub use self::exec::*;
+#[cfg(feature = "transforms-explode")]
+pub(crate) use self::explode::*;
 #[cfg(any(
     feature = "sources-file",
     feature = "sources-kubernetes-logs",
+    feature = "sources-osquery",
     feature = "sinks-file",
 ))]
 pub use self::file::*;
@@ -165,11 +234,15 @@ pub use self::generator::*;
 pub(crate) use self::geoip::*;
 #[cfg(feature = "transforms-grok_parser")]
 pub(crate) use self::grok_parser::*;
+#[cfg(feature = "sources-haproxy_metrics")]
+pub(crate) use self::haproxy_metrics::*;
 pub use self::heartbeat::*;
 #[cfg(feature = "sources-host_metrics")]
 pub(crate) use self::host_metrics::*;
 #[cfg(any(feature = "sources-utils-http", feature = "sinks-http"))]
 pub(crate) use self::http::*;
+#[cfg(any(feature = "sources-utils-http", feature = "sources-splunk_hec"))]
+pub(crate) use self::http_server::*;
 #[cfg(all(unix, feature = "sources-journald"))]
 pub(crate) use self::journald::*;
 #[cfg(feature = "transforms-json_parser")]
@@ -181,19 +254,35 @@ pub(crate) use self::key_value_parser::*;
 #[cfg(feature = "sources-kubernetes-logs")]
 pub use self::kubernetes_logs::*;
 #[cfg(feature = "transforms-log_to_metric")]
+#[cfg(feature = "transforms-log_patterns")]
+pub(crate) use self::log_patterns::*;
 pub(crate) use self::log_to_metric::*;
 #[cfg(feature = "transforms-logfmt_parser")]
 pub use self::logfmt_parser::*;
 pub use self::logplex::*;
 #[cfg(feature = "transforms-lua")]
 pub use self::lua::*;
+#[cfg(all(target_os = "macos", feature = "sources-macos_unified_log"))]
+pub(crate) use self::macos_unified_log::*;
+#[cfg(feature = "transforms-metric_kind")]
+pub(crate) use self::metric_kind::*;
 #[cfg(feature = "transforms-metric_to_log")]
 pub(crate) use self::metric_to_log::*;
+#[cfg(feature = "sources-mongodb_change_stream")]
+pub(crate) use self::mongodb_change_stream::*;
 #[cfg(feature = "sinks-nats")]
 pub use self::nats::*;
 #[cfg(feature = "sources-nginx_metrics")]
 pub(crate) use self::nginx_metrics::*;
 pub use self::open::*;
+#[cfg(feature = "sources-opentelemetry")]
+pub(crate) use self::opentelemetry::*;
+#[cfg(feature = "sources-osquery")]
+pub(crate) use self::osquery::*;
+#[cfg(feature = "sinks-pagerduty")]
+pub(crate) use self::pagerduty::*;
+#[cfg(feature = "sources-postgresql_cdc")]
+pub(crate) use self::postgresql_cdc::*;
 #[cfg(feature = "sources-postgresql_metrics")]
 pub(crate) use self::postgresql_metrics::*;
 pub use self::process::*;
@@ -212,12 +301,20 @@ pub use self::rename_fields::*;
 #[cfg(feature = "transforms-route")]
 pub use self::route::*;
 pub use self::sample::*;
+#[cfg(feature = "transforms-shard")]
+pub use self::shard::*;
 #[cfg(feature = "sinks-sematext")]
 pub use self::sematext_metrics::*;
+#[cfg(feature = "sinks-sentry")]
+pub(crate) use self::sentry::*;
 pub(crate) use self::socket::*;
 pub use self::split::*;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 pub(crate) use self::splunk_hec::*;
+#[cfg(feature = "sources-sql_query")]
+pub(crate) use self::sql_query::*;
+#[cfg(feature = "transforms-sql")]
+pub(crate) use self::sql::*;
 #[cfg(feature = "sinks-statsd")]
 pub use self::statsd_sink::*;
 #[cfg(feature = "sources-statsd")]
@@ -227,8 +324,12 @@ pub use self::syslog::*;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub(crate) use self::tag_cardinality_limit::*;
 pub use self::tcp::*;
+#[cfg(feature = "transforms-throttle")]
+pub(crate) use self::throttle::*;
 #[cfg(feature = "transforms-tokenizer")]
 pub(crate) use self::tokenizer::*;
+#[cfg(feature = "transforms-trace_sampling")]
+pub(crate) use self::trace_sampling::*;
 pub use self::topology::*;
 pub use self::udp::*;
 pub use self::unix::*;
@@ -261,6 +362,7 @@ macro_rules! emit {
 #[cfg(any(
     feature = "sources-file",
     feature = "sources-kubernetes-logs",
+    feature = "sources-osquery",
     feature = "sinks-file",
 ))]
 mod file;
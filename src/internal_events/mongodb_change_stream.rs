@@ -0,0 +1,47 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct MongoDBChangeStreamEventsReceived {
+    pub count: usize,
+}
+
+impl InternalEvent for MongoDBChangeStreamEventsReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received changes.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct MongoDBChangeStreamRequestError {
+    pub error: String,
+}
+
+impl InternalEvent for MongoDBChangeStreamRequestError {
+    fn emit_logs(&self) {
+        error!(message = "MongoDB request error.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("request_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct MongoDBChangeStreamCheckpointWriteFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for MongoDBChangeStreamCheckpointWriteFailed {
+    fn emit_logs(&self) {
+        error!(message = "Failed writing checkpoint.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("checkpoint_write_errors_total", 1);
+    }
+}
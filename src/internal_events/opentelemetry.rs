@@ -0,0 +1,45 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct OpenTelemetryEventsReceived {
+    pub signal: &'static str,
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for OpenTelemetryEventsReceived {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Received events.",
+            signal = self.signal,
+            count = self.count
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", self.count as u64, "signal" => self.signal);
+        counter!("processed_bytes_total", self.byte_size as u64, "signal" => self.signal);
+    }
+}
+
+#[derive(Debug)]
+pub struct OpenTelemetryRequestError<'a> {
+    pub path: &'a str,
+    pub error: &'a str,
+}
+
+impl<'a> InternalEvent for OpenTelemetryRequestError<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Rejected OTLP request.",
+            path = %self.path,
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("request_errors_total", 1);
+    }
+}
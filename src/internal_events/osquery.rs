@@ -0,0 +1,56 @@
+use super::InternalEvent;
+use crate::sources::osquery::ParseError;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct OsqueryEventsReceived<'a> {
+    pub file: &'a str,
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for OsqueryEventsReceived<'_> {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Received query results.",
+            file = %self.file,
+            count = %self.count,
+            byte_size = %self.byte_size,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "processed_events_total", self.count as u64,
+            "file" => self.file.to_owned(),
+        );
+        counter!(
+            "processed_bytes_total", self.byte_size as u64,
+            "file" => self.file.to_owned(),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct OsqueryParseError<'a> {
+    pub file: &'a str,
+    pub error: ParseError,
+}
+
+impl InternalEvent for OsqueryParseError<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed parsing query results.",
+            file = %self.file,
+            error = %self.error,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "parse_errors_total", 1,
+            "file" => self.file.to_owned(),
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct PagerDutyEventMissingKeys<'a> {
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for PagerDutyEventMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Keys do not exist on the event; dropping event.",
+            missing_keys = ?self.keys,
+            internal_log_rate_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct PagerDutyEventMissingDedupKey<'a> {
+    pub action: &'a str,
+}
+
+impl<'a> InternalEvent for PagerDutyEventMissingDedupKey<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Alert has no dedup key; dropping event.",
+            action = %self.action,
+            internal_log_rate_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1, "reason" => "missing_dedup_key");
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct PagerDutyEventThrottled<'a> {
+    pub dedup_key: &'a str,
+}
+
+impl<'a> InternalEvent for PagerDutyEventThrottled<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Alert was triggered recently; discarding event.",
+            dedup_key = %self.dedup_key,
+            internal_log_rate_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1, "reason" => "throttled");
+    }
+}
@@ -0,0 +1,70 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct PostgresqlCdcChangesReceived {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for PostgresqlCdcChangesReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received changes.", count = %self.count, byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", self.count as u64);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct PostgresqlCdcQueryFailed {
+    pub error: String,
+}
+
+impl InternalEvent for PostgresqlCdcQueryFailed {
+    fn emit_logs(&self) {
+        error!(message = "PostgreSQL query error.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("request_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct PostgresqlCdcDecodeFailed<'a> {
+    pub error: String,
+    pub lsn: &'a str,
+}
+
+impl<'a> InternalEvent for PostgresqlCdcDecodeFailed<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Invalid logical replication message, discarding.",
+            error = %self.error,
+            lsn = %self.lsn,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("invalid_record_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct PostgresqlCdcCheckpointWriteFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for PostgresqlCdcCheckpointWriteFailed {
+    fn emit_logs(&self) {
+        error!(message = "Failed writing checkpoint.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("checkpoint_write_errors_total", 1);
+    }
+}
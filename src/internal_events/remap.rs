@@ -30,6 +30,31 @@ impl InternalEvent for RemapMappingError {
     }
 }
 
+#[derive(Debug, Copy, Clone)]
+pub struct RemapMappingAbort {
+    /// If set to true, the remap transform has dropped the event after an
+    /// aborted mapping.
+    pub event_dropped: bool,
+}
+
+impl InternalEvent for RemapMappingAbort {
+    fn emit_logs(&self) {
+        let message = if self.event_dropped {
+            "Mapping aborted with event; discarding event."
+        } else {
+            "Mapping aborted with event."
+        };
+
+        debug!(message, internal_log_rate_secs = 30)
+    }
+
+    fn emit_metrics(&self) {
+        if self.event_dropped {
+            counter!("events_discarded_total", 1);
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct RemapConditionExecutionError;
 
@@ -0,0 +1,21 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct SentryEventRateLimited<'a> {
+    pub key: &'a str,
+}
+
+impl<'a> InternalEvent for SentryEventRateLimited<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Rate limit of key exceeded; discarding event.",
+            key = %self.key,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1, "reason" => "rate_limited");
+    }
+}
@@ -0,0 +1,21 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct ShardTemplateRenderingError {
+    pub fields: Vec<String>,
+}
+
+impl InternalEvent for ShardTemplateRenderingError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to render the key template; passing the event through unsharded.",
+            fields = ?self.fields,
+            internal_log_rate_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1);
+    }
+}
@@ -1,5 +1,6 @@
 use super::InternalEvent;
 use metrics::counter;
+use std::net::SocketAddr;
 
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum SocketMode {
@@ -68,3 +69,24 @@ impl InternalEvent for SocketReceiveError {
         counter!("connection_errors_total", 1, "mode" => self.mode.as_str());
     }
 }
+
+#[derive(Debug)]
+pub(crate) struct SocketOriginRejected {
+    pub mode: SocketMode,
+    pub peer_addr: SocketAddr,
+}
+
+impl InternalEvent for SocketOriginRejected {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Rejected peer not permitted by the origin options.",
+            peer_addr = %self.peer_addr,
+            mode = %self.mode.as_str(),
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connections_rejected_total", 1, "mode" => self.mode.as_str());
+    }
+}
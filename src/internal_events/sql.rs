@@ -0,0 +1,26 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct SqlEventDiscarded;
+
+impl InternalEvent for SqlEventDiscarded {
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SqlWindowFlushed {
+    pub groups: usize,
+}
+
+impl InternalEvent for SqlWindowFlushed {
+    fn emit_logs(&self) {
+        trace!(message = "Window flushed.", groups = self.groups);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("windows_flushed_total", 1);
+    }
+}
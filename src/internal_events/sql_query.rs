@@ -0,0 +1,47 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct SqlQueryEventsReceived {
+    pub count: usize,
+}
+
+impl InternalEvent for SqlQueryEventsReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received rows.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SqlQueryFailed {
+    pub error: String,
+}
+
+impl InternalEvent for SqlQueryFailed {
+    fn emit_logs(&self) {
+        error!(message = "SQL query error.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("request_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SqlQueryCheckpointWriteFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for SqlQueryCheckpointWriteFailed {
+    fn emit_logs(&self) {
+        error!(message = "Failed writing checkpoint.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("checkpoint_write_errors_total", 1);
+    }
+}
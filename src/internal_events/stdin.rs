@@ -31,3 +31,36 @@ impl InternalEvent for StdinReadFailed {
         counter!("stdin_reads_failed_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct StdinDecodeFailed<E> {
+    pub error: E,
+    /// Whether an event describing the error was sent to the `decode_errors`
+    /// output.
+    pub forwarded: bool,
+}
+
+impl<E> InternalEvent for StdinDecodeFailed<E>
+where
+    E: std::error::Error,
+{
+    fn emit_logs(&self) {
+        if self.forwarded {
+            warn!(
+                message = "Unable to decode frame, forwarding the error.",
+                error = %self.error,
+                internal_log_rate_secs = 10
+            );
+        } else {
+            warn!(
+                message = "Unable to decode frame, discarding it.",
+                error = %self.error,
+                internal_log_rate_secs = 10
+            );
+        }
+    }
+
+    fn emit_metrics(&self) {
+        counter!("decode_errors_total", 1);
+    }
+}
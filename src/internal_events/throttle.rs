@@ -0,0 +1,45 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct ThrottleEventDiscarded<'a> {
+    pub key: &'a str,
+    pub emit_events_discarded_per_key: bool,
+}
+
+impl<'a> InternalEvent for ThrottleEventDiscarded<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Rate limit exceeded; discarding event.",
+            key = %self.key,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        if self.emit_events_discarded_per_key {
+            counter!("events_discarded_total", 1, "key" => self.key.to_owned());
+        } else {
+            counter!("events_discarded_total", 1);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ThrottleKeyRenderingError {
+    pub error: String,
+}
+
+impl InternalEvent for ThrottleKeyRenderingError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to render the key; passing the event through unthrottled.",
+            error = %self.error,
+            internal_log_rate_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "render_error");
+    }
+}
@@ -1,5 +1,5 @@
 use super::InternalEvent;
-use metrics::counter;
+use metrics::{counter, gauge};
 
 #[derive(Debug)]
 pub struct EventProcessed;
@@ -9,3 +9,212 @@ impl InternalEvent for EventProcessed {
         counter!("processed_events_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct SinkHealthcheckCompleted {
+    pub healthy: bool,
+}
+
+impl InternalEvent for SinkHealthcheckCompleted {
+    fn emit_metrics(&self) {
+        gauge!("healthcheck_healthy", if self.healthy { 1.0 } else { 0.0 });
+        if !self.healthy {
+            counter!("healthcheck_failures_total", 1);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SourceEventFiltered;
+
+impl InternalEvent for SourceEventFiltered {
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct SourceEventsShed {
+    pub count: u64,
+}
+
+impl InternalEvent for SourceEventsShed {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Downstream is saturated, shedding events.",
+            count = %self.count,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_shed_total", self.count);
+    }
+}
+
+/// A source acknowledges events its sinks failed to deliver, as retrying them
+/// would fail again.
+#[derive(Debug)]
+pub struct SourceEventDeliveryFailed;
+
+impl InternalEvent for SourceEventDeliveryFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Sinks failed to deliver an event; acknowledging it anyway as retrying would fail again.",
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_delivery_failed_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct BufferEventDropped {
+    pub priority: &'static str,
+}
+
+impl InternalEvent for BufferEventDropped {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Buffer is filling up, dropping event.",
+            priority = %self.priority,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("buffer_events_dropped_total", 1, "priority" => self.priority);
+    }
+}
+
+#[derive(Debug)]
+pub struct SinkQuotaPeriodStarted {
+    pub max_bytes: Option<u64>,
+}
+
+impl InternalEvent for SinkQuotaPeriodStarted {
+    fn emit_logs(&self) {
+        debug!(message = "Byte quota period started.", max_bytes = ?self.max_bytes);
+    }
+
+    fn emit_metrics(&self) {
+        gauge!("quota_used_bytes", 0.0);
+        gauge!("quota_exceeded", 0.0);
+        if let Some(max_bytes) = self.max_bytes {
+            gauge!("quota_limit_bytes", max_bytes as f64);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SinkQuotaBytesSent {
+    pub byte_size: u64,
+    pub used_bytes: u64,
+}
+
+impl InternalEvent for SinkQuotaBytesSent {
+    fn emit_metrics(&self) {
+        counter!("quota_sent_bytes_total", self.byte_size);
+        gauge!("quota_used_bytes", self.used_bytes as f64);
+    }
+}
+
+#[derive(Debug)]
+pub struct SinkQuotaExceeded {
+    pub action: &'static str,
+}
+
+impl InternalEvent for SinkQuotaExceeded {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Byte quota exceeded.",
+            action = %self.action,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        gauge!("quota_exceeded", 1.0);
+        counter!("quota_exceeded_events_total", 1, "action" => self.action);
+    }
+}
+
+#[derive(Debug)]
+pub struct DiskBufferUsage {
+    pub events: usize,
+    pub byte_size: usize,
+    /// The space available on the file system holding the buffer, if known.
+    pub available_space: Option<u64>,
+}
+
+impl InternalEvent for DiskBufferUsage {
+    fn emit_metrics(&self) {
+        gauge!("buffer_events", self.events as f64);
+        gauge!("buffer_byte_size", self.byte_size as f64);
+        if let Some(available_space) = self.available_space {
+            gauge!("data_dir_available_bytes", available_space as f64);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DiskBufferLowSpace<'a> {
+    pub data_dir: &'a std::path::Path,
+    pub available: u64,
+    pub min_free_space: u64,
+}
+
+impl<'a> InternalEvent for DiskBufferLowSpace<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Disk buffer is low on space, handling it as full.",
+            data_dir = ?self.data_dir,
+            available = %self.available,
+            min_free_space = %self.min_free_space,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        gauge!("data_dir_available_bytes", self.available as f64);
+        counter!("buffer_low_space_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct DiskBufferRecordCorrupted {
+    pub segment: u64,
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for DiskBufferRecordCorrupted {
+    fn emit_logs(&self) {
+        error!(
+            message = "Disk buffer segment is corrupted, dropping its remaining records.",
+            segment = %self.segment,
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("buffer_corrupted_records_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct MetricSeriesExpired {
+    pub count: usize,
+}
+
+impl InternalEvent for MetricSeriesExpired {
+    fn emit_logs(&self) {
+        debug!(message = "Metric series expired.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("expired_metric_series_total", self.count as u64);
+    }
+}
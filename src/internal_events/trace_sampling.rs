@@ -0,0 +1,60 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct TraceSamplingDecision {
+    pub policy: &'static str,
+    pub keep: bool,
+    pub spans: usize,
+}
+
+impl InternalEvent for TraceSamplingDecision {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Sampling decision made.",
+            policy = self.policy,
+            keep = self.keep,
+            spans = self.spans
+        );
+    }
+
+    fn emit_metrics(&self) {
+        let decision = if self.keep { "keep" } else { "drop" };
+        counter!(
+            "trace_sampling_decisions_total", 1,
+            "policy" => self.policy,
+            "decision" => decision,
+        );
+        if !self.keep {
+            counter!("events_discarded_total", self.spans as u64);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TraceSamplingBufferFull {
+    pub max_traces: usize,
+}
+
+impl InternalEvent for TraceSamplingBufferFull {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Trace buffer is full, deciding on the oldest trace early.",
+            max_traces = self.max_traces,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("trace_sampling_early_decisions_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TraceSamplingLateSpanDropped;
+
+impl InternalEvent for TraceSamplingLateSpanDropped {
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
@@ -68,3 +68,24 @@ impl InternalEvent for UdpSendIncomplete {
         counter!("connection_send_errors_total", 1, "mode" => "udp");
     }
 }
+
+#[derive(Debug)]
+pub struct UdpReceiveDrops {
+    pub port: u16,
+    pub count: u64,
+}
+
+impl InternalEvent for UdpReceiveDrops {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Datagrams were dropped by the kernel, the socket receive buffer is full.",
+            port = %self.port,
+            count = %self.count,
+            internal_log_rate_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("udp_receive_drops_total", self.count, "port" => self.port.to_string());
+    }
+}
@@ -27,8 +27,13 @@ static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 #[macro_use]
 pub mod config;
 pub mod buffers;
+#[cfg(feature = "sources-file")]
+pub mod checkpoints;
 pub mod cli;
+pub mod cluster;
 pub mod conditions;
+pub mod convert_config;
+pub mod crash_report;
 pub mod dns;
 pub mod event;
 pub mod expiring_hash_map;
@@ -42,6 +47,9 @@ pub mod api;
 pub mod app;
 pub mod async_read;
 pub mod encoding_transcode;
+#[cfg(feature = "transforms-remap")]
+pub mod encryption;
+pub mod enrichment_tables;
 pub mod heartbeat;
 pub mod http;
 #[cfg(feature = "rdkafka")]
@@ -51,6 +59,9 @@ pub mod line_agg;
 pub mod list;
 pub mod mapping;
 pub mod metrics;
+#[cfg(any(feature = "sinks-opentelemetry", feature = "sources-opentelemetry"))]
+pub(crate) mod opentelemetry;
+pub mod origin;
 pub(crate) mod pipeline;
 #[cfg(any(feature = "sinks-prometheus", feature = "sources-prometheus"))]
 pub(crate) mod prometheus;
@@ -63,7 +74,10 @@ pub mod signal;
 pub mod sink;
 pub mod sinks;
 pub mod sources;
+pub mod state;
 pub mod stream;
+#[cfg(feature = "api-client")]
+pub mod tap;
 pub mod tcp;
 pub mod template;
 pub mod test_util;
@@ -83,7 +97,7 @@ pub mod validate;
 pub mod vector_windows;
 
 pub use event::{Event, Value};
-pub use pipeline::Pipeline;
+pub use pipeline::{BackpressurePolicy, Pipeline};
 
 pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
 
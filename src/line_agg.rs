@@ -160,7 +160,8 @@ where
 {
     /// `K` - file name, or other line source,
     /// `Bytes` - the line data,
-    /// `C` - the context related the the line data.
+    /// `C` - the context related the the line data, the one of the last line
+    /// for aggregated lines.
     type Item = (K, Bytes, C);
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
@@ -299,7 +300,7 @@ where
                     Mode::ContinueThrough => {
                         if condition_matched {
                             let buffered = entry.get_mut();
-                            buffered.add_next_line(line);
+                            buffered.add_next_line(line, context);
                             None
                         } else {
                             let (src, buffered) = entry.remove_entry();
@@ -311,11 +312,11 @@ where
                     Mode::ContinuePast => {
                         if condition_matched {
                             let buffered = entry.get_mut();
-                            buffered.add_next_line(line);
+                            buffered.add_next_line(line, context);
                             None
                         } else {
                             let (src, mut buffered) = entry.remove_entry();
-                            buffered.add_next_line(line);
+                            buffered.add_next_line(line, context);
                             Some((src, Emit::One(buffered.merge())))
                         }
                     }
@@ -327,7 +328,7 @@ where
                             Some((src, Emit::Two(buffered.merge(), (line, context))))
                         } else {
                             let buffered = entry.get_mut();
-                            buffered.add_next_line(line);
+                            buffered.add_next_line(line, context);
                             None
                         }
                     }
@@ -336,11 +337,11 @@ where
                     Mode::HaltWith => {
                         if condition_matched {
                             let (src, mut buffered) = entry.remove_entry();
-                            buffered.add_next_line(line);
+                            buffered.add_next_line(line, context);
                             Some((src, Emit::One(buffered.merge())))
                         } else {
                             let buffered = entry.get_mut();
-                            buffered.add_next_line(line);
+                            buffered.add_next_line(line, context);
                             None
                         }
                     }
@@ -366,6 +367,7 @@ where
 
 struct Aggregate<C> {
     lines: Vec<Bytes>,
+    /// The context of the last line.
     context: C,
 }
 
@@ -377,8 +379,9 @@ impl<C> Aggregate<C> {
         }
     }
 
-    fn add_next_line(&mut self, line: Bytes) {
+    fn add_next_line(&mut self, line: Bytes, context: C) {
         self.lines.push(line);
+        self.context = context;
     }
 
     fn merge(self) -> (Bytes, C) {
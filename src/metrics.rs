@@ -1,4 +1,4 @@
-use crate::{event::Metric, Event};
+use crate::{event::Metric, trace, Event};
 use dashmap::DashMap;
 use metrics::{GaugeValue, Key, KeyData, Label, Recorder, SharedString, Unit};
 use metrics_tracing_context::{LabelFilter, TracingContextLayer};
@@ -231,10 +231,27 @@ fn snapshot(controller: &Controller) -> Vec<Event> {
         .registry
         .map
         .iter()
-        .map(|valref| Metric::from_metric_kv(valref.key().key(), valref.value()).into())
+        .map(|valref| {
+            let mut metric = Metric::from_metric_kv(valref.key().key(), valref.value());
+            add_component_labels(&mut metric);
+            metric.into()
+        })
         .collect()
 }
 
+/// Adds the telemetry labels of the component a metric was recorded on
+/// behalf of to its tags, which take precedence.
+fn add_component_labels(metric: &mut Metric) {
+    let labels = metric
+        .tag_value("component_name")
+        .and_then(|name| trace::component_labels(&name));
+    if let (Some(labels), Some(tags)) = (labels, metric.tags_mut()) {
+        for (key, value) in labels {
+            tags.entry(key).or_insert(value);
+        }
+    }
+}
+
 /// Clear all metrics from the registry.
 pub fn reset(controller: &Controller) {
     controller.registry.map.clear()
@@ -0,0 +1,17 @@
+//! The parts of the OpenTelemetry protocol (OTLP) shared by the
+//! `opentelemetry` source and sink.
+
+pub mod proto {
+    include!(concat!(env!("OUT_DIR"), "/opentelemetry.rs"));
+}
+
+/// The gRPC methods of the OTLP services, which are also the paths of their
+/// HTTP/2 requests.
+pub const GRPC_LOGS_PATH: &str = "/opentelemetry.proto.collector.logs.v1.LogsService/Export";
+pub const GRPC_METRICS_PATH: &str =
+    "/opentelemetry.proto.collector.metrics.v1.MetricsService/Export";
+pub const GRPC_TRACES_PATH: &str = "/opentelemetry.proto.collector.trace.v1.TraceService/Export";
+
+/// The length of the prefix of gRPC messages: a compression flag, then the
+/// length of the message as a big-endian `u32`.
+pub const GRPC_MESSAGE_PREFIX_LEN: usize = 5;
@@ -0,0 +1,129 @@
+//! Restricting the peers the listening sources accept connections and
+//! datagrams from, for hosts where the firewall can't be configured.
+
+use crate::internal_events::{SocketMode, SocketOriginRejected};
+use cidr_utils::cidr::IpCidr;
+use std::{
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+};
+
+/// The peers accepted by a source, built from its `permit_origin` and
+/// `deny_origin` options. Every peer is accepted by default.
+#[derive(Debug, Clone, Default)]
+pub struct OriginFilter {
+    permit: Option<Vec<IpCidr>>,
+    deny: Vec<IpCidr>,
+}
+
+impl OriginFilter {
+    pub fn new(
+        permit_origin: &Option<Vec<String>>,
+        deny_origin: &Option<Vec<String>>,
+    ) -> crate::Result<Self> {
+        Ok(Self {
+            permit: permit_origin.as_deref().map(parse).transpose()?,
+            deny: deny_origin
+                .as_deref()
+                .map(parse)
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Whether `ip` is accepted, a denied address being rejected even when
+    /// it's also permitted.
+    pub fn permits(&self, ip: IpAddr) -> bool {
+        let ip = unmap(ip);
+        !self.deny.iter().any(|cidr| cidr.contains(ip))
+            && self
+                .permit
+                .as_ref()
+                .map_or(true, |permit| permit.iter().any(|cidr| cidr.contains(ip)))
+    }
+
+    /// Whether the peer is accepted, emitting an event when it's rejected.
+    pub(crate) fn check(&self, peer_addr: SocketAddr, mode: SocketMode) -> bool {
+        let permitted = self.permits(peer_addr.ip());
+        if !permitted {
+            emit!(SocketOriginRejected { peer_addr, mode });
+        }
+        permitted
+    }
+}
+
+fn parse(cidrs: &[String]) -> crate::Result<Vec<IpCidr>> {
+    cidrs
+        .iter()
+        .map(|cidr| {
+            IpCidr::from_str(cidr)
+                .map_err(|error| format!("Invalid IP CIDR {:?}: {}", cidr, error).into())
+        })
+        .collect()
+}
+
+/// The IPv4 address of an IPv4-mapped IPv6 address, as seen by sockets bound
+/// to both protocols.
+fn unmap(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => match v6.segments() {
+            [0, 0, 0, 0, 0, 0xffff, ..] => v6.to_ipv4().map_or(ip, IpAddr::V4),
+            _ => ip,
+        },
+        IpAddr::V4(_) => ip,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(permit: Option<&[&str]>, deny: Option<&[&str]>) -> OriginFilter {
+        let strings = |cidrs: &[&str]| {
+            cidrs
+                .iter()
+                .map(|cidr| cidr.to_string())
+                .collect::<Vec<_>>()
+        };
+        OriginFilter::new(&permit.map(strings), &deny.map(strings)).unwrap()
+    }
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    #[test]
+    fn permits_everything_by_default() {
+        let filter = filter(None, None);
+        assert!(filter.permits(ip("10.1.2.3")));
+        assert!(filter.permits(ip("2001:db8::1")));
+    }
+
+    #[test]
+    fn permit_origin() {
+        let filter = filter(Some(&["10.0.0.0/8", "2001:db8::/32"]), None);
+        assert!(filter.permits(ip("10.1.2.3")));
+        assert!(filter.permits(ip("2001:db8::1")));
+        assert!(!filter.permits(ip("192.168.1.1")));
+    }
+
+    #[test]
+    fn deny_origin_wins() {
+        let filter = filter(Some(&["10.0.0.0/8"]), Some(&["10.0.0.0/24"]));
+        assert!(filter.permits(ip("10.1.2.3")));
+        assert!(!filter.permits(ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn unmaps_ipv4_mapped_addresses() {
+        let filter = filter(Some(&["10.0.0.0/8"]), None);
+        assert!(filter.permits(ip("::ffff:10.1.2.3")));
+        assert!(!filter.permits(ip("::ffff:192.168.1.1")));
+    }
+
+    #[test]
+    fn rejects_invalid_cidrs() {
+        let permit = Some(vec!["10.0.0.0/33".to_owned()]);
+        assert!(OriginFilter::new(&permit, &None).is_err());
+    }
+}
@@ -1,6 +1,17 @@
-use crate::{transforms::FunctionTransform, Event};
+use crate::{
+    buffers::Priority,
+    event::{Event, EventStatus},
+    internal_events::SourceEventsShed,
+    transforms::FunctionTransform,
+};
 use futures::{task::Poll, Sink};
-use std::{collections::VecDeque, fmt, pin::Pin, task::Context};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    pin::Pin,
+    task::Context,
+};
 use tokio::sync::mpsc;
 
 #[derive(Debug)]
@@ -16,6 +27,23 @@ impl std::error::Error for ClosedError {}
 
 const MAX_ENQUEUED: usize = 1000;
 
+/// What a source does with its events while the components downstream of it
+/// can't keep up.
+#[derive(Deserialize, Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackpressurePolicy {
+    /// Wait for room downstream, slowing the source down.
+    Block,
+    /// Drop the events there is no room for.
+    Shed,
+}
+
+impl Default for BackpressurePolicy {
+    fn default() -> Self {
+        BackpressurePolicy::Block
+    }
+}
+
 #[derive(Derivative, Clone)]
 #[derivative(Debug)]
 pub struct Pipeline {
@@ -24,6 +52,10 @@ pub struct Pipeline {
     #[derivative(Debug = "ignore")]
     inlines: Vec<Box<dyn FunctionTransform>>,
     enqueued: VecDeque<Event>,
+    backpressure: BackpressurePolicy,
+    priority: Option<Priority>,
+    /// The outputs of the source besides this one, by name.
+    outputs: HashMap<&'static str, Pipeline>,
 }
 
 impl Pipeline {
@@ -62,20 +94,47 @@ impl Pipeline {
         }
         Poll::Ready(Ok(()))
     }
+
+    /// Sends `events` without waiting, dropping the ones the channel has no
+    /// room for. Those are errored, so that the sources waiting for their
+    /// delivery don't acknowledge them.
+    fn try_send_or_shed(&mut self, events: Vec<Event>) -> Result<(), ClosedError> {
+        use mpsc::error::TrySendError::*;
+
+        let mut shed = 0;
+        for event in events {
+            match self.inner.try_send(event) {
+                Ok(()) => {}
+                Err(Full(mut item)) => {
+                    item.take_finalizers().update_status(EventStatus::Errored);
+                    shed += 1;
+                }
+                Err(Closed(_item)) => return Err(ClosedError),
+            }
+        }
+        if shed > 0 {
+            emit!(SourceEventsShed { count: shed });
+        }
+        Ok(())
+    }
 }
 
 impl Sink<Event> for Pipeline {
     type Error = ClosedError;
 
     fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        if self.enqueued.len() < MAX_ENQUEUED {
+        if self.backpressure == BackpressurePolicy::Shed || self.enqueued.len() < MAX_ENQUEUED {
             Poll::Ready(Ok(()))
         } else {
             self.try_flush(cx)
         }
     }
 
-    fn start_send(mut self: Pin<&mut Self>, item: Event) -> Result<(), Self::Error> {
+    fn start_send(mut self: Pin<&mut Self>, mut item: Event) -> Result<(), Self::Error> {
+        if let Some(priority) = self.priority {
+            priority.set_default(&mut item);
+        }
+
         // Note how this gets **swapped** with `new_working_set` in the loop.
         // At the end of the loop, it will only contain finalized events.
         let mut working_set = vec![item];
@@ -86,7 +145,10 @@ impl Sink<Event> for Pipeline {
             }
             core::mem::swap(&mut new_working_set, &mut working_set);
         }
-        self.enqueued.extend(working_set);
+        match self.backpressure {
+            BackpressurePolicy::Block => self.enqueued.extend(working_set),
+            BackpressurePolicy::Shed => self.try_send_or_shed(working_set)?,
+        }
         Ok(())
     }
 
@@ -123,14 +185,40 @@ impl Pipeline {
             // We ensure the buffer is sufficient that it is unlikely to require reallocations.
             // There is a possibility a component might blow this queue size.
             enqueued: VecDeque::with_capacity(10),
+            backpressure: BackpressurePolicy::Block,
+            priority: None,
+            outputs: HashMap::new(),
         }
     }
+
+    pub fn with_backpressure(mut self, backpressure: BackpressurePolicy) -> Self {
+        self.backpressure = backpressure;
+        self
+    }
+
+    /// Gives the events sent without a priority this one.
+    pub fn with_priority(mut self, priority: Option<Priority>) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Gives the source the output `name`, sending its events to `output`.
+    pub fn with_output(mut self, name: &'static str, output: Pipeline) -> Self {
+        self.outputs.insert(name, output);
+        self
+    }
+
+    /// The output `name` of the source, if the topology gave it one.
+    pub fn output(&self, name: &str) -> Option<Pipeline> {
+        self.outputs.get(name).cloned()
+    }
 }
 
 #[cfg(all(test, feature = "transforms-add_fields", feature = "transforms-filter"))]
 mod test {
-    use super::Pipeline;
+    use super::{BackpressurePolicy, Pipeline};
     use crate::{
+        event::{BatchNotifier, BatchStatus},
         test_util::collect_ready,
         transforms::{add_fields::AddFields, filter::Filter},
         Event, Value,
@@ -198,4 +286,37 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn sheds_when_full() -> Result<(), crate::Error> {
+        let (pipeline, receiver) = Pipeline::new_with_buffer(2, vec![]);
+        let mut pipeline = pipeline.with_backpressure(BackpressurePolicy::Shed);
+
+        for _ in 0..5 {
+            pipeline.send(Event::from("MESSAGE_MARKER")).await?;
+        }
+        drop(pipeline);
+        let out = collect_ready(receiver).await;
+
+        assert_eq!(out.len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn errors_shed_events() -> Result<(), crate::Error> {
+        let (pipeline, _receiver) = Pipeline::new_with_buffer(1, vec![]);
+        let mut pipeline = pipeline.with_backpressure(BackpressurePolicy::Shed);
+
+        let (batch, status) = BatchNotifier::new_with_receiver();
+        pipeline.send(Event::from("MESSAGE_MARKER")).await?;
+        pipeline
+            .send(Event::from("MESSAGE_MARKER").with_batch_notifier(&batch))
+            .await?;
+        drop(batch);
+
+        assert_eq!(status.await, Ok(BatchStatus::Errored));
+
+        Ok(())
+    }
 }
@@ -3,7 +3,7 @@ use rusoto_core::Region;
 use serde::{Deserialize, Serialize};
 
 /// Configuration for configuring authentication strategy for AWS.
-#[derive(Serialize, Deserialize, Clone, Debug, Derivative)]
+#[derive(Serialize, Deserialize, Clone, Debug, Derivative, PartialEq, Eq, Hash)]
 #[derivative(Default)]
 #[serde(untagged)]
 #[serde(deny_unknown_fields)]
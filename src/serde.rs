@@ -1,5 +1,6 @@
 use indexmap::map::IndexMap;
 use serde::{de, Deserialize, Serialize};
+use std::convert::TryFrom;
 use std::fmt;
 use std::marker::PhantomData;
 
@@ -89,3 +90,99 @@ where
 
     deserializer.deserialize_any(BoolOrStruct(PhantomData))
 }
+
+/// Enables deserializing a number of bytes from either an integer or a
+/// string with a unit.
+/// Example:
+/// max_size = 5242880
+/// max_size = "5MiB"
+/// Both are accepted.
+pub fn bytes<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: TryFrom<u64>,
+{
+    let bytes = deserializer.deserialize_any(IntegerOrUnit {
+        expecting: "a number of bytes, or a size such as \"5MiB\"",
+        parse: |s| shared::units::parse_bytes(s).map_err(|error| error.to_string()),
+    })?;
+    T::try_from(bytes).map_err(|_| de::Error::custom("size is too large"))
+}
+
+/// Like [`bytes`], for optional fields which must also be `#[serde(default)]`.
+pub fn optional_bytes<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: TryFrom<u64>,
+{
+    bytes(deserializer).map(Some)
+}
+
+/// Enables deserializing a number of seconds from either an integer or a
+/// duration string.
+/// Example:
+/// timeout_secs = 90
+/// timeout_secs = "1m30s"
+/// Both are accepted.
+pub fn duration_secs<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: TryFrom<u64>,
+{
+    let secs = deserializer.deserialize_any(IntegerOrUnit {
+        expecting: "a number of seconds, or a duration such as \"30s\"",
+        parse: |s| {
+            let duration = shared::units::parse_duration(s).map_err(|error| error.to_string())?;
+            match duration.subsec_nanos() {
+                0 => Ok(duration.as_secs()),
+                _ => Err(format!("{:?} is not a whole number of seconds", s)),
+            }
+        },
+    })?;
+    T::try_from(secs).map_err(|_| de::Error::custom("duration is too long"))
+}
+
+/// Like [`duration_secs`], for optional fields which must also be
+/// `#[serde(default)]`.
+pub fn optional_duration_secs<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: TryFrom<u64>,
+{
+    duration_secs(deserializer).map(Some)
+}
+
+/// Visits an unsigned integer as is, or a string with a unit through `parse`.
+struct IntegerOrUnit {
+    expecting: &'static str,
+    parse: fn(&str) -> Result<u64, String>,
+}
+
+impl<'de> de::Visitor<'de> for IntegerOrUnit {
+    type Value = u64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.expecting)
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        Ok(value)
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        u64::try_from(value).map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        (self.parse)(value).map_err(E::custom)
+    }
+}
@@ -144,11 +144,13 @@ impl CloudWatchMetricsSvc {
         let svc = request.service(CloudWatchMetricsRetryLogic, cloudwatch_metrics);
 
         let buffer = PartitionBuffer::new(MetricsBuffer::new(batch.size));
-        let mut normalizer = MetricNormalizer::<AwsCloudwatchMetricNormalize>::default();
+        let mut normalizer = MetricNormalizer::<AwsCloudwatchMetricNormalize>::default()
+            .with_expiration(cx.expire_metrics());
 
         let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
             .sink_map_err(|error| error!(message = "Fatal CloudwatchMetrics sink error.", %error))
             .with_flat_map(move |event: Event| {
+                normalizer.expire();
                 stream::iter(normalizer.apply(event).map(|mut event| {
                     let namespace = event
                         .as_mut_metric()
@@ -484,6 +484,7 @@ mod tests {
             only_fields: None,
             except_fields: Some(vec!["key".into()]),
             timestamp_format: None,
+            remap: None,
         };
 
         let bytes = encode_event(event, &key_prefix, &encoding_config).unwrap();
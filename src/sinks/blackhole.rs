@@ -92,6 +92,7 @@ impl StreamSink for BlackholeSink {
             let message_len = match event {
                 Event::Log(log) => serde_json::to_string(&log),
                 Event::Metric(metric) => serde_json::to_string(&metric),
+                Event::Trace(trace) => serde_json::to_string(&trace),
             }
             .map(|v| v.len())
             .unwrap_or(0);
@@ -140,7 +140,7 @@ impl HttpSink for ClickhouseConfig {
         let mut request = builder.body(events).unwrap();
 
         if let Some(auth) = &self.auth {
-            auth.apply(&mut request);
+            auth.authorize(&mut request).await?;
         }
 
         Ok(request)
@@ -153,7 +153,7 @@ async fn healthcheck(client: HttpClient, config: ClickhouseConfig) -> crate::Res
     let mut request = Request::get(uri).body(Body::empty()).unwrap();
 
     if let Some(auth) = &config.auth {
-        auth.apply(&mut request);
+        auth.authorize(&mut request).await?;
     }
 
     let response = client.send(request).await?;
@@ -337,6 +337,7 @@ mod integration_tests {
         let host = String::from("http://localhost:8123");
         let encoding = EncodingConfigWithDefault {
             timestamp_format: Some(TimestampFormat::Unix),
+            remap: None,
             ..Default::default()
         };
 
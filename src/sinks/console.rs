@@ -121,6 +121,14 @@ fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option
                 .ok(),
             Encoding::Text => Some(format!("{}", metric)),
         },
+        Event::Trace(trace) => match encoding.codec() {
+            Encoding::Json => serde_json::to_string(&trace)
+                .map_err(|error| {
+                    error!(message = "Error encoding json.", %error);
+                })
+                .ok(),
+            Encoding::Text => trace.name(),
+        },
     }
 }
 
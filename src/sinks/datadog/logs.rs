@@ -7,9 +7,9 @@ use crate::{
             batch::{Batch, BatchError},
             encode_event,
             encoding::{EncodingConfig, EncodingConfiguration},
-            http::{BatchedHttpSink, HttpSink},
+            http::{HttpSink, PartitionHttpSink},
             BatchConfig, BatchSettings, BoxedRawValue, Compression, Encoding, JsonArrayBuffer,
-            TowerRequestConfig, VecBuffer,
+            PartitionBuffer, PartitionInnerBuffer, TowerRequestConfig, VecBuffer,
         },
         Healthcheck, VectorSink,
     },
@@ -22,7 +22,7 @@ use http::{Request, StatusCode};
 use hyper::body::Body;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::{io::Write, time::Duration};
+use std::{io::Write, sync::Arc, time::Duration};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -77,6 +77,17 @@ impl DatadogLogsConfig {
             })
     }
 
+    /// The API key of the events, which is the one of the agent that sent them
+    /// when they come from a `datadog_agent` source storing it.
+    fn api_key(&self, event: &Event) -> Arc<str> {
+        event
+            .as_log()
+            .metadata()
+            .datadog_api_key()
+            .cloned()
+            .unwrap_or_else(|| Arc::from(self.api_key.as_str()))
+    }
+
     fn batch_settings<T: Batch>(&self) -> Result<BatchSettings<T>, BatchError> {
         BatchSettings::default()
             .bytes(bytesize::kib(100u64))
@@ -85,7 +96,8 @@ impl DatadogLogsConfig {
             .parse_config(self.batch)
     }
 
-    /// Builds the required BatchedHttpSink.
+    /// Builds the required PartitionHttpSink, the events being batched by
+    /// their API key.
     /// Since the DataDog sink can create one of two different sinks, this
     /// extracts most of the shared functionality required to create either sink.
     fn build_sink<T, B, O>(
@@ -100,7 +112,10 @@ impl DatadogLogsConfig {
         B: Batch<Output = Vec<O>> + std::marker::Send + 'static,
         B::Output: std::marker::Send + Clone,
         B::Input: std::marker::Send,
-        T: HttpSink<Input = B::Input, Output = B::Output> + Clone,
+        T: HttpSink<
+                Input = PartitionInnerBuffer<B::Input, Arc<str>>,
+                Output = PartitionInnerBuffer<B::Output, Arc<str>>,
+            > + Clone,
     {
         let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
 
@@ -110,10 +125,15 @@ impl DatadogLogsConfig {
         )?;
 
         let client = HttpClient::new(tls_settings)?;
-        let healthcheck = healthcheck(service.clone(), client.clone()).boxed();
-        let sink = BatchedHttpSink::new(
+        let healthcheck = healthcheck(
+            service.clone(),
+            client.clone(),
+            Arc::from(self.api_key.as_str()),
+        )
+        .boxed();
+        let sink = PartitionHttpSink::new(
             service,
-            batch,
+            PartitionBuffer::new(batch),
             request_settings,
             timeout,
             client,
@@ -129,11 +149,12 @@ impl DatadogLogsConfig {
         &self,
         content_type: &str,
         body: Vec<u8>,
+        api_key: &str,
     ) -> crate::Result<http::Request<Vec<u8>>> {
         let uri = format!("{}/v1/input", self.get_endpoint());
         let request = Request::post(uri)
             .header("Content-Type", content_type)
-            .header("DD-API-KEY", self.api_key.clone());
+            .header("DD-API-KEY", api_key);
 
         let compression = self.compression.unwrap_or(Compression::Gzip(None));
 
@@ -205,10 +226,11 @@ impl SinkConfig for DatadogLogsConfig {
 
 #[async_trait::async_trait]
 impl HttpSink for DatadogLogsJsonService {
-    type Input = serde_json::Value;
-    type Output = Vec<BoxedRawValue>;
+    type Input = PartitionInnerBuffer<serde_json::Value, Arc<str>>;
+    type Output = PartitionInnerBuffer<Vec<BoxedRawValue>, Arc<str>>;
 
     fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
+        let api_key = self.config.api_key(&event);
         let log = event.as_mut_log();
 
         if let Some(message) = log.remove(log_schema().message_key()) {
@@ -225,37 +247,45 @@ impl HttpSink for DatadogLogsJsonService {
 
         self.config.encoding.apply_rules(&mut event);
 
-        Some(json!(event.into_log()))
+        Some(PartitionInnerBuffer::new(json!(event.into_log()), api_key))
     }
 
-    async fn build_request(&self, events: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+    async fn build_request(&self, output: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+        let (events, api_key) = output.into_parts();
         let body = serde_json::to_vec(&events)?;
-        self.config.build_request("application/json", body)
+        self.config
+            .build_request("application/json", body, &api_key)
     }
 }
 
 #[async_trait::async_trait]
 impl HttpSink for DatadogLogsTextService {
-    type Input = Bytes;
-    type Output = Vec<Bytes>;
+    type Input = PartitionInnerBuffer<Bytes, Arc<str>>;
+    type Output = PartitionInnerBuffer<Vec<Bytes>, Arc<str>>;
 
     fn encode_event(&self, event: Event) -> Option<Self::Input> {
+        let api_key = self.config.api_key(&event);
         encode_event(event, &self.config.encoding)
+            .map(|line| PartitionInnerBuffer::new(line, api_key))
     }
 
-    async fn build_request(&self, events: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+    async fn build_request(&self, output: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+        let (events, api_key) = output.into_parts();
         let body: Vec<u8> = events.into_iter().flat_map(Bytes::into_iter).collect();
-        self.config.build_request("text/plain", body)
+        self.config.build_request("text/plain", body, &api_key)
     }
 }
 
 /// The healthcheck is performed by sending an empty request to Datadog and checking
 /// the return.
-async fn healthcheck<T, O>(sink: T, client: HttpClient) -> crate::Result<()>
+async fn healthcheck<T, O>(sink: T, client: HttpClient, api_key: Arc<str>) -> crate::Result<()>
 where
-    T: HttpSink<Output = Vec<O>>,
+    T: HttpSink<Output = PartitionInnerBuffer<Vec<O>, Arc<str>>>,
 {
-    let req = sink.build_request(Vec::new()).await?.map(Body::from);
+    let req = sink
+        .build_request(PartitionInnerBuffer::new(Vec::new(), api_key))
+        .await?
+        .map(Body::from);
 
     let res = client.send(req).await?;
 
@@ -392,4 +422,55 @@ mod tests {
             assert_eq!(message, expected[i]);
         }
     }
+
+    #[tokio::test]
+    async fn uses_the_api_keys_of_the_events() {
+        let (mut config, cx) = load_sink::<DatadogLogsConfig>(
+            r#"
+            api_key = "atoken"
+            encoding = "json"
+            compression = "none"
+            batch.max_events = 1
+            "#,
+        )
+        .unwrap();
+
+        let addr = next_addr();
+        config.endpoint = Some(format!("http://{}", addr));
+
+        let (sink, _) = config.build(cx).await.unwrap();
+
+        let (rx, _trigger, server) = build_test_server(addr);
+        tokio::spawn(server);
+
+        let mut agent_event = Event::from("from an agent");
+        agent_event
+            .as_mut_log()
+            .metadata_mut()
+            .set_datadog_api_key(Some(Arc::from("agent-key")));
+        let events = vec![agent_event, Event::from("from elsewhere")];
+        let _ = sink.run(futures::stream::iter(events)).await.unwrap();
+
+        let mut output = rx
+            .take(2)
+            .map(|(parts, body)| {
+                let json: serde_json::Value = serde_json::from_slice(&body[..]).unwrap();
+                // The key is never part of the events sent.
+                assert!(json[0].get("datadog_api_key").is_none());
+                (
+                    parts.headers["DD-API-KEY"].to_str().unwrap().to_owned(),
+                    json[0]["message"].as_str().unwrap().to_owned(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .await;
+        output.sort();
+        assert_eq!(
+            output,
+            vec![
+                ("agent-key".to_owned(), "from an agent".to_owned()),
+                ("atoken".to_owned(), "from elsewhere".to_owned()),
+            ]
+        );
+    }
 }
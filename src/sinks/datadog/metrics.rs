@@ -188,11 +188,13 @@ impl SinkConfig for DatadogConfig {
         );
 
         let buffer = PartitionBuffer::new(MetricsBuffer::new(batch.size));
-        let mut normalizer = MetricNormalizer::<DatadogMetricNormalize>::default();
+        let mut normalizer = MetricNormalizer::<DatadogMetricNormalize>::default()
+            .with_expiration(cx.expire_metrics());
 
         let svc_sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
             .sink_map_err(|error| error!(message = "Fatal datadog metric sink error.", %error))
             .with_flat_map(move |event: Event| {
+                normalizer.expire();
                 stream::iter(normalizer.apply(event).map(|event| {
                     let endpoint = DatadogEndpoint::from_metric(&event);
                     Ok(PartitionInnerBuffer::new(event, endpoint))
@@ -0,0 +1,363 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    internal_events::ExecCommandExecuted,
+    sinks::util::{
+        encoding::{EncodingConfig, EncodingConfiguration},
+        retries::{RetryAction, RetryLogic},
+        sink::Response,
+        BatchConfig, BatchSettings, Buffer, Compression, Concurrency, TowerRequestConfig,
+    },
+    Event,
+};
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::BTreeMap,
+    io,
+    path::PathBuf,
+    process::{ExitStatus, Stdio},
+    sync::Arc,
+    task::{Context, Poll},
+    time::Instant,
+};
+use tokio::{io::AsyncWriteExt, process::Command};
+use tower::Service;
+
+/// The longest tail of the standard error of a failed command kept to report it.
+const MAX_STDERR_LEN: usize = 1024;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ExecSinkConfig {
+    /// The program to run for each batch, followed by its arguments.
+    pub command: Vec<String>,
+    pub working_directory: Option<PathBuf>,
+    /// Variables added to the environment of the command.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environment: BTreeMap<String, String>,
+    pub encoding: EncodingConfig<Encoding>,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+}
+
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        concurrency: Concurrency::Fixed(1),
+        timeout_secs: Some(300),
+        ..Default::default()
+    };
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Text,
+    Ndjson,
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`command` must name the program to run"))]
+    EmptyCommand,
+}
+
+#[derive(Debug, Snafu)]
+pub enum ExecError {
+    #[snafu(display("Could not run {:?}: {}", program, source))]
+    Spawn { program: String, source: io::Error },
+    #[snafu(display("Could not write the batch to the command: {}", source))]
+    WriteBatch { source: io::Error },
+    #[snafu(display("Could not wait for the command: {}", source))]
+    Wait { source: io::Error },
+}
+
+inventory::submit! {
+    SinkDescription::new::<ExecSinkConfig>("exec")
+}
+
+impl GenerateConfig for ExecSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"command = ["bq", "load", "--source_format=NEWLINE_DELIMITED_JSON", "dataset.table"]
+            encoding.codec = "ndjson""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "exec")]
+impl SinkConfig for ExecSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        if self.command.is_empty() {
+            return Err(Box::new(BuildError::EmptyCommand));
+        }
+
+        let batch = BatchSettings::default()
+            .bytes(10_000_000)
+            .timeout(30)
+            .parse_config(self.batch)?;
+        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+        let encoding = self.encoding.clone();
+
+        let exec = ExecSink {
+            config: Arc::new(self.clone()),
+        };
+
+        let sink = request
+            .batch_sink(
+                ExecRetryLogic,
+                exec,
+                Buffer::new(batch.size, Compression::None),
+                batch.timeout,
+                cx.acker(),
+            )
+            .sink_map_err(|error| error!(message = "Fatal exec sink error.", %error))
+            .with_flat_map(move |event| stream::iter(Some(encode_event(event, &encoding))).map(Ok));
+
+        Ok((
+            super::VectorSink::Sink(Box::new(sink)),
+            futures::future::ok(()).boxed(),
+        ))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "exec"
+    }
+}
+
+fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Vec<u8> {
+    encoding.apply_rules(&mut event);
+    let log = event.into_log();
+    let mut line = match encoding.codec() {
+        Encoding::Ndjson => serde_json::to_vec(&log).expect("Unable to encode event as JSON."),
+        Encoding::Text => log
+            .get(log_schema().message_key())
+            .map(|v| v.to_string_lossy().into_bytes())
+            .unwrap_or_default(),
+    };
+    line.push(b'\n');
+    line
+}
+
+/// Runs the command once per batch, writing the batch to its standard input.
+#[derive(Clone)]
+struct ExecSink {
+    config: Arc<ExecSinkConfig>,
+}
+
+impl ExecSink {
+    fn command(&self) -> Command {
+        let mut command = Command::new(&self.config.command[0]);
+        command
+            .args(&self.config.command[1..])
+            .envs(&self.config.environment)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            // The command is killed when the request times out.
+            .kill_on_drop(true);
+        if let Some(directory) = &self.config.working_directory {
+            command.current_dir(directory);
+        }
+        command
+    }
+}
+
+impl Service<Vec<u8>> for ExecSink {
+    type Response = ExecResponse;
+    type Error = ExecError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, batch: Vec<u8>) -> Self::Future {
+        let mut command = self.command();
+        let program = self.config.command[0].clone();
+
+        Box::pin(async move {
+            let start = Instant::now();
+            let mut child = command.spawn().context(Spawn { program: &program })?;
+
+            let mut stdin = child.stdin.take().expect("The standard input is piped.");
+            let write = async move {
+                let result = stdin.write_all(&batch).await;
+                // Closing the standard input ends the batch.
+                drop(stdin);
+                match result {
+                    // The command may exit without reading the whole batch,
+                    // its exit status tells whether that's a failure.
+                    Err(error) if error.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+                    result => result,
+                }
+            };
+            let byte_size = batch.len();
+
+            let (written, output) = futures::join!(write, child.wait_with_output());
+            let output = output.context(Wait)?;
+            written.context(WriteBatch)?;
+
+            emit!(ExecCommandExecuted {
+                command: &program,
+                exit_status: output.status.code(),
+                elapsed: start.elapsed(),
+                byte_size,
+            });
+
+            let stderr = &output.stderr[output.stderr.len().saturating_sub(MAX_STDERR_LEN)..];
+            Ok(ExecResponse {
+                status: output.status,
+                stderr: String::from_utf8_lossy(stderr).trim().to_owned(),
+            })
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ExecResponse {
+    status: ExitStatus,
+    /// The tail of the standard error of the command.
+    stderr: String,
+}
+
+impl Response for ExecResponse {
+    fn is_successful(&self) -> bool {
+        self.status.success()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ExecRetryLogic;
+
+impl RetryLogic for ExecRetryLogic {
+    type Error = ExecError;
+    type Response = ExecResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        // A missing program won't appear by retrying.
+        !matches!(error, ExecError::Spawn { .. })
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        if response.status.success() {
+            RetryAction::Successful
+        } else {
+            RetryAction::Retry(format!("command {}: {}", response.status, response.stderr))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{random_lines_with_stream, trace_init};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ExecSinkConfig>();
+    }
+
+    #[test]
+    fn encodes_events_as_lines() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("key", "value");
+
+        assert_eq!(
+            encode_event(event.clone(), &Encoding::Text.into()),
+            b"hello world\n".to_vec()
+        );
+
+        let line = encode_event(event, &Encoding::Ndjson.into());
+        assert_eq!(line.last(), Some(&b'\n'));
+        let map: BTreeMap<String, String> = serde_json::from_slice(&line).unwrap();
+        assert_eq!(map["key"], "value");
+    }
+
+    fn sink(command: &[&str]) -> ExecSink {
+        let config: ExecSinkConfig = toml::from_str(&format!(
+            "command = {:?}\nencoding.codec = \"text\"",
+            command
+        ))
+        .unwrap();
+        ExecSink {
+            config: Arc::new(config),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn passes_the_batch_on_stdin() {
+        trace_init();
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let path = file.path().to_str().unwrap().to_owned();
+        let mut exec = sink(&["sh", "-c", &format!("cat > {}", path)]);
+
+        let response = exec.call(b"first\nsecond\n".to_vec()).await.unwrap();
+        assert!(response.is_successful());
+
+        let contents = std::fs::read_to_string(file.path()).unwrap();
+        assert_eq!(contents, "first\nsecond\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn retries_failed_commands() {
+        trace_init();
+
+        let mut exec = sink(&["sh", "-c", "echo quota exceeded >&2; exit 3"]);
+        let response = exec.call(b"line\n".to_vec()).await.unwrap();
+
+        assert!(!response.is_successful());
+        assert!(matches!(
+            ExecRetryLogic.should_retry_response(&response),
+            RetryAction::Retry(reason) if reason.ends_with("quota exceeded")
+        ));
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_missing_programs() {
+        let mut exec = sink(&["vector-exec-sink-missing-program"]);
+        let error = exec.call(b"line\n".to_vec()).await.unwrap_err();
+
+        assert!(!ExecRetryLogic.is_retriable_error(&error));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn sends_batches() {
+        trace_init();
+
+        let directory = tempfile::tempdir().unwrap();
+        let config: ExecSinkConfig = toml::from_str(&format!(
+            r#"
+            command = ["sh", "-c", "cat >> batches"]
+            working_directory = "{}"
+            encoding.codec = "text"
+            batch.max_events = 10
+            "#,
+            directory.path().display()
+        ))
+        .unwrap();
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let (lines, events) = random_lines_with_stream(32, 25);
+        sink.run(events).await.unwrap();
+
+        let contents = std::fs::read_to_string(directory.path().join("batches")).unwrap();
+        assert_eq!(contents.lines().collect::<Vec<_>>(), lines);
+    }
+}
@@ -6,7 +6,7 @@ use crate::{
     sinks::util::{
         buffer::compression::GZIP_DEFAULT,
         encoding::{EncodingConfig, EncodingConfiguration},
-        http::{BatchedHttpSink, HttpSink, RequestConfig},
+        http::{AuthRetryLogic, BatchedHttpSink, HttpSink, RequestConfig},
         BatchConfig, BatchSettings, Buffer, Compression, Concurrency, TowerRequestConfig, UriSerde,
     },
     tls::{TlsOptions, TlsSettings},
@@ -136,6 +136,9 @@ impl SinkConfig for HttpSinkConfig {
 
         config.request.add_old_option(config.headers.take());
         validate_headers(&config.request.headers, &config.auth)?;
+        if let Some(auth) = &config.auth {
+            auth.validate()?;
+        }
 
         let batch = BatchSettings::default()
             .bytes(bytesize::mib(10u64))
@@ -143,9 +146,11 @@ impl SinkConfig for HttpSinkConfig {
             .parse_config(config.batch)?;
         let request = config.request.tower.unwrap_with(&REQUEST_DEFAULTS);
 
-        let sink = BatchedHttpSink::new(
+        let retry_logic = AuthRetryLogic::new(config.auth.clone());
+        let sink = BatchedHttpSink::with_retry_logic(
             config,
             Buffer::new(batch.size, Compression::None),
+            retry_logic,
             request,
             batch.timeout,
             client,
@@ -254,7 +259,7 @@ impl HttpSink for HttpSinkConfig {
         let mut request = builder.body(body).unwrap();
 
         if let Some(auth) = &self.auth {
-            auth.apply(&mut request);
+            auth.authorize(&mut request).await?;
         }
 
         Ok(request)
@@ -267,7 +272,7 @@ async fn healthcheck(uri: UriSerde, auth: Option<Auth>, client: HttpClient) -> c
     let mut request = Request::head(&uri.uri).body(Body::empty()).unwrap();
 
     if let Some(auth) = auth {
-        auth.apply(&mut request);
+        auth.authorize(&mut request).await?;
     }
 
     let response = client.send(request).await?;
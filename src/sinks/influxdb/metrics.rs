@@ -138,7 +138,8 @@ impl InfluxDBSvc {
             protocol_version,
             inner: http_service,
         };
-        let mut normalizer = MetricNormalizer::<InfluxMetricNormalize>::default();
+        let mut normalizer = MetricNormalizer::<InfluxMetricNormalize>::default()
+            .with_expiration(cx.expire_metrics());
 
         let sink = request
             .batch_sink(
@@ -148,7 +149,10 @@ impl InfluxDBSvc {
                 batch.timeout,
                 cx.acker(),
             )
-            .with_flat_map(move |event: Event| stream::iter(normalizer.apply(event).map(Ok)))
+            .with_flat_map(move |event: Event| {
+                normalizer.expire();
+                stream::iter(normalizer.apply(event).map(Ok))
+            })
             .sink_map_err(|error| error!(message = "Fatal influxdb sink error.", %error));
 
         Ok(VectorSink::Sink(Box::new(sink)))
@@ -1,6 +1,7 @@
 use crate::{
     buffers::Acker,
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::EventStatus,
     kafka::{KafkaAuthConfig, KafkaCompression},
     serde::to_string,
     sinks::util::{
@@ -23,7 +24,7 @@ use rdkafka::{
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::HashMap,
     convert::TryFrom,
     pin::Pin,
     sync::Arc,
@@ -31,6 +32,11 @@ use std::{
 };
 use tokio::time::{delay_for, Duration};
 
+mod schema_registry;
+
+use schema_registry::SchemaRegistry;
+pub use schema_registry::{SchemaRegistryConfig, SubjectNameStrategy};
+
 // Maximum number of futures blocked by [send_result](https://docs.rs/rdkafka/0.24.0/rdkafka/producer/future_producer/struct.FutureProducer.html#method.send_result)
 const SEND_RESULT_LIMIT: usize = 5;
 
@@ -40,6 +46,10 @@ enum BuildError {
     KafkaCreateFailed { source: KafkaError },
     #[snafu(display("invalid topic template: {}", source))]
     TopicTemplate { source: TemplateError },
+    #[snafu(display("Avro requires a schema, specify it with `encoding.schema`"))]
+    MissingAvroSchema,
+    #[snafu(display("`schema_registry` requires the `avro` codec"))]
+    SchemaRegistryWithoutAvro,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -61,6 +71,9 @@ pub struct KafkaSinkConfig {
     message_timeout_ms: u64,
     #[serde(default)]
     librdkafka_options: HashMap<String, String>,
+    /// The Avro records are prefixed with the ID of their schema in this
+    /// registry.
+    schema_registry: Option<SchemaRegistryConfig>,
 }
 
 fn default_socket_timeout_ms() -> u64 {
@@ -76,6 +89,7 @@ fn default_message_timeout_ms() -> u64 {
 pub enum Encoding {
     Text,
     Json,
+    Avro,
 }
 
 pub struct KafkaSink {
@@ -83,15 +97,16 @@ pub struct KafkaSink {
     topic: Template,
     key_field: Option<String>,
     encoding: EncodingConfig<Encoding>,
-    delivery_fut: FuturesUnordered<BoxFuture<'static, (usize, Result<DeliveryFuture, KafkaError>)>>,
-    in_flight: FuturesUnordered<
-        BoxFuture<'static, (usize, Result<Result<(i32, i64), KafkaError>, Canceled>)>,
-    >,
+    avro_schema: Option<avro_rs::Schema>,
+    schema_registry: Option<Arc<SchemaRegistry>>,
+    delivery_fut: FuturesUnordered<BoxFuture<'static, (usize, crate::Result<DeliveryFuture>)>>,
+    in_flight:
+        FuturesUnordered<BoxFuture<'static, (usize, Result<crate::Result<(i32, i64)>, Canceled>)>>,
 
     acker: Acker,
     seq_head: usize,
     seq_tail: usize,
-    pending_acks: HashSet<usize>,
+    pending_acks: HashMap<usize, EventStatus>,
 }
 
 inventory::submit! {
@@ -226,17 +241,47 @@ impl KafkaSink {
     fn new(config: KafkaSinkConfig, acker: Acker) -> crate::Result<Self> {
         let producer_config = config.to_rdkafka(KafkaRole::Producer)?;
         let producer = producer_config.create().context(KafkaCreateFailed)?;
+
+        let avro_schema = match config.encoding.codec() {
+            Encoding::Avro => {
+                let schema = config
+                    .encoding
+                    .schema()
+                    .as_ref()
+                    .ok_or(BuildError::MissingAvroSchema)?;
+                Some(
+                    avro_rs::Schema::parse_str(schema)
+                        .map_err(|error| format!("Invalid Avro schema: {}", error))?,
+                )
+            }
+            _ => None,
+        };
+        let schema_registry = match (&config.schema_registry, &avro_schema) {
+            (Some(registry), Some(parsed)) => {
+                let schema = config
+                    .encoding
+                    .schema()
+                    .as_deref()
+                    .expect("The Avro schema was parsed.");
+                Some(Arc::new(SchemaRegistry::new(registry, schema, parsed)?))
+            }
+            (Some(_), None) => return Err(Box::new(BuildError::SchemaRegistryWithoutAvro)),
+            (None, _) => None,
+        };
+
         Ok(KafkaSink {
             producer: Arc::new(producer),
             topic: Template::try_from(config.topic).context(TopicTemplate)?,
             key_field: config.key_field,
             encoding: config.encoding,
+            avro_schema,
+            schema_registry,
             delivery_fut: FuturesUnordered::new(),
             in_flight: FuturesUnordered::new(),
             acker,
             seq_head: 0,
             seq_tail: 0,
-            pending_acks: HashSet::new(),
+            pending_acks: HashMap::new(),
         })
     }
 
@@ -247,7 +292,7 @@ impl KafkaSink {
             self.in_flight.push(Box::pin(async move {
                 let result = match result {
                     Ok(fut) => {
-                        fut.map_ok(|result| result.map_err(|(error, _owned_message)| error))
+                        fut.map_ok(|result| result.map_err(|(error, _owned_message)| error.into()))
                             .await
                     }
                     Err(error) => Ok(Err(error)),
@@ -286,37 +331,65 @@ impl Sink<Event> for KafkaSink {
                 .get(log_schema().timestamp_key())
                 .and_then(|v| v.as_timestamp()),
             Event::Metric(metric) => metric.data.timestamp.as_ref(),
+            Event::Trace(trace) => trace.start_time(),
         }
         .map(|ts| ts.timestamp_millis());
-        let (key, body) = encode_event(item, &self.key_field, &self.encoding);
+        // The events failing to encode are acknowledged in order with the
+        // others, once their error is logged.
+        let encoded = encode_event(
+            item,
+            &self.key_field,
+            &self.encoding,
+            self.avro_schema.as_ref(),
+        );
 
         let seqno = self.seq_head;
         self.seq_head += 1;
 
         let producer = Arc::clone(&self.producer);
+        let schema_registry = self.schema_registry.clone();
         self.delivery_fut.push(Box::pin(async move {
-            let mut record = FutureRecord::to(&topic).key(&key).payload(&body[..]);
-            if let Some(timestamp) = timestamp_ms {
-                record = record.timestamp(timestamp);
-            }
+            let result: crate::Result<DeliveryFuture> = async {
+                let (key, mut body) = encoded?;
+                if let Some(registry) = schema_registry {
+                    body = loop {
+                        match registry.frame(&topic, &body).await {
+                            Ok(record) => break record,
+                            // Holding the events back until the registry is
+                            // available again.
+                            Err(error) if error.is_retriable() => {
+                                warn!(message = "Failed to get schema ID, retrying.", %error, internal_log_rate_secs = 10);
+                                delay_for(Duration::from_secs(1)).await;
+                            }
+                            Err(error) => return Err(error.into()),
+                        }
+                    };
+                }
+
+                let mut record = FutureRecord::to(&topic).key(&key).payload(&body[..]);
+                if let Some(timestamp) = timestamp_ms {
+                    record = record.timestamp(timestamp);
+                }
 
-            let result = loop {
-                debug!(message = "Sending event.", count = 1);
-                match producer.send_result(record) {
-                    Ok(future) => break Ok(future),
-                    // Try again if queue is full.
-                    // See item 4 on GitHub: https://github.com/timberio/vector/pull/101#issue-257150924
-                    // https://docs.rs/rdkafka/0.24.0/src/rdkafka/producer/future_producer.rs.html#296
-                    Err((error, future_record))
-                        if error == KafkaError::MessageProduction(RDKafkaError::QueueFull) =>
-                    {
-                        debug!(message = "The rdkafka queue full.", %error, %seqno, internal_log_rate_secs = 1);
-                        record = future_record;
-                        delay_for(Duration::from_millis(10)).await;
+                loop {
+                    debug!(message = "Sending event.", count = 1);
+                    match producer.send_result(record) {
+                        Ok(future) => break Ok(future),
+                        // Try again if queue is full.
+                        // See item 4 on GitHub: https://github.com/timberio/vector/pull/101#issue-257150924
+                        // https://docs.rs/rdkafka/0.24.0/src/rdkafka/producer/future_producer.rs.html#296
+                        Err((error, future_record))
+                            if error == KafkaError::MessageProduction(RDKafkaError::QueueFull) =>
+                        {
+                            debug!(message = "The rdkafka queue full.", %error, %seqno, internal_log_rate_secs = 1);
+                            record = future_record;
+                            delay_for(Duration::from_millis(10)).await;
+                        }
+                        Err((error, _)) => break Err(error.into()),
                     }
-                    Err((error, _)) => break Err(error),
                 }
-            };
+            }
+            .await;
 
             (seqno, result)
         }));
@@ -331,21 +404,23 @@ impl Sink<Event> for KafkaSink {
         while !this.in_flight.is_empty() {
             match ready!(Pin::new(&mut this.in_flight).poll_next(cx)) {
                 Some((seqno, Ok(result))) => {
-                    match result {
+                    let status = match result {
                         Ok((partition, offset)) => {
-                            trace!(message = "Produced message.", ?partition, ?offset)
+                            trace!(message = "Produced message.", ?partition, ?offset);
+                            EventStatus::Delivered
+                        }
+                        Err(error) => {
+                            error!(message = "Kafka error.", %error);
+                            EventStatus::Errored
                         }
-                        Err(error) => error!(message = "Kafka error.", %error),
                     };
 
-                    this.pending_acks.insert(seqno);
+                    this.pending_acks.insert(seqno, status);
 
-                    let mut num_to_ack = 0;
-                    while this.pending_acks.remove(&this.seq_tail) {
-                        num_to_ack += 1;
+                    while let Some(status) = this.pending_acks.remove(&this.seq_tail) {
+                        this.acker.ack_with_status(1, status);
                         this.seq_tail += 1
                     }
-                    this.acker.ack(num_to_ack);
                 }
                 Some((_, Err(Canceled))) => {
                     error!(message = "Request canceled.");
@@ -397,7 +472,8 @@ fn encode_event(
     mut event: Event,
     key_field: &Option<String>,
     encoding: &EncodingConfig<Encoding>,
-) -> (Vec<u8>, Vec<u8>) {
+    avro_schema: Option<&avro_rs::Schema>,
+) -> crate::Result<(Vec<u8>, Vec<u8>)> {
     let key = key_field
         .as_ref()
         .and_then(|f| match &event {
@@ -406,6 +482,7 @@ fn encode_event(
                 .tags()
                 .and_then(|tags| tags.get(f))
                 .map(|value| value.clone().into_bytes()),
+            Event::Trace(trace) => trace.get(f).map(|value| value.as_bytes().to_vec()),
         })
         .unwrap_or_default();
 
@@ -418,14 +495,31 @@ fn encode_event(
                 .get(log_schema().message_key())
                 .map(|v| v.as_bytes().to_vec())
                 .unwrap_or_default(),
+            Encoding::Avro => encode_avro(log, avro_schema)?,
         },
         Event::Metric(metric) => match encoding.codec() {
             Encoding::Json => serde_json::to_vec(&metric).unwrap(),
             Encoding::Text => metric.to_string().into_bytes(),
+            Encoding::Avro => encode_avro(metric, avro_schema)?,
+        },
+        Event::Trace(trace) => match encoding.codec() {
+            Encoding::Json => serde_json::to_vec(&trace).unwrap(),
+            Encoding::Text => trace.name().unwrap_or_default().into_bytes(),
+            Encoding::Avro => encode_avro(trace, avro_schema)?,
         },
     };
 
-    (key, body)
+    Ok((key, body))
+}
+
+fn encode_avro(
+    value: impl Serialize,
+    avro_schema: Option<&avro_rs::Schema>,
+) -> crate::Result<Vec<u8>> {
+    let schema =
+        avro_schema.expect("Avro encoding selected but no schema found. Please report this.");
+    let value = avro_rs::types::Value::resolve(avro_rs::to_value(value)?, schema)?;
+    Ok(avro_rs::to_avro_datum(schema, value)?)
 }
 
 #[cfg(test)]
@@ -448,7 +542,9 @@ mod tests {
             message.clone().into(),
             &None,
             &EncodingConfig::from(Encoding::Text),
-        );
+            None,
+        )
+        .unwrap();
 
         assert_eq!(&key_bytes[..], key.as_bytes());
         assert_eq!(&bytes[..], message.as_bytes());
@@ -466,7 +562,9 @@ mod tests {
             event,
             &Some("key".into()),
             &EncodingConfig::from(Encoding::Json),
-        );
+            None,
+        )
+        .unwrap();
 
         let map: BTreeMap<String, String> = serde_json::from_slice(&bytes[..]).unwrap();
 
@@ -487,7 +585,9 @@ mod tests {
             metric.clone().into(),
             &None,
             &EncodingConfig::from(Encoding::Text),
-        );
+            None,
+        )
+        .unwrap();
 
         assert_eq!("", String::from_utf8_lossy(&key_bytes));
         assert_eq!(metric.to_string(), String::from_utf8_lossy(&bytes));
@@ -504,7 +604,9 @@ mod tests {
             metric.clone().into(),
             &None,
             &EncodingConfig::from(Encoding::Json),
-        );
+            None,
+        )
+        .unwrap();
 
         assert_eq!("", String::from_utf8_lossy(&key_bytes));
         assert_eq!(
@@ -528,14 +630,63 @@ mod tests {
                 only_fields: None,
                 except_fields: Some(vec!["key".into()]),
                 timestamp_format: None,
+                remap: None,
             },
-        );
+            None,
+        )
+        .unwrap();
 
         let map: BTreeMap<String, String> = serde_json::from_slice(&bytes[..]).unwrap();
 
         assert_eq!(&key[..], b"value");
         assert!(!map.contains_key("key"));
     }
+
+    #[test]
+    fn kafka_encode_event_log_avro() {
+        crate::test_util::trace_init();
+        let raw_schema = r#"{
+            "type": "record",
+            "name": "Log",
+            "fields": [{"name": "message", "type": "string"}]
+        }"#;
+        let schema = avro_rs::Schema::parse_str(raw_schema).unwrap();
+        let encoding = EncodingConfig {
+            codec: Encoding::Avro,
+            schema: Some(raw_schema.to_owned()),
+            only_fields: Some(vec![log_schema().message_key().into()]),
+            except_fields: None,
+            timestamp_format: None,
+            remap: None,
+        };
+
+        let (_, bytes) =
+            encode_event(Event::from("hello"), &None, &encoding, Some(&schema)).unwrap();
+
+        let value = avro_rs::from_avro_datum(&schema, &mut &bytes[..], None).unwrap();
+        assert_eq!(
+            value,
+            avro_rs::types::Value::Record(vec![(
+                "message".to_owned(),
+                avro_rs::types::Value::String("hello".to_owned())
+            )])
+        );
+    }
+
+    #[test]
+    fn kafka_avro_requires_schema() {
+        let config: KafkaSinkConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9091"
+            topic = "logs"
+            encoding.codec = "avro"
+            "#,
+        )
+        .unwrap();
+        let (acker, _) = Acker::new_for_testing();
+
+        assert!(KafkaSink::new(config, acker).is_err());
+    }
 }
 
 #[cfg(feature = "kafka-integration-tests")]
@@ -571,6 +722,7 @@ mod integration_test {
             socket_timeout_ms: 60000,
             message_timeout_ms: 300000,
             librdkafka_options: HashMap::new(),
+            schema_registry: None,
         };
 
         super::healthcheck(config).await.unwrap();
@@ -625,6 +777,7 @@ mod integration_test {
             message_timeout_ms: 300000,
             batch,
             librdkafka_options,
+            schema_registry: None,
         };
         let (acker, _ack_counter) = Acker::new_for_testing();
         config.clone().to_rdkafka(KafkaRole::Consumer)?;
@@ -776,6 +929,7 @@ mod integration_test {
             socket_timeout_ms: 60000,
             message_timeout_ms: 300000,
             librdkafka_options: HashMap::new(),
+            schema_registry: None,
         };
         let topic = format!("{}-{}", topic, chrono::Utc::now().format("%Y%m%d"));
         let (acker, ack_counter) = Acker::new_for_testing();
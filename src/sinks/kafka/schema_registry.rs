@@ -0,0 +1,361 @@
+//! Confluent Schema Registry client, giving the ID of the schema of the Avro
+//! records written to each topic.
+//!
+//! The schema is registered, or only looked up, once per subject. The records
+//! are then prefixed with the magic byte and the schema ID, following the
+//! Confluent wire format.
+
+use crate::{
+    http::{Auth, AuthError, HttpClient, HttpError},
+    tls::{TlsOptions, TlsSettings},
+};
+use http::{
+    header::{ACCEPT, CONTENT_TYPE},
+    Request, StatusCode, Uri,
+};
+use hyper::Body;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{collections::HashMap, sync::Mutex};
+
+const SCHEMA_REGISTRY_CONTENT_TYPE: &str = "application/vnd.schemaregistry.v1+json";
+
+/// The first byte of the records in the Confluent wire format.
+const MAGIC_BYTE: u8 = 0;
+
+/// The characters escaped in the subjects put in the request paths.
+const SUBJECT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_');
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SchemaRegistryConfig {
+    pub url: String,
+    pub auth: Option<Auth>,
+    #[serde(default)]
+    pub subject_name_strategy: SubjectNameStrategy,
+    /// Whether to register the schema, rather than only looking up the ID it
+    /// was already registered with.
+    #[serde(default = "crate::serde::default_true")]
+    pub auto_register: bool,
+    pub tls: Option<TlsOptions>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SubjectNameStrategy {
+    /// The subject is `<topic>-value`.
+    TopicName,
+    /// The subject is the fully qualified name of the record.
+    RecordName,
+    /// The subject is `<topic>-<fully qualified name of the record>`.
+    TopicRecordName,
+}
+
+impl Default for SubjectNameStrategy {
+    fn default() -> Self {
+        SubjectNameStrategy::TopicName
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum SchemaRegistryError {
+    #[snafu(display("Invalid schema registry URL {:?}: {}", url, source))]
+    InvalidUrl {
+        url: String,
+        source: http::uri::InvalidUri,
+    },
+    #[snafu(display("The {:?} subject name strategy requires a record schema", strategy))]
+    NotARecord { strategy: SubjectNameStrategy },
+    #[snafu(display("Failed to authorize the schema registry request: {}", source))]
+    Authorize { source: AuthError },
+    #[snafu(display("Failed to send the schema registry request: {}", source))]
+    SendRequest { source: HttpError },
+    #[snafu(display("Failed to read the schema registry response: {}", source))]
+    ReadResponse { source: hyper::Error },
+    #[snafu(display(
+        "Schema registry request for subject {:?} failed with {}: {}",
+        subject,
+        status,
+        body
+    ))]
+    RequestFailed {
+        subject: String,
+        status: StatusCode,
+        body: String,
+        retriable: bool,
+    },
+    #[snafu(display("Invalid schema registry response: {}", source))]
+    ParseResponse { source: serde_json::Error },
+}
+
+impl SchemaRegistryError {
+    /// Whether the request may succeed later, the other errors coming from
+    /// the configuration or the schema.
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            SchemaRegistryError::Authorize { source } => source.is_retriable(),
+            SchemaRegistryError::SendRequest { .. } | SchemaRegistryError::ReadResponse { .. } => {
+                true
+            }
+            SchemaRegistryError::RequestFailed { retriable, .. } => *retriable,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SchemaIdResponse {
+    id: u32,
+}
+
+pub struct SchemaRegistry {
+    url: String,
+    auth: Option<Auth>,
+    subject_name_strategy: SubjectNameStrategy,
+    auto_register: bool,
+    client: HttpClient,
+    /// The body of the requests registering or looking up the schema.
+    request_body: String,
+    record_name: Option<String>,
+    ids: Mutex<HashMap<String, u32>>,
+}
+
+impl SchemaRegistry {
+    /// Builds the client registering `schema`, the source of `parsed`.
+    pub fn new(
+        config: &SchemaRegistryConfig,
+        schema: &str,
+        parsed: &avro_rs::Schema,
+    ) -> crate::Result<Self> {
+        let url = config.url.trim_end_matches('/').to_owned();
+        url.parse::<Uri>()
+            .with_context(|| InvalidUrl { url: url.clone() })?;
+        if let Some(auth) = &config.auth {
+            auth.validate()?;
+        }
+
+        let record_name = match parsed {
+            avro_rs::Schema::Record { name, .. } => Some(match &name.namespace {
+                Some(namespace) => format!("{}.{}", namespace, name.name),
+                None => name.name.clone(),
+            }),
+            _ => None,
+        };
+        if record_name.is_none() && config.subject_name_strategy != SubjectNameStrategy::TopicName {
+            return Err(Box::new(SchemaRegistryError::NotARecord {
+                strategy: config.subject_name_strategy,
+            }));
+        }
+
+        let tls = TlsSettings::from_options(&config.tls)?;
+        Ok(Self {
+            url,
+            auth: config.auth.clone(),
+            subject_name_strategy: config.subject_name_strategy,
+            auto_register: config.auto_register,
+            client: HttpClient::new(tls)?,
+            request_body: serde_json::json!({ "schema": schema }).to_string(),
+            record_name,
+            ids: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn subject(&self, topic: &str) -> String {
+        let record_name = || {
+            self.record_name
+                .as_deref()
+                .expect("The schema was checked to be a record.")
+        };
+        match self.subject_name_strategy {
+            SubjectNameStrategy::TopicName => format!("{}-value", topic),
+            SubjectNameStrategy::RecordName => record_name().to_owned(),
+            SubjectNameStrategy::TopicRecordName => format!("{}-{}", topic, record_name()),
+        }
+    }
+
+    /// Prefixes the Avro `datum` written to `topic` with its schema ID.
+    pub async fn frame(&self, topic: &str, datum: &[u8]) -> Result<Vec<u8>, SchemaRegistryError> {
+        let id = self.schema_id(topic).await?;
+        Ok(frame(id, datum))
+    }
+
+    async fn schema_id(&self, topic: &str) -> Result<u32, SchemaRegistryError> {
+        let subject = self.subject(topic);
+        let cached = self
+            .ids
+            .lock()
+            .expect("poisoned lock")
+            .get(&subject)
+            .copied();
+        if let Some(id) = cached {
+            return Ok(id);
+        }
+
+        // Concurrent requests for the same subject are harmless, registering
+        // a schema again gives the same ID.
+        let id = self.request_id(&subject).await?;
+        debug!(message = "Got schema ID.", %subject, %id);
+        self.ids.lock().expect("poisoned lock").insert(subject, id);
+        Ok(id)
+    }
+
+    async fn request_id(&self, subject: &str) -> Result<u32, SchemaRegistryError> {
+        let subject_path = utf8_percent_encode(subject, SUBJECT);
+        let uri = if self.auto_register {
+            format!("{}/subjects/{}/versions", self.url, subject_path)
+        } else {
+            format!("{}/subjects/{}", self.url, subject_path)
+        };
+
+        let mut request = Request::post(uri)
+            .header(CONTENT_TYPE, SCHEMA_REGISTRY_CONTENT_TYPE)
+            .header(ACCEPT, SCHEMA_REGISTRY_CONTENT_TYPE)
+            .body(self.request_body.clone().into_bytes())
+            .expect("The schema registry URL was validated.");
+        if let Some(auth) = &self.auth {
+            auth.authorize(&mut request).await.context(Authorize)?;
+        }
+
+        let response = self
+            .client
+            .send(request.map(Body::from))
+            .await
+            .context(SendRequest)?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .context(ReadResponse)?;
+        if !status.is_success() {
+            let retriable = status.is_server_error()
+                || status == StatusCode::TOO_MANY_REQUESTS
+                || (status == StatusCode::UNAUTHORIZED
+                    && self.auth.as_ref().map_or(false, Auth::reject_token));
+            return Err(SchemaRegistryError::RequestFailed {
+                subject: subject.to_owned(),
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+                retriable,
+            });
+        }
+
+        let response: SchemaIdResponse = serde_json::from_slice(&body).context(ParseResponse)?;
+        Ok(response.id)
+    }
+}
+
+fn frame(id: u32, datum: &[u8]) -> Vec<u8> {
+    let mut record = Vec::with_capacity(5 + datum.len());
+    record.push(MAGIC_BYTE);
+    record.extend_from_slice(&id.to_be_bytes());
+    record.extend_from_slice(datum);
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::next_addr;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+    use std::{convert::Infallible, sync::Arc};
+
+    const SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "Log",
+        "namespace": "com.example",
+        "fields": [{"name": "message", "type": "string"}]
+    }"#;
+
+    fn registry(config: &str) -> crate::Result<SchemaRegistry> {
+        let config: SchemaRegistryConfig = toml::from_str(config).unwrap();
+        let parsed = avro_rs::Schema::parse_str(SCHEMA).unwrap();
+        SchemaRegistry::new(&config, SCHEMA, &parsed)
+    }
+
+    /// Answers with ID 7, recording the paths it was requested at.
+    fn registry_server() -> (String, Arc<Mutex<Vec<String>>>) {
+        let addr = next_addr();
+        let paths = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&paths);
+        let make_service = make_service_fn(move |_| {
+            let paths = Arc::clone(&recorded);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                    paths.lock().unwrap().push(request.uri().path().to_owned());
+                    async move { Ok::<_, Infallible>(Response::new(Body::from(r#"{"id": 7}"#))) }
+                }))
+            }
+        });
+        tokio::spawn(Server::bind(&addr).serve(make_service));
+
+        (format!("http://{}/", addr), paths)
+    }
+
+    #[test]
+    fn frames_records() {
+        assert_eq!(frame(7, b"datum"), b"\x00\x00\x00\x00\x07datum".to_vec());
+    }
+
+    #[test]
+    fn names_subjects() {
+        let subject = |strategy| {
+            registry(&format!(
+                "url = \"http://localhost:8081\"\nsubject_name_strategy = \"{}\"",
+                strategy
+            ))
+            .unwrap()
+            .subject("logs")
+        };
+
+        assert_eq!(subject("topic_name"), "logs-value");
+        assert_eq!(subject("record_name"), "com.example.Log");
+        assert_eq!(subject("topic_record_name"), "logs-com.example.Log");
+    }
+
+    #[test]
+    fn record_strategies_require_records() {
+        let config: SchemaRegistryConfig = toml::from_str(
+            "url = \"http://localhost:8081\"\nsubject_name_strategy = \"record_name\"",
+        )
+        .unwrap();
+        let parsed = avro_rs::Schema::parse_str(r#""string""#).unwrap();
+
+        assert!(SchemaRegistry::new(&config, r#""string""#, &parsed).is_err());
+    }
+
+    #[tokio::test]
+    async fn registers_schemas_once_per_subject() {
+        let (url, paths) = registry_server();
+        let registry = registry(&format!("url = {:?}", url)).unwrap();
+
+        for topic in &["logs", "logs", "audit"] {
+            let record = registry.frame(topic, b"datum").await.unwrap();
+            assert_eq!(record, b"\x00\x00\x00\x00\x07datum".to_vec());
+        }
+
+        assert_eq!(
+            *paths.lock().unwrap(),
+            vec![
+                "/subjects/logs-value/versions".to_owned(),
+                "/subjects/audit-value/versions".to_owned()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn looks_schemas_up() {
+        let (url, paths) = registry_server();
+        let registry = registry(&format!("url = {:?}\nauto_register = false", url)).unwrap();
+
+        registry.frame("logs", b"datum").await.unwrap();
+
+        assert_eq!(
+            *paths.lock().unwrap(),
+            vec!["/subjects/logs-value".to_owned()]
+        );
+    }
+}
@@ -14,6 +14,7 @@ use futures::{FutureExt, SinkExt};
 use http::{Request, StatusCode, Uri};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use snafu::Snafu;
 use std::time::SystemTime;
 
 lazy_static::lazy_static! {
@@ -22,6 +23,24 @@ lazy_static::lazy_static! {
 
 const PATH: &str = "/logs/ingest";
 
+// The maximum lengths of the reserved fields accepted by the ingestion API.
+const MAX_HOSTNAME_LEN: usize = 256;
+const MAX_TAG_LEN: usize = 80;
+const MAX_APP_LEN: usize = 512;
+const MAX_FILE_LEN: usize = 512;
+const MAX_ENV_LEN: usize = 80;
+const MAX_LEVEL_LEN: usize = 80;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display(
+        "{} is longer than the {} characters accepted by LogDNA",
+        field,
+        max_len
+    ))]
+    FieldTooLong { field: &'static str, max_len: usize },
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LogdnaConfig {
     api_key: String,
@@ -34,6 +53,11 @@ pub struct LogdnaConfig {
     ip: Option<String>,
     tags: Option<Vec<Template>>,
 
+    app: Option<Template>,
+    env: Option<Template>,
+    file: Option<Template>,
+    level: Option<Template>,
+
     #[serde(
         skip_serializing_if = "crate::serde::skip_serializing_if_default",
         default
@@ -79,6 +103,8 @@ impl SinkConfig for LogdnaConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        self.validate()?;
+
         let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
         let batch_settings = BatchSettings::default()
             .bytes(bytesize::mib(10u64))
@@ -132,6 +158,9 @@ impl HttpSink for LogdnaConfig {
                 );
             })
             .ok()?;
+        // Rendered before the encoding rules apply, so that the fields they
+        // read can be excluded from the `meta` of the line.
+        let fields = self.render_fields(&event);
 
         self.encoding.apply_rules(&mut event);
         let mut log = event.into_log();
@@ -148,16 +177,20 @@ impl HttpSink for LogdnaConfig {
         map.insert("line".to_string(), json!(line));
         map.insert("timestamp".to_string(), json!(timestamp));
 
+        for (name, value) in fields {
+            map.insert(name.to_string(), json!(value));
+        }
+
         if let Some(env) = log.remove("env") {
-            map.insert("env".to_string(), json!(env));
+            map.entry("env").or_insert_with(|| json!(env));
         }
 
         if let Some(app) = log.remove("app") {
-            map.insert("app".to_string(), json!(app));
+            map.entry("app").or_insert_with(|| json!(app));
         }
 
         if let Some(file) = log.remove("file") {
-            map.insert("file".to_string(), json!(file));
+            map.entry("file").or_insert_with(|| json!(file));
         }
 
         if !map.contains_key("env") {
@@ -249,20 +282,105 @@ impl LogdnaConfig {
     }
 
     fn render_key(&self, event: &Event) -> Result<PartitionKey, Vec<String>> {
-        let hostname = self.hostname.render_string(&event)?;
+        let hostname = truncate(
+            "hostname",
+            self.hostname.render_string(&event)?,
+            MAX_HOSTNAME_LEN,
+        );
         let tags = self
             .tags
             .as_ref()
             .map(|tags| -> Result<Option<Vec<String>>, Vec<String>> {
                 let mut vec = Vec::with_capacity(tags.len());
                 for tag in tags {
-                    vec.push(tag.render_string(event)?);
+                    vec.push(truncate("tags", tag.render_string(event)?, MAX_TAG_LEN));
                 }
                 Ok(Some(vec))
             })
             .unwrap_or(Ok(None))?;
         Ok(PartitionKey { hostname, tags })
     }
+
+    /// Renders the reserved fields of a line that are set by a template. A
+    /// template that can't be rendered leaves its field to the defaults.
+    fn render_fields(&self, event: &Event) -> Vec<(&'static str, String)> {
+        self.reserved_fields()
+            .filter_map(|(name, template, max_len)| {
+                let value = template?.render_string(event).ok()?;
+                Some((name, truncate(name, value, max_len)))
+            })
+            .collect()
+    }
+
+    fn reserved_fields(&self) -> impl Iterator<Item = (&'static str, Option<&Template>, usize)> {
+        vec![
+            ("app", self.app.as_ref(), MAX_APP_LEN),
+            ("env", self.env.as_ref(), MAX_ENV_LEN),
+            ("file", self.file.as_ref(), MAX_FILE_LEN),
+            ("level", self.level.as_ref(), MAX_LEVEL_LEN),
+        ]
+        .into_iter()
+    }
+
+    /// Checks the values known before any event is rendered against the
+    /// limits of the ingestion API.
+    fn validate(&self) -> Result<(), BuildError> {
+        let static_value = |template: &Template| {
+            Some(template)
+                .filter(|template| !template.is_dynamic())
+                .map(|template| template.get_ref().len())
+        };
+
+        let mut lengths = vec![
+            ("hostname", static_value(&self.hostname), MAX_HOSTNAME_LEN),
+            (
+                "default_app",
+                self.default_app.as_ref().map(String::len),
+                MAX_APP_LEN,
+            ),
+            (
+                "default_env",
+                self.default_env.as_ref().map(String::len),
+                MAX_ENV_LEN,
+            ),
+        ];
+        lengths.extend(
+            self.tags
+                .iter()
+                .flatten()
+                .map(|tag| ("tags", static_value(tag), MAX_TAG_LEN)),
+        );
+        lengths.extend(
+            self.reserved_fields()
+                .map(|(name, template, max_len)| (name, template.and_then(static_value), max_len)),
+        );
+
+        match lengths
+            .into_iter()
+            .find(|(_, len, max_len)| len.map_or(false, |len| len > *max_len))
+        {
+            Some((field, _, max_len)) => Err(BuildError::FieldTooLong { field, max_len }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Shortens a rendered field to the length accepted by the ingestion API.
+fn truncate(field: &'static str, mut value: String, max_len: usize) -> String {
+    if value.len() > max_len {
+        let mut len = max_len;
+        while !value.is_char_boundary(len) {
+            len -= 1;
+        }
+        value.truncate(len);
+        warn!(
+            message = "Field is too long for LogDNA, truncating.",
+            %field,
+            %max_len,
+            internal_log_rate_secs = 30
+        );
+    }
+    value
 }
 
 async fn healthcheck(config: LogdnaConfig, client: HttpClient) -> crate::Result<()> {
@@ -340,6 +458,59 @@ mod tests {
         assert_eq!(event4_out.get("env").unwrap(), &json!("staging"));
     }
 
+    #[test]
+    fn encode_event_reserved_fields() {
+        let (config, _cx) = load_sink::<LogdnaConfig>(
+            r#"
+            api_key = "mylogtoken"
+            hostname = "vector"
+            app = "{{ service }}"
+            level = "{{ severity }}"
+            env = "{{ stage }}"
+            default_env = "acceptance"
+            encoding.except_fields = ["service", "severity"]
+        "#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("service", "checkout");
+        event.as_mut_log().insert("severity", "x".repeat(100));
+        event.as_mut_log().insert("app", "notvector");
+
+        let output = config.encode_event(event).unwrap().into_parts().0;
+        let output = output.as_object().unwrap();
+
+        assert_eq!(output.get("app").unwrap(), &json!("checkout"));
+        assert_eq!(output.get("level").unwrap(), &json!("x".repeat(80)));
+        assert_eq!(output.get("env").unwrap(), &json!("acceptance"));
+        assert_eq!(output.get("meta"), None);
+    }
+
+    #[test]
+    fn validate_limits() {
+        let config = |extra: &str| {
+            load_sink::<LogdnaConfig>(&format!(
+                r#"
+                api_key = "mylogtoken"
+                hostname = "vector"
+                {}
+            "#,
+                extra
+            ))
+            .unwrap()
+            .0
+        };
+
+        assert!(config(r#"level = "{{ severity }}""#).validate().is_ok());
+        assert!(config(&format!(r#"default_env = "{}""#, "x".repeat(81)))
+            .validate()
+            .is_err());
+        assert!(config(&format!(r#"tags = ["{}"]"#, "x".repeat(81)))
+            .validate()
+            .is_err());
+    }
+
     #[tokio::test]
     async fn smoke() {
         trace_init();
@@ -11,6 +11,10 @@
 //!
 //! If an event produces no labels, this can happen if the template
 //! does not match, we will add a default label `{agent="vector"}`.
+//!
+//! With `api = "otlp"` the batches are sent to the OTLP endpoint of Loki
+//! instead, the labels being the attributes of the resource of the logs and
+//! the structured metadata the attributes of the log records.
 
 use crate::{
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
@@ -19,7 +23,7 @@ use crate::{
     sinks::util::{
         buffer::loki::{GlobalTimestamps, LokiBuffer, LokiEvent, LokiRecord, PartitionKey},
         encoding::{EncodingConfig, EncodingConfiguration},
-        http::{HttpSink, PartitionHttpSink},
+        http::{AuthRetryLogic, HttpSink, PartitionHttpSink},
         service::ConcurrencyOption,
         BatchConfig, BatchSettings, PartitionBuffer, PartitionInnerBuffer, TowerRequestConfig,
         UriSerde,
@@ -29,6 +33,7 @@ use crate::{
 };
 use futures::{FutureExt, SinkExt};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::collections::HashMap;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -39,13 +44,19 @@ pub struct LokiConfig {
 
     tenant_id: Option<Template>,
     labels: HashMap<String, Template>,
+    #[serde(default)]
+    structured_metadata: HashMap<String, Template>,
 
     #[serde(default = "crate::serde::default_false")]
     remove_label_fields: bool,
+    #[serde(default = "crate::serde::default_false")]
+    remove_structured_metadata_fields: bool,
     #[serde(default = "crate::serde::default_true")]
     remove_timestamp: bool,
     #[serde(default)]
     out_of_order_action: OutOfOrderAction,
+    #[serde(default)]
+    api: LokiApi,
 
     auth: Option<Auth>,
 
@@ -67,6 +78,17 @@ pub enum OutOfOrderAction {
     RewriteTimestamp,
 }
 
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq, Eq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LokiApi {
+    /// The push API, `/loki/api/v1/push`.
+    #[derivative(Default)]
+    Push,
+    /// The OTLP endpoint of Loki 3.x, `/otlp/v1/logs`, as OTLP HTTP/JSON.
+    Otlp,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 enum Encoding {
@@ -121,13 +143,14 @@ impl SinkConfig for LokiConfig {
 
         let sink = LokiSink::new(config.clone());
 
-        let sink = PartitionHttpSink::new(
+        let sink = PartitionHttpSink::with_retry_logic(
             sink,
             PartitionBuffer::new(LokiBuffer::new(
                 batch_settings.size,
                 GlobalTimestamps::default(),
                 config.out_of_order_action.clone(),
             )),
+            AuthRetryLogic::new(config.auth.clone()),
             request_settings,
             batch_settings.timeout,
             client.clone(),
@@ -155,9 +178,12 @@ struct LokiSink {
 
     tenant_id: Option<Template>,
     labels: HashMap<String, Template>,
+    structured_metadata: HashMap<String, Template>,
 
     remove_label_fields: bool,
+    remove_structured_metadata_fields: bool,
     remove_timestamp: bool,
+    api: LokiApi,
 
     auth: Option<Auth>,
 }
@@ -169,8 +195,11 @@ impl LokiSink {
             encoding: config.encoding,
             tenant_id: config.tenant_id,
             labels: config.labels,
+            structured_metadata: config.structured_metadata,
             remove_label_fields: config.remove_label_fields,
+            remove_structured_metadata_fields: config.remove_structured_metadata_fields,
             remove_timestamp: config.remove_timestamp,
+            api: config.api,
             auth: config.auth,
         }
     }
@@ -203,15 +232,23 @@ impl HttpSink for LokiSink {
             }
         }
 
-        if self.remove_label_fields {
-            for template in self.labels.values() {
-                if let Some(fields) = template.get_fields() {
-                    for field in fields {
-                        event.as_mut_log().remove(&field);
-                    }
-                }
+        let mut structured_metadata = Vec::new();
+
+        for (key, template) in &self.structured_metadata {
+            if let Ok(value) = template.render_string(&event) {
+                structured_metadata.push((key.clone(), value));
             }
         }
+        // Keep the order of the entries stable, as the templates are in a `HashMap`.
+        structured_metadata.sort_unstable();
+
+        if self.remove_label_fields {
+            remove_template_fields(&mut event, self.labels.values());
+        }
+
+        if self.remove_structured_metadata_fields {
+            remove_template_fields(&mut event, self.structured_metadata.values());
+        }
 
         let timestamp = match event.as_log().get(log_schema().timestamp_key()) {
             Some(event::Value::Timestamp(ts)) => ts.timestamp_nanos(),
@@ -241,7 +278,11 @@ impl HttpSink for LokiSink {
             labels = vec![("agent".to_string(), "vector".to_string())]
         }
 
-        let event = LokiEvent { timestamp, event };
+        let event = LokiEvent {
+            timestamp,
+            event,
+            structured_metadata,
+        };
         Some(PartitionInnerBuffer::new(
             LokiRecord {
                 labels,
@@ -256,9 +297,16 @@ impl HttpSink for LokiSink {
         let (json, key) = output.into_parts();
         let tenant_id = key.tenant_id;
 
-        let body = serde_json::to_vec(&json).unwrap();
-
-        let uri = format!("{}loki/api/v1/push", self.endpoint.uri);
+        let (body, uri) = match self.api {
+            LokiApi::Push => (
+                serde_json::to_vec(&json).unwrap(),
+                format!("{}loki/api/v1/push", self.endpoint.uri),
+            ),
+            LokiApi::Otlp => (
+                serde_json::to_vec(&to_otlp(&json)).unwrap(),
+                format!("{}otlp/v1/logs", self.endpoint.uri),
+            ),
+        };
 
         let mut req = http::Request::post(uri).header("Content-Type", "application/json");
 
@@ -269,20 +317,71 @@ impl HttpSink for LokiSink {
         let mut req = req.body(body).unwrap();
 
         if let Some(auth) = &self.auth {
-            auth.apply(&mut req);
+            auth.authorize(&mut req).await?;
         }
 
         Ok(req)
     }
 }
 
+fn remove_template_fields<'a>(event: &mut Event, templates: impl Iterator<Item = &'a Template>) {
+    for template in templates {
+        if let Some(fields) = template.get_fields() {
+            for field in fields {
+                event.as_mut_log().remove(&field);
+            }
+        }
+    }
+}
+
+/// Converts a batch of streams, as sent to the push API, into the logs of the
+/// OTLP HTTP/JSON protocol. Each stream becomes a resource whose attributes
+/// are its labels, and the structured metadata of its entries the attributes
+/// of their log records.
+fn to_otlp(push: &serde_json::Value) -> serde_json::Value {
+    let resource_logs = push["streams"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|stream| {
+            let log_records = stream["values"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|entry| {
+                    json!({
+                        "timeUnixNano": entry[0],
+                        "body": { "stringValue": entry[1] },
+                        "attributes": otlp_attributes(&entry[2]),
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            json!({
+                "resource": { "attributes": otlp_attributes(&stream["stream"]) },
+                "scopeLogs": [{ "logRecords": log_records }],
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({ "resourceLogs": resource_logs })
+}
+
+fn otlp_attributes(map: &serde_json::Value) -> Vec<serde_json::Value> {
+    map.as_object()
+        .into_iter()
+        .flatten()
+        .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+        .collect()
+}
+
 async fn healthcheck(config: LokiConfig, client: HttpClient) -> crate::Result<()> {
     let uri = format!("{}ready", config.endpoint.uri);
 
     let mut req = http::Request::get(uri).body(hyper::Body::empty()).unwrap();
 
     if let Some(auth) = &config.auth {
-        auth.apply(&mut req);
+        auth.authorize(&mut req).await?;
     }
 
     let res = client.send(req).await?;
@@ -376,6 +475,80 @@ mod tests {
         assert_eq!(record.labels[0], ("bar".to_string(), "bar".to_string()));
     }
 
+    #[test]
+    fn render_structured_metadata() {
+        let (config, _cx) = load_sink::<LokiConfig>(
+            r#"
+            endpoint = "http://localhost:3100"
+            labels = {app = "web"}
+            structured_metadata = {trace_id = "{{ trace_id }}", pod = "{{ pod }}"}
+            remove_structured_metadata_fields = true
+            encoding = "json"
+        "#,
+        )
+        .unwrap();
+        let sink = LokiSink::new(config);
+
+        let mut e1 = Event::from("hello world");
+        e1.as_mut_log().insert("trace_id", "abc");
+        e1.as_mut_log().insert("pod", "web-1");
+
+        let record = sink.encode_event(e1).unwrap().into_parts().0;
+
+        let expected_line = serde_json::to_string(&serde_json::json!({
+            "message": "hello world",
+        }))
+        .unwrap();
+
+        assert_eq!(record.event.event, expected_line);
+        assert_eq!(record.labels, vec![("app".to_string(), "web".to_string())]);
+        assert_eq!(
+            record.event.structured_metadata,
+            vec![
+                ("pod".to_string(), "web-1".to_string()),
+                ("trace_id".to_string(), "abc".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_streams_to_otlp() {
+        let push = serde_json::json!({
+            "streams": [{
+                "stream": {"app": "web"},
+                "values": [
+                    ["123456789", "first"],
+                    ["123456790", "second", {"trace_id": "abc"}],
+                ],
+            }],
+        });
+
+        assert_eq!(
+            to_otlp(&push),
+            serde_json::json!({
+                "resourceLogs": [{
+                    "resource": {
+                        "attributes": [{"key": "app", "value": {"stringValue": "web"}}],
+                    },
+                    "scopeLogs": [{
+                        "logRecords": [
+                            {
+                                "timeUnixNano": "123456789",
+                                "body": {"stringValue": "first"},
+                                "attributes": [],
+                            },
+                            {
+                                "timeUnixNano": "123456790",
+                                "body": {"stringValue": "second"},
+                                "attributes": [{"key": "trace_id", "value": {"stringValue": "abc"}}],
+                            },
+                        ],
+                    }],
+                }],
+            })
+        );
+    }
+
     #[tokio::test]
     async fn healthcheck_includes_auth() {
         let (mut config, _cx) = load_sink::<LokiConfig>(
@@ -0,0 +1,460 @@
+//! Conversion of events into OTLP records.
+//!
+//! This is the reverse of the conversion of the `opentelemetry` source: the
+//! `resource` and `attributes` fields of logs, and the `resource.*` tags of
+//! metrics, are sent back as the resource and attributes of their records.
+
+use crate::{
+    config::log_schema,
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        trace, LogEvent, Value,
+    },
+    opentelemetry::proto::{self, any_value, metric, number_data_point},
+    sinks::util::{encode_namespace, EncodedLength},
+};
+use chrono::{DateTime, Utc};
+use prost::Message;
+use std::collections::BTreeMap;
+
+/// The `AggregationTemporality` of points that are the change since the
+/// previous one.
+const AGGREGATION_TEMPORALITY_DELTA: i32 = 1;
+/// The `AggregationTemporality` of points that are the total since the start
+/// of the series.
+const AGGREGATION_TEMPORALITY_CUMULATIVE: i32 = 2;
+
+const RESOURCE_TAG_PREFIX: &str = "resource.";
+
+/// The `DataPointFlags` of points ending their series, whose values are
+/// ignored.
+const FLAG_NO_RECORDED_VALUE: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Record {
+    Log(proto::LogRecord),
+    Metric(proto::Metric),
+}
+
+/// A record along with the attributes of the resource it comes from, which
+/// are only known once the event is encoded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Encoded {
+    pub resource: Vec<proto::KeyValue>,
+    pub record: Record,
+}
+
+impl EncodedLength for Encoded {
+    fn encoded_length(&self) -> usize {
+        let record = match &self.record {
+            Record::Log(record) => record.encoded_len(),
+            Record::Metric(metric) => metric.encoded_len(),
+        };
+        record
+            + self
+                .resource
+                .iter()
+                .map(|attribute| attribute.encoded_len())
+                .sum::<usize>()
+    }
+}
+
+/// Encodes a log, the attributes of `resource` overriding the ones of its
+/// `resource` field.
+pub fn encode_log(mut log: LogEvent, resource: BTreeMap<String, Value>) -> Encoded {
+    let body = log
+        .remove(log_schema().message_key())
+        .map(value_to_any_value);
+    let time_unix_nano = log
+        .remove(log_schema().timestamp_key())
+        .and_then(|timestamp| timestamp_nanos(timestamp.as_timestamp()?))
+        .unwrap_or(0);
+    let observed_time_unix_nano = log
+        .remove("observed_timestamp")
+        .and_then(|timestamp| timestamp_nanos(timestamp.as_timestamp()?))
+        .or_else(|| timestamp_nanos(&Utc::now()))
+        .unwrap_or(0);
+
+    let severity_text = log
+        .remove("severity_text")
+        .map(|severity| severity.to_string_lossy())
+        .unwrap_or_default();
+    let severity_number = match log.remove("severity_number") {
+        Some(Value::Integer(severity)) => severity as i32,
+        _ => 0,
+    };
+    let trace_id = remove_id(&mut log, trace::TRACE_ID);
+    let span_id = remove_id(&mut log, trace::SPAN_ID);
+
+    let mut resource_attributes = remove_map(&mut log, trace::RESOURCE);
+    resource_attributes.extend(resource);
+
+    // Any other field is sent as an attribute.
+    let mut attributes = remove_map(&mut log, trace::ATTRIBUTES);
+    attributes.extend(log);
+
+    Encoded {
+        resource: key_values(resource_attributes),
+        record: Record::Log(proto::LogRecord {
+            time_unix_nano,
+            observed_time_unix_nano,
+            severity_number,
+            severity_text,
+            body,
+            attributes: key_values(attributes),
+            dropped_attributes_count: 0,
+            flags: 0,
+            trace_id,
+            span_id,
+        }),
+    }
+}
+
+/// Encodes a metric, the attributes of `resource` overriding the ones of its
+/// `resource.*` tags. Sets and distributions have no equivalent in OTLP, so
+/// `None` is returned for them.
+pub fn encode_metric(metric: Metric, resource: BTreeMap<String, Value>) -> Option<Encoded> {
+    let name = encode_namespace(metric.namespace(), '_', metric.name());
+
+    let mut resource_attributes = BTreeMap::new();
+    let mut attributes = BTreeMap::new();
+    for (key, value) in metric.tags().cloned().unwrap_or_default() {
+        match key.strip_prefix(RESOURCE_TAG_PREFIX) {
+            Some(key) => resource_attributes.insert(key.to_owned(), Value::from(value)),
+            None => attributes.insert(key, Value::from(value)),
+        };
+    }
+    resource_attributes.extend(resource);
+    let attributes = key_values(attributes);
+
+    let time_unix_nano =
+        timestamp_nanos(&metric.data.timestamp.unwrap_or_else(Utc::now)).unwrap_or(0);
+    let aggregation_temporality = match metric.data.kind {
+        MetricKind::Incremental => AGGREGATION_TEMPORALITY_DELTA,
+        MetricKind::Absolute => AGGREGATION_TEMPORALITY_CUMULATIVE,
+    };
+    let flags = if metric.is_stale() {
+        FLAG_NO_RECORDED_VALUE
+    } else {
+        0
+    };
+    let number_point = |value| proto::NumberDataPoint {
+        attributes: attributes.clone(),
+        start_time_unix_nano: 0,
+        time_unix_nano,
+        value: Some(number_data_point::Value::AsDouble(value)),
+        flags,
+    };
+
+    let data = match metric.data.value {
+        MetricValue::Counter { value } => metric::Data::Sum(proto::Sum {
+            data_points: vec![number_point(value)],
+            aggregation_temporality,
+            is_monotonic: true,
+        }),
+        MetricValue::Gauge { value } => match metric.data.kind {
+            MetricKind::Absolute => metric::Data::Gauge(proto::Gauge {
+                data_points: vec![number_point(value)],
+            }),
+            // A change of a gauge is the delta of a sum that can decrease.
+            MetricKind::Incremental => metric::Data::Sum(proto::Sum {
+                data_points: vec![number_point(value)],
+                aggregation_temporality,
+                is_monotonic: false,
+            }),
+        },
+        MetricValue::AggregatedHistogram {
+            buckets,
+            count,
+            sum,
+        } => {
+            let mut bucket_counts = buckets
+                .iter()
+                .map(|bucket| bucket.count as u64)
+                .collect::<Vec<_>>();
+            // The last bucket counts the values above the highest bound.
+            let bucketed = bucket_counts.iter().sum::<u64>();
+            bucket_counts.push((count as u64).saturating_sub(bucketed));
+
+            metric::Data::Histogram(proto::Histogram {
+                data_points: vec![proto::HistogramDataPoint {
+                    attributes,
+                    start_time_unix_nano: 0,
+                    time_unix_nano,
+                    count: count as u64,
+                    sum,
+                    bucket_counts,
+                    explicit_bounds: buckets.iter().map(|bucket| bucket.upper_limit).collect(),
+                    flags,
+                }],
+                aggregation_temporality,
+            })
+        }
+        MetricValue::AggregatedSummary {
+            quantiles,
+            count,
+            sum,
+        } => metric::Data::Summary(proto::Summary {
+            data_points: vec![proto::SummaryDataPoint {
+                attributes,
+                start_time_unix_nano: 0,
+                time_unix_nano,
+                count: count as u64,
+                sum,
+                quantile_values: quantiles
+                    .iter()
+                    .map(|quantile| proto::summary_data_point::ValueAtQuantile {
+                        quantile: quantile.upper_limit,
+                        value: quantile.value,
+                    })
+                    .collect(),
+                flags,
+            }],
+        }),
+        MetricValue::Set { .. } | MetricValue::Distribution { .. } => return None,
+    };
+
+    Some(Encoded {
+        resource: key_values(resource_attributes),
+        record: Record::Metric(proto::Metric {
+            name,
+            description: String::new(),
+            unit: String::new(),
+            data: Some(data),
+        }),
+    })
+}
+
+fn remove_map(log: &mut LogEvent, key: &str) -> BTreeMap<String, Value> {
+    match log.remove(key) {
+        Some(Value::Map(map)) => map,
+        Some(value) => {
+            // Not a map, so it's kept as an attribute of the same name.
+            log.insert_flat(key, value);
+            BTreeMap::new()
+        }
+        None => BTreeMap::new(),
+    }
+}
+
+/// Removes a hex encoded trace or span ID, which are sent as bytes.
+fn remove_id(log: &mut LogEvent, key: &str) -> Vec<u8> {
+    log.remove(key)
+        .and_then(|id| parse_hex(&id.to_string_lossy()))
+        .unwrap_or_default()
+}
+
+fn key_values(attributes: BTreeMap<String, Value>) -> Vec<proto::KeyValue> {
+    attributes
+        .into_iter()
+        .map(|(key, value)| proto::KeyValue {
+            key,
+            value: Some(value_to_any_value(value)),
+        })
+        .collect()
+}
+
+fn value_to_any_value(value: Value) -> proto::AnyValue {
+    let value = match value {
+        Value::Bytes(bytes) => match String::from_utf8(bytes.to_vec()) {
+            Ok(string) => any_value::Value::StringValue(string),
+            Err(error) => any_value::Value::BytesValue(error.into_bytes()),
+        },
+        Value::Integer(value) => any_value::Value::IntValue(value),
+        Value::Float(value) => any_value::Value::DoubleValue(value),
+        Value::Boolean(value) => any_value::Value::BoolValue(value),
+        Value::Timestamp(_) => any_value::Value::StringValue(value.to_string_lossy()),
+        Value::Map(map) => any_value::Value::KvlistValue(proto::KeyValueList {
+            values: key_values(map),
+        }),
+        Value::Array(array) => any_value::Value::ArrayValue(proto::ArrayValue {
+            values: array.into_iter().map(value_to_any_value).collect(),
+        }),
+        Value::Null => return proto::AnyValue { value: None },
+    };
+    proto::AnyValue { value: Some(value) }
+}
+
+/// OTLP timestamps are nanoseconds since the epoch, which can't represent
+/// times before it.
+fn timestamp_nanos(timestamp: &DateTime<Utc>) -> Option<u64> {
+    let nanos = timestamp.timestamp_nanos();
+    if nanos > 0 {
+        Some(nanos as u64)
+    } else {
+        None
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::{Bucket, MetricTags};
+    use chrono::TimeZone;
+
+    fn string_attribute(key: &str, value: &str) -> proto::KeyValue {
+        proto::KeyValue {
+            key: key.to_owned(),
+            value: Some(proto::AnyValue {
+                value: Some(any_value::Value::StringValue(value.to_owned())),
+            }),
+        }
+    }
+
+    #[test]
+    fn encodes_logs() {
+        let mut log = LogEvent::default();
+        log.insert(log_schema().message_key(), "hello");
+        log.insert(
+            log_schema().timestamp_key(),
+            Utc.ymd(2021, 1, 1).and_hms(0, 0, 1),
+        );
+        log.insert("severity_text", "INFO");
+        log.insert("severity_number", 9);
+        log.insert(trace::TRACE_ID, "0a0B");
+        log.insert("http.method", "GET");
+        let mut resource = BTreeMap::new();
+        resource.insert("host.name".to_owned(), Value::from("event"));
+        resource.insert("service.name".to_owned(), Value::from("checkout"));
+        log.insert_flat(trace::RESOURCE, resource);
+
+        let mut config_resource = BTreeMap::new();
+        config_resource.insert("host.name".to_owned(), Value::from("config"));
+        let encoded = encode_log(log, config_resource);
+
+        assert_eq!(
+            encoded.resource,
+            vec![
+                string_attribute("host.name", "config"),
+                string_attribute("service.name", "checkout"),
+            ]
+        );
+        let record = match encoded.record {
+            Record::Log(record) => record,
+            record => panic!("unexpected record {:?}", record),
+        };
+        assert_eq!(
+            record.body,
+            Some(proto::AnyValue {
+                value: Some(any_value::Value::StringValue("hello".to_owned())),
+            })
+        );
+        assert_eq!(record.time_unix_nano, 1_609_459_201_000_000_000);
+        assert!(record.observed_time_unix_nano > record.time_unix_nano);
+        assert_eq!(record.severity_text, "INFO");
+        assert_eq!(record.severity_number, 9);
+        assert_eq!(record.trace_id, vec![0x0a, 0x0b]);
+        assert_eq!(
+            record.attributes,
+            vec![string_attribute("http.method", "GET")]
+        );
+    }
+
+    #[test]
+    fn encodes_metrics() {
+        let mut tags = MetricTags::new();
+        tags.insert("resource.service.name".to_owned(), "checkout".to_owned());
+        tags.insert("path".to_owned(), "/".to_owned());
+
+        let counter = Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 3.0 },
+        )
+        .with_namespace(Some("http"))
+        .with_tags(Some(tags.clone()));
+        let encoded = encode_metric(counter, BTreeMap::new()).unwrap();
+
+        assert_eq!(
+            encoded.resource,
+            vec![string_attribute("service.name", "checkout")]
+        );
+        let metric = match encoded.record {
+            Record::Metric(metric) => metric,
+            record => panic!("unexpected record {:?}", record),
+        };
+        assert_eq!(metric.name, "http_requests");
+        match metric.data {
+            Some(metric::Data::Sum(sum)) => {
+                assert!(sum.is_monotonic);
+                assert_eq!(sum.aggregation_temporality, AGGREGATION_TEMPORALITY_DELTA);
+                assert_eq!(
+                    sum.data_points[0].attributes,
+                    vec![string_attribute("path", "/")]
+                );
+                assert_eq!(
+                    sum.data_points[0].value,
+                    Some(number_data_point::Value::AsDouble(3.0))
+                );
+            }
+            data => panic!("unexpected data {:?}", data),
+        }
+
+        let histogram = Metric::new(
+            "latency",
+            MetricKind::Absolute,
+            MetricValue::AggregatedHistogram {
+                buckets: vec![
+                    Bucket {
+                        upper_limit: 0.1,
+                        count: 2,
+                    },
+                    Bucket {
+                        upper_limit: 1.0,
+                        count: 3,
+                    },
+                ],
+                count: 6,
+                sum: 4.5,
+            },
+        );
+        let encoded = encode_metric(histogram, BTreeMap::new()).unwrap();
+        match encoded.record {
+            Record::Metric(proto::Metric {
+                data: Some(metric::Data::Histogram(histogram)),
+                ..
+            }) => {
+                let point = &histogram.data_points[0];
+                assert_eq!(point.explicit_bounds, vec![0.1, 1.0]);
+                assert_eq!(point.bucket_counts, vec![2, 3, 1]);
+                assert_eq!(
+                    histogram.aggregation_temporality,
+                    AGGREGATION_TEMPORALITY_CUMULATIVE
+                );
+            }
+            record => panic!("unexpected record {:?}", record),
+        }
+
+        let set = Metric::new(
+            "users",
+            MetricKind::Incremental,
+            MetricValue::Set {
+                values: vec!["alice".to_owned()].into_iter().collect(),
+            },
+        );
+        assert_eq!(encode_metric(set, BTreeMap::new()), None);
+    }
+
+    #[test]
+    fn encodes_stale_metrics() {
+        let gauge = MetricValue::Gauge { value: 1.0 };
+        let stale = Metric::new("temperature", MetricKind::Absolute, gauge.stale().unwrap());
+        match encode_metric(stale, BTreeMap::new()).unwrap().record {
+            Record::Metric(proto::Metric {
+                data: Some(metric::Data::Gauge(gauge)),
+                ..
+            }) => assert_eq!(gauge.data_points[0].flags, FLAG_NO_RECORDED_VALUE),
+            record => panic!("unexpected record {:?}", record),
+        }
+    }
+}
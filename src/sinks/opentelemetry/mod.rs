@@ -0,0 +1,635 @@
+//! OpenTelemetry sink
+//!
+//! Exports logs and metrics to OpenTelemetry collectors and backends, with
+//! the `Export` calls of the OTLP/gRPC services or the OTLP/HTTP endpoints.
+//!
+//! https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/protocol/otlp.md
+
+mod encode;
+
+use self::encode::{Encoded, Record};
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::{Event, Metric, Value},
+    http::{Auth, HttpClient, MaybeAuth},
+    opentelemetry::{proto, GRPC_LOGS_PATH, GRPC_MESSAGE_PREFIX_LEN, GRPC_METRICS_PATH},
+    sinks::util::{
+        buffer::{
+            compression::GZIP_DEFAULT,
+            metrics::{MetricNormalize, MetricNormalizer, MetricSet},
+        },
+        encoding::{EncodingConfigWithDefault, EncodingConfiguration},
+        http::{HttpRetryLogic, HttpSink, PartitionHttpSink, RequestConfig},
+        retries::{RetryAction, RetryLogic},
+        BatchConfig, BatchSettings, Compression, PartitionBuffer, PartitionInnerBuffer,
+        TowerRequestConfig, UriSerde, VecBuffer,
+    },
+    template::Template,
+    tls::{TlsOptions, TlsSettings},
+};
+use bytes::Bytes;
+use flate2::write::GzEncoder;
+use futures::{stream, FutureExt, SinkExt};
+use http::{
+    header::{HeaderName, HeaderValue},
+    Request,
+};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, io::Write, time::Duration};
+
+const HTTP_LOGS_PATH: &str = "/v1/logs";
+const HTTP_METRICS_PATH: &str = "/v1/metrics";
+
+/// The gRPC status codes the OTLP specification asks to retry on.
+const GRPC_RETRIABLE_CODES: &[u32] = &[
+    1,  // CANCELLED
+    4,  // DEADLINE_EXCEEDED
+    8,  // RESOURCE_EXHAUSTED
+    10, // ABORTED
+    11, // OUT_OF_RANGE
+    14, // UNAVAILABLE
+    15, // DATA_LOSS
+];
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OpenTelemetrySinkConfig {
+    endpoint: UriSerde,
+    #[serde(default)]
+    protocol: Protocol,
+    #[serde(default)]
+    resource_attributes: BTreeMap<String, Template>,
+    #[serde(default)]
+    compression: Compression,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    encoding: EncodingConfigWithDefault<Encoding>,
+    auth: Option<Auth>,
+    #[serde(default)]
+    batch: BatchConfig,
+    #[serde(default)]
+    request: RequestConfig,
+    /// Overrides the global `expire_metrics_secs` option.
+    expire_metrics_secs: Option<u64>,
+    tls: Option<TlsOptions>,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Protocol {
+    #[derivative(Default)]
+    Grpc,
+    Http,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum Encoding {
+    #[derivative(Default)]
+    Default,
+}
+
+inventory::submit! {
+    SinkDescription::new::<OpenTelemetrySinkConfig>("opentelemetry")
+}
+
+impl GenerateConfig for OpenTelemetrySinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "http://localhost:4317"
+            resource_attributes."service.name" = "vector""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "opentelemetry")]
+impl SinkConfig for OpenTelemetrySinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        for (name, value) in &self.request.headers {
+            HeaderName::from_bytes(name.as_bytes())?;
+            HeaderValue::from_str(value)?;
+        }
+
+        let request_settings = self
+            .request
+            .tower
+            .unwrap_with(&TowerRequestConfig::default());
+        let batch_settings = BatchSettings::default()
+            .bytes(bytesize::mib(1u64))
+            .events(1000)
+            .timeout(1)
+            .parse_config(self.batch)?;
+
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = match self.protocol {
+            Protocol::Grpc => HttpClient::new_http2_only(tls)?,
+            Protocol::Http => HttpClient::new(tls)?,
+        };
+
+        let sink = OpenTelemetrySink {
+            endpoint: self.endpoint.with_default_parts().uri.to_string(),
+            auth: self.auth.choose_one(&self.endpoint.auth)?,
+            ..OpenTelemetrySink::from(self.clone())
+        };
+
+        let healthcheck = healthcheck(sink.clone(), client.clone()).boxed();
+
+        let mut normalizer = MetricNormalizer::<OpenTelemetryMetricNormalize>::default()
+            .with_expiration(
+                self.expire_metrics_secs
+                    .map(Duration::from_secs)
+                    .or_else(|| cx.expire_metrics()),
+            );
+
+        let sink = PartitionHttpSink::with_retry_logic(
+            sink,
+            PartitionBuffer::new(VecBuffer::new(batch_settings.size)),
+            OpenTelemetryRetryLogic,
+            request_settings,
+            batch_settings.timeout,
+            client,
+            cx.acker(),
+        )
+        .with_flat_map(move |event: Event| {
+            // The expired series are ended with points without values.
+            let mut events = normalizer
+                .expire()
+                .into_iter()
+                .map(Event::from)
+                .collect::<Vec<_>>();
+            events.extend(match event {
+                Event::Metric(_) => normalizer.apply(event),
+                event => Some(event),
+            });
+            stream::iter(events.into_iter().map(Ok))
+        })
+        .sink_map_err(|error| error!(message = "Fatal opentelemetry sink error.", %error));
+
+        Ok((super::VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "opentelemetry"
+    }
+}
+
+/// Metrics are sent as they are, the normalizer only tracks their series to
+/// expire them.
+struct OpenTelemetryMetricNormalize;
+
+impl MetricNormalize for OpenTelemetryMetricNormalize {
+    fn apply_state(_state: &mut MetricSet, metric: Metric) -> Option<Metric> {
+        Some(metric)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Signal {
+    Logs,
+    Metrics,
+}
+
+#[derive(Clone, Debug)]
+struct OpenTelemetrySink {
+    endpoint: String,
+    protocol: Protocol,
+    resource_attributes: BTreeMap<String, Template>,
+    compression: Compression,
+    encoding: EncodingConfigWithDefault<Encoding>,
+    auth: Option<Auth>,
+    headers: Vec<(String, String)>,
+}
+
+impl From<OpenTelemetrySinkConfig> for OpenTelemetrySink {
+    fn from(config: OpenTelemetrySinkConfig) -> Self {
+        Self {
+            endpoint: config.endpoint.uri.to_string(),
+            protocol: config.protocol,
+            resource_attributes: config.resource_attributes,
+            compression: config.compression,
+            encoding: config.encoding,
+            auth: config.auth,
+            headers: config.request.headers.into_iter().collect(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpSink for OpenTelemetrySink {
+    type Input = PartitionInnerBuffer<Encoded, Signal>;
+    type Output = PartitionInnerBuffer<Vec<Encoded>, Signal>;
+
+    fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
+        // Rendered before the encoding rules apply, so that the fields they
+        // read can be excluded from the attributes of the record.
+        let resource = self.render_resource_attributes(&event);
+        self.encoding.apply_rules(&mut event);
+
+        match event {
+            Event::Log(log) => Some(PartitionInnerBuffer::new(
+                encode::encode_log(log, resource),
+                Signal::Logs,
+            )),
+            Event::Metric(metric) => {
+                let name = metric.name().to_owned();
+                match encode::encode_metric(metric, resource) {
+                    Some(encoded) => Some(PartitionInnerBuffer::new(encoded, Signal::Metrics)),
+                    None => {
+                        warn!(
+                            message = "Sets and distributions are not supported by OTLP, dropping metric.",
+                            metric = %name,
+                            internal_log_rate_secs = 30
+                        );
+                        None
+                    }
+                }
+            }
+            Event::Trace(_) => {
+                warn!(
+                    message = "Traces are not supported, dropping event.",
+                    internal_log_rate_secs = 30
+                );
+                None
+            }
+        }
+    }
+
+    async fn build_request(&self, output: Self::Output) -> crate::Result<Request<Vec<u8>>> {
+        let (records, signal) = output.into_parts();
+        let message = encode_request(signal, records);
+
+        let (path, content_type) = match (self.protocol, signal) {
+            (Protocol::Grpc, Signal::Logs) => (GRPC_LOGS_PATH, "application/grpc"),
+            (Protocol::Grpc, Signal::Metrics) => (GRPC_METRICS_PATH, "application/grpc"),
+            (Protocol::Http, Signal::Logs) => (HTTP_LOGS_PATH, "application/x-protobuf"),
+            (Protocol::Http, Signal::Metrics) => (HTTP_METRICS_PATH, "application/x-protobuf"),
+        };
+        let uri = format!("{}{}", self.endpoint.trim_end_matches('/'), path);
+        let mut builder = Request::post(uri).header("Content-Type", content_type);
+
+        let (message, compressed) = match self.compression {
+            Compression::Gzip(level) => {
+                let level = level.unwrap_or(GZIP_DEFAULT) as u32;
+                let mut w = GzEncoder::new(Vec::new(), flate2::Compression::new(level));
+                w.write_all(&message).expect("Writing to Vec can't fail");
+                (w.finish().expect("Writing to Vec can't fail"), true)
+            }
+            Compression::None => (message, false),
+        };
+
+        let body = match self.protocol {
+            Protocol::Grpc => {
+                if compressed {
+                    builder = builder.header("grpc-encoding", "gzip");
+                }
+                builder = builder.header("te", "trailers");
+
+                let mut body = Vec::with_capacity(GRPC_MESSAGE_PREFIX_LEN + message.len());
+                body.push(compressed as u8);
+                body.extend_from_slice(&(message.len() as u32).to_be_bytes());
+                body.extend_from_slice(&message);
+                body
+            }
+            Protocol::Http => {
+                if compressed {
+                    builder = builder.header("Content-Encoding", "gzip");
+                }
+                message
+            }
+        };
+
+        for (header, value) in &self.headers {
+            builder = builder.header(header.as_str(), value.as_str());
+        }
+
+        let mut request = builder.body(body).unwrap();
+
+        if let Some(auth) = &self.auth {
+            auth.authorize(&mut request).await?;
+        }
+
+        Ok(request)
+    }
+}
+
+impl OpenTelemetrySink {
+    /// Renders the configured resource attributes. An attribute whose
+    /// template can't be rendered is left out.
+    fn render_resource_attributes(&self, event: &Event) -> BTreeMap<String, Value> {
+        self.resource_attributes
+            .iter()
+            .filter_map(|(key, template)| match template.render_string(event) {
+                Ok(value) => Some((key.clone(), Value::from(value))),
+                Err(missing) => {
+                    warn!(
+                        message = "Error rendering resource attribute template, leaving it out.",
+                        attribute = %key,
+                        ?missing,
+                        internal_log_rate_secs = 30
+                    );
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Encodes the export request of a batch, its records grouped by the
+/// resource they come from.
+fn encode_request(signal: Signal, batch: Vec<Encoded>) -> Vec<u8> {
+    let mut resources: Vec<(Vec<proto::KeyValue>, Vec<Record>)> = Vec::new();
+    for Encoded { resource, record } in batch {
+        match resources
+            .iter_mut()
+            .find(|(attributes, _)| *attributes == resource)
+        {
+            Some((_, records)) => records.push(record),
+            None => resources.push((resource, vec![record])),
+        }
+    }
+
+    let scope = Some(proto::InstrumentationScope {
+        name: "vector".to_owned(),
+        version: crate::vector_version().to_string(),
+        attributes: Vec::new(),
+    });
+    match signal {
+        Signal::Logs => encode_message(proto::ExportLogsServiceRequest {
+            resource_logs: resources
+                .into_iter()
+                .map(|(attributes, records)| proto::ResourceLogs {
+                    resource: Some(proto::Resource { attributes }),
+                    scope_logs: vec![proto::ScopeLogs {
+                        scope: scope.clone(),
+                        log_records: records
+                            .into_iter()
+                            .filter_map(|record| match record {
+                                Record::Log(record) => Some(record),
+                                Record::Metric(_) => None,
+                            })
+                            .collect(),
+                        schema_url: String::new(),
+                    }],
+                    schema_url: String::new(),
+                })
+                .collect(),
+        }),
+        Signal::Metrics => encode_message(proto::ExportMetricsServiceRequest {
+            resource_metrics: resources
+                .into_iter()
+                .map(|(attributes, records)| proto::ResourceMetrics {
+                    resource: Some(proto::Resource { attributes }),
+                    scope_metrics: vec![proto::ScopeMetrics {
+                        scope: scope.clone(),
+                        metrics: records
+                            .into_iter()
+                            .filter_map(|record| match record {
+                                Record::Metric(metric) => Some(metric),
+                                Record::Log(_) => None,
+                            })
+                            .collect(),
+                        schema_url: String::new(),
+                    }],
+                    schema_url: String::new(),
+                })
+                .collect(),
+        }),
+    }
+}
+
+fn encode_message(message: impl Message) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(message.encoded_len());
+    message.encode(&mut buf).expect("Writing to Vec can't fail");
+    buf
+}
+
+#[derive(Debug, Default, Clone)]
+struct OpenTelemetryRetryLogic;
+
+impl RetryLogic for OpenTelemetryRetryLogic {
+    type Error = hyper::Error;
+    type Response = http::Response<Bytes>;
+
+    fn is_retriable_error(&self, _error: &Self::Error) -> bool {
+        true
+    }
+
+    /// gRPC servers answer failed calls with a "Trailers-Only" response,
+    /// whose status is in the headers. As the trailers of other responses
+    /// aren't kept by the HTTP client, those are checked as plain HTTP.
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        let headers = response.headers();
+        let code = headers
+            .get("grpc-status")
+            .and_then(|code| code.to_str().ok())
+            .and_then(|code| code.parse::<u32>().ok());
+        let reason = || {
+            let message = headers
+                .get("grpc-message")
+                .map(|message| String::from_utf8_lossy(message.as_bytes()).into_owned())
+                .unwrap_or_default();
+            format!("gRPC status {}: {}", code.unwrap_or_default(), message)
+        };
+
+        match code {
+            None | Some(0) => HttpRetryLogic.should_retry_response(response),
+            Some(code) if GRPC_RETRIABLE_CODES.contains(&code) => RetryAction::Retry(reason()),
+            Some(_) => RetryAction::DontRetry(reason()),
+        }
+    }
+}
+
+/// Exports an empty batch of logs, which OTLP receivers accept without
+/// side effects.
+async fn healthcheck(sink: OpenTelemetrySink, client: HttpClient) -> crate::Result<()> {
+    let request = sink
+        .build_request(PartitionInnerBuffer::new(Vec::new(), Signal::Logs))
+        .await?;
+    let response = client.send(request.map(hyper::Body::from)).await?;
+    let (parts, body) = response.into_parts();
+    let response = http::Response::from_parts(parts, hyper::body::to_bytes(body).await?);
+
+    match OpenTelemetryRetryLogic.should_retry_response(&response) {
+        RetryAction::Successful => Ok(()),
+        RetryAction::Retry(reason) | RetryAction::DontRetry(reason) => Err(reason.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::metric::{Metric, MetricKind, MetricValue},
+        sinks::util::test::{build_test_server, load_sink},
+        test_util::{next_addr, trace_init},
+    };
+    use flate2::read::GzDecoder;
+    use futures::{stream, StreamExt};
+    use std::io::Read;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<OpenTelemetrySinkConfig>();
+    }
+
+    fn sink(config: &str) -> OpenTelemetrySink {
+        let (config, _cx) = load_sink::<OpenTelemetrySinkConfig>(config).unwrap();
+        OpenTelemetrySink::from(config)
+    }
+
+    fn log(message: &str, service: &str) -> Event {
+        let mut event = Event::from(message);
+        event.as_mut_log().insert("service", service);
+        event
+    }
+
+    #[tokio::test]
+    async fn builds_grpc_requests() {
+        let sink = sink(
+            r#"
+            endpoint = "http://localhost:4317/"
+            compression = "gzip"
+            resource_attributes."service.name" = "{{ service }}"
+            encoding.except_fields = ["service"]
+            request.headers.api-key = "secret"
+        "#,
+        );
+
+        let events = vec![
+            log("first", "checkout"),
+            log("second", "payment"),
+            log("third", "checkout"),
+        ];
+        let batch = events
+            .into_iter()
+            .map(|event| sink.encode_event(event).unwrap().into_parts().0)
+            .collect();
+        let request = sink
+            .build_request(PartitionInnerBuffer::new(batch, Signal::Logs))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            request.uri(),
+            "http://localhost:4317/opentelemetry.proto.collector.logs.v1.LogsService/Export"
+        );
+        assert_eq!(request.headers()["content-type"], "application/grpc");
+        assert_eq!(request.headers()["grpc-encoding"], "gzip");
+        assert_eq!(request.headers()["api-key"], "secret");
+
+        let body = request.body();
+        assert_eq!(body[0], 1);
+        let len = u32::from_be_bytes([body[1], body[2], body[3], body[4]]) as usize;
+        assert_eq!(body.len(), GRPC_MESSAGE_PREFIX_LEN + len);
+
+        let mut message = Vec::new();
+        GzDecoder::new(&body[GRPC_MESSAGE_PREFIX_LEN..])
+            .read_to_end(&mut message)
+            .unwrap();
+        let request = proto::ExportLogsServiceRequest::decode(message.as_slice()).unwrap();
+
+        // The records are grouped by resource.
+        assert_eq!(request.resource_logs.len(), 2);
+        let lines = request
+            .resource_logs
+            .iter()
+            .map(|resource_logs| resource_logs.scope_logs[0].log_records.len())
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec![2, 1]);
+        assert!(request.resource_logs[0].scope_logs[0].log_records[0]
+            .attributes
+            .is_empty());
+    }
+
+    #[test]
+    fn retries_grpc_statuses() {
+        let response = |code: Option<&str>| {
+            let mut response = http::Response::builder();
+            if let Some(code) = code {
+                response = response.header("grpc-status", code);
+            }
+            response.body(Bytes::new()).unwrap()
+        };
+
+        let logic = OpenTelemetryRetryLogic;
+        assert!(matches!(
+            logic.should_retry_response(&response(None)),
+            RetryAction::Successful
+        ));
+        assert!(matches!(
+            logic.should_retry_response(&response(Some("0"))),
+            RetryAction::Successful
+        ));
+        assert!(matches!(
+            logic.should_retry_response(&response(Some("14"))),
+            RetryAction::Retry(_)
+        ));
+        assert!(matches!(
+            logic.should_retry_response(&response(Some("3"))),
+            RetryAction::DontRetry(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn smoke_http() {
+        trace_init();
+
+        let addr = next_addr();
+        let (config, cx) = load_sink::<OpenTelemetrySinkConfig>(&format!(
+            r#"
+            endpoint = "http://{}"
+            protocol = "http"
+        "#,
+            addr
+        ))
+        .unwrap();
+        let (sink, _) = config.build(cx).await.unwrap();
+
+        let (mut rx, _trigger, server) = build_test_server(addr);
+        tokio::spawn(server);
+
+        let metric = Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        );
+        let events = vec![log("hello", "checkout"), Event::from(metric)];
+        sink.run(stream::iter(events)).await.unwrap();
+
+        let mut paths = Vec::new();
+        for _ in 0..2 {
+            let (parts, body) = rx.next().await.unwrap();
+            assert_eq!(parts.headers["content-type"], "application/x-protobuf");
+            match parts.uri.path() {
+                HTTP_LOGS_PATH => {
+                    let request = proto::ExportLogsServiceRequest::decode(body).unwrap();
+                    let record = &request.resource_logs[0].scope_logs[0].log_records[0];
+                    assert!(record.body.is_some());
+                }
+                HTTP_METRICS_PATH => {
+                    let request = proto::ExportMetricsServiceRequest::decode(body).unwrap();
+                    let metric = &request.resource_metrics[0].scope_metrics[0].metrics[0];
+                    assert_eq!(metric.name, "requests");
+                }
+                path => panic!("unexpected path {}", path),
+            }
+            paths.push(parts.uri.path().to_owned());
+        }
+        paths.sort();
+        assert_eq!(paths, vec![HTTP_LOGS_PATH, HTTP_METRICS_PATH]);
+    }
+}
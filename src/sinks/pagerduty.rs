@@ -0,0 +1,566 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::{Event, Value},
+    http::HttpClient,
+    internal_events::{
+        PagerDutyEventMissingDedupKey, PagerDutyEventMissingKeys, PagerDutyEventThrottled,
+    },
+    sinks::util::{
+        encoding::{EncodingConfigWithDefault, EncodingConfiguration},
+        http::{BatchedHttpSink, HttpSink},
+        BatchSettings, TowerRequestConfig, VecBuffer,
+    },
+    template::Template,
+    tls::{TlsOptions, TlsSettings},
+};
+use bytes::Bytes;
+use chrono::SecondsFormat;
+use futures::{future, FutureExt, SinkExt};
+use http::Request;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// The largest event accepted by the Events API, once serialized.
+const MAX_EVENT_BYTES: u64 = 512_000;
+
+const MAX_SUMMARY_LEN: usize = 1024;
+const MAX_DEDUP_KEY_LEN: usize = 255;
+
+/// The number of dedup keys above which the throttle forgets the ones whose
+/// window has ended.
+const MAX_THROTTLE_KEYS: usize = 10_000;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PagerDutyConfig {
+    #[serde(default = "default_endpoint")]
+    endpoint: String,
+    routing_key: String,
+    summary: Template,
+    severity: Option<Template>,
+    source: Option<Template>,
+    dedup_key: Option<Template>,
+    action: Option<Template>,
+    component: Option<Template>,
+    group: Option<Template>,
+    class: Option<Template>,
+    throttle_secs: Option<u64>,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    encoding: EncodingConfigWithDefault<Encoding>,
+    #[serde(default)]
+    request: TowerRequestConfig,
+    tls: Option<TlsOptions>,
+}
+
+fn default_endpoint() -> String {
+    "https://events.pagerduty.com/v2/enqueue".to_owned()
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum Encoding {
+    #[derivative(Default)]
+    Default,
+}
+
+/// The severities of PagerDuty alerts.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Severity {
+    Critical,
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    /// Reads the usual names of log levels, ignoring case.
+    fn parse(severity: &str) -> Option<Self> {
+        match severity.to_lowercase().as_str() {
+            "crit" | "critical" | "alert" | "emerg" | "emergency" | "fatal" | "panic" => {
+                Some(Severity::Critical)
+            }
+            "err" | "error" => Some(Severity::Error),
+            "warn" | "warning" => Some(Severity::Warning),
+            "trace" | "debug" | "info" | "information" | "notice" => Some(Severity::Info),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Severity::Critical => "critical",
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        }
+    }
+}
+
+/// The actions of the Events API, which open, acknowledge, or close the alert
+/// of a dedup key.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Action {
+    Trigger,
+    Acknowledge,
+    Resolve,
+}
+
+impl Action {
+    fn parse(action: &str) -> Option<Self> {
+        match action.to_lowercase().as_str() {
+            "trigger" => Some(Action::Trigger),
+            "acknowledge" | "ack" => Some(Action::Acknowledge),
+            "resolve" => Some(Action::Resolve),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Action::Trigger => "trigger",
+            Action::Acknowledge => "acknowledge",
+            Action::Resolve => "resolve",
+        }
+    }
+}
+
+inventory::submit! {
+    SinkDescription::new::<PagerDutyConfig>("pagerduty")
+}
+
+impl GenerateConfig for PagerDutyConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"routing_key = "${PAGERDUTY_ROUTING_KEY}"
+            summary = "{{ message }}""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "pagerduty")]
+impl SinkConfig for PagerDutyConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let sink = PagerDutySink::new(self.clone());
+
+        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        // The Events API only accepts a single event per request.
+        let batch_settings = BatchSettings::default()
+            .bytes(MAX_EVENT_BYTES)
+            .events(1)
+            .timeout(1);
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls)?;
+
+        let sink = BatchedHttpSink::new(
+            sink,
+            VecBuffer::new(batch_settings.size),
+            request_settings,
+            batch_settings.timeout,
+            client,
+            cx.acker(),
+        )
+        .sink_map_err(|error| error!(message = "Fatal pagerduty sink error.", %error));
+
+        Ok((
+            super::VectorSink::Sink(Box::new(sink)),
+            future::ok(()).boxed(),
+        ))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "pagerduty"
+    }
+}
+
+/// Lets through a single trigger of each dedup key per window of time.
+struct Throttle {
+    window: Duration,
+    triggers: Mutex<HashMap<String, Instant>>,
+}
+
+impl Throttle {
+    /// Records a trigger of `dedup_key`, returning `false` if it was already
+    /// triggered in the current window.
+    fn trigger(&self, dedup_key: &str) -> bool {
+        let now = Instant::now();
+        let mut triggers = self.triggers.lock().expect("Throttle lock is poisoned");
+        if triggers.len() >= MAX_THROTTLE_KEYS {
+            let window = self.window;
+            triggers.retain(|_, triggered| now.duration_since(*triggered) < window);
+        }
+
+        match triggers.get(dedup_key) {
+            Some(triggered) if now.duration_since(*triggered) < self.window => false,
+            _ => {
+                triggers.insert(dedup_key.to_owned(), now);
+                true
+            }
+        }
+    }
+
+    /// Forgets `dedup_key` once its alert is resolved, so that the next
+    /// trigger opens a new alert right away.
+    fn resolve(&self, dedup_key: &str) {
+        self.triggers
+            .lock()
+            .expect("Throttle lock is poisoned")
+            .remove(dedup_key);
+    }
+}
+
+struct PagerDutySink {
+    endpoint: String,
+    routing_key: String,
+    summary: Template,
+    severity: Option<Template>,
+    source: Option<Template>,
+    dedup_key: Option<Template>,
+    action: Option<Template>,
+    component: Option<Template>,
+    group: Option<Template>,
+    class: Option<Template>,
+    throttle: Option<Throttle>,
+    encoding: EncodingConfigWithDefault<Encoding>,
+}
+
+impl PagerDutySink {
+    fn new(config: PagerDutyConfig) -> Self {
+        Self {
+            endpoint: config.endpoint,
+            routing_key: config.routing_key,
+            summary: config.summary,
+            severity: config.severity,
+            source: config.source,
+            dedup_key: config.dedup_key,
+            action: config.action,
+            component: config.component,
+            group: config.group,
+            class: config.class,
+            throttle: config.throttle_secs.map(|secs| Throttle {
+                window: Duration::from_secs(secs),
+                triggers: Mutex::new(HashMap::new()),
+            }),
+            encoding: config.encoding,
+        }
+    }
+
+    fn render(template: &Option<Template>, event: &Event) -> Option<String> {
+        template
+            .as_ref()
+            .and_then(|template| template.render_string(event).ok())
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpSink for PagerDutySink {
+    type Input = Bytes;
+    type Output = Vec<Bytes>;
+
+    fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
+        // Events without a known action trigger alerts, as most events sent
+        // here are meant to.
+        let action = Self::render(&self.action, &event)
+            .and_then(|action| Action::parse(&action))
+            .unwrap_or(Action::Trigger);
+        let dedup_key = Self::render(&self.dedup_key, &event)
+            .map(|dedup_key| truncate("dedup_key", dedup_key, MAX_DEDUP_KEY_LEN));
+
+        let mut body = serde_json::Map::new();
+        body.insert("routing_key".into(), json!(self.routing_key));
+        body.insert("event_action".into(), json!(action.as_str()));
+
+        if action != Action::Trigger {
+            let dedup_key = match dedup_key {
+                Some(dedup_key) => dedup_key,
+                None => {
+                    emit!(PagerDutyEventMissingDedupKey {
+                        action: action.as_str()
+                    });
+                    return None;
+                }
+            };
+            if action == Action::Resolve {
+                if let Some(throttle) = &self.throttle {
+                    throttle.resolve(&dedup_key);
+                }
+            }
+            body.insert("dedup_key".into(), json!(dedup_key));
+            return encode_body(body);
+        }
+
+        if let (Some(throttle), Some(dedup_key)) = (&self.throttle, &dedup_key) {
+            if !throttle.trigger(dedup_key) {
+                emit!(PagerDutyEventThrottled { dedup_key });
+                return None;
+            }
+        }
+
+        let summary = self
+            .summary
+            .render_string(&event)
+            .map_err(|missing_keys| {
+                emit!(PagerDutyEventMissingKeys {
+                    keys: &missing_keys
+                });
+            })
+            .ok()?;
+        // Events without a known severity are taken as errors.
+        let severity = Self::render(&self.severity, &event)
+            .and_then(|severity| Severity::parse(&severity))
+            .unwrap_or(Severity::Error);
+        let source = Self::render(&self.source, &event)
+            .or_else(|| {
+                event
+                    .as_log()
+                    .get(log_schema().host_key())
+                    .map(Value::to_string_lossy)
+            })
+            .unwrap_or_else(|| "vector".to_owned());
+
+        let mut payload = serde_json::Map::new();
+        payload.insert(
+            "summary".into(),
+            json!(truncate("summary", summary, MAX_SUMMARY_LEN)),
+        );
+        payload.insert("source".into(), json!(source));
+        payload.insert("severity".into(), json!(severity.as_str()));
+        for (name, template) in &[
+            ("component", &self.component),
+            ("group", &self.group),
+            ("class", &self.class),
+        ] {
+            if let Some(value) = Self::render(template, &event) {
+                payload.insert((*name).into(), json!(value));
+            }
+        }
+
+        self.encoding.apply_rules(&mut event);
+        let mut log = event.into_log();
+        if let Some(Value::Timestamp(timestamp)) = log.remove(log_schema().timestamp_key()) {
+            payload.insert(
+                "timestamp".into(),
+                json!(timestamp.to_rfc3339_opts(SecondsFormat::Millis, true)),
+            );
+        }
+        if !log.is_empty() {
+            payload.insert("custom_details".into(), json!(&log));
+        }
+
+        if let Some(dedup_key) = dedup_key {
+            body.insert("dedup_key".into(), json!(dedup_key));
+        }
+        body.insert("payload".into(), json!(payload));
+        body.insert("client".into(), json!("Vector"));
+
+        encode_body(body)
+    }
+
+    async fn build_request(&self, mut events: Self::Output) -> crate::Result<Request<Vec<u8>>> {
+        let body = events.pop().map(|event| event.to_vec()).unwrap_or_default();
+
+        let request = Request::post(&self.endpoint)
+            .header("Content-Type", "application/json")
+            .body(body)?;
+
+        Ok(request)
+    }
+}
+
+fn encode_body(body: serde_json::Map<String, serde_json::Value>) -> Option<Bytes> {
+    serde_json::to_vec(&body)
+        .map(Bytes::from)
+        .map_err(|error| error!(message = "Failed to encode PagerDuty event.", %error))
+        .ok()
+}
+
+fn truncate(field: &'static str, mut value: String, max_len: usize) -> String {
+    if value.len() > max_len {
+        let mut len = max_len;
+        while !value.is_char_boundary(len) {
+            len -= 1;
+        }
+        value.truncate(len);
+        warn!(
+            message = "Field is too long for PagerDuty, truncating.",
+            %field,
+            %max_len,
+            internal_log_rate_secs = 30
+        );
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sinks::util::test::{build_test_server, load_sink},
+        test_util::{next_addr, trace_init},
+    };
+    use futures::{stream, StreamExt};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<PagerDutyConfig>();
+    }
+
+    fn sink(config: &str) -> PagerDutySink {
+        let (config, _cx) = load_sink::<PagerDutyConfig>(config).unwrap();
+        PagerDutySink::new(config)
+    }
+
+    fn encode(sink: &PagerDutySink, event: Event) -> Option<serde_json::Value> {
+        sink.encode_event(event)
+            .map(|body| serde_json::from_slice(&body).unwrap())
+    }
+
+    fn alert(service: &str, action: &str) -> Event {
+        let mut event = Event::from("disk is full");
+        let log = event.as_mut_log();
+        log.insert("service", service);
+        log.insert("action", action);
+        event
+    }
+
+    #[test]
+    fn encodes_triggers() {
+        let sink = sink(
+            r#"
+            routing_key = "key"
+            summary = "{{ service }}: {{ message }}"
+            severity = "{{ level }}"
+            dedup_key = "{{ service }}-disk"
+            component = "{{ service }}"
+            encoding.except_fields = ["level"]
+        "#,
+        );
+
+        let mut event = Event::from("disk is full");
+        let log = event.as_mut_log();
+        log.insert("level", "WARN");
+        log.insert("service", "db");
+        log.insert("host", "db-1");
+
+        let body = encode(&sink, event).unwrap();
+        assert_eq!(body["routing_key"], json!("key"));
+        assert_eq!(body["event_action"], json!("trigger"));
+        assert_eq!(body["dedup_key"], json!("db-disk"));
+        assert_eq!(body["payload"]["summary"], json!("db: disk is full"));
+        assert_eq!(body["payload"]["severity"], json!("warning"));
+        assert_eq!(body["payload"]["source"], json!("db-1"));
+        assert_eq!(body["payload"]["component"], json!("db"));
+        assert!(body["payload"]["timestamp"].is_string());
+        assert_eq!(
+            body["payload"]["custom_details"],
+            json!({"message": "disk is full", "service": "db", "host": "db-1"})
+        );
+    }
+
+    #[test]
+    fn encodes_resolves() {
+        let with_dedup_key = sink(
+            r#"
+            routing_key = "key"
+            summary = "{{ message }}"
+            dedup_key = "{{ service }}-disk"
+            action = "{{ action }}"
+        "#,
+        );
+        let without_dedup_key = sink(
+            r#"
+            routing_key = "key"
+            summary = "{{ message }}"
+            action = "{{ action }}"
+        "#,
+        );
+
+        let body = encode(&with_dedup_key, alert("db", "resolve")).unwrap();
+        assert_eq!(
+            body,
+            json!({"routing_key": "key", "event_action": "resolve", "dedup_key": "db-disk"})
+        );
+        assert!(encode(&without_dedup_key, alert("db", "resolve")).is_none());
+    }
+
+    #[test]
+    fn throttles_dedup_keys() {
+        let sink = sink(
+            r#"
+            routing_key = "key"
+            summary = "{{ message }}"
+            dedup_key = "{{ service }}"
+            action = "{{ action }}"
+            throttle_secs = 60
+        "#,
+        );
+
+        assert!(encode(&sink, alert("db", "trigger")).is_some());
+        assert!(encode(&sink, alert("db", "trigger")).is_none());
+        assert!(encode(&sink, alert("web", "trigger")).is_some());
+        assert!(encode(&sink, alert("db", "resolve")).is_some());
+        assert!(encode(&sink, alert("db", "trigger")).is_some());
+    }
+
+    #[test]
+    fn drops_missing_summary() {
+        let sink = sink(
+            r#"
+            routing_key = "key"
+            summary = "{{ missing }}"
+        "#,
+        );
+
+        assert!(encode(&sink, Event::from("message")).is_none());
+    }
+
+    #[tokio::test]
+    async fn smoke() {
+        trace_init();
+
+        let addr = next_addr();
+        let (config, cx) = load_sink::<PagerDutyConfig>(&format!(
+            r#"
+            endpoint = "http://{}/v2/enqueue"
+            routing_key = "key"
+            summary = "{{{{ message }}}}"
+        "#,
+            addr
+        ))
+        .unwrap();
+        let (sink, _) = config.build(cx).await.unwrap();
+
+        let (mut rx, _trigger, server) = build_test_server(addr);
+        tokio::spawn(server);
+
+        let events = vec![Event::from("first"), Event::from("second")];
+        sink.run(stream::iter(events)).await.unwrap();
+
+        let mut summaries = Vec::new();
+        for _ in 0..2 {
+            let (parts, body) = rx.next().await.unwrap();
+            assert_eq!(parts.uri.path(), "/v2/enqueue");
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            summaries.push(body["payload"]["summary"].as_str().unwrap().to_owned());
+        }
+        summaries.sort();
+        assert_eq!(summaries, vec!["first", "second"]);
+    }
+}
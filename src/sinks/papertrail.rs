@@ -136,6 +136,7 @@ mod tests {
                 only_fields: None,
                 except_fields: Some(vec!["magic".into()]),
                 timestamp_format: None,
+                remap: None,
             },
         )
         .unwrap();
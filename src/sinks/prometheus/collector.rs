@@ -1,5 +1,5 @@
 use crate::{
-    event::metric::{Metric, MetricValue, StatisticKind},
+    event::metric::{Metric, MetricValue, StatisticKind, STALE_NAN_BITS},
     prometheus::{proto, METRIC_NAME_LABEL},
     sinks::util::{encode_namespace, statistic::DistributionStatistic},
 };
@@ -37,6 +37,16 @@ pub(super) trait MetricCollector {
         let name = encode_namespace(metric.namespace().or(default_namespace), '_', metric.name());
         let name = &name;
         let timestamp = metric.data.timestamp.map(|t| t.timestamp_millis());
+        // Every sample of a series that ended is the stale NaN, including the
+        // counts of histograms and summaries.
+        let stale = metric.data.value.is_stale();
+        let sample = |value: f64| {
+            if stale {
+                f64::from_bits(STALE_NAN_BITS)
+            } else {
+                value
+            }
+        };
 
         if metric.data.kind.is_absolute() {
             let tags = metric.tags();
@@ -143,7 +153,7 @@ pub(super) trait MetricCollector {
                             timestamp,
                             &name,
                             "_bucket",
-                            value,
+                            sample(value),
                             tags,
                             Some(("le", bucket.upper_limit.to_string())),
                         );
@@ -152,12 +162,19 @@ pub(super) trait MetricCollector {
                         timestamp,
                         &name,
                         "_bucket",
-                        *count as f64,
+                        sample(*count as f64),
                         tags,
                         Some(("le", "+Inf".to_string())),
                     );
                     self.emit_value(timestamp, &name, "_sum", *sum, tags, None);
-                    self.emit_value(timestamp, &name, "_count", *count as f64, tags, None);
+                    self.emit_value(
+                        timestamp,
+                        &name,
+                        "_count",
+                        sample(*count as f64),
+                        tags,
+                        None,
+                    );
                 }
                 MetricValue::AggregatedSummary {
                     quantiles,
@@ -175,7 +192,14 @@ pub(super) trait MetricCollector {
                         );
                     }
                     self.emit_value(timestamp, &name, "_sum", *sum, tags, None);
-                    self.emit_value(timestamp, &name, "_count", *count as f64, tags, None);
+                    self.emit_value(
+                        timestamp,
+                        &name,
+                        "_count",
+                        sample(*count as f64),
+                        tags,
+                        None,
+                    );
                 }
             }
         }
@@ -562,6 +586,28 @@ vector_users 0
         encode_one::<T>(Some("vector"), &[], &[], true, &metric)
     }
 
+    #[test]
+    fn encodes_stale_histogram_request() {
+        let histogram = MetricValue::AggregatedHistogram {
+            buckets: crate::buckets![1.0 => 1, 2.0 => 2],
+            count: 3,
+            sum: 4.0,
+        };
+        let metric = Metric::new("requests", MetricKind::Absolute, histogram.stale().unwrap());
+        let request = encode_one::<TimeSeries>(None, &[], &[], false, &metric);
+
+        let samples = request
+            .timeseries
+            .iter()
+            .flat_map(|series| series.samples.iter())
+            .collect::<Vec<_>>();
+        // Three buckets, the sum, and the count.
+        assert_eq!(samples.len(), 5);
+        assert!(samples
+            .iter()
+            .all(|sample| sample.value.to_bits() == STALE_NAN_BITS));
+    }
+
     #[test]
     fn encodes_distribution_text() {
         assert_eq!(
@@ -444,6 +444,7 @@ mod tests {
                 ..m1.series.clone()
             },
             data: m1.data.clone(),
+            metadata: Default::default(),
         };
 
         let metrics = vec![
@@ -453,6 +454,7 @@ mod tests {
                     value: MetricValue::Counter { value: 32. },
                     ..m1.data.clone()
                 },
+                metadata: Default::default(),
             }),
             Event::Metric(Metric {
                 series: m2.series.clone(),
@@ -460,6 +462,7 @@ mod tests {
                     value: MetricValue::Counter { value: 33. },
                     ..m2.data.clone()
                 },
+                metadata: Default::default(),
             }),
             Event::Metric(Metric {
                 series: m1.series.clone(),
@@ -467,6 +470,7 @@ mod tests {
                     value: MetricValue::Counter { value: 40. },
                     ..m1.data.clone()
                 },
+                metadata: Default::default(),
             }),
         ];
 
@@ -4,11 +4,12 @@ use crate::{
     event::{Event, Metric},
     http::{Auth, HttpClient},
     internal_events::PrometheusTemplateRenderingError,
+    prometheus::proto,
     sinks::{
         self,
         util::{
             buffer::metrics::{MetricNormalize, MetricNormalizer, MetricSet, MetricsBuffer},
-            http::HttpRetryLogic,
+            http::{AuthRetryLogic, RequestConfig},
             BatchConfig, BatchSettings, PartitionBatchSink, PartitionBuffer, PartitionInnerBuffer,
             TowerRequestConfig,
         },
@@ -18,19 +19,100 @@ use crate::{
 };
 use bytes::{Bytes, BytesMut};
 use futures::{future::BoxFuture, stream, FutureExt, SinkExt};
-use http::Uri;
+use http::{
+    header::{HeaderName, HeaderValue},
+    Uri,
+};
+use indexmap::IndexMap;
 use prost::Message;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
-use std::task;
+use std::{task, time::Duration};
 use tower::ServiceBuilder;
 
+const DEFAULT_TENANT_HEADER: &str = "X-Scope-OrgID";
+
 #[derive(Debug, Snafu)]
 enum Errors {
     #[snafu(display(r#"Prometheus remote_write sink cannot accept "set" metrics"#))]
     SetMetricInvalid,
 }
 
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("{}: {}", source, name))]
+    InvalidHeaderName {
+        name: String,
+        source: http::header::InvalidHeaderName,
+    },
+    #[snafu(display("{}: {}", source, value))]
+    InvalidHeaderValue {
+        value: String,
+        source: http::header::InvalidHeaderValue,
+    },
+    #[snafu(display(
+        "Header {:?} is set by the sink and can't be set in `request.headers`",
+        name
+    ))]
+    ReservedHeader { name: String },
+    #[snafu(display("`max_samples_per_request` must be greater than 0"))]
+    ZeroMaxSamples,
+    #[snafu(display(
+        "`import_format` is only supported with `compatibility = \"victoriametrics\"`"
+    ))]
+    ImportFormatUnsupported,
+    #[snafu(display(
+        "`max_samples_per_request` is only supported with `import_format = \"remote_write\"`"
+    ))]
+    MaxSamplesUnsupported,
+    #[snafu(display(
+        "VictoriaMetrics reads the tenant from the endpoint path, not from `tenant_id`"
+    ))]
+    TenantUnsupported,
+    #[snafu(display(
+        "Chronosphere requires `auth` or an authentication header in `request.headers`"
+    ))]
+    MissingAuthentication,
+}
+
+/// The backend the metrics are written to, whose quirks the requests are
+/// adapted to.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum Compatibility {
+    #[derivative(Default)]
+    Prometheus,
+    /// Requires authentication, and drops the samples without timestamps.
+    Chronosphere,
+    /// Drops the samples without timestamps.
+    M3,
+    /// Reads the tenant from the endpoint path, and can import the Prometheus
+    /// text format.
+    Victoriametrics,
+}
+
+impl Compatibility {
+    /// Whether the backend drops the samples without timestamps, which are
+    /// then sent with the current time.
+    fn requires_timestamps(self) -> bool {
+        matches!(self, Compatibility::Chronosphere | Compatibility::M3)
+    }
+}
+
+/// The format the metrics are written in.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum ImportFormat {
+    /// The snappy compressed protobuf of the remote_write protocol.
+    #[derivative(Default)]
+    RemoteWrite,
+    /// The Prometheus text exposition format, for the
+    /// `/api/v1/import/prometheus` endpoint of VictoriaMetrics.
+    Prometheus,
+}
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct RemoteWriteConfig {
@@ -46,10 +128,22 @@ pub(crate) struct RemoteWriteConfig {
     #[serde(default)]
     pub batch: BatchConfig,
     #[serde(default)]
-    pub request: TowerRequestConfig,
+    pub request: RequestConfig,
 
     #[serde(default)]
     pub tenant_id: Option<Template>,
+    /// The header the rendered `tenant_id` is sent in, `X-Scope-OrgID` by default.
+    pub tenant_header: Option<String>,
+
+    #[serde(default)]
+    pub compatibility: Compatibility,
+    #[serde(default)]
+    pub import_format: ImportFormat,
+    /// The most samples sent in one request, the batches above it being split
+    /// into several requests.
+    pub max_samples_per_request: Option<usize>,
+    /// Overrides the global `expire_metrics_secs` option.
+    pub expire_metrics_secs: Option<u64>,
 
     pub tls: Option<TlsOptions>,
 
@@ -74,12 +168,16 @@ impl SinkConfig for RemoteWriteConfig {
         cx: config::SinkContext,
     ) -> crate::Result<(sinks::VectorSink, sinks::Healthcheck)> {
         let endpoint = self.endpoint.parse::<Uri>().context(sinks::UriParseError)?;
+        self.validate()?;
+        if let Some(auth) = &self.auth {
+            auth.validate()?;
+        }
         let tls_settings = TlsSettings::from_options(&self.tls)?;
         let batch = BatchSettings::default()
             .events(1_000)
             .timeout(1)
             .parse_config(self.batch)?;
-        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+        let request = self.request.tower.unwrap_with(&REQUEST_DEFAULTS);
         let buckets = self.buckets.clone();
         let quantiles = self.quantiles.clone();
 
@@ -87,7 +185,7 @@ impl SinkConfig for RemoteWriteConfig {
         let tenant_id = self.tenant_id.clone();
         let auth = self.auth.clone();
 
-        let healthcheck = healthcheck(endpoint.clone(), client.clone()).boxed();
+        let healthcheck = healthcheck(endpoint.clone(), self.auth.clone(), client.clone()).boxed();
         let service = RemoteWriteService {
             endpoint,
             default_namespace: self.default_namespace.clone(),
@@ -95,28 +193,51 @@ impl SinkConfig for RemoteWriteConfig {
             buckets,
             quantiles,
             auth,
+            headers: self.request.headers.clone(),
+            tenant_header: self.tenant_header().to_owned(),
+            compatibility: self.compatibility,
+            import_format: self.import_format,
+            max_samples_per_request: self.max_samples_per_request,
         };
 
         let sink = {
-            let service = request.service(HttpRetryLogic, service);
+            let retry_logic = AuthRetryLogic::new(self.auth.clone());
+            let service = request.service(retry_logic, service);
             let service = ServiceBuilder::new().service(service);
             let buffer = PartitionBuffer::new(MetricsBuffer::new(batch.size));
-            let mut normalizer = MetricNormalizer::<PrometheusMetricNormalize>::default();
+            let mut normalizer = MetricNormalizer::<PrometheusMetricNormalize>::default()
+                .with_expiration(
+                    self.expire_metrics_secs
+                        .map(Duration::from_secs)
+                        .or_else(|| cx.expire_metrics()),
+                );
+            // Only the remote_write protocol has staleness markers.
+            let send_stale_markers = self.import_format == ImportFormat::RemoteWrite;
 
             PartitionBatchSink::new(service, buffer, batch.timeout, cx.acker())
                 .with_flat_map(move |event: Event| {
-                    stream::iter(normalizer.apply(event).map(|event| {
-                        let tenant_id = tenant_id.as_ref().and_then(|template| {
-                            template
-                                .render_string(&event)
-                                .map_err(|fields| {
-                                    emit!(PrometheusTemplateRenderingError { fields })
-                                })
-                                .ok()
-                        });
-                        let key = PartitionKey { tenant_id };
-                        Ok(PartitionInnerBuffer::new(event, key))
-                    }))
+                    let mut metrics = normalizer.expire();
+                    if !send_stale_markers {
+                        metrics.clear();
+                    }
+                    let events = metrics
+                        .into_iter()
+                        .map(Event::from)
+                        .chain(normalizer.apply(event))
+                        .map(|event| {
+                            let tenant_id = tenant_id.as_ref().and_then(|template| {
+                                template
+                                    .render_string(&event)
+                                    .map_err(|fields| {
+                                        emit!(PrometheusTemplateRenderingError { fields })
+                                    })
+                                    .ok()
+                            });
+                            let key = PartitionKey { tenant_id };
+                            Ok(PartitionInnerBuffer::new(event, key))
+                        })
+                        .collect::<Vec<_>>();
+                    stream::iter(events)
                 })
                 .sink_map_err(
                     |error| error!(message = "Prometheus remote_write sink error.", %error),
@@ -135,16 +256,75 @@ impl SinkConfig for RemoteWriteConfig {
     }
 }
 
+impl RemoteWriteConfig {
+    fn tenant_header(&self) -> &str {
+        self.tenant_header
+            .as_deref()
+            .unwrap_or(DEFAULT_TENANT_HEADER)
+    }
+
+    /// Checks the options against the quirks of the backend.
+    fn validate(&self) -> Result<(), BuildError> {
+        let tenant_header = self.tenant_header();
+        HeaderName::from_bytes(tenant_header.as_bytes()).context(InvalidHeaderName {
+            name: tenant_header,
+        })?;
+
+        for (name, value) in &self.request.headers {
+            HeaderName::from_bytes(name.as_bytes()).with_context(|| InvalidHeaderName { name })?;
+            HeaderValue::from_bytes(value.as_bytes())
+                .with_context(|| InvalidHeaderValue { value })?;
+
+            let reserved = (self.auth.is_some() && name.eq_ignore_ascii_case("Authorization"))
+                || (self.tenant_id.is_some() && name.eq_ignore_ascii_case(tenant_header))
+                || ["Content-Encoding", "Content-Type"]
+                    .iter()
+                    .any(|reserved| name.eq_ignore_ascii_case(reserved));
+            if reserved {
+                return Err(BuildError::ReservedHeader { name: name.clone() });
+            }
+        }
+
+        match self.max_samples_per_request {
+            Some(0) => return Err(BuildError::ZeroMaxSamples),
+            Some(_) if self.import_format != ImportFormat::RemoteWrite => {
+                return Err(BuildError::MaxSamplesUnsupported)
+            }
+            _ => (),
+        }
+
+        match self.compatibility {
+            Compatibility::Victoriametrics if self.tenant_id.is_some() => {
+                Err(BuildError::TenantUnsupported)
+            }
+            Compatibility::Victoriametrics => Ok(()),
+            _ if self.import_format != ImportFormat::RemoteWrite => {
+                Err(BuildError::ImportFormatUnsupported)
+            }
+            Compatibility::Chronosphere
+                if self.auth.is_none() && self.request.headers.is_empty() =>
+            {
+                Err(BuildError::MissingAuthentication)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 #[derive(Clone, Eq, Hash, PartialEq)]
 struct PartitionKey {
     tenant_id: Option<String>,
 }
 
-async fn healthcheck(endpoint: Uri, client: HttpClient) -> crate::Result<()> {
-    let request = http::Request::get(endpoint)
+async fn healthcheck(endpoint: Uri, auth: Option<Auth>, client: HttpClient) -> crate::Result<()> {
+    let mut request = http::Request::get(endpoint)
         .body(hyper::Body::empty())
         .unwrap();
 
+    if let Some(auth) = &auth {
+        auth.authorize(&mut request).await?;
+    }
+
     let response = client.send(request).await?;
 
     match response.status() {
@@ -168,28 +348,130 @@ struct RemoteWriteService {
     buckets: Vec<f64>,
     quantiles: Vec<f64>,
     auth: Option<Auth>,
+    headers: IndexMap<String, String>,
+    tenant_header: String,
+    compatibility: Compatibility,
+    import_format: ImportFormat,
+    max_samples_per_request: Option<usize>,
 }
 
 impl RemoteWriteService {
-    fn encode_events(&self, metrics: Vec<Metric>) -> Bytes {
-        let mut time_series = collector::TimeSeries::new();
-        for metric in metrics {
-            time_series.encode_metric(
-                self.default_namespace.as_deref(),
-                &self.buckets,
-                &self.quantiles,
-                false,
-                &metric,
-            );
+    /// Encodes the metrics into the bodies of the requests they are sent in.
+    fn encode_events(&self, metrics: Vec<Metric>) -> Vec<Vec<u8>> {
+        let now = chrono::Utc::now();
+        let metrics = metrics.into_iter().map(|metric| {
+            if self.compatibility.requires_timestamps() && metric.data.timestamp.is_none() {
+                metric.with_timestamp(Some(now))
+            } else {
+                metric
+            }
+        });
+
+        match self.import_format {
+            ImportFormat::RemoteWrite => {
+                let mut time_series = collector::TimeSeries::new();
+                for metric in metrics {
+                    time_series.encode_metric(
+                        self.default_namespace.as_deref(),
+                        &self.buckets,
+                        &self.quantiles,
+                        false,
+                        &metric,
+                    );
+                }
+                let request = time_series.finish();
+
+                split_request(request, self.max_samples_per_request)
+                    .into_iter()
+                    .map(|request| {
+                        let mut out = BytesMut::with_capacity(request.encoded_len());
+                        request.encode(&mut out).expect("Out of memory");
+                        snap_block(out.freeze())
+                    })
+                    .collect()
+            }
+            ImportFormat::Prometheus => {
+                let mut collector = collector::StringCollector::new();
+                for metric in metrics {
+                    collector.encode_metric(
+                        self.default_namespace.as_deref(),
+                        &self.buckets,
+                        &self.quantiles,
+                        false,
+                        &metric,
+                    );
+                }
+                vec![collector.finish().into_bytes()]
+            }
         }
-        let request = time_series.finish();
+    }
 
-        let mut out = BytesMut::with_capacity(request.encoded_len());
-        request.encode(&mut out).expect("Out of memory");
-        out.freeze()
+    fn build_request(&self, body: Vec<u8>, tenant_id: Option<&str>) -> http::Request<Vec<u8>> {
+        let mut builder = http::Request::post(self.endpoint.clone());
+        builder = match self.import_format {
+            ImportFormat::RemoteWrite => builder
+                .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+                .header("Content-Encoding", "snappy")
+                .header("Content-Type", "application/x-protobuf"),
+            ImportFormat::Prometheus => builder.header("Content-Type", "text/plain"),
+        };
+        if let Some(tenant_id) = tenant_id {
+            builder = builder.header(self.tenant_header.as_str(), tenant_id);
+        }
+        for (name, value) in &self.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+
+        builder.body(body).unwrap()
     }
 }
 
+/// Splits a request into requests of at most `max_samples` samples, the
+/// samples of a time series being split across them if needed. The metadata is
+/// sent with the first one.
+fn split_request(
+    request: proto::WriteRequest,
+    max_samples: Option<usize>,
+) -> Vec<proto::WriteRequest> {
+    let max_samples = match max_samples {
+        Some(max_samples) => max_samples,
+        None => return vec![request],
+    };
+
+    let mut requests = vec![proto::WriteRequest {
+        timeseries: vec![],
+        metadata: request.metadata,
+    }];
+    let mut samples = 0;
+
+    for series in request.timeseries {
+        let mut rest = &series.samples[..];
+        while !rest.is_empty() {
+            if samples == max_samples {
+                requests.push(proto::WriteRequest {
+                    timeseries: vec![],
+                    metadata: vec![],
+                });
+                samples = 0;
+            }
+
+            let (head, tail) = rest.split_at(rest.len().min(max_samples - samples));
+            requests
+                .last_mut()
+                .expect("at least one request")
+                .timeseries
+                .push(proto::TimeSeries {
+                    labels: series.labels.clone(),
+                    samples: head.to_vec(),
+                });
+            samples += head.len();
+            rest = tail;
+        }
+    }
+
+    requests
+}
+
 impl tower::Service<PartitionInnerBuffer<Vec<Metric>, PartitionKey>> for RemoteWriteService {
     type Response = http::Response<Bytes>;
     type Error = crate::Error;
@@ -201,28 +483,32 @@ impl tower::Service<PartitionInnerBuffer<Vec<Metric>, PartitionKey>> for RemoteW
 
     fn call(&mut self, buffer: PartitionInnerBuffer<Vec<Metric>, PartitionKey>) -> Self::Future {
         let (events, key) = buffer.into_parts();
-        let body = self.encode_events(events);
-        let body = snap_block(body);
-
-        let mut builder = http::Request::post(self.endpoint.clone())
-            .header("X-Prometheus-Remote-Write-Version", "0.1.0")
-            .header("Content-Encoding", "snappy")
-            .header("Content-Type", "application/x-protobuf");
-        if let Some(tenant_id) = key.tenant_id {
-            builder = builder.header("X-Scope-OrgID", tenant_id);
-        }
-
-        let mut request = builder.body(body.into()).unwrap();
-        if let Some(auth) = &self.auth {
-            auth.apply(&mut request);
-        }
+        let requests = self
+            .encode_events(events)
+            .into_iter()
+            .map(|body| self.build_request(body, key.tenant_id.as_deref()))
+            .collect::<Vec<_>>();
         let client = self.client.clone();
+        let auth = self.auth.clone();
 
         Box::pin(async move {
-            let response = client.send(request).await?;
-            let (parts, body) = response.into_parts();
-            let body = hyper::body::to_bytes(body).await?;
-            Ok(hyper::Response::from_parts(parts, body))
+            // The requests of a batch are sent in order, stopping at the
+            // first one not accepted, whose response is then retried upon.
+            let mut last = None;
+            for mut request in requests {
+                if let Some(auth) = &auth {
+                    auth.authorize(&mut request).await?;
+                }
+                let response = client.send(request.map(hyper::Body::from)).await?;
+                let (parts, body) = response.into_parts();
+                let body = hyper::body::to_bytes(body).await?;
+                let response = hyper::Response::from_parts(parts, body);
+                if !response.status().is_success() {
+                    return Ok(response);
+                }
+                last = Some(response);
+            }
+            Ok(last.expect("at least one request"))
         })
     }
 }
@@ -239,7 +525,6 @@ mod tests {
     use crate::{
         config::SinkContext,
         event::{MetricKind, MetricValue},
-        prometheus::proto,
         sinks::util::test::build_test_server,
         test_util,
     };
@@ -367,10 +652,149 @@ mod tests {
         check_output(2, "counter-1", 26.0);
     }
 
-    async fn send_request(
-        config: &str,
-        events: Vec<Event>,
-    ) -> Vec<(HeaderMap, proto::WriteRequest)> {
+    #[tokio::test]
+    async fn sends_compatibility_headers() {
+        let outputs = send_request(
+            r#"
+            compatibility = "chronosphere"
+            tenant_id = "tenant"
+            tenant_header = "M3-Tenant"
+            request.headers.API-Token = "token"
+            "#,
+            vec![create_event("gauge-2".into(), 32.0)],
+        )
+        .await;
+
+        assert_eq!(outputs.len(), 1);
+        let (headers, _) = &outputs[0];
+        assert_eq!(headers["api-token"], "token");
+        assert_eq!(headers["m3-tenant"], "tenant");
+        assert!(!headers.contains_key("x-scope-orgid"));
+    }
+
+    #[tokio::test]
+    async fn adds_missing_timestamps() {
+        let event = Metric::new(
+            "gauge-4",
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 1.0 },
+        );
+        let outputs = send_request(r#"compatibility = "m3""#, vec![event.into()]).await;
+
+        assert_eq!(outputs.len(), 1);
+        let (_, req) = &outputs[0];
+        assert!(req.timeseries[0].samples[0].timestamp > 0);
+    }
+
+    #[tokio::test]
+    async fn splits_requests_by_samples() {
+        let outputs = send_request(
+            r#"max_samples_per_request = 2"#,
+            vec![
+                create_event("gauge-1".into(), 1.0),
+                create_event("gauge-2".into(), 2.0),
+                create_event("gauge-3".into(), 3.0),
+            ],
+        )
+        .await;
+
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0].1.timeseries.len(), 2);
+        assert_eq!(outputs[0].1.metadata.len(), 3);
+        assert_eq!(outputs[1].1.timeseries.len(), 1);
+        assert_eq!(outputs[1].1.metadata.len(), 0);
+    }
+
+    #[test]
+    fn splits_time_series() {
+        let sample = |timestamp| proto::Sample {
+            value: 1.0,
+            timestamp,
+        };
+        let request = proto::WriteRequest {
+            timeseries: vec![proto::TimeSeries {
+                labels: labels!("__name__" => "gauge"),
+                samples: vec![sample(1), sample(2), sample(3)],
+            }],
+            metadata: vec![],
+        };
+
+        let requests = split_request(request, Some(2));
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[0].timeseries[0].samples,
+            vec![sample(1), sample(2)]
+        );
+        assert_eq!(requests[1].timeseries[0].samples, vec![sample(3)]);
+        assert_eq!(
+            requests[1].timeseries[0].labels,
+            labels!("__name__" => "gauge")
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_victoriametrics_text_format() {
+        let outputs = send(
+            r#"
+            compatibility = "victoriametrics"
+            import_format = "prometheus"
+            "#,
+            vec![create_event("gauge-2".into(), 32.0)],
+        )
+        .await;
+
+        assert_eq!(outputs.len(), 1);
+        let (headers, body) = &outputs[0];
+        assert_eq!(headers["content-type"], "text/plain");
+        assert!(!headers.contains_key("content-encoding"));
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains(r#"gauge-2{production="true",region="us-west-1"} 32"#));
+    }
+
+    #[test]
+    fn validates_compatibility() {
+        let validate = |config: &str| {
+            let config = format!("endpoint = \"http://localhost/write\"\n{}", config);
+            toml::from_str::<RemoteWriteConfig>(&config)
+                .unwrap()
+                .validate()
+        };
+
+        assert!(validate(r#"compatibility = "chronosphere""#).is_err());
+        assert!(validate(
+            r#"
+            compatibility = "chronosphere"
+            request.headers.API-Token = "token"
+            "#
+        )
+        .is_ok());
+        assert!(validate(
+            r#"
+            compatibility = "victoriametrics"
+            tenant_id = "tenant"
+            "#
+        )
+        .is_err());
+        assert!(validate(r#"import_format = "prometheus""#).is_err());
+        assert!(validate(
+            r#"
+            compatibility = "victoriametrics"
+            import_format = "prometheus"
+            max_samples_per_request = 100
+            "#
+        )
+        .is_err());
+        assert!(validate(r#"max_samples_per_request = 0"#).is_err());
+        assert!(validate(
+            r#"
+            tenant_id = "tenant"
+            request.headers.X-Scope-OrgID = "other"
+            "#
+        )
+        .is_err());
+    }
+
+    async fn send(config: &str, events: Vec<Event>) -> Vec<(HeaderMap, Bytes)> {
         let addr = test_util::next_addr();
         let (rx, trigger, server) = build_test_server(addr);
         tokio::spawn(server);
@@ -387,26 +811,39 @@ mod tests {
         rx.map(|(parts, body)| {
             assert_eq!(parts.method, "POST");
             assert_eq!(parts.uri.path(), "/write");
-            let headers = parts.headers;
-            assert_eq!(headers["x-prometheus-remote-write-version"], "0.1.0");
-            assert_eq!(headers["content-encoding"], "snappy");
-            assert_eq!(headers["content-type"], "application/x-protobuf");
 
             if config.auth.is_some() {
-                assert!(headers.contains_key("authorization"));
+                assert!(parts.headers.contains_key("authorization"));
             }
 
-            let decoded = snap::raw::Decoder::new()
-                .decompress_vec(&body)
-                .expect("Invalid snappy compressed data");
-            let request =
-                proto::WriteRequest::decode(Bytes::from(decoded)).expect("Invalid protobuf");
-            (headers, request)
+            (parts.headers, body)
         })
         .collect::<Vec<_>>()
         .await
     }
 
+    async fn send_request(
+        config: &str,
+        events: Vec<Event>,
+    ) -> Vec<(HeaderMap, proto::WriteRequest)> {
+        send(config, events)
+            .await
+            .into_iter()
+            .map(|(headers, body)| {
+                assert_eq!(headers["x-prometheus-remote-write-version"], "0.1.0");
+                assert_eq!(headers["content-encoding"], "snappy");
+                assert_eq!(headers["content-type"], "application/x-protobuf");
+
+                let decoded = snap::raw::Decoder::new()
+                    .decompress_vec(&body)
+                    .expect("Invalid snappy compressed data");
+                let request =
+                    proto::WriteRequest::decode(Bytes::from(decoded)).expect("Invalid protobuf");
+                (headers, request)
+            })
+            .collect()
+    }
+
     pub(super) fn create_event(name: String, value: f64) -> Event {
         Metric::new(name, MetricKind::Absolute, MetricValue::Gauge { value })
             .with_tags(Some(
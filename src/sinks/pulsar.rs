@@ -380,6 +380,7 @@ mod tests {
                 only_fields: None,
                 except_fields: Some(vec!["key".into()]),
                 timestamp_format: None,
+                remap: None,
             },
             &None,
         )
@@ -145,7 +145,8 @@ impl SematextMetricsService {
             config,
             inner: http_service,
         };
-        let mut normalizer = MetricNormalizer::<SematextMetricNormalize>::default();
+        let mut normalizer = MetricNormalizer::<SematextMetricNormalize>::default()
+            .with_expiration(cx.expire_metrics());
 
         let sink = request
             .batch_sink(
@@ -155,7 +156,10 @@ impl SematextMetricsService {
                 batch.timeout,
                 cx.acker(),
             )
-            .with_flat_map(move |event: Event| stream::iter(normalizer.apply(event).map(Ok)))
+            .with_flat_map(move |event: Event| {
+                normalizer.expire();
+                stream::iter(normalizer.apply(event).map(Ok))
+            })
             .sink_map_err(|error| error!(message = "Fatal sematext metrics sink error.", %error));
 
         Ok(VectorSink::Sink(Box::new(sink)))
@@ -1,18 +1,22 @@
 use super::semaphore::ShrinkableSemaphore;
-use super::{instant_now, AdaptiveConcurrencySettings};
+use super::{instant_now, registry, AdaptiveConcurrencySettings};
 #[cfg(test)]
 use crate::test_util::stats::{TimeHistogram, TimeWeightedSum};
 use crate::{
     emit,
     http::HttpError,
     internal_events::{
-        AdaptiveConcurrencyAveragedRtt, AdaptiveConcurrencyInFlight, AdaptiveConcurrencyLimit,
-        AdaptiveConcurrencyObservedRtt,
+        AdaptiveConcurrencyAveragedRtt, AdaptiveConcurrencyDecision, AdaptiveConcurrencyInFlight,
+        AdaptiveConcurrencyLimit, AdaptiveConcurrencyObservedRtt,
     },
     sinks::util::retries::{RetryAction, RetryLogic},
+    trace,
 };
 use std::future::Future;
-use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex, MutexGuard,
+};
 use std::time::{Duration, Instant};
 use tokio::sync::OwnedSemaphorePermit;
 use tower::timeout::error::Elapsed;
@@ -21,11 +25,12 @@ use tower::timeout::error::Elapsed;
 /// semaphore size and other associated data.
 #[derive(Clone, Debug)]
 pub(super) struct Controller<L> {
-    semaphore: Arc<ShrinkableSemaphore>,
+    pub(super) semaphore: Arc<ShrinkableSemaphore>,
     concurrency: Option<usize>,
     settings: AdaptiveConcurrencySettings,
     logic: L,
     pub(super) inner: Arc<Mutex<Inner>>,
+    registered: Arc<AtomicBool>,
     #[cfg(test)]
     pub(super) stats: Arc<Mutex<ControllerStatistics>>,
 }
@@ -33,7 +38,9 @@ pub(super) struct Controller<L> {
 #[derive(Debug)]
 pub(super) struct Inner {
     pub(super) current_limit: usize,
-    in_flight: usize,
+    pub(super) in_flight: usize,
+    /// Whether the limit was pinned through the API, suspending its adjustments.
+    pub(super) pinned: bool,
     past_rtt: EWMA,
     next_update: Instant,
     current_rtt: Mean,
@@ -74,7 +81,9 @@ impl<L> Controller<L> {
                 current_rtt: Default::default(),
                 had_back_pressure: false,
                 reached_limit: false,
+                pinned: false,
             })),
+            registered: Arc::new(AtomicBool::new(false)),
             #[cfg(test)]
             stats: Arc::new(Mutex::new(ControllerStatistics::default())),
         }
@@ -89,6 +98,8 @@ impl<L> Controller<L> {
     }
 
     pub(super) fn start_request(&self) {
+        self.register();
+
         let mut inner = self.inner.lock().expect("Controller mutex is poisoned");
 
         #[cfg(test)]
@@ -103,6 +114,20 @@ impl<L> Controller<L> {
         });
     }
 
+    /// Records the controller under the name of its sink, for its limit to be
+    /// pinned through the API. Requests are started in the span of the sink,
+    /// unlike the controller which is created when the sink is built.
+    fn register(&self) {
+        if self.concurrency.is_some() || self.registered.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(sink_name) = trace::current_component() {
+            if !self.registered.swap(true, Ordering::Relaxed) {
+                registry::register(sink_name, &self.semaphore, &self.inner);
+            }
+        }
+    }
+
     /// Adjust the controller to a response, based on type of response
     /// given (backpressure or not) and if it should be used as a valid
     /// RTT measurement.
@@ -166,8 +191,9 @@ impl<L> Controller<L> {
                         });
                     }
 
-                    // Only manage the concurrency if `concurrency` was set to "adaptive"
-                    if self.concurrency.is_none() {
+                    // Only manage the concurrency if `concurrency` was set to "adaptive",
+                    // and its limit isn't pinned.
+                    if self.concurrency.is_none() && !inner.pinned {
                         self.manage_limit(&mut inner, past_rtt, current_rtt);
                     }
 
@@ -200,6 +226,9 @@ impl<L> Controller<L> {
             // Increase (additive) the current concurrency limit
             self.semaphore.add_permits(1);
             inner.current_limit += 1;
+            emit!(AdaptiveConcurrencyDecision {
+                decision: "increase"
+            });
         }
         // Back pressure responses, either explicit or implicit due
         // to increasing response times, trigger a decrease in the
@@ -212,6 +241,9 @@ impl<L> Controller<L> {
                 - (inner.current_limit as f64 * self.settings.decrease_ratio) as usize;
             self.semaphore.forget_permits(to_forget);
             inner.current_limit -= to_forget;
+            emit!(AdaptiveConcurrencyDecision {
+                decision: "decrease"
+            });
         }
         emit!(AdaptiveConcurrencyLimit {
             concurrency: inner.current_limit as u64,
@@ -0,0 +1,172 @@
+use super::{controller::Inner, semaphore::ShrinkableSemaphore, MAX_CONCURRENCY};
+use crate::{emit, internal_events::AdaptiveConcurrencyPinned};
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, Weak},
+};
+
+/// SINKS holds the adaptive concurrency controllers of the running sinks, and
+/// the limits pinned through the API, by sink name. Pins outlive the
+/// controllers so they still apply after a reload.
+static SINKS: Lazy<Mutex<HashMap<String, Sink>>> = Lazy::new(Default::default);
+
+#[derive(Default)]
+struct Sink {
+    pinned: Option<usize>,
+    controllers: Vec<Registered>,
+}
+
+struct Registered {
+    semaphore: Weak<ShrinkableSemaphore>,
+    inner: Weak<Mutex<Inner>>,
+}
+
+/// The adaptive concurrency of a sink, over all of its controllers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConcurrencyStatus {
+    /// The highest limit of the controllers.
+    pub current_limit: usize,
+    pub in_flight: usize,
+    pub pinned: bool,
+}
+
+/// Records a controller of a sink, applying the limit pinned for it, if any.
+pub(super) fn register(
+    sink_name: String,
+    semaphore: &Arc<ShrinkableSemaphore>,
+    inner: &Arc<Mutex<Inner>>,
+) {
+    let mut sinks = SINKS
+        .lock()
+        .expect("Adaptive concurrency registry is poisoned");
+    let sink = sinks.entry(sink_name).or_default();
+    sink.controllers
+        .retain(|registered| registered.inner.strong_count() > 0);
+
+    if let Some(limit) = sink.pinned {
+        pin_controller(semaphore, inner, Some(limit));
+    }
+    sink.controllers.push(Registered {
+        semaphore: Arc::downgrade(semaphore),
+        inner: Arc::downgrade(inner),
+    });
+}
+
+/// Pins the concurrency limit of a sink, disabling its adjustments, or unpins
+/// it when `limit` is `None`. Returns `None` if the sink has no running
+/// adaptive concurrency controller.
+pub fn pin(sink_name: &str, limit: Option<usize>) -> Option<ConcurrencyStatus> {
+    let limit = limit.map(|limit| limit.max(1).min(MAX_CONCURRENCY));
+
+    let mut sinks = SINKS
+        .lock()
+        .expect("Adaptive concurrency registry is poisoned");
+    let sink = sinks.get_mut(sink_name)?;
+    if sink
+        .controllers
+        .iter()
+        .all(|registered| registered.inner.strong_count() == 0)
+    {
+        return None;
+    }
+
+    sink.pinned = limit;
+    for registered in &sink.controllers {
+        if let (Some(semaphore), Some(inner)) =
+            (registered.semaphore.upgrade(), registered.inner.upgrade())
+        {
+            pin_controller(&semaphore, &inner, limit);
+        }
+    }
+
+    emit!(AdaptiveConcurrencyPinned {
+        sink_name,
+        limit: limit.map(|limit| limit as u64),
+    });
+    status_of(sink)
+}
+
+/// The adaptive concurrency of a sink, if it has a running controller.
+pub fn status(sink_name: &str) -> Option<ConcurrencyStatus> {
+    let sinks = SINKS
+        .lock()
+        .expect("Adaptive concurrency registry is poisoned");
+    sinks.get(sink_name).and_then(status_of)
+}
+
+fn status_of(sink: &Sink) -> Option<ConcurrencyStatus> {
+    let controllers = sink
+        .controllers
+        .iter()
+        .filter_map(|registered| registered.inner.upgrade())
+        .collect::<Vec<_>>();
+    if controllers.is_empty() {
+        return None;
+    }
+
+    let mut status = ConcurrencyStatus {
+        current_limit: 0,
+        in_flight: 0,
+        pinned: sink.pinned.is_some(),
+    };
+    for inner in controllers {
+        let inner = inner.lock().expect("Controller mutex is poisoned");
+        status.current_limit = status.current_limit.max(inner.current_limit);
+        status.in_flight += inner.in_flight;
+    }
+    Some(status)
+}
+
+fn pin_controller(semaphore: &ShrinkableSemaphore, inner: &Mutex<Inner>, limit: Option<usize>) {
+    let mut inner = inner.lock().expect("Controller mutex is poisoned");
+    inner.pinned = limit.is_some();
+    if let Some(limit) = limit {
+        if limit > inner.current_limit {
+            semaphore.add_permits(limit - inner.current_limit);
+        } else {
+            semaphore.forget_permits(inner.current_limit - limit);
+        }
+        inner.current_limit = limit;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{controller::Controller, AdaptiveConcurrencySettings};
+    use super::*;
+
+    #[test]
+    fn pins_limit() {
+        let controller = Controller::new(None, AdaptiveConcurrencySettings::default(), ());
+        register(
+            "pins_limit".into(),
+            &controller.semaphore,
+            &controller.inner,
+        );
+
+        assert_eq!(
+            pin("pins_limit", Some(5)),
+            Some(ConcurrencyStatus {
+                current_limit: 5,
+                in_flight: 0,
+                pinned: true,
+            })
+        );
+        assert!(controller.inner.lock().unwrap().pinned);
+
+        assert_eq!(
+            pin("pins_limit", None),
+            Some(ConcurrencyStatus {
+                current_limit: 5,
+                in_flight: 0,
+                pinned: false,
+            })
+        );
+        assert!(!controller.inner.lock().unwrap().pinned);
+
+        drop(controller);
+        assert_eq!(pin("pins_limit", Some(5)), None);
+        assert_eq!(status("pins_limit"), None);
+    }
+}
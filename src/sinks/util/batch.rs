@@ -16,11 +16,13 @@ pub enum BatchError {
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
 pub struct BatchConfig {
+    #[serde(default, deserialize_with = "crate::serde::optional_bytes")]
     pub max_bytes: Option<usize>,
     pub max_events: Option<usize>,
     /// Deprecated. Left in for backwards compatibility, use `max_bytes`
     /// or `max_events` instead.
     pub max_size: Option<usize>,
+    #[serde(default, deserialize_with = "crate::serde::optional_duration_secs")]
     pub timeout_secs: Option<u64>,
 }
 
@@ -11,7 +11,7 @@ use super::{
 use crate::sinks::loki::OutOfOrderAction;
 use dashmap::DashMap;
 use serde_json::{json, value::to_raw_value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 const WRAPPER_OVERHEAD: usize = r#"{"streams":[]}"#.len();
@@ -24,6 +24,8 @@ pub type Labels = Vec<(String, String)>;
 pub struct LokiEvent {
     pub timestamp: i64,
     pub event: String,
+    /// The structured metadata of the entry, kept outside of the stream labels.
+    pub structured_metadata: Labels,
 }
 
 #[derive(Clone, Debug)]
@@ -41,12 +43,24 @@ struct LokiEncodedEvent {
 
 impl From<&LokiEvent> for LokiEncodedEvent {
     // Pre-encode the record to JSON, but keep the timestamp for sorting at the end.
-    // The final output should be: `[ts, line]'
+    // The final output should be: `[ts, line]', or `[ts, line, metadata]' when
+    // the entry has structured metadata.
     fn from(event: &LokiEvent) -> Self {
+        let timestamp = format!("{}", event.timestamp);
+        let encoded = if event.structured_metadata.is_empty() {
+            to_raw_value(&json!([timestamp, event.event]))
+        } else {
+            let metadata = event
+                .structured_metadata
+                .iter()
+                .cloned()
+                .collect::<BTreeMap<_, _>>();
+            to_raw_value(&json!([timestamp, event.event, metadata]))
+        };
+
         Self {
             timestamp: event.timestamp,
-            encoded: to_raw_value(&json!([format!("{}", event.timestamp), event.event]))
-                .expect("JSON encoding should never fail"),
+            encoded: encoded.expect("JSON encoding should never fail"),
         }
     }
 }
@@ -297,6 +311,7 @@ mod tests {
                 event: LokiEvent {
                     timestamp: 123456789,
                     event: "this is an event".into(),
+                    structured_metadata: Vec::new(),
                 },
             }),
             PushResult::Ok(false)
@@ -325,6 +340,7 @@ mod tests {
                     event: LokiEvent {
                         timestamp: 123456780 + n,
                         event: format!("event #{}", n),
+                        structured_metadata: Vec::new(),
                     },
                 }),
                 PushResult::Ok(false)
@@ -354,6 +370,7 @@ mod tests {
                     event: LokiEvent {
                         timestamp: 123456780 + n,
                         event: format!("event #{}", n),
+                        structured_metadata: Vec::new(),
                     },
                 }),
                 PushResult::Ok(false)
@@ -367,4 +384,33 @@ mod tests {
             r#"{"streams":[{"stream":{"asdf":"value1"},"values":[["123456781","event #1"],["123456782","event #2"],["123456783","event #3"]]}]}"#,
         );
     }
+
+    #[test]
+    fn insert_structured_metadata() {
+        let mut buffer = LokiBuffer::new(
+            BatchSettings::default().size,
+            Default::default(),
+            Default::default(),
+        );
+        assert!(matches!(
+            buffer.push(LokiRecord {
+                partition: PartitionKey { tenant_id: None },
+                labels: vec![("label1".into(), "value1".into())],
+                event: LokiEvent {
+                    timestamp: 123456789,
+                    event: "this is an event".into(),
+                    structured_metadata: vec![
+                        ("trace_id".into(), "abc".into()),
+                        ("pod".into(), "web-1".into()),
+                    ],
+                },
+            }),
+            PushResult::Ok(false)
+        ));
+
+        test_finish(
+            buffer,
+            r#"{"streams":[{"stream":{"label1":"value1"},"values":[["123456789","this is an event",{"pod":"web-1","trace_id":"abc"}]]}]}"#,
+        );
+    }
 }
@@ -1,17 +1,23 @@
 use crate::{
-    event::metric::{Metric, MetricKind, MetricValue, Sample},
+    event::metric::{Metric, MetricData, MetricKind, MetricSeries, MetricValue, Sample},
+    internal_events::MetricSeriesExpired,
     sinks::util::batch::{Batch, BatchConfig, BatchError, BatchSettings, BatchSize, PushResult},
     Event,
 };
+use chrono::Utc;
 use std::{
     cmp::Ordering,
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     hash::{Hash, Hasher},
     marker::PhantomData,
     mem::discriminant,
     ops::{Deref, DerefMut},
+    time::{Duration, Instant},
 };
 
+/// The series are checked for expiration at most this often.
+const EXPIRATION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Clone, Debug)]
 pub struct MetricEntry(pub Metric);
 
@@ -197,6 +203,7 @@ impl Batch for MetricsBuffer {
 /// before sending the events to the `MetricsBuffer`
 pub struct MetricNormalizer<N> {
     state: MetricSet,
+    expiration: Option<Expiration>,
     _norm: PhantomData<N>,
 }
 
@@ -204,14 +211,114 @@ impl<N: MetricNormalize> MetricNormalizer<N> {
     pub fn default() -> Self {
         Self {
             state: MetricSet::default(),
+            expiration: None,
             _norm: PhantomData::default(),
         }
     }
 
+    /// Expires the series not updated for `ttl`, see `expire`.
+    pub fn with_expiration(mut self, ttl: Option<Duration>) -> Self {
+        self.expiration = ttl.map(Expiration::new);
+        self
+    }
+
     /// This wraps `MetricNormalize::apply_state`, converting to/from
     /// the `Event` type wrapper. See that function for return values.
     pub fn apply(&mut self, event: Event) -> Option<Event> {
-        N::apply_state(&mut self.state, event.into_metric()).map(Into::into)
+        let metric = event.into_metric();
+        if let Some(expiration) = &mut self.expiration {
+            expiration.update(&metric);
+        }
+        N::apply_state(&mut self.state, metric).map(Into::into)
+    }
+
+    /// Forgets the state of the series not updated for the expiration
+    /// period, returning the markers ending the series that have one, for
+    /// the sinks whose protocol supports them. The series are only checked
+    /// once a second, so this is cheap enough to be called for each event.
+    pub fn expire(&mut self) -> Vec<Metric> {
+        let expired = match &mut self.expiration {
+            Some(expiration) => expiration.expire(Instant::now()),
+            None => return Vec::new(),
+        };
+        if expired.is_empty() {
+            return Vec::new();
+        }
+
+        emit!(MetricSeriesExpired {
+            count: expired.len()
+        });
+        let series = expired
+            .iter()
+            .map(|(series, _)| series)
+            .collect::<HashSet<_>>();
+        self.state.retain(|entry| !series.contains(&entry.series));
+
+        let timestamp = Some(Utc::now());
+        expired
+            .into_iter()
+            .filter_map(|(series, stale)| {
+                Some(Metric {
+                    series,
+                    data: MetricData {
+                        timestamp,
+                        kind: MetricKind::Absolute,
+                        value: stale?,
+                    },
+                    metadata: Default::default(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Tracks when the series were last updated, to expire them.
+struct Expiration {
+    ttl: Duration,
+    last_check: Instant,
+    /// When each series was last updated, and the marker ending it, of the
+    /// type of its last value.
+    series: HashMap<MetricSeries, (Instant, Option<MetricValue>)>,
+}
+
+impl Expiration {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            last_check: Instant::now(),
+            series: HashMap::new(),
+        }
+    }
+
+    fn update(&mut self, metric: &Metric) {
+        let seen = (Instant::now(), metric.data.value.stale());
+        match self.series.get_mut(&metric.series) {
+            Some(entry) => *entry = seen,
+            None => {
+                self.series.insert(metric.series.clone(), seen);
+            }
+        }
+    }
+
+    /// Removes the series not updated for `ttl`, returning them along with
+    /// their markers.
+    fn expire(&mut self, now: Instant) -> Vec<(MetricSeries, Option<MetricValue>)> {
+        if now.duration_since(self.last_check) < EXPIRATION_CHECK_INTERVAL.min(self.ttl) {
+            return Vec::new();
+        }
+        self.last_check = now;
+
+        let ttl = self.ttl;
+        let mut expired = Vec::new();
+        self.series.retain(|series, (updated, stale)| {
+            if now.duration_since(*updated) < ttl {
+                true
+            } else {
+                expired.push((series.clone(), stale.take()));
+                false
+            }
+        });
+        expired
     }
 }
 
@@ -877,6 +984,31 @@ mod test {
         assert_eq!(buffer.len(), 1);
     }
 
+    #[test]
+    fn expires_stale_series() {
+        let mut normalizer = MetricNormalizer::<AbsoluteMetricNormalize>::default()
+            .with_expiration(Some(Duration::from_millis(50)));
+        normalizer.apply(sample_counter(0, "production", Incremental, 1.0).into());
+        normalizer.apply(sample_set(0, Incremental, &[1]).into());
+        assert!(normalizer.expire().is_empty());
+
+        std::thread::sleep(Duration::from_millis(100));
+        normalizer.apply(sample_gauge(0, Absolute, 1.0).into());
+
+        // Sets have no marker, so only the counter's is returned.
+        let markers = normalizer.expire();
+        assert_eq!(markers.len(), 1);
+        assert_eq!(markers[0].name(), "counter-0");
+        assert!(markers[0].is_stale());
+
+        // The state of the counter is forgotten, so it starts over.
+        let counter = normalizer
+            .apply(sample_counter(0, "production", Incremental, 2.0).into())
+            .unwrap()
+            .into_metric();
+        assert_eq!(counter.data.value, MetricValue::Counter { value: 2.0 });
+    }
+
     fn sample_counter(num: usize, tagstr: &str, kind: MetricKind, value: f64) -> Metric {
         Metric::new(
             format!("counter-{}", num),
@@ -2,7 +2,8 @@ use crate::{
     event::{PathComponent, PathIter},
     serde::skip_serializing_if_default,
     sinks::util::encoding::{
-        with_default::EncodingConfigWithDefault, EncodingConfiguration, TimestampFormat,
+        with_default::EncodingConfigWithDefault, EncodingConfiguration, EncodingRemap,
+        TimestampFormat,
     },
 };
 use serde::{
@@ -31,6 +32,8 @@ pub struct EncodingConfig<E> {
     pub(crate) except_fields: Option<Vec<String>>,
     #[serde(default, skip_serializing_if = "skip_serializing_if_default")]
     pub(crate) timestamp_format: Option<TimestampFormat>,
+    #[serde(default, skip_serializing_if = "skip_serializing_if_default")]
+    pub(crate) remap: Option<EncodingRemap>,
 }
 
 impl<E> EncodingConfiguration<E> for EncodingConfig<E> {
@@ -50,6 +53,9 @@ impl<E> EncodingConfiguration<E> for EncodingConfig<E> {
     fn timestamp_format(&self) -> &Option<TimestampFormat> {
         &self.timestamp_format
     }
+    fn remap(&self) -> &Option<EncodingRemap> {
+        &self.remap
+    }
 }
 
 impl<E> From<EncodingConfigWithDefault<E>> for EncodingConfig<E>
@@ -63,6 +69,7 @@ where
             only_fields: encoding.only_fields,
             except_fields: encoding.except_fields,
             timestamp_format: encoding.timestamp_format,
+            remap: encoding.remap,
         }
     }
 }
@@ -79,6 +86,7 @@ impl<E> EncodingConfig<E> {
             only_fields: self.only_fields,
             except_fields: self.except_fields,
             timestamp_format: self.timestamp_format,
+            remap: self.remap,
         }
     }
 }
@@ -91,6 +99,7 @@ impl<E> From<E> for EncodingConfig<E> {
             only_fields: Default::default(),
             except_fields: Default::default(),
             timestamp_format: Default::default(),
+            remap: Default::default(),
         }
     }
 }
@@ -132,6 +141,7 @@ where
                     only_fields: Default::default(),
                     except_fields: Default::default(),
                     timestamp_format: Default::default(),
+                    remap: Default::default(),
                 })
             }
 
@@ -161,6 +171,7 @@ where
             }),
             except_fields: inner.except_fields,
             timestamp_format: inner.timestamp_format,
+            remap: inner.remap,
         };
 
         concrete.validate().map_err(serde::de::Error::custom)?;
@@ -179,4 +190,6 @@ pub struct Inner<E> {
     except_fields: Option<Vec<String>>,
     #[serde(default)]
     timestamp_format: Option<TimestampFormat>,
+    #[serde(default)]
+    remap: Option<EncodingRemap>,
 }
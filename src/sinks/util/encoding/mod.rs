@@ -33,9 +33,11 @@ mod config;
 pub use config::EncodingConfig;
 mod with_default;
 pub use with_default::EncodingConfigWithDefault;
+mod remap;
+pub use self::remap::EncodingRemap;
 
 use crate::{
-    event::{PathComponent, PathIter, Value},
+    event::{PathComponent, PathIter, TraceEvent, Value},
     Event, Result,
 };
 use serde::{Deserialize, Serialize};
@@ -51,11 +53,12 @@ pub trait EncodingConfiguration<E> {
     fn only_fields(&self) -> &Option<Vec<Vec<PathComponent>>>;
     fn except_fields(&self) -> &Option<Vec<String>>;
     fn timestamp_format(&self) -> &Option<TimestampFormat>;
+    fn remap(&self) -> &Option<EncodingRemap>;
 
     fn apply_only_fields(&self, event: &mut Event) {
         if let Some(only_fields) = &self.only_fields() {
             match event {
-                Event::Log(log_event) => {
+                Event::Log(log_event) | Event::Trace(TraceEvent(log_event)) => {
                     let to_remove = log_event
                         .keys()
                         .filter(|field| {
@@ -79,7 +82,7 @@ pub trait EncodingConfiguration<E> {
     fn apply_except_fields(&self, event: &mut Event) {
         if let Some(except_fields) = &self.except_fields() {
             match event {
-                Event::Log(log_event) => {
+                Event::Log(log_event) | Event::Trace(TraceEvent(log_event)) => {
                     for field in except_fields {
                         log_event.remove(field);
                     }
@@ -91,7 +94,7 @@ pub trait EncodingConfiguration<E> {
     fn apply_timestamp_format(&self, event: &mut Event) {
         if let Some(timestamp_format) = &self.timestamp_format() {
             match event {
-                Event::Log(log_event) => {
+                Event::Log(log_event) | Event::Trace(TraceEvent(log_event)) => {
                     match timestamp_format {
                         TimestampFormat::Unix => {
                             let mut unix_timestamps = Vec::new();
@@ -139,6 +142,10 @@ pub trait EncodingConfiguration<E> {
     ///
     /// Currently, this is idempotent.
     fn apply_rules(&self, event: &mut Event) {
+        // The remap program sees the event as it arrived at the sink.
+        if let Some(remap) = self.remap() {
+            remap.apply(event);
+        }
         // Ordering in here should not matter.
         self.apply_except_fields(event);
         self.apply_only_fields(event);
@@ -312,4 +319,37 @@ mod tests {
             ),
         }
     }
+
+    const TOML_REMAP: &str = r#"
+        encoding.codec = "Snoot"
+        encoding.except_fields = ["internal"]
+        encoding.remap = """
+        .checksum = md5(.message)
+        .seen = exists(.internal)
+        """
+    "#;
+    #[test]
+    fn test_remap() {
+        let config: TestConfig = toml::from_str(TOML_REMAP).unwrap();
+        config.encoding.validate().unwrap();
+        let mut event = Event::from("Demo");
+        event.as_mut_log().insert("internal", "secret");
+
+        config.encoding.apply_rules(&mut event);
+
+        let log = event.as_log();
+        assert_eq!(
+            log["checksum"],
+            Value::from("f0258b6685684c113bad94d91b8fa02a")
+        );
+        assert_eq!(log["seen"], Value::Boolean(true));
+        assert!(!log.contains("internal"));
+    }
+
+    #[test]
+    fn remap_compile_error() {
+        let config: std::result::Result<TestConfig, _> =
+            toml::from_str(r#"encoding = { codec = "Snoot", remap = ".foo = " }"#);
+        assert!(config.is_err());
+    }
 }
@@ -0,0 +1,83 @@
+use crate::{event::Event, internal_events::RemapMappingError};
+use remap::{value, Program, Runtime, TypeConstraint, TypeDef};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// A VRL program run on each event right before a sink encodes it.
+#[derive(Clone)]
+pub struct EncodingRemap {
+    source: String,
+    program: Program,
+}
+
+impl EncodingRemap {
+    pub fn new(source: String) -> crate::Result<Self> {
+        let accepts = TypeConstraint {
+            allow_any: true,
+            type_def: TypeDef {
+                fallible: true,
+                kind: value::Kind::all(),
+                ..Default::default()
+            },
+        };
+
+        let (program, _) = Program::new(
+            source.clone(),
+            &remap_functions::all(),
+            Some(accepts),
+            false,
+        )
+        .map_err(|diagnostics| {
+            remap::Formatter::new(&source, diagnostics)
+                .colored()
+                .to_string()
+        })?;
+
+        Ok(Self { source, program })
+    }
+
+    /// Runs the program on `event`. A failing program leaves the event as it
+    /// was at the point of failure, it is still sent.
+    pub fn apply(&self, event: &mut Event) {
+        let mut runtime = Runtime::default();
+        let result = match event {
+            Event::Log(ref mut event) => runtime.run(event, &self.program),
+            Event::Metric(ref mut event) => runtime.run(event, &self.program),
+            Event::Trace(ref mut event) => runtime.run(event.as_mut_log(), &self.program),
+        };
+
+        if let Err(error) = result {
+            emit!(RemapMappingError {
+                error: error.to_string(),
+                event_dropped: false,
+            });
+        }
+    }
+}
+
+impl fmt::Debug for EncodingRemap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("EncodingRemap").field(&self.source).finish()
+    }
+}
+
+impl PartialEq for EncodingRemap {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for EncodingRemap {}
+
+impl Serialize for EncodingRemap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for EncodingRemap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Self::new(source).map_err(de::Error::custom)
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     event::{PathComponent, PathIter},
     serde::skip_serializing_if_default,
-    sinks::util::encoding::{EncodingConfiguration, TimestampFormat},
+    sinks::util::encoding::{EncodingConfiguration, EncodingRemap, TimestampFormat},
 };
 use serde::{
     de::{self, DeserializeOwned, Deserializer, IntoDeserializer, MapAccess, Visitor},
@@ -34,6 +34,9 @@ pub struct EncodingConfigWithDefault<E: Default + PartialEq> {
     /// Format for outgoing timestamps.
     #[serde(default, skip_serializing_if = "skip_serializing_if_default")]
     pub(crate) timestamp_format: Option<TimestampFormat>,
+    /// A VRL program run on each event before the other rules are applied.
+    #[serde(default, skip_serializing_if = "skip_serializing_if_default")]
+    pub(crate) remap: Option<EncodingRemap>,
 }
 
 impl<E: Default + PartialEq> EncodingConfiguration<E> for EncodingConfigWithDefault<E> {
@@ -53,6 +56,9 @@ impl<E: Default + PartialEq> EncodingConfiguration<E> for EncodingConfigWithDefa
     fn timestamp_format(&self) -> &Option<TimestampFormat> {
         &self.timestamp_format
     }
+    fn remap(&self) -> &Option<EncodingRemap> {
+        &self.remap
+    }
 }
 
 impl<E> From<E> for EncodingConfigWithDefault<E>
@@ -66,6 +72,7 @@ where
             only_fields: Default::default(),
             except_fields: Default::default(),
             timestamp_format: Default::default(),
+            remap: Default::default(),
         }
     }
 }
@@ -109,6 +116,7 @@ where
                     only_fields: Default::default(),
                     except_fields: Default::default(),
                     timestamp_format: Default::default(),
+                    remap: Default::default(),
                 })
             }
 
@@ -138,6 +146,7 @@ where
             }),
             except_fields: inner.except_fields,
             timestamp_format: inner.timestamp_format,
+            remap: inner.remap,
         };
 
         concrete.validate().map_err(de::Error::custom)?;
@@ -157,4 +166,6 @@ pub struct InnerWithDefault<E: Default> {
     except_fields: Option<Vec<String>>,
     #[serde(default)]
     timestamp_format: Option<TimestampFormat>,
+    #[serde(default)]
+    remap: Option<EncodingRemap>,
 }
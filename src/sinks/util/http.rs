@@ -1,9 +1,14 @@
 use super::{
+    debug_capture,
     retries::{RetryAction, RetryLogic},
     sink, Batch, Partition, TowerBatchedSink, TowerPartitionSink, TowerRequestConfig,
     TowerRequestSettings,
 };
-use crate::{buffers::Acker, http::HttpClient, Event};
+use crate::{
+    buffers::Acker,
+    http::{Auth, AuthError, HttpClient},
+    Event,
+};
 use bytes::{Buf, Bytes};
 use futures::{future::BoxFuture, ready, Sink};
 use http::StatusCode;
@@ -95,7 +100,7 @@ impl<T, B, L> BatchedHttpSink<T, B, L>
 where
     B: Batch,
     B::Output: Clone + Send + 'static,
-    L: RetryLogic<Response = http::Response<Bytes>, Error = hyper::Error> + Send + 'static,
+    L: RetryLogic<Response = http::Response<Bytes>> + Send + 'static,
     T: HttpSink<Input = B::Input, Output = B::Output>,
 {
     pub fn with_retry_logic(
@@ -232,7 +237,7 @@ where
     B::Output: Clone + Send + 'static,
     B::Input: Partition<K>,
     K: Hash + Eq + Clone + Send + 'static,
-    L: RetryLogic<Response = http::Response<Bytes>, Error = hyper::Error> + Send + 'static,
+    L: RetryLogic<Response = http::Response<Bytes>> + Send + 'static,
     T: HttpSink<Input = B::Input, Output = B::Output>,
 {
     pub fn with_retry_logic(
@@ -348,13 +353,31 @@ where
     fn call(&mut self, body: B) -> Self::Future {
         let request_builder = Arc::clone(&self.request_builder);
         let mut http_client = self.inner.clone();
+        let capture = debug_capture::sample();
 
         Box::pin(async move {
-            let request = request_builder(body).await?.map(Body::from);
-            let response = http_client.call(request).await?;
-            let (parts, body) = response.into_parts();
-            let mut body = body::aggregate(body).await?;
-            Ok(hyper::Response::from_parts(parts, body.to_bytes()))
+            let request = request_builder(body).await?;
+            let captured = capture
+                .as_ref()
+                .map(|(_, capture)| capture.capture_request(&request));
+
+            let response: crate::Result<_> = async {
+                let response = http_client.call(request.map(Body::from)).await?;
+                let (parts, body) = response.into_parts();
+                let mut body = body::aggregate(body).await?;
+                Ok(hyper::Response::from_parts(parts, body.to_bytes()))
+            }
+            .await;
+
+            if let (Some((component, capture)), Some(request)) = (capture, captured) {
+                let captured = match &response {
+                    Ok(response) => Ok(capture.capture_response(response)),
+                    Err(error) => Err(format!("{}", error)),
+                };
+                capture.emit(&component, request, captured);
+            }
+
+            response
         })
     }
 }
@@ -404,6 +427,39 @@ impl RetryLogic for HttpRetryLogic {
     }
 }
 
+/// Retries the requests whose OAuth2 token was rejected, after forgetting the
+/// token so that the next attempt fetches another one, and the requests which
+/// couldn't get a token. Other responses are handled as by `HttpRetryLogic`.
+#[derive(Debug, Clone)]
+pub struct AuthRetryLogic {
+    auth: Option<Auth>,
+}
+
+impl AuthRetryLogic {
+    pub fn new(auth: Option<Auth>) -> Self {
+        Self { auth }
+    }
+}
+
+impl RetryLogic for AuthRetryLogic {
+    type Error = AuthError;
+    type Response = hyper::Response<Bytes>;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        error.is_retriable()
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        if response.status() == StatusCode::UNAUTHORIZED
+            && self.auth.as_ref().map_or(false, Auth::reject_token)
+        {
+            return RetryAction::Retry("OAuth2 token rejected".into());
+        }
+
+        HttpRetryLogic.should_retry_response(response)
+    }
+}
+
 /// A helper config struct
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
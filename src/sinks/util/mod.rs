@@ -1,8 +1,10 @@
 pub mod adaptive_concurrency;
 pub mod batch;
 pub mod buffer;
+pub mod debug_capture;
 pub mod encoding;
 pub mod http;
+pub mod quota;
 pub mod retries;
 pub mod service;
 pub mod sink;
@@ -0,0 +1,373 @@
+use crate::{
+    buffers::SplitAcker,
+    config::{SinkConfig, SinkContext},
+    event::{Event, EventStatus, TraceEvent},
+    internal_events::{SinkQuotaBytesSent, SinkQuotaExceeded, SinkQuotaPeriodStarted},
+    sinks::{Healthcheck, VectorSink},
+};
+use chrono::{DateTime, Timelike, Utc};
+use futures::{channel::mpsc, future, FutureExt, SinkExt, Stream, StreamExt, TryFutureExt};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// The consumers of the events of a sink with a quota, sharing the acker of
+/// its buffer.
+const SINK: usize = 0;
+const DIVERT: usize = 1;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct QuotaConfig {
+    /// Bytes the sink may send per period. Without it, bytes are only accounted.
+    #[serde(default)]
+    pub max_bytes: Option<u64>,
+    #[serde(default)]
+    pub period: QuotaPeriod,
+    #[serde(default)]
+    pub when_exceeded: WhenExceeded,
+    /// Send one in this many events while sampling.
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate: u64,
+    /// The sink events are sent to instead while diverting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub divert: Option<Box<dyn SinkConfig>>,
+}
+
+fn default_sample_rate() -> u64 {
+    10
+}
+
+#[derive(Deserialize, Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaPeriod {
+    Hourly,
+    Daily,
+}
+
+impl Default for QuotaPeriod {
+    fn default() -> Self {
+        QuotaPeriod::Daily
+    }
+}
+
+impl QuotaPeriod {
+    /// The start of the period `time` falls in, periods are aligned to UTC.
+    fn start(self, time: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            QuotaPeriod::Hourly => time.date().and_hms(time.hour(), 0, 0),
+            QuotaPeriod::Daily => time.date().and_hms(0, 0, 0),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WhenExceeded {
+    Drop,
+    Sample,
+    Divert,
+}
+
+impl Default for WhenExceeded {
+    fn default() -> Self {
+        WhenExceeded::Drop
+    }
+}
+
+impl WhenExceeded {
+    fn as_str(self) -> &'static str {
+        match self {
+            WhenExceeded::Drop => "drop",
+            WhenExceeded::Sample => "sample",
+            WhenExceeded::Divert => "divert",
+        }
+    }
+}
+
+impl QuotaConfig {
+    /// Builds the quota of a sink, along with the healthcheck of the sink it
+    /// diverts to. The sink has to be built with `Quota::sink_context`.
+    pub async fn build(&self, cx: SinkContext) -> crate::Result<(Quota, Option<Healthcheck>)> {
+        if self.sample_rate == 0 {
+            return Err("`quota.sample_rate` must be greater than zero.".into());
+        }
+
+        let acks = SplitAcker::new(cx.acker(), 2);
+        let (divert, healthcheck) = match (self.when_exceeded, &self.divert) {
+            (WhenExceeded::Divert, Some(divert)) => {
+                let (sink, healthcheck) = divert.build(cx.with_acker(acks.acker(DIVERT))).await?;
+                (Some(sink), Some(healthcheck))
+            }
+            (WhenExceeded::Divert, None) => {
+                return Err(
+                    "`quota.divert` must be set when `quota.when_exceeded` is \"divert\".".into(),
+                )
+            }
+            (_, Some(_)) => {
+                return Err(
+                    "`quota.divert` is only used when `quota.when_exceeded` is \"divert\".".into(),
+                )
+            }
+            (_, None) => (None, None),
+        };
+
+        let quota = Quota {
+            state: QuotaState::new(
+                self.max_bytes,
+                self.period,
+                self.when_exceeded,
+                self.sample_rate,
+            ),
+            divert,
+            acks,
+        };
+        Ok((quota, healthcheck))
+    }
+}
+
+/// What to do with an event.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Decision {
+    Send,
+    Drop,
+    Divert,
+}
+
+/// Outbound byte accounting of a sink over the current period.
+#[derive(Debug)]
+struct QuotaState {
+    max_bytes: Option<u64>,
+    period: QuotaPeriod,
+    when_exceeded: WhenExceeded,
+    sample_rate: u64,
+    period_start: Option<DateTime<Utc>>,
+    used_bytes: u64,
+    exceeded_events: u64,
+}
+
+impl QuotaState {
+    fn new(
+        max_bytes: Option<u64>,
+        period: QuotaPeriod,
+        when_exceeded: WhenExceeded,
+        sample_rate: u64,
+    ) -> Self {
+        Self {
+            max_bytes,
+            period,
+            when_exceeded,
+            sample_rate,
+            period_start: None,
+            used_bytes: 0,
+            exceeded_events: 0,
+        }
+    }
+
+    /// Accounts an event of `byte_size` bytes seen at `now`, returning what
+    /// to do with it.
+    fn check(&mut self, byte_size: u64, now: DateTime<Utc>) -> Decision {
+        let period_start = self.period.start(now);
+        if self.period_start != Some(period_start) {
+            self.period_start = Some(period_start);
+            self.used_bytes = 0;
+            self.exceeded_events = 0;
+            emit!(SinkQuotaPeriodStarted {
+                max_bytes: self.max_bytes,
+            });
+        }
+
+        let within_quota = self
+            .max_bytes
+            .map_or(true, |max_bytes| self.used_bytes + byte_size <= max_bytes);
+        let decision = if within_quota {
+            Decision::Send
+        } else {
+            self.exceeded_events += 1;
+            emit!(SinkQuotaExceeded {
+                action: self.when_exceeded.as_str(),
+            });
+            match self.when_exceeded {
+                WhenExceeded::Drop => Decision::Drop,
+                WhenExceeded::Divert => Decision::Divert,
+                // Head sampling, the first event over the quota is sent.
+                WhenExceeded::Sample if (self.exceeded_events - 1) % self.sample_rate == 0 => {
+                    Decision::Send
+                }
+                WhenExceeded::Sample => Decision::Drop,
+            }
+        };
+
+        if decision == Decision::Send {
+            self.used_bytes += byte_size;
+            emit!(SinkQuotaBytesSent {
+                byte_size,
+                used_bytes: self.used_bytes,
+            });
+        }
+        decision
+    }
+}
+
+/// The size of an event encoded as JSON, which stands in for the size it's
+/// encoded with by the sink.
+fn encoded_size(event: &Event) -> u64 {
+    let size = match event {
+        Event::Log(log) | Event::Trace(TraceEvent(log)) => serde_json::to_vec(log).map(|v| v.len()),
+        Event::Metric(metric) => serde_json::to_vec(metric).map(|v| v.len()),
+    };
+    size.unwrap_or(0) as u64
+}
+
+pub struct Quota {
+    state: QuotaState,
+    divert: Option<VectorSink>,
+    /// Merges the acks of the sink, of the sink it diverts to, and of the
+    /// events dropped, into the order the buffer has to be acked in.
+    acks: Arc<SplitAcker>,
+}
+
+impl Quota {
+    /// The context to build the sink with, so that it acks the events within
+    /// the quota in order with the other ones.
+    pub fn sink_context(&self, cx: &SinkContext) -> SinkContext {
+        cx.with_acker(self.acks.acker(SINK))
+    }
+
+    /// Runs `sink` on the events of `input` that are within the quota.
+    pub async fn run<S>(self, sink: VectorSink, input: S) -> Result<(), ()>
+    where
+        S: Stream<Item = Event> + Send,
+    {
+        let Quota {
+            mut state,
+            divert,
+            acks,
+        } = self;
+
+        let (divert_tx, divert_rx) = match divert {
+            Some(_) => {
+                let (tx, rx) = mpsc::channel(100);
+                (Some(tx), Some(rx))
+            }
+            None => (None, None),
+        };
+
+        let input = input.filter_map(move |event| {
+            let decision = state.check(encoded_size(&event), Utc::now());
+            let divert_tx = divert_tx.clone();
+            let acks = Arc::clone(&acks);
+            async move {
+                match (decision, divert_tx) {
+                    (Decision::Send, _) => {
+                        acks.handed_to(SINK);
+                        Some(event)
+                    }
+                    (Decision::Divert, Some(mut divert_tx)) => {
+                        // The diverted sink acks the event instead.
+                        acks.handed_to(DIVERT);
+                        if divert_tx.send(event).await.is_err() {
+                            acks.acker(DIVERT).ack_with_status(1, EventStatus::Errored);
+                        }
+                        None
+                    }
+                    (Decision::Drop, _) | (Decision::Divert, None) => {
+                        acks.done(EventStatus::Dropped);
+                        None
+                    }
+                }
+            }
+        });
+
+        match (divert, divert_rx) {
+            (Some(divert), Some(divert_rx)) => {
+                let (sent, diverted) = future::join(sink.run(input), divert.run(divert_rx)).await;
+                sent.and(diverted)
+            }
+            _ => sink.run(input).await,
+        }
+    }
+}
+
+/// Combines the healthcheck of a sink with the one of the sink it diverts to.
+pub fn join_healthchecks(healthcheck: Healthcheck, divert: Option<Healthcheck>) -> Healthcheck {
+    match divert {
+        Some(divert) => future::try_join(healthcheck, divert).map_ok(|_| ()).boxed(),
+        None => healthcheck,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.ymd(2021, 1, 1).and_hms(hour, minute, 0)
+    }
+
+    #[cfg(feature = "sinks-blackhole")]
+    #[test]
+    fn parse_config() {
+        let config: QuotaConfig = toml::from_str(
+            r#"
+            max_bytes = 1000
+            period = "hourly"
+            when_exceeded = "divert"
+            divert.type = "blackhole"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.max_bytes, Some(1000));
+        assert_eq!(config.period, QuotaPeriod::Hourly);
+        assert_eq!(config.when_exceeded, WhenExceeded::Divert);
+        assert_eq!(config.sample_rate, 10);
+        assert!(config.divert.is_some());
+    }
+
+    #[test]
+    fn drops_over_quota_until_next_period() {
+        let mut state = QuotaState::new(Some(100), QuotaPeriod::Hourly, WhenExceeded::Drop, 10);
+
+        assert_eq!(state.check(60, at(10, 0)), Decision::Send);
+        assert_eq!(state.check(40, at(10, 10)), Decision::Send);
+        assert_eq!(state.check(1, at(10, 20)), Decision::Drop);
+        assert_eq!(state.check(60, at(11, 0)), Decision::Send);
+        assert_eq!(state.used_bytes, 60);
+    }
+
+    #[test]
+    fn samples_over_quota() {
+        let mut state = QuotaState::new(Some(10), QuotaPeriod::Daily, WhenExceeded::Sample, 3);
+
+        assert_eq!(state.check(10, at(1, 0)), Decision::Send);
+        let decisions = (0..6).map(|_| state.check(5, at(2, 0))).collect::<Vec<_>>();
+        assert_eq!(
+            decisions,
+            vec![
+                Decision::Send,
+                Decision::Drop,
+                Decision::Drop,
+                Decision::Send,
+                Decision::Drop,
+                Decision::Drop,
+            ]
+        );
+        assert_eq!(state.used_bytes, 20);
+    }
+
+    #[test]
+    fn diverts_over_quota() {
+        let mut state = QuotaState::new(Some(10), QuotaPeriod::Daily, WhenExceeded::Divert, 10);
+
+        assert_eq!(state.check(20, at(1, 0)), Decision::Divert);
+        assert_eq!(state.check(10, at(1, 0)), Decision::Send);
+    }
+
+    #[test]
+    fn accounts_without_limit() {
+        let mut state = QuotaState::new(None, QuotaPeriod::Daily, WhenExceeded::Drop, 10);
+
+        assert_eq!(state.check(1 << 40, at(1, 0)), Decision::Send);
+        assert_eq!(state.check(1 << 40, at(2, 0)), Decision::Send);
+        assert_eq!(state.used_bytes, 2 << 40);
+    }
+}
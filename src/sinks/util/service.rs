@@ -174,11 +174,15 @@ pub struct TowerRequestConfig<T: ConcurrencyOption = Concurrency> {
     #[serde(default)]
     #[serde(skip_serializing_if = "ConcurrencyOption::is_none")]
     pub in_flight_limit: T, // 5
-    pub timeout_secs: Option<u64>,             // 60
+    #[serde(default, deserialize_with = "crate::serde::optional_duration_secs")]
+    pub timeout_secs: Option<u64>, // 60
+    #[serde(default, deserialize_with = "crate::serde::optional_duration_secs")]
     pub rate_limit_duration_secs: Option<u64>, // 1
-    pub rate_limit_num: Option<u64>,           // 5
-    pub retry_attempts: Option<usize>,         // max_value()
+    pub rate_limit_num: Option<u64>,   // 5
+    pub retry_attempts: Option<usize>, // max_value()
+    #[serde(default, deserialize_with = "crate::serde::optional_duration_secs")]
     pub retry_max_duration_secs: Option<u64>,
+    #[serde(default, deserialize_with = "crate::serde::optional_duration_secs")]
     pub retry_initial_backoff_secs: Option<u64>, // 1
     #[serde(default)]
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
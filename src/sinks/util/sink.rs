@@ -35,7 +35,7 @@ use super::{
     batch::{Batch, PushResult, StatefulBatch},
     buffer::partition::Partition,
 };
-use crate::{buffers::Acker, Event};
+use crate::{buffers::Acker, event::EventStatus, Event};
 use async_trait::async_trait;
 use futures::{
     future::BoxFuture,
@@ -448,11 +448,11 @@ where
 
 struct ServiceSink<S, Request> {
     service: S,
-    in_flight: FuturesUnordered<oneshot::Receiver<(usize, usize)>>,
+    in_flight: FuturesUnordered<oneshot::Receiver<(usize, usize, EventStatus)>>,
     acker: Acker,
     seq_head: usize,
     seq_tail: usize,
-    pending_acks: HashMap<usize, usize>,
+    pending_acks: HashMap<usize, (usize, EventStatus)>,
     next_request_id: usize,
     _pd: PhantomData<Request>,
 }
@@ -500,22 +500,25 @@ where
             .call(req)
             .err_into()
             .map(move |result| {
-                match result {
+                let status = match result {
                     Ok(response) if response.is_successful() => {
                         trace!(message = "Response successful.", ?response);
+                        EventStatus::Delivered
                     }
                     Ok(response) => {
                         error!(message = "Response wasn't successful.", ?response);
+                        EventStatus::Failed
                     }
                     Err(error) => {
                         error!(message = "Request failed.", %error);
+                        EventStatus::Errored
                     }
-                }
+                };
 
                 // If the rx end is dropped we still completed
                 // the request so this is a weird case that we can
                 // ignore for now.
-                let _ = tx.send((seqno, batch_size));
+                let _ = tx.send((seqno, batch_size, status));
             })
             .instrument(info_span!("request", %request_id))
             .boxed()
@@ -524,16 +527,15 @@ where
     fn poll_complete(&mut self, cx: &mut Context<'_>) -> Poll<()> {
         while !self.in_flight.is_empty() {
             match ready!(Pin::new(&mut self.in_flight).poll_next(cx)) {
-                Some(Ok((seqno, batch_size))) => {
-                    self.pending_acks.insert(seqno, batch_size);
+                Some(Ok((seqno, batch_size, status))) => {
+                    self.pending_acks.insert(seqno, (batch_size, status));
 
-                    let mut num_to_ack = 0;
-                    while let Some(ack_size) = self.pending_acks.remove(&self.seq_tail) {
-                        num_to_ack += ack_size;
+                    // The batches are acked in order, each with its own status.
+                    while let Some((ack_size, status)) = self.pending_acks.remove(&self.seq_tail) {
+                        trace!(message = "Acking events.", acking_num = ack_size, ?status);
+                        self.acker.ack_with_status(ack_size, status);
                         self.seq_tail += 1
                     }
-                    trace!(message = "Acking events.", acking_num = num_to_ack);
-                    self.acker.ack(num_to_ack);
                 }
                 Some(Err(_)) => panic!("ServiceSink service sender dropped."),
                 None => break,
@@ -575,6 +577,7 @@ mod tests {
     use super::*;
     use crate::{
         buffers::Acker,
+        event::{BatchNotifier, BatchStatus},
         sinks::util::{BatchSettings, EncodedLength, VecBuffer},
         test_util::trace_init,
     };
@@ -1001,6 +1004,56 @@ mod tests {
         assert_eq!(ack_counter.load(Relaxed), 10);
     }
 
+    #[tokio::test]
+    async fn service_sink_finalizes_with_request_status() {
+        let (acker, pending) = Acker::Null.with_finalizers();
+        let mut receivers = Vec::new();
+        for _ in 0..3 {
+            let (batch, receiver) = BatchNotifier::new_with_receiver();
+            let mut event = Event::from("line").with_batch_notifier(&batch);
+            pending.lock().unwrap().push_back(event.take_finalizers());
+            receivers.push(receiver);
+        }
+
+        #[derive(Debug)]
+        struct Accepted(bool);
+
+        impl Response for Accepted {
+            fn is_successful(&self) -> bool {
+                self.0
+            }
+        }
+
+        let svc = tower::service_fn(|req: u8| match req {
+            1 => future::ok(Accepted(true)),
+            2 => future::err("bad"),
+            _ => future::ok(Accepted(false)),
+        });
+        let mut sink = ServiceSink::new(svc, acker);
+
+        let mut cx = Context::from_waker(noop_waker_ref());
+        for req in 1..=3 {
+            assert!(matches!(
+                sink.call(req, 1).poll_unpin(&mut cx),
+                Poll::Ready(())
+            ));
+        }
+        assert!(matches!(sink.poll_complete(&mut cx), Poll::Ready(())));
+
+        let statuses = receivers
+            .into_iter()
+            .map(|mut receiver| receiver.try_recv().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                BatchStatus::Delivered,
+                BatchStatus::Errored,
+                BatchStatus::Failed
+            ]
+        );
+    }
+
     #[derive(Debug, PartialEq, Eq, Ord, PartialOrd)]
     enum Partitions {
         A,
@@ -1,24 +1,20 @@
 use crate::{
     config::{self, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription},
-    event::metric::{Metric, MetricKind, MetricValue},
-    http::HttpClient,
+    event::metric::Metric,
+    http::{Auth, HttpClient},
     internal_events::{
         ApacheMetricsErrorResponse, ApacheMetricsEventReceived, ApacheMetricsHttpError,
         ApacheMetricsParseError, ApacheMetricsRequestCompleted,
     },
     shutdown::ShutdownSignal,
+    sources::util::{MetricsEndpoint, ScrapeError, ScrapeTarget},
+    tls::{TlsOptions, TlsSettings},
     Event, Pipeline,
 };
 use chrono::Utc;
-use futures::{stream, FutureExt, SinkExt, StreamExt, TryFutureExt};
-use hyper::{Body, Request};
+use futures::{future::join_all, stream, SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use snafu::ResultExt;
-use std::{
-    collections::BTreeMap,
-    future::ready,
-    time::{Duration, Instant},
-};
+use std::time::{Duration, Instant};
 
 mod parser;
 
@@ -26,11 +22,16 @@ pub use parser::ParseError;
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 struct ApacheMetricsConfig {
-    endpoints: Vec<String>,
-    #[serde(default = "default_scrape_interval_secs")]
+    endpoints: Vec<MetricsEndpoint>,
+    #[serde(
+        default = "default_scrape_interval_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
     scrape_interval_secs: u64,
     #[serde(default = "default_namespace")]
     namespace: String,
+    tls: Option<TlsOptions>,
+    auth: Option<Auth>,
 }
 
 pub fn default_scrape_interval_secs() -> u64 {
@@ -48,9 +49,11 @@ inventory::submit! {
 impl GenerateConfig for ApacheMetricsConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
-            endpoints: vec!["http://localhost:8080/server-status/?auto".to_owned()],
+            endpoints: vec!["http://localhost:8080/server-status/?auto".into()],
             scrape_interval_secs: default_scrape_interval_secs(),
             namespace: default_namespace(),
+            tls: None,
+            auth: None,
         })
         .unwrap()
     }
@@ -66,19 +69,24 @@ impl SourceConfig for ApacheMetricsConfig {
         shutdown: ShutdownSignal,
         out: Pipeline,
     ) -> crate::Result<super::Source> {
-        let urls = self
-            .endpoints
-            .iter()
-            .map(|endpoint| endpoint.parse::<http::Uri>())
-            .collect::<Result<Vec<_>, _>>()
-            .context(super::UriParseError)?;
+        let tls = TlsSettings::from_options(&self.tls)?;
+        if let Some(auth) = &self.auth {
+            auth.validate()?;
+        }
+        let http_client = HttpClient::new(tls)?;
 
         let namespace = Some(self.namespace.clone()).filter(|namespace| !namespace.is_empty());
+        let targets = self
+            .endpoints
+            .iter()
+            .map(|endpoint| ScrapeTarget::new(endpoint, namespace.clone()))
+            .collect::<crate::Result<Vec<_>>>()?;
 
         Ok(apache_metrics(
-            urls,
+            http_client,
+            targets,
+            self.auth.clone(),
             self.scrape_interval_secs,
-            namespace,
             shutdown,
             out,
         ))
@@ -93,174 +101,98 @@ impl SourceConfig for ApacheMetricsConfig {
     }
 }
 
-trait UriExt {
-    fn to_sanitized_string(&self) -> String;
-
-    fn sanitized_authority(&self) -> String;
-}
-
-impl UriExt for http::Uri {
-    fn to_sanitized_string(&self) -> String {
-        let mut s = String::new();
-
-        if let Some(scheme) = self.scheme() {
-            s.push_str(scheme.as_str());
-            s.push_str("://");
+fn apache_metrics(
+    http_client: HttpClient,
+    targets: Vec<ScrapeTarget>,
+    auth: Option<Auth>,
+    interval: u64,
+    shutdown: ShutdownSignal,
+    out: Pipeline,
+) -> super::Source {
+    let mut out = out.sink_map_err(|error| error!(message = "Error sending metric.", %error));
+
+    Box::pin(async move {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(interval)).take_until(shutdown);
+        while interval.next().await.is_some() {
+            let metrics = join_all(
+                targets
+                    .iter()
+                    .map(|target| scrape(&http_client, target, auth.as_ref())),
+            )
+            .await;
+
+            let mut stream = stream::iter(metrics)
+                .map(stream::iter)
+                .flatten()
+                .map(Event::Metric)
+                .map(Ok);
+            out.send_all(&mut stream).await?;
         }
 
-        s.push_str(&self.sanitized_authority());
+        info!("Finished sending.");
+        Ok(())
+    })
+}
 
-        s.push_str(self.path());
+async fn scrape(
+    http_client: &HttpClient,
+    target: &ScrapeTarget,
+    auth: Option<&Auth>,
+) -> Vec<Metric> {
+    let start = Instant::now();
+    let url = target.endpoint();
+    let (up, mut metrics) = match target.get(http_client, auth).await {
+        Ok(body) => {
+            emit!(ApacheMetricsRequestCompleted {
+                start,
+                end: Instant::now()
+            });
+
+            let byte_size = body.len();
+            let body = String::from_utf8_lossy(&body);
+
+            let mut failed = false;
+            let metrics = parser::parse(&body, target.namespace(), Utc::now(), Some(target.tags()))
+                .filter_map(|res| match res {
+                    Ok(metric) => Some(metric),
+                    Err(error) => {
+                        emit!(ApacheMetricsParseError { error, url });
+                        failed = true;
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            if failed {
+                target.scrape_failed();
+            }
 
-        if let Some(query) = self.query() {
-            s.push_str(query);
+            emit!(ApacheMetricsEventReceived {
+                byte_size,
+                count: metrics.len(),
+            });
+            (true, metrics)
         }
-
-        s
-    }
-
-    fn sanitized_authority(&self) -> String {
-        let mut s = String::new();
-
-        if let Some(host) = self.host() {
-            s.push_str(host);
+        // we still publish `up=1` for bad status codes following the pattern of the Prometheus exporter:
+        //
+        // https://github.com/Lusitaniae/apache_exporter/blob/712a6796fb84f741ef3cd562dc11418f2ee8b741/apache_exporter.go#L200
+        Err(ScrapeError::InvalidResponseStatus { status }) => {
+            emit!(ApacheMetricsErrorResponse { code: status, url });
+            target.scrape_failed();
+            (true, vec![])
         }
-
-        if let Some(port) = self.port() {
-            s.push(':');
-            s.push_str(port.as_str());
+        Err(error) => {
+            emit!(ApacheMetricsHttpError {
+                error: error.into(),
+                url
+            });
+            target.scrape_failed();
+            (false, vec![])
         }
+    };
 
-        s
-    }
-}
-
-fn apache_metrics(
-    urls: Vec<http::Uri>,
-    interval: u64,
-    namespace: Option<String>,
-    shutdown: ShutdownSignal,
-    out: Pipeline,
-) -> super::Source {
-    let out = out.sink_map_err(|error| error!(message = "Error sending metric.", %error));
-
-    Box::pin(
-        tokio::time::interval(Duration::from_secs(interval))
-            .take_until(shutdown)
-            .map(move |_| stream::iter(urls.clone()))
-            .flatten()
-            .map(move |url| {
-                let client = HttpClient::new(None).expect("HTTPS initialization failed");
-                let sanitized_url = url.to_sanitized_string();
-
-                let request = Request::get(&url)
-                    .body(Body::empty())
-                    .expect("error creating request");
-
-                let mut tags: BTreeMap<String, String> = BTreeMap::new();
-                tags.insert("endpoint".into(), sanitized_url.to_string());
-                tags.insert("host".into(), url.sanitized_authority());
-
-                let start = Instant::now();
-                let namespace = namespace.clone();
-                client
-                    .send(request)
-                    .map_err(crate::Error::from)
-                    .and_then(|response| async {
-                        let (header, body) = response.into_parts();
-                        let body = hyper::body::to_bytes(body).await?;
-                        Ok((header, body))
-                    })
-                    .into_stream()
-                    .filter_map(move |response| {
-                        ready(match response {
-                            Ok((header, body)) if header.status == hyper::StatusCode::OK => {
-                                emit!(ApacheMetricsRequestCompleted {
-                                    start,
-                                    end: Instant::now()
-                                });
-
-                                let byte_size = body.len();
-                                let body = String::from_utf8_lossy(&body);
-
-                                let results = parser::parse(
-                                    &body,
-                                    namespace.as_deref(),
-                                    Utc::now(),
-                                    Some(&tags),
-                                )
-                                .chain(vec![Ok(Metric::new(
-                                    "up",
-                                    MetricKind::Absolute,
-                                    MetricValue::Gauge { value: 1.0 },
-                                )
-                                .with_namespace(namespace.clone())
-                                .with_tags(Some(tags.clone()))
-                                .with_timestamp(Some(Utc::now())))]);
-
-                                let metrics = results
-                                    .filter_map(|res| match res {
-                                        Ok(metric) => Some(metric),
-                                        Err(e) => {
-                                            emit!(ApacheMetricsParseError {
-                                                error: e,
-                                                url: &sanitized_url,
-                                            });
-                                            None
-                                        }
-                                    })
-                                    .collect::<Vec<_>>();
-
-                                emit!(ApacheMetricsEventReceived {
-                                    byte_size,
-                                    count: metrics.len(),
-                                });
-                                Some(stream::iter(metrics).map(Event::Metric).map(Ok))
-                            }
-                            Ok((header, _)) => {
-                                emit!(ApacheMetricsErrorResponse {
-                                    code: header.status,
-                                    url: &sanitized_url,
-                                });
-                                Some(
-                                    stream::iter(vec![Metric::new(
-                                        "up",
-                                        MetricKind::Absolute,
-                                        MetricValue::Gauge { value: 1.0 },
-                                    )
-                                    .with_namespace(namespace.clone())
-                                    .with_tags(Some(tags.clone()))
-                                    .with_timestamp(Some(Utc::now()))])
-                                    .map(Event::Metric)
-                                    .map(Ok),
-                                )
-                            }
-                            Err(error) => {
-                                emit!(ApacheMetricsHttpError {
-                                    error,
-                                    url: &sanitized_url
-                                });
-                                Some(
-                                    stream::iter(vec![Metric::new(
-                                        "up",
-                                        MetricKind::Absolute,
-                                        MetricValue::Gauge { value: 0.0 },
-                                    )
-                                    .with_namespace(namespace.clone())
-                                    .with_tags(Some(tags.clone()))
-                                    .with_timestamp(Some(Utc::now()))])
-                                    .map(Event::Metric)
-                                    .map(Ok),
-                                )
-                            }
-                        })
-                    })
-                    .flatten()
-            })
-            .flatten()
-            .forward(out)
-            .inspect(|_| info!("Finished sending.")),
-    )
+    metrics.extend(target.status_metrics(up));
+    metrics
 }
 
 #[cfg(test)]
@@ -268,6 +200,7 @@ mod test {
     use super::*;
     use crate::{
         config::{GlobalOptions, SourceConfig},
+        event::metric::MetricValue,
         test_util::{collect_ready, next_addr, wait_for_tcp},
         Error,
     };
@@ -343,9 +276,14 @@ Scoreboard: ____S_____I______R____I_______KK___D__C__G_L____________W___________
         let (tx, rx) = Pipeline::new_test();
 
         let source = ApacheMetricsConfig {
-            endpoints: vec![format!("http://foo:bar@{}/metrics", in_addr)],
+            endpoints: vec![MetricsEndpoint::Url(format!(
+                "http://foo:bar@{}/metrics",
+                in_addr
+            ))],
             scrape_interval_secs: 1,
             namespace: "custom".to_string(),
+            tls: None,
+            auth: None,
         }
         .build(
             "default",
@@ -409,9 +347,11 @@ Scoreboard: ____S_____I______R____I_______KK___D__C__G_L____________W___________
         let (tx, rx) = Pipeline::new_test();
 
         let source = ApacheMetricsConfig {
-            endpoints: vec![format!("http://{}", in_addr)],
+            endpoints: vec![MetricsEndpoint::Url(format!("http://{}", in_addr))],
             scrape_interval_secs: 1,
             namespace: "apache".to_string(),
+            tls: None,
+            auth: None,
         }
         .build(
             "default",
@@ -448,9 +388,11 @@ Scoreboard: ____S_____I______R____I_______KK___D__C__G_L____________W___________
         let (tx, rx) = Pipeline::new_test();
 
         let source = ApacheMetricsConfig {
-            endpoints: vec![format!("http://{}", in_addr)],
+            endpoints: vec![MetricsEndpoint::Url(format!("http://{}", in_addr))],
             scrape_interval_secs: 1,
             namespace: "custom".to_string(),
+            tls: None,
+            auth: None,
         }
         .build(
             "default",
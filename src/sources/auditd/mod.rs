@@ -0,0 +1,331 @@
+use crate::{
+    config::{log_schema, DataType, GlobalOptions, Resource, SourceConfig, SourceDescription},
+    event::{Event, LogEvent, Value},
+    internal_events::{
+        AuditdEventsLost, AuditdEventsReceived, AuditdReadError, AuditdRecordParseError,
+    },
+    shutdown::ShutdownSignal,
+    Pipeline,
+};
+use futures::{channel::mpsc, executor, stream, SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead},
+    thread,
+    time::{Duration, Instant},
+};
+use tokio::time;
+
+mod netlink;
+mod parser;
+mod reassembler;
+
+pub use parser::ParseError;
+
+use netlink::{AuditSocket, Received};
+use parser::{FieldValue, Record};
+use reassembler::Reassembler;
+
+/// How often the socket is checked for the source being stopped.
+const RECEIVE_TIMEOUT_SECS: i64 = 1;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct AuditdConfig {
+    mode: Mode,
+    #[derivative(Default(value = "2"))]
+    #[serde(deserialize_with = "crate::serde::duration_secs")]
+    reassembly_timeout_secs: u64,
+    #[derivative(Default(value = "256"))]
+    max_in_flight: usize,
+    host_key: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, PartialEq, Eq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Reads the records from the audit netlink socket.
+    #[derivative(Default)]
+    Netlink,
+    /// Reads the records from stdin, as a plugin of `audispd` or `auditd`.
+    Audisp,
+}
+
+inventory::submit! {
+    SourceDescription::new::<AuditdConfig>("auditd")
+}
+
+impl_generate_config_from_default!(AuditdConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "auditd")]
+impl SourceConfig for AuditdConfig {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let (tx, rx) = mpsc::channel(1024);
+        match self.mode {
+            Mode::Netlink => {
+                let socket = AuditSocket::open(RECEIVE_TIMEOUT_SECS)?;
+                thread::Builder::new()
+                    .name("auditd".into())
+                    .spawn(move || read_netlink(socket, tx))?;
+            }
+            Mode::Audisp => {
+                thread::Builder::new()
+                    .name("auditd".into())
+                    .spawn(move || read_lines(io::BufReader::new(io::stdin()), tx))?;
+            }
+        }
+
+        Ok(auditd_source(self, rx, shutdown, out))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "auditd"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        match self.mode {
+            Mode::Netlink => Vec::new(),
+            Mode::Audisp => vec![Resource::Stdin],
+        }
+    }
+}
+
+fn read_netlink(mut socket: AuditSocket, mut tx: mpsc::Sender<Record>) {
+    info!("Capturing audit records.");
+
+    while !tx.is_closed() {
+        let messages = match socket.receive() {
+            Ok(Received::Records(messages)) => messages,
+            Ok(Received::Timeout) => continue,
+            Ok(Received::Overrun) => {
+                emit!(AuditdEventsLost);
+                continue;
+            }
+            Err(error) => {
+                emit!(AuditdReadError {
+                    error: error.into()
+                });
+                return;
+            }
+        };
+
+        for (record_type, text) in messages {
+            match parser::parse_message(record_type, &text) {
+                Ok(record) => {
+                    if executor::block_on(tx.send(record)).is_err() {
+                        return;
+                    }
+                }
+                Err(error) => emit!(AuditdRecordParseError { error }),
+            }
+        }
+    }
+}
+
+fn read_lines(lines: impl BufRead, mut tx: mpsc::Sender<Record>) {
+    info!("Capturing audit records from STDIN.");
+
+    for line in lines.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(error) => {
+                emit!(AuditdReadError {
+                    error: error.into()
+                });
+                return;
+            }
+        };
+        if line.is_empty() {
+            continue;
+        }
+
+        match parser::parse_line(&line) {
+            Ok(record) => {
+                if executor::block_on(tx.send(record)).is_err() {
+                    return;
+                }
+            }
+            Err(error) => emit!(AuditdRecordParseError { error }),
+        }
+    }
+}
+
+fn auditd_source(
+    config: &AuditdConfig,
+    records: mpsc::Receiver<Record>,
+    shutdown: ShutdownSignal,
+    out: Pipeline,
+) -> super::Source {
+    let host_key = config
+        .host_key
+        .clone()
+        .unwrap_or_else(|| log_schema().host_key().to_string());
+    let hostname = crate::get_hostname().ok();
+    let timeout = Duration::from_secs(config.reassembly_timeout_secs);
+    let mut reassembler = Reassembler::new(timeout, config.max_in_flight);
+
+    let mut out = out.sink_map_err(|error| error!(message = "Error sending event.", %error));
+    Box::pin(async move {
+        let mut records = records.take_until(shutdown);
+        let mut ticks = time::interval(Duration::from_millis(500));
+
+        loop {
+            let (events, done) = tokio::select! {
+                record = records.next() => match record {
+                    Some(record) => (reassembler.push(record, Instant::now()), false),
+                    None => (reassembler.flush(), true),
+                },
+                _ = ticks.tick() => (reassembler.flush_expired(Instant::now()), false),
+            };
+
+            if !events.is_empty() {
+                emit!(AuditdEventsReceived {
+                    count: events.len()
+                });
+                let mut events = stream::iter(events).map(|records| {
+                    let mut log = create_log(records);
+                    if let Some(hostname) = &hostname {
+                        log.insert(host_key.as_str(), hostname.clone());
+                    }
+                    Ok(Event::Log(log))
+                });
+                out.send_all(&mut events).await?;
+            }
+
+            if done {
+                return Ok(());
+            }
+        }
+    })
+}
+
+/// Creates the event of its records, which are keyed by the lowercase name of
+/// their type, the records of the same type making up an array.
+fn create_log(records: Vec<Record>) -> LogEvent {
+    let mut log = LogEvent::default();
+    let first = &records[0];
+    log.insert(log_schema().timestamp_key(), first.timestamp);
+    log.insert("sequence", first.serial as i64);
+    log.insert("type", first.type_name.clone());
+    if let Some(node) = &first.node {
+        log.insert("node", node.clone());
+    }
+
+    let keys = records
+        .iter()
+        .flat_map(|record| record.keys())
+        .map(Value::from)
+        .collect::<Vec<_>>();
+    if !keys.is_empty() {
+        log.insert("keys", keys);
+    }
+
+    let mut grouped = BTreeMap::<String, Vec<Value>>::new();
+    for record in records {
+        grouped
+            .entry(record.type_name.to_lowercase())
+            .or_default()
+            .push(fields_value(record.fields));
+    }
+    for (name, mut values) in grouped {
+        let value = if values.len() == 1 {
+            values.remove(0)
+        } else {
+            Value::Array(values)
+        };
+        log.insert_flat(name, value);
+    }
+
+    log
+}
+
+fn fields_value(fields: BTreeMap<String, FieldValue>) -> Value {
+    Value::Map(
+        fields
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    FieldValue::Value(value) => Value::from(value),
+                    FieldValue::Fields(fields) => fields_value(fields),
+                };
+                (key, value)
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::collect_ready, Pipeline};
+    use chrono::{TimeZone, Utc};
+    use std::io::Cursor;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AuditdConfig>();
+    }
+
+    #[test]
+    fn creates_logs() {
+        let records = vec![
+            parser::parse_line(r#"type=SYSCALL msg=audit(1610000000.500:7): syscall=59 success=yes comm="ls" key="exec""#).unwrap(),
+            parser::parse_line(r#"type=PATH msg=audit(1610000000.500:7): item=0 name="/usr/bin/ls""#).unwrap(),
+            parser::parse_line(r#"type=PATH msg=audit(1610000000.500:7): item=1 name="/lib64/ld-linux-x86-64.so.2""#).unwrap(),
+        ];
+
+        let log = create_log(records);
+        assert_eq!(log["type"], "SYSCALL".into());
+        assert_eq!(log["sequence"], Value::Integer(7));
+        assert_eq!(log["keys"], Value::Array(vec!["exec".into()]));
+        assert_eq!(log["syscall.comm"], "ls".into());
+        assert_eq!(log["path[1].name"], "/lib64/ld-linux-x86-64.so.2".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Value::Timestamp(Utc.timestamp(1610000000, 500_000_000))
+        );
+    }
+
+    #[tokio::test]
+    async fn reassembles_lines() {
+        let lines = Cursor::new(
+            "type=SYSCALL msg=audit(1610000000.000:1): syscall=2 key=(null)\n\
+             type=USER_LOGIN msg=audit(1610000000.000:2): pid=1 msg='op=login res=success'\n\
+             type=CWD msg=audit(1610000000.000:1): cwd=\"/root\"\n\
+             invalid\n\
+             type=EOE msg=audit(1610000000.000:1): \n",
+        );
+        let (tx, rx) = mpsc::channel(1024);
+        read_lines(lines, tx);
+
+        let (out, mut events) = Pipeline::new_test();
+        let source = auditd_source(&AuditdConfig::default(), rx, ShutdownSignal::noop(), out);
+        source.await.unwrap();
+
+        let events = collect_ready(&mut events).await;
+        assert_eq!(events.len(), 2);
+
+        let log = events[0].as_log();
+        assert_eq!(log["type"], "USER_LOGIN".into());
+        assert_eq!(log["user_login.msg.res"], "success".into());
+
+        let log = events[1].as_log();
+        assert_eq!(log["type"], "SYSCALL".into());
+        assert_eq!(log["cwd.cwd"], "/root".into());
+        assert!(log.get("keys").is_none());
+    }
+}
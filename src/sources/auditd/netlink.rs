@@ -0,0 +1,143 @@
+//! Receives the records of the kernel through the audit netlink socket, as a
+//! reader of its multicast group, so `auditd` can keep running alongside.
+
+use nix::{
+    errno::Errno,
+    sys::{
+        socket::{
+            bind, recv, setsockopt, socket, sockopt::ReceiveTimeout, AddressFamily, MsgFlags,
+            NetlinkAddr, SockAddr, SockFlag, SockProtocol, SockType,
+        },
+        time::{TimeVal, TimeValLike},
+    },
+    unistd::close,
+};
+use std::{convert::TryInto, os::unix::io::RawFd};
+
+/// `AUDIT_NLGRP_READLOG`, the group of the readers of the records.
+const READLOG_GROUP: u32 = 1;
+
+const HEADER_LEN: usize = 16;
+const NLMSG_NOOP: u16 = 1;
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+const NLMSG_OVERRUN: u16 = 4;
+
+/// The largest message of the kernel, `MAX_AUDIT_MESSAGE_LENGTH`.
+const BUFFER_LEN: usize = 8970;
+
+pub struct AuditSocket {
+    fd: RawFd,
+    buffer: Vec<u8>,
+}
+
+pub enum Received {
+    /// The type and the text of the records received.
+    Records(Vec<(u16, String)>),
+    /// Nothing was received before the timeout.
+    Timeout,
+    /// The socket buffer overflowed, and records were lost.
+    Overrun,
+}
+
+impl AuditSocket {
+    /// Joins the group, which requires the `CAP_AUDIT_READ` capability.
+    pub fn open(timeout_secs: i64) -> nix::Result<Self> {
+        let fd = socket(
+            AddressFamily::Netlink,
+            SockType::Raw,
+            SockFlag::SOCK_CLOEXEC,
+            SockProtocol::NetlinkAudit,
+        )?;
+        let socket = Self {
+            fd,
+            buffer: vec![0; BUFFER_LEN],
+        };
+
+        setsockopt(fd, ReceiveTimeout, &TimeVal::seconds(timeout_secs))?;
+        // Groups 1 to 32 are joined by the mask of the address bound to.
+        bind(
+            fd,
+            &SockAddr::Netlink(NetlinkAddr::new(0, 1 << (READLOG_GROUP - 1))),
+        )?;
+
+        Ok(socket)
+    }
+
+    pub fn receive(&mut self) -> nix::Result<Received> {
+        let len = match recv(self.fd, &mut self.buffer, MsgFlags::empty()) {
+            Ok(len) => len,
+            Err(nix::Error::Sys(Errno::EAGAIN)) | Err(nix::Error::Sys(Errno::EINTR)) => {
+                return Ok(Received::Timeout)
+            }
+            Err(nix::Error::Sys(Errno::ENOBUFS)) => return Ok(Received::Overrun),
+            Err(error) => return Err(error),
+        };
+
+        Ok(Received::Records(parse_messages(&self.buffer[..len])))
+    }
+}
+
+impl Drop for AuditSocket {
+    fn drop(&mut self) {
+        let _ = close(self.fd);
+    }
+}
+
+/// Splits a datagram into its netlink messages, and keeps those of records.
+fn parse_messages(mut data: &[u8]) -> Vec<(u16, String)> {
+    let mut records = Vec::new();
+
+    while data.len() >= HEADER_LEN {
+        let len = u32::from_ne_bytes(data[0..4].try_into().unwrap()) as usize;
+        let message_type = u16::from_ne_bytes(data[4..6].try_into().unwrap());
+        if len < HEADER_LEN || len > data.len() {
+            break;
+        }
+
+        match message_type {
+            NLMSG_NOOP | NLMSG_ERROR | NLMSG_DONE | NLMSG_OVERRUN => (),
+            record_type => {
+                let text = String::from_utf8_lossy(&data[HEADER_LEN..len]);
+                records.push((record_type, text.trim_end_matches('\0').to_owned()));
+            }
+        }
+
+        // Messages are aligned to 4 bytes.
+        let aligned = (len + 3) & !3;
+        data = data.get(aligned..).unwrap_or(&[]);
+    }
+
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(message_type: u16, text: &str) -> Vec<u8> {
+        let len = HEADER_LEN + text.len();
+        let mut data = Vec::new();
+        data.extend_from_slice(&(len as u32).to_ne_bytes());
+        data.extend_from_slice(&message_type.to_ne_bytes());
+        data.extend_from_slice(&[0; 10]);
+        data.extend_from_slice(text.as_bytes());
+        data.resize((len + 3) & !3, 0);
+        data
+    }
+
+    #[test]
+    fn parses_messages() {
+        let mut data = message(1300, "audit(1.000:1): pid=1");
+        data.extend(message(NLMSG_DONE, ""));
+        data.extend(message(1320, "audit(1.000:1): \0"));
+
+        assert_eq!(
+            parse_messages(&data),
+            vec![
+                (1300, "audit(1.000:1): pid=1".to_owned()),
+                (1320, "audit(1.000:1): ".to_owned())
+            ]
+        );
+    }
+}
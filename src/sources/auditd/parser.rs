@@ -0,0 +1,501 @@
+use chrono::{DateTime, TimeZone, Utc};
+use snafu::Snafu;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum ParseError {
+    #[snafu(display("Missing the `audit(<time>:<serial>): ` header"))]
+    MissingHeader,
+    #[snafu(display("Invalid header `{}`", header))]
+    InvalidHeader { header: String },
+    #[snafu(display("Missing the record type"))]
+    MissingType,
+}
+
+/// The types of the records, from `linux/audit.h` and `libaudit.h`.
+const RECORD_TYPES: &[(u16, &str)] = &[
+    (1100, "USER_AUTH"),
+    (1101, "USER_ACCT"),
+    (1102, "USER_MGMT"),
+    (1103, "CRED_ACQ"),
+    (1104, "CRED_DISP"),
+    (1105, "USER_START"),
+    (1106, "USER_END"),
+    (1107, "USER_AVC"),
+    (1108, "USER_CHAUTHTOK"),
+    (1109, "USER_ERR"),
+    (1110, "CRED_REFR"),
+    (1111, "USYS_CONFIG"),
+    (1112, "USER_LOGIN"),
+    (1113, "USER_LOGOUT"),
+    (1114, "ADD_USER"),
+    (1115, "DEL_USER"),
+    (1116, "ADD_GROUP"),
+    (1117, "DEL_GROUP"),
+    (1118, "DAC_CHECK"),
+    (1119, "CHGRP_ID"),
+    (1120, "TEST"),
+    (1121, "TRUSTED_APP"),
+    (1122, "USER_SELINUX_ERR"),
+    (1123, "USER_CMD"),
+    (1124, "USER_TTY"),
+    (1125, "CHUSER_ID"),
+    (1126, "GRP_AUTH"),
+    (1127, "SYSTEM_BOOT"),
+    (1128, "SYSTEM_SHUTDOWN"),
+    (1129, "SYSTEM_RUNLEVEL"),
+    (1130, "SERVICE_START"),
+    (1131, "SERVICE_STOP"),
+    (1132, "GRP_MGMT"),
+    (1133, "GRP_CHAUTHTOK"),
+    (1134, "MAC_CHECK"),
+    (1135, "ACCT_LOCK"),
+    (1136, "ACCT_UNLOCK"),
+    (1137, "USER_DEVICE"),
+    (1138, "SOFTWARE_UPDATE"),
+    (1200, "DAEMON_START"),
+    (1201, "DAEMON_END"),
+    (1202, "DAEMON_ABORT"),
+    (1203, "DAEMON_CONFIG"),
+    (1204, "DAEMON_RECONFIG"),
+    (1205, "DAEMON_ROTATE"),
+    (1206, "DAEMON_RESUME"),
+    (1207, "DAEMON_ACCEPT"),
+    (1208, "DAEMON_CLOSE"),
+    (1209, "DAEMON_ERR"),
+    (1300, "SYSCALL"),
+    (1302, "PATH"),
+    (1303, "IPC"),
+    (1304, "SOCKETCALL"),
+    (1305, "CONFIG_CHANGE"),
+    (1306, "SOCKADDR"),
+    (1307, "CWD"),
+    (1309, "EXECVE"),
+    (1311, "IPC_SET_PERM"),
+    (1312, "MQ_OPEN"),
+    (1313, "MQ_SENDRECV"),
+    (1314, "MQ_NOTIFY"),
+    (1315, "MQ_GETSETATTR"),
+    (1316, "KERNEL_OTHER"),
+    (1317, "FD_PAIR"),
+    (1318, "OBJ_PID"),
+    (1319, "TTY"),
+    (1320, "EOE"),
+    (1321, "BPRM_FCAPS"),
+    (1322, "CAPSET"),
+    (1323, "MMAP"),
+    (1324, "NETFILTER_PKT"),
+    (1325, "NETFILTER_CFG"),
+    (1326, "SECCOMP"),
+    (1327, "PROCTITLE"),
+    (1328, "FEATURE_CHANGE"),
+    (1329, "REPLACE"),
+    (1330, "KERN_MODULE"),
+    (1331, "FANOTIFY"),
+    (1332, "TIME_INJOFFSET"),
+    (1333, "TIME_ADJNTPVAL"),
+    (1334, "BPF"),
+    (1335, "EVENT_LISTENER"),
+    (1400, "AVC"),
+    (1401, "SELINUX_ERR"),
+    (1402, "AVC_PATH"),
+    (1403, "MAC_POLICY_LOAD"),
+    (1404, "MAC_STATUS"),
+    (1405, "MAC_CONFIG_CHANGE"),
+    (1700, "ANOM_PROMISCUOUS"),
+    (1701, "ANOM_ABEND"),
+    (1702, "ANOM_LINK"),
+    (1703, "ANOM_CREAT"),
+    (1800, "INTEGRITY_DATA"),
+    (1801, "INTEGRITY_METADATA"),
+    (1802, "INTEGRITY_STATUS"),
+    (1803, "INTEGRITY_HASH"),
+    (1804, "INTEGRITY_PCR"),
+    (1805, "INTEGRITY_RULE"),
+    (1806, "INTEGRITY_EVM_XATTR"),
+    (1807, "INTEGRITY_POLICY_RULE"),
+    (2100, "ANOM_LOGIN_FAILURES"),
+    (2101, "ANOM_LOGIN_TIME"),
+    (2102, "ANOM_LOGIN_SESSIONS"),
+    (2103, "ANOM_LOGIN_ACCT"),
+    (2104, "ANOM_LOGIN_LOCATION"),
+    (2105, "ANOM_MAX_DAC"),
+    (2106, "ANOM_MAX_MAC"),
+    (2107, "ANOM_AMTU_FAIL"),
+    (2108, "ANOM_RBAC_FAIL"),
+    (2109, "ANOM_RBAC_INTEGRITY_FAIL"),
+    (2110, "ANOM_CRYPTO_FAIL"),
+    (2111, "ANOM_ACCESS_FS"),
+    (2112, "ANOM_EXEC"),
+    (2113, "ANOM_MK_EXEC"),
+    (2114, "ANOM_ADD_ACCT"),
+    (2115, "ANOM_DEL_ACCT"),
+    (2116, "ANOM_MOD_ACCT"),
+    (2117, "ANOM_ROOT_TRANS"),
+    (2118, "ANOM_LOGIN_SERVICE"),
+    (2200, "RESP_ANOMALY"),
+    (2300, "USER_ROLE_CHANGE"),
+    (2301, "ROLE_ASSIGN"),
+    (2302, "ROLE_REMOVE"),
+    (2309, "USER_LABELED_EXPORT"),
+    (2310, "USER_UNLABELED_EXPORT"),
+    (2311, "LABEL_OVERRIDE"),
+    (2312, "LABEL_LEVEL_CHANGE"),
+    (2400, "CRYPTO_TEST_USER"),
+    (2401, "CRYPTO_PARAM_CHANGE_USER"),
+    (2402, "CRYPTO_LOGIN"),
+    (2403, "CRYPTO_LOGOUT"),
+    (2404, "CRYPTO_KEY_USER"),
+    (2405, "CRYPTO_FAILURE_USER"),
+    (2406, "CRYPTO_REPLAY_USER"),
+    (2407, "CRYPTO_SESSION"),
+    (2408, "CRYPTO_IKE_SA"),
+    (2409, "CRYPTO_IPSEC_SA"),
+    (2500, "VIRT_CONTROL"),
+    (2501, "VIRT_RESOURCE"),
+    (2502, "VIRT_MACHINE_ID"),
+    (2503, "VIRT_INTEGRITY_CHECK"),
+    (2504, "VIRT_CREATE"),
+    (2505, "VIRT_DESTROY"),
+    (2506, "VIRT_MIGRATE_IN"),
+    (2507, "VIRT_MIGRATE_OUT"),
+];
+
+/// The fields whose unquoted values are hex encoded, as their values contain
+/// spaces, quotes or control characters.
+const ENCODED_FIELDS: &[&str] = &[
+    "acct",
+    "cmd",
+    "comm",
+    "cwd",
+    "data",
+    "dir",
+    "exe",
+    "file",
+    "key",
+    "name",
+    "new",
+    "old",
+    "path",
+    "proctitle",
+    "vm",
+];
+
+const END_OF_EVENT: u16 = 1320;
+const EXECVE: u16 = 1309;
+const PROCTITLE: u16 = 1327;
+
+/// The separator of the keys of a rule, in the `key` field.
+const KEY_SEPARATOR: char = '\x01';
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    /// The numeric type, 0 for the names without one known.
+    pub record_type: u16,
+    pub type_name: String,
+    pub timestamp: DateTime<Utc>,
+    pub serial: u64,
+    pub node: Option<String>,
+    pub fields: BTreeMap<String, FieldValue>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Value(String),
+    /// The `msg='...'` of user space records, with fields of its own.
+    Fields(BTreeMap<String, FieldValue>),
+}
+
+impl Record {
+    /// The last record of the events made of several records.
+    pub fn is_end_of_event(&self) -> bool {
+        self.record_type == END_OF_EVENT
+    }
+
+    /// The records of user space, which make up an event on their own.
+    pub fn is_standalone(&self) -> bool {
+        matches!(self.record_type, 1100..=1299 | 2100..=2999)
+    }
+
+    /// The keys of the rules which matched, from the `key` field.
+    pub fn keys(&self) -> Vec<String> {
+        match self.fields.get("key") {
+            Some(FieldValue::Value(key)) if key != "(null)" => {
+                key.split(KEY_SEPARATOR).map(Into::into).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+pub fn type_name(record_type: u16) -> String {
+    RECORD_TYPES
+        .iter()
+        .find(|(number, _)| *number == record_type)
+        .map(|(_, name)| (*name).to_owned())
+        .unwrap_or_else(|| format!("UNKNOWN[{}]", record_type))
+}
+
+fn type_number(name: &str) -> u16 {
+    RECORD_TYPES
+        .iter()
+        .find(|(_, known)| *known == name)
+        .map(|(number, _)| *number)
+        .or_else(|| {
+            name.strip_prefix("UNKNOWN[")
+                .and_then(|rest| rest.strip_suffix(']'))
+                .and_then(|number| number.parse().ok())
+        })
+        .unwrap_or(0)
+}
+
+/// Parses the text of a netlink message, `audit(<time>:<serial>): <fields>`.
+pub fn parse_message(record_type: u16, text: &str) -> Result<Record, ParseError> {
+    let (timestamp, serial, payload) = parse_header(text)?;
+    Ok(Record {
+        record_type,
+        type_name: type_name(record_type),
+        timestamp,
+        serial,
+        node: None,
+        fields: parse_fields(record_type, payload),
+    })
+}
+
+/// Parses a line of the audisp plugin interface, or of `audit.log`,
+/// `[node=<node> ]type=<type> msg=audit(<time>:<serial>): <fields>`.
+pub fn parse_line(line: &str) -> Result<Record, ParseError> {
+    let mut rest = line.trim_end();
+    let mut node = None;
+    if let Some(after) = rest.strip_prefix("node=") {
+        let end = after.find(' ').ok_or(ParseError::MissingType)?;
+        node = Some(after[..end].to_owned());
+        rest = &after[end + 1..];
+    }
+
+    let after = rest.strip_prefix("type=").ok_or(ParseError::MissingType)?;
+    let end = after.find(' ').ok_or(ParseError::MissingHeader)?;
+    let type_name = &after[..end];
+    let text = after[end + 1..]
+        .strip_prefix("msg=")
+        .ok_or(ParseError::MissingHeader)?;
+
+    let record_type = type_number(type_name);
+    let (timestamp, serial, payload) = parse_header(text)?;
+    Ok(Record {
+        record_type,
+        type_name: type_name.to_owned(),
+        timestamp,
+        serial,
+        node,
+        fields: parse_fields(record_type, payload),
+    })
+}
+
+fn parse_header(text: &str) -> Result<(DateTime<Utc>, u64, &str), ParseError> {
+    let text = text.trim_end_matches(|c: char| c == '\0' || c.is_whitespace());
+    let rest = text
+        .strip_prefix("audit(")
+        .ok_or(ParseError::MissingHeader)?;
+    let end = rest.find("):").ok_or(ParseError::MissingHeader)?;
+    let header = &rest[..end];
+    let invalid = || ParseError::InvalidHeader {
+        header: header.to_owned(),
+    };
+
+    let (time, serial) = split_once(header, ':').ok_or_else(invalid)?;
+    let (seconds, millis) = split_once(time, '.').ok_or_else(invalid)?;
+    let seconds = seconds.parse::<i64>().map_err(|_| invalid())?;
+    let millis = millis.parse::<u32>().map_err(|_| invalid())?;
+    let serial = serial.parse::<u64>().map_err(|_| invalid())?;
+
+    Ok((
+        Utc.timestamp(seconds, millis * 1_000_000),
+        serial,
+        rest[end + 2..].trim_start(),
+    ))
+}
+
+fn split_once(text: &str, separator: char) -> Option<(&str, &str)> {
+    let position = text.find(separator)?;
+    Some((&text[..position], &text[position + separator.len_utf8()..]))
+}
+
+/// Parses the `key=value` pairs, the values being bare, hex encoded, or in
+/// double or single quotes. Words without `=`, such as those of AVC records,
+/// are skipped.
+fn parse_fields(record_type: u16, payload: &str) -> BTreeMap<String, FieldValue> {
+    let mut fields = BTreeMap::new();
+    let mut rest = payload.trim_start();
+
+    while !rest.is_empty() {
+        let word_end = rest.find(' ').unwrap_or_else(|| rest.len());
+        let equals = match rest[..word_end].find('=') {
+            Some(equals) => equals,
+            None => {
+                rest = rest[word_end..].trim_start();
+                continue;
+            }
+        };
+        let key = &rest[..equals];
+        rest = &rest[equals + 1..];
+
+        let value = match rest.chars().next() {
+            Some(quote @ '"') | Some(quote @ '\'') => {
+                let end = rest[1..].find(quote).map_or(rest.len(), |end| end + 1);
+                let value = &rest[1..end];
+                rest = rest.get(end + 1..).unwrap_or("");
+                if quote == '\'' && value.contains('=') {
+                    FieldValue::Fields(parse_fields(record_type, value))
+                } else {
+                    FieldValue::Value(value.to_owned())
+                }
+            }
+            _ => {
+                let end = rest.find(' ').unwrap_or_else(|| rest.len());
+                let value = &rest[..end];
+                rest = &rest[end..];
+                FieldValue::Value(decode_value(record_type, key, value))
+            }
+        };
+
+        fields.insert(key.to_owned(), value);
+        rest = rest.trim_start();
+    }
+
+    fields
+}
+
+fn decode_value(record_type: u16, key: &str, value: &str) -> String {
+    let encoded = ENCODED_FIELDS.contains(&key)
+        || (record_type == EXECVE
+            && key.starts_with('a')
+            && key.len() > 1
+            && key[1..].bytes().all(|b| b.is_ascii_digit()));
+    if !encoded {
+        return value.to_owned();
+    }
+
+    match hex_decode(value) {
+        Some(mut bytes) => {
+            // The arguments of the command line are separated by NULs.
+            if record_type == PROCTITLE {
+                for byte in bytes.iter_mut().filter(|byte| **byte == 0) {
+                    *byte = b' ';
+                }
+            }
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+        None => value.to_owned(),
+    }
+}
+
+fn hex_decode(value: &str) -> Option<Vec<u8>> {
+    if value.is_empty() || value.len() % 2 != 0 {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(record: &Record, key: &str) -> String {
+        match &record.fields[key] {
+            FieldValue::Value(value) => value.clone(),
+            FieldValue::Fields(fields) => panic!("Unexpected fields {:?}", fields),
+        }
+    }
+
+    #[test]
+    fn parses_syscall_message() {
+        let record = parse_message(
+            1300,
+            r#"audit(1364481363.243:24287): arch=c000003e syscall=2 success=no exit=-13 a0=7fffd19c5592 items=1 ppid=2686 pid=3538 comm="cat" exe="/usr/bin/cat" key=7365637265747301706173737764"#,
+        )
+        .unwrap();
+
+        assert_eq!(record.type_name, "SYSCALL");
+        assert_eq!(record.serial, 24287);
+        assert_eq!(record.timestamp, Utc.timestamp(1364481363, 243_000_000));
+        assert_eq!(value(&record, "a0"), "7fffd19c5592");
+        assert_eq!(value(&record, "comm"), "cat");
+        assert_eq!(value(&record, "exit"), "-13");
+        assert_eq!(record.keys(), vec!["secrets", "passwd"]);
+        assert!(!record.is_standalone());
+    }
+
+    #[test]
+    fn parses_user_line() {
+        let record = parse_line(
+            "node=web1 type=USER_LOGIN msg=audit(1610000000.001:42): pid=1 uid=0 msg='op=login acct=\"root\" exe=\"/usr/sbin/sshd\" res=success'\n",
+        )
+        .unwrap();
+
+        assert_eq!(record.record_type, 1112);
+        assert_eq!(record.node.as_deref(), Some("web1"));
+        assert!(record.is_standalone());
+        match &record.fields["msg"] {
+            FieldValue::Fields(fields) => {
+                assert_eq!(fields["acct"], FieldValue::Value("root".into()));
+                assert_eq!(fields["res"], FieldValue::Value("success".into()));
+            }
+            value => panic!("Unexpected value {:?}", value),
+        }
+    }
+
+    #[test]
+    fn decodes_hex_values() {
+        let record = parse_line(
+            "type=PROCTITLE msg=audit(1610000000.001:43): proctitle=636174002F6574632F736861646F77",
+        )
+        .unwrap();
+        assert_eq!(value(&record, "proctitle"), "cat /etc/shadow");
+
+        let record =
+            parse_line("type=EXECVE msg=audit(1610000000.001:43): argc=2 a0=\"ls\" a1=2D6C2061")
+                .unwrap();
+        assert_eq!(value(&record, "a0"), "ls");
+        assert_eq!(value(&record, "a1"), "-l a");
+    }
+
+    #[test]
+    fn parses_unknown_types() {
+        let record = parse_line("type=UNKNOWN[1999] msg=audit(1610000000.001:44): a=b").unwrap();
+        assert_eq!(record.record_type, 1999);
+        assert_eq!(type_name(1999), "UNKNOWN[1999]");
+    }
+
+    #[test]
+    fn skips_words() {
+        let record = parse_message(
+            1400,
+            "audit(1610000000.001:45): avc:  denied  { read } for  pid=100 comm=\"cat\"",
+        )
+        .unwrap();
+        assert_eq!(record.fields.len(), 2);
+        assert_eq!(value(&record, "pid"), "100");
+    }
+
+    #[test]
+    fn rejects_invalid_headers() {
+        assert_eq!(
+            parse_message(1300, "arch=c000003e"),
+            Err(ParseError::MissingHeader)
+        );
+        assert_eq!(
+            parse_message(1300, "audit(1610000000:45): a=b"),
+            Err(ParseError::InvalidHeader {
+                header: "1610000000:45".into()
+            })
+        );
+        assert_eq!(
+            parse_line("msg=audit(1.0:1): a=b"),
+            Err(ParseError::MissingType)
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Groups the records of an event, which share its serial number.
+
+use super::parser::Record;
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+struct Pending {
+    records: Vec<Record>,
+    received: Instant,
+}
+
+pub struct Reassembler {
+    timeout: Duration,
+    max_in_flight: usize,
+    pending: BTreeMap<u64, Pending>,
+}
+
+impl Reassembler {
+    pub fn new(timeout: Duration, max_in_flight: usize) -> Self {
+        Self {
+            timeout,
+            max_in_flight: max_in_flight.max(1),
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a record, and returns the events it completes. The oldest events
+    /// are completed when more than `max_in_flight` of them are pending.
+    pub fn push(&mut self, record: Record, now: Instant) -> Vec<Vec<Record>> {
+        let mut complete = Vec::new();
+
+        if record.is_end_of_event() {
+            if let Some(pending) = self.pending.remove(&record.serial) {
+                complete.push(pending.records);
+            }
+        } else if record.is_standalone() && !self.pending.contains_key(&record.serial) {
+            complete.push(vec![record]);
+        } else {
+            self.pending
+                .entry(record.serial)
+                .or_insert_with(|| Pending {
+                    records: Vec::new(),
+                    received: now,
+                })
+                .records
+                .push(record);
+        }
+
+        while self.pending.len() > self.max_in_flight {
+            let serial = *self.pending.keys().next().expect("pending events");
+            complete.extend(self.pending.remove(&serial).map(|pending| pending.records));
+        }
+
+        complete
+    }
+
+    /// Completes the events whose first record was received more than
+    /// `timeout` ago, as some never get an end of event record.
+    pub fn flush_expired(&mut self, now: Instant) -> Vec<Vec<Record>> {
+        let timeout = self.timeout;
+        let expired = self
+            .pending
+            .iter()
+            .filter(|(_, pending)| now.duration_since(pending.received) >= timeout)
+            .map(|(serial, _)| *serial)
+            .collect::<Vec<_>>();
+
+        expired
+            .into_iter()
+            .filter_map(|serial| self.pending.remove(&serial))
+            .map(|pending| pending.records)
+            .collect()
+    }
+
+    /// Completes all of the pending events.
+    pub fn flush(&mut self) -> Vec<Vec<Record>> {
+        std::mem::take(&mut self.pending)
+            .into_iter()
+            .map(|(_, pending)| pending.records)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::auditd::parser::parse_line;
+
+    fn record(record_type: &str, serial: u64) -> Record {
+        parse_line(&format!(
+            "type={} msg=audit(1610000000.000:{}): pid=1",
+            record_type, serial
+        ))
+        .unwrap()
+    }
+
+    fn types(event: &[Record]) -> Vec<&str> {
+        event
+            .iter()
+            .map(|record| record.type_name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn completes_events_on_end_of_event() {
+        let mut reassembler = Reassembler::new(Duration::from_secs(2), 16);
+        let now = Instant::now();
+
+        assert!(reassembler.push(record("SYSCALL", 1), now).is_empty());
+        assert!(reassembler.push(record("SYSCALL", 2), now).is_empty());
+        assert!(reassembler.push(record("PATH", 1), now).is_empty());
+        assert!(reassembler.push(record("PROCTITLE", 1), now).is_empty());
+
+        let complete = reassembler.push(record("EOE", 1), now);
+        assert_eq!(complete.len(), 1);
+        assert_eq!(types(&complete[0]), vec!["SYSCALL", "PATH", "PROCTITLE"]);
+
+        let complete = reassembler.flush();
+        assert_eq!(complete.len(), 1);
+        assert_eq!(types(&complete[0]), vec!["SYSCALL"]);
+    }
+
+    #[test]
+    fn completes_standalone_records() {
+        let mut reassembler = Reassembler::new(Duration::from_secs(2), 16);
+
+        let complete = reassembler.push(record("USER_LOGIN", 1), Instant::now());
+        assert_eq!(complete.len(), 1);
+        assert_eq!(types(&complete[0]), vec!["USER_LOGIN"]);
+    }
+
+    #[test]
+    fn completes_expired_events() {
+        let mut reassembler = Reassembler::new(Duration::from_secs(2), 16);
+        let now = Instant::now();
+
+        reassembler.push(record("CONFIG_CHANGE", 1), now);
+        reassembler.push(record("SYSCALL", 2), now + Duration::from_secs(1));
+
+        assert!(reassembler
+            .flush_expired(now + Duration::from_secs(1))
+            .is_empty());
+        let complete = reassembler.flush_expired(now + Duration::from_secs(2));
+        assert_eq!(complete.len(), 1);
+        assert_eq!(types(&complete[0]), vec!["CONFIG_CHANGE"]);
+    }
+
+    #[test]
+    fn limits_events_in_flight() {
+        let mut reassembler = Reassembler::new(Duration::from_secs(2), 2);
+        let now = Instant::now();
+
+        reassembler.push(record("SYSCALL", 3), now);
+        reassembler.push(record("SYSCALL", 1), now);
+        let complete = reassembler.push(record("SYSCALL", 2), now);
+        assert_eq!(complete.len(), 1);
+        assert_eq!(complete[0][0].serial, 1);
+    }
+}
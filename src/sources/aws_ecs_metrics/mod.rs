@@ -30,7 +30,10 @@ struct AwsEcsMetricsSourceConfig {
     endpoint: String,
     #[serde(default = "default_version")]
     version: Version,
-    #[serde(default = "default_scrape_interval_secs")]
+    #[serde(
+        default = "default_scrape_interval_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
     scrape_interval_secs: u64,
     #[serde(default = "default_namespace")]
     namespace: String,
@@ -19,6 +19,15 @@ pub enum RequestError {
         source: serde_json::error::Error,
         request_id: String,
     },
+    #[snafu(display(
+        "Request ID of the body ({}) does not match X-Amz-Firehose-Request-Id for request: {}",
+        body_request_id,
+        request_id
+    ))]
+    RequestIdMismatch {
+        request_id: String,
+        body_request_id: String,
+    },
     #[snafu(display(
         "Could not parse records from incoming request {}: {}",
         request_id,
@@ -59,7 +68,8 @@ impl RequestError {
         match *self {
             AccessKeyMissing { .. } => StatusCode::UNAUTHORIZED,
             AccessKeyInvalid { .. } => StatusCode::UNAUTHORIZED,
-            Parse { .. } => StatusCode::UNAUTHORIZED,
+            Parse { .. } => StatusCode::BAD_REQUEST,
+            RequestIdMismatch { .. } => StatusCode::BAD_REQUEST,
             UnsupportedEncoding { .. } => StatusCode::BAD_REQUEST,
             ParseRecords { .. } => StatusCode::BAD_REQUEST,
             Decode { .. } => StatusCode::BAD_REQUEST,
@@ -74,6 +84,7 @@ impl RequestError {
             AccessKeyMissing { ref request_id, .. } => Some(request_id),
             AccessKeyInvalid { ref request_id, .. } => Some(request_id),
             Parse { ref request_id, .. } => Some(request_id),
+            RequestIdMismatch { ref request_id, .. } => Some(request_id),
             UnsupportedEncoding { ref request_id, .. } => Some(request_id),
             ParseRecords { ref request_id, .. } => Some(request_id),
             Decode { ref request_id, .. } => Some(request_id),
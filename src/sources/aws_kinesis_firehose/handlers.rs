@@ -16,6 +16,13 @@ pub async fn firehose(
     request: FirehoseRequest,
     mut out: Pipeline,
 ) -> Result<impl warp::Reply, reject::Rejection> {
+    if request.request_id != request_id {
+        return Err(reject::custom(RequestError::RequestIdMismatch {
+            request_id,
+            body_request_id: request.request_id,
+        }));
+    }
+
     let events = parse_records(request, request_id.as_str(), source_arn.as_str())
         .with_context(|| ParseRecords {
             request_id: request_id.clone(),
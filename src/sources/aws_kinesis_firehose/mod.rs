@@ -331,4 +331,71 @@ mod tests {
         let response: models::FirehoseResponse = res.json().await.unwrap();
         assert_eq!(response.request_id, request_id);
     }
+
+    #[tokio::test]
+    async fn aws_kinesis_firehose_rejects_missing_access_key() {
+        let (_rx, addr) = source(Some("an access key".to_string())).await;
+
+        let request_id = "e17265d6-97af-4938-982e-90d5614c4242";
+
+        let res = send(addr, Utc::now(), vec![], None, request_id, "", false)
+            .await
+            .unwrap();
+        assert_eq!(401, res.status().as_u16());
+    }
+
+    /// Sends `body` as is, with the Firehose headers of `request_id`
+    async fn send_body(
+        address: SocketAddr,
+        request_id: &str,
+        body: Vec<u8>,
+    ) -> reqwest::Result<reqwest::Response> {
+        reqwest::Client::new()
+            .post(&format!("http://{}", address))
+            .header("x-amz-firehose-protocol-version", "1.0")
+            .header("x-amz-firehose-request-id", request_id.to_string())
+            .header("x-amz-firehose-source-arn", "")
+            .header("content-type", "application/json")
+            .body(body)
+            .send()
+            .await
+    }
+
+    #[tokio::test]
+    async fn aws_kinesis_firehose_rejects_invalid_bodies() {
+        let (_rx, addr) = source(None).await;
+
+        let request_id = "e17265d6-97af-4938-982e-90d5614c4242";
+
+        let res = send_body(addr, request_id, b"{\"records\": ".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(400, res.status().as_u16());
+
+        let response: models::FirehoseResponse = res.json().await.unwrap();
+        assert_eq!(response.request_id, request_id);
+    }
+
+    #[tokio::test]
+    async fn aws_kinesis_firehose_rejects_mismatched_request_ids() {
+        let (rx, addr) = source(None).await;
+
+        let request_id = "e17265d6-97af-4938-982e-90d5614c4242";
+        let request = models::FirehoseRequest {
+            request_id: "another request".to_string(),
+            timestamp: Utc::now(),
+            records: vec![models::EncodedFirehoseRecord {
+                data: encode_record("hello").unwrap(),
+            }],
+        };
+
+        let res = send_body(addr, request_id, serde_json::to_vec(&request).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(400, res.status().as_u16());
+
+        let response: models::FirehoseResponse = res.json().await.unwrap();
+        assert_eq!(response.request_id, request_id);
+        assert!(collect_ready(rx).await.is_empty());
+    }
 }
@@ -37,10 +37,16 @@ lazy_static! {
 pub(super) struct Config {
     pub(super) queue_url: String,
 
-    #[serde(default = "default_poll_interval_secs")]
+    #[serde(
+        default = "default_poll_interval_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
     #[derivative(Default(value = "default_poll_interval_secs()"))]
     pub(super) poll_secs: u64,
-    #[serde(default = "default_visibility_timeout_secs")]
+    #[serde(
+        default = "default_visibility_timeout_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
     #[derivative(Default(value = "default_visibility_timeout_secs()"))]
     // restricted to u32 for safe conversion to i64 later
     pub(super) visibility_timeout_secs: u32,
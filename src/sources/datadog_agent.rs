@@ -0,0 +1,706 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, GlobalOptions, Resource, SourceConfig,
+        SourceDescription,
+    },
+    event::{
+        metric::{Metric, MetricKind, MetricTags, MetricValue, Sample, StatisticKind},
+        Event,
+    },
+    internal_events::{HTTPBadRequest, HTTPEventsReceived},
+    origin::OriginFilter,
+    shutdown::ShutdownSignal,
+    sources::util::{access_log, ErrorMessage},
+    tls::{MaybeTlsSettings, TlsConfig},
+    Pipeline,
+};
+use bytes::{buf::BufExt, Bytes};
+use chrono::{TimeZone, Utc};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use futures::{FutureExt, SinkExt, StreamExt};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{collections::HashMap, io::Read, net::SocketAddr, sync::Arc};
+use warp::{
+    filters::BoxedFilter, http::StatusCode, path, reject::Rejection, reply::Response, Filter, Reply,
+};
+
+mod proto {
+    include!(concat!(env!("OUT_DIR"), "/datadog.agentpayload.rs"));
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DatadogAgentConfig {
+    address: SocketAddr,
+    tls: Option<TlsConfig>,
+    /// Whether the API key sent by the agents is kept in the metadata of the
+    /// log events.
+    #[serde(default)]
+    store_api_key: bool,
+    #[serde(default)]
+    access_log: bool,
+    permit_origin: Option<Vec<String>>,
+    deny_origin: Option<Vec<String>>,
+}
+
+inventory::submit! {
+    SourceDescription::new::<DatadogAgentConfig>("datadog_agent")
+}
+
+impl GenerateConfig for DatadogAgentConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            address: "0.0.0.0:8080".parse().unwrap(),
+            tls: None,
+            store_api_key: false,
+            access_log: false,
+            permit_origin: None,
+            deny_origin: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "datadog_agent")]
+impl SourceConfig for DatadogAgentConfig {
+    async fn build(
+        &self,
+        _: &str,
+        _: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let source = DatadogAgentSource {
+            store_api_key: self.store_api_key,
+        };
+
+        let services = source
+            .logs_service(out.clone())
+            .or(source.series_service(out.clone()))
+            .unify()
+            .or(source.sketches_service(out))
+            .unify()
+            .or(validate_service())
+            .unify()
+            .recover(|rejection: Rejection| async move {
+                match rejection.find::<ErrorMessage>() {
+                    Some(error) => Ok(warp::reply::with_status(
+                        warp::reply::json(error),
+                        StatusCode::from_u16(error.code)
+                            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                    )),
+                    None => Err(rejection),
+                }
+            })
+            .with(access_log(self.access_log));
+
+        let tls = MaybeTlsSettings::from_config(&self.tls, true)?;
+        let origin = OriginFilter::new(&self.permit_origin, &self.deny_origin)?;
+        let listener = tls.bind(&self.address).await?.with_origin(origin);
+
+        Ok(Box::pin(async move {
+            let _ = warp::serve(services)
+                .serve_incoming_with_graceful_shutdown(
+                    listener.accept_stream(),
+                    shutdown.clone().map(|_| ()),
+                )
+                .await;
+            // We need to drop the last copy of ShutdownSignalToken only after server has shut down.
+            drop(shutdown);
+            Ok(())
+        }))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn source_type(&self) -> &'static str {
+        "datadog_agent"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::tcp(self.address)]
+    }
+}
+
+#[derive(Clone, Copy)]
+struct DatadogAgentSource {
+    store_api_key: bool,
+}
+
+impl DatadogAgentSource {
+    /// The logs intake, the API key being either in a header, in the query or
+    /// at the end of the path.
+    fn logs_service(self, out: Pipeline) -> BoxedFilter<(Response,)> {
+        warp::post()
+            .and(
+                path!("v1" / "input" / String)
+                    .map(Some)
+                    .or(path!("v1" / "input").map(|| None))
+                    .unify()
+                    .or(path!("api" / "v2" / "logs").map(|| None))
+                    .unify(),
+            )
+            .and(api_key())
+            .and(warp::header::optional::<String>("content-encoding"))
+            .and(warp::body::bytes())
+            .and_then(
+                move |path_key: Option<String>,
+                      api_key: Option<String>,
+                      encoding: Option<String>,
+                      body: Bytes| {
+                    let api_key = path_key
+                        .or(api_key)
+                        .filter(|_| self.store_api_key)
+                        .map(Arc::from);
+                    let events = decode(&encoding, body).and_then(|body| {
+                        let byte_size = body.len();
+                        decode_logs(body, api_key).map(|events| (events, byte_size))
+                    });
+                    forward(out.clone(), events, StatusCode::OK)
+                },
+            )
+            .boxed()
+    }
+
+    fn series_service(self, out: Pipeline) -> BoxedFilter<(Response,)> {
+        warp::post()
+            .and(path!("api" / "v1" / "series"))
+            .and(warp::header::optional::<String>("content-encoding"))
+            .and(warp::body::bytes())
+            .and_then(move |encoding: Option<String>, body: Bytes| {
+                let events = decode(&encoding, body).and_then(|body| {
+                    let byte_size = body.len();
+                    decode_series(body).map(|events| (events, byte_size))
+                });
+                forward(out.clone(), events, StatusCode::ACCEPTED)
+            })
+            .boxed()
+    }
+
+    fn sketches_service(self, out: Pipeline) -> BoxedFilter<(Response,)> {
+        warp::post()
+            .and(path!("api" / "beta" / "sketches"))
+            .and(warp::header::optional::<String>("content-encoding"))
+            .and(warp::body::bytes())
+            .and_then(move |encoding: Option<String>, body: Bytes| {
+                let events = decode(&encoding, body).and_then(|body| {
+                    let byte_size = body.len();
+                    decode_sketches(body).map(|events| (events, byte_size))
+                });
+                forward(out.clone(), events, StatusCode::ACCEPTED)
+            })
+            .boxed()
+    }
+}
+
+/// The agents validate their API key on startup, any key is accepted.
+fn validate_service() -> BoxedFilter<(Response,)> {
+    warp::get()
+        .and(path!("api" / "v1" / "validate"))
+        .map(|| warp::reply::json(&json!({ "valid": true })).into_response())
+        .boxed()
+}
+
+fn api_key() -> BoxedFilter<(Option<String>,)> {
+    warp::header::optional::<String>("dd-api-key")
+        .and(warp::query::<HashMap<String, String>>())
+        .map(
+            |header: Option<String>, mut query: HashMap<String, String>| {
+                header.or_else(|| query.remove("api_key"))
+            },
+        )
+        .boxed()
+}
+
+async fn forward(
+    mut out: Pipeline,
+    events: Result<(Vec<Event>, usize), ErrorMessage>,
+    status: StatusCode,
+) -> Result<Response, Rejection> {
+    match events {
+        Ok((events, byte_size)) => {
+            emit!(HTTPEventsReceived {
+                events_count: events.len(),
+                byte_size,
+            });
+            out.send_all(&mut futures::stream::iter(events).map(Ok))
+                .await
+                .map_err(|_| {
+                    warp::reject::custom(ErrorMessage::new(
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        "Shutting down.".to_owned(),
+                    ))
+                })?;
+            Ok(warp::reply::with_status(warp::reply::json(&json!({})), status).into_response())
+        }
+        Err(error) => {
+            emit!(HTTPBadRequest {
+                error_code: error.code,
+                error_message: error.message.as_str(),
+            });
+            Err(warp::reject::custom(error))
+        }
+    }
+}
+
+/// Decompresses the body, the agents using zlib for `deflate`.
+fn decode(encoding: &Option<String>, body: Bytes) -> Result<Bytes, ErrorMessage> {
+    let mut decoded = Vec::new();
+    let result = match encoding.as_deref() {
+        None | Some("identity") => return Ok(body),
+        Some("gzip") => GzDecoder::new(body.reader()).read_to_end(&mut decoded),
+        Some("deflate") => ZlibDecoder::new(body.reader()).read_to_end(&mut decoded),
+        Some(encoding) => {
+            return Err(ErrorMessage::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                format!("Unsupported encoding {}", encoding),
+            ))
+        }
+    };
+
+    result.map(|_| decoded.into()).map_err(|error| {
+        ErrorMessage::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Failed decompressing payload: {}", error),
+        )
+    })
+}
+
+/// A log message, as sent by the agents.
+#[derive(Deserialize, Debug)]
+struct LogMessage {
+    message: String,
+    status: Option<String>,
+    /// Milliseconds since the epoch.
+    timestamp: Option<i64>,
+    hostname: Option<String>,
+    service: Option<String>,
+    ddsource: Option<String>,
+    ddtags: Option<String>,
+}
+
+fn decode_logs(body: Bytes, api_key: Option<Arc<str>>) -> Result<Vec<Event>, ErrorMessage> {
+    let messages: Vec<LogMessage> = serde_json::from_slice(&body).map_err(|error| {
+        ErrorMessage::new(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid logs payload: {}", error),
+        )
+    })?;
+
+    Ok(messages
+        .into_iter()
+        .map(|message| {
+            let mut event = Event::from(message.message);
+            let log = event.as_mut_log();
+
+            if let Some(timestamp) = message
+                .timestamp
+                .and_then(|millis| Utc.timestamp_millis_opt(millis).latest())
+            {
+                log.insert(log_schema().timestamp_key(), timestamp);
+            }
+            if let Some(hostname) = message.hostname {
+                log.insert(log_schema().host_key(), hostname);
+            }
+            for (key, value) in vec![
+                ("status", message.status),
+                ("service", message.service),
+                ("ddsource", message.ddsource),
+                ("ddtags", message.ddtags),
+            ] {
+                if let Some(value) = value {
+                    log.insert(key, value);
+                }
+            }
+            log.metadata_mut().set_datadog_api_key(api_key.clone());
+            log.try_insert(log_schema().source_type_key(), Bytes::from("datadog_agent"));
+
+            event
+        })
+        .collect())
+}
+
+#[derive(Deserialize, Debug)]
+struct SeriesPayload {
+    series: Vec<Series>,
+}
+
+/// A metric series, as sent by the agents.
+#[derive(Deserialize, Debug)]
+struct Series {
+    metric: String,
+    /// Pairs of a timestamp in seconds and a value.
+    #[serde(default)]
+    points: Vec<(f64, f64)>,
+    #[serde(default)]
+    tags: Vec<String>,
+    host: Option<String>,
+    #[serde(rename = "type", default)]
+    kind: SeriesKind,
+    /// The seconds a rate was measured over.
+    interval: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum SeriesKind {
+    Gauge,
+    Count,
+    Rate,
+}
+
+impl Default for SeriesKind {
+    fn default() -> Self {
+        SeriesKind::Gauge
+    }
+}
+
+fn decode_series(body: Bytes) -> Result<Vec<Event>, ErrorMessage> {
+    let payload: SeriesPayload = serde_json::from_slice(&body).map_err(|error| {
+        ErrorMessage::new(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid series payload: {}", error),
+        )
+    })?;
+
+    Ok(payload
+        .series
+        .into_iter()
+        .flat_map(|series| {
+            let tags = decode_tags(&series.tags, series.host.as_deref());
+
+            let Series {
+                metric,
+                points,
+                kind,
+                interval,
+                ..
+            } = series;
+            points.into_iter().map(move |(timestamp, value)| {
+                let (kind, value) = match kind {
+                    SeriesKind::Gauge => (MetricKind::Absolute, MetricValue::Gauge { value }),
+                    SeriesKind::Count => (MetricKind::Incremental, MetricValue::Counter { value }),
+                    // A rate is the count over the interval, per second.
+                    SeriesKind::Rate => (
+                        MetricKind::Incremental,
+                        MetricValue::Counter {
+                            value: value * interval.unwrap_or(1) as f64,
+                        },
+                    ),
+                };
+                Event::Metric(
+                    Metric::new(metric.clone(), kind, value)
+                        .with_tags(Some(tags.clone()))
+                        .with_timestamp(Some(Utc.timestamp(timestamp as i64, 0))),
+                )
+            })
+        })
+        .collect())
+}
+
+/// Splits the `key:value` tags of the agents, adding the host as a tag.
+fn decode_tags(tags: &[String], host: Option<&str>) -> MetricTags {
+    let mut tags = tags
+        .iter()
+        .map(|tag| {
+            let mut parts = tag.splitn(2, ':');
+            let key = parts.next().unwrap_or_default().to_owned();
+            (key, parts.next().unwrap_or_default().to_owned())
+        })
+        .collect::<MetricTags>();
+    if let Some(host) = host.filter(|host| !host.is_empty()) {
+        tags.insert("host".to_owned(), host.to_owned());
+    }
+    tags
+}
+
+/// The relative accuracy of the sketches of the agents.
+const SKETCH_EPSILON: f64 = 1.0 / 128.0;
+/// The smallest value the sketches of the agents tell apart from zero.
+const SKETCH_MIN_VALUE: f64 = 1e-9;
+/// The key of the bin holding the values too large to be indexed.
+const SKETCH_MAX_KEY: i32 = i16::MAX as i32;
+
+/// The lower bound of the values counted in the bin `key` of a sketch, the
+/// agents indexing the values by their logarithm in base `gamma`, offset so
+/// that the key zero holds the values too small to be told apart from zero.
+fn sketch_bin_value(key: i32) -> f64 {
+    let gamma_ln = (2.0 * SKETCH_EPSILON).ln_1p();
+    let bias = 1 - (SKETCH_MIN_VALUE.ln() / gamma_ln).floor() as i32;
+    match key {
+        0 => 0.0,
+        key if key < 0 => -sketch_bin_value(-key),
+        key if key >= SKETCH_MAX_KEY => f64::INFINITY,
+        key => (f64::from(key - bias) * gamma_ln).exp(),
+    }
+}
+
+/// Decodes the sketches of the distributions of the agents, each bin of a
+/// sketch becoming a sample of its lower bound.
+fn decode_sketches(body: Bytes) -> Result<Vec<Event>, ErrorMessage> {
+    let payload = proto::SketchPayload::decode(body).map_err(|error| {
+        ErrorMessage::new(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid sketches payload: {}", error),
+        )
+    })?;
+
+    Ok(payload
+        .sketches
+        .into_iter()
+        .flat_map(|sketch| {
+            let tags = decode_tags(&sketch.tags, Some(&sketch.host));
+            let metric = sketch.metric;
+            sketch.dogsketches.into_iter().map(move |dogsketch| {
+                let samples = dogsketch
+                    .k
+                    .into_iter()
+                    .zip(dogsketch.n.into_iter())
+                    .map(|(key, rate)| Sample {
+                        value: sketch_bin_value(key),
+                        rate,
+                    })
+                    .collect();
+                Event::Metric(
+                    Metric::new(
+                        metric.clone(),
+                        MetricKind::Incremental,
+                        MetricValue::Distribution {
+                            samples,
+                            statistic: StatisticKind::Summary,
+                        },
+                    )
+                    .with_tags(Some(tags.clone()))
+                    .with_timestamp(Some(Utc.timestamp(dogsketch.ts, 0))),
+                )
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{collect_n, next_addr, trace_init, wait_for_tcp};
+    use flate2::{write::ZlibEncoder, Compression};
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<DatadogAgentConfig>();
+    }
+
+    async fn source(store_api_key: bool) -> (mpsc::Receiver<Event>, SocketAddr) {
+        let (sender, recv) = Pipeline::new_test();
+        let address = next_addr();
+        tokio::spawn(async move {
+            DatadogAgentConfig {
+                address,
+                tls: None,
+                store_api_key,
+                access_log: false,
+                permit_origin: None,
+                deny_origin: None,
+            }
+            .build(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                sender,
+            )
+            .await
+            .unwrap()
+            .await
+            .unwrap()
+        });
+        wait_for_tcp(address).await;
+        (recv, address)
+    }
+
+    async fn send(request: reqwest::RequestBuilder, body: Vec<u8>) -> u16 {
+        request.body(body).send().await.unwrap().status().as_u16()
+    }
+
+    #[tokio::test]
+    async fn receives_logs() {
+        trace_init();
+        let (rx, address) = source(true).await;
+
+        let body = json!([{
+            "message": "foo",
+            "status": "info",
+            "timestamp": 1_617_216_000_123_i64,
+            "hostname": "web-1",
+            "service": "checkout",
+            "ddsource": "nginx",
+            "ddtags": "env:prod"
+        }]);
+        let request = reqwest::Client::new()
+            .post(&format!("http://{}/v1/input/12345678abcdefgh", address))
+            .header("Content-Type", "application/json");
+        assert_eq!(200, send(request, body.to_string().into_bytes()).await);
+
+        let events = collect_n(rx, 1).await;
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "foo".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp_millis(1_617_216_000_123).into()
+        );
+        assert_eq!(log[log_schema().host_key()], "web-1".into());
+        assert_eq!(log["status"], "info".into());
+        assert_eq!(log["service"], "checkout".into());
+        assert_eq!(log["ddsource"], "nginx".into());
+        assert_eq!(log["ddtags"], "env:prod".into());
+        assert_eq!(log[log_schema().source_type_key()], "datadog_agent".into());
+        assert_eq!(
+            log.metadata().datadog_api_key().map(|key| &**key),
+            Some("12345678abcdefgh")
+        );
+        assert!(log.get("datadog_api_key").is_none());
+    }
+
+    #[tokio::test]
+    async fn drops_api_key_unless_stored() {
+        trace_init();
+        let (rx, address) = source(false).await;
+
+        let body = json!([{ "message": "foo" }]);
+        let request = reqwest::Client::new()
+            .post(&format!("http://{}/api/v2/logs", address))
+            .header("DD-API-KEY", "12345678abcdefgh");
+        assert_eq!(200, send(request, body.to_string().into_bytes()).await);
+
+        let events = collect_n(rx, 1).await;
+        assert!(events[0].as_log().metadata().datadog_api_key().is_none());
+    }
+
+    #[tokio::test]
+    async fn receives_compressed_series() {
+        trace_init();
+        let (rx, address) = source(false).await;
+
+        let body = json!({
+            "series": [
+                {
+                    "metric": "system.load.1",
+                    "points": [[1_617_216_000, 0.5], [1_617_216_010, 0.75]],
+                    "tags": ["env:prod", "canary"],
+                    "host": "web-1",
+                    "type": "gauge"
+                },
+                {
+                    "metric": "requests",
+                    "points": [[1_617_216_000, 2.5]],
+                    "type": "rate",
+                    "interval": 10
+                }
+            ]
+        });
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(body.to_string().as_bytes()).unwrap();
+        let request = reqwest::Client::new()
+            .post(&format!("http://{}/api/v1/series", address))
+            .header("Content-Encoding", "deflate");
+        assert_eq!(202, send(request, encoder.finish().unwrap()).await);
+
+        let tags = vec![
+            ("env".to_owned(), "prod".to_owned()),
+            ("canary".to_owned(), "".to_owned()),
+            ("host".to_owned(), "web-1".to_owned()),
+        ]
+        .into_iter()
+        .collect::<MetricTags>();
+        let gauge = |value, timestamp| {
+            Event::Metric(
+                Metric::new(
+                    "system.load.1",
+                    MetricKind::Absolute,
+                    MetricValue::Gauge { value },
+                )
+                .with_tags(Some(tags.clone()))
+                .with_timestamp(Some(Utc.timestamp(timestamp, 0))),
+            )
+        };
+        assert_eq!(
+            collect_n(rx, 3).await,
+            vec![
+                gauge(0.5, 1_617_216_000),
+                gauge(0.75, 1_617_216_010),
+                Event::Metric(
+                    Metric::new(
+                        "requests",
+                        MetricKind::Incremental,
+                        MetricValue::Counter { value: 25.0 },
+                    )
+                    .with_tags(Some(MetricTags::new()))
+                    .with_timestamp(Some(Utc.timestamp(1_617_216_000, 0))),
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn receives_sketches() {
+        trace_init();
+        let (rx, address) = source(false).await;
+
+        let payload = proto::SketchPayload {
+            sketches: vec![proto::sketch_payload::Sketch {
+                metric: "request.duration".to_owned(),
+                host: "web-1".to_owned(),
+                distributions: Vec::new(),
+                tags: vec!["env:prod".to_owned()],
+                dogsketches: vec![proto::sketch_payload::sketch::Dogsketch {
+                    ts: 1_617_216_000,
+                    cnt: 6,
+                    k: vec![-1, 0, 1338],
+                    n: vec![1, 2, 3],
+                    ..Default::default()
+                }],
+            }],
+            metadata: None,
+        };
+        let mut body = Vec::new();
+        payload.encode(&mut body).unwrap();
+        let request = reqwest::Client::new()
+            .post(&format!("http://{}/api/beta/sketches", address))
+            .header("Content-Type", "application/x-protobuf");
+        assert_eq!(202, send(request, body).await);
+
+        let events = collect_n(rx, 1).await;
+        let metric = events[0].as_metric();
+        assert_eq!(metric.name(), "request.duration");
+        assert_eq!(metric.tag_value("env"), Some("prod".to_owned()));
+        assert_eq!(metric.tag_value("host"), Some("web-1".to_owned()));
+        assert_eq!(metric.data.kind, MetricKind::Incremental);
+        assert_eq!(metric.data.timestamp, Some(Utc.timestamp(1_617_216_000, 0)));
+        match &metric.data.value {
+            MetricValue::Distribution { samples, statistic } => {
+                assert_eq!(*statistic, StatisticKind::Summary);
+                let rates = samples.iter().map(|sample| sample.rate).collect::<Vec<_>>();
+                assert_eq!(rates, vec![1, 2, 3]);
+                assert!(samples[0].value < 0.0);
+                assert_eq!(samples[1].value, 0.0);
+                assert!((samples[2].value - 1.0).abs() < f64::EPSILON);
+            }
+            value => panic!("Unexpected value {:?}", value),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_payloads() {
+        trace_init();
+        let (_rx, address) = source(false).await;
+
+        let request = reqwest::Client::new().post(&format!("http://{}/api/v1/series", address));
+        assert_eq!(400, send(request, b"{\"series\": 1}".to_vec()).await);
+    }
+}
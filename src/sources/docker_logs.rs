@@ -74,6 +74,7 @@ pub struct DockerLogsConfig {
     partial_event_marker_field: Option<String>,
     auto_partial_merge: bool,
     multiline: Option<MultilineConfig>,
+    #[serde(deserialize_with = "crate::serde::duration_secs")]
     retry_backoff_secs: u64,
 }
 
@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: MPL-2.0 OR GPL-2.0
+/*
+ * Process exec, TCP connection and DNS query events, sent to the `ebpf` source
+ * through the `events` perf buffer. Built with CO-RE, against the `vmlinux.h`
+ * of the build host, so it loads on any kernel with BTF.
+ */
+#include "vmlinux.h"
+#include <bpf/bpf_core_read.h>
+#include <bpf/bpf_endian.h>
+#include <bpf/bpf_helpers.h>
+#include <bpf/bpf_tracing.h>
+#include "telemetry.h"
+
+#define AF_INET 2
+#define AF_INET6 10
+#define DNS_PORT 53
+
+char LICENSE[] SEC("license") = "Dual MPL/GPL";
+
+struct {
+	__uint(type, BPF_MAP_TYPE_PERF_EVENT_ARRAY);
+	__uint(key_size, sizeof(__u32));
+	__uint(value_size, sizeof(__u32));
+} events SEC(".maps");
+
+/* The layouts of `struct iov_iter` across kernel versions. */
+struct iov_iter___ubuf {
+	__u8 iter_type;
+	void *ubuf;
+} __attribute__((preserve_access_index));
+
+struct iov_iter___iov {
+	const struct iovec *iov;
+} __attribute__((preserve_access_index));
+
+struct iov_iter___renamed_iov {
+	const struct iovec *__iov;
+} __attribute__((preserve_access_index));
+
+enum iter_type___ubuf {
+	ITER_UBUF___ubuf = 0,
+};
+
+static __always_inline void fill_header(struct event_header *header, __u32 kind)
+{
+	header->kind = kind;
+	header->pid = bpf_get_current_pid_tgid() >> 32;
+	header->uid = (__u32)bpf_get_current_uid_gid();
+	bpf_get_current_comm(&header->comm, sizeof(header->comm));
+}
+
+SEC("tracepoint/sched/sched_process_exec")
+int handle_exec(struct trace_event_raw_sched_process_exec *ctx)
+{
+	struct task_struct *task = (struct task_struct *)bpf_get_current_task();
+	unsigned int filename_offset = ctx->__data_loc_filename & 0xFFFF;
+	struct exec_event event = {};
+
+	fill_header(&event.header, EVENT_EXEC);
+	event.ppid = BPF_CORE_READ(task, real_parent, tgid);
+	bpf_probe_read_kernel_str(&event.filename, sizeof(event.filename),
+				  (void *)ctx + filename_offset);
+
+	bpf_perf_event_output(ctx, &events, BPF_F_CURRENT_CPU, &event, sizeof(event));
+	return 0;
+}
+
+SEC("tracepoint/sock/inet_sock_set_state")
+int handle_tcp_state(struct trace_event_raw_inet_sock_set_state *ctx)
+{
+	int old_state = ctx->oldstate;
+	int new_state = ctx->newstate;
+	struct tcp_event event = {};
+
+	if (ctx->protocol != IPPROTO_TCP)
+		return 0;
+	/* Only the connections established or closed, not the handshakes, nor the listeners. */
+	if ((new_state != TCP_ESTABLISHED && new_state != TCP_CLOSE) || old_state == TCP_LISTEN)
+		return 0;
+
+	fill_header(&event.header, EVENT_TCP);
+	event.family = ctx->family;
+	event.sport = ctx->sport;
+	event.dport = ctx->dport;
+	event.old_state = old_state;
+	event.new_state = new_state;
+	if (ctx->family == AF_INET) {
+		bpf_probe_read_kernel(&event.saddr, 4, ctx->saddr);
+		bpf_probe_read_kernel(&event.daddr, 4, ctx->daddr);
+	} else if (ctx->family == AF_INET6) {
+		bpf_probe_read_kernel(&event.saddr, 16, ctx->saddr_v6);
+		bpf_probe_read_kernel(&event.daddr, 16, ctx->daddr_v6);
+	} else {
+		return 0;
+	}
+
+	bpf_perf_event_output(ctx, &events, BPF_F_CURRENT_CPU, &event, sizeof(event));
+	return 0;
+}
+
+/* The user space address of the first segment of the data sent. */
+static __always_inline const void *payload_address(struct msghdr *msg)
+{
+	void *iter = __builtin_preserve_access_index(&msg->msg_iter);
+
+	if (bpf_core_field_exists(((struct iov_iter___ubuf *)0)->ubuf) &&
+	    bpf_core_enum_value_exists(enum iter_type___ubuf, ITER_UBUF___ubuf)) {
+		struct iov_iter___ubuf *ubuf_iter = iter;
+
+		if (BPF_CORE_READ(ubuf_iter, iter_type) ==
+		    bpf_core_enum_value(enum iter_type___ubuf, ITER_UBUF___ubuf))
+			return BPF_CORE_READ(ubuf_iter, ubuf);
+	}
+
+	if (bpf_core_field_exists(((struct iov_iter___renamed_iov *)0)->__iov))
+		return BPF_CORE_READ((struct iov_iter___renamed_iov *)iter, __iov, iov_base);
+	return BPF_CORE_READ((struct iov_iter___iov *)iter, iov, iov_base);
+}
+
+static __always_inline int trace_dns(struct pt_regs *ctx, struct sock *sk, struct msghdr *msg,
+				     size_t len)
+{
+	struct sockaddr_in6 *addr = BPF_CORE_READ(msg, msg_name);
+	__u16 family = BPF_CORE_READ(sk, __sk_common.skc_family);
+	struct dns_event event = {};
+	const void *payload;
+	__u32 size;
+	__u16 dport;
+
+	/* `sin_port` and `sin6_port` share their offset. */
+	if (addr)
+		bpf_probe_read_kernel(&dport, sizeof(dport), &addr->sin6_port);
+	else
+		dport = BPF_CORE_READ(sk, __sk_common.skc_dport);
+	if (dport != bpf_htons(DNS_PORT))
+		return 0;
+
+	fill_header(&event.header, EVENT_DNS);
+	event.family = family;
+	event.dport = DNS_PORT;
+	if (family == AF_INET) {
+		if (addr) {
+			bpf_probe_read_kernel(&event.daddr, 4,
+					      &((struct sockaddr_in *)addr)->sin_addr);
+		} else {
+			__be32 daddr = BPF_CORE_READ(sk, __sk_common.skc_daddr);
+
+			__builtin_memcpy(&event.daddr, &daddr, 4);
+		}
+	} else if (family == AF_INET6) {
+		if (addr)
+			bpf_probe_read_kernel(&event.daddr, 16, &addr->sin6_addr);
+		else
+			BPF_CORE_READ_INTO(&event.daddr, sk, __sk_common.skc_v6_daddr);
+	} else {
+		return 0;
+	}
+
+	size = len;
+	if (size > MAX_DNS_LEN)
+		size = MAX_DNS_LEN;
+	payload = payload_address(msg);
+	if (!payload || bpf_probe_read_user(&event.payload, size, payload))
+		return 0;
+	event.len = size;
+
+	bpf_perf_event_output(ctx, &events, BPF_F_CURRENT_CPU, &event, sizeof(event));
+	return 0;
+}
+
+SEC("kprobe/udp_sendmsg")
+int BPF_KPROBE(handle_udp_sendmsg, struct sock *sk, struct msghdr *msg, size_t len)
+{
+	return trace_dns(ctx, sk, msg, len);
+}
+
+SEC("kprobe/udpv6_sendmsg")
+int BPF_KPROBE(handle_udpv6_sendmsg, struct sock *sk, struct msghdr *msg, size_t len)
+{
+	return trace_dns(ctx, sk, msg, len);
+}
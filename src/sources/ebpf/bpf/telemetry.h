@@ -0,0 +1,49 @@
+/* The events sent by `telemetry.bpf.c`, mirrored by `src/sources/ebpf/event.rs`. */
+#ifndef __TELEMETRY_H
+#define __TELEMETRY_H
+
+#define TASK_COMM_LEN 16
+#define MAX_FILENAME_LEN 256
+#define MAX_DNS_LEN 256
+
+enum event_kind {
+	EVENT_EXEC = 1,
+	EVENT_TCP = 2,
+	EVENT_DNS = 3,
+};
+
+struct event_header {
+	__u32 kind;
+	__u32 pid;
+	__u32 uid;
+	char comm[TASK_COMM_LEN];
+};
+
+struct exec_event {
+	struct event_header header;
+	__u32 ppid;
+	char filename[MAX_FILENAME_LEN];
+};
+
+struct tcp_event {
+	struct event_header header;
+	__u16 family;
+	__u16 sport;
+	__u16 dport;
+	__u16 padding;
+	__u32 old_state;
+	__u32 new_state;
+	__u8 saddr[16];
+	__u8 daddr[16];
+};
+
+struct dns_event {
+	struct event_header header;
+	__u16 family;
+	__u16 dport;
+	__u8 daddr[16];
+	__u32 len;
+	__u8 payload[MAX_DNS_LEN];
+};
+
+#endif /* __TELEMETRY_H */
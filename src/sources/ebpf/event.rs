@@ -0,0 +1,335 @@
+//! Decodes the events of `bpf/telemetry.h`.
+
+use crate::{config::log_schema, event::LogEvent};
+use chrono::Utc;
+use snafu::Snafu;
+use std::{
+    mem::size_of,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    ptr,
+};
+
+const TASK_COMM_LEN: usize = 16;
+const MAX_FILENAME_LEN: usize = 256;
+const MAX_DNS_LEN: usize = 256;
+
+const EVENT_EXEC: u32 = 1;
+const EVENT_TCP: u32 = 2;
+const EVENT_DNS: u32 = 3;
+
+const AF_INET: u16 = 2;
+const AF_INET6: u16 = 10;
+
+const TCP_ESTABLISHED: u32 = 1;
+const TCP_SYN_SENT: u32 = 2;
+const TCP_SYN_RECV: u32 = 3;
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum DecodeError {
+    #[snafu(display("Truncated event of {} bytes", size))]
+    Truncated { size: usize },
+    #[snafu(display("Unknown event kind {}", kind))]
+    UnknownKind { kind: u32 },
+    #[snafu(display("Unknown address family {}", family))]
+    UnknownFamily { family: u16 },
+    #[snafu(display("Invalid DNS query"))]
+    InvalidDnsQuery,
+}
+
+/// Marks the types which are only integers and arrays of bytes, so any bytes
+/// are a valid value.
+unsafe trait Plain: Copy {}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct EventHeader {
+    kind: u32,
+    pid: u32,
+    uid: u32,
+    comm: [u8; TASK_COMM_LEN],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct ExecEvent {
+    header: EventHeader,
+    ppid: u32,
+    filename: [u8; MAX_FILENAME_LEN],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct TcpEvent {
+    header: EventHeader,
+    family: u16,
+    sport: u16,
+    dport: u16,
+    _padding: u16,
+    old_state: u32,
+    new_state: u32,
+    saddr: [u8; 16],
+    daddr: [u8; 16],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct DnsEvent {
+    header: EventHeader,
+    family: u16,
+    dport: u16,
+    daddr: [u8; 16],
+    len: u32,
+    payload: [u8; MAX_DNS_LEN],
+}
+
+unsafe impl Plain for EventHeader {}
+unsafe impl Plain for ExecEvent {}
+unsafe impl Plain for TcpEvent {}
+unsafe impl Plain for DnsEvent {}
+
+fn read<T: Plain>(data: &[u8]) -> Result<T, DecodeError> {
+    if data.len() < size_of::<T>() {
+        return Err(DecodeError::Truncated { size: data.len() });
+    }
+    // Safety: `data` holds enough bytes, and `T` is `Plain`.
+    Ok(unsafe { ptr::read_unaligned(data.as_ptr() as *const T) })
+}
+
+/// Decodes an event of the perf buffer.
+pub fn decode(data: &[u8]) -> Result<LogEvent, DecodeError> {
+    let header = read::<EventHeader>(data)?;
+
+    let mut log = LogEvent::default();
+    log.insert(log_schema().timestamp_key(), Utc::now());
+    log.insert("pid", header.pid as i64);
+    log.insert("uid", header.uid as i64);
+    log.insert("comm", c_string(&header.comm));
+
+    match header.kind {
+        EVENT_EXEC => {
+            let event = read::<ExecEvent>(data)?;
+            log.insert("event", "exec");
+            log.insert("ppid", event.ppid as i64);
+            log.insert("filename", c_string(&event.filename));
+        }
+        EVENT_TCP => {
+            let event = read::<TcpEvent>(data)?;
+            let action = match (event.old_state, event.new_state) {
+                (TCP_SYN_SENT, TCP_ESTABLISHED) => "connect",
+                (TCP_SYN_RECV, TCP_ESTABLISHED) => "accept",
+                (_, TCP_ESTABLISHED) => "established",
+                _ => "close",
+            };
+            log.insert("event", "tcp");
+            log.insert("action", action);
+            log.insert(
+                "source_address",
+                address(event.family, &event.saddr)?.to_string(),
+            );
+            log.insert("source_port", event.sport as i64);
+            log.insert(
+                "destination_address",
+                address(event.family, &event.daddr)?.to_string(),
+            );
+            log.insert("destination_port", event.dport as i64);
+        }
+        EVENT_DNS => {
+            let event = read::<DnsEvent>(data)?;
+            let len = (event.len as usize).min(MAX_DNS_LEN);
+            let (query, query_type) =
+                parse_dns_query(&event.payload[..len]).ok_or(DecodeError::InvalidDnsQuery)?;
+            log.insert("event", "dns");
+            log.insert(
+                "destination_address",
+                address(event.family, &event.daddr)?.to_string(),
+            );
+            log.insert("destination_port", event.dport as i64);
+            log.insert("query", query);
+            log.insert("query_type", dns_type_name(query_type));
+        }
+        kind => return Err(DecodeError::UnknownKind { kind }),
+    }
+
+    Ok(log)
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn address(family: u16, bytes: &[u8; 16]) -> Result<IpAddr, DecodeError> {
+    match family {
+        AF_INET => Ok(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]).into()),
+        AF_INET6 => Ok(Ipv6Addr::from(*bytes).into()),
+        family => Err(DecodeError::UnknownFamily { family }),
+    }
+}
+
+/// The name and the type of the first question of a DNS query.
+fn parse_dns_query(payload: &[u8]) -> Option<(String, u16)> {
+    let header = payload.get(..12)?;
+    // Responses, and queries without questions.
+    if header[2] & 0x80 != 0 || u16::from_be_bytes([header[4], header[5]]) == 0 {
+        return None;
+    }
+
+    let mut labels = Vec::new();
+    let mut position = 12;
+    loop {
+        let len = *payload.get(position)? as usize;
+        position += 1;
+        if len == 0 {
+            break;
+        }
+        // Questions don't use compression, so labels are at most 63 bytes.
+        if len > 63 {
+            return None;
+        }
+        labels.push(String::from_utf8_lossy(
+            payload.get(position..position + len)?,
+        ));
+        position += len;
+    }
+
+    let query_type = payload.get(position..position + 2)?;
+    Some((
+        labels.join("."),
+        u16::from_be_bytes([query_type[0], query_type[1]]),
+    ))
+}
+
+fn dns_type_name(query_type: u16) -> String {
+    match query_type {
+        1 => "A".to_owned(),
+        2 => "NS".to_owned(),
+        5 => "CNAME".to_owned(),
+        6 => "SOA".to_owned(),
+        12 => "PTR".to_owned(),
+        15 => "MX".to_owned(),
+        16 => "TXT".to_owned(),
+        28 => "AAAA".to_owned(),
+        33 => "SRV".to_owned(),
+        65 => "HTTPS".to_owned(),
+        255 => "ANY".to_owned(),
+        query_type => format!("TYPE{}", query_type),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Value;
+
+    fn bytes<T: Plain>(event: &T) -> Vec<u8> {
+        // Safety: `T` is `Plain`, so it's only made of initialized bytes.
+        unsafe { std::slice::from_raw_parts(event as *const T as *const u8, size_of::<T>()) }
+            .to_vec()
+    }
+
+    fn header(kind: u32) -> EventHeader {
+        let mut comm = [0; TASK_COMM_LEN];
+        comm[..4].copy_from_slice(b"curl");
+        EventHeader {
+            kind,
+            pid: 42,
+            uid: 1000,
+            comm,
+        }
+    }
+
+    fn ip(value: &[u8]) -> [u8; 16] {
+        let mut array = [0; 16];
+        array[..value.len()].copy_from_slice(value);
+        array
+    }
+
+    fn string(value: &[u8]) -> [u8; 256] {
+        let mut array = [0; 256];
+        array[..value.len()].copy_from_slice(value);
+        array
+    }
+
+    #[test]
+    fn decodes_exec() {
+        let event = ExecEvent {
+            header: header(EVENT_EXEC),
+            ppid: 1,
+            filename: string(b"/usr/bin/curl"),
+        };
+
+        let log = decode(&bytes(&event)).unwrap();
+        assert_eq!(log["event"], "exec".into());
+        assert_eq!(log["pid"], Value::Integer(42));
+        assert_eq!(log["uid"], Value::Integer(1000));
+        assert_eq!(log["comm"], "curl".into());
+        assert_eq!(log["ppid"], Value::Integer(1));
+        assert_eq!(log["filename"], "/usr/bin/curl".into());
+    }
+
+    #[test]
+    fn decodes_tcp() {
+        let event = TcpEvent {
+            header: header(EVENT_TCP),
+            family: AF_INET,
+            sport: 51234,
+            dport: 443,
+            _padding: 0,
+            old_state: TCP_SYN_SENT,
+            new_state: TCP_ESTABLISHED,
+            saddr: ip(&[10, 0, 0, 2]),
+            daddr: ip(&[93, 184, 216, 34]),
+        };
+
+        let log = decode(&bytes(&event)).unwrap();
+        assert_eq!(log["event"], "tcp".into());
+        assert_eq!(log["action"], "connect".into());
+        assert_eq!(log["source_address"], "10.0.0.2".into());
+        assert_eq!(log["source_port"], Value::Integer(51234));
+        assert_eq!(log["destination_address"], "93.184.216.34".into());
+        assert_eq!(log["destination_port"], Value::Integer(443));
+    }
+
+    #[test]
+    fn decodes_dns() {
+        let query = [
+            0x12, 0x34, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 7, b'e', b'x',
+            b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0, 0x00, 0x1c, 0x00, 0x01,
+        ];
+        let event = DnsEvent {
+            header: header(EVENT_DNS),
+            family: AF_INET6,
+            dport: 53,
+            daddr: ip(&[
+                0x20, 0x01, 0x48, 0x60, 0x48, 0x60, 0, 0, 0, 0, 0, 0, 0, 0, 0x88, 0x88,
+            ]),
+            len: query.len() as u32,
+            payload: string(&query),
+        };
+
+        let log = decode(&bytes(&event)).unwrap();
+        assert_eq!(log["event"], "dns".into());
+        assert_eq!(log["destination_address"], "2001:4860:4860::8888".into());
+        assert_eq!(log["query"], "example.com".into());
+        assert_eq!(log["query_type"], "AAAA".into());
+
+        let response = DnsEvent {
+            payload: string(&[0x12, 0x34, 0x81, 0x80, 0x00, 0x01]),
+            len: 6,
+            ..event
+        };
+        assert_eq!(
+            decode(&bytes(&response)).unwrap_err(),
+            DecodeError::InvalidDnsQuery
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_events() {
+        let event = bytes(&header(EVENT_EXEC));
+        assert_eq!(
+            decode(&event).unwrap_err(),
+            DecodeError::Truncated { size: event.len() }
+        );
+    }
+}
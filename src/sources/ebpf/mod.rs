@@ -0,0 +1,219 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription,
+    },
+    event::{Event, LogEvent},
+    internal_events::{EbpfEventDecodeError, EbpfEventReceived, EbpfEventsDiscarded},
+    shutdown::ShutdownSignal,
+    Pipeline,
+};
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt, StreamExt,
+};
+use libbpf_rs::{Link, Object, ObjectBuilder, PerfBufferBuilder};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+mod event;
+
+pub use event::DecodeError;
+
+/// The programs of `bpf/telemetry.bpf.c`, compiled by `build.rs`.
+static BPF_OBJECT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/telemetry.bpf.o"));
+
+const POLL_TIMEOUT: Duration = Duration::from_millis(100);
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct EbpfConfig {
+    #[serde(default = "default_events")]
+    events: Vec<EbpfEventKind>,
+    host_key: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EbpfEventKind {
+    Exec,
+    Tcp,
+    Dns,
+}
+
+impl EbpfEventKind {
+    fn programs(self) -> &'static [&'static str] {
+        match self {
+            Self::Exec => &["handle_exec"],
+            Self::Tcp => &["handle_tcp_state"],
+            Self::Dns => &["handle_udp_sendmsg", "handle_udpv6_sendmsg"],
+        }
+    }
+}
+
+fn default_events() -> Vec<EbpfEventKind> {
+    vec![EbpfEventKind::Exec, EbpfEventKind::Tcp, EbpfEventKind::Dns]
+}
+
+inventory::submit! {
+    SourceDescription::new::<EbpfConfig>("ebpf")
+}
+
+impl GenerateConfig for EbpfConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            events: default_events(),
+            host_key: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "ebpf")]
+impl SourceConfig for EbpfConfig {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let host_key = self
+            .host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().to_string());
+        let hostname = crate::get_hostname().ok();
+        let mut programs = self
+            .events
+            .iter()
+            .flat_map(|kind| kind.programs())
+            .copied()
+            .collect::<Vec<_>>();
+        programs.sort_unstable();
+        programs.dedup();
+
+        // The programs are loaded, and the perf buffer polled, on a thread of
+        // their own, as libbpf objects can't be sent between threads.
+        let (tx, rx) = mpsc::channel(1024);
+        let (loaded_tx, loaded_rx) = oneshot::channel();
+        let stopped = Arc::new(AtomicBool::new(false));
+        let poll_stopped = Arc::clone(&stopped);
+        thread::Builder::new()
+            .name("ebpf".into())
+            .spawn(move || match load(&programs) {
+                Ok((object, _links)) => {
+                    let _ = loaded_tx.send(Ok(()));
+                    if let Err(error) = poll(&object, tx, &poll_stopped) {
+                        error!(message = "Failed polling the perf buffer.", %error);
+                    }
+                }
+                Err(error) => {
+                    let _ = loaded_tx.send(Err(error));
+                }
+            })?;
+        loaded_rx.await??;
+
+        let mut out = out.sink_map_err(|error| error!(message = "Error sending event.", %error));
+        Ok(Box::pin(async move {
+            let mut events = rx.take_until(shutdown).map(move |mut log: LogEvent| {
+                if let Some(hostname) = &hostname {
+                    log.insert(host_key.as_str(), hostname.clone());
+                }
+                Ok(Event::Log(log))
+            });
+            let result = out.send_all(&mut events).await;
+            stopped.store(true, Ordering::Relaxed);
+            result
+        }))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "ebpf"
+    }
+}
+
+/// Loads the programs and attaches the given ones, which stay attached as long
+/// as their links are kept.
+fn load(programs: &[&str]) -> crate::Result<(Object, Vec<Link>)> {
+    let mut object = ObjectBuilder::default()
+        .open_memory("telemetry", BPF_OBJECT)?
+        .load()?;
+
+    let mut links = Vec::with_capacity(programs.len());
+    for name in programs {
+        let program = object
+            .prog_mut(name)
+            .ok_or_else(|| format!("Missing the `{}` program.", name))?;
+        links.push(program.attach()?);
+    }
+
+    Ok((object, links))
+}
+
+fn poll(
+    object: &Object,
+    mut tx: mpsc::Sender<LogEvent>,
+    stopped: &AtomicBool,
+) -> crate::Result<()> {
+    let map = object
+        .map("events")
+        .ok_or("Missing the `events` perf buffer.")?;
+    let perf = PerfBufferBuilder::new(map)
+        .sample_cb(move |_cpu, data: &[u8]| match event::decode(data) {
+            Ok(log) => {
+                emit!(EbpfEventReceived {
+                    byte_size: data.len()
+                });
+                if tx.try_send(log).is_err() {
+                    emit!(EbpfEventsDiscarded {
+                        count: 1,
+                        reason: "The pipeline is full."
+                    });
+                }
+            }
+            Err(error) => emit!(EbpfEventDecodeError { error }),
+        })
+        .lost_cb(|_cpu, count| {
+            emit!(EbpfEventsDiscarded {
+                count,
+                reason: "The perf buffer is full."
+            })
+        })
+        .build()?;
+
+    while !stopped.load(Ordering::Relaxed) {
+        perf.poll(POLL_TIMEOUT)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<EbpfConfig>();
+    }
+
+    #[test]
+    fn parses_events() {
+        let config: EbpfConfig = toml::from_str(r#"events = ["exec", "dns"]"#).unwrap();
+        assert_eq!(config.events, vec![EbpfEventKind::Exec, EbpfEventKind::Dns]);
+
+        let config: EbpfConfig = toml::from_str("").unwrap();
+        assert_eq!(config.events, default_events());
+    }
+}
@@ -1,11 +1,17 @@
-use super::util::{EncodingConfig, MultilineConfig};
+use super::util::{
+    DecodeErrorBehavior, DecodingConfig, EncodingConfig, MultilineConfig, DECODE_ERRORS_OUTPUT,
+};
 use crate::{
     config::{log_schema, DataType, GlobalOptions, SourceConfig, SourceDescription},
     encoding_transcode::{Decoder, Encoder},
-    event::Event,
-    internal_events::{FileEventReceived, FileOpen, FileSourceInternalEventsEmitter},
+    event::{finalization::OrderedFinalizer, BatchNotifier, BatchStatus, Event},
+    internal_events::{
+        FileDecodeFailed, FileEventReceived, FileOpen, FileSourceInternalEventsEmitter,
+        SourceEventDeliveryFailed,
+    },
     line_agg::{self, LineAgg},
     shutdown::ShutdownSignal,
+    state::FileCheckpoints,
     trace::{current_span, Instrument},
     Pipeline,
 };
@@ -13,16 +19,18 @@ use bytes::Bytes;
 use chrono::Utc;
 use file_source::{
     paths_provider::glob::{Glob, MatchOptions},
-    FileServer, FingerprintStrategy, Fingerprinter, ReadFrom,
+    Acknowledgement, CheckpointStore, FileServer, FingerprintStrategy, Fingerprinter, Line,
+    ReadFrom,
 };
 use futures::{
-    future::TryFutureExt,
+    future::{self, TryFutureExt},
     stream::{Stream, StreamExt},
     SinkExt,
 };
 use regex::bytes::Regex;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::path::PathBuf;
 use std::time::Duration;
@@ -66,11 +74,16 @@ pub struct FileConfig {
     pub start_at_beginning: Option<bool>,
     pub ignore_checkpoints: Option<bool>,
     pub read_from: Option<ReadFromConfig>,
+    #[serde(default, deserialize_with = "crate::serde::optional_duration_secs")]
     pub ignore_older: Option<u64>, // secs
-    #[serde(default = "default_max_line_bytes")]
+    #[serde(
+        default = "default_max_line_bytes",
+        deserialize_with = "crate::serde::bytes"
+    )]
     pub max_line_bytes: usize,
     pub host_key: Option<String>,
     pub data_dir: Option<PathBuf>,
+    pub checkpoint: CheckpointConfig,
     pub glob_minimum_cooldown: u64, // millis
     // Deprecated name
     #[serde(alias = "fingerprinting")]
@@ -79,11 +92,29 @@ pub struct FileConfig {
     pub message_start_indicator: Option<String>,
     pub multi_line_timeout: u64, // millis
     pub multiline: Option<MultilineConfig>,
+    #[serde(deserialize_with = "crate::serde::bytes")]
     pub max_read_bytes: usize,
     pub oldest_first: bool,
+    #[serde(default, deserialize_with = "crate::serde::optional_duration_secs")]
     pub remove_after: Option<u64>,
     pub line_delimiter: String,
     pub encoding: Option<EncodingConfig>,
+    pub decoding: DecodingConfig,
+    /// Whether the lines failing to decode are discarded, or sent to the
+    /// `decode_errors` output as events describing the error.
+    pub on_decode_error: DecodeErrorBehavior,
+    /// Only checkpoints the lines once their events were delivered by the sinks
+    /// with `acknowledgements` enabled.
+    pub acknowledgements: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CheckpointConfig {
+    /// A file written by `vector checkpoints export`, possibly on another
+    /// host, whose checkpoints are imported on startup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import_path: Option<PathBuf>,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
@@ -91,8 +122,13 @@ pub struct FileConfig {
 pub enum FingerprintConfig {
     Checksum {
         // Deprecated name
-        #[serde(alias = "fingerprint_bytes")]
+        #[serde(
+            default,
+            alias = "fingerprint_bytes",
+            deserialize_with = "crate::serde::optional_bytes"
+        )]
         bytes: Option<usize>,
+        #[serde(deserialize_with = "crate::serde::bytes")]
         ignored_header_bytes: usize,
     },
     #[serde(rename = "device_and_inode")]
@@ -161,6 +197,7 @@ impl Default for FileConfig {
             ignore_not_found: false,
             host_key: None,
             data_dir: None,
+            checkpoint: CheckpointConfig::default(),
             glob_minimum_cooldown: 1000, // millis
             message_start_indicator: None,
             multi_line_timeout: 1000, // millis
@@ -170,6 +207,9 @@ impl Default for FileConfig {
             remove_after: None,
             line_delimiter: "\n".to_string(),
             encoding: None,
+            decoding: DecodingConfig::default(),
+            on_decode_error: DecodeErrorBehavior::default(),
+            acknowledgements: false,
         }
     }
 }
@@ -195,6 +235,8 @@ impl SourceConfig for FileConfig {
         // without the file servers' checkpointers interfering with each
         // other
         let data_dir = globals.resolve_and_make_data_subdir(self.data_dir.as_ref(), name)?;
+        let checkpoint_store =
+            FileCheckpoints::new(globals.state_store(self.data_dir.as_ref(), name)?.into());
 
         // Clippy rule, because async_trait?
         #[allow(clippy::suspicious_else_formatting)]
@@ -209,7 +251,13 @@ impl SourceConfig for FileConfig {
             }
         }
 
-        Ok(file_source(self, data_dir, shutdown, out))
+        Ok(file_source(
+            self,
+            data_dir,
+            Some(Box::new(checkpoint_store)),
+            shutdown,
+            out,
+        ))
     }
 
     fn output_type(&self) -> DataType {
@@ -219,11 +267,16 @@ impl SourceConfig for FileConfig {
     fn source_type(&self) -> &'static str {
         "file"
     }
+
+    fn outputs(&self) -> Vec<&'static str> {
+        self.on_decode_error.outputs()
+    }
 }
 
 pub fn file_source(
     config: &FileConfig,
     data_dir: PathBuf,
+    checkpoint_store: Option<Box<dyn CheckpointStore>>,
     shutdown: ShutdownSignal,
     mut out: Pipeline,
 ) -> super::Source {
@@ -254,6 +307,14 @@ pub fn file_source(
         None => Bytes::from(config.line_delimiter.clone()),
     };
 
+    // The checkpoints only advance once the lines before them are delivered.
+    let (acknowledged_tx, acknowledged_rx) = if config.acknowledgements {
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        (Some(tx), Some(rx))
+    } else {
+        (None, None)
+    };
+
     let file_server = FileServer {
         paths_provider,
         max_read_bytes: config.max_read_bytes,
@@ -263,6 +324,8 @@ pub fn file_source(
         max_line_bytes: config.max_line_bytes,
         line_delimiter: line_delimiter_as_bytes,
         data_dir,
+        checkpoint_import_path: config.checkpoint.import_path.clone(),
+        checkpoint_store,
         glob_minimum_cooldown,
         fingerprinter: Fingerprinter {
             strategy: config.fingerprint.clone().into(),
@@ -273,6 +336,7 @@ pub fn file_source(
         remove_after: config.remove_after.map(Duration::from_secs),
         emitter: FileSourceInternalEventsEmitter,
         handle: tokio::runtime::Handle::current(),
+        acknowledgements: acknowledged_rx,
     };
 
     let file_key = config.file_key.clone();
@@ -287,6 +351,10 @@ pub fn file_source(
     let multiline_config = config.multiline.clone();
     let message_start_indicator = config.message_start_indicator.clone();
     let multi_line_timeout = config.multi_line_timeout;
+    let decoding = config.decoding;
+    let on_decode_error = config.on_decode_error;
+    let mut decode_errors = out.output(DECODE_ERRORS_OUTPUT);
+    let forward_decode_errors = decode_errors.is_some();
 
     Box::pin(async move {
         info!(message = "Starting file server.", include = ?include, exclude = ?exclude);
@@ -294,19 +362,19 @@ pub fn file_source(
         let mut encoding_decoder = encoding_charset.map(|e| Decoder::new(e));
 
         // sizing here is just a guess
-        let (tx, rx) = futures::channel::mpsc::channel::<Vec<(Bytes, String)>>(2);
+        let (tx, rx) = futures::channel::mpsc::channel::<Vec<Line>>(2);
         let rx = rx
             .map(futures::stream::iter)
             .flatten()
-            .map(move |(line, src)| {
+            .map(move |mut line| {
                 // transcode each line from the file's encoding charset to utf8
-                match encoding_decoder.as_mut() {
-                    Some(d) => (d.decode_to_utf8(line), src),
-                    None => (line, src),
+                if let Some(d) = encoding_decoder.as_mut() {
+                    line.text = d.decode_to_utf8(line.text);
                 }
+                line
             });
 
-        let messages: Box<dyn Stream<Item = (Bytes, String)> + Send + std::marker::Unpin> =
+        let messages: Box<dyn Stream<Item = Line> + Send + std::marker::Unpin> =
             if let Some(ref multiline_config) = multiline_config {
                 wrap_with_line_agg(
                     rx,
@@ -328,13 +396,90 @@ pub fn file_source(
         // logs in the queue.
         let span = current_span();
         let span2 = span.clone();
-        let mut messages = messages
-            .map(move |(msg, file): (Bytes, String)| {
-                let _enter = span2.enter();
-                create_event(msg, file, &host_key, &hostname, &file_key)
+        // The file of an errored line is read again from the line on, and the
+        // lines of the file read before that are ignored once finalized, until
+        // the errored line is finalized again. Failed lines would fail again,
+        // so they're acknowledged.
+        let finalizer = acknowledged_tx.map(|acknowledged_tx| {
+            let mut errored = HashMap::new();
+            OrderedFinalizer::new(move |status, (file_id, position)| {
+                match errored.get(&file_id) {
+                    Some(errored_position) if *errored_position != position => return,
+                    Some(_) => {
+                        errored.remove(&file_id);
+                    }
+                    None => {}
+                }
+
+                let acknowledgement = match status {
+                    BatchStatus::Delivered => Acknowledgement::Delivered(file_id, position),
+                    BatchStatus::Errored => {
+                        errored.insert(file_id, position);
+                        Acknowledgement::Errored(file_id)
+                    }
+                    BatchStatus::Failed => {
+                        emit!(SourceEventDeliveryFailed);
+                        Acknowledgement::Delivered(file_id, position)
+                    }
+                };
+                let _ = acknowledged_tx.unbounded_send(acknowledgement);
             })
-            .map(Ok);
-        tokio::spawn(async move { out.send_all(&mut messages).instrument(span).await });
+        });
+        let mut messages = messages.filter_map(move |line: Line| {
+            let _enter = span2.enter();
+            let byte_size = line.text.len();
+            let (event, decode_error) = match decoding.decode(line.text.clone()) {
+                Ok(event) => {
+                    emit!(FileEventReceived {
+                        file: &line.filename,
+                        byte_size,
+                    });
+                    (Some(event), false)
+                }
+                Err(error) => {
+                    let event = if forward_decode_errors {
+                        on_decode_error.error_event(&line.text, &error)
+                    } else {
+                        None
+                    };
+                    emit!(FileDecodeFailed {
+                        file: &line.filename,
+                        error,
+                        forwarded: event.is_some(),
+                    });
+                    (event, true)
+                }
+            };
+
+            // The batch of a discarded line is delivered once dropped here.
+            let batch = finalizer.as_ref().map(|finalizer| {
+                let (batch, receiver) = BatchNotifier::new_with_receiver();
+                finalizer.add((line.file_id, line.offset), receiver);
+                batch
+            });
+            let event = event.map(|event| {
+                let event = create_event(event, line.filename, &host_key, &hostname, &file_key);
+                match &batch {
+                    Some(batch) => event.with_batch_notifier(batch),
+                    None => event,
+                }
+            });
+            future::ready(event.map(|event| (event, decode_error)))
+        });
+        tokio::spawn(
+            async move {
+                while let Some((event, decode_error)) = messages.next().await {
+                    let sent = match decode_errors.as_mut() {
+                        Some(decode_errors) if decode_error => decode_errors.send(event).await,
+                        _ => out.send(event).await,
+                    };
+                    if sent.is_err() {
+                        break;
+                    }
+                }
+            }
+            .instrument(span),
+        );
 
         let span = info_span!("file_server");
         spawn_blocking(move || {
@@ -375,30 +520,34 @@ fn reconcile_position_options(
 }
 
 fn wrap_with_line_agg(
-    rx: impl Stream<Item = (Bytes, String)> + Send + std::marker::Unpin + 'static,
+    rx: impl Stream<Item = Line> + Send + std::marker::Unpin + 'static,
     config: line_agg::Config,
-) -> Box<dyn Stream<Item = (Bytes, String)> + Send + std::marker::Unpin + 'static> {
+) -> Box<dyn Stream<Item = Line> + Send + std::marker::Unpin + 'static> {
     let logic = line_agg::Logic::new(config);
     Box::new(
-        LineAgg::new(rx.map(|(line, src)| (src, line, ())), logic)
-            .map(|(src, line, _context)| (line, src)),
+        LineAgg::new(
+            rx.map(|line| (line.filename, line.text, (line.file_id, line.offset))),
+            logic,
+        )
+        // The position of an aggregated line is the one after its last line.
+        .map(|(filename, text, (file_id, offset))| Line {
+            text,
+            filename,
+            file_id,
+            offset,
+        }),
     )
 }
 
+/// Adds the source type, the file and the host to the event of a line read
+/// from `file`.
 fn create_event(
-    line: Bytes,
+    mut event: Event,
     file: String,
     host_key: &str,
     hostname: &Option<String>,
     file_key: &Option<String>,
 ) -> Event {
-    emit!(FileEventReceived {
-        file: &file,
-        byte_size: line.len(),
-    });
-
-    let mut event = Event::from(line);
-
     // Add source type
     event
         .as_mut_log()
@@ -418,7 +567,7 @@ fn create_event(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{config::Config, shutdown::ShutdownSignal, sources::file};
+    use crate::{config::Config, event::EventStatus, shutdown::ShutdownSignal, sources::file};
     use encoding_rs::UTF_16LE;
     use pretty_assertions::assert_eq;
     use std::{
@@ -530,6 +679,39 @@ mod tests {
         )
         .unwrap();
         assert_eq!(config.read_from, Some(ReadFromConfig::End));
+
+        let config: FileConfig = toml::from_str(
+            r#"
+        [checkpoint]
+        import_path = "/var/lib/vector/checkpoints.json"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.checkpoint.import_path,
+            Some(PathBuf::from("/var/lib/vector/checkpoints.json"))
+        );
+
+        let config: FileConfig = toml::from_str(
+            r#"
+        max_line_bytes = "1KiB"
+        remove_after = "1h"
+        [fingerprint]
+        strategy = "checksum"
+        bytes = "1KiB"
+        ignored_header_bytes = 512
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.max_line_bytes, 1024);
+        assert_eq!(config.remove_after, Some(3600));
+        assert_eq!(
+            config.fingerprint,
+            FingerprintConfig::Checksum {
+                bytes: Some(1024),
+                ignored_header_bytes: 512,
+            }
+        );
     }
 
     #[test]
@@ -560,7 +742,7 @@ mod tests {
         let hostname = Some("Some.Machine".to_string());
         let file_key = Some("file".to_string());
 
-        let event = create_event(line, file, &host_key, &hostname, &file_key);
+        let event = create_event(Event::from(line), file, &host_key, &hostname, &file_key);
         let log = event.into_log();
 
         assert_eq!(log["file"], "some_file.rs".into());
@@ -569,6 +751,57 @@ mod tests {
         assert_eq!(log[log_schema().source_type_key()], "file".into());
     }
 
+    #[tokio::test]
+    async fn file_forwards_decode_errors() {
+        let (tx, rx) = Pipeline::new_test();
+        let (errors_tx, errors_rx) = Pipeline::new_test();
+        let (trigger_shutdown, shutdown, _) = ShutdownSignal::new_wired();
+
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*")],
+            read_from: Some(ReadFromConfig::Beginning),
+            decoding: DecodingConfig::Json,
+            on_decode_error: DecodeErrorBehavior::Forward,
+            ..test_default_file_config(&dir)
+        };
+        assert_eq!(config.outputs(), vec![DECODE_ERRORS_OUTPUT]);
+
+        let path = dir.path().join("file");
+        let mut file = File::create(&path).unwrap();
+        writeln!(&mut file, r#"{{"message": "first"}}"#).unwrap();
+        writeln!(&mut file, r#"{{"message": "cut sh"#).unwrap();
+        writeln!(&mut file, r#"{{"message": "last", "level": "info"}}"#).unwrap();
+
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx.with_output(DECODE_ERRORS_OUTPUT, errors_tx),
+        );
+        tokio::spawn(source);
+
+        sleep_500_millis().await;
+        drop(trigger_shutdown);
+
+        let received = wait_with_timeout(rx.collect::<Vec<_>>()).await;
+        let messages = received
+            .iter()
+            .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["first", "last"]);
+        assert_eq!(received[1].as_log()["level"], "info".into());
+        assert_eq!(received[1].as_log()["file"], path.to_str().unwrap().into());
+
+        let errors = wait_with_timeout(errors_rx.collect::<Vec<_>>()).await;
+        assert_eq!(errors.len(), 1);
+        let log = errors[0].as_log();
+        assert_eq!(log["decode_error.raw"], r#"{"message": "cut sh"#.into());
+        assert_eq!(log["file"], path.to_str().unwrap().into());
+        assert!(!log.contains(log_schema().message_key()));
+    }
+
     #[tokio::test]
     async fn file_happy_path() {
         let n = 5;
@@ -581,7 +814,13 @@ mod tests {
             ..test_default_file_config(&dir)
         };
 
-        let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx,
+        );
         tokio::spawn(source);
 
         let path1 = dir.path().join("file1");
@@ -638,7 +877,13 @@ mod tests {
             include: vec![dir.path().join("*")],
             ..test_default_file_config(&dir)
         };
-        let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx,
+        );
         tokio::spawn(source);
 
         let path = dir.path().join("file");
@@ -703,7 +948,13 @@ mod tests {
             include: vec![dir.path().join("*")],
             ..test_default_file_config(&dir)
         };
-        let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx,
+        );
         tokio::spawn(source);
 
         let path = dir.path().join("file");
@@ -771,7 +1022,13 @@ mod tests {
             ..test_default_file_config(&dir)
         };
 
-        let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx,
+        );
         tokio::spawn(source);
 
         let path1 = dir.path().join("a.txt");
@@ -827,7 +1084,13 @@ mod tests {
                 ..test_default_file_config(&dir)
             };
 
-            let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+            let source = file::file_source(
+                &config,
+                config.data_dir.clone().unwrap(),
+                None,
+                shutdown,
+                tx,
+            );
             tokio::spawn(source);
 
             let path = dir.path().join("file");
@@ -861,7 +1124,13 @@ mod tests {
                 ..test_default_file_config(&dir)
             };
 
-            let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+            let source = file::file_source(
+                &config,
+                config.data_dir.clone().unwrap(),
+                None,
+                shutdown,
+                tx,
+            );
             tokio::spawn(source);
 
             let path = dir.path().join("file");
@@ -895,7 +1164,13 @@ mod tests {
                 ..test_default_file_config(&dir)
             };
 
-            let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+            let source = file::file_source(
+                &config,
+                config.data_dir.clone().unwrap(),
+                None,
+                shutdown,
+                tx,
+            );
             tokio::spawn(source);
 
             let path = dir.path().join("file");
@@ -943,7 +1218,13 @@ mod tests {
             let (trigger_shutdown, shutdown, _) = ShutdownSignal::new_wired();
 
             let (tx, rx) = Pipeline::new_test();
-            let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+            let source = file::file_source(
+                &config,
+                config.data_dir.clone().unwrap(),
+                None,
+                shutdown,
+                tx,
+            );
             tokio::spawn(source);
 
             sleep_500_millis().await;
@@ -964,7 +1245,13 @@ mod tests {
             let (trigger_shutdown, shutdown, _) = ShutdownSignal::new_wired();
 
             let (tx, rx) = Pipeline::new_test();
-            let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+            let source = file::file_source(
+                &config,
+                config.data_dir.clone().unwrap(),
+                None,
+                shutdown,
+                tx,
+            );
             tokio::spawn(source);
 
             sleep_500_millis().await;
@@ -991,7 +1278,13 @@ mod tests {
                 ..test_default_file_config(&dir)
             };
             let (tx, rx) = Pipeline::new_test();
-            let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+            let source = file::file_source(
+                &config,
+                config.data_dir.clone().unwrap(),
+                None,
+                shutdown,
+                tx,
+            );
             tokio::spawn(source);
 
             sleep_500_millis().await;
@@ -1012,6 +1305,211 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn file_start_position_server_restart_with_acknowledgements() {
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*")],
+            acknowledgements: true,
+            ..test_default_file_config(&dir)
+        };
+
+        let path = dir.path().join("file");
+        let mut file = File::create(&path).unwrap();
+        writeln!(&mut file, "first line").unwrap();
+        writeln!(&mut file, "second line").unwrap();
+        sleep_500_millis().await;
+
+        // Only the first line is delivered before the server stops.
+        let undelivered = {
+            let (trigger_shutdown, shutdown, _) = ShutdownSignal::new_wired();
+
+            let (tx, rx) = Pipeline::new_test();
+            let source = file::file_source(
+                &config,
+                config.data_dir.clone().unwrap(),
+                None,
+                shutdown,
+                tx,
+            );
+            tokio::spawn(source);
+
+            let (first, rx) = wait_with_timeout(rx.into_future()).await;
+            let (second, rx) = wait_with_timeout(rx.into_future()).await;
+            drop(first.unwrap());
+            // Lets the server apply the acknowledgement, even once backed off.
+            delay_for(Duration::from_secs(3)).await;
+
+            drop(trigger_shutdown);
+            wait_with_timeout(rx.collect::<Vec<_>>()).await;
+            second.unwrap()
+        };
+        // Restart server, read the undelivered line again.
+        {
+            let (trigger_shutdown, shutdown, _) = ShutdownSignal::new_wired();
+
+            let (tx, rx) = Pipeline::new_test();
+            let source = file::file_source(
+                &config,
+                config.data_dir.clone().unwrap(),
+                None,
+                shutdown,
+                tx,
+            );
+            tokio::spawn(source);
+
+            sleep_500_millis().await;
+            drop(trigger_shutdown);
+
+            let received = wait_with_timeout(rx.collect::<Vec<_>>()).await;
+            let lines = received
+                .into_iter()
+                .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+                .collect::<Vec<_>>();
+            assert_eq!(lines, vec!["second line"]);
+        }
+        drop(undelivered);
+    }
+
+    #[tokio::test]
+    async fn file_reads_errored_lines_again() {
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*")],
+            acknowledgements: true,
+            ..test_default_file_config(&dir)
+        };
+
+        let path = dir.path().join("file");
+        let mut file = File::create(&path).unwrap();
+        writeln!(&mut file, "first line").unwrap();
+        writeln!(&mut file, "second line").unwrap();
+        sleep_500_millis().await;
+
+        // The first line errors, the second one is delivered, and both are
+        // read again.
+        {
+            let (trigger_shutdown, shutdown, _) = ShutdownSignal::new_wired();
+
+            let (tx, rx) = Pipeline::new_test();
+            let source = file::file_source(
+                &config,
+                config.data_dir.clone().unwrap(),
+                None,
+                shutdown,
+                tx,
+            );
+            tokio::spawn(source);
+
+            let (first, rx) = wait_with_timeout(rx.into_future()).await;
+            let (second, rx) = wait_with_timeout(rx.into_future()).await;
+            first
+                .unwrap()
+                .take_finalizers()
+                .update_status(EventStatus::Errored);
+            drop(second.unwrap());
+
+            let (first, rx) = wait_with_timeout(rx.into_future()).await;
+            let (second, rx) = wait_with_timeout(rx.into_future()).await;
+            let lines = vec![first.unwrap(), second.unwrap()]
+                .into_iter()
+                .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+                .collect::<Vec<_>>();
+            assert_eq!(lines, vec!["first line", "second line"]);
+            // Lets the server apply the acknowledgements, even once backed off.
+            delay_for(Duration::from_secs(3)).await;
+
+            drop(trigger_shutdown);
+            let received = wait_with_timeout(rx.collect::<Vec<_>>()).await;
+            assert!(received.is_empty());
+        }
+        // Restart server, both lines were delivered.
+        {
+            let (trigger_shutdown, shutdown, _) = ShutdownSignal::new_wired();
+
+            let (tx, rx) = Pipeline::new_test();
+            let source = file::file_source(
+                &config,
+                config.data_dir.clone().unwrap(),
+                None,
+                shutdown,
+                tx,
+            );
+            tokio::spawn(source);
+
+            sleep_500_millis().await;
+            drop(trigger_shutdown);
+
+            let received = wait_with_timeout(rx.collect::<Vec<_>>()).await;
+            assert!(received.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn file_checkpoints_past_failed_lines() {
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*")],
+            acknowledgements: true,
+            ..test_default_file_config(&dir)
+        };
+
+        let path = dir.path().join("file");
+        let mut file = File::create(&path).unwrap();
+        writeln!(&mut file, "first line").unwrap();
+        writeln!(&mut file, "second line").unwrap();
+        sleep_500_millis().await;
+
+        // The first line fails to be delivered, which retrying wouldn't fix.
+        {
+            let (trigger_shutdown, shutdown, _) = ShutdownSignal::new_wired();
+
+            let (tx, rx) = Pipeline::new_test();
+            let source = file::file_source(
+                &config,
+                config.data_dir.clone().unwrap(),
+                None,
+                shutdown,
+                tx,
+            );
+            tokio::spawn(source);
+
+            let (first, rx) = wait_with_timeout(rx.into_future()).await;
+            let (second, rx) = wait_with_timeout(rx.into_future()).await;
+            first
+                .unwrap()
+                .take_finalizers()
+                .update_status(EventStatus::Failed);
+            drop(second.unwrap());
+            // Lets the server apply the acknowledgements, even once backed off.
+            delay_for(Duration::from_secs(3)).await;
+
+            drop(trigger_shutdown);
+            let received = wait_with_timeout(rx.collect::<Vec<_>>()).await;
+            assert!(received.is_empty());
+        }
+        // Restart server, the checkpoint advanced past both lines.
+        {
+            let (trigger_shutdown, shutdown, _) = ShutdownSignal::new_wired();
+
+            let (tx, rx) = Pipeline::new_test();
+            let source = file::file_source(
+                &config,
+                config.data_dir.clone().unwrap(),
+                None,
+                shutdown,
+                tx,
+            );
+            tokio::spawn(source);
+
+            sleep_500_millis().await;
+            drop(trigger_shutdown);
+
+            let received = wait_with_timeout(rx.collect::<Vec<_>>()).await;
+            assert!(received.is_empty());
+        }
+    }
+
     #[tokio::test]
     async fn file_start_position_server_restart_with_file_rotation() {
         let dir = tempdir().unwrap();
@@ -1027,7 +1525,13 @@ mod tests {
             let (trigger_shutdown, shutdown, _) = ShutdownSignal::new_wired();
 
             let (tx, rx) = Pipeline::new_test();
-            let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+            let source = file::file_source(
+                &config,
+                config.data_dir.clone().unwrap(),
+                None,
+                shutdown,
+                tx,
+            );
             tokio::spawn(source);
 
             let mut file = File::create(&path).unwrap();
@@ -1052,7 +1556,13 @@ mod tests {
             let (trigger_shutdown, shutdown, _) = ShutdownSignal::new_wired();
 
             let (tx, rx) = Pipeline::new_test();
-            let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+            let source = file::file_source(
+                &config,
+                config.data_dir.clone().unwrap(),
+                None,
+                shutdown,
+                tx,
+            );
             tokio::spawn(source);
 
             let mut file = File::create(&path).unwrap();
@@ -1086,7 +1596,13 @@ mod tests {
             ..test_default_file_config(&dir)
         };
 
-        let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx,
+        );
         tokio::spawn(source);
 
         let before_path = dir.path().join("before");
@@ -1161,7 +1677,13 @@ mod tests {
             ..test_default_file_config(&dir)
         };
 
-        let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx,
+        );
         tokio::spawn(source);
 
         let path = dir.path().join("file");
@@ -1219,7 +1741,13 @@ mod tests {
             ..test_default_file_config(&dir)
         };
 
-        let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx,
+        );
         tokio::spawn(source);
 
         let path = dir.path().join("file");
@@ -1293,7 +1821,13 @@ mod tests {
             ..test_default_file_config(&dir)
         };
 
-        let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx,
+        );
         tokio::spawn(source);
 
         let path = dir.path().join("file");
@@ -1381,7 +1915,13 @@ mod tests {
 
         sleep_500_millis().await;
 
-        let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx,
+        );
         tokio::spawn(source);
 
         sleep_500_millis().await;
@@ -1444,7 +1984,13 @@ mod tests {
 
         sleep_500_millis().await;
 
-        let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx,
+        );
         tokio::spawn(source);
 
         sleep_500_millis().await;
@@ -1495,7 +2041,13 @@ mod tests {
 
         sleep_500_millis().await;
 
-        let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx,
+        );
         tokio::spawn(source);
 
         sleep_500_millis().await;
@@ -1550,7 +2102,13 @@ mod tests {
             ..test_default_file_config(&dir)
         };
 
-        let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx,
+        );
         tokio::spawn(source);
 
         sleep_500_millis().await;
@@ -1593,7 +2151,13 @@ mod tests {
             ..test_default_file_config(&dir)
         };
 
-        let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx,
+        );
         tokio::spawn(source);
 
         sleep_500_millis().await;
@@ -1636,7 +2200,13 @@ mod tests {
             ..test_default_file_config(&dir)
         };
 
-        let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx,
+        );
         tokio::spawn(source);
 
         let path = dir.path().join("file");
@@ -1692,7 +2262,13 @@ mod tests {
             ..test_default_file_config(&dir)
         };
 
-        let source = file::file_source(&config, config.data_dir.clone().unwrap(), shutdown, tx);
+        let source = file::file_source(
+            &config,
+            config.data_dir.clone().unwrap(),
+            None,
+            shutdown,
+            tx,
+        );
         tokio::spawn(source);
 
         let path = dir.path().join("file");
@@ -0,0 +1,446 @@
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription},
+    event::metric::{Metric, MetricValue},
+    http::{Auth, HttpClient},
+    internal_events::{
+        HaproxyMetricsCollectCompleted, HaproxyMetricsParseError, HaproxyMetricsRequestError,
+    },
+    shutdown::ShutdownSignal,
+    sources::util::{MetricsEndpoint, ScrapeTarget},
+    tls::{TlsOptions, TlsSettings},
+    Event, Pipeline,
+};
+use bytes::Bytes;
+use futures::{future::join_all, stream, SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tokio::time;
+
+pub mod parser;
+use parser::StatsLine;
+
+enum FieldKind {
+    Counter,
+    Gauge,
+}
+
+/// The numeric fields of the statistics, with the names of their metrics.
+const FIELDS: &[(&str, &str, FieldKind)] = &[
+    ("qcur", "current_queue", FieldKind::Gauge),
+    ("scur", "current_sessions", FieldKind::Gauge),
+    ("slim", "limit_sessions", FieldKind::Gauge),
+    ("stot", "sessions_total", FieldKind::Counter),
+    ("bin", "bytes_in_total", FieldKind::Counter),
+    ("bout", "bytes_out_total", FieldKind::Counter),
+    ("dreq", "requests_denied_total", FieldKind::Counter),
+    ("dresp", "responses_denied_total", FieldKind::Counter),
+    ("ereq", "request_errors_total", FieldKind::Counter),
+    ("econ", "connection_errors_total", FieldKind::Counter),
+    ("eresp", "response_errors_total", FieldKind::Counter),
+    ("wretr", "retry_warnings_total", FieldKind::Counter),
+    ("wredis", "redispatch_warnings_total", FieldKind::Counter),
+    ("weight", "weight", FieldKind::Gauge),
+    ("act", "active_servers", FieldKind::Gauge),
+    ("bck", "backup_servers", FieldKind::Gauge),
+    ("chkfail", "check_failures_total", FieldKind::Counter),
+    ("downtime", "downtime_seconds_total", FieldKind::Counter),
+    ("rate", "current_session_rate", FieldKind::Gauge),
+    ("req_tot", "http_requests_total", FieldKind::Counter),
+];
+
+/// The fields counting the HTTP responses by class of status code.
+const RESPONSE_FIELDS: &[(&str, &str)] = &[
+    ("hrsp_1xx", "1xx"),
+    ("hrsp_2xx", "2xx"),
+    ("hrsp_3xx", "3xx"),
+    ("hrsp_4xx", "4xx"),
+    ("hrsp_5xx", "5xx"),
+    ("hrsp_other", "other"),
+];
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+struct HaproxyMetricsConfig {
+    endpoints: Vec<MetricsEndpoint>,
+    #[serde(
+        default = "default_scrape_interval_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
+    scrape_interval_secs: u64,
+    #[serde(default = "default_namespace")]
+    namespace: String,
+    tls: Option<TlsOptions>,
+    auth: Option<Auth>,
+}
+
+pub fn default_scrape_interval_secs() -> u64 {
+    15
+}
+
+pub fn default_namespace() -> String {
+    "haproxy".to_string()
+}
+
+inventory::submit! {
+    SourceDescription::new::<HaproxyMetricsConfig>("haproxy_metrics")
+}
+
+impl GenerateConfig for HaproxyMetricsConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            endpoints: vec!["http://localhost:8404/stats;csv".into()],
+            scrape_interval_secs: default_scrape_interval_secs(),
+            namespace: default_namespace(),
+            tls: None,
+            auth: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "haproxy_metrics")]
+impl SourceConfig for HaproxyMetricsConfig {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let tls = TlsSettings::from_options(&self.tls)?;
+        if let Some(auth) = &self.auth {
+            auth.validate()?;
+        }
+        let http_client = HttpClient::new(tls)?;
+
+        let namespace = Some(self.namespace.clone()).filter(|namespace| !namespace.is_empty());
+        let mut sources = Vec::with_capacity(self.endpoints.len());
+        for endpoint in self.endpoints.iter() {
+            sources.push(HaproxyMetrics {
+                http_client: http_client.clone(),
+                auth: self.auth.clone(),
+                target: ScrapeTarget::new(endpoint, namespace.clone())?,
+            });
+        }
+
+        let mut out =
+            out.sink_map_err(|error| error!(message = "Error sending haproxy metrics.", %error));
+
+        let duration = time::Duration::from_secs(self.scrape_interval_secs);
+        Ok(Box::pin(async move {
+            let mut interval = time::interval(duration).take_until(shutdown);
+            while interval.next().await.is_some() {
+                let start = Instant::now();
+                let metrics = join_all(sources.iter().map(|haproxy| haproxy.collect())).await;
+                emit!(HaproxyMetricsCollectCompleted {
+                    start,
+                    end: Instant::now()
+                });
+
+                let mut stream = stream::iter(metrics).flatten().map(Event::Metric).map(Ok);
+                out.send_all(&mut stream).await?;
+            }
+
+            Ok(())
+        }))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn source_type(&self) -> &'static str {
+        "haproxy_metrics"
+    }
+}
+
+#[derive(Debug)]
+struct HaproxyMetrics {
+    http_client: HttpClient,
+    auth: Option<Auth>,
+    target: ScrapeTarget,
+}
+
+impl HaproxyMetrics {
+    async fn collect(&self) -> stream::BoxStream<'static, Metric> {
+        let (up, metrics) = match self.collect_metrics().await {
+            Ok(metrics) => (true, metrics),
+            Err(()) => {
+                self.target.scrape_failed();
+                (false, vec![])
+            }
+        };
+
+        stream::iter(self.target.status_metrics(up))
+            .chain(stream::iter(metrics))
+            .boxed()
+    }
+
+    async fn collect_metrics(&self) -> Result<Vec<Metric>, ()> {
+        let response = self.get_stats().await.map_err(|error| {
+            emit!(HaproxyMetricsRequestError {
+                error,
+                endpoint: self.target.endpoint(),
+            })
+        })?;
+
+        let response = String::from_utf8_lossy(&response);
+        let lines = parser::parse(&response).map_err(|error| {
+            emit!(HaproxyMetricsParseError {
+                error,
+                endpoint: self.target.endpoint(),
+            })
+        })?;
+
+        Ok(lines
+            .iter()
+            .flat_map(|line| self.line_metrics(line))
+            .collect())
+    }
+
+    /// Reads the statistics from the `show stat` command of the stats socket,
+    /// or from the CSV export of the stats page.
+    async fn get_stats(&self) -> crate::Result<Bytes> {
+        match self.target.url().strip_prefix("unix://") {
+            Some(path) => read_stats_socket(path).await,
+            None => Ok(self
+                .target
+                .get(&self.http_client, self.auth.as_ref())
+                .await?),
+        }
+    }
+
+    fn line_metrics(&self, line: &StatsLine<'_>) -> Vec<Metric> {
+        let mut tags = self.target.tags().clone();
+        tags.insert("proxy".into(), line.proxy.into());
+        tags.insert("service".into(), line.service.into());
+        tags.insert("type".into(), line.service_type.as_str().into());
+
+        let create_metric = |name: &str, value: MetricValue| {
+            self.target
+                .create_metric(name, value)
+                .with_tags(Some(tags.clone()))
+        };
+
+        let mut metrics = FIELDS
+            .iter()
+            .filter_map(|(field, name, kind)| {
+                let value = line.fields.get(field)?.parse::<f64>().ok()?;
+                Some(create_metric(
+                    *name,
+                    match kind {
+                        FieldKind::Counter => MetricValue::Counter { value },
+                        FieldKind::Gauge => MetricValue::Gauge { value },
+                    },
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        metrics.extend(RESPONSE_FIELDS.iter().filter_map(|(field, code)| {
+            let value = line.fields.get(field)?.parse::<f64>().ok()?;
+            let mut tags = tags.clone();
+            tags.insert("code".into(), (*code).into());
+            Some(
+                self.target
+                    .create_metric("http_responses_total", MetricValue::Counter { value })
+                    .with_tags(Some(tags)),
+            )
+        }));
+
+        if let Some(status) = line.fields.get("status") {
+            let up = status.starts_with("UP") || *status == "OPEN";
+            metrics.push(create_metric(
+                "service_up",
+                MetricValue::Gauge {
+                    value: if up { 1.0 } else { 0.0 },
+                },
+            ));
+        }
+
+        metrics
+    }
+}
+
+#[cfg(unix)]
+async fn read_stats_socket(path: &str) -> crate::Result<Bytes> {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::UnixStream,
+    };
+
+    let mut stream = UnixStream::connect(path).await?;
+    stream.write_all(b"show stat\n").await?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+    Ok(response.into())
+}
+
+#[cfg(not(unix))]
+async fn read_stats_socket(_path: &str) -> crate::Result<Bytes> {
+    Err("Stats sockets are only supported on unix.".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{collect_ready, next_addr, wait_for_tcp},
+        Error,
+    };
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Body, Response, Server,
+    };
+    use pretty_assertions::assert_eq;
+
+    const STATS: &str = "# pxname,svname,qcur,scur,stot,bin,bout,status,type,hrsp_2xx,hrsp_5xx,\n\
+                         http,FRONTEND,,3,120,4096,8192,OPEN,0,100,2,\n\
+                         app,web1,0,1,60,2048,4096,DOWN,2,50,1,\n";
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<HaproxyMetricsConfig>();
+    }
+
+    async fn scrape(endpoint: MetricsEndpoint) -> Vec<Metric> {
+        let (tx, rx) = Pipeline::new_test();
+
+        let source = HaproxyMetricsConfig {
+            endpoints: vec![endpoint],
+            scrape_interval_secs: 1,
+            namespace: "haproxy".to_string(),
+            tls: None,
+            auth: None,
+        }
+        .build(
+            "default",
+            &GlobalOptions::default(),
+            ShutdownSignal::noop(),
+            tx,
+        )
+        .await
+        .unwrap();
+        tokio::spawn(source);
+
+        time::delay_for(time::Duration::from_millis(500)).await;
+
+        collect_ready(rx)
+            .await
+            .into_iter()
+            .map(|e| e.into_metric())
+            .collect()
+    }
+
+    fn find<'a>(metrics: &'a [Metric], name: &str, service: &str) -> &'a Metric {
+        metrics
+            .iter()
+            .find(|m| {
+                m.name() == name
+                    && m.tags()
+                        .and_then(|tags| tags.get("service"))
+                        .map(String::as_str)
+                        == Some(service)
+            })
+            .unwrap_or_else(|| panic!("missing {} of {}", name, service))
+    }
+
+    #[tokio::test]
+    async fn scrapes_csv_endpoint() {
+        let in_addr = next_addr();
+
+        let make_svc = make_service_fn(|_| async {
+            Ok::<_, Error>(service_fn(|_| async {
+                Ok::<_, Error>(Response::new(Body::from(STATS)))
+            }))
+        });
+
+        tokio::spawn(async move {
+            if let Err(error) = Server::bind(&in_addr).serve(make_svc).await {
+                error!(message = "Server error.", %error);
+            }
+        });
+        wait_for_tcp(in_addr).await;
+
+        let metrics = scrape(MetricsEndpoint::Tagged {
+            url: format!("http://{}/stats;csv", in_addr),
+            tags: vec![("region".to_owned(), "eu".to_owned())]
+                .into_iter()
+                .collect(),
+        })
+        .await;
+
+        let up = metrics.iter().find(|m| m.name() == "up").unwrap();
+        assert_eq!(up.data.value, MetricValue::Gauge { value: 1.0 });
+        assert_eq!(up.tags().unwrap()["region"], "eu");
+
+        let sessions = find(&metrics, "sessions_total", "FRONTEND");
+        assert_eq!(sessions.data.value, MetricValue::Counter { value: 120.0 });
+        let tags = sessions.tags().unwrap();
+        assert_eq!(tags["proxy"], "http");
+        assert_eq!(tags["type"], "frontend");
+        assert_eq!(tags["region"], "eu");
+
+        assert_eq!(
+            find(&metrics, "service_up", "web1").data.value,
+            MetricValue::Gauge { value: 0.0 }
+        );
+        let responses = metrics
+            .iter()
+            .filter(|m| m.name() == "http_responses_total")
+            .count();
+        assert_eq!(responses, 4);
+        assert!(!metrics
+            .iter()
+            .any(|m| m.name() == "current_queue" && m.tags().unwrap()["service"] == "FRONTEND"));
+    }
+
+    #[tokio::test]
+    async fn reports_unreachable_endpoint() {
+        let in_addr = next_addr();
+
+        let metrics = scrape(format!("http://{}/stats;csv", in_addr).as_str().into()).await;
+
+        let up = metrics.iter().find(|m| m.name() == "up").unwrap();
+        assert_eq!(up.data.value, MetricValue::Gauge { value: 0.0 });
+        let errors = metrics
+            .iter()
+            .find(|m| m.name() == "scrape_errors_total")
+            .unwrap();
+        assert_eq!(errors.data.value, MetricValue::Counter { value: 1.0 });
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn scrapes_stats_socket() {
+        use tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::UnixListener,
+        };
+
+        let path = tempfile::tempdir()
+            .unwrap()
+            .into_path()
+            .join("haproxy.sock");
+        let mut listener = UnixListener::bind(&path).unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut command = [0; 10];
+                stream.read_exact(&mut command).await.unwrap();
+                assert_eq!(&command, b"show stat\n");
+                stream.write_all(STATS.as_bytes()).await.unwrap();
+            }
+        });
+
+        let endpoint = format!("unix://{}", path.display());
+        let metrics = scrape(endpoint.as_str().into()).await;
+
+        let up = metrics.iter().find(|m| m.name() == "up").unwrap();
+        assert_eq!(up.data.value, MetricValue::Gauge { value: 1.0 });
+        assert_eq!(up.tags().unwrap()["endpoint"], endpoint);
+        assert_eq!(
+            find(&metrics, "bytes_out_total", "web1").data.value,
+            MetricValue::Counter { value: 4096.0 }
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use snafu::Snafu;
+use std::collections::HashMap;
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum ParseError {
+    #[snafu(display("Missing the `# pxname,svname,...` header"))]
+    MissingHeader,
+    #[snafu(display("Missing field `{}` on line {}", field, line))]
+    MissingField { field: &'static str, line: usize },
+    #[snafu(display("Invalid type `{}` on line {}", value, line))]
+    InvalidType { value: String, line: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceType {
+    Frontend,
+    Backend,
+    Server,
+    Listener,
+}
+
+impl ServiceType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Frontend => "frontend",
+            Self::Backend => "backend",
+            Self::Server => "server",
+            Self::Listener => "listener",
+        }
+    }
+}
+
+/// A line of the statistics, for a frontend, a backend, a server or a listener
+/// of a proxy, with its non-empty fields by name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsLine<'a> {
+    pub proxy: &'a str,
+    pub service: &'a str,
+    pub service_type: ServiceType,
+    pub fields: HashMap<&'a str, &'a str>,
+}
+
+// The CSV format of the statistics:
+// https://www.haproxy.org/download/2.2/doc/management.txt (9.1. CSV format)
+pub fn parse(input: &str) -> Result<Vec<StatsLine<'_>>, ParseError> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let header = match lines.next() {
+        Some((_, line)) if line.starts_with("# ") => line[2..].split(',').collect::<Vec<_>>(),
+        _ => return Err(ParseError::MissingHeader),
+    };
+
+    lines
+        .map(|(index, line)| {
+            let line_number = index + 1;
+            let mut fields = header
+                .iter()
+                .copied()
+                .zip(line.split(','))
+                .filter(|(_, value)| !value.is_empty())
+                .collect::<HashMap<_, _>>();
+
+            let mut take = |field: &'static str| {
+                fields.remove(field).ok_or(ParseError::MissingField {
+                    field,
+                    line: line_number,
+                })
+            };
+            let proxy = take("pxname")?;
+            let service = take("svname")?;
+            let service_type = match take("type")? {
+                "0" => ServiceType::Frontend,
+                "1" => ServiceType::Backend,
+                "2" => ServiceType::Server,
+                "3" => ServiceType::Listener,
+                value => {
+                    return Err(ParseError::InvalidType {
+                        value: value.to_owned(),
+                        line: line_number,
+                    })
+                }
+            };
+
+            Ok(StatsLine {
+                proxy,
+                service,
+                service_type,
+                fields,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_stats() {
+        let data = "# pxname,svname,qcur,scur,stot,status,type,\n\
+                    http,FRONTEND,,3,120,OPEN,0,\n\
+                    app,web1,0,1,60,UP,2,\n\
+                    \n\
+                    app,BACKEND,0,1,60,UP,1,\n";
+
+        let lines = parse(data).expect("valid data");
+        assert_eq!(lines.len(), 3);
+
+        assert_eq!(lines[0].proxy, "http");
+        assert_eq!(lines[0].service, "FRONTEND");
+        assert_eq!(lines[0].service_type, ServiceType::Frontend);
+        assert_eq!(lines[0].fields.get("qcur"), None);
+        assert_eq!(lines[0].fields.get("stot"), Some(&"120"));
+
+        assert_eq!(lines[1].service_type, ServiceType::Server);
+        assert_eq!(lines[1].fields.get("status"), Some(&"UP"));
+        assert_eq!(lines[2].service_type, ServiceType::Backend);
+    }
+
+    #[test]
+    fn rejects_invalid_stats() {
+        assert_eq!(parse("pxname,svname\n"), Err(ParseError::MissingHeader));
+        assert_eq!(
+            parse("# pxname,svname,type\napp,web1\n"),
+            Err(ParseError::MissingField {
+                field: "type",
+                line: 2
+            })
+        );
+        assert_eq!(
+            parse("# pxname,svname,type\napp,web1,7\n"),
+            Err(ParseError::InvalidType {
+                value: "7".to_owned(),
+                line: 2
+            })
+        );
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     },
     event::Event,
     internal_events::{HerokuLogplexRequestReadError, HerokuLogplexRequestReceived},
+    origin::OriginFilter,
     shutdown::ShutdownSignal,
     sources::util::{add_query_parameters, ErrorMessage, HttpSource, HttpSourceAuthConfig},
     tls::TlsConfig,
@@ -29,6 +30,10 @@ pub struct LogplexConfig {
     query_parameters: Vec<String>,
     tls: Option<TlsConfig>,
     auth: Option<HttpSourceAuthConfig>,
+    #[serde(default)]
+    access_log: bool,
+    permit_origin: Option<Vec<String>>,
+    deny_origin: Option<Vec<String>>,
 }
 
 inventory::submit! {
@@ -46,6 +51,9 @@ impl GenerateConfig for LogplexConfig {
             query_parameters: Vec::new(),
             tls: None,
             auth: None,
+            access_log: false,
+            permit_origin: None,
+            deny_origin: None,
         })
         .unwrap()
     }
@@ -54,6 +62,7 @@ impl GenerateConfig for LogplexConfig {
 #[derive(Clone, Default)]
 struct LogplexSource {
     query_parameters: Vec<String>,
+    access_log: bool,
 }
 
 impl HttpSource for LogplexSource {
@@ -66,6 +75,10 @@ impl HttpSource for LogplexSource {
         decode_message(body, header_map)
             .map(|events| add_query_parameters(events, &self.query_parameters, query_parameters))
     }
+
+    fn access_log(&self) -> bool {
+        self.access_log
+    }
 }
 
 #[async_trait::async_trait]
@@ -80,8 +93,18 @@ impl SourceConfig for LogplexConfig {
     ) -> crate::Result<super::Source> {
         let source = LogplexSource {
             query_parameters: self.query_parameters.clone(),
+            access_log: self.access_log,
         };
-        source.run(self.address, "events", &self.tls, &self.auth, out, shutdown)
+        let origin = OriginFilter::new(&self.permit_origin, &self.deny_origin)?;
+        source.run(
+            self.address,
+            "events",
+            &self.tls,
+            &self.auth,
+            origin,
+            out,
+            shutdown,
+        )
     }
 
     fn output_type(&self) -> DataType {
@@ -262,6 +285,9 @@ mod tests {
                 query_parameters,
                 tls: None,
                 auth,
+                access_log: false,
+                permit_origin: None,
+                deny_origin: None,
             }
             .build(
                 "default",
@@ -287,7 +313,9 @@ mod tests {
         let len = body.lines().count();
         let mut req = reqwest::Client::new().post(&format!("http://{}/events?{}", address, query));
         if let Some(auth) = auth {
-            req = req.basic_auth(auth.username, Some(auth.password));
+            if let HttpSourceAuthConfig::Basic { username, password } = auth {
+                req = req.basic_auth(username, Some(password));
+            }
         }
         req.header("Logplex-Msg-Count", len)
             .header("Logplex-Frame-Id", "frame-foo")
@@ -306,7 +334,7 @@ mod tests {
 
         let body = r#"267 <158>1 2020-01-08T22:33:57.353034+00:00 host heroku router - at=info method=GET path="/cart_link" host=lumberjack-store.timber.io request_id=05726858-c44e-4f94-9a20-37df73be9006 fwd="73.75.38.87" dyno=web.1 connect=1ms service=22ms status=304 bytes=656 protocol=http"#;
 
-        let auth = HttpSourceAuthConfig {
+        let auth = HttpSourceAuthConfig::Basic {
             username: "vector_user".to_owned(),
             password: "vector_pass".to_owned(),
         };
@@ -89,7 +89,10 @@ impl Default for Namespace {
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct HostMetricsConfig {
-    #[serde(default = "default_scrape_interval")]
+    #[serde(
+        default = "default_scrape_interval",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
     scrape_interval_secs: u64,
 
     collectors: Option<Vec<Collector>>,
@@ -4,6 +4,7 @@ use crate::{
         SourceDescription,
     },
     event::{Event, Value},
+    origin::OriginFilter,
     shutdown::ShutdownSignal,
     sources::util::{add_query_parameters, ErrorMessage, HttpSource, HttpSourceAuthConfig},
     tls::TlsConfig,
@@ -30,6 +31,10 @@ pub struct SimpleHttpConfig {
     query_parameters: Vec<String>,
     tls: Option<TlsConfig>,
     auth: Option<HttpSourceAuthConfig>,
+    #[serde(default)]
+    access_log: bool,
+    permit_origin: Option<Vec<String>>,
+    deny_origin: Option<Vec<String>>,
 }
 
 inventory::submit! {
@@ -45,6 +50,9 @@ impl GenerateConfig for SimpleHttpConfig {
             query_parameters: Vec::new(),
             tls: None,
             auth: None,
+            access_log: false,
+            permit_origin: None,
+            deny_origin: None,
         })
         .unwrap()
     }
@@ -55,6 +63,7 @@ struct SimpleHttpSource {
     encoding: Encoding,
     headers: Vec<String>,
     query_parameters: Vec<String>,
+    access_log: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative, Copy)]
@@ -86,6 +95,10 @@ impl HttpSource for SimpleHttpSource {
                 events
             })
     }
+
+    fn access_log(&self) -> bool {
+        self.access_log
+    }
 }
 
 #[async_trait::async_trait]
@@ -102,8 +115,18 @@ impl SourceConfig for SimpleHttpConfig {
             encoding: self.encoding,
             headers: self.headers.clone(),
             query_parameters: self.query_parameters.clone(),
+            access_log: self.access_log,
         };
-        source.run(self.address, "", &self.tls, &self.auth, out, shutdown)
+        let origin = OriginFilter::new(&self.permit_origin, &self.deny_origin)?;
+        source.run(
+            self.address,
+            "",
+            &self.tls,
+            &self.auth,
+            origin,
+            out,
+            shutdown,
+        )
     }
 
     fn output_type(&self) -> DataType {
@@ -272,6 +295,9 @@ mod tests {
                 query_parameters,
                 tls: None,
                 auth: None,
+                access_log: false,
+                permit_origin: None,
+                deny_origin: None,
             }
             .build(
                 "default",
@@ -14,6 +14,7 @@ use tokio::time;
 #[serde(deny_unknown_fields, default)]
 pub struct InternalMetricsConfig {
     #[derivative(Default(value = "2"))]
+    #[serde(deserialize_with = "crate::serde::duration_secs")]
     scrape_interval_secs: u64,
 }
 
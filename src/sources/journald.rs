@@ -3,6 +3,7 @@ use crate::{
     event::{Event, LogEvent, Value},
     internal_events::{JournaldEventReceived, JournaldInvalidRecord},
     shutdown::ShutdownSignal,
+    state::StateStore,
     Pipeline,
 };
 use bytes::Bytes;
@@ -19,7 +20,6 @@ use serde_json::{Error as JsonError, Value as JsonValue};
 use snafu::{ResultExt, Snafu};
 use std::{
     collections::{HashMap, HashSet},
-    io::SeekFrom,
     iter::FromIterator,
     path::PathBuf,
     process::Stdio,
@@ -28,12 +28,7 @@ use std::{
 };
 use tokio_util::codec::FramedRead;
 
-use tokio::{
-    fs::{File, OpenOptions},
-    io::{self, AsyncReadExt, AsyncWriteExt},
-    process::Command,
-    time::delay_for,
-};
+use tokio::{io, process::Command, time::delay_for};
 use tracing_futures::Instrument;
 
 const DEFAULT_BATCH_SIZE: usize = 16;
@@ -102,7 +97,7 @@ impl SourceConfig for JournaldConfig {
             warn!("Option `remap_priority` has been deprecated. Please use the `remap` transform and function `to_syslog_level` instead.");
         }
 
-        let data_dir = globals.resolve_and_make_data_subdir(self.data_dir.as_ref(), name)?;
+        let checkpointer = Checkpointer::new(globals.state_store(self.data_dir.as_ref(), name)?);
 
         let include_units = match (!self.units.is_empty(), !self.include_units.is_empty()) {
             (true, true) => return Err(BuildError::BothUnitsAndIncludeUnits.into()),
@@ -124,9 +119,6 @@ impl SourceConfig for JournaldConfig {
             return Err(BuildError::DuplicatedUnit { unit }.into());
         }
 
-        let mut checkpoint_path = data_dir;
-        checkpoint_path.push(CHECKPOINT_FILENAME);
-
         let journalctl_path = self
             .journalctl_path
             .clone()
@@ -142,12 +134,11 @@ impl SourceConfig for JournaldConfig {
             JournaldSource {
                 include_units,
                 exclude_units,
-                checkpoint_path,
                 batch_size,
                 remap_priority: self.remap_priority,
                 out,
             }
-            .run_shutdown(shutdown, start)
+            .run_shutdown(checkpointer, shutdown, start)
             .instrument(info_span!("journald-server")),
         ))
     }
@@ -164,7 +155,6 @@ impl SourceConfig for JournaldConfig {
 struct JournaldSource {
     include_units: HashSet<String>,
     exclude_units: HashSet<String>,
-    checkpoint_path: PathBuf,
     batch_size: usize,
     remap_priority: bool,
     out: Pipeline,
@@ -173,19 +163,10 @@ struct JournaldSource {
 impl JournaldSource {
     async fn run_shutdown(
         self,
+        checkpointer: Checkpointer,
         shutdown: ShutdownSignal,
         start_journalctl: StartJournalctlFn,
     ) -> Result<(), ()> {
-        let mut checkpointer = Checkpointer::new(self.checkpoint_path.clone())
-            .await
-            .map_err(|error| {
-                error!(
-                    message = "Unable to open checkpoint file.",
-                    path = ?self.checkpoint_path,
-                    %error,
-                );
-            })?;
-
         let mut cursor = match checkpointer.get().await {
             Ok(cursor) => cursor,
             Err(error) => {
@@ -198,26 +179,21 @@ impl JournaldSource {
         };
 
         let mut on_stop = None;
-        let run = Box::pin(self.run(
-            &mut checkpointer,
-            &mut cursor,
-            &mut on_stop,
-            start_journalctl,
-        ));
+        let run = Box::pin(self.run(&checkpointer, &mut cursor, &mut on_stop, start_journalctl));
         future::select(run, shutdown).await;
 
         if let Some(stop) = on_stop {
             stop();
         }
 
-        Self::save_checkpoint(&mut checkpointer, &cursor).await;
+        Self::save_checkpoint(&checkpointer, &cursor).await;
 
         Ok(())
     }
 
     async fn run<'a>(
         mut self,
-        checkpointer: &'a mut Checkpointer,
+        checkpointer: &'a Checkpointer,
         cursor: &'a mut Option<String>,
         on_stop: &'a mut Option<StopJournalctlFn>,
         start_journalctl: StartJournalctlFn,
@@ -251,7 +227,7 @@ impl JournaldSource {
     async fn run_stream<'a>(
         &'a mut self,
         mut stream: BoxStream<'static, io::Result<Bytes>>,
-        checkpointer: &'a mut Checkpointer,
+        checkpointer: &'a Checkpointer,
         cursor: &'a mut Option<String>,
     ) -> bool {
         loop {
@@ -314,13 +290,12 @@ impl JournaldSource {
         }
     }
 
-    async fn save_checkpoint(checkpointer: &mut Checkpointer, cursor: &Option<String>) {
+    async fn save_checkpoint(checkpointer: &Checkpointer, cursor: &Option<String>) {
         if let Some(cursor) = cursor {
             if let Err(error) = checkpointer.set(cursor).await {
                 error!(
                     message = "Could not set journald checkpoint.",
                     %error,
-                    filename = ?checkpointer.filename,
                 );
             }
         }
@@ -491,43 +466,33 @@ fn filter_unit(
     }
 }
 
+/// Keeps the cursor in the state store, in the same format as the
+/// checkpoint file used before the state store existed.
 struct Checkpointer {
-    file: File,
-    filename: PathBuf,
+    store: Box<dyn StateStore>,
 }
 
 impl Checkpointer {
-    async fn new(filename: PathBuf) -> Result<Self, io::Error> {
-        let file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .create(true)
-            .open(&filename)
-            .await?;
-        Ok(Checkpointer { file, filename })
-    }
-
-    async fn set(&mut self, token: &str) -> Result<(), io::Error> {
-        self.file.seek(SeekFrom::Start(0)).await?;
-        self.file
-            .write_all(format!("{}\n", token).as_bytes())
-            .await?;
-        Ok(())
+    fn new(store: Box<dyn StateStore>) -> Self {
+        Checkpointer { store }
+    }
+
+    async fn set(&self, token: &str) -> crate::Result<()> {
+        self.store
+            .put(CHECKPOINT_FILENAME, format!("{}\n", token).into())
+            .await
     }
 
-    async fn get(&mut self) -> Result<Option<String>, io::Error> {
-        let mut buf = Vec::<u8>::new();
-        self.file.seek(SeekFrom::Start(0)).await?;
-        self.file.read_to_end(&mut buf).await?;
-        match buf.len() {
-            0 => Ok(None),
-            _ => {
+    async fn get(&self) -> crate::Result<Option<String>> {
+        match self.store.get(CHECKPOINT_FILENAME).await? {
+            Some(buf) if !buf.is_empty() => {
                 let text = String::from_utf8_lossy(&buf);
                 match text.find('\n') {
                     Some(nl) => Ok(Some(String::from(&text[..nl]))),
                     None => Ok(None), // Maybe return an error?
                 }
             }
+            _ => Ok(None),
         }
     }
 }
@@ -535,6 +500,7 @@ impl Checkpointer {
 #[cfg(test)]
 mod checkpointer_tests {
     use super::*;
+    use crate::state::LocalStore;
     use tempfile::tempdir;
     use tokio::fs::read_to_string;
 
@@ -548,9 +514,8 @@ mod checkpointer_tests {
         let tempdir = tempdir().unwrap();
         let mut filename = tempdir.path().to_path_buf();
         filename.push(CHECKPOINT_FILENAME);
-        let mut checkpointer = Checkpointer::new(filename.clone())
-            .await
-            .expect("Creating checkpointer failed!");
+        let checkpointer =
+            Checkpointer::new(Box::new(LocalStore::new(tempdir.path().to_path_buf())));
 
         assert!(checkpointer.get().await.unwrap().is_none());
 
@@ -579,6 +544,7 @@ mod checkpointer_tests {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::LocalStore;
     use futures::Stream;
     use std::pin::Pin;
     use std::{
@@ -651,12 +617,8 @@ mod tests {
         let (trigger, shutdown, _) = ShutdownSignal::new_wired();
 
         let tempdir = tempdir().unwrap();
-        let mut checkpoint_path = tempdir.path().to_path_buf();
-        checkpoint_path.push(CHECKPOINT_FILENAME);
-
-        let mut checkpointer = Checkpointer::new(checkpoint_path.clone())
-            .await
-            .expect("Creating checkpointer failed!");
+        let checkpointer =
+            Checkpointer::new(Box::new(LocalStore::new(tempdir.path().to_path_buf())));
 
         if let Some(cursor) = cursor {
             checkpointer
@@ -671,12 +633,11 @@ mod tests {
         let source = JournaldSource {
             include_units,
             exclude_units,
-            checkpoint_path,
             batch_size: DEFAULT_BATCH_SIZE,
             remap_priority: true,
             out: tx,
         }
-        .run_shutdown(shutdown, Box::new(FakeJournal::new));
+        .run_shutdown(checkpointer, shutdown, Box::new(FakeJournal::new));
         tokio::spawn(source);
 
         delay_for(Duration::from_millis(100)).await;
@@ -1,7 +1,9 @@
 use crate::{
     config::{log_schema, DataType, GlobalOptions, SourceConfig, SourceDescription},
-    event::{Event, Value},
-    internal_events::{KafkaEventFailed, KafkaEventReceived, KafkaOffsetUpdateFailed},
+    event::{finalization::OrderedFinalizer, BatchNotifier, BatchStatus, Event, Value},
+    internal_events::{
+        KafkaEventFailed, KafkaEventReceived, KafkaOffsetUpdateFailed, SourceEventDeliveryFailed,
+    },
     kafka::KafkaAuthConfig,
     shutdown::ShutdownSignal,
     Pipeline,
@@ -13,6 +15,7 @@ use rdkafka::{
     config::ClientConfig,
     consumer::{Consumer, StreamConsumer},
     message::Message,
+    Offset, TopicPartitionList,
 };
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
@@ -49,6 +52,10 @@ pub struct KafkaSourceConfig {
     librdkafka_options: Option<HashMap<String, String>>,
     #[serde(flatten)]
     auth: KafkaAuthConfig,
+    /// Only stores the offsets of the messages once their events were delivered
+    /// by the sinks with `acknowledgements` enabled.
+    #[serde(default)]
+    acknowledgements: bool,
 }
 
 fn default_session_timeout_ms() -> u64 {
@@ -108,9 +115,24 @@ fn kafka_source(
     let topic_key = config.topic_key.clone();
     let partition_key = config.partition_key.clone();
     let offset_key = config.offset_key.clone();
+    let acknowledgements = config.acknowledgements;
     let consumer = Arc::new(create_consumer(config)?);
 
     Ok(Box::pin(async move {
+        let finalizer = if acknowledgements {
+            let consumer = Arc::clone(&consumer);
+            let mut offsets = AcknowledgedOffsets::default();
+            Some(Arc::new(OrderedFinalizer::new(
+                move |status, (topic, partition, offset): (String, i32, i64)| {
+                    if offsets.finalize(status, &topic, partition, offset) {
+                        store_offset(&consumer, &topic, partition, offset);
+                    }
+                },
+            )))
+        } else {
+            None
+        };
+
         Arc::clone(&consumer)
             .start()
             .take_until(shutdown.clone())
@@ -120,6 +142,7 @@ fn kafka_source(
                 let partition_key = partition_key.clone();
                 let offset_key = offset_key.clone();
                 let consumer = Arc::clone(&consumer);
+                let finalizer = finalizer.clone();
 
                 async move {
                     match message {
@@ -179,11 +202,22 @@ fn kafka_source(
                                 log.insert(offset_key, Value::from(msg.offset()));
                             }
 
-                            consumer.store_offset(&msg).map_err(|error| {
-                                emit!(KafkaOffsetUpdateFailed { error });
-                            })?;
-
-                            Ok(event)
+                            match finalizer {
+                                Some(finalizer) => {
+                                    let (batch, receiver) = BatchNotifier::new_with_receiver();
+                                    finalizer.add(
+                                        (msg.topic().to_owned(), msg.partition(), msg.offset()),
+                                        receiver,
+                                    );
+                                    Ok(event.with_batch_notifier(&batch))
+                                }
+                                None => {
+                                    consumer.store_offset(&msg).map_err(|error| {
+                                        emit!(KafkaOffsetUpdateFailed { error });
+                                    })?;
+                                    Ok(event)
+                                }
+                            }
                         }
                     }
                 }
@@ -209,6 +243,57 @@ fn kafka_source(
     }))
 }
 
+/// Tracks the errored messages of the partitions, whose offsets can't be
+/// stored past them so that they're consumed again after a restart.
+#[derive(Debug, Default)]
+struct AcknowledgedOffsets {
+    /// The offset of the first errored message of each partition.
+    errored: HashMap<(String, i32), i64>,
+}
+
+impl AcknowledgedOffsets {
+    /// Whether the offset of a finalized message can be stored, which is only
+    /// the case for a delivered or failed message preceding the errored ones
+    /// of its partition. Failed messages would fail again, so they're
+    /// acknowledged rather than consumed again. A message consumed again after
+    /// a rebalance and delivered this time unblocks its partition.
+    fn finalize(&mut self, status: BatchStatus, topic: &str, partition: i32, offset: i64) -> bool {
+        let key = (topic.to_owned(), partition);
+        match status {
+            BatchStatus::Delivered => {}
+            BatchStatus::Errored => {
+                let first = self.errored.entry(key).or_insert(offset);
+                *first = (*first).min(offset);
+                return false;
+            }
+            BatchStatus::Failed => emit!(SourceEventDeliveryFailed),
+        }
+
+        match self.errored.get(&key) {
+            Some(&first) if offset > first => false,
+            Some(&first) => {
+                if offset == first {
+                    self.errored.remove(&key);
+                }
+                true
+            }
+            None => true,
+        }
+    }
+}
+
+/// Stores the offset following the one of a message, where the consumption of
+/// its partition resumes.
+fn store_offset(consumer: &StreamConsumer, topic: &str, partition: i32, offset: i64) {
+    let mut offsets = TopicPartitionList::new();
+    if let Err(error) = offsets
+        .add_partition_offset(topic, partition, Offset::Offset(offset + 1))
+        .and_then(|_| consumer.store_offsets(&offsets))
+    {
+        emit!(KafkaOffsetUpdateFailed { error });
+    }
+}
+
 fn create_consumer(config: &KafkaSourceConfig) -> crate::Result<StreamConsumer> {
     let mut client_config = ClientConfig::new();
     client_config
@@ -244,8 +329,8 @@ fn create_consumer(config: &KafkaSourceConfig) -> crate::Result<StreamConsumer>
 
 #[cfg(test)]
 mod test {
-    use super::{kafka_source, KafkaSourceConfig};
-    use crate::{shutdown::ShutdownSignal, Pipeline};
+    use super::{kafka_source, AcknowledgedOffsets, KafkaSourceConfig};
+    use crate::{event::BatchStatus, shutdown::ShutdownSignal, Pipeline};
 
     #[test]
     fn generate_config() {
@@ -284,6 +369,26 @@ mod test {
         };
         assert!(kafka_source(&config, ShutdownSignal::noop(), Pipeline::new_test().0).is_err());
     }
+
+    #[test]
+    fn offsets_dont_advance_past_errored_messages() {
+        let mut offsets = AcknowledgedOffsets::default();
+        assert!(offsets.finalize(BatchStatus::Delivered, "topic", 0, 1));
+        assert!(!offsets.finalize(BatchStatus::Errored, "topic", 0, 2));
+        assert!(!offsets.finalize(BatchStatus::Delivered, "topic", 0, 3));
+        assert!(!offsets.finalize(BatchStatus::Failed, "topic", 0, 4));
+        assert!(!offsets.finalize(BatchStatus::Delivered, "topic", 0, 5));
+
+        // The other partitions aren't held back.
+        assert!(offsets.finalize(BatchStatus::Delivered, "topic", 1, 3));
+        assert!(offsets.finalize(BatchStatus::Delivered, "other", 0, 3));
+        // Failed messages would fail again, they're not held back either.
+        assert!(offsets.finalize(BatchStatus::Failed, "topic", 1, 4));
+
+        // Consumed again, the errored message is delivered this time.
+        assert!(offsets.finalize(BatchStatus::Delivered, "topic", 0, 2));
+        assert!(offsets.finalize(BatchStatus::Delivered, "topic", 0, 3));
+    }
 }
 
 #[cfg(feature = "kafka-integration-tests")]
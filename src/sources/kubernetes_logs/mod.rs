@@ -15,15 +15,19 @@ use crate::{
     config::{DataType, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription},
     shutdown::ShutdownSignal,
     sources,
+    state::{FileCheckpoints, StateStore},
     transforms::{FunctionTransform, TaskTransform},
     Pipeline,
 };
 use bytes::Bytes;
-use file_source::{FileServer, FileServerShutdown, FingerprintStrategy, Fingerprinter, ReadFrom};
+use file_source::{
+    FileServer, FileServerShutdown, FingerprintStrategy, Fingerprinter, Line, ReadFrom,
+};
 use k8s_openapi::api::core::v1::Pod;
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
 mod k8s_paths_provider;
@@ -78,7 +82,10 @@ pub struct Config {
     /// to the next file.
     /// This allows distributing the reads more or less evenly accross
     /// the files.
-    #[serde(default = "default_max_read_bytes")]
+    #[serde(
+        default = "default_max_read_bytes",
+        deserialize_with = "crate::serde::bytes"
+    )]
     max_read_bytes: usize,
 
     /// This value specifies not exactly the globbing, but interval
@@ -145,6 +152,7 @@ impl SourceConfig for Config {
 struct Source {
     client: k8s::client::Client,
     data_dir: PathBuf,
+    state_store: Arc<dyn StateStore>,
     auto_partial_merge: bool,
     fields_spec: pod_metadata_annotator::FieldsSpec,
     field_selector: String,
@@ -164,6 +172,7 @@ impl Source {
         let client = k8s::client::Client::new(k8s_config)?;
 
         let data_dir = globals.resolve_and_make_data_subdir(None, name)?;
+        let state_store = globals.state_store(None, name)?.into();
 
         let exclude_paths = config
             .exclude_paths_glob_patterns
@@ -184,6 +193,7 @@ impl Source {
         Ok(Self {
             client,
             data_dir,
+            state_store,
             auto_partial_merge: config.auto_partial_merge,
             fields_spec: config.annotation_fields.clone(),
             field_selector,
@@ -203,6 +213,7 @@ impl Source {
         let Self {
             client,
             data_dir,
+            state_store,
             auto_partial_merge,
             fields_spec,
             field_selector,
@@ -268,6 +279,11 @@ impl Source {
             line_delimiter: Bytes::from("\n"),
             // The directory where to keep the checkpoints.
             data_dir,
+            // Checkpoints are not imported from other hosts.
+            checkpoint_import_path: None,
+            // The checkpoints are persisted with the state of the other
+            // components, in the directory above unless configured otherwise.
+            checkpoint_store: Some(Box::new(FileCheckpoints::new(state_store))),
             // This value specifies not exactly the globbing, but interval
             // between the polling the files to watch from the `paths_provider`.
             glob_minimum_cooldown,
@@ -296,17 +312,19 @@ impl Source {
             emitter: FileSourceInternalEventsEmitter,
             // A handle to the current tokio runtime
             handle: tokio::runtime::Handle::current(),
+            // Checkpoints advance as soon as the lines are read.
+            acknowledgements: None,
         };
 
-        let (file_source_tx, file_source_rx) =
-            futures::channel::mpsc::channel::<Vec<(Bytes, String)>>(2);
+        let (file_source_tx, file_source_rx) = futures::channel::mpsc::channel::<Vec<Line>>(2);
 
         let mut parser = parser::build();
         let partial_events_merger = Box::new(partial_events_merger::build(auto_partial_merge));
 
         let events = file_source_rx.map(futures::stream::iter);
         let events = events.flatten();
-        let events = events.map(move |(bytes, file)| {
+        let events = events.map(move |line: Line| {
+            let (bytes, file) = (line.text, line.filename);
             emit!(KubernetesLogsEventReceived {
                 file: &file,
                 byte_size: bytes.len(),
@@ -0,0 +1,358 @@
+use crate::{
+    config::{log_schema, DataType, GlobalOptions, SourceConfig, SourceDescription},
+    event::{Event, LogEvent},
+    internal_events::{MacosUnifiedLogEventReceived, MacosUnifiedLogParseError},
+    shutdown::ShutdownSignal,
+    Pipeline,
+};
+use chrono::{DateTime, Utc};
+use codec::BytesDelimitedCodec;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, process::Stdio, time::Duration};
+use tokio::{process::Command, time::delay_for};
+use tokio_util::codec::FramedRead;
+
+const BACKOFF_DURATION: Duration = Duration::from_secs(1);
+
+/// The format of the timestamps of the `ndjson` style.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f%z";
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct MacosUnifiedLogConfig {
+    predicate: Option<String>,
+    level: Level,
+    event_types: Vec<EventType>,
+    log_path: Option<PathBuf>,
+    host_key: Option<String>,
+}
+
+/// The most verbose level of the messages streamed.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, PartialEq, Eq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    #[derivative(Default)]
+    Default,
+    Info,
+    Debug,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventType {
+    Activity,
+    Log,
+    Trace,
+}
+
+impl Level {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Info => "info",
+            Self::Debug => "debug",
+        }
+    }
+}
+
+impl EventType {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Activity => "activity",
+            Self::Log => "log",
+            Self::Trace => "trace",
+        }
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<MacosUnifiedLogConfig>("macos_unified_log")
+}
+
+impl_generate_config_from_default!(MacosUnifiedLogConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "macos_unified_log")]
+impl SourceConfig for MacosUnifiedLogConfig {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let host_key = self
+            .host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().to_string());
+        let hostname = crate::get_hostname().ok();
+        let command = self.command();
+
+        Ok(Box::pin(run(command, host_key, hostname, shutdown, out)))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "macos_unified_log"
+    }
+}
+
+impl MacosUnifiedLogConfig {
+    /// The path and the arguments of the `log stream` command.
+    fn command(&self) -> (PathBuf, Vec<String>) {
+        let path = self
+            .log_path
+            .clone()
+            .unwrap_or_else(|| "/usr/bin/log".into());
+
+        let mut args = vec![
+            "stream".to_owned(),
+            "--style".to_owned(),
+            "ndjson".to_owned(),
+            "--level".to_owned(),
+            self.level.as_str().to_owned(),
+        ];
+        for event_type in &self.event_types {
+            args.push("--type".to_owned());
+            args.push(event_type.as_str().to_owned());
+        }
+        if let Some(predicate) = &self.predicate {
+            args.push("--predicate".to_owned());
+            args.push(predicate.clone());
+        }
+
+        (path, args)
+    }
+}
+
+/// Runs `log stream` until shutdown, and restarts it when it stops.
+async fn run(
+    (path, args): (PathBuf, Vec<String>),
+    host_key: String,
+    hostname: Option<String>,
+    mut shutdown: ShutdownSignal,
+    out: Pipeline,
+) -> Result<(), ()> {
+    let mut out = out.sink_map_err(|error| error!(message = "Error sending event.", %error));
+
+    loop {
+        info!("Starting log stream.");
+        let mut child = match Command::new(&path)
+            .args(&args)
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(error) => {
+                error!(message = "Error starting log stream.", %error);
+                tokio::select! {
+                    _ = &mut shutdown => return Ok(()),
+                    _ = delay_for(BACKOFF_DURATION) => continue,
+                }
+            }
+        };
+
+        let mut lines = FramedRead::new(
+            child.stdout.take().expect("piped stdout"),
+            BytesDelimitedCodec::new(b'\n'),
+        );
+
+        loop {
+            let line = tokio::select! {
+                _ = &mut shutdown => return Ok(()),
+                line = lines.next() => line,
+            };
+            let line = match line {
+                Some(Ok(line)) => line,
+                Some(Err(error)) => {
+                    error!(message = "Error reading log stream.", %error);
+                    break;
+                }
+                None => break,
+            };
+
+            // The stream starts with a line describing the filter.
+            if !line.starts_with(b"{") {
+                continue;
+            }
+
+            match parse_entry(&line) {
+                Ok(mut log) => {
+                    emit!(MacosUnifiedLogEventReceived {
+                        byte_size: line.len()
+                    });
+                    if let Some(hostname) = &hostname {
+                        log.insert(host_key.as_str(), hostname.clone());
+                    }
+                    out.send(Event::Log(log)).await?;
+                }
+                Err(error) => emit!(MacosUnifiedLogParseError { error }),
+            }
+        }
+
+        warn!("Log stream stopped.");
+        drop(child);
+        tokio::select! {
+            _ = &mut shutdown => return Ok(()),
+            _ = delay_for(BACKOFF_DURATION) => (),
+        }
+    }
+}
+
+/// An entry of the `ndjson` style of `log stream`.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Entry {
+    timestamp: String,
+    event_type: Option<String>,
+    message_type: Option<String>,
+    event_message: Option<String>,
+    format_string: Option<String>,
+    subsystem: Option<String>,
+    category: Option<String>,
+    process_image_path: Option<String>,
+    sender_image_path: Option<String>,
+    #[serde(rename = "processID")]
+    process_id: Option<i64>,
+    #[serde(rename = "threadID")]
+    thread_id: Option<i64>,
+    #[serde(rename = "userID")]
+    user_id: Option<i64>,
+    activity_identifier: Option<i64>,
+    parent_activity_identifier: Option<i64>,
+}
+
+fn parse_entry(line: &[u8]) -> Result<LogEvent, serde_json::Error> {
+    let entry: Entry = serde_json::from_slice(line)?;
+
+    let mut log = LogEvent::default();
+    let timestamp = DateTime::parse_from_str(&entry.timestamp, TIMESTAMP_FORMAT)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now());
+    log.insert(log_schema().timestamp_key(), timestamp);
+    log.insert(
+        log_schema().message_key(),
+        entry.event_message.unwrap_or_default(),
+    );
+
+    let strings = vec![
+        ("event_type", entry.event_type),
+        (
+            "level",
+            entry.message_type.map(|level| level.to_lowercase()),
+        ),
+        ("format_string", entry.format_string),
+        ("subsystem", entry.subsystem),
+        ("category", entry.category),
+        ("process", basename(&entry.process_image_path)),
+        ("process_path", entry.process_image_path),
+        ("sender", basename(&entry.sender_image_path)),
+        ("sender_path", entry.sender_image_path),
+    ];
+    for (key, value) in strings {
+        // Empty strings stand for the values that aren't set.
+        if let Some(value) = value.filter(|value| !value.is_empty()) {
+            log.insert(key, value);
+        }
+    }
+
+    let integers = vec![
+        ("pid", entry.process_id),
+        ("tid", entry.thread_id),
+        ("uid", entry.user_id),
+        ("activity_id", entry.activity_identifier),
+        ("parent_activity_id", entry.parent_activity_identifier),
+    ];
+    for (key, value) in integers {
+        if let Some(value) = value {
+            log.insert(key, value);
+        }
+    }
+
+    Ok(log)
+}
+
+fn basename(path: &Option<String>) -> Option<String> {
+    path.as_ref()
+        .and_then(|path| path.rsplit('/').next())
+        .map(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Value;
+    use chrono::TimeZone;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MacosUnifiedLogConfig>();
+    }
+
+    #[test]
+    fn builds_command() {
+        let config: MacosUnifiedLogConfig = toml::from_str(
+            r#"
+            predicate = 'subsystem == "com.apple.sharing"'
+            level = "info"
+            event_types = ["log", "activity"]
+            "#,
+        )
+        .unwrap();
+
+        let (path, args) = config.command();
+        assert_eq!(path, PathBuf::from("/usr/bin/log"));
+        assert_eq!(
+            args,
+            vec![
+                "stream",
+                "--style",
+                "ndjson",
+                "--level",
+                "info",
+                "--type",
+                "log",
+                "--type",
+                "activity",
+                "--predicate",
+                r#"subsystem == "com.apple.sharing""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_entries() {
+        let log = parse_entry(br#"{"traceID":4257451324882948,"eventMessage":"Connection to 10.0.0.2 established","eventType":"logEvent","source":null,"formatString":"Connection to %{public}@ established","activityIdentifier":0,"subsystem":"com.example.agent","category":"network","threadID":781214,"senderImageUUID":"9A4D0A2B-8D66-3B6E-B0A4-1B4C5C7F3E6D","backtrace":{"frames":[]},"bootUUID":"","processImagePath":"\/usr\/local\/bin\/agent","timestamp":"2021-01-11 06:13:20.123456-0800","senderImagePath":"\/usr\/lib\/libnetwork.dylib","machTimestamp":3217862353153,"messageType":"Error","processImageUUID":"0B4B5A6C-2E3F-3A1B-9C8D-7E6F5A4B3C2D","processID":420,"senderProgramCounter":102345,"parentActivityIdentifier":0,"timezoneName":""}"#).unwrap();
+
+        assert_eq!(
+            log[log_schema().message_key()],
+            "Connection to 10.0.0.2 established".into()
+        );
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Value::Timestamp(Utc.ymd(2021, 1, 11).and_hms_micro(14, 13, 20, 123_456))
+        );
+        assert_eq!(log["level"], "error".into());
+        assert_eq!(log["subsystem"], "com.example.agent".into());
+        assert_eq!(log["process"], "agent".into());
+        assert_eq!(log["sender"], "libnetwork.dylib".into());
+        assert_eq!(log["pid"], Value::Integer(420));
+        assert_eq!(log["tid"], Value::Integer(781214));
+        assert!(log.get("uid").is_none());
+    }
+
+    #[test]
+    fn skips_empty_fields() {
+        let log = parse_entry(br#"{"timestamp":"2021-01-11 06:13:20.000000+0000","eventType":"activityCreateEvent","eventMessage":"","subsystem":"","category":"","processID":1}"#).unwrap();
+
+        assert_eq!(log["event_type"], "activityCreateEvent".into());
+        assert!(log.get("subsystem").is_none());
+        assert!(log.get("level").is_none());
+    }
+}
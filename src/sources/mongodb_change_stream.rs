@@ -0,0 +1,484 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription,
+    },
+    event::{Event, LogEvent, Value},
+    internal_events::{
+        MongoDBChangeStreamCheckpointWriteFailed, MongoDBChangeStreamEventsReceived,
+        MongoDBChangeStreamRequestError,
+    },
+    shutdown::ShutdownSignal,
+    state::StateStore,
+    Pipeline,
+};
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use futures::{stream, SinkExt, StreamExt};
+use mongodb::{
+    bson::{self, doc, Bson, Document},
+    error::Error as MongoError,
+    options::ClientOptions,
+    Client, Database,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use tokio::time;
+
+const CHECKPOINT_FILENAME: &str = "resume_token.bson";
+
+/// How long `getMore` waits for changes before returning an empty batch.
+const MAX_AWAIT_TIME_MS: i64 = 1000;
+
+/// Without changes the resume token still moves forward, it's checkpointed at
+/// this interval so that it doesn't fall out of the oplog.
+const IDLE_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(60);
+
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("invalid endpoint: {}", source))]
+    InvalidEndpoint { source: MongoError },
+    #[snafu(display("invalid client options: {}", source))]
+    InvalidClientOptions { source: MongoError },
+    #[snafu(display("`collection` requires `database` to be set"))]
+    CollectionWithoutDatabase,
+}
+
+#[derive(Debug, Snafu)]
+enum ReadError {
+    #[snafu(display("command failed: {}", source))]
+    CommandFailed { source: MongoError },
+    #[snafu(display("invalid command reply: {}", source))]
+    InvalidReply { source: bson::de::Error },
+}
+
+#[derive(Derivative, Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+enum FullDocument {
+    #[derivative(Default)]
+    Default,
+    UpdateLookup,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MongoDBChangeStreamConfig {
+    endpoint: String,
+    database: Option<String>,
+    collection: Option<String>,
+    #[serde(default)]
+    full_document: FullDocument,
+    data_dir: Option<PathBuf>,
+}
+
+inventory::submit! {
+    SourceDescription::new::<MongoDBChangeStreamConfig>("mongodb_change_stream")
+}
+
+impl GenerateConfig for MongoDBChangeStreamConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            endpoint: "mongodb://localhost:27017".to_owned(),
+            database: None,
+            collection: None,
+            full_document: FullDocument::Default,
+            data_dir: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mongodb_change_stream")]
+impl SourceConfig for MongoDBChangeStreamConfig {
+    async fn build(
+        &self,
+        name: &str,
+        globals: &GlobalOptions,
+        mut shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        if self.collection.is_some() && self.database.is_none() {
+            return Err(BuildError::CollectionWithoutDatabase.into());
+        }
+        let options = ClientOptions::parse(&self.endpoint)
+            .await
+            .context(InvalidEndpoint)?;
+        let client = Client::with_options(options).context(InvalidClientOptions)?;
+
+        let checkpointer = Checkpointer::new(globals.state_store(self.data_dir.as_ref(), name)?);
+        let mut reader = ChangeStream {
+            database: client.database(self.database.as_deref().unwrap_or("admin")),
+            collection: self.collection.clone(),
+            full_document: self.full_document,
+            resume_token: None,
+            cursor: None,
+        };
+        let mut out =
+            out.sink_map_err(|error| error!(message = "Error sending mongodb changes.", %error));
+
+        Ok(Box::pin(async move {
+            reader.resume_token = checkpointer.get().await.map_err(|error| {
+                error!(message = "Failed reading checkpoint.", %error);
+            })?;
+
+            let mut last_checkpoint = Instant::now();
+            loop {
+                let result = tokio::select! {
+                    result = reader.next_batch() => result,
+                    _ = &mut shutdown => break,
+                };
+
+                match result {
+                    Ok(events) => {
+                        let forwarded = !events.is_empty();
+                        if forwarded {
+                            emit!(MongoDBChangeStreamEventsReceived {
+                                count: events.len()
+                            });
+                            out.send_all(&mut stream::iter(events).map(Ok)).await?;
+                        }
+
+                        if forwarded || last_checkpoint.elapsed() >= IDLE_CHECKPOINT_INTERVAL {
+                            if let Some(token) = &reader.resume_token {
+                                if let Err(error) = checkpointer.set(token).await {
+                                    emit!(MongoDBChangeStreamCheckpointWriteFailed { error });
+                                }
+                            }
+                            last_checkpoint = Instant::now();
+                        }
+                    }
+                    Err(error) => {
+                        emit!(MongoDBChangeStreamRequestError {
+                            error: error.to_string()
+                        });
+                        // Reopens the change stream after the last change read.
+                        reader.cursor = None;
+                        tokio::select! {
+                            _ = time::delay_for(RETRY_DELAY) => {},
+                            _ = &mut shutdown => break,
+                        }
+                    }
+                }
+            }
+
+            Ok(())
+        }))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "mongodb_change_stream"
+    }
+}
+
+/// Reads a change stream with the `aggregate` and `getMore` commands.
+struct ChangeStream {
+    /// The database the stream is opened on, `admin` for the whole
+    /// deployment.
+    database: Database,
+    collection: Option<String>,
+    full_document: FullDocument,
+    /// The token of the last change read, or the position the server reached
+    /// when a batch came back empty.
+    resume_token: Option<Document>,
+    cursor: Option<Cursor>,
+}
+
+struct Cursor {
+    id: i64,
+    /// The collection part of the cursor namespace, as `getMore` expects it.
+    collection: String,
+}
+
+/// https://docs.mongodb.com/manual/reference/command/aggregate/#command-response
+#[derive(Debug, Deserialize)]
+struct CursorReply {
+    cursor: CursorBatch,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CursorBatch {
+    id: i64,
+    ns: String,
+    #[serde(rename = "firstBatch", alias = "nextBatch")]
+    batch: Vec<Document>,
+    post_batch_resume_token: Option<Document>,
+}
+
+impl ChangeStream {
+    async fn next_batch(&mut self) -> Result<Vec<Event>, ReadError> {
+        let command = match &self.cursor {
+            Some(cursor) => doc! {
+                "getMore": cursor.id,
+                "collection": cursor.collection.clone(),
+                "maxTimeMS": MAX_AWAIT_TIME_MS,
+            },
+            None => self.aggregate_command(),
+        };
+        let reply = self
+            .database
+            .run_command(command, None)
+            .await
+            .context(CommandFailed)?;
+        let reply: CursorReply = bson::from_document(reply).context(InvalidReply)?;
+        let cursor = reply.cursor;
+
+        let mut events = Vec::with_capacity(cursor.batch.len());
+        for mut change in cursor.batch {
+            if let Some(Bson::Document(token)) = change.remove("_id") {
+                self.resume_token = Some(token);
+            }
+            events.push(change_event(change));
+        }
+        if let Some(token) = cursor.post_batch_resume_token {
+            self.resume_token = Some(token);
+        }
+
+        // The server closes the cursor after an `invalidate` change, the
+        // stream is then opened again after it.
+        self.cursor = match cursor.id {
+            0 => None,
+            id => Some(Cursor {
+                id,
+                collection: cursor
+                    .ns
+                    .splitn(2, '.')
+                    .nth(1)
+                    .unwrap_or_default()
+                    .to_owned(),
+            }),
+        };
+
+        Ok(events)
+    }
+
+    fn aggregate_command(&self) -> Document {
+        let full_document = match self.full_document {
+            FullDocument::Default => "default",
+            FullDocument::UpdateLookup => "updateLookup",
+        };
+        let mut stage = doc! { "fullDocument": full_document };
+        if let Some(token) = &self.resume_token {
+            // Unlike `resumeAfter`, also resumes after an `invalidate` change.
+            stage.insert("startAfter", token.clone());
+        }
+        if self.database.name() == "admin" && self.collection.is_none() {
+            stage.insert("allChangesForCluster", true);
+        }
+
+        let aggregate = match &self.collection {
+            Some(collection) => Bson::from(collection.as_str()),
+            None => Bson::Int32(1),
+        };
+        doc! {
+            "aggregate": aggregate,
+            "pipeline": [{ "$changeStream": stage }],
+            "cursor": {},
+        }
+    }
+}
+
+/// Turns a change event document into an event, leaving out the resume token.
+/// https://docs.mongodb.com/manual/reference/change-events/
+fn change_event(mut change: Document) -> Event {
+    let mut log = LogEvent::default();
+
+    if let Some(Bson::String(op)) = change.remove("operationType") {
+        log.insert("op", op);
+    }
+    if let Some(Bson::Document(mut ns)) = change.remove("ns") {
+        if let Some(Bson::String(database)) = ns.remove("db") {
+            log.insert("database", database);
+        }
+        if let Some(Bson::String(collection)) = ns.remove("coll") {
+            log.insert("collection", collection);
+        }
+    }
+    for (field, key) in &[
+        ("documentKey", "document_key"),
+        ("fullDocument", "full_document"),
+        ("to", "to"),
+    ] {
+        if let Some(value) = change.remove(field) {
+            log.insert(*key, bson_value(value));
+        }
+    }
+    if let Some(Bson::Document(mut update)) = change.remove("updateDescription") {
+        if let Some(fields) = update.remove("updatedFields") {
+            log.insert("updated_fields", bson_value(fields));
+        }
+        if let Some(fields) = update.remove("removedFields") {
+            log.insert("removed_fields", bson_value(fields));
+        }
+    }
+
+    let timestamp = match change.remove("clusterTime") {
+        Some(Bson::Timestamp(timestamp)) => Utc.timestamp(timestamp.time.into(), 0),
+        _ => Utc::now(),
+    };
+    log.insert(log_schema().timestamp_key(), timestamp);
+    log.insert(
+        log_schema().source_type_key(),
+        Bytes::from("mongodb_change_stream"),
+    );
+
+    log.into()
+}
+
+/// Converts the BSON types without an equivalent to strings.
+fn bson_value(value: Bson) -> Value {
+    match value {
+        Bson::Double(value) => Value::Float(value),
+        Bson::String(value) => Value::from(value),
+        Bson::Array(values) => Value::Array(values.into_iter().map(bson_value).collect()),
+        // Field names are kept as is, updated fields are named by their path.
+        Bson::Document(document) => Value::Map(
+            document
+                .into_iter()
+                .map(|(key, value)| (key, bson_value(value)))
+                .collect(),
+        ),
+        Bson::Boolean(value) => Value::Boolean(value),
+        Bson::Null | Bson::Undefined => Value::Null,
+        Bson::Int32(value) => Value::Integer(value.into()),
+        Bson::Int64(value) => Value::Integer(value),
+        Bson::DateTime(value) => Value::Timestamp(value),
+        Bson::ObjectId(value) => Value::from(value.to_hex()),
+        Bson::Binary(value) => Value::Bytes(value.bytes.into()),
+        value => Value::from(value.to_string()),
+    }
+}
+
+struct Checkpointer {
+    store: Box<dyn StateStore>,
+}
+
+impl Checkpointer {
+    fn new(store: Box<dyn StateStore>) -> Self {
+        Checkpointer { store }
+    }
+
+    async fn set(&self, token: &Document) -> crate::Result<()> {
+        let mut buf = Vec::new();
+        token.to_writer(&mut buf)?;
+        self.store.put(CHECKPOINT_FILENAME, buf.into()).await
+    }
+
+    async fn get(&self) -> crate::Result<Option<Document>> {
+        match self.store.get(CHECKPOINT_FILENAME).await? {
+            Some(buf) => Ok(Some(Document::from_reader(&mut buf.as_ref())?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::LocalStore;
+    use mongodb::bson::{oid::ObjectId, Timestamp};
+    use tempfile::tempdir;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MongoDBChangeStreamConfig>();
+    }
+
+    #[test]
+    fn converts_update() {
+        let id = ObjectId::with_string("5f9b2a7c8e1d4a3b2c1d0e0f").unwrap();
+        let event = change_event(doc! {
+            "operationType": "update",
+            "clusterTime": Timestamp { time: 1_600_000_000, increment: 1 },
+            "ns": { "db": "shop", "coll": "orders" },
+            "documentKey": { "_id": id },
+            "updateDescription": {
+                "updatedFields": { "status": "shipped", "items.0.count": 2 },
+                "removedFields": ["note"],
+            },
+        });
+        let log = event.as_log();
+
+        assert_eq!(log["op"], "update".into());
+        assert_eq!(log["database"], "shop".into());
+        assert_eq!(log["collection"], "orders".into());
+        assert_eq!(log["document_key._id"], "5f9b2a7c8e1d4a3b2c1d0e0f".into());
+        assert_eq!(log["updated_fields.status"], "shipped".into());
+        match &log["updated_fields"] {
+            Value::Map(fields) => assert_eq!(fields["items.0.count"], 2.into()),
+            value => panic!("unexpected updated fields {:?}", value),
+        }
+        assert_eq!(log["removed_fields"], Value::Array(vec!["note".into()]));
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp(1_600_000_000, 0).into()
+        );
+        assert!(log.get("full_document").is_none());
+    }
+
+    #[tokio::test]
+    async fn resumes_after_token() {
+        let options = ClientOptions::parse("mongodb://localhost:27017")
+            .await
+            .unwrap();
+        let client = Client::with_options(options).unwrap();
+        let mut reader = ChangeStream {
+            database: client.database("admin"),
+            collection: None,
+            full_document: FullDocument::UpdateLookup,
+            resume_token: None,
+            cursor: None,
+        };
+        assert_eq!(
+            reader.aggregate_command(),
+            doc! {
+                "aggregate": 1,
+                "pipeline": [{ "$changeStream": {
+                    "fullDocument": "updateLookup",
+                    "allChangesForCluster": true,
+                } }],
+                "cursor": {},
+            }
+        );
+
+        reader.database = client.database("shop");
+        reader.collection = Some("orders".to_owned());
+        reader.resume_token = Some(doc! { "_data": "8263" });
+        assert_eq!(
+            reader.aggregate_command(),
+            doc! {
+                "aggregate": "orders",
+                "pipeline": [{ "$changeStream": {
+                    "fullDocument": "updateLookup",
+                    "startAfter": { "_data": "8263" },
+                } }],
+                "cursor": {},
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn checkpointer_works() {
+        let tempdir = tempdir().unwrap();
+        let checkpointer =
+            Checkpointer::new(Box::new(LocalStore::new(tempdir.path().to_path_buf())));
+
+        assert_eq!(checkpointer.get().await.unwrap(), None);
+        checkpointer.set(&doc! { "_data": "8263" }).await.unwrap();
+        assert_eq!(
+            checkpointer.get().await.unwrap(),
+            Some(doc! { "_data": "8263" })
+        );
+    }
+}
@@ -24,7 +24,10 @@ use std::{collections::BTreeMap, future::ready, time::Instant};
 use tokio::time;
 
 mod types;
-use types::{CommandBuildInfo, CommandIsMaster, CommandServerStatus, NodeType};
+use types::{
+    CommandBuildInfo, CommandCollStats, CommandIsMaster, CommandReplSetGetStatus,
+    CommandReplSetGetStatusMember, CommandServerStatus, NodeType,
+};
 
 macro_rules! tags {
     ($tags:expr) => { $tags.clone() };
@@ -61,6 +64,11 @@ enum BuildError {
     InvalidEndpoint { source: MongoError },
     #[snafu(display("invalid client options: {}", source))]
     InvalidClientOptions { source: MongoError },
+    #[snafu(display(
+        "invalid collection {:?}, expected \"<database>.<collection>\"",
+        namespace
+    ))]
+    InvalidCollection { namespace: String },
 }
 
 #[derive(Debug)]
@@ -73,10 +81,15 @@ enum CollectError {
 #[serde(deny_unknown_fields)]
 struct MongoDBMetricsConfig {
     endpoints: Vec<String>,
-    #[serde(default = "default_scrape_interval_secs")]
+    #[serde(
+        default = "default_scrape_interval_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
     scrape_interval_secs: u64,
     #[serde(default = "default_namespace")]
     namespace: String,
+    #[serde(default)]
+    collections: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -84,6 +97,8 @@ struct MongoDBMetrics {
     client: Client,
     endpoint: String,
     namespace: Option<String>,
+    /// Database and collection names to collect `collStats` for.
+    collections: Vec<(String, String)>,
     tags: BTreeMap<String, String>,
 }
 
@@ -112,13 +127,17 @@ impl SourceConfig for MongoDBMetricsConfig {
         out: Pipeline,
     ) -> crate::Result<super::Source> {
         let namespace = Some(self.namespace.clone()).filter(|namespace| !namespace.is_empty());
-
-        let sources = try_join_all(
-            self.endpoints
-                .iter()
-                .map(|endpoint| MongoDBMetrics::new(endpoint, namespace.clone())),
-        )
-        .await?;
+        let collections = self
+            .collections
+            .iter()
+            .map(|namespace| parse_collection(namespace))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let sources =
+            try_join_all(self.endpoints.iter().map(|endpoint| {
+                MongoDBMetrics::new(endpoint, namespace.clone(), collections.clone())
+            }))
+            .await?;
 
         let mut out =
             out.sink_map_err(|error| error!(message = "Error sending mongodb metrics.", %error));
@@ -154,7 +173,11 @@ impl SourceConfig for MongoDBMetricsConfig {
 impl MongoDBMetrics {
     /// Works only with Standalone connection-string. Collect metrics only from specified instance.
     /// https://docs.mongodb.com/manual/reference/connection-string/#standard-connection-string-format
-    async fn new(endpoint: &str, namespace: Option<String>) -> Result<MongoDBMetrics, BuildError> {
+    async fn new(
+        endpoint: &str,
+        namespace: Option<String>,
+        collections: Vec<(String, String)>,
+    ) -> Result<MongoDBMetrics, BuildError> {
         let mut tags: BTreeMap<String, String> = BTreeMap::new();
 
         let mut client_options = ClientOptions::parse(endpoint)
@@ -170,6 +193,7 @@ impl MongoDBMetrics {
             client: Client::with_options(client_options).context(InvalidClientOptions)?,
             endpoint,
             namespace,
+            collections,
             tags,
         })
     }
@@ -232,20 +256,10 @@ impl MongoDBMetrics {
 
     async fn collect(&self) -> stream::BoxStream<'static, Metric> {
         // `up` metric is `1` if collection is successful, otherwise `0`.
-        let (up_value, metrics) = match self.collect_server_status().await {
+        let (up_value, metrics) = match self.collect_metrics().await {
             Ok(metrics) => (1.0, metrics),
             Err(error) => {
-                match error {
-                    CollectError::Mongo(error) => emit!(MongoDBMetricsRequestError {
-                        error,
-                        endpoint: &self.endpoint,
-                    }),
-                    CollectError::Bson(error) => emit!(MongoDBMetricsBsonParseError {
-                        error,
-                        endpoint: &self.endpoint,
-                    }),
-                }
-
+                self.emit_error(error);
                 (0.0, vec![])
             }
         };
@@ -259,6 +273,104 @@ impl MongoDBMetrics {
         .boxed()
     }
 
+    fn emit_error(&self, error: CollectError) {
+        match error {
+            CollectError::Mongo(error) => emit!(MongoDBMetricsRequestError {
+                error,
+                endpoint: &self.endpoint,
+            }),
+            CollectError::Bson(error) => emit!(MongoDBMetricsBsonParseError {
+                error,
+                endpoint: &self.endpoint,
+            }),
+        }
+    }
+
+    async fn collect_metrics(&self) -> Result<Vec<Metric>, CollectError> {
+        let mut metrics = self.collect_server_status().await?;
+
+        if self.get_node_type().await? == NodeType::Replset {
+            metrics.extend(self.collect_replset_status().await?);
+        }
+
+        // A collection that can't be read doesn't make the server down.
+        for (database, collection) in &self.collections {
+            match self.collect_collection_stats(database, collection).await {
+                Ok(collection_metrics) => metrics.extend(collection_metrics),
+                Err(error) => self.emit_error(error),
+            }
+        }
+
+        Ok(metrics)
+    }
+
+    /// Collect replica set members metrics from `replSetGetStatus` command.
+    /// https://docs.mongodb.com/manual/reference/command/replSetGetStatus/
+    async fn collect_replset_status(&self) -> Result<Vec<Metric>, CollectError> {
+        let doc = self
+            .client
+            .database("admin")
+            .run_command(doc! { "replSetGetStatus": 1 }, None)
+            .await
+            .map_err(CollectError::Mongo)?;
+        let status: CommandReplSetGetStatus = from_document(doc).map_err(CollectError::Bson)?;
+
+        let mut metrics = vec![];
+
+        // mongod_replset_member_health
+        for member in &status.members {
+            metrics.push(self.create_metric(
+                "mongod_replset_member_health",
+                gauge!(member.health),
+                tags!(self.tags, "member" => &member.name, "state" => &member.state_str),
+            ));
+        }
+
+        // mongod_replset_member_replication_lag_seconds
+        for (member, lag) in replication_lags(&status.members) {
+            metrics.push(self.create_metric(
+                "mongod_replset_member_replication_lag_seconds",
+                gauge!(lag),
+                tags!(self.tags, "member" => member),
+            ));
+        }
+
+        Ok(metrics)
+    }
+
+    /// Collect collection metrics from `collStats` command.
+    /// https://docs.mongodb.com/manual/reference/command/collStats/
+    async fn collect_collection_stats(
+        &self,
+        database: &str,
+        collection: &str,
+    ) -> Result<Vec<Metric>, CollectError> {
+        let doc = self
+            .client
+            .database(database)
+            .run_command(doc! { "collStats": collection }, None)
+            .await
+            .map_err(CollectError::Mongo)?;
+        let stats: CommandCollStats = from_document(doc).map_err(CollectError::Bson)?;
+
+        let tags = tags!(self.tags, "database" => database, "collection" => collection);
+        Ok(vec![
+            self.create_metric("collection_documents", gauge!(stats.count), tags.clone()),
+            self.create_metric("collection_size_bytes", gauge!(stats.size), tags.clone()),
+            self.create_metric(
+                "collection_storage_size_bytes",
+                gauge!(stats.storage_size),
+                tags.clone(),
+            ),
+            self.create_metric("collection_indexes", gauge!(stats.nindexes), tags.clone()),
+            self.create_metric(
+                "collection_index_size_bytes",
+                gauge!(stats.total_index_size),
+                tags,
+            ),
+        ])
+    }
+
     /// Collect metrics from `serverStatus` command.
     /// https://docs.mongodb.com/manual/reference/command/serverStatus/
     async fn collect_server_status(&self) -> Result<Vec<Metric>, CollectError> {
@@ -952,6 +1064,43 @@ impl MongoDBMetrics {
     }
 }
 
+/// How far behind the primary the last operation applied by each member is, in
+/// seconds. Empty without a primary, during an election for example.
+fn replication_lags(members: &[CommandReplSetGetStatusMember]) -> Vec<(&str, f64)> {
+    const PRIMARY: i32 = 1;
+
+    let primary = match members
+        .iter()
+        .find(|member| member.state == PRIMARY)
+        .and_then(|member| member.optime_date.as_ref())
+    {
+        Some(optime) => optime.timestamp_millis(),
+        None => return vec![],
+    };
+
+    members
+        .iter()
+        .filter_map(|member| {
+            let optime = member.optime_date.as_ref()?.timestamp_millis();
+            Some((member.name.as_str(), (primary - optime) as f64 / 1000.0))
+        })
+        .collect()
+}
+
+/// Splits a `<database>.<collection>` namespace, collection names can contain
+/// dots but database names can't.
+fn parse_collection(namespace: &str) -> Result<(String, String), BuildError> {
+    let mut parts = namespace.splitn(2, '.');
+    match (parts.next(), parts.next()) {
+        (Some(database), Some(collection)) if !database.is_empty() && !collection.is_empty() => {
+            Ok((database.to_owned(), collection.to_owned()))
+        }
+        _ => Err(BuildError::InvalidCollection {
+            namespace: namespace.to_owned(),
+        }),
+    }
+}
+
 /// Remove credentials from endpoint.
 /// URI components: https://docs.mongodb.com/manual/reference/connection-string/#components
 /// It's not possible to use [url::Url](https://docs.rs/url/2.1.1/url/struct.Url.html) because connection string can have multiple hosts.
@@ -1015,6 +1164,7 @@ fn sanitize_endpoint(endpoint: &str, options: &ClientOptions) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
 
     #[test]
     fn generate_config() {
@@ -1028,6 +1178,46 @@ mod tests {
         let endpoint = sanitize_endpoint(endpoint, &client_options);
         assert_eq!(&endpoint, "mongodb://mongos0.example.com:27017,mongos1.example.com:27017,mongos2.example.com:27017/?tls=true");
     }
+
+    #[test]
+    fn parse_collection_test() {
+        assert_eq!(
+            parse_collection("shop.orders.archive").unwrap(),
+            ("shop".to_owned(), "orders.archive".to_owned())
+        );
+        assert!(parse_collection("orders").is_err());
+        assert!(parse_collection(".orders").is_err());
+    }
+
+    #[test]
+    fn replication_lags_test() {
+        let member = |name: &str, state: i32, optime: Option<i64>| {
+            let mut member = doc! { "name": name, "health": 1.0, "state": state, "stateStr": "" };
+            if let Some(optime) = optime {
+                member.insert("optimeDate", Utc.timestamp_millis(optime));
+            }
+            member
+        };
+        let status: CommandReplSetGetStatus = from_document(doc! {
+            "members": [
+                member("a:27017", 2, Some(98_500)),
+                member("b:27017", 1, Some(100_000)),
+                member("c:27017", 7, None),
+            ]
+        })
+        .unwrap();
+
+        assert_eq!(
+            replication_lags(&status.members),
+            vec![("a:27017", 1.5), ("b:27017", 0.0)]
+        );
+
+        let status: CommandReplSetGetStatus = from_document(doc! {
+            "members": [member("a:27017", 2, Some(98_500))]
+        })
+        .unwrap();
+        assert!(replication_lags(&status.members).is_empty());
+    }
 }
 
 #[cfg(all(test, feature = "mongodb_metrics-integration-tests"))]
@@ -1048,6 +1238,7 @@ mod integration_tests {
                 endpoints: vec![endpoint.to_owned()],
                 scrape_interval_secs: 15,
                 namespace: namespace.to_owned(),
+                collections: vec![],
             }
             .build(
                 "default",
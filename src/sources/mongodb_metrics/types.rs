@@ -429,3 +429,32 @@ pub struct CommandServerStatusNetwork {
     pub bytes_out: i64,
     pub num_requests: i64,
 }
+
+/// https://docs.mongodb.com/manual/reference/command/replSetGetStatus/
+#[derive(Debug, Deserialize)]
+pub struct CommandReplSetGetStatus {
+    pub members: Vec<CommandReplSetGetStatusMember>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandReplSetGetStatusMember {
+    pub name: String,
+    pub health: f64,
+    pub state: i32,
+    pub state_str: String,
+    // Not reported by arbiters.
+    pub optime_date: Option<DateTime>,
+}
+
+/// https://docs.mongodb.com/manual/reference/command/collStats/
+/// Sizes are doubles once they no longer fit in an `int`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandCollStats {
+    pub count: f64,
+    pub size: f64,
+    pub storage_size: f64,
+    pub nindexes: f64,
+    pub total_index_size: f64,
+}
@@ -1,22 +1,18 @@
 use crate::{
     config::{DataType, GlobalOptions, SourceConfig, SourceDescription},
-    event::metric::{Metric, MetricKind, MetricValue},
+    event::metric::{Metric, MetricValue},
     http::{Auth, HttpClient},
     internal_events::{
         NginxMetricsCollectCompleted, NginxMetricsRequestError, NginxMetricsStubStatusParseError,
     },
     shutdown::ShutdownSignal,
+    sources::util::{MetricsEndpoint, ScrapeTarget},
     tls::{TlsOptions, TlsSettings},
     Event, Pipeline,
 };
-use bytes::Bytes;
-use chrono::Utc;
-use futures::{future::join_all, stream, SinkExt, StreamExt, TryFutureExt};
-use http::{Request, StatusCode};
-use hyper::{body::to_bytes as body_to_bytes, Body, Uri};
+use futures::{future::join_all, stream, SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use snafu::{ResultExt, Snafu};
-use std::{collections::BTreeMap, convert::TryFrom, future::ready, time::Instant};
+use std::{convert::TryFrom, time::Instant};
 use tokio::time;
 
 pub mod parser;
@@ -38,23 +34,14 @@ macro_rules! gauge {
     };
 }
 
-#[derive(Debug, Snafu)]
-enum NginxBuildError {
-    #[snafu(display("Failed to parse endpoint: {}", source))]
-    HostInvalidUri { source: http::uri::InvalidUri },
-}
-
-#[derive(Debug, Snafu)]
-enum NginxError {
-    #[snafu(display("Invalid response status: {}", status))]
-    InvalidResponseStatus { status: StatusCode },
-}
-
 #[derive(Deserialize, Serialize, Clone, Debug, Default)]
 #[serde(deny_unknown_fields)]
 struct NginxMetricsConfig {
-    endpoints: Vec<String>,
-    #[serde(default = "default_scrape_interval_secs")]
+    endpoints: Vec<MetricsEndpoint>,
+    #[serde(
+        default = "default_scrape_interval_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
     scrape_interval_secs: u64,
     #[serde(default = "default_namespace")]
     namespace: String,
@@ -87,21 +74,23 @@ impl SourceConfig for NginxMetricsConfig {
         out: Pipeline,
     ) -> crate::Result<super::Source> {
         let tls = TlsSettings::from_options(&self.tls)?;
+        if let Some(auth) = &self.auth {
+            auth.validate()?;
+        }
         let http_client = HttpClient::new(tls)?;
 
         let namespace = Some(self.namespace.clone()).filter(|namespace| !namespace.is_empty());
         let mut sources = Vec::with_capacity(self.endpoints.len());
         for endpoint in self.endpoints.iter() {
-            sources.push(NginxMetrics::new(
-                http_client.clone(),
-                endpoint.clone(),
-                self.auth.clone(),
-                namespace.clone(),
-            )?);
+            sources.push(NginxMetrics {
+                http_client: http_client.clone(),
+                auth: self.auth.clone(),
+                target: ScrapeTarget::new(endpoint, namespace.clone())?,
+            });
         }
 
         let mut out =
-            out.sink_map_err(|error| error!(message = "Error sending mongodb metrics.", %error));
+            out.sink_map_err(|error| error!(message = "Error sending nginx metrics.", %error));
 
         let duration = time::Duration::from_secs(self.scrape_interval_secs);
         Ok(Box::pin(async move {
@@ -134,98 +123,56 @@ impl SourceConfig for NginxMetricsConfig {
 #[derive(Debug)]
 struct NginxMetrics {
     http_client: HttpClient,
-    endpoint: String,
     auth: Option<Auth>,
-    namespace: Option<String>,
-    tags: BTreeMap<String, String>,
+    target: ScrapeTarget,
 }
 
 impl NginxMetrics {
-    fn new(
-        http_client: HttpClient,
-        endpoint: String,
-        auth: Option<Auth>,
-        namespace: Option<String>,
-    ) -> crate::Result<Self> {
-        let mut tags = BTreeMap::new();
-        tags.insert("endpoint".into(), endpoint.clone());
-        tags.insert("host".into(), Self::get_endpoint_host(&endpoint)?);
-
-        Ok(Self {
-            http_client,
-            endpoint,
-            auth,
-            namespace,
-            tags,
-        })
-    }
-
-    fn get_endpoint_host(endpoint: &str) -> crate::Result<String> {
-        let uri: Uri = endpoint.parse().context(HostInvalidUri)?;
-        Ok(match (uri.host().unwrap_or(""), uri.port()) {
-            (host, None) => host.to_owned(),
-            (host, Some(port)) => format!("{}:{}", host, port),
-        })
-    }
-
     async fn collect(&self) -> stream::BoxStream<'static, Metric> {
-        let (up_value, metrics) = match self.collect_metrics().await {
-            Ok(metrics) => (1.0, metrics),
-            Err(()) => (0.0, vec![]),
+        let (up, metrics) = match self.collect_metrics().await {
+            Ok(metrics) => (true, metrics),
+            Err(()) => {
+                self.target.scrape_failed();
+                (false, vec![])
+            }
         };
 
-        stream::once(ready(self.create_metric("up", gauge!(up_value))))
+        stream::iter(self.target.status_metrics(up))
             .chain(stream::iter(metrics))
             .boxed()
     }
 
     async fn collect_metrics(&self) -> Result<Vec<Metric>, ()> {
-        let response = self.get_nginx_response().await.map_err(|error| {
-            emit!(NginxMetricsRequestError {
-                error,
-                endpoint: &self.endpoint,
-            })
-        })?;
+        let response = self
+            .target
+            .get(&self.http_client, self.auth.as_ref())
+            .await
+            .map_err(|error| {
+                emit!(NginxMetricsRequestError {
+                    error: error.into(),
+                    endpoint: self.target.endpoint(),
+                })
+            })?;
 
         let status = NginxStubStatus::try_from(String::from_utf8_lossy(&response).as_ref())
             .map_err(|error| {
                 emit!(NginxMetricsStubStatusParseError {
                     error,
-                    endpoint: &self.endpoint,
+                    endpoint: self.target.endpoint(),
                 })
             })?;
 
+        let target = &self.target;
         Ok(vec![
-            self.create_metric("connections_active", gauge!(status.active)),
-            self.create_metric("connections_accepted_total", counter!(status.accepts)),
-            self.create_metric("connections_handled_total", counter!(status.handled)),
-            self.create_metric("http_requests_total", counter!(status.requests)),
-            self.create_metric("connections_reading", gauge!(status.reading)),
-            self.create_metric("connections_writing", gauge!(status.writing)),
-            self.create_metric("connections_waiting", gauge!(status.waiting)),
+            target.create_metric("connections_active", gauge!(status.active)),
+            target.create_metric("connections_accepted_total", counter!(status.accepts)),
+            target.create_metric("connections_handled_total", counter!(status.handled)),
+            target.create_metric("http_requests_total", counter!(status.requests)),
+            target.create_metric("connections_reading", gauge!(status.reading)),
+            target.create_metric("connections_writing", gauge!(status.writing)),
+            target.create_metric("connections_waiting", gauge!(status.waiting)),
         ])
     }
-
-    async fn get_nginx_response(&self) -> crate::Result<Bytes> {
-        let mut request = Request::get(&self.endpoint).body(Body::empty())?;
-        if let Some(auth) = &self.auth {
-            auth.apply(&mut request);
-        }
-
-        let response = self.http_client.send(request).await?;
-        let (parts, body) = response.into_parts();
-        match parts.status {
-            StatusCode::OK => body_to_bytes(body).err_into().await,
-            status => Err(Box::new(NginxError::InvalidResponseStatus { status })),
-        }
-    }
-
-    fn create_metric(&self, name: &str, value: MetricValue) -> Metric {
-        Metric::new(name, MetricKind::Absolute, value)
-            .with_namespace(self.namespace.clone())
-            .with_tags(Some(self.tags.clone()))
-            .with_timestamp(Some(Utc::now()))
-    }
 }
 
 #[cfg(test)]
@@ -250,7 +197,7 @@ mod integration_tests {
 
         tokio::spawn(async move {
             NginxMetricsConfig {
-                endpoints: vec![endpoint.to_owned()],
+                endpoints: vec![endpoint.into()],
                 scrape_interval_secs: 15,
                 namespace: "vector_nginx".to_owned(),
                 tls: None,
@@ -281,7 +228,7 @@ mod integration_tests {
             }
         }
 
-        assert_eq!(events.len(), 8);
+        assert_eq!(events.len(), 9);
     }
 
     #[tokio::test]
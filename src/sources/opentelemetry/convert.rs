@@ -0,0 +1,578 @@
+//! Conversion of the OTLP requests into events.
+//!
+//! The resource of the signals and their instrumentation scope are kept on
+//! every event: under the `resource` and `scope` fields of logs and spans,
+//! and as `resource.*` and `scope.*` tags of metrics.
+
+use crate::{
+    config::log_schema,
+    event::{
+        metric::{Bucket, Metric, MetricKind, MetricTags, MetricValue, Quantile},
+        trace::{self, SpanLink, TraceEvent},
+        Event, LogEvent, Value,
+    },
+    opentelemetry::proto::{
+        self, any_value, metric, number_data_point, span::SpanKind, status::StatusCode,
+    },
+};
+use chrono::{DateTime, TimeZone, Utc};
+use std::collections::BTreeMap;
+
+const SOURCE_TYPE: &str = "opentelemetry";
+
+/// The `AggregationTemporality` of sums and histograms whose points are the
+/// change since the previous one.
+const AGGREGATION_TEMPORALITY_DELTA: i32 = 1;
+
+pub fn decode_logs(request: proto::ExportLogsServiceRequest) -> Vec<Event> {
+    let mut events = Vec::new();
+    for resource_logs in request.resource_logs {
+        let resource = resource_attributes(resource_logs.resource);
+        for scope_logs in resource_logs.scope_logs {
+            let scope = scope_fields(scope_logs.scope);
+            for record in scope_logs.log_records {
+                events.push(decode_log_record(record, &resource, &scope).into());
+            }
+        }
+    }
+    events
+}
+
+fn decode_log_record(
+    record: proto::LogRecord,
+    resource: &BTreeMap<String, Value>,
+    scope: &BTreeMap<String, Value>,
+) -> LogEvent {
+    let mut log = LogEvent::default();
+
+    let body = record.body.map_or(Value::Null, any_value_to_value);
+    log.insert(log_schema().message_key(), body);
+
+    let time = timestamp(record.time_unix_nano)
+        .or_else(|| timestamp(record.observed_time_unix_nano))
+        .unwrap_or_else(Utc::now);
+    log.insert(log_schema().timestamp_key(), time);
+    if let Some(observed) = timestamp(record.observed_time_unix_nano) {
+        log.insert_flat("observed_timestamp", observed);
+    }
+
+    if !record.severity_text.is_empty() {
+        log.insert_flat("severity_text", record.severity_text);
+    }
+    if record.severity_number != 0 {
+        log.insert_flat("severity_number", record.severity_number);
+    }
+    if !record.trace_id.is_empty() {
+        log.insert_flat(trace::TRACE_ID, hex(&record.trace_id));
+    }
+    if !record.span_id.is_empty() {
+        log.insert_flat(trace::SPAN_ID, hex(&record.span_id));
+    }
+
+    insert_map(&mut log, trace::ATTRIBUTES, attributes(record.attributes));
+    insert_map(&mut log, trace::RESOURCE, resource.clone());
+    insert_map(&mut log, "scope", scope.clone());
+    log.insert(log_schema().source_type_key(), SOURCE_TYPE);
+
+    log
+}
+
+pub fn decode_metrics(request: proto::ExportMetricsServiceRequest) -> Vec<Event> {
+    let mut events = Vec::new();
+    for resource_metrics in request.resource_metrics {
+        let mut tags = MetricTags::new();
+        for (key, value) in resource_attributes(resource_metrics.resource) {
+            tags.insert(format!("resource.{}", key), value.to_string_lossy());
+        }
+
+        for scope_metrics in resource_metrics.scope_metrics {
+            let mut tags = tags.clone();
+            for (key, value) in scope_fields(scope_metrics.scope) {
+                tags.insert(format!("scope.{}", key), value.to_string_lossy());
+            }
+
+            for metric in scope_metrics.metrics {
+                events.extend(decode_metric(metric, &tags).into_iter().map(Event::from));
+            }
+        }
+    }
+    events
+}
+
+fn decode_metric(metric: proto::Metric, tags: &MetricTags) -> Vec<Metric> {
+    let name = metric.name;
+    let new_metric = |kind, value, attributes: Vec<proto::KeyValue>, time_unix_nano| {
+        let mut tags = tags.clone();
+        for (key, attribute) in self::attributes(attributes) {
+            tags.insert(key, attribute.to_string_lossy());
+        }
+        Metric::new(name.clone(), kind, value)
+            .with_tags(Some(tags))
+            .with_timestamp(timestamp(time_unix_nano))
+    };
+
+    match metric.data {
+        Some(metric::Data::Gauge(gauge)) => gauge
+            .data_points
+            .into_iter()
+            .map(|point| {
+                let value = MetricValue::Gauge {
+                    value: number_value(point.value),
+                };
+                new_metric(
+                    MetricKind::Absolute,
+                    value,
+                    point.attributes,
+                    point.time_unix_nano,
+                )
+            })
+            .collect(),
+        Some(metric::Data::Sum(sum)) => {
+            let kind = metric_kind(sum.aggregation_temporality);
+            sum.data_points
+                .into_iter()
+                .map(|point| {
+                    let value = number_value(point.value);
+                    let value = if sum.is_monotonic {
+                        MetricValue::Counter { value }
+                    } else {
+                        MetricValue::Gauge { value }
+                    };
+                    new_metric(kind, value, point.attributes, point.time_unix_nano)
+                })
+                .collect()
+        }
+        Some(metric::Data::Histogram(histogram)) => {
+            let kind = metric_kind(histogram.aggregation_temporality);
+            histogram
+                .data_points
+                .into_iter()
+                .map(|point| {
+                    // The last bucket counts the values above the highest
+                    // bound, which are only part of the total count.
+                    let buckets = point
+                        .explicit_bounds
+                        .iter()
+                        .zip(&point.bucket_counts)
+                        .map(|(upper_limit, count)| Bucket {
+                            upper_limit: *upper_limit,
+                            count: *count as u32,
+                        })
+                        .collect();
+                    let value = MetricValue::AggregatedHistogram {
+                        buckets,
+                        count: point.count as u32,
+                        sum: point.sum,
+                    };
+                    new_metric(kind, value, point.attributes, point.time_unix_nano)
+                })
+                .collect()
+        }
+        Some(metric::Data::Summary(summary)) => summary
+            .data_points
+            .into_iter()
+            .map(|point| {
+                let quantiles = point
+                    .quantile_values
+                    .iter()
+                    .map(|quantile| Quantile {
+                        upper_limit: quantile.quantile,
+                        value: quantile.value,
+                    })
+                    .collect();
+                let value = MetricValue::AggregatedSummary {
+                    quantiles,
+                    count: point.count as u32,
+                    sum: point.sum,
+                };
+                new_metric(
+                    MetricKind::Absolute,
+                    value,
+                    point.attributes,
+                    point.time_unix_nano,
+                )
+            })
+            .collect(),
+        Some(metric::Data::ExponentialHistogram(_)) => {
+            warn!(
+                message = "Exponential histograms are not supported, dropping metric.",
+                metric = %name,
+                internal_log_rate_secs = 30
+            );
+            Vec::new()
+        }
+        None => Vec::new(),
+    }
+}
+
+fn metric_kind(aggregation_temporality: i32) -> MetricKind {
+    if aggregation_temporality == AGGREGATION_TEMPORALITY_DELTA {
+        MetricKind::Incremental
+    } else {
+        MetricKind::Absolute
+    }
+}
+
+fn number_value(value: Option<number_data_point::Value>) -> f64 {
+    match value {
+        Some(number_data_point::Value::AsDouble(value)) => value,
+        Some(number_data_point::Value::AsInt(value)) => value as f64,
+        None => 0.0,
+    }
+}
+
+pub fn decode_traces(request: proto::ExportTraceServiceRequest) -> Vec<Event> {
+    let mut events = Vec::new();
+    for resource_spans in request.resource_spans {
+        let resource = resource_attributes(resource_spans.resource);
+        for scope_spans in resource_spans.scope_spans {
+            let scope = scope_fields(scope_spans.scope);
+            for span in scope_spans.spans {
+                events.push(decode_span(span, &resource, &scope).into());
+            }
+        }
+    }
+    events
+}
+
+fn decode_span(
+    span: proto::Span,
+    resource: &BTreeMap<String, Value>,
+    scope: &BTreeMap<String, Value>,
+) -> TraceEvent {
+    let mut event = TraceEvent::new(hex(&span.trace_id), hex(&span.span_id)).with_name(span.name);
+    if !span.parent_span_id.is_empty() {
+        event = event.with_parent_span_id(hex(&span.parent_span_id));
+    }
+    if let (Some(start), Some(end)) = (
+        timestamp(span.start_time_unix_nano),
+        timestamp(span.end_time_unix_nano),
+    ) {
+        event = event.with_times(start, end);
+    }
+    for link in span.links {
+        event = event.with_link(SpanLink {
+            trace_id: hex(&link.trace_id),
+            span_id: hex(&link.span_id),
+            attributes: attributes(link.attributes),
+        });
+    }
+
+    let kind = match SpanKind::from_i32(span.kind) {
+        Some(SpanKind::Internal) => Some("internal"),
+        Some(SpanKind::Server) => Some("server"),
+        Some(SpanKind::Client) => Some("client"),
+        Some(SpanKind::Producer) => Some("producer"),
+        Some(SpanKind::Consumer) => Some("consumer"),
+        Some(SpanKind::Unspecified) | None => None,
+    };
+
+    let log = event.as_mut_log();
+    if let Some(kind) = kind {
+        log.insert_flat(trace::KIND, kind);
+    }
+    if !span.trace_state.is_empty() {
+        log.insert_flat("trace_state", span.trace_state);
+    }
+    if let Some(status) = span.status {
+        let code = match StatusCode::from_i32(status.code) {
+            Some(StatusCode::Ok) => Some("ok"),
+            Some(StatusCode::Error) => Some("error"),
+            Some(StatusCode::Unset) | None => None,
+        };
+        if let Some(code) = code {
+            let mut fields = BTreeMap::new();
+            fields.insert("code".to_owned(), Value::from(code));
+            if !status.message.is_empty() {
+                fields.insert("message".to_owned(), status.message.into());
+            }
+            log.insert_flat(trace::STATUS, fields);
+        }
+    }
+    if !span.events.is_empty() {
+        let events = span
+            .events
+            .into_iter()
+            .map(|span_event| {
+                let mut fields = BTreeMap::new();
+                fields.insert("name".to_owned(), Value::from(span_event.name));
+                if let Some(timestamp) = timestamp(span_event.time_unix_nano) {
+                    fields.insert("timestamp".to_owned(), timestamp.into());
+                }
+                fields.insert(
+                    trace::ATTRIBUTES.to_owned(),
+                    Value::Map(attributes(span_event.attributes)),
+                );
+                Value::Map(fields)
+            })
+            .collect::<Vec<_>>();
+        log.insert_flat("events", events);
+    }
+
+    insert_map(log, trace::ATTRIBUTES, attributes(span.attributes));
+    insert_map(log, trace::RESOURCE, resource.clone());
+    insert_map(log, "scope", scope.clone());
+    log.insert(log_schema().source_type_key(), SOURCE_TYPE);
+
+    event
+}
+
+/// Inserts `map` as a whole, as attribute keys usually contain dots.
+fn insert_map(log: &mut LogEvent, key: &str, map: BTreeMap<String, Value>) {
+    if !map.is_empty() {
+        log.insert_flat(key, map);
+    }
+}
+
+fn resource_attributes(resource: Option<proto::Resource>) -> BTreeMap<String, Value> {
+    resource.map_or_else(BTreeMap::new, |resource| attributes(resource.attributes))
+}
+
+fn scope_fields(scope: Option<proto::InstrumentationScope>) -> BTreeMap<String, Value> {
+    let mut fields = BTreeMap::new();
+    if let Some(scope) = scope {
+        if !scope.name.is_empty() {
+            fields.insert("name".to_owned(), scope.name.into());
+        }
+        if !scope.version.is_empty() {
+            fields.insert("version".to_owned(), scope.version.into());
+        }
+        if !scope.attributes.is_empty() {
+            fields.insert(
+                trace::ATTRIBUTES.to_owned(),
+                Value::Map(attributes(scope.attributes)),
+            );
+        }
+    }
+    fields
+}
+
+fn attributes(attributes: Vec<proto::KeyValue>) -> BTreeMap<String, Value> {
+    attributes
+        .into_iter()
+        .map(|attribute| {
+            let value = attribute.value.map_or(Value::Null, any_value_to_value);
+            (attribute.key, value)
+        })
+        .collect()
+}
+
+fn any_value_to_value(value: proto::AnyValue) -> Value {
+    match value.value {
+        Some(any_value::Value::StringValue(value)) => value.into(),
+        Some(any_value::Value::BoolValue(value)) => value.into(),
+        Some(any_value::Value::IntValue(value)) => value.into(),
+        Some(any_value::Value::DoubleValue(value)) => value.into(),
+        Some(any_value::Value::ArrayValue(array)) => {
+            Value::Array(array.values.into_iter().map(any_value_to_value).collect())
+        }
+        Some(any_value::Value::KvlistValue(list)) => Value::Map(attributes(list.values)),
+        Some(any_value::Value::BytesValue(value)) => Value::Bytes(value.into()),
+        None => Value::Null,
+    }
+}
+
+/// OTLP timestamps are nanoseconds since the epoch, zero when unknown.
+fn timestamp(nanos: u64) -> Option<DateTime<Utc>> {
+    if nanos == 0 {
+        None
+    } else {
+        Some(Utc.timestamp_nanos(nanos as i64))
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string_attribute(key: &str, value: &str) -> proto::KeyValue {
+        proto::KeyValue {
+            key: key.to_owned(),
+            value: Some(proto::AnyValue {
+                value: Some(any_value::Value::StringValue(value.to_owned())),
+            }),
+        }
+    }
+
+    fn resource() -> Option<proto::Resource> {
+        Some(proto::Resource {
+            attributes: vec![string_attribute("service.name", "checkout")],
+        })
+    }
+
+    fn scope() -> Option<proto::InstrumentationScope> {
+        Some(proto::InstrumentationScope {
+            name: "io.opentelemetry.http".to_owned(),
+            version: "1.0.0".to_owned(),
+            attributes: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn decodes_logs() {
+        let request = proto::ExportLogsServiceRequest {
+            resource_logs: vec![proto::ResourceLogs {
+                resource: resource(),
+                scope_logs: vec![proto::ScopeLogs {
+                    scope: scope(),
+                    log_records: vec![proto::LogRecord {
+                        time_unix_nano: 1_600_000_000_000_000_000,
+                        severity_number: 9,
+                        severity_text: "INFO".to_owned(),
+                        body: Some(proto::AnyValue {
+                            value: Some(any_value::Value::StringValue("hello".to_owned())),
+                        }),
+                        attributes: vec![string_attribute("http.method", "GET")],
+                        trace_id: vec![0xab; 16],
+                        span_id: vec![0x01, 0x02],
+                        ..Default::default()
+                    }],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        };
+
+        let events = decode_logs(request);
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "hello".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp(1_600_000_000, 0).into()
+        );
+        assert_eq!(log["severity_text"], "INFO".into());
+        assert_eq!(log["severity_number"], 9.into());
+        assert_eq!(log["trace_id"], "ab".repeat(16).into());
+        assert_eq!(log["span_id"], "0102".into());
+        assert_eq!(log.get_flat("attributes").unwrap(), &{
+            let mut attributes = BTreeMap::new();
+            attributes.insert("http.method".to_owned(), Value::from("GET"));
+            Value::Map(attributes)
+        });
+        assert_eq!(log.get_flat("resource").unwrap(), &{
+            let mut resource = BTreeMap::new();
+            resource.insert("service.name".to_owned(), Value::from("checkout"));
+            Value::Map(resource)
+        });
+        assert_eq!(log["scope.name"], "io.opentelemetry.http".into());
+        assert_eq!(log[log_schema().source_type_key()], "opentelemetry".into());
+    }
+
+    #[test]
+    fn decodes_metrics() {
+        let request = proto::ExportMetricsServiceRequest {
+            resource_metrics: vec![proto::ResourceMetrics {
+                resource: resource(),
+                scope_metrics: vec![proto::ScopeMetrics {
+                    scope: scope(),
+                    metrics: vec![
+                        proto::Metric {
+                            name: "requests".to_owned(),
+                            data: Some(metric::Data::Sum(proto::Sum {
+                                data_points: vec![proto::NumberDataPoint {
+                                    attributes: vec![string_attribute("code", "200")],
+                                    value: Some(number_data_point::Value::AsInt(3)),
+                                    ..Default::default()
+                                }],
+                                aggregation_temporality: AGGREGATION_TEMPORALITY_DELTA,
+                                is_monotonic: true,
+                            })),
+                            ..Default::default()
+                        },
+                        proto::Metric {
+                            name: "latency".to_owned(),
+                            data: Some(metric::Data::Histogram(proto::Histogram {
+                                data_points: vec![proto::HistogramDataPoint {
+                                    count: 4,
+                                    sum: 2.5,
+                                    bucket_counts: vec![1, 2, 1],
+                                    explicit_bounds: vec![0.5, 1.0],
+                                    ..Default::default()
+                                }],
+                                aggregation_temporality: 2,
+                            })),
+                            ..Default::default()
+                        },
+                    ],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        };
+
+        let events = decode_metrics(request);
+        assert_eq!(events.len(), 2);
+
+        let requests = events[0].as_metric();
+        assert_eq!(requests.name(), "requests");
+        assert_eq!(requests.data.kind, MetricKind::Incremental);
+        assert_eq!(requests.data.value, MetricValue::Counter { value: 3.0 });
+        let tags = requests.tags().unwrap();
+        assert_eq!(tags["code"], "200");
+        assert_eq!(tags["resource.service.name"], "checkout");
+        assert_eq!(tags["scope.version"], "1.0.0");
+
+        let latency = events[1].as_metric();
+        assert_eq!(latency.data.kind, MetricKind::Absolute);
+        assert_eq!(
+            latency.data.value,
+            MetricValue::AggregatedHistogram {
+                buckets: vec![
+                    Bucket {
+                        upper_limit: 0.5,
+                        count: 1
+                    },
+                    Bucket {
+                        upper_limit: 1.0,
+                        count: 2
+                    },
+                ],
+                count: 4,
+                sum: 2.5,
+            }
+        );
+    }
+
+    #[test]
+    fn decodes_traces() {
+        let request = proto::ExportTraceServiceRequest {
+            resource_spans: vec![proto::ResourceSpans {
+                resource: resource(),
+                scope_spans: vec![proto::ScopeSpans {
+                    scope: scope(),
+                    spans: vec![proto::Span {
+                        trace_id: vec![0x0a; 16],
+                        span_id: vec![0x0b; 8],
+                        parent_span_id: vec![0x0c; 8],
+                        name: "GET /cart".to_owned(),
+                        kind: SpanKind::Server as i32,
+                        start_time_unix_nano: 1_600_000_000_000_000_000,
+                        end_time_unix_nano: 1_600_000_000_500_000_000,
+                        status: Some(proto::Status {
+                            message: "timeout".to_owned(),
+                            code: StatusCode::Error as i32,
+                        }),
+                        ..Default::default()
+                    }],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        };
+
+        let events = decode_traces(request);
+        assert_eq!(events.len(), 1);
+        let span = events[0].as_trace();
+        assert_eq!(span.trace_id(), Some("0a".repeat(16)));
+        assert_eq!(span.parent_span_id(), Some("0c".repeat(8)));
+        assert_eq!(span.name(), Some("GET /cart".to_owned()));
+        assert_eq!(span.duration(), Some(chrono::Duration::milliseconds(500)));
+        assert!(span.is_error());
+        assert_eq!(span.as_log()[trace::KIND], "server".into());
+    }
+}
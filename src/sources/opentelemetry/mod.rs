@@ -0,0 +1,579 @@
+//! OpenTelemetry source
+//!
+//! Receives logs, metrics and traces from OpenTelemetry SDKs and collectors,
+//! as the unary `Export` calls of the OTLP/gRPC services.
+//!
+//! https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/protocol/otlp.md
+//!
+//! The gRPC protocol is served directly over HTTP/2: each request holds a
+//! single length-prefixed message, optionally gzip-compressed, and the status
+//! of the call is sent back in the `grpc-status` trailer.
+
+mod convert;
+
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, Resource, SourceConfig, SourceDescription},
+    internal_events::{OpenTelemetryEventsReceived, OpenTelemetryRequestError},
+    opentelemetry::{
+        proto, GRPC_LOGS_PATH, GRPC_MESSAGE_PREFIX_LEN, GRPC_METRICS_PATH, GRPC_TRACES_PATH,
+    },
+    shutdown::ShutdownSignal,
+    tls::{MaybeTlsSettings, TlsConfig},
+    Event, Pipeline,
+};
+use bytes::{Buf, Bytes, BytesMut};
+use flate2::read::GzDecoder;
+use futures::{FutureExt, SinkExt, StreamExt};
+use hyper::{
+    body::HttpBody,
+    header::{HeaderMap, HeaderValue, CONTENT_LENGTH},
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server,
+};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::Infallible,
+    io::Read,
+    net::SocketAddr,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tracing_futures::Instrument;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OpenTelemetryConfig {
+    address: SocketAddr,
+    #[serde(
+        default = "default_max_message_bytes",
+        deserialize_with = "crate::serde::bytes"
+    )]
+    max_message_bytes: usize,
+    tls: Option<TlsConfig>,
+}
+
+/// The default limit of gRPC servers.
+fn default_max_message_bytes() -> usize {
+    4 * 1024 * 1024
+}
+
+inventory::submit! {
+    SourceDescription::new::<OpenTelemetryConfig>("opentelemetry")
+}
+
+impl GenerateConfig for OpenTelemetryConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            address: "0.0.0.0:4317".parse().unwrap(),
+            max_message_bytes: default_max_message_bytes(),
+            tls: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "opentelemetry")]
+impl SourceConfig for OpenTelemetryConfig {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let tls = MaybeTlsSettings::from_config(&self.tls, true)?;
+        Ok(Box::pin(run(
+            self.address,
+            tls,
+            self.max_message_bytes,
+            out,
+            shutdown,
+        )))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn source_type(&self) -> &'static str {
+        "opentelemetry"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::tcp(self.address)]
+    }
+}
+
+async fn run(
+    address: SocketAddr,
+    tls: MaybeTlsSettings,
+    max_message_bytes: usize,
+    out: Pipeline,
+    shutdown: ShutdownSignal,
+) -> Result<(), ()> {
+    let span = crate::trace::current_span();
+    let service = make_service_fn(move |_| {
+        let out = out.clone();
+        let span = span.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                handle(request, out.clone(), max_message_bytes)
+                    .map(Ok::<_, Infallible>)
+                    .instrument(span.clone())
+            }))
+        }
+    });
+
+    info!(message = "Building gRPC server.", address = %address);
+
+    let listener = tls
+        .bind(&address)
+        .await
+        .map_err(|error| error!(message = "Failed to bind listener.", %error))?;
+    Server::builder(hyper::server::accept::from_stream(listener.accept_stream()))
+        .http2_only(true)
+        .serve(service)
+        .with_graceful_shutdown(shutdown.clone().map(|_| ()))
+        .await
+        .map_err(|error| error!(message = "gRPC server failed.", %error))?;
+
+    // We need to drop the last copy of ShutdownSignalToken only after server has shut down.
+    drop(shutdown);
+    Ok(())
+}
+
+/// The gRPC status codes the source answers with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Code {
+    Ok = 0,
+    InvalidArgument = 3,
+    ResourceExhausted = 8,
+    Unimplemented = 12,
+    Unavailable = 14,
+}
+
+#[derive(Debug, PartialEq)]
+struct Status {
+    code: Code,
+    message: String,
+}
+
+impl Status {
+    fn new(code: Code, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+async fn handle(
+    request: Request<Body>,
+    mut out: Pipeline,
+    max_message_bytes: usize,
+) -> Response<GrpcBody> {
+    let path = request.uri().path().to_owned();
+    let encoding = request
+        .headers()
+        .get("grpc-encoding")
+        .and_then(|encoding| encoding.to_str().ok())
+        .map(str::to_owned);
+
+    let events = match read_body(request, max_message_bytes).await {
+        Ok(body) => decode_request(&path, encoding.as_deref(), body, max_message_bytes),
+        Err(status) => Err(status),
+    };
+
+    let status = match events {
+        Ok(events) => out
+            .send_all(&mut futures::stream::iter(events).map(Ok))
+            .await
+            .map_err(|_| {
+                // can only fail if receiving end disconnected, so we are shutting down,
+                // probably not gracefully.
+                error!(message = "Failed to forward events, downstream is closed.");
+                Status::new(Code::Unavailable, "Vector is shutting down")
+            }),
+        Err(status) => Err(status),
+    };
+
+    match status {
+        Ok(()) => GrpcBody::ok(),
+        Err(status) => {
+            emit!(OpenTelemetryRequestError {
+                path: &path,
+                error: &status.message,
+            });
+            GrpcBody::error(status)
+        }
+    }
+}
+
+/// Reads the body of a request holding a message of at most
+/// `max_message_bytes`, without buffering more than that.
+async fn read_body(request: Request<Body>, max_message_bytes: usize) -> Result<Bytes, Status> {
+    let limit = max_message_bytes + GRPC_MESSAGE_PREFIX_LEN;
+    let too_large = || {
+        Status::new(
+            Code::ResourceExhausted,
+            format!(
+                "request is larger than the limit of {} bytes",
+                max_message_bytes
+            ),
+        )
+    };
+
+    let content_length = request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|length| length.to_str().ok())
+        .and_then(|length| length.parse::<usize>().ok());
+    if content_length.map_or(false, |length| length > limit) {
+        return Err(too_large());
+    }
+
+    let mut body = request.into_body();
+    let mut buffer = BytesMut::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|error| Status::new(Code::InvalidArgument, error.to_string()))?;
+        if buffer.len() + chunk.len() > limit {
+            return Err(too_large());
+        }
+        buffer.extend_from_slice(&chunk);
+    }
+    Ok(buffer.freeze())
+}
+
+fn decode_request(
+    path: &str,
+    encoding: Option<&str>,
+    body: Bytes,
+    max_message_bytes: usize,
+) -> Result<Vec<Event>, Status> {
+    let signal = match path {
+        GRPC_LOGS_PATH => "logs",
+        GRPC_METRICS_PATH => "metrics",
+        GRPC_TRACES_PATH => "traces",
+        _ => {
+            return Err(Status::new(
+                Code::Unimplemented,
+                format!("unknown method {}", path),
+            ))
+        }
+    };
+
+    let message = decode_message(encoding, body, max_message_bytes)?;
+    let byte_size = message.len();
+    let invalid = |error: prost::DecodeError| Status::new(Code::InvalidArgument, error.to_string());
+    let events = match signal {
+        "logs" => proto::ExportLogsServiceRequest::decode(message).map(convert::decode_logs),
+        "metrics" => {
+            proto::ExportMetricsServiceRequest::decode(message).map(convert::decode_metrics)
+        }
+        _ => proto::ExportTraceServiceRequest::decode(message).map(convert::decode_traces),
+    }
+    .map_err(invalid)?;
+
+    emit!(OpenTelemetryEventsReceived {
+        signal,
+        count: events.len(),
+        byte_size,
+    });
+    Ok(events)
+}
+
+/// Reads the single message of a unary call, decompressing it if needed.
+fn decode_message(
+    encoding: Option<&str>,
+    mut body: Bytes,
+    max_message_bytes: usize,
+) -> Result<Bytes, Status> {
+    if body.len() < GRPC_MESSAGE_PREFIX_LEN {
+        return Err(Status::new(Code::InvalidArgument, "missing message"));
+    }
+    let compressed = body.get_u8() == 1;
+    let len = body.get_u32() as usize;
+    if len > max_message_bytes {
+        return Err(Status::new(
+            Code::ResourceExhausted,
+            format!(
+                "message of {} bytes is larger than the limit of {} bytes",
+                len, max_message_bytes
+            ),
+        ));
+    }
+    if body.len() < len {
+        return Err(Status::new(Code::InvalidArgument, "truncated message"));
+    }
+    let message = body.split_to(len);
+
+    if !compressed {
+        return Ok(message);
+    }
+    match encoding {
+        Some("gzip") => {
+            let mut decompressed = Vec::new();
+            GzDecoder::new(message.as_ref())
+                .take(max_message_bytes as u64 + 1)
+                .read_to_end(&mut decompressed)
+                .map_err(|error| Status::new(Code::InvalidArgument, error.to_string()))?;
+            if decompressed.len() > max_message_bytes {
+                return Err(Status::new(
+                    Code::ResourceExhausted,
+                    format!(
+                        "decompressed message is larger than the limit of {} bytes",
+                        max_message_bytes
+                    ),
+                ));
+            }
+            Ok(decompressed.into())
+        }
+        encoding => Err(Status::new(
+            Code::Unimplemented,
+            format!("unsupported message encoding {:?}", encoding),
+        )),
+    }
+}
+
+/// The body of a response to a unary call: the empty `Export*ServiceResponse`
+/// message on success, followed by the trailers holding the status.
+struct GrpcBody {
+    message: Option<Bytes>,
+    trailers: Option<HeaderMap>,
+}
+
+impl GrpcBody {
+    fn ok() -> Response<Self> {
+        let mut trailers = HeaderMap::new();
+        trailers.insert("grpc-status", HeaderValue::from(Code::Ok as u8));
+
+        Self::response(Self {
+            // The response messages have no fields, so their encoding is empty.
+            message: Some(Bytes::from_static(&[0; GRPC_MESSAGE_PREFIX_LEN])),
+            trailers: Some(trailers),
+        })
+    }
+
+    /// A "Trailers-Only" response, the status is sent along with the headers.
+    fn error(status: Status) -> Response<Self> {
+        let mut response = Self::response(Self {
+            message: None,
+            trailers: None,
+        });
+        let headers = response.headers_mut();
+        headers.insert("grpc-status", HeaderValue::from(status.code as u8));
+        if let Ok(message) = HeaderValue::from_str(&percent_encode(&status.message)) {
+            headers.insert("grpc-message", message);
+        }
+        response
+    }
+
+    fn response(body: Self) -> Response<Self> {
+        Response::builder()
+            .header("content-type", "application/grpc")
+            .header("grpc-accept-encoding", "gzip")
+            .body(body)
+            .expect("valid response")
+    }
+}
+
+impl HttpBody for GrpcBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        Poll::Ready(self.message.take().map(Ok))
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        Poll::Ready(Ok(self.trailers.take()))
+    }
+}
+
+/// The `grpc-message` header is percent-encoded, outside of printable ASCII.
+fn percent_encode(message: &str) -> String {
+    message
+        .bytes()
+        .map(|byte| match byte {
+            b' '..=b'~' if byte != b'%' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{collect_n, next_addr, trace_init, wait_for_tcp};
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<OpenTelemetryConfig>();
+    }
+
+    async fn source() -> (mpsc::Receiver<Event>, SocketAddr) {
+        trace_init();
+        let (sender, recv) = Pipeline::new_test();
+        let address = next_addr();
+        tokio::spawn(async move {
+            OpenTelemetryConfig {
+                address,
+                max_message_bytes: 1024,
+                tls: None,
+            }
+            .build(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                sender,
+            )
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+        });
+        wait_for_tcp(address).await;
+        (recv, address)
+    }
+
+    fn logs_request(messages: &[&str]) -> Vec<u8> {
+        let records = messages
+            .iter()
+            .map(|message| proto::LogRecord {
+                body: Some(proto::AnyValue {
+                    value: Some(proto::any_value::Value::StringValue((*message).to_owned())),
+                }),
+                ..Default::default()
+            })
+            .collect();
+        let request = proto::ExportLogsServiceRequest {
+            resource_logs: vec![proto::ResourceLogs {
+                resource: None,
+                scope_logs: vec![proto::ScopeLogs {
+                    scope: None,
+                    log_records: records,
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        };
+
+        let mut message = Vec::new();
+        request.encode(&mut message).unwrap();
+        message
+    }
+
+    fn frame(compressed: bool, message: &[u8]) -> Vec<u8> {
+        let mut framed = vec![compressed as u8];
+        framed.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        framed.extend_from_slice(message);
+        framed
+    }
+
+    /// Makes a unary call, returning its `grpc-status`.
+    async fn call(
+        address: SocketAddr,
+        path: &str,
+        encoding: Option<&str>,
+        body: Vec<u8>,
+    ) -> String {
+        let client = hyper::Client::builder()
+            .http2_only(true)
+            .build_http::<Body>();
+        let mut request = Request::post(format!("http://{}{}", address, path))
+            .header("content-type", "application/grpc")
+            .header("te", "trailers");
+        if let Some(encoding) = encoding {
+            request = request.header("grpc-encoding", encoding);
+        }
+
+        let response = client
+            .request(request.body(Body::from(body)).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+
+        let (parts, mut body) = response.into_parts();
+        if let Some(status) = parts.headers.get("grpc-status") {
+            return status.to_str().unwrap().to_owned();
+        }
+        let message = hyper::body::to_bytes(&mut body).await.unwrap();
+        assert_eq!(message.as_ref(), &[0; GRPC_MESSAGE_PREFIX_LEN]);
+        let trailers = body.trailers().await.unwrap().unwrap();
+        trailers["grpc-status"].to_str().unwrap().to_owned()
+    }
+
+    #[tokio::test]
+    async fn receives_logs() {
+        let (rx, address) = source().await;
+
+        let body = frame(false, &logs_request(&["first", "second"]));
+        assert_eq!(call(address, GRPC_LOGS_PATH, None, body).await, "0");
+
+        let events = collect_n(rx, 2).await;
+        assert_eq!(events[0].as_log()["message"], "first".into());
+        assert_eq!(events[1].as_log()["message"], "second".into());
+    }
+
+    #[tokio::test]
+    async fn receives_gzipped_logs() {
+        let (rx, address) = source().await;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&logs_request(&["compressed"])).unwrap();
+        let body = frame(true, &encoder.finish().unwrap());
+        assert_eq!(call(address, GRPC_LOGS_PATH, Some("gzip"), body).await, "0");
+
+        let events = collect_n(rx, 1).await;
+        assert_eq!(events[0].as_log()["message"], "compressed".into());
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_calls() {
+        let (_rx, address) = source().await;
+
+        let body = frame(false, &logs_request(&["unknown"]));
+        let path = "/opentelemetry.proto.collector.profiles.v1development.ProfilesService/Export";
+        assert_eq!(call(address, path, None, body).await, "12");
+
+        let body = frame(false, &logs_request(&["x"; 200]));
+        assert_eq!(call(address, GRPC_LOGS_PATH, None, body).await, "8");
+
+        let body = frame(false, b"\xff\xff\xff");
+        assert_eq!(call(address, GRPC_TRACES_PATH, None, body).await, "3");
+    }
+
+    #[tokio::test]
+    async fn limits_request_bodies() {
+        let chunks = vec![Ok::<_, std::io::Error>(vec![0u8; 600]); 4];
+        let request = Request::new(Body::wrap_stream(futures::stream::iter(chunks)));
+        let status = read_body(request, 1024).await.unwrap_err();
+        assert_eq!(status.code, Code::ResourceExhausted);
+
+        let request = Request::post("/")
+            .header("content-length", "4096")
+            .body(Body::empty())
+            .unwrap();
+        let status = read_body(request, 1024).await.unwrap_err();
+        assert_eq!(status.code, Code::ResourceExhausted);
+
+        let request = Request::new(Body::from(vec![0u8; 100]));
+        assert_eq!(read_body(request, 1024).await.unwrap().len(), 100);
+    }
+
+    #[test]
+    fn percent_encodes_messages() {
+        assert_eq!(percent_encode("100% café"), "100%25 caf%C3%A9");
+    }
+}
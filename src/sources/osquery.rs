@@ -0,0 +1,343 @@
+use crate::{
+    config::{log_schema, DataType, GlobalOptions, SourceConfig, SourceDescription},
+    event::{Event, LogEvent, Value},
+    internal_events::{
+        FileOpen, FileSourceInternalEventsEmitter, OsqueryEventsReceived, OsqueryParseError,
+    },
+    shutdown::ShutdownSignal,
+    state::FileCheckpoints,
+    Pipeline,
+};
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use file_source::{
+    paths_provider::glob::{Glob, MatchOptions},
+    FileServer, FingerprintStrategy, Fingerprinter, Line, ReadFrom,
+};
+use futures::{future::TryFutureExt, stream, SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+use snafu::{ResultExt, Snafu};
+use std::{path::PathBuf, time::Duration};
+use tokio::task::spawn_blocking;
+
+#[derive(Debug, Snafu)]
+pub enum ParseError {
+    #[snafu(display("Invalid JSON: {}", source))]
+    InvalidJson { source: serde_json::Error },
+    #[snafu(display("Query results without `columns`, `diffResults` or `snapshot`"))]
+    MissingRows,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OsqueryConfig {
+    #[serde(default = "default_include")]
+    include: Vec<PathBuf>,
+    #[serde(default)]
+    read_from_beginning: bool,
+    #[serde(
+        default = "default_max_line_bytes",
+        deserialize_with = "crate::serde::bytes"
+    )]
+    max_line_bytes: usize,
+    data_dir: Option<PathBuf>,
+    host_key: Option<String>,
+}
+
+/// The logs of the `filesystem` logger plugin, in the default `logger_path`.
+fn default_include() -> Vec<PathBuf> {
+    vec![
+        "/var/log/osquery/osqueryd.results.log".into(),
+        "/var/log/osquery/osqueryd.snapshots.log".into(),
+    ]
+}
+
+/// The results of a query are logged as a single line, which can be large in
+/// the batch and snapshot formats.
+fn default_max_line_bytes() -> usize {
+    bytesize::mib(1u64) as usize
+}
+
+inventory::submit! {
+    SourceDescription::new::<OsqueryConfig>("osquery")
+}
+
+impl_generate_config_from_default!(OsqueryConfig);
+
+impl Default for OsqueryConfig {
+    fn default() -> Self {
+        Self {
+            include: default_include(),
+            read_from_beginning: false,
+            max_line_bytes: default_max_line_bytes(),
+            data_dir: None,
+            host_key: None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "osquery")]
+impl SourceConfig for OsqueryConfig {
+    async fn build(
+        &self,
+        name: &str,
+        globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let data_dir = globals.resolve_and_make_data_subdir(self.data_dir.as_ref(), name)?;
+        let checkpoint_store =
+            FileCheckpoints::new(globals.state_store(self.data_dir.as_ref(), name)?.into());
+        let paths_provider = Glob::new(
+            &self.include,
+            &[],
+            MatchOptions::default(),
+            FileSourceInternalEventsEmitter,
+        )
+        .ok_or("Invalid `include` patterns.")?;
+
+        let file_server = FileServer {
+            paths_provider,
+            max_read_bytes: 2048,
+            ignore_checkpoints: false,
+            read_from: if self.read_from_beginning {
+                ReadFrom::Beginning
+            } else {
+                ReadFrom::End
+            },
+            ignore_before: None,
+            max_line_bytes: self.max_line_bytes,
+            line_delimiter: Bytes::from("\n"),
+            data_dir,
+            checkpoint_import_path: None,
+            checkpoint_store: Some(Box::new(checkpoint_store)),
+            glob_minimum_cooldown: Duration::from_secs(1),
+            fingerprinter: Fingerprinter {
+                strategy: FingerprintStrategy::DevInode,
+                max_line_length: self.max_line_bytes,
+                ignore_not_found: true,
+            },
+            oldest_first: false,
+            remove_after: None,
+            emitter: FileSourceInternalEventsEmitter,
+            handle: tokio::runtime::Handle::current(),
+            acknowledgements: None,
+        };
+
+        let host_key = self
+            .host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().to_string());
+        let hostname = crate::get_hostname().ok();
+
+        let mut out = out.sink_map_err(|error| error!(message = "Error sending event.", %error));
+        Ok(Box::pin(async move {
+            let (tx, rx) = futures::channel::mpsc::channel::<Vec<Line>>(2);
+            let mut events = rx
+                .map(stream::iter)
+                .flatten()
+                .map(|line: Line| (line.text, line.filename))
+                .flat_map(move |(line, file)| {
+                    let logs = match parse_results(&line) {
+                        Ok(logs) => {
+                            emit!(OsqueryEventsReceived {
+                                file: &file,
+                                count: logs.len(),
+                                byte_size: line.len(),
+                            });
+                            logs
+                        }
+                        Err(error) => {
+                            emit!(OsqueryParseError { file: &file, error });
+                            Vec::new()
+                        }
+                    };
+                    let host_key = host_key.clone();
+                    let hostname = hostname.clone();
+                    stream::iter(logs.into_iter().map(move |mut log| {
+                        if let Some(hostname) = &hostname {
+                            log.insert(host_key.as_str(), hostname.clone());
+                        }
+                        Ok(Event::Log(log))
+                    }))
+                });
+            tokio::spawn(async move { out.send_all(&mut events).await });
+
+            spawn_blocking(move || {
+                let result = file_server.run(tx, shutdown);
+                emit!(FileOpen { count: 0 });
+                result.unwrap();
+            })
+            .map_err(|error| error!(message = "File server unexpectedly stopped.", %error))
+            .await
+        }))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "osquery"
+    }
+}
+
+/// The results of a scheduled query, in any of the formats of the
+/// `filesystem` logger plugin.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct QueryResults {
+    name: String,
+    host_identifier: Option<String>,
+    /// A number, or a string when `logger_numerics` is disabled.
+    unix_time: Option<JsonValue>,
+    epoch: Option<u64>,
+    counter: Option<u64>,
+    decorations: Option<Map<String, JsonValue>>,
+    /// The event format, with a row per line.
+    columns: Option<Map<String, JsonValue>>,
+    action: Option<String>,
+    /// The batch format, with the rows added and removed since the last run.
+    diff_results: Option<DiffResults>,
+    /// The snapshot format, with all of the rows of the run.
+    snapshot: Option<Vec<Map<String, JsonValue>>>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DiffResults {
+    #[serde(default)]
+    added: Vec<Map<String, JsonValue>>,
+    #[serde(default)]
+    removed: Vec<Map<String, JsonValue>>,
+}
+
+/// Parses a line of the results log into an event per row.
+fn parse_results(line: &[u8]) -> Result<Vec<LogEvent>, ParseError> {
+    let results: QueryResults = serde_json::from_slice(line).context(InvalidJson)?;
+
+    let rows = match (results.columns, results.diff_results, results.snapshot) {
+        (Some(columns), _, _) => {
+            let action = results.action.unwrap_or_else(|| "added".into());
+            vec![(action, columns)]
+        }
+        (None, Some(diff), _) => diff
+            .added
+            .into_iter()
+            .map(|row| ("added".to_owned(), row))
+            .chain(
+                diff.removed
+                    .into_iter()
+                    .map(|row| ("removed".to_owned(), row)),
+            )
+            .collect(),
+        (None, None, Some(snapshot)) => snapshot
+            .into_iter()
+            .map(|row| ("snapshot".to_owned(), row))
+            .collect(),
+        (None, None, None) => return Err(ParseError::MissingRows),
+    };
+
+    let timestamp = results
+        .unix_time
+        .as_ref()
+        .and_then(|time| match time {
+            JsonValue::Number(number) => number.as_i64(),
+            JsonValue::String(string) => string.parse().ok(),
+            _ => None,
+        })
+        .map(|secs| Utc.timestamp(secs, 0))
+        .unwrap_or_else(Utc::now);
+
+    Ok(rows
+        .into_iter()
+        .map(|(action, columns)| {
+            let mut log = LogEvent::default();
+            log.insert(log_schema().timestamp_key(), timestamp);
+            log.insert("name", results.name.clone());
+            log.insert("action", action);
+            log.insert_flat("columns", Value::from(JsonValue::Object(columns)));
+            if let Some(host_identifier) = &results.host_identifier {
+                log.insert("host_identifier", host_identifier.clone());
+            }
+            if let Some(epoch) = results.epoch {
+                log.insert("epoch", epoch as i64);
+            }
+            if let Some(counter) = results.counter {
+                log.insert("counter", counter as i64);
+            }
+            if let Some(decorations) = &results.decorations {
+                log.insert_flat(
+                    "decorations",
+                    Value::from(JsonValue::Object(decorations.clone())),
+                );
+            }
+            log
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<OsqueryConfig>();
+    }
+
+    #[test]
+    fn parses_event_format() {
+        let logs = parse_results(br#"{"name":"pack_it_processes","hostIdentifier":"web1","calendarTime":"Mon Jan 11 06:13:20 2021 UTC","unixTime":1610345600,"epoch":0,"counter":3,"numerics":false,"decorations":{"uuid":"abc"},"columns":{"pid":"42","name":"nginx"},"action":"removed"}"#).unwrap();
+
+        assert_eq!(logs.len(), 1);
+        let log = &logs[0];
+        assert_eq!(log["name"], "pack_it_processes".into());
+        assert_eq!(log["action"], "removed".into());
+        assert_eq!(log["host_identifier"], "web1".into());
+        assert_eq!(log["counter"], Value::Integer(3));
+        assert_eq!(log["columns.pid"], "42".into());
+        assert_eq!(log["decorations.uuid"], "abc".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Value::Timestamp(Utc.timestamp(1610345600, 0))
+        );
+    }
+
+    #[test]
+    fn parses_batch_format() {
+        let logs = parse_results(br#"{"name":"users","hostIdentifier":"web1","unixTime":"1610345600","epoch":0,"counter":1,"diffResults":{"added":[{"uid":1000},{"uid":1001}],"removed":[{"uid":999}]}}"#).unwrap();
+
+        assert_eq!(logs.len(), 3);
+        assert_eq!(logs[1]["action"], "added".into());
+        assert_eq!(logs[1]["columns.uid"], Value::Integer(1001));
+        assert_eq!(logs[2]["action"], "removed".into());
+        assert_eq!(logs[2]["columns.uid"], Value::Integer(999));
+        assert_eq!(
+            logs[2][log_schema().timestamp_key()],
+            Value::Timestamp(Utc.timestamp(1610345600, 0))
+        );
+    }
+
+    #[test]
+    fn parses_snapshot_format() {
+        let logs = parse_results(br#"{"name":"listening_ports","unixTime":1610345600,"action":"snapshot","snapshot":[{"port":"22"},{"port":"443"}]}"#).unwrap();
+
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0]["action"], "snapshot".into());
+        assert_eq!(logs[1]["columns.port"], "443".into());
+    }
+
+    #[test]
+    fn rejects_invalid_results() {
+        assert!(matches!(
+            parse_results(br#"{"name":"users","unixTime":1610345600}"#),
+            Err(ParseError::MissingRows)
+        ));
+        assert!(matches!(
+            parse_results(b"I0111 06:13:20.000000 osqueryd started"),
+            Err(ParseError::InvalidJson { .. })
+        ));
+    }
+}
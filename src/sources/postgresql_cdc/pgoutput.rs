@@ -0,0 +1,351 @@
+//! Decoding of the messages of the `pgoutput` logical decoding plugin, as
+//! described by the logical replication message formats of protocol version 1.
+
+use bytes::Bytes;
+use snafu::Snafu;
+use std::convert::TryInto;
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum DecodeError {
+    #[snafu(display("message is truncated"))]
+    Truncated,
+    #[snafu(display("unknown message type {:?}", *tag as char))]
+    UnknownMessage { tag: u8 },
+    #[snafu(display("unknown tuple data type {:?}", *tag as char))]
+    UnknownDatum { tag: u8 },
+    #[snafu(display("string is not valid UTF-8"))]
+    InvalidString,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Message {
+    Begin {
+        final_lsn: u64,
+        /// Microseconds since 2000-01-01.
+        timestamp: i64,
+        xid: u32,
+    },
+    Commit {
+        lsn: u64,
+        end_lsn: u64,
+        timestamp: i64,
+    },
+    Relation(Relation),
+    Insert {
+        relation_id: u32,
+        new: Tuple,
+    },
+    Update {
+        relation_id: u32,
+        /// Only sent for tables with `REPLICA IDENTITY FULL`, or when the key
+        /// changed.
+        old: Option<Tuple>,
+        new: Tuple,
+    },
+    Delete {
+        relation_id: u32,
+        old: Tuple,
+    },
+    Truncate {
+        relation_ids: Vec<u32>,
+    },
+    /// Origin and type messages, which carry nothing the changes need.
+    Ignored,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Relation {
+    pub id: u32,
+    pub namespace: String,
+    pub name: String,
+    pub columns: Vec<Column>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub type_id: u32,
+}
+
+pub type Tuple = Vec<Datum>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Datum {
+    Null,
+    /// A TOASTed value that didn't change, which isn't sent again.
+    Unchanged,
+    /// The value in its text representation.
+    Text(Bytes),
+}
+
+impl Message {
+    pub fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        let mut reader = Reader(data);
+        let message = match reader.u8()? {
+            b'B' => Message::Begin {
+                final_lsn: reader.u64()?,
+                timestamp: reader.u64()? as i64,
+                xid: reader.u32()?,
+            },
+            b'C' => {
+                let _flags = reader.u8()?;
+                Message::Commit {
+                    lsn: reader.u64()?,
+                    end_lsn: reader.u64()?,
+                    timestamp: reader.u64()? as i64,
+                }
+            }
+            b'R' => {
+                let id = reader.u32()?;
+                let namespace = reader.string()?;
+                let name = reader.string()?;
+                let _replica_identity = reader.u8()?;
+                let columns = (0..reader.u16()?)
+                    .map(|_| {
+                        let _flags = reader.u8()?;
+                        let name = reader.string()?;
+                        let type_id = reader.u32()?;
+                        let _type_modifier = reader.u32()?;
+                        Ok(Column { name, type_id })
+                    })
+                    .collect::<Result<_, DecodeError>>()?;
+                Message::Relation(Relation {
+                    id,
+                    namespace,
+                    name,
+                    columns,
+                })
+            }
+            b'I' => {
+                let relation_id = reader.u32()?;
+                reader.tag(b'N')?;
+                Message::Insert {
+                    relation_id,
+                    new: reader.tuple()?,
+                }
+            }
+            b'U' => {
+                let relation_id = reader.u32()?;
+                let old = match reader.u8()? {
+                    b'K' | b'O' => {
+                        let old = reader.tuple()?;
+                        reader.tag(b'N')?;
+                        Some(old)
+                    }
+                    b'N' => None,
+                    tag => return Err(DecodeError::UnknownMessage { tag }),
+                };
+                Message::Update {
+                    relation_id,
+                    old,
+                    new: reader.tuple()?,
+                }
+            }
+            b'D' => {
+                let relation_id = reader.u32()?;
+                match reader.u8()? {
+                    b'K' | b'O' => Message::Delete {
+                        relation_id,
+                        old: reader.tuple()?,
+                    },
+                    tag => return Err(DecodeError::UnknownMessage { tag }),
+                }
+            }
+            b'T' => {
+                let count = reader.u32()?;
+                let _options = reader.u8()?;
+                Message::Truncate {
+                    relation_ids: (0..count).map(|_| reader.u32()).collect::<Result<_, _>>()?,
+                }
+            }
+            b'O' | b'Y' => Message::Ignored,
+            tag => return Err(DecodeError::UnknownMessage { tag }),
+        };
+
+        Ok(message)
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.0.len() < len {
+            return Err(DecodeError::Truncated);
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, DecodeError> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, DecodeError> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn tag(&mut self, expected: u8) -> Result<(), DecodeError> {
+        match self.u8()? {
+            tag if tag == expected => Ok(()),
+            tag => Err(DecodeError::UnknownMessage { tag }),
+        }
+    }
+
+    /// A null terminated string.
+    fn string(&mut self) -> Result<String, DecodeError> {
+        let len = self
+            .0
+            .iter()
+            .position(|byte| *byte == 0)
+            .ok_or(DecodeError::Truncated)?;
+        let string = std::str::from_utf8(self.take(len)?)
+            .map_err(|_| DecodeError::InvalidString)?
+            .to_owned();
+        self.take(1)?;
+        Ok(string)
+    }
+
+    fn tuple(&mut self) -> Result<Tuple, DecodeError> {
+        (0..self.u16()?)
+            .map(|_| match self.u8()? {
+                b'n' => Ok(Datum::Null),
+                b'u' => Ok(Datum::Unchanged),
+                b't' => {
+                    let len = self.u32()? as usize;
+                    Ok(Datum::Text(Bytes::copy_from_slice(self.take(len)?)))
+                }
+                tag => Err(DecodeError::UnknownDatum { tag }),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    /// Builds messages the way `pgoutput` writes them.
+    #[derive(Default)]
+    pub struct Writer(pub Vec<u8>);
+
+    impl Writer {
+        pub fn u8(mut self, value: u8) -> Self {
+            self.0.push(value);
+            self
+        }
+
+        pub fn u16(mut self, value: u16) -> Self {
+            self.0.extend_from_slice(&value.to_be_bytes());
+            self
+        }
+
+        pub fn u32(mut self, value: u32) -> Self {
+            self.0.extend_from_slice(&value.to_be_bytes());
+            self
+        }
+
+        pub fn u64(mut self, value: u64) -> Self {
+            self.0.extend_from_slice(&value.to_be_bytes());
+            self
+        }
+
+        pub fn string(mut self, value: &str) -> Self {
+            self.0.extend_from_slice(value.as_bytes());
+            self.0.push(0);
+            self
+        }
+
+        pub fn tuple(self, values: &[Option<&str>]) -> Self {
+            values
+                .iter()
+                .fold(self.u16(values.len() as u16), |writer, value| match value {
+                    Some(value) => writer.u8(b't').u32(value.len() as u32).text(value),
+                    None => writer.u8(b'n'),
+                })
+        }
+
+        fn text(mut self, value: &str) -> Self {
+            self.0.extend_from_slice(value.as_bytes());
+            self
+        }
+    }
+
+    pub fn relation(id: u32) -> Vec<u8> {
+        Writer::default()
+            .u8(b'R')
+            .u32(id)
+            .string("public")
+            .string("users")
+            .u8(b'd')
+            .u16(2)
+            .u8(1)
+            .string("id")
+            .u32(23)
+            .u32(u32::MAX)
+            .u8(0)
+            .string("name")
+            .u32(25)
+            .u32(u32::MAX)
+            .0
+    }
+
+    #[test]
+    fn decodes_relation() {
+        assert_eq!(
+            Message::decode(&relation(16384)),
+            Ok(Message::Relation(Relation {
+                id: 16384,
+                namespace: "public".into(),
+                name: "users".into(),
+                columns: vec![
+                    Column {
+                        name: "id".into(),
+                        type_id: 23,
+                    },
+                    Column {
+                        name: "name".into(),
+                        type_id: 25,
+                    },
+                ],
+            }))
+        );
+    }
+
+    #[test]
+    fn decodes_update_with_old_tuple() {
+        let data = Writer::default()
+            .u8(b'U')
+            .u32(16384)
+            .u8(b'O')
+            .tuple(&[Some("1"), Some("alice")])
+            .u8(b'N')
+            .tuple(&[Some("1"), None])
+            .0;
+
+        assert_eq!(
+            Message::decode(&data),
+            Ok(Message::Update {
+                relation_id: 16384,
+                old: Some(vec![Datum::Text("1".into()), Datum::Text("alice".into())]),
+                new: vec![Datum::Text("1".into()), Datum::Null],
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_truncated() {
+        let data = Writer::default().u8(b'I').u32(16384).u8(b'N').u16(1).0;
+
+        assert_eq!(Message::decode(&data), Err(DecodeError::Truncated));
+    }
+}
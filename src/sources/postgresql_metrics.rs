@@ -101,6 +101,7 @@ struct PostgresqlMetricsConfig {
     endpoints: Vec<String>,
     include_databases: Option<Vec<String>>,
     exclude_databases: Option<Vec<String>>,
+    #[serde(deserialize_with = "crate::serde::duration_secs")]
     scrape_interval_secs: u64,
     namespace: String,
     tls: Option<PostgresqlMetricsTlsConfig>,
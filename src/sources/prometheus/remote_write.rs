@@ -4,6 +4,7 @@ use crate::{
     internal_events::{
         PrometheusNoNameError, PrometheusRemoteWriteParseError, PrometheusRemoteWriteReceived,
     },
+    origin::OriginFilter,
     prometheus::{proto, METRIC_NAME_LABEL},
     shutdown::ShutdownSignal,
     sources::{
@@ -32,6 +33,9 @@ struct PrometheusRemoteWriteConfig {
     tls: Option<TlsConfig>,
 
     auth: Option<HttpSourceAuthConfig>,
+
+    permit_origin: Option<Vec<String>>,
+    deny_origin: Option<Vec<String>>,
 }
 
 inventory::submit! {
@@ -44,6 +48,8 @@ impl GenerateConfig for PrometheusRemoteWriteConfig {
             address: "127.0.0.1:9090".parse().unwrap(),
             tls: None,
             auth: None,
+            permit_origin: None,
+            deny_origin: None,
         })
         .unwrap()
     }
@@ -60,7 +66,16 @@ impl SourceConfig for PrometheusRemoteWriteConfig {
         out: Pipeline,
     ) -> crate::Result<sources::Source> {
         let source = RemoteWriteSource;
-        source.run(self.address, "", &self.tls, &self.auth, out, shutdown)
+        let origin = OriginFilter::new(&self.permit_origin, &self.deny_origin)?;
+        source.run(
+            self.address,
+            "",
+            &self.tls,
+            &self.auth,
+            origin,
+            out,
+            shutdown,
+        )
     }
 
     fn output_type(&self) -> crate::config::DataType {
@@ -195,6 +210,8 @@ mod test {
             address,
             auth: None,
             tls: tls.clone(),
+            permit_origin: None,
+            deny_origin: None,
         };
         let source = source
             .build(
@@ -259,6 +276,8 @@ mod integration_tests {
             address: PROMETHEUS_RECEIVE_ADDRESS.parse().unwrap(),
             auth: None,
             tls: None,
+            permit_origin: None,
+            deny_origin: None,
         };
 
         let (tx, rx) = Pipeline::new_test();
@@ -1,5 +1,6 @@
 use super::parser;
 use crate::{
+    cluster::{self, Membership},
     config::{self, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription},
     http::Auth,
     http::HttpClient,
@@ -12,7 +13,7 @@ use crate::{
     tls::{TlsOptions, TlsSettings},
     Event, Pipeline,
 };
-use futures::{stream, FutureExt, SinkExt, StreamExt, TryFutureExt};
+use futures::{stream, FutureExt, SinkExt, StreamExt};
 use hyper::{Body, Request};
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
@@ -32,7 +33,10 @@ struct PrometheusScrapeConfig {
     // Deprecated name
     #[serde(alias = "hosts")]
     endpoints: Vec<String>,
-    #[serde(default = "default_scrape_interval_secs")]
+    #[serde(
+        default = "default_scrape_interval_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
     scrape_interval_secs: u64,
 
     tls: Option<TlsOptions>,
@@ -70,21 +74,26 @@ impl SourceConfig for PrometheusScrapeConfig {
     async fn build(
         &self,
         _name: &str,
-        _globals: &GlobalOptions,
+        globals: &GlobalOptions,
         shutdown: ShutdownSignal,
         out: Pipeline,
     ) -> crate::Result<sources::Source> {
+        let membership = cluster::join(globals).await?;
         let urls = self
             .endpoints
             .iter()
             .map(|s| s.parse::<http::Uri>().context(sources::UriParseError))
             .collect::<Result<Vec<http::Uri>, sources::BuildError>>()?;
         let tls = TlsSettings::from_options(&self.tls)?;
+        if let Some(auth) = &self.auth {
+            auth.validate()?;
+        }
         Ok(prometheus(
             urls,
             tls,
             self.auth.clone(),
             self.scrape_interval_secs,
+            membership,
             shutdown,
             out,
         ))
@@ -107,7 +116,10 @@ struct PrometheusCompatConfig {
     // https://github.com/serde-rs/serde/issues/1504
     #[serde(alias = "hosts")]
     endpoints: Vec<String>,
-    #[serde(default = "default_scrape_interval_secs")]
+    #[serde(
+        default = "default_scrape_interval_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
     scrape_interval_secs: u64,
 
     tls: Option<TlsOptions>,
@@ -151,6 +163,7 @@ fn prometheus(
     tls: TlsSettings,
     auth: Option<Auth>,
     interval: u64,
+    membership: Membership,
     shutdown: ShutdownSignal,
     out: Pipeline,
 ) -> sources::Source {
@@ -158,7 +171,16 @@ fn prometheus(
 
     Box::pin(tokio::time::interval(Duration::from_secs(interval))
         .take_until(shutdown)
-        .map(move |_| stream::iter(urls.clone()))
+        // When running in cluster mode, only scrape the endpoints currently
+        // assigned to this instance.
+        .map(move |_| {
+            stream::iter(
+                urls.iter()
+                    .filter(|url| membership.owns(&url.to_string()))
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            )
+        })
         .flatten()
         .map(move |url| {
             let client = HttpClient::new(tls.clone()).expect("Building HTTP client failed");
@@ -166,19 +188,22 @@ fn prometheus(
             let mut request = Request::get(&url)
                 .body(Body::empty())
                 .expect("error creating request");
-            if let Some(auth) = &auth {
-                auth.apply(&mut request);
-            }
+            let auth = auth.clone();
 
             let start = Instant::now();
-            client
-                .send(request)
-                .map_err(crate::Error::from)
-                .and_then(|response| async move {
-                    let (header, body) = response.into_parts();
-                    let body = hyper::body::to_bytes(body).await?;
-                    Ok((header, body))
-                })
+            async move {
+                if let Some(auth) = &auth {
+                    auth.authorize(&mut request).await?;
+                }
+                let response = client.send(request).await?;
+                if let (hyper::StatusCode::UNAUTHORIZED, Some(auth)) = (response.status(), &auth) {
+                    // The next scrape fetches another OAuth2 token.
+                    auth.reject_token();
+                }
+                let (header, body) = response.into_parts();
+                let body = hyper::body::to_bytes(body).await?;
+                Ok::<_, crate::Error>((header, body))
+            }
                 .into_stream()
                 .filter_map(move |response| {
                     ready(match response {
@@ -9,9 +9,10 @@ use crate::{
         log_schema, DataType, GenerateConfig, GlobalOptions, Resource, SourceConfig,
         SourceDescription,
     },
+    origin::OriginFilter,
     shutdown::ShutdownSignal,
     tls::MaybeTlsSettings,
-    Pipeline,
+    BackpressurePolicy, Pipeline,
 };
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
@@ -92,27 +93,35 @@ impl SourceConfig for SocketConfig {
                     config: config.clone(),
                 };
                 let tls = MaybeTlsSettings::from_config(&config.tls(), true)?;
+                let origin = OriginFilter::new(config.permit_origin(), config.deny_origin())?;
                 tcp.run(
                     config.address(),
                     config.keepalive(),
                     config.shutdown_timeout_secs(),
                     tls,
                     config.receive_buffer_bytes(),
+                    origin,
                     shutdown,
                     out,
                 )
             }
             Mode::Udp(config) => {
+                if config.workers() == 0 {
+                    return Err("`workers` must be greater than 0.".into());
+                }
                 let host_key = config
                     .host_key()
                     .clone()
                     .unwrap_or_else(|| log_schema().host_key().to_string());
+                let origin = OriginFilter::new(config.permit_origin(), config.deny_origin())?;
                 Ok(udp::udp(
                     config.address(),
                     config.max_length(),
                     host_key,
                     #[cfg(unix)]
                     config.receive_buffer_bytes(),
+                    config.workers(),
+                    origin,
                     shutdown,
                     out,
                 ))
@@ -154,6 +163,13 @@ impl SourceConfig for SocketConfig {
         "socket"
     }
 
+    fn default_on_backpressure(&self) -> BackpressurePolicy {
+        match self.mode {
+            Mode::Udp(_) => BackpressurePolicy::Shed,
+            _ => BackpressurePolicy::Block,
+        }
+    }
+
     fn resources(&self) -> Vec<Resource> {
         match self.mode.clone() {
             Mode::Tcp(tcp) => vec![tcp.address().into()],
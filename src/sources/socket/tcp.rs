@@ -16,10 +16,16 @@ pub struct TcpConfig {
     address: SocketListenAddr,
     #[get_copy = "pub"]
     keepalive: Option<TcpKeepaliveConfig>,
-    #[serde(default = "default_max_length")]
+    #[serde(
+        default = "default_max_length",
+        deserialize_with = "crate::serde::bytes"
+    )]
     #[getset(get_copy = "pub", set = "pub")]
     max_length: usize,
-    #[serde(default = "default_shutdown_timeout_secs")]
+    #[serde(
+        default = "default_shutdown_timeout_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
     #[getset(get_copy = "pub", set = "pub")]
     shutdown_timeout_secs: u64,
     #[get = "pub"]
@@ -27,7 +33,12 @@ pub struct TcpConfig {
     #[getset(get = "pub", set = "pub")]
     tls: Option<TlsConfig>,
     #[get_copy = "pub"]
+    #[serde(default, deserialize_with = "crate::serde::optional_bytes")]
     receive_buffer_bytes: Option<usize>,
+    #[get = "pub"]
+    permit_origin: Option<Vec<String>>,
+    #[get = "pub"]
+    deny_origin: Option<Vec<String>>,
 }
 
 fn default_max_length() -> usize {
@@ -47,6 +58,8 @@ impl TcpConfig {
         host_key: Option<String>,
         tls: Option<TlsConfig>,
         receive_buffer_bytes: Option<usize>,
+        permit_origin: Option<Vec<String>>,
+        deny_origin: Option<Vec<String>>,
     ) -> Self {
         Self {
             address,
@@ -56,6 +69,8 @@ impl TcpConfig {
             host_key,
             tls,
             receive_buffer_bytes,
+            permit_origin,
+            deny_origin,
         }
     }
 
@@ -68,6 +83,8 @@ impl TcpConfig {
             host_key: None,
             tls: None,
             receive_buffer_bytes: None,
+            permit_origin: None,
+            deny_origin: None,
         }
     }
 }
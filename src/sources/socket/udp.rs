@@ -1,11 +1,10 @@
-#[cfg(unix)]
-use crate::udp;
 use crate::{
     event::Event,
     internal_events::{SocketEventReceived, SocketMode, SocketReceiveError},
+    origin::OriginFilter,
     shutdown::ShutdownSignal,
     sources::Source,
-    Pipeline,
+    udp, Pipeline,
 };
 use bytes::{Bytes, BytesMut};
 use codec::BytesDelimitedCodec;
@@ -22,20 +21,36 @@ use tokio_util::codec::Decoder;
 pub struct UdpConfig {
     #[get_copy = "pub"]
     address: SocketAddr,
-    #[serde(default = "default_max_length")]
+    #[serde(
+        default = "default_max_length",
+        deserialize_with = "crate::serde::bytes"
+    )]
     #[get_copy = "pub"]
     max_length: usize,
     #[get = "pub"]
     host_key: Option<String>,
     #[cfg(unix)]
     #[get_copy = "pub"]
+    #[serde(default, deserialize_with = "crate::serde::optional_bytes")]
     receive_buffer_bytes: Option<usize>,
+    /// Sockets bound to the address, each received from by its own task.
+    #[serde(default = "default_workers")]
+    #[get_copy = "pub"]
+    workers: usize,
+    #[get = "pub"]
+    permit_origin: Option<Vec<String>>,
+    #[get = "pub"]
+    deny_origin: Option<Vec<String>>,
 }
 
 fn default_max_length() -> usize {
     bytesize::kib(100u64) as usize
 }
 
+const fn default_workers() -> usize {
+    1
+}
+
 impl UdpConfig {
     pub fn from_address(address: SocketAddr) -> Self {
         Self {
@@ -44,6 +59,9 @@ impl UdpConfig {
             host_key: None,
             #[cfg(unix)]
             receive_buffer_bytes: None,
+            workers: default_workers(),
+            permit_origin: None,
+            deny_origin: None,
         }
     }
 }
@@ -53,19 +71,21 @@ pub fn udp(
     max_length: usize,
     host_key: String,
     #[cfg(unix)] receive_buffer_bytes: Option<usize>,
-    mut shutdown: ShutdownSignal,
+    workers: usize,
+    origin: OriginFilter,
+    shutdown: ShutdownSignal,
     out: Pipeline,
 ) -> Source {
-    let mut out = out.sink_map_err(|error| error!(message = "Error sending event.", %error));
-
     Box::pin(async move {
-        let mut socket = UdpSocket::bind(&address)
+        let sockets = udp::bind(address, workers)
             .await
             .expect("Failed to bind to udp listener socket");
 
         #[cfg(unix)]
         if let Some(receive_buffer_bytes) = receive_buffer_bytes {
-            udp::set_receive_buffer_size(&socket, receive_buffer_bytes);
+            for socket in &sockets {
+                udp::set_receive_buffer_size(socket, receive_buffer_bytes);
+            }
         }
 
         #[cfg(unix)]
@@ -75,48 +95,77 @@ pub fn udp(
             max_length
         };
 
-        info!(message = "Listening.", address = %address);
-
-        let mut buf = BytesMut::with_capacity(max_length);
-        loop {
-            buf.resize(max_length, 0);
-            tokio::select! {
-                recv = socket.recv_from(&mut buf) => {
-                    let (byte_size, address) = recv.map_err(|error| {
-                        emit!(SocketReceiveError {
-                            error,
-                            mode: SocketMode::Udp
-                        });
-                    })?;
-
-                    let mut payload = buf.split_to(byte_size);
-
-                    // UDP processes messages per payload, where messages are separated by newline
-                    // and stretch to end of payload.
-                    let mut decoder = BytesDelimitedCodec::new(b'\n');
-                    while let Ok(Some(line)) = decoder.decode_eof(&mut payload) {
-                        let mut event = Event::from(line);
-
-                        event
-                            .as_mut_log()
-                            .insert(crate::config::log_schema().source_type_key(), Bytes::from("socket"));
-                        event
-                            .as_mut_log()
-                            .insert(host_key.clone(), address.to_string());
-
-                        emit!(SocketEventReceived { byte_size,mode:SocketMode::Udp });
-
-                        tokio::select!{
-                            result = out.send(event) => {match result {
-                                Ok(()) => { },
-                                Err(()) => return Ok(()),
-                            }}
-                            _ = &mut shutdown => return Ok(()),
-                        }
+        info!(message = "Listening.", address = %address, workers = sockets.len());
+
+        let workers = sockets
+            .into_iter()
+            .map(|socket| {
+                receive(
+                    socket,
+                    max_length,
+                    host_key.clone(),
+                    origin.clone(),
+                    shutdown.clone(),
+                    out.clone(),
+                )
+            })
+            .collect();
+        udp::run_workers(address.port(), workers).await
+    })
+}
+
+async fn receive(
+    mut socket: UdpSocket,
+    max_length: usize,
+    host_key: String,
+    origin: OriginFilter,
+    mut shutdown: ShutdownSignal,
+    out: Pipeline,
+) -> Result<(), ()> {
+    let mut out = out.sink_map_err(|error| error!(message = "Error sending event.", %error));
+
+    let mut buf = BytesMut::with_capacity(max_length);
+    loop {
+        buf.resize(max_length, 0);
+        tokio::select! {
+            recv = socket.recv_from(&mut buf) => {
+                let (byte_size, address) = recv.map_err(|error| {
+                    emit!(SocketReceiveError {
+                        error,
+                        mode: SocketMode::Udp
+                    });
+                })?;
+
+                let mut payload = buf.split_to(byte_size);
+                if !origin.check(address, SocketMode::Udp) {
+                    continue;
+                }
+
+                // UDP processes messages per payload, where messages are separated by newline
+                // and stretch to end of payload.
+                let mut decoder = BytesDelimitedCodec::new(b'\n');
+                while let Ok(Some(line)) = decoder.decode_eof(&mut payload) {
+                    let mut event = Event::from(line);
+
+                    event
+                        .as_mut_log()
+                        .insert(crate::config::log_schema().source_type_key(), Bytes::from("socket"));
+                    event
+                        .as_mut_log()
+                        .insert(host_key.clone(), address.to_string());
+
+                    emit!(SocketEventReceived { byte_size,mode:SocketMode::Udp });
+
+                    tokio::select!{
+                        result = out.send(event) => {match result {
+                            Ok(()) => { },
+                            Err(()) => return Ok(()),
+                        }}
+                        _ = &mut shutdown => return Ok(()),
                     }
                 }
-                _ = &mut shutdown => return Ok(()),
             }
+            _ = &mut shutdown => return Ok(()),
         }
-    })
+    }
 }
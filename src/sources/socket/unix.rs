@@ -17,7 +17,10 @@ use tokio_util::codec::LinesCodec;
 #[serde(deny_unknown_fields)]
 pub struct UnixConfig {
     pub path: PathBuf,
-    #[serde(default = "default_max_length")]
+    #[serde(
+        default = "default_max_length",
+        deserialize_with = "crate::serde::bytes"
+    )]
     pub max_length: usize,
     pub host_key: Option<String>,
 }
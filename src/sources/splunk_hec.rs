@@ -5,7 +5,9 @@ use crate::{
         SplunkHECEventReceived, SplunkHECRequestBodyInvalid, SplunkHECRequestError,
         SplunkHECRequestReceived,
     },
+    origin::OriginFilter,
     shutdown::ShutdownSignal,
+    sources::util::access_log,
     tls::{MaybeTlsSettings, TlsConfig},
     Pipeline,
 };
@@ -42,6 +44,11 @@ pub struct SplunkConfig {
     /// Splunk HEC token
     token: Option<String>,
     tls: Option<TlsConfig>,
+    /// Log and count every request served
+    access_log: bool,
+    /// Origins allowed and refused to connect
+    permit_origin: Option<Vec<String>>,
+    deny_origin: Option<Vec<String>>,
 }
 
 inventory::submit! {
@@ -66,6 +73,9 @@ impl Default for SplunkConfig {
             address: default_socket_address(),
             token: None,
             tls: None,
+            access_log: false,
+            permit_origin: None,
+            deny_origin: None,
         }
     }
 }
@@ -110,10 +120,12 @@ impl SourceConfig for SplunkConfig {
                     .or(options)
                     .unify(),
             )
-            .or_else(finish_err);
+            .or_else(finish_err)
+            .with(access_log(self.access_log));
 
         let tls = MaybeTlsSettings::from_config(&self.tls, true)?;
-        let listener = tls.bind(&self.address).await?;
+        let origin = OriginFilter::new(&self.permit_origin, &self.deny_origin)?;
+        let listener = tls.bind(&self.address).await?.with_origin(origin);
 
         Ok(Box::pin(async move {
             let _ = warp::serve(services)
@@ -786,6 +798,9 @@ mod tests {
                 address,
                 token,
                 tls: None,
+                access_log: false,
+                permit_origin: None,
+                deny_origin: None,
             }
             .build(
                 "default",
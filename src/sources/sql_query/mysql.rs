@@ -0,0 +1,91 @@
+use super::{BuildError, InvalidMySqlEndpoint, MySqlQueryFailed, QueryError, Record};
+use crate::event::Value;
+use chrono::{NaiveDate, TimeZone, Utc};
+use mysql_async::{prelude::Queryable, Opts, Pool, Row, Value as MySqlValue};
+use snafu::ResultExt;
+use std::convert::TryFrom;
+
+pub(super) struct MySql {
+    pool: Pool,
+}
+
+impl MySql {
+    pub(super) fn new(endpoint: &str) -> Result<Self, BuildError> {
+        let opts = Opts::from_url(endpoint).context(InvalidMySqlEndpoint)?;
+        Ok(MySql {
+            pool: Pool::new(opts),
+        })
+    }
+
+    pub(super) async fn query(&mut self, sql: &str) -> Result<Vec<Record>, QueryError> {
+        let mut conn = self.pool.get_conn().await.context(MySqlQueryFailed)?;
+        // Unlike the text protocol of plain queries, the binary protocol of
+        // prepared statements keeps the types of the values.
+        let rows: Vec<Row> = conn.exec(sql, ()).await.context(MySqlQueryFailed)?;
+        Ok(rows.into_iter().map(record).collect())
+    }
+}
+
+fn record(row: Row) -> Record {
+    let columns = row.columns();
+    columns
+        .iter()
+        .map(|column| column.name_str().into_owned())
+        .zip(row.unwrap().into_iter().map(value))
+        .collect()
+}
+
+/// `DATETIME` values are taken to be in UTC, and `TIME` values are written
+/// as `[-]HH:MM:SS.ffffff`.
+fn value(value: MySqlValue) -> Value {
+    match value {
+        MySqlValue::NULL => Value::Null,
+        MySqlValue::Bytes(bytes) => Value::Bytes(bytes.into()),
+        MySqlValue::Int(value) => Value::Integer(value),
+        // Kept exact, like the unsigned integers too wide for an integer in JSON.
+        MySqlValue::UInt(value) => i64::try_from(value)
+            .map(Value::Integer)
+            .unwrap_or_else(|_| Value::from(value.to_string())),
+        MySqlValue::Float(value) => Value::Float(value),
+        MySqlValue::Date(year, month, day, hour, minute, second, micros) => {
+            NaiveDate::from_ymd_opt(year.into(), month.into(), day.into())
+                .and_then(|date| {
+                    date.and_hms_micro_opt(hour.into(), minute.into(), second.into(), micros)
+                })
+                .map(|datetime| Value::Timestamp(Utc.from_utc_datetime(&datetime)))
+                // Zero dates such as `0000-00-00`.
+                .unwrap_or(Value::Null)
+        }
+        MySqlValue::Time(negative, days, hours, minutes, seconds, micros) => Value::from(format!(
+            "{}{:02}:{:02}:{:02}.{:06}",
+            if negative { "-" } else { "" },
+            days * 24 + u32::from(hours),
+            minutes,
+            seconds,
+            micros
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_values() {
+        assert_eq!(value(MySqlValue::UInt(7)), Value::Integer(7));
+        assert_eq!(
+            value(MySqlValue::UInt(u64::MAX)),
+            Value::from("18446744073709551615")
+        );
+        assert_eq!(
+            value(MySqlValue::Date(2021, 1, 2, 3, 4, 5, 6)),
+            Value::Timestamp(Utc.ymd(2021, 1, 2).and_hms_micro(3, 4, 5, 6))
+        );
+        assert_eq!(value(MySqlValue::Date(0, 0, 0, 0, 0, 0, 0)), Value::Null);
+        assert_eq!(
+            value(MySqlValue::Time(true, 1, 2, 3, 4, 5)),
+            Value::from("-26:03:04.000005")
+        );
+    }
+}
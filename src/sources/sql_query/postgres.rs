@@ -0,0 +1,106 @@
+use super::{
+    BuildError, ConnectionFailed, InvalidPostgresEndpoint, PostgresQueryFailed, QueryError, Record,
+    SqlQueryTlsConfig, TlsFailed,
+};
+use crate::event::Value;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use openssl::ssl::{SslConnector, SslMethod};
+use postgres_openssl::MakeTlsConnector;
+use snafu::ResultExt;
+use tokio_postgres::{Client, Config, NoTls, Row};
+
+pub(super) struct Postgres {
+    config: Config,
+    tls: Option<SqlQueryTlsConfig>,
+    client: Option<Client>,
+}
+
+impl Postgres {
+    pub(super) fn new(endpoint: &str, tls: Option<SqlQueryTlsConfig>) -> Result<Self, BuildError> {
+        Ok(Postgres {
+            config: endpoint.parse().context(InvalidPostgresEndpoint)?,
+            tls,
+            client: None,
+        })
+    }
+
+    async fn connect(&self) -> Result<Client, QueryError> {
+        Ok(match &self.tls {
+            Some(tls) => {
+                let mut builder =
+                    SslConnector::builder(SslMethod::tls_client()).context(TlsFailed)?;
+                builder
+                    .set_ca_file(tls.ca_file.clone())
+                    .context(TlsFailed)?;
+                let connector = MakeTlsConnector::new(builder.build());
+
+                let (client, connection) = self
+                    .config
+                    .connect(connector)
+                    .await
+                    .context(ConnectionFailed)?;
+                tokio::spawn(connection);
+                client
+            }
+            None => {
+                let (client, connection) =
+                    self.config.connect(NoTls).await.context(ConnectionFailed)?;
+                tokio::spawn(connection);
+                client
+            }
+        })
+    }
+
+    pub(super) async fn query(&mut self, sql: &str) -> Result<Vec<Record>, QueryError> {
+        if self.client.as_ref().map_or(true, Client::is_closed) {
+            self.client = Some(self.connect().await?);
+        }
+
+        let rows = self
+            .client
+            .as_ref()
+            .unwrap()
+            .query(sql, &[])
+            .await
+            .context(PostgresQueryFailed)?;
+        rows.iter().map(record).collect()
+    }
+}
+
+fn record(row: &Row) -> Result<Record, QueryError> {
+    row.columns()
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            let value = match column.type_().name() {
+                "bool" => get::<bool>(row, index)?.map(Value::Boolean),
+                "int2" => get::<i16>(row, index)?.map(|value| Value::Integer(value.into())),
+                "int4" => get::<i32>(row, index)?.map(|value| Value::Integer(value.into())),
+                "int8" => get::<i64>(row, index)?.map(Value::Integer),
+                "float4" => get::<f32>(row, index)?.map(|value| Value::Float(value.into())),
+                "float8" => get::<f64>(row, index)?.map(Value::Float),
+                "text" | "varchar" | "bpchar" | "name" => {
+                    get::<String>(row, index)?.map(Value::from)
+                }
+                "bytea" => get::<Vec<u8>>(row, index)?.map(|value| Value::Bytes(value.into())),
+                "timestamp" => get::<NaiveDateTime>(row, index)?
+                    .map(|value| Value::Timestamp(Utc.from_utc_datetime(&value))),
+                "timestamptz" => get::<DateTime<Utc>>(row, index)?.map(Value::Timestamp),
+                type_name => {
+                    return Err(QueryError::UnsupportedType {
+                        column: column.name().to_owned(),
+                        type_name: type_name.to_owned(),
+                    })
+                }
+            };
+            Ok((column.name().to_owned(), value.unwrap_or(Value::Null)))
+        })
+        .collect()
+}
+
+fn get<'a, T: tokio_postgres::types::FromSql<'a>>(
+    row: &'a Row,
+    index: usize,
+) -> Result<Option<T>, QueryError> {
+    row.try_get(index).context(PostgresQueryFailed)
+}
@@ -1,13 +1,12 @@
-#[cfg(unix)]
-use crate::udp;
 use crate::{
     config::{self, GenerateConfig, GlobalOptions, Resource, SourceConfig, SourceDescription},
-    internal_events::{StatsdEventReceived, StatsdInvalidRecord, StatsdSocketError},
+    internal_events::{SocketMode, StatsdEventReceived, StatsdInvalidRecord, StatsdSocketError},
+    origin::OriginFilter,
     shutdown::ShutdownSignal,
     sources::util::{SocketListenAddr, TcpSource},
     tcp::TcpKeepaliveConfig,
     tls::{MaybeTlsSettings, TlsConfig},
-    Event, Pipeline,
+    udp, BackpressurePolicy, Event, Pipeline,
 };
 use bytes::Bytes;
 use codec::BytesDelimitedCodec;
@@ -38,7 +37,12 @@ enum StatsdConfig {
 pub struct UdpConfig {
     address: SocketAddr,
     #[cfg(unix)]
+    #[serde(default, deserialize_with = "crate::serde::optional_bytes")]
     receive_buffer_bytes: Option<usize>,
+    #[serde(default = "default_workers")]
+    workers: usize,
+    permit_origin: Option<Vec<String>>,
+    deny_origin: Option<Vec<String>>,
 }
 
 impl UdpConfig {
@@ -47,19 +51,32 @@ impl UdpConfig {
             address,
             #[cfg(unix)]
             receive_buffer_bytes: None,
+            workers: default_workers(),
+            permit_origin: None,
+            deny_origin: None,
         }
     }
 }
 
+const fn default_workers() -> usize {
+    1
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 struct TcpConfig {
     address: SocketListenAddr,
     keepalive: Option<TcpKeepaliveConfig>,
     #[serde(default)]
     tls: Option<TlsConfig>,
-    #[serde(default = "default_shutdown_timeout_secs")]
+    #[serde(
+        default = "default_shutdown_timeout_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
     shutdown_timeout_secs: u64,
+    #[serde(default, deserialize_with = "crate::serde::optional_bytes")]
     receive_buffer_bytes: Option<usize>,
+    permit_origin: Option<Vec<String>>,
+    deny_origin: Option<Vec<String>>,
 }
 
 impl TcpConfig {
@@ -71,6 +88,8 @@ impl TcpConfig {
             tls: None,
             shutdown_timeout_secs: default_shutdown_timeout_secs(),
             receive_buffer_bytes: None,
+            permit_origin: None,
+            deny_origin: None,
         }
     }
 }
@@ -103,15 +122,23 @@ impl SourceConfig for StatsdConfig {
         out: Pipeline,
     ) -> crate::Result<super::Source> {
         match self {
-            StatsdConfig::Udp(config) => Ok(Box::pin(statsd_udp(config.clone(), shutdown, out))),
+            StatsdConfig::Udp(config) => {
+                if config.workers == 0 {
+                    return Err("`workers` must be greater than 0.".into());
+                }
+                let origin = OriginFilter::new(&config.permit_origin, &config.deny_origin)?;
+                Ok(Box::pin(statsd_udp(config.clone(), origin, shutdown, out)))
+            }
             StatsdConfig::Tcp(config) => {
                 let tls = MaybeTlsSettings::from_config(&config.tls, true)?;
+                let origin = OriginFilter::new(&config.permit_origin, &config.deny_origin)?;
                 StatsdTcpSource.run(
                     config.address,
                     config.keepalive,
                     config.shutdown_timeout_secs,
                     tls,
                     config.receive_buffer_bytes,
+                    origin,
                     shutdown,
                     out,
                 )
@@ -129,6 +156,13 @@ impl SourceConfig for StatsdConfig {
         "statsd"
     }
 
+    fn default_on_backpressure(&self) -> BackpressurePolicy {
+        match self {
+            Self::Udp(_) => BackpressurePolicy::Shed,
+            _ => BackpressurePolicy::Block,
+        }
+    }
+
     fn resources(&self) -> Vec<Resource> {
         match self.clone() {
             Self::Tcp(tcp) => vec![tcp.address.into()],
@@ -156,27 +190,45 @@ pub(self) fn parse_event(line: &str) -> Option<Event> {
 
 async fn statsd_udp(
     config: UdpConfig,
+    origin: OriginFilter,
     shutdown: ShutdownSignal,
-    mut out: Pipeline,
+    out: Pipeline,
 ) -> Result<(), ()> {
-    let socket = UdpSocket::bind(&config.address)
+    let sockets = udp::bind(config.address, config.workers)
         .map_err(|error| emit!(StatsdSocketError::bind(error)))
         .await?;
 
     #[cfg(unix)]
     if let Some(receive_buffer_bytes) = config.receive_buffer_bytes {
-        udp::set_receive_buffer_size(&socket, receive_buffer_bytes);
+        for socket in &sockets {
+            udp::set_receive_buffer_size(socket, receive_buffer_bytes);
+        }
     }
 
     info!(
         message = "Listening.",
         addr = %config.address,
-        r#type = "udp"
+        r#type = "udp",
+        workers = sockets.len()
     );
 
+    let workers = sockets
+        .into_iter()
+        .map(|socket| statsd_udp_receive(socket, origin.clone(), shutdown.clone(), out.clone()))
+        .collect();
+    udp::run_workers(config.address.port(), workers).await
+}
+
+async fn statsd_udp_receive(
+    socket: UdpSocket,
+    origin: OriginFilter,
+    shutdown: ShutdownSignal,
+    mut out: Pipeline,
+) -> Result<(), ()> {
     let mut stream = UdpFramed::new(socket, BytesCodec::new()).take_until(shutdown);
     while let Some(frame) = stream.next().await {
         match frame {
+            Ok((_, peer_addr)) if !origin.check(peer_addr, SocketMode::Udp) => {}
             Ok((bytes, _sock)) => {
                 let packet = String::from_utf8_lossy(bytes.as_ref());
                 let metrics = packet.lines().filter_map(parse_event).map(Ok);
@@ -234,6 +286,27 @@ mod test {
         crate::test_util::test_generate_config::<StatsdConfig>();
     }
 
+    #[test]
+    fn sheds_only_udp_by_default() {
+        let udp: StatsdConfig = toml::from_str(
+            r#"
+            mode = "udp"
+            address = "127.0.0.1:8125"
+            "#,
+        )
+        .unwrap();
+        let tcp: StatsdConfig = toml::from_str(
+            r#"
+            mode = "tcp"
+            address = "127.0.0.1:8125"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(udp.default_on_backpressure(), BackpressurePolicy::Shed);
+        assert_eq!(tcp.default_on_backpressure(), BackpressurePolicy::Block);
+    }
+
     fn parse_count(lines: &[&str], prefix: &str) -> usize {
         lines
             .iter()
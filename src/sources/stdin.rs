@@ -1,22 +1,41 @@
+use super::util::{
+    decoding::Framer, DecodeErrorBehavior, DecodingConfig, FramingConfig, MultilineConfig,
+    DECODE_ERRORS_OUTPUT,
+};
 use crate::{
     config::{log_schema, DataType, GlobalOptions, Resource, SourceConfig, SourceDescription},
     event::Event,
-    internal_events::{StdinEventReceived, StdinReadFailed},
+    internal_events::{StdinDecodeFailed, StdinEventReceived, StdinReadFailed},
+    line_agg::{self, LineAgg},
     shutdown::ShutdownSignal,
     Pipeline,
 };
-use bytes::Bytes;
-use futures::{executor, FutureExt, SinkExt, StreamExt, TryStreamExt};
+use bytes::{Bytes, BytesMut};
+use futures::{executor, future, FutureExt, SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::{io, thread};
+use std::{convert::TryInto, io, thread};
 use tokio::sync::mpsc::channel;
+use tokio_util::codec::Decoder;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields, default)]
 pub struct StdinConfig {
-    #[serde(default = "default_max_length")]
+    #[serde(
+        default = "default_max_length",
+        deserialize_with = "crate::serde::bytes"
+    )]
     pub max_length: usize,
     pub host_key: Option<String>,
+    /// The field set to `stdin` on each event, if any.
+    pub stream_key: Option<String>,
+    pub framing: FramingConfig,
+    pub decoding: DecodingConfig,
+    /// Whether the frames failing to decode are discarded, or replaced by an
+    /// event describing the error.
+    pub on_decode_error: DecodeErrorBehavior,
+    /// Aggregates the frames into multi-line messages before decoding them.
+    pub multiline: Option<MultilineConfig>,
+    pub on_eof: EofBehavior,
 }
 
 impl Default for StdinConfig {
@@ -24,6 +43,12 @@ impl Default for StdinConfig {
         StdinConfig {
             max_length: default_max_length(),
             host_key: None,
+            stream_key: None,
+            framing: FramingConfig::default(),
+            decoding: DecodingConfig::default(),
+            on_decode_error: DecodeErrorBehavior::default(),
+            multiline: None,
+            on_eof: EofBehavior::default(),
         }
     }
 }
@@ -32,6 +57,22 @@ fn default_max_length() -> usize {
     bytesize::kib(100u64) as usize
 }
 
+/// What the source does once it reads the end of STDIN.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EofBehavior {
+    /// Finishes, shutting Vector down when it was the last source running.
+    Shutdown,
+    /// Keeps running until Vector shuts down.
+    KeepRunning,
+}
+
+impl Default for EofBehavior {
+    fn default() -> Self {
+        EofBehavior::Shutdown
+    }
+}
+
 inventory::submit! {
     SourceDescription::new::<StdinConfig>("stdin")
 }
@@ -48,7 +89,7 @@ impl SourceConfig for StdinConfig {
         shutdown: ShutdownSignal,
         out: Pipeline,
     ) -> crate::Result<super::Source> {
-        stdin_source(io::BufReader::new(io::stdin()), self.clone(), shutdown, out)
+        stdin_source(io::stdin(), self.clone(), shutdown, out)
     }
 
     fn output_type(&self) -> DataType {
@@ -62,6 +103,10 @@ impl SourceConfig for StdinConfig {
     fn resources(&self) -> Vec<Resource> {
         vec![Resource::Stdin]
     }
+
+    fn outputs(&self) -> Vec<&'static str> {
+        self.on_decode_error.outputs()
+    }
 }
 
 pub fn stdin_source<R>(
@@ -71,12 +116,22 @@ pub fn stdin_source<R>(
     out: Pipeline,
 ) -> crate::Result<super::Source>
 where
-    R: Send + io::BufRead + 'static,
+    R: Send + io::Read + 'static,
 {
     let host_key = config
         .host_key
         .unwrap_or_else(|| log_schema().host_key().to_string());
     let hostname = crate::get_hostname().ok();
+    let stream_key = config.stream_key;
+    let decoding = config.decoding;
+    let on_decode_error = config.on_decode_error;
+    let on_eof = config.on_eof;
+    let framer = config.framing.build(config.max_length);
+    let multiline: Option<line_agg::Config> = config
+        .multiline
+        .as_ref()
+        .map(TryInto::try_into)
+        .transpose()?;
 
     let (mut sender, receiver) = channel(1024);
 
@@ -84,47 +139,152 @@ where
     thread::spawn(move || {
         info!("Capturing STDIN.");
 
-        for line in stdin.lines() {
-            if executor::block_on(sender.send(line)).is_err() {
-                // receiver has closed so we should shutdown
-                return;
-            }
-        }
+        read_frames(stdin, framer, |frame| {
+            // The receiver closing means we should shutdown.
+            executor::block_on(sender.send(frame)).is_ok()
+        });
     });
 
     Ok(Box::pin(async move {
+        let mut decode_errors = out.output(DECODE_ERRORS_OUTPUT).map(|output| {
+            output.sink_map_err(
+                |error| error!(message = "Unable to send event to decode_errors.", %error),
+            )
+        });
         let mut out =
             out.sink_map_err(|error| error!(message = "Unable to send event to out.", %error));
 
-        let res = receiver
-            .take_until(shutdown)
-            .map_err(|error| emit!(StdinReadFailed { error }))
-            .map_ok(move |line| {
-                emit!(StdinEventReceived {
-                    byte_size: line.len()
-                });
-                create_event(Bytes::from(line), &host_key, &hostname)
+        // The reading thread stops at the first error.
+        let frames = receiver
+            .take_until(shutdown.clone())
+            .filter_map(|frame| {
+                future::ready(match frame {
+                    Ok(frame) => Some(frame),
+                    Err(error) => {
+                        emit!(StdinReadFailed { error });
+                        None
+                    }
+                })
             })
-            .forward(&mut out)
-            .inspect(|_| info!("Finished sending."))
-            .await;
+            .boxed();
+
+        let mut frames = match multiline {
+            Some(config) => LineAgg::new(
+                frames.map(|frame| ((), frame, ())),
+                line_agg::Logic::new(config),
+            )
+            .map(|((), frame, ())| frame)
+            .boxed(),
+            None => frames,
+        };
+
+        let res = async {
+            while let Some(frame) = frames.next().await {
+                let byte_size = frame.len();
+                match decoding.decode(frame.clone()) {
+                    Ok(event) => {
+                        emit!(StdinEventReceived { byte_size });
+                        let event =
+                            enrich_event(event, &host_key, &hostname, stream_key.as_deref());
+                        out.send(event).await?;
+                    }
+                    Err(error) => {
+                        let event = decode_errors
+                            .as_ref()
+                            .and_then(|_| on_decode_error.error_event(&frame, &error));
+                        emit!(StdinDecodeFailed {
+                            error,
+                            forwarded: event.is_some(),
+                        });
+                        if let (Some(event), Some(output)) = (event, decode_errors.as_mut()) {
+                            let event =
+                                enrich_event(event, &host_key, &hostname, stream_key.as_deref());
+                            output.send(event).await?;
+                        }
+                    }
+                }
+            }
+            Ok::<(), ()>(())
+        }
+        .inspect(|_| info!("Finished sending."))
+        .await;
 
-        let _ = out.flush().await; // error emitted by sink_map_err
+        if on_eof == EofBehavior::KeepRunning {
+            let _ = shutdown.await;
+        }
 
         res
     }))
 }
 
-fn create_event(line: Bytes, host_key: &str, hostname: &Option<String>) -> Event {
-    let mut event = Event::from(line);
+/// Reads the frames of `stdin` until its end or the first error, and passes
+/// each of them to `send` for as long as it returns true.
+fn read_frames<R, F>(mut stdin: R, mut framer: Framer, mut send: F)
+where
+    R: io::Read,
+    F: FnMut(io::Result<Bytes>) -> bool,
+{
+    let mut buf = BytesMut::new();
+    let mut chunk = [0; 8192];
+    loop {
+        match framer.decode(&mut buf) {
+            Ok(Some(frame)) => {
+                if !send(Ok(frame)) {
+                    return;
+                }
+                continue;
+            }
+            Ok(None) => (),
+            Err(error) => {
+                send(Err(error));
+                return;
+            }
+        }
+
+        match stdin.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(read) => buf.extend_from_slice(&chunk[..read]),
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => (),
+            Err(error) => {
+                send(Err(error));
+                return;
+            }
+        }
+    }
+
+    loop {
+        match framer.decode_eof(&mut buf) {
+            Ok(Some(frame)) => {
+                if !send(Ok(frame)) {
+                    return;
+                }
+            }
+            Ok(None) => return,
+            Err(error) => {
+                send(Err(error));
+                return;
+            }
+        }
+    }
+}
+
+fn enrich_event(
+    mut event: Event,
+    host_key: &str,
+    hostname: &Option<String>,
+    stream_key: Option<&str>,
+) -> Event {
+    let log = event.as_mut_log();
 
     // Add source type
-    event
-        .as_mut_log()
-        .insert(log_schema().source_type_key(), Bytes::from("stdin"));
+    log.insert(log_schema().source_type_key(), Bytes::from("stdin"));
 
     if let Some(hostname) = &hostname {
-        event.as_mut_log().insert(host_key, hostname.clone());
+        log.insert(host_key, hostname.clone());
+    }
+
+    if let Some(stream_key) = stream_key {
+        log.insert(stream_key, Bytes::from("stdin"));
     }
 
     event
@@ -143,15 +303,16 @@ mod tests {
     }
 
     #[test]
-    fn stdin_create_event() {
-        let line = Bytes::from("hello world");
+    fn stdin_enrich_event() {
+        let event = Event::from("hello world");
         let host_key = "host".to_string();
         let hostname = Some("Some.Machine".to_string());
 
-        let event = create_event(line, &host_key, &hostname);
+        let event = enrich_event(event, &host_key, &hostname, Some("stream"));
         let log = event.into_log();
 
         assert_eq!(log["host"], "Some.Machine".into());
+        assert_eq!(log["stream"], "stdin".into());
         assert_eq!(log[log_schema().message_key()], "hello world".into());
         assert_eq!(log[log_schema().source_type_key()], "stdin".into());
     }
@@ -188,4 +349,108 @@ mod tests {
         assert!(event.is_err());
         assert_eq!(Err(mpsc::error::TryRecvError::Closed), event);
     }
+
+    async fn run(input: &'static str, config: StdinConfig) -> Vec<Event> {
+        let (tx, rx) = Pipeline::new_test();
+
+        stdin_source(Cursor::new(input), config, ShutdownSignal::noop(), tx)
+            .unwrap()
+            .await
+            .unwrap();
+
+        rx.collect().await
+    }
+
+    #[tokio::test]
+    async fn stdin_decodes_json() {
+        trace_init();
+
+        let config = StdinConfig {
+            decoding: DecodingConfig::Json,
+            ..Default::default()
+        };
+        let events = run(
+            "{\"message\": \"hello\", \"pod\": \"web-1\"}\nnot json\n",
+            config,
+        )
+        .await;
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].as_log()["message"], "hello".into());
+        assert_eq!(events[0].as_log()["pod"], "web-1".into());
+    }
+
+    #[tokio::test]
+    async fn stdin_forwards_decode_errors() {
+        trace_init();
+
+        let config = StdinConfig {
+            decoding: DecodingConfig::Json,
+            on_decode_error: DecodeErrorBehavior::Forward,
+            ..Default::default()
+        };
+        assert_eq!(config.outputs(), vec![DECODE_ERRORS_OUTPUT]);
+        let (tx, rx) = Pipeline::new_test();
+        let (errors_tx, errors_rx) = Pipeline::new_test();
+        let input = "{\"message\": \"hello\"}\n{\"message\": \"cut\n";
+
+        stdin_source(
+            Cursor::new(input),
+            config,
+            ShutdownSignal::noop(),
+            tx.with_output(DECODE_ERRORS_OUTPUT, errors_tx),
+        )
+        .unwrap()
+        .await
+        .unwrap();
+
+        let events = rx.collect::<Vec<_>>().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].as_log()["message"], "hello".into());
+        let errors = errors_rx.collect::<Vec<_>>().await;
+        assert_eq!(errors.len(), 1);
+        let log = errors[0].as_log();
+        assert_eq!(log["decode_error.raw"], "{\"message\": \"cut".into());
+        assert_eq!(log[log_schema().source_type_key()], "stdin".into());
+        assert!(!log.contains("message"));
+    }
+
+    #[tokio::test]
+    async fn stdin_aggregates_multiline() {
+        trace_init();
+
+        let config = StdinConfig {
+            multiline: Some(MultilineConfig {
+                start_pattern: "^[^\\s]".to_owned(),
+                condition_pattern: "^[\\s]+".to_owned(),
+                mode: line_agg::Mode::ContinueThrough,
+                timeout_ms: 1000,
+            }),
+            ..Default::default()
+        };
+        let events = run("Exception: boom\n  at main()\nnext\n", config).await;
+
+        let messages = events
+            .iter()
+            .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["Exception: boom\n  at main()", "next"]);
+    }
+
+    #[tokio::test]
+    async fn stdin_frames_length_delimited() {
+        trace_init();
+
+        let config = StdinConfig {
+            framing: FramingConfig::LengthDelimited,
+            ..Default::default()
+        };
+        let events = run("\x00\x00\x00\x0bhello\nworld", config).await;
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "hello\nworld".into()
+        );
+    }
 }
@@ -1,19 +1,18 @@
 use super::util::{SocketListenAddr, TcpSource};
 #[cfg(unix)]
 use crate::sources::util::build_unix_stream_source;
-#[cfg(unix)]
-use crate::udp;
 use crate::{
     config::{
         log_schema, DataType, GenerateConfig, GlobalOptions, Resource, SourceConfig,
         SourceDescription,
     },
     event::{Event, Value},
-    internal_events::{SyslogEventReceived, SyslogUdpReadError, SyslogUdpUtf8Error},
+    internal_events::{SocketMode, SyslogEventReceived, SyslogUdpReadError, SyslogUdpUtf8Error},
+    origin::OriginFilter,
     shutdown::ShutdownSignal,
     tcp::TcpKeepaliveConfig,
     tls::{MaybeTlsSettings, TlsConfig},
-    Pipeline,
+    udp, BackpressurePolicy, Pipeline,
 };
 use bytes::{Buf, Bytes, BytesMut};
 use chrono::{Datelike, Utc};
@@ -37,10 +36,16 @@ use tokio_util::{
 pub struct SyslogConfig {
     #[serde(flatten)]
     mode: Mode,
-    #[serde(default = "default_max_length")]
+    #[serde(
+        default = "default_max_length",
+        deserialize_with = "crate::serde::bytes"
+    )]
     max_length: usize,
     /// The host key of the log. (This differs from `hostname`)
     host_key: Option<String>,
+    /// The origins permitted to connect, in `tcp` and `udp` modes.
+    permit_origin: Option<Vec<String>>,
+    deny_origin: Option<Vec<String>>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, is_enum_variant)]
@@ -50,12 +55,16 @@ pub enum Mode {
         address: SocketListenAddr,
         keepalive: Option<TcpKeepaliveConfig>,
         tls: Option<TlsConfig>,
+        #[serde(default, deserialize_with = "crate::serde::optional_bytes")]
         receive_buffer_bytes: Option<usize>,
     },
     Udp {
         address: SocketAddr,
         #[cfg(unix)]
+        #[serde(default, deserialize_with = "crate::serde::optional_bytes")]
         receive_buffer_bytes: Option<usize>,
+        #[serde(default = "default_workers")]
+        workers: usize,
     },
     #[cfg(unix)]
     Unix { path: PathBuf },
@@ -65,12 +74,18 @@ pub fn default_max_length() -> usize {
     bytesize::kib(100u64) as usize
 }
 
+const fn default_workers() -> usize {
+    1
+}
+
 impl SyslogConfig {
     pub fn from_mode(mode: Mode) -> Self {
         Self {
             mode,
             host_key: None,
             max_length: default_max_length(),
+            permit_origin: None,
+            deny_origin: None,
         }
     }
 }
@@ -90,6 +105,8 @@ impl GenerateConfig for SyslogConfig {
             },
             host_key: None,
             max_length: default_max_length(),
+            permit_origin: None,
+            deny_origin: None,
         })
         .unwrap()
     }
@@ -109,6 +126,7 @@ impl SourceConfig for SyslogConfig {
             .host_key
             .clone()
             .unwrap_or_else(|| log_schema().host_key().to_string());
+        let origin = OriginFilter::new(&self.permit_origin, &self.deny_origin)?;
 
         match self.mode.clone() {
             Mode::Tcp {
@@ -129,24 +147,37 @@ impl SourceConfig for SyslogConfig {
                     shutdown_secs,
                     tls,
                     receive_buffer_bytes,
+                    origin,
                     shutdown,
                     out,
                 )
             }
+            Mode::Udp { workers: 0, .. } => Err("`workers` must be greater than 0.".into()),
             #[cfg(unix)]
             Mode::Udp {
                 address,
                 receive_buffer_bytes,
+                workers,
             } => Ok(udp(
                 address,
                 self.max_length,
                 host_key,
                 receive_buffer_bytes,
+                workers,
+                origin,
                 shutdown,
                 out,
             )),
             #[cfg(not(unix))]
-            Mode::Udp { address } => Ok(udp(address, self.max_length, host_key, shutdown, out)),
+            Mode::Udp { address, workers } => Ok(udp(
+                address,
+                self.max_length,
+                host_key,
+                workers,
+                origin,
+                shutdown,
+                out,
+            )),
             #[cfg(unix)]
             Mode::Unix { path } => Ok(build_unix_stream_source(
                 path,
@@ -167,6 +198,13 @@ impl SourceConfig for SyslogConfig {
         "syslog"
     }
 
+    fn default_on_backpressure(&self) -> BackpressurePolicy {
+        match self.mode {
+            Mode::Udp { .. } => BackpressurePolicy::Shed,
+            _ => BackpressurePolicy::Block,
+        }
+    }
+
     fn resources(&self) -> Vec<Resource> {
         match self.mode.clone() {
             Mode::Tcp { address, .. } => vec![address.into()],
@@ -303,58 +341,87 @@ pub fn udp(
     _max_length: usize,
     host_key: String,
     #[cfg(unix)] receive_buffer_bytes: Option<usize>,
+    workers: usize,
+    origin: OriginFilter,
     shutdown: ShutdownSignal,
     out: Pipeline,
 ) -> super::Source {
-    let out = out.sink_map_err(|error| error!(message = "Error sending line.", %error));
-
     Box::pin(async move {
-        let socket = UdpSocket::bind(&addr)
+        let sockets = udp::bind(addr, workers)
             .await
             .expect("Failed to bind to UDP listener socket");
 
         #[cfg(unix)]
         if let Some(receive_buffer_bytes) = receive_buffer_bytes {
-            udp::set_receive_buffer_size(&socket, receive_buffer_bytes);
+            for socket in &sockets {
+                udp::set_receive_buffer_size(socket, receive_buffer_bytes);
+            }
         }
 
         info!(
             message = "Listening.",
             addr = %addr,
-            r#type = "udp"
+            r#type = "udp",
+            workers = sockets.len()
         );
 
-        let _ = UdpFramed::new(socket, BytesCodec::new())
-            .take_until(shutdown)
-            .filter_map(|frame| {
-                let host_key = host_key.clone();
-                async move {
-                    match frame {
-                        Ok((bytes, received_from)) => {
-                            let received_from = received_from.ip().to_string().into();
-
-                            std::str::from_utf8(&bytes)
-                                .map_err(|error| emit!(SyslogUdpUtf8Error { error }))
-                                .ok()
-                                .and_then(|s| {
-                                    event_from_str(&host_key, Some(received_from), s).map(Ok)
-                                })
-                        }
-                        Err(error) => {
-                            emit!(SyslogUdpReadError { error });
-                            None
-                        }
-                    }
-                }
+        let workers = sockets
+            .into_iter()
+            .map(|socket| {
+                udp_receive(
+                    socket,
+                    host_key.clone(),
+                    origin.clone(),
+                    shutdown.clone(),
+                    out.clone(),
+                )
             })
-            .forward(out)
-            .await;
+            .collect();
+        let result = udp::run_workers(addr.port(), workers).await;
 
         info!("Finished sending.");
-        Ok(())
+        result
     })
 }
 
+async fn udp_receive(
+    socket: UdpSocket,
+    host_key: String,
+    origin: OriginFilter,
+    shutdown: ShutdownSignal,
+    out: Pipeline,
+) -> Result<(), ()> {
+    let out = out.sink_map_err(|error| error!(message = "Error sending line.", %error));
+
+    let _ = UdpFramed::new(socket, BytesCodec::new())
+        .take_until(shutdown)
+        .filter_map(|frame| {
+            let host_key = host_key.clone();
+            let origin = origin.clone();
+            async move {
+                match frame {
+                    Ok((_, received_from)) if !origin.check(received_from, SocketMode::Udp) => None,
+                    Ok((bytes, received_from)) => {
+                        let received_from = received_from.ip().to_string().into();
+
+                        std::str::from_utf8(&bytes)
+                            .map_err(|error| emit!(SyslogUdpUtf8Error { error }))
+                            .ok()
+                            .and_then(|s| event_from_str(&host_key, Some(received_from), s).map(Ok))
+                    }
+                    Err(error) => {
+                        emit!(SyslogUdpReadError { error });
+                        None
+                    }
+                }
+            }
+        })
+        .forward(out)
+        .await;
+
+    Ok(())
+}
+
 /// Function used to resolve the year for syslog messages that don't include the year.
 /// If the current month is January, and the syslog message is for December, it will take the previous year.
 /// Otherwise, take the current year.
@@ -549,6 +616,23 @@ mod test {
         assert!(config.mode.is_udp());
     }
 
+    #[test]
+    fn config_udp_with_workers() {
+        let config: SyslogConfig = toml::from_str(
+            r#"
+            mode = "udp"
+            address = "127.0.0.1:1235"
+            workers = 4
+          "#,
+        )
+        .unwrap();
+
+        match config.mode {
+            Mode::Udp { workers, .. } => assert_eq!(workers, 4),
+            _ => panic!("expected Mode::Udp"),
+        }
+    }
+
     #[cfg(unix)]
     #[test]
     fn config_udp_with_receive_buffer_size() {
@@ -0,0 +1,26 @@
+use crate::internal_events::HTTPRequestCompleted;
+use warp::{
+    http::header::CONTENT_LENGTH,
+    log::{Info, Log},
+};
+
+/// Wraps the routes of an HTTP source, emitting an event for every request
+/// it serves when `enabled`.
+pub fn access_log(enabled: bool) -> Log<impl Fn(Info<'_>) + Clone + Send + Sync> {
+    warp::log::custom(move |info: Info<'_>| {
+        if enabled {
+            emit!(HTTPRequestCompleted {
+                method: info.method().as_str(),
+                path: info.path(),
+                status: info.status().as_u16(),
+                byte_size: info
+                    .request_headers()
+                    .get(CONTENT_LENGTH)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse().ok()),
+                duration: info.elapsed(),
+                client: info.remote_addr(),
+            });
+        }
+    })
+}
@@ -0,0 +1,289 @@
+//! Splitting the bytes a source reads into frames, and decoding each frame
+//! into an event.
+
+use crate::{
+    config::log_schema,
+    event::{Event, LogEvent},
+};
+use bytes::{Bytes, BytesMut};
+use codec::BytesDelimitedCodec;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::io;
+use tokio_util::codec::{Decoder, LengthDelimitedCodec};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FramingConfig {
+    /// Frames end with a newline, optionally preceded by a carriage return.
+    NewlineDelimited,
+    /// Frames start with their length, as a 4 bytes big-endian integer.
+    LengthDelimited,
+}
+
+impl Default for FramingConfig {
+    fn default() -> Self {
+        FramingConfig::NewlineDelimited
+    }
+}
+
+impl FramingConfig {
+    /// Builds the framer, discarding the frames longer than `max_length`.
+    pub fn build(self, max_length: usize) -> Framer {
+        match self {
+            FramingConfig::NewlineDelimited => Framer::NewlineDelimited(
+                BytesDelimitedCodec::new_with_max_length(b'\n', max_length),
+            ),
+            FramingConfig::LengthDelimited => Framer::LengthDelimited(
+                LengthDelimitedCodec::builder()
+                    .max_frame_length(max_length)
+                    .new_codec(),
+            ),
+        }
+    }
+}
+
+pub enum Framer {
+    NewlineDelimited(BytesDelimitedCodec),
+    LengthDelimited(LengthDelimitedCodec),
+}
+
+impl Decoder for Framer {
+    type Item = Bytes;
+    type Error = io::Error;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Bytes>, io::Error> {
+        match self {
+            Framer::NewlineDelimited(codec) => Ok(codec.decode(buf)?.map(trim_carriage_return)),
+            Framer::LengthDelimited(codec) => Ok(codec.decode(buf)?.map(BytesMut::freeze)),
+        }
+    }
+
+    fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Bytes>, io::Error> {
+        match self {
+            Framer::NewlineDelimited(codec) => Ok(codec.decode_eof(buf)?.map(trim_carriage_return)),
+            Framer::LengthDelimited(codec) => Ok(codec.decode_eof(buf)?.map(BytesMut::freeze)),
+        }
+    }
+}
+
+fn trim_carriage_return(frame: Bytes) -> Bytes {
+    if frame.ends_with(b"\r") {
+        frame.slice(..frame.len() - 1)
+    } else {
+        frame
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DecodingConfig {
+    /// The frame is the message of the event.
+    Bytes,
+    /// The frame is a JSON object holding the fields of the event.
+    Json,
+}
+
+impl Default for DecodingConfig {
+    fn default() -> Self {
+        DecodingConfig::Bytes
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum DecodeError {
+    #[snafu(display("Invalid JSON: {}", source))]
+    InvalidJson { source: serde_json::Error },
+    #[snafu(display("Expected a JSON object, got {}", kind))]
+    NotAnObject { kind: &'static str },
+}
+
+impl DecodingConfig {
+    /// Decodes `frame` into a log event. The JSON objects without a timestamp
+    /// are given the current time.
+    pub fn decode(self, frame: Bytes) -> Result<Event, DecodeError> {
+        match self {
+            DecodingConfig::Bytes => Ok(Event::from(frame)),
+            DecodingConfig::Json => {
+                let fields = match serde_json::from_slice(&frame).context(InvalidJson)? {
+                    serde_json::Value::Object(fields) => fields,
+                    value => {
+                        return Err(DecodeError::NotAnObject {
+                            kind: json_kind(&value),
+                        })
+                    }
+                };
+
+                let mut log = LogEvent::default();
+                for (key, value) in fields {
+                    log.insert_flat(key, value);
+                }
+                if !log.contains(log_schema().timestamp_key()) {
+                    log.insert(log_schema().timestamp_key(), chrono::Utc::now());
+                }
+                Ok(Event::Log(log))
+            }
+        }
+    }
+}
+
+/// The output of the source receiving the events describing the frames that
+/// failed to decode, taken as input as `<source>.decode_errors`.
+pub const DECODE_ERRORS_OUTPUT: &str = "decode_errors";
+
+/// What the source does with the frames that fail to decode.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DecodeErrorBehavior {
+    /// Discards the frame.
+    Drop,
+    /// Sends an event describing the error to the `decode_errors` output of
+    /// the source, apart from the decoded events.
+    Forward,
+}
+
+impl Default for DecodeErrorBehavior {
+    fn default() -> Self {
+        DecodeErrorBehavior::Drop
+    }
+}
+
+/// The number of bytes of a frame kept in its decode error event.
+pub const DECODE_ERROR_RAW_MAX_BYTES: usize = 1024;
+
+impl DecodeErrorBehavior {
+    /// The outputs of the source besides its main one.
+    pub fn outputs(self) -> Vec<&'static str> {
+        match self {
+            DecodeErrorBehavior::Drop => Vec::new(),
+            DecodeErrorBehavior::Forward => vec![DECODE_ERRORS_OUTPUT],
+        }
+    }
+
+    /// The event sent to the `decode_errors` output for `frame`, which
+    /// failed to decode with `error`, if any. Its `decode_error` field holds
+    /// the error and the start of the frame.
+    pub fn error_event(self, frame: &[u8], error: &DecodeError) -> Option<Event> {
+        match self {
+            DecodeErrorBehavior::Drop => None,
+            DecodeErrorBehavior::Forward => {
+                let truncated = frame.len() > DECODE_ERROR_RAW_MAX_BYTES;
+                let raw = &frame[..frame.len().min(DECODE_ERROR_RAW_MAX_BYTES)];
+
+                let mut log = LogEvent::default();
+                log.insert("decode_error.message", error.to_string());
+                log.insert(
+                    "decode_error.raw",
+                    String::from_utf8_lossy(raw).into_owned(),
+                );
+                log.insert("decode_error.raw_bytes", frame.len() as i64);
+                log.insert("decode_error.truncated", truncated);
+                log.insert(log_schema().timestamp_key(), chrono::Utc::now());
+                Some(Event::Log(log))
+            }
+        }
+    }
+}
+
+fn json_kind(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "a boolean",
+        serde_json::Value::Number(_) => "a number",
+        serde_json::Value::String(_) => "a string",
+        serde_json::Value::Array(_) => "an array",
+        serde_json::Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames(framing: FramingConfig, input: &[u8]) -> Vec<Bytes> {
+        let mut framer = framing.build(16);
+        let mut buf = BytesMut::from(input);
+        let mut frames = Vec::new();
+        while let Some(frame) = framer.decode(&mut buf).unwrap() {
+            frames.push(frame);
+        }
+        while let Some(frame) = framer.decode_eof(&mut buf).unwrap() {
+            frames.push(frame);
+        }
+        frames
+    }
+
+    #[test]
+    fn frames_newline_delimited() {
+        assert_eq!(
+            frames(
+                FramingConfig::NewlineDelimited,
+                b"first\r\nthis one is too long\nlast"
+            ),
+            vec![Bytes::from("first"), Bytes::from("last")]
+        );
+    }
+
+    #[test]
+    fn frames_length_delimited() {
+        assert_eq!(
+            frames(
+                FramingConfig::LengthDelimited,
+                b"\x00\x00\x00\x05first\x00\x00\x00\x04last"
+            ),
+            vec![Bytes::from("first"), Bytes::from("last")]
+        );
+    }
+
+    #[test]
+    fn decodes_json_objects() {
+        let event = DecodingConfig::Json
+            .decode(Bytes::from(r#"{"message": "hello", "level": "info"}"#))
+            .unwrap();
+        let log = event.as_log();
+        assert_eq!(log[log_schema().message_key()], "hello".into());
+        assert_eq!(log["level"], "info".into());
+        assert!(log.contains(log_schema().timestamp_key()));
+
+        assert_eq!(
+            DecodingConfig::Json
+                .decode(Bytes::from("[1, 2]"))
+                .unwrap_err()
+                .to_string(),
+            "Expected a JSON object, got an array"
+        );
+        assert!(DecodingConfig::Json.decode(Bytes::from("{")).is_err());
+    }
+
+    #[test]
+    fn forwards_decode_errors() {
+        let frame = Bytes::from(r#"{"message": "cut sh"#);
+        let error = DecodingConfig::Json.decode(frame.clone()).unwrap_err();
+        assert!(DecodeErrorBehavior::Drop
+            .error_event(&frame, &error)
+            .is_none());
+
+        let event = DecodeErrorBehavior::Forward
+            .error_event(&frame, &error)
+            .unwrap();
+        let log = event.as_log();
+        assert_eq!(log["decode_error.message"], error.to_string().into());
+        assert_eq!(log["decode_error.raw"], r#"{"message": "cut sh"#.into());
+        assert_eq!(log["decode_error.raw_bytes"], 19.into());
+        assert_eq!(log["decode_error.truncated"], false.into());
+
+        let frame = vec![b'x'; DECODE_ERROR_RAW_MAX_BYTES + 1];
+        let error = DecodingConfig::Json
+            .decode(Bytes::from(frame.clone()))
+            .unwrap_err();
+        let event = DecodeErrorBehavior::Forward
+            .error_event(&frame, &error)
+            .unwrap();
+        let log = event.as_log();
+        assert_eq!(
+            log["decode_error.raw"],
+            "x".repeat(DECODE_ERROR_RAW_MAX_BYTES).into()
+        );
+        assert_eq!(log["decode_error.truncated"], true.into());
+    }
+}
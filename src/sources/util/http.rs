@@ -1,6 +1,9 @@
 use crate::{
     event::Event,
-    internal_events::{HTTPBadRequest, HTTPDecompressError, HTTPEventsReceived},
+    internal_events::{
+        HTTPAuthenticationFailed, HTTPBadRequest, HTTPDecompressError, HTTPEventsReceived,
+    },
+    origin::OriginFilter,
     shutdown::ShutdownSignal,
     tls::{MaybeTlsSettings, TlsConfig},
     Pipeline,
@@ -10,6 +13,11 @@ use bytes::{buf::BufExt, Bytes};
 use flate2::read::{DeflateDecoder, GzDecoder};
 use futures::{FutureExt, SinkExt, StreamExt, TryFutureExt};
 use headers::{Authorization, HeaderMapExt};
+use openssl::{
+    hash::MessageDigest,
+    pkey::{PKey, Private},
+    sign::Signer,
+};
 use serde::{Deserialize, Serialize};
 use snap::raw::Decoder as SnappyDecoder;
 use std::{collections::HashMap, convert::TryFrom, error::Error, fmt, io::Read, net::SocketAddr};
@@ -42,8 +50,8 @@ pub(crate) fn add_query_parameters(
 
 #[derive(Serialize, Debug)]
 pub struct ErrorMessage {
-    code: u16,
-    message: String,
+    pub(crate) code: u16,
+    pub(crate) message: String,
 }
 impl ErrorMessage {
     pub fn new(code: StatusCode, message: String) -> Self {
@@ -69,63 +77,203 @@ impl fmt::Debug for RejectShuttingDown {
 }
 impl warp::reject::Reject for RejectShuttingDown {}
 
+/// The authentication required from the clients of an HTTP source.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum HttpSourceAuthConfig {
+    Strategy(HttpSourceAuthStrategy),
+    /// Basic authentication, configured without a `strategy`.
+    Basic {
+        username: String,
+        password: String,
+    },
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
-pub struct HttpSourceAuthConfig {
-    pub username: String,
-    pub password: String,
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+pub enum HttpSourceAuthStrategy {
+    Basic {
+        username: String,
+        password: String,
+    },
+    /// Any of the tokens is accepted, so that they can be rotated.
+    Bearer {
+        tokens: Vec<String>,
+    },
+    /// A signature of the body in a header, as sent by webhooks.
+    Hmac {
+        secret: String,
+        header: String,
+        #[serde(default)]
+        algorithm: HmacAlgorithm,
+        /// Prepended to the hex encoded signature, such as `sha256=`.
+        #[serde(default)]
+        prefix: String,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum HmacAlgorithm {
+    Sha1,
+    #[derivative(Default)]
+    Sha256,
+}
+
+impl HmacAlgorithm {
+    fn digest(self) -> MessageDigest {
+        match self {
+            HmacAlgorithm::Sha1 => MessageDigest::sha1(),
+            HmacAlgorithm::Sha256 => MessageDigest::sha256(),
+        }
+    }
 }
 
 impl TryFrom<Option<&HttpSourceAuthConfig>> for HttpSourceAuth {
     type Error = String;
 
     fn try_from(auth: Option<&HttpSourceAuthConfig>) -> Result<Self, Self::Error> {
-        match auth {
-            Some(auth) => {
+        let strategy = match auth {
+            Some(HttpSourceAuthConfig::Strategy(strategy)) => strategy.clone(),
+            Some(HttpSourceAuthConfig::Basic { username, password }) => {
+                HttpSourceAuthStrategy::Basic {
+                    username: username.clone(),
+                    password: password.clone(),
+                }
+            }
+            None => return Ok(HttpSourceAuth::None),
+        };
+
+        match strategy {
+            HttpSourceAuthStrategy::Basic { username, password } => {
                 let mut headers = HeaderMap::new();
-                headers.typed_insert(Authorization::basic(&auth.username, &auth.password));
+                headers.typed_insert(Authorization::basic(&username, &password));
                 match headers.get("authorization") {
                     Some(value) => {
                         let token = value
                             .to_str()
                             .map_err(|error| format!("Failed stringify HeaderValue: {:?}", error))?
                             .to_owned();
-                        Ok(HttpSourceAuth { token: Some(token) })
+                        Ok(HttpSourceAuth::Authorization {
+                            accepted: vec![token],
+                        })
                     }
                     None => Err("Authorization headers wasn't generated".to_owned()),
                 }
             }
-            None => Ok(HttpSourceAuth { token: None }),
+            HttpSourceAuthStrategy::Bearer { tokens } => {
+                if tokens.is_empty() {
+                    return Err("At least one bearer token must be configured".to_owned());
+                }
+                Ok(HttpSourceAuth::Authorization {
+                    accepted: tokens
+                        .iter()
+                        .map(|token| format!("Bearer {}", token))
+                        .collect(),
+                })
+            }
+            HttpSourceAuthStrategy::Hmac {
+                secret,
+                header,
+                algorithm,
+                prefix,
+            } => Ok(HttpSourceAuth::Hmac {
+                key: PKey::hmac(secret.as_bytes())
+                    .map_err(|error| format!("Invalid HMAC secret: {}", error))?,
+                header,
+                algorithm,
+                prefix,
+            }),
         }
     }
 }
 
 #[derive(Debug, Clone)]
-struct HttpSourceAuth {
-    pub token: Option<String>,
+enum HttpSourceAuth {
+    None,
+    /// The accepted values of the `Authorization` header.
+    Authorization {
+        accepted: Vec<String>,
+    },
+    Hmac {
+        key: PKey<Private>,
+        header: String,
+        algorithm: HmacAlgorithm,
+        prefix: String,
+    },
 }
 
 impl HttpSourceAuth {
-    pub fn is_valid(&self, header: &Option<String>) -> Result<(), ErrorMessage> {
-        match (&self.token, header) {
-            (Some(token1), Some(token2)) => {
-                if token1 == token2 {
-                    Ok(())
-                } else {
-                    Err(ErrorMessage::new(
-                        StatusCode::UNAUTHORIZED,
-                        "Invalid username/password".to_owned(),
-                    ))
+    pub fn is_valid(&self, headers: &HeaderMap, body: &[u8]) -> Result<(), ErrorMessage> {
+        match self {
+            HttpSourceAuth::None => Ok(()),
+            HttpSourceAuth::Authorization { accepted } => match headers.get("authorization") {
+                Some(value) => {
+                    if accepted
+                        .iter()
+                        .any(|token| constant_time_eq(token.as_bytes(), value.as_bytes()))
+                    {
+                        Ok(())
+                    } else {
+                        Err(unauthorized("invalid_credentials", "Invalid credentials"))
+                    }
                 }
-            }
-            (Some(_), None) => Err(ErrorMessage::new(
-                StatusCode::UNAUTHORIZED,
-                "No authorization header".to_owned(),
-            )),
-            (None, _) => Ok(()),
+                None => Err(unauthorized(
+                    "missing_credentials",
+                    "No authorization header",
+                )),
+            },
+            HttpSourceAuth::Hmac {
+                key,
+                header,
+                algorithm,
+                prefix,
+            } => match headers.get(header.as_str()) {
+                Some(value) => {
+                    let signature = sign(key, *algorithm, body).map_err(|error| {
+                        ErrorMessage::new(
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            format!("Failed computing signature: {}", error),
+                        )
+                    })?;
+                    let expected = format!("{}{}", prefix, signature);
+                    if constant_time_eq(expected.as_bytes(), value.as_bytes()) {
+                        Ok(())
+                    } else {
+                        Err(unauthorized("invalid_signature", "Invalid signature"))
+                    }
+                }
+                None => Err(unauthorized("missing_signature", "No signature header")),
+            },
         }
     }
 }
 
+/// The hex encoded HMAC of `body`.
+fn sign(
+    key: &PKey<Private>,
+    algorithm: HmacAlgorithm,
+    body: &[u8],
+) -> Result<String, openssl::error::ErrorStack> {
+    let mut signer = Signer::new(algorithm.digest(), key)?;
+    signer.update(body)?;
+    Ok(signer
+        .sign_to_vec()?
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && openssl::memcmp::eq(a, b)
+}
+
+fn unauthorized(reason: &'static str, message: &str) -> ErrorMessage {
+    emit!(HTTPAuthenticationFailed { reason });
+    ErrorMessage::new(StatusCode::UNAUTHORIZED, message.to_owned())
+}
+
 pub fn decode(header: &Option<String>, mut body: Bytes) -> Result<Bytes, ErrorMessage> {
     if let Some(encodings) = header {
         for encoding in encodings.rsplit(',').map(str::trim) {
@@ -182,17 +330,24 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
         query_parameters: HashMap<String, String>,
     ) -> Result<Vec<Event>, ErrorMessage>;
 
+    /// Whether every request served is logged and counted.
+    fn access_log(&self) -> bool {
+        false
+    }
+
     fn run(
         self,
         address: SocketAddr,
         path: &'static str,
         tls: &Option<TlsConfig>,
         auth: &Option<HttpSourceAuthConfig>,
+        origin: OriginFilter,
         out: Pipeline,
         shutdown: ShutdownSignal,
     ) -> crate::Result<crate::sources::Source> {
         let tls = MaybeTlsSettings::from_config(tls, true)?;
         let auth = HttpSourceAuth::try_from(auth.as_ref())?;
+        let access_log = self.access_log();
         Ok(Box::pin(async move {
             let span = crate::trace::current_span();
 
@@ -204,14 +359,12 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
             }
             let svc = filter
                 .and(warp::path::end())
-                .and(warp::header::optional::<String>("authorization"))
                 .and(warp::header::optional::<String>("content-encoding"))
                 .and(warp::header::headers_cloned())
                 .and(warp::body::bytes())
                 .and(warp::query::<HashMap<String, String>>())
                 .and_then(
-                    move |auth_header,
-                          encoding_header,
+                    move |encoding_header,
                           headers: HeaderMap,
                           body: Bytes,
                           query_parameters: HashMap<String, String>| {
@@ -221,7 +374,7 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                         let mut out = out.clone();
 
                         let events = auth
-                            .is_valid(&auth_header)
+                            .is_valid(&headers, &body)
                             .and_then(|()| decode(&encoding_header, body))
                             .and_then(|body| {
                                 let body_len=body.len();
@@ -261,23 +414,26 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                 );
 
             let ping = warp::get().and(warp::path("ping")).map(|| "pong");
-            let routes = svc.or(ping).recover(|r: Rejection| async move {
-                if let Some(e_msg) = r.find::<ErrorMessage>() {
-                    let json = warp::reply::json(e_msg);
-                    Ok(warp::reply::with_status(
-                        json,
-                        StatusCode::from_u16(e_msg.code)
-                            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
-                    ))
-                } else {
-                    //other internal error - will return 500 internal server error
-                    Err(r)
-                }
-            });
+            let routes = svc
+                .or(ping)
+                .recover(|r: Rejection| async move {
+                    if let Some(e_msg) = r.find::<ErrorMessage>() {
+                        let json = warp::reply::json(e_msg);
+                        Ok(warp::reply::with_status(
+                            json,
+                            StatusCode::from_u16(e_msg.code)
+                                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                        ))
+                    } else {
+                        //other internal error - will return 500 internal server error
+                        Err(r)
+                    }
+                })
+                .with(super::access_log(access_log));
 
             info!(message = "Building HTTP server.", address = %address);
 
-            let listener = tls.bind(&address).await.unwrap();
+            let listener = tls.bind(&address).await.unwrap().with_origin(origin);
             let _ = warp::serve(routes)
                 .serve_incoming_with_graceful_shutdown(
                     listener.accept_stream(),
@@ -290,3 +446,107 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use warp::http::HeaderValue;
+
+    fn auth(config: &str) -> HttpSourceAuth {
+        let config: HttpSourceAuthConfig = toml::from_str(config).unwrap();
+        HttpSourceAuth::try_from(Some(&config)).unwrap()
+    }
+
+    fn headers(name: &'static str, value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_static(value));
+        headers
+    }
+
+    fn status(result: Result<(), ErrorMessage>) -> Option<u16> {
+        result.err().map(|error| error.code)
+    }
+
+    #[test]
+    fn basic_without_strategy() {
+        let auth = auth(
+            r#"
+            username = "user"
+            password = "pass"
+            "#,
+        );
+
+        let valid = headers("authorization", "Basic dXNlcjpwYXNz");
+        assert_eq!(status(auth.is_valid(&valid, b"")), None);
+        let invalid = headers("authorization", "Basic dXNlcjpvdGhlcg==");
+        assert_eq!(status(auth.is_valid(&invalid, b"")), Some(401));
+        assert_eq!(status(auth.is_valid(&HeaderMap::new(), b"")), Some(401));
+    }
+
+    #[test]
+    fn bearer_tokens() {
+        let auth = auth(
+            r#"
+            strategy = "bearer"
+            tokens = ["old", "new"]
+            "#,
+        );
+
+        let old = headers("authorization", "Bearer old");
+        assert_eq!(status(auth.is_valid(&old, b"")), None);
+        let new = headers("authorization", "Bearer new");
+        assert_eq!(status(auth.is_valid(&new, b"")), None);
+        let other = headers("authorization", "Bearer other");
+        assert_eq!(status(auth.is_valid(&other, b"")), Some(401));
+    }
+
+    #[test]
+    fn bearer_requires_tokens() {
+        let config: HttpSourceAuthConfig = toml::from_str(
+            r#"
+            strategy = "bearer"
+            tokens = []
+            "#,
+        )
+        .unwrap();
+        assert!(HttpSourceAuth::try_from(Some(&config)).is_err());
+    }
+
+    #[test]
+    fn hmac_signature() {
+        let auth = auth(
+            r#"
+            strategy = "hmac"
+            secret = "It's a Secret to Everybody"
+            header = "X-Hub-Signature-256"
+            prefix = "sha256="
+            "#,
+        );
+
+        let signed = headers(
+            "x-hub-signature-256",
+            "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17",
+        );
+        assert_eq!(status(auth.is_valid(&signed, b"Hello, World!")), None);
+        assert_eq!(status(auth.is_valid(&signed, b"Hello, World?")), Some(401));
+        assert_eq!(
+            status(auth.is_valid(&HeaderMap::new(), b"Hello, World!")),
+            Some(401)
+        );
+    }
+
+    #[test]
+    fn hmac_sha1_signature() {
+        let auth = auth(
+            r#"
+            strategy = "hmac"
+            secret = "It's a Secret to Everybody"
+            header = "X-Signature"
+            algorithm = "sha1"
+            "#,
+        );
+
+        let signed = headers("x-signature", "01dc10d0c83e72ed246219cdd91669667fe2ca59");
+        assert_eq!(status(auth.is_valid(&signed, b"Hello, World!")), None);
+    }
+}
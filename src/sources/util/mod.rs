@@ -1,8 +1,13 @@
+#[cfg(any(feature = "sources-utils-http", feature = "sources-splunk_hec"))]
+mod access_log;
+pub mod decoding;
 mod encoding_config;
 #[cfg(feature = "sources-utils-fake")]
 pub mod fake;
 #[cfg(feature = "sources-utils-http")]
 mod http;
+#[cfg(feature = "sources-utils-metrics-scrape")]
+mod metrics_scrape;
 pub mod multiline_config;
 #[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
 mod tcp;
@@ -11,12 +16,17 @@ mod unix_datagram;
 #[cfg(all(unix, feature = "sources-utils-unix"))]
 mod unix_stream;
 
+#[cfg(any(feature = "sources-utils-http", feature = "sources-splunk_hec"))]
+pub(crate) use self::access_log::access_log;
 #[cfg(any(feature = "sources-http", feature = "sources-heroku_logs"))]
 pub(crate) use self::http::add_query_parameters;
 #[cfg(feature = "sources-prometheus")]
 pub(crate) use self::http::decode;
 #[cfg(feature = "sources-utils-http")]
 pub(crate) use self::http::{ErrorMessage, HttpSource, HttpSourceAuthConfig};
+#[cfg(feature = "sources-utils-metrics-scrape")]
+pub(crate) use self::metrics_scrape::{MetricsEndpoint, ScrapeError, ScrapeTarget};
+pub use decoding::{DecodeErrorBehavior, DecodingConfig, FramingConfig, DECODE_ERRORS_OUTPUT};
 pub use encoding_config::EncodingConfig;
 pub use multiline_config::MultilineConfig;
 #[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
@@ -1,6 +1,7 @@
 use crate::{
     config::Resource,
     internal_events::{ConnectionOpen, OpenGauge, TcpSocketConnectionError},
+    origin::OriginFilter,
     shutdown::ShutdownSignal,
     tcp::TcpKeepaliveConfig,
     tls::{MaybeTlsIncomingStream, MaybeTlsListener, MaybeTlsSettings},
@@ -68,6 +69,7 @@ pub trait TcpSource: Clone + Send + Sync + 'static {
         shutdown_timeout_secs: u64,
         tls: MaybeTlsSettings,
         receive_buffer_bytes: Option<usize>,
+        origin: OriginFilter,
         shutdown: ShutdownSignal,
         out: Pipeline,
     ) -> crate::Result<crate::sources::Source> {
@@ -78,7 +80,7 @@ pub trait TcpSource: Clone + Send + Sync + 'static {
         Ok(Box::pin(async move {
             let listener = match make_listener(addr, listenfd, &tls).await {
                 None => return Err(()),
-                Some(listener) => listener,
+                Some(listener) => listener.with_origin(origin),
             };
 
             info!(
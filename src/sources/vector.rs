@@ -3,6 +3,7 @@ use crate::{
     config::{DataType, GenerateConfig, GlobalOptions, Resource, SourceConfig, SourceDescription},
     event::proto,
     internal_events::{VectorEventReceived, VectorProtoDecodeError},
+    origin::OriginFilter,
     shutdown::ShutdownSignal,
     tcp::TcpKeepaliveConfig,
     tls::{MaybeTlsSettings, TlsConfig},
@@ -19,11 +20,17 @@ use tokio_util::codec::LengthDelimitedCodec;
 pub struct VectorConfig {
     address: SocketListenAddr,
     keepalive: Option<TcpKeepaliveConfig>,
-    #[serde(default = "default_shutdown_timeout_secs")]
+    #[serde(
+        default = "default_shutdown_timeout_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
     shutdown_timeout_secs: u64,
     #[set = "pub"]
     tls: Option<TlsConfig>,
+    #[serde(default, deserialize_with = "crate::serde::optional_bytes")]
     receive_buffer_bytes: Option<usize>,
+    permit_origin: Option<Vec<String>>,
+    deny_origin: Option<Vec<String>>,
 }
 
 fn default_shutdown_timeout_secs() -> u64 {
@@ -38,6 +45,8 @@ impl VectorConfig {
             shutdown_timeout_secs: default_shutdown_timeout_secs(),
             tls: None,
             receive_buffer_bytes: None,
+            permit_origin: None,
+            deny_origin: None,
         }
     }
 }
@@ -67,12 +76,14 @@ impl SourceConfig for VectorConfig {
     ) -> crate::Result<super::Source> {
         let vector = VectorSource;
         let tls = MaybeTlsSettings::from_config(&self.tls, true)?;
+        let origin = OriginFilter::new(&self.permit_origin, &self.deny_origin)?;
         vector.run(
             self.address,
             self.keepalive,
             self.shutdown_timeout_secs,
             tls,
             self.receive_buffer_bytes,
+            origin,
             shutdown,
             out,
         )
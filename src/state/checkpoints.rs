@@ -0,0 +1,69 @@
+use super::StateStore;
+use file_source::CheckpointStore;
+use std::{io, sync::Arc};
+use tokio::runtime::Handle;
+
+/// The key the checkpoints are stored under. It is the name of the file the
+/// file server kept them in, so the local backend reads the checkpoints of
+/// the previous versions.
+const KEY: &str = "checkpoints.json";
+
+/// Keeps the checkpoints of a `file_source::FileServer` in a `StateStore`.
+/// The file server runs on a blocking thread, which blocks on the store.
+pub struct FileCheckpoints {
+    store: Arc<dyn StateStore>,
+    handle: Handle,
+}
+
+impl FileCheckpoints {
+    pub fn new(store: Arc<dyn StateStore>) -> Self {
+        Self {
+            store,
+            handle: Handle::current(),
+        }
+    }
+}
+
+impl CheckpointStore for FileCheckpoints {
+    fn get(&self) -> Result<Option<Vec<u8>>, io::Error> {
+        self.handle
+            .block_on(self.store.get(KEY))
+            .map(|value| value.map(|value| value.to_vec()))
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))
+    }
+
+    fn put(&self, checkpoints: Vec<u8>) -> Result<(), io::Error> {
+        self.handle
+            .block_on(self.store.put(KEY, checkpoints.into()))
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::LocalStore;
+    use file_source::Checkpointer;
+    use tempfile::tempdir;
+
+    #[tokio::test(core_threads = 2)]
+    async fn reads_the_checkpoint_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_path_buf();
+        let store = FileCheckpoints::new(Arc::new(LocalStore::new(path.clone())));
+
+        tokio::task::spawn_blocking(move || {
+            assert_eq!(store.get().unwrap(), None);
+
+            // The checkpoints written before the store are found in it.
+            Checkpointer::new(&path).write_checkpoints().unwrap();
+            let written = std::fs::read(path.join(KEY)).unwrap();
+            assert_eq!(store.get().unwrap(), Some(written));
+
+            store.put(b"{}".to_vec()).unwrap();
+            assert_eq!(std::fs::read(path.join(KEY)).unwrap(), b"{}");
+        })
+        .await
+        .unwrap();
+    }
+}
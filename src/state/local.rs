@@ -0,0 +1,90 @@
+use super::StateStore;
+use bytes::Bytes;
+use snafu::{ResultExt, Snafu};
+use std::{io, path::PathBuf};
+use tokio::{fs, io::AsyncWriteExt};
+
+#[derive(Debug, Snafu)]
+enum LocalStoreError {
+    #[snafu(display("Could not read state file {:?}: {}", path, source))]
+    Read { path: PathBuf, source: io::Error },
+    #[snafu(display("Could not write state file {:?}: {}", path, source))]
+    Write { path: PathBuf, source: io::Error },
+    #[snafu(display("Could not delete state file {:?}: {}", path, source))]
+    Delete { path: PathBuf, source: io::Error },
+}
+
+/// Stores each key in a file of the same name in `dir`.
+pub struct LocalStore {
+    dir: PathBuf,
+}
+
+impl LocalStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+}
+
+#[async_trait::async_trait]
+impl StateStore for LocalStore {
+    async fn get(&self, key: &str) -> crate::Result<Option<Bytes>> {
+        let path = self.dir.join(key);
+        match fs::read(&path).await {
+            Ok(data) => Ok(Some(data.into())),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(LocalStoreError::Read { path, source }.into()),
+        }
+    }
+
+    async fn put(&self, key: &str, value: Bytes) -> crate::Result<()> {
+        let path = self.dir.join(key);
+        let tmp = self.dir.join(format!("{}.tmp", key));
+
+        // Write to a temporary file, flushed to disk, first so a crash can't
+        // leave a partially written value behind.
+        let mut file = fs::File::create(&tmp)
+            .await
+            .context(Write { path: tmp.clone() })?;
+        file.write_all(&value)
+            .await
+            .context(Write { path: tmp.clone() })?;
+        file.sync_all().await.context(Write { path: tmp.clone() })?;
+        fs::rename(&tmp, &path).await.context(Write { path })?;
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> crate::Result<()> {
+        let path = self.dir.join(key);
+        match fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(source) => Err(LocalStoreError::Delete { path, source }.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn stores_values() {
+        let dir = tempdir().unwrap();
+        let store = LocalStore::new(dir.path().to_path_buf());
+
+        assert_eq!(store.get("cursor").await.unwrap(), None);
+
+        store.put("cursor", "first".into()).await.unwrap();
+        store.put("cursor", "second".into()).await.unwrap();
+        assert_eq!(
+            store.get("cursor").await.unwrap(),
+            Some(Bytes::from("second"))
+        );
+        assert!(!dir.path().join("cursor.tmp").exists());
+
+        store.delete("cursor").await.unwrap();
+        assert_eq!(store.get("cursor").await.unwrap(), None);
+        store.delete("cursor").await.unwrap();
+    }
+}
@@ -0,0 +1,94 @@
+//! Keyed storage for component state.
+//!
+//! Components that need to remember where they left off, such as the cursor
+//! of the `journald` source, store it under a key through a `StateStore`
+//! rather than writing to the `data_dir` themselves. By default the state
+//! lives in the component's subdirectory of the `data_dir`, but it can be
+//! moved to a remote backend so that instances without a persistent disk,
+//! like containers, keep their state across restarts.
+//!
+//! This includes the checkpoints of the sources reading files, like `file`,
+//! which the file server writes through `FileCheckpoints`. Disk buffers and
+//! the `dedupe` cache are out of scope: they are logs appended to on every
+//! event, which a store of values replaced as a whole can't hold efficiently,
+//! so they stay in the `data_dir` whatever the backend.
+
+#[cfg(feature = "file-source")]
+mod checkpoints;
+mod local;
+#[cfg(feature = "state-s3")]
+mod s3;
+
+#[cfg(feature = "file-source")]
+pub use self::checkpoints::FileCheckpoints;
+pub use self::local::LocalStore;
+#[cfg(feature = "state-s3")]
+pub use self::s3::{S3StateConfig, S3Store};
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum StateOptions {
+    /// Files in the component's subdirectory of the `data_dir`.
+    Local,
+    /// Objects in an S3 bucket, one per component and key.
+    #[cfg(feature = "state-s3")]
+    S3(S3StateConfig),
+}
+
+impl Default for StateOptions {
+    fn default() -> Self {
+        StateOptions::Local
+    }
+}
+
+impl StateOptions {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn merge(&mut self, other: Self) -> Result<(), String> {
+        if other.is_default() {
+            return Ok(());
+        }
+        if !self.is_default() && *self != other {
+            return Err("conflicting values for 'state' found".to_owned());
+        }
+        *self = other;
+        Ok(())
+    }
+}
+
+/// Storage for the state of a single component.
+///
+/// Values are replaced as a whole, so a reader only ever sees a value that
+/// was completely written.
+#[async_trait::async_trait]
+pub trait StateStore: Send + Sync {
+    async fn get(&self, key: &str) -> crate::Result<Option<Bytes>>;
+
+    async fn put(&self, key: &str, value: Bytes) -> crate::Result<()>;
+
+    /// Removes `key`, doing nothing if it isn't set.
+    async fn delete(&self, key: &str) -> crate::Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_to_local() {
+        let options: StateOptions = toml::from_str(r#"backend = "local""#).unwrap();
+        assert!(options.is_default());
+    }
+
+    #[test]
+    fn merges_options() {
+        let mut options = StateOptions::default();
+        assert!(options.merge(StateOptions::default()).is_ok());
+        assert_eq!(options, StateOptions::Local);
+    }
+}
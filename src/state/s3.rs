@@ -0,0 +1,136 @@
+use super::StateStore;
+use crate::rusoto::{self, AWSAuthentication, RegionOrEndpoint};
+use bytes::Bytes;
+use http::StatusCode;
+use rusoto_core::RusotoError;
+use rusoto_s3::{
+    DeleteObjectError, DeleteObjectRequest, GetObjectError, GetObjectRequest, PutObjectError,
+    PutObjectRequest, S3Client, S3,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::convert::TryInto;
+use tokio::io::AsyncReadExt;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct S3StateConfig {
+    pub bucket: String,
+    #[serde(default = "default_key_prefix")]
+    pub key_prefix: String,
+    #[serde(flatten)]
+    pub region: RegionOrEndpoint,
+    #[serde(default)]
+    pub auth: AWSAuthentication,
+}
+
+fn default_key_prefix() -> String {
+    "vector/state/".to_owned()
+}
+
+#[derive(Debug, Snafu)]
+enum S3StoreError {
+    #[snafu(display("Could not get state object {:?}: {}", key, source))]
+    GetObject {
+        key: String,
+        source: RusotoError<GetObjectError>,
+    },
+    #[snafu(display("Could not read state object {:?}: {}", key, source))]
+    ReadObject { key: String, source: std::io::Error },
+    #[snafu(display("Could not put state object {:?}: {}", key, source))]
+    PutObject {
+        key: String,
+        source: RusotoError<PutObjectError>,
+    },
+    #[snafu(display("Could not delete state object {:?}: {}", key, source))]
+    DeleteObject {
+        key: String,
+        source: RusotoError<DeleteObjectError>,
+    },
+}
+
+/// Stores each key in an object named `<key_prefix><component>/<key>`.
+pub struct S3Store {
+    client: S3Client,
+    bucket: String,
+    prefix: String,
+}
+
+impl S3Store {
+    pub fn new(config: &S3StateConfig, component: &str) -> crate::Result<Self> {
+        let region = (&config.region).try_into()?;
+        let client = rusoto::client()?;
+        let creds = config.auth.build(&region, None)?;
+
+        Ok(Self {
+            client: S3Client::new_with(client, creds, region),
+            bucket: config.bucket.clone(),
+            prefix: format!("{}{}/", config.key_prefix, component),
+        })
+    }
+
+    fn object_key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+}
+
+#[async_trait::async_trait]
+impl StateStore for S3Store {
+    async fn get(&self, key: &str) -> crate::Result<Option<Bytes>> {
+        let key = self.object_key(key);
+        let request = GetObjectRequest {
+            bucket: self.bucket.clone(),
+            key: key.clone(),
+            ..Default::default()
+        };
+
+        let object = match self.client.get_object(request).await {
+            Ok(object) => object,
+            Err(RusotoError::Service(GetObjectError::NoSuchKey(_))) => return Ok(None),
+            Err(RusotoError::Unknown(response)) if response.status == StatusCode::NOT_FOUND => {
+                return Ok(None)
+            }
+            Err(source) => return Err(S3StoreError::GetObject { key, source }.into()),
+        };
+
+        let mut data = Vec::new();
+        if let Some(body) = object.body {
+            body.into_async_read()
+                .read_to_end(&mut data)
+                .await
+                .context(ReadObject { key })?;
+        }
+        Ok(Some(data.into()))
+    }
+
+    async fn put(&self, key: &str, value: Bytes) -> crate::Result<()> {
+        let key = self.object_key(key);
+        let request = PutObjectRequest {
+            bucket: self.bucket.clone(),
+            key: key.clone(),
+            body: Some(value.to_vec().into()),
+            ..Default::default()
+        };
+
+        self.client
+            .put_object(request)
+            .await
+            .context(PutObject { key })?;
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> crate::Result<()> {
+        // Deleting a missing object succeeds, so there is nothing to ignore.
+        let key = self.object_key(key);
+        let request = DeleteObjectRequest {
+            bucket: self.bucket.clone(),
+            key: key.clone(),
+            ..Default::default()
+        };
+
+        self.client
+            .delete_object(request)
+            .await
+            .context(DeleteObject { key })?;
+        Ok(())
+    }
+}
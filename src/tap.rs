@@ -0,0 +1,168 @@
+//! The `vector tap` command, printing a sample of the events flowing out of a
+//! source or transform of a running Vector instance.
+
+use crate::config;
+use colored::*;
+use indoc::indoc;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use tokio::stream::StreamExt;
+use url::Url;
+use vector_api_client::{
+    connect_subscription_client_with_options,
+    gql::{EventsSubscriptionExt, HealthQueryExt},
+    Client, ClientOptions,
+};
+
+#[derive(StructOpt, Debug, Clone)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// Name of the source or transform to sample the output events of
+    component: String,
+
+    /// Interval to sample events at, in milliseconds
+    #[structopt(default_value = "500", short = "i", long)]
+    interval: u32,
+
+    /// Maximum number of events printed per interval, the others being skipped
+    #[structopt(default_value = "100", short = "l", long)]
+    limit: u32,
+
+    /// Vector GraphQL API server endpoint
+    #[structopt(short, long)]
+    url: Option<Url>,
+
+    /// Token to authenticate with, if the API server requires one
+    #[structopt(long, env = "VECTOR_API_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+
+    /// PEM encoded CA certificate to verify the API server with
+    #[structopt(long, parse(from_os_str))]
+    ca_file: Option<PathBuf>,
+
+    /// PKCS#12 archive with a client certificate and key, for mutual TLS
+    #[structopt(long, parse(from_os_str))]
+    identity_file: Option<PathBuf>,
+
+    /// Password of the client identity archive
+    #[structopt(long)]
+    identity_pass: Option<String>,
+}
+
+impl Opts {
+    fn client_options(&self) -> ClientOptions {
+        ClientOptions {
+            token: self.token.clone(),
+            ca_file: self.ca_file.clone(),
+            identity_file: self.identity_file.clone(),
+            identity_pass: self.identity_pass.clone(),
+        }
+    }
+}
+
+/// Pretty-prints an event received as JSON, as is if it can't be parsed.
+fn pretty_json(json: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(json)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+        .unwrap_or_else(|_| json.to_owned())
+}
+
+/// CLI command func for printing the events flowing out of a component, sampled by the
+/// Vector API server, until the component stops
+pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let url = opts.url.clone().unwrap_or_else(|| {
+        let addr = config::api::default_address().unwrap();
+        Url::parse(&*format!("http://{}/graphql", addr))
+            .expect("Couldn't parse default API URL. Please report this.")
+    });
+    let client_options = opts.client_options();
+
+    let client = match Client::with_options(url.clone(), &client_options) {
+        Ok(client) => client,
+        Err(error) => {
+            eprintln!("Couldn't create Vector API client: {:#}", error);
+            return exitcode::CONFIG;
+        }
+    };
+
+    // Check that the GraphQL server is reachable
+    if client.health_query().await.is_err() {
+        eprintln!(
+            indoc! {"
+                Vector API server isn't reachable ({}).
+
+                Have you enabled the API?
+
+                To enable the API, add the following to your `vector.toml` config file:
+
+                [api]
+                  enabled = true"},
+            url
+        );
+        return exitcode::UNAVAILABLE;
+    }
+
+    // Change the HTTP schema to WebSockets
+    let mut ws_url = url.clone();
+    ws_url
+        .set_scheme(match url.scheme() {
+            "https" => "wss",
+            _ => "ws",
+        })
+        .expect("Couldn't build WebSocket URL. Please report.");
+
+    let subscription_client =
+        match connect_subscription_client_with_options(ws_url, &client_options).await {
+            Ok(client) => client,
+            Err(error) => {
+                eprintln!(
+                    "Couldn't connect to Vector API via WebSockets ({}): {:?}",
+                    url, error
+                );
+                return exitcode::UNAVAILABLE;
+            }
+        };
+
+    let subscription = subscription_client.output_events_subscription(
+        opts.component.clone(),
+        opts.interval as i64,
+        opts.limit as i64,
+    );
+    tokio::pin! {
+        let stream = subscription.stream();
+    };
+
+    while let Some(Some(response)) = stream.next().await {
+        if let Some(errors) = response.errors {
+            for error in errors {
+                eprintln!("{}", error.message.red());
+            }
+            return exitcode::USAGE;
+        }
+        if let Some(data) = response.data {
+            for event in data.output_events {
+                println!("{}", pretty_json(&event.json));
+            }
+        }
+    }
+
+    eprintln!(
+        "Stopped sampling {:?}, the component or the API server stopped.",
+        opts.component
+    );
+    exitcode::OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pretty_prints_events() {
+        assert_eq!(
+            pretty_json(r#"{"message":"hello"}"#),
+            "{\n  \"message\": \"hello\"\n}"
+        );
+        assert_eq!(pretty_json("not json"), "not json");
+    }
+}
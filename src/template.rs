@@ -156,6 +156,7 @@ fn render_fields(src: &str, event: &Event) -> Result<String, Vec<String>> {
             match event {
                 Event::Log(log) => log.get(&key).map(|val| val.to_string_lossy()),
                 Event::Metric(metric) => render_metric_field(key, metric),
+                Event::Trace(trace) => trace.get(&key).map(|val| val.to_string_lossy()),
             }
             .unwrap_or_else(|| {
                 missing_fields.push(key.to_owned());
@@ -189,6 +190,7 @@ fn render_timestamp(src: &str, event: &Event) -> String {
             .get(log_schema().timestamp_key())
             .and_then(Value::as_timestamp),
         Event::Metric(metric) => metric.data.timestamp.as_ref(),
+        Event::Trace(trace) => trace.start_time(),
     };
     if let Some(ts) = timestamp {
         ts.format(src).to_string()
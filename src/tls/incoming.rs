@@ -6,6 +6,7 @@ use super::{
 };
 #[cfg(feature = "sources-utils-tcp-keepalive")]
 use crate::tcp::TcpKeepaliveConfig;
+use crate::{internal_events::SocketMode, origin::OriginFilter};
 use bytes::{Buf, BufMut};
 use futures::{future::BoxFuture, stream, FutureExt, Stream};
 use openssl::ssl::{SslAcceptor, SslMethod};
@@ -46,24 +47,59 @@ impl MaybeTlsSettings {
             Self::Raw(()) => None,
         };
 
-        Ok(MaybeTlsListener { listener, acceptor })
+        Ok(MaybeTlsListener {
+            listener,
+            acceptor,
+            origin: OriginFilter::default(),
+        })
+    }
+
+    /// Wraps an already bound listener, for when the bound address must be
+    /// known before the listener is used.
+    pub(crate) fn bind_std(
+        &self,
+        listener: std::net::TcpListener,
+    ) -> crate::tls::Result<MaybeTlsListener> {
+        let listener = TcpListener::from_std(listener).context(TcpBind)?;
+
+        let acceptor = match self {
+            Self::Tls(tls) => Some(tls.acceptor()?),
+            Self::Raw(()) => None,
+        };
+
+        Ok(MaybeTlsListener {
+            listener,
+            acceptor,
+            origin: OriginFilter::default(),
+        })
     }
 }
 
 pub(crate) struct MaybeTlsListener {
     listener: TcpListener,
     acceptor: Option<SslAcceptor>,
+    origin: OriginFilter,
 }
 
 impl MaybeTlsListener {
+    /// Closes the connections of the peers not permitted by `origin` as soon
+    /// as they're accepted.
+    pub(crate) fn with_origin(mut self, origin: OriginFilter) -> Self {
+        self.origin = origin;
+        self
+    }
+
     pub(crate) async fn accept(&mut self) -> crate::tls::Result<MaybeTlsIncomingStream<TcpStream>> {
-        self.listener
-            .accept()
-            .await
-            .map(|(stream, peer_addr)| {
-                MaybeTlsIncomingStream::new(stream, peer_addr, self.acceptor.clone())
-            })
-            .context(IncomingListener)
+        loop {
+            let (stream, peer_addr) = self.listener.accept().await.context(IncomingListener)?;
+            if self.origin.check(peer_addr, SocketMode::Tcp) {
+                return Ok(MaybeTlsIncomingStream::new(
+                    stream,
+                    peer_addr,
+                    self.acceptor.clone(),
+                ));
+            }
+        }
     }
 
     async fn into_accept(
@@ -96,6 +132,7 @@ impl From<TcpListener> for MaybeTlsListener {
         Self {
             listener,
             acceptor: None,
+            origin: OriginFilter::default(),
         }
     }
 }
@@ -5,28 +5,41 @@ use super::{
 use crate::config;
 use indoc::indoc;
 use url::Url;
-use vector_api_client::{connect_subscription_client, gql::HealthQueryExt, Client};
+use vector_api_client::{
+    connect_subscription_client_with_options, gql::HealthQueryExt, Client, ClientOptions,
+    SubscriptionClient,
+};
 
-/// CLI command func for displaying Vector components, and communicating with a local/remote
-/// Vector API server via HTTP/WebSockets
-pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
-    // Exit early if the terminal is not a teletype
-    if !is_tty() {
-        eprintln!("Terminal must be a teletype (TTY) to display a Vector dashboard.");
-        return exitcode::IOERR;
+/// A connected Vector instance
+struct Instance {
+    name: String,
+    state: state::State,
+    subscription_client: SubscriptionClient,
+}
+
+/// Returns the name an instance is tagged with in the dashboard, which is the host and port
+/// of its API server
+fn instance_name(url: &Url) -> String {
+    match (url.host_str(), url.port_or_known_default()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        _ => url.to_string(),
     }
+}
 
-    // Use the provided URL as the Vector GraphQL API server, or default to the local port
-    // provided by the API config. This will work despite `api` and `api-client` being distinct
-    // features; the config is available even if `api` is disabled
-    let url = opts.url.clone().unwrap_or_else(|| {
-        let addr = config::api::default_address().unwrap();
-        Url::parse(&*format!("http://{}/graphql", addr))
-            .expect("Couldn't parse default API URL. Please report this.")
-    });
+/// Connects to the Vector API server at `url`, retrieving its initial component state and
+/// establishing the WebSocket connection for subscriptions. Errors are printed, returning the
+/// exit code to use.
+async fn connect(url: Url, client_options: &ClientOptions) -> Result<Instance, exitcode::ExitCode> {
+    let name = instance_name(&url);
 
     // Create a new API client for connecting to the local/remote Vector instance
-    let client = Client::new(url.clone());
+    let client = match Client::with_options(url.clone(), client_options) {
+        Ok(client) => client,
+        Err(error) => {
+            eprintln!("Couldn't create Vector API client: {:#}", error);
+            return Err(exitcode::CONFIG);
+        }
+    };
 
     // Check that the GraphQL server is reachable
     match client.health_query().await {
@@ -44,19 +57,16 @@ pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
                       enabled = true"},
                 url
             );
-            return exitcode::UNAVAILABLE;
+            return Err(exitcode::UNAVAILABLE);
         }
     }
 
-    // Create a metrics state updater
-    let (tx, rx) = tokio::sync::mpsc::channel(20);
-
     // Get the initial component state
-    let sender = match metrics::init_components(&client).await {
-        Ok(state) => state::updater(state, rx).await,
+    let state = match metrics::init_components(&client, &name).await {
+        Ok(state) => state,
         _ => {
-            eprintln!("Couldn't query Vector components.");
-            return exitcode::UNAVAILABLE;
+            eprintln!("Couldn't query Vector components ({}).", url);
+            return Err(exitcode::UNAVAILABLE);
         }
     };
 
@@ -69,19 +79,76 @@ pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
         })
         .expect("Couldn't build WebSocket URL. Please report.");
 
-    let subscription_client = match connect_subscription_client(ws_url).await {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("Couldn't connect to Vector API via WebSockets: {:?}", e);
-            return exitcode::UNAVAILABLE;
-        }
+    let subscription_client =
+        match connect_subscription_client_with_options(ws_url, client_options).await {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!(
+                    "Couldn't connect to Vector API via WebSockets ({}): {:?}",
+                    url, e
+                );
+                return Err(exitcode::UNAVAILABLE);
+            }
+        };
+
+    Ok(Instance {
+        name,
+        state,
+        subscription_client,
+    })
+}
+
+/// CLI command func for displaying Vector components, and communicating with one or more
+/// local/remote Vector API servers via HTTP/WebSockets
+pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
+    // Exit early if the terminal is not a teletype
+    if !is_tty() {
+        eprintln!("Terminal must be a teletype (TTY) to display a Vector dashboard.");
+        return exitcode::IOERR;
+    }
+
+    // Use the provided URLs as the Vector GraphQL API servers, or default to the local port
+    // provided by the API config. This will work despite `api` and `api-client` being distinct
+    // features; the config is available even if `api` is disabled
+    let urls = if opts.targets.is_empty() {
+        vec![opts.url.clone().unwrap_or_else(|| {
+            let addr = config::api::default_address().unwrap();
+            Url::parse(&*format!("http://{}/graphql", addr))
+                .expect("Couldn't parse default API URL. Please report this.")
+        })]
+    } else {
+        opts.targets.clone()
     };
 
+    let client_options = opts.client_options();
+    let mut instances = Vec::with_capacity(urls.len());
+    for url in &urls {
+        match connect(url.clone(), &client_options).await {
+            Ok(instance) => instances.push(instance),
+            Err(code) => return code,
+        }
+    }
+
+    // Create a metrics state updater, merging the components of every instance
+    let (tx, rx) = tokio::sync::mpsc::channel(20);
+    let state = instances
+        .iter_mut()
+        .flat_map(|instance| std::mem::take(&mut instance.state))
+        .collect();
+    let sender = state::updater(state, rx).await;
+
     // Subscribe to updated metrics
-    metrics::subscribe(subscription_client, tx.clone(), opts.interval as i64);
+    for instance in instances {
+        metrics::subscribe(
+            instance.subscription_client,
+            state::instance_tx(instance.name, tx.clone()),
+            opts.interval as i64,
+        );
+    }
 
     // Initialize the dashboard
-    match init_dashboard(url.as_str(), opts, sender).await {
+    let title = urls.iter().map(Url::as_str).collect::<Vec<_>>().join(", ");
+    match init_dashboard(&title, opts, sender).await {
         Ok(_) => exitcode::OK,
         _ => {
             eprintln!("Your terminal doesn't support building a dashboard. Exiting.");
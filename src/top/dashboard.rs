@@ -73,7 +73,9 @@ impl HumanFormatter for i64 {
     }
 }
 
-static HEADER: [&str; 6] = ["Name", "Kind", "Type", "Events", "Bytes", "Errors"];
+static HEADER: [&str; 7] = [
+    "Name", "Kind", "Type", "Events", "Bytes", "Errors", "Buffer",
+];
 
 struct Widgets<'a> {
     constraints: Vec<Constraint>,
@@ -121,15 +123,29 @@ impl<'a> Widgets<'a> {
     /// Renders a components table, showing sources, transforms and sinks in tabular form, with
     /// statistics pulled from `ComponentsState`,
     fn components_table<B: Backend>(&self, f: &mut Frame<B>, state: &state::State, area: Rect) {
+        // Components of multiple instances are tagged with the instance they run on
+        let multiple_instances = self.opts.targets.len() > 1;
+
         // Header columns
-        let header = HEADER
+        let instance_header = if multiple_instances {
+            Some("Instance")
+        } else {
+            None
+        };
+        let header = instance_header
             .iter()
+            .chain(HEADER.iter())
             .map(|s| Cell::from(*s).style(Style::default().add_modifier(Modifier::BOLD)))
             .collect::<Vec<_>>();
 
         // Data columns
         let items = state.iter().map(|(_, r)| {
-            let mut data = vec![r.name.clone(), r.kind.clone(), r.component_type.clone()];
+            let mut data = if multiple_instances {
+                vec![r.instance.clone()]
+            } else {
+                vec![]
+            };
+            data.extend_from_slice(&[r.name.clone(), r.kind.clone(), r.component_type.clone()]);
 
             let formatted_metrics = [
                 match r.processed_events_total {
@@ -161,24 +177,44 @@ impl<'a> Widgets<'a> {
                 } else {
                     r.errors.thousands_format()
                 },
+                match r.buffer_utilization {
+                    Some(utilization) => format!("{:.0}%", utilization * 100.0),
+                    None => "--".to_string(),
+                },
             ];
 
             data.extend_from_slice(&formatted_metrics);
             Row::new(data).style(Style::default())
         });
 
-        let w = Table::new(items)
-            .header(Row::new(header).bottom_margin(1))
-            .block(Block::default().borders(Borders::ALL).title("Components"))
-            .column_spacing(2)
-            .widths(&[
+        let widths: &[Constraint] = if multiple_instances {
+            &[
+                Constraint::Percentage(15),
+                Constraint::Percentage(15),
+                Constraint::Percentage(10),
+                Constraint::Percentage(10),
+                Constraint::Percentage(15),
+                Constraint::Percentage(15),
+                Constraint::Percentage(10),
+                Constraint::Percentage(10),
+            ]
+        } else {
+            &[
                 Constraint::Percentage(20),
                 Constraint::Percentage(10),
                 Constraint::Percentage(10),
                 Constraint::Percentage(20),
                 Constraint::Percentage(20),
                 Constraint::Percentage(10),
-            ]);
+                Constraint::Percentage(10),
+            ]
+        };
+
+        let w = Table::new(items)
+            .header(Row::new(header).bottom_margin(1))
+            .block(Block::default().borders(Borders::ALL).title("Components"))
+            .column_spacing(2)
+            .widths(widths);
 
         f.render_widget(w, area);
     }
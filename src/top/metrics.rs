@@ -19,6 +19,8 @@ async fn component_added(client: Arc<SubscriptionClient>, mut tx: state::EventTx
             let c = d.component_added;
             let _ = tx
                 .send(state::EventType::ComponentAdded(state::ComponentRow {
+                    // Set by the updater, which knows the instance the event originates from
+                    instance: String::new(),
                     name: c.name,
                     kind: c.on.to_string(),
                     component_type: c.component_type,
@@ -27,6 +29,7 @@ async fn component_added(client: Arc<SubscriptionClient>, mut tx: state::EventTx
                     processed_bytes_total: 0,
                     processed_bytes_throughput_sec: 0,
                     errors: 0,
+                    buffer_utilization: None,
                 }))
                 .await;
         }
@@ -147,6 +150,52 @@ async fn processed_bytes_throughputs(
     }
 }
 
+async fn errors_totals(client: Arc<SubscriptionClient>, mut tx: state::EventTx, interval: i64) {
+    let res = client.component_errors_totals_subscription(interval);
+
+    tokio::pin! {
+        let stream = res.stream();
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_errors_totals;
+            let _ = tx
+                .send(state::EventType::ErrorsTotals(
+                    c.into_iter()
+                        .map(|c| (c.name, c.metric.errors_total as i64))
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
+async fn buffer_utilizations(
+    client: Arc<SubscriptionClient>,
+    mut tx: state::EventTx,
+    interval: i64,
+) {
+    let res = client.component_buffers_subscription(interval);
+
+    tokio::pin! {
+        let stream = res.stream();
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_buffers;
+            let _ = tx
+                .send(state::EventType::BufferUtilizations(
+                    c.into_iter()
+                        .filter_map(|c| Some((c.name, c.buffer.utilization?)))
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
 /// Subscribe to each metrics channel through a separate client. This is a temporary workaround
 /// until client multiplexing is fixed. In future, we should be able to use a single client
 pub fn subscribe(client: SubscriptionClient, tx: state::EventTx, interval: i64) {
@@ -171,14 +220,16 @@ pub fn subscribe(client: SubscriptionClient, tx: state::EventTx, interval: i64)
     ));
     tokio::spawn(processed_bytes_throughputs(
         Arc::clone(&client),
-        tx,
+        tx.clone(),
         interval,
     ));
+    tokio::spawn(errors_totals(Arc::clone(&client), tx.clone(), interval));
+    tokio::spawn(buffer_utilizations(Arc::clone(&client), tx, interval));
 }
 
-/// Retrieve the initial components/metrics for first paint. Further updating the metrics
-/// will be handled by subscriptions.
-pub async fn init_components(client: &Client) -> Result<state::State, ()> {
+/// Retrieve the initial components/metrics of `instance` for first paint. Further updating the
+/// metrics will be handled by subscriptions.
+pub async fn init_components(client: &Client, instance: &str) -> Result<state::State, ()> {
     // Execute a query to get the latest components, and aggregate metrics for each resource.
     // Since we don't know currently have a mechanism for scrolling/paging through results,
     // we're using an artificially high page size to capture all likely component configurations.
@@ -195,8 +246,9 @@ pub async fn init_components(client: &Client) -> Result<state::State, ()> {
             d.into_iter().filter_map(|edge| {
                 let d = edge?.node;
                 Some((
-                    d.name.clone(),
+                    (instance.to_owned(), d.name.clone()),
                     state::ComponentRow {
+                        instance: instance.to_owned(),
                         name: d.name,
                         kind: d.on.to_string(),
                         component_type: d.component_type,
@@ -205,6 +257,7 @@ pub async fn init_components(client: &Client) -> Result<state::State, ()> {
                         processed_bytes_total: d.on.processed_bytes_total(),
                         processed_bytes_throughput_sec: 0,
                         errors: 0,
+                        buffer_utilization: None,
                     },
                 ))
             })
@@ -4,8 +4,10 @@ mod events;
 mod metrics;
 mod state;
 
+use std::path::PathBuf;
 use structopt::StructOpt;
 use url::Url;
+use vector_api_client::ClientOptions;
 
 pub use cmd::cmd;
 
@@ -20,7 +22,39 @@ pub struct Opts {
     #[structopt(short, long)]
     url: Option<Url>,
 
+    /// Comma separated Vector GraphQL API server endpoints, to display the components of
+    /// multiple instances together. Each component is tagged with the instance it runs on
+    #[structopt(long, use_delimiter = true, conflicts_with = "url")]
+    targets: Vec<Url>,
+
     /// Humanize metrics, using numeric suffixes - e.g. 1,100 = 1.10 k, 1,000,000 = 1.00 M
     #[structopt(short, long)]
     human_metrics: bool,
+
+    /// Token to authenticate with, if the API server requires one
+    #[structopt(long, env = "VECTOR_API_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+
+    /// PEM encoded CA certificate to verify the API server with
+    #[structopt(long, parse(from_os_str))]
+    ca_file: Option<PathBuf>,
+
+    /// PKCS#12 archive with a client certificate and key, for mutual TLS
+    #[structopt(long, parse(from_os_str))]
+    identity_file: Option<PathBuf>,
+
+    /// Password of the client identity archive
+    #[structopt(long)]
+    identity_pass: Option<String>,
+}
+
+impl Opts {
+    fn client_options(&self) -> ClientOptions {
+        ClientOptions {
+            token: self.token.clone(),
+            ca_file: self.ca_file.clone(),
+            identity_file: self.identity_file.clone(),
+            identity_pass: self.identity_pass.clone(),
+        }
+    }
 }
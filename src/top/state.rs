@@ -11,17 +11,24 @@ pub enum EventType {
     ProcessedBytesTotals(Vec<NamedMetric>),
     /// Interval + named metric
     ProcessedBytesThroughputs(i64, Vec<NamedMetric>),
+    ErrorsTotals(Vec<NamedMetric>),
+    /// Sink name + how full its buffer is, between 0 and 1
+    BufferUtilizations(Vec<(String, f64)>),
     ComponentAdded(ComponentRow),
     ComponentRemoved(String),
 }
 
-pub type State = BTreeMap<String, ComponentRow>;
+/// Components are keyed by the instance they run on, followed by their name
+pub type ComponentKey = (String, String);
+pub type State = BTreeMap<ComponentKey, ComponentRow>;
 pub type EventTx = mpsc::Sender<EventType>;
-pub type EventRx = mpsc::Receiver<EventType>;
+pub type InstanceEventTx = mpsc::Sender<(String, EventType)>;
+pub type InstanceEventRx = mpsc::Receiver<(String, EventType)>;
 pub type StateRx = mpsc::Receiver<State>;
 
 #[derive(Debug, Clone)]
 pub struct ComponentRow {
+    pub instance: String,
     pub name: String,
     pub kind: String,
     pub component_type: String,
@@ -30,12 +37,30 @@ pub struct ComponentRow {
     pub processed_bytes_total: i64,
     pub processed_bytes_throughput_sec: i64,
     pub errors: i64,
+    /// How full the buffer of a sink is, between 0 and 1
+    pub buffer_utilization: Option<f64>,
 }
 
-/// Takes the receiver `EventRx` channel, and returns a `StateTx` state transmitter. This
-/// represents the single destination for handling subscriptions and returning 'immutable' state
-/// for re-rendering the dashboard. This approach uses channels vs. mutexes.
-pub async fn updater(mut state: State, mut event_rx: EventRx) -> StateRx {
+/// Returns an `EventTx` for the subscriptions of a single instance, which forwards events
+/// tagged with the `instance` they originate from to the shared `updater`.
+pub fn instance_tx(instance: String, mut tx: InstanceEventTx) -> EventTx {
+    let (instance_tx, mut instance_rx) = mpsc::channel(20);
+
+    tokio::spawn(async move {
+        while let Some(event_type) = instance_rx.recv().await {
+            if tx.send((instance.clone(), event_type)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    instance_tx
+}
+
+/// Takes the receiver `InstanceEventRx` channel, and returns a `StateTx` state transmitter. This
+/// represents the single destination for handling subscriptions of every instance and returning
+/// 'immutable' state for re-rendering the dashboard. This approach uses channels vs. mutexes.
+pub async fn updater(mut state: State, mut event_rx: InstanceEventRx) -> StateRx {
     let (mut tx, rx) = mpsc::channel(20);
 
     // Prime the receiver with the initial state
@@ -43,18 +68,20 @@ pub async fn updater(mut state: State, mut event_rx: EventRx) -> StateRx {
 
     tokio::spawn(async move {
         loop {
-            if let Some(event_type) = event_rx.recv().await {
+            if let Some((instance, event_type)) = event_rx.recv().await {
+                let key = |name: String| (instance.clone(), name);
+
                 match event_type {
                     EventType::ProcessedEventsTotals(rows) => {
                         for (name, v) in rows {
-                            if let Some(r) = state.get_mut(&name) {
+                            if let Some(r) = state.get_mut(&key(name)) {
                                 r.processed_events_total = v;
                             }
                         }
                     }
                     EventType::ProcessedEventsThroughputs(interval, rows) => {
                         for (name, v) in rows {
-                            if let Some(r) = state.get_mut(&name) {
+                            if let Some(r) = state.get_mut(&key(name)) {
                                 r.processed_events_throughput_sec =
                                     (v as f64 * (1000.0 / interval as f64)) as i64;
                             }
@@ -62,24 +89,39 @@ pub async fn updater(mut state: State, mut event_rx: EventRx) -> StateRx {
                     }
                     EventType::ProcessedBytesTotals(rows) => {
                         for (name, v) in rows {
-                            if let Some(r) = state.get_mut(&name) {
+                            if let Some(r) = state.get_mut(&key(name)) {
                                 r.processed_bytes_total = v;
                             }
                         }
                     }
                     EventType::ProcessedBytesThroughputs(interval, rows) => {
                         for (name, v) in rows {
-                            if let Some(r) = state.get_mut(&name) {
+                            if let Some(r) = state.get_mut(&key(name)) {
                                 r.processed_bytes_throughput_sec =
                                     (v as f64 * (1000.0 / interval as f64)) as i64;
                             }
                         }
                     }
-                    EventType::ComponentAdded(c) => {
-                        let _ = state.insert(c.name.clone(), c);
+                    EventType::ErrorsTotals(rows) => {
+                        for (name, v) in rows {
+                            if let Some(r) = state.get_mut(&key(name)) {
+                                r.errors = v;
+                            }
+                        }
+                    }
+                    EventType::BufferUtilizations(rows) => {
+                        for (name, v) in rows {
+                            if let Some(r) = state.get_mut(&key(name)) {
+                                r.buffer_utilization = Some(v);
+                            }
+                        }
+                    }
+                    EventType::ComponentAdded(mut c) => {
+                        c.instance = instance.clone();
+                        let _ = state.insert(key(c.name.clone()), c);
                     }
                     EventType::ComponentRemoved(name) => {
-                        let _ = state.remove(&name);
+                        let _ = state.remove(&key(name));
                     }
                 }
 
@@ -91,3 +133,48 @@ pub async fn updater(mut state: State, mut event_rx: EventRx) -> StateRx {
 
     rx
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(instance: &str, name: &str) -> ComponentRow {
+        ComponentRow {
+            instance: instance.to_owned(),
+            name: name.to_owned(),
+            kind: "source".to_owned(),
+            component_type: "stdin".to_owned(),
+            processed_events_total: 0,
+            processed_events_throughput_sec: 0,
+            processed_bytes_total: 0,
+            processed_bytes_throughput_sec: 0,
+            errors: 0,
+            buffer_utilization: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn keeps_instances_apart() {
+        let state = vec![row("a:8686", "in"), row("b:8686", "in")]
+            .into_iter()
+            .map(|r| ((r.instance.clone(), r.name.clone()), r))
+            .collect::<State>();
+
+        let (tx, rx) = mpsc::channel(20);
+        let mut state_rx = updater(state, rx).await;
+        let _ = state_rx.recv().await;
+
+        let mut a_tx = instance_tx("a:8686".to_owned(), tx);
+        a_tx.send(EventType::ProcessedEventsTotals(vec![(
+            "in".to_owned(),
+            10,
+        )]))
+        .await
+        .unwrap();
+
+        let state = state_rx.recv().await.unwrap();
+        let key = |instance: &str| (instance.to_owned(), "in".to_owned());
+        assert_eq!(state[&key("a:8686")].processed_events_total, 10);
+        assert_eq!(state[&key("b:8686")].processed_events_total, 0);
+    }
+}
@@ -5,22 +5,25 @@ use super::{
 };
 use crate::{
     buffers,
-    config::{DataType, SinkContext},
+    conditions::Condition,
+    config::{DataType, SinkConfig, SinkContext},
     event::Event,
-    internal_events::EventProcessed,
+    internal_events::{EventProcessed, SinkHealthcheckCompleted, SourceEventFiltered},
     shutdown::SourceShutdownCoordinator,
+    sinks::{util::quota::join_healthchecks, Healthcheck},
     stream::VecStreamExt,
-    transforms::Transform,
+    transforms::{FunctionTransform, Transform},
     Pipeline,
 };
 use futures::{future, stream, FutureExt, StreamExt, TryFutureExt};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     future::ready,
     sync::{Arc, Mutex},
 };
 use stream_cancel::{StreamExt as StreamCancelExt, Trigger, Tripwire};
-use tokio::time::{timeout, Duration};
+use tokio::time::{timeout, Duration, Instant};
+use tracing_futures::Instrument;
 
 pub struct Pieces {
     pub inputs: HashMap<String, (buffers::BufferInputCloner, Vec<String>)>,
@@ -28,8 +31,12 @@ pub struct Pieces {
     pub tasks: HashMap<String, Task>,
     pub source_tasks: HashMap<String, Task>,
     pub healthchecks: HashMap<String, Task>,
+    /// Sinks whose failing healthcheck prevents the topology from starting.
+    pub required_healthchecks: HashSet<String>,
     pub shutdown_coordinator: SourceShutdownCoordinator,
     pub detach_triggers: HashMap<String, Trigger>,
+    /// The names of the outputs of each source besides its main one.
+    pub source_outputs: HashMap<String, Vec<String>>,
 }
 
 /// Builds only the new pieces, and doesn't check their topology.
@@ -43,8 +50,10 @@ pub async fn build_pieces(
     let mut tasks = HashMap::new();
     let mut source_tasks = HashMap::new();
     let mut healthchecks = HashMap::new();
+    let mut required_healthchecks = HashSet::new();
     let mut shutdown_coordinator = SourceShutdownCoordinator::default();
     let mut detach_triggers = HashMap::new();
+    let mut source_outputs = HashMap::new();
 
     let mut errors = vec![];
 
@@ -54,14 +63,49 @@ pub async fn build_pieces(
         .iter()
         .filter(|(name, _)| diff.sources.contains_new(&name))
     {
+        let filter = match source.build_filter() {
+            Err(error) => {
+                errors.push(format!("Source \"{}\": Invalid filter: {}", name, error));
+                continue;
+            }
+            Ok(filter) => filter,
+        };
+        let inlines = filter
+            .map(|condition| Box::new(SourceFilter { condition }) as Box<dyn FunctionTransform>)
+            .into_iter()
+            .collect();
+
         let (tx, rx) = tokio::sync::mpsc::channel(1000);
-        let pipeline = Pipeline::from_sender(tx, vec![]);
+        let mut pipeline = Pipeline::from_sender(tx, inlines)
+            .with_backpressure(source.on_backpressure())
+            .with_priority(source.priority);
+
+        let (output, control) = Fanout::new();
+        let mut pumps = vec![rx.map(Ok).forward(output)];
+        let mut controls = vec![(name.clone(), control)];
+        let mut output_names = Vec::new();
+        // The filter of the source only applies to its main output.
+        for (output_name, output) in source.outputs(name) {
+            let (tx, rx) = tokio::sync::mpsc::channel(1000);
+            pipeline = pipeline.with_output(
+                output_name,
+                Pipeline::from_sender(tx, Vec::new())
+                    .with_backpressure(source.on_backpressure())
+                    .with_priority(source.priority),
+            );
 
-        let typetag = source.source_type();
+            let (fanout, control) = Fanout::new();
+            pumps.push(rx.map(Ok).forward(fanout));
+            output_names.push(output.clone());
+            controls.push((output, control));
+        }
+
+        let typetag = source.inner.source_type();
 
         let (shutdown_signal, force_shutdown_tripwire) = shutdown_coordinator.register_source(name);
 
         let server = match source
+            .inner
             .build(&name, &config.global, shutdown_signal, pipeline)
             .await
         {
@@ -72,8 +116,7 @@ pub async fn build_pieces(
             Ok(server) => server,
         };
 
-        let (output, control) = Fanout::new();
-        let pump = rx.map(Ok).forward(output).map_ok(|_| TaskOutput::Source);
+        let pump = future::try_join_all(pumps).map_ok(|_| TaskOutput::Source);
         let pump = Task::new(name, typetag, pump);
 
         // The force_shutdown_tripwire is a Future that when it resolves means that this source
@@ -89,7 +132,8 @@ pub async fn build_pieces(
             .map_err(|_| ());
         let server = Task::new(name, typetag, server);
 
-        outputs.insert(name.clone(), control);
+        source_outputs.insert(name.clone(), output_names);
+        outputs.extend(controls);
         tasks.insert(name.clone(), pump);
         source_tasks.insert(name.clone(), server);
     }
@@ -105,7 +149,7 @@ pub async fn build_pieces(
         let typetag = transform.inner.transform_type();
 
         let input_type = transform.inner.input_type();
-        let transform = match transform.inner.build().await {
+        let transform = match transform.inner.build(name, &config.global).await {
             Err(error) => {
                 errors.push(format!("Transform \"{}\": {}", name, error));
                 continue;
@@ -114,7 +158,7 @@ pub async fn build_pieces(
         };
 
         let (input_tx, input_rx) = futures::channel::mpsc::channel(100);
-        let input_tx = buffers::BufferInputCloner::Memory(input_tx, buffers::WhenFull::Block);
+        let input_tx = buffers::BufferInputCloner::Memory(input_tx, buffers::WhenFull::Block, None);
 
         let (output, control) = Fanout::new();
 
@@ -159,6 +203,12 @@ pub async fn build_pieces(
         let sink_inputs = &sink.inputs;
         let healthcheck = sink.healthcheck();
         let enable_healthcheck = healthcheck.enabled && config.healthchecks.enabled;
+        let healthcheck_required = healthcheck.is_required(&config.healthchecks);
+        let healthcheck_timeout = Duration::from_secs(healthcheck.timeout_secs);
+        let healthcheck_interval = healthcheck
+            .interval_secs
+            .filter(|_| enable_healthcheck)
+            .map(Duration::from_secs);
 
         let typetag = sink.inner.sink_type();
         let input_type = sink.inner.input_type();
@@ -176,11 +226,60 @@ pub async fn build_pieces(
             }
         };
 
+        // Without acknowledgements, the events are finalized as soon as
+        // they're buffered.
+        let acknowledgements = sink.acknowledgements(&config.global);
+        let tx = tx.finalizing(!acknowledgements);
+
+        // The acker given back once the sink ends is the buffer's own, so that
+        // a rebuilt sink doesn't wrap it again.
+        let (sink_acker, pending_finalizers) = if acknowledgements {
+            let (sink_acker, pending) = acker.clone().with_finalizers();
+            (sink_acker, Some(pending))
+        } else {
+            (acker.clone(), None)
+        };
+
         let cx = SinkContext {
-            acker: acker.clone(),
+            acker: sink_acker,
             healthcheck,
+            expire_metrics: config.global.expire_metrics_secs.map(Duration::from_secs),
+        };
+
+        let recheck = match healthcheck_interval {
+            Some(interval) => match copy_sink_config(&*sink.inner) {
+                Ok(inner) => Some(recheck_healthcheck(
+                    inner,
+                    cx.clone(),
+                    interval,
+                    healthcheck_timeout,
+                )),
+                Err(error) => {
+                    errors.push(format!(
+                        "Sink \"{}\": Unable to re-check healthcheck: {}",
+                        name, error
+                    ));
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        let quota = match &sink.quota {
+            Some(quota) => match quota.build(cx.clone()).await {
+                Err(error) => {
+                    errors.push(format!("Sink \"{}\": {}", name, error));
+                    continue;
+                }
+                Ok(built) => Some(built),
+            },
+            None => None,
         };
 
+        let cx = match &quota {
+            Some((quota, _)) => quota.sink_context(&cx),
+            None => cx,
+        };
         let (sink, healthcheck) = match sink.inner.build(cx).await {
             Err(error) => {
                 errors.push(format!("Sink \"{}\": {}", name, error));
@@ -188,6 +287,13 @@ pub async fn build_pieces(
             }
             Ok(built) => built,
         };
+        let (quota, healthcheck) = match quota {
+            Some((quota, divert_healthcheck)) => (
+                Some(quota),
+                join_healthchecks(healthcheck, divert_healthcheck),
+            ),
+            None => (None, healthcheck),
+        };
 
         let (trigger, tripwire) = Tripwire::new();
 
@@ -204,59 +310,65 @@ pub async fn build_pieces(
                 .take()
                 .expect("Task started but input has been taken.");
 
-            sink.run(
-                rx.by_ref()
-                    .filter(|event| ready(filter_event_type(event, input_type)))
-                    .take_until_if(tripwire),
-            )
-            .await
-            .map(|_| {
+            let input = rx
+                .by_ref()
+                .filter(|event| ready(filter_event_type(event, input_type)))
+                .map(move |mut event| {
+                    // Without acknowledgements, the events buffered before
+                    // they were disabled are finalized as the sink reads them.
+                    let finalizers = event.take_finalizers();
+                    if let Some(pending) = &pending_finalizers {
+                        pending.lock().unwrap().push_back(finalizers);
+                    }
+                    event
+                })
+                .take_until_if(tripwire);
+            let run = match quota {
+                Some(quota) => future::Either::Left(quota.run(sink, input)),
+                None => future::Either::Right(sink.run(input)),
+            };
+            let result = match recheck {
+                // Re-checks run for as long as the sink does
+                Some(recheck) => tokio::select! {
+                    result = run => result,
+                    _ = recheck => unreachable!("Healthcheck re-checks don't finish."),
+                },
+                None => run.await,
+            };
+
+            result.map(|_| {
                 debug!("Finished.");
                 TaskOutput::Sink(rx, acker)
             })
         };
         let task = Task::new(name, typetag, sink);
 
-        let component_name = name.clone();
+        let span = error_span!(
+            "sink",
+            component_kind = "sink",
+            component_name = %name,
+            component_type = %typetag,
+        );
         let healthcheck_task = async move {
             if enable_healthcheck {
-                let duration = Duration::from_secs(10);
-                timeout(duration, healthcheck)
-                    .map(|result| match result {
-                        Ok(Ok(_)) => {
-                            info!("Healthcheck: Passed.");
-                            Ok(TaskOutput::Healthcheck)
-                        }
-                        Ok(Err(error)) => {
-                            error!(
-                                msg = "Healthcheck: Failed Reason.",
-                                %error,
-                                component_kind = "sink",
-                                component_type = typetag,
-                                ?component_name,
-                            );
-                            Err(())
-                        }
-                        Err(_) => {
-                            error!(
-                                msg = "Healthcheck: timeout.",
-                                component_kind = "sink",
-                                component_type = typetag,
-                                ?component_name,
-                            );
-                            Err(())
-                        }
-                    })
-                    .await
+                if run_healthcheck(healthcheck, healthcheck_timeout).await {
+                    Ok(TaskOutput::Healthcheck)
+                } else {
+                    Err(())
+                }
             } else {
                 info!("Healthcheck: Disabled.");
                 Ok(TaskOutput::Healthcheck)
             }
-        };
+        }
+        .instrument(span);
         let healthcheck_task = Task::new(name, typetag, healthcheck_task);
 
         inputs.insert(name.clone(), (tx, sink_inputs.clone()));
         healthchecks.insert(name.clone(), healthcheck_task);
+        if healthcheck_required {
+            required_healthchecks.insert(name.clone());
+        }
         tasks.insert(name.clone(), task);
         detach_triggers.insert(name.clone(), trigger);
     }
@@ -268,8 +380,10 @@ pub async fn build_pieces(
             tasks,
             source_tasks,
             healthchecks,
+            required_healthchecks,
             shutdown_coordinator,
             detach_triggers,
+            source_outputs,
         };
 
         Ok(pieces)
@@ -278,10 +392,77 @@ pub async fn build_pieces(
     }
 }
 
+/// Drops the events of a source not satisfying its `filter`, before they're sent on.
+#[derive(Clone)]
+struct SourceFilter {
+    condition: Box<dyn Condition>,
+}
+
+impl FunctionTransform for SourceFilter {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        if self.condition.check(&event) {
+            output.push(event);
+        } else {
+            emit!(SourceEventFiltered);
+        }
+    }
+}
+
+/// Runs the healthcheck of a sink, returning whether the sink is healthy.
+async fn run_healthcheck(healthcheck: Healthcheck, duration: Duration) -> bool {
+    let healthy = match timeout(duration, healthcheck).await {
+        Ok(Ok(_)) => {
+            info!("Healthcheck: Passed.");
+            true
+        }
+        Ok(Err(error)) => {
+            error!(msg = "Healthcheck: Failed Reason.", %error);
+            false
+        }
+        Err(_) => {
+            error!(msg = "Healthcheck: timeout.");
+            false
+        }
+    };
+    emit!(SinkHealthcheckCompleted { healthy });
+    healthy
+}
+
+/// Builds a fresh healthcheck of a sink every `interval`, and runs it. Never finishes.
+async fn recheck_healthcheck(
+    sink: Box<dyn SinkConfig>,
+    cx: SinkContext,
+    interval: Duration,
+    duration: Duration,
+) {
+    let mut interval = tokio::time::interval_at(Instant::now() + interval, interval);
+    loop {
+        interval.tick().await;
+        match sink.build(cx.clone()).await {
+            Ok((_, healthcheck)) => {
+                run_healthcheck(healthcheck, duration).await;
+            }
+            Err(error) => {
+                error!(msg = "Healthcheck: Failed to build.", %error);
+                emit!(SinkHealthcheckCompleted { healthy: false });
+            }
+        }
+    }
+}
+
+/// Sink configs aren't `Clone`, so copies are made through their serialized form. JSON is
+/// used since TOML does not support serializing `None`.
+fn copy_sink_config(sink: &dyn SinkConfig) -> crate::Result<Box<dyn SinkConfig>> {
+    let json = serde_json::to_value(sink)?;
+    Ok(serde_json::from_value(json)?)
+}
+
 fn filter_event_type(event: &Event, data_type: DataType) -> bool {
     match data_type {
         DataType::Any => true,
         DataType::Log => matches!(event, Event::Log(_)),
         DataType::Metric => matches!(event, Event::Metric(_)),
+        DataType::Trace => matches!(event, Event::Trace(_)),
+        DataType::LogOrTrace => matches!(event, Event::Log(_) | Event::Trace(_)),
     }
 }
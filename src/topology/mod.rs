@@ -8,6 +8,7 @@
 
 pub mod builder;
 mod fanout;
+pub mod tap;
 mod task;
 
 use crate::{
@@ -15,6 +16,7 @@ use crate::{
     config::{Config, ConfigDiff, HealthcheckOptions, Resource},
     event::Event,
     shutdown::SourceShutdownCoordinator,
+    sinks::util::debug_capture,
     topology::{
         builder::Pieces,
         task::{Task, TaskOutput},
@@ -61,6 +63,9 @@ pub async fn start_validated(
 ) -> Option<(RunningTopology, mpsc::UnboundedReceiver<()>)> {
     let (abort_tx, abort_rx) = mpsc::unbounded_channel();
 
+    crate::trace::set_component_labels(config.telemetry_labels());
+    debug_capture::set_captures(config.debug_captures());
+
     let mut running_topology = RunningTopology {
         inputs: HashMap::new(),
         outputs: HashMap::new(),
@@ -226,6 +231,8 @@ impl RunningTopology {
         }
 
         // Now let's actually build the new pieces.
+        crate::trace::set_component_labels(new_config.telemetry_labels());
+        debug_capture::set_captures(new_config.debug_captures());
         if let Some(mut new_pieces) = build_or_log_errors(&new_config, &diff, buffers.clone()).await
         {
             if self
@@ -242,6 +249,8 @@ impl RunningTopology {
 
         // We need to rebuild the removed.
         info!("Rebuilding old configuration.");
+        crate::trace::set_component_labels(self.config.telemetry_labels());
+        debug_capture::set_captures(self.config.debug_captures());
         let diff = diff.flip();
         if let Some(mut new_pieces) = build_or_log_errors(&self.config, &diff, buffers).await {
             if self
@@ -268,25 +277,24 @@ impl RunningTopology {
         options: HealthcheckOptions,
     ) -> bool {
         if options.enabled {
-            let healthchecks = take_healthchecks(diff, pieces)
+            let (required, advisory): (Vec<_>, Vec<_>) = take_healthchecks(diff, pieces)
                 .into_iter()
-                .map(|(_, task)| task);
-            let healthchecks = future::try_join_all(healthchecks);
+                .partition(|(name, _)| pieces.required_healthchecks.contains(name));
 
             info!("Running healthchecks.");
-            if options.require_healthy {
-                let success = healthchecks.await;
+            tokio::spawn(future::join_all(advisory.into_iter().map(|(_, task)| task)));
 
-                if success.is_ok() {
-                    info!("All healthchecks passed.");
-                    true
-                } else {
-                    error!("Sinks unhealthy.");
-                    false
-                }
-            } else {
-                tokio::spawn(healthchecks);
+            if required.is_empty() {
+                return true;
+            }
+
+            let success = future::try_join_all(required.into_iter().map(|(_, task)| task)).await;
+            if success.is_ok() {
+                info!("All healthchecks passed.");
                 true
+            } else {
+                error!("Sinks unhealthy.");
+                false
             }
         } else {
             true
@@ -321,12 +329,12 @@ impl RunningTopology {
             let previous = self.tasks.remove(name).unwrap();
             drop(previous); // detach and forget
 
-            self.remove_outputs(name);
+            self.remove_source_outputs(name);
             source_shutdown_complete_futures
                 .push(self.shutdown_coordinator.shutdown_source(name, deadline));
         }
         for name in &diff.sources.to_change {
-            self.remove_outputs(name);
+            self.remove_source_outputs(name);
             source_shutdown_complete_futures
                 .push(self.shutdown_coordinator.shutdown_source(name, deadline));
         }
@@ -472,6 +480,10 @@ impl RunningTopology {
         // Sources
         for name in diff.sources.changed_and_added() {
             self.setup_outputs(&name, new_pieces);
+            let outputs = new_pieces.source_outputs.remove(name).unwrap_or_default();
+            for output in outputs {
+                self.setup_outputs(&output, new_pieces);
+            }
         }
 
         // Transforms
@@ -590,6 +602,15 @@ impl RunningTopology {
 
     fn remove_outputs(&mut self, name: &str) {
         self.outputs.remove(name);
+        tap::remove_output(name);
+    }
+
+    /// Removes the main output of the source and its other outputs.
+    fn remove_source_outputs(&mut self, name: &str) {
+        self.remove_outputs(name);
+        for (_, output) in self.config.sources[name].outputs(name) {
+            self.remove_outputs(&output);
+        }
     }
 
     fn remove_inputs(&mut self, name: &str) {
@@ -638,6 +659,7 @@ impl RunningTopology {
             }
         }
 
+        tap::set_output(name, output.clone());
         self.outputs.insert(name.to_string(), output);
     }
 
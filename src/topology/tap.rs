@@ -0,0 +1,134 @@
+//! Taps on the outputs of the running sources and transforms, through which
+//! the API samples the events flowing out of them.
+//!
+//! A tap is one more output of the fanout of the component. It never applies
+//! backpressure: the events it has no room for are dropped, so a slow client
+//! can't slow the topology down.
+
+use super::fanout::{ControlChannel, ControlMessage};
+use crate::event::Event;
+use futures::Sink;
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    task::{Context, Poll},
+};
+use tokio::sync::mpsc;
+
+lazy_static! {
+    static ref OUTPUTS: Mutex<HashMap<String, ControlChannel>> = Mutex::new(HashMap::new());
+}
+
+static NEXT_TAP_ID: AtomicUsize = AtomicUsize::new(0);
+
+pub(super) fn set_output(name: &str, output: ControlChannel) {
+    OUTPUTS
+        .lock()
+        .expect("poisoned lock")
+        .insert(name.to_owned(), output);
+}
+
+pub(super) fn remove_output(name: &str) {
+    OUTPUTS.lock().expect("poisoned lock").remove(name);
+}
+
+/// A tap on the output of a component, removed from it when dropped.
+#[derive(Debug)]
+pub struct Tap {
+    name: String,
+    output: ControlChannel,
+}
+
+impl Tap {
+    /// Taps the output of the source or transform `component`, receiving up
+    /// to `capacity` events until they are read. `None` if there is no such
+    /// component running.
+    pub fn new(component: &str, capacity: usize) -> Option<(Self, mpsc::Receiver<Event>)> {
+        let output = OUTPUTS
+            .lock()
+            .expect("poisoned lock")
+            .get(component)?
+            .clone();
+
+        let name = format!("_tap_{}", NEXT_TAP_ID.fetch_add(1, Ordering::Relaxed));
+        let (tx, rx) = mpsc::channel(capacity);
+        output
+            .send(ControlMessage::Add(name.clone(), Box::new(TapSink { tx })))
+            .ok()?;
+
+        Some((Self { name, output }, rx))
+    }
+}
+
+impl Drop for Tap {
+    fn drop(&mut self) {
+        // This can only fail if the component is gone, taking the tap along.
+        let _ = self.output.send(ControlMessage::Remove(self.name.clone()));
+    }
+}
+
+struct TapSink {
+    tx: mpsc::Sender<Event>,
+}
+
+impl Sink<Event> for TapSink {
+    type Error = ();
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, event: Event) -> Result<(), ()> {
+        // Dropped if the channel is full, or if the tap is being removed.
+        let _ = self.tx.try_send(event);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::log_schema, topology::fanout::Fanout};
+    use futures::SinkExt;
+
+    #[tokio::test]
+    async fn samples_the_output_of_components() {
+        let (mut fanout, output) = Fanout::new();
+        set_output("tap_test_in", output);
+
+        assert!(Tap::new("tap_test_missing", 2).is_none());
+        let (tap, mut rx) = Tap::new("tap_test_in", 2).unwrap();
+
+        for message in &["one", "two", "three"] {
+            fanout.send(Event::from(*message)).await.unwrap();
+        }
+        for message in &["one", "two"] {
+            let event = rx.recv().await.unwrap();
+            assert_eq!(
+                event.as_log()[log_schema().message_key()],
+                (*message).into()
+            );
+        }
+
+        // The tap is removed from the fanout, closing the channel.
+        drop(tap);
+        fanout.send(Event::from("four")).await.unwrap();
+        assert_eq!(rx.recv().await, None);
+
+        remove_output("tap_test_in");
+        assert!(Tap::new("tap_test_in", 2).is_none());
+    }
+}
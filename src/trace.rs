@@ -2,9 +2,10 @@ use crate::event::{Event, LogEvent, Value};
 use metrics_tracing_context::MetricsLayer;
 use once_cell::sync::OnceCell;
 use std::{
+    collections::{BTreeMap, HashMap},
     convert::TryInto,
     fmt::Debug,
-    sync::{Mutex, MutexGuard},
+    sync::{Mutex, MutexGuard, RwLock},
 };
 use tokio::sync::broadcast::{self, Receiver, Sender};
 use tracing::{
@@ -29,6 +30,16 @@ static BUFFER: OnceCell<Mutex<Option<Vec<Event>>>> = OnceCell::new();
 /// initialized.
 static SENDER: OnceCell<Sender<Event>> = OnceCell::new();
 
+/// COMPONENT_LABELS holds the telemetry labels of the components of the
+/// running topology, which are added to their internal log events and
+/// metrics.
+static COMPONENT_LABELS: OnceCell<RwLock<HashMap<String, BTreeMap<String, String>>>> =
+    OnceCell::new();
+
+/// SPAN_COMPONENTS holds the component each open span was entered on behalf
+/// of, either through its own `component_name` field or one of its parents.
+static SPAN_COMPONENTS: OnceCell<Mutex<HashMap<Id, String>>> = OnceCell::new();
+
 pub use tracing_futures::Instrument;
 pub use tracing_tower::{InstrumentableService, InstrumentedService};
 
@@ -53,9 +64,9 @@ pub fn init(color: bool, json: bool, levels: &str) {
             .with(Limit::default());
         if metrics_layer_enabled {
             let formatter = formatter.with(MetricsLayer::new());
-            Dispatch::new(BroadcastSubscriber { formatter })
+            Dispatch::new(BroadcastSubscriber::new(formatter))
         } else {
-            Dispatch::new(BroadcastSubscriber { formatter })
+            Dispatch::new(BroadcastSubscriber::new(formatter))
         }
     } else {
         let formatter = FmtSubscriber::builder()
@@ -65,9 +76,9 @@ pub fn init(color: bool, json: bool, levels: &str) {
             .with(Limit::default());
         if metrics_layer_enabled {
             let formatter = formatter.with(MetricsLayer::new());
-            Dispatch::new(BroadcastSubscriber { formatter })
+            Dispatch::new(BroadcastSubscriber::new(formatter))
         } else {
-            Dispatch::new(BroadcastSubscriber { formatter })
+            Dispatch::new(BroadcastSubscriber::new(formatter))
         }
     };
 
@@ -96,6 +107,37 @@ pub fn current_span() -> Span {
     Span::current()
 }
 
+/// Replaces the telemetry labels of the components, by component name.
+pub fn set_component_labels(labels: HashMap<String, BTreeMap<String, String>>) {
+    *COMPONENT_LABELS
+        .get_or_init(Default::default)
+        .write()
+        .expect("Couldn't acquire lock on component labels") = labels;
+}
+
+/// The telemetry labels of the component named `name`, if it has any.
+pub fn component_labels(name: &str) -> Option<BTreeMap<String, String>> {
+    COMPONENT_LABELS
+        .get()?
+        .read()
+        .expect("Couldn't acquire lock on component labels")
+        .get(name)
+        .cloned()
+}
+
+/// The component the current span was entered on behalf of, if any.
+pub fn current_component() -> Option<String> {
+    let id = Span::current().id()?;
+    span_components().get(&id).cloned()
+}
+
+fn span_components() -> MutexGuard<'static, HashMap<Id, String>> {
+    SPAN_COMPONENTS
+        .get_or_init(Default::default)
+        .lock()
+        .expect("Couldn't acquire lock on span components")
+}
+
 pub struct TraceSubscription {
     pub buffer: Vec<Event>,
     pub receiver: Receiver<Event>,
@@ -106,14 +148,54 @@ pub fn subscribe() -> TraceSubscription {
         Some(buffer) => buffer.drain(..).collect(),
         None => Vec::new(),
     };
-    let receiver = SENDER.get_or_init(|| broadcast::channel(99).0).subscribe();
+    let receiver = subscribe_live();
     TraceSubscription { buffer, receiver }
 }
 
+/// Subscribes to the internal log events generated from now on, leaving the
+/// early buffer to `subscribe`.
+pub fn subscribe_live() -> Receiver<Event> {
+    SENDER.get_or_init(|| broadcast::channel(99).0).subscribe()
+}
+
 struct BroadcastSubscriber<F> {
     formatter: F,
 }
 
+impl<F: Subscriber> BroadcastSubscriber<F> {
+    fn new(formatter: F) -> Self {
+        Self { formatter }
+    }
+
+    fn component(&self, parent: Option<&Id>) -> Option<String> {
+        let parent = parent?;
+        span_components().get(parent).cloned()
+    }
+
+    fn make_event(&self, event: &tracing::Event<'_>) -> Event {
+        let parent = if event.is_contextual() {
+            self.formatter.current_span().id().cloned()
+        } else {
+            event.parent().cloned()
+        };
+
+        let mut log: Event = event.into();
+        if let Some(component) = self.component(parent.as_ref()) {
+            if let Some(labels) = component_labels(&component) {
+                let labels = labels
+                    .into_iter()
+                    .map(|(key, value)| (key, Value::from(value)))
+                    .collect();
+                log.as_mut_log()
+                    .insert("metadata.labels", Value::Map(labels));
+            }
+            log.as_mut_log()
+                .insert("metadata.component_name", component);
+        }
+        log
+    }
+}
+
 impl<F: Subscriber + 'static> Subscriber for BroadcastSubscriber<F> {
     #[inline]
     fn enabled(&self, metadata: &Metadata<'_>) -> bool {
@@ -122,7 +204,19 @@ impl<F: Subscriber + 'static> Subscriber for BroadcastSubscriber<F> {
 
     #[inline]
     fn new_span(&self, span: &tracing::span::Attributes<'_>) -> Id {
-        self.formatter.new_span(span)
+        let parent = if span.is_contextual() {
+            self.formatter.current_span().id().cloned()
+        } else {
+            span.parent().cloned()
+        };
+        let id = self.formatter.new_span(span);
+
+        let mut visitor = ComponentName::default();
+        span.record(&mut visitor);
+        if let Some(component) = visitor.0.or_else(|| self.component(parent.as_ref())) {
+            span_components().insert(id.clone(), component);
+        }
+        id
     }
 
     #[inline]
@@ -138,10 +232,10 @@ impl<F: Subscriber + 'static> Subscriber for BroadcastSubscriber<F> {
     #[inline]
     fn event(&self, event: &tracing::Event<'_>) {
         if let Some(buffer) = early_buffer().as_mut() {
-            buffer.push(event.into());
+            buffer.push(self.make_event(event));
         }
         if let Some(sender) = SENDER.get() {
-            let _ = sender.send(event.into()); // Ignore errors
+            let _ = sender.send(self.make_event(event)); // Ignore errors
         }
         self.formatter.event(event)
     }
@@ -168,7 +262,11 @@ impl<F: Subscriber + 'static> Subscriber for BroadcastSubscriber<F> {
 
     #[inline]
     fn try_close(&self, id: Id) -> bool {
-        self.formatter.try_close(id)
+        let closed = self.formatter.try_close(id.clone());
+        if closed {
+            span_components().remove(&id);
+        }
+        closed
     }
 
     #[inline]
@@ -249,3 +347,21 @@ impl Visit for MakeLogEvent {
         self.0.insert(field.name(), value);
     }
 }
+
+/// Picks the `component_name` field out of the fields of a span.
+#[derive(Debug, Default)]
+struct ComponentName(Option<String>);
+
+impl Visit for ComponentName {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "component_name" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "component_name" {
+            self.0 = Some(format!("{:?}", value));
+        }
+    }
+}
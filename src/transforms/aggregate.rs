@@ -0,0 +1,387 @@
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
+    event::{
+        metric::{Metric, MetricData, MetricKind, MetricSeries, MetricValue},
+        Event,
+    },
+    internal_events::AggregateFlushed,
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use futures::{stream, Stream, StreamExt};
+use indexmap::{map::Entry, IndexMap};
+use serde::{Deserialize, Serialize};
+use std::{mem, pin::Pin, time::Duration};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AggregateConfig {
+    /// The window over which the metrics of a series are merged.
+    #[serde(default = "default_interval_ms")]
+    pub interval_ms: u64,
+}
+
+const fn default_interval_ms() -> u64 {
+    10_000
+}
+
+inventory::submit! {
+    TransformDescription::new::<AggregateConfig>("aggregate")
+}
+
+impl GenerateConfig for AggregateConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            interval_ms: default_interval_ms(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "aggregate")]
+impl TransformConfig for AggregateConfig {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
+        Aggregate::new(self).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "aggregate"
+    }
+}
+
+pub struct Aggregate {
+    interval: Duration,
+    /// The merged metric of each series seen in the window, in the order
+    /// they were first seen.
+    series: IndexMap<MetricSeries, MetricData>,
+}
+
+impl Aggregate {
+    pub fn new(config: &AggregateConfig) -> crate::Result<Self> {
+        if config.interval_ms == 0 {
+            return Err("`interval_ms` must be greater than 0".into());
+        }
+
+        Ok(Self {
+            interval: Duration::from_millis(config.interval_ms),
+            series: IndexMap::new(),
+        })
+    }
+
+    /// Merges `metric` into the one of its series. An absolute metric
+    /// replaces it, while an incremental one is added to it. A metric whose
+    /// value can't be merged with the one of its series flushes it first.
+    fn record(&mut self, output: &mut Vec<Event>, metric: Metric) {
+        let Metric { series, data, .. } = metric;
+        match self.series.entry(series) {
+            Entry::Vacant(entry) => {
+                entry.insert(data);
+            }
+            Entry::Occupied(mut entry) => {
+                let merged = entry.get_mut();
+                if data.kind == MetricKind::Incremental && mergeable(&merged.value, &data.value) {
+                    merged.add(&data);
+                    merged.timestamp = data.timestamp.or(merged.timestamp);
+                } else if data.kind == MetricKind::Absolute {
+                    *merged = data;
+                } else {
+                    let flushed = mem::replace(merged, data);
+                    output.push(Event::Metric(Metric {
+                        series: entry.key().clone(),
+                        data: flushed,
+                        metadata: Default::default(),
+                    }));
+                }
+            }
+        }
+    }
+
+    /// Emits the merged metric of each series, and starts the next window.
+    fn flush_into(&mut self, output: &mut Vec<Event>) {
+        if self.series.is_empty() {
+            return;
+        }
+
+        emit!(AggregateFlushed {
+            series: self.series.len()
+        });
+        output.extend(self.series.drain(..).map(|(series, data)| {
+            Event::Metric(Metric {
+                series,
+                data,
+                metadata: Default::default(),
+            })
+        }));
+    }
+}
+
+/// Whether adding `other` to `value` accounts for all of it.
+fn mergeable(value: &MetricValue, other: &MetricValue) -> bool {
+    match (value, other) {
+        (MetricValue::Counter { .. }, MetricValue::Counter { .. })
+        | (MetricValue::Gauge { .. }, MetricValue::Gauge { .. })
+        | (MetricValue::Set { .. }, MetricValue::Set { .. }) => true,
+        (
+            MetricValue::Distribution { statistic, .. },
+            MetricValue::Distribution {
+                statistic: other_statistic,
+                ..
+            },
+        ) => statistic == other_statistic,
+        (
+            MetricValue::AggregatedHistogram { buckets, .. },
+            MetricValue::AggregatedHistogram {
+                buckets: other_buckets,
+                ..
+            },
+        ) => {
+            buckets.len() == other_buckets.len()
+                && buckets
+                    .iter()
+                    .zip(other_buckets)
+                    .all(|(a, b)| a.upper_limit == b.upper_limit)
+        }
+        (
+            MetricValue::AggregatedSummary { quantiles, .. },
+            MetricValue::AggregatedSummary {
+                quantiles: other_quantiles,
+                ..
+            },
+        ) => {
+            quantiles.len() == other_quantiles.len()
+                && quantiles
+                    .iter()
+                    .zip(other_quantiles)
+                    .all(|(a, b)| a.upper_limit == b.upper_limit)
+        }
+        _ => false,
+    }
+}
+
+impl TaskTransform for Aggregate {
+    fn transform(
+        self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let mut flush_stream = tokio::time::interval(me.interval);
+
+        Box::pin(
+            stream! {
+              loop {
+                let mut output = Vec::new();
+                let done = tokio::select! {
+                    _ = flush_stream.next() => {
+                      me.flush_into(&mut output);
+                      false
+                    }
+                    maybe_event = input_rx.next() => {
+                      match maybe_event {
+                        None => {
+                          me.flush_into(&mut output);
+                          true
+                        }
+                        Some(event) => {
+                          me.record(&mut output, event.into_metric());
+                          false
+                        }
+                      }
+                    }
+                };
+                yield stream::iter(output.into_iter());
+                if done { break }
+              }
+            }
+            .flatten(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::StatisticKind;
+    use chrono::{offset::TimeZone, DateTime, Utc};
+
+    fn aggregate() -> Aggregate {
+        Aggregate::new(&toml::from_str("").unwrap()).unwrap()
+    }
+
+    fn ts(secs: u32) -> DateTime<Utc> {
+        Utc.ymd(2021, 3, 4).and_hms(5, 6, secs)
+    }
+
+    fn metric(name: &str, kind: MetricKind, value: MetricValue, secs: u32) -> Metric {
+        Metric::new(name, kind, value).with_timestamp(Some(ts(secs)))
+    }
+
+    fn counter(name: &str, value: f64, secs: u32) -> Metric {
+        metric(
+            name,
+            MetricKind::Incremental,
+            MetricValue::Counter { value },
+            secs,
+        )
+    }
+
+    fn record(aggregate: &mut Aggregate, metrics: Vec<Metric>) -> Vec<Event> {
+        let mut output = Vec::new();
+        for metric in metrics {
+            aggregate.record(&mut output, metric);
+        }
+        output
+    }
+
+    fn flush(aggregate: &mut Aggregate) -> Vec<Metric> {
+        let mut output = Vec::new();
+        aggregate.flush_into(&mut output);
+        output.into_iter().map(Event::into_metric).collect()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AggregateConfig>();
+    }
+
+    #[test]
+    fn merges_counters_by_series() {
+        let mut aggregate = aggregate();
+        let tagged = counter("requests", 1.0, 2).with_tags(Some(
+            vec![("status".to_owned(), "500".to_owned())]
+                .into_iter()
+                .collect(),
+        ));
+        let output = record(
+            &mut aggregate,
+            vec![
+                counter("requests", 1.0, 1),
+                tagged.clone(),
+                counter("requests", 2.0, 3),
+                counter("errors", 1.0, 4),
+            ],
+        );
+        assert!(output.is_empty());
+
+        assert_eq!(
+            flush(&mut aggregate),
+            vec![
+                counter("requests", 3.0, 3),
+                tagged,
+                counter("errors", 1.0, 4)
+            ]
+        );
+        assert!(flush(&mut aggregate).is_empty());
+    }
+
+    #[test]
+    fn merges_sets_and_distributions() {
+        let mut aggregate = aggregate();
+        let set = |values: &[&str], secs| {
+            metric(
+                "users",
+                MetricKind::Incremental,
+                MetricValue::Set {
+                    values: values.iter().map(|value| (*value).to_owned()).collect(),
+                },
+                secs,
+            )
+        };
+        let distribution = |value: f64, secs| {
+            metric(
+                "latency",
+                MetricKind::Incremental,
+                MetricValue::Distribution {
+                    samples: crate::samples![value => 1],
+                    statistic: StatisticKind::Histogram,
+                },
+                secs,
+            )
+        };
+        record(
+            &mut aggregate,
+            vec![
+                set(&["a", "b"], 1),
+                distribution(1.0, 1),
+                set(&["b", "c"], 2),
+                distribution(2.0, 2),
+            ],
+        );
+
+        assert_eq!(
+            flush(&mut aggregate),
+            vec![
+                set(&["a", "b", "c"], 2),
+                metric(
+                    "latency",
+                    MetricKind::Incremental,
+                    MetricValue::Distribution {
+                        samples: crate::samples![1.0 => 1, 2.0 => 1],
+                        statistic: StatisticKind::Histogram,
+                    },
+                    2,
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn absolute_metrics_replace_their_series() {
+        let mut aggregate = aggregate();
+        let gauge = |value, secs| {
+            metric(
+                "memory",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value },
+                secs,
+            )
+        };
+        record(&mut aggregate, vec![gauge(10.0, 1), gauge(12.0, 2)]);
+        assert_eq!(flush(&mut aggregate), vec![gauge(12.0, 2)]);
+
+        // Increments are added to the absolute value.
+        let increment = metric(
+            "memory",
+            MetricKind::Incremental,
+            MetricValue::Gauge { value: 1.0 },
+            3,
+        );
+        record(&mut aggregate, vec![gauge(12.0, 2), increment]);
+        assert_eq!(flush(&mut aggregate), vec![gauge(13.0, 3)]);
+    }
+
+    #[test]
+    fn flushes_unmergeable_metrics() {
+        let mut aggregate = aggregate();
+        let gauge = metric(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Gauge { value: 5.0 },
+            2,
+        );
+        let output = record(
+            &mut aggregate,
+            vec![counter("requests", 1.0, 1), gauge.clone()],
+        );
+
+        assert_eq!(output, vec![Event::Metric(counter("requests", 1.0, 1))]);
+        assert_eq!(flush(&mut aggregate), vec![gauge]);
+    }
+
+    #[test]
+    fn rejects_empty_interval() {
+        assert!(Aggregate::new(&toml::from_str("interval_ms = 0").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,327 @@
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
+    event::{discriminant::Discriminant, Event, Value},
+    internal_events::{AnomalyDetected, AnomalyDetectionFieldInvalid},
+    transforms::{TaskTransform, Transform},
+};
+use futures::{Stream, StreamExt};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, future::ready, pin::Pin};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AnomalyDetectionConfig {
+    /// The numeric field to track.
+    pub field: String,
+    /// Fields whose values identify independent series.
+    #[serde(default)]
+    pub group_by: Vec<String>,
+    /// Weight of the latest value in the moving average, between 0 and 1.
+    #[serde(default = "default_alpha")]
+    pub alpha: f64,
+    /// The z-score above which a value is considered anomalous.
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+    /// The number of values needed in a series before anything is flagged.
+    #[serde(default = "default_min_samples")]
+    pub min_samples: u64,
+    #[serde(default = "default_max_series")]
+    pub max_series: usize,
+    #[serde(default = "default_target_field")]
+    pub target_field: String,
+    /// Only forward anomalous events, turning the transform into an alert
+    /// stream.
+    #[serde(default)]
+    pub drop_normal: bool,
+}
+
+const fn default_alpha() -> f64 {
+    0.1
+}
+
+const fn default_threshold() -> f64 {
+    3.0
+}
+
+const fn default_min_samples() -> u64 {
+    10
+}
+
+const fn default_max_series() -> usize {
+    10_000
+}
+
+fn default_target_field() -> String {
+    "anomaly".to_owned()
+}
+
+inventory::submit! {
+    TransformDescription::new::<AnomalyDetectionConfig>("anomaly_detection")
+}
+
+impl GenerateConfig for AnomalyDetectionConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            field: "duration_ms".to_owned(),
+            group_by: vec!["service".to_owned()],
+            alpha: default_alpha(),
+            threshold: default_threshold(),
+            min_samples: default_min_samples(),
+            max_series: default_max_series(),
+            target_field: default_target_field(),
+            drop_normal: false,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "anomaly_detection")]
+impl TransformConfig for AnomalyDetectionConfig {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
+        AnomalyDetection::new(self.clone()).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "anomaly_detection"
+    }
+}
+
+/// Exponentially weighted moving mean and variance of a series.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Ewma {
+    mean: f64,
+    variance: f64,
+    samples: u64,
+}
+
+impl Ewma {
+    fn stddev(&self) -> f64 {
+        self.variance.sqrt()
+    }
+
+    /// The number of standard deviations `value` is away from the mean.
+    fn score(&self, value: f64) -> f64 {
+        let stddev = self.stddev();
+        if stddev > 0.0 {
+            (value - self.mean) / stddev
+        } else if value == self.mean {
+            0.0
+        } else {
+            // Any deviation from a perfectly flat series is infinitely
+            // unlikely.
+            f64::INFINITY.copysign(value - self.mean)
+        }
+    }
+
+    fn update(&mut self, value: f64, alpha: f64) {
+        if self.samples == 0 {
+            self.mean = value;
+        } else {
+            let diff = value - self.mean;
+            let increment = alpha * diff;
+            self.mean += increment;
+            self.variance = (1.0 - alpha) * (self.variance + diff * increment);
+        }
+        self.samples += 1;
+    }
+}
+
+pub struct AnomalyDetection {
+    config: AnomalyDetectionConfig,
+    series: LruCache<Discriminant, Ewma>,
+}
+
+impl AnomalyDetection {
+    pub fn new(config: AnomalyDetectionConfig) -> crate::Result<Self> {
+        if config.alpha.is_nan() || config.alpha <= 0.0 || config.alpha > 1.0 {
+            return Err("`alpha` must be greater than 0 and at most 1".into());
+        }
+        if config.threshold.is_nan() || config.threshold <= 0.0 {
+            return Err("`threshold` must be greater than 0".into());
+        }
+        if config.max_series == 0 {
+            return Err("`max_series` must be greater than 0".into());
+        }
+
+        Ok(Self {
+            series: LruCache::new(config.max_series),
+            config,
+        })
+    }
+
+    fn transform_one(&mut self, mut event: Event) -> Option<Event> {
+        let log = event.as_mut_log();
+        let value = match log.get(&self.config.field).and_then(as_f64) {
+            Some(value) => value,
+            None => {
+                emit!(AnomalyDetectionFieldInvalid {
+                    field: &self.config.field
+                });
+                return self.pass(event);
+            }
+        };
+
+        let discriminant = Discriminant::from_log_event(log, &self.config.group_by);
+        if !self.series.contains(&discriminant) {
+            self.series.put(discriminant.clone(), Ewma::default());
+        }
+        let series = self.series.get_mut(&discriminant).expect("just inserted");
+
+        let anomalous = series.samples >= self.config.min_samples && {
+            let score = series.score(value);
+            if score.abs() > self.config.threshold {
+                let mut anomaly = BTreeMap::new();
+                anomaly.insert("score".to_owned(), finite(score));
+                anomaly.insert("mean".to_owned(), Value::Float(series.mean));
+                anomaly.insert("stddev".to_owned(), Value::Float(series.stddev()));
+                log.insert(&self.config.target_field, Value::Map(anomaly));
+                true
+            } else {
+                false
+            }
+        };
+        series.update(value, self.config.alpha);
+
+        if anomalous {
+            emit!(AnomalyDetected {
+                field: &self.config.field
+            });
+            Some(event)
+        } else {
+            self.pass(event)
+        }
+    }
+
+    fn pass(&self, event: Event) -> Option<Event> {
+        if self.config.drop_normal {
+            None
+        } else {
+            Some(event)
+        }
+    }
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(value) => Some(*value as f64),
+        Value::Float(value) => Some(*value),
+        Value::Bytes(bytes) => String::from_utf8_lossy(bytes).trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// JSON has no infinity, so clamp scores against flat series.
+fn finite(score: f64) -> Value {
+    Value::Float(score.max(f64::MIN).min(f64::MAX))
+}
+
+impl TaskTransform for AnomalyDetection {
+    fn transform(
+        self: Box<Self>,
+        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut inner = self;
+        Box::pin(task.filter_map(move |v| ready(inner.transform_one(v))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector(config: &str) -> AnomalyDetection {
+        AnomalyDetection::new(toml::from_str(config).unwrap()).unwrap()
+    }
+
+    fn event(service: &str, value: impl Into<Value>) -> Event {
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("service", service);
+        event.as_mut_log().insert("latency", value.into());
+        event
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AnomalyDetectionConfig>();
+    }
+
+    #[test]
+    fn flags_spikes_after_warmup() {
+        let mut detector = detector(
+            r#"
+            field = "latency"
+            min_samples = 5
+            "#,
+        );
+
+        for value in &[10, 11, 9, 10, 11, 9, 10] {
+            let output = detector.transform_one(event("api", *value)).unwrap();
+            assert!(output.as_log().get("anomaly").is_none());
+        }
+
+        let output = detector.transform_one(event("api", "100")).unwrap();
+        let score = output.as_log()["anomaly.score"].clone();
+        assert!(matches!(score, Value::Float(score) if score > 3.0));
+    }
+
+    #[test]
+    fn tracks_series_independently() {
+        let mut detector = detector(
+            r#"
+            field = "latency"
+            group_by = ["service"]
+            min_samples = 3
+            drop_normal = true
+            "#,
+        );
+
+        for _ in 0..5 {
+            assert!(detector.transform_one(event("api", 10)).is_none());
+            assert!(detector.transform_one(event("batch", 1000)).is_none());
+        }
+
+        assert!(detector.transform_one(event("batch", 1000)).is_none());
+        assert!(detector.transform_one(event("api", 1000)).is_some());
+    }
+
+    #[test]
+    fn passes_invalid_values_through() {
+        let mut detector = detector(r#"field = "latency""#);
+        assert!(detector.transform_one(event("api", "slow")).is_some());
+        assert!(detector.transform_one(Event::new_empty_log()).is_some());
+    }
+
+    #[test]
+    fn rejects_invalid_options() {
+        for config in &[
+            "field = \"latency\"\nalpha = 0.0",
+            "field = \"latency\"\nthreshold = 0.0",
+            "field = \"latency\"\nmax_series = 0",
+        ] {
+            assert!(AnomalyDetection::new(toml::from_str(config).unwrap()).is_err());
+        }
+    }
+
+    #[test]
+    fn ewma_converges() {
+        let mut ewma = Ewma::default();
+        for _ in 0..200 {
+            ewma.update(5.0, 0.1);
+        }
+        assert!((ewma.mean - 5.0).abs() < 1e-9);
+        assert!(ewma.variance.abs() < 1e-9);
+    }
+}
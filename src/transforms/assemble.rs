@@ -0,0 +1,335 @@
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
+    event::{discriminant::Discriminant, Event, LogEvent, Value},
+    internal_events::AssembleFlushed,
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use futures::{stream, Stream, StreamExt};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AssembleConfig {
+    /// The fields the events are grouped by, copied to the assembled events.
+    #[serde(default)]
+    pub group_by: Vec<String>,
+    /// The field of the assembled event holding the array of events.
+    #[serde(default = "default_field")]
+    pub field: String,
+    #[serde(default = "default_max_events")]
+    pub max_events: usize,
+    /// The maximum size of the events of an assembled event, encoded as JSON.
+    #[serde(default, deserialize_with = "crate::serde::optional_bytes")]
+    pub max_bytes: Option<usize>,
+    /// The time after which a group is assembled, from its first event.
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_field() -> String {
+    "events".to_owned()
+}
+
+const fn default_max_events() -> usize {
+    100
+}
+
+const fn default_timeout_ms() -> u64 {
+    1000
+}
+
+inventory::submit! {
+    TransformDescription::new::<AssembleConfig>("assemble")
+}
+
+impl GenerateConfig for AssembleConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            group_by: Vec::new(),
+            field: default_field(),
+            max_events: default_max_events(),
+            max_bytes: None,
+            timeout_ms: default_timeout_ms(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "assemble")]
+impl TransformConfig for AssembleConfig {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
+        Assemble::new(self).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "assemble"
+    }
+}
+
+/// The events of a group waiting to be assembled.
+struct Batch {
+    /// The `group_by` fields of the first event.
+    fields: Vec<(String, Value)>,
+    events: Vec<Value>,
+    bytes: usize,
+    started: Instant,
+}
+
+pub struct Assemble {
+    group_by: Vec<String>,
+    field: String,
+    max_events: usize,
+    max_bytes: Option<usize>,
+    timeout: Duration,
+    /// The batches in the order they were started, so the oldest are
+    /// assembled first.
+    batches: IndexMap<Discriminant, Batch>,
+}
+
+impl Assemble {
+    pub fn new(config: &AssembleConfig) -> crate::Result<Self> {
+        if config.max_events == 0 {
+            return Err("`max_events` must be greater than 0".into());
+        }
+        if config.timeout_ms == 0 {
+            return Err("`timeout_ms` must be greater than 0".into());
+        }
+
+        Ok(Self {
+            group_by: config.group_by.clone(),
+            field: config.field.clone(),
+            max_events: config.max_events,
+            max_bytes: config.max_bytes,
+            timeout: Duration::from_millis(config.timeout_ms),
+            batches: IndexMap::new(),
+        })
+    }
+
+    fn record(&mut self, output: &mut Vec<Event>, log: LogEvent, now: Instant) {
+        let discriminant = Discriminant::from_log_event(&log, &self.group_by);
+        let bytes = serde_json::to_vec(&log).map_or(0, |json| json.len());
+
+        // An event that doesn't fit assembles the batch, and starts the next.
+        let full = match (self.batches.get(&discriminant), self.max_bytes) {
+            (Some(batch), Some(max_bytes)) => batch.bytes + bytes > max_bytes,
+            _ => false,
+        };
+        if full {
+            if let Some(batch) = self.batches.shift_remove(&discriminant) {
+                output.push(self.assemble(batch, "max_bytes"));
+            }
+        }
+
+        let group_by = &self.group_by;
+        let batch = self
+            .batches
+            .entry(discriminant.clone())
+            .or_insert_with(|| Batch {
+                fields: group_by
+                    .iter()
+                    .filter_map(|field| Some((field.clone(), log.get(field)?.clone())))
+                    .collect(),
+                events: Vec::new(),
+                bytes: 0,
+                started: now,
+            });
+        batch.bytes += bytes;
+        batch
+            .events
+            .push(Value::Map(Into::<BTreeMap<_, _>>::into(log)));
+
+        if batch.events.len() >= self.max_events {
+            if let Some(batch) = self.batches.shift_remove(&discriminant) {
+                output.push(self.assemble(batch, "max_events"));
+            }
+        }
+    }
+
+    /// Assembles the batches started `timeout` ago or earlier.
+    fn flush_expired_into(&mut self, output: &mut Vec<Event>, now: Instant) {
+        while let Some((_, batch)) = self.batches.get_index(0) {
+            if now.saturating_duration_since(batch.started) < self.timeout {
+                break;
+            }
+            if let Some((_, batch)) = self.batches.shift_remove_index(0) {
+                output.push(self.assemble(batch, "timeout"));
+            }
+        }
+    }
+
+    fn flush_all_into(&mut self, output: &mut Vec<Event>) {
+        let batches = std::mem::take(&mut self.batches);
+        output.extend(
+            batches
+                .into_iter()
+                .map(|(_, batch)| self.assemble(batch, "shutdown")),
+        );
+    }
+
+    fn assemble(&self, batch: Batch, reason: &'static str) -> Event {
+        emit!(AssembleFlushed {
+            events: batch.events.len(),
+            reason,
+        });
+
+        let mut log = LogEvent::default();
+        for (field, value) in batch.fields {
+            log.insert(field, value);
+        }
+        log.insert(self.field.clone(), Value::Array(batch.events));
+        log.into()
+    }
+}
+
+impl TaskTransform for Assemble {
+    fn transform(
+        self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        // Checked a few times per timeout, so the batches don't wait much
+        // longer than it.
+        let mut flush_stream =
+            tokio::time::interval((me.timeout / 10).max(Duration::from_millis(1)));
+
+        Box::pin(
+            stream! {
+              loop {
+                let mut output = Vec::new();
+                let done = tokio::select! {
+                    _ = flush_stream.next() => {
+                      me.flush_expired_into(&mut output, Instant::now());
+                      false
+                    }
+                    maybe_event = input_rx.next() => {
+                      match maybe_event {
+                        None => {
+                          me.flush_all_into(&mut output);
+                          true
+                        }
+                        Some(event) => {
+                          me.record(&mut output, event.into_log(), Instant::now());
+                          false
+                        }
+                      }
+                    }
+                };
+                yield stream::iter(output.into_iter());
+                if done { break }
+              }
+            }
+            .flatten(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::convert::TryFrom;
+
+    fn assemble(config: &str) -> Assemble {
+        Assemble::new(&toml::from_str(config).unwrap()).unwrap()
+    }
+
+    fn log(json: serde_json::Value) -> LogEvent {
+        LogEvent::try_from(json).unwrap()
+    }
+
+    fn to_json(events: Vec<Event>) -> Vec<serde_json::Value> {
+        events
+            .into_iter()
+            .map(|event| serde_json::to_value(event.as_log()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AssembleConfig>();
+    }
+
+    #[test]
+    fn assembles_groups_after_timeout() {
+        let mut assemble = assemble("group_by = [\"tenant\"]\ntimeout_ms = 1000");
+        let start = Instant::now();
+        let mut output = Vec::new();
+
+        assemble.record(&mut output, log(json!({"tenant": "a", "n": 1})), start);
+        assemble.record(&mut output, log(json!({"tenant": "b", "n": 2})), start);
+        assemble.record(&mut output, log(json!({"tenant": "a", "n": 3})), start);
+        assemble.flush_expired_into(&mut output, start + Duration::from_millis(999));
+        assert!(output.is_empty());
+
+        assemble.flush_expired_into(&mut output, start + Duration::from_millis(1000));
+        assert_eq!(
+            to_json(output),
+            vec![
+                json!({"tenant": "a", "events": [{"tenant": "a", "n": 1}, {"tenant": "a", "n": 3}]}),
+                json!({"tenant": "b", "events": [{"tenant": "b", "n": 2}]}),
+            ]
+        );
+    }
+
+    #[test]
+    fn assembles_full_batches() {
+        let mut assemble = assemble("max_events = 2");
+        let start = Instant::now();
+        let mut output = Vec::new();
+
+        for n in 1..=3 {
+            assemble.record(&mut output, log(json!({ "n": n })), start);
+        }
+        assert_eq!(
+            to_json(output),
+            vec![json!({"events": [{"n": 1}, {"n": 2}]})]
+        );
+
+        let mut output = Vec::new();
+        assemble.flush_all_into(&mut output);
+        assert_eq!(to_json(output), vec![json!({"events": [{"n": 3}]})]);
+    }
+
+    #[test]
+    fn assembles_batches_before_max_bytes() {
+        // Each event is 7 bytes long encoded as JSON.
+        let mut assemble = assemble("max_bytes = 20\nfield = \"batch\"");
+        let start = Instant::now();
+        let mut output = Vec::new();
+
+        for n in 1..=4 {
+            assemble.record(&mut output, log(json!({ "n": n })), start);
+        }
+        assert_eq!(
+            to_json(output),
+            vec![json!({"batch": [{"n": 1}, {"n": 2}]})]
+        );
+    }
+
+    #[test]
+    fn rejects_empty_limits() {
+        for config in &["max_events = 0", "timeout_ms = 0"] {
+            assert!(Assemble::new(&toml::from_str(config).unwrap()).is_err());
+        }
+    }
+}
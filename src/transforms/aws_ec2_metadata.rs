@@ -1,5 +1,5 @@
 use crate::{
-    config::{DataType, TransformConfig, TransformDescription},
+    config::{DataType, GlobalOptions, TransformConfig, TransformDescription},
     event::Event,
     http::HttpClient,
     internal_events::{AwsEc2MetadataRefreshFailed, AwsEc2MetadataRefreshSuccessful},
@@ -76,6 +76,7 @@ pub struct Ec2Metadata {
     #[serde(alias = "host")]
     endpoint: Option<String>,
     namespace: Option<String>,
+    #[serde(default, deserialize_with = "crate::serde::optional_duration_secs")]
     refresh_interval_secs: Option<u64>,
     fields: Option<Vec<String>>,
 }
@@ -110,7 +111,7 @@ impl_generate_config_from_default!(Ec2Metadata);
 #[async_trait::async_trait]
 #[typetag::serde(name = "aws_ec2_metadata")]
 impl TransformConfig for Ec2Metadata {
-    async fn build(&self) -> crate::Result<Transform> {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
         let (read, write) = evmap::new();
 
         // Check if the namespace is set to `""` which should mean that we do
@@ -529,7 +530,11 @@ mod integration_tests {
             endpoint: Some(HOST.to_string()),
             ..Default::default()
         };
-        let transform = config.build().await.unwrap().into_task();
+        let transform = config
+            .build("default", &GlobalOptions::default())
+            .await
+            .unwrap()
+            .into_task();
 
         let (mut tx, rx) = futures::channel::mpsc::channel(100);
         let mut rx = transform.transform(Box::pin(rx));
@@ -567,7 +572,11 @@ mod integration_tests {
             fields: Some(vec!["public-ipv4".into(), "region".into()]),
             ..Default::default()
         };
-        let transform = config.build().await.unwrap().into_task();
+        let transform = config
+            .build("default", &GlobalOptions::default())
+            .await
+            .unwrap()
+            .into_task();
 
         let (mut tx, rx) = futures::channel::mpsc::channel(100);
         let mut rx = transform.transform(Box::pin(rx));
@@ -600,7 +609,11 @@ mod integration_tests {
                 namespace: Some("ec2.metadata".into()),
                 ..Default::default()
             };
-            let transform = config.build().await.unwrap().into_task();
+            let transform = config
+                .build("default", &GlobalOptions::default())
+                .await
+                .unwrap()
+                .into_task();
 
             let (mut tx, rx) = futures::channel::mpsc::channel(100);
             let mut rx = transform.transform(Box::pin(rx));
@@ -631,7 +644,11 @@ mod integration_tests {
                 namespace: Some("".into()),
                 ..Default::default()
             };
-            let transform = config.build().await.unwrap().into_task();
+            let transform = config
+                .build("default", &GlobalOptions::default())
+                .await
+                .unwrap()
+                .into_task();
 
             let (mut tx, rx) = futures::channel::mpsc::channel(100);
             let mut rx = transform.transform(Box::pin(rx));
@@ -0,0 +1,293 @@
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event,
+    },
+    internal_events::ByteAttributionFlushed,
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use chrono::Utc;
+use futures::{stream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    pin::Pin,
+    time::Duration,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ByteAttributionConfig {
+    /// Fields whose values the bytes are attributed to, each becoming a tag.
+    pub group_by: Vec<String>,
+    #[serde(
+        default = "default_interval_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
+    pub interval_secs: u64,
+    #[serde(default = "default_namespace")]
+    pub namespace: Option<String>,
+}
+
+const fn default_interval_secs() -> u64 {
+    60
+}
+
+fn default_namespace() -> Option<String> {
+    Some("vector".to_owned())
+}
+
+inventory::submit! {
+    TransformDescription::new::<ByteAttributionConfig>("byte_attribution")
+}
+
+impl GenerateConfig for ByteAttributionConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            group_by: vec!["service".to_owned()],
+            interval_secs: default_interval_secs(),
+            namespace: default_namespace(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "byte_attribution")]
+impl TransformConfig for ByteAttributionConfig {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
+        ByteAttribution::new(self.clone()).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "byte_attribution"
+    }
+}
+
+/// The values of the `group_by` fields, `None` where an event lacks the field.
+type Group = Vec<Option<String>>;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Totals {
+    events: u64,
+    bytes: u64,
+}
+
+pub struct ByteAttribution {
+    config: ByteAttributionConfig,
+    groups: HashMap<Group, Totals>,
+}
+
+impl ByteAttribution {
+    pub fn new(config: ByteAttributionConfig) -> crate::Result<Self> {
+        if config.group_by.is_empty() {
+            return Err("`group_by` must name at least one field".into());
+        }
+        if config.interval_secs == 0 {
+            return Err("`interval_secs` must be greater than 0".into());
+        }
+
+        Ok(Self {
+            config,
+            groups: HashMap::new(),
+        })
+    }
+
+    /// Accounts the size of `event`, which is estimated from its JSON encoding.
+    fn record(&mut self, event: &Event) {
+        let log = event.as_log();
+        let group = self
+            .config
+            .group_by
+            .iter()
+            .map(|field| log.get(field).map(|value| value.to_string_lossy()))
+            .collect::<Group>();
+        let bytes = serde_json::to_vec(log).map_or(0, |encoded| encoded.len());
+
+        let totals = self.groups.entry(group).or_default();
+        totals.events += 1;
+        totals.bytes += bytes as u64;
+    }
+
+    /// Emits the totals accumulated since the last flush as incremental
+    /// counters, one pair per group.
+    fn flush_into(&mut self, output: &mut Vec<Event>) {
+        if self.groups.is_empty() {
+            return;
+        }
+        emit!(ByteAttributionFlushed {
+            groups: self.groups.len()
+        });
+
+        let timestamp = Utc::now();
+        for (group, totals) in self.groups.drain() {
+            let tags = self
+                .config
+                .group_by
+                .iter()
+                .zip(group)
+                .filter_map(|(field, value)| Some((field.clone(), value?)))
+                .collect::<BTreeMap<_, _>>();
+
+            for (name, value) in &[
+                ("attributed_bytes_total", totals.bytes),
+                ("attributed_events_total", totals.events),
+            ] {
+                let metric = Metric::new(
+                    *name,
+                    MetricKind::Incremental,
+                    MetricValue::Counter {
+                        value: *value as f64,
+                    },
+                )
+                .with_namespace(self.config.namespace.clone())
+                .with_tags(Some(tags.clone()))
+                .with_timestamp(Some(timestamp));
+                output.push(metric.into());
+            }
+        }
+    }
+}
+
+impl TaskTransform for ByteAttribution {
+    fn transform(
+        self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let mut flush_stream = tokio::time::interval(Duration::from_secs(me.config.interval_secs));
+
+        Box::pin(
+            stream! {
+              loop {
+                let mut output = Vec::new();
+                let done = tokio::select! {
+                    _ = flush_stream.next() => {
+                      me.flush_into(&mut output);
+                      false
+                    }
+                    maybe_event = input_rx.next() => {
+                      match maybe_event {
+                        None => {
+                          me.flush_into(&mut output);
+                          true
+                        }
+                        Some(event) => {
+                          me.record(&event);
+                          output.push(event);
+                          false
+                        }
+                      }
+                    }
+                };
+                yield stream::iter(output.into_iter());
+                if done { break }
+              }
+            }
+            .flatten(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribution(config: &str) -> ByteAttribution {
+        ByteAttribution::new(toml::from_str(config).unwrap()).unwrap()
+    }
+
+    fn event(service: Option<&str>, message: &str) -> Event {
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("message", message);
+        if let Some(service) = service {
+            event.as_mut_log().insert("service", service);
+        }
+        event
+    }
+
+    fn counters(output: &[Event]) -> BTreeMap<(String, Option<String>), f64> {
+        output
+            .iter()
+            .map(|event| {
+                let metric = event.as_metric();
+                let service = metric.tag_value("service");
+                match metric.data.value {
+                    MetricValue::Counter { value } => ((metric.name().to_owned(), service), value),
+                    _ => panic!("Expected a counter."),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ByteAttributionConfig>();
+    }
+
+    #[test]
+    fn attributes_bytes_per_group() {
+        let mut attribution = attribution(r#"group_by = ["service"]"#);
+        let events = vec![
+            event(Some("api"), "a"),
+            event(Some("api"), "bb"),
+            event(Some("batch"), "ccc"),
+            event(None, "dddd"),
+        ];
+        let size = |event: &Event| serde_json::to_vec(event.as_log()).unwrap().len() as f64;
+
+        for event in &events {
+            attribution.record(event);
+        }
+        let mut output = Vec::new();
+        attribution.flush_into(&mut output);
+
+        let counters = counters(&output);
+        assert_eq!(counters.len(), 6);
+        let bytes = |service: Option<&str>| {
+            counters[&("attributed_bytes_total".to_owned(), service.map(Into::into))]
+        };
+        assert_eq!(bytes(Some("api")), size(&events[0]) + size(&events[1]));
+        assert_eq!(bytes(Some("batch")), size(&events[2]));
+        assert_eq!(bytes(None), size(&events[3]));
+        assert_eq!(
+            counters[&("attributed_events_total".to_owned(), Some("api".to_owned()))],
+            2.0
+        );
+        assert_eq!(output[0].as_metric().namespace(), Some("vector"));
+    }
+
+    #[test]
+    fn resets_after_flush() {
+        let mut attribution = attribution(r#"group_by = ["service"]"#);
+        attribution.record(&event(Some("api"), "a"));
+
+        let mut output = Vec::new();
+        attribution.flush_into(&mut output);
+        assert_eq!(output.len(), 2);
+
+        output.clear();
+        attribution.flush_into(&mut output);
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn rejects_empty_group_by() {
+        let config = toml::from_str(r#"group_by = []"#).unwrap();
+        assert!(ByteAttribution::new(config).is_err());
+    }
+}
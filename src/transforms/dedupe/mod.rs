@@ -1,14 +1,20 @@
+mod persistence;
+
+use self::persistence::Store;
 use crate::{
-    config::{log_schema, DataType, GenerateConfig, TransformConfig, TransformDescription},
+    config::{
+        log_schema, DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription,
+    },
     event::{Event, Value},
-    internal_events::DedupeEventDiscarded,
+    internal_events::{DedupeEventDiscarded, DedupePersistenceFailed},
     transforms::{TaskTransform, Transform},
 };
+use async_stream::stream;
 use bytes::Bytes;
 use futures::{Stream, StreamExt};
 use lru::LruCache;
 use serde::{Deserialize, Serialize};
-use std::{future::ready, pin::Pin};
+use std::{future::ready, path::PathBuf, pin::Pin, time::Duration};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -25,6 +31,17 @@ pub struct CacheConfig {
     pub num_events: usize,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PersistenceConfig {
+    /// Overrides the global `data_dir` option.
+    #[serde(default)]
+    pub data_dir: Option<PathBuf>,
+    /// How often the state is synced to disk. `0` syncs after every event.
+    #[serde(default = "default_sync_interval_ms")]
+    pub sync_interval_ms: u64,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct DedupeConfig {
@@ -32,12 +49,19 @@ pub struct DedupeConfig {
     pub fields: Option<FieldMatchConfig>,
     #[serde(default = "default_cache_config")]
     pub cache: CacheConfig,
+    /// Keep the cache on disk so it survives restarts.
+    #[serde(default)]
+    pub persistence: Option<PersistenceConfig>,
 }
 
 fn default_cache_config() -> CacheConfig {
     CacheConfig { num_events: 5000 }
 }
 
+const fn default_sync_interval_ms() -> u64 {
+    1000
+}
+
 impl DedupeConfig {
     /// We cannot rely on Serde to populate the default since we want it to be based on the user's
     /// configured log_schema, which we only know about after we've already parsed the config.
@@ -57,6 +81,7 @@ impl DedupeConfig {
 pub struct Dedupe {
     fields: FieldMatchConfig,
     cache: LruCache<CacheEntry, bool>,
+    store: Option<Store>,
 }
 
 inventory::submit! {
@@ -68,6 +93,7 @@ impl GenerateConfig for DedupeConfig {
         toml::Value::try_from(Self {
             fields: None,
             cache: default_cache_config(),
+            persistence: None,
         })
         .unwrap()
     }
@@ -76,8 +102,25 @@ impl GenerateConfig for DedupeConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "dedupe")]
 impl TransformConfig for DedupeConfig {
-    async fn build(&self) -> crate::Result<Transform> {
-        Ok(Transform::task(Dedupe::new(self.clone())))
+    async fn build(&self, name: &str, globals: &GlobalOptions) -> crate::Result<Transform> {
+        let dedupe = Dedupe::new(self.clone());
+        match &self.persistence {
+            Some(persistence) => {
+                if !globals.state.is_default() {
+                    warn!(
+                        message = "The `dedupe` cache is persisted in the `data_dir`, not in the configured `state` backend.",
+                        transform = %name,
+                    );
+                }
+                let data_dir =
+                    globals.resolve_and_make_data_subdir(persistence.data_dir.as_ref(), name)?;
+                let sync_interval = Duration::from_millis(persistence.sync_interval_ms);
+                Ok(Transform::task(
+                    dedupe.with_persistence(data_dir, sync_interval)?,
+                ))
+            }
+            None => Ok(Transform::task(dedupe)),
+        }
     }
 
     fn input_type(&self) -> DataType {
@@ -114,7 +157,7 @@ type TypeId = u8;
 /// are backed by a BTreeMap), and we build CacheEntries by iterating over the fields of the
 /// incoming Events, we know that the CacheEntries for 2 equivalent events will always contain the
 /// fields in the same order.
-#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
 enum CacheEntry {
     Match(Vec<Option<(TypeId, Bytes)>>),
     Ignore(Vec<(String, TypeId, Bytes)>),
@@ -141,18 +184,65 @@ impl Dedupe {
         Self {
             fields,
             cache: LruCache::new(num_entries),
+            store: None,
+        }
+    }
+
+    /// Restores the cache from `data_dir`, and records every entry added to
+    /// it from then on.
+    pub fn with_persistence(
+        mut self,
+        data_dir: PathBuf,
+        sync_interval: Duration,
+    ) -> std::io::Result<Self> {
+        let (store, entries) = Store::open(data_dir, sync_interval, self.cache.cap())?;
+        for entry in entries {
+            self.cache.put(entry, true);
         }
+        self.store = Some(store);
+        Ok(self)
     }
 
     fn transform_one(&mut self, event: Event) -> Option<Event> {
         let cache_entry = build_cache_entry(&event, &self.fields);
-        if self.cache.put(cache_entry, true).is_some() {
+        if let Some(store) = &mut self.store {
+            // Duplicates are recorded too, as they refresh the entry's
+            // position in the cache.
+            if let Err(error) = store.append(&cache_entry) {
+                emit!(DedupePersistenceFailed { error });
+            }
+        }
+
+        let duplicate = self.cache.put(cache_entry, true).is_some();
+
+        if let Some(store) = &mut self.store {
+            if store.needs_compaction() {
+                let entries = self
+                    .cache
+                    .iter()
+                    .map(|(entry, _)| entry)
+                    .collect::<Vec<_>>();
+                if let Err(error) = store.compact(entries.into_iter().rev()) {
+                    emit!(DedupePersistenceFailed { error });
+                }
+            }
+        }
+
+        if duplicate {
             emit!(DedupeEventDiscarded { event });
             None
         } else {
             Some(event)
         }
     }
+
+    fn sync_pending(&mut self) {
+        if let Some(store) = &mut self.store {
+            if let Err(error) = store.sync_pending() {
+                emit!(DedupePersistenceFailed { error });
+            }
+        }
+    }
 }
 
 /// Takes in an Event and returns a CacheEntry to place into the LRU cache containing
@@ -188,13 +278,43 @@ fn build_cache_entry(event: &Event, fields: &FieldMatchConfig) -> CacheEntry {
 impl TaskTransform for Dedupe {
     fn transform(
         self: Box<Self>,
-        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+        mut task: Pin<Box<dyn Stream<Item = Event> + Send>>,
     ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
     where
         Self: 'static,
     {
         let mut inner = self;
-        Box::pin(task.filter_map(move |v| ready(inner.transform_one(v))))
+        let sync_interval = match inner
+            .store
+            .as_ref()
+            .map(Store::sync_interval)
+            .filter(|interval| *interval > Duration::from_secs(0))
+        {
+            Some(sync_interval) => sync_interval,
+            None => return Box::pin(task.filter_map(move |v| ready(inner.transform_one(v)))),
+        };
+
+        // Sync the entries of the last events on time even when no other
+        // event follows them.
+        let mut sync_stream = tokio::time::interval(sync_interval);
+        Box::pin(stream! {
+          loop {
+            let (output, done) = tokio::select! {
+                _ = sync_stream.next() => {
+                  inner.sync_pending();
+                  (None, false)
+                }
+                maybe_event = task.next() => match maybe_event {
+                  Some(event) => (inner.transform_one(event), false),
+                  None => (None, true),
+                }
+            };
+            if let Some(event) = output {
+              yield event;
+            }
+            if done { break }
+          }
+        })
     }
 }
 
@@ -203,6 +323,7 @@ mod tests {
     use super::*;
     use crate::transforms::dedupe::{CacheConfig, DedupeConfig, FieldMatchConfig};
     use crate::{event::Event, event::Value};
+    use futures::SinkExt;
     use std::collections::BTreeMap;
 
     #[test]
@@ -214,6 +335,7 @@ mod tests {
         Dedupe::new(DedupeConfig {
             cache: CacheConfig { num_events },
             fields: Some(FieldMatchConfig::MatchFields(fields)),
+            persistence: None,
         })
     }
 
@@ -225,6 +347,7 @@ mod tests {
         Dedupe::new(DedupeConfig {
             cache: CacheConfig { num_events },
             fields: Some(FieldMatchConfig::IgnoreFields(fields)),
+            persistence: None,
         })
     }
 
@@ -469,4 +592,52 @@ mod tests {
         let new_event = transform.transform_one(event2).unwrap();
         assert_eq!(false, new_event.as_log().contains("matched"));
     }
+
+    #[test]
+    fn dedupe_persists_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let persistent = || {
+            make_match_transform(2, vec!["matched".into()])
+                .with_persistence(dir.path().to_path_buf(), Duration::from_secs(0))
+                .unwrap()
+        };
+
+        let mut event1 = Event::from("message");
+        event1.as_mut_log().insert("matched", "some value");
+        let mut event2 = Event::from("message");
+        event2.as_mut_log().insert("matched", "some value2");
+        let mut event3 = Event::from("message");
+        event3.as_mut_log().insert("matched", "some value3");
+
+        let mut transform = persistent();
+        assert!(transform.transform_one(event1.clone()).is_some());
+        assert!(transform.transform_one(event2.clone()).is_some());
+        // Evicts the first event, after the state was compacted.
+        assert!(transform.transform_one(event3.clone()).is_some());
+        drop(transform);
+
+        let mut transform = persistent();
+        assert_eq!(None, transform.transform_one(event2));
+        assert_eq!(None, transform.transform_one(event3));
+        assert!(transform.transform_one(event1).is_some());
+    }
+
+    #[tokio::test]
+    async fn dedupe_syncs_while_idle() {
+        let dir = tempfile::tempdir().unwrap();
+        let transform = make_match_transform(2, vec!["matched".into()])
+            .with_persistence(dir.path().to_path_buf(), Duration::from_millis(10))
+            .unwrap();
+        let (mut tx, rx) = futures::channel::mpsc::channel(1);
+        let mut output = Box::new(transform).transform(Box::pin(rx));
+        tokio::spawn(async move { while output.next().await.is_some() {} });
+
+        let mut event = Event::from("message");
+        event.as_mut_log().insert("matched", "some value");
+        tx.send(event).await.unwrap();
+        tokio::time::delay_for(Duration::from_millis(100)).await;
+
+        let wal = std::fs::metadata(dir.path().join("wal.json")).unwrap();
+        assert!(wal.len() > 0);
+    }
 }
@@ -0,0 +1,230 @@
+//! On-disk state for the dedupe cache, so duplicates are still caught after a
+//! restart.
+//!
+//! The state is a snapshot of the cache, stored from the least to the most
+//! recently used entry, followed by a write-ahead log of every entry seen
+//! since. Replaying both into a cache of the same size reproduces it exactly.
+//! Once the log holds as many entries as the cache, a new snapshot is written
+//! and the log truncated, bounding the state to twice the cache size.
+//!
+//! Unlike the state of the sources, it isn't kept in a `crate::state` store,
+//! whose values are replaced as a whole rather than appended to.
+
+use super::CacheEntry;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+const SNAPSHOT_FILENAME: &str = "snapshot.json";
+const WAL_FILENAME: &str = "wal.json";
+
+pub struct Store {
+    dir: PathBuf,
+    wal: BufWriter<File>,
+    wal_entries: usize,
+    compact_after: usize,
+    sync_interval: Duration,
+    last_sync: Instant,
+    /// Whether entries were appended since the last sync.
+    unsynced: bool,
+}
+
+impl Store {
+    /// Opens the state in `dir`, returning the entries to replay in order.
+    pub fn open(
+        dir: PathBuf,
+        sync_interval: Duration,
+        capacity: usize,
+    ) -> io::Result<(Self, Vec<CacheEntry>)> {
+        let (mut entries, _) = read_entries(&dir.join(SNAPSHOT_FILENAME))?;
+        let (wal, torn) = read_entries(&dir.join(WAL_FILENAME))?;
+        let wal_entries = wal.len();
+        entries.extend(wal);
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(WAL_FILENAME))?;
+        if torn {
+            // Terminate the partial line so it doesn't swallow the next entry.
+            file.write_all(b"\n")?;
+        }
+
+        let store = Self {
+            dir,
+            wal: BufWriter::new(file),
+            wal_entries,
+            compact_after: capacity.max(1),
+            sync_interval,
+            last_sync: Instant::now(),
+            unsynced: false,
+        };
+        Ok((store, entries))
+    }
+
+    pub fn append(&mut self, entry: &CacheEntry) -> io::Result<()> {
+        serde_json::to_writer(&mut self.wal, entry)?;
+        self.wal.write_all(b"\n")?;
+        self.wal_entries += 1;
+        self.unsynced = true;
+
+        if self.last_sync.elapsed() >= self.sync_interval {
+            self.sync()?;
+        }
+        Ok(())
+    }
+
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.wal.flush()?;
+        self.wal.get_ref().sync_data()?;
+        self.last_sync = Instant::now();
+        self.unsynced = false;
+        Ok(())
+    }
+
+    /// Syncs the entries appended since the last sync, for when no event
+    /// comes along to do it.
+    pub fn sync_pending(&mut self) -> io::Result<()> {
+        if self.unsynced {
+            self.sync()
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn sync_interval(&self) -> Duration {
+        self.sync_interval
+    }
+
+    pub fn needs_compaction(&self) -> bool {
+        self.wal_entries >= self.compact_after
+    }
+
+    /// Replaces the snapshot with `entries`, ordered from the least to the
+    /// most recently used, and truncates the log.
+    pub fn compact<'a>(&mut self, entries: impl Iterator<Item = &'a CacheEntry>) -> io::Result<()> {
+        let snapshot = self.dir.join(SNAPSHOT_FILENAME);
+        let tmp = snapshot.with_extension("json.tmp");
+
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        for entry in entries {
+            serde_json::to_writer(&mut writer, entry)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        fs::rename(&tmp, &snapshot)?;
+
+        // Only truncate once the snapshot is in place. Crashing in between
+        // replays the log on top of a snapshot that already contains it,
+        // which leaves the same entries in the cache.
+        self.wal.flush()?;
+        self.wal.get_ref().set_len(0)?;
+        self.wal_entries = 0;
+        self.sync()
+    }
+}
+
+impl Drop for Store {
+    fn drop(&mut self) {
+        let _ = self.sync();
+    }
+}
+
+/// Reads the entries in `path`, along with whether its last line was only
+/// partially written.
+fn read_entries(path: &Path) -> io::Result<(Vec<CacheEntry>, bool)> {
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok((Vec::new(), false)),
+        Err(error) => return Err(error),
+    };
+
+    // A crash can leave a partially written last line behind, losing only
+    // the entry that was being written at the time.
+    let entries = data
+        .split(|b| *b == b'\n')
+        .filter_map(|line| serde_json::from_slice(line).ok())
+        .collect();
+    let torn = data.last().map_or(false, |b| *b != b'\n');
+    Ok((entries, torn))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use tempfile::tempdir;
+
+    fn entry(value: &str) -> CacheEntry {
+        CacheEntry::Match(vec![Some((0, Bytes::from(value.to_owned())))])
+    }
+
+    #[test]
+    fn replays_snapshot_then_log() {
+        let dir = tempdir().unwrap();
+
+        {
+            let (mut store, entries) =
+                Store::open(dir.path().to_path_buf(), Duration::from_secs(1), 2).unwrap();
+            assert!(entries.is_empty());
+
+            store.append(&entry("a")).unwrap();
+            store.append(&entry("b")).unwrap();
+            assert!(store.needs_compaction());
+            store.compact(vec![entry("a"), entry("b")].iter()).unwrap();
+            assert!(!store.needs_compaction());
+
+            store.append(&entry("c")).unwrap();
+        }
+
+        let (mut store, entries) =
+            Store::open(dir.path().to_path_buf(), Duration::from_secs(1), 2).unwrap();
+        assert_eq!(entries, vec![entry("a"), entry("b"), entry("c")]);
+
+        // The log entries found on open count towards the next compaction.
+        store.append(&entry("d")).unwrap();
+        assert!(store.needs_compaction());
+    }
+
+    #[test]
+    fn syncs_pending_entries() {
+        let dir = tempdir().unwrap();
+        let (mut store, _) =
+            Store::open(dir.path().to_path_buf(), Duration::from_secs(3600), 2).unwrap();
+        store.append(&entry("a")).unwrap();
+        assert!(store.unsynced);
+
+        store.sync_pending().unwrap();
+        assert!(!store.unsynced);
+        let (entries, _) = read_entries(&dir.path().join(WAL_FILENAME)).unwrap();
+        assert_eq!(entries, vec![entry("a")]);
+    }
+
+    #[test]
+    fn skips_torn_writes() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join(WAL_FILENAME),
+            format!(
+                "{}\n{{\"Match\":[",
+                serde_json::to_string(&entry("a")).unwrap()
+            ),
+        )
+        .unwrap();
+
+        {
+            let (mut store, entries) =
+                Store::open(dir.path().to_path_buf(), Duration::from_secs(1), 2).unwrap();
+            assert_eq!(entries, vec![entry("a")]);
+            store.append(&entry("b")).unwrap();
+        }
+
+        let (_, entries) =
+            Store::open(dir.path().to_path_buf(), Duration::from_secs(1), 2).unwrap();
+        assert_eq!(entries, vec![entry("a"), entry("b")]);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    internal_events::{ExplodeFieldMissing, ExplodeFieldNotArray},
+    transforms::{FunctionTransform, Transform},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ExplodeConfig {
+    /// The array field holding the elements to emit an event for.
+    pub field: String,
+    /// The field each element is written to. Unset, the fields of the
+    /// elements that are maps are merged into the event, and the other
+    /// elements written to `field`.
+    pub target: Option<String>,
+}
+
+inventory::submit! {
+    TransformDescription::new::<ExplodeConfig>("explode")
+}
+
+impl GenerateConfig for ExplodeConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            field: "events".to_owned(),
+            target: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "explode")]
+impl TransformConfig for ExplodeConfig {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
+        Ok(Transform::function(Explode {
+            field: self.field.clone(),
+            target: self.target.clone(),
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "explode"
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Explode {
+    field: String,
+    target: Option<String>,
+}
+
+impl FunctionTransform for Explode {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let elements = match event.as_mut_log().remove(&self.field) {
+            Some(Value::Array(elements)) => elements,
+            Some(value) => {
+                emit!(ExplodeFieldNotArray { field: &self.field });
+                event.as_mut_log().insert(&self.field, value);
+                output.push(event);
+                return;
+            }
+            None => {
+                emit!(ExplodeFieldMissing { field: &self.field });
+                output.push(event);
+                return;
+            }
+        };
+
+        // The last element takes the event itself rather than a copy.
+        let mut elements = elements.into_iter().peekable();
+        while let Some(element) = elements.next() {
+            let mut event = if elements.peek().is_some() {
+                event.clone()
+            } else {
+                std::mem::replace(&mut event, Event::new_empty_log())
+            };
+
+            let log = event.as_mut_log();
+            match (&self.target, element) {
+                (Some(target), element) => {
+                    log.insert(target, element);
+                }
+                (None, Value::Map(fields)) => {
+                    for (key, value) in fields {
+                        log.insert_flat(key, value);
+                    }
+                }
+                (None, element) => {
+                    log.insert(&self.field, element);
+                }
+            }
+            output.push(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::LogEvent;
+    use serde_json::json;
+
+    fn explode(config: &str, event: serde_json::Value) -> Vec<serde_json::Value> {
+        let config: ExplodeConfig = toml::from_str(config).unwrap();
+        let mut explode = Explode {
+            field: config.field,
+            target: config.target,
+        };
+
+        let log = match event {
+            serde_json::Value::Object(fields) => fields
+                .into_iter()
+                .map(|(key, value)| (key, Value::from(value)))
+                .collect::<LogEvent>(),
+            _ => panic!("expected an object"),
+        };
+        let mut output = Vec::new();
+        explode.transform(&mut output, log.into());
+        output
+            .into_iter()
+            .map(|event| serde_json::to_value(event.as_log()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ExplodeConfig>();
+    }
+
+    #[test]
+    fn merges_map_elements_in_order() {
+        let output = explode(
+            r#"field = "events""#,
+            json!({"source": "webhook", "events": [{"id": 1}, {"id": 2, "source": "inner"}]}),
+        );
+
+        assert_eq!(
+            output,
+            vec![
+                json!({"source": "webhook", "id": 1}),
+                json!({"source": "inner", "id": 2}),
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_other_elements_to_the_field() {
+        let output = explode(
+            r#"field = "tags""#,
+            json!({"host": "a", "tags": ["x", "y"]}),
+        );
+
+        assert_eq!(
+            output,
+            vec![
+                json!({"host": "a", "tags": "x"}),
+                json!({"host": "a", "tags": "y"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_elements_to_the_target() {
+        let output = explode(
+            "field = \"events\"\ntarget = \"event\"",
+            json!({"host": "a", "events": [{"id": 1}, 2]}),
+        );
+
+        assert_eq!(
+            output,
+            vec![
+                json!({"host": "a", "event": {"id": 1}}),
+                json!({"host": "a", "event": 2}),
+            ]
+        );
+    }
+
+    #[test]
+    fn passes_events_without_array_through() {
+        assert_eq!(
+            explode(r#"field = "events""#, json!({"events": "one"})),
+            vec![json!({"events": "one"})]
+        );
+        assert_eq!(
+            explode(r#"field = "events""#, json!({"host": "a"})),
+            vec![json!({"host": "a"})]
+        );
+        assert!(explode(r#"field = "events""#, json!({"events": []})).is_empty());
+    }
+}
@@ -0,0 +1,650 @@
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
+    event::{
+        metric::{Bucket, Metric, MetricKind, MetricSeries, MetricTags, MetricValue, Sample},
+        Event, StatisticKind,
+    },
+    internal_events::MetricSeriesExpired,
+    transforms::{FunctionTransform, Transform},
+};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+/// The series are checked for expiration at most this often.
+const EXPIRATION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Bucket upper limits must be increasing"))]
+    UnsortedBuckets,
+    #[snafu(display("Quantiles must be in range [0.0,1.0]"))]
+    QuantileOutOfRange,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct HistogramConfig {
+    /// The upper limits of the buckets the histograms are re-bucketed to.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub buckets: Vec<f64>,
+    /// The tags dropped from the histograms, merging the series only they told apart.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub drop_tags: Vec<String>,
+    /// The quantiles estimated from the histograms, sent as gauges instead of them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quantiles: Vec<f64>,
+    /// Overrides the global `expire_metrics_secs` option.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::serde::optional_duration_secs"
+    )]
+    pub expire_metrics_secs: Option<u64>,
+}
+
+inventory::submit! {
+    TransformDescription::new::<HistogramConfig>("histogram")
+}
+
+impl GenerateConfig for HistogramConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            buckets: vec![0.1, 1.0, 10.0],
+            drop_tags: vec!["instance".to_owned()],
+            ..Self::default()
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "histogram")]
+impl TransformConfig for HistogramConfig {
+    async fn build(&self, _name: &str, globals: &GlobalOptions) -> crate::Result<Transform> {
+        if self.buckets.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(BuildError::UnsortedBuckets.into());
+        }
+        if !self
+            .quantiles
+            .iter()
+            .all(|&quantile| (0.0..=1.0).contains(&quantile))
+        {
+            return Err(BuildError::QuantileOutOfRange.into());
+        }
+
+        let expire_after = self
+            .expire_metrics_secs
+            .or(globals.expire_metrics_secs)
+            .map(Duration::from_secs);
+        Ok(Transform::function(Histogram::new(
+            self.buckets.clone(),
+            self.drop_tags.clone(),
+            self.quantiles.clone(),
+            expire_after,
+        )))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "histogram"
+    }
+}
+
+/// The last absolute value of a series merged into another.
+#[derive(Clone, Debug)]
+struct SeriesState {
+    value: MetricValue,
+    updated: Instant,
+}
+
+#[derive(Clone, Debug)]
+pub struct Histogram {
+    buckets: Vec<f64>,
+    drop_tags: Vec<String>,
+    quantiles: Vec<f64>,
+    expire_after: Option<Duration>,
+    last_check: Instant,
+    /// The merged series, holding the series merged into them by the values
+    /// of their dropped tags.
+    merged: HashMap<MetricSeries, BTreeMap<MetricTags, SeriesState>>,
+}
+
+impl Histogram {
+    pub fn new(
+        buckets: Vec<f64>,
+        drop_tags: Vec<String>,
+        quantiles: Vec<f64>,
+        expire_after: Option<Duration>,
+    ) -> Self {
+        Self {
+            buckets,
+            drop_tags,
+            quantiles,
+            expire_after,
+            last_check: Instant::now(),
+            merged: HashMap::new(),
+        }
+    }
+
+    /// Forgets the series not updated for `expire_after`, so that they're no
+    /// longer part of the series they were merged into.
+    fn expire(&mut self, now: Instant) {
+        let expire_after = match self.expire_after {
+            Some(expire_after) => expire_after,
+            None => return,
+        };
+        if now.duration_since(self.last_check) < EXPIRATION_CHECK_INTERVAL.min(expire_after) {
+            return;
+        }
+        self.last_check = now;
+
+        let mut count = 0;
+        self.merged.retain(|_, series| {
+            let before = series.len();
+            series.retain(|_, state| now.duration_since(state.updated) < expire_after);
+            count += before - series.len();
+            !series.is_empty()
+        });
+        if count > 0 {
+            emit!(MetricSeriesExpired { count });
+        }
+    }
+
+    /// Drops the tags of the metric. The absolute histograms are then the sum
+    /// of the last values of the series merged, while the other metrics are
+    /// added up downstream.
+    fn merge(&mut self, mut metric: Metric, now: Instant) -> Metric {
+        let dropped = self
+            .drop_tags
+            .iter()
+            .filter_map(|tag| metric.delete_tag(tag).map(|value| (tag.clone(), value)))
+            .collect::<MetricTags>();
+        if metric.tags().map_or(false, MetricTags::is_empty) {
+            *metric.tags_mut() = None;
+        }
+
+        if metric.data.kind == MetricKind::Incremental
+            || !metric.data.value.is_aggregated_histogram()
+        {
+            return metric;
+        }
+
+        let series = self.merged.entry(metric.series.clone()).or_default();
+        series.insert(
+            dropped,
+            SeriesState {
+                value: metric.data.value.clone(),
+                updated: now,
+            },
+        );
+
+        let mut states = series.values();
+        let mut total = states.next().expect("just inserted").value.clone();
+        for state in states {
+            add_histogram(&mut total, &state.value);
+        }
+        metric.data.value = total;
+        metric
+    }
+
+    /// The gauges of the quantiles estimated from the histogram.
+    fn quantile_gauges<'a>(&'a self, metric: &'a Metric) -> impl Iterator<Item = Metric> + 'a {
+        estimate_quantiles(&metric.data.value, &self.quantiles)
+            .into_iter()
+            .map(move |(quantile, value)| {
+                let mut gauge = Metric::new(
+                    metric.name(),
+                    MetricKind::Absolute,
+                    MetricValue::Gauge { value },
+                )
+                .with_namespace(metric.namespace())
+                .with_timestamp(metric.data.timestamp)
+                .with_tags(metric.tags().cloned());
+                gauge.set_tag_value("quantile".to_owned(), quantile.to_string());
+                gauge
+            })
+    }
+}
+
+impl FunctionTransform for Histogram {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        let now = Instant::now();
+        self.expire(now);
+
+        let mut metric = event.into_metric();
+        if !matches!(
+            metric.data.value,
+            MetricValue::AggregatedHistogram { .. } | MetricValue::Distribution { .. }
+        ) {
+            output.push(metric.into());
+            return;
+        }
+
+        if !self.buckets.is_empty() {
+            metric.data.value = rebucket_value(metric.data.value, &self.buckets);
+        }
+        if !self.drop_tags.is_empty() {
+            metric = self.merge(metric, now);
+        }
+
+        if self.quantiles.is_empty() {
+            output.push(metric.into());
+        } else {
+            output.extend(self.quantile_gauges(&metric).map(Event::Metric));
+        }
+    }
+}
+
+/// Counts the observations of aggregated histograms, and of distributions
+/// meant as histograms, into buckets of the `limits`.
+fn rebucket_value(value: MetricValue, limits: &[f64]) -> MetricValue {
+    match value {
+        MetricValue::AggregatedHistogram {
+            buckets,
+            count,
+            sum,
+        } => MetricValue::AggregatedHistogram {
+            buckets: rebucket(&buckets, limits),
+            count,
+            sum,
+        },
+        MetricValue::Distribution {
+            samples,
+            statistic: StatisticKind::Histogram,
+        } => bucket_samples(&samples, limits),
+        value => value,
+    }
+}
+
+/// Spreads the observations of each bucket evenly over its range to count
+/// them into buckets of the `limits`. The observations above the last limit
+/// are only part of the total count, as the ones above the last bucket.
+fn rebucket(buckets: &[Bucket], limits: &[f64]) -> Vec<Bucket> {
+    // Rounding the observations up to each limit, rather than the ones within
+    // each bucket, keeps the sum of the counts.
+    let mut previous = 0;
+    limits
+        .iter()
+        .map(|&upper_limit| {
+            let cumulative = cumulative_count(buckets, upper_limit).round() as u32;
+            let count = cumulative - previous;
+            previous = cumulative;
+            Bucket { upper_limit, count }
+        })
+        .collect()
+}
+
+/// The observations of the histogram up to `value`, interpolated within the
+/// bucket holding it.
+fn cumulative_count(buckets: &[Bucket], value: f64) -> f64 {
+    let mut total = 0.0;
+    let mut lower_limit = None;
+    for bucket in buckets {
+        let upper_limit = bucket.upper_limit;
+        // The first bucket starts at zero.
+        let lower = lower_limit.unwrap_or_else(|| upper_limit.min(0.0));
+        let fraction = if value >= upper_limit {
+            1.0
+        } else if value <= lower {
+            0.0
+        } else {
+            (value - lower) / (upper_limit - lower)
+        };
+        total += bucket.count as f64 * fraction;
+        lower_limit = Some(upper_limit);
+    }
+    total
+}
+
+fn bucket_samples(samples: &[Sample], limits: &[f64]) -> MetricValue {
+    let mut buckets = limits
+        .iter()
+        .map(|&upper_limit| Bucket {
+            upper_limit,
+            count: 0,
+        })
+        .collect::<Vec<_>>();
+    let mut count = 0;
+    let mut sum = 0.0;
+    for sample in samples {
+        if let Some(bucket) = buckets
+            .iter_mut()
+            .find(|bucket| sample.value <= bucket.upper_limit)
+        {
+            bucket.count += sample.rate;
+        }
+        count += sample.rate;
+        sum += sample.value * sample.rate as f64;
+    }
+    MetricValue::AggregatedHistogram {
+        buckets,
+        count,
+        sum,
+    }
+}
+
+/// Adds a histogram to another, re-bucketed to its buckets if they differ.
+fn add_histogram(total: &mut MetricValue, value: &MetricValue) {
+    let rebucketed = match (&*total, value) {
+        (
+            MetricValue::AggregatedHistogram {
+                buckets: total_buckets,
+                ..
+            },
+            MetricValue::AggregatedHistogram { buckets, .. },
+        ) if total_buckets.len() != buckets.len()
+            || total_buckets
+                .iter()
+                .zip(buckets)
+                .any(|(a, b)| a.upper_limit != b.upper_limit) =>
+        {
+            let limits = total_buckets
+                .iter()
+                .map(|bucket| bucket.upper_limit)
+                .collect::<Vec<_>>();
+            Some(rebucket_value(value.clone(), &limits))
+        }
+        _ => None,
+    };
+    total.add(rebucketed.as_ref().unwrap_or(value));
+}
+
+/// Estimates the quantiles of histograms the way Prometheus' `histogram_quantile`
+/// does, and the ones of distributions from their samples.
+fn estimate_quantiles(value: &MetricValue, quantiles: &[f64]) -> Vec<(f64, f64)> {
+    match value {
+        MetricValue::AggregatedHistogram { buckets, count, .. } => quantiles
+            .iter()
+            .filter_map(|&quantile| {
+                histogram_quantile(buckets, *count, quantile).map(|value| (quantile, value))
+            })
+            .collect(),
+        MetricValue::Distribution { samples, .. } => {
+            let mut samples = samples.clone();
+            samples.sort_by(|a, b| a.value.partial_cmp(&b.value).unwrap_or(Ordering::Equal));
+            quantiles
+                .iter()
+                .filter_map(|&quantile| {
+                    sample_quantile(&samples, quantile).map(|value| (quantile, value))
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Interpolates the quantile linearly within the bucket holding it. Quantiles
+/// above the last bucket are estimated as its upper limit.
+fn histogram_quantile(buckets: &[Bucket], count: u32, quantile: f64) -> Option<f64> {
+    if count == 0 {
+        return None;
+    }
+
+    let rank = quantile * count as f64;
+    let mut cumulative = 0.0;
+    let mut lower_limit = None;
+    for bucket in buckets {
+        let upper_limit = bucket.upper_limit;
+        let lower = lower_limit.unwrap_or_else(|| upper_limit.min(0.0));
+        let next = cumulative + bucket.count as f64;
+        if bucket.count > 0 && next >= rank {
+            return Some(lower + (upper_limit - lower) * (rank - cumulative) / bucket.count as f64);
+        }
+        cumulative = next;
+        lower_limit = Some(upper_limit);
+    }
+    buckets.last().map(|bucket| bucket.upper_limit)
+}
+
+/// The value of the sample at the rank of the quantile, among the `samples`
+/// sorted by value.
+fn sample_quantile(samples: &[Sample], quantile: f64) -> Option<f64> {
+    let total = samples.iter().map(|sample| sample.rate as u64).sum::<u64>();
+    if total == 0 {
+        return None;
+    }
+
+    let rank = ((quantile * total as f64).ceil() as u64).max(1);
+    let mut cumulative = 0;
+    samples
+        .iter()
+        .find(|sample| {
+            cumulative += sample.rate as u64;
+            cumulative >= rank
+        })
+        .map(|sample| sample.value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<HistogramConfig>();
+    }
+
+    fn latency(kind: MetricKind, host: &str, buckets: &[(f64, u32)], count: u32) -> Metric {
+        Metric::new(
+            "latency",
+            kind,
+            MetricValue::AggregatedHistogram {
+                buckets: buckets
+                    .iter()
+                    .map(|&(upper_limit, count)| Bucket { upper_limit, count })
+                    .collect(),
+                count,
+                sum: 10.0,
+            },
+        )
+        .with_tags(Some(
+            vec![("host".to_owned(), host.to_owned())]
+                .into_iter()
+                .collect(),
+        ))
+    }
+
+    fn transform(histogram: &mut Histogram, metric: Metric) -> Vec<Metric> {
+        let mut output = Vec::new();
+        histogram.transform(&mut output, metric.into());
+        output.into_iter().map(Event::into_metric).collect()
+    }
+
+    #[test]
+    fn rebuckets_histograms() {
+        let mut histogram = Histogram::new(vec![1.0, 3.0], vec![], vec![], None);
+        let metric = latency(
+            MetricKind::Absolute,
+            "a",
+            &[(2.0, 10), (4.0, 10), (8.0, 4)],
+            30,
+        );
+
+        assert_eq!(
+            transform(&mut histogram, metric),
+            vec![latency(
+                MetricKind::Absolute,
+                "a",
+                &[(1.0, 5), (3.0, 10)],
+                30
+            )]
+        );
+    }
+
+    #[test]
+    fn rebuckets_distributions() {
+        let mut histogram = Histogram::new(vec![1.0, 10.0], vec![], vec![], None);
+        let metric = Metric::new(
+            "latency",
+            MetricKind::Incremental,
+            MetricValue::Distribution {
+                samples: vec![
+                    Sample {
+                        value: 0.5,
+                        rate: 2,
+                    },
+                    Sample {
+                        value: 5.0,
+                        rate: 1,
+                    },
+                    Sample {
+                        value: 50.0,
+                        rate: 1,
+                    },
+                ],
+                statistic: StatisticKind::Histogram,
+            },
+        );
+
+        assert_eq!(
+            transform(&mut histogram, metric).pop().unwrap().data.value,
+            MetricValue::AggregatedHistogram {
+                buckets: vec![
+                    Bucket {
+                        upper_limit: 1.0,
+                        count: 2
+                    },
+                    Bucket {
+                        upper_limit: 10.0,
+                        count: 1
+                    },
+                ],
+                count: 4,
+                sum: 56.0,
+            }
+        );
+    }
+
+    #[test]
+    fn merges_absolute_histograms() {
+        let mut histogram = Histogram::new(vec![], vec!["host".to_owned()], vec![], None);
+        let merged = |buckets: &[(f64, u32)], count| {
+            let mut metric = latency(MetricKind::Absolute, "", buckets, count);
+            *metric.tags_mut() = None;
+            metric
+        };
+
+        let a = latency(MetricKind::Absolute, "a", &[(1.0, 1), (2.0, 2)], 4);
+        assert_eq!(
+            transform(&mut histogram, a),
+            vec![merged(&[(1.0, 1), (2.0, 2)], 4)]
+        );
+
+        let b = latency(MetricKind::Absolute, "b", &[(1.0, 3), (2.0, 1)], 5);
+        let mut expected = merged(&[(1.0, 4), (2.0, 3)], 9);
+        if let MetricValue::AggregatedHistogram { sum, .. } = &mut expected.data.value {
+            *sum = 20.0;
+        }
+        assert_eq!(transform(&mut histogram, b), vec![expected.clone()]);
+
+        // A new value of a series replaces its previous one.
+        let a = latency(MetricKind::Absolute, "a", &[(1.0, 1), (2.0, 2)], 4);
+        assert_eq!(transform(&mut histogram, a), vec![expected]);
+    }
+
+    #[test]
+    fn estimates_quantiles() {
+        let mut histogram = Histogram::new(vec![], vec![], vec![0.5, 0.9, 1.0], None);
+        let metric = latency(
+            MetricKind::Absolute,
+            "a",
+            &[(1.0, 5), (2.0, 0), (4.0, 4)],
+            10,
+        );
+
+        let gauges = transform(&mut histogram, metric)
+            .into_iter()
+            .map(|gauge| {
+                assert_eq!(gauge.data.kind, MetricKind::Absolute);
+                let value = match gauge.data.value {
+                    MetricValue::Gauge { value } => value,
+                    value => panic!("not a gauge: {:?}", value),
+                };
+                (gauge.tag_value("quantile").unwrap(), value)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            gauges,
+            vec![
+                ("0.5".to_owned(), 1.0),
+                ("0.9".to_owned(), 4.0),
+                // The highest observation is above the last bucket.
+                ("1".to_owned(), 4.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn estimates_distribution_quantiles() {
+        let samples = vec![
+            Sample {
+                value: 3.0,
+                rate: 1,
+            },
+            Sample {
+                value: 1.0,
+                rate: 3,
+            },
+        ];
+        let value = MetricValue::Distribution {
+            samples,
+            statistic: StatisticKind::Summary,
+        };
+
+        assert_eq!(
+            estimate_quantiles(&value, &[0.0, 0.75, 0.9]),
+            vec![(0.0, 1.0), (0.75, 1.0), (0.9, 3.0)]
+        );
+    }
+
+    #[test]
+    fn passes_other_metrics() {
+        let mut histogram = Histogram::new(vec![1.0], vec!["host".to_owned()], vec![0.5], None);
+        let gauge = Metric::new(
+            "temperature",
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 1.0 },
+        );
+
+        assert_eq!(transform(&mut histogram, gauge.clone()), vec![gauge]);
+    }
+
+    #[test]
+    fn expires_merged_series() {
+        let mut histogram = Histogram::new(
+            vec![],
+            vec!["host".to_owned()],
+            vec![],
+            Some(Duration::from_millis(50)),
+        );
+        let a = latency(MetricKind::Absolute, "a", &[(1.0, 1)], 1);
+        transform(&mut histogram, a);
+
+        std::thread::sleep(Duration::from_millis(100));
+        let b = latency(MetricKind::Absolute, "b", &[(1.0, 2)], 2);
+        let merged = transform(&mut histogram, b).pop().unwrap();
+        assert_eq!(
+            merged.data.value,
+            MetricValue::AggregatedHistogram {
+                buckets: vec![Bucket {
+                    upper_limit: 1.0,
+                    count: 2,
+                }],
+                count: 2,
+                sum: 10.0,
+            }
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! An incremental implementation of the Drain log parsing algorithm.
+//!
+//! Messages are routed through a fixed depth prefix tree, first by their
+//! number of tokens and then by their leading tokens, to a small set of
+//! candidate clusters. The message joins the most similar candidate, which
+//! generalizes its template by replacing the tokens that differ with a
+//! wildcard, or starts a new cluster if none is similar enough.
+//!
+//! See "Drain: An Online Log Parsing Approach with Fixed Depth Tree" by He et
+//! al. for the original description.
+
+use lru::LruCache;
+use std::collections::HashMap;
+
+pub const WILDCARD: &str = "<*>";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cluster {
+    pub template: Vec<String>,
+    pub size: u64,
+}
+
+impl Cluster {
+    pub fn template(&self) -> String {
+        self.template.join(" ")
+    }
+
+    /// The share of non-wildcard template tokens equal to the message's,
+    /// along with the number of wildcards to break ties.
+    fn similarity(&self, tokens: &[&str]) -> (f64, usize) {
+        let mut equal = 0;
+        let mut wildcards = 0;
+        for (template, token) in self.template.iter().zip(tokens) {
+            if template == WILDCARD {
+                wildcards += 1;
+            } else if template == token {
+                equal += 1;
+            }
+        }
+        (equal as f64 / tokens.len() as f64, wildcards)
+    }
+
+    fn merge(&mut self, tokens: &[&str]) -> bool {
+        let mut changed = false;
+        for (template, token) in self.template.iter_mut().zip(tokens) {
+            if template != WILDCARD && template != token {
+                *template = WILDCARD.to_owned();
+                changed = true;
+            }
+        }
+        self.size += 1;
+        changed
+    }
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    children: HashMap<String, Node>,
+    clusters: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Match {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+pub struct Drain {
+    depth: usize,
+    similarity_threshold: f64,
+    max_children: usize,
+    roots: HashMap<usize, Node>,
+    clusters: LruCache<usize, Cluster>,
+    next_id: usize,
+}
+
+impl Drain {
+    pub fn new(
+        depth: usize,
+        similarity_threshold: f64,
+        max_children: usize,
+        max_clusters: usize,
+    ) -> Self {
+        Self {
+            depth,
+            similarity_threshold,
+            max_children,
+            roots: HashMap::new(),
+            clusters: LruCache::new(max_clusters),
+            next_id: 1,
+        }
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Cluster> {
+        self.clusters.peek(&id)
+    }
+
+    /// Assigns `message` to a cluster, returning the cluster's id and how it
+    /// was affected.
+    pub fn add(&mut self, message: &str) -> (usize, Match) {
+        let tokens = message.split_whitespace().collect::<Vec<_>>();
+
+        let depth = self.depth.min(tokens.len());
+        let max_children = self.max_children;
+        let mut node = self.roots.entry(tokens.len()).or_insert_with(Node::default);
+        for token in &tokens[..depth] {
+            let key = if has_digits(token) {
+                WILDCARD
+            } else if node.children.contains_key(*token) || node.children.len() + 1 < max_children {
+                *token
+            } else {
+                // Once a node is full, further distinct tokens share a
+                // single wildcard child to bound the tree's size.
+                WILDCARD
+            };
+            node = node
+                .children
+                .entry(key.to_owned())
+                .or_insert_with(Node::default);
+        }
+
+        // Clusters evicted from the cache leave dangling ids behind.
+        let clusters = &mut self.clusters;
+        node.clusters.retain(|id| clusters.contains(id));
+
+        let mut best: Option<(usize, (f64, usize))> = None;
+        for id in &node.clusters {
+            let cluster = clusters.peek(id).expect("dangling ids were removed");
+            let similarity = cluster.similarity(&tokens);
+            let better = match best {
+                None => true,
+                Some((_, best)) => similarity > best,
+            };
+            if better {
+                best = Some((*id, similarity));
+            }
+        }
+
+        match best {
+            Some((id, (score, _))) if score >= self.similarity_threshold => {
+                let cluster = clusters.get_mut(&id).expect("cluster exists");
+                if cluster.merge(&tokens) {
+                    (id, Match::Updated)
+                } else {
+                    (id, Match::Unchanged)
+                }
+            }
+            _ => {
+                let id = self.next_id;
+                self.next_id += 1;
+                clusters.put(
+                    id,
+                    Cluster {
+                        template: tokens.iter().map(|t| (*t).to_owned()).collect(),
+                        size: 1,
+                    },
+                );
+                node.clusters.push(id);
+                (id, Match::Created)
+            }
+        }
+    }
+}
+
+fn has_digits(token: &str) -> bool {
+    token.bytes().any(|b| b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain() -> Drain {
+        Drain::new(2, 0.4, 100, 1000)
+    }
+
+    #[test]
+    fn generalizes_templates() {
+        let mut drain = drain();
+
+        assert_eq!(
+            drain.add("connected to 10.0.0.1 port 22"),
+            (1, Match::Created)
+        );
+        assert_eq!(
+            drain.add("connected to 10.0.0.2 port 22"),
+            (1, Match::Updated)
+        );
+        assert_eq!(
+            drain.add("connected to 10.0.0.3 port 22"),
+            (1, Match::Unchanged)
+        );
+
+        let cluster = drain.get(1).unwrap();
+        assert_eq!(cluster.template(), "connected to <*> port 22");
+        assert_eq!(cluster.size, 3);
+    }
+
+    #[test]
+    fn separates_dissimilar_messages() {
+        let mut drain = drain();
+
+        assert_eq!(drain.add("user alice logged in"), (1, Match::Created));
+        assert_eq!(drain.add("disk sda is full"), (2, Match::Created));
+        // Same leading tokens, but too different afterwards.
+        assert_eq!(
+            drain.add("job backup started at nine today"),
+            (3, Match::Created)
+        );
+        assert_eq!(
+            drain.add("job backup failed with exit status"),
+            (4, Match::Created)
+        );
+        // Different lengths never share a cluster.
+        assert_eq!(drain.add("user alice logged in twice"), (5, Match::Created));
+        assert_eq!(drain.get(1).unwrap().size, 1);
+    }
+
+    #[test]
+    fn routes_numeric_tokens_through_wildcard() {
+        let mut drain = drain();
+
+        assert_eq!(drain.add("42 requests served"), (1, Match::Created));
+        assert_eq!(drain.add("17 requests served"), (1, Match::Updated));
+        assert_eq!(drain.get(1).unwrap().template(), "<*> requests served");
+    }
+
+    #[test]
+    fn evicts_least_recently_used_clusters() {
+        let mut drain = Drain::new(2, 0.4, 100, 2);
+
+        drain.add("first message here");
+        drain.add("second kind now");
+        drain.add("third thing entirely");
+
+        assert!(drain.get(1).is_none());
+        assert_eq!(drain.add("first message here"), (4, Match::Created));
+    }
+}
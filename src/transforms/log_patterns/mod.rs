@@ -0,0 +1,306 @@
+mod drain;
+
+use self::drain::{Drain, Match};
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription,
+    },
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event, Value,
+    },
+    internal_events::{LogPatternCreated, LogPatternFieldMissing},
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use chrono::Utc;
+use futures::{stream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    pin::Pin,
+    time::Duration,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct LogPatternsConfig {
+    /// The field holding the message to cluster, defaults to the global
+    /// message key.
+    pub field: Option<String>,
+    /// The number of leading tokens used to route messages to candidate
+    /// clusters.
+    pub depth: usize,
+    /// The share of tokens a message must have in common with a pattern to
+    /// be assigned to it.
+    pub similarity_threshold: f64,
+    pub max_children: usize,
+    pub max_patterns: usize,
+    pub id_field: String,
+    pub pattern_field: String,
+    /// When set, a counter of the events seen per pattern is emitted at this
+    /// interval.
+    #[serde(default, deserialize_with = "crate::serde::optional_duration_secs")]
+    pub summary_interval_secs: Option<u64>,
+}
+
+impl Default for LogPatternsConfig {
+    fn default() -> Self {
+        Self {
+            field: None,
+            depth: 2,
+            similarity_threshold: 0.4,
+            max_children: 100,
+            max_patterns: 10_000,
+            id_field: "pattern_id".to_owned(),
+            pattern_field: "pattern".to_owned(),
+            summary_interval_secs: None,
+        }
+    }
+}
+
+inventory::submit! {
+    TransformDescription::new::<LogPatternsConfig>("log_patterns")
+}
+
+impl GenerateConfig for LogPatternsConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self::default()).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "log_patterns")]
+impl TransformConfig for LogPatternsConfig {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
+        LogPatterns::new(self.clone()).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        if self.summary_interval_secs.is_some() {
+            DataType::Any
+        } else {
+            DataType::Log
+        }
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "log_patterns"
+    }
+}
+
+pub struct LogPatterns {
+    field: String,
+    id_field: String,
+    pattern_field: String,
+    summary_interval: Option<Duration>,
+    drain: Drain,
+    counts: HashMap<usize, u64>,
+}
+
+impl LogPatterns {
+    pub fn new(config: LogPatternsConfig) -> crate::Result<Self> {
+        if config.depth == 0 {
+            return Err("`depth` must be at least 1".into());
+        }
+        if config.similarity_threshold.is_nan()
+            || config.similarity_threshold < 0.0
+            || config.similarity_threshold > 1.0
+        {
+            return Err("`similarity_threshold` must be between 0 and 1".into());
+        }
+        if config.summary_interval_secs == Some(0) {
+            return Err("`summary_interval_secs` must be greater than 0".into());
+        }
+
+        Ok(Self {
+            field: config
+                .field
+                .unwrap_or_else(|| log_schema().message_key().to_owned()),
+            id_field: config.id_field,
+            pattern_field: config.pattern_field,
+            summary_interval: config.summary_interval_secs.map(Duration::from_secs),
+            drain: Drain::new(
+                config.depth,
+                config.similarity_threshold,
+                config.max_children,
+                config.max_patterns,
+            ),
+            counts: HashMap::new(),
+        })
+    }
+
+    fn transform_one(&mut self, mut event: Event) -> Event {
+        let log = event.as_mut_log();
+        let message = match log.get(&self.field) {
+            Some(value) => value.to_string_lossy(),
+            None => {
+                emit!(LogPatternFieldMissing { field: &self.field });
+                return event;
+            }
+        };
+
+        let (id, matched) = self.drain.add(&message);
+        if matched == Match::Created {
+            emit!(LogPatternCreated);
+        }
+        if self.summary_interval.is_some() {
+            *self.counts.entry(id).or_insert(0) += 1;
+        }
+
+        let cluster = self.drain.get(id).expect("cluster was just added");
+        log.insert(&self.id_field, Value::Integer(id as i64));
+        log.insert(&self.pattern_field, cluster.template());
+        event
+    }
+
+    fn flush_into(&mut self, output: &mut Vec<Event>) {
+        let timestamp = Utc::now();
+        let mut counts = self.counts.drain().collect::<Vec<_>>();
+        counts.sort_unstable();
+        for (id, count) in counts {
+            // Patterns evicted since they were counted can't be described
+            // anymore.
+            let cluster = match self.drain.get(id) {
+                Some(cluster) => cluster,
+                None => continue,
+            };
+
+            let mut tags = BTreeMap::new();
+            tags.insert("pattern_id".to_owned(), id.to_string());
+            tags.insert("pattern".to_owned(), cluster.template());
+
+            output.push(Event::Metric(
+                Metric::new(
+                    "log_pattern_events_total",
+                    MetricKind::Incremental,
+                    MetricValue::Counter {
+                        value: count as f64,
+                    },
+                )
+                .with_tags(Some(tags))
+                .with_timestamp(Some(timestamp)),
+            ));
+        }
+    }
+}
+
+impl TaskTransform for LogPatterns {
+    fn transform(
+        self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let summary_interval = match me.summary_interval {
+            Some(interval) => interval,
+            None => return Box::pin(input_rx.map(move |event| me.transform_one(event))),
+        };
+        let mut flush_stream = tokio::time::interval(summary_interval);
+
+        Box::pin(
+            stream! {
+              loop {
+                let mut output = Vec::new();
+                let done = tokio::select! {
+                    _ = flush_stream.next() => {
+                      me.flush_into(&mut output);
+                      false
+                    }
+                    maybe_event = input_rx.next() => {
+                      match maybe_event {
+                        None => {
+                          me.flush_into(&mut output);
+                          true
+                        }
+                        Some(event) => {
+                          output.push(me.transform_one(event));
+                          false
+                        }
+                      }
+                    }
+                };
+                yield stream::iter(output.into_iter());
+                if done { break }
+              }
+            }
+            .flatten(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(message: &str) -> Event {
+        Event::from(message)
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<LogPatternsConfig>();
+    }
+
+    #[test]
+    fn attaches_patterns() {
+        let mut patterns = LogPatterns::new(LogPatternsConfig::default()).unwrap();
+
+        let first = patterns.transform_one(log("GET /users/1 took 12ms"));
+        assert_eq!(first.as_log()["pattern_id"], Value::Integer(1));
+        assert_eq!(first.as_log()["pattern"], "GET /users/1 took 12ms".into());
+
+        let second = patterns.transform_one(log("GET /users/2 took 7ms"));
+        assert_eq!(second.as_log()["pattern_id"], Value::Integer(1));
+        assert_eq!(second.as_log()["pattern"], "GET <*> took <*>".into());
+
+        let other = patterns.transform_one(log("cache miss for key sessions"));
+        assert_eq!(other.as_log()["pattern_id"], Value::Integer(2));
+    }
+
+    #[test]
+    fn passes_events_without_field() {
+        let mut patterns = LogPatterns::new(LogPatternsConfig::default()).unwrap();
+
+        let event = patterns.transform_one(Event::new_empty_log());
+        assert!(event.as_log().get("pattern_id").is_none());
+    }
+
+    #[test]
+    fn summarizes_counts() {
+        let mut patterns = LogPatterns::new(LogPatternsConfig {
+            summary_interval_secs: Some(10),
+            ..Default::default()
+        })
+        .unwrap();
+
+        patterns.transform_one(log("worker 1 started"));
+        patterns.transform_one(log("worker 2 started"));
+        patterns.transform_one(log("shutting down now"));
+
+        let mut output = Vec::new();
+        patterns.flush_into(&mut output);
+        assert_eq!(output.len(), 2);
+
+        let metric = output[0].as_metric();
+        assert_eq!(metric.name(), "log_pattern_events_total");
+        assert_eq!(metric.tag_value("pattern_id"), Some("1".to_owned()));
+        assert_eq!(
+            metric.tag_value("pattern"),
+            Some("worker <*> started".to_owned())
+        );
+        assert_eq!(metric.data.value, MetricValue::Counter { value: 2.0 });
+
+        // Counts are reset once flushed.
+        let mut output = Vec::new();
+        patterns.flush_into(&mut output);
+        assert!(output.is_empty());
+    }
+}
@@ -1,14 +1,16 @@
 use crate::{
-    config::{log_schema, DataType, GenerateConfig, TransformConfig, TransformDescription},
+    config::{
+        log_schema, DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription,
+    },
     event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
     event::LogEvent,
     event::Value,
     internal_events::{
         LogToMetricFieldNotFound, LogToMetricParseFloatError, LogToMetricTemplateParseError,
-        LogToMetricTemplateRenderError,
+        LogToMetricTemplateRenderError, LogToMetricVrlError,
     },
     template::{Template, TemplateError},
-    transforms::{FunctionTransform, Transform},
+    transforms::{util::value_template::ValueTemplate, FunctionTransform, Transform},
     Event,
 };
 use indexmap::IndexMap;
@@ -26,43 +28,43 @@ pub struct LogToMetricConfig {
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct CounterConfig {
     field: String,
-    name: Option<String>,
-    namespace: Option<String>,
+    name: Option<ValueTemplate>,
+    namespace: Option<ValueTemplate>,
     #[serde(default = "default_increment_by_value")]
     increment_by_value: bool,
-    tags: Option<IndexMap<String, String>>,
+    tags: Option<IndexMap<String, ValueTemplate>>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct GaugeConfig {
     pub field: String,
-    pub name: Option<String>,
-    pub namespace: Option<String>,
-    pub tags: Option<IndexMap<String, String>>,
+    pub name: Option<ValueTemplate>,
+    pub namespace: Option<ValueTemplate>,
+    pub tags: Option<IndexMap<String, ValueTemplate>>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct SetConfig {
     field: String,
-    name: Option<String>,
-    namespace: Option<String>,
-    tags: Option<IndexMap<String, String>>,
+    name: Option<ValueTemplate>,
+    namespace: Option<ValueTemplate>,
+    tags: Option<IndexMap<String, ValueTemplate>>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct HistogramConfig {
     field: String,
-    name: Option<String>,
-    namespace: Option<String>,
-    tags: Option<IndexMap<String, String>>,
+    name: Option<ValueTemplate>,
+    namespace: Option<ValueTemplate>,
+    tags: Option<IndexMap<String, ValueTemplate>>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct SummaryConfig {
     field: String,
-    name: Option<String>,
-    namespace: Option<String>,
-    tags: Option<IndexMap<String, String>>,
+    name: Option<ValueTemplate>,
+    namespace: Option<ValueTemplate>,
+    tags: Option<IndexMap<String, ValueTemplate>>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -106,7 +108,7 @@ impl GenerateConfig for LogToMetricConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "log_to_metric")]
 impl TransformConfig for LogToMetricConfig {
-    async fn build(&self) -> crate::Result<Transform> {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
         Ok(Transform::function(LogToMetric::new(self.clone())))
     }
 
@@ -141,6 +143,9 @@ enum TransformError {
         field: String,
         error: ParseFloatError,
     },
+    VrlError {
+        error: String,
+    },
 }
 
 fn render_template(s: &str, event: &Event) -> Result<String, TransformError> {
@@ -150,8 +155,29 @@ fn render_template(s: &str, event: &Event) -> Result<String, TransformError> {
         .map_err(|missing_keys| TransformError::TemplateRenderError { missing_keys })
 }
 
+fn render_value(value: &ValueTemplate, event: &Event) -> Result<String, TransformError> {
+    match value {
+        ValueTemplate::Template(template) => render_template(template, event),
+        ValueTemplate::Vrl { vrl } => vrl
+            .render(event.as_log())
+            .map_err(|error| TransformError::VrlError { error }),
+    }
+}
+
+/// Renders the name of the metric, the name of its field by default.
+fn render_name(
+    name: &Option<ValueTemplate>,
+    field: &str,
+    event: &Event,
+) -> Result<String, TransformError> {
+    match name {
+        Some(name) => render_value(name, event),
+        None => render_template(field, event),
+    }
+}
+
 fn render_tags(
-    tags: &Option<IndexMap<String, String>>,
+    tags: &Option<IndexMap<String, ValueTemplate>>,
     event: &Event,
 ) -> Result<Option<BTreeMap<String, String>>, TransformError> {
     Ok(match tags {
@@ -159,13 +185,16 @@ fn render_tags(
         Some(tags) => {
             let mut map = BTreeMap::new();
             for (name, value) in tags {
-                match render_template(value, event) {
+                match render_value(value, event) {
                     Ok(tag) => {
                         map.insert(name.to_string(), tag);
                     }
                     Err(TransformError::TemplateRenderError { missing_keys }) => {
                         emit!(LogToMetricTemplateRenderError { missing_keys });
                     }
+                    Err(TransformError::VrlError { error }) => {
+                        emit!(LogToMetricVrlError { error });
+                    }
                     Err(other) => return Err(other),
                 }
             }
@@ -219,12 +248,11 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
                 1.0
             };
 
-            let name = counter.name.as_ref().unwrap_or(&counter.field);
-            let name = render_template(&name, &event)?;
+            let name = render_name(&counter.name, &counter.field, &event)?;
 
             let namespace = counter.namespace.as_ref();
             let namespace = namespace
-                .map(|namespace| render_template(namespace, &event))
+                .map(|namespace| render_value(namespace, &event))
                 .transpose()?;
 
             let tags = render_tags(&counter.tags, &event)?;
@@ -241,12 +269,11 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
         MetricConfig::Histogram(hist) => {
             let value = parse_field(&log, &hist.field)?;
 
-            let name = hist.name.as_ref().unwrap_or(&hist.field);
-            let name = render_template(&name, &event)?;
+            let name = render_name(&hist.name, &hist.field, &event)?;
 
             let namespace = hist.namespace.as_ref();
             let namespace = namespace
-                .map(|namespace| render_template(namespace, &event))
+                .map(|namespace| render_value(namespace, &event))
                 .transpose()?;
 
             let tags = render_tags(&hist.tags, &event)?;
@@ -266,12 +293,11 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
         MetricConfig::Summary(summary) => {
             let value = parse_field(&log, &summary.field)?;
 
-            let name = summary.name.as_ref().unwrap_or(&summary.field);
-            let name = render_template(&name, &event)?;
+            let name = render_name(&summary.name, &summary.field, &event)?;
 
             let namespace = summary.namespace.as_ref();
             let namespace = namespace
-                .map(|namespace| render_template(namespace, &event))
+                .map(|namespace| render_value(namespace, &event))
                 .transpose()?;
 
             let tags = render_tags(&summary.tags, &event)?;
@@ -291,12 +317,11 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
         MetricConfig::Gauge(gauge) => {
             let value = parse_field(&log, &gauge.field)?;
 
-            let name = gauge.name.as_ref().unwrap_or(&gauge.field);
-            let name = render_template(&name, &event)?;
+            let name = render_name(&gauge.name, &gauge.field, &event)?;
 
             let namespace = gauge.namespace.as_ref();
             let namespace = namespace
-                .map(|namespace| render_template(namespace, &event))
+                .map(|namespace| render_value(namespace, &event))
                 .transpose()?;
 
             let tags = render_tags(&gauge.tags, &event)?;
@@ -316,12 +341,11 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
                 })?;
             let value = value.to_string_lossy();
 
-            let name = set.name.as_ref().unwrap_or(&set.field);
-            let name = render_template(&name, &event)?;
+            let name = render_name(&set.name, &set.field, &event)?;
 
             let namespace = set.namespace.as_ref();
             let namespace = namespace
-                .map(|namespace| render_template(namespace, &event))
+                .map(|namespace| render_value(namespace, &event))
                 .transpose()?;
 
             let tags = render_tags(&set.tags, &event)?;
@@ -362,6 +386,7 @@ impl FunctionTransform for LogToMetric {
                 Err(TransformError::TemplateParseError(error)) => {
                     emit!(LogToMetricTemplateParseError { error })
                 }
+                Err(TransformError::VrlError { error }) => emit!(LogToMetricVrlError { error }),
             }
         }
     }
@@ -463,6 +488,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn count_http_requests_with_vrl_tags() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "counter"
+            field = "status"
+            name.vrl = 'downcase!(.method) + "_requests_total"'
+            namespace = "app"
+            tags.status_class.vrl = 'floor(to_int!(.status) / 100)'
+            tags.method = "{{method}}"
+            tags.missing_tag.vrl = 'to_int!(.unknown)'
+            "#,
+        );
+
+        let mut event = create_event("status", "404");
+        event.as_mut_log().insert("method", "POST");
+
+        let mut transform = LogToMetric::new(config);
+        let metric = transform.transform_one(event).unwrap();
+
+        assert_eq!(
+            metric.into_metric(),
+            Metric::new(
+                "post_requests_total",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            )
+            .with_namespace(Some("app"))
+            .with_tags(Some(
+                vec![
+                    ("status_class".to_owned(), "4".to_owned()),
+                    ("method".to_owned(), "POST".to_owned()),
+                ]
+                .into_iter()
+                .collect(),
+            ))
+            .with_timestamp(Some(ts()))
+        );
+    }
+
+    #[test]
+    fn vrl_compilation_error() {
+        let config = toml::from_str::<LogToMetricConfig>(
+            r#"
+            [[metrics]]
+            type = "counter"
+            field = "status"
+            tags.status_class.vrl = 'floor('
+            "#,
+        );
+
+        assert!(config.is_err());
+    }
+
     #[test]
     fn count_exceptions() {
         let config = parse_config(
@@ -77,6 +77,7 @@ struct HooksConfig {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 struct TimerConfig {
+    #[serde(deserialize_with = "crate::serde::duration_secs")]
     interval_seconds: u64,
     handler: String,
 }
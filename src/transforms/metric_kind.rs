@@ -0,0 +1,278 @@
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
+    event::{
+        metric::{MetricKind, MetricSeries, MetricValue},
+        Event,
+    },
+    internal_events::{MetricKindCounterReset, MetricSeriesExpired},
+    transforms::{FunctionTransform, Transform},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// The series are checked for expiration at most this often.
+const EXPIRATION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MetricKindConfig {
+    /// The kind the counters are converted to.
+    pub to: MetricKind,
+    /// Overrides the global `expire_metrics_secs` option.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::serde::optional_duration_secs"
+    )]
+    pub expire_metrics_secs: Option<u64>,
+}
+
+inventory::submit! {
+    TransformDescription::new::<MetricKindConfig>("metric_kind")
+}
+
+impl GenerateConfig for MetricKindConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            to: MetricKind::Incremental,
+            expire_metrics_secs: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "metric_kind")]
+impl TransformConfig for MetricKindConfig {
+    async fn build(&self, _name: &str, globals: &GlobalOptions) -> crate::Result<Transform> {
+        let expire_after = self
+            .expire_metrics_secs
+            .or(globals.expire_metrics_secs)
+            .map(Duration::from_secs);
+        Ok(Transform::function(ConvertKind::new(self.to, expire_after)))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "metric_kind"
+    }
+}
+
+/// The last value of a series, absolute in both directions.
+#[derive(Clone, Debug)]
+struct SeriesState {
+    value: f64,
+    updated: Instant,
+}
+
+/// Converts counters to `to`, keeping the last absolute value of each series.
+#[derive(Clone, Debug)]
+pub struct ConvertKind {
+    to: MetricKind,
+    expire_after: Option<Duration>,
+    last_check: Instant,
+    series: HashMap<MetricSeries, SeriesState>,
+}
+
+impl ConvertKind {
+    pub fn new(to: MetricKind, expire_after: Option<Duration>) -> Self {
+        Self {
+            to,
+            expire_after,
+            last_check: Instant::now(),
+            series: HashMap::new(),
+        }
+    }
+
+    /// Forgets the series not updated for `expire_after`, so that the next
+    /// value of an incremental series starts from zero again, and the next
+    /// value of an absolute one is only a reference.
+    fn expire(&mut self, now: Instant) {
+        let expire_after = match self.expire_after {
+            Some(expire_after) => expire_after,
+            None => return,
+        };
+        if now.duration_since(self.last_check) < EXPIRATION_CHECK_INTERVAL.min(expire_after) {
+            return;
+        }
+        self.last_check = now;
+
+        let count = self.series.len();
+        self.series
+            .retain(|_, state| now.duration_since(state.updated) < expire_after);
+        let count = count - self.series.len();
+        if count > 0 {
+            emit!(MetricSeriesExpired { count });
+        }
+    }
+
+    /// Updates the state of the series, returning the previous value.
+    fn update(&mut self, series: &MetricSeries, value: f64, now: Instant) -> Option<f64> {
+        let state = SeriesState {
+            value,
+            updated: now,
+        };
+        match self.series.get_mut(series) {
+            Some(previous) => Some(std::mem::replace(previous, state).value),
+            None => {
+                self.series.insert(series.clone(), state);
+                None
+            }
+        }
+    }
+}
+
+impl FunctionTransform for ConvertKind {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        let now = Instant::now();
+        self.expire(now);
+
+        let mut metric = event.into_metric();
+        let value = match metric.data.value {
+            MetricValue::Counter { value } if metric.data.kind != self.to => value,
+            // Only counters have a kind to convert, other metrics pass through.
+            _ => {
+                output.push(metric.into());
+                return;
+            }
+        };
+
+        let value = match self.to {
+            MetricKind::Incremental => match self.update(&metric.series, value, now) {
+                // The first value is only the reference of the next ones.
+                None => return,
+                // The counter decreased, so its source restarted and counted
+                // from zero again.
+                Some(previous) if value < previous => {
+                    emit!(MetricKindCounterReset {
+                        name: metric.name()
+                    });
+                    value
+                }
+                Some(previous) => value - previous,
+            },
+            MetricKind::Absolute => {
+                let previous = self.series.get(&metric.series).map(|state| state.value);
+                let total = previous.unwrap_or(0.0) + value;
+                self.update(&metric.series, total, now);
+                total
+            }
+        };
+
+        metric.data.kind = self.to;
+        metric.data.value = MetricValue::Counter { value };
+        output.push(metric.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Metric;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MetricKindConfig>();
+    }
+
+    fn counter(kind: MetricKind, value: f64) -> Event {
+        Metric::new("requests", kind, MetricValue::Counter { value }).into()
+    }
+
+    fn transform(convert: &mut ConvertKind, event: Event) -> Option<Metric> {
+        let mut output = Vec::new();
+        convert.transform(&mut output, event);
+        assert!(output.len() <= 1);
+        output.pop().map(Event::into_metric)
+    }
+
+    #[test]
+    fn absolute_to_incremental() {
+        let mut convert = ConvertKind::new(MetricKind::Incremental, None);
+
+        assert_eq!(
+            transform(&mut convert, counter(MetricKind::Absolute, 10.0)),
+            None
+        );
+        assert_eq!(
+            transform(&mut convert, counter(MetricKind::Absolute, 15.0)),
+            Some(counter(MetricKind::Incremental, 5.0).into_metric())
+        );
+        // The counter was reset.
+        assert_eq!(
+            transform(&mut convert, counter(MetricKind::Absolute, 3.0)),
+            Some(counter(MetricKind::Incremental, 3.0).into_metric())
+        );
+        assert_eq!(
+            transform(&mut convert, counter(MetricKind::Incremental, 2.0)),
+            Some(counter(MetricKind::Incremental, 2.0).into_metric())
+        );
+    }
+
+    #[test]
+    fn incremental_to_absolute() {
+        let mut convert = ConvertKind::new(MetricKind::Absolute, None);
+
+        assert_eq!(
+            transform(&mut convert, counter(MetricKind::Incremental, 10.0)),
+            Some(counter(MetricKind::Absolute, 10.0).into_metric())
+        );
+        assert_eq!(
+            transform(&mut convert, counter(MetricKind::Incremental, 5.0)),
+            Some(counter(MetricKind::Absolute, 15.0).into_metric())
+        );
+
+        let other = Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        )
+        .with_tags(Some(
+            vec![("host".to_owned(), "a".to_owned())]
+                .into_iter()
+                .collect(),
+        ));
+        assert_eq!(
+            transform(&mut convert, other.clone().into()),
+            Some(Metric {
+                data: other.data.into_absolute(),
+                ..other
+            })
+        );
+    }
+
+    #[test]
+    fn passes_other_metrics() {
+        let mut convert = ConvertKind::new(MetricKind::Absolute, None);
+        let gauge = Metric::new(
+            "temperature",
+            MetricKind::Incremental,
+            MetricValue::Gauge { value: 1.0 },
+        );
+
+        assert_eq!(transform(&mut convert, gauge.clone().into()), Some(gauge));
+    }
+
+    #[test]
+    fn expires_series() {
+        let mut convert = ConvertKind::new(MetricKind::Absolute, Some(Duration::from_millis(50)));
+        transform(&mut convert, counter(MetricKind::Incremental, 10.0));
+
+        std::thread::sleep(Duration::from_millis(100));
+        // The total starts over once the series expired.
+        assert_eq!(
+            transform(&mut convert, counter(MetricKind::Incremental, 5.0)),
+            Some(counter(MetricKind::Absolute, 5.0).into_metric())
+        );
+    }
+}
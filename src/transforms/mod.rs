@@ -9,18 +9,28 @@ pub mod util;
 pub mod add_fields;
 #[cfg(feature = "transforms-add_tags")]
 pub mod add_tags;
+#[cfg(feature = "transforms-aggregate")]
+pub mod aggregate;
+#[cfg(feature = "transforms-anomaly_detection")]
+pub mod anomaly_detection;
 #[cfg(feature = "transforms-ansi_stripper")]
 pub mod ansi_stripper;
+#[cfg(feature = "transforms-assemble")]
+pub mod assemble;
 #[cfg(feature = "transforms-aws_cloudwatch_logs_subscription_parser")]
 pub mod aws_cloudwatch_logs_subscription_parser;
 #[cfg(feature = "transforms-aws_ec2_metadata")]
 pub mod aws_ec2_metadata;
+#[cfg(feature = "transforms-byte_attribution")]
+pub mod byte_attribution;
 #[cfg(feature = "transforms-coercer")]
 pub mod coercer;
 #[cfg(feature = "transforms-concat")]
 pub mod concat;
 #[cfg(feature = "transforms-dedupe")]
 pub mod dedupe;
+#[cfg(feature = "transforms-explode")]
+pub mod explode;
 #[cfg(feature = "transforms-field_filter")]
 pub mod field_filter;
 #[cfg(feature = "transforms-filter")]
@@ -29,10 +39,14 @@ pub mod filter;
 pub mod geoip;
 #[cfg(feature = "transforms-grok_parser")]
 pub mod grok_parser;
+#[cfg(feature = "transforms-histogram")]
+pub mod histogram;
 #[cfg(feature = "transforms-json_parser")]
 pub mod json_parser;
 #[cfg(feature = "transforms-key_value_parser")]
 pub mod key_value_parser;
+#[cfg(feature = "transforms-log_patterns")]
+pub mod log_patterns;
 #[cfg(feature = "transforms-log_to_metric")]
 pub mod log_to_metric;
 #[cfg(feature = "transforms-logfmt_parser")]
@@ -41,6 +55,8 @@ pub mod logfmt_parser;
 pub mod lua;
 #[cfg(feature = "transforms-merge")]
 pub mod merge;
+#[cfg(feature = "transforms-metric_kind")]
+pub mod metric_kind;
 #[cfg(feature = "transforms-metric_to_log")]
 pub mod metric_to_log;
 #[cfg(feature = "transforms-reduce")]
@@ -59,12 +75,22 @@ pub mod rename_fields;
 pub mod route;
 #[cfg(feature = "transforms-sample")]
 pub mod sample;
+#[cfg(feature = "transforms-shard")]
+pub mod shard;
 #[cfg(feature = "transforms-split")]
 pub mod split;
+#[cfg(feature = "transforms-sql")]
+pub mod sql;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub mod tag_cardinality_limit;
+#[cfg(feature = "transforms-throttle")]
+pub mod throttle;
 #[cfg(feature = "transforms-tokenizer")]
 pub mod tokenizer;
+#[cfg(feature = "transforms-top_k")]
+pub mod top_k;
+#[cfg(feature = "transforms-trace_sampling")]
+pub mod trace_sampling;
 #[cfg(feature = "wasm")]
 pub mod wasm;
 
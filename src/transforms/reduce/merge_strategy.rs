@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "snake_case")]
 pub enum MergeStrategy {
     Discard,
+    Retain,
     Sum,
     Max,
     Min,
@@ -41,6 +42,31 @@ impl ReduceValueMerger for DiscardMerger {
 
 //------------------------------------------------------------------------------
 
+#[derive(Debug, Clone)]
+struct RetainMerger {
+    v: Value,
+}
+
+impl RetainMerger {
+    fn new(v: Value) -> Self {
+        Self { v }
+    }
+}
+
+impl ReduceValueMerger for RetainMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        self.v = v;
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String> {
+        v.insert(k, self.v);
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+
 #[derive(Debug, Clone)]
 struct ConcatMerger {
     v: BytesMut,
@@ -419,6 +445,7 @@ pub fn get_value_merger(v: Value, m: &MergeStrategy) -> Result<Box<dyn ReduceVal
         },
         MergeStrategy::Array => Ok(Box::new(ArrayMerger::new(v))),
         MergeStrategy::Discard => Ok(Box::new(DiscardMerger::new(v))),
+        MergeStrategy::Retain => Ok(Box::new(RetainMerger::new(v))),
     }
 }
 
@@ -431,6 +458,7 @@ mod test {
     #[test]
     fn initial_values() {
         assert!(get_value_merger("foo".into(), &MergeStrategy::Discard).is_ok());
+        assert!(get_value_merger("foo".into(), &MergeStrategy::Retain).is_ok());
         assert!(get_value_merger("foo".into(), &MergeStrategy::Sum).is_err());
         assert!(get_value_merger("foo".into(), &MergeStrategy::Max).is_err());
         assert!(get_value_merger("foo".into(), &MergeStrategy::Min).is_err());
@@ -438,6 +466,7 @@ mod test {
         assert!(get_value_merger("foo".into(), &MergeStrategy::Concat).is_ok());
 
         assert!(get_value_merger(42.into(), &MergeStrategy::Discard).is_ok());
+        assert!(get_value_merger(42.into(), &MergeStrategy::Retain).is_ok());
         assert!(get_value_merger(42.into(), &MergeStrategy::Sum).is_ok());
         assert!(get_value_merger(42.into(), &MergeStrategy::Min).is_ok());
         assert!(get_value_merger(42.into(), &MergeStrategy::Max).is_ok());
@@ -446,6 +475,7 @@ mod test {
         assert!(get_value_merger(42.into(), &MergeStrategy::ConcatNewline).is_err());
 
         assert!(get_value_merger(4.2.into(), &MergeStrategy::Discard).is_ok());
+        assert!(get_value_merger(4.2.into(), &MergeStrategy::Retain).is_ok());
         assert!(get_value_merger(4.2.into(), &MergeStrategy::Sum).is_ok());
         assert!(get_value_merger(4.2.into(), &MergeStrategy::Min).is_ok());
         assert!(get_value_merger(4.2.into(), &MergeStrategy::Max).is_ok());
@@ -454,6 +484,7 @@ mod test {
         assert!(get_value_merger(4.2.into(), &MergeStrategy::ConcatNewline).is_err());
 
         assert!(get_value_merger(true.into(), &MergeStrategy::Discard).is_ok());
+        assert!(get_value_merger(true.into(), &MergeStrategy::Retain).is_ok());
         assert!(get_value_merger(true.into(), &MergeStrategy::Sum).is_err());
         assert!(get_value_merger(true.into(), &MergeStrategy::Max).is_err());
         assert!(get_value_merger(true.into(), &MergeStrategy::Min).is_err());
@@ -462,6 +493,7 @@ mod test {
         assert!(get_value_merger(true.into(), &MergeStrategy::ConcatNewline).is_err());
 
         assert!(get_value_merger(Utc::now().into(), &MergeStrategy::Discard).is_ok());
+        assert!(get_value_merger(Utc::now().into(), &MergeStrategy::Retain).is_ok());
         assert!(get_value_merger(Utc::now().into(), &MergeStrategy::Sum).is_err());
         assert!(get_value_merger(Utc::now().into(), &MergeStrategy::Max).is_err());
         assert!(get_value_merger(Utc::now().into(), &MergeStrategy::Min).is_err());
@@ -470,6 +502,7 @@ mod test {
         assert!(get_value_merger(Utc::now().into(), &MergeStrategy::ConcatNewline).is_err());
 
         assert!(get_value_merger(json!([]).into(), &MergeStrategy::Discard).is_ok());
+        assert!(get_value_merger(json!([]).into(), &MergeStrategy::Retain).is_ok());
         assert!(get_value_merger(json!([]).into(), &MergeStrategy::Sum).is_err());
         assert!(get_value_merger(json!([]).into(), &MergeStrategy::Max).is_err());
         assert!(get_value_merger(json!([]).into(), &MergeStrategy::Min).is_err());
@@ -478,6 +511,7 @@ mod test {
         assert!(get_value_merger(json!([]).into(), &MergeStrategy::ConcatNewline).is_err());
 
         assert!(get_value_merger(json!({}).into(), &MergeStrategy::Discard).is_ok());
+        assert!(get_value_merger(json!({}).into(), &MergeStrategy::Retain).is_ok());
         assert!(get_value_merger(json!({}).into(), &MergeStrategy::Sum).is_err());
         assert!(get_value_merger(json!({}).into(), &MergeStrategy::Max).is_err());
         assert!(get_value_merger(json!({}).into(), &MergeStrategy::Min).is_err());
@@ -486,6 +520,7 @@ mod test {
         assert!(get_value_merger(json!({}).into(), &MergeStrategy::ConcatNewline).is_err());
 
         assert!(get_value_merger(json!(null).into(), &MergeStrategy::Discard).is_ok());
+        assert!(get_value_merger(json!(null).into(), &MergeStrategy::Retain).is_ok());
         assert!(get_value_merger(json!(null).into(), &MergeStrategy::Sum).is_err());
         assert!(get_value_merger(json!(null).into(), &MergeStrategy::Max).is_err());
         assert!(get_value_merger(json!(null).into(), &MergeStrategy::Min).is_err());
@@ -500,6 +535,14 @@ mod test {
             merge("foo".into(), "bar".into(), &MergeStrategy::Discard),
             Ok("foo".into())
         );
+        assert_eq!(
+            merge("foo".into(), "bar".into(), &MergeStrategy::Retain),
+            Ok("bar".into())
+        );
+        assert_eq!(
+            merge("foo".into(), 42.into(), &MergeStrategy::Retain),
+            Ok(42.into())
+        );
         assert_eq!(
             merge("foo".into(), "bar".into(), &MergeStrategy::Array),
             Ok(json!(["foo", "bar"]).into())
@@ -1,6 +1,6 @@
 use crate::{
     conditions::{AnyCondition, Condition},
-    config::{DataType, TransformConfig, TransformDescription},
+    config::{DataType, GlobalOptions, TransformConfig, TransformDescription},
     event::discriminant::Discriminant,
     event::{Event, LogEvent},
     internal_events::ReduceStaleEventFlushed,
@@ -52,7 +52,7 @@ impl_generate_config_from_default!(ReduceConfig);
 #[async_trait::async_trait]
 #[typetag::serde(name = "reduce")]
 impl TransformConfig for ReduceConfig {
-    async fn build(&self) -> crate::Result<Transform> {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
         Reduce::new(self).map(Transform::task)
     }
 
@@ -309,7 +309,7 @@ group_by = [ "request_id" ]
 "#,
         )
         .unwrap()
-        .build()
+        .build("default", &GlobalOptions::default())
         .await
         .unwrap();
         let reduce = reduce.into_task();
@@ -367,7 +367,7 @@ merge_strategies.baz = "max"
 "#,
         )
         .unwrap()
-        .build()
+        .build("default", &GlobalOptions::default())
         .await
         .unwrap();
         let reduce = reduce.into_task();
@@ -405,6 +405,53 @@ merge_strategies.baz = "max"
         assert_eq!(output_1.as_log()["baz"], 3.into(),);
     }
 
+    #[tokio::test]
+    async fn reduce_from_starts_when() {
+        let reduce = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "host" ]
+starts_when = 'match(.message, /^[^\s]/)'
+
+merge_strategies.message = "concat_newline"
+merge_strategies.severity = "retain"
+"#,
+        )
+        .unwrap()
+        .build("default", &GlobalOptions::default())
+        .await
+        .unwrap();
+        let reduce = reduce.into_task();
+
+        let lines = vec![
+            ("Exception in thread main", "info"),
+            ("  at foo.bar(Foo.java:42)", "info"),
+            ("  at foo.baz(Foo.java:12)", "error"),
+            ("Next message", "debug"),
+        ];
+        let inputs = lines
+            .into_iter()
+            .map(|(message, severity)| {
+                let mut event = Event::from(message);
+                event.as_mut_log().insert("host", "web-1");
+                event.as_mut_log().insert("severity", severity);
+                event
+            })
+            .collect::<Vec<_>>();
+        let in_stream = Box::pin(stream::iter(inputs));
+        let mut out_stream = reduce.transform(in_stream);
+
+        let output_1 = out_stream.next().await.unwrap();
+        assert_eq!(
+            output_1.as_log()["message"],
+            "Exception in thread main\n  at foo.bar(Foo.java:42)\n  at foo.baz(Foo.java:12)".into()
+        );
+        assert_eq!(output_1.as_log()["severity"], "error".into());
+
+        let output_2 = out_stream.next().await.unwrap();
+        assert_eq!(output_2.as_log()["message"], "Next message".into());
+        assert_eq!(output_2.as_log()["severity"], "debug".into());
+    }
+
     #[tokio::test]
     async fn missing_group_by() {
         let reduce = toml::from_str::<ReduceConfig>(
@@ -417,7 +464,7 @@ group_by = [ "request_id" ]
 "#,
         )
         .unwrap()
-        .build()
+        .build("default", &GlobalOptions::default())
         .await
         .unwrap();
         let reduce = reduce.into_task();
@@ -474,7 +521,7 @@ merge_strategies.bar = "concat"
 "#,
         )
         .unwrap()
-        .build()
+        .build("default", &GlobalOptions::default())
         .await
         .unwrap();
         let reduce = reduce.into_task();
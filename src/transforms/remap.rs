@@ -1,19 +1,42 @@
 use crate::{
-    config::{DataType, TransformConfig, TransformDescription},
+    config::{DataType, GlobalOptions, TransformConfig, TransformDescription},
+    encryption::{self, KeyProviderConfig},
     event::Event,
-    internal_events::RemapMappingError,
-    transforms::{FunctionTransform, Transform},
+    internal_events::{RemapMappingAbort, RemapMappingError},
+    transforms::{FunctionTransform, TaskTransform, Transform},
     Result,
 };
-use remap::{value, Program, Runtime, TypeConstraint, TypeDef};
+use async_stream::stream;
+use futures::{
+    channel::mpsc::{self, Receiver, Sender},
+    SinkExt, Stream, StreamExt,
+};
+use indexmap::IndexMap;
+use once_cell::sync::OnceCell;
+use remap::{value, Abort, Coverage, Program, Runtime, TypeConstraint, TypeDef};
 use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
 
 #[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
 #[serde(deny_unknown_fields, default)]
 #[derivative(Default)]
 pub struct RemapConfig {
     pub source: String,
-    pub drop_on_err: bool,
+    // Deprecated name
+    #[serde(alias = "drop_on_err")]
+    pub drop_on_error: bool,
+    #[derivative(Default(value = "true"))]
+    pub drop_on_abort: bool,
+    /// Sends the dropped events to the `dropped` output, instead of discarding
+    /// them.
+    pub reroute_dropped: bool,
+    /// Data keys for `encrypt_field` and `decrypt_field`, by key id.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub encryption_keys: BTreeMap<String, KeyProviderConfig>,
 }
 
 inventory::submit! {
@@ -22,11 +45,201 @@ inventory::submit! {
 
 impl_generate_config_from_default!(RemapConfig);
 
+impl RemapConfig {
+    /// The functions of the program, with the data keys of `encryption_keys`
+    /// and the enrichment tables of `globals`.
+    async fn functions(&self, globals: &GlobalOptions) -> Result<Vec<Box<dyn remap::Function>>> {
+        let keyring = encryption::build_keyring(&self.encryption_keys).await?;
+        Ok(remap_functions::with_enrichment_tables(
+            remap_functions::all_with_keyring(keyring),
+            globals.enrichment_tables.clone(),
+        ))
+    }
+
+    /// Builds the transform, sharing the coverage of its program while
+    /// collecting coverage.
+    async fn build_remap(&self, name: &str, globals: &GlobalOptions) -> Result<Remap> {
+        let functions = self.functions(globals).await?;
+        let mut remap = Remap::with_functions(self.clone(), &functions)?;
+
+        if let Some(programs) = COVERED_PROGRAMS.get() {
+            // Unit tests build a transform once per test, all of the builds
+            // share the coverage of the first one.
+            let mut programs = programs
+                .lock()
+                .expect("Couldn't acquire lock on covered programs");
+            let program = programs.entry(name.to_owned()).or_insert_with(|| {
+                let mut program = remap.program.clone();
+                program.collect_coverage(Coverage::default());
+                program
+            });
+            remap.program = program.clone();
+        }
+
+        Ok(remap)
+    }
+}
+
+/// The name of the output of the events dropped, once expanded.
+pub const DROPPED_OUTPUT: &str = "dropped";
+
+/// The number of dropped events buffered until the `dropped` output takes
+/// them, past which the transform waits for it.
+const DROPPED_BUFFER: usize = 100;
+
+/// The channel from a transform to its `dropped` output, shared by the two
+/// configurations of one expansion until both are built. An end taken again
+/// belongs to a new build, which gets a new channel.
+#[derive(Debug, Clone, Default)]
+struct DroppedChannel(Arc<Mutex<DroppedEnds>>);
+
+type DroppedEnds = (Option<Sender<Event>>, Option<Receiver<Event>>);
+
+impl DroppedChannel {
+    fn take<T>(&self, take: impl Fn(&mut DroppedEnds) -> Option<T>) -> T {
+        let mut ends = self
+            .0
+            .lock()
+            .expect("Couldn't acquire lock on dropped channel");
+        take(&mut ends).unwrap_or_else(|| {
+            let (tx, rx) = mpsc::channel(DROPPED_BUFFER);
+            *ends = (Some(tx), Some(rx));
+            take(&mut ends).expect("New channel has both ends")
+        })
+    }
+
+    fn sender(&self) -> Sender<Event> {
+        self.take(|ends| ends.0.take())
+    }
+
+    fn receiver(&self) -> Receiver<Event> {
+        self.take(|ends| ends.1.take())
+    }
+}
+
+/// The programs of the transforms built while collecting coverage, by
+/// transform name.
+static COVERED_PROGRAMS: OnceCell<Mutex<BTreeMap<String, Program>>> = OnceCell::new();
+
+/// Collects the coverage of the programs of all transforms built from now on.
+pub fn collect_coverage() {
+    let _ = COVERED_PROGRAMS.set(Mutex::new(BTreeMap::new()));
+}
+
+/// Returns the line coverage of the program of each transform built while
+/// collecting coverage, by transform name.
+pub fn line_coverage() -> BTreeMap<String, BTreeMap<usize, u64>> {
+    COVERED_PROGRAMS
+        .get()
+        .map(|programs| {
+            programs
+                .lock()
+                .expect("Couldn't acquire lock on covered programs")
+                .iter()
+                .filter_map(|(name, program)| Some((name.clone(), program.line_coverage()?)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "remap")]
 impl TransformConfig for RemapConfig {
-    async fn build(&self) -> Result<Transform> {
-        Remap::new(self.clone()).map(Transform::function)
+    async fn build(&self, name: &str, globals: &GlobalOptions) -> Result<Transform> {
+        Ok(Transform::function(self.build_remap(name, globals).await?))
+    }
+
+    /// With `reroute_dropped`, expands into the transform keeping its name,
+    /// and the `dropped` output receiving the events it drops.
+    fn expand(&mut self) -> Result<Option<IndexMap<String, Box<dyn TransformConfig>>>> {
+        if !self.reroute_dropped {
+            return Ok(None);
+        }
+
+        let dropped = DroppedChannel::default();
+        let mut map: IndexMap<String, Box<dyn TransformConfig>> = IndexMap::new();
+        map.insert(
+            String::new(),
+            Box::new(RemapReroutingConfig {
+                config: self.clone(),
+                dropped: dropped.clone(),
+            }),
+        );
+        map.insert(
+            DROPPED_OUTPUT.to_owned(),
+            Box::new(RemapDroppedConfig {
+                config: self.clone(),
+                dropped,
+            }),
+        );
+        Ok(Some(map))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "remap"
+    }
+}
+
+/// A remap transform sending the events it drops to its `dropped` output,
+/// once expanded.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct RemapReroutingConfig {
+    config: RemapConfig,
+    #[serde(skip)]
+    dropped: DroppedChannel,
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "remap_rerouting")]
+impl TransformConfig for RemapReroutingConfig {
+    async fn build(&self, name: &str, globals: &GlobalOptions) -> Result<Transform> {
+        let remap = self.config.build_remap(name, globals).await?;
+        Ok(Transform::task(
+            remap.dropped_output(name, self.dropped.sender()),
+        ))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "remap"
+    }
+}
+
+/// The `dropped` output of a remap transform. It holds the configuration of
+/// the transform so that both are rebuilt together on reload.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct RemapDroppedConfig {
+    config: RemapConfig,
+    #[serde(skip)]
+    dropped: DroppedChannel,
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "remap_dropped")]
+impl TransformConfig for RemapDroppedConfig {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> Result<Transform> {
+        Ok(Transform::task(RemapDropped {
+            dropped: self.dropped.receiver(),
+        }))
+    }
+
+    fn is_secondary_output(&self) -> bool {
+        true
     }
 
     fn input_type(&self) -> DataType {
@@ -42,14 +255,46 @@ impl TransformConfig for RemapConfig {
     }
 }
 
+/// Sends the events dropped by the transform it was expanded from, which has
+/// no inputs, and ends once that transform has stopped.
+struct RemapDropped {
+    dropped: Receiver<Event>,
+}
+
+impl TaskTransform for RemapDropped {
+    fn transform(
+        self: Box<Self>,
+        _task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        Box::pin(self.dropped)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Remap {
     program: Program,
-    drop_on_err: bool,
+    drop_on_error: bool,
+    drop_on_abort: bool,
+    /// The name of the transform, when it sends the events it drops to its
+    /// `dropped` output.
+    dropped_output: Option<String>,
 }
 
 impl Remap {
+    /// Builds the transform without any data keys for `encrypt_field` and
+    /// `decrypt_field`, whatever `encryption_keys` configures, nor any
+    /// enrichment tables.
     pub fn new(config: RemapConfig) -> crate::Result<Self> {
+        Self::with_functions(config, &remap_functions::all())
+    }
+
+    pub fn with_functions(
+        config: RemapConfig,
+        functions: &[Box<dyn remap::Function>],
+    ) -> crate::Result<Self> {
         let accepts = TypeConstraint {
             allow_any: true,
             type_def: TypeDef {
@@ -59,56 +304,157 @@ impl Remap {
             },
         };
 
-        let (program, _) = Program::new(
-            config.source.clone(),
-            &remap_functions::all(),
-            Some(accepts),
-            false,
-        )
-        .map_err(|diagnostics| {
-            remap::Formatter::new(&config.source, diagnostics)
-                .colored()
-                .to_string()
-        })?;
+        let (program, _) = Program::new(config.source.clone(), functions, Some(accepts), false)
+            .map_err(|diagnostics| {
+                remap::Formatter::new(&config.source, diagnostics)
+                    .colored()
+                    .to_string()
+            })?;
 
         Ok(Remap {
             program,
-            drop_on_err: config.drop_on_err,
+            drop_on_error: config.drop_on_error,
+            drop_on_abort: config.drop_on_abort,
+            dropped_output: None,
         })
     }
+
+    /// Sends the events the transform drops to `dropped`, as they were before
+    /// the program ran, with the reason they were dropped under
+    /// `metadata.dropped`.
+    pub fn dropped_output(mut self, component: &str, dropped: Sender<Event>) -> RemapRerouting {
+        self.dropped_output = Some(component.to_owned());
+        RemapRerouting {
+            remap: self,
+            dropped,
+        }
+    }
+
+    fn annotate_dropped(event: &mut Event, component: &str, abort: &Abort) {
+        let component = component.to_owned();
+        let reason = match abort {
+            Abort::Error(_) => "error",
+            Abort::Explicit => "abort",
+        };
+        let fields = vec![
+            ("metadata.dropped.reason", reason.to_owned()),
+            ("metadata.dropped.message", abort.to_string()),
+            ("metadata.dropped.component", component),
+        ];
+
+        let log = match event {
+            Event::Log(log) => log,
+            Event::Trace(trace) => trace.as_mut_log(),
+            Event::Metric(metric) => {
+                for (name, value) in fields {
+                    metric.set_tag_value(name.to_owned(), value);
+                }
+                return;
+            }
+        };
+        for (name, value) in fields {
+            log.insert(name, value);
+        }
+    }
 }
 
-impl FunctionTransform for Remap {
-    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+impl Remap {
+    /// Runs the program on the event. Returns the event to send on, or the
+    /// event to send to the `dropped` output, if any, once it's dropped.
+    fn run(&mut self, mut event: Event) -> std::result::Result<Event, Option<Event>> {
+        let original = self.dropped_output.as_ref().map(|_| event.clone());
+
         let mut runtime = Runtime::default();
         let result = match event {
             Event::Log(ref mut event) => runtime.run(event, &self.program),
             Event::Metric(ref mut event) => runtime.run(event, &self.program),
+            Event::Trace(ref mut event) => runtime.run(event.as_mut_log(), &self.program),
         };
 
-        if let Err(error) = result {
-            emit!(RemapMappingError {
-                error: error.to_string(),
-                event_dropped: self.drop_on_err,
-            });
+        let abort = match result {
+            Ok(_) => return Ok(event),
+            Err(abort) => abort,
+        };
+        let dropped = match abort {
+            Abort::Error(_) => self.drop_on_error,
+            Abort::Explicit => self.drop_on_abort,
+        };
 
-            if self.drop_on_err {
-                return;
-            }
+        match abort {
+            Abort::Error(ref error) => emit!(RemapMappingError {
+                error: error.clone(),
+                event_dropped: dropped,
+            }),
+            Abort::Explicit => emit!(RemapMappingAbort {
+                event_dropped: dropped,
+            }),
+        }
+
+        if !dropped {
+            return Ok(event);
+        }
+        Err(original.map(|mut original| {
+            let component = self.dropped_output.as_deref().unwrap_or_default();
+            Self::annotate_dropped(&mut original, component, &abort);
+            original
+        }))
+    }
+}
+
+impl FunctionTransform for Remap {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        if let Ok(event) = self.run(event) {
+            output.push(event);
         }
+    }
+}
+
+/// A remap transform sending the events it drops to its `dropped` output,
+/// waiting for the output once its buffer is full.
+pub struct RemapRerouting {
+    remap: Remap,
+    dropped: Sender<Event>,
+}
 
-        output.push(event);
+impl TaskTransform for RemapRerouting {
+    fn transform(
+        self: Box<Self>,
+        mut task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let Self {
+            mut remap,
+            mut dropped,
+        } = *self;
+        Box::pin(stream! {
+          while let Some(event) = task.next().await {
+            match remap.run(event) {
+              Ok(event) => yield event,
+              Err(Some(event)) => {
+                // The output only goes away once the transform has stopped.
+                let _ = dropped.send(event).await;
+              }
+              Err(None) => (),
+            }
+          }
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::event::{
-        metric::{MetricKind, MetricValue},
-        Metric,
+    use crate::{
+        buffers::Priority,
+        event::{
+            metric::{MetricKind, MetricValue},
+            Metric,
+        },
     };
-    use std::collections::BTreeMap;
+    use futures::stream;
+    use std::{collections::BTreeMap, time::Duration};
 
     #[test]
     fn generate_config() {
@@ -133,7 +479,8 @@ mod tests {
   .copy = .copy_from
 "#
             .to_string(),
-            drop_on_err: true,
+            drop_on_error: true,
+            ..Default::default()
         };
         let mut tform = Remap::new(conf).unwrap();
 
@@ -159,7 +506,8 @@ mod tests {
                        .namespace = "zerk"
                        .kind = "incremental""#
                 .to_string(),
-            drop_on_err: true,
+            drop_on_error: true,
+            ..Default::default()
         };
         let mut tform = Remap::new(conf).unwrap();
 
@@ -181,4 +529,211 @@ mod tests {
             )
         );
     }
+
+    fn remap(source: &str, drop_on_error: bool, drop_on_abort: bool) -> Remap {
+        Remap::new(RemapConfig {
+            source: source.to_owned(),
+            drop_on_error,
+            drop_on_abort,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn check_remap_sets_priority() {
+        let mut tform = remap(
+            r#"
+                set_metadata_field("priority", "low")
+                .priority = get_metadata_field("priority")
+            "#,
+            true,
+            true,
+        );
+
+        let result = tform.transform_one(Event::from("lower me")).unwrap();
+        assert_eq!(Priority::of(&result), Priority::Low);
+        assert_eq!(get_field_string(&result, "priority"), "low");
+
+        let mut tform = remap(r#"set_metadata_field("priority", "urgent")"#, true, true);
+        assert!(tform.transform_one(Event::from("lower me")).is_none());
+    }
+
+    const FAILING_SOURCE: &str = r#"
+        .foo = "bar"
+        if .message == "abort" { abort }
+        .parsed = parse_json!(.message)
+    "#;
+
+    #[test]
+    fn check_remap_drops() {
+        let mut tform = remap(FAILING_SOURCE, true, true);
+        assert!(tform.transform_one(Event::from("abort")).is_none());
+        assert!(tform.transform_one(Event::from("not json")).is_none());
+
+        let mut tform = remap(FAILING_SOURCE, false, false);
+        for message in &["abort", "not json"] {
+            let result = tform.transform_one(Event::from(*message)).unwrap();
+            assert_eq!(get_field_string(&result, "foo"), "bar");
+        }
+    }
+
+    #[tokio::test]
+    async fn check_remap_dropped_output() {
+        let (tx, rx) = mpsc::channel(10);
+        let tform = remap(FAILING_SOURCE, true, false).dropped_output("my_remap", tx);
+
+        let events = vec![r#"{"a": 1}"#, "abort", "not json"]
+            .into_iter()
+            .map(Event::from)
+            .collect::<Vec<_>>();
+        let output = Box::new(tform)
+            .transform(Box::pin(stream::iter(events)))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(output.len(), 2);
+        assert_eq!(get_field_string(&output[0], "foo"), "bar");
+        assert_eq!(get_field_string(&output[1], "foo"), "bar");
+
+        let dropped = rx.collect::<Vec<_>>().await;
+        assert_eq!(dropped.len(), 1);
+        let result = dropped.into_iter().next().unwrap();
+        assert_eq!(get_field_string(&result, "message"), "not json");
+        assert!(result.as_log().get("foo").is_none());
+        assert_eq!(
+            get_field_string(&result, "metadata.dropped.reason"),
+            "error"
+        );
+        assert_eq!(
+            get_field_string(&result, "metadata.dropped.component"),
+            "my_remap"
+        );
+        assert!(!get_field_string(&result, "metadata.dropped.message").is_empty());
+    }
+
+    #[tokio::test]
+    async fn check_remap_dropped_output_is_bounded() {
+        let (tx, rx) = mpsc::channel(0);
+        let tform = remap(FAILING_SOURCE, true, false).dropped_output("my_remap", tx);
+
+        let events = vec!["not json", "not json", "not json", r#"{"a": 1}"#]
+            .into_iter()
+            .map(Event::from)
+            .collect::<Vec<_>>();
+        let mut output = Box::new(tform).transform(Box::pin(stream::iter(events)));
+        // The first dropped event fills the buffer, the second waits for it.
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), output.next())
+                .await
+                .is_err()
+        );
+
+        let output = tokio::spawn(output.collect::<Vec<_>>());
+        assert_eq!(rx.take(3).collect::<Vec<_>>().await.len(), 3);
+        assert_eq!(output.await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn check_remap_expands_dropped_output() {
+        let mut config = RemapConfig {
+            source: FAILING_SOURCE.to_owned(),
+            ..Default::default()
+        };
+        assert!(config.expand().unwrap().is_none());
+
+        config.reroute_dropped = true;
+        let expanded = config.expand().unwrap().unwrap();
+        assert_eq!(
+            expanded.keys().collect::<Vec<_>>(),
+            vec!["", DROPPED_OUTPUT]
+        );
+        assert!(!expanded[""].is_secondary_output());
+        assert!(expanded[DROPPED_OUTPUT].is_secondary_output());
+    }
+
+    #[tokio::test]
+    async fn check_remap_builds_dropped_output() {
+        let mut config = RemapConfig {
+            source: FAILING_SOURCE.to_owned(),
+            drop_on_error: true,
+            reroute_dropped: true,
+            ..Default::default()
+        };
+        let globals = GlobalOptions::default();
+        let expanded = config.expand().unwrap().unwrap();
+        let tform = expanded[""]
+            .build("check_remap_once", &globals)
+            .await
+            .unwrap()
+            .into_task();
+        let dropped = expanded[DROPPED_OUTPUT]
+            .build("check_remap_once.dropped", &globals)
+            .await
+            .unwrap()
+            .into_task();
+
+        let events = vec![r#"{"a": 1}"#, "not json"]
+            .into_iter()
+            .map(Event::from)
+            .collect::<Vec<_>>();
+        let output = tform
+            .transform(Box::pin(stream::iter(events)))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(output.len(), 1);
+
+        let events = dropped
+            .transform(Box::pin(stream::empty()))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(get_field_string(&events[0], "message"), "not json");
+        assert_eq!(
+            get_field_string(&events[0], "metadata.dropped.component"),
+            "check_remap_once"
+        );
+    }
+
+    #[test]
+    fn check_remap_enrichment_tables() {
+        let table = remap_functions::Table::new(
+            vec!["code".to_owned(), "message".to_owned()],
+            vec![vec!["404".to_owned(), "Not Found".to_owned()]],
+        )
+        .unwrap();
+        let mut tables = BTreeMap::new();
+        tables.insert("codes".to_owned(), table);
+        let functions = remap_functions::with_enrichment_tables(
+            remap_functions::all(),
+            remap_functions::EnrichmentTables::new(tables),
+        );
+
+        let config = RemapConfig {
+            source: r#"
+                record = get_enrichment_table_record!("codes", { "code": .code })
+                .status = record.message
+            "#
+            .to_owned(),
+            ..Default::default()
+        };
+        let mut tform = Remap::with_functions(config, &functions).unwrap();
+
+        let mut event = Event::from("request");
+        event.as_mut_log().insert("code", 404);
+        let result = tform.transform_one(event).unwrap();
+        assert_eq!(get_field_string(&result, "status"), "Not Found");
+    }
+
+    #[test]
+    fn check_remap_deprecated_drop_on_err() {
+        let config = toml::from_str::<RemapConfig>(
+            r#"
+            source = ".foo = 1"
+            drop_on_err = true
+        "#,
+        )
+        .unwrap();
+        assert!(config.drop_on_error);
+        assert!(config.drop_on_abort);
+    }
 }
@@ -1,6 +1,6 @@
 use crate::{
     conditions::{AnyCondition, Condition},
-    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
     event::Event,
     internal_events::RouteEventDiscarded,
     transforms::{FunctionTransform, Transform},
@@ -20,7 +20,7 @@ pub struct LaneConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "lane")]
 impl TransformConfig for LaneConfig {
-    async fn build(&self) -> crate::Result<Transform> {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
         Ok(Transform::function(Lane::new(self.condition.build()?)))
     }
 
@@ -62,6 +62,69 @@ impl FunctionTransform for Lane {
 
 //------------------------------------------------------------------------------
 
+/// The name of the lane holding the events matching none of the routes.
+pub const UNMATCHED_ROUTE: &str = "_unmatched";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct UnmatchedLaneConfig {
+    conditions: Vec<AnyCondition>,
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "unmatched_lane")]
+impl TransformConfig for UnmatchedLaneConfig {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
+        let conditions = self
+            .conditions
+            .iter()
+            .map(AnyCondition::build)
+            .collect::<crate::Result<_>>()?;
+        Ok(Transform::function(UnmatchedLane::new(conditions)))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "unmatched_lane"
+    }
+}
+
+#[derive(Clone, Derivative)]
+#[derivative(Debug)]
+pub struct UnmatchedLane {
+    #[derivative(Debug = "ignore")]
+    conditions: Vec<Box<dyn Condition>>,
+}
+
+impl UnmatchedLane {
+    pub fn new(conditions: Vec<Box<dyn Condition>>) -> Self {
+        Self { conditions }
+    }
+}
+
+impl FunctionTransform for UnmatchedLane {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        if self
+            .conditions
+            .iter()
+            .any(|condition| condition.check(&event))
+        {
+            emit!(RouteEventDiscarded);
+        } else {
+            output.push(event);
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct RouteConfig {
@@ -90,22 +153,30 @@ impl GenerateConfig for RouteConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "route")]
 impl TransformConfig for RouteConfig {
-    async fn build(&self) -> crate::Result<Transform> {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
         Err("this transform must be expanded".into())
     }
 
     fn expand(&mut self) -> crate::Result<Option<IndexMap<String, Box<dyn TransformConfig>>>> {
+        if self.route.is_empty() {
+            return Err("must specify at least one lane".into());
+        }
+        if self.route.contains_key(UNMATCHED_ROUTE) {
+            return Err(format!("the lane name `{}` is reserved", UNMATCHED_ROUTE).into());
+        }
+
         let mut map: IndexMap<String, Box<dyn TransformConfig>> = IndexMap::new();
+        let conditions = self.route.values().cloned().collect();
 
         while let Some((k, v)) = self.route.pop() {
             map.insert(k.clone(), Box::new(LaneConfig { condition: v }));
         }
+        map.insert(
+            UNMATCHED_ROUTE.to_owned(),
+            Box::new(UnmatchedLaneConfig { conditions }),
+        );
 
-        if !map.is_empty() {
-            Ok(Some(map))
-        } else {
-            Err("must specify at least one lane".into())
-        }
+        Ok(Some(map))
     }
 
     fn input_type(&self) -> DataType {
@@ -128,8 +199,8 @@ struct RouteCompatConfig(RouteConfig);
 #[async_trait::async_trait]
 #[typetag::serde(name = "swimlanes")]
 impl TransformConfig for RouteCompatConfig {
-    async fn build(&self) -> crate::Result<Transform> {
-        self.0.build().await
+    async fn build(&self, name: &str, globals: &GlobalOptions) -> crate::Result<Transform> {
+        self.0.build(name, globals).await
     }
 
     fn expand(&mut self) -> crate::Result<Option<IndexMap<String, Box<dyn TransformConfig>>>> {
@@ -153,11 +224,12 @@ impl TransformConfig for RouteCompatConfig {
 
 #[cfg(test)]
 mod test {
-    use super::RouteConfig;
+    use super::*;
+    use crate::event::LogEvent;
 
     #[test]
     fn generate_config() {
-        crate::test_util::test_generate_config::<super::RouteConfig>();
+        crate::test_util::test_generate_config::<RouteConfig>();
     }
 
     #[test]
@@ -170,4 +242,46 @@ mod test {
         )
         .unwrap();
     }
+
+    #[tokio::test]
+    async fn unmatched_lane() {
+        let mut config = toml::from_str::<RouteConfig>(
+            r#"
+            route.first = '.message == "first"'
+            route.second = '.message == "second"'
+        "#,
+        )
+        .unwrap();
+        let lanes = config.expand().unwrap().unwrap();
+        assert_eq!(
+            lanes.keys().collect::<Vec<_>>(),
+            vec!["second", "first", UNMATCHED_ROUTE]
+        );
+
+        let mut unmatched = lanes[UNMATCHED_ROUTE]
+            .build("route._unmatched", &GlobalOptions::default())
+            .await
+            .unwrap()
+            .into_function();
+        let mut output = Vec::new();
+        for message in &["first", "second", "third"] {
+            let log = vec![("message", *message)]
+                .into_iter()
+                .collect::<LogEvent>();
+            unmatched.transform(&mut output, log.into());
+        }
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["message"], "third".into());
+    }
+
+    #[test]
+    fn unmatched_name_reserved() {
+        let mut config = toml::from_str::<RouteConfig>(
+            r#"
+            route._unmatched = '.message == "first"'
+        "#,
+        )
+        .unwrap();
+        assert!(config.expand().is_err());
+    }
 }
@@ -1,6 +1,6 @@
 use crate::{
     conditions::{CheckFieldsConfig, Condition, ConditionConfig},
-    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
     event::Event,
     internal_events::SampleEventDiscarded,
     transforms::{FunctionTransform, Transform},
@@ -37,7 +37,7 @@ impl GenerateConfig for SampleConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "sample")]
 impl TransformConfig for SampleConfig {
-    async fn build(&self) -> crate::Result<Transform> {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
         Ok(Transform::function(Sample::new(
             self.rate,
             self.key_field.clone(),
@@ -49,11 +49,11 @@ impl TransformConfig for SampleConfig {
     }
 
     fn input_type(&self) -> DataType {
-        DataType::Log
+        DataType::LogOrTrace
     }
 
     fn output_type(&self) -> DataType {
-        DataType::Log
+        DataType::LogOrTrace
     }
 
     fn transform_type(&self) -> &'static str {
@@ -68,8 +68,8 @@ struct SampleCompatConfig(SampleConfig);
 #[async_trait::async_trait]
 #[typetag::serde(name = "sampler")]
 impl TransformConfig for SampleCompatConfig {
-    async fn build(&self) -> crate::Result<Transform> {
-        self.0.build().await
+    async fn build(&self, name: &str, globals: &GlobalOptions) -> crate::Result<Transform> {
+        self.0.build(name, globals).await
     }
 
     fn input_type(&self) -> DataType {
@@ -113,11 +113,25 @@ impl FunctionTransform for Sample {
             }
         }
 
-        let value = self
-            .key_field
-            .as_ref()
-            .and_then(|key_field| event.as_log().get(key_field))
-            .map(|v| v.to_string_lossy());
+        let value = match &event {
+            Event::Log(log) => self
+                .key_field
+                .as_ref()
+                .and_then(|key_field| log.get(key_field))
+                .map(|v| v.to_string_lossy()),
+            // Metrics aren't sampled. They can still get here as `vector test`
+            // inputs, which aren't filtered by type.
+            Event::Metric(_) => {
+                output.push(event);
+                return;
+            }
+            // Spans are sampled by trace id unless told otherwise, so that
+            // whole traces are either kept or dropped.
+            Event::Trace(trace) => match &self.key_field {
+                Some(key_field) => trace.get(key_field).map(|v| v.to_string_lossy()),
+                None => trace.trace_id(),
+            },
+        };
 
         let num = if let Some(value) = value {
             seahash::hash(value.as_bytes())
@@ -128,9 +142,15 @@ impl FunctionTransform for Sample {
         self.count = (self.count + 1) % self.rate;
 
         if num % self.rate == 0 {
-            event
-                .as_mut_log()
-                .insert("sample_rate", self.rate.to_string());
+            match &mut event {
+                Event::Log(log) => {
+                    log.insert("sample_rate", self.rate.to_string());
+                }
+                Event::Trace(trace) => {
+                    trace.insert("sample_rate", self.rate.to_string());
+                }
+                Event::Metric(_) => (),
+            }
             output.push(event);
         } else {
             emit!(SampleEventDiscarded);
@@ -142,7 +162,12 @@ impl FunctionTransform for Sample {
 mod tests {
     use super::*;
     use crate::{
-        conditions::check_fields::CheckFieldsPredicateArg, config::log_schema, event::Event,
+        conditions::check_fields::CheckFieldsPredicateArg,
+        config::log_schema,
+        event::{
+            metric::{Metric, MetricKind, MetricValue},
+            Event, TraceEvent,
+        },
         test_util::random_lines,
     };
     use approx::assert_relative_eq;
@@ -290,6 +315,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn samples_whole_traces() {
+        let mut sampler = Sample::new(4, None, None);
+        for trace in 0..100 {
+            let trace_id = format!("trace-{}", trace);
+            let kept = (0..5)
+                .map(|span| TraceEvent::new(trace_id.clone(), span.to_string()))
+                .filter_map(|span| sampler.transform_one(span.into()))
+                .count();
+            assert!(kept == 0 || kept == 5);
+        }
+    }
+
+    #[test]
+    fn passes_metrics_through() {
+        let mut sampler = Sample::new(2, None, None);
+        for _ in 0..10 {
+            let metric = Metric::new(
+                "requests",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            );
+            let output = sampler.transform_one(metric.clone().into()).unwrap();
+            assert_eq!(output, Event::Metric(metric));
+        }
+    }
+
     fn random_events(n: usize) -> Vec<Event> {
         random_lines(10).take(n).map(Event::from).collect()
     }
@@ -0,0 +1,271 @@
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
+    event::Event,
+    internal_events::ShardTemplateRenderingError,
+    template::Template,
+    transforms::{FunctionTransform, Transform},
+};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ShardConfig {
+    pub key: Template,
+    pub shards: u32,
+    #[serde(default)]
+    pub algorithm: ShardAlgorithm,
+    #[serde(default = "default_field")]
+    pub field: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShardAlgorithm {
+    /// Jump consistent hashing, fast and evenly spread, but only moving the
+    /// fewest keys when shards are added or removed at the end.
+    Jump,
+    /// Rendezvous (highest random weight) hashing, costing one hash per shard.
+    Rendezvous,
+}
+
+impl Default for ShardAlgorithm {
+    fn default() -> Self {
+        ShardAlgorithm::Jump
+    }
+}
+
+fn default_field() -> String {
+    "shard".to_owned()
+}
+
+inventory::submit! {
+    TransformDescription::new::<ShardConfig>("shard")
+}
+
+impl GenerateConfig for ShardConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            key: Template::try_from("{{ host }}").unwrap(),
+            shards: 4,
+            algorithm: ShardAlgorithm::default(),
+            field: default_field(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "shard")]
+impl TransformConfig for ShardConfig {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
+        if self.shards == 0 {
+            return Err("`shards` must be greater than 0".into());
+        }
+
+        Ok(Transform::function(Shard {
+            key: self.key.clone(),
+            shards: self.shards,
+            algorithm: self.algorithm,
+            field: self.field.clone(),
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "shard"
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Shard {
+    key: Template,
+    shards: u32,
+    algorithm: ShardAlgorithm,
+    field: String,
+}
+
+impl Shard {
+    fn shard(&self, key: &[u8]) -> u32 {
+        match self.algorithm {
+            ShardAlgorithm::Jump => jump_hash(seahash::hash(key), self.shards),
+            ShardAlgorithm::Rendezvous => rendezvous_hash(key, self.shards),
+        }
+    }
+}
+
+impl FunctionTransform for Shard {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let key = match self.key.render_string(&event) {
+            Ok(key) => key,
+            Err(fields) => {
+                emit!(ShardTemplateRenderingError { fields });
+                output.push(event);
+                return;
+            }
+        };
+
+        let shard = self.shard(key.as_bytes());
+        match &mut event {
+            Event::Log(log) => {
+                log.insert(&self.field, shard as i64);
+            }
+            Event::Metric(metric) => {
+                metric.set_tag_value(self.field.clone(), shard.to_string());
+            }
+            Event::Trace(trace) => {
+                trace.insert(&self.field, shard as i64);
+            }
+        }
+        output.push(event);
+    }
+}
+
+/// The jump consistent hash of Lamping and Veach, mapping `key` to one of
+/// `buckets` buckets. Only integer and `f64` arithmetic is used, so the
+/// buckets are the same on all the architectures.
+fn jump_hash(mut key: u64, buckets: u32) -> u32 {
+    let mut bucket = -1i64;
+    let mut next = 0i64;
+    while next < buckets as i64 {
+        bucket = next;
+        key = key.wrapping_mul(2_862_933_555_777_941_757).wrapping_add(1);
+        next = ((bucket + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+    bucket as u32
+}
+
+/// The shard with the highest hash of `key` followed by the little-endian
+/// shard number, the lowest of them on ties.
+fn rendezvous_hash(key: &[u8], shards: u32) -> u32 {
+    let mut buffer = Vec::with_capacity(key.len() + 4);
+    let mut best = (0, 0);
+    for shard in 0..shards {
+        buffer.clear();
+        buffer.extend_from_slice(key);
+        buffer.extend_from_slice(&shard.to_le_bytes());
+        let weight = seahash::hash(&buffer);
+        if shard == 0 || weight > best.1 {
+            best = (shard, weight);
+        }
+    }
+    best.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{
+        metric::{Metric, MetricKind, MetricValue},
+        LogEvent, Value,
+    };
+
+    fn transform(config: &str) -> Shard {
+        let config: ShardConfig = toml::from_str(config).unwrap();
+        Shard {
+            key: config.key,
+            shards: config.shards,
+            algorithm: config.algorithm,
+            field: config.field,
+        }
+    }
+
+    fn log(host: &str) -> Event {
+        let mut log = LogEvent::default();
+        log.insert("host", host);
+        Event::Log(log)
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ShardConfig>();
+    }
+
+    #[test]
+    fn jump_hash_moves_keys_to_new_buckets_only() {
+        for key in 0..1000u64 {
+            let key = seahash::hash(&key.to_le_bytes());
+            assert_eq!(jump_hash(key, 1), 0);
+            for buckets in 1..20 {
+                let before = jump_hash(key, buckets);
+                let after = jump_hash(key, buckets + 1);
+                assert!(before < buckets);
+                assert!(after == before || after == buckets);
+            }
+        }
+    }
+
+    #[test]
+    fn rendezvous_hash_moves_keys_to_new_shards_only() {
+        for key in 0..1000u64 {
+            let key = key.to_le_bytes();
+            assert_eq!(rendezvous_hash(&key, 1), 0);
+            for shards in 1..20 {
+                let before = rendezvous_hash(&key, shards);
+                let after = rendezvous_hash(&key, shards + 1);
+                assert!(before < shards);
+                assert!(after == before || after == shards);
+            }
+        }
+    }
+
+    #[test]
+    fn spreads_keys_evenly() {
+        for algorithm in &["jump", "rendezvous"] {
+            let shard = transform(&format!(
+                "key = \"{{{{ host }}}}\"\nshards = 4\nalgorithm = \"{}\"",
+                algorithm
+            ));
+            let mut counts = [0; 4];
+            for key in 0..4000 {
+                counts[shard.shard(format!("host-{}", key).as_bytes()) as usize] += 1;
+            }
+            assert!(counts.iter().all(|&count| count > 800 && count < 1200));
+        }
+    }
+
+    #[test]
+    fn shards_logs_by_key() {
+        let mut shard = transform("key = \"{{ host }}\"\nshards = 8\nfield = \"partition\"");
+
+        let first = shard.transform_one(log("foo")).unwrap().into_log();
+        let second = shard.transform_one(log("foo")).unwrap().into_log();
+        let expected = shard.shard(b"foo") as i64;
+        assert_eq!(first.get("partition"), Some(&Value::Integer(expected)));
+        assert_eq!(second.get("partition"), Some(&Value::Integer(expected)));
+    }
+
+    #[test]
+    fn shards_metrics_by_key() {
+        let mut shard = transform("key = \"{{ name }}\"\nshards = 8");
+
+        let metric = Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        );
+        let metric = shard
+            .transform_one(Event::Metric(metric))
+            .unwrap()
+            .into_metric();
+        assert_eq!(
+            metric.tag_value("shard"),
+            Some(shard.shard(b"requests").to_string())
+        );
+    }
+
+    #[test]
+    fn passes_events_without_key_through() {
+        let mut shard = transform("key = \"{{ region }}\"\nshards = 8");
+
+        let log = shard.transform_one(log("foo")).unwrap().into_log();
+        assert_eq!(log.get("shard"), None);
+    }
+}
@@ -0,0 +1,588 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription,
+    },
+    event::{discriminant::Discriminant, Event, LogEvent, Value},
+    internal_events::{SqlEventDiscarded, SqlWindowFlushed},
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::{stream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    pin::Pin,
+    time::Duration,
+};
+
+mod parser;
+
+use parser::{AggregateFunction, Condition, Operator, Projection, Query};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid query: {}", source))]
+    InvalidQuery { source: parser::ParseError },
+    #[snafu(display("Window size and hop must be greater than zero"))]
+    InvalidWindow,
+    #[snafu(display(
+        "Hopping windows overlap {} times, at most {} overlapping windows are allowed",
+        overlap,
+        MAX_OVERLAPPING_WINDOWS
+    ))]
+    TooManyWindows { overlap: i64 },
+}
+
+/// Every event is aggregated into each window covering it, so the overlap of
+/// hopping windows is bounded to keep the cost of an event in check.
+const MAX_OVERLAPPING_WINDOWS: i64 = 100;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SqlConfig {
+    pub query: String,
+    #[serde(default)]
+    pub window: WindowConfig,
+    pub flush_period_ms: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum WindowConfig {
+    /// Consecutive, non-overlapping windows.
+    Tumbling { size_secs: u64 },
+    /// Windows of `size_secs` starting every `hop_secs`, so that an event
+    /// may belong to several windows.
+    Hopping { size_secs: u64, hop_secs: u64 },
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        WindowConfig::Tumbling { size_secs: 60 }
+    }
+}
+
+impl WindowConfig {
+    fn size_and_hop(&self) -> (i64, i64) {
+        match *self {
+            WindowConfig::Tumbling { size_secs } => (size_secs as i64, size_secs as i64),
+            WindowConfig::Hopping {
+                size_secs,
+                hop_secs,
+            } => (size_secs as i64, hop_secs as i64),
+        }
+    }
+}
+
+inventory::submit! {
+    TransformDescription::new::<SqlConfig>("sql")
+}
+
+impl GenerateConfig for SqlConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            query: "SELECT service, count(*) AS errors WHERE level = 'error' GROUP BY service"
+                .to_owned(),
+            window: WindowConfig::default(),
+            flush_period_ms: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sql")]
+impl TransformConfig for SqlConfig {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
+        Sql::new(self).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "sql"
+    }
+}
+
+#[derive(Debug)]
+enum Accumulator {
+    Count(i64),
+    Sum(Option<f64>),
+    Avg { sum: f64, count: u64 },
+    Min(Option<f64>),
+    Max(Option<f64>),
+}
+
+impl Accumulator {
+    fn new(function: AggregateFunction) -> Self {
+        match function {
+            AggregateFunction::Count => Accumulator::Count(0),
+            AggregateFunction::Sum => Accumulator::Sum(None),
+            AggregateFunction::Avg => Accumulator::Avg { sum: 0.0, count: 0 },
+            AggregateFunction::Min => Accumulator::Min(None),
+            AggregateFunction::Max => Accumulator::Max(None),
+        }
+    }
+
+    /// Adds a value, `None` standing for the row itself in `COUNT(*)`.
+    fn add(&mut self, value: Option<&Value>) {
+        if let Accumulator::Count(count) = self {
+            if value.map_or(true, |value| !matches!(value, Value::Null)) {
+                *count += 1;
+            }
+            return;
+        }
+
+        let number = match value.and_then(as_f64) {
+            Some(number) => number,
+            None => return,
+        };
+        match self {
+            Accumulator::Count(_) => unreachable!(),
+            Accumulator::Sum(sum) => *sum = Some(sum.unwrap_or(0.0) + number),
+            Accumulator::Avg { sum, count } => {
+                *sum += number;
+                *count += 1;
+            }
+            Accumulator::Min(min) => *min = Some(min.map_or(number, |min| min.min(number))),
+            Accumulator::Max(max) => *max = Some(max.map_or(number, |max| max.max(number))),
+        }
+    }
+
+    fn value(&self) -> Value {
+        match *self {
+            Accumulator::Count(count) => Value::Integer(count),
+            Accumulator::Avg { count: 0, .. } => Value::Null,
+            Accumulator::Avg { sum, count } => Value::Float(sum / count as f64),
+            Accumulator::Sum(value) | Accumulator::Min(value) | Accumulator::Max(value) => {
+                value.map_or(Value::Null, Value::Float)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Group {
+    keys: Vec<(String, Option<Value>)>,
+    accumulators: Vec<Accumulator>,
+}
+
+pub struct Sql {
+    query: Query,
+    window_size: i64,
+    window_hop: i64,
+    flush_period: Duration,
+    /// Open windows by start time, in seconds since the epoch.
+    windows: BTreeMap<i64, HashMap<Discriminant, Group>>,
+}
+
+impl Sql {
+    fn new(config: &SqlConfig) -> crate::Result<Self> {
+        let query = parser::parse(&config.query).context(InvalidQuery)?;
+        let (window_size, window_hop) = config.window.size_and_hop();
+        if window_size <= 0 || window_hop <= 0 {
+            return Err(BuildError::InvalidWindow.into());
+        }
+        let overlap = (window_size + window_hop - 1) / window_hop;
+        if overlap > MAX_OVERLAPPING_WINDOWS {
+            return Err(BuildError::TooManyWindows { overlap }.into());
+        }
+
+        Ok(Self {
+            query,
+            window_size,
+            window_hop,
+            flush_period: Duration::from_millis(config.flush_period_ms.unwrap_or(1000)),
+            windows: BTreeMap::new(),
+        })
+    }
+
+    /// Aggregates by processing time: the windows an event falls in are the
+    /// ones covering `now`, whatever its own timestamp.
+    fn transform_one(&mut self, output: &mut Vec<Event>, event: Event, now: DateTime<Utc>) {
+        let log = event.into_log();
+
+        if let Some(filter) = &self.query.filter {
+            if !evaluate(filter, &log) {
+                emit!(SqlEventDiscarded);
+                return;
+            }
+        }
+
+        if self.query.is_aggregate() {
+            self.aggregate(&log, now.timestamp());
+        } else {
+            output.push(self.project(log).into());
+        }
+    }
+
+    fn project(&self, log: LogEvent) -> LogEvent {
+        let mut projected = if self.query.projections.contains(&Projection::Wildcard) {
+            log.clone()
+        } else {
+            LogEvent::default()
+        };
+        for projection in &self.query.projections {
+            if let Projection::Field { field, alias } = projection {
+                if let Some(value) = log.get(field) {
+                    projected.insert(alias, value.clone());
+                }
+            }
+        }
+        projected
+    }
+
+    /// The start of the windows containing `timestamp`.
+    fn window_starts(&self, timestamp: i64) -> impl Iterator<Item = i64> {
+        let hop = self.window_hop;
+        let first = (timestamp - self.window_size).div_euclid(hop) * hop + hop;
+        let last = timestamp.div_euclid(hop) * hop;
+        (0..)
+            .map(move |i| first + i * hop)
+            .take_while(move |start| *start <= last)
+    }
+
+    fn aggregate(&mut self, log: &LogEvent, timestamp: i64) {
+        let discriminant = Discriminant::from_log_event(log, &self.query.group_by);
+        let starts = self.window_starts(timestamp).collect::<Vec<_>>();
+        for start in starts {
+            let query = &self.query;
+            let group = self
+                .windows
+                .entry(start)
+                .or_default()
+                .entry(discriminant.clone())
+                .or_insert_with(|| Group {
+                    keys: query
+                        .projections
+                        .iter()
+                        .filter_map(|projection| match projection {
+                            Projection::Field { field, alias } => {
+                                Some((alias.clone(), log.get(field).cloned()))
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                    accumulators: query
+                        .projections
+                        .iter()
+                        .filter_map(|projection| match projection {
+                            Projection::Aggregate { function, .. } => {
+                                Some(Accumulator::new(*function))
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                });
+
+            let fields = query
+                .projections
+                .iter()
+                .filter_map(|projection| match projection {
+                    Projection::Aggregate { field, .. } => Some(field),
+                    _ => None,
+                });
+            for (accumulator, field) in group.accumulators.iter_mut().zip(fields) {
+                match field {
+                    Some(field) => accumulator.add(Some(log.get(field).unwrap_or(&Value::Null))),
+                    None => accumulator.add(None),
+                }
+            }
+        }
+    }
+
+    /// Emits the summary events of the windows ending at or before `now`.
+    fn flush_into(&mut self, output: &mut Vec<Event>, now: Option<DateTime<Utc>>) {
+        let now = now.map(|now| now.timestamp());
+        let window_size = self.window_size;
+        let closed = self
+            .windows
+            .keys()
+            .copied()
+            .take_while(|start| now.map_or(true, |now| start + window_size <= now))
+            .collect::<Vec<_>>();
+
+        for start in closed {
+            let groups = self.windows.remove(&start).unwrap_or_default();
+            emit!(SqlWindowFlushed {
+                groups: groups.len()
+            });
+            for (_, group) in groups {
+                output.push(self.summarize(start, group).into());
+            }
+        }
+    }
+
+    fn summarize(&self, start: i64, group: Group) -> LogEvent {
+        let mut log = LogEvent::default();
+        let window_start = Utc.timestamp(start, 0);
+        let window_end = Utc.timestamp(start + self.window_size, 0);
+        log.insert(log_schema().timestamp_key(), window_start);
+        log.insert("window_start", window_start);
+        log.insert("window_end", window_end);
+
+        for (alias, value) in group.keys {
+            if let Some(value) = value {
+                log.insert(alias, value);
+            }
+        }
+        let aliases = self
+            .query
+            .projections
+            .iter()
+            .filter_map(|projection| match projection {
+                Projection::Aggregate { alias, .. } => Some(alias),
+                _ => None,
+            });
+        for (alias, accumulator) in aliases.zip(group.accumulators) {
+            log.insert(alias, accumulator.value());
+        }
+        log
+    }
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(i) => Some(*i as f64),
+        Value::Float(f) => Some(*f),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Bytes(left), Value::Bytes(right)) => Some(left.cmp(right)),
+        (Value::Boolean(left), Value::Boolean(right)) => Some(left.cmp(right)),
+        (Value::Timestamp(left), Value::Timestamp(right)) => Some(left.cmp(right)),
+        _ => as_f64(left)?.partial_cmp(&as_f64(right)?),
+    }
+}
+
+/// Evaluates a `WHERE` condition. Comparisons against missing fields are
+/// false, as comparisons against `NULL` are in SQL.
+fn evaluate(condition: &Condition, log: &LogEvent) -> bool {
+    match condition {
+        Condition::And(left, right) => evaluate(left, log) && evaluate(right, log),
+        Condition::Or(left, right) => evaluate(left, log) || evaluate(right, log),
+        Condition::Not(condition) => !evaluate(condition, log),
+        Condition::IsNull { field, negated } => {
+            let is_null = matches!(log.get(field), None | Some(Value::Null));
+            is_null != *negated
+        }
+        Condition::Compare { field, op, value } => {
+            let ordering = match log.get(field).and_then(|field| compare(field, value)) {
+                Some(ordering) => ordering,
+                None => return false,
+            };
+            match op {
+                Operator::Eq => ordering == Ordering::Equal,
+                Operator::Ne => ordering != Ordering::Equal,
+                Operator::Lt => ordering == Ordering::Less,
+                Operator::Le => ordering != Ordering::Greater,
+                Operator::Gt => ordering == Ordering::Greater,
+                Operator::Ge => ordering != Ordering::Less,
+            }
+        }
+    }
+}
+
+impl TaskTransform for Sql {
+    fn transform(
+        self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let mut flush_stream = tokio::time::interval(me.flush_period);
+
+        Box::pin(
+            stream! {
+              loop {
+                let mut output = Vec::new();
+                let done = tokio::select! {
+                    _ = flush_stream.next() => {
+                      me.flush_into(&mut output, Some(Utc::now()));
+                      false
+                    }
+                    maybe_event = input_rx.next() => {
+                      match maybe_event {
+                        None => {
+                          me.flush_into(&mut output, None);
+                          true
+                        }
+                        Some(event) => {
+                          me.transform_one(&mut output, event, Utc::now());
+                          false
+                        }
+                      }
+                    }
+                };
+                yield stream::iter(output.into_iter());
+                if done { break }
+              }
+            }
+            .flatten(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sql(config: &str) -> Sql {
+        Sql::new(&toml::from_str(config).unwrap()).unwrap()
+    }
+
+    fn log(fields: &[(&str, Value)]) -> Event {
+        let mut event = Event::new_empty_log();
+        for (key, value) in fields {
+            event.as_mut_log().insert(*key, value.clone());
+        }
+        event
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp(secs, 0)
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SqlConfig>();
+    }
+
+    #[test]
+    fn filters_and_projects() {
+        let mut sql = sql(r#"query = "SELECT host AS hostname, status WHERE status >= 500""#);
+        let mut output = Vec::new();
+
+        sql.transform_one(
+            &mut output,
+            log(&[("host", "a".into()), ("status", 200.into())]),
+            at(0),
+        );
+        sql.transform_one(
+            &mut output,
+            log(&[
+                ("host", "b".into()),
+                ("status", "503".into()),
+                ("extra", true.into()),
+            ]),
+            at(0),
+        );
+
+        assert_eq!(output.len(), 1);
+        let log = output[0].as_log();
+        assert_eq!(log["hostname"], "b".into());
+        assert_eq!(log["status"], "503".into());
+        assert!(log.get("extra").is_none());
+    }
+
+    #[test]
+    fn tumbling_window_roll_up() {
+        let mut sql = sql(r#"
+            query = "SELECT service, count(*) AS errors, max(duration) FROM logs WHERE level = 'error' GROUP BY service"
+            window.type = "tumbling"
+            window.size_secs = 60
+            "#);
+        let mut output = Vec::new();
+
+        for (secs, service, level, duration) in &[
+            (0, "api", "error", 10),
+            (30, "api", "error", 30),
+            (45, "web", "error", 5),
+            (50, "api", "info", 100),
+            (70, "api", "error", 1),
+        ] {
+            sql.transform_one(
+                &mut output,
+                log(&[
+                    ("service", (*service).into()),
+                    ("level", (*level).into()),
+                    ("duration", (*duration).into()),
+                ]),
+                at(*secs),
+            );
+        }
+        assert!(output.is_empty());
+
+        sql.flush_into(&mut output, Some(at(60)));
+        output.sort_by_key(|event| event.as_log()["service"].to_string_lossy());
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0].as_log()["service"], "api".into());
+        assert_eq!(output[0].as_log()["errors"], Value::Integer(2));
+        assert_eq!(output[0].as_log()["max_duration"], Value::Float(30.0));
+        assert_eq!(output[0].as_log()["window_start"], Value::from(at(0)));
+        assert_eq!(output[1].as_log()["errors"], Value::Integer(1));
+
+        output.clear();
+        sql.flush_into(&mut output, None);
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["window_end"], Value::from(at(120)));
+    }
+
+    #[test]
+    fn hopping_windows_overlap() {
+        let sql = sql(r#"
+            query = "SELECT count(*)"
+            window.type = "hopping"
+            window.size_secs = 60
+            window.hop_secs = 20
+            "#);
+
+        assert_eq!(sql.window_starts(70).collect::<Vec<_>>(), vec![20, 40, 60]);
+        assert_eq!(sql.window_starts(60).collect::<Vec<_>>(), vec![20, 40, 60]);
+        assert_eq!(sql.window_starts(59).collect::<Vec<_>>(), vec![0, 20, 40]);
+    }
+
+    #[test]
+    fn hopping_windows_with_gaps() {
+        let sql = sql(r#"
+            query = "SELECT count(*)"
+            window.type = "hopping"
+            window.size_secs = 10
+            window.hop_secs = 30
+            "#);
+
+        assert_eq!(sql.window_starts(35).collect::<Vec<_>>(), vec![30]);
+        assert!(sql.window_starts(45).next().is_none());
+    }
+
+    #[test]
+    fn bounds_overlapping_windows() {
+        let config = |size, hop| {
+            toml::from_str::<SqlConfig>(&format!(
+                r#"
+                query = "SELECT count(*)"
+                window.type = "hopping"
+                window.size_secs = {}
+                window.hop_secs = {}
+                "#,
+                size, hop
+            ))
+            .unwrap()
+        };
+        assert!(Sql::new(&config(100, 1)).is_ok());
+        assert!(Sql::new(&config(101, 1)).is_err());
+        assert!(Sql::new(&config(86400, 1)).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_queries() {
+        let config = toml::from_str::<SqlConfig>(r#"query = "SELECT host, count(*)""#).unwrap();
+        assert!(Sql::new(&config).is_err());
+    }
+}
@@ -0,0 +1,631 @@
+//! A small recursive descent parser for the subset of SQL understood by the
+//! `sql` transform:
+//!
+//! ```text
+//! SELECT <projection> [, <projection>]* [FROM <name>]
+//!   [WHERE <condition>]
+//!   [GROUP BY <field> [, <field>]*]
+//! ```
+
+use crate::event::Value;
+use snafu::Snafu;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum ParseError {
+    #[snafu(display("Unexpected character {:?} at position {}", character, position))]
+    UnexpectedCharacter { character: char, position: usize },
+    #[snafu(display("Unterminated string starting at position {}", position))]
+    UnterminatedString { position: usize },
+    #[snafu(display("Expected {}, found {}", expected, found))]
+    UnexpectedToken { expected: String, found: String },
+    #[snafu(display("Unknown aggregate function {:?}", name))]
+    UnknownFunction { name: String },
+    #[snafu(display(
+        "Field {:?} must appear in the GROUP BY clause or be used in an aggregate function",
+        field
+    ))]
+    NotGrouped { field: String },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Query {
+    pub projections: Vec<Projection>,
+    pub filter: Option<Condition>,
+    pub group_by: Vec<String>,
+}
+
+impl Query {
+    /// Whether the query rolls events up into windows, rather than
+    /// filtering and projecting them one by one.
+    pub fn is_aggregate(&self) -> bool {
+        !self.group_by.is_empty()
+            || self
+                .projections
+                .iter()
+                .any(|p| matches!(p, Projection::Aggregate { .. }))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Projection {
+    Wildcard,
+    Field {
+        field: String,
+        alias: String,
+    },
+    Aggregate {
+        function: AggregateFunction,
+        /// `None` for `COUNT(*)`.
+        field: Option<String>,
+        alias: String,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AggregateFunction {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl AggregateFunction {
+    fn name(self) -> &'static str {
+        match self {
+            AggregateFunction::Count => "count",
+            AggregateFunction::Sum => "sum",
+            AggregateFunction::Avg => "avg",
+            AggregateFunction::Min => "min",
+            AggregateFunction::Max => "max",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Condition {
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+    Compare {
+        field: String,
+        op: Operator,
+        value: Value,
+    },
+    IsNull {
+        field: String,
+        negated: bool,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Ident(String),
+    QuotedIdent(String),
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Op(Operator),
+    Comma,
+    Star,
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(ident) | Token::QuotedIdent(ident) => write!(f, "{:?}", ident),
+            Token::String(s) => write!(f, "'{}'", s),
+            Token::Integer(i) => write!(f, "{}", i),
+            Token::Float(n) => write!(f, "{}", n),
+            Token::Op(op) => write!(f, "{:?}", op),
+            Token::Comma => write!(f, "\",\""),
+            Token::Star => write!(f, "\"*\""),
+            Token::LParen => write!(f, "\"(\""),
+            Token::RParen => write!(f, "\")\""),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    let chars = input.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '*' => {
+                tokens.push(Token::Star);
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '=' => {
+                tokens.push(Token::Op(Operator::Eq));
+                i += 1;
+            }
+            '!' | '<' | '>' => {
+                let next = chars.get(i + 1).copied();
+                let (op, len) = match (c, next) {
+                    ('!', Some('=')) => (Operator::Ne, 2),
+                    ('<', Some('>')) => (Operator::Ne, 2),
+                    ('<', Some('=')) => (Operator::Le, 2),
+                    ('>', Some('=')) => (Operator::Ge, 2),
+                    ('<', _) => (Operator::Lt, 1),
+                    ('>', _) => (Operator::Gt, 1),
+                    _ => {
+                        return Err(ParseError::UnexpectedCharacter {
+                            character: c,
+                            position: i,
+                        })
+                    }
+                };
+                tokens.push(Token::Op(op));
+                i += len;
+            }
+            '\'' => {
+                let start = i;
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(ParseError::UnterminatedString { position: start }),
+                        // A doubled quote is an escaped quote.
+                        Some('\'') if chars.get(i + 1) == Some(&'\'') => {
+                            value.push('\'');
+                            i += 2;
+                        }
+                        Some('\'') => {
+                            i += 1;
+                            break;
+                        }
+                        Some(c) => {
+                            value.push(*c);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push(Token::String(value));
+            }
+            '"' => {
+                // Quoted identifiers allow field names that clash with
+                // keywords or contain special characters.
+                let start = i;
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|c| *c == '"')
+                    .ok_or(ParseError::UnterminatedString { position: start })?;
+                tokens.push(Token::QuotedIdent(
+                    chars[i + 1..i + 1 + end].iter().collect(),
+                ));
+                i += end + 2;
+            }
+            c if c.is_ascii_digit() || (c == '-' && next_is_digit(&chars, i)) => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let literal = chars[start..i].iter().collect::<String>();
+                tokens.push(match literal.parse::<i64>() {
+                    Ok(integer) => Token::Integer(integer),
+                    Err(_) => Token::Float(literal.parse::<f64>().map_err(|_| {
+                        ParseError::UnexpectedCharacter {
+                            character: c,
+                            position: start,
+                        }
+                    })?),
+                });
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '.' | '@'))
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            _ => {
+                return Err(ParseError::UnexpectedCharacter {
+                    character: c,
+                    position: i,
+                })
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn next_is_digit(chars: &[char], i: usize) -> bool {
+    chars.get(i + 1).map_or(false, char::is_ascii_digit)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek_keyword(keyword);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.peek() == Some(token);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn unexpected(&self, expected: &str) -> ParseError {
+        ParseError::UnexpectedToken {
+            expected: expected.to_owned(),
+            found: self
+                .peek()
+                .map_or_else(|| "end of query".to_owned(), ToString::to_string),
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.unexpected(keyword))
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), ParseError> {
+        if self.eat(&token) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&token.to_string()))
+        }
+    }
+
+    fn field(&mut self) -> Result<String, ParseError> {
+        match self.peek() {
+            Some(Token::Ident(ident)) if !is_keyword(ident) => {
+                let ident = ident.clone();
+                self.position += 1;
+                Ok(ident)
+            }
+            Some(Token::QuotedIdent(ident)) => {
+                let ident = ident.clone();
+                self.position += 1;
+                Ok(ident)
+            }
+            _ => Err(self.unexpected("a field name")),
+        }
+    }
+
+    fn query(&mut self) -> Result<Query, ParseError> {
+        self.expect_keyword("select")?;
+        let mut projections = vec![self.projection()?];
+        while self.eat(&Token::Comma) {
+            projections.push(self.projection()?);
+        }
+
+        // The input is given by the transform's `inputs`, so the source
+        // name is only accepted for readability.
+        if self.eat_keyword("from") {
+            self.field()?;
+        }
+
+        let filter = if self.eat_keyword("where") {
+            Some(self.or()?)
+        } else {
+            None
+        };
+
+        let mut group_by = Vec::new();
+        if self.eat_keyword("group") {
+            self.expect_keyword("by")?;
+            group_by.push(self.field()?);
+            while self.eat(&Token::Comma) {
+                group_by.push(self.field()?);
+            }
+        }
+
+        if self.peek().is_some() {
+            return Err(self.unexpected("end of query"));
+        }
+
+        let query = Query {
+            projections,
+            filter,
+            group_by,
+        };
+        if query.is_aggregate() {
+            for projection in &query.projections {
+                match projection {
+                    Projection::Field { field, .. } if !query.group_by.contains(field) => {
+                        return Err(ParseError::NotGrouped {
+                            field: field.clone(),
+                        })
+                    }
+                    Projection::Wildcard => {
+                        return Err(ParseError::NotGrouped {
+                            field: "*".to_owned(),
+                        })
+                    }
+                    _ => (),
+                }
+            }
+        }
+        Ok(query)
+    }
+
+    fn projection(&mut self) -> Result<Projection, ParseError> {
+        if self.eat(&Token::Star) {
+            return Ok(Projection::Wildcard);
+        }
+
+        let name = self.field()?;
+        let projection = if self.eat(&Token::LParen) {
+            let function = match name.to_ascii_lowercase().as_str() {
+                "count" => AggregateFunction::Count,
+                "sum" => AggregateFunction::Sum,
+                "avg" => AggregateFunction::Avg,
+                "min" => AggregateFunction::Min,
+                "max" => AggregateFunction::Max,
+                _ => return Err(ParseError::UnknownFunction { name }),
+            };
+            let field = if function == AggregateFunction::Count && self.eat(&Token::Star) {
+                None
+            } else {
+                Some(self.field()?)
+            };
+            self.expect(Token::RParen)?;
+
+            let default_alias = match &field {
+                Some(field) => format!("{}_{}", function.name(), field.replace('.', "_")),
+                None => function.name().to_owned(),
+            };
+            Projection::Aggregate {
+                function,
+                field,
+                alias: self.alias()?.unwrap_or(default_alias),
+            }
+        } else {
+            Projection::Field {
+                alias: self.alias()?.unwrap_or_else(|| name.clone()),
+                field: name,
+            }
+        };
+        Ok(projection)
+    }
+
+    fn alias(&mut self) -> Result<Option<String>, ParseError> {
+        if self.eat_keyword("as") {
+            self.field().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn or(&mut self) -> Result<Condition, ParseError> {
+        let mut condition = self.and()?;
+        while self.eat_keyword("or") {
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition, ParseError> {
+        let mut condition = self.not()?;
+        while self.eat_keyword("and") {
+            condition = Condition::And(Box::new(condition), Box::new(self.not()?));
+        }
+        Ok(condition)
+    }
+
+    fn not(&mut self) -> Result<Condition, ParseError> {
+        if self.eat_keyword("not") {
+            Ok(Condition::Not(Box::new(self.not()?)))
+        } else {
+            self.comparison()
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Condition, ParseError> {
+        if self.eat(&Token::LParen) {
+            let condition = self.or()?;
+            self.expect(Token::RParen)?;
+            return Ok(condition);
+        }
+
+        let field = self.field()?;
+        if self.eat_keyword("is") {
+            let negated = self.eat_keyword("not");
+            self.expect_keyword("null")?;
+            return Ok(Condition::IsNull { field, negated });
+        }
+
+        let op = match self.advance() {
+            Some(Token::Op(op)) => op,
+            _ => {
+                self.position -= 1;
+                return Err(self.unexpected("a comparison operator"));
+            }
+        };
+        let value = match self.advance() {
+            Some(Token::String(s)) => Value::from(s),
+            Some(Token::Integer(i)) => Value::Integer(i),
+            Some(Token::Float(f)) => Value::Float(f),
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("true") => Value::Boolean(true),
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("false") => {
+                Value::Boolean(false)
+            }
+            _ => {
+                self.position -= 1;
+                return Err(self.unexpected("a literal"));
+            }
+        };
+        Ok(Condition::Compare { field, op, value })
+    }
+}
+
+fn is_keyword(ident: &str) -> bool {
+    [
+        "select", "from", "where", "group", "by", "as", "and", "or", "not", "is", "null", "true",
+        "false",
+    ]
+    .iter()
+    .any(|keyword| ident.eq_ignore_ascii_case(keyword))
+}
+
+pub fn parse(query: &str) -> Result<Query, ParseError> {
+    Parser {
+        tokens: tokenize(query)?,
+        position: 0,
+    }
+    .query()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_aggregate_query() {
+        let query = parse(
+            "SELECT service, count(*) AS errors, avg(duration_ms) \
+             FROM logs WHERE level = 'error' AND (status >= 500 OR retry IS NOT NULL) \
+             GROUP BY service",
+        )
+        .unwrap();
+
+        assert_eq!(
+            query,
+            Query {
+                projections: vec![
+                    Projection::Field {
+                        field: "service".into(),
+                        alias: "service".into()
+                    },
+                    Projection::Aggregate {
+                        function: AggregateFunction::Count,
+                        field: None,
+                        alias: "errors".into()
+                    },
+                    Projection::Aggregate {
+                        function: AggregateFunction::Avg,
+                        field: Some("duration_ms".into()),
+                        alias: "avg_duration_ms".into()
+                    },
+                ],
+                filter: Some(Condition::And(
+                    Box::new(Condition::Compare {
+                        field: "level".into(),
+                        op: Operator::Eq,
+                        value: "error".into(),
+                    }),
+                    Box::new(Condition::Or(
+                        Box::new(Condition::Compare {
+                            field: "status".into(),
+                            op: Operator::Ge,
+                            value: Value::Integer(500),
+                        }),
+                        Box::new(Condition::IsNull {
+                            field: "retry".into(),
+                            negated: true,
+                        }),
+                    )),
+                )),
+                group_by: vec!["service".into()],
+            }
+        );
+        assert!(query.is_aggregate());
+    }
+
+    #[test]
+    fn parses_projection_query() {
+        let query = parse(r#"select "from", host.name as host where not level = 'it''s'"#).unwrap();
+
+        assert!(!query.is_aggregate());
+        assert_eq!(
+            query.filter,
+            Some(Condition::Not(Box::new(Condition::Compare {
+                field: "level".into(),
+                op: Operator::Eq,
+                value: "it's".into(),
+            })))
+        );
+        assert_eq!(
+            query.projections[1],
+            Projection::Field {
+                field: "host.name".into(),
+                alias: "host".into()
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_queries() {
+        assert_eq!(
+            parse("SELECT host, count(*)"),
+            Err(ParseError::NotGrouped {
+                field: "host".into()
+            })
+        );
+        assert_eq!(
+            parse("SELECT median(x)"),
+            Err(ParseError::UnknownFunction {
+                name: "median".into()
+            })
+        );
+        assert_eq!(
+            parse("SELECT * WHERE x"),
+            Err(ParseError::UnexpectedToken {
+                expected: "a comparison operator".into(),
+                found: "end of query".into()
+            })
+        );
+        assert!(parse("SELECT * WHERE x = 'oops").is_err());
+    }
+}
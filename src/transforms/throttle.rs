@@ -0,0 +1,331 @@
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
+    event::Event,
+    internal_events::{ThrottleEventDiscarded, ThrottleKeyRenderingError},
+    template::Template,
+    transforms::{
+        util::value_template::{ValueTemplate, VrlExpression},
+        FunctionTransform, Transform,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    time::{Duration, Instant},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ThrottleConfig {
+    /// The number of events allowed per key in each window.
+    pub threshold: u32,
+    #[serde(deserialize_with = "crate::serde::duration_secs")]
+    pub window_secs: u64,
+    /// The key the events are throttled by, all of them sharing a single
+    /// bucket if unset.
+    pub key_field: Option<ValueTemplate>,
+    /// Whether the discarded events metric is tagged with their key.
+    #[serde(default)]
+    pub emit_events_discarded_per_key: bool,
+}
+
+inventory::submit! {
+    TransformDescription::new::<ThrottleConfig>("throttle")
+}
+
+impl GenerateConfig for ThrottleConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            threshold: 100,
+            window_secs: 1,
+            key_field: None,
+            emit_events_discarded_per_key: false,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "throttle")]
+impl TransformConfig for ThrottleConfig {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
+        Throttle::new(self).map(Transform::function)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "throttle"
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Key {
+    Template(Template),
+    Vrl(VrlExpression),
+}
+
+impl Key {
+    fn render(&self, event: &Event) -> Result<String, String> {
+        match self {
+            Key::Template(template) => template
+                .render_string(event)
+                .map_err(|fields| format!("Missing fields: {:?}.", fields)),
+            Key::Vrl(vrl) => vrl.render(event.as_log()),
+        }
+    }
+}
+
+/// A token bucket, holding up to `threshold` tokens and refilled with
+/// `threshold` tokens per window. Each event passing through takes one.
+#[derive(Clone, Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+}
+
+#[derive(Clone, Debug)]
+pub struct Throttle {
+    key: Option<Key>,
+    capacity: f64,
+    /// Tokens per second.
+    rate: f64,
+    window: Duration,
+    emit_events_discarded_per_key: bool,
+    buckets: HashMap<String, Bucket>,
+    next_cleanup: Option<Instant>,
+}
+
+impl Throttle {
+    pub fn new(config: &ThrottleConfig) -> crate::Result<Self> {
+        if config.threshold == 0 {
+            return Err("`threshold` must be greater than 0".into());
+        }
+        if config.window_secs == 0 {
+            return Err("`window_secs` must be greater than 0".into());
+        }
+
+        let key = match &config.key_field {
+            None => None,
+            Some(ValueTemplate::Template(template)) => {
+                Some(Key::Template(Template::try_from(template.as_str())?))
+            }
+            Some(ValueTemplate::Vrl { vrl }) => Some(Key::Vrl(vrl.clone())),
+        };
+
+        Ok(Self {
+            key,
+            capacity: config.threshold as f64,
+            rate: config.threshold as f64 / config.window_secs as f64,
+            window: Duration::from_secs(config.window_secs),
+            emit_events_discarded_per_key: config.emit_events_discarded_per_key,
+            buckets: HashMap::new(),
+            next_cleanup: None,
+        })
+    }
+
+    /// Takes a token from the bucket of `key`, `false` if it's empty.
+    fn take(&mut self, key: &str, now: Instant) -> bool {
+        self.cleanup(now);
+
+        let (capacity, rate) = (self.capacity, self.rate);
+        let bucket = self.buckets.entry(key.to_owned()).or_insert(Bucket {
+            tokens: capacity,
+            refilled: now,
+        });
+        refill(bucket, capacity, rate, now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Once per window, removes the buckets refilled up to the threshold. They
+    /// would be recreated as they are, and the keys seen only once don't pile
+    /// up this way.
+    fn cleanup(&mut self, now: Instant) {
+        match self.next_cleanup {
+            Some(next_cleanup) if now < next_cleanup => return,
+            _ => self.next_cleanup = Some(now + self.window),
+        }
+
+        let (capacity, rate) = (self.capacity, self.rate);
+        self.buckets.retain(|_, bucket| {
+            refill(bucket, capacity, rate, now);
+            bucket.tokens < capacity
+        });
+    }
+}
+
+fn refill(bucket: &mut Bucket, capacity: f64, rate: f64, now: Instant) {
+    let elapsed = now.saturating_duration_since(bucket.refilled);
+    bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * rate).min(capacity);
+    bucket.refilled = now;
+}
+
+impl FunctionTransform for Throttle {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        let key = match &self.key {
+            Some(key) => match key.render(&event) {
+                Ok(key) => key,
+                Err(error) => {
+                    emit!(ThrottleKeyRenderingError { error });
+                    output.push(event);
+                    return;
+                }
+            },
+            None => String::new(),
+        };
+
+        if self.take(&key, Instant::now()) {
+            output.push(event);
+        } else {
+            emit!(ThrottleEventDiscarded {
+                key: &key,
+                emit_events_discarded_per_key: self.emit_events_discarded_per_key,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::LogEvent;
+
+    fn throttle(config: &str) -> Throttle {
+        Throttle::new(&toml::from_str(config).unwrap()).unwrap()
+    }
+
+    fn transform_all(throttle: &mut Throttle, events: Vec<Event>) -> Vec<Event> {
+        let mut output = Vec::new();
+        for event in events {
+            throttle.transform(&mut output, event);
+        }
+        output
+    }
+
+    fn tenant_event(tenant: &str) -> Event {
+        let mut log = LogEvent::default();
+        log.insert("tenant", tenant);
+        log.into()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ThrottleConfig>();
+    }
+
+    #[test]
+    fn parses_window_durations() {
+        let config = toml::from_str::<ThrottleConfig>("threshold = 2\nwindow_secs = \"1m30s\"");
+        assert_eq!(config.unwrap().window_secs, 90);
+    }
+
+    #[test]
+    fn refills_over_the_window() {
+        let mut throttle = throttle("threshold = 2\nwindow_secs = 10");
+        let start = Instant::now();
+
+        assert!(throttle.take("", start));
+        assert!(throttle.take("", start));
+        assert!(!throttle.take("", start));
+
+        // Half the window refills half the bucket.
+        assert!(throttle.take("", start + Duration::from_secs(5)));
+        assert!(!throttle.take("", start + Duration::from_secs(5)));
+
+        // The bucket doesn't fill past the threshold.
+        let later = start + Duration::from_secs(60);
+        assert!(throttle.take("", later));
+        assert!(throttle.take("", later));
+        assert!(!throttle.take("", later));
+    }
+
+    #[test]
+    fn cleans_up_full_buckets() {
+        let mut throttle = throttle("threshold = 2\nwindow_secs = 10");
+        let start = Instant::now();
+
+        assert!(throttle.take("a", start));
+        assert!(throttle.take("b", start + Duration::from_secs(5)));
+        assert!(throttle.take("b", start + Duration::from_secs(5)));
+        assert_eq!(throttle.buckets.len(), 2);
+
+        // `a` has been refilled by then, but not `b`.
+        assert!(throttle.take("c", start + Duration::from_secs(10)));
+        let mut keys = throttle.buckets.keys().cloned().collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(keys, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn throttles_by_key() {
+        let mut throttle = throttle(
+            r#"
+            threshold = 2
+            window_secs = 60
+            key_field = "{{ tenant }}"
+            "#,
+        );
+
+        let events = vec![
+            tenant_event("noisy"),
+            tenant_event("noisy"),
+            tenant_event("quiet"),
+            tenant_event("noisy"),
+            tenant_event("noisy"),
+        ];
+        let output = transform_all(&mut throttle, events.clone());
+        assert_eq!(output, events[..3].to_vec());
+    }
+
+    #[test]
+    fn throttles_by_vrl_key() {
+        let mut throttle = throttle(
+            r#"
+            threshold = 1
+            window_secs = 60
+            key_field = { vrl = "downcase!(.tenant)" }
+            "#,
+        );
+
+        let events = vec![tenant_event("A"), tenant_event("a"), tenant_event("b")];
+        let output = transform_all(&mut throttle, events.clone());
+        assert_eq!(output, vec![events[0].clone(), events[2].clone()]);
+    }
+
+    #[test]
+    fn passes_events_without_key_through() {
+        let mut throttle = throttle(
+            r#"
+            threshold = 1
+            window_secs = 60
+            key_field = "{{ missing }}"
+            "#,
+        );
+
+        let events = vec![tenant_event("a"), tenant_event("a")];
+        assert_eq!(transform_all(&mut throttle, events.clone()), events);
+    }
+
+    #[test]
+    fn rejects_empty_threshold_and_window() {
+        for config in &[
+            "threshold = 0\nwindow_secs = 1",
+            "threshold = 1\nwindow_secs = 0",
+        ] {
+            assert!(Throttle::new(&toml::from_str(config).unwrap()).is_err());
+        }
+    }
+}
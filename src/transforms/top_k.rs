@@ -0,0 +1,466 @@
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event, Value,
+    },
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use chrono::Utc;
+use futures::{stream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    pin::Pin,
+    time::Duration,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TopKConfig {
+    /// The field of logs, or the tag of metrics, whose values are counted.
+    pub field: String,
+    #[serde(default = "default_k")]
+    pub k: usize,
+    /// The number of values counted, defaulting to ten times `k`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<usize>,
+    #[serde(
+        default = "default_window_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
+    pub window_secs: u64,
+    #[serde(
+        default = "default_interval_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
+    pub interval_secs: u64,
+    /// The field, or tag, set on the events whose value is a heavy hitter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heavy_hitter_field: Option<String>,
+    #[serde(default = "default_namespace")]
+    pub namespace: Option<String>,
+}
+
+const fn default_k() -> usize {
+    10
+}
+
+const fn default_window_secs() -> u64 {
+    300
+}
+
+const fn default_interval_secs() -> u64 {
+    60
+}
+
+fn default_namespace() -> Option<String> {
+    Some("vector".to_owned())
+}
+
+inventory::submit! {
+    TransformDescription::new::<TopKConfig>("top_k")
+}
+
+impl GenerateConfig for TopKConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            field: "tenant".to_owned(),
+            k: default_k(),
+            capacity: None,
+            window_secs: default_window_secs(),
+            interval_secs: default_interval_secs(),
+            heavy_hitter_field: None,
+            namespace: default_namespace(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "top_k")]
+impl TransformConfig for TopKConfig {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
+        TopK::new(self.clone()).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "top_k"
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Counter {
+    count: u64,
+    /// How much `count` may overestimate the occurrences of the value.
+    error: u64,
+}
+
+/// Counts the most frequent values with a bounded number of counters, using
+/// the space-saving algorithm: a value without a counter takes over the one
+/// of the least frequent value, whose count becomes its error.
+#[derive(Debug)]
+struct SpaceSaving {
+    capacity: usize,
+    counters: HashMap<String, Counter>,
+    by_count: BTreeSet<(u64, String)>,
+}
+
+impl SpaceSaving {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            counters: HashMap::new(),
+            by_count: BTreeSet::new(),
+        }
+    }
+
+    fn add(&mut self, value: &str) {
+        if let Some(counter) = self.counters.get_mut(value) {
+            self.by_count.remove(&(counter.count, value.to_owned()));
+            counter.count += 1;
+            self.by_count.insert((counter.count, value.to_owned()));
+            return;
+        }
+
+        let counter = if self.counters.len() < self.capacity {
+            Counter { count: 1, error: 0 }
+        } else {
+            let (min, evicted) = self
+                .by_count
+                .iter()
+                .next()
+                .cloned()
+                .expect("the capacity is positive");
+            self.by_count.remove(&(min, evicted.clone()));
+            self.counters.remove(&evicted);
+            Counter {
+                count: min + 1,
+                error: min,
+            }
+        };
+        self.counters.insert(value.to_owned(), counter);
+        self.by_count.insert((counter.count, value.to_owned()));
+    }
+}
+
+pub struct TopK {
+    config: TopKConfig,
+    capacity: usize,
+    /// The number of intervals the window spans.
+    intervals: usize,
+    current: SpaceSaving,
+    /// The counters of the previous intervals of the window, oldest first.
+    previous: VecDeque<SpaceSaving>,
+    heavy_hitters: HashSet<String>,
+}
+
+impl TopK {
+    pub fn new(config: TopKConfig) -> crate::Result<Self> {
+        let capacity = config.capacity.unwrap_or(config.k * 10);
+        if config.k == 0 {
+            return Err("`k` must be greater than 0".into());
+        }
+        if capacity < config.k {
+            return Err("`capacity` must be at least `k`".into());
+        }
+        if config.interval_secs == 0 {
+            return Err("`interval_secs` must be greater than 0".into());
+        }
+        if config.window_secs < config.interval_secs {
+            return Err("`window_secs` must be at least `interval_secs`".into());
+        }
+
+        let intervals =
+            ((config.window_secs + config.interval_secs - 1) / config.interval_secs) as usize;
+        Ok(Self {
+            config,
+            capacity,
+            intervals,
+            current: SpaceSaving::new(capacity),
+            previous: VecDeque::new(),
+            heavy_hitters: HashSet::new(),
+        })
+    }
+
+    /// Counts the value of the field of `event`, and marks the event if the
+    /// value is a heavy hitter.
+    fn record(&mut self, event: &mut Event) {
+        let field = &self.config.field;
+        let value = match event {
+            Event::Log(log) => log.get(field).map(Value::to_string_lossy),
+            Event::Trace(trace) => trace.as_log().get(field).map(Value::to_string_lossy),
+            Event::Metric(metric) => metric.tag_value(field),
+        };
+        let value = match value {
+            Some(value) => value,
+            None => return,
+        };
+        self.current.add(&value);
+
+        if let Some(field) = &self.config.heavy_hitter_field {
+            if self.heavy_hitters.contains(&value) {
+                match event {
+                    Event::Log(log) => {
+                        log.insert(field.clone(), true);
+                    }
+                    Event::Trace(trace) => {
+                        trace.as_mut_log().insert(field.clone(), true);
+                    }
+                    Event::Metric(metric) => metric.set_tag_value(field.clone(), "true".to_owned()),
+                }
+            }
+        }
+    }
+
+    /// The `k` most frequent values over the window, most frequent first.
+    fn top_k(&self) -> Vec<(String, Counter)> {
+        let mut totals = HashMap::<&str, Counter>::new();
+        for summary in self.previous.iter().chain(Some(&self.current)) {
+            for (value, counter) in &summary.counters {
+                let total = totals.entry(value).or_default();
+                total.count += counter.count;
+                total.error += counter.error;
+            }
+        }
+
+        let mut top = totals.into_iter().collect::<Vec<_>>();
+        top.sort_by(|(a, a_counter), (b, b_counter)| {
+            b_counter.count.cmp(&a_counter.count).then(a.cmp(b))
+        });
+        top.truncate(self.config.k);
+        top.into_iter()
+            .map(|(value, counter)| (value.to_owned(), counter))
+            .collect()
+    }
+
+    /// Emits the top values over the window as gauges, updates the heavy
+    /// hitters from them, and starts the next interval.
+    fn flush_into(&mut self, output: &mut Vec<Event>) {
+        let top = self.top_k();
+
+        let timestamp = Utc::now();
+        for (rank, (value, counter)) in top.iter().enumerate() {
+            let tags = vec![
+                ("field".to_owned(), self.config.field.clone()),
+                ("value".to_owned(), value.clone()),
+                ("rank".to_owned(), (rank + 1).to_string()),
+            ]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+            let metric = Metric::new(
+                "top_k_count",
+                MetricKind::Absolute,
+                MetricValue::Gauge {
+                    value: counter.count as f64,
+                },
+            )
+            .with_namespace(self.config.namespace.clone())
+            .with_tags(Some(tags))
+            .with_timestamp(Some(timestamp));
+            output.push(metric.into());
+        }
+
+        self.heavy_hitters = top.into_iter().map(|(value, _)| value).collect();
+
+        let current = std::mem::replace(&mut self.current, SpaceSaving::new(self.capacity));
+        self.previous.push_back(current);
+        while self.previous.len() >= self.intervals {
+            self.previous.pop_front();
+        }
+    }
+}
+
+impl TaskTransform for TopK {
+    fn transform(
+        self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let mut flush_stream = tokio::time::interval(Duration::from_secs(me.config.interval_secs));
+
+        Box::pin(
+            stream! {
+              loop {
+                let mut output = Vec::new();
+                let done = tokio::select! {
+                    _ = flush_stream.next() => {
+                      me.flush_into(&mut output);
+                      false
+                    }
+                    maybe_event = input_rx.next() => {
+                      match maybe_event {
+                        None => {
+                          me.flush_into(&mut output);
+                          true
+                        }
+                        Some(mut event) => {
+                          me.record(&mut event);
+                          output.push(event);
+                          false
+                        }
+                      }
+                    }
+                };
+                yield stream::iter(output.into_iter());
+                if done { break }
+              }
+            }
+            .flatten(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn top_k(config: &str) -> TopK {
+        TopK::new(toml::from_str(config).unwrap()).unwrap()
+    }
+
+    fn event(tenant: &str) -> Event {
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("tenant", tenant);
+        event
+    }
+
+    fn record(top_k: &mut TopK, tenants: &[&str]) -> Vec<Event> {
+        tenants
+            .iter()
+            .map(|tenant| {
+                let mut event = event(tenant);
+                top_k.record(&mut event);
+                event
+            })
+            .collect()
+    }
+
+    fn flush(top_k: &mut TopK) -> Vec<(String, String, f64)> {
+        let mut output = Vec::new();
+        top_k.flush_into(&mut output);
+        output
+            .into_iter()
+            .map(|event| {
+                let metric = event.into_metric();
+                let count = match metric.data.value {
+                    MetricValue::Gauge { value } => value,
+                    _ => panic!("Expected a gauge."),
+                };
+                (
+                    metric.tag_value("rank").unwrap(),
+                    metric.tag_value("value").unwrap(),
+                    count,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<TopKConfig>();
+    }
+
+    #[test]
+    fn emits_top_values() {
+        let mut top_k = top_k("field = \"tenant\"\nk = 2");
+        record(&mut top_k, &["a", "b", "a", "c", "b", "a"]);
+
+        assert_eq!(
+            flush(&mut top_k),
+            vec![
+                ("1".to_owned(), "a".to_owned(), 3.0),
+                ("2".to_owned(), "b".to_owned(), 2.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn slides_window() {
+        let mut top_k = top_k("field = \"tenant\"\nk = 1\nwindow_secs = 20\ninterval_secs = 10");
+        record(&mut top_k, &["a", "a"]);
+        flush(&mut top_k);
+        record(&mut top_k, &["b"]);
+        assert_eq!(
+            flush(&mut top_k),
+            vec![("1".to_owned(), "a".to_owned(), 2.0)]
+        );
+
+        // The first interval left the window.
+        record(&mut top_k, &["b"]);
+        assert_eq!(
+            flush(&mut top_k),
+            vec![("1".to_owned(), "b".to_owned(), 2.0)]
+        );
+    }
+
+    #[test]
+    fn evicts_least_frequent() {
+        let mut summary = SpaceSaving::new(2);
+        for value in &["a", "a", "b", "c"] {
+            summary.add(value);
+        }
+
+        assert_eq!(summary.counters.len(), 2);
+        assert_eq!(summary.counters["a"], Counter { count: 2, error: 0 });
+        assert_eq!(summary.counters["c"], Counter { count: 2, error: 1 });
+    }
+
+    #[test]
+    fn tags_heavy_hitters() {
+        let mut top_k = top_k("field = \"tenant\"\nk = 1\nheavy_hitter_field = \"noisy\"");
+        record(&mut top_k, &["a", "a", "b"]);
+        flush(&mut top_k);
+
+        let events = record(&mut top_k, &["a", "b"]);
+        assert_eq!(events[0].as_log()["noisy"], true.into());
+        assert!(events[1].as_log().get("noisy").is_none());
+    }
+
+    #[test]
+    fn counts_metric_tags() {
+        let mut top_k = top_k("field = \"tenant\"\nk = 1");
+        let mut metric = Event::Metric(
+            Metric::new(
+                "requests",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            )
+            .with_tags(Some(
+                vec![("tenant".to_owned(), "a".to_owned())]
+                    .into_iter()
+                    .collect(),
+            )),
+        );
+        top_k.record(&mut metric);
+
+        assert_eq!(
+            flush(&mut top_k),
+            vec![("1".to_owned(), "a".to_owned(), 1.0)]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        for config in &[
+            "field = \"tenant\"\nk = 0",
+            "field = \"tenant\"\nk = 10\ncapacity = 5",
+            "field = \"tenant\"\nwindow_secs = 10\ninterval_secs = 60",
+        ] {
+            assert!(TopK::new(toml::from_str(config).unwrap()).is_err());
+        }
+    }
+}
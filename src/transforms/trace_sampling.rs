@@ -0,0 +1,412 @@
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
+    event::{Event, TraceEvent},
+    internal_events::{
+        TraceSamplingBufferFull, TraceSamplingDecision, TraceSamplingLateSpanDropped,
+    },
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use futures::{stream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TraceSamplingConfig {
+    /// Keep one in `rate` of the traces not kept by another policy.
+    pub rate: u64,
+    #[serde(default = "default_keep_errors")]
+    pub keep_errors: bool,
+    pub latency_threshold_ms: Option<u64>,
+    #[serde(
+        default = "default_decision_wait_secs",
+        deserialize_with = "crate::serde::duration_secs"
+    )]
+    pub decision_wait_secs: u64,
+    #[serde(default = "default_flush_period_ms")]
+    pub flush_period_ms: u64,
+    #[serde(default = "default_max_traces")]
+    pub max_traces: usize,
+}
+
+const fn default_keep_errors() -> bool {
+    true
+}
+
+const fn default_decision_wait_secs() -> u64 {
+    30
+}
+
+const fn default_flush_period_ms() -> u64 {
+    1000
+}
+
+const fn default_max_traces() -> usize {
+    50_000
+}
+
+inventory::submit! {
+    TransformDescription::new::<TraceSamplingConfig>("trace_sampling")
+}
+
+impl GenerateConfig for TraceSamplingConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            rate: 10,
+            keep_errors: default_keep_errors(),
+            latency_threshold_ms: None,
+            decision_wait_secs: default_decision_wait_secs(),
+            flush_period_ms: default_flush_period_ms(),
+            max_traces: default_max_traces(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "trace_sampling")]
+impl TransformConfig for TraceSamplingConfig {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
+        TraceSampling::new(self).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Trace
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Trace
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "trace_sampling"
+    }
+}
+
+/// The spans of a trace received so far.
+#[derive(Debug)]
+struct PendingTrace {
+    spans: Vec<TraceEvent>,
+    first_seen: Instant,
+}
+
+impl PendingTrace {
+    fn new(now: Instant) -> Self {
+        Self {
+            spans: Vec::new(),
+            first_seen: now,
+        }
+    }
+
+    fn has_error(&self) -> bool {
+        self.spans.iter().any(TraceEvent::is_error)
+    }
+
+    /// The duration of the root span if we have it, otherwise the time
+    /// between the earliest start and the latest end of the received spans.
+    fn duration(&self) -> Option<chrono::Duration> {
+        if let Some(root) = self.spans.iter().find(|span| span.is_root()) {
+            if let Some(duration) = root.duration() {
+                return Some(duration);
+            }
+        }
+        let start = self.spans.iter().filter_map(TraceEvent::start_time).min()?;
+        let end = self.spans.iter().filter_map(TraceEvent::end_time).max()?;
+        Some(*end - *start)
+    }
+}
+
+pub struct TraceSampling {
+    rate: u64,
+    keep_errors: bool,
+    latency_threshold: Option<chrono::Duration>,
+    decision_wait: Duration,
+    flush_period: Duration,
+    max_traces: usize,
+    pending: HashMap<String, PendingTrace>,
+    /// Decisions already made, kept around for `decision_wait` so that spans
+    /// arriving after their trace was decided share its fate.
+    decided: HashMap<String, (bool, Instant)>,
+}
+
+impl TraceSampling {
+    fn new(config: &TraceSamplingConfig) -> crate::Result<Self> {
+        if config.rate == 0 {
+            return Err("`rate` must be greater than zero".into());
+        }
+
+        Ok(Self {
+            rate: config.rate,
+            keep_errors: config.keep_errors,
+            latency_threshold: config
+                .latency_threshold_ms
+                .map(|ms| chrono::Duration::milliseconds(ms as i64)),
+            decision_wait: Duration::from_secs(config.decision_wait_secs),
+            flush_period: Duration::from_millis(config.flush_period_ms),
+            max_traces: config.max_traces,
+            pending: HashMap::new(),
+            decided: HashMap::new(),
+        })
+    }
+
+    /// Applies the policies in order, returning the deciding policy and
+    /// whether the trace is kept.
+    fn decide(&self, trace_id: &str, trace: &PendingTrace) -> (&'static str, bool) {
+        if self.keep_errors && trace.has_error() {
+            return ("error", true);
+        }
+        if let (Some(threshold), Some(duration)) = (self.latency_threshold, trace.duration()) {
+            if duration >= threshold {
+                return ("latency", true);
+            }
+        }
+        (
+            "probabilistic",
+            seahash::hash(trace_id.as_bytes()) % self.rate == 0,
+        )
+    }
+
+    fn finish(&mut self, trace_id: String, output: &mut Vec<Event>, now: Instant) {
+        let trace = match self.pending.remove(&trace_id) {
+            Some(trace) => trace,
+            None => return,
+        };
+
+        let (policy, keep) = self.decide(&trace_id, &trace);
+        emit!(TraceSamplingDecision {
+            policy,
+            keep,
+            spans: trace.spans.len(),
+        });
+
+        if keep {
+            let rate = if policy == "probabilistic" {
+                self.rate
+            } else {
+                1
+            };
+            output.extend(trace.spans.into_iter().map(|mut span| {
+                span.insert("sample_rate", rate.to_string());
+                Event::Trace(span)
+            }));
+        }
+        self.decided.insert(trace_id, (keep, now));
+    }
+
+    fn transform_one(&mut self, output: &mut Vec<Event>, event: Event, now: Instant) {
+        let span = event.into_trace();
+        let trace_id = span.trace_id().unwrap_or_default();
+
+        if let Some((keep, _)) = self.decided.get(&trace_id) {
+            if *keep {
+                output.push(Event::Trace(span));
+            } else {
+                emit!(TraceSamplingLateSpanDropped);
+            }
+            return;
+        }
+
+        if !self.pending.contains_key(&trace_id) && self.pending.len() >= self.max_traces {
+            emit!(TraceSamplingBufferFull {
+                max_traces: self.max_traces,
+            });
+            if let Some(oldest) = self
+                .pending
+                .iter()
+                .min_by_key(|(_, trace)| trace.first_seen)
+                .map(|(id, _)| id.clone())
+            {
+                self.finish(oldest, output, now);
+            }
+        }
+
+        let is_root = span.is_root();
+        self.pending
+            .entry(trace_id.clone())
+            .or_insert_with(|| PendingTrace::new(now))
+            .spans
+            .push(span);
+
+        // The root span completes last, so the trace is done once we see it.
+        if is_root {
+            self.finish(trace_id, output, now);
+        }
+    }
+
+    fn flush_into(&mut self, output: &mut Vec<Event>, now: Instant) {
+        let expired = self
+            .pending
+            .iter()
+            .filter(|(_, trace)| now.duration_since(trace.first_seen) >= self.decision_wait)
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<_>>();
+        for trace_id in expired {
+            self.finish(trace_id, output, now);
+        }
+
+        let decision_wait = self.decision_wait;
+        self.decided
+            .retain(|_, (_, decided_at)| now.duration_since(*decided_at) < decision_wait);
+    }
+
+    fn flush_all_into(&mut self, output: &mut Vec<Event>, now: Instant) {
+        let trace_ids = self.pending.keys().cloned().collect::<Vec<_>>();
+        for trace_id in trace_ids {
+            self.finish(trace_id, output, now);
+        }
+    }
+}
+
+impl TaskTransform for TraceSampling {
+    fn transform(
+        self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let mut flush_stream = tokio::time::interval(me.flush_period);
+
+        Box::pin(
+            stream! {
+              loop {
+                let mut output = Vec::new();
+                let done = tokio::select! {
+                    _ = flush_stream.next() => {
+                      me.flush_into(&mut output, Instant::now());
+                      false
+                    }
+                    maybe_event = input_rx.next() => {
+                      match maybe_event {
+                        None => {
+                          me.flush_all_into(&mut output, Instant::now());
+                          true
+                        }
+                        Some(event) => {
+                          me.transform_one(&mut output, event, Instant::now());
+                          false
+                        }
+                      }
+                    }
+                };
+                yield stream::iter(output.into_iter());
+                if done { break }
+              }
+            }
+            .flatten(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn sampler(config: &str) -> TraceSampling {
+        TraceSampling::new(&toml::from_str(config).unwrap()).unwrap()
+    }
+
+    fn span(trace_id: &str, span_id: &str, parent: Option<&str>, millis: i64) -> Event {
+        let start = Utc.ymd(2021, 1, 1).and_hms(0, 0, 0);
+        let span = TraceEvent::new(trace_id, span_id)
+            .with_times(start, start + chrono::Duration::milliseconds(millis));
+        match parent {
+            Some(parent) => span.with_parent_span_id(parent).into(),
+            None => span.into(),
+        }
+    }
+
+    /// Trace ids that the probabilistic policy drops at the given rate.
+    fn dropped_trace_ids(rate: u64, count: usize) -> Vec<String> {
+        (0..)
+            .map(|i| format!("trace-{}", i))
+            .filter(|id| seahash::hash(id.as_bytes()) % rate != 0)
+            .take(count)
+            .collect()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<TraceSamplingConfig>();
+    }
+
+    #[test]
+    fn keeps_error_traces_atomically() {
+        let mut sampler = sampler("rate = 1000000");
+        let now = Instant::now();
+        let id = dropped_trace_ids(1_000_000, 1).remove(0);
+        let mut output = Vec::new();
+
+        let mut child = span(&id, "2", Some("1"), 10);
+        child.as_mut_trace().insert("status.code", "error");
+        sampler.transform_one(&mut output, child, now);
+        assert!(output.is_empty());
+
+        sampler.transform_one(&mut output, span(&id, "1", None, 20), now);
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0].as_trace()["sample_rate"], "1".into());
+
+        // Late spans follow the decision already made.
+        sampler.transform_one(&mut output, span(&id, "3", Some("1"), 5), now);
+        assert_eq!(output.len(), 3);
+    }
+
+    #[test]
+    fn latency_threshold() {
+        let mut sampler = sampler("rate = 1000000\nlatency_threshold_ms = 500");
+        let now = Instant::now();
+        let ids = dropped_trace_ids(1_000_000, 2);
+        let (slow, fast) = (ids[0].clone(), ids[1].clone());
+        let mut output = Vec::new();
+
+        sampler.transform_one(&mut output, span(&slow, "1", None, 800), now);
+        sampler.transform_one(&mut output, span(&fast, "1", None, 100), now);
+
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_trace().trace_id(), Some(slow));
+    }
+
+    #[test]
+    fn drops_whole_traces_and_times_out() {
+        let mut sampler = sampler("rate = 1000000\ndecision_wait_secs = 10");
+        let now = Instant::now();
+        let id = dropped_trace_ids(1_000_000, 1).remove(0);
+        let mut output = Vec::new();
+
+        sampler.transform_one(&mut output, span(&id, "2", Some("1"), 10), now);
+        sampler.flush_into(&mut output, now + Duration::from_secs(5));
+        assert_eq!(sampler.pending.len(), 1);
+
+        sampler.flush_into(&mut output, now + Duration::from_secs(10));
+        assert!(sampler.pending.is_empty());
+
+        sampler.transform_one(
+            &mut output,
+            span(&id, "1", None, 20),
+            now + Duration::from_secs(11),
+        );
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn decides_early_when_full() {
+        let mut sampler = sampler("rate = 1\nmax_traces = 1");
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        sampler.transform_one(&mut output, span("a", "2", Some("1"), 10), now);
+        sampler.transform_one(&mut output, span("b", "2", Some("1"), 10), now);
+
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_trace().trace_id(), Some("a".into()));
+        assert!(sampler.pending.contains_key("b"));
+    }
+}
@@ -1,2 +1,4 @@
 #[cfg(any(feature = "transforms-lua"))]
 pub mod runtime_transform;
+#[cfg(any(feature = "transforms-log_to_metric", feature = "transforms-throttle"))]
+pub mod value_template;
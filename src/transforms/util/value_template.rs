@@ -0,0 +1,98 @@
+//! Values configured either as templates or as VRL expressions, rendered
+//! from each log event.
+
+use crate::event::LogEvent;
+use remap::{value, Program, Runtime, TypeConstraint, TypeDef};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// A value rendered from each log event: either a template, or a VRL
+/// expression written as `{ vrl = "..." }`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ValueTemplate {
+    Template(String),
+    Vrl { vrl: VrlExpression },
+}
+
+impl<'de> Deserialize<'de> for ValueTemplate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Compiled apart from the untagged enum, so the compilation
+        // errors aren't hidden behind a generic one.
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Template(String),
+            Vrl { vrl: String },
+        }
+
+        Ok(match Raw::deserialize(deserializer)? {
+            Raw::Template(template) => ValueTemplate::Template(template),
+            Raw::Vrl { vrl } => ValueTemplate::Vrl {
+                vrl: VrlExpression::new(vrl).map_err(de::Error::custom)?,
+            },
+        })
+    }
+}
+
+/// A VRL expression evaluated against a copy of each log event.
+#[derive(Clone)]
+pub struct VrlExpression {
+    source: String,
+    program: Program,
+}
+
+impl VrlExpression {
+    pub fn new(source: String) -> crate::Result<Self> {
+        let accepts = TypeConstraint {
+            allow_any: true,
+            type_def: TypeDef {
+                fallible: true,
+                kind: value::Kind::all(),
+                ..Default::default()
+            },
+        };
+
+        let (program, _) = Program::new(
+            source.clone(),
+            &remap_functions::all(),
+            Some(accepts),
+            false,
+        )
+        .map_err(|diagnostics| {
+            remap::Formatter::new(&source, diagnostics)
+                .colored()
+                .to_string()
+        })?;
+
+        Ok(Self { source, program })
+    }
+
+    /// The value of the expression as a string, strings being unquoted.
+    pub fn render(&self, log: &LogEvent) -> Result<String, String> {
+        match Runtime::default().run(&mut log.clone(), &self.program) {
+            Ok(remap::Value::Bytes(bytes)) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+            Ok(remap::Value::Null) => Err("The expression resolved to null.".to_owned()),
+            Ok(value) => Ok(value.to_string()),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+}
+
+impl fmt::Debug for VrlExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VrlExpression").field(&self.source).finish()
+    }
+}
+
+impl PartialEq for VrlExpression {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Serialize for VrlExpression {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
@@ -1,6 +1,6 @@
 use super::{TaskTransform, Transform};
 use crate::{
-    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
     event::Event,
     wasm::WasmModule,
 };
@@ -26,7 +26,10 @@ pub struct WasmConfig {
     pub module: PathBuf,
     /// The location of the WASM artifact cache.
     pub artifact_cache: PathBuf,
-    #[serde(default = "defaults::heap_memory_size")]
+    #[serde(
+        default = "defaults::heap_memory_size",
+        deserialize_with = "crate::serde::bytes"
+    )]
     pub heap_memory_size: usize,
     /// Options to be passed to the WASM module.
     #[serde(default)]
@@ -64,7 +67,7 @@ impl GenerateConfig for WasmConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "wasm")]
 impl TransformConfig for WasmConfig {
-    async fn build(&self) -> crate::Result<Transform> {
+    async fn build(&self, _name: &str, _globals: &GlobalOptions) -> crate::Result<Transform> {
         Ok(Transform::task(Wasm::new(self.clone())?))
     }
 
@@ -1,6 +1,9 @@
+#[cfg(target_os = "linux")]
+use crate::internal_events::UdpReceiveDrops;
+use futures::future;
 #[cfg(unix)]
 use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
-#[cfg(unix)]
+use std::{future::Future, io, net::SocketAddr};
 use tokio::net::UdpSocket;
 
 #[cfg(unix)]
@@ -38,3 +41,121 @@ pub fn set_send_buffer_size(socket: &UdpSocket, size: usize) {
 
     socket.into_raw_fd();
 }
+
+/// Binds `workers` sockets to `address`. With more than one, they share the address
+/// through `SO_REUSEPORT` and the kernel spreads the datagrams across them.
+pub async fn bind(address: SocketAddr, workers: usize) -> io::Result<Vec<UdpSocket>> {
+    if workers <= 1 {
+        return Ok(vec![UdpSocket::bind(&address).await?]);
+    }
+
+    #[cfg(unix)]
+    {
+        (0..workers).map(|_| bind_reuseport(address)).collect()
+    }
+    #[cfg(not(unix))]
+    {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            "Multiple UDP workers are only supported on Unix.",
+        ))
+    }
+}
+
+#[cfg(unix)]
+fn bind_reuseport(address: SocketAddr) -> io::Result<UdpSocket> {
+    use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+
+    let domain = match address {
+        SocketAddr::V4(_) => Domain::ipv4(),
+        SocketAddr::V6(_) => Domain::ipv6(),
+    };
+    let socket = Socket::new(domain, Type::dgram(), Some(Protocol::udp()))?;
+    socket.set_reuse_port(true)?;
+    socket.bind(&SockAddr::from(address))?;
+    socket.set_nonblocking(true)?;
+
+    UdpSocket::from_std(socket.into_udp_socket())
+}
+
+/// Runs the receive loops of the sockets bound to `port` until they all finish,
+/// reporting the datagrams the kernel dropped on them meanwhile.
+pub async fn run_workers<F>(port: u16, workers: Vec<F>) -> Result<(), ()>
+where
+    F: Future<Output = Result<(), ()>>,
+{
+    tokio::select! {
+        result = future::try_join_all(workers) => result.map(|_| ()),
+        _ = report_drops(port) => Ok(()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+async fn report_drops(port: u16) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(10));
+    // The sockets are bound by the source, so they start out without drops.
+    let mut previous = 0;
+    loop {
+        interval.tick().await;
+
+        let mut drops = 0;
+        for table in &["/proc/net/udp", "/proc/net/udp6"] {
+            if let Ok(contents) = tokio::fs::read_to_string(table).await {
+                drops += parse_drops(&contents, port);
+            }
+        }
+        // Sockets of a reloaded source were closed, restarting the count.
+        if drops < previous {
+            previous = 0;
+        }
+        if drops > previous {
+            emit!(UdpReceiveDrops {
+                port,
+                count: drops - previous,
+            });
+        }
+        previous = drops;
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+async fn report_drops(_port: u16) {
+    future::pending().await
+}
+
+/// Sums the `drops` column of the sockets bound to `port` in a `/proc/net/udp`
+/// style table.
+#[cfg(any(target_os = "linux", test))]
+fn parse_drops(table: &str, port: u16) -> u64 {
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let local_port = fields.get(1)?.rsplit(':').next()?;
+            if u16::from_str_radix(local_port, 16).ok()? == port {
+                fields.last()?.parse::<u64>().ok()
+            } else {
+                None
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_drops_of_port() {
+        let table = "   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  172: 00000000:1FBD 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 31501 2 0000000000000000 12
+  173: 00000000:1FBD 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 31502 2 0000000000000000 30
+  256: 0100007F:0035 00000000:0000 07 00000000:00000000 00:00000000 00000000   101        0 18421 2 0000000000000000 7
+";
+
+        assert_eq!(parse_drops(table, 8125), 42);
+        assert_eq!(parse_drops(table, 53), 7);
+        assert_eq!(parse_drops(table, 514), 0);
+    }
+}
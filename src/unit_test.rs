@@ -18,6 +18,16 @@ pub struct Opts {
     #[structopt(name = "config-yaml", long)]
     paths_yaml: Vec<PathBuf>,
 
+    /// Only run the tests whose name contains this string.
+    #[structopt(long)]
+    filter: Option<String>,
+
+    /// Write an lcov report of the lines of the `remap` transform programs
+    /// exercised by the tests to this file.
+    #[cfg(feature = "transforms-remap")]
+    #[structopt(long)]
+    coverage: Option<PathBuf>,
+
     /// Any number of Vector config files to test. If none are specified the
     /// default config path `/etc/vector/vector.toml` will be targeted.
     paths: Vec<PathBuf>,
@@ -44,8 +54,13 @@ pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
         None => return exitcode::CONFIG,
     };
 
+    #[cfg(feature = "transforms-remap")]
+    if opts.coverage.is_some() {
+        crate::transforms::remap::collect_coverage();
+    }
+
     println!("Running tests");
-    match config::build_unit_tests(&paths).await {
+    match config::build_unit_tests(&paths, opts.filter.as_deref()).await {
         Ok(mut tests) => {
             tests.iter_mut().for_each(|t| {
                 let (test_inspections, test_errors) = t.run();
@@ -79,6 +94,14 @@ pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
         }
     }
 
+    #[cfg(feature = "transforms-remap")]
+    if let Some(path) = &opts.coverage {
+        if let Err(error) = write_coverage(path) {
+            error!("Failed to write coverage report to {:?}: {}.", path, error);
+            return exitcode::IOERR;
+        }
+    }
+
     if !aggregated_test_errors.is_empty() {
         println!("\nfailures:");
         for (test_name, fails) in aggregated_test_errors {
@@ -93,3 +116,28 @@ pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
         exitcode::OK
     }
 }
+
+/// Writes the line coverage of the `remap` transforms built by the tests as an
+/// lcov report, with a source file per transform.
+#[cfg(feature = "transforms-remap")]
+fn write_coverage(path: &std::path::Path) -> std::io::Result<()> {
+    use std::fmt::Write;
+
+    let mut report = String::new();
+    println!("\ncoverage:\n");
+    for (name, lines) in crate::transforms::remap::line_coverage() {
+        let hit = lines.values().filter(|hits| **hits > 0).count();
+        println!("transforms.{} ... {}/{} lines", name, hit, lines.len());
+
+        let _ = writeln!(report, "TN:");
+        let _ = writeln!(report, "SF:transforms.{}.source", name);
+        for (line, hits) in &lines {
+            let _ = writeln!(report, "DA:{},{}", line, hits);
+        }
+        let _ = writeln!(report, "LF:{}", lines.len());
+        let _ = writeln!(report, "LH:{}", hit);
+        let _ = writeln!(report, "end_of_record");
+    }
+
+    std::fs::write(path, report)
+}
@@ -1,11 +1,19 @@
 use crate::{
-    config::{self, Config, ConfigDiff},
+    config::{
+        self,
+        deprecation::{self, Deprecation},
+        Config, ConfigDiff,
+    },
     topology::{self, builder::Pieces},
 };
 use colored::*;
 use exitcode::ExitCode;
 use std::collections::HashMap;
-use std::{fmt, fs::remove_dir_all, path::PathBuf};
+use std::{
+    fmt,
+    fs::{self, remove_dir_all},
+    path::{Path, PathBuf},
+};
 use structopt::StructOpt;
 
 const TEMPORARY_DIRECTORY: &str = "validate_tmp";
@@ -22,6 +30,12 @@ pub struct Opts {
     #[structopt(short, long)]
     deny_warnings: bool,
 
+    /// Rewrite deprecated options in the config files in place before validating,
+    /// where the migration is mechanical. Comments and formatting of rewritten
+    /// files are not preserved.
+    #[structopt(long)]
+    fix: bool,
+
     /// Vector config files in TOML format to validate.
     #[structopt(name = "config-toml", long)]
     paths_toml: Vec<PathBuf>,
@@ -58,6 +72,10 @@ pub async fn validate(opts: &Opts, color: bool) -> ExitCode {
 
     let mut validated = true;
 
+    if opts.fix && !fix_deprecations(opts, &mut fmt) {
+        return exitcode::CONFIG;
+    }
+
     let mut config = match validate_config(opts, &mut fmt) {
         Some(config) => config,
         None => return exitcode::CONFIG,
@@ -80,6 +98,53 @@ pub async fn validate(opts: &Opts, color: bool) -> ExitCode {
     }
 }
 
+/// Rewrites deprecated options in every config file, returning false if any
+/// file couldn't be rewritten.
+fn fix_deprecations(opts: &Opts, fmt: &mut Formatter) -> bool {
+    let paths = match config::process_paths(&opts.paths_with_formats()) {
+        Some(paths) => paths,
+        None => {
+            fmt.error("No config file paths");
+            return false;
+        }
+    };
+
+    let mut fixed_all = true;
+    for (path, format) in paths {
+        let format = format.or_else(|| config::Format::from_path(&path).ok());
+        match fix_file(&path, format) {
+            Ok(fixed) if fixed.is_empty() => (),
+            Ok(fixed) => {
+                fmt.success(format!("Fixed {:?}", path));
+                for deprecation in fixed {
+                    fmt.warning(deprecation.to_string());
+                }
+            }
+            Err(errors) => {
+                fmt.title(format!("Failed to fix {:?}", path));
+                fmt.sub_error(errors);
+                fixed_all = false;
+            }
+        }
+    }
+
+    fixed_all
+}
+
+fn fix_file(path: &Path, format: config::FormatHint) -> Result<Vec<Deprecation>, Vec<String>> {
+    // Environment variables are not interpolated so that they survive the rewrite.
+    let content = fs::read_to_string(path).map_err(|error| vec![error.to_string()])?;
+    let mut raw: toml::Value = config::format::deserialize(&content, format)?;
+
+    let fixed = deprecation::fix(&mut raw);
+    if !fixed.is_empty() {
+        let content = config::format::serialize(&raw, format)?;
+        fs::write(path, content).map_err(|error| vec![error.to_string()])?;
+    }
+
+    Ok(fixed)
+}
+
 /// Ok if all configs were successfully validated.
 /// Err Some contains only successfully validated configs.
 fn validate_config(opts: &Opts, fmt: &mut Formatter) -> Option<Config> {
@@ -27,8 +27,8 @@ mod tests {
     use vector_api_client::{
         connect_subscription_client,
         gql::{
-            ComponentsSubscriptionExt, HealthQueryExt, HealthSubscriptionExt, MetaQueryExt,
-            MetricsSubscriptionExt,
+            ComponentsSubscriptionExt, EventsSubscriptionExt, HealthQueryExt,
+            HealthSubscriptionExt, MetaQueryExt, MetricsSubscriptionExt,
         },
         test::*,
         Client, SubscriptionClient,
@@ -89,7 +89,7 @@ mod tests {
     // Starts and returns the server
     fn start_server() -> Server {
         let config = api_enabled_config();
-        api::Server::start(&config)
+        api::Server::start(&config).unwrap()
     }
 
     fn make_client(addr: SocketAddr) -> Client {
@@ -104,7 +104,7 @@ mod tests {
         let addr = config.api.address.unwrap();
         let url = format!("http://{}:{}/{}", addr.ip(), addr.port(), url);
 
-        let _server = api::Server::start(&config);
+        let _server = api::Server::start(&config).unwrap();
 
         // Build the request
         let client = reqwest::Client::new();
@@ -291,7 +291,7 @@ mod tests {
             config_builder.api.address = Some(next_addr());
 
             let config = config_builder.build().unwrap();
-            let server = api::Server::start(&config);
+            let server = api::Server::start(&config).unwrap();
 
             let client = make_client(server.addr());
 
@@ -472,7 +472,7 @@ mod tests {
 
                 tokio::time::delay_for(tokio::time::Duration::from_millis(500)).await;
 
-                let server = api::Server::start(topology.config());
+                let server = api::Server::start(topology.config()).unwrap();
                 let client = new_subscription_client(server.addr()).await;
                 let subscription = client.component_processed_events_totals_subscription(500);
 
@@ -522,7 +522,7 @@ mod tests {
 
                 let topology = from_str_config(conf).await;
 
-                let server = api::Server::start(topology.config());
+                let server = api::Server::start(topology.config()).unwrap();
                 let client = new_subscription_client(server.addr()).await;
                 let subscription = client.component_processed_bytes_totals_subscription(500);
 
@@ -542,6 +542,88 @@ mod tests {
         )
     }
 
+    #[test]
+    /// Tests componentSentBytesTotals only returns the bytes sent by sinks
+    fn api_graphql_component_sent_bytes_totals() {
+        metrics_test("tests::api_graphql_component_sent_bytes_totals", async {
+            let conf = r#"
+                [api]
+                  enabled = true
+
+                [sources.sent_bytes_total_source]
+                  type = "generator"
+                  format = "shuffle"
+                  lines = ["Random line", "And another"]
+                  interval = 0.1
+
+                [sinks.sent_bytes_total_sink]
+                  type = "blackhole"
+                  inputs = ["sent_bytes_total_source"]
+                  print_amount = 100000
+            "#;
+
+            let topology = from_str_config(conf).await;
+
+            let server = api::Server::start(topology.config()).unwrap();
+            let client = new_subscription_client(server.addr()).await;
+            let subscription = client.component_sent_bytes_totals_subscription(500);
+
+            let data = subscription
+                .stream()
+                .skip(1)
+                .take(1)
+                .map(|r| r.unwrap().data.unwrap().component_sent_bytes_totals)
+                .next()
+                .await
+                .expect("Didn't return results");
+
+            assert_eq!(data.len(), 1);
+            assert_eq!(data[0].name, "sent_bytes_total_sink");
+            assert!(data[0].metric.processed_bytes_total > 0.00);
+        })
+    }
+
+    #[test]
+    /// Tests componentBuffers returns the buffers of the running sinks
+    fn api_graphql_component_buffers() {
+        metrics_test("tests::api_graphql_component_buffers", async {
+            let conf = r#"
+                [api]
+                  enabled = true
+
+                [sources.component_buffers_source]
+                  type = "generator"
+                  format = "shuffle"
+                  lines = ["Random line", "And another"]
+                  interval = 0.1
+
+                [sinks.component_buffers_sink]
+                  type = "blackhole"
+                  inputs = ["component_buffers_source"]
+                  print_amount = 100000
+            "#;
+
+            let topology = from_str_config(conf).await;
+
+            let server = api::Server::start(topology.config()).unwrap();
+            let client = new_subscription_client(server.addr()).await;
+            let subscription = client.component_buffers_subscription(100);
+
+            let data = subscription
+                .stream()
+                .take(1)
+                .map(|r| r.unwrap().data.unwrap().component_buffers)
+                .next()
+                .await
+                .expect("Didn't return results");
+
+            assert_eq!(data.len(), 1);
+            assert_eq!(data[0].name, "component_buffers_sink");
+            let utilization = data[0].buffer.utilization.unwrap();
+            assert!((0.0..=1.0).contains(&utilization));
+        })
+    }
+
     #[test]
     /// Tests componentAdded receives an added component
     fn api_graphql_component_added_subscription() {
@@ -565,7 +647,7 @@ mod tests {
 
             let mut topology = from_str_config(conf).await;
 
-            let server = api::Server::start(topology.config());
+            let server = api::Server::start(topology.config()).unwrap();
             let client = new_subscription_client(server.addr()).await;
 
             // Spawn a handler for listening to changes
@@ -655,7 +737,7 @@ mod tests {
 
             let mut topology = from_str_config(conf).await;
 
-            let server = api::Server::start(topology.config());
+            let server = api::Server::start(topology.config()).unwrap();
             let client = new_subscription_client(server.addr()).await;
 
             // Spawn a handler for listening to changes
@@ -733,7 +815,7 @@ mod tests {
 
             let topology = from_str_config(conf).await;
 
-            let server = api::Server::start(topology.config());
+            let server = api::Server::start(topology.config()).unwrap();
             let client = new_subscription_client(server.addr()).await;
 
             // Spawn a handler for listening to changes
@@ -788,7 +870,7 @@ mod tests {
 
             let topology = from_str_config(conf).await;
 
-            let server = api::Server::start(topology.config());
+            let server = api::Server::start(topology.config()).unwrap();
             let client = new_subscription_client(server.addr()).await;
 
             // Spawn a handler for listening to changes
@@ -859,7 +941,7 @@ mod tests {
             );
 
             let topology = from_str_config(&conf).await;
-            let server = api::Server::start(topology.config());
+            let server = api::Server::start(topology.config()).unwrap();
 
             // Short delay to ensure logs are picked up
             tokio::time::delay_for(tokio::time::Duration::from_millis(200)).await;
@@ -902,7 +984,7 @@ mod tests {
             "#;
 
             let topology = from_str_config(&conf).await;
-            let server = api::Server::start(topology.config());
+            let server = api::Server::start(topology.config()).unwrap();
             let client = make_client(server.addr());
 
             // Retrieving a component that doesn't exist should return None
@@ -958,7 +1040,7 @@ mod tests {
 
             let topology = from_str_config(&conf).await;
 
-            let server = api::Server::start(topology.config());
+            let server = api::Server::start(topology.config()).unwrap();
             let client = make_client(server.addr());
 
             // Test after/first with a page size of 2, exhausting all results
@@ -1041,4 +1123,59 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    /// Tests outputEvents samples the events flowing out of a source, and fails for
+    /// components that aren't running
+    fn api_graphql_output_events() {
+        metrics_test("tests::api_graphql_output_events", async {
+            let conf = r#"
+                [api]
+                  enabled = true
+
+                [sources.output_events_source]
+                  type = "generator"
+                  format = "shuffle"
+                  lines = ["Random line"]
+                  interval = 0.01
+
+                [sinks.output_events_sink]
+                  type = "blackhole"
+                  inputs = ["output_events_source"]
+                  print_amount = 100000
+            "#;
+
+            let topology = from_str_config(conf).await;
+
+            let server = api::Server::start(topology.config()).unwrap();
+            let client = new_subscription_client(server.addr()).await;
+
+            let subscription =
+                client.output_events_subscription("output_events_source".to_owned(), 100, 5);
+            let events = subscription
+                .stream()
+                .take(1)
+                .map(|r| r.unwrap().data.unwrap().output_events)
+                .next()
+                .await
+                .expect("Didn't return results");
+
+            assert!(!events.is_empty() && events.len() <= 5);
+            for event in events {
+                assert_eq!(event.component_name, "output_events_source");
+                assert!(event.json.contains("Random line"));
+            }
+
+            let subscription = client.output_events_subscription("missing".to_owned(), 100, 5);
+            let errors = subscription
+                .stream()
+                .take(1)
+                .map(|r| r.unwrap().errors)
+                .next()
+                .await
+                .expect("Didn't return results");
+
+            assert!(errors.is_some());
+        })
+    }
 }
@@ -254,6 +254,37 @@ async fn duplicate_name() {
     );
 }
 
+#[cfg(all(feature = "sources-socket", feature = "sinks-socket"))]
+#[tokio::test]
+async fn cluster_without_store_path() {
+    let err = load(
+        r#"
+        [cluster]
+        enabled = true
+
+        [sources.in]
+        type = "socket"
+        mode = "tcp"
+        address = "127.0.0.1:1235"
+
+        [sinks.out]
+        type = "socket"
+        mode = "tcp"
+        inputs = ["in"]
+        encoding = "text"
+        address = "127.0.0.1:9999"
+        "#,
+        Some(Format::TOML),
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(
+        err,
+        vec!["Cluster mode requires a `cluster.store.path` shared by all the instances."]
+    );
+}
+
 #[cfg(all(
     feature = "sources-socket",
     feature = "transforms-sample",
@@ -242,6 +242,7 @@ impl FunctionTransform for MockTransform {
                     values.insert(self.suffix.clone());
                 }
             },
+            Event::Trace(_) => {}
         };
         output.push(event);
     }
@@ -262,7 +263,11 @@ impl MockTransformConfig {
 #[async_trait]
 #[typetag::serde(name = "mock")]
 impl TransformConfig for MockTransformConfig {
-    async fn build(&self) -> Result<Transform, vector::Error> {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+    ) -> Result<Transform, vector::Error> {
         Ok(Transform::function(MockTransform {
             suffix: self.suffix.clone(),
             increase: self.increase,